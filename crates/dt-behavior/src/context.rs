@@ -1,7 +1,7 @@
 //! Read-only simulation state passed to every behavior callback.
 
 use dt_agent::AgentStore;
-use dt_core::Tick;
+use dt_core::{Tick, TickDuration};
 use dt_schedule::ActivityPlan;
 
 /// A read-only snapshot of the simulation state passed to every
@@ -21,7 +21,8 @@ pub struct SimContext<'a> {
 
     /// How many wall-clock seconds one tick represents.
     ///
-    /// Useful for computing durations: `n_ticks * tick_duration_secs`.
+    /// Prefer [`duration_from_secs`][Self::duration_from_secs] and
+    /// [`duration_secs`][Self::duration_secs] to converting by hand.
     pub tick_duration_secs: u32,
 
     /// Read-only view of every agent's SoA state arrays.
@@ -45,4 +46,17 @@ impl<'a> SimContext<'a> {
     ) -> Self {
         Self { tick, tick_duration_secs, agents, plans }
     }
+
+    /// The duration covering `secs` seconds (rounds up), e.g. for
+    /// `Intent::WakeAt(ctx.tick + ctx.duration_from_secs(900))`.
+    #[inline]
+    pub fn duration_from_secs(&self, secs: u64) -> TickDuration {
+        TickDuration::from_secs(secs, self.tick_duration_secs)
+    }
+
+    /// Length of `duration` in seconds.
+    #[inline]
+    pub fn duration_secs(&self, duration: TickDuration) -> u64 {
+        duration.as_secs(self.tick_duration_secs)
+    }
 }
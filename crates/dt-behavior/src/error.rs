@@ -1,3 +1,4 @@
+use dt_core::{DtError, ErrorCategory};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -7,3 +8,9 @@ pub enum BehaviorError {
 }
 
 pub type BehaviorResult<T> = Result<T, BehaviorError>;
+
+impl From<BehaviorError> for DtError {
+    fn from(err: BehaviorError) -> Self {
+        DtError::subsystem(ErrorCategory::Behavior, err)
+    }
+}
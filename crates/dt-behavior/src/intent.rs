@@ -9,7 +9,12 @@ use dt_core::{AgentId, NodeId, Tick, TransportMode};
 ///
 /// Multiple intents may be returned per agent per tick; the caller is
 /// responsible for resolving any conflicts (e.g. two `TravelTo` requests).
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// `Count` and `Sample` carry metrics rather than actions.  Because they are
+/// applied with the other intents (sequentially, in agent order) their
+/// per-tick totals are deterministic even when the intent phase runs in
+/// parallel.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Intent {
     /// Agent wants to travel to `destination` via `mode`.
     ///
@@ -32,4 +37,17 @@ pub enum Intent {
         to:      AgentId,
         payload: Vec<u8>,
     },
+
+    /// Add `delta` to the named per-tick counter.
+    Count {
+        name:  &'static str,
+        delta: u64,
+    },
+
+    /// Record one observation of the named per-tick sample (count, sum,
+    /// min, max are kept).
+    Sample {
+        name:  &'static str,
+        value: f64,
+    },
 }
@@ -1,6 +1,6 @@
 //! The `BehaviorModel` trait — the main extension point for user code.
 
-use dt_core::{AgentId, AgentRng, NodeId};
+use dt_core::{AgentId, AgentRng, EdgeId, NodeId, TransitVehicle};
 
 use crate::{Intent, SimContext};
 
@@ -71,6 +71,47 @@ pub trait BehaviorModel: Send + Sync + 'static {
         vec![]
     }
 
+    /// Called when other in-transit agents are traversing the same edge as
+    /// this agent during the tick.
+    ///
+    /// Only fires when the sim is built with
+    /// `SimBuilder::edge_contacts(true)`.  Unlike the other hooks it is
+    /// called for every traveler on a shared edge, whether or not the agent
+    /// woke this tick.  `agents_on_edge` includes `agent` itself.
+    ///
+    /// Default: returns no intents (co-travelers are ignored).
+    fn on_edge_contacts(
+        &self,
+        _agent:          AgentId,
+        _edge:           EdgeId,
+        _agents_on_edge: &[AgentId],
+        _ctx:            &SimContext<'_>,
+        _rng:            &mut AgentRng,
+    ) -> Vec<Intent> {
+        vec![]
+    }
+
+    /// Called when other agents are riding the same scheduled transit
+    /// vehicle as this agent during the tick.
+    ///
+    /// Fires under the same `SimBuilder::edge_contacts(true)` switch as
+    /// [`on_edge_contacts`](Self::on_edge_contacts), for every rider whether
+    /// or not it woke, after the edge contacts.  Riders are grouped by
+    /// vehicle regardless of which edge each is estimated to be on.
+    /// `riders` includes `agent` itself.
+    ///
+    /// Default: returns no intents (fellow riders are ignored).
+    fn on_vehicle_contacts(
+        &self,
+        _agent:   AgentId,
+        _vehicle: TransitVehicle,
+        _riders:  &[AgentId],
+        _ctx:     &SimContext<'_>,
+        _rng:     &mut AgentRng,
+    ) -> Vec<Intent> {
+        vec![]
+    }
+
     /// Called when another agent sent this agent a message via
     /// [`Intent::SendMessage`].
     ///
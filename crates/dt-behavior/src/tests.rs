@@ -62,6 +62,18 @@ mod intent_tests {
             _ => panic!("wrong variant"),
         }
     }
+
+    #[test]
+    fn metric_intents() {
+        assert_eq!(
+            Intent::Count { name: "contacts", delta: 2 },
+            Intent::Count { name: "contacts", delta: 2 },
+        );
+        assert_ne!(
+            Intent::Sample { name: "wait", value: 1.5 },
+            Intent::Sample { name: "wait", value: 2.5 },
+        );
+    }
 }
 
 // ── SimContext ─────────────────────────────────────────────────────────────────
@@ -80,6 +92,17 @@ mod context_tests {
         assert_eq!(ctx.agents.count, 2);
         assert_eq!(ctx.plans.len(), 2);
     }
+
+    #[test]
+    fn duration_helpers_use_tick_length() {
+        let store = make_store(1);
+        let plans = vec![ActivityPlan::empty()];
+        let ctx = make_context(&store, &plans);
+        // 1-hour ticks: 90 minutes round up to 2 ticks.
+        assert_eq!(ctx.duration_from_secs(5_400), dt_core::TickDuration(2));
+        assert_eq!(ctx.tick + ctx.duration_from_secs(5_400), Tick(2));
+        assert_eq!(ctx.duration_secs(dt_core::TickDuration(2)), 7_200);
+    }
 }
 
 // ── NoopBehavior ──────────────────────────────────────────────────────────────
@@ -0,0 +1,37 @@
+//! Error types for dt-bench.
+
+use std::path::PathBuf;
+
+use dt_core::{DtError, ErrorCategory};
+use thiserror::Error;
+
+/// Errors that can occur while running benchmarks or handling reports.
+#[derive(Debug, Error)]
+pub enum BenchError {
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+
+    #[error("invalid benchmark report: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error(transparent)]
+    Cli(#[from] dt_cli::CliError),
+
+    #[error(transparent)]
+    Sim(#[from] dt_sim::SimError),
+
+    #[error(transparent)]
+    Output(#[from] dt_output::OutputError),
+}
+
+/// Alias for `Result<T, BenchError>`.
+pub type BenchResult<T> = Result<T, BenchError>;
+
+impl From<BenchError> for DtError {
+    fn from(err: BenchError) -> Self {
+        DtError::subsystem(ErrorCategory::Sim, err)
+    }
+}
@@ -0,0 +1,65 @@
+//! `dt-bench` — standard benchmark scenarios with machine-readable reports.
+//!
+//! A [`BenchScenario`] is a synthetic run — a square grid network, a number
+//! of agents following one of dt-cli's built-in behaviors on the same daily
+//! plan, and an output backend — that needs no input files, so the same
+//! scenario can be measured release after release.  [`standard_scenarios`]
+//! covers the tick loop and routing (grid sizes × agent counts × behaviors)
+//! and each output backend; [`Matrix`] builds custom grids of scenarios.
+//!
+//! [`Runner`] runs each scenario a few times and records setup and run
+//! time, throughput, per-phase time (from dt-sim's `PhaseTimings`), time
+//! spent writing output, and peak resident memory in a [`BenchReport`],
+//! which serializes to JSON or CSV and compares against a baseline:
+//!
+//! ```rust,no_run
+//! use dt_bench::{BenchReport, Runner, standard_scenarios};
+//!
+//! let report = Runner::new().samples(3).run(&standard_scenarios())?;
+//! report.write_json("bench.json".as_ref())?;
+//!
+//! let baseline = BenchReport::read_json("baseline.json".as_ref())?;
+//! for change in report.compare(&baseline) {
+//!     if change.is_regression(0.10) {
+//!         eprintln!("regression: {change}");
+//!     }
+//! }
+//! # Ok::<(), dt_bench::BenchError>(())
+//! ```
+//!
+//! The `dt-bench` binary does the same from the command line; build it in
+//! release mode for meaningful numbers:
+//!
+//! ```text
+//! dt-bench [--quick] [--filter TEXT] [--samples N] [--json PATH] [--csv PATH]
+//!          [--baseline PATH] [--tolerance FRACTION] [--list] [--quiet]
+//! ```
+//!
+//! # Crate layout
+//!
+//! | Module       | Contents                                                 |
+//! |--------------|----------------------------------------------------------|
+//! | [`scenario`] | `BenchScenario`, `Matrix`, the standard and quick suites |
+//! | [`measure`]  | `Runner`, `measure`, `BenchObserver`, `Sample`           |
+//! | [`report`]   | `BenchReport`, `ScenarioResult`, `Change` (comparison)   |
+//! | [`error`]    | `BenchError`, `BenchResult<T>`                           |
+//!
+//! # Feature flags
+//!
+//! | Feature                     | Enables                                    |
+//! |-----------------------------|--------------------------------------------|
+//! | `parallel`                  | dt-sim's Rayon-parallel intent phase       |
+//! | `parquet`, `sqlite`, `jsonl`| output scenarios for those dt-cli backends |
+
+pub mod error;
+pub mod measure;
+pub mod report;
+pub mod scenario;
+
+#[cfg(test)]
+mod tests;
+
+pub use error::{BenchError, BenchResult};
+pub use measure::{BenchObserver, Runner, Sample, measure};
+pub use report::{BenchReport, Change, Host, PhaseSecs, REPORT_SCHEMA, ScenarioResult};
+pub use scenario::{BenchScenario, Matrix, quick_scenarios, standard_scenarios};
@@ -0,0 +1,136 @@
+//! `dt-bench` — run the standard benchmark scenarios and report.
+//!
+//! ```text
+//! dt-bench [--quick] [--filter TEXT] [--samples N] [--json PATH] [--csv PATH]
+//!          [--baseline PATH] [--tolerance FRACTION] [--list] [--quiet]
+//! ```
+//!
+//! Exits with status 1 if any scenario is slower than `--baseline` by more
+//! than `--tolerance` (default 0.10).
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use dt_bench::{BenchReport, BenchResult, Runner, quick_scenarios, standard_scenarios};
+
+const USAGE: &str = "usage: dt-bench [--quick] [--filter TEXT] [--samples N] [--json PATH] [--csv PATH] \
+                     [--baseline PATH] [--tolerance FRACTION] [--list] [--quiet]";
+
+#[derive(Debug)]
+struct Args {
+    quick:     bool,
+    filter:    Option<String>,
+    samples:   u32,
+    json:      Option<PathBuf>,
+    csv:       Option<PathBuf>,
+    baseline:  Option<PathBuf>,
+    tolerance: f64,
+    list:      bool,
+    quiet:     bool,
+    help:      bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        quick:     false,
+        filter:    None,
+        samples:   3,
+        json:      None,
+        csv:       None,
+        baseline:  None,
+        tolerance: 0.10,
+        list:      false,
+        quiet:     false,
+        help:      false,
+    };
+    let mut it = std::env::args().skip(1);
+    while let Some(arg) = it.next() {
+        let mut value = |flag: &str| it.next().ok_or_else(|| format!("{flag} needs a value"));
+        match arg.as_str() {
+            "--quick" => args.quick = true,
+            "--list" => args.list = true,
+            "-q" | "--quiet" => args.quiet = true,
+            "-h" | "--help" => args.help = true,
+            "--filter" => args.filter = Some(value("--filter")?),
+            "--json" => args.json = Some(value("--json")?.into()),
+            "--csv" => args.csv = Some(value("--csv")?.into()),
+            "--baseline" => args.baseline = Some(value("--baseline")?.into()),
+            "--samples" => {
+                args.samples = value("--samples")?.parse().map_err(|_| "--samples takes a number".to_string())?;
+            }
+            "--tolerance" => {
+                args.tolerance =
+                    value("--tolerance")?.parse().map_err(|_| "--tolerance takes a fraction".to_string())?;
+            }
+            other => return Err(format!("unknown argument {other}")),
+        }
+    }
+    Ok(args)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) if args.help => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut scenarios = if args.quick { quick_scenarios() } else { standard_scenarios() };
+    if let Some(filter) = &args.filter {
+        scenarios.retain(|s| s.name().contains(filter.as_str()));
+    }
+    if args.list {
+        scenarios.iter().for_each(|s| println!("{}", s.name()));
+        return ExitCode::SUCCESS;
+    }
+    if scenarios.is_empty() {
+        eprintln!("no scenario matches the filter");
+        return ExitCode::from(2);
+    }
+    if cfg!(debug_assertions) && !args.quiet {
+        eprintln!("warning: dt-bench was built without optimizations; use --release for meaningful numbers");
+    }
+
+    match run(&args, &scenarios) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run, print, and write the report; `false` if it regressed against the
+/// baseline.
+fn run(args: &Args, scenarios: &[dt_bench::BenchScenario]) -> BenchResult<bool> {
+    // Read the baseline first so a bad path fails before the long part.
+    let baseline = args.baseline.as_deref().map(BenchReport::read_json).transpose()?;
+    let report = Runner::new().samples(args.samples).quiet(args.quiet).run(scenarios)?;
+    print!("{report}");
+    if let Some(path) = &args.json {
+        report.write_json(path)?;
+    }
+    if let Some(path) = &args.csv {
+        report.write_csv(path)?;
+    }
+
+    let Some(baseline) = baseline else {
+        return Ok(true);
+    };
+    let changes = report.compare(&baseline);
+    println!("\ncompared with {} ({} scenarios):", args.baseline.as_ref().unwrap().display(), changes.len());
+    let mut ok = true;
+    for change in &changes {
+        let regressed = change.is_regression(args.tolerance);
+        ok &= !regressed;
+        println!("{} {change}", if regressed { "REGRESSION" } else { "          " });
+    }
+    Ok(ok)
+}
@@ -0,0 +1,316 @@
+//! Running scenarios: `Runner`, `BenchObserver`, and `Sample`.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use dt_agent::AgentStore;
+use dt_cli::{BuiltinBehavior, DailyPlan, GridSpec, PlansSpec, Population, PopulationSpec};
+use dt_core::{AgentId, NodeId, SimConfig, Tick, TransportMode};
+use dt_mobility::{MobilityStore, MovementState, Trip};
+use dt_output::SimOutputObserver;
+use dt_sim::{FailurePolicy, NoopObserver, PhaseTimings, Sim, SimBuilder, SimObserver, TickMetrics, TickStats, TraceEvent};
+use dt_spatial::{DijkstraRouter, Route};
+
+use crate::scenario::behavior_name;
+use crate::{BenchReport, BenchResult, BenchScenario, PhaseSecs, ScenarioResult};
+
+// ── Sample ────────────────────────────────────────────────────────────────────
+
+/// Measurements of one run of a scenario.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sample {
+    /// Building the network, population, plans, and sim.
+    pub setup:            Duration,
+    /// The tick loop, including output.
+    pub run:              Duration,
+    /// Time spent in the wrapped observer.
+    pub output:           Duration,
+    pub phases:           PhaseSecs,
+    pub ticks:            u64,
+    pub wakeups:          u64,
+    pub departures:       u64,
+    pub arrivals:         u64,
+    pub routing_failures: u64,
+    pub rss_before:       Option<u64>,
+    pub rss_peak:         Option<u64>,
+}
+
+fn resident_memory() -> Option<u64> {
+    memory_stats::memory_stats().map(|usage| usage.physical_mem as u64)
+}
+
+// ── BenchObserver ─────────────────────────────────────────────────────────────
+
+/// A [`SimObserver`] that fills a [`Sample`] — phase timings, counters,
+/// time spent in `inner`, and peak resident memory (read at every tick
+/// end) — and forwards every callback to `inner`.
+pub struct BenchObserver<O> {
+    inner:  O,
+    sample: Sample,
+}
+
+impl<O: SimObserver> BenchObserver<O> {
+    pub fn new(inner: O) -> Self {
+        Self { inner, sample: Sample { rss_before: resident_memory(), ..Sample::default() } }
+    }
+
+    /// The measurements so far.
+    pub fn sample(&self) -> &Sample {
+        &self.sample
+    }
+
+    /// Unwrap the wrapped observer and the measurements.
+    pub fn into_parts(self) -> (O, Sample) {
+        (self.inner, self.sample)
+    }
+
+    /// Run `f` on `inner`, adding its wall time to the output time.
+    fn timed<R>(&mut self, f: impl FnOnce(&mut O) -> R) -> R {
+        let started = Instant::now();
+        let result = f(&mut self.inner);
+        self.sample.output += started.elapsed();
+        result
+    }
+
+    fn read_memory(&mut self) {
+        if let Some(rss) = resident_memory() {
+            self.sample.rss_peak = Some(self.sample.rss_peak.map_or(rss, |peak| peak.max(rss)));
+        }
+    }
+}
+
+impl<O: SimObserver> SimObserver for BenchObserver<O> {
+    fn on_tick_start(&mut self, tick: Tick) {
+        self.timed(|inner| inner.on_tick_start(tick));
+    }
+
+    fn on_tick_end(&mut self, tick: Tick, woken: usize) {
+        self.timed(|inner| inner.on_tick_end(tick, woken));
+        self.read_memory();
+    }
+
+    fn on_snapshot(&mut self, tick: Tick, mobility: &MobilityStore, agents: &AgentStore) {
+        self.timed(|inner| inner.on_snapshot(tick, mobility, agents));
+    }
+
+    fn on_trip(&mut self, trip: &Trip) {
+        self.timed(|inner| inner.on_trip(trip));
+    }
+
+    fn on_departure(&mut self, tick: Tick, agent: AgentId, state: &MovementState, route: &Route) {
+        self.timed(|inner| inner.on_departure(tick, agent, state, route));
+    }
+
+    fn on_contacts(&mut self, tick: Tick, agent: AgentId, node: NodeId, agents_at_node: &[AgentId]) {
+        self.timed(|inner| inner.on_contacts(tick, agent, node, agents_at_node));
+    }
+
+    fn on_tick_stats(&mut self, tick: Tick, stats: &TickStats) {
+        let s = &mut self.sample;
+        s.ticks            += 1;
+        s.wakeups          += stats.woken;
+        s.departures       += stats.departures;
+        s.arrivals         += stats.arrivals;
+        s.routing_failures += stats.routing_failures;
+        self.timed(|inner| inner.on_tick_stats(tick, stats));
+    }
+
+    fn on_phase_timings(&mut self, tick: Tick, timings: &PhaseTimings) {
+        self.sample.phases.add(timings);
+        self.timed(|inner| inner.on_phase_timings(tick, timings));
+    }
+
+    fn on_metrics(&mut self, tick: Tick, metrics: &TickMetrics) {
+        self.timed(|inner| inner.on_metrics(tick, metrics));
+    }
+
+    fn on_trace(&mut self, event: &TraceEvent) {
+        self.timed(|inner| inner.on_trace(event));
+    }
+
+    fn on_ticks_skipped(&mut self, from: Tick, to: Tick) {
+        self.timed(|inner| inner.on_ticks_skipped(from, to));
+    }
+
+    fn on_sim_end(&mut self, final_tick: Tick) {
+        self.timed(|inner| inner.on_sim_end(final_tick));
+        self.read_memory();
+    }
+
+    fn poll_error(&mut self) -> Option<String> {
+        self.inner.poll_error()
+    }
+}
+
+// ── Measuring ─────────────────────────────────────────────────────────────────
+
+/// Build and run `scenario` once, writing any output under `output_dir`.
+pub fn measure(scenario: &BenchScenario, output_dir: &Path) -> BenchResult<Sample> {
+    let rss_before = resident_memory();
+    let started = Instant::now();
+    let config = SimConfig {
+        tick_duration_secs: 3600,
+        total_ticks:        scenario.ticks,
+        seed:               scenario.seed,
+        ..SimConfig::default()
+    };
+    let network = dt_cli::network::grid_network(&GridSpec {
+        rows:      scenario.grid,
+        cols:      scenario.grid,
+        spacing_m: 500.0,
+        speed_kmh: 50.0,
+        origin:    [0.0, 0.0],
+    });
+    let spec = PopulationSpec { agents: Some(scenario.agents), ..Default::default() };
+    let population = Population::load(&spec, network.node_count(), TransportMode::Car, config.seed)?;
+    let daily = DailyPlan { depart: 8 * 3600, work: 9 * 3600 };
+    let plans = PlansSpec { daily: Some(daily), ..Default::default() };
+    let plans = dt_cli::plans::load_plans(&plans, scenario.agents, config.tick_duration_secs)?;
+    let (store, rngs) = population.build_store(config.seed);
+    let observer = scenario
+        .output
+        .open(output_dir)?
+        .map(|writer| SimOutputObserver::new(writer, &config).with_network(&network));
+    let mut sim = SimBuilder::new(config, store, rngs, scenario.behavior, DijkstraRouter)
+        .plans(plans)
+        .network(network)
+        .initial_positions(population.homes)
+        .failure_policy(FailurePolicy::CollectAndReport)
+        .build()?;
+    let setup = started.elapsed();
+
+    let mut sample = match observer {
+        Some(observer) => {
+            let (mut observer, sample) = drive(&mut sim, observer)?;
+            if let Some(e) = observer.take_error() {
+                return Err(e.into());
+            }
+            sample
+        }
+        None => drive(&mut sim, NoopObserver)?.1,
+    };
+    sample.setup = setup;
+    sample.rss_before = rss_before;
+    Ok(sample)
+}
+
+/// Run `sim` to the end under a [`BenchObserver`] wrapping `inner`.
+fn drive<O: SimObserver>(sim: &mut Sim<BuiltinBehavior, DijkstraRouter>, inner: O) -> BenchResult<(O, Sample)> {
+    let mut observer = BenchObserver::new(inner);
+    let started = Instant::now();
+    sim.run(&mut observer)?;
+    let run = started.elapsed();
+    let (inner, mut sample) = observer.into_parts();
+    sample.run = run;
+    Ok((inner, sample))
+}
+
+// ── Runner ────────────────────────────────────────────────────────────────────
+
+/// Runs scenarios a number of times each and summarizes them into a
+/// [`BenchReport`].
+#[derive(Debug, Clone)]
+pub struct Runner {
+    samples:    u32,
+    output_dir: PathBuf,
+    quiet:      bool,
+}
+
+impl Runner {
+    /// Three samples per scenario, output in a scratch directory under the
+    /// system temp dir, progress on stderr.
+    pub fn new() -> Self {
+        Self {
+            samples:    3,
+            output_dir: std::env::temp_dir().join(format!("dt-bench-{}", std::process::id())),
+            quiet:      false,
+        }
+    }
+
+    /// Runs per scenario (at least one).
+    pub fn samples(mut self, samples: u32) -> Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    /// Scratch directory for output-backend scenarios.  Each run writes
+    /// into its own subdirectory, removed afterwards.
+    pub fn output_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = dir.into();
+        self
+    }
+
+    /// Print nothing while running.
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    /// Run every scenario in turn.
+    pub fn run(&self, scenarios: &[BenchScenario]) -> BenchResult<BenchReport> {
+        let results = scenarios.iter().map(|s| self.run_scenario(s)).collect::<BenchResult<_>>()?;
+        Ok(BenchReport::new(results))
+    }
+
+    /// Run one scenario [`samples`][Self::samples] times.
+    pub fn run_scenario(&self, scenario: &BenchScenario) -> BenchResult<ScenarioResult> {
+        let name = scenario.name();
+        let dir = self.output_dir.join(name.replace('/', "_"));
+        let mut samples = Vec::with_capacity(self.samples as usize);
+        for i in 0..self.samples {
+            let sample = measure(scenario, &dir);
+            // Output is scratch; a failed cleanup must not fail the bench.
+            let _ = std::fs::remove_dir_all(&dir);
+            let sample = sample?;
+            if !self.quiet {
+                eprintln!("{name} [{}/{}]: {:.3}s", i + 1, self.samples, sample.run.as_secs_f64());
+            }
+            samples.push(sample);
+        }
+        let _ = std::fs::remove_dir(&self.output_dir);
+        Ok(summarize(scenario, &samples))
+    }
+}
+
+impl Default for Runner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `samples` of `scenario` as one result (see [`ScenarioResult`]).
+fn summarize(scenario: &BenchScenario, samples: &[Sample]) -> ScenarioResult {
+    let mut runs: Vec<f64> = samples.iter().map(|s| s.run.as_secs_f64()).collect();
+    runs.sort_by(f64::total_cmp);
+    let run_secs = runs[runs.len() / 2];
+    let mut setups: Vec<f64> = samples.iter().map(|s| s.setup.as_secs_f64()).collect();
+    setups.sort_by(f64::total_cmp);
+    let n = samples.len() as f64;
+    let last = samples.last().expect("at least one sample");
+    let per_sec = |count: u64| if run_secs > 0.0 { count as f64 / run_secs } else { 0.0 };
+    let phases: Vec<PhaseSecs> = samples.iter().map(|s| s.phases).collect();
+
+    ScenarioResult {
+        name:             scenario.name(),
+        grid:             scenario.grid,
+        agents:           scenario.agents,
+        behavior:         behavior_name(scenario.behavior).into(),
+        output:           scenario.output.name().into(),
+        ticks:            last.ticks,
+        samples:          samples.len() as u32,
+        setup_secs:       setups[setups.len() / 2],
+        run_secs,
+        run_secs_min:     runs[0],
+        run_secs_max:     runs[runs.len() - 1],
+        ticks_per_sec:    per_sec(last.ticks),
+        wakeups_per_sec:  per_sec(last.wakeups),
+        output_secs:      samples.iter().map(|s| s.output.as_secs_f64()).sum::<f64>() / n,
+        phase_secs:       PhaseSecs::mean(&phases),
+        wakeups:          last.wakeups,
+        departures:       last.departures,
+        arrivals:         last.arrivals,
+        routing_failures: last.routing_failures,
+        rss_before_bytes: samples.iter().filter_map(|s| s.rss_before).min(),
+        rss_peak_bytes:   samples.iter().filter_map(|s| s.rss_peak).max(),
+    }
+}
@@ -0,0 +1,330 @@
+//! `BenchReport` — machine-readable results, and comparison against a
+//! baseline.
+
+use std::fmt;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use dt_sim::PhaseTimings;
+
+use crate::{BenchError, BenchResult};
+
+/// Version of the report format; bumped when fields change meaning.
+pub const REPORT_SCHEMA: u32 = 1;
+
+// ── ScenarioResult ────────────────────────────────────────────────────────────
+
+/// Measurements of one scenario.
+///
+/// Times are the median over `samples` runs except where noted; counters
+/// are deterministic and come from the last run.  Memory is the process's
+/// resident set size, so it includes whatever earlier scenarios in the same
+/// process left allocated — run one scenario per process for isolated
+/// numbers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioResult {
+    pub name:             String,
+    pub grid:             u32,
+    pub agents:           usize,
+    pub behavior:         String,
+    pub output:           String,
+    pub ticks:            u64,
+    pub samples:          u32,
+    /// Building the network, population, plans, and sim.
+    pub setup_secs:       f64,
+    /// The tick loop, including output.
+    pub run_secs:         f64,
+    pub run_secs_min:     f64,
+    pub run_secs_max:     f64,
+    pub ticks_per_sec:    f64,
+    pub wakeups_per_sec:  f64,
+    /// Time spent in the output observer (writing and flushing), mean.
+    pub output_secs:      f64,
+    /// Time in each tick-loop phase, summed over the run, mean.  Routing
+    /// happens while intents are applied (`apply`).
+    pub phase_secs:       PhaseSecs,
+    pub wakeups:          u64,
+    pub departures:       u64,
+    pub arrivals:         u64,
+    pub routing_failures: u64,
+    /// Resident memory before setup, lowest over the samples.
+    pub rss_before_bytes: Option<u64>,
+    /// Resident memory at the end of the busiest tick, highest over the
+    /// samples.
+    pub rss_peak_bytes:   Option<u64>,
+}
+
+/// [`PhaseTimings`] summed over a run, in seconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PhaseSecs {
+    pub arrivals:      f64,
+    pub wake:          f64,
+    pub contact_index: f64,
+    pub intents:       f64,
+    pub apply:         f64,
+    pub reactions:     f64,
+    pub custom_phases: f64,
+    pub total:         f64,
+}
+
+impl PhaseSecs {
+    /// Add one tick's timings.
+    pub fn add(&mut self, t: &PhaseTimings) {
+        self.arrivals      += t.arrivals.as_secs_f64();
+        self.wake          += t.wake.as_secs_f64();
+        self.contact_index += t.contact_index.as_secs_f64();
+        self.intents       += t.intents.as_secs_f64();
+        self.apply         += t.apply.as_secs_f64();
+        self.reactions     += t.reactions.as_secs_f64();
+        self.custom_phases += t.custom_phases.as_secs_f64();
+        self.total         += t.total.as_secs_f64();
+    }
+
+    /// Each phase with its name, as in [`PhaseTimings::phases`], then
+    /// `total`.
+    pub fn named(&self) -> [(&'static str, f64); 8] {
+        [
+            ("arrivals",      self.arrivals),
+            ("wake",          self.wake),
+            ("contact_index", self.contact_index),
+            ("intents",       self.intents),
+            ("apply",         self.apply),
+            ("reactions",     self.reactions),
+            ("custom_phases", self.custom_phases),
+            ("total",         self.total),
+        ]
+    }
+
+    /// The element-wise mean of `all`.
+    pub(crate) fn mean(all: &[PhaseSecs]) -> PhaseSecs {
+        let mut sum = PhaseSecs::default();
+        for p in all {
+            sum.arrivals      += p.arrivals;
+            sum.wake          += p.wake;
+            sum.contact_index += p.contact_index;
+            sum.intents       += p.intents;
+            sum.apply         += p.apply;
+            sum.reactions     += p.reactions;
+            sum.custom_phases += p.custom_phases;
+            sum.total         += p.total;
+        }
+        let n = all.len().max(1) as f64;
+        PhaseSecs {
+            arrivals:      sum.arrivals / n,
+            wake:          sum.wake / n,
+            contact_index: sum.contact_index / n,
+            intents:       sum.intents / n,
+            apply:         sum.apply / n,
+            reactions:     sum.reactions / n,
+            custom_phases: sum.custom_phases / n,
+            total:         sum.total / n,
+        }
+    }
+}
+
+// ── BenchReport ───────────────────────────────────────────────────────────────
+
+/// The machine a report was measured on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Host {
+    pub os:       String,
+    pub arch:     String,
+    pub cpus:     usize,
+    /// `"release"` or `"debug"` (whether dt-bench had debug assertions).
+    pub profile:  String,
+    /// Whether dt-bench was built with feature `parallel`.
+    pub parallel: bool,
+}
+
+impl Host {
+    /// This machine and build.
+    pub fn current() -> Self {
+        Self {
+            os:       std::env::consts::OS.into(),
+            arch:     std::env::consts::ARCH.into(),
+            cpus:     std::thread::available_parallelism().map_or(1, |n| n.get()),
+            profile:  if cfg!(debug_assertions) { "debug" } else { "release" }.into(),
+            parallel: cfg!(feature = "parallel"),
+        }
+    }
+}
+
+/// The results of one benchmark session, serialized as JSON (or flattened
+/// to CSV) so releases can be compared.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchReport {
+    /// [`REPORT_SCHEMA`] at the time of writing.
+    pub schema:            u32,
+    /// dt-bench's version.
+    pub version:           String,
+    pub created_unix_secs: u64,
+    pub host:              Host,
+    pub results:           Vec<ScenarioResult>,
+}
+
+impl BenchReport {
+    /// A report of `results` measured now on this machine.
+    pub fn new(results: Vec<ScenarioResult>) -> Self {
+        Self {
+            schema:            REPORT_SCHEMA,
+            version:           env!("CARGO_PKG_VERSION").into(),
+            created_unix_secs: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            host:              Host::current(),
+            results,
+        }
+    }
+
+    /// The result of the scenario named `name`.
+    pub fn get(&self, name: &str) -> Option<&ScenarioResult> {
+        self.results.iter().find(|r| r.name == name)
+    }
+
+    pub fn to_json(&self) -> BenchResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> BenchResult<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn write_json(&self, path: &Path) -> BenchResult<()> {
+        std::fs::write(path, self.to_json()? + "\n").map_err(|source| io_error(path, source))
+    }
+
+    pub fn read_json(path: &Path) -> BenchResult<Self> {
+        Self::from_json(&std::fs::read_to_string(path).map_err(|source| io_error(path, source))?)
+    }
+
+    /// One row per scenario, with a `<phase>_secs` column per phase.
+    pub fn to_csv(&self) -> BenchResult<String> {
+        let mut out = csv::Writer::from_writer(Vec::new());
+        let mut header: Vec<String> = [
+            "name", "grid", "agents", "behavior", "output", "ticks", "samples", "setup_secs", "run_secs",
+            "run_secs_min", "run_secs_max", "ticks_per_sec", "wakeups_per_sec", "output_secs", "wakeups",
+            "departures", "arrivals", "routing_failures", "rss_before_bytes", "rss_peak_bytes",
+        ]
+        .map(String::from)
+        .into();
+        header.extend(PhaseSecs::default().named().map(|(name, _)| format!("{name}_secs")));
+        out.write_record(&header)?;
+
+        let opt = |v: Option<u64>| v.map_or(String::new(), |v| v.to_string());
+        for r in &self.results {
+            let mut row = vec![
+                r.name.clone(),
+                r.grid.to_string(),
+                r.agents.to_string(),
+                r.behavior.clone(),
+                r.output.clone(),
+                r.ticks.to_string(),
+                r.samples.to_string(),
+                r.setup_secs.to_string(),
+                r.run_secs.to_string(),
+                r.run_secs_min.to_string(),
+                r.run_secs_max.to_string(),
+                r.ticks_per_sec.to_string(),
+                r.wakeups_per_sec.to_string(),
+                r.output_secs.to_string(),
+                r.wakeups.to_string(),
+                r.departures.to_string(),
+                r.arrivals.to_string(),
+                r.routing_failures.to_string(),
+                opt(r.rss_before_bytes),
+                opt(r.rss_peak_bytes),
+            ];
+            row.extend(r.phase_secs.named().map(|(_, secs)| secs.to_string()));
+            out.write_record(&row)?;
+        }
+        let bytes = out.into_inner().map_err(|e| BenchError::Csv(e.into_error().into()))?;
+        Ok(String::from_utf8(bytes).expect("CSV of UTF-8 fields"))
+    }
+
+    pub fn write_csv(&self, path: &Path) -> BenchResult<()> {
+        std::fs::write(path, self.to_csv()?).map_err(|source| io_error(path, source))
+    }
+
+    /// How each scenario's median run time changed since `baseline`, for
+    /// the scenarios both reports measured.
+    pub fn compare(&self, baseline: &BenchReport) -> Vec<Change> {
+        self.results
+            .iter()
+            .filter_map(|current| {
+                let base = baseline.get(&current.name)?;
+                Some(Change {
+                    name:            current.name.clone(),
+                    baseline_secs:   base.run_secs,
+                    current_secs:    current.run_secs,
+                    ratio:           current.run_secs / base.run_secs.max(f64::MIN_POSITIVE),
+                    wakeups_changed: (base.ticks, base.wakeups) != (current.ticks, current.wakeups),
+                })
+            })
+            .collect()
+    }
+}
+
+/// A plain-text table of the results.
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.results.iter().map(|r| r.name.len()).max().unwrap_or(0).max("scenario".len());
+        writeln!(
+            f,
+            "{:width$}  {:>9}  {:>9}  {:>12}  {:>14}  {:>9}  {:>10}",
+            "scenario", "setup s", "run s", "ticks/s", "wake-ups/s", "output s", "peak MiB",
+        )?;
+        for r in &self.results {
+            let peak = r.rss_peak_bytes.map_or("-".into(), |b| format!("{:.1}", b as f64 / (1 << 20) as f64));
+            writeln!(
+                f,
+                "{:width$}  {:>9.3}  {:>9.3}  {:>12.1}  {:>14.0}  {:>9.3}  {:>10}",
+                r.name, r.setup_secs, r.run_secs, r.ticks_per_sec, r.wakeups_per_sec, r.output_secs, peak,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> BenchError {
+    BenchError::Io { path: path.to_path_buf(), source }
+}
+
+// ── Change ────────────────────────────────────────────────────────────────────
+
+/// One scenario's median run time in a baseline report and now.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub name:            String,
+    pub baseline_secs:   f64,
+    pub current_secs:    f64,
+    /// `current_secs / baseline_secs`.
+    pub ratio:           f64,
+    /// The run did a different amount of work (ticks or wake-ups), so the
+    /// times are not comparable as-is.
+    pub wakeups_changed: bool,
+}
+
+impl Change {
+    /// Slower than the baseline by more than `tolerance` (e.g. `0.1` for
+    /// 10 %).
+    pub fn is_regression(&self, tolerance: f64) -> bool {
+        self.ratio > 1.0 + tolerance
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {:.3}s -> {:.3}s ({:+.1}%)",
+            self.name,
+            self.baseline_secs,
+            self.current_secs,
+            (self.ratio - 1.0) * 100.0,
+        )?;
+        if self.wakeups_changed {
+            write!(f, " [workload changed]")?;
+        }
+        Ok(())
+    }
+}
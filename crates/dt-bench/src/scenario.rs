@@ -0,0 +1,171 @@
+//! `BenchScenario` and the standard scenario matrix.
+
+use dt_cli::{BuiltinBehavior, OutputBackend};
+
+/// Ticks of a standard scenario: one week of hourly ticks.
+pub const STANDARD_TICKS: u64 = 168;
+
+/// Ticks of a quick scenario: two days of hourly ticks.
+pub const QUICK_TICKS: u64 = 48;
+
+/// One benchmark scenario: `agents` commuters (or idlers) on a square
+/// `grid` × `grid` road network with 500 m blocks at 50 km/h, each
+/// following the same daily plan (leave at 08:00, work nine hours) for
+/// `ticks` hourly ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchScenario {
+    pub grid:     u32,
+    pub agents:   usize,
+    pub behavior: BuiltinBehavior,
+    pub output:   OutputBackend,
+    pub ticks:    u64,
+    pub seed:     u64,
+}
+
+impl BenchScenario {
+    /// A scenario without output, over [`STANDARD_TICKS`] ticks.
+    pub fn new(grid: u32, agents: usize, behavior: BuiltinBehavior) -> Self {
+        Self { grid, agents, behavior, output: OutputBackend::None, ticks: STANDARD_TICKS, seed: 42 }
+    }
+
+    /// Write output with `backend` (into a scratch directory).
+    pub fn output(mut self, backend: OutputBackend) -> Self {
+        self.output = backend;
+        self
+    }
+
+    pub fn ticks(mut self, ticks: u64) -> Self {
+        self.ticks = ticks;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// A stable name such as `grid32/a10000/commute/none`, used to match
+    /// results across reports.  The tick count is not part of it.
+    pub fn name(&self) -> String {
+        format!("grid{}/a{}/{}/{}", self.grid, self.agents, behavior_name(self.behavior), self.output.name())
+    }
+}
+
+/// The name of `behavior` in a scenario file.
+pub fn behavior_name(behavior: BuiltinBehavior) -> &'static str {
+    match behavior {
+        BuiltinBehavior::Commute => "commute",
+        BuiltinBehavior::Noop    => "noop",
+    }
+}
+
+// ── Matrix ────────────────────────────────────────────────────────────────────
+
+/// Every combination of grid size, agent count, behavior, and output
+/// backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix {
+    grids:     Vec<u32>,
+    agents:    Vec<usize>,
+    behaviors: Vec<BuiltinBehavior>,
+    outputs:   Vec<OutputBackend>,
+    ticks:     u64,
+}
+
+impl Matrix {
+    /// One 10 × 10 grid with 1 000 commuters, no output, [`STANDARD_TICKS`]
+    /// ticks; narrow or widen it with the setters.
+    pub fn new() -> Self {
+        Self {
+            grids:     vec![10],
+            agents:    vec![1_000],
+            behaviors: vec![BuiltinBehavior::Commute],
+            outputs:   vec![OutputBackend::None],
+            ticks:     STANDARD_TICKS,
+        }
+    }
+
+    pub fn grids(mut self, grids: impl IntoIterator<Item = u32>) -> Self {
+        self.grids = grids.into_iter().collect();
+        self
+    }
+
+    pub fn agents(mut self, agents: impl IntoIterator<Item = usize>) -> Self {
+        self.agents = agents.into_iter().collect();
+        self
+    }
+
+    pub fn behaviors(mut self, behaviors: impl IntoIterator<Item = BuiltinBehavior>) -> Self {
+        self.behaviors = behaviors.into_iter().collect();
+        self
+    }
+
+    pub fn outputs(mut self, outputs: impl IntoIterator<Item = OutputBackend>) -> Self {
+        self.outputs = outputs.into_iter().collect();
+        self
+    }
+
+    pub fn ticks(mut self, ticks: u64) -> Self {
+        self.ticks = ticks;
+        self
+    }
+
+    /// The scenarios, grid-major.
+    pub fn scenarios(&self) -> Vec<BenchScenario> {
+        let mut scenarios = Vec::new();
+        for &grid in &self.grids {
+            for &agents in &self.agents {
+                for &behavior in &self.behaviors {
+                    for &output in &self.outputs {
+                        scenarios.push(BenchScenario::new(grid, agents, behavior).output(output).ticks(self.ticks));
+                    }
+                }
+            }
+        }
+        scenarios
+    }
+}
+
+impl Default for Matrix {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The output backends this build can write: `csv`, plus `parquet`,
+/// `sqlite`, and `jsonl` when the matching features are enabled.
+pub fn output_backends() -> Vec<OutputBackend> {
+    let mut backends = vec![OutputBackend::Csv];
+    if cfg!(feature = "parquet") {
+        backends.push(OutputBackend::Parquet);
+    }
+    if cfg!(feature = "sqlite") {
+        backends.push(OutputBackend::Sqlite);
+    }
+    if cfg!(feature = "jsonl") {
+        backends.push(OutputBackend::Jsonl);
+    }
+    backends
+}
+
+/// The standard suite, compared release to release:
+///
+/// - the tick loop and routing: 10², 32², and 100² grids × 1 k, 10 k, and
+///   100 k agents × `commute` and `noop`, without output;
+/// - output: a 32² grid with 10 k commuters and each of
+///   [`output_backends`].
+pub fn standard_scenarios() -> Vec<BenchScenario> {
+    let all = [BuiltinBehavior::Commute, BuiltinBehavior::Noop];
+    let mut scenarios = Matrix::new().grids([10, 32, 100]).agents([1_000, 10_000, 100_000]).behaviors(all).scenarios();
+    scenarios.extend(Matrix::new().grids([32]).agents([10_000]).outputs(output_backends()).scenarios());
+    scenarios
+}
+
+/// A small suite for smoke tests and CI: the standard suite's shape on a
+/// 10² grid with 1 k agents over [`QUICK_TICKS`] ticks.
+pub fn quick_scenarios() -> Vec<BenchScenario> {
+    let all = [BuiltinBehavior::Commute, BuiltinBehavior::Noop];
+    let mut scenarios = Matrix::new().behaviors(all).ticks(QUICK_TICKS).scenarios();
+    scenarios.extend(Matrix::new().outputs(output_backends()).ticks(QUICK_TICKS).scenarios());
+    scenarios
+}
@@ -0,0 +1,171 @@
+// ── Scenarios ─────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod scenario_tests {
+    use std::collections::BTreeSet;
+
+    use dt_cli::{BuiltinBehavior, OutputBackend};
+
+    use crate::scenario::{QUICK_TICKS, output_backends};
+    use crate::{BenchScenario, Matrix, quick_scenarios, standard_scenarios};
+
+    #[test]
+    fn names_are_stable_and_unique() {
+        let s = BenchScenario::new(32, 10_000, BuiltinBehavior::Commute).output(OutputBackend::Csv).ticks(5);
+        assert_eq!(s.name(), "grid32/a10000/commute/csv");
+
+        for suite in [standard_scenarios(), quick_scenarios()] {
+            let names: BTreeSet<String> = suite.iter().map(BenchScenario::name).collect();
+            assert_eq!(names.len(), suite.len());
+        }
+    }
+
+    #[test]
+    fn matrix_is_the_cross_product() {
+        let scenarios = Matrix::new()
+            .grids([5, 10])
+            .agents([10, 20, 30])
+            .behaviors([BuiltinBehavior::Commute, BuiltinBehavior::Noop])
+            .ticks(7)
+            .scenarios();
+        assert_eq!(scenarios.len(), 12);
+        assert_eq!(scenarios[0].name(), "grid5/a10/commute/none");
+        assert_eq!(scenarios[11].name(), "grid10/a30/noop/none");
+        assert!(scenarios.iter().all(|s| s.ticks == 7 && s.output == OutputBackend::None));
+    }
+
+    #[test]
+    fn suites_cover_behaviors_and_backends() {
+        let standard = standard_scenarios();
+        assert_eq!(standard.len(), 18 + output_backends().len());
+        assert!(standard.iter().any(|s| s.grid == 100 && s.agents == 100_000));
+        let quick = quick_scenarios();
+        assert!(quick.iter().all(|s| s.ticks == QUICK_TICKS && s.agents == 1_000));
+        assert!(quick.iter().any(|s| s.output == OutputBackend::Csv));
+    }
+}
+
+// ── Measuring ─────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod measure_tests {
+    use dt_cli::{BuiltinBehavior, OutputBackend};
+
+    use crate::{BenchScenario, Runner, measure};
+
+    fn tiny(behavior: BuiltinBehavior) -> BenchScenario {
+        BenchScenario::new(4, 50, behavior).ticks(48)
+    }
+
+    #[test]
+    fn commuters_wake_depart_and_arrive() {
+        let dir = tempfile::tempdir().unwrap();
+        let sample = measure(&tiny(BuiltinBehavior::Commute), dir.path()).unwrap();
+        assert_eq!(sample.ticks, 48);
+        assert!(sample.wakeups >= 100);
+        assert!(sample.departures >= 100);
+        assert_eq!(sample.routing_failures, 0);
+        assert!(sample.phases.total > 0.0);
+        assert!(sample.run >= sample.output);
+        if cfg!(any(target_os = "linux", target_os = "macos", target_os = "windows")) {
+            assert!(sample.rss_peak.unwrap() > 0);
+        }
+
+        let idle = measure(&tiny(BuiltinBehavior::Noop), dir.path()).unwrap();
+        assert_eq!(idle.departures, 0);
+    }
+
+    #[test]
+    fn runner_summarizes_samples_and_cleans_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let scenarios = [tiny(BuiltinBehavior::Commute), tiny(BuiltinBehavior::Commute).output(OutputBackend::Csv)];
+        let report = Runner::new().samples(3).quiet(true).output_dir(dir.path().join("scratch")).run(&scenarios).unwrap();
+
+        assert_eq!(report.results.len(), 2);
+        let [none, csv] = [&report.results[0], &report.results[1]];
+        assert_eq!((none.samples, none.ticks, none.output.as_str()), (3, 48, "none"));
+        assert!(none.run_secs_min <= none.run_secs && none.run_secs <= none.run_secs_max);
+        assert!(none.wakeups_per_sec > 0.0);
+        // Output does not change what the sim does.
+        assert_eq!((csv.wakeups, csv.departures), (none.wakeups, none.departures));
+        assert!(csv.output_secs > 0.0);
+        assert!(!dir.path().join("scratch").exists());
+    }
+}
+
+// ── Reports ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod report_tests {
+    use crate::{BenchReport, PhaseSecs, REPORT_SCHEMA, ScenarioResult};
+
+    fn result(name: &str, run_secs: f64, wakeups: u64) -> ScenarioResult {
+        ScenarioResult {
+            name:             name.into(),
+            grid:             10,
+            agents:           1_000,
+            behavior:         "commute".into(),
+            output:           "none".into(),
+            ticks:            48,
+            samples:          1,
+            setup_secs:       0.01,
+            run_secs,
+            run_secs_min:     run_secs,
+            run_secs_max:     run_secs,
+            ticks_per_sec:    48.0 / run_secs,
+            wakeups_per_sec:  wakeups as f64 / run_secs,
+            output_secs:      0.0,
+            phase_secs:       PhaseSecs { apply: run_secs / 2.0, total: run_secs, ..Default::default() },
+            wakeups,
+            departures:       2_000,
+            arrivals:         2_000,
+            routing_failures: 0,
+            rss_before_bytes: Some(1 << 20),
+            rss_peak_bytes:   None,
+        }
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let report = BenchReport::new(vec![result("a", 1.0, 10), result("b", 2.0, 20)]);
+        assert_eq!(report.schema, REPORT_SCHEMA);
+        assert!(report.host.cpus >= 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bench.json");
+        report.write_json(&path).unwrap();
+        assert_eq!(BenchReport::read_json(&path).unwrap(), report);
+        assert!(BenchReport::from_json("{}").is_err());
+    }
+
+    #[test]
+    fn csv_has_one_row_per_scenario() {
+        let csv = BenchReport::new(vec![result("a", 1.0, 10), result("b", 2.0, 20)]).to_csv().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("name,grid,agents,behavior,output,ticks,"));
+        assert!(lines[0].ends_with(",reactions_secs,custom_phases_secs,total_secs"));
+        assert_eq!(lines[1].split(',').count(), lines[0].split(',').count());
+        assert!(lines[1].contains(",1048576,,"));
+    }
+
+    #[test]
+    fn comparison_flags_regressions_and_workload_changes() {
+        let baseline = BenchReport::new(vec![result("a", 1.0, 10), result("b", 2.0, 20), result("gone", 1.0, 1)]);
+        let current = BenchReport::new(vec![result("a", 1.2, 10), result("b", 1.9, 21), result("new", 1.0, 1)]);
+        let changes = current.compare(&baseline);
+
+        assert_eq!(changes.len(), 2);
+        let [a, b] = [&changes[0], &changes[1]];
+        assert!(a.is_regression(0.10) && !a.is_regression(0.25));
+        assert!(!a.wakeups_changed);
+        assert!(!b.is_regression(0.0));
+        assert!(b.wakeups_changed);
+        assert_eq!(a.to_string(), "a: 1.000s -> 1.200s (+20.0%)");
+        assert!(b.to_string().ends_with("[workload changed]"));
+
+        let table = current.to_string();
+        assert!(table.starts_with("scenario"));
+        assert_eq!(table.lines().count(), 4);
+    }
+}
@@ -0,0 +1,47 @@
+//! Error types for dt-calibration.
+
+use std::path::PathBuf;
+
+use dt_core::{DtError, ErrorCategory};
+use thiserror::Error;
+
+/// Errors that can occur while setting up or running a sweep.
+#[derive(Debug, Error)]
+pub enum CalibrationError {
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+
+    /// The scenario template could not be parsed or re-serialized.
+    #[error("scenario template: {0}")]
+    Template(String),
+
+    /// A parameter is malformed or cannot be set on the template.
+    #[error("parameter {key}: {message}")]
+    Parameter { key: String, message: String },
+
+    /// The sweep itself (or its spec file) is inconsistent.
+    #[error("sweep: {0}")]
+    Sweep(String),
+
+    /// A run failed; `run` names its point and seed.
+    #[error("{run}: {source}")]
+    Run { run: String, source: dt_cli::CliError },
+
+    #[error("invalid sweep report: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error(transparent)]
+    Cli(#[from] dt_cli::CliError),
+}
+
+/// Alias for `Result<T, CalibrationError>`.
+pub type CalibrationResult<T> = Result<T, CalibrationError>;
+
+impl From<CalibrationError> for DtError {
+    fn from(err: CalibrationError) -> Self {
+        DtError::subsystem(ErrorCategory::Sim, err)
+    }
+}
@@ -0,0 +1,72 @@
+//! `dt-calibration` — parameter sweeps and calibration against observed
+//! data.
+//!
+//! A [`Sweep`] runs one dt-cli scenario many times: at every point of a
+//! [`Design`] — a full grid of parameter values, or a Latin hypercube
+//! sample of ranges — and with each of a set of seeds, several runs at a
+//! time.  A [`Parameter`] names a scenario option by its dotted TOML key,
+//! so anything a scenario file can say can be swept: plan times, the
+//! travel mode, an intervention's factor, `[sim]` settings.
+//!
+//! Each run is reduced to a [`RunOutput`] (trip and routing totals, and
+//! link volumes if wanted) and scored by a [`Scorer`] — a closure, or
+//! [`LinkCounts`] comparing link volumes with observed counts.  The
+//! [`SweepReport`] holds every run and summarizes each point over its
+//! seeds; the best point is the calibrated one:
+//!
+//! ```rust,no_run
+//! use dt_calibration::{Design, LinkCounts, Metric, Parameter, ScenarioTemplate, Sweep};
+//!
+//! let counts = LinkCounts::read_csv("counts.csv".as_ref(), 24)?.metric(Metric::Geh);
+//! let report = Sweep::new(ScenarioTemplate::load("scenario.toml".as_ref())?)
+//!     .parameter(Parameter::values("plans.daily.depart", ["7h", "7h30m", "8h"]))
+//!     .parameter(Parameter::values("behavior.mode", ["car", "bike"]))
+//!     .replicates(3)
+//!     .scorer(counts)
+//!     .run()?;
+//! report.write_csv("sweep.csv".as_ref())?;
+//! println!("{report}\nbest: {:?}", report.best());
+//! # Ok::<(), dt_calibration::CalibrationError>(())
+//! ```
+//!
+//! The `dt-calibrate` binary runs a sweep described in a TOML file (see
+//! [`spec`]):
+//!
+//! ```text
+//! dt-calibrate sweep.toml [--json PATH] [--csv PATH] [--threads N] [--quiet]
+//! ```
+//!
+//! # Crate layout
+//!
+//! | Module       | Contents                                                    |
+//! |--------------|-------------------------------------------------------------|
+//! | [`param`]    | `Value`, `Parameter`, `Space`, `Design`, `ScenarioTemplate` |
+//! | [`sweep`]    | `Sweep`, `simulate`                                         |
+//! | [`score`]    | `RunOutput`, `Scorer`, `LinkCounts`, `Metric`               |
+//! | [`report`]   | `SweepReport`, `RunResult`, `PointSummary`                  |
+//! | [`spec`]     | `SweepSpec` (the sweep file)                                |
+//! | [`error`]    | `CalibrationError`, `CalibrationResult<T>`                  |
+//!
+//! # Feature flags
+//!
+//! | Feature          | Enables                                          |
+//! |------------------|--------------------------------------------------|
+//! | `parallel`       | dt-sim's Rayon-parallel intent phase in each run |
+//! | `osm`, `parquet` | scenario sources needing those dt-cli features   |
+
+pub mod error;
+pub mod param;
+pub mod report;
+pub mod score;
+pub mod spec;
+pub mod sweep;
+
+#[cfg(test)]
+mod tests;
+
+pub use error::{CalibrationError, CalibrationResult};
+pub use param::{Design, Parameter, ScenarioTemplate, Space, Value};
+pub use report::{PointSummary, RunResult, SweepReport};
+pub use score::{LinkCounts, Metric, RunOutput, Scorer};
+pub use spec::SweepSpec;
+pub use sweep::{Sweep, simulate};
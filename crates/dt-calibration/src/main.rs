@@ -0,0 +1,94 @@
+//! `dt-calibrate` — run the parameter sweep described in a TOML file.
+//!
+//! ```text
+//! dt-calibrate sweep.toml [--json PATH] [--csv PATH] [--threads N] [--quiet]
+//! ```
+//!
+//! Prints a table of the points, best first, and writes every run to
+//! `--json` and `--csv`.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use dt_calibration::{CalibrationResult, SweepSpec};
+
+const USAGE: &str = "usage: dt-calibrate <sweep.toml> [--json PATH] [--csv PATH] [--threads N] [--quiet]";
+
+#[derive(Debug)]
+struct Args {
+    sweep:   Option<PathBuf>,
+    json:    Option<PathBuf>,
+    csv:     Option<PathBuf>,
+    threads: Option<usize>,
+    quiet:   bool,
+    help:    bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args { sweep: None, json: None, csv: None, threads: None, quiet: false, help: false };
+    let mut it = std::env::args().skip(1);
+    while let Some(arg) = it.next() {
+        let mut value = |flag: &str| it.next().ok_or_else(|| format!("{flag} needs a value"));
+        match arg.as_str() {
+            "-q" | "--quiet" => args.quiet = true,
+            "-h" | "--help" => args.help = true,
+            "--json" => args.json = Some(value("--json")?.into()),
+            "--csv" => args.csv = Some(value("--csv")?.into()),
+            "--threads" => {
+                args.threads = Some(value("--threads")?.parse().map_err(|_| "--threads takes a number".to_string())?);
+            }
+            other if other.starts_with('-') => return Err(format!("unknown argument {other}")),
+            other if args.sweep.is_none() => args.sweep = Some(other.into()),
+            other => return Err(format!("unexpected argument {other}")),
+        }
+    }
+    Ok(args)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) if args.help => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Ok(Args { sweep: None, .. }) => {
+            eprintln!("missing sweep file\n{USAGE}");
+            return ExitCode::from(2);
+        }
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &Args) -> CalibrationResult<()> {
+    let spec = SweepSpec::load(args.sweep.as_deref().expect("checked in main"))?;
+    let mut sweep = spec.sweep()?.quiet(args.quiet);
+    if let Some(threads) = args.threads {
+        sweep = sweep.threads(threads);
+    }
+    let report = sweep.run()?;
+    print!("{report}");
+    if let Some(best) = report.best() {
+        let values: Vec<String> =
+            report.parameters.iter().zip(&best.values).map(|(key, value)| format!("{key} = {value}")).collect();
+        println!("\nbest: point {} ({})", best.point, values.join(", "));
+    }
+    if let Some(path) = &args.json {
+        report.write_json(path)?;
+    }
+    if let Some(path) = &args.csv {
+        report.write_csv(path)?;
+    }
+    Ok(())
+}
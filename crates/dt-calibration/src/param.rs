@@ -0,0 +1,287 @@
+//! Parameters, the designs that turn them into points, and the scenario
+//! template the points are applied to.
+//!
+//! A parameter names a scenario option by its dotted key — the TOML path,
+//! with a number indexing into an array (`interventions.0.factor`) — and
+//! the values it may take.  Each point of a design is one value per
+//! parameter, written over a copy of the template before it is parsed as a
+//! [`Scenario`].
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use dt_cli::Scenario;
+use dt_core::SimRng;
+
+use crate::{CalibrationError, CalibrationResult};
+
+// ── Value ─────────────────────────────────────────────────────────────────────
+
+/// One value of a parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+}
+
+impl Value {
+    fn to_toml(&self) -> toml::Value {
+        match self {
+            Value::Bool(b)  => toml::Value::Boolean(*b),
+            Value::Int(i)   => toml::Value::Integer(*i),
+            Value::Float(x) => toml::Value::Float(*x),
+            Value::Str(s)   => toml::Value::String(s.clone()),
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(i: i64) -> Self {
+        Value::Int(i)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Self {
+        Value::Float(x)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::Str(s.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::Str(s)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(b)  => write!(f, "{b}"),
+            Value::Int(i)   => write!(f, "{i}"),
+            Value::Float(x) => write!(f, "{x}"),
+            Value::Str(s)   => f.write_str(s),
+        }
+    }
+}
+
+// ── Parameter ─────────────────────────────────────────────────────────────────
+
+/// A scenario option to vary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    /// Dotted key of the option, e.g. `plans.daily.depart`.
+    pub key:   String,
+    pub space: Space,
+}
+
+/// The values a [`Parameter`] may take.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Space {
+    /// Exactly these values.
+    Values(Vec<Value>),
+    /// Anywhere in `[min, max]`; whole numbers only if `integer`.  Only
+    /// Latin hypercube designs can sample a range.
+    Range { min: f64, max: f64, integer: bool },
+}
+
+impl Parameter {
+    /// `key` taking each of `values`.
+    pub fn values<V: Into<Value>>(key: &str, values: impl IntoIterator<Item = V>) -> Self {
+        Self { key: key.into(), space: Space::Values(values.into_iter().map(Into::into).collect()) }
+    }
+
+    /// `key` anywhere in `[min, max]`.
+    pub fn range(key: &str, min: f64, max: f64) -> Self {
+        Self { key: key.into(), space: Space::Range { min, max, integer: false } }
+    }
+
+    /// `key` taking whole numbers in `[min, max]`.
+    pub fn int_range(key: &str, min: i64, max: i64) -> Self {
+        Self { key: key.into(), space: Space::Range { min: min as f64, max: max as f64, integer: true } }
+    }
+
+    pub(crate) fn validate(&self) -> CalibrationResult<()> {
+        let invalid = |message: &str| CalibrationError::Parameter { key: self.key.clone(), message: message.into() };
+        if self.key.is_empty() || self.key.split('.').any(str::is_empty) {
+            return Err(invalid("the key must be dot-separated names"));
+        }
+        match &self.space {
+            Space::Values(values) if values.is_empty() => Err(invalid("no values")),
+            Space::Range { min, max, .. } if !(min.is_finite() && max.is_finite() && min <= max) => {
+                Err(invalid("`min` must not be above `max`"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The value at `u` in `[0, 1)` of the way through the space.
+    fn at(&self, u: f64) -> Value {
+        match &self.space {
+            Space::Values(values) => values[((u * values.len() as f64) as usize).min(values.len() - 1)].clone(),
+            Space::Range { min, max, integer: false } => Value::Float(min + u * (max - min)),
+            Space::Range { min, max, integer: true } => {
+                let (min, max) = (min.ceil(), max.floor());
+                Value::Int((min + (u * (max - min + 1.0)).floor()).min(max) as i64)
+            }
+        }
+    }
+}
+
+// ── Design ────────────────────────────────────────────────────────────────────
+
+/// How a sweep picks its points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Design {
+    /// Every combination of the parameters' values, the first parameter
+    /// varying slowest.
+    Grid,
+    /// `samples` points stratified along every parameter (each parameter's
+    /// space is cut into `samples` equal slices and each slice is used
+    /// once), drawn from `seed`.  Value lists are treated as evenly spaced
+    /// levels.
+    LatinHypercube { samples: usize, seed: u64 },
+}
+
+impl Design {
+    /// The points of this design over `parameters`, one value per
+    /// parameter.
+    pub fn points(&self, parameters: &[Parameter]) -> CalibrationResult<Vec<Vec<Value>>> {
+        for p in parameters {
+            p.validate()?;
+        }
+        match *self {
+            Design::Grid => grid(parameters),
+            Design::LatinHypercube { samples, seed } => Ok(latin_hypercube(parameters, samples, seed)),
+        }
+    }
+}
+
+fn grid(parameters: &[Parameter]) -> CalibrationResult<Vec<Vec<Value>>> {
+    let mut points = vec![Vec::new()];
+    for p in parameters {
+        let Space::Values(values) = &p.space else {
+            return Err(CalibrationError::Parameter {
+                key:     p.key.clone(),
+                message: "a range needs a Latin hypercube design; grids take `values`".into(),
+            });
+        };
+        points = points
+            .into_iter()
+            .flat_map(|point| {
+                values.iter().map(move |v| {
+                    let mut point = point.clone();
+                    point.push(v.clone());
+                    point
+                })
+            })
+            .collect();
+    }
+    Ok(points)
+}
+
+fn latin_hypercube(parameters: &[Parameter], samples: usize, seed: u64) -> Vec<Vec<Value>> {
+    let mut rng = SimRng::new(seed).stream("dt-calibration-lhs");
+    let mut points = vec![Vec::with_capacity(parameters.len()); samples];
+    for p in parameters {
+        // A random permutation of the slices (Fisher–Yates).
+        let mut slices: Vec<usize> = (0..samples).collect();
+        for i in (1..samples).rev() {
+            slices.swap(i, rng.gen_range(0..=i));
+        }
+        for (point, slice) in points.iter_mut().zip(slices) {
+            let u = (slice as f64 + rng.random::<f64>()) / samples as f64;
+            point.push(p.at(u));
+        }
+    }
+    points
+}
+
+// ── ScenarioTemplate ──────────────────────────────────────────────────────────
+
+/// A scenario file kept as a TOML document so parameters can be written
+/// into it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioTemplate {
+    doc:  toml::Table,
+    /// Directory relative paths are resolved against.
+    base: PathBuf,
+}
+
+impl ScenarioTemplate {
+    /// Read the scenario file at `path` (TOML, or JSON if the name ends in
+    /// `.json`).
+    pub fn load(path: &Path) -> CalibrationResult<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|source| CalibrationError::Io { path: path.to_path_buf(), source })?;
+        let base = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let doc = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            serde_json::from_str(&text).map_err(|e| CalibrationError::Template(format!("{}: {e}", path.display())))?
+        } else {
+            text.parse().map_err(|e| CalibrationError::Template(format!("{}: {e}", path.display())))?
+        };
+        let template = Self { doc, base };
+        template.instantiate(&[], &[])?;
+        Ok(template)
+    }
+
+    /// A TOML scenario whose relative paths are resolved against `base`.
+    pub fn from_toml(text: &str, base: &Path) -> CalibrationResult<Self> {
+        let doc = text.parse().map_err(|e: toml::de::Error| CalibrationError::Template(e.to_string()))?;
+        let template = Self { doc, base: base.to_path_buf() };
+        template.instantiate(&[], &[])?;
+        Ok(template)
+    }
+
+    /// The scenario with each of `parameters` set to the matching entry of
+    /// `values`.
+    pub fn instantiate(&self, parameters: &[Parameter], values: &[Value]) -> CalibrationResult<Scenario> {
+        let mut doc = self.doc.clone();
+        for (p, value) in parameters.iter().zip(values) {
+            set_key(&mut doc, &p.key, value.to_toml())
+                .map_err(|message| CalibrationError::Parameter { key: p.key.clone(), message })?;
+        }
+        let text = toml::to_string(&doc).map_err(|e| CalibrationError::Template(e.to_string()))?;
+        Ok(Scenario::from_toml(&text)?.resolved_against(&self.base))
+    }
+}
+
+/// Set the dotted `key` in `doc`, creating missing tables.
+fn set_key(doc: &mut toml::Table, key: &str, value: toml::Value) -> Result<(), String> {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let last = parts.pop().expect("split yields at least one part");
+    let mut slot = doc.entry(parts.first().copied().unwrap_or(last)).or_insert_with(|| toml::Table::new().into());
+    if parts.is_empty() {
+        *slot = value;
+        return Ok(());
+    }
+    for part in parts[1..].iter().copied().chain([last]) {
+        slot = match slot {
+            toml::Value::Table(table) => table.entry(part).or_insert_with(|| toml::Table::new().into()),
+            toml::Value::Array(array) => {
+                let index: usize = part.parse().map_err(|_| format!("`{part}` does not index an array"))?;
+                let len = array.len();
+                array.get_mut(index).ok_or_else(|| format!("index {index} is past the end ({len} entries)"))?
+            }
+            _ => return Err(format!("`{part}` is inside a value that is not a table or an array")),
+        };
+    }
+    *slot = value;
+    Ok(())
+}
@@ -0,0 +1,179 @@
+//! `SweepReport` — every run of a sweep, summarized per point.
+
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{CalibrationError, CalibrationResult, Value};
+
+// ── RunResult ─────────────────────────────────────────────────────────────────
+
+/// One run of a sweep: a point of the design with one seed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunResult {
+    /// Index of the point in the design.
+    pub point:            usize,
+    pub seed:             u64,
+    /// One value per parameter, in [`SweepReport::parameters`] order.
+    pub values:           Vec<Value>,
+    /// `None` if the sweep had no scorer.
+    pub score:            Option<f64>,
+    pub departures:       u64,
+    pub arrivals:         u64,
+    pub routing_failures: u64,
+    pub failures:         usize,
+    pub trips:            u64,
+    pub mean_travel_secs: f64,
+    pub wall_secs:        f64,
+}
+
+/// The runs of one point, over all seeds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointSummary {
+    pub point:      usize,
+    pub values:     Vec<Value>,
+    pub runs:       usize,
+    /// Mean score over the seeds, if scored.
+    pub score_mean: Option<f64>,
+    /// Sample standard deviation of the score; `0.0` for a single seed.
+    pub score_std:  Option<f64>,
+}
+
+// ── SweepReport ───────────────────────────────────────────────────────────────
+
+/// Every run of a sweep, in point then seed order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SweepReport {
+    /// The parameters' keys.
+    pub parameters: Vec<String>,
+    pub runs:       Vec<RunResult>,
+}
+
+impl SweepReport {
+    pub fn new(parameters: Vec<String>, mut runs: Vec<RunResult>) -> Self {
+        runs.sort_by_key(|r| r.point);
+        Self { parameters, runs }
+    }
+
+    /// One summary per point, in point order.
+    pub fn points(&self) -> Vec<PointSummary> {
+        self.runs
+            .chunk_by(|a, b| a.point == b.point)
+            .map(|runs| {
+                let scores: Option<Vec<f64>> = runs.iter().map(|r| r.score).collect();
+                let (mean, std) = match scores {
+                    Some(scores) => {
+                        let n = scores.len() as f64;
+                        let mean = scores.iter().sum::<f64>() / n;
+                        let var = scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0).max(1.0);
+                        (Some(mean), Some(var.sqrt()))
+                    }
+                    None => (None, None),
+                };
+                PointSummary {
+                    point:      runs[0].point,
+                    values:     runs[0].values.clone(),
+                    runs:       runs.len(),
+                    score_mean: mean,
+                    score_std:  std,
+                }
+            })
+            .collect()
+    }
+
+    /// The point with the lowest mean score, if the runs were scored.
+    pub fn best(&self) -> Option<PointSummary> {
+        self.points()
+            .into_iter()
+            .filter(|p| p.score_mean.is_some_and(f64::is_finite))
+            .min_by(|a, b| a.score_mean.unwrap().total_cmp(&b.score_mean.unwrap()))
+    }
+
+    pub fn to_json(&self) -> CalibrationResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> CalibrationResult<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn write_json(&self, path: &Path) -> CalibrationResult<()> {
+        std::fs::write(path, self.to_json()? + "\n").map_err(|source| io_error(path, source))
+    }
+
+    pub fn read_json(path: &Path) -> CalibrationResult<Self> {
+        Self::from_json(&std::fs::read_to_string(path).map_err(|source| io_error(path, source))?)
+    }
+
+    /// One row per run, with a column per parameter (named by its key).
+    pub fn to_csv(&self) -> CalibrationResult<String> {
+        let mut out = csv::Writer::from_writer(Vec::new());
+        let mut header = vec!["point".to_string(), "seed".into()];
+        header.extend(self.parameters.iter().cloned());
+        header.extend(
+            [
+                "score", "departures", "arrivals", "routing_failures", "failures", "trips", "mean_travel_secs",
+                "wall_secs",
+            ]
+            .map(String::from),
+        );
+        out.write_record(&header)?;
+
+        for r in &self.runs {
+            let mut row = vec![r.point.to_string(), r.seed.to_string()];
+            row.extend(r.values.iter().map(Value::to_string));
+            row.extend([
+                r.score.map_or(String::new(), |s| s.to_string()),
+                r.departures.to_string(),
+                r.arrivals.to_string(),
+                r.routing_failures.to_string(),
+                r.failures.to_string(),
+                r.trips.to_string(),
+                r.mean_travel_secs.to_string(),
+                r.wall_secs.to_string(),
+            ]);
+            out.write_record(&row)?;
+        }
+        let bytes = out.into_inner().map_err(|e| CalibrationError::Csv(e.into_error().into()))?;
+        Ok(String::from_utf8(bytes).expect("CSV of UTF-8 fields"))
+    }
+
+    pub fn write_csv(&self, path: &Path) -> CalibrationResult<()> {
+        std::fs::write(path, self.to_csv()?).map_err(|source| io_error(path, source))
+    }
+}
+
+/// A plain-text table of the points, best first if scored.
+impl fmt::Display for SweepReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut points = self.points();
+        points.sort_by(|a, b| {
+            let key = |p: &PointSummary| p.score_mean.unwrap_or(f64::INFINITY);
+            key(a).total_cmp(&key(b)).then(a.point.cmp(&b.point))
+        });
+        let width = |i: usize, key: &str| {
+            points.iter().map(|p| p.values[i].to_string().len()).max().unwrap_or(0).max(key.len())
+        };
+        let widths: Vec<usize> = self.parameters.iter().enumerate().map(|(i, key)| width(i, key)).collect();
+
+        write!(f, "{:>5}", "point")?;
+        for (key, w) in self.parameters.iter().zip(&widths) {
+            write!(f, "  {key:>w$}")?;
+        }
+        writeln!(f, "  {:>4}  {:>12}  {:>10}", "runs", "score", "std")?;
+        for p in &points {
+            write!(f, "{:>5}", p.point)?;
+            for (value, w) in p.values.iter().zip(&widths) {
+                write!(f, "  {:>w$}", value.to_string())?;
+            }
+            let num = |x: Option<f64>, prec: usize| x.map_or("-".into(), |x| format!("{x:.prec$}"));
+            writeln!(f, "  {:>4}  {:>12}  {:>10}", p.runs, num(p.score_mean, 4), num(p.score_std, 4))?;
+        }
+        Ok(())
+    }
+}
+
+fn io_error(path: &Path, source: std::io::Error) -> CalibrationError {
+    CalibrationError::Io { path: path.to_path_buf(), source }
+}
@@ -0,0 +1,168 @@
+//! What a run produced, and scoring it against observed data.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::{CalibrationError, CalibrationResult};
+
+// ── RunOutput ─────────────────────────────────────────────────────────────────
+
+/// Totals of one run, handed to the [`Scorer`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunOutput {
+    pub ticks:            u64,
+    pub departures:       u64,
+    pub arrivals:         u64,
+    pub routing_failures: u64,
+    /// Failures the sim collected (routing failures and the like).
+    pub failures:         usize,
+    /// Completed trips.
+    pub trips:            u64,
+    /// Routed travel time of the completed trips, summed.
+    pub travel_secs:      f64,
+    /// Vehicles entering each edge per interval, keyed by (interval start
+    /// tick, edge), if the scorer asked for them (see
+    /// [`Scorer::link_volume_interval`]).
+    pub link_volumes:     BTreeMap<(u64, u32), u64>,
+}
+
+impl RunOutput {
+    /// Mean routed travel time of the completed trips; `0.0` without trips.
+    pub fn mean_travel_secs(&self) -> f64 {
+        if self.trips == 0 { 0.0 } else { self.travel_secs / self.trips as f64 }
+    }
+}
+
+// ── Scorer ────────────────────────────────────────────────────────────────────
+
+/// Scores a run: lower is better.  Called from the sweep's worker threads.
+///
+/// Closures `Fn(&RunOutput) -> f64` are scorers:
+///
+/// ```rust,ignore
+/// // Aim for a mean trip of 20 minutes.
+/// sweep.scorer(|run: &RunOutput| (run.mean_travel_secs() - 1200.0).abs())
+/// ```
+pub trait Scorer: Sync {
+    fn score(&self, run: &RunOutput) -> f64;
+
+    /// Ticks per link-volume interval the scorer needs in
+    /// [`RunOutput::link_volumes`]; `None` (the default) skips counting.
+    fn link_volume_interval(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl<F: Fn(&RunOutput) -> f64 + Sync> Scorer for F {
+    fn score(&self, run: &RunOutput) -> f64 {
+        self(run)
+    }
+}
+
+// ── LinkCounts ────────────────────────────────────────────────────────────────
+
+/// How [`LinkCounts`] compares simulated and observed volumes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    /// Root mean squared difference.
+    #[default]
+    Rmse,
+    /// Mean GEH statistic, `sqrt(2 (m - c)² / (m + c))` for simulated `m`
+    /// and observed `c`; below 5 is the usual target for a link.
+    Geh,
+}
+
+#[derive(Deserialize)]
+struct CountRecord {
+    tick:     u64,
+    edge_id:  u32,
+    vehicles: u64,
+}
+
+/// Observed vehicle counts per link and interval, scored against each
+/// run's link volumes.
+///
+/// Only the observed (interval, edge) pairs are scored; a pair the run
+/// never used counts as zero vehicles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkCounts {
+    interval: u64,
+    metric:   Metric,
+    counts:   BTreeMap<(u64, u32), u64>,
+}
+
+impl LinkCounts {
+    /// Counts over intervals of `interval_ticks` ticks (`0` is treated as
+    /// `1`), keyed by (interval start tick, edge), scored by RMSE.
+    pub fn new(interval_ticks: u64, counts: BTreeMap<(u64, u32), u64>) -> Self {
+        Self { interval: interval_ticks.max(1), metric: Metric::default(), counts }
+    }
+
+    /// Read a CSV of `tick,edge_id,vehicles` rows — the format of dt-output's
+    /// `link_volumes.csv` — where `tick` starts an interval of
+    /// `interval_ticks`.
+    pub fn read_csv(path: &Path, interval_ticks: u64) -> CalibrationResult<Self> {
+        let file = std::fs::File::open(path)
+            .map_err(|source| CalibrationError::Io { path: path.to_path_buf(), source })?;
+        let mut counts = BTreeMap::new();
+        for record in csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(file).deserialize() {
+            let r: CountRecord = record?;
+            if !r.tick.is_multiple_of(interval_ticks.max(1)) {
+                return Err(CalibrationError::Sweep(format!(
+                    "{}: tick {} does not start a {interval_ticks}-tick interval",
+                    path.display(),
+                    r.tick
+                )));
+            }
+            *counts.entry((r.tick, r.edge_id)).or_default() += r.vehicles;
+        }
+        Ok(Self::new(interval_ticks, counts))
+    }
+
+    /// Score with `metric` instead of RMSE.
+    pub fn metric(mut self, metric: Metric) -> Self {
+        self.metric = metric;
+        self
+    }
+
+    /// The observed (interval, edge) pairs.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+}
+
+impl Scorer for LinkCounts {
+    fn score(&self, run: &RunOutput) -> f64 {
+        if self.counts.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = self
+            .counts
+            .iter()
+            .map(|(key, &observed)| {
+                let (m, c) = (run.link_volumes.get(key).copied().unwrap_or(0) as f64, observed as f64);
+                match self.metric {
+                    Metric::Rmse => (m - c).powi(2),
+                    Metric::Geh if m + c == 0.0 => 0.0,
+                    Metric::Geh => (2.0 * (m - c).powi(2) / (m + c)).sqrt(),
+                }
+            })
+            .sum();
+        let mean = total / self.counts.len() as f64;
+        match self.metric {
+            Metric::Rmse => mean.sqrt(),
+            Metric::Geh  => mean,
+        }
+    }
+
+    fn link_volume_interval(&self) -> Option<u64> {
+        Some(self.interval)
+    }
+}
@@ -0,0 +1,153 @@
+//! The sweep file read by `dt-calibrate`.
+//!
+//! ```toml
+//! scenario   = "scenario.toml"
+//! replicates = 3              # or: seeds = [1, 2, 3]
+//! threads    = 4              # default: every core
+//!
+//! [latin_hypercube]           # optional: without it, every combination
+//! samples = 20
+//! seed    = 1
+//!
+//! [[parameters]]
+//! key    = "plans.daily.depart"
+//! values = ["7h", "8h", "9h"]
+//!
+//! [[parameters]]
+//! key     = "interventions.0.factor"
+//! min     = 1.0
+//! max     = 2.0
+//! integer = false             # optional
+//!
+//! [link_counts]               # optional: score against observed counts
+//! csv            = "counts.csv"
+//! interval_ticks = 24
+//! metric         = "geh"      # or "rmse" (the default)
+//! ```
+//!
+//! Relative paths are resolved against the sweep file's directory.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::param::{Design, Parameter, ScenarioTemplate, Space, Value};
+use crate::score::{LinkCounts, Metric};
+use crate::{CalibrationError, CalibrationResult, Sweep};
+
+/// A sweep file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SweepSpec {
+    pub scenario:        PathBuf,
+    #[serde(default)]
+    pub parameters:      Vec<ParameterSpec>,
+    pub latin_hypercube: Option<LatinHypercubeSpec>,
+    pub replicates:      Option<u32>,
+    pub seeds:           Option<Vec<u64>>,
+    pub threads:         Option<usize>,
+    pub link_counts:     Option<LinkCountsSpec>,
+}
+
+/// `[[parameters]]`: `values`, or `min` and `max`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParameterSpec {
+    pub key:     String,
+    pub values:  Option<Vec<Value>>,
+    pub min:     Option<f64>,
+    pub max:     Option<f64>,
+    #[serde(default)]
+    pub integer: bool,
+}
+
+/// `[latin_hypercube]`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LatinHypercubeSpec {
+    pub samples: usize,
+    #[serde(default)]
+    pub seed:    u64,
+}
+
+/// `[link_counts]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LinkCountsSpec {
+    pub csv:            PathBuf,
+    pub interval_ticks: u64,
+    #[serde(default)]
+    pub metric:         Metric,
+}
+
+impl SweepSpec {
+    /// Read the sweep file at `path`, resolving its paths against the
+    /// file's directory.
+    pub fn load(path: &Path) -> CalibrationResult<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|source| CalibrationError::Io { path: path.to_path_buf(), source })?;
+        let spec = Self::from_toml(&text).map_err(|e| match e {
+            CalibrationError::Sweep(message) => CalibrationError::Sweep(format!("{}: {message}", path.display())),
+            other => other,
+        })?;
+        Ok(spec.resolved_against(path.parent().unwrap_or(Path::new(""))))
+    }
+
+    /// Parse a sweep file.  Paths are left as written.
+    pub fn from_toml(text: &str) -> CalibrationResult<Self> {
+        toml::from_str(text).map_err(|e| CalibrationError::Sweep(e.message().to_owned()))
+    }
+
+    /// Make relative paths relative to `base`.
+    pub fn resolved_against(mut self, base: &Path) -> Self {
+        self.scenario = base.join(&self.scenario);
+        if let Some(counts) = &mut self.link_counts {
+            counts.csv = base.join(&counts.csv);
+        }
+        self
+    }
+
+    /// The [`Sweep`] this file describes, with the scenario and any counts
+    /// read.
+    pub fn sweep(&self) -> CalibrationResult<Sweep> {
+        let mut sweep = Sweep::new(ScenarioTemplate::load(&self.scenario)?);
+        for p in &self.parameters {
+            sweep = sweep.parameter(p.parameter()?);
+        }
+        if let Some(lhs) = self.latin_hypercube {
+            if lhs.samples == 0 {
+                return Err(CalibrationError::Sweep("latin_hypercube.samples must be at least 1".into()));
+            }
+            sweep = sweep.design(Design::LatinHypercube { samples: lhs.samples, seed: lhs.seed });
+        }
+        sweep = match (&self.seeds, self.replicates) {
+            (Some(_), Some(_)) => return Err(CalibrationError::Sweep("give `seeds` or `replicates`, not both".into())),
+            (Some(seeds), None) => sweep.seeds(seeds.iter().copied()),
+            (None, Some(n)) => sweep.replicates(n),
+            (None, None) => sweep,
+        };
+        if let Some(threads) = self.threads {
+            sweep = sweep.threads(threads);
+        }
+        if let Some(counts) = &self.link_counts {
+            sweep = sweep.scorer(LinkCounts::read_csv(&counts.csv, counts.interval_ticks)?.metric(counts.metric));
+        }
+        Ok(sweep)
+    }
+}
+
+impl ParameterSpec {
+    fn parameter(&self) -> CalibrationResult<Parameter> {
+        let space = match (&self.values, self.min, self.max) {
+            (Some(values), None, None) => Space::Values(values.clone()),
+            (None, Some(min), Some(max)) => Space::Range { min, max, integer: self.integer },
+            _ => {
+                return Err(CalibrationError::Parameter {
+                    key:     self.key.clone(),
+                    message: "give `values`, or `min` and `max`".into(),
+                });
+            }
+        };
+        Ok(Parameter { key: self.key.clone(), space })
+    }
+}
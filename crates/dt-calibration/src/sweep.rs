@@ -0,0 +1,253 @@
+//! Running a batch of scenarios: `Sweep` and `simulate`.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Instant;
+
+use dt_cli::Scenario;
+use dt_core::{AgentId, Tick};
+use dt_mobility::{MovementState, Trip};
+use dt_output::LinkVolumes;
+use dt_sim::{SimObserver, TickStats};
+use dt_spatial::{DijkstraRouter, Route};
+
+use crate::param::{Design, Parameter, ScenarioTemplate, Value};
+use crate::{CalibrationError, CalibrationResult, RunOutput, RunResult, Scorer, SweepReport};
+
+// ── simulate ──────────────────────────────────────────────────────────────────
+
+/// Run `scenario` with its built-in behavior, writing no output, and total
+/// what it did.  Link volumes are counted per `link_volume_interval` ticks
+/// if given.
+pub fn simulate(scenario: &Scenario, link_volume_interval: Option<u64>) -> dt_cli::CliResult<RunOutput> {
+    let mut sim = scenario.sim_builder(scenario.behavior.model, DijkstraRouter)?.build()?;
+    let mut collector = Collector {
+        output:  RunOutput::default(),
+        volumes: link_volume_interval.map(|interval| LinkVolumes::new(&sim.network, interval, &[])),
+    };
+    sim.run(&mut collector)?;
+
+    let mut output = collector.output;
+    if let Some(mut volumes) = collector.volumes {
+        let end = sim.clock.current_tick.0;
+        output.link_volumes = volumes.take_all(end).into_iter().map(|r| ((r.tick, r.edge), r.vehicles)).collect();
+    }
+    output.failures = sim.failures.len();
+    Ok(output)
+}
+
+/// Totals the callbacks of one run into a [`RunOutput`].
+struct Collector {
+    output:  RunOutput,
+    volumes: Option<LinkVolumes>,
+}
+
+impl SimObserver for Collector {
+    fn on_trip(&mut self, trip: &Trip) {
+        self.output.trips       += 1;
+        self.output.travel_secs += trip.travel_secs as f64;
+    }
+
+    fn on_departure(&mut self, _tick: Tick, _agent: AgentId, state: &MovementState, route: &Route) {
+        if let Some(volumes) = &mut self.volumes {
+            volumes.record(state, route);
+        }
+    }
+
+    fn on_tick_stats(&mut self, _tick: Tick, stats: &TickStats) {
+        let o = &mut self.output;
+        o.ticks            += 1;
+        o.departures       += stats.departures;
+        o.arrivals         += stats.arrivals;
+        o.routing_failures += stats.routing_failures;
+    }
+}
+
+// ── Sweep ─────────────────────────────────────────────────────────────────────
+
+/// A batch of runs of one scenario template: every point of a [`Design`]
+/// over some [`Parameter`]s, each run once per seed, spread over worker
+/// threads, optionally scored.
+///
+/// The same seeds are used at every point (common random numbers), so
+/// differences between points are not seed noise.  Runs write no output;
+/// the scenario's `[output]` section is ignored.
+///
+/// ```rust,ignore
+/// let report = Sweep::new(ScenarioTemplate::load("scenario.toml".as_ref())?)
+///     .parameter(Parameter::values("plans.daily.depart", ["7h", "8h", "9h"]))
+///     .parameter(Parameter::range("interventions.0.factor", 1.0, 2.0))
+///     .design(Design::LatinHypercube { samples: 20, seed: 1 })
+///     .replicates(3)
+///     .scorer(LinkCounts::read_csv("counts.csv".as_ref(), 24)?)
+///     .run()?;
+/// println!("{:?}", report.best());
+/// ```
+pub struct Sweep {
+    template:   ScenarioTemplate,
+    parameters: Vec<Parameter>,
+    design:     Design,
+    seeds:      Seeds,
+    threads:    usize,
+    quiet:      bool,
+    scorer:     Option<Box<dyn Scorer>>,
+}
+
+enum Seeds {
+    /// The scenario's seed plus `0..n`.
+    Replicates(u32),
+    List(Vec<u64>),
+}
+
+/// One run of a sweep.
+struct Job {
+    point:  usize,
+    values: Vec<Value>,
+    seed:   u64,
+}
+
+impl Sweep {
+    /// A grid over no parameters — the template run once with its own
+    /// seed — on every available core, with progress on stderr.
+    pub fn new(template: ScenarioTemplate) -> Self {
+        Self {
+            template,
+            parameters: Vec::new(),
+            design:     Design::Grid,
+            seeds:      Seeds::Replicates(1),
+            threads:    std::thread::available_parallelism().map_or(1, |n| n.get()),
+            quiet:      false,
+            scorer:     None,
+        }
+    }
+
+    /// Vary `parameter`.
+    pub fn parameter(mut self, parameter: Parameter) -> Self {
+        self.parameters.push(parameter);
+        self
+    }
+
+    pub fn design(mut self, design: Design) -> Self {
+        self.design = design;
+        self
+    }
+
+    /// Run each point `n` times (at least once), with the scenario's seed,
+    /// seed + 1, and so on.
+    pub fn replicates(mut self, n: u32) -> Self {
+        self.seeds = Seeds::Replicates(n.max(1));
+        self
+    }
+
+    /// Run each point once with each of `seeds` instead.
+    pub fn seeds(mut self, seeds: impl IntoIterator<Item = u64>) -> Self {
+        self.seeds = Seeds::List(seeds.into_iter().collect());
+        self
+    }
+
+    /// Runs at a time (at least one).
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Print nothing while running.
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    /// Score every run with `scorer`.
+    pub fn scorer(mut self, scorer: impl Scorer + 'static) -> Self {
+        self.scorer = Some(Box::new(scorer));
+        self
+    }
+
+    /// The parameters' values at each point of the design.
+    pub fn points(&self) -> CalibrationResult<Vec<Vec<Value>>> {
+        self.design.points(&self.parameters)
+    }
+
+    /// Run every point with every seed.  Every point is applied to the
+    /// template before the first run starts, so a bad key fails fast; the
+    /// first failed run stops the sweep.
+    pub fn run(&self) -> CalibrationResult<SweepReport> {
+        let jobs = self.jobs()?;
+        let results: Vec<Mutex<Option<CalibrationResult<RunResult>>>> = jobs.iter().map(|_| Mutex::new(None)).collect();
+        let next = AtomicUsize::new(0);
+        let done = AtomicUsize::new(0);
+        let failed = AtomicBool::new(false);
+
+        std::thread::scope(|scope| {
+            for _ in 0..self.threads.min(jobs.len()) {
+                scope.spawn(|| {
+                    while !failed.load(Ordering::Relaxed) {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(job) = jobs.get(i) else { break };
+                        let result = self.run_job(job);
+                        failed.fetch_or(result.is_err(), Ordering::Relaxed);
+                        if let (Ok(run), false) = (&result, self.quiet) {
+                            let n = done.fetch_add(1, Ordering::Relaxed) + 1;
+                            let score = run.score.map_or(String::new(), |s| format!(", score {s:.4}"));
+                            let (total, secs) = (jobs.len(), run.wall_secs);
+                            eprintln!("[{n}/{total}] point {} seed {}{score} ({secs:.2}s)", job.point, job.seed);
+                        }
+                        *results[i].lock().expect("no worker panics while holding a result") = Some(result);
+                    }
+                });
+            }
+        });
+
+        let mut runs = Vec::with_capacity(jobs.len());
+        for result in results {
+            match result.into_inner().expect("workers have finished") {
+                Some(result) => runs.push(result?),
+                // Skipped after another run failed; that error comes first.
+                None => continue,
+            }
+        }
+        Ok(SweepReport::new(self.parameters.iter().map(|p| p.key.clone()).collect(), runs))
+    }
+
+    fn jobs(&self) -> CalibrationResult<Vec<Job>> {
+        let points = self.points()?;
+        let base_seed = self.template.instantiate(&[], &[])?.sim.seed;
+        let seeds = match &self.seeds {
+            Seeds::Replicates(n) => (0..*n as u64).map(|r| base_seed.wrapping_add(r)).collect(),
+            Seeds::List(seeds) if seeds.is_empty() => return Err(CalibrationError::Sweep("no seeds".into())),
+            Seeds::List(seeds) => seeds.clone(),
+        };
+        for values in &points {
+            self.template.instantiate(&self.parameters, values)?;
+        }
+        Ok(points
+            .into_iter()
+            .enumerate()
+            .flat_map(|(point, values)| seeds.iter().map(move |&seed| Job { point, values: values.clone(), seed }))
+            .collect())
+    }
+
+    fn run_job(&self, job: &Job) -> CalibrationResult<RunResult> {
+        let started = Instant::now();
+        let mut scenario = self.template.instantiate(&self.parameters, &job.values)?;
+        scenario.sim.seed = job.seed;
+        let interval = self.scorer.as_ref().and_then(|s| s.link_volume_interval());
+        let output = simulate(&scenario, interval).map_err(|source| CalibrationError::Run {
+            run: format!("point {} seed {}", job.point, job.seed),
+            source,
+        })?;
+        Ok(RunResult {
+            point:            job.point,
+            seed:             job.seed,
+            values:           job.values.clone(),
+            score:            self.scorer.as_ref().map(|s| s.score(&output)),
+            departures:       output.departures,
+            arrivals:         output.arrivals,
+            routing_failures: output.routing_failures,
+            failures:         output.failures,
+            trips:            output.trips,
+            mean_travel_secs: output.mean_travel_secs(),
+            wall_secs:        started.elapsed().as_secs_f64(),
+        })
+    }
+}
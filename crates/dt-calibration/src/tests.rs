@@ -0,0 +1,405 @@
+//! Unit tests for dt-calibration.
+
+use std::path::Path;
+
+use crate::ScenarioTemplate;
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// A 3 × 3 grid, 4 agents commuting by car for two days at 1 h per tick.
+const SCENARIO: &str = r#"
+[sim]
+start_unix_secs       = 0
+tick_duration_secs    = 3600
+total_ticks           = "2d"
+seed                  = 7
+output_interval_ticks = 1
+
+[network.grid]
+rows = 3
+cols = 3
+
+[population]
+agents = 4
+
+[plans]
+daily = { depart = "8h", work = "9h" }
+
+[output]
+backend = "none"
+"#;
+
+fn template() -> ScenarioTemplate {
+    ScenarioTemplate::from_toml(SCENARIO, Path::new("")).unwrap()
+}
+
+fn write(dir: &Path, name: &str, contents: &str) -> std::path::PathBuf {
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+// ── Parameters and designs ────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod param_tests {
+    use super::*;
+
+    use crate::{CalibrationError, Design, Parameter, Value};
+
+    #[test]
+    fn grid_is_the_cross_product() {
+        let params = [Parameter::values("a", [1i64, 2]), Parameter::values("b", ["x", "y", "z"])];
+        let points = Design::Grid.points(&params).unwrap();
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[0], [Value::Int(1), Value::from("x")]);
+        assert_eq!(points[5], [Value::Int(2), Value::from("z")]);
+        assert_eq!(Design::Grid.points(&[]).unwrap(), [Vec::<Value>::new()]);
+
+        let err = Design::Grid.points(&[Parameter::range("a", 0.0, 1.0)]).unwrap_err();
+        assert!(matches!(err, CalibrationError::Parameter { .. }), "{err}");
+    }
+
+    #[test]
+    fn latin_hypercube_uses_each_slice_once() {
+        let params = [
+            Parameter::range("x", 0.0, 10.0),
+            Parameter::int_range("n", 1, 5),
+            Parameter::values("mode", ["car", "bike", "walk", "bus", "train"]),
+        ];
+        let design = Design::LatinHypercube { samples: 5, seed: 3 };
+        let points = design.points(&params).unwrap();
+        assert_eq!(points.len(), 5);
+
+        let slice = |v: &Value| if let Value::Float(x) = v { (x / 2.0) as usize } else { usize::MAX };
+        let mut slices: Vec<usize> = points.iter().map(|p| slice(&p[0])).collect();
+        slices.sort_unstable();
+        assert_eq!(slices, [0, 1, 2, 3, 4]);
+        let mut ints: Vec<String> = points.iter().map(|p| p[1].to_string()).collect();
+        ints.sort();
+        assert_eq!(ints, ["1", "2", "3", "4", "5"]);
+        let mut modes: Vec<String> = points.iter().map(|p| p[2].to_string()).collect();
+        modes.sort();
+        assert_eq!(modes, ["bike", "bus", "car", "train", "walk"]);
+
+        assert_eq!(design.points(&params).unwrap(), points);
+        assert_ne!(Design::LatinHypercube { samples: 5, seed: 4 }.points(&params).unwrap(), points);
+    }
+
+    #[test]
+    fn rejects_bad_parameters() {
+        let bad = [
+            Parameter::values::<i64>("a", []),
+            Parameter::range("a", 2.0, 1.0),
+            Parameter::range("a", 0.0, f64::NAN),
+            Parameter::values("a..b", [1i64]),
+        ];
+        for p in bad {
+            let design = Design::LatinHypercube { samples: 2, seed: 0 };
+            let result = design.points(std::slice::from_ref(&p));
+            assert!(matches!(result, Err(CalibrationError::Parameter { .. })), "{p:?}");
+        }
+    }
+
+    #[test]
+    fn template_sets_dotted_keys() {
+        let t = template();
+        let params = [
+            Parameter::values("plans.daily.depart", ["7h"]),
+            Parameter::values("behavior.mode", ["bike"]),
+            Parameter::values("sim.seed", [99i64]),
+        ];
+        let s = t.instantiate(&params, &[Value::from("7h"), Value::from("bike"), Value::Int(99)]).unwrap();
+        assert_eq!(s.plans.daily.unwrap().depart, 7 * 3600);
+        assert_eq!(s.behavior.mode, dt_core::TransportMode::Bike);
+        assert_eq!(s.sim.seed, 99);
+
+        let with_intervention =
+            SCENARIO.to_owned() + "[[interventions]]\nkind = \"scale_travel_time\"\nfactor = 1.5\nat = 0\n";
+        let t = ScenarioTemplate::from_toml(&with_intervention, Path::new("")).unwrap();
+        let factor = [Parameter::values("interventions.0.factor", [2.5])];
+        let s = t.instantiate(&factor, &[Value::Float(2.5)]).unwrap();
+        assert_eq!(s.interventions[0].action, dt_cli::Action::ScaleTravelTime { factor: 2.5, roads: vec![] });
+
+        let past_end = [Parameter::values("interventions.1.factor", [2.5])];
+        let err = t.instantiate(&past_end, &[Value::Float(2.5)]).unwrap_err();
+        assert!(matches!(err, CalibrationError::Parameter { .. }), "{err}");
+        let unknown = [Parameter::values("population.agnets", [3i64])];
+        assert!(matches!(t.instantiate(&unknown, &[Value::Int(3)]), Err(CalibrationError::Cli(_))));
+    }
+
+    #[test]
+    fn template_loads_relative_to_its_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml = SCENARIO.replace("agents = 4", "agents = 4\nattributes = \"people.csv\"");
+        let t = ScenarioTemplate::load(&write(dir.path(), "scenario.toml", &toml)).unwrap();
+        let s = t.instantiate(&[], &[]).unwrap();
+        assert_eq!(s.population.attributes.unwrap(), dir.path().join("people.csv"));
+
+        assert!(matches!(ScenarioTemplate::load(&dir.path().join("missing.toml")), Err(CalibrationError::Io { .. })));
+        let json = write(dir.path(), "bad.json", "{ \"sim\": ");
+        assert!(matches!(ScenarioTemplate::load(&json), Err(CalibrationError::Template(_))));
+    }
+}
+
+// ── Scoring ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod score_tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    use crate::{CalibrationError, LinkCounts, Metric, RunOutput, Scorer};
+
+    fn run_with(volumes: &[((u64, u32), u64)]) -> RunOutput {
+        RunOutput { link_volumes: volumes.iter().copied().collect(), ..RunOutput::default() }
+    }
+
+    #[test]
+    fn link_counts_score_observed_pairs_only() {
+        let observed = LinkCounts::new(24, BTreeMap::from([((0, 1), 10), ((0, 2), 0), ((24, 1), 4)]));
+        assert_eq!(observed.link_volume_interval(), Some(24));
+        // Differences 3, 0 (never used), and 4 (missing).
+        let run = run_with(&[((0, 1), 13), ((0, 7), 50)]);
+        assert!((observed.score(&run) - (25.0f64 / 3.0).sqrt()).abs() < 1e-12);
+
+        let geh = observed.clone().metric(Metric::Geh);
+        let expected = ((2.0 * 9.0 / 23.0f64).sqrt() + 0.0 + (2.0 * 16.0 / 4.0f64).sqrt()) / 3.0;
+        assert!((geh.score(&run) - expected).abs() < 1e-12);
+        assert_eq!(observed.score(&run_with(&[((0, 1), 10), ((24, 1), 4)])), 0.0);
+    }
+
+    #[test]
+    fn reads_link_volume_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(dir.path(), "counts.csv", "tick,edge_id,vehicles\n0,3,5\n24,3,2\n0,3,1\n");
+        let counts = LinkCounts::read_csv(&path, 24).unwrap();
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts.score(&run_with(&[((0, 3), 6), ((24, 3), 2)])), 0.0);
+
+        let off_interval = write(dir.path(), "off.csv", "tick,edge_id,vehicles\n5,3,5\n");
+        assert!(matches!(LinkCounts::read_csv(&off_interval, 24), Err(CalibrationError::Sweep(_))));
+        let bad = write(dir.path(), "bad.csv", "tick,edge_id,vehicles\n0,x,5\n");
+        assert!(matches!(LinkCounts::read_csv(&bad, 24), Err(CalibrationError::Csv(_))));
+    }
+
+    #[test]
+    fn closures_are_scorers() {
+        let scorer = |run: &RunOutput| run.departures as f64;
+        let run = RunOutput { departures: 3, trips: 2, travel_secs: 90.0, ..RunOutput::default() };
+        assert_eq!(scorer.score(&run), 3.0);
+        assert_eq!(scorer.link_volume_interval(), None);
+        assert_eq!(run.mean_travel_secs(), 45.0);
+    }
+}
+
+// ── Sweeps ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod sweep_tests {
+    use super::*;
+
+    use crate::{CalibrationError, LinkCounts, Parameter, RunOutput, Sweep, Value, simulate};
+
+    #[test]
+    fn runs_every_point_with_every_seed() {
+        let report = Sweep::new(template())
+            .parameter(Parameter::values("behavior.model", ["commute", "noop"]))
+            .replicates(3)
+            .threads(2)
+            .quiet(true)
+            .scorer(|run: &RunOutput| run.departures as f64)
+            .run()
+            .unwrap();
+
+        assert_eq!(report.parameters, ["behavior.model"]);
+        assert_eq!(report.runs.len(), 6);
+        let seeds: Vec<(usize, u64)> = report.runs.iter().map(|r| (r.point, r.seed)).collect();
+        assert_eq!(seeds, [(0, 7), (0, 8), (0, 9), (1, 7), (1, 8), (1, 9)]);
+        assert!(report.runs[..3].iter().all(|r| r.departures > 0 && r.score == Some(r.departures as f64)));
+        assert!(report.runs[3..].iter().all(|r| r.departures == 0 && r.trips == 0));
+
+        let best = report.best().unwrap();
+        assert_eq!((best.point, best.values.as_slice()), (1, [Value::from("noop")].as_slice()));
+        assert_eq!((best.score_mean, best.score_std), (Some(0.0), Some(0.0)));
+    }
+
+    #[test]
+    fn results_do_not_depend_on_threads() {
+        let sweep = |threads| {
+            Sweep::new(template())
+                .parameter(Parameter::values("plans.daily.depart", ["6h", "7h", "8h"]))
+                .seeds([1, 2])
+                .threads(threads)
+                .quiet(true)
+                .run()
+                .unwrap()
+        };
+        let strip = |report: crate::SweepReport| {
+            report.runs.into_iter().map(|r| (r.point, r.seed, r.departures, r.trips)).collect::<Vec<_>>()
+        };
+        assert_eq!(strip(sweep(1)), strip(sweep(4)));
+    }
+
+    #[test]
+    fn link_counts_find_the_observed_departure_time() {
+        // "Observe" the 8 h run, then look for its departure time.
+        let observed = simulate(&template().instantiate(&[], &[]).unwrap(), Some(1)).unwrap();
+        assert!(!observed.link_volumes.is_empty());
+        let counts = LinkCounts::new(1, observed.link_volumes.clone());
+
+        let report = Sweep::new(template())
+            .parameter(Parameter::values("plans.daily.depart", ["6h", "7h", "8h", "9h"]))
+            .quiet(true)
+            .scorer(counts)
+            .run()
+            .unwrap();
+        let best = report.best().unwrap();
+        assert_eq!((best.values[0].to_string().as_str(), best.score_mean), ("8h", Some(0.0)));
+        assert!(report.points().iter().filter(|p| p.point != best.point).all(|p| p.score_mean.unwrap() > 0.0));
+    }
+
+    #[test]
+    fn bad_points_fail_before_running_and_bad_runs_name_themselves() {
+        let bad_value = Sweep::new(template()).parameter(Parameter::values("behavior.mode", ["car", "teleport"]));
+        assert!(matches!(bad_value.run(), Err(CalibrationError::Cli(_))));
+        assert!(matches!(Sweep::new(template()).seeds([]).run(), Err(CalibrationError::Sweep(_))));
+
+        // The road is only checked against the network when the sim is built.
+        let closure = SCENARIO.to_owned() + "[[interventions]]\nkind = \"close_road\"\nfrom = 0\nto = 1\nat = 0\n";
+        let t = ScenarioTemplate::from_toml(&closure, Path::new("")).unwrap();
+        let err = Sweep::new(t)
+            .parameter(Parameter::values("interventions.0.to", [1i64, 8]))
+            .seeds([5])
+            .quiet(true)
+            .run()
+            .unwrap_err();
+        assert!(matches!(&err, CalibrationError::Run { run, .. } if run == "point 1 seed 5"), "{err}");
+    }
+}
+
+// ── Sweep files ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod spec_tests {
+    use super::*;
+
+    use crate::{CalibrationError, SweepSpec};
+
+    const SPEC: &str = r#"
+scenario = "scenario.toml"
+seeds    = [1, 2]
+threads  = 2
+
+[latin_hypercube]
+samples = 3
+seed    = 9
+
+[[parameters]]
+key    = "plans.daily.depart"
+values = ["6h", "7h", "8h"]
+
+[[parameters]]
+key     = "sim.mobility.min_travel_ticks"
+min     = 0
+max     = 2
+integer = true
+
+[link_counts]
+csv            = "counts.csv"
+interval_ticks = 24
+metric         = "geh"
+"#;
+
+    #[test]
+    fn sweep_file_describes_a_sweep() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "scenario.toml", SCENARIO);
+        write(dir.path(), "counts.csv", "tick,edge_id,vehicles\n0,0,2\n");
+        let spec = SweepSpec::load(&write(dir.path(), "sweep.toml", SPEC)).unwrap();
+        assert_eq!(spec.scenario, dir.path().join("scenario.toml"));
+
+        let report = spec.sweep().unwrap().quiet(true).run().unwrap();
+        assert_eq!(report.parameters, ["plans.daily.depart", "sim.mobility.min_travel_ticks"]);
+        assert_eq!(report.runs.len(), 6);
+        assert!(report.runs.iter().all(|r| r.score.is_some()));
+        assert!(report.best().is_some());
+    }
+
+    #[test]
+    fn rejects_inconsistent_files() {
+        let both = SPEC.replace("threads  = 2", "replicates = 2");
+        let neither = SPEC.replace("min     = 0\n", "");
+        let mixed = SPEC.replace("integer = true", "integer = true\nvalues = [1]");
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "scenario.toml", SCENARIO);
+        write(dir.path(), "counts.csv", "tick,edge_id,vehicles\n");
+        for text in [both, neither, mixed] {
+            let spec = SweepSpec::from_toml(&text).unwrap().resolved_against(dir.path());
+            assert!(spec.sweep().is_err(), "{text}");
+        }
+        let unknown = SPEC.replace("threads ", "thread ");
+        assert!(matches!(SweepSpec::from_toml(&unknown), Err(CalibrationError::Sweep(_))));
+    }
+}
+
+// ── Reports ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod report_tests {
+    use crate::{RunResult, SweepReport, Value};
+
+    fn run(point: usize, seed: u64, score: Option<f64>) -> RunResult {
+        RunResult {
+            point,
+            seed,
+            values:           vec![Value::Int(point as i64), Value::from("car")],
+            score,
+            departures:       4,
+            arrivals:         4,
+            routing_failures: 0,
+            failures:         0,
+            trips:            4,
+            mean_travel_secs: 300.0,
+            wall_secs:        0.1,
+        }
+    }
+
+    fn report() -> SweepReport {
+        let runs = vec![run(1, 1, Some(2.0)), run(0, 1, Some(5.0)), run(0, 2, Some(7.0)), run(1, 2, Some(4.0))];
+        SweepReport::new(vec!["a".into(), "behavior.mode".into()], runs)
+    }
+
+    #[test]
+    fn points_summarize_over_seeds() {
+        let report = report();
+        let points = report.points();
+        assert_eq!(points.len(), 2);
+        assert_eq!((points[0].runs, points[0].score_mean), (2, Some(6.0)));
+        assert!((points[0].score_std.unwrap() - 2f64.sqrt()).abs() < 1e-12);
+        assert_eq!(report.best().unwrap().point, 1);
+
+        let unscored = SweepReport::new(vec![], vec![run(0, 1, None)]);
+        assert_eq!(unscored.points()[0].score_mean, None);
+        assert!(unscored.best().is_none());
+    }
+
+    #[test]
+    fn json_and_csv() {
+        let report = report();
+        assert_eq!(SweepReport::from_json(&report.to_json().unwrap()).unwrap(), report);
+
+        let csv = report.to_csv().unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].starts_with("point,seed,a,behavior.mode,score,departures,"));
+        assert!(lines[1].starts_with("0,1,0,car,5,4,"));
+
+        let table = report.to_string();
+        let rows: Vec<&str> = table.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[0].contains("behavior.mode"));
+        assert!(rows[1].trim_start().starts_with('1'), "best first: {table}");
+    }
+}
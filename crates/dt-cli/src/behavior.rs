@@ -0,0 +1,64 @@
+//! Built-in behavior models selectable from `[behavior]`.
+
+use serde::Deserialize;
+
+use dt_behavior::{BehaviorModel, Intent, SimContext};
+use dt_core::{AgentId, AgentRng, NodeId, TransportMode};
+use dt_schedule::Destination;
+
+// ── Components ────────────────────────────────────────────────────────────────
+
+/// An agent's home node, the target of `Destination::Home`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Home(pub NodeId);
+
+/// An agent's work node, the target of `Destination::Work`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Work(pub NodeId);
+
+/// The mode an agent travels in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TravelMode(pub TransportMode);
+
+// ── BuiltinBehavior ───────────────────────────────────────────────────────────
+
+/// The behavior models a scenario can name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuiltinBehavior {
+    /// Travel to the destination of the current activity whenever woken,
+    /// resolving `home` and `work` through the [`Home`] and [`Work`]
+    /// components, in the agent's [`TravelMode`].
+    #[default]
+    Commute,
+    /// Never move.
+    Noop,
+}
+
+impl BehaviorModel for BuiltinBehavior {
+    fn replan(
+        &self,
+        agent: AgentId,
+        ctx:   &SimContext<'_>,
+        _rng:  &mut AgentRng,
+    ) -> Vec<Intent> {
+        if *self == BuiltinBehavior::Noop {
+            return vec![];
+        }
+        let Some(activity) = ctx.plans[agent.index()].current_activity(ctx.tick) else {
+            return vec![];
+        };
+        let destination = match &activity.destination {
+            Destination::Home    => ctx.agents.component::<Home>().map(|v| v[agent.index()].0),
+            Destination::Work    => ctx.agents.component::<Work>().map(|v| v[agent.index()].0),
+            Destination::Node(n) => Some(*n),
+        };
+        let mode = ctx.agents.component::<TravelMode>().map_or(TransportMode::Car, |v| v[agent.index()].0);
+        match destination {
+            Some(destination) if destination != NodeId::INVALID => {
+                vec![Intent::TravelTo { destination, mode }]
+            }
+            _ => vec![],
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Error types for dt-cli.
+
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Errors that can occur while loading or running a scenario.
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+
+    #[error("invalid scenario: {0}")]
+    Scenario(String),
+
+    #[error("invalid population attributes: {0}")]
+    Population(String),
+
+    #[error("{0} requires dt-cli to be built with feature `{1}`")]
+    Feature(&'static str, &'static str),
+
+    #[error(transparent)]
+    Core(#[from] dt_core::DtError),
+
+    #[error(transparent)]
+    Spatial(#[from] dt_spatial::SpatialError),
+
+    #[error(transparent)]
+    Schedule(#[from] dt_schedule::ScheduleError),
+
+    #[error(transparent)]
+    Sim(#[from] dt_sim::SimError),
+
+    #[error(transparent)]
+    Output(#[from] dt_output::OutputError),
+
+    #[cfg(feature = "parquet")]
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[cfg(feature = "parquet")]
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] arrow::error::ArrowError),
+}
+
+/// Alias for `Result<T, CliError>`.
+pub type CliResult<T> = Result<T, CliError>;
@@ -0,0 +1,304 @@
+//! Scheduled interventions from `[[interventions]]`.
+//!
+//! Each intervention starts `at` a time after the start of the run and, with
+//! `until`, ends again; both take seconds or a duration such as `"1d6h"`
+//! and are rounded up to a tick.
+//!
+//! ```toml
+//! [[interventions]]          # close the road between nodes 4 and 5 to cars
+//! kind  = "close_road"
+//! from  = 4
+//! to    = 5
+//! at    = "1d"
+//! until = "2d"               # optional: for the rest of the run
+//!
+//! [[interventions]]          # congestion: travel times × 1.5 in the morning
+//! kind   = "scale_travel_time"
+//! factor = 1.5
+//! roads  = [[4, 5], [5, 6]]  # optional: every road
+//! at     = "7h"
+//! until  = "10h"
+//!
+//! [[interventions]]          # 30 % of agents stop travelling
+//! kind     = "cancel_trips"
+//! fraction = 0.3
+//! at       = "3d"
+//! ```
+//!
+//! Road interventions change `edge_travel_ms` (the car cost) in both
+//! directions before the behavior callbacks run; journeys already under way
+//! keep their route.  Interventions built together by
+//! [`Intervention::all`] share a record of the times they found, so
+//! whenever one starts or ends each of its edges is recomputed from that
+//! time and the interventions still active: factors multiply, and a closure
+//! — or a road that was closed to begin with — wins over any factor.
+//! `cancel_trips` drops the `TravelTo` intents of a fixed, seeded share of
+//! the agents.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use serde::Deserialize;
+
+use dt_behavior::Intent;
+use dt_core::{SimConfig, SimRng, Tick, TickDuration};
+use dt_sim::{PhaseContext, PhasePoint, TickPhase};
+use dt_spatial::RoadNetwork;
+
+use crate::scenario::{duration_secs, opt_duration_secs};
+use crate::{CliError, CliResult};
+
+// ── InterventionSpec ──────────────────────────────────────────────────────────
+
+/// One `[[interventions]]` entry.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct InterventionSpec {
+    /// Start, in seconds after the start of the run.
+    #[serde(deserialize_with = "duration_secs")]
+    pub at:     u64,
+    /// End, in seconds after the start of the run.  Default: never.
+    #[serde(default, deserialize_with = "opt_duration_secs")]
+    pub until:  Option<u64>,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+/// What an intervention does, named by its `kind`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Action {
+    /// Make the road between two nodes impassable to cars.
+    CloseRoad { from: u32, to: u32 },
+    /// Multiply car travel times on `roads` (node pairs), or on every road
+    /// if empty.
+    ScaleTravelTime {
+        factor: f32,
+        #[serde(default)]
+        roads:  Vec<[u32; 2]>,
+    },
+    /// Cancel every trip of a `fraction` of the agents.
+    CancelTrips { fraction: f64 },
+}
+
+impl InterventionSpec {
+    /// Check values that do not depend on the network.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.until.is_some_and(|until| until <= self.at) {
+            return Err("`until` must be after `at`".into());
+        }
+        match &self.action {
+            Action::ScaleTravelTime { factor, .. } if !(factor.is_finite() && *factor > 0.0) => {
+                Err("scale_travel_time: `factor` must be positive".into())
+            }
+            Action::CancelTrips { fraction } if !(0.0..=1.0).contains(fraction) => {
+                Err("cancel_trips: `fraction` must be between 0 and 1".into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// ── Intervention ──────────────────────────────────────────────────────────────
+
+/// An [`InterventionSpec`] bound to a network and population, run as a
+/// [`TickPhase`] at [`point`][Self::point].
+pub struct Intervention {
+    name:   String,
+    index:  usize,
+    start:  Tick,
+    end:    Option<Tick>,
+    effect: Effect,
+    active: bool,
+}
+
+enum Effect {
+    /// `None` closes the edges, which are sorted.
+    Roads { edges: Arc<[usize]>, factor: Option<f32>, ledger: Arc<Mutex<RoadLedger>> },
+    CancelTrips { cancelled: Vec<bool> },
+}
+
+/// The road interventions in effect and the travel times of their edges
+/// without them.
+#[derive(Default)]
+struct RoadLedger {
+    /// `edge_travel_ms` of every edge an active intervention changes, as
+    /// found before the first of them started.
+    base:   HashMap<usize, u32>,
+    /// Active interventions by index: their edges and factor.
+    active: BTreeMap<usize, (Arc<[usize]>, Option<f32>)>,
+}
+
+impl RoadLedger {
+    /// Start (`Some`) or end (`None`) intervention `index` and recompute
+    /// `edges` from their base times and every intervention still active.
+    fn update(
+        &mut self,
+        index:   usize,
+        edges:   &[usize],
+        effect:  Option<(Arc<[usize]>, Option<f32>)>,
+        network: &mut RoadNetwork,
+    ) {
+        match effect {
+            Some(effect) => self.active.insert(index, effect),
+            None         => self.active.remove(&index),
+        };
+        for &e in edges {
+            let base = *self.base.entry(e).or_insert(network.edge_travel_ms[e]);
+            let mut covered = false;
+            let mut ms = base as f64;
+            for (_, factor) in self.active.values().filter(|(edges, _)| edges.binary_search(&e).is_ok()) {
+                covered = true;
+                ms = match factor {
+                    Some(f) => ms * *f as f64,
+                    None    => f64::INFINITY,
+                };
+            }
+            network.edge_travel_ms[e] = if !covered {
+                self.base.remove(&e);
+                base
+            } else if base == u32::MAX || ms.is_infinite() {
+                u32::MAX
+            } else {
+                ms.round().min((u32::MAX - 1) as f64) as u32
+            };
+        }
+    }
+}
+
+impl Intervention {
+    /// Intervention number `index` of a run of `config` with `agents`
+    /// agents on `network`.  It does not know about other interventions;
+    /// build those that may overlap with [`all`][Self::all].
+    pub fn new(
+        spec:    &InterventionSpec,
+        index:   usize,
+        network: &RoadNetwork,
+        config:  &SimConfig,
+        agents:  usize,
+    ) -> CliResult<Self> {
+        Self::with_ledger(spec, index, network, config, agents, Arc::default())
+    }
+
+    /// One intervention per entry of `specs`, numbered in order, whose road
+    /// changes combine where they overlap (see the module docs).
+    pub fn all(
+        specs:   &[InterventionSpec],
+        network: &RoadNetwork,
+        config:  &SimConfig,
+        agents:  usize,
+    ) -> CliResult<Vec<Self>> {
+        let ledger = Arc::<Mutex<RoadLedger>>::default();
+        specs
+            .iter()
+            .enumerate()
+            .map(|(i, spec)| Self::with_ledger(spec, i, network, config, agents, Arc::clone(&ledger)))
+            .collect()
+    }
+
+    fn with_ledger(
+        spec:    &InterventionSpec,
+        index:   usize,
+        network: &RoadNetwork,
+        config:  &SimConfig,
+        agents:  usize,
+        ledger:  Arc<Mutex<RoadLedger>>,
+    ) -> CliResult<Self> {
+        let name = format!("interventions[{index}]");
+        let invalid = |message: String| CliError::Scenario(format!("{name}: {message}"));
+        spec.validate().map_err(invalid)?;
+
+        let effect = match &spec.action {
+            Action::CloseRoad { from, to } => {
+                let mut edges = road_edges(network, *from, *to).map_err(invalid)?;
+                edges.sort_unstable();
+                Effect::Roads { edges: edges.into(), factor: None, ledger }
+            }
+            Action::ScaleTravelTime { factor, roads } => {
+                let edges = if roads.is_empty() {
+                    (0..network.edge_count()).collect()
+                } else {
+                    let mut edges = Vec::new();
+                    for &[from, to] in roads {
+                        edges.extend(road_edges(network, from, to).map_err(invalid)?);
+                    }
+                    edges.sort_unstable();
+                    edges.dedup();
+                    edges
+                };
+                Effect::Roads { edges: edges.into(), factor: Some(*factor), ledger }
+            }
+            Action::CancelTrips { fraction } => {
+                let mut rng = SimRng::new(config.seed).stream(&format!("dt-cli-{name}"));
+                Effect::CancelTrips { cancelled: (0..agents).map(|_| rng.gen_bool(*fraction)).collect() }
+            }
+        };
+        let tick = |secs: u64| Tick::ZERO + TickDuration::from_secs(secs, config.tick_duration_secs);
+        Ok(Self { name, index, start: tick(spec.at), end: spec.until.map(tick), effect, active: false })
+    }
+
+    /// Where in the tick loop the intervention runs: before the behavior
+    /// callbacks for road changes, after them for cancelled trips.
+    pub fn point(&self) -> PhasePoint {
+        match self.effect {
+            Effect::Roads { .. }       => PhasePoint::BeforeIntents,
+            Effect::CancelTrips { .. } => PhasePoint::AfterIntents,
+        }
+    }
+
+    /// Whether the intervention is in effect.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    fn set_active(&mut self, active: bool, network: &mut RoadNetwork) {
+        self.active = active;
+        let Effect::Roads { edges, factor, ledger } = &self.effect else {
+            return;
+        };
+        let effect = active.then(|| (Arc::clone(edges), *factor));
+        // A poisoned lock only means another phase panicked mid-update; the
+        // recompute below repairs its edges' state.
+        let mut ledger = ledger.lock().unwrap_or_else(|e| e.into_inner());
+        ledger.update(self.index, edges, effect, network);
+    }
+}
+
+impl TickPhase for Intervention {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&mut self, ctx: &mut PhaseContext<'_>) -> Result<(), String> {
+        let due = ctx.tick >= self.start && self.end.is_none_or(|end| ctx.tick < end);
+        if due != self.active {
+            self.set_active(due, ctx.network);
+        }
+        if let (true, Effect::CancelTrips { cancelled }) = (self.active, &self.effect) {
+            for (agent, intents) in ctx.intents.iter_mut() {
+                if cancelled.get(agent.index()).copied().unwrap_or(false) {
+                    intents.retain(|intent| !matches!(intent, Intent::TravelTo { .. }));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The edges between `a` and `b`, in either direction.
+fn road_edges(network: &RoadNetwork, a: u32, b: u32) -> Result<Vec<usize>, String> {
+    let nodes = network.node_count() as u32;
+    if a >= nodes || b >= nodes {
+        return Err(format!("road {a} - {b}: the network has {nodes} nodes"));
+    }
+    let between = |from: u32, to: u32| {
+        network
+            .out_edges(dt_core::NodeId(from))
+            .filter(move |&e| network.edge_to[e.index()].0 == to)
+            .map(|e| e.index())
+    };
+    let edges: Vec<usize> = between(a, b).chain(between(b, a)).collect();
+    if edges.is_empty() {
+        return Err(format!("no road between nodes {a} and {b}"));
+    }
+    Ok(edges)
+}
@@ -0,0 +1,107 @@
+//! `dt-cli` — run a simulation from a scenario file.
+//!
+//! A scenario is a TOML (or JSON) file that names everything a run needs —
+//! the run configuration, a road network, the population, their activity
+//! plans, a built-in behavior, and an output backend — so simple studies
+//! need no Rust code:
+//!
+//! ```toml
+//! [sim]
+//! start_unix_secs       = "2024-03-04T00:00:00Z"
+//! tick_duration_secs    = 3600
+//! total_ticks           = "7d"
+//! seed                  = 42
+//! output_interval_ticks = 1
+//!
+//! [network.grid]           # or: [network] osm = "city.osm.pbf"
+//! rows      = 10
+//! cols      = 10
+//! spacing_m = 500
+//!
+//! [population]
+//! agents     = 1000
+//! attributes = "population.csv"   # optional: agent_id,home,work[,mode]
+//! positions  = "positions.csv"    # optional: agent_id,node (default: home)
+//!
+//! [plans]                  # or: csv = "plans.csv" / parquet = "plans.parquet"
+//! daily = { depart = "8h", work = "9h" }
+//!
+//! [behavior]
+//! model = "commute"        # or "noop"
+//! mode  = "car"
+//!
+//! [output]
+//! backend = "csv"          # "parquet", "sqlite", "jsonl", or "none"
+//! dir     = "output"
+//!
+//! [[interventions]]        # optional, any number
+//! kind  = "close_road"
+//! from  = 4
+//! to    = 5
+//! at    = "1d"
+//! until = "2d"
+//! ```
+//!
+//! `[sim]` is a [`SimConfig`][dt_core::SimConfig], with its subsystem
+//! sections (`[sim.output]`, `[sim.contacts]`, …).  Relative paths are
+//! resolved against the scenario file's directory.  See [`Scenario`] for
+//! every option and its default, and [`intervention`] for the intervention
+//! kinds.  To build the run from Rust instead — with another behavior or
+//! router, or to drive the tick loop yourself — use [`scenario::load`] or
+//! [`Scenario::sim_builder`], which return a `SimBuilder`.
+//!
+//! ```text
+//! dt-cli scenario.toml [--quiet]
+//! ```
+//!
+//! The binary prints progress — percent done, simulated time, and the
+//! estimated time remaining — to stderr while the run proceeds.
+//!
+//! # Crate layout
+//!
+//! | Module           | Contents                                                 |
+//! |------------------|----------------------------------------------------------|
+//! | [`scenario`]     | `Scenario` and its sections; loading and path resolution |
+//! | [`intervention`] | Road closures, travel-time changes, cancelled trips      |
+//! | [`network`]      | Road network from a grid spec or an OSM file             |
+//! | [`population`]   | Home, work, and mode components per agent                |
+//! | [`plans`]        | Activity plans from CSV, Parquet, or a daily template    |
+//! | [`behavior`]     | `BuiltinBehavior` (`commute`, `noop`)                    |
+//! | [`output`]       | `OutputBackend` and writer selection                     |
+//! | [`progress`]     | `Progress` observer (stderr progress and ETA)            |
+//! | [`run`]          | `run`, `RunReport`                                       |
+//! | [`error`]        | `CliError`, `CliResult<T>`                               |
+//!
+//! # Feature flags
+//!
+//! | Feature   | Enables                                             |
+//! |-----------|-----------------------------------------------------|
+//! | `osm`     | `network.osm` (OSM PBF files)                       |
+//! | `parquet` | `plans.parquet` and the `parquet` output backend    |
+//! | `sqlite`  | the `sqlite` output backend                         |
+//! | `jsonl`   | the `jsonl` output backend                          |
+
+pub mod behavior;
+pub mod error;
+pub mod intervention;
+pub mod network;
+pub mod output;
+pub mod plans;
+pub mod population;
+pub mod progress;
+pub mod run;
+pub mod scenario;
+
+#[cfg(test)]
+mod tests;
+
+pub use behavior::{BuiltinBehavior, Home, TravelMode, Work};
+pub use error::{CliError, CliResult};
+pub use intervention::{Action, Intervention, InterventionSpec};
+pub use output::OutputBackend;
+pub use population::Population;
+pub use progress::Progress;
+pub use run::{RunReport, run};
+pub use scenario::{
+    BehaviorSpec, DailyPlan, GridSpec, NetworkSpec, OutputSpec, PlansSpec, PopulationSpec, Scenario,
+};
@@ -0,0 +1,73 @@
+//! `dt-cli` — run a simulation from a scenario file.
+//!
+//! ```text
+//! dt-cli <scenario.toml|scenario.json> [--quiet]
+//! ```
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use dt_cli::{Scenario, run};
+
+const USAGE: &str = "usage: dt-cli <scenario.toml|scenario.json> [--quiet]";
+
+fn main() -> ExitCode {
+    let mut scenario_path: Option<PathBuf> = None;
+    let mut quiet = false;
+    for arg in std::env::args_os().skip(1) {
+        match arg.to_str() {
+            Some("-q" | "--quiet") => quiet = true,
+            Some("-h" | "--help") => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            Some(flag) if flag.starts_with('-') => {
+                eprintln!("unknown option {flag}\n{USAGE}");
+                return ExitCode::from(2);
+            }
+            _ if scenario_path.is_none() => scenario_path = Some(PathBuf::from(arg)),
+            _ => {
+                eprintln!("{USAGE}");
+                return ExitCode::from(2);
+            }
+        }
+    }
+    let Some(scenario_path) = scenario_path else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+
+    let result = Scenario::load(&scenario_path).and_then(|scenario| {
+        if !quiet {
+            eprintln!(
+                "{}: {} from {}, {} ticks of {}s",
+                scenario_path.display(),
+                scenario.sim.format_run_length(),
+                scenario.sim.start_datetime(),
+                scenario.sim.total_ticks,
+                scenario.sim.tick_duration_secs,
+            );
+        }
+        run(&scenario, quiet)
+    });
+    match result {
+        Ok(report) => {
+            println!(
+                "{} agents, {} ticks in {:.2}s; {} failures{}",
+                report.agents,
+                report.ticks,
+                report.wall_time.as_secs_f64(),
+                report.failures,
+                report.trips.map_or(String::new(), |trips| format!(", {trips} trips")),
+            );
+            if let Some(dir) = report.output_dir {
+                println!("output written to {}", dir.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
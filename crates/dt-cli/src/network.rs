@@ -0,0 +1,48 @@
+//! Road network construction from `[network]`.
+
+use dt_core::{GeoPoint, LocalProjection};
+use dt_spatial::{RoadNetwork, RoadNetworkBuilder};
+
+use crate::scenario::{GridSpec, NetworkSpec};
+use crate::{CliError, CliResult};
+
+/// The network `spec` describes.
+pub fn load_network(spec: &NetworkSpec) -> CliResult<RoadNetwork> {
+    match (&spec.osm, &spec.grid) {
+        (_, Some(grid)) => Ok(grid_network(grid)),
+        #[cfg(feature = "osm")]
+        (Some(path), None) => Ok(dt_spatial::osm::load_from_pbf(path)?),
+        #[cfg(not(feature = "osm"))]
+        (Some(_), None) => Err(CliError::Feature("network.osm", "osm")),
+        (None, None) => Err(CliError::Scenario("network: one of `osm` or `grid` is required".into())),
+    }
+}
+
+/// A `rows` × `cols` grid with two-way roads between horizontal and
+/// vertical neighbours.  Node `r * cols + c` is row `r` (from the south)
+/// and column `c` (from the west).
+pub fn grid_network(grid: &GridSpec) -> RoadNetwork {
+    let projection = LocalProjection::new(GeoPoint::new(grid.origin[0], grid.origin[1]));
+    let travel_ms = (grid.spacing_m / (grid.speed_kmh / 3.6) * 1000.0).round() as u32;
+    let (rows, cols) = (grid.rows as usize, grid.cols as usize);
+
+    let mut b = RoadNetworkBuilder::with_capacity(rows * cols, 4 * rows * cols);
+    let nodes: Vec<_> = (0..rows * cols)
+        .map(|i| {
+            let (r, c) = ((i / cols) as f64, (i % cols) as f64);
+            b.add_node(projection.unproject(c * grid.spacing_m as f64, r * grid.spacing_m as f64))
+        })
+        .collect();
+    for r in 0..rows {
+        for c in 0..cols {
+            let here = nodes[r * cols + c];
+            if c + 1 < cols {
+                b.add_road(here, nodes[r * cols + c + 1], grid.spacing_m, travel_ms);
+            }
+            if r + 1 < rows {
+                b.add_road(here, nodes[(r + 1) * cols + c], grid.spacing_m, travel_ms);
+            }
+        }
+    }
+    b.build()
+}
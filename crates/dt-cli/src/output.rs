@@ -0,0 +1,61 @@
+//! Output backend selection from `[output]`.
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use dt_output::OutputWriter;
+
+use crate::{CliError, CliResult};
+
+/// The output backends a scenario can name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputBackend {
+    /// `CsvWriter`.
+    #[default]
+    Csv,
+    /// `ParquetWriter` (feature `parquet`).
+    Parquet,
+    /// `SqliteWriter` (feature `sqlite`).
+    Sqlite,
+    /// `JsonlWriter` (feature `jsonl`).
+    Jsonl,
+    /// No output files.
+    None,
+}
+
+impl OutputBackend {
+    /// A writer of this backend into `dir`, which is created if missing;
+    /// `None` for [`OutputBackend::None`].
+    pub fn open(self, dir: &Path) -> CliResult<Option<Box<dyn OutputWriter>>> {
+        if self == OutputBackend::None {
+            return Ok(None);
+        }
+        std::fs::create_dir_all(dir).map_err(|source| CliError::Io { path: dir.to_path_buf(), source })?;
+        let writer: Box<dyn OutputWriter> = match self {
+            OutputBackend::Csv     => Box::new(dt_output::CsvWriter::new(dir)?),
+            #[cfg(feature = "parquet")]
+            OutputBackend::Parquet => Box::new(dt_output::ParquetWriter::new(dir)?),
+            #[cfg(feature = "sqlite")]
+            OutputBackend::Sqlite  => Box::new(dt_output::SqliteWriter::new(dir)?),
+            #[cfg(feature = "jsonl")]
+            OutputBackend::Jsonl   => Box::new(dt_output::JsonlWriter::new(dir)?),
+            #[allow(unreachable_patterns)]
+            backend => return Err(CliError::Feature(backend.name(), backend.name())),
+        };
+        Ok(Some(writer))
+    }
+
+    /// The backend's name in a scenario, which is also the name of the
+    /// feature it needs.
+    pub fn name(self) -> &'static str {
+        match self {
+            OutputBackend::Csv     => "csv",
+            OutputBackend::Parquet => "parquet",
+            OutputBackend::Sqlite  => "sqlite",
+            OutputBackend::Jsonl   => "jsonl",
+            OutputBackend::None    => "none",
+        }
+    }
+}
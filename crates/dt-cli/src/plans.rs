@@ -0,0 +1,165 @@
+//! Activity plans from `[plans]`.
+
+use dt_core::ActivityId;
+use dt_schedule::{ActivityPlan, Destination, ScheduledActivity, load_plans_csv};
+
+use crate::scenario::{DailyPlan, PlansSpec};
+use crate::{CliError, CliResult};
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// `activity_id` of the home activities of a [`DailyPlan`].
+pub const HOME_ACTIVITY: ActivityId = ActivityId(0);
+
+/// `activity_id` of the work activity of a [`DailyPlan`].
+pub const WORK_ACTIVITY: ActivityId = ActivityId(1);
+
+/// One plan per agent, as `spec` describes, for ticks of
+/// `tick_duration_secs` seconds.
+pub fn load_plans(spec: &PlansSpec, agents: usize, tick_duration_secs: u32) -> CliResult<Vec<ActivityPlan>> {
+    if let Some(path) = &spec.csv {
+        return Ok(load_plans_csv(path, agents)?);
+    }
+    if let Some(path) = &spec.parquet {
+        #[cfg(feature = "parquet")]
+        return parquet_plans::load(path, agents);
+        #[cfg(not(feature = "parquet"))]
+        {
+            let _ = path;
+            return Err(CliError::Feature("plans.parquet", "parquet"));
+        }
+    }
+    if let Some(daily) = &spec.daily {
+        return Ok(vec![daily_plan(daily, tick_duration_secs)?; agents]);
+    }
+    Ok(vec![ActivityPlan::empty(); agents])
+}
+
+/// The one-day plan `daily` at `tick_duration_secs` seconds per tick: work
+/// from `depart`, then home until the next day's departure.  Times are
+/// rounded down to whole ticks; with less than a tick of work the agent
+/// stays home.
+///
+/// Home is one activity spanning midnight, so agents are not woken at the
+/// start of each day only to stay where they are.
+pub fn daily_plan(daily: &DailyPlan, tick_duration_secs: u32) -> CliResult<ActivityPlan> {
+    let tick = tick_duration_secs as u64;
+    if tick == 0 || !SECS_PER_DAY.is_multiple_of(tick) {
+        return Err(CliError::Scenario(format!(
+            "plans.daily: tick_duration_secs {tick_duration_secs} does not divide one day"
+        )));
+    }
+    let cycle = (SECS_PER_DAY / tick) as u32;
+    let depart = (daily.depart / tick) as u32;
+    let back = ((daily.depart + daily.work) / tick).min(cycle as u64) as u32;
+
+    let activity = |start: u32, duration: u32, activity_id, destination| ScheduledActivity {
+        start_offset_ticks: start % cycle,
+        duration_ticks:     duration,
+        activity_id,
+        destination,
+    };
+    let activities = if back > depart {
+        vec![
+            activity(depart, back - depart, WORK_ACTIVITY, Destination::Work),
+            activity(back, cycle - (back - depart), HOME_ACTIVITY, Destination::Home),
+        ]
+    } else {
+        vec![activity(0, cycle, HOME_ACTIVITY, Destination::Home)]
+    };
+    Ok(ActivityPlan::new(activities, cycle))
+}
+
+// ── Parquet ───────────────────────────────────────────────────────────────────
+
+#[cfg(feature = "parquet")]
+mod parquet_plans {
+    use std::collections::HashMap;
+    use std::path::Path;
+
+    use arrow::array::{Array, AsArray, RecordBatch};
+    use arrow::compute::cast;
+    use arrow::datatypes::{DataType, UInt16Type, UInt32Type};
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use dt_core::{ActivityId, NodeId};
+    use dt_schedule::{ActivityPlan, Destination, ScheduleError, ScheduledActivity};
+
+    use crate::{CliError, CliResult};
+
+    /// Plans from a Parquet file with the plans CSV's columns.  Integer
+    /// columns may be any integer type; `destination` may be text or a
+    /// node id.
+    pub(super) fn load(path: &Path, agents: usize) -> CliResult<Vec<ActivityPlan>> {
+        let file = std::fs::File::open(path)
+            .map_err(|source| CliError::Io { path: path.to_path_buf(), source })?;
+        let mut by_agent: HashMap<u32, (u32, Vec<ScheduledActivity>)> = HashMap::new();
+
+        for batch in ParquetRecordBatchReaderBuilder::try_new(file)?.build()? {
+            let batch = batch?;
+            let agent_ids = column(&batch, "agent_id", &DataType::UInt32)?;
+            let activity_ids = column(&batch, "activity_id", &DataType::UInt16)?;
+            let starts = column(&batch, "start_offset_ticks", &DataType::UInt32)?;
+            let durations = column(&batch, "duration_ticks", &DataType::UInt32)?;
+            let destinations = column(&batch, "destination", &DataType::Utf8)?;
+            let cycles = column(&batch, "cycle_ticks", &DataType::UInt32)?;
+            let (agent_ids, activity_ids) =
+                (agent_ids.as_primitive::<UInt32Type>(), activity_ids.as_primitive::<UInt16Type>());
+            let (starts, durations, cycles) = (
+                starts.as_primitive::<UInt32Type>(),
+                durations.as_primitive::<UInt32Type>(),
+                cycles.as_primitive::<UInt32Type>(),
+            );
+            let destinations = destinations.as_string::<i32>();
+
+            for i in 0..batch.num_rows() {
+                let (agent, cycle, start) = (agent_ids.value(i), cycles.value(i), starts.value(i));
+                if start >= cycle {
+                    return Err(parse_error(format!(
+                        "agent {agent}: start_offset_ticks {start} is not below cycle_ticks {cycle}"
+                    )));
+                }
+                let entry = by_agent.entry(agent).or_insert_with(|| (cycle, Vec::new()));
+                entry.1.push(ScheduledActivity {
+                    start_offset_ticks: start,
+                    duration_ticks:     durations.value(i),
+                    activity_id:        ActivityId(activity_ids.value(i)),
+                    destination:        destination(destinations.value(i))?,
+                });
+            }
+        }
+
+        Ok((0..agents as u32)
+            .map(|agent| match by_agent.remove(&agent) {
+                Some((cycle, activities)) => ActivityPlan::new(activities, cycle),
+                None => ActivityPlan::empty(),
+            })
+            .collect())
+    }
+
+    /// Column `name` of `batch` cast to `ty`, rejecting nulls.
+    fn column(batch: &RecordBatch, name: &str, ty: &DataType) -> CliResult<arrow::array::ArrayRef> {
+        let array = batch
+            .column_by_name(name)
+            .ok_or_else(|| parse_error(format!("missing column {name:?}")))?;
+        if array.null_count() > 0 {
+            return Err(parse_error(format!("column {name:?} has nulls")));
+        }
+        Ok(cast(array, ty)?)
+    }
+
+    fn destination(s: &str) -> CliResult<Destination> {
+        match s.trim() {
+            "home" => Ok(Destination::Home),
+            "work" => Ok(Destination::Work),
+            n => n
+                .parse()
+                .map(|id| Destination::Node(NodeId(id)))
+                .map_err(|_| parse_error(format!("invalid destination {n:?}"))),
+        }
+    }
+
+    fn parse_error(message: String) -> CliError {
+        ScheduleError::Parse(message).into()
+    }
+}
@@ -0,0 +1,192 @@
+//! Per-agent attributes from `[population]`.
+//!
+//! The attributes file is a CSV with one row per agent:
+//!
+//! ```csv
+//! agent_id,home,work,mode
+//! 0,12,40,car
+//! 1,12,7,walk
+//! ```
+//!
+//! `home` and `work` are node ids; `mode` is optional (as a column or a
+//! cell) and falls back to `behavior.mode`.  Agents without a row get a
+//! home and work node drawn uniformly from the network, from the run's
+//! seed.
+//!
+//! The optional positions file places agents somewhere other than at home
+//! when the run starts:
+//!
+//! ```csv
+//! agent_id,node
+//! 0,40
+//! ```
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+use dt_agent::{AgentRngs, AgentStore, AgentStoreBuilder};
+use dt_core::{NodeId, SimRng, TransportMode};
+
+use crate::behavior::{Home, TravelMode, Work};
+use crate::scenario::{PopulationSpec, parse_mode};
+use crate::{CliError, CliResult};
+
+#[derive(Deserialize)]
+struct AttributeRecord {
+    agent_id: u32,
+    home:     u32,
+    work:     u32,
+    mode:     Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PositionRecord {
+    agent_id: u32,
+    node:     u32,
+}
+
+/// Home, work, mode, and starting node of every agent, indexed by
+/// `AgentId`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Population {
+    pub homes:     Vec<NodeId>,
+    pub works:     Vec<NodeId>,
+    pub modes:     Vec<TransportMode>,
+    /// Starting node, or `NodeId::INVALID` to start at home.
+    pub positions: Vec<NodeId>,
+}
+
+impl Population {
+    /// The population `spec` describes on a network of `node_count` nodes.
+    pub fn load(
+        spec:         &PopulationSpec,
+        node_count:   usize,
+        default_mode: TransportMode,
+        seed:         u64,
+    ) -> CliResult<Self> {
+        let records = match &spec.attributes {
+            Some(path) => read_csv::<AttributeRecord>(path)?,
+            None => Vec::new(),
+        };
+        let agents = spec
+            .agents
+            .unwrap_or_else(|| records.iter().map(|r| r.agent_id as usize + 1).max().unwrap_or(0));
+        if agents > 0 && node_count == 0 {
+            return Err(CliError::Population("the network has no nodes to place agents on".into()));
+        }
+
+        let mut rng = SimRng::new(seed).stream("dt-cli-population");
+        let mut random_node = || NodeId(rng.gen_range(0..node_count as u32));
+        let mut population = Self {
+            homes:     Vec::with_capacity(agents),
+            works:     Vec::with_capacity(agents),
+            modes:     vec![default_mode; agents],
+            positions: vec![NodeId::INVALID; agents],
+        };
+        for _ in 0..agents {
+            population.homes.push(random_node());
+            population.works.push(random_node());
+        }
+
+        for r in records {
+            let agent = r.agent_id as usize;
+            if agent >= agents {
+                return Err(CliError::Population(format!("agent_id {agent} is not below agents = {agents}")));
+            }
+            for node in [r.home, r.work] {
+                if node as usize >= node_count {
+                    return Err(CliError::Population(format!(
+                        "agent {agent}: node {node} is not in the network ({node_count} nodes)"
+                    )));
+                }
+            }
+            population.homes[agent] = NodeId(r.home);
+            population.works[agent] = NodeId(r.work);
+            if let Some(mode) = r.mode.as_deref().filter(|m| !m.trim().is_empty()) {
+                population.modes[agent] = parse_mode(mode)
+                    .ok_or_else(|| CliError::Population(format!("agent {agent}: unknown mode {mode:?}")))?;
+            }
+        }
+
+        let positions = match &spec.positions {
+            Some(path) => read_csv::<PositionRecord>(path)?,
+            None => Vec::new(),
+        };
+        for r in positions {
+            let agent = r.agent_id as usize;
+            if agent >= agents {
+                return Err(CliError::Population(format!("positions: agent_id {agent} is not below agents = {agents}")));
+            }
+            if r.node as usize >= node_count {
+                return Err(CliError::Population(format!(
+                    "positions: agent {agent}: node {} is not in the network ({node_count} nodes)",
+                    r.node
+                )));
+            }
+            population.positions[agent] = NodeId(r.node);
+        }
+        Ok(population)
+    }
+
+    /// Number of agents.
+    pub fn len(&self) -> usize {
+        self.homes.len()
+    }
+
+    /// `true` if there are no agents.
+    pub fn is_empty(&self) -> bool {
+        self.homes.is_empty()
+    }
+
+    /// Where each agent starts: its position if one was given, else home.
+    pub fn initial_positions(&self) -> Vec<NodeId> {
+        self.positions
+            .iter()
+            .zip(&self.homes)
+            .map(|(&position, &home)| if position == NodeId::INVALID { home } else { position })
+            .collect()
+    }
+
+    /// An agent store holding this population as [`Home`], [`Work`], and
+    /// [`TravelMode`] components.
+    pub fn build_store(&self, seed: u64) -> (AgentStore, AgentRngs) {
+        self.build_store_with(seed, |agents| agents)
+    }
+
+    /// [`build_store`][Self::build_store], with `register` adding further
+    /// components.
+    pub fn build_store_with(
+        &self,
+        seed:     u64,
+        register: impl FnOnce(AgentStoreBuilder) -> AgentStoreBuilder,
+    ) -> (AgentStore, AgentRngs) {
+        let builder = AgentStoreBuilder::new(self.len(), seed)
+            .register_component::<Home>()
+            .register_component::<Work>()
+            .register_component::<TravelMode>();
+        let (mut store, rngs) = register(builder).build();
+        if let Some(homes) = store.component_mut::<Home>() {
+            homes.iter_mut().zip(&self.homes).for_each(|(c, &n)| *c = Home(n));
+        }
+        if let Some(works) = store.component_mut::<Work>() {
+            works.iter_mut().zip(&self.works).for_each(|(c, &n)| *c = Work(n));
+        }
+        if let Some(modes) = store.component_mut::<TravelMode>() {
+            modes.iter_mut().zip(&self.modes).for_each(|(c, &m)| *c = TravelMode(m));
+        }
+        (store, rngs)
+    }
+}
+
+fn read_csv<T: serde::de::DeserializeOwned>(path: &Path) -> CliResult<Vec<T>> {
+    let file = std::fs::File::open(path)
+        .map_err(|source| CliError::Io { path: path.to_path_buf(), source })?;
+    csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(file)
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .map_err(|e| CliError::Population(format!("{}: {e}", path.display())))
+}
@@ -0,0 +1,159 @@
+//! Progress reporting while a run proceeds.
+
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use dt_agent::AgentStore;
+use dt_core::{AgentId, NodeId, SimClock, SimConfig, Tick, timefmt};
+use dt_mobility::{MobilityStore, MovementState, Trip};
+use dt_sim::{PhaseTimings, SimObserver, TickMetrics, TickStats, TraceEvent};
+use dt_spatial::Route;
+
+/// A [`SimObserver`] that reports progress and forwards every callback to
+/// `inner`.
+///
+/// At most once per [`every`][Self::every] interval, and once when the run
+/// ends, it writes a line such as
+///
+/// ```text
+/// [ 42%] tick 70/168  2024-03-06T22:00:00Z  elapsed 3s  eta 4s
+/// ```
+///
+/// to stderr (or the sink given to [`to_writer`][Self::to_writer]).  The
+/// estimate assumes the remaining ticks run at the average rate so far.
+pub struct Progress<O> {
+    inner:       O,
+    clock:       SimClock,
+    total_ticks: u64,
+    sink:        Box<dyn Write>,
+    every:       Duration,
+    started:     Instant,
+    last_report: Option<Instant>,
+}
+
+impl<O: SimObserver> Progress<O> {
+    /// Report progress through a run of `config`, forwarding to `inner`.
+    pub fn new(inner: O, config: &SimConfig) -> Self {
+        Self {
+            inner,
+            clock:       SimClock::new(config.start_unix_secs, config.tick_duration_secs),
+            total_ticks: config.total_ticks,
+            sink:        Box::new(std::io::stderr()),
+            every:       Duration::from_secs(1),
+            started:     Instant::now(),
+            last_report: None,
+        }
+    }
+
+    /// Shortest time between reports.  Default: 1 s.
+    pub fn every(mut self, interval: Duration) -> Self {
+        self.every = interval;
+        self
+    }
+
+    /// Write reports to `sink` instead of stderr.
+    pub fn to_writer(mut self, sink: impl Write + 'static) -> Self {
+        self.sink = Box::new(sink);
+        self
+    }
+
+    /// The wrapped observer.
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    /// The wrapped observer, mutably.
+    pub fn inner_mut(&mut self) -> &mut O {
+        &mut self.inner
+    }
+
+    /// Unwrap the wrapped observer.
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+
+    /// Report that ticks before `next` are done, if a report is due.
+    fn report(&mut self, next: Tick, force: bool) {
+        let now = Instant::now();
+        if !force && self.last_report.is_some_and(|last| now - last < self.every) {
+            return;
+        }
+        self.last_report = Some(now);
+
+        let done = next.0.min(self.total_ticks);
+        let percent = (done * 100).checked_div(self.total_ticks).unwrap_or(100);
+        let elapsed = now - self.started;
+        let eta = match done {
+            0 => "?".to_owned(),
+            _ => {
+                let remaining = elapsed.as_secs_f64() * (self.total_ticks - done) as f64 / done as f64;
+                timefmt::format_duration(remaining.ceil() as u64)
+            }
+        };
+        // Progress is best effort; a closed stderr must not stop the run.
+        let _ = writeln!(
+            self.sink,
+            "[{percent:>3}%] tick {done}/{}  {}  elapsed {}  eta {eta}",
+            self.total_ticks,
+            self.clock.format_tick(next),
+            timefmt::format_duration(elapsed.as_secs()),
+        );
+    }
+}
+
+impl<O: SimObserver> SimObserver for Progress<O> {
+    fn on_tick_start(&mut self, tick: Tick) {
+        self.inner.on_tick_start(tick);
+    }
+
+    fn on_tick_end(&mut self, tick: Tick, woken: usize) {
+        self.inner.on_tick_end(tick, woken);
+        self.report(tick + 1, false);
+    }
+
+    fn on_snapshot(&mut self, tick: Tick, mobility: &MobilityStore, agents: &AgentStore) {
+        self.inner.on_snapshot(tick, mobility, agents);
+    }
+
+    fn on_trip(&mut self, trip: &Trip) {
+        self.inner.on_trip(trip);
+    }
+
+    fn on_departure(&mut self, tick: Tick, agent: AgentId, state: &MovementState, route: &Route) {
+        self.inner.on_departure(tick, agent, state, route);
+    }
+
+    fn on_contacts(&mut self, tick: Tick, agent: AgentId, node: NodeId, agents_at_node: &[AgentId]) {
+        self.inner.on_contacts(tick, agent, node, agents_at_node);
+    }
+
+    fn on_tick_stats(&mut self, tick: Tick, stats: &TickStats) {
+        self.inner.on_tick_stats(tick, stats);
+    }
+
+    fn on_phase_timings(&mut self, tick: Tick, timings: &PhaseTimings) {
+        self.inner.on_phase_timings(tick, timings);
+    }
+
+    fn on_metrics(&mut self, tick: Tick, metrics: &TickMetrics) {
+        self.inner.on_metrics(tick, metrics);
+    }
+
+    fn on_trace(&mut self, event: &TraceEvent) {
+        self.inner.on_trace(event);
+    }
+
+    fn on_ticks_skipped(&mut self, from: Tick, to: Tick) {
+        self.inner.on_ticks_skipped(from, to);
+        self.report(to, false);
+    }
+
+    fn on_sim_end(&mut self, final_tick: Tick) {
+        self.inner.on_sim_end(final_tick);
+        self.report(final_tick, true);
+    }
+
+    fn poll_error(&mut self) -> Option<String> {
+        self.inner.poll_error()
+    }
+}
@@ -0,0 +1,77 @@
+//! Building and running the sim a scenario describes.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use dt_sim::{NoopObserver, Sim, SimObserver};
+use dt_spatial::DijkstraRouter;
+
+use crate::{BuiltinBehavior, CliResult, Progress, Scenario};
+
+/// What a finished run did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunReport {
+    pub agents:     usize,
+    pub ticks:      u64,
+    /// Failures collected during the run (routing failures and the like).
+    pub failures:   usize,
+    /// Completed trips, if a run summary was kept.
+    pub trips:      Option<u64>,
+    /// Where output was written, if anywhere.
+    pub output_dir: Option<PathBuf>,
+    pub wall_time:  Duration,
+}
+
+/// Build and run `scenario`, reporting progress to stderr unless `quiet`.
+///
+/// The sim comes from [`Scenario::sim_builder`].  Failures during the run
+/// are collected rather than printed and counted in the report; an output
+/// write error ends the run with an error.
+pub fn run(scenario: &Scenario, quiet: bool) -> CliResult<RunReport> {
+    let started = Instant::now();
+    let mut sim = scenario.sim_builder(scenario.behavior.model, DijkstraRouter)?.build()?;
+    let observer = scenario.output_observer(&sim.network)?;
+
+    let mut report = RunReport {
+        agents:     sim.agents.count,
+        ticks:      scenario.sim.total_ticks,
+        failures:   0,
+        trips:      None,
+        output_dir: None,
+        wall_time:  Duration::ZERO,
+    };
+    match observer {
+        Some(observer) => {
+            let mut observer = drive(&mut sim, observer, scenario, quiet)?;
+            if let Some(e) = observer.take_error() {
+                return Err(e.into());
+            }
+            report.trips = observer.run_summary().map(|s| s.trips());
+            report.output_dir = Some(scenario.output.dir.clone());
+        }
+        None => {
+            drive(&mut sim, NoopObserver, scenario, quiet)?;
+        }
+    }
+    report.failures = sim.failures.len();
+    report.wall_time = started.elapsed();
+    Ok(report)
+}
+
+/// Run `sim` to the end with `observer`, wrapped in [`Progress`] unless
+/// `quiet`.
+fn drive<O: SimObserver>(
+    sim:      &mut Sim<BuiltinBehavior, DijkstraRouter>,
+    observer: O,
+    scenario: &Scenario,
+    quiet:    bool,
+) -> CliResult<O> {
+    if quiet {
+        let mut observer = observer;
+        sim.run(&mut observer)?;
+        return Ok(observer);
+    }
+    let mut progress = Progress::new(observer, &scenario.sim);
+    sim.run(&mut progress)?;
+    Ok(progress.into_inner())
+}
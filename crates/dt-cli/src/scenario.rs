@@ -0,0 +1,364 @@
+//! The scenario file: what to simulate and where to write the results.
+//!
+//! [`Scenario::load`] reads TOML, or JSON if the file name ends in `.json`,
+//! then resolves relative paths against the file's directory and checks
+//! that each section names exactly the sources it must.
+//! [`Scenario::sim_builder`] then runs every loader and returns a
+//! [`SimBuilder`] ready to build; [`load`] does both for the scenario's
+//! built-in behavior.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Deserializer};
+
+use dt_agent::AgentStoreBuilder;
+use dt_behavior::BehaviorModel;
+use dt_core::{SimConfig, TransportMode, timefmt};
+use dt_output::{OutputWriter, SimOutputObserver};
+use dt_sim::{FailurePolicy, SimBuilder};
+use dt_spatial::{DijkstraRouter, RoadNetwork, Router};
+
+use crate::behavior::BuiltinBehavior;
+use crate::intervention::{Intervention, InterventionSpec};
+use crate::network::load_network;
+use crate::output::OutputBackend;
+use crate::plans::load_plans;
+use crate::population::Population;
+use crate::{CliError, CliResult};
+
+/// Read the scenario file at `path` and load everything it names into a
+/// [`SimBuilder`] with its built-in behavior and [`DijkstraRouter`].
+///
+/// ```rust,ignore
+/// let mut sim = dt_cli::scenario::load("scenario.toml".as_ref())?.build()?;
+/// sim.run(&mut NoopObserver)?;
+/// ```
+pub fn load(path: &Path) -> CliResult<SimBuilder<BuiltinBehavior, DijkstraRouter>> {
+    let scenario = Scenario::load(path)?;
+    scenario.sim_builder(scenario.behavior.model, DijkstraRouter)
+}
+
+// ── Scenario ──────────────────────────────────────────────────────────────────
+
+/// A complete run description.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// Run configuration, including its subsystem sections.
+    pub sim:           SimConfig,
+    pub network:       NetworkSpec,
+    pub population:    PopulationSpec,
+    #[serde(default)]
+    pub plans:         PlansSpec,
+    #[serde(default)]
+    pub behavior:      BehaviorSpec,
+    #[serde(default)]
+    pub output:        OutputSpec,
+    /// Scheduled changes during the run; see [`crate::intervention`].
+    #[serde(default)]
+    pub interventions: Vec<InterventionSpec>,
+}
+
+impl Scenario {
+    /// Read and validate the scenario file at `path`.
+    pub fn load(path: &Path) -> CliResult<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|source| CliError::Io { path: path.to_path_buf(), source })?;
+        let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let base = path.parent().unwrap_or(Path::new(""));
+
+        let scenario = if is_json { Self::from_json(&text) } else { Self::from_toml(&text) };
+        scenario
+            .map_err(|e| match e {
+                CliError::Scenario(message) => CliError::Scenario(format!("{}: {message}", path.display())),
+                other => other,
+            })
+            .map(|scenario| scenario.resolved_against(base))
+    }
+
+    /// Parse and validate a TOML scenario.  Paths are left as written.
+    pub fn from_toml(text: &str) -> CliResult<Self> {
+        let scenario: Self = toml::from_str(text).map_err(|e| scenario_error(e.message()))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Parse and validate a JSON scenario.  Paths are left as written.
+    pub fn from_json(text: &str) -> CliResult<Self> {
+        let scenario: Self = serde_json::from_str(text).map_err(|e| scenario_error(&e.to_string()))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// This scenario with every relative path joined onto `base`.
+    pub fn resolved_against(mut self, base: &Path) -> Self {
+        let paths = [
+            self.network.osm.as_mut(),
+            self.population.attributes.as_mut(),
+            self.population.positions.as_mut(),
+            self.plans.csv.as_mut(),
+            self.plans.parquet.as_mut(),
+            Some(&mut self.output.dir),
+        ];
+        for path in paths.into_iter().flatten() {
+            if path.is_relative() {
+                *path = base.join(&*path);
+            }
+        }
+        self
+    }
+
+    fn validate(&self) -> CliResult<()> {
+        match (&self.network.osm, &self.network.grid) {
+            (Some(_), Some(_)) => return Err(scenario_error("network: give either `osm` or `grid`, not both")),
+            (None, None)       => return Err(scenario_error("network: one of `osm` or `grid` is required")),
+            _ => {}
+        }
+        if let Some(grid) = &self.network.grid
+            && (grid.rows == 0 || grid.cols == 0 || grid.spacing_m <= 0.0 || grid.speed_kmh <= 0.0)
+        {
+            return Err(scenario_error("network.grid: rows, cols, spacing_m, and speed_kmh must be positive"));
+        }
+
+        if self.population.agents.is_none() && self.population.attributes.is_none() {
+            return Err(scenario_error("population: `agents` is required without `attributes`"));
+        }
+
+        let sources = [self.plans.csv.is_some(), self.plans.parquet.is_some(), self.plans.daily.is_some()];
+        if sources.iter().filter(|&&given| given).count() > 1 {
+            return Err(scenario_error("plans: give at most one of `csv`, `parquet`, or `daily`"));
+        }
+        if let Some(daily) = &self.plans.daily
+            && daily.depart.saturating_add(daily.work) > 86_400
+        {
+            return Err(scenario_error("plans.daily: `depart` + `work` exceeds one day"));
+        }
+        for (i, intervention) in self.interventions.iter().enumerate() {
+            intervention.validate().map_err(|e| scenario_error(&format!("interventions[{i}]: {e}")))?;
+        }
+        Ok(())
+    }
+
+    // ── Building ──────────────────────────────────────────────────────────
+
+    /// Load the network, population, and plans, and return a
+    /// [`SimBuilder`] for them with `behavior` and `router`: plans set,
+    /// agents at their initial positions, each intervention registered as
+    /// a phase, and failures collected ([`FailurePolicy::CollectAndReport`]).
+    ///
+    /// The agent store holds the population's [`Home`][crate::Home],
+    /// [`Work`][crate::Work], and [`TravelMode`][crate::TravelMode]
+    /// components; see [`sim_builder_with`][Self::sim_builder_with] to add
+    /// more.
+    pub fn sim_builder<B: BehaviorModel, R: Router>(&self, behavior: B, router: R) -> CliResult<SimBuilder<B, R>> {
+        self.sim_builder_with(behavior, router, |agents| agents)
+    }
+
+    /// [`sim_builder`][Self::sim_builder], with `register` adding the
+    /// behavior's own components to the agent store.
+    pub fn sim_builder_with<B: BehaviorModel, R: Router>(
+        &self,
+        behavior: B,
+        router:   R,
+        register: impl FnOnce(AgentStoreBuilder) -> AgentStoreBuilder,
+    ) -> CliResult<SimBuilder<B, R>> {
+        let config = &self.sim;
+        let network = load_network(&self.network)?;
+        let population = Population::load(&self.population, network.node_count(), self.behavior.mode, config.seed)?;
+        let plans = load_plans(&self.plans, population.len(), config.tick_duration_secs)?;
+        let interventions = Intervention::all(&self.interventions, &network, config, population.len())?;
+
+        let (store, rngs) = population.build_store_with(config.seed, register);
+        let mut builder = SimBuilder::new(config.clone(), store, rngs, behavior, router)
+            .plans(plans)
+            .network(network)
+            .initial_positions(population.initial_positions())
+            .failure_policy(FailurePolicy::CollectAndReport);
+        for intervention in interventions {
+            builder = builder.phase(intervention.point(), intervention);
+        }
+        Ok(builder)
+    }
+
+    /// An output observer for `[output]` writing into `output.dir`, with
+    /// `network` (normally the built sim's `network`); `None` for backend
+    /// `none`.
+    pub fn output_observer(
+        &self,
+        network: &RoadNetwork,
+    ) -> CliResult<Option<SimOutputObserver<Box<dyn OutputWriter>>>> {
+        Ok(self.output.backend.open(&self.output.dir)?.map(|writer| {
+            let observer = SimOutputObserver::new(writer, &self.sim).with_network(network);
+            if self.output.run_summary { observer.with_run_summary() } else { observer }
+        }))
+    }
+}
+
+fn scenario_error(message: &str) -> CliError {
+    CliError::Scenario(message.to_owned())
+}
+
+// ── Sections ──────────────────────────────────────────────────────────────────
+
+/// `[network]` — exactly one source.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkSpec {
+    /// OSM PBF file (feature `osm`).
+    pub osm:  Option<PathBuf>,
+    /// Synthetic grid.
+    pub grid: Option<GridSpec>,
+}
+
+/// `[network.grid]` — `rows` × `cols` nodes joined by two-way roads.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GridSpec {
+    pub rows:      u32,
+    pub cols:      u32,
+    /// Distance between neighbouring nodes in metres.  Default: 500.
+    #[serde(default = "default_spacing")]
+    pub spacing_m: f32,
+    /// Road speed in km/h.  Default: 50.
+    #[serde(default = "default_speed")]
+    pub speed_kmh: f32,
+    /// `[lat, lon]` of the south-west node.  Default: `[0, 0]`.
+    #[serde(default)]
+    pub origin:    [f32; 2],
+}
+
+fn default_spacing() -> f32 {
+    500.0
+}
+
+fn default_speed() -> f32 {
+    50.0
+}
+
+/// `[population]`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PopulationSpec {
+    /// Number of agents.  Default: one past the largest `agent_id` in
+    /// `attributes`.
+    pub agents:     Option<usize>,
+    /// CSV of `agent_id,home,work[,mode]` rows.  Agents without a row get
+    /// a random home and work node.
+    pub attributes: Option<PathBuf>,
+    /// CSV of `agent_id,node` rows: where agents start.  Agents without a
+    /// row start at home.
+    pub positions:  Option<PathBuf>,
+}
+
+/// `[plans]` — at most one source; without one every plan is empty.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlansSpec {
+    /// Plans CSV in the `dt_schedule::load_plans_csv` format.
+    pub csv:     Option<PathBuf>,
+    /// Plans Parquet file with the same columns as the CSV (feature
+    /// `parquet`).
+    pub parquet: Option<PathBuf>,
+    /// The same day for every agent.
+    pub daily:   Option<DailyPlan>,
+}
+
+/// `[plans.daily]` — home, then work, then home again, every day.
+///
+/// Both fields take seconds or a duration such as `"8h30m"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DailyPlan {
+    /// Time of day the agent leaves for work, in seconds.
+    #[serde(deserialize_with = "duration_secs")]
+    pub depart: u64,
+    /// Time spent at work, in seconds.
+    #[serde(deserialize_with = "duration_secs")]
+    pub work:   u64,
+}
+
+/// `[behavior]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BehaviorSpec {
+    /// Built-in model.  Default: `commute`.
+    #[serde(default)]
+    pub model: BuiltinBehavior,
+    /// Mode for agents whose attributes name none.  Default: `car`.
+    #[serde(default = "default_mode", deserialize_with = "transport_mode")]
+    pub mode:  TransportMode,
+}
+
+impl Default for BehaviorSpec {
+    fn default() -> Self {
+        Self { model: BuiltinBehavior::default(), mode: default_mode() }
+    }
+}
+
+fn default_mode() -> TransportMode {
+    TransportMode::Car
+}
+
+/// `[output]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputSpec {
+    /// Default: `csv`.
+    #[serde(default)]
+    pub backend:     OutputBackend,
+    /// Created if missing.  Default: `output`.
+    #[serde(default = "default_dir")]
+    pub dir:         PathBuf,
+    /// Write `run_summary.json`.  Default: `true`.
+    #[serde(default = "default_true")]
+    pub run_summary: bool,
+}
+
+impl Default for OutputSpec {
+    fn default() -> Self {
+        Self { backend: OutputBackend::default(), dir: default_dir(), run_summary: true }
+    }
+}
+
+fn default_dir() -> PathBuf {
+    PathBuf::from("output")
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// ── Field parsers ─────────────────────────────────────────────────────────────
+
+/// `mode` as written by [`TransportMode::as_str`], e.g. `"walk"`.
+pub fn parse_mode(s: &str) -> Option<TransportMode> {
+    [TransportMode::Car, TransportMode::Walk, TransportMode::Bike, TransportMode::Transit]
+        .into_iter()
+        .find(|mode| mode.as_str().eq_ignore_ascii_case(s.trim()))
+}
+
+fn transport_mode<'de, D: Deserializer<'de>>(d: D) -> Result<TransportMode, D::Error> {
+    let s = String::deserialize(d)?;
+    parse_mode(&s).ok_or_else(|| {
+        serde::de::Error::custom(format!("unknown mode {s:?}: expected car, walk, bike, or transit"))
+    })
+}
+
+/// Seconds, or a duration string such as `"1h30m"`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Span {
+    Secs(u64),
+    Text(String),
+}
+
+pub(crate) fn duration_secs<'de, D: Deserializer<'de>>(d: D) -> Result<u64, D::Error> {
+    match Span::deserialize(d)? {
+        Span::Secs(secs) => Ok(secs),
+        Span::Text(text) => timefmt::parse_duration(&text).map_err(serde::de::Error::custom),
+    }
+}
+
+pub(crate) fn opt_duration_secs<'de, D: Deserializer<'de>>(d: D) -> Result<Option<u64>, D::Error> {
+    duration_secs(d).map(Some)
+}
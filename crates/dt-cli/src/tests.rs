@@ -0,0 +1,613 @@
+//! Unit tests for dt-cli.
+
+use std::path::Path;
+
+use dt_core::TransportMode;
+
+use crate::Scenario;
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// A 3 × 3 grid, 4 agents commuting daily for two days at 1 h per tick,
+/// writing `backend` into `output`.
+fn scenario_toml(backend: &str) -> String {
+    format!(
+        r#"
+[sim]
+start_unix_secs       = "2024-03-04T00:00:00Z"
+tick_duration_secs    = 3600
+total_ticks           = "2d"
+seed                  = 7
+output_interval_ticks = 1
+
+[network.grid]
+rows      = 3
+cols      = 3
+spacing_m = 400
+
+[population]
+agents = 4
+
+[plans]
+daily = {{ depart = "8h", work = "9h" }}
+
+[behavior]
+mode = "walk"
+
+[output]
+backend = "{backend}"
+"#
+    )
+}
+
+fn write(dir: &Path, name: &str, contents: &str) -> std::path::PathBuf {
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+// ── Scenario ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod scenario {
+    use super::*;
+
+    use crate::{BuiltinBehavior, CliError, OutputBackend};
+
+    #[test]
+    fn parses_toml_with_defaults() {
+        let s = Scenario::from_toml(&scenario_toml("csv")).unwrap();
+        assert_eq!(s.sim.start_unix_secs, 1_709_510_400);
+        assert_eq!(s.sim.total_ticks, 48);
+        let grid = s.network.grid.as_ref().unwrap();
+        assert_eq!((grid.rows, grid.cols, grid.spacing_m, grid.speed_kmh), (3, 3, 400.0, 50.0));
+        assert_eq!(s.population.agents, Some(4));
+        let daily = s.plans.daily.unwrap();
+        assert_eq!((daily.depart, daily.work), (8 * 3600, 9 * 3600));
+        assert_eq!(s.behavior.model, BuiltinBehavior::Commute);
+        assert_eq!(s.behavior.mode, TransportMode::Walk);
+        assert_eq!(s.output.backend, OutputBackend::Csv);
+        assert_eq!(s.output.dir, Path::new("output"));
+        assert!(s.output.run_summary);
+    }
+
+    #[test]
+    fn parses_json() {
+        let json = r#"{
+            "sim": { "start_unix_secs": 0, "tick_duration_secs": 60, "total_ticks": 10,
+                     "seed": 1, "output_interval_ticks": 1 },
+            "network": { "grid": { "rows": 2, "cols": 2 } },
+            "population": { "agents": 3 },
+            "behavior": { "model": "noop" },
+            "output": { "backend": "none" }
+        }"#;
+        let s = Scenario::from_json(json).unwrap();
+        assert_eq!(s.behavior.model, BuiltinBehavior::Noop);
+        assert_eq!(s.output.backend, OutputBackend::None);
+        assert!(s.plans.csv.is_none() && s.plans.daily.is_none());
+    }
+
+    #[test]
+    fn load_resolves_paths_against_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml = scenario_toml("csv").replace("[population]\n", "[population]\nattributes = \"people.csv\"\n");
+        let path = write(dir.path(), "scenario.toml", &toml);
+        let s = Scenario::load(&path).unwrap();
+        assert_eq!(s.population.attributes.unwrap(), dir.path().join("people.csv"));
+        assert_eq!(s.output.dir, dir.path().join("output"));
+    }
+
+    #[test]
+    fn rejects_inconsistent_sections() {
+        let both = scenario_toml("csv").replace("[network.grid]", "[network]\nosm = \"x.pbf\"\n[network.grid]");
+        let no_agents = scenario_toml("csv").replace("agents = 4", "");
+        let two_plans = scenario_toml("csv").replace("[plans]", "[plans]\ncsv = \"plans.csv\"");
+        let long_day = scenario_toml("csv").replace("work = \"9h\"", "work = \"17h\"");
+        for toml in [both, no_agents, two_plans, long_day] {
+            assert!(matches!(Scenario::from_toml(&toml), Err(CliError::Scenario(_))), "{toml}");
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        let mode = scenario_toml("csv").replace("mode = \"walk\"", "mode = \"teleport\"");
+        let backend = scenario_toml("hdf5");
+        let field = scenario_toml("csv").replace("agents = 4", "agents = 4\nagnets = 5");
+        for toml in [mode, backend, field] {
+            let err = Scenario::from_toml(&toml).unwrap_err();
+            assert!(matches!(err, CliError::Scenario(_)), "{err}");
+        }
+    }
+
+    #[test]
+    fn load_reports_missing_file() {
+        let err = Scenario::load(Path::new("/nonexistent/scenario.toml")).unwrap_err();
+        assert!(matches!(err, CliError::Io { .. }));
+    }
+}
+
+// ── Network and plans ─────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod inputs {
+    use dt_core::{ActivityId, NodeId, Tick};
+    use dt_schedule::Destination;
+
+    use crate::network::grid_network;
+    use crate::plans::{daily_plan, load_plans};
+    use crate::{DailyPlan, GridSpec, PlansSpec};
+
+    #[test]
+    fn grid_network_links_neighbours() {
+        let grid = GridSpec { rows: 3, cols: 2, spacing_m: 1000.0, speed_kmh: 36.0, origin: [10.0, 20.0] };
+        let network = grid_network(&grid);
+        assert_eq!(network.node_count(), 6);
+        // 3 horizontal and 4 vertical two-way roads.
+        assert_eq!(network.edge_count(), 14);
+        assert_eq!(network.out_degree(NodeId(0)), 2);
+        assert_eq!(network.out_degree(NodeId(2)), 3);
+
+        let (sw, east, north) = (network.node_pos[0], network.node_pos[1], network.node_pos[2]);
+        assert_eq!((sw.lat, sw.lon), (10.0, 20.0));
+        assert!((sw.distance_m(east) - 1000.0).abs() < 1.0);
+        assert!((sw.distance_m(north) - 1000.0).abs() < 1.0);
+        assert!(north.lat > sw.lat && east.lon > sw.lon);
+    }
+
+    #[test]
+    fn daily_plan_at_hourly_ticks() {
+        let plan = daily_plan(&DailyPlan { depart: 8 * 3600, work: 9 * 3600 }, 3600).unwrap();
+        assert_eq!(plan.cycle_ticks, 24);
+        let starts: Vec<(u32, u32, ActivityId)> = plan
+            .activities()
+            .iter()
+            .map(|a| (a.start_offset_ticks, a.duration_ticks, a.activity_id))
+            .collect();
+        assert_eq!(starts, [(8, 9, ActivityId(1)), (17, 15, ActivityId(0))]);
+        assert_eq!(plan.current_activity(Tick(33)).unwrap().destination, Destination::Work);
+        // Home spans midnight: no wake at the start of the day.
+        assert_eq!(plan.current_activity(Tick(24)).unwrap().destination, Destination::Home);
+        assert_eq!(plan.next_wake_tick(Tick(17)), Some(Tick(32)));
+    }
+
+    #[test]
+    fn daily_plan_edge_cases() {
+        // Work until midnight: home starts at offset 0.
+        let plan = daily_plan(&DailyPlan { depart: 12 * 3600, work: 12 * 3600 }, 900).unwrap();
+        assert_eq!(plan.cycle_ticks, 96);
+        let starts: Vec<u32> = plan.activities().iter().map(|a| a.start_offset_ticks).collect();
+        assert_eq!(starts, [0, 48]);
+        // Less than a tick of work: home all day.
+        let plan = daily_plan(&DailyPlan { depart: 8 * 3600, work: 600 }, 3600).unwrap();
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan.activities()[0].destination, Destination::Home);
+    }
+
+    #[test]
+    fn daily_plan_needs_ticks_that_divide_a_day() {
+        assert!(daily_plan(&DailyPlan { depart: 0, work: 0 }, 7 * 3600).is_err());
+    }
+
+    #[test]
+    fn missing_plans_are_empty() {
+        let plans = load_plans(&PlansSpec::default(), 3, 3600).unwrap();
+        assert_eq!(plans.len(), 3);
+        assert!(plans.iter().all(|p| p.is_empty()));
+    }
+}
+
+// ── Population ────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod population {
+    use super::*;
+
+    use dt_core::NodeId;
+
+    use crate::{CliError, Home, Population, PopulationSpec, TravelMode};
+
+    #[test]
+    fn attributes_override_random_placement() {
+        let dir = tempfile::tempdir().unwrap();
+        let csv = "agent_id,home,work,mode\n1,2,3,bike\n0,4,5,\n";
+        let spec = PopulationSpec {
+            agents:     Some(3),
+            attributes: Some(write(dir.path(), "people.csv", csv)),
+            positions:  None,
+        };
+        let p = Population::load(&spec, 9, TransportMode::Car, 1).unwrap();
+        assert_eq!(p.len(), 3);
+        assert_eq!((p.homes[0], p.works[0], p.modes[0]), (NodeId(4), NodeId(5), TransportMode::Car));
+        assert_eq!((p.homes[1], p.works[1], p.modes[1]), (NodeId(2), NodeId(3), TransportMode::Bike));
+        assert!(p.homes[2].0 < 9 && p.works[2].0 < 9);
+
+        let (store, _) = p.build_store(1);
+        assert_eq!(store.component::<Home>().unwrap()[1], Home(NodeId(2)));
+        assert_eq!(store.component::<TravelMode>().unwrap()[1], TravelMode(TransportMode::Bike));
+    }
+
+    #[test]
+    fn agent_count_defaults_to_attribute_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let spec = PopulationSpec {
+            agents:     None,
+            attributes: Some(write(dir.path(), "people.csv", "agent_id,home,work\n4,0,1\n")),
+            positions:  None,
+        };
+        assert_eq!(Population::load(&spec, 2, TransportMode::Car, 1).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn random_placement_follows_the_seed() {
+        let spec = PopulationSpec { agents: Some(50), ..Default::default() };
+        let a = Population::load(&spec, 100, TransportMode::Car, 3).unwrap();
+        assert_eq!(a, Population::load(&spec, 100, TransportMode::Car, 3).unwrap());
+        assert_ne!(a, Population::load(&spec, 100, TransportMode::Car, 4).unwrap());
+    }
+
+    #[test]
+    fn rejects_bad_attributes() {
+        let dir = tempfile::tempdir().unwrap();
+        let rows = ["agent_id,home,work\n0,9,0\n", "agent_id,home,work\n3,0,0\n", "agent_id,home,work,mode\n0,0,0,ufo\n"];
+        for (i, csv) in rows.into_iter().enumerate() {
+            let spec = PopulationSpec {
+                agents:     Some(2),
+                attributes: Some(write(dir.path(), &format!("people{i}.csv"), csv)),
+                positions:  None,
+            };
+            let err = Population::load(&spec, 9, TransportMode::Car, 1).unwrap_err();
+            assert!(matches!(err, CliError::Population(_)), "{csv}: {err}");
+        }
+    }
+}
+
+// ── Running ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod run {
+    use std::cell::RefCell;
+    use std::io::Write;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use dt_sim::{NoopObserver, SimBuilder};
+    use dt_spatial::DijkstraRouter;
+
+    use super::*;
+
+    use crate::{BuiltinBehavior, Population, Progress, run};
+
+    #[test]
+    fn commute_scenario_writes_csv_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write(dir.path(), "scenario.toml", &scenario_toml("csv"));
+        let report = run(&Scenario::load(&path).unwrap(), true).unwrap();
+
+        assert_eq!((report.agents, report.ticks, report.failures), (4, 48, 0));
+        let out = dir.path().join("output");
+        assert_eq!(report.output_dir.as_deref(), Some(out.as_path()));
+        for file in ["agent_snapshots.csv", "tick_summaries.csv", "trips.csv", "run_manifest.json", "run_summary.json"] {
+            assert!(out.join(file).is_file(), "{file}");
+        }
+        // Agents whose home and work differ commute twice a day.
+        let s = Scenario::load(&path).unwrap();
+        let p = Population::load(&s.population, 9, s.behavior.mode, s.sim.seed).unwrap();
+        let commuters = (0..p.len()).filter(|&i| p.homes[i] != p.works[i]).count() as u64;
+        assert_eq!(report.trips, Some(4 * commuters));
+    }
+
+    #[test]
+    fn noop_scenario_without_output() {
+        let toml = scenario_toml("none").replace("mode = \"walk\"", "model = \"noop\"");
+        let report = run(&Scenario::from_toml(&toml).unwrap(), true).unwrap();
+        assert_eq!((report.agents, report.trips, report.output_dir), (4, None, None));
+    }
+
+    /// The commute scenario's results are pinned by a golden trace; re-record
+    /// it with `DT_BLESS_GOLDEN=1` when a change is meant to alter them.
+    #[test]
+    fn commute_scenario_matches_golden_trace() {
+        let dir = tempfile::tempdir().unwrap();
+        let toml = scenario_toml("none").replace("agents = 4", "agents = 60");
+        let mut sim = crate::scenario::load(&write(dir.path(), "scenario.toml", &toml)).unwrap().build().unwrap();
+
+        let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("golden/commute.golden");
+        dt_sim::check_golden(&golden, &mut sim).unwrap();
+    }
+
+    #[test]
+    fn missing_feature_is_reported() {
+        #[cfg(not(feature = "sqlite"))]
+        {
+            let s = Scenario::from_toml(&scenario_toml("sqlite")).unwrap();
+            let dir = tempfile::tempdir().unwrap();
+            let s = s.resolved_against(dir.path());
+            assert!(matches!(run(&s, true), Err(crate::CliError::Feature("sqlite", "sqlite"))));
+        }
+    }
+
+    /// Collects what `Progress` writes.
+    #[derive(Clone, Default)]
+    struct Sink(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Sink {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn progress_reports_each_interval_and_at_the_end() {
+        let s = Scenario::from_toml(&scenario_toml("none")).unwrap();
+        let (store, rngs) = dt_agent::AgentStoreBuilder::new(0, 1).build();
+        let mut sim = SimBuilder::new(s.sim.clone(), store, rngs, BuiltinBehavior::Noop, DijkstraRouter)
+            .build()
+            .unwrap();
+
+        let sink = Sink::default();
+        let mut progress = Progress::new(NoopObserver, &s.sim).every(Duration::ZERO).to_writer(sink.clone());
+        sim.run(&mut progress).unwrap();
+        let text = String::from_utf8(sink.0.borrow().clone()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        // One line per tick, plus the final report.
+        assert_eq!(lines.len(), 49);
+        assert!(lines[0].starts_with("[  2%] tick 1/48  2024-03-04T01:00:00Z"), "{}", lines[0]);
+        assert!(lines[48].starts_with("[100%] tick 48/48  2024-03-06T00:00:00Z"), "{}", lines[48]);
+        assert!(lines[48].ends_with("eta 0s"));
+    }
+}
+
+// ── Interventions and scenario::load ──────────────────────────────────────────
+
+#[cfg(test)]
+mod interventions {
+    use dt_core::{NodeId, Tick};
+    use dt_sim::{NoopObserver, SimObserver, TickStats};
+
+    use super::*;
+
+    use crate::{Action, CliError, scenario};
+
+    /// Two nodes one road apart; every agent lives at 0 and works at 1,
+    /// driving.
+    fn one_road(dir: &Path, interventions: &str) -> std::path::PathBuf {
+        let attributes = "agent_id,home,work\n0,0,1\n1,0,1\n2,0,1\n3,0,1\n";
+        write(dir, "people.csv", attributes);
+        let toml = scenario_toml("none")
+            .replace("rows      = 3\ncols      = 3", "rows      = 1\ncols      = 2")
+            .replace("agents = 4", "agents = 4\nattributes = \"people.csv\"")
+            .replace("mode = \"walk\"", "mode = \"car\"");
+        write(dir, "scenario.toml", &format!("{toml}\n{interventions}"))
+    }
+
+    /// Departures and routing failures per tick.
+    #[derive(Default)]
+    struct Counts(Vec<(u64, u64)>);
+
+    impl Counts {
+        fn departures(&self, ticks: std::ops::Range<usize>) -> u64 {
+            self.0[ticks].iter().map(|c| c.0).sum()
+        }
+
+        fn failures(&self, ticks: std::ops::Range<usize>) -> u64 {
+            self.0[ticks].iter().map(|c| c.1).sum()
+        }
+    }
+
+    impl SimObserver for Counts {
+        fn on_tick_stats(&mut self, _tick: Tick, stats: &TickStats) {
+            self.0.push((stats.departures, stats.routing_failures));
+        }
+    }
+
+    #[test]
+    fn parses_every_kind() {
+        let toml = scenario_toml("none")
+            + r#"
+[[interventions]]
+kind  = "close_road"
+from  = 4
+to    = 5
+at    = "1d"
+until = "1d6h"
+
+[[interventions]]
+kind   = "scale_travel_time"
+factor = 1.5
+at     = 3600
+
+[[interventions]]
+kind     = "cancel_trips"
+fraction = 0.25
+at       = "12h"
+"#;
+        let s = Scenario::from_toml(&toml).unwrap();
+        assert_eq!(s.interventions.len(), 3);
+        let [close, scale, cancel] = [&s.interventions[0], &s.interventions[1], &s.interventions[2]];
+        assert_eq!((close.at, close.until, &close.action), (86_400, Some(108_000), &Action::CloseRoad { from: 4, to: 5 }));
+        assert_eq!((scale.at, scale.until), (3600, None));
+        assert_eq!(scale.action, Action::ScaleTravelTime { factor: 1.5, roads: vec![] });
+        assert_eq!(cancel.action, Action::CancelTrips { fraction: 0.25 });
+    }
+
+    #[test]
+    fn rejects_bad_interventions() {
+        let entries = [
+            "kind = \"close_road\"\nfrom = 0\nto = 1\nat = \"2h\"\nuntil = \"1h\"",
+            "kind = \"scale_travel_time\"\nfactor = 0\nat = 0",
+            "kind = \"cancel_trips\"\nfraction = 1.5\nat = 0",
+            "kind = \"flood\"\nat = 0",
+            "kind = \"cancel_trips\"\nfraction = 0.5\nat = 0\nroads = []",
+        ];
+        for entry in entries {
+            let toml = format!("{}\n[[interventions]]\n{entry}\n", scenario_toml("none"));
+            let err = Scenario::from_toml(&toml).unwrap_err();
+            assert!(matches!(err, CliError::Scenario(_)), "{entry}: {err}");
+        }
+
+        // Roads are checked against the network when the sim is built.
+        let dir = tempfile::tempdir().unwrap();
+        for (from, to) in [(0, 7), (0, 9)] {
+            let entry = format!("[[interventions]]\nkind = \"close_road\"\nfrom = {from}\nto = {to}\nat = 0\n");
+            let path = write(dir.path(), "scenario.toml", &(scenario_toml("none") + &entry));
+            let err = scenario::load(&path).err().unwrap();
+            assert!(err.to_string().contains("interventions[0]"), "{err}");
+        }
+    }
+
+    #[test]
+    fn load_places_agents_at_their_positions() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "positions.csv", "agent_id,node\n2,8\n");
+        let toml = scenario_toml("none").replace("agents = 4", "agents = 4\npositions = \"positions.csv\"");
+        let path = write(dir.path(), "scenario.toml", &toml);
+
+        let s = Scenario::load(&path).unwrap();
+        let p = crate::Population::load(&s.population, 9, s.behavior.mode, s.sim.seed).unwrap();
+        assert_eq!(p.initial_positions()[2], NodeId(8));
+
+        let mut sim = scenario::load(&path).unwrap().build().unwrap();
+        let states = &sim.mobility.store.states;
+        assert_eq!(states[2].departure_node, NodeId(8));
+        assert_eq!(states[0].departure_node, p.homes[0]);
+        sim.run(&mut NoopObserver).unwrap();
+        assert!(sim.failures.is_empty());
+
+        for (i, csv) in ["agent_id,node\n4,0\n", "agent_id,node\n0,9\n"].into_iter().enumerate() {
+            write(dir.path(), "positions.csv", csv);
+            let err = scenario::load(&path).err().unwrap();
+            assert!(matches!(err, CliError::Population(_)), "{i}: {err}");
+        }
+    }
+
+    #[test]
+    fn closed_road_fails_car_trips_until_it_reopens() {
+        let dir = tempfile::tempdir().unwrap();
+        let closure = "[[interventions]]\nkind = \"close_road\"\nfrom = 1\nto = 0\nat = 0\nuntil = \"1d\"\n";
+        let mut sim = scenario::load(&one_road(dir.path(), closure)).unwrap().build().unwrap();
+        let open_ms = sim.network.edge_travel_ms.clone();
+        let mut counts = Counts::default();
+
+        sim.run_ticks(12, &mut counts).unwrap();
+        assert!(sim.network.edge_travel_ms.iter().all(|&ms| ms == u32::MAX));
+        sim.run(&mut counts).unwrap();
+        assert_eq!(sim.network.edge_travel_ms, open_ms);
+        assert_eq!(counts.failures(0..24), 4);
+        assert_eq!(counts.failures(24..48), 0);
+        assert_eq!(counts.departures(24..48), 8);
+    }
+
+    #[test]
+    fn travel_times_scale_while_active() {
+        let dir = tempfile::tempdir().unwrap();
+        let scale = "[[interventions]]\nkind = \"scale_travel_time\"\nfactor = 2\nroads = [[0, 1]]\nat = \"6h\"\nuntil = \"1d\"\n";
+        let mut sim = scenario::load(&one_road(dir.path(), scale)).unwrap().build().unwrap();
+        let base = sim.network.edge_travel_ms.clone();
+
+        sim.run_ticks(3, &mut NoopObserver).unwrap();
+        assert_eq!(sim.network.edge_travel_ms, base);
+        sim.run_ticks(6, &mut NoopObserver).unwrap();
+        let doubled: Vec<u32> = base.iter().map(|ms| ms * 2).collect();
+        assert_eq!(sim.network.edge_travel_ms, doubled);
+        sim.run(&mut NoopObserver).unwrap();
+        assert_eq!(sim.network.edge_travel_ms, base);
+    }
+
+    #[test]
+    fn overlapping_road_interventions_combine() {
+        let dir = tempfile::tempdir().unwrap();
+        let both = "[[interventions]]\nkind = \"scale_travel_time\"\nfactor = 2\nroads = [[0, 1]]\nat = \"7h\"\nuntil = \"10h\"\n\
+                    [[interventions]]\nkind = \"close_road\"\nfrom = 0\nto = 1\nat = \"8h\"\nuntil = \"12h\"\n";
+        let mut sim = scenario::load(&one_road(dir.path(), both)).unwrap().build().unwrap();
+        let base = sim.network.edge_travel_ms.clone();
+        let doubled: Vec<u32> = base.iter().map(|ms| ms * 2).collect();
+
+        sim.run_ticks(8, &mut NoopObserver).unwrap();
+        assert_eq!(sim.network.edge_travel_ms, doubled);
+        // The closure wins over the factor, and outlasts it.
+        sim.run_ticks(2, &mut NoopObserver).unwrap();
+        assert!(sim.network.edge_travel_ms.iter().all(|&ms| ms == u32::MAX));
+        sim.run_ticks(2, &mut NoopObserver).unwrap();
+        assert!(sim.network.edge_travel_ms.iter().all(|&ms| ms == u32::MAX));
+        // Both over: the original times, not the scaled ones.
+        sim.run_ticks(1, &mut NoopObserver).unwrap();
+        assert_eq!(sim.network.edge_travel_ms, base);
+    }
+
+    #[test]
+    fn cancelled_trips_stop_departures() {
+        let dir = tempfile::tempdir().unwrap();
+        let cancel = "[[interventions]]\nkind = \"cancel_trips\"\nfraction = 1\nat = \"1d\"\n";
+        let mut sim = scenario::load(&one_road(dir.path(), cancel)).unwrap().build().unwrap();
+        let mut counts = Counts::default();
+        sim.run(&mut counts).unwrap();
+        assert_eq!(counts.departures(0..24), 8);
+        assert_eq!(counts.departures(24..48), 0);
+
+        // With none cancelled the run is unchanged.
+        let keep = cancel.replace("fraction = 1", "fraction = 0");
+        let mut sim = scenario::load(&one_road(dir.path(), &keep)).unwrap().build().unwrap();
+        let mut counts = Counts::default();
+        sim.run(&mut counts).unwrap();
+        assert_eq!(counts.departures(0..48), 16);
+    }
+}
+
+// ── Parquet plans ─────────────────────────────────────────────────────────────
+
+#[cfg(all(test, feature = "parquet"))]
+mod parquet_plans {
+    use std::sync::Arc;
+
+    use arrow::array::{Int64Array, RecordBatch, StringArray, UInt32Array};
+    use parquet::arrow::ArrowWriter;
+
+    use dt_core::NodeId;
+    use dt_schedule::Destination;
+
+    use crate::plans::load_plans;
+    use crate::{CliError, PlansSpec};
+
+    fn write_plans(path: &std::path::Path, starts: Vec<u32>, destinations: Vec<&str>) {
+        let n = starts.len();
+        let batch = RecordBatch::try_from_iter([
+            ("agent_id", Arc::new(Int64Array::from(vec![1; n])) as _),
+            ("activity_id", Arc::new(UInt32Array::from((0..n as u32).collect::<Vec<_>>())) as _),
+            ("start_offset_ticks", Arc::new(UInt32Array::from(starts)) as _),
+            ("duration_ticks", Arc::new(UInt32Array::from(vec![1; n])) as _),
+            ("destination", Arc::new(StringArray::from(destinations)) as _),
+            ("cycle_ticks", Arc::new(Int64Array::from(vec![24; n])) as _),
+        ])
+        .unwrap();
+        let mut writer = ArrowWriter::try_new(std::fs::File::create(path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    #[test]
+    fn loads_plans_with_any_integer_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plans.parquet");
+        write_plans(&path, vec![8, 0], vec!["work", "12"]);
+        let spec = PlansSpec { parquet: Some(path), ..Default::default() };
+        let plans = load_plans(&spec, 2, 3600).unwrap();
+        assert!(plans[0].is_empty());
+        assert_eq!(plans[1].cycle_ticks, 24);
+        let destinations: Vec<Destination> = plans[1].activities().iter().map(|a| a.destination.clone()).collect();
+        assert_eq!(destinations, [Destination::Node(NodeId(12)), Destination::Work]);
+    }
+
+    #[test]
+    fn rejects_offsets_past_the_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plans.parquet");
+        write_plans(&path, vec![24], vec!["home"]);
+        let spec = PlansSpec { parquet: Some(path), ..Default::default() };
+        assert!(matches!(load_plans(&spec, 2, 3600), Err(CliError::Schedule(_))));
+    }
+}
@@ -0,0 +1,118 @@
+//! Per-subsystem sections of [`SimConfig`][crate::SimConfig].
+//!
+//! Each section groups the options of one subsystem so that they live in
+//! the run's configuration file rather than in ad-hoc builder calls:
+//!
+//! ```toml
+//! start_unix_secs       = 1700000000
+//! tick_duration_secs    = 3600
+//! total_ticks           = 168
+//! seed                  = 42
+//! output_interval_ticks = 24
+//!
+//! [contacts]
+//! edge_contacts = true
+//!
+//! [output]
+//! trips = 24
+//!
+//! [extensions]
+//! vaccination_rate = "0.35"
+//! ```
+//!
+//! Every section and field is optional when deserialized; a missing one
+//! takes its default, which matches the framework's behavior without the
+//! section.  Settings that belong to the application rather than the
+//! framework go in [`SimConfig::extensions`][crate::SimConfig::extensions].
+
+// ── MobilityConfig ────────────────────────────────────────────────────────────
+
+/// Movement options, read by `dt-sim` when it builds the mobility engine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct MobilityConfig {
+    /// Shortest journey in ticks, however short the route.  Values below 1
+    /// are treated as 1, so an agent never arrives in the tick it departs.
+    /// Default: 1.
+    pub min_travel_ticks: u64,
+
+    /// Move in-transit agents along their routes edge by edge, so
+    /// snapshots and edge contacts see the edge each agent is on (see
+    /// `MobilityStore::path_following`).  Default: `false`.
+    pub path_following: bool,
+}
+
+impl Default for MobilityConfig {
+    fn default() -> Self {
+        Self { min_travel_ticks: 1, path_following: false }
+    }
+}
+
+// ── ContactConfig ─────────────────────────────────────────────────────────────
+
+/// Contact detection options, read by `dt-sim`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ContactConfig {
+    /// Report co-travelers on the same edge or transit vehicle to the
+    /// behavior model each tick (see `SimBuilder::edge_contacts`).
+    /// Default: `false`.
+    pub edge_contacts: bool,
+}
+
+// ── OutputConfig ──────────────────────────────────────────────────────────────
+
+/// Output cadence, read by `dt-output`'s observer.  Each field writes its
+/// table every N ticks; `0` never writes it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct OutputConfig {
+    /// Agent snapshots, thinning those taken every
+    /// `SimConfig::output_interval_ticks`.  Default: 1.
+    pub snapshots:      u64,
+
+    /// Tick summaries.  Default: 1.
+    pub tick_summaries: u64,
+
+    /// Contact events.  Default: 1.
+    pub contacts:       u64,
+
+    /// Completed trips.  Default: 1.
+    pub trips:          u64,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self { snapshots: 1, tick_summaries: 1, contacts: 1, trips: 1 }
+    }
+}
+
+// ── RoutingConfig ─────────────────────────────────────────────────────────────
+
+/// Assumed travel speeds for modes without per-edge travel times.
+///
+/// `dt-sim` copies this section to the network's `mode_speeds` when it
+/// builds the sim, and every dt-spatial router and edge cost derives walk,
+/// bike, and transit edge times from it.  Speeds must be positive.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct RoutingConfig {
+    /// Walking speed in m/s.  Default: 1.4.
+    pub walk_speed_mps:    f32,
+
+    /// Cycling speed in m/s.  Default: 4.2.
+    pub bike_speed_mps:    f32,
+
+    /// Average transit speed in m/s, stops included.  Default: 8.3.
+    pub transit_speed_mps: f32,
+}
+
+impl Default for RoutingConfig {
+    fn default() -> Self {
+        Self { walk_speed_mps: 1.4, bike_speed_mps: 4.2, transit_speed_mps: 8.3 }
+    }
+}
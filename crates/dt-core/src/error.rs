@@ -3,11 +3,45 @@
 //! Sub-crates may define their own error enums and convert them into `DtError`
 //! via `From` impls, or keep them separate and wrap `DtError` as one variant.
 //! Both patterns are acceptable; prefer whichever keeps error sites clean.
+//!
+//! Every framework crate's error converts into `DtError::Subsystem`, tagged
+//! with the [`ErrorCategory`] it came from, so application code can bubble
+//! any framework error with `?` through one type and still recover the
+//! original with [`DtError::downcast_ref`].
+
+use std::fmt;
 
 use thiserror::Error;
 
 use crate::{AgentId, NodeId};
 
+/// The framework subsystem an error came from.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ErrorCategory {
+    /// `dt-core` itself (configuration, parsing, I/O).
+    Core,
+    Spatial,
+    Schedule,
+    Behavior,
+    Mobility,
+    Sim,
+    Output,
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ErrorCategory::Core     => "core",
+            ErrorCategory::Spatial  => "spatial",
+            ErrorCategory::Schedule => "schedule",
+            ErrorCategory::Behavior => "behavior",
+            ErrorCategory::Mobility => "mobility",
+            ErrorCategory::Sim      => "simulation",
+            ErrorCategory::Output   => "output",
+        })
+    }
+}
+
 /// The top-level error type for `dt-core` and a common base for sub-crates.
 #[derive(Debug, Error)]
 pub enum DtError {
@@ -25,6 +59,41 @@ pub enum DtError {
 
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
+
+    /// An error from another framework crate.
+    #[error("{category} error: {source}")]
+    Subsystem {
+        category: ErrorCategory,
+        source:   Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+}
+
+impl DtError {
+    /// Wrap `source`, an error of the `category` subsystem.  Used by the
+    /// `From` impls in the other `dt-*` crates.
+    pub fn subsystem(
+        category: ErrorCategory,
+        source:   impl std::error::Error + Send + Sync + 'static,
+    ) -> Self {
+        DtError::Subsystem { category, source: Box::new(source) }
+    }
+
+    /// The subsystem the error came from.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            DtError::Subsystem { category, .. } => *category,
+            _                                   => ErrorCategory::Core,
+        }
+    }
+
+    /// The original subsystem error, if it is an `E`
+    /// (e.g. `err.downcast_ref::<SpatialError>()`).
+    pub fn downcast_ref<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        match self {
+            DtError::Subsystem { source, .. } => source.downcast_ref(),
+            _                                 => None,
+        }
+    }
 }
 
 /// Shorthand result type for all `dt-*` crates.
@@ -3,6 +3,19 @@
 //! `GeoPoint` uses `f32` (single-precision) latitude/longitude.  At the
 //! equator this gives ~1 m precision — more than sufficient for city-scale
 //! simulation while halving memory consumption vs. `f64`.
+//!
+//! `BBox` is the shared lat/lon rectangle used for clipping loaders and
+//! sizing grids.  Bearings are in degrees clockwise from true north.
+//!
+//! `LocalProjection` maps points to planar metres around an origin
+//! (equirectangular), so distance-heavy inner loops can use Euclidean
+//! arithmetic instead of repeated haversine.  Within ~50 km of the origin
+//! the error is well under 1 %; project around a point near the data.
+
+/// Mean Earth radius, metres.
+const EARTH_RADIUS_M: f32 = 6_371_000.0;
+
+// ── GeoPoint ─────────────────────────────────────────────────────────────────
 
 /// A WGS-84 geographic coordinate stored as single-precision floats.
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -24,8 +37,6 @@ impl GeoPoint {
     /// detection at city scale.  Use f64 Vincenty if sub-metre fidelity is
     /// ever required.
     pub fn distance_m(self, other: GeoPoint) -> f32 {
-        const R: f32 = 6_371_000.0; // mean Earth radius, metres
-
         let d_lat = (other.lat - self.lat).to_radians();
         let d_lon = (other.lon - self.lon).to_radians();
 
@@ -36,7 +47,69 @@ impl GeoPoint {
             + lat1.cos() * lat2.cos() * (d_lon * 0.5).sin().powi(2);
 
         let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
-        R * c
+        EARTH_RADIUS_M * c
+    }
+
+    /// Equirectangular approximation of [`distance_m`][Self::distance_m]:
+    /// one cosine and a square root instead of haversine's six trig calls.
+    ///
+    /// Within 0.1 % of haversine for points up to ~100 km apart away from
+    /// the poles, which covers road edges and city-scale radius queries.
+    #[inline]
+    pub fn approx_distance_m(self, other: GeoPoint) -> f32 {
+        let mean_lat = ((self.lat + other.lat) * 0.5).to_radians();
+        let x = (other.lon - self.lon).to_radians() * mean_lat.cos();
+        let y = (other.lat - self.lat).to_radians();
+        EARTH_RADIUS_M * (x * x + y * y).sqrt()
+    }
+
+    /// Initial great-circle bearing from `self` towards `other`, in degrees
+    /// clockwise from north in `[0, 360)`.  `0` if the points coincide.
+    pub fn bearing_to(self, other: GeoPoint) -> f32 {
+        let lat1 = (self.lat as f64).to_radians();
+        let lat2 = (other.lat as f64).to_radians();
+        let d_lon = (other.lon as f64 - self.lon as f64).to_radians();
+
+        let y = d_lon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lon.cos();
+        let deg = y.atan2(x).to_degrees().rem_euclid(360.0) as f32;
+        // Rounding to f32 can turn 359.99999… into 360.
+        if deg >= 360.0 { 0.0 } else { deg }
+    }
+
+    /// The point `meters` away from `self` along the great circle leaving at
+    /// `bearing_deg` (degrees clockwise from north).
+    ///
+    /// Computed in `f64`, so repeated small offsets do not accumulate `f32`
+    /// rounding beyond the final conversion.
+    pub fn offset_by(self, meters: f32, bearing_deg: f32) -> GeoPoint {
+        let lat1 = (self.lat as f64).to_radians();
+        let lon1 = (self.lon as f64).to_radians();
+        let bearing = (bearing_deg as f64).to_radians();
+        let angle = meters as f64 / EARTH_RADIUS_M as f64;
+
+        let lat2 = (lat1.sin() * angle.cos() + lat1.cos() * angle.sin() * bearing.cos()).asin();
+        let lon2 = lon1
+            + (bearing.sin() * angle.sin() * lat1.cos()).atan2(angle.cos() - lat1.sin() * lat2.sin());
+        // Normalise longitude to [-180, 180).
+        let lon2 = (lon2.to_degrees() + 540.0).rem_euclid(360.0) - 180.0;
+        GeoPoint::new(lat2.to_degrees() as f32, lon2 as f32)
+    }
+
+    /// `(x, y)` metres east and north of `origin` in the local projection.
+    ///
+    /// For many points around the same origin, build a [`LocalProjection`]
+    /// once instead.
+    #[inline]
+    pub fn to_local(self, origin: GeoPoint) -> (f64, f64) {
+        LocalProjection::new(origin).project(self)
+    }
+
+    /// The point `x` metres east and `y` metres north of `origin`; the
+    /// inverse of [`to_local`][Self::to_local].
+    #[inline]
+    pub fn from_local(origin: GeoPoint, x: f64, y: f64) -> GeoPoint {
+        LocalProjection::new(origin).unproject(x, y)
     }
 
     /// Approximate bounding-box check — much cheaper than `distance_m` for
@@ -53,3 +126,145 @@ impl std::fmt::Display for GeoPoint {
         write!(f, "({:.6}, {:.6})", self.lat, self.lon)
     }
 }
+
+// ── LocalProjection ──────────────────────────────────────────────────────────
+
+/// Equirectangular projection to metres around a fixed origin.
+///
+/// `x` grows east and `y` north; the origin maps to `(0, 0)`.  Longitudes
+/// are taken relative to the origin's, wrapped to ±180°, so points just
+/// across the antimeridian stay near.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LocalProjection {
+    origin:    GeoPoint,
+    /// Metres per degree of longitude at the origin's latitude.
+    m_per_lon: f64,
+}
+
+/// Metres per degree of latitude (and of longitude at the equator).
+const M_PER_DEG: f64 = EARTH_RADIUS_M as f64 * std::f64::consts::PI / 180.0;
+
+impl LocalProjection {
+    pub fn new(origin: GeoPoint) -> Self {
+        let m_per_lon = M_PER_DEG * (origin.lat as f64).to_radians().cos();
+        Self { origin, m_per_lon }
+    }
+
+    pub fn origin(&self) -> GeoPoint {
+        self.origin
+    }
+
+    /// `(x, y)` metres east and north of the origin.
+    #[inline]
+    pub fn project(&self, p: GeoPoint) -> (f64, f64) {
+        let d_lon = (p.lon as f64 - self.origin.lon as f64 + 540.0).rem_euclid(360.0) - 180.0;
+        let d_lat = p.lat as f64 - self.origin.lat as f64;
+        (d_lon * self.m_per_lon, d_lat * M_PER_DEG)
+    }
+
+    /// The point `x` metres east and `y` metres north of the origin.
+    #[inline]
+    pub fn unproject(&self, x: f64, y: f64) -> GeoPoint {
+        let lat = self.origin.lat as f64 + y / M_PER_DEG;
+        let lon = self.origin.lon as f64 + x / self.m_per_lon;
+        let lon = (lon + 540.0).rem_euclid(360.0) - 180.0;
+        GeoPoint::new(lat as f32, lon as f32)
+    }
+
+    /// Planar distance in metres between two points.
+    #[inline]
+    pub fn distance_m(&self, a: GeoPoint, b: GeoPoint) -> f64 {
+        let ((ax, ay), (bx, by)) = (self.project(a), self.project(b));
+        (ax - bx).hypot(ay - by)
+    }
+}
+
+// ── BBox ─────────────────────────────────────────────────────────────────────
+
+/// A lat/lon rectangle, edges inclusive.
+///
+/// Boxes crossing the antimeridian are not supported: `west` must not exceed
+/// `east`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BBox {
+    pub south: f32,
+    pub west:  f32,
+    pub north: f32,
+    pub east:  f32,
+}
+
+impl BBox {
+    #[inline]
+    pub fn new(south: f32, west: f32, north: f32, east: f32) -> Self {
+        Self { south, west, north, east }
+    }
+
+    /// The smallest box containing every point, or `None` if there are none.
+    pub fn from_points(points: impl IntoIterator<Item = GeoPoint>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bbox = Self::new(first.lat, first.lon, first.lat, first.lon);
+        for p in points {
+            bbox.extend(p);
+        }
+        Some(bbox)
+    }
+
+    /// The box enclosing the circle of `radius_m` metres around `center`.
+    pub fn around(center: GeoPoint, radius_m: f32) -> Self {
+        let north = center.offset_by(radius_m, 0.0).lat;
+        let south = center.offset_by(radius_m, 180.0).lat;
+        // A degree of longitude is shortest at the edge nearest a pole.
+        let polar_lat = north.abs().max(south.abs()).min(89.999);
+        let d_lon = (radius_m / (EARTH_RADIUS_M * polar_lat.to_radians().cos())).to_degrees();
+        Self::new(south, center.lon - d_lon, north, center.lon + d_lon)
+    }
+
+    /// Grow the box to include `p`.
+    pub fn extend(&mut self, p: GeoPoint) {
+        self.south = self.south.min(p.lat);
+        self.west = self.west.min(p.lon);
+        self.north = self.north.max(p.lat);
+        self.east = self.east.max(p.lon);
+    }
+
+    /// The smallest box containing both `self` and `other`.
+    pub fn union(self, other: BBox) -> BBox {
+        BBox::new(
+            self.south.min(other.south),
+            self.west.min(other.west),
+            self.north.max(other.north),
+            self.east.max(other.east),
+        )
+    }
+
+    /// The box grown by `deg` degrees on every side.
+    pub fn padded(self, deg: f32) -> BBox {
+        BBox::new(self.south - deg, self.west - deg, self.north + deg, self.east + deg)
+    }
+
+    #[inline]
+    pub fn contains(&self, p: GeoPoint) -> bool {
+        (self.south..=self.north).contains(&p.lat) && (self.west..=self.east).contains(&p.lon)
+    }
+
+    /// `true` if the boxes share at least one point.
+    #[inline]
+    pub fn intersects(&self, other: &BBox) -> bool {
+        self.south <= other.north
+            && other.south <= self.north
+            && self.west <= other.east
+            && other.west <= self.east
+    }
+
+    /// The midpoint of the box in degrees.
+    pub fn center(&self) -> GeoPoint {
+        GeoPoint::new((self.south + self.north) * 0.5, (self.west + self.east) * 0.5)
+    }
+
+    /// `true` if the box has positive height and width.
+    pub fn has_area(&self) -> bool {
+        self.south < self.north && self.west < self.east
+    }
+}
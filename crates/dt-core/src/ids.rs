@@ -5,18 +5,36 @@
 //! direct indexing into SoA `Vec`s via `id.0 as usize`, but callers should
 //! prefer the `.index()` helpers for clarity.
 
-use std::fmt;
-
-/// Generate a typed ID wrapper around a primitive integer.
-macro_rules! typed_id {
+/// Define a typed ID wrapper around a primitive unsigned integer.
+///
+/// The generated type follows the conventions of the built-in IDs: it is
+/// `Copy + Ord + Hash`, its inner integer is `pub`, `INVALID` (the integer's
+/// `MAX`) is the sentinel and the `Default`, `.index()` casts to `usize`, and
+/// it converts to `usize` and (fallibly) from it.  `Display` prints
+/// `Name(n)`.
+///
+/// Extra attributes such as doc comments or `serde` derives are passed
+/// through:
+///
+/// ```
+/// dt_core::define_id! {
+///     /// Index of a bus stop.
+///     pub struct StopId(u32);
+/// }
+///
+/// assert_eq!(StopId::default(), StopId::INVALID);
+/// assert_eq!(StopId(7).index(), 7);
+/// assert_eq!(StopId(7).to_string(), "StopId(7)");
+/// ```
+#[macro_export]
+macro_rules! define_id {
     ($(#[$attr:meta])* $vis:vis struct $name:ident($inner:ty);) => {
         $(#[$attr])*
         #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
-        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         $vis struct $name(pub $inner);
 
         impl $name {
-            /// Sentinel meaning "no valid ID" — equivalent to `u32::MAX`.
+            /// Sentinel meaning "no valid ID" — the inner type's `MAX`.
             pub const INVALID: $name = $name(<$inner>::MAX);
 
             /// Cast to `usize` for direct use as a `Vec` index.
@@ -26,7 +44,7 @@ macro_rules! typed_id {
             }
         }
 
-        impl Default for $name {
+        impl ::core::default::Default for $name {
             /// Returns the `INVALID` sentinel so uninitialized IDs are visibly invalid.
             #[inline(always)]
             fn default() -> Self {
@@ -34,28 +52,39 @@ macro_rules! typed_id {
             }
         }
 
-        impl fmt::Display for $name {
-            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
                 write!(f, "{}({})", stringify!($name), self.0)
             }
         }
 
-        impl From<$name> for usize {
+        impl ::core::convert::From<$name> for usize {
             #[inline(always)]
             fn from(id: $name) -> usize {
                 id.0 as usize
             }
         }
 
-        impl TryFrom<usize> for $name {
-            type Error = std::num::TryFromIntError;
-            fn try_from(n: usize) -> Result<$name, Self::Error> {
+        impl ::core::convert::TryFrom<usize> for $name {
+            type Error = ::core::num::TryFromIntError;
+            fn try_from(n: usize) -> ::core::result::Result<$name, Self::Error> {
                 <$inner>::try_from(n).map($name)
             }
         }
     };
 }
 
+/// [`define_id!`] plus the `serde` derives behind this crate's feature flag.
+macro_rules! typed_id {
+    ($(#[$attr:meta])* $vis:vis struct $name:ident($inner:ty);) => {
+        define_id! {
+            $(#[$attr])*
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+            $vis struct $name($inner);
+        }
+    };
+}
+
 typed_id! {
     /// Index of an agent in SoA storage.  Max ~4.3 billion agents.
     pub struct AgentId(u32);
@@ -76,3 +105,23 @@ typed_id! {
     /// Using `u16` keeps schedule arrays compact (max 65,535 activity types).
     pub struct ActivityId(u16);
 }
+
+typed_id! {
+    /// Index of a household (agents sharing a home and budget).
+    pub struct HouseholdId(u32);
+}
+
+typed_id! {
+    /// Index of a vehicle in a fleet or household.
+    pub struct VehicleId(u32);
+}
+
+typed_id! {
+    /// Index of an analysis or traffic zone.
+    pub struct ZoneId(u32);
+}
+
+typed_id! {
+    /// Index of a point of interest (shop, school, venue).
+    pub struct PoiId(u32);
+}
@@ -2,41 +2,61 @@
 //!
 //! This crate is a dependency of every other `dt-*` crate.  It intentionally
 //! has no `dt-*` dependencies and minimal external ones (only `rand` and
-//! `thiserror`, plus optional `serde`).
+//! `thiserror`, plus optional `serde` and `rand_distr`; `web-time` and
+//! `getrandom` on `wasm32-unknown-unknown`).
 //!
 //! # What lives here
 //!
 //! | Module          | Contents                                              |
 //! |-----------------|-------------------------------------------------------|
-//! | [`ids`]         | `AgentId`, `NodeId`, `EdgeId`, `ActivityId`           |
-//! | [`geo`]         | `GeoPoint`, haversine distance                        |
-//! | [`time`]        | `Tick`, `SimClock`, `SimConfig`                       |
+//! | [`ids`]         | `AgentId`, `NodeId`, `EdgeId`, … and `define_id!`     |
+//! | [`geo`]         | `GeoPoint`, `BBox`, `LocalProjection`, distance       |
+//! | [`time`]        | `Tick`, `TickDuration`, `TickRange`, `SimClock`, …    |
 //! | [`rng`]         | `AgentRng` (per-agent), `SimRng` (global)             |
-//! | [`transport`]   | `TransportMode` enum                                  |
-//! | [`error`]       | `DtError`, `DtResult`                                 |
+//! | [`transport`]   | `TransportMode` enum, `TransitVehicle`                |
+//! | [`error`]       | `DtError`, `DtResult`, `ErrorCategory`                |
+//! | [`config`]      | Per-subsystem sections of `SimConfig`                 |
+//! | [`timefmt`]     | RFC 3339 datetimes and `"1h30m"` durations            |
+//! | [`wallclock`]   | `Instant` that also works in the browser              |
 //!
 //! # Feature flags
 //!
-//! | Flag    | Effect                                                     |
-//! |---------|------------------------------------------------------------|
-//! | `serde` | Adds `Serialize`/`Deserialize` to all public types.        |
-//!           | Required by `dt-checkpoint`.                               |
+//! | Flag            | Effect                                             |
+//! |-----------------|----------------------------------------------------|
+//! | `serde`         | Adds `Serialize`/`Deserialize` to all public types.|
+//! |                 | Required by `dt-checkpoint`.                       |
+//! | `distributions` | `sample_normal`, `sample_lognormal`,               |
+//! |                 | `sample_exponential`, `sample_weighted` on the     |
+//! |                 | RNGs (via `rand_distr`).                           |
+//!
+//! # WebAssembly
+//!
+//! The crate builds for `wasm32-unknown-unknown` with no extra flags:
+//! `rand`'s entropy source is routed to the browser's `crypto` API, and
+//! [`wallclock::Instant`] uses `performance.now()`.  Runs are reproducible
+//! for a given seed, but `rand`'s `SmallRng` is a different generator on
+//! 32-bit targets, so a browser run does not match a native run of the same
+//! scenario tick for tick.
 
+pub mod config;
 pub mod error;
 pub mod geo;
 pub mod ids;
 pub mod rng;
 pub mod time;
+pub mod timefmt;
 pub mod transport;
+pub mod wallclock;
 
 #[cfg(test)]
 mod tests;
 
 // ── Re-exports ────────────────────────────────────────────────────────────────
 
-pub use error::{DtError, DtResult};
-pub use geo::GeoPoint;
-pub use ids::{ActivityId, AgentId, EdgeId, NodeId};
+pub use config::{ContactConfig, MobilityConfig, OutputConfig, RoutingConfig};
+pub use error::{DtError, DtResult, ErrorCategory};
+pub use geo::{BBox, GeoPoint, LocalProjection};
+pub use ids::{ActivityId, AgentId, EdgeId, HouseholdId, NodeId, PoiId, VehicleId, ZoneId};
 pub use rng::{AgentRng, SimRng};
-pub use time::{SimClock, SimConfig, Tick};
-pub use transport::TransportMode;
+pub use time::{Every, SimClock, SimConfig, Tick, TickDuration, TickRange};
+pub use transport::{TransitVehicle, TransportMode};
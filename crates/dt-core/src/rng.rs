@@ -14,6 +14,23 @@
 //! - Adding or removing agents at the end of the list does not disturb the
 //!   seeds of existing agents — runs are reproducible even as populations grow.
 //! - All RNG calls are local to the owning thread; no synchronisation needed.
+//!
+//! # Named streams
+//!
+//! Global consumers should not share one `SimRng`: adding a new consumer
+//! would shift every draw made after it.  Instead each takes its own named
+//! stream, `SimRng::new(seed).stream("mobility-noise")`, seeded from a hash
+//! of the name and the master seed alone.  Streams are independent of each
+//! other and of the order in which they are created or drawn from, so a new
+//! consumer leaves existing results untouched.
+//!
+//! # Distributions
+//!
+//! With feature `distributions`, both RNGs sample the normal, log-normal,
+//! exponential, and discrete weighted distributions directly
+//! (`rng.sample_normal(30.0, 5.0)`), so applications need no `rand_distr`
+//! dependency of their own.  Invalid parameters never panic; each method
+//! documents what it returns for them.
 
 use rand::rngs::SmallRng;
 use rand::{Rng, SeedableRng};
@@ -23,6 +40,106 @@ use crate::AgentId;
 /// 64-bit fractional golden-ratio constant for seed mixing.
 const MIXING_CONSTANT: u64 = 0x9e37_79b9_7f4a_7c15;
 
+/// 64-bit FNV-1a hash of `name`.  Fixed here rather than taken from `std`
+/// so stream seeds never change across Rust versions or platforms.
+fn name_hash(name: &str) -> u64 {
+    name.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// SplitMix64 finaliser: scrambles `x` so nearby inputs give unrelated seeds.
+fn mix(mut x: u64) -> u64 {
+    x = x.wrapping_add(MIXING_CONSTANT);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+// ── Distributions ─────────────────────────────────────────────────────────────
+
+#[cfg(feature = "distributions")]
+mod dist {
+    use rand::Rng;
+    use rand_distr::{Distribution, Exp, LogNormal, Normal, WeightedIndex};
+
+    /// `rand_distr` accepts a negative spread (mirroring the samples), so
+    /// it is rejected here.
+    fn valid_spread(spread: f64) -> bool {
+        spread >= 0.0 && spread.is_finite()
+    }
+
+    pub(super) fn normal(rng: &mut impl Rng, mean: f64, std_dev: f64) -> f64 {
+        if !valid_spread(std_dev) {
+            return mean;
+        }
+        Normal::new(mean, std_dev).map_or(mean, |d| d.sample(rng))
+    }
+
+    pub(super) fn lognormal(rng: &mut impl Rng, mu: f64, sigma: f64) -> f64 {
+        if !valid_spread(sigma) {
+            return mu.exp();
+        }
+        LogNormal::new(mu, sigma).map_or(mu.exp(), |d| d.sample(rng))
+    }
+
+    pub(super) fn exponential(rng: &mut impl Rng, rate: f64) -> f64 {
+        if rate > 0.0 {
+            Exp::new(rate).map_or(f64::INFINITY, |d| d.sample(rng))
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    pub(super) fn weighted(rng: &mut impl Rng, weights: &[f32]) -> Option<usize> {
+        // `WeightedIndex` panics rather than erring on an infinite total.
+        if !weights.iter().sum::<f32>().is_finite() {
+            return None;
+        }
+        WeightedIndex::new(weights).ok().map(|d| d.sample(rng))
+    }
+}
+
+/// The distribution methods shared by [`AgentRng`] and [`SimRng`].
+macro_rules! distribution_methods {
+    () => {
+        /// Normal (Gaussian) sample.  A negative or non-finite `std_dev`
+        /// returns `mean`.
+        #[cfg(feature = "distributions")]
+        #[inline]
+        pub fn sample_normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+            dist::normal(self.inner(), mean, std_dev)
+        }
+
+        /// Log-normal sample: `exp(N(mu, sigma))`, so `mu` and `sigma` are
+        /// the mean and standard deviation of the logarithm.  A negative or
+        /// non-finite `sigma` returns `exp(mu)`.
+        #[cfg(feature = "distributions")]
+        #[inline]
+        pub fn sample_lognormal(&mut self, mu: f64, sigma: f64) -> f64 {
+            dist::lognormal(self.inner(), mu, sigma)
+        }
+
+        /// Exponential sample (e.g. a waiting time) with `rate` events per
+        /// unit; the mean is `1 / rate`.  A `rate` that is not positive
+        /// returns infinity — the event never happens.
+        #[cfg(feature = "distributions")]
+        #[inline]
+        pub fn sample_exponential(&mut self, rate: f64) -> f64 {
+            dist::exponential(self.inner(), rate)
+        }
+
+        /// Index into `weights` chosen with probability proportional to its
+        /// weight.  `None` if `weights` is empty, sums to zero, or contains a
+        /// negative or non-finite weight.
+        #[cfg(feature = "distributions")]
+        #[inline]
+        pub fn sample_weighted(&mut self, weights: &[f32]) -> Option<usize> {
+            dist::weighted(self.inner(), weights)
+        }
+    };
+}
+
 // ── AgentRng ──────────────────────────────────────────────────────────────────
 
 /// Per-agent deterministic RNG.
@@ -85,6 +202,8 @@ impl AgentRng {
         use rand::seq::SliceRandom;
         slice.choose(&mut self.0)
     }
+
+    distribution_methods!();
 }
 
 // ── SimRng ────────────────────────────────────────────────────────────────────
@@ -94,24 +213,45 @@ impl AgentRng {
 ///
 /// Used only in single-threaded or explicitly synchronised contexts.  If you
 /// need parallel randomness, give each worker thread its own `SimRng` seeded
-/// from this one.
-pub struct SimRng(SmallRng);
+/// from this one.  Give each independent consumer its own
+/// [`stream`][Self::stream].
+pub struct SimRng {
+    rng:  SmallRng,
+    /// The seed this RNG was created from; streams derive from it.
+    seed: u64,
+}
 
 impl SimRng {
     pub fn new(seed: u64) -> Self {
-        SimRng(SmallRng::seed_from_u64(seed))
+        SimRng { rng: SmallRng::seed_from_u64(seed), seed }
+    }
+
+    /// The seed this RNG was created from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The sub-stream called `name`, seeded from a hash of `name` and this
+    /// RNG's seed only — not from its state, so it does not matter how
+    /// many values have been drawn or which other streams exist.  Streams
+    /// can be nested (`stream("a").stream("b")`).
+    pub fn stream(&self, name: &str) -> SimRng {
+        SimRng::new(mix(self.seed ^ mix(name_hash(name))))
     }
 
     /// Derive a child `SimRng` with a different seed offset — useful for
     /// seeding per-thread RNGs deterministically from the root seed.
+    ///
+    /// Unlike [`stream`][Self::stream], this draws from `self`, so the
+    /// child depends on every draw made before it.
     pub fn child(&mut self, offset: u64) -> SimRng {
-        let child_seed: u64 = self.0.r#gen::<u64>() ^ offset.wrapping_mul(MIXING_CONSTANT);
-        SimRng(SmallRng::seed_from_u64(child_seed))
+        let child_seed: u64 = self.rng.r#gen::<u64>() ^ offset.wrapping_mul(MIXING_CONSTANT);
+        SimRng::new(child_seed)
     }
 
     #[inline]
     pub fn inner(&mut self) -> &mut SmallRng {
-        &mut self.0
+        &mut self.rng
     }
 
     #[inline]
@@ -119,7 +259,7 @@ impl SimRng {
     where
         rand::distributions::Standard: rand::distributions::Distribution<T>,
     {
-        self.0.r#gen()
+        self.rng.r#gen()
     }
 
     #[inline]
@@ -128,11 +268,13 @@ impl SimRng {
         T: rand::distributions::uniform::SampleUniform,
         R: rand::distributions::uniform::SampleRange<T>,
     {
-        self.0.gen_range(range)
+        self.rng.gen_range(range)
     }
 
     #[inline]
     pub fn gen_bool(&mut self, p: f64) -> bool {
-        self.0.gen_bool(p.clamp(0.0, 1.0))
+        self.rng.gen_bool(p.clamp(0.0, 1.0))
     }
+
+    distribution_methods!();
 }
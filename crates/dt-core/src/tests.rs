@@ -2,7 +2,7 @@
 
 #[cfg(test)]
 mod ids {
-    use crate::{AgentId, EdgeId, NodeId};
+    use crate::{AgentId, EdgeId, HouseholdId, NodeId, PoiId, VehicleId, ZoneId};
 
     #[test]
     fn index_roundtrip() {
@@ -28,11 +28,35 @@ mod ids {
     fn display() {
         assert_eq!(AgentId(7).to_string(), "AgentId(7)");
     }
+
+    #[test]
+    fn additional_ids_follow_conventions() {
+        assert_eq!(HouseholdId::default(), HouseholdId::INVALID);
+        assert_eq!(VehicleId::INVALID.0, u32::MAX);
+        assert_eq!(ZoneId(3).index(), 3);
+        assert_eq!(PoiId(9).to_string(), "PoiId(9)");
+    }
+
+    crate::define_id! {
+        /// A narrow ID defined the way downstream crates would.
+        struct LaneId(u8);
+    }
+
+    #[test]
+    fn define_id_macro() {
+        assert_eq!(LaneId::INVALID, LaneId(u8::MAX));
+        assert_eq!(LaneId::default(), LaneId::INVALID);
+        assert_eq!(LaneId(4).index(), 4);
+        assert_eq!(usize::from(LaneId(4)), 4);
+        assert_eq!(LaneId::try_from(4usize).unwrap(), LaneId(4));
+        assert!(LaneId::try_from(256usize).is_err());
+        assert!(LaneId(1) < LaneId(2));
+    }
 }
 
 #[cfg(test)]
 mod geo {
-    use crate::GeoPoint;
+    use crate::{BBox, GeoPoint, LocalProjection};
 
     #[test]
     fn zero_distance() {
@@ -49,6 +73,17 @@ mod geo {
         assert!((d - 111_195.0).abs() < 500.0, "got {d}");
     }
 
+    #[test]
+    fn approx_distance_close_to_haversine() {
+        let a = GeoPoint::new(52.52, 13.405);
+        for (bearing, meters) in [(0.0, 50.0), (45.0, 800.0), (100.0, 5_000.0), (250.0, 40_000.0)] {
+            let b = a.offset_by(meters, bearing);
+            let (approx, exact) = (a.approx_distance_m(b), a.distance_m(b));
+            assert!((approx - exact).abs() / exact < 0.001, "{meters} m: {approx} vs {exact}");
+        }
+        assert_eq!(a.approx_distance_m(a), 0.0);
+    }
+
     #[test]
     fn bbox_check() {
         let center = GeoPoint::new(30.694, -88.043);
@@ -57,11 +92,98 @@ mod geo {
         assert!(nearby.within_bbox(center, 0.1));
         assert!(!far.within_bbox(center, 0.1));
     }
+
+    #[test]
+    fn bearing_cardinal_directions() {
+        let p = GeoPoint::new(30.0, -88.0);
+        assert!(p.bearing_to(GeoPoint::new(31.0, -88.0)).abs() < 0.01);
+        assert!((p.bearing_to(GeoPoint::new(30.0, -87.9)) - 90.0).abs() < 0.1);
+        assert!((p.bearing_to(GeoPoint::new(29.0, -88.0)) - 180.0).abs() < 0.01);
+        assert!((p.bearing_to(GeoPoint::new(30.0, -88.1)) - 270.0).abs() < 0.1);
+        assert_eq!(p.bearing_to(p), 0.0);
+    }
+
+    #[test]
+    fn offset_round_trips_distance_and_bearing() {
+        let p = GeoPoint::new(30.694, -88.043);
+        for bearing in [0.0, 45.0, 135.0, 225.0, 300.0] {
+            let q = p.offset_by(1_000.0, bearing);
+            assert!((p.distance_m(q) - 1_000.0).abs() < 5.0, "bearing {bearing}: {q}");
+            assert!((p.bearing_to(q) - bearing).abs() < 0.1, "bearing {bearing}: {q}");
+        }
+        // Longitude wraps across the antimeridian.
+        let q = GeoPoint::new(0.0, 179.999).offset_by(1_000.0, 90.0);
+        assert!(q.lon < -179.0, "{q}");
+    }
+
+    #[test]
+    fn bbox_from_points_and_queries() {
+        assert!(BBox::from_points([]).is_none());
+        let bbox = BBox::from_points([
+            GeoPoint::new(30.6, -88.1),
+            GeoPoint::new(30.8, -88.0),
+            GeoPoint::new(30.7, -88.2),
+        ])
+        .unwrap();
+        assert_eq!(bbox, BBox::new(30.6, -88.2, 30.8, -88.0));
+        assert!(bbox.contains(GeoPoint::new(30.8, -88.2)));
+        assert!(!bbox.contains(GeoPoint::new(30.9, -88.1)));
+        assert!(bbox.has_area());
+
+        let east = BBox::new(30.7, -88.0, 31.0, -87.5);
+        assert!(bbox.intersects(&east));
+        assert!(!bbox.intersects(&BBox::new(31.0, -88.1, 31.1, -88.0)));
+        assert_eq!(bbox.union(east), BBox::new(30.6, -88.2, 31.0, -87.5));
+        assert_eq!(bbox.padded(0.5).south, 30.1);
+    }
+
+    #[test]
+    fn bbox_around_encloses_circle() {
+        let center = GeoPoint::new(45.0, 10.0);
+        let bbox = BBox::around(center, 2_000.0);
+        assert!(bbox.center().distance_m(center) < 1.0);
+        for bearing in (0..360).step_by(15) {
+            let edge = center.offset_by(1_999.0, bearing as f32);
+            assert!(bbox.contains(edge), "bearing {bearing}: {edge}");
+        }
+        assert!(!bbox.contains(center.offset_by(2_100.0, 0.0)));
+    }
+
+    #[test]
+    fn local_projection_axes_and_round_trip() {
+        let origin = GeoPoint::new(30.694, -88.043);
+        assert_eq!(origin.to_local(origin), (0.0, 0.0));
+
+        let (x, y) = origin.offset_by(1_000.0, 90.0).to_local(origin);
+        assert!((x - 1_000.0).abs() < 2.0 && y.abs() < 2.0, "({x}, {y})");
+        let (x, y) = origin.offset_by(1_000.0, 180.0).to_local(origin);
+        assert!(x.abs() < 2.0 && (y + 1_000.0).abs() < 2.0, "({x}, {y})");
+
+        let p = GeoPoint::from_local(origin, 2_500.0, -1_200.0);
+        let (x, y) = p.to_local(origin);
+        assert!((x - 2_500.0).abs() < 0.5 && (y + 1_200.0).abs() < 0.5, "({x}, {y})");
+    }
+
+    #[test]
+    fn local_distance_matches_haversine_nearby() {
+        let proj = LocalProjection::new(GeoPoint::new(52.52, 13.405));
+        let a = GeoPoint::new(52.53, 13.39);
+        let b = GeoPoint::new(52.49, 13.45);
+        let planar = proj.distance_m(a, b);
+        let great_circle = a.distance_m(b) as f64;
+        assert!((planar - great_circle).abs() / great_circle < 0.005, "{planar} vs {great_circle}");
+
+        // Across the antimeridian, points stay a few hundred metres apart.
+        let proj = LocalProjection::new(GeoPoint::new(0.0, 179.999));
+        let (x, _) = proj.project(GeoPoint::new(0.0, -179.999));
+        // ~0.002° of longitude at the equator, give or take f32 rounding.
+        assert!((x - 222.4).abs() < 5.0, "{x}");
+    }
 }
 
 #[cfg(test)]
 mod time {
-    use crate::{SimClock, SimConfig, Tick};
+    use crate::{SimClock, SimConfig, Tick, TickDuration, TickRange};
 
     #[test]
     fn tick_arithmetic() {
@@ -71,6 +193,94 @@ mod time {
         assert_eq!(Tick(15) - Tick(10), 5u64);
     }
 
+    #[test]
+    fn duration_arithmetic() {
+        let mut t = Tick(10) + TickDuration(5);
+        assert_eq!(t, Tick(15));
+        t += TickDuration(2);
+        assert_eq!(t - TickDuration(7), Tick(10));
+        assert_eq!(Tick(15).duration_since(Tick(10)), TickDuration(5));
+        assert_eq!(TickDuration(3) + TickDuration(4), TickDuration(7));
+        assert_eq!(TickDuration(3) * 4, TickDuration(12));
+        assert_eq!(TickDuration(3).saturating_sub(TickDuration(4)), TickDuration::ZERO);
+        assert_eq!(TickDuration(3).to_string(), "3 ticks");
+    }
+
+    #[test]
+    fn arithmetic_saturates_instead_of_wrapping() {
+        assert_eq!(Tick(10) - TickDuration(48), Tick::ZERO);
+        assert_eq!(Tick(10) - Tick(15), 0);
+        assert_eq!(Tick(u64::MAX) + TickDuration(1), Tick(u64::MAX));
+        assert_eq!(Tick(u64::MAX) + 1, Tick(u64::MAX));
+        assert_eq!(Tick(u64::MAX - 1).offset(5), Tick(u64::MAX));
+        assert_eq!(TickDuration(3) - TickDuration(4), TickDuration::ZERO);
+        assert_eq!(TickDuration(u64::MAX) * 2, TickDuration(u64::MAX));
+
+        let mut t = Tick(u64::MAX - 1);
+        t += TickDuration(3);
+        assert_eq!(t, Tick(u64::MAX));
+    }
+
+    #[test]
+    fn checked_arithmetic_reports_out_of_range() {
+        assert_eq!(Tick(10).checked_sub(TickDuration(4)), Some(Tick(6)));
+        assert_eq!(Tick(10).checked_sub(TickDuration(11)), None);
+        assert_eq!(Tick(10).saturating_sub(TickDuration(11)), Tick::ZERO);
+        assert_eq!(Tick(10).checked_add(TickDuration(1)), Some(Tick(11)));
+        assert_eq!(Tick(u64::MAX).checked_add(TickDuration(1)), None);
+        assert_eq!(Tick(15).checked_since(Tick(10)), Some(5));
+        assert_eq!(Tick(10).checked_since(Tick(15)), None);
+        assert_eq!(Tick(10).saturating_since(Tick(15)), 0);
+        assert_eq!(TickDuration(3).checked_sub(TickDuration(4)), None);
+    }
+
+    #[test]
+    fn tick_alignment() {
+        assert!(Tick(48).is_multiple_of(24));
+        assert!(!Tick(50).is_multiple_of(24));
+        assert_eq!(Tick(50).next_multiple_of(24), Tick(72));
+        assert_eq!(Tick(48).next_multiple_of(24), Tick(48));
+        assert_eq!(Tick(50).prev_multiple_of(24), Tick(48));
+    }
+
+    #[test]
+    fn tick_ranges() {
+        let ticks: Vec<Tick> = Tick(3).until(Tick(6)).collect();
+        assert_eq!(ticks, [Tick(3), Tick(4), Tick(5)]);
+        assert_eq!(Tick(6).until(Tick(3)).count(), 0);
+
+        let range = TickRange::new(Tick(1), Tick(10)).step(4);
+        assert_eq!(range.clone().count(), 3);
+        assert!(range.contains(Tick(5)) && !range.contains(Tick(6)) && !range.contains(Tick(13)));
+        assert_eq!(range.collect::<Vec<_>>(), [Tick(1), Tick(5), Tick(9)]);
+
+        // Aligned to the cadence, not to the window start.
+        let days: Vec<Tick> = Tick::every(24).between(Tick(10), Tick(80)).collect();
+        assert_eq!(days, [Tick(24), Tick(48), Tick(72)]);
+        assert!(Tick::every(24).contains(Tick(0)));
+        assert_eq!(Tick::every(24).next_from(Tick(25)), Tick(48));
+
+        // A range ending at the top of the tick space terminates.
+        let top = TickRange::new(Tick(u64::MAX - 3), Tick(u64::MAX)).step(2);
+        assert_eq!(top.count(), 2);
+    }
+
+    #[test]
+    fn duration_secs_conversion() {
+        // 15-minute ticks.
+        assert_eq!(TickDuration::from_secs(1_800, 900), TickDuration(2));
+        // partial tick rounds up
+        assert_eq!(TickDuration::from_secs(901, 900), TickDuration(2));
+        assert_eq!(TickDuration::from_hours(1, 900), TickDuration(4));
+        assert_eq!(TickDuration::from_days(1, 3600), TickDuration(24));
+        assert_eq!(TickDuration(4).as_secs(900), 3_600);
+
+        let clock = SimClock::new(0, 900);
+        assert_eq!(clock.duration_from_hours(2), TickDuration(8));
+        assert_eq!(clock.duration_from_days(1), TickDuration(96));
+        assert_eq!(clock.duration_secs(clock.duration_from_secs(600)), 900);
+    }
+
     #[test]
     fn clock_elapsed() {
         let mut clock = SimClock::new(0, 3600); // 1 tick = 1 hour
@@ -112,6 +322,7 @@ mod time {
             seed: 42,
             num_threads: None,
             output_interval_ticks: 24,
+            ..Default::default()
         };
         assert_eq!(cfg.end_tick(), Tick(8760));
     }
@@ -119,7 +330,7 @@ mod time {
 
 #[cfg(test)]
 mod rng {
-    use crate::{AgentId, AgentRng};
+    use crate::{AgentId, AgentRng, SimRng};
 
     #[test]
     fn deterministic_same_seed() {
@@ -156,6 +367,265 @@ mod rng {
         assert!(!rng.gen_bool(0.0));
         assert!(rng.gen_bool(1.0));
     }
+
+    #[test]
+    fn named_streams_ignore_parent_draws() {
+        let fresh = SimRng::new(42);
+        let mut used = SimRng::new(42);
+        for _ in 0..10 {
+            let _: u64 = used.random();
+        }
+        let mut a = fresh.stream("mobility-noise");
+        let mut b = used.stream("mobility-noise");
+        for _ in 0..20 {
+            assert_eq!(a.random::<u64>(), b.random::<u64>());
+        }
+    }
+
+    #[test]
+    fn named_streams_are_distinct() {
+        let root = SimRng::new(42);
+        let x: u64 = root.stream("mobility-noise").random();
+        assert_ne!(x, root.stream("infection").random::<u64>());
+        assert_ne!(x, SimRng::new(43).stream("mobility-noise").random::<u64>());
+        assert_ne!(x, SimRng::new(42).random::<u64>());
+        // Nested streams derive from the stream's own seed.
+        let nested = root.stream("a").stream("b");
+        assert_eq!(nested.seed(), SimRng::new(root.stream("a").seed()).stream("b").seed());
+        assert_ne!(nested.seed(), root.stream("b").seed());
+    }
+}
+
+#[cfg(all(test, feature = "distributions"))]
+mod distributions {
+    use crate::{AgentId, AgentRng, SimRng};
+
+    const N: usize = 20_000;
+
+    fn mean(samples: impl Iterator<Item = f64>) -> f64 {
+        samples.sum::<f64>() / N as f64
+    }
+
+    #[test]
+    fn normal_and_lognormal_moments() {
+        let mut rng = AgentRng::new(7, AgentId(3));
+        let m = mean((0..N).map(|_| rng.sample_normal(30.0, 5.0)));
+        assert!((m - 30.0).abs() < 0.2, "{m}");
+        // E[exp(N(0, 0.5))] = exp(0.125)
+        let m = mean((0..N).map(|_| rng.sample_lognormal(0.0, 0.5)));
+        assert!((m - 0.125f64.exp()).abs() < 0.02, "{m}");
+        // Invalid spread falls back to the centre.
+        assert_eq!(rng.sample_normal(30.0, -1.0), 30.0);
+        assert_eq!(rng.sample_lognormal(0.0, f64::NAN), 1.0);
+    }
+
+    #[test]
+    fn exponential_mean_and_zero_rate() {
+        let mut rng = SimRng::new(7);
+        let m = mean((0..N).map(|_| rng.sample_exponential(0.5)));
+        assert!((m - 2.0).abs() < 0.1, "{m}");
+        assert_eq!(rng.sample_exponential(0.0), f64::INFINITY);
+        assert_eq!(rng.sample_exponential(-1.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn weighted_proportions_and_invalid_weights() {
+        let mut rng = AgentRng::new(7, AgentId(0));
+        let mut counts = [0usize; 3];
+        for _ in 0..N {
+            counts[rng.sample_weighted(&[1.0, 0.0, 3.0]).unwrap()] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        let share = counts[2] as f64 / N as f64;
+        assert!((share - 0.75).abs() < 0.02, "{share}");
+
+        assert_eq!(rng.sample_weighted(&[]), None);
+        assert_eq!(rng.sample_weighted(&[0.0, 0.0]), None);
+        assert_eq!(rng.sample_weighted(&[1.0, -1.0]), None);
+        assert_eq!(rng.sample_weighted(&[1.0, f32::INFINITY]), None);
+        assert_eq!(rng.sample_weighted(&[f32::MAX, f32::MAX]), None);
+    }
+}
+
+#[cfg(test)]
+mod error {
+    use crate::{DtError, ErrorCategory, NodeId};
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("no route to {0}")]
+    struct RouteError(NodeId);
+
+    #[test]
+    fn core_variants_are_core() {
+        let err = DtError::Config("bad seed".into());
+        assert_eq!(err.category(), ErrorCategory::Core);
+        assert!(err.downcast_ref::<RouteError>().is_none());
+    }
+
+    #[test]
+    fn subsystem_errors_keep_category_and_source() {
+        let err = DtError::subsystem(ErrorCategory::Spatial, RouteError(NodeId(4)));
+        assert_eq!(err.category(), ErrorCategory::Spatial);
+        assert_eq!(err.to_string(), "spatial error: no route to NodeId(4)");
+        assert_eq!(err.downcast_ref::<RouteError>().map(|e| e.0), Some(NodeId(4)));
+        assert!(std::error::Error::source(&err).is_some());
+    }
+}
+
+#[cfg(test)]
+mod config {
+    use crate::{DtError, OutputConfig, SimConfig};
+
+    #[test]
+    fn default_sections_match_framework_defaults() {
+        let cfg = SimConfig::default();
+        assert_eq!(cfg.mobility.min_travel_ticks, 1);
+        assert!(!cfg.mobility.path_following);
+        assert!(!cfg.contacts.edge_contacts);
+        assert_eq!(cfg.output, OutputConfig { snapshots: 1, tick_summaries: 1, contacts: 1, trips: 1 });
+        assert_eq!(cfg.routing.walk_speed_mps, 1.4);
+        assert!(cfg.extensions.is_empty());
+    }
+
+    #[test]
+    fn extensions_parse_on_read() {
+        let cfg = SimConfig::default()
+            .with_extension("vaccination_rate", 0.35)
+            .with_extension("region", "north");
+        assert_eq!(cfg.extension("region"), Some("north"));
+        assert_eq!(cfg.extension_as::<f64>("vaccination_rate").unwrap(), Some(0.35));
+        assert_eq!(cfg.extension_as::<u32>("missing").unwrap(), None);
+
+        let err = cfg.extension_as::<u32>("region").unwrap_err();
+        assert!(matches!(&err, DtError::Config(msg) if msg.contains("region")), "{err}");
+    }
+}
+
+#[cfg(test)]
+mod timefmt {
+    use crate::timefmt::{format_datetime, format_duration, parse_datetime, parse_duration};
+    use crate::{SimClock, SimConfig, Tick, TickDuration};
+
+    #[test]
+    fn datetimes_roundtrip_through_utc() {
+        let secs = parse_datetime("2024-03-04T08:00:00+01:00").unwrap();
+        assert_eq!(secs, 1_709_535_600);
+        assert_eq!(format_datetime(secs), "2024-03-04T07:00:00Z");
+        assert_eq!(parse_datetime("2024-03-04T07:00:00.750Z").unwrap(), secs);
+        assert_eq!(parse_datetime("1970-01-01 00:00:00z").unwrap(), 0);
+        assert_eq!(format_datetime(-1), "1969-12-31T23:59:59Z");
+        assert_eq!(parse_datetime("2024-02-29T12:00:00-05:30").unwrap(), 1_709_227_800);
+    }
+
+    #[test]
+    fn invalid_datetimes_rejected() {
+        for s in [
+            "2024-03-04",
+            "2024-03-04T07:00:00",
+            "2023-02-29T00:00:00Z",
+            "2024-13-01T00:00:00Z",
+            "2024-03-04T24:00:00Z",
+            "2024-03-04T07:00:00+0100",
+            "2024-03-04T07:00:00.Z",
+        ] {
+            assert!(parse_datetime(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn durations_parse_with_units() {
+        assert_eq!(parse_duration("30s").unwrap(), 30);
+        assert_eq!(parse_duration("15m").unwrap(), 900);
+        assert_eq!(parse_duration("1h30m").unwrap(), 5_400);
+        assert_eq!(parse_duration("2 days 12 hours").unwrap(), 216_000);
+        assert_eq!(parse_duration("1w").unwrap(), 604_800);
+        assert_eq!(parse_duration("90").unwrap(), 90);
+        for s in ["", "h", "5x", "1mo", "1h30", "99999999999999999999w"] {
+            assert!(parse_duration(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn durations_format_compactly() {
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_duration(45), "45s");
+        assert_eq!(format_duration(5_400), "1h30m");
+        assert_eq!(format_duration(93_600), "1d2h");
+        assert_eq!(parse_duration(&format_duration(987_654)).unwrap(), 987_654);
+    }
+
+    #[test]
+    fn clock_and_config_format_for_logs() {
+        let clock = SimClock::new(1_709_535_600, 900);
+        assert_eq!(clock.format_tick(Tick(4)), "2024-03-04T08:00:00Z");
+        assert_eq!(clock.format_duration(TickDuration(6)), "1h30m");
+
+        let cfg = SimConfig { start_unix_secs: 1_709_535_600, total_ticks: 168, ..Default::default() };
+        assert_eq!(cfg.start_datetime(), "2024-03-04T07:00:00Z");
+        assert_eq!(cfg.format_run_length(), "7d");
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod config_serde {
+    use crate::SimConfig;
+
+    fn parse(json: &str) -> Result<SimConfig, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    #[test]
+    fn human_readable_times_convert() {
+        let cfg = parse(
+            r#"{ "start_unix_secs": "2024-03-04T00:00:00+01:00", "tick_duration_secs": "15m",
+                 "total_ticks": "7d", "seed": 1, "output_interval_ticks": "1h",
+                 "contacts": { "edge_contacts": true } }"#,
+        )
+        .unwrap();
+        assert_eq!(cfg.start_unix_secs, 1_709_506_800);
+        assert_eq!(cfg.tick_duration_secs, 900);
+        assert_eq!(cfg.total_ticks, 7 * 96);
+        assert_eq!(cfg.output_interval_ticks, 4);
+        assert_eq!(cfg.num_threads, None);
+        assert!(cfg.contacts.edge_contacts);
+        assert_eq!(cfg.mobility.min_travel_ticks, 1);
+    }
+
+    #[test]
+    fn numbers_still_accepted_and_written() {
+        let json = r#"{ "start_unix_secs": 0, "tick_duration_secs": 3600, "total_ticks": 24,
+                        "seed": 7, "num_threads": 2, "output_interval_ticks": 6 }"#;
+        let cfg = parse(json).unwrap();
+        assert_eq!((cfg.total_ticks, cfg.seed, cfg.num_threads), (24, 7, Some(2)));
+
+        let again = parse(&serde_json::to_string(&cfg).unwrap()).unwrap();
+        assert_eq!((again.start_unix_secs, again.output_interval_ticks), (0, 6));
+    }
+
+    #[test]
+    fn durations_round_up_to_whole_ticks() {
+        let cfg = parse(
+            r#"{ "start_unix_secs": 0, "tick_duration_secs": "1h", "total_ticks": "90m",
+                 "seed": 0, "output_interval_ticks": 1 }"#,
+        )
+        .unwrap();
+        assert_eq!(cfg.total_ticks, 2);
+    }
+
+    #[test]
+    fn bad_times_are_errors() {
+        let err = parse(
+            r#"{ "start_unix_secs": "next monday", "tick_duration_secs": 3600,
+                 "total_ticks": 1, "seed": 0, "output_interval_ticks": 1 }"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("next monday"), "{err}");
+        assert!(parse(
+            r#"{ "start_unix_secs": 0, "tick_duration_secs": 0, "total_ticks": "1d",
+                 "seed": 0, "output_interval_ticks": 1 }"#,
+        )
+        .is_err());
+    }
 }
 
 #[cfg(test)]
@@ -13,8 +13,27 @@
 //! The default tick duration is 3,600 s (1 simulated hour).  Applications
 //! that need finer resolution set `tick_duration_secs` to a smaller value;
 //! the rest of the framework is agnostic.
+//!
+//! Spans of time are `TickDuration`s, kept distinct from both absolute ticks
+//! and seconds: `Tick + TickDuration` is a `Tick`, and converting seconds
+//! to a duration (or back) always goes through `tick_duration_secs`.
+//!
+//! Recurring ticks (snapshot cadence, intervention schedules) are iterated
+//! with `TickRange`, e.g. `Tick::every(24).between(start, end)` for every
+//! day boundary in `[start, end)`.
+//!
+//! The `+` and `-` operators on `Tick` and `TickDuration` saturate rather
+//! than panic or wrap, so a tick computed from a stale one (say
+//! `ctx.tick - TickDuration(48)` on tick 10) clamps to `Tick::ZERO` in debug
+//! and release builds alike.  The `checked_*` methods report such cases.
 
+use std::collections::BTreeMap;
 use std::fmt;
+use std::str::FromStr;
+
+use crate::config::{ContactConfig, MobilityConfig, OutputConfig, RoutingConfig};
+use crate::timefmt;
+use crate::{DtError, DtResult};
 
 // ── Tick ─────────────────────────────────────────────────────────────────────
 
@@ -23,6 +42,10 @@ use std::fmt;
 /// Stored as `u64` to avoid overflow: at 1 tick/second and 1 s per tick, a
 /// u64 lasts ~585 billion years.  At the default 1 tick/hour it lasts far
 /// longer than any conceivable run.
+///
+/// Arithmetic saturates at `Tick(0)` and `Tick(u64::MAX)`; see
+/// [`checked_sub`][Self::checked_sub] and
+/// [`checked_since`][Self::checked_since] to detect it.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tick(pub u64);
@@ -30,35 +53,145 @@ pub struct Tick(pub u64);
 impl Tick {
     pub const ZERO: Tick = Tick(0);
 
-    /// Return the tick `n` steps after `self`.
+    /// Return the tick `n` steps after `self` (saturating).
     #[inline]
     pub fn offset(self, n: u64) -> Tick {
-        Tick(self.0 + n)
+        Tick(self.0.saturating_add(n))
     }
 
     /// Ticks elapsed from `earlier` to `self`.
     ///
     /// # Panics
-    /// Panics in debug mode if `earlier > self`.
+    /// Panics in debug mode if `earlier > self`; use
+    /// [`saturating_since`][Self::saturating_since] or
+    /// [`checked_since`][Self::checked_since] when the order is not known.
     #[inline]
     pub fn since(self, earlier: Tick) -> u64 {
         self.0 - earlier.0
     }
+
+    /// Ticks elapsed from `earlier` to `self`, or `None` if `earlier` is
+    /// later.
+    #[inline]
+    pub fn checked_since(self, earlier: Tick) -> Option<u64> {
+        self.0.checked_sub(earlier.0)
+    }
+
+    /// Ticks elapsed from `earlier` to `self`, or 0 if `earlier` is later.
+    #[inline]
+    pub fn saturating_since(self, earlier: Tick) -> u64 {
+        self.0.saturating_sub(earlier.0)
+    }
+
+    /// `self + duration`, or `None` past `u64::MAX`.
+    #[inline]
+    pub fn checked_add(self, duration: TickDuration) -> Option<Tick> {
+        self.0.checked_add(duration.0).map(Tick)
+    }
+
+    /// `self - duration`, or `None` before tick 0.
+    #[inline]
+    pub fn checked_sub(self, duration: TickDuration) -> Option<Tick> {
+        self.0.checked_sub(duration.0).map(Tick)
+    }
+
+    /// `self - duration`, or tick 0 if `duration` reaches back further.
+    /// Same as the `-` operator.
+    #[inline]
+    pub fn saturating_sub(self, duration: TickDuration) -> Tick {
+        Tick(self.0.saturating_sub(duration.0))
+    }
+
+    /// Time elapsed from `earlier` to `self`.
+    ///
+    /// # Panics
+    /// Panics in debug mode if `earlier > self`.
+    #[inline]
+    pub fn duration_since(self, earlier: Tick) -> TickDuration {
+        TickDuration(self.0 - earlier.0)
+    }
+
+    /// `true` if `self` is a multiple of `n` ticks.  As for
+    /// [`u64::is_multiple_of`], only `Tick(0)` is a multiple of `0`.
+    #[inline]
+    pub fn is_multiple_of(self, n: u64) -> bool {
+        self.0.is_multiple_of(n)
+    }
+
+    /// The first multiple of `n` at or after `self`.
+    ///
+    /// # Panics
+    /// Panics if `n == 0`.
+    #[inline]
+    pub fn next_multiple_of(self, n: u64) -> Tick {
+        Tick(self.0.next_multiple_of(n))
+    }
+
+    /// The last multiple of `n` at or before `self`.
+    ///
+    /// # Panics
+    /// Panics if `n == 0`.
+    #[inline]
+    pub fn prev_multiple_of(self, n: u64) -> Tick {
+        Tick(self.0 - self.0 % n)
+    }
+
+    /// Every `period`-th tick, counted from tick 0.  Use
+    /// [`Every::between`] to iterate over a window.
+    ///
+    /// # Panics
+    /// Panics if `period == 0`.
+    #[inline]
+    pub fn every(period: u64) -> Every {
+        assert!(period > 0, "period must be > 0");
+        Every { period }
+    }
+
+    /// The ticks from `self` up to (not including) `end`.
+    #[inline]
+    pub fn until(self, end: Tick) -> TickRange {
+        TickRange::new(self, end)
+    }
 }
 
 impl std::ops::Add<u64> for Tick {
     type Output = Tick;
     #[inline]
     fn add(self, rhs: u64) -> Tick {
-        Tick(self.0 + rhs)
+        Tick(self.0.saturating_add(rhs))
     }
 }
 
+/// Ticks from `rhs` to `self`; 0 if `rhs` is later.
 impl std::ops::Sub for Tick {
     type Output = u64;
     #[inline]
     fn sub(self, rhs: Tick) -> u64 {
-        self.0 - rhs.0
+        self.0.saturating_sub(rhs.0)
+    }
+}
+
+impl std::ops::Add<TickDuration> for Tick {
+    type Output = Tick;
+    #[inline]
+    fn add(self, rhs: TickDuration) -> Tick {
+        Tick(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl std::ops::AddAssign<TickDuration> for Tick {
+    #[inline]
+    fn add_assign(&mut self, rhs: TickDuration) {
+        *self = *self + rhs;
+    }
+}
+
+/// The tick `rhs` before `self`; tick 0 if that is before the start.
+impl std::ops::Sub<TickDuration> for Tick {
+    type Output = Tick;
+    #[inline]
+    fn sub(self, rhs: TickDuration) -> Tick {
+        self.saturating_sub(rhs)
     }
 }
 
@@ -68,6 +201,198 @@ impl fmt::Display for Tick {
     }
 }
 
+// ── TickRange ────────────────────────────────────────────────────────────────
+
+/// An iterator over the ticks `start, start + step, …` below `end`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TickRange {
+    next: Tick,
+    end:  Tick,
+    step: u64,
+}
+
+impl TickRange {
+    /// Every tick in `[start, end)`.
+    #[inline]
+    pub fn new(start: Tick, end: Tick) -> Self {
+        Self { next: start, end, step: 1 }
+    }
+
+    /// Advance by `step` ticks instead of one.
+    ///
+    /// # Panics
+    /// Panics if `step == 0`.
+    pub fn step(mut self, step: u64) -> Self {
+        assert!(step > 0, "step must be > 0");
+        self.step = step;
+        self
+    }
+
+    /// The next tick the iterator yields, if any remain.
+    #[inline]
+    pub fn peek(&self) -> Option<Tick> {
+        (self.next < self.end).then_some(self.next)
+    }
+
+    /// `true` if `tick` is one of the ticks still to be yielded.
+    pub fn contains(&self, tick: Tick) -> bool {
+        tick >= self.next && tick < self.end && (tick.0 - self.next.0).is_multiple_of(self.step)
+    }
+}
+
+impl Iterator for TickRange {
+    type Item = Tick;
+
+    #[inline]
+    fn next(&mut self) -> Option<Tick> {
+        let tick = self.peek()?;
+        // Saturate so a range ending near `u64::MAX` terminates.
+        self.next = Tick(tick.0.saturating_add(self.step));
+        Some(tick)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = if self.next < self.end {
+            (self.end.0 - self.next.0).div_ceil(self.step)
+        } else {
+            0
+        };
+        let n = usize::try_from(n).ok();
+        (n.unwrap_or(usize::MAX), n)
+    }
+}
+
+impl std::iter::FusedIterator for TickRange {}
+
+/// A recurring cadence of every `period`-th tick; see [`Tick::every`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Every {
+    period: u64,
+}
+
+impl Every {
+    pub fn period(self) -> u64 {
+        self.period
+    }
+
+    /// `true` if `tick` falls on the cadence.
+    #[inline]
+    pub fn contains(self, tick: Tick) -> bool {
+        tick.is_multiple_of(self.period)
+    }
+
+    /// The first tick on the cadence at or after `tick`.
+    #[inline]
+    pub fn next_from(self, tick: Tick) -> Tick {
+        tick.next_multiple_of(self.period)
+    }
+
+    /// The ticks on the cadence in `[start, end)`.
+    pub fn between(self, start: Tick, end: Tick) -> TickRange {
+        TickRange::new(self.next_from(start), end).step(self.period)
+    }
+}
+
+// ── TickDuration ─────────────────────────────────────────────────────────────
+
+/// A span of simulation time, counted in ticks.
+///
+/// Unlike a bare `u64`, a `TickDuration` cannot be mistaken for seconds:
+/// [`from_secs`][Self::from_secs] and [`as_secs`][Self::as_secs] take the
+/// tick length explicitly, and [`SimClock`] offers the same conversions for
+/// its own resolution.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TickDuration(pub u64);
+
+impl TickDuration {
+    pub const ZERO: TickDuration = TickDuration(0);
+    pub const ONE: TickDuration = TickDuration(1);
+
+    /// The number of ticks.
+    #[inline]
+    pub fn ticks(self) -> u64 {
+        self.0
+    }
+
+    /// The duration covering `secs` seconds at `tick_duration_secs` seconds
+    /// per tick.  Rounds up, so an agent waiting this long is never early.
+    #[inline]
+    pub fn from_secs(secs: u64, tick_duration_secs: u32) -> TickDuration {
+        TickDuration(secs.div_ceil(tick_duration_secs as u64))
+    }
+
+    /// The duration covering `hours` hours; rounds up like
+    /// [`from_secs`][Self::from_secs].
+    #[inline]
+    pub fn from_hours(hours: u64, tick_duration_secs: u32) -> TickDuration {
+        Self::from_secs(hours * 3_600, tick_duration_secs)
+    }
+
+    /// The duration covering `days` days; rounds up like
+    /// [`from_secs`][Self::from_secs].
+    #[inline]
+    pub fn from_days(days: u64, tick_duration_secs: u32) -> TickDuration {
+        Self::from_secs(days * 86_400, tick_duration_secs)
+    }
+
+    /// Length in seconds at `tick_duration_secs` seconds per tick.
+    #[inline]
+    pub fn as_secs(self, tick_duration_secs: u32) -> u64 {
+        self.0 * tick_duration_secs as u64
+    }
+
+    /// `self - rhs`, or zero if `rhs` is longer.  Same as the `-` operator.
+    #[inline]
+    pub fn saturating_sub(self, rhs: TickDuration) -> TickDuration {
+        TickDuration(self.0.saturating_sub(rhs.0))
+    }
+
+    /// `self - rhs`, or `None` if `rhs` is longer.
+    #[inline]
+    pub fn checked_sub(self, rhs: TickDuration) -> Option<TickDuration> {
+        self.0.checked_sub(rhs.0).map(TickDuration)
+    }
+}
+
+impl std::ops::Add for TickDuration {
+    type Output = TickDuration;
+    #[inline]
+    fn add(self, rhs: TickDuration) -> TickDuration {
+        TickDuration(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl std::ops::AddAssign for TickDuration {
+    #[inline]
+    fn add_assign(&mut self, rhs: TickDuration) {
+        *self = *self + rhs;
+    }
+}
+
+/// `self - rhs`; zero if `rhs` is longer.
+impl std::ops::Sub for TickDuration {
+    type Output = TickDuration;
+    #[inline]
+    fn sub(self, rhs: TickDuration) -> TickDuration {
+        self.saturating_sub(rhs)
+    }
+}
+
+impl std::ops::Mul<u64> for TickDuration {
+    type Output = TickDuration;
+    #[inline]
+    fn mul(self, rhs: u64) -> TickDuration {
+        TickDuration(self.0.saturating_mul(rhs))
+    }
+}
+
+impl fmt::Display for TickDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ticks", self.0)
+    }
+}
+
 // ── SimClock ──────────────────────────────────────────────────────────────────
 
 /// Converts between tick counts and Unix wall-clock seconds.
@@ -139,6 +464,51 @@ impl SimClock {
     pub fn ticks_for_days(&self, days: u64) -> u64 {
         self.ticks_for_secs(days * 86_400)
     }
+
+    // ── Durations ─────────────────────────────────────────────────────────
+
+    /// The duration covering `secs` seconds (rounds up).
+    #[inline]
+    pub fn duration_from_secs(&self, secs: u64) -> TickDuration {
+        TickDuration::from_secs(secs, self.tick_duration_secs)
+    }
+
+    /// The duration covering `hours` hours (rounds up).
+    #[inline]
+    pub fn duration_from_hours(&self, hours: u64) -> TickDuration {
+        TickDuration::from_hours(hours, self.tick_duration_secs)
+    }
+
+    /// The duration covering `days` days (rounds up).
+    #[inline]
+    pub fn duration_from_days(&self, days: u64) -> TickDuration {
+        TickDuration::from_days(days, self.tick_duration_secs)
+    }
+
+    /// Length of `duration` in seconds.
+    #[inline]
+    pub fn duration_secs(&self, duration: TickDuration) -> u64 {
+        duration.as_secs(self.tick_duration_secs)
+    }
+
+    // ── Formatting ────────────────────────────────────────────────────────
+
+    /// Unix timestamp at the start of `tick`.
+    #[inline]
+    pub fn unix_secs_at(&self, tick: Tick) -> i64 {
+        self.start_unix_secs + tick.0 as i64 * self.tick_duration_secs as i64
+    }
+
+    /// The UTC RFC 3339 datetime at the start of `tick`, for logs, e.g.
+    /// `"2024-03-04T07:00:00Z"`.
+    pub fn format_tick(&self, tick: Tick) -> String {
+        timefmt::format_datetime(self.unix_secs_at(tick))
+    }
+
+    /// `duration` as e.g. `"1d2h"` or `"15m"`, for logs.
+    pub fn format_duration(&self, duration: TickDuration) -> String {
+        timefmt::format_duration(self.duration_secs(duration))
+    }
 }
 
 impl fmt::Display for SimClock {
@@ -153,9 +523,18 @@ impl fmt::Display for SimClock {
 /// Top-level simulation configuration.
 ///
 /// Typically loaded from a TOML/JSON file by the application crate and passed
-/// to the simulation runner.
+/// to the simulation runner.  Subsystem options live in the typed sections
+/// (see [`config`][crate::config]) and application settings in
+/// [`extensions`][Self::extensions]; all of them may be omitted.
+///
+/// When deserialized, `start_unix_secs` also accepts an RFC 3339 datetime
+/// (`"2024-03-04T00:00:00+01:00"`), `tick_duration_secs` a duration
+/// (`"15m"`), and `total_ticks` and `output_interval_ticks` a duration that
+/// is converted to ticks, rounding up (`"7d"`, `"1d"`); see
+/// [`timefmt`][crate::timefmt].  Serialization always writes numbers.
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "config_serde::SimConfigRepr"))]
 pub struct SimConfig {
     /// Unix timestamp for tick 0 (e.g. a Monday 00:00 local time).
     pub start_unix_secs: i64,
@@ -176,6 +555,43 @@ pub struct SimConfig {
     /// Write output every N ticks.  1 = every tick; 24 = once per day (at
     /// 1-hour resolution).
     pub output_interval_ticks: u64,
+
+    /// Movement options.
+    pub mobility: MobilityConfig,
+
+    /// Contact detection options.
+    pub contacts: ContactConfig,
+
+    /// Output cadence.
+    pub output: OutputConfig,
+
+    /// Mode speeds for routing.
+    pub routing: RoutingConfig,
+
+    /// Application settings the framework does not interpret, by key.
+    /// Read them with [`extension`][Self::extension] or
+    /// [`extension_as`][Self::extension_as].
+    pub extensions: BTreeMap<String, String>,
+}
+
+impl Default for SimConfig {
+    /// Tick 0 at the Unix epoch, one-hour ticks, no ticks to run, seed 0,
+    /// all cores, output every tick, and default sections.
+    fn default() -> Self {
+        Self {
+            start_unix_secs:       0,
+            tick_duration_secs:    3600,
+            total_ticks:           0,
+            seed:                  0,
+            num_threads:           None,
+            output_interval_ticks: 1,
+            mobility:              MobilityConfig::default(),
+            contacts:              ContactConfig::default(),
+            output:                OutputConfig::default(),
+            routing:               RoutingConfig::default(),
+            extensions:            BTreeMap::new(),
+        }
+    }
 }
 
 impl SimConfig {
@@ -189,4 +605,135 @@ impl SimConfig {
     pub fn make_clock(&self) -> SimClock {
         SimClock::new(self.start_unix_secs, self.tick_duration_secs)
     }
+
+    /// The application setting `key`, if present.
+    pub fn extension(&self, key: &str) -> Option<&str> {
+        self.extensions.get(key).map(String::as_str)
+    }
+
+    /// The application setting `key` parsed as `T`; `Ok(None)` if absent.
+    ///
+    /// # Errors
+    ///
+    /// [`DtError::Config`] if the value does not parse.
+    pub fn extension_as<T>(&self, key: &str) -> DtResult<Option<T>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.extension(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|e| DtError::Config(format!("extension `{key}` = {value:?}: {e}")))
+            })
+            .transpose()
+    }
+
+    /// The start of the run as a UTC RFC 3339 datetime, for logs.
+    pub fn start_datetime(&self) -> String {
+        timefmt::format_datetime(self.start_unix_secs)
+    }
+
+    /// The whole run's length, e.g. `"7d"`, for logs.
+    pub fn format_run_length(&self) -> String {
+        timefmt::format_duration(self.total_ticks.saturating_mul(self.tick_duration_secs as u64))
+    }
+
+    /// Set the application setting `key` to `value`.
+    pub fn with_extension(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.extensions.insert(key.into(), value.to_string());
+        self
+    }
+}
+
+/// Deserialization of `SimConfig` with human-readable times.
+#[cfg(feature = "serde")]
+mod config_serde {
+    use std::collections::BTreeMap;
+
+    use serde::Deserialize;
+
+    use super::SimConfig;
+    use crate::config::{ContactConfig, MobilityConfig, OutputConfig, RoutingConfig};
+    use crate::timefmt;
+    use crate::{DtError, DtResult};
+
+    /// A timestamp: Unix seconds or an RFC 3339 datetime.
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum Instant {
+        Unix(i64),
+        Text(String),
+    }
+
+    /// A count (of seconds or ticks, depending on the field) or a duration.
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum Span {
+        Count(u64),
+        Text(String),
+    }
+
+    #[derive(Deserialize)]
+    pub(super) struct SimConfigRepr {
+        start_unix_secs:       Instant,
+        tick_duration_secs:    Span,
+        total_ticks:           Span,
+        seed:                  u64,
+        num_threads:           Option<usize>,
+        output_interval_ticks: Span,
+        #[serde(default)]
+        mobility:              MobilityConfig,
+        #[serde(default)]
+        contacts:              ContactConfig,
+        #[serde(default)]
+        output:                OutputConfig,
+        #[serde(default)]
+        routing:               RoutingConfig,
+        #[serde(default)]
+        extensions:            BTreeMap<String, String>,
+    }
+
+    /// Ticks of `tick_secs` seconds covering `span` (a count is ticks).
+    fn ticks(field: &str, span: Span, tick_secs: u32) -> DtResult<u64> {
+        match span {
+            Span::Count(ticks) => Ok(ticks),
+            Span::Text(_) if tick_secs == 0 => {
+                Err(DtError::Config(format!("{field}: a duration needs a non-zero tick_duration_secs")))
+            }
+            Span::Text(text) => Ok(timefmt::parse_duration(&text)?.div_ceil(tick_secs as u64)),
+        }
+    }
+
+    impl TryFrom<SimConfigRepr> for SimConfig {
+        type Error = DtError;
+
+        fn try_from(repr: SimConfigRepr) -> DtResult<Self> {
+            let start_unix_secs = match repr.start_unix_secs {
+                Instant::Unix(secs) => secs,
+                Instant::Text(text) => timefmt::parse_datetime(&text)?,
+            };
+            let tick_duration_secs = match repr.tick_duration_secs {
+                Span::Count(secs) => secs,
+                Span::Text(text) => timefmt::parse_duration(&text)?,
+            };
+            let tick_duration_secs = u32::try_from(tick_duration_secs).map_err(|_| {
+                DtError::Config(format!("tick_duration_secs: {tick_duration_secs} s is too long"))
+            })?;
+            Ok(Self {
+                start_unix_secs,
+                tick_duration_secs,
+                total_ticks:           ticks("total_ticks", repr.total_ticks, tick_duration_secs)?,
+                seed:                  repr.seed,
+                num_threads:           repr.num_threads,
+                output_interval_ticks: ticks("output_interval_ticks", repr.output_interval_ticks, tick_duration_secs)?,
+                mobility:              repr.mobility,
+                contacts:              repr.contacts,
+                output:                repr.output,
+                routing:               repr.routing,
+                extensions:            repr.extensions,
+            })
+        }
+    }
 }
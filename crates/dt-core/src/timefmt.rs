@@ -0,0 +1,206 @@
+//! Human-readable times: RFC 3339 datetimes and durations such as `"1h30m"`.
+//!
+//! Used by `SimConfig` deserialization (with `serde`), so a config file can
+//! say `start_unix_secs = "2024-03-04T00:00:00+01:00"` and
+//! `total_ticks = "7d"` instead of hand-computed numbers, and by the
+//! `SimClock` formatting helpers for logs.
+//!
+//! No time zone database is involved: datetimes carry their own UTC offset,
+//! and formatted datetimes are always UTC.
+
+use crate::{DtError, DtResult};
+
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Duration units and their length in seconds, longest spelling first
+/// within each unit so that prefixes do not match early.
+const UNITS: [(&str, u64); 19] = [
+    ("weeks", 604_800), ("week", 604_800), ("w", 604_800),
+    ("days", 86_400), ("day", 86_400), ("d", 86_400),
+    ("hours", 3_600), ("hour", 3_600), ("hrs", 3_600), ("hr", 3_600), ("h", 3_600),
+    ("minutes", 60), ("minute", 60), ("mins", 60), ("min", 60), ("m", 60),
+    ("seconds", 1), ("second", 1), ("s", 1),
+];
+
+// ── Datetimes ─────────────────────────────────────────────────────────────────
+
+/// Days from 1970-01-01 to the proleptic Gregorian date `y-m-d`.
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// The date `(y, m, d)` `days` after 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    (if m <= 2 { yoe + era * 400 + 1 } else { yoe + era * 400 }, m, d)
+}
+
+fn days_in_month(y: i64, m: u32) -> u32 {
+    match m {
+        2 if y % 4 == 0 && (y % 100 != 0 || y % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parse a run of exactly `len` ASCII digits at the start of `s`.
+fn digits(s: &str, len: usize) -> Option<(u32, &str)> {
+    let (head, rest) = (s.get(..len)?, &s[len..]);
+    if !head.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((head.parse().ok()?, rest))
+}
+
+/// Expect the character `c` at the start of `s`.
+fn expect(s: &str, c: char) -> Option<&str> {
+    s.strip_prefix(c)
+}
+
+fn parse_datetime_parts(s: &str) -> Option<i64> {
+    let (year, s) = digits(s, 4)?;
+    let (month, s) = digits(expect(s, '-')?, 2)?;
+    let (day, s) = digits(expect(s, '-')?, 2)?;
+    let s = s.strip_prefix(['T', 't', ' '])?;
+    let (hour, s) = digits(s, 2)?;
+    let (minute, s) = digits(expect(s, ':')?, 2)?;
+    let (second, mut s) = digits(expect(s, ':')?, 2)?;
+    // Fractional seconds are accepted and truncated.
+    if let Some(frac) = s.strip_prefix('.') {
+        let end = frac.find(|c: char| !c.is_ascii_digit()).unwrap_or(frac.len());
+        if end == 0 {
+            return None;
+        }
+        s = &frac[end..];
+    }
+    let offset_secs = match s {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match s.chars().next()? {
+                '+' => 1,
+                '-' => -1,
+                _ => return None,
+            };
+            let (oh, rest) = digits(&s[1..], 2)?;
+            let (om, rest) = digits(expect(rest, ':')?, 2)?;
+            if !rest.is_empty() || oh > 23 || om > 59 {
+                return None;
+            }
+            sign * (oh as i64 * 3_600 + om as i64 * 60)
+        }
+    };
+
+    let year = year as i64;
+    if !(1..=12).contains(&month)
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+    let local = days_from_civil(year, month, day) * SECS_PER_DAY
+        + hour as i64 * 3_600
+        + minute as i64 * 60
+        + second as i64;
+    Some(local - offset_secs)
+}
+
+/// Unix seconds of the RFC 3339 datetime `s`, e.g.
+/// `"2024-03-04T08:00:00+01:00"` or `"2024-03-04T07:00:00Z"`.
+///
+/// The UTC offset is required; fractional seconds are truncated.
+///
+/// # Errors
+///
+/// [`DtError::Parse`] if `s` is not an RFC 3339 datetime.
+pub fn parse_datetime(s: &str) -> DtResult<i64> {
+    parse_datetime_parts(s.trim())
+        .ok_or_else(|| DtError::Parse(format!("invalid RFC 3339 datetime {s:?}")))
+}
+
+/// The UTC RFC 3339 datetime of `unix_secs`, e.g. `"2024-03-04T07:00:00Z"`.
+pub fn format_datetime(unix_secs: i64) -> String {
+    let (days, secs) = (unix_secs.div_euclid(SECS_PER_DAY), unix_secs.rem_euclid(SECS_PER_DAY));
+    let (y, m, d) = civil_from_days(days);
+    format!(
+        "{y:04}-{m:02}-{d:02}T{:02}:{:02}:{:02}Z",
+        secs / 3_600,
+        secs % 3_600 / 60,
+        secs % 60,
+    )
+}
+
+// ── Durations ─────────────────────────────────────────────────────────────────
+
+/// Seconds in the duration `s`: one or more `<number><unit>` terms such as
+/// `"30s"`, `"15m"`, `"1h30m"`, or `"2 days 12 hours"`.  Units are `s`,
+/// `m`/`min`, `h`/`hr`, `d`, and `w` (and their spelled-out forms); a bare
+/// number is seconds.
+///
+/// # Errors
+///
+/// [`DtError::Parse`] if `s` is empty, has an unknown unit, or overflows.
+pub fn parse_duration(s: &str) -> DtResult<u64> {
+    let invalid = || DtError::Parse(format!("invalid duration {s:?}"));
+    let mut rest = s.trim();
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+    if let Ok(secs) = rest.parse::<u64>() {
+        return Ok(secs);
+    }
+
+    let mut total: u64 = 0;
+    while !rest.is_empty() {
+        let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        let value: u64 = rest[..end].parse().map_err(|_| invalid())?;
+        rest = rest[end..].trim_start();
+        let (unit, secs) = UNITS
+            .iter()
+            .find(|(unit, _)| {
+                rest.strip_prefix(unit)
+                    .is_some_and(|after| !after.starts_with(|c: char| c.is_ascii_alphabetic()))
+            })
+            .ok_or_else(invalid)?;
+        rest = rest[unit.len()..].trim_start();
+        total = value
+            .checked_mul(*secs)
+            .and_then(|term| total.checked_add(term))
+            .ok_or_else(invalid)?;
+    }
+    Ok(total)
+}
+
+/// `secs` as days, hours, minutes, and seconds, omitting zero parts, e.g.
+/// `"1d2h"`, `"1h30m"`, `"45s"`; `"0s"` for zero.
+pub fn format_duration(secs: u64) -> String {
+    if secs == 0 {
+        return "0s".to_owned();
+    }
+    let parts = [
+        (secs / 86_400, 'd'),
+        (secs % 86_400 / 3_600, 'h'),
+        (secs % 3_600 / 60, 'm'),
+        (secs % 60, 's'),
+    ];
+    parts
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, unit)| format!("{n}{unit}"))
+        .collect()
+}
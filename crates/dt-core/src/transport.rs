@@ -1,4 +1,5 @@
-//! Transportation mode enum shared across all mobility-related crates.
+//! Transportation mode enum shared across all mobility-related crates, and
+//! the [`TransitVehicle`] id of a scheduled transit run.
 //!
 //! All variants are always compiled in (no per-variant feature flags).
 //! Feature flags in `dt-mobility` control which movement implementations
@@ -47,3 +48,18 @@ impl std::fmt::Display for TransportMode {
         f.write_str(self.as_str())
     }
 }
+
+/// One run of a scheduled transit line: every agent riding the same
+/// `TransitVehicle` at the same time shares a vehicle.
+///
+/// Produced by `dt_spatial::TransitRouter` for the rides of a transit route.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TransitVehicle {
+    /// Index of the line in the router's line list.
+    pub line:         u32,
+    /// When the run left the line's first stop, in milliseconds after the
+    /// midnight that starts tick 0's day (negative for a run that set off
+    /// the day before).
+    pub departure_ms: i64,
+}
@@ -0,0 +1,13 @@
+//! Wall-clock time, for timing phases and runs.
+//!
+//! `std::time::Instant::now` panics on `wasm32-unknown-unknown`, which has
+//! no clock of its own.  There, [`Instant`] is `web_time::Instant`, backed by
+//! the browser's `performance.now()`; on every other target it is
+//! `std::time::Instant`.  Both measure elapsed time as a
+//! `std::time::Duration`.
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub use std::time::Instant;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub use web_time::Instant;
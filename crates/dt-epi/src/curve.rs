@@ -0,0 +1,210 @@
+//! `EpiCurve` — what the epidemic did, tick by tick and case by case.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use dt_core::{AgentId, NodeId, Tick};
+
+use crate::{EpiError, EpiResult};
+
+/// Compartment counts and flows for one tick, taken after the tick's
+/// transmission and progression.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EpiTick {
+    pub tick:           Tick,
+    pub susceptible:    u64,
+    pub exposed:        u64,
+    pub infectious:     u64,
+    pub recovered:      u64,
+    /// Agents isolating at the end of the tick.
+    pub isolated:       u64,
+    /// Infections this tick, seeded ones included.
+    pub new_infections: u64,
+    /// Agents that became infectious this tick.
+    pub new_infectious: u64,
+    pub new_recoveries: u64,
+}
+
+/// One infection: who, when, where, and by whom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Infection {
+    pub tick:   Tick,
+    pub agent:  AgentId,
+    /// `None` for a seeded (imported) infection.
+    pub source: Option<AgentId>,
+    /// Where it happened; `NodeId::INVALID` for a seeded infection.
+    pub node:   NodeId,
+}
+
+/// The course of an epidemic: one [`EpiTick`] per tick run and every
+/// [`Infection`] in order, which together give incidence curves and
+/// reproduction numbers.
+///
+/// Binned methods group ticks into bins of `bin_ticks` (e.g. a day) from
+/// the first tick recorded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EpiCurve {
+    pub ticks:      Vec<EpiTick>,
+    pub infections: Vec<Infection>,
+}
+
+impl EpiCurve {
+    /// Agents ever infected (seeds included), as a share of the population.
+    pub fn attack_rate(&self) -> f64 {
+        let Some(last) = self.ticks.last() else {
+            return 0.0;
+        };
+        let population = last.susceptible + last.exposed + last.infectious + last.recovered;
+        if population == 0 { 0.0 } else { self.infections.len() as f64 / population as f64 }
+    }
+
+    /// The tick with the most agents infectious, and that number.
+    pub fn peak(&self) -> Option<(Tick, u64)> {
+        self.ticks.iter().max_by_key(|t| (t.infectious, std::cmp::Reverse(t.tick))).map(|t| (t.tick, t.infectious))
+    }
+
+    /// New infections per bin, seeds included.
+    pub fn incidence(&self, bin_ticks: u64) -> Vec<u64> {
+        self.binned(bin_ticks, |_| true)
+    }
+
+    /// The case reproduction number per bin: the mean number of agents
+    /// infected by each case infected in the bin.  `None` for bins without
+    /// cases.
+    ///
+    /// Counts every onward infection whenever it happened, so the last bins
+    /// are low until their cases have recovered.
+    pub fn case_reproduction(&self, bin_ticks: u64) -> Vec<Option<f64>> {
+        let (Some(start), bins) = self.bins(bin_ticks) else {
+            return Vec::new();
+        };
+        let bin = |tick: Tick| ((tick.0.saturating_sub(start.0) / bin_ticks.max(1)) as usize).min(bins - 1);
+        let infected_bin: std::collections::HashMap<AgentId, usize> =
+            self.infections.iter().map(|i| (i.agent, bin(i.tick))).collect();
+        let (mut cases, mut onward) = (vec![0u64; bins], vec![0u64; bins]);
+        for infection in &self.infections {
+            cases[bin(infection.tick)] += 1;
+            if let Some(b) = infection.source.and_then(|s| infected_bin.get(&s)) {
+                onward[*b] += 1;
+            }
+        }
+        cases.iter().zip(&onward).map(|(&c, &o)| (c > 0).then(|| o as f64 / c as f64)).collect()
+    }
+
+    /// The distribution of the generation interval, in bins: the share of
+    /// infections that came `s` bins after their source was infected, for
+    /// `s = 0, 1, …`.  Empty if no infection has a source.
+    pub fn generation_interval(&self, bin_ticks: u64) -> Vec<f64> {
+        let (Some(start), _) = self.bins(bin_ticks) else {
+            return Vec::new();
+        };
+        let bin = |tick: Tick| tick.0.saturating_sub(start.0) / bin_ticks.max(1);
+        let infected: std::collections::HashMap<AgentId, u64> =
+            self.infections.iter().map(|i| (i.agent, bin(i.tick))).collect();
+        let mut counts: Vec<u64> = Vec::new();
+        for infection in &self.infections {
+            if let Some(&source_bin) = infection.source.and_then(|s| infected.get(&s)) {
+                let s = bin(infection.tick).saturating_sub(source_bin) as usize;
+                if counts.len() <= s {
+                    counts.resize(s + 1, 0);
+                }
+                counts[s] += 1;
+            }
+        }
+        let total: u64 = counts.iter().sum();
+        counts.iter().map(|&c| c as f64 / total.max(1) as f64).collect()
+    }
+
+    /// The instantaneous reproduction number R_t per bin, estimated from
+    /// incidence as Cori et al. (2013) do, with the generation interval
+    /// measured from the run ([`generation_interval`][Self::generation_interval]).
+    pub fn rt(&self, bin_ticks: u64, window_bins: usize) -> Vec<Option<f64>> {
+        self.rt_with(bin_ticks, window_bins, &self.generation_interval(bin_ticks))
+    }
+
+    /// R_t per bin given the generation-interval distribution `weights`
+    /// (`weights[s]` for a lag of `s` bins):
+    ///
+    /// `R_t = Σ local infections / Σ_b Σ_s weights[s] · infections[b − s]`
+    ///
+    /// over the `window_bins` bins ending at `t`.  Seeded infections count
+    /// as sources but not as transmission.  `None` where nothing infectious
+    /// was circulating.
+    pub fn rt_with(&self, bin_ticks: u64, window_bins: usize, weights: &[f64]) -> Vec<Option<f64>> {
+        let all = self.incidence(bin_ticks);
+        let local = self.binned(bin_ticks, |i| i.source.is_some());
+        let pressure: Vec<f64> = (0..all.len())
+            .map(|b| weights.iter().enumerate().take(b + 1).map(|(s, w)| w * all[b - s] as f64).sum())
+            .collect();
+        (0..all.len())
+            .map(|b| {
+                let from = (b + 1).saturating_sub(window_bins.max(1));
+                let infections: u64 = local[from..=b].iter().sum();
+                let pressure: f64 = pressure[from..=b].iter().sum();
+                (pressure > 0.0).then(|| infections as f64 / pressure)
+            })
+            .collect()
+    }
+
+    /// One row per tick.
+    pub fn to_csv(&self) -> String {
+        let mut text = String::from(
+            "tick,susceptible,exposed,infectious,recovered,isolated,new_infections,new_infectious,new_recoveries\n",
+        );
+        for t in &self.ticks {
+            let _ = writeln!(
+                text,
+                "{},{},{},{},{},{},{},{},{}",
+                t.tick.0, t.susceptible, t.exposed, t.infectious, t.recovered, t.isolated, t.new_infections,
+                t.new_infectious, t.new_recoveries,
+            );
+        }
+        text
+    }
+
+    /// One row per infection; `source` and `node` are empty for seeds.
+    pub fn infections_csv(&self) -> String {
+        let mut text = String::from("tick,agent,source,node\n");
+        for i in &self.infections {
+            let source = i.source.map_or(String::new(), |s| s.0.to_string());
+            let node = if i.node == NodeId::INVALID { String::new() } else { i.node.0.to_string() };
+            let _ = writeln!(text, "{},{},{source},{node}", i.tick.0, i.agent.0);
+        }
+        text
+    }
+
+    pub fn write_csv(&self, path: &Path) -> EpiResult<()> {
+        write(path, &self.to_csv())
+    }
+
+    pub fn write_infections_csv(&self, path: &Path) -> EpiResult<()> {
+        write(path, &self.infections_csv())
+    }
+
+    /// The first tick and the number of bins covering the recorded ticks.
+    fn bins(&self, bin_ticks: u64) -> (Option<Tick>, usize) {
+        match (self.ticks.first(), self.ticks.last()) {
+            (Some(first), Some(last)) => {
+                (Some(first.tick), ((last.tick.0 - first.tick.0) / bin_ticks.max(1)) as usize + 1)
+            }
+            _ => (None, 0),
+        }
+    }
+
+    /// Infections matching `keep`, counted per bin.
+    fn binned(&self, bin_ticks: u64, keep: impl Fn(&Infection) -> bool) -> Vec<u64> {
+        let (Some(start), bins) = self.bins(bin_ticks) else {
+            return Vec::new();
+        };
+        let mut counts = vec![0u64; bins];
+        for infection in self.infections.iter().filter(|i| keep(i)) {
+            let b = (infection.tick.0.saturating_sub(start.0) / bin_ticks.max(1)) as usize;
+            counts[b.min(bins - 1)] += 1;
+        }
+        counts
+    }
+}
+
+fn write(path: &Path, text: &str) -> EpiResult<()> {
+    std::fs::write(path, text).map_err(|source| EpiError::Io { path: path.to_path_buf(), source })
+}
@@ -0,0 +1,125 @@
+//! Disease states and the natural history of an infection.
+
+use dt_core::{AgentId, SimRng, Tick, TickDuration};
+
+// ── DiseaseState ──────────────────────────────────────────────────────────────
+
+/// Compartment of an agent in an S(E)IR model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DiseaseState {
+    #[default]
+    Susceptible,
+    /// Infected but not yet infectious.
+    Exposed,
+    Infectious,
+    /// Immune for the rest of the run.
+    Recovered,
+}
+
+impl DiseaseState {
+    /// Lower-case label, for CSV columns and logs.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DiseaseState::Susceptible => "susceptible",
+            DiseaseState::Exposed     => "exposed",
+            DiseaseState::Infectious  => "infectious",
+            DiseaseState::Recovered   => "recovered",
+        }
+    }
+}
+
+// ── EpiState ──────────────────────────────────────────────────────────────────
+
+/// One agent's infection, stored as an [`AgentStore`][dt_agent::AgentStore]
+/// component.
+///
+/// Written by the epidemic's tick phase before the behavior callbacks run;
+/// behaviors may read it (`ctx.agents.component::<EpiState>()`), e.g. to
+/// keep symptomatic agents at home.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EpiState {
+    pub state:    DiseaseState,
+    /// Tick the agent entered `state`.
+    pub since:    Tick,
+    /// Tick the agent leaves `state`; set while exposed or infectious.
+    pub until:    Option<Tick>,
+    /// The agent that infected this one; `None` for seeded infections.
+    pub source:   Option<AgentId>,
+    /// Isolating: the agent neither travels nor meets anyone.
+    pub isolated: bool,
+}
+
+impl EpiState {
+    pub fn is_infectious(&self) -> bool {
+        self.state == DiseaseState::Infectious
+    }
+}
+
+// ── Period ────────────────────────────────────────────────────────────────────
+
+/// How long a stage of the infection lasts.  Always at least one tick.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Period {
+    Fixed { secs: u64 },
+    /// Exponentially distributed, as in a compartmental ODE model.
+    Exponential { mean_secs: u64 },
+}
+
+impl Period {
+    /// A fixed period of `hours` hours.
+    pub fn hours(hours: u64) -> Self {
+        Period::Fixed { secs: hours * 3600 }
+    }
+
+    /// A fixed period of `days` days.
+    pub fn days(days: u64) -> Self {
+        Period::Fixed { secs: days * 86_400 }
+    }
+
+    fn mean_secs(&self) -> u64 {
+        match *self {
+            Period::Fixed { secs } => secs,
+            Period::Exponential { mean_secs } => mean_secs,
+        }
+    }
+
+    pub(crate) fn sample(&self, rng: &mut SimRng, tick_duration_secs: u32) -> TickDuration {
+        let secs = match *self {
+            Period::Fixed { secs } => secs,
+            Period::Exponential { mean_secs } => {
+                let u: f64 = rng.random();
+                (-(mean_secs as f64) * (1.0 - u).ln()).round() as u64
+            }
+        };
+        TickDuration::from_secs(secs, tick_duration_secs).max(TickDuration::ONE)
+    }
+}
+
+// ── Disease ───────────────────────────────────────────────────────────────────
+
+/// The stages an infected agent goes through.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Disease {
+    /// Time from infection to becoming infectious; `None` for an SIR model,
+    /// where agents are infectious at once.
+    pub latent:     Option<Period>,
+    /// Time from becoming infectious to recovery.
+    pub infectious: Period,
+}
+
+impl Disease {
+    pub fn sir(infectious: Period) -> Self {
+        Self { latent: None, infectious }
+    }
+
+    pub fn seir(latent: Period, infectious: Period) -> Self {
+        Self { latent: Some(latent), infectious }
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.infectious.mean_secs() == 0 {
+            return Err("the infectious period must be positive".into());
+        }
+        Ok(())
+    }
+}
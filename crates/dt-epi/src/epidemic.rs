@@ -0,0 +1,337 @@
+//! `Epidemic` — installs the disease model into a sim as tick phases.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use dt_behavior::{BehaviorModel, Intent};
+use dt_core::{AgentId, NodeId, SimRng, Tick};
+use dt_sim::{PhaseContext, PhasePoint, SimBuilder, TickPhase};
+use dt_spatial::Router;
+
+use crate::{
+    Disease, DiseaseState, EpiCurve, EpiError, EpiResult, EpiState, EpiTick, Infection, Isolation, Transmission,
+    VenueClosure,
+};
+
+// ── Epidemic ──────────────────────────────────────────────────────────────────
+
+/// An epidemic to run alongside a sim's behaviors.
+///
+/// [`install`][Self::install] adds two tick phases to a [`SimBuilder`]:
+///
+/// - at `BeforeIntents`, after arrivals, the *disease step* moves agents
+///   through their [`Disease`] stages, starts and ends isolation, and
+///   infects susceptible agents sharing a node with infectious ones (see
+///   [`Transmission`]) — so behaviors see each agent's current state;
+/// - at `AfterIntents`, *containment* drops the `TravelTo` intents of
+///   isolating agents and of trips to closed venues.
+///
+/// Agents' states live in the [`EpiState`] component, registered on the
+/// store on the first tick if missing; the course of the epidemic is
+/// collected in the [`EpiCurve`] behind the returned [`EpiHandle`].
+///
+/// Randomness comes from one stream derived from `config.seed`, used in
+/// agent order, so runs are reproducible.
+#[derive(Debug, Clone)]
+pub struct Epidemic {
+    disease:      Disease,
+    transmission: Transmission,
+    seeds:        Vec<AgentId>,
+    isolation:    Option<Isolation>,
+    closures:     Vec<VenueClosure>,
+}
+
+impl Epidemic {
+    pub fn new(disease: Disease, transmission: Transmission) -> Self {
+        Self { disease, transmission, seeds: Vec::new(), isolation: None, closures: Vec::new() }
+    }
+
+    /// Make `agents` infectious on the first tick.
+    pub fn seed(mut self, agents: impl IntoIterator<Item = AgentId>) -> Self {
+        self.seeds.extend(agents);
+        self
+    }
+
+    /// Isolate cases (see [`Isolation`]).
+    pub fn isolation(mut self, isolation: Isolation) -> Self {
+        self.isolation = Some(isolation);
+        self
+    }
+
+    /// Close venues for a while (see [`VenueClosure`]).  May be called
+    /// more than once.
+    pub fn close_venues(mut self, closure: VenueClosure) -> Self {
+        self.closures.push(closure);
+        self
+    }
+
+    /// Add the epidemic's phases to `builder`.  Returns the builder and a
+    /// handle on the epidemic curve.
+    pub fn install<B: BehaviorModel, R: Router>(
+        self,
+        builder: SimBuilder<B, R>,
+    ) -> EpiResult<(SimBuilder<B, R>, EpiHandle)> {
+        self.disease.validate().map_err(EpiError::Config)?;
+        self.transmission.validate().map_err(EpiError::Config)?;
+        if let Some(isolation) = &self.isolation {
+            isolation.validate().map_err(EpiError::Config)?;
+        }
+        for closure in &self.closures {
+            closure.validate().map_err(EpiError::Config)?;
+        }
+
+        let handle = EpiHandle::default();
+        let containment = Containment { closures: self.closures.clone() };
+        let step = DiseaseStep {
+            epidemic: self,
+            curve:    Arc::clone(&handle.curve),
+            state:    None,
+        };
+        let builder = builder.phase(PhasePoint::BeforeIntents, step).phase(PhasePoint::AfterIntents, containment);
+        Ok((builder, handle))
+    }
+}
+
+// ── EpiHandle ─────────────────────────────────────────────────────────────────
+
+/// Shared view of an installed epidemic's [`EpiCurve`], updated every tick.
+#[derive(Debug, Clone, Default)]
+pub struct EpiHandle {
+    curve: Arc<Mutex<EpiCurve>>,
+}
+
+impl EpiHandle {
+    /// A copy of the curve so far.
+    pub fn curve(&self) -> EpiCurve {
+        self.curve.lock().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    /// The latest tick's counts.
+    pub fn latest(&self) -> Option<EpiTick> {
+        self.curve.lock().ok()?.ticks.last().copied()
+    }
+}
+
+// ── Containment ───────────────────────────────────────────────────────────────
+
+/// Drops the trips isolation and venue closures forbid.
+struct Containment {
+    closures: Vec<VenueClosure>,
+}
+
+impl TickPhase for Containment {
+    fn name(&self) -> &str {
+        "epidemic containment"
+    }
+
+    fn run(&mut self, ctx: &mut PhaseContext<'_>) -> Result<(), String> {
+        let closed = closed_nodes(&self.closures, ctx.tick, ctx.config.tick_duration_secs);
+        let states = ctx.agents.component::<EpiState>();
+        for (agent, intents) in ctx.intents.iter_mut() {
+            let isolated = states.is_some_and(|s| s[agent.index()].isolated);
+            intents.retain(|intent| match intent {
+                Intent::TravelTo { destination, .. } => !isolated && !closed.contains(destination),
+                _ => true,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The nodes closed at `tick`.
+fn closed_nodes(closures: &[VenueClosure], tick: Tick, tick_duration_secs: u32) -> HashSet<NodeId> {
+    closures
+        .iter()
+        .filter(|c| {
+            let (start, end) = c.window(tick_duration_secs);
+            tick >= start && end.is_none_or(|end| tick < end)
+        })
+        .flat_map(|c| c.nodes.iter().copied())
+        .collect()
+}
+
+// ── DiseaseStep ───────────────────────────────────────────────────────────────
+
+/// Progression, isolation, and transmission, once per tick.
+struct DiseaseStep {
+    epidemic: Epidemic,
+    curve:    Arc<Mutex<EpiCurve>>,
+    /// Created on the first tick, when the population size is known.
+    state:    Option<StepState>,
+}
+
+struct StepState {
+    rng:             SimRng,
+    /// Node each agent was at after the previous tick (`INVALID` if
+    /// travelling), and the tick it got there.
+    node:            Vec<NodeId>,
+    arrived:         Vec<Tick>,
+    /// When each infectious agent that complies will start isolating.
+    isolate_from:    Vec<Option<Tick>>,
+    isolated_until:  Vec<Option<Tick>>,
+}
+
+impl TickPhase for DiseaseStep {
+    fn name(&self) -> &str {
+        "epidemic"
+    }
+
+    fn run(&mut self, ctx: &mut PhaseContext<'_>) -> Result<(), String> {
+        let now = ctx.tick;
+        let count = ctx.agents.count;
+        let tick_secs = ctx.config.tick_duration_secs;
+        let epidemic = &self.epidemic;
+        let mut row = EpiTick { tick: now, ..Default::default() };
+        let mut infections = Vec::new();
+
+        if !ctx.agents.components().contains::<EpiState>() {
+            ctx.agents.components_mut().register::<EpiState>(count);
+        }
+        let states = ctx.agents.component_mut::<EpiState>().ok_or("EpiState component missing")?;
+        let st = match &mut self.state {
+            Some(st) => st,
+            None => {
+                let mut rng = SimRng::new(ctx.config.seed).stream("dt-epi");
+                let mut isolate_from = vec![None; count];
+                for &agent in &epidemic.seeds {
+                    let s = states.get_mut(agent.index()).ok_or_else(|| format!("seed {agent} out of range"))?;
+                    *s = infectious(epidemic, now, None, &mut rng, tick_secs);
+                    isolate_from[agent.index()] = isolation_start(epidemic.isolation, now, &mut rng, tick_secs);
+                    infections.push(Infection { tick: now, agent, source: None, node: NodeId::INVALID });
+                }
+                self.state.insert(StepState {
+                    rng,
+                    node: vec![NodeId::INVALID; count],
+                    arrived: vec![now; count],
+                    isolate_from,
+                    isolated_until: vec![None; count],
+                })
+            }
+        };
+
+        // ── Progression and isolation ─────────────────────────────────────
+        for (i, s) in states.iter_mut().enumerate() {
+            if s.until.is_some_and(|until| until <= now) {
+                match s.state {
+                    DiseaseState::Exposed => {
+                        *s = infectious(epidemic, now, s.source, &mut st.rng, tick_secs);
+                        st.isolate_from[i] = isolation_start(epidemic.isolation, now, &mut st.rng, tick_secs);
+                        row.new_infectious += 1;
+                    }
+                    DiseaseState::Infectious => {
+                        let source = s.source;
+                        *s = EpiState { state: DiseaseState::Recovered, since: now, source, ..Default::default() };
+                        st.isolate_from[i] = None;
+                        st.isolated_until[i] = None;
+                        row.new_recoveries += 1;
+                    }
+                    _ => {}
+                }
+            }
+            if st.isolate_from[i].is_some_and(|from| from <= now) {
+                st.isolate_from[i] = None;
+                s.isolated = true;
+                st.isolated_until[i] = epidemic
+                    .isolation
+                    .and_then(|iso| iso.duration_secs)
+                    .map(|secs| now + dt_core::TickDuration::from_secs(secs, tick_secs));
+            }
+            if s.isolated && st.isolated_until[i].is_some_and(|until| until <= now) {
+                s.isolated = false;
+                st.isolated_until[i] = None;
+            }
+        }
+
+        // ── Who is where ──────────────────────────────────────────────────
+        let closed = closed_nodes(&epidemic.closures, now, tick_secs);
+        let mut present: Vec<NodeId> = vec![NodeId::INVALID; count];
+        for (i, m) in ctx.mobility.states.iter().enumerate().take(count) {
+            let node = if m.in_transit { NodeId::INVALID } else { m.departure_node };
+            if node != st.node[i] {
+                st.node[i] = node;
+                st.arrived[i] = now;
+            }
+            if node != NodeId::INVALID && !states[i].isolated && !closed.contains(&node) {
+                present[i] = node;
+            }
+        }
+
+        // ── Transmission ──────────────────────────────────────────────────
+        let mut sources: HashMap<NodeId, Vec<AgentId>> = HashMap::new();
+        for (i, s) in states.iter().enumerate() {
+            if s.is_infectious() && present[i] != NodeId::INVALID {
+                sources.entry(present[i]).or_default().push(AgentId(i as u32));
+            }
+        }
+        let new_only = epidemic.transmission.counts_new_contacts_only();
+        let mut exposures = Vec::new();
+        for i in 0..count {
+            if states[i].state != DiseaseState::Susceptible {
+                continue;
+            }
+            let Some(infectious_here) = sources.get(&present[i]) else {
+                continue;
+            };
+            exposures.clear();
+            if new_only && st.arrived[i] != now {
+                exposures.extend(infectious_here.iter().filter(|a| st.arrived[a.index()] == now));
+            } else {
+                exposures.extend_from_slice(infectious_here);
+            }
+            let p = epidemic.transmission.probability(exposures.len(), tick_secs);
+            if p > 0.0 && st.rng.gen_bool(p) {
+                let source = exposures[st.rng.gen_range(0..exposures.len())];
+                let agent = AgentId(i as u32);
+                states[i] = match epidemic.disease.latent {
+                    Some(latent) => EpiState {
+                        state:    DiseaseState::Exposed,
+                        since:    now,
+                        until:    Some(now + latent.sample(&mut st.rng, tick_secs)),
+                        source:   Some(source),
+                        isolated: false,
+                    },
+                    None => {
+                        st.isolate_from[i] = isolation_start(epidemic.isolation, now, &mut st.rng, tick_secs);
+                        row.new_infectious += 1;
+                        infectious(epidemic, now, Some(source), &mut st.rng, tick_secs)
+                    }
+                };
+                infections.push(Infection { tick: now, agent, source: Some(source), node: present[i] });
+            }
+        }
+
+        // ── Counts ────────────────────────────────────────────────────────
+        for s in states.iter() {
+            match s.state {
+                DiseaseState::Susceptible => row.susceptible += 1,
+                DiseaseState::Exposed     => row.exposed += 1,
+                DiseaseState::Infectious  => row.infectious += 1,
+                DiseaseState::Recovered   => row.recovered += 1,
+            }
+            row.isolated += s.isolated as u64;
+        }
+        row.new_infections = infections.len() as u64;
+        let mut curve = self.curve.lock().map_err(|_| "epidemic curve lock poisoned")?;
+        curve.ticks.push(row);
+        curve.infections.extend(infections);
+        Ok(())
+    }
+}
+
+/// A newly infectious agent's state.
+fn infectious(epidemic: &Epidemic, now: Tick, source: Option<AgentId>, rng: &mut SimRng, tick_secs: u32) -> EpiState {
+    EpiState {
+        state:    DiseaseState::Infectious,
+        since:    now,
+        until:    Some(now + epidemic.disease.infectious.sample(rng, tick_secs)),
+        source,
+        isolated: false,
+    }
+}
+
+/// When a newly infectious agent will isolate, if it complies.
+fn isolation_start(isolation: Option<Isolation>, now: Tick, rng: &mut SimRng, tick_secs: u32) -> Option<Tick> {
+    let isolation = isolation?;
+    rng.gen_bool(isolation.compliance)
+        .then(|| now + dt_core::TickDuration::from_secs(isolation.delay_secs, tick_secs))
+}
@@ -0,0 +1,26 @@
+//! Error types for dt-epi.
+
+use std::path::PathBuf;
+
+use dt_core::{DtError, ErrorCategory};
+use thiserror::Error;
+
+/// Errors that can occur when setting up an epidemic or writing its output.
+#[derive(Debug, Error)]
+pub enum EpiError {
+    /// A disease, transmission, or intervention parameter is out of range.
+    #[error("epidemic: {0}")]
+    Config(String),
+
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+}
+
+/// Alias for `Result<T, EpiError>`.
+pub type EpiResult<T> = Result<T, EpiError>;
+
+impl From<EpiError> for DtError {
+    fn from(err: EpiError) -> Self {
+        DtError::subsystem(ErrorCategory::Sim, err)
+    }
+}
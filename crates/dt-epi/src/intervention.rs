@@ -0,0 +1,79 @@
+//! Non-pharmaceutical interventions: isolation of cases and venue closures.
+//!
+//! Both act by dropping `TravelTo` intents before they are applied, and by
+//! taking the affected agents out of transmission.  Times are seconds after
+//! the start of the run, rounded up to a tick, as in dt-cli's
+//! `[[interventions]]`.
+
+use dt_core::{NodeId, Tick, TickDuration};
+
+/// Infectious agents isolate `delay_secs` after becoming infectious (e.g.
+/// at symptom onset), with probability `compliance`, until they recover or
+/// `duration_secs` has passed.
+///
+/// An isolating agent stays where it is — home, if its plans have it there
+/// at the time — and has no contacts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Isolation {
+    pub delay_secs:    u64,
+    pub compliance:    f64,
+    /// `None`: until recovery.
+    pub duration_secs: Option<u64>,
+}
+
+impl Isolation {
+    pub fn new(delay_secs: u64, compliance: f64) -> Self {
+        Self { delay_secs, compliance, duration_secs: None }
+    }
+
+    /// End isolation after `secs` even if still infectious.
+    pub fn duration(mut self, secs: u64) -> Self {
+        self.duration_secs = Some(secs);
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.compliance) {
+            return Err("isolation compliance must be between 0 and 1".into());
+        }
+        Ok(())
+    }
+}
+
+/// Close `nodes` (schools, offices, venues) from `at_secs` until
+/// `until_secs`.
+///
+/// While closed, trips to the nodes are cancelled, and agents still inside
+/// — they may leave — meet no one there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VenueClosure {
+    pub nodes:      Vec<NodeId>,
+    pub at_secs:    u64,
+    /// `None`: for the rest of the run.
+    pub until_secs: Option<u64>,
+}
+
+impl VenueClosure {
+    pub fn new(nodes: impl IntoIterator<Item = NodeId>, at_secs: u64) -> Self {
+        Self { nodes: nodes.into_iter().collect(), at_secs, until_secs: None }
+    }
+
+    /// Reopen at `secs` after the start of the run.
+    pub fn until(mut self, secs: u64) -> Self {
+        self.until_secs = Some(secs);
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.until_secs.is_some_and(|until| until <= self.at_secs) {
+            return Err("venue closure must end after it starts".into());
+        }
+        Ok(())
+    }
+
+    /// The closure's `[start, end)` in ticks.
+    pub(crate) fn window(&self, tick_duration_secs: u32) -> (Tick, Option<Tick>) {
+        let tick = |secs: u64| Tick::ZERO + TickDuration::from_secs(secs, tick_duration_secs);
+        (tick(self.at_secs), self.until_secs.map(tick))
+    }
+}
@@ -0,0 +1,64 @@
+//! `dt-epi` — epidemics spreading through a rust_dt population.
+//!
+//! Agents move as their behaviors and plans say; an [`Epidemic`] adds the
+//! disease on top.  Each tick, susceptible agents parked at the same node
+//! as infectious ones may be infected — per new encounter or in proportion
+//! to the time spent together (see [`Transmission`]) — and infected agents
+//! move through the stages of a [`Disease`] (SIR or SEIR).  Cases can be
+//! isolated ([`Isolation`]) and venues closed for a time
+//! ([`VenueClosure`]); both cancel trips before they start.
+//!
+//! ```rust,ignore
+//! use dt_epi::{Disease, Epidemic, Isolation, Period, Transmission, VenueClosure};
+//!
+//! let epidemic = Epidemic::new(
+//!     Disease::seir(Period::days(3), Period::Exponential { mean_secs: 5 * 86_400 }),
+//!     Transmission::Duration { rate_per_hour: 0.02 },
+//! )
+//! .seed([AgentId(0), AgentId(1)])
+//! .isolation(Isolation::new(2 * 86_400, 0.7))
+//! .close_venues(VenueClosure::new(schools, 14 * 86_400).until(28 * 86_400));
+//!
+//! let (builder, epi) = epidemic.install(SimBuilder::new(config, store, rngs, behavior, router))?;
+//! builder.build()?.run(&mut observer)?;
+//!
+//! let curve = epi.curve();
+//! curve.write_csv("epi.csv".as_ref())?;
+//! let ticks_per_day = 86_400 / u64::from(config.tick_duration_secs);
+//! println!("attack rate {:.1} %", 100.0 * curve.attack_rate());
+//! println!("daily R_t {:?}", curve.rt(ticks_per_day, 7));
+//! ```
+//!
+//! Each agent's state is the [`EpiState`] component, which behaviors can
+//! read to react to their own infection.  The [`EpiCurve`] holds the
+//! compartment counts of every tick and every infection with its source,
+//! from which it derives incidence, the case reproduction number, the
+//! generation interval, and an R_t estimate.
+//!
+//! # Crate layout
+//!
+//! | Module           | Contents                                          |
+//! |------------------|---------------------------------------------------|
+//! | [`epidemic`]     | `Epidemic` (builder and tick phases), `EpiHandle` |
+//! | [`disease`]      | `DiseaseState`, `EpiState`, `Disease`, `Period`   |
+//! | [`transmission`] | `Transmission`                                    |
+//! | [`intervention`] | `Isolation`, `VenueClosure`                       |
+//! | [`curve`]        | `EpiCurve`, `EpiTick`, `Infection`                |
+//! | [`error`]        | `EpiError`, `EpiResult<T>`                        |
+
+pub mod curve;
+pub mod disease;
+pub mod epidemic;
+pub mod error;
+pub mod intervention;
+pub mod transmission;
+
+#[cfg(test)]
+mod tests;
+
+pub use curve::{EpiCurve, EpiTick, Infection};
+pub use disease::{Disease, DiseaseState, EpiState, Period};
+pub use epidemic::{EpiHandle, Epidemic};
+pub use error::{EpiError, EpiResult};
+pub use intervention::{Isolation, VenueClosure};
+pub use transmission::Transmission;
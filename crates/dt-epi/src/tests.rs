@@ -0,0 +1,306 @@
+//! Unit tests for dt-epi.
+
+use dt_agent::AgentStoreBuilder;
+use dt_behavior::{BehaviorModel, Intent, SimContext};
+use dt_core::{AgentId, AgentRng, GeoPoint, NodeId, SimConfig, TransportMode};
+use dt_sim::{Sim, SimBuilder};
+use dt_spatial::{DijkstraRouter, RoadNetworkBuilder};
+
+use crate::{EpiHandle, Epidemic};
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// Woken agents drive to `0`.
+struct GoTo(NodeId);
+
+impl BehaviorModel for GoTo {
+    fn replan(&self, _agent: AgentId, _ctx: &SimContext<'_>, _rng: &mut AgentRng) -> Vec<Intent> {
+        vec![Intent::TravelTo { destination: self.0, mode: TransportMode::Car }]
+    }
+}
+
+fn config(seed: u64) -> SimConfig {
+    SimConfig { start_unix_secs: 0, tick_duration_secs: 60, total_ticks: 60, seed, ..Default::default() }
+}
+
+/// `starts.len()` agents on a two-node network (home 0, venue 1, one
+/// minute apart) heading for node 1 when woken; `woken` wake at tick 0.
+fn sim(
+    epidemic: Epidemic,
+    seed:     u64,
+    starts:   Vec<NodeId>,
+    woken:    &[u32],
+) -> (Sim<GoTo, DijkstraRouter>, EpiHandle) {
+    let mut b = RoadNetworkBuilder::new();
+    let home = b.add_node(GeoPoint { lat: 0.0, lon: 0.0 });
+    let venue = b.add_node(GeoPoint { lat: 0.0, lon: 0.01 });
+    b.add_road(home, venue, 1000.0, 60_000);
+    let config = config(seed);
+    let (store, rngs) = AgentStoreBuilder::new(starts.len(), config.seed).build();
+    let builder = SimBuilder::new(config, store, rngs, GoTo(venue), DijkstraRouter)
+        .network(b.build())
+        .initial_positions(starts);
+    let (builder, handle) = epidemic.install(builder).unwrap();
+    let mut sim = builder.build().unwrap();
+    for &a in woken {
+        sim.wake_queue.push(dt_core::Tick(0), AgentId(a));
+    }
+    (sim, handle)
+}
+
+// ── Disease and transmission ──────────────────────────────────────────────────
+
+#[cfg(test)]
+mod model_tests {
+    use dt_core::{SimRng, TickDuration};
+
+    use super::*;
+    use crate::{Disease, DiseaseState, EpiError, Isolation, Period, Transmission, VenueClosure};
+
+    #[test]
+    fn transmission_probabilities() {
+        let per_contact = Transmission::PerContact { probability: 0.5 };
+        assert_eq!(per_contact.probability(0, 60), 0.0);
+        assert_eq!(per_contact.probability(1, 60), 0.5);
+        assert_eq!(per_contact.probability(2, 60), 0.75);
+
+        let duration = Transmission::Duration { rate_per_hour: 0.6 };
+        assert!((duration.probability(1, 3600) - (1.0 - (-0.6f64).exp())).abs() < 1e-12);
+        // Twice the infectious agents is twice the time together.
+        assert!((duration.probability(2, 1800) - duration.probability(1, 3600)).abs() < 1e-12);
+        assert_eq!(duration.probability(0, 3600), 0.0);
+    }
+
+    #[test]
+    fn periods_last_at_least_a_tick() {
+        let mut rng = SimRng::new(1);
+        assert_eq!(Period::hours(2).sample(&mut rng, 3600), TickDuration(2));
+        assert_eq!(Period::days(1).sample(&mut rng, 3600), TickDuration(24));
+        assert_eq!(Period::Fixed { secs: 10 }.sample(&mut rng, 3600), TickDuration(1));
+        let samples: Vec<u64> =
+            (0..2000).map(|_| Period::Exponential { mean_secs: 36_000 }.sample(&mut rng, 3600).0).collect();
+        let mean = samples.iter().sum::<u64>() as f64 / samples.len() as f64;
+        assert!((9.0..11.0).contains(&mean), "mean {mean}");
+        assert!(samples.iter().all(|&s| s >= 1));
+        assert_eq!(DiseaseState::default().as_str(), "susceptible");
+    }
+
+    #[test]
+    fn install_rejects_bad_parameters() {
+        let disease = Disease::sir(Period::hours(1));
+        let bad = [
+            Epidemic::new(Disease::sir(Period::Fixed { secs: 0 }), Transmission::PerContact { probability: 0.1 }),
+            Epidemic::new(disease, Transmission::PerContact { probability: 1.5 }),
+            Epidemic::new(disease, Transmission::Duration { rate_per_hour: -1.0 }),
+            Epidemic::new(disease, Transmission::Duration { rate_per_hour: 1.0 }).isolation(Isolation::new(0, 2.0)),
+            Epidemic::new(disease, Transmission::Duration { rate_per_hour: 1.0 })
+                .close_venues(VenueClosure::new([NodeId(1)], 600).until(600)),
+        ];
+        for epidemic in bad {
+            let (store, rngs) = AgentStoreBuilder::new(1, 1).build();
+            let builder = SimBuilder::new(config(1), store, rngs, GoTo(NodeId(0)), DijkstraRouter);
+            assert!(matches!(epidemic.install(builder), Err(EpiError::Config(_))));
+        }
+    }
+}
+
+// ── Epidemics ─────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod epidemic_tests {
+    use dt_core::Tick;
+    use dt_sim::NoopObserver;
+
+    use super::*;
+    use crate::{Disease, DiseaseState, EpiState, Isolation, Period, Transmission, VenueClosure};
+
+    /// Infection is all but certain for anyone sharing a node with a case.
+    const CERTAIN: Transmission = Transmission::Duration { rate_per_hour: 1000.0 };
+
+    #[test]
+    fn crowd_is_infected_and_recovers() {
+        let epidemic = Epidemic::new(Disease::sir(Period::Fixed { secs: 600 }), CERTAIN).seed([AgentId(0)]);
+        let (mut sim, epi) = sim(epidemic, 1, vec![NodeId(0); 10], &[]);
+        sim.run_ticks(15, &mut NoopObserver).unwrap();
+
+        let curve = epi.curve();
+        assert_eq!(curve.ticks.len(), 15);
+        assert!(curve.ticks.iter().all(|t| t.susceptible + t.exposed + t.infectious + t.recovered == 10));
+        assert_eq!(curve.ticks[0].new_infections, 10);
+        assert_eq!(curve.ticks[0].infectious, 10);
+        assert_eq!(curve.ticks[10].new_recoveries, 10);
+        assert_eq!(epi.latest().unwrap().recovered, 10);
+        assert_eq!(curve.attack_rate(), 1.0);
+        assert_eq!(curve.peak(), Some((Tick(0), 10)));
+        assert_eq!(curve.infections[0].source, None);
+        assert!(curve.infections[1..].iter().all(|i| i.source == Some(AgentId(0)) && i.node == NodeId(0)));
+
+        let states = sim.agents.component::<EpiState>().unwrap();
+        assert!(states.iter().all(|s| s.state == DiseaseState::Recovered && s.since == Tick(10)));
+    }
+
+    #[test]
+    fn latent_period_delays_infectiousness() {
+        let disease = Disease::seir(Period::Fixed { secs: 300 }, Period::Fixed { secs: 600 });
+        let (mut sim, epi) = sim(Epidemic::new(disease, CERTAIN).seed([AgentId(0)]), 1, vec![NodeId(0); 3], &[]);
+        sim.run_ticks(20, &mut NoopObserver).unwrap();
+
+        let ticks = epi.curve().ticks;
+        assert_eq!((ticks[0].exposed, ticks[0].infectious), (2, 1));
+        assert_eq!((ticks[5].exposed, ticks[5].infectious, ticks[5].new_infectious), (0, 3, 2));
+        assert_eq!(ticks[10].new_recoveries, 1);
+        assert_eq!(ticks[15].recovered, 3);
+    }
+
+    #[test]
+    fn per_contact_counts_only_new_encounters() {
+        let epidemic = Epidemic::new(Disease::sir(Period::hours(1)), Transmission::PerContact { probability: 0.5 })
+            .seed([AgentId(0)]);
+        let (mut sim, epi) = sim(epidemic, 3, vec![NodeId(0); 50], &[]);
+        sim.run_ticks(30, &mut NoopObserver).unwrap();
+
+        // Nobody moves, so everyone meets only on the first tick.
+        let curve = epi.curve();
+        assert!(curve.infections.iter().all(|i| i.tick == Tick(0)));
+        assert!((2..50).contains(&curve.infections.len()), "{}", curve.infections.len());
+    }
+
+    #[test]
+    fn arrivals_meet_the_cases_at_their_destination() {
+        // Agent 0 is infectious at the venue; the others drive there.
+        let epidemic = Epidemic::new(Disease::sir(Period::hours(1)), CERTAIN).seed([AgentId(0)]);
+        let starts = vec![NodeId(1), NodeId(0), NodeId(0)];
+        let (mut sim, epi) = sim(epidemic, 1, starts, &[1, 2]);
+        sim.run_ticks(5, &mut NoopObserver).unwrap();
+
+        let curve = epi.curve();
+        assert_eq!(curve.ticks[0].new_infections, 1);
+        assert_eq!(curve.infections.len(), 3);
+        assert!(curve.infections[1..].iter().all(|i| i.node == NodeId(1) && i.tick == Tick(1)));
+    }
+
+    #[test]
+    fn venue_closure_cancels_trips_and_contacts() {
+        let epidemic = Epidemic::new(Disease::sir(Period::hours(1)), CERTAIN)
+            .seed([AgentId(0)])
+            .close_venues(VenueClosure::new([NodeId(1)], 0).until(3600));
+        let (mut sim, epi) = sim(epidemic, 1, vec![NodeId(1), NodeId(0), NodeId(0)], &[1, 2]);
+        sim.run_ticks(5, &mut NoopObserver).unwrap();
+
+        assert_eq!(epi.curve().infections.len(), 1);
+        assert!(sim.mobility.store.states[1..].iter().all(|s| s.departure_node == NodeId(0) && !s.in_transit));
+    }
+
+    #[test]
+    fn isolated_cases_stay_put_and_infect_no_one() {
+        let epidemic = Epidemic::new(Disease::sir(Period::hours(1)), CERTAIN)
+            .seed([AgentId(0)])
+            .isolation(Isolation::new(0, 1.0).duration(300));
+        let (mut sim, epi) = sim(epidemic, 1, vec![NodeId(0); 4], &[0]);
+        sim.run_ticks(4, &mut NoopObserver).unwrap();
+
+        let curve = epi.curve();
+        assert_eq!(curve.infections.len(), 1);
+        assert!(curve.ticks.iter().all(|t| t.isolated == 1));
+        assert_eq!(sim.mobility.store.states[0].departure_node, NodeId(0));
+        assert!(!sim.mobility.store.states[0].in_transit);
+
+        // Isolation ends after five minutes; the case is still infectious.
+        sim.run_ticks(2, &mut NoopObserver).unwrap();
+        assert_eq!(epi.latest().unwrap().isolated, 0);
+        assert_eq!(epi.curve().infections.len(), 4);
+    }
+
+    #[test]
+    fn runs_are_reproducible() {
+        let run = |seed| {
+            let epidemic = Epidemic::new(
+                Disease::seir(Period::Exponential { mean_secs: 600 }, Period::Exponential { mean_secs: 1200 }),
+                Transmission::Duration { rate_per_hour: 3.0 },
+            )
+            .seed([AgentId(0), AgentId(1)])
+            .isolation(Isolation::new(300, 0.5));
+            let (mut sim, epi) = sim(epidemic, seed, vec![NodeId(0); 40], &[]);
+            sim.run_ticks(60, &mut NoopObserver).unwrap();
+            epi.curve()
+        };
+        let curve = run(5);
+        assert!(curve.infections.len() > 2);
+        assert_eq!(curve, run(5));
+        assert_ne!(curve, run(6));
+    }
+}
+
+// ── Curves ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod curve_tests {
+    use dt_core::Tick;
+
+    use super::*;
+    use crate::{EpiCurve, EpiTick, Infection};
+
+    fn infection(tick: u64, agent: u32, source: Option<u32>) -> Infection {
+        let node = if source.is_some() { NodeId(4) } else { NodeId::INVALID };
+        Infection { tick: Tick(tick), agent: AgentId(agent), source: source.map(AgentId), node }
+    }
+
+    /// Agent 0 seeded at tick 0 infects 1 and 2 in the second bin (ticks
+    /// 10–19); 1 infects 3 in the third.  Ten agents, 30 ticks.
+    fn curve() -> EpiCurve {
+        EpiCurve {
+            ticks:      (0..30)
+                .map(|t| EpiTick { tick: Tick(t), susceptible: 10 - t % 7, infectious: t % 7, ..Default::default() })
+                .collect(),
+            infections: vec![
+                infection(0, 0, None),
+                infection(12, 1, Some(0)),
+                infection(15, 2, Some(0)),
+                infection(25, 3, Some(1)),
+            ],
+        }
+    }
+
+    #[test]
+    fn incidence_and_reproduction() {
+        let curve = curve();
+        assert_eq!(curve.incidence(10), [1, 2, 1]);
+        assert_eq!(curve.incidence(30), [4]);
+        assert_eq!(curve.case_reproduction(10), [Some(2.0), Some(0.5), Some(0.0)]);
+        assert_eq!(curve.generation_interval(10), [0.0, 1.0]);
+        assert_eq!(curve.attack_rate(), 0.4);
+        assert_eq!(curve.peak(), Some((Tick(6), 6)));
+        assert_eq!(EpiCurve::default().peak(), None);
+        assert_eq!(EpiCurve::default().incidence(10), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn rt_from_incidence() {
+        let curve = curve();
+        // Every infection one bin after its source: R = local / previous bin.
+        assert_eq!(curve.rt_with(10, 1, &[0.0, 1.0]), [None, Some(2.0), Some(0.5)]);
+        assert_eq!(curve.rt_with(10, 2, &[0.0, 1.0]), [None, Some(2.0), Some(1.0)]);
+        assert_eq!(curve.rt(10, 1), curve.rt_with(10, 1, &[0.0, 1.0]));
+        // Same-bin transmission counts against the bin itself.
+        assert_eq!(curve.rt_with(10, 1, &[0.5, 0.5]), [Some(0.0), Some(2.0 / 1.5), Some(1.0 / 1.5)]);
+    }
+
+    #[test]
+    fn csv_output() {
+        let mut curve = curve();
+        curve.ticks.truncate(1);
+        assert_eq!(
+            curve.to_csv(),
+            "tick,susceptible,exposed,infectious,recovered,isolated,new_infections,new_infectious,new_recoveries\n\
+             0,10,0,0,0,0,0,0,0\n",
+        );
+        let infections = curve.infections_csv();
+        assert_eq!(infections.lines().take(3).collect::<Vec<_>>(), ["tick,agent,source,node", "0,0,,", "12,1,0,4"]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("epi.csv");
+        curve.write_csv(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), curve.to_csv());
+        assert!(curve.write_infections_csv(&dir.path().join("missing/infections.csv")).is_err());
+    }
+}
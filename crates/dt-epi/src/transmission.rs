@@ -0,0 +1,54 @@
+//! How infection passes between agents at the same node.
+
+/// The chance that a susceptible agent sharing a node with infectious
+/// agents is infected.
+///
+/// Only stationary agents at the same node meet; agents in transit, agents
+/// isolating, and agents at a closed venue meet no one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transmission {
+    /// Each new encounter with an infectious agent infects with
+    /// `probability`.  An encounter is new on the tick either agent arrives
+    /// at the node; staying together longer adds nothing.
+    PerContact { probability: f64 },
+    /// A constant hazard of `rate_per_hour` per infectious agent present,
+    /// so the risk grows with the time spent together:
+    /// `1 − exp(−rate · infectious · hours)` per tick.
+    Duration { rate_per_hour: f64 },
+}
+
+impl Transmission {
+    /// The chance of infection over one tick of `tick_duration_secs`, given
+    /// `exposures` infectious agents counted by the model: those newly met
+    /// for [`PerContact`][Self::PerContact], all present for
+    /// [`Duration`][Self::Duration].
+    pub fn probability(&self, exposures: usize, tick_duration_secs: u32) -> f64 {
+        if exposures == 0 {
+            return 0.0;
+        }
+        match *self {
+            Transmission::PerContact { probability } => 1.0 - (1.0 - probability).powi(exposures as i32),
+            Transmission::Duration { rate_per_hour } => {
+                let hours = f64::from(tick_duration_secs) / 3600.0;
+                1.0 - (-rate_per_hour * exposures as f64 * hours).exp()
+            }
+        }
+    }
+
+    /// Whether only encounters that start this tick count.
+    pub(crate) fn counts_new_contacts_only(&self) -> bool {
+        matches!(self, Transmission::PerContact { .. })
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        match *self {
+            Transmission::PerContact { probability } if !(0.0..=1.0).contains(&probability) => {
+                Err("per-contact probability must be between 0 and 1".into())
+            }
+            Transmission::Duration { rate_per_hour } if !(rate_per_hour.is_finite() && rate_per_hour >= 0.0) => {
+                Err("transmission rate must be non-negative".into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+/*
+ * dt.h — C API of rust_dt (the dt-ffi crate).
+ *
+ * Objects are opaque pointers released by the matching dt_*_free.
+ * Constructors return NULL on failure; calls returning DtStatus return
+ * DT_STATUS_OK on success.  After any failure, dt_last_error() describes
+ * it until the next failure on the same thread.  Strings are
+ * NUL-terminated UTF-8; UINT32_MAX is "no node".
+ */
+#ifndef RUST_DT_H
+#define RUST_DT_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* ── Types ──────────────────────────────────────────────────────────────── */
+
+typedef enum DtStatus {
+    DT_STATUS_OK      = 0,
+    DT_STATUS_NULL    = 1, /* a required pointer argument was NULL */
+    DT_STATUS_INVALID = 2, /* an argument was out of range */
+    DT_STATUS_LOAD    = 3, /* reading an input file failed */
+    DT_STATUS_SIM     = 4, /* the simulation failed */
+    DT_STATUS_PANIC   = 5  /* an internal error; the object may be unusable */
+} DtStatus;
+
+typedef enum DtBehavior {
+    DT_BEHAVIOR_COMMUTE = 0, /* travel to the current activity's destination */
+    DT_BEHAVIOR_NOOP    = 1  /* never move */
+} DtBehavior;
+
+typedef enum DtMode {
+    DT_MODE_CAR     = 0,
+    DT_MODE_WALK    = 1,
+    DT_MODE_BIKE    = 2,
+    DT_MODE_TRANSIT = 3
+} DtMode;
+
+typedef struct DtNetworkBuilder DtNetworkBuilder;
+typedef struct DtNetwork DtNetwork;
+typedef struct DtPlans DtPlans;
+typedef struct DtSim DtSim;
+
+typedef struct DtSimConfig {
+    int64_t  start_unix_secs;
+    uint32_t tick_duration_secs;
+    uint64_t total_ticks;
+    uint64_t seed;
+} DtSimConfig;
+
+typedef struct DtTickStats {
+    uint64_t tick;
+    uint64_t woken;
+    uint64_t in_transit;
+    uint64_t departures;
+    uint64_t arrivals;
+} DtTickStats;
+
+typedef struct DtAgentPosition {
+    float    lat;         /* NaN if the agent's node is not in the network */
+    float    lon;
+    uint32_t node;        /* current node, or departure node in transit */
+    uint32_t destination; /* equals node when stationary */
+    bool     in_transit;
+} DtAgentPosition;
+
+/* ── Errors ─────────────────────────────────────────────────────────────── */
+
+const char *dt_last_error(void);
+void dt_clear_error(void);
+
+/* ── Networks ───────────────────────────────────────────────────────────── */
+
+DtNetworkBuilder *dt_network_builder_new(void);
+uint32_t dt_network_builder_add_node(DtNetworkBuilder *builder, float lat, float lon);
+DtStatus dt_network_builder_add_road(DtNetworkBuilder *builder, uint32_t from, uint32_t to,
+                                     float length_m, uint32_t travel_ms, bool two_way);
+/* Consumes the builder, even on failure. */
+DtNetwork *dt_network_builder_build(DtNetworkBuilder *builder);
+void dt_network_builder_free(DtNetworkBuilder *builder);
+
+DtNetwork *dt_network_grid(uint32_t rows, uint32_t cols, float spacing_m, float speed_kmh,
+                           float origin_lat, float origin_lon);
+/* NULL unless the library was built with feature `osm`. */
+DtNetwork *dt_network_load_osm(const char *path);
+
+size_t dt_network_node_count(const DtNetwork *network);
+size_t dt_network_edge_count(const DtNetwork *network);
+DtStatus dt_network_node_position(const DtNetwork *network, uint32_t node, float *lat, float *lon);
+uint32_t dt_network_nearest_node(const DtNetwork *network, float lat, float lon);
+void dt_network_free(DtNetwork *network);
+
+/* ── Plans ──────────────────────────────────────────────────────────────── */
+
+DtPlans *dt_plans_load_csv(const char *path, size_t agents);
+DtPlans *dt_plans_daily(size_t agents, uint64_t depart_secs, uint64_t work_secs,
+                        uint32_t tick_duration_secs);
+DtPlans *dt_plans_empty(size_t agents);
+size_t dt_plans_len(const DtPlans *plans);
+void dt_plans_free(DtPlans *plans);
+
+/* ── Simulations ────────────────────────────────────────────────────────── */
+
+DtSimConfig dt_sim_config_default(void);
+
+/* One agent per plan.  `homes` and `works` may be NULL (random nodes) or
+ * hold dt_plans_len(plans) node ids.  The network and plans are copied. */
+DtSim *dt_sim_new(const DtSimConfig *config, const DtNetwork *network, const DtPlans *plans,
+                  const uint32_t *homes, const uint32_t *works, DtBehavior behavior, DtMode mode);
+void dt_sim_free(DtSim *sim);
+
+DtStatus dt_sim_step(DtSim *sim, uint64_t ticks);
+uint64_t dt_sim_current_tick(const DtSim *sim);
+bool dt_sim_finished(const DtSim *sim);
+size_t dt_sim_agent_count(const DtSim *sim);
+DtStatus dt_sim_last_stats(const DtSim *sim, DtTickStats *out);
+size_t dt_sim_failure_count(const DtSim *sim);
+
+DtStatus dt_sim_agent_position(const DtSim *sim, uint32_t agent, DtAgentPosition *out);
+/* Writes min(capacity, agents) positions and returns the agent count. */
+size_t dt_sim_positions(const DtSim *sim, DtAgentPosition *out, size_t capacity);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RUST_DT_H */
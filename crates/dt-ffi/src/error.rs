@@ -0,0 +1,158 @@
+//! Error types for dt-ffi, status codes, and the per-thread last error.
+
+use std::cell::RefCell;
+use std::ffi::{CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use dt_core::{DtError, ErrorCategory};
+use thiserror::Error;
+
+/// Errors reported across the C ABI.
+#[derive(Debug, Error)]
+pub enum FfiError {
+    #[error("`{0}` is null")]
+    Null(&'static str),
+
+    #[error("{0}")]
+    Invalid(String),
+
+    #[error(transparent)]
+    Cli(#[from] dt_cli::CliError),
+
+    #[error(transparent)]
+    Sim(#[from] dt_sim::SimError),
+
+    #[error("panic: {0}")]
+    Panic(String),
+}
+
+/// Alias for `Result<T, FfiError>`.
+pub type FfiResult<T> = Result<T, FfiError>;
+
+impl From<FfiError> for DtError {
+    fn from(err: FfiError) -> Self {
+        DtError::subsystem(ErrorCategory::Sim, err)
+    }
+}
+
+// ── DtStatus ──────────────────────────────────────────────────────────────────
+
+/// Result of a call that can fail.  Anything but `DT_OK` leaves a message
+/// for [`dt_last_error`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DtStatus {
+    Ok      = 0,
+    Null    = 1,
+    Invalid = 2,
+    Load    = 3,
+    Sim     = 4,
+    Panic   = 5,
+}
+
+impl FfiError {
+    /// The status this error is reported as.
+    pub fn status(&self) -> DtStatus {
+        match self {
+            FfiError::Null(_)    => DtStatus::Null,
+            FfiError::Invalid(_) => DtStatus::Invalid,
+            FfiError::Cli(_)     => DtStatus::Load,
+            FfiError::Sim(_)     => DtStatus::Sim,
+            FfiError::Panic(_)   => DtStatus::Panic,
+        }
+    }
+}
+
+// ── Last error ────────────────────────────────────────────────────────────────
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(err: &FfiError) {
+    // Interior NULs would truncate the message; replace them.
+    let message = CString::new(err.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// The message of the last failed call on this thread, or null if none
+/// failed.  Valid until the next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn dt_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(std::ptr::null(), |m| m.as_ptr()))
+}
+
+/// Forget the last error on this thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn dt_clear_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+// ── Guards ────────────────────────────────────────────────────────────────────
+
+/// Run `f`, turning panics into [`FfiError::Panic`] so they never unwind
+/// into C.
+fn guarded<T>(f: impl FnOnce() -> FfiResult<T>) -> FfiResult<T> {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown".into());
+        Err(FfiError::Panic(message))
+    })
+}
+
+/// Run `f` for a status-returning function.
+pub(crate) fn status(f: impl FnOnce() -> FfiResult<()>) -> DtStatus {
+    match guarded(f) {
+        Ok(()) => DtStatus::Ok,
+        Err(err) => {
+            set_last_error(&err);
+            err.status()
+        }
+    }
+}
+
+/// Run `f` for a constructor: the new object on the heap, or null.
+pub(crate) fn boxed<T>(f: impl FnOnce() -> FfiResult<T>) -> *mut T {
+    match guarded(f) {
+        Ok(value) => Box::into_raw(Box::new(value)),
+        Err(err) => {
+            set_last_error(&err);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+// ── Pointers ──────────────────────────────────────────────────────────────────
+
+/// `*ptr`, or [`FfiError::Null`] naming the argument.
+///
+/// # Safety
+/// `ptr` must be null or valid for reads for `'a`.
+pub(crate) unsafe fn deref<'a, T>(ptr: *const T, name: &'static str) -> FfiResult<&'a T> {
+    unsafe { ptr.as_ref() }.ok_or(FfiError::Null(name))
+}
+
+/// `*ptr` mutably, or [`FfiError::Null`] naming the argument.
+///
+/// # Safety
+/// `ptr` must be null or valid for reads and writes for `'a`, with no other
+/// references to it.
+pub(crate) unsafe fn deref_mut<'a, T>(ptr: *mut T, name: &'static str) -> FfiResult<&'a mut T> {
+    unsafe { ptr.as_mut() }.ok_or(FfiError::Null(name))
+}
+
+/// The UTF-8 string `ptr` points to.
+///
+/// # Safety
+/// `ptr` must be null or a NUL-terminated string valid for reads.
+pub(crate) unsafe fn c_str<'a>(ptr: *const c_char, name: &'static str) -> FfiResult<&'a str> {
+    if ptr.is_null() {
+        return Err(FfiError::Null(name));
+    }
+    unsafe { std::ffi::CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| FfiError::Invalid(format!("`{name}` is not UTF-8")))
+}
@@ -0,0 +1,74 @@
+//! `dt-ffi` — a stable C ABI for embedding rust_dt.
+//!
+//! Front-ends written in C, C++, or C# (via P/Invoke) build or load a road
+//! network, load activity plans, create a simulation with one of dt-cli's
+//! built-in behaviors, step it at their own pace, and read agent positions
+//! between steps.  The declarations are in `include/dt.h`:
+//!
+//! ```c
+//! #include "dt.h"
+//!
+//! DtNetwork *network = dt_network_grid(10, 10, 500.0f, 50.0f, 52.0f, 13.0f);
+//! DtPlans *plans = dt_plans_daily(1000, 8 * 3600, 9 * 3600, 3600);
+//! DtSimConfig config = dt_sim_config_default();
+//! config.total_ticks = 24;
+//!
+//! DtSim *sim = dt_sim_new(&config, network, plans, NULL, NULL, DT_BEHAVIOR_COMMUTE, DT_MODE_CAR);
+//! if (!sim) { fprintf(stderr, "%s\n", dt_last_error()); return 1; }
+//!
+//! DtAgentPosition positions[1000];
+//! while (!dt_sim_finished(sim)) {
+//!     dt_sim_step(sim, 1);
+//!     dt_sim_positions(sim, positions, 1000);
+//!     /* draw positions */
+//! }
+//! dt_sim_free(sim);
+//! dt_plans_free(plans);
+//! dt_network_free(network);
+//! ```
+//!
+//! # Conventions
+//!
+//! - Objects are opaque pointers, created by `dt_*_new`/`dt_*_load_*` and
+//!   released by the matching `dt_*_free`.  Constructors return null on
+//!   failure.
+//! - Calls that can fail return a [`DtStatus`]; on failure,
+//!   [`dt_last_error`] describes it until the next failure on the same
+//!   thread.
+//! - Panics never unwind into the caller; they are reported as
+//!   `DT_STATUS_PANIC`.
+//! - Strings are NUL-terminated UTF-8.  Node and agent ids are `uint32_t`;
+//!   `UINT32_MAX` means none.
+//! - An object may be used from any thread, but not from two at once.
+//!
+//! Build with `cargo build -p dt-ffi --release`; the shared and static
+//! libraries land in `target/release` as `libdt_ffi.{so,dylib,a}` or
+//! `dt_ffi.{dll,lib}`.
+//!
+//! # Crate layout
+//!
+//! | Module      | Contents                                                |
+//! |-------------|---------------------------------------------------------|
+//! | [`network`] | `DtNetworkBuilder`, `DtNetwork`: explicit, grid, or OSM |
+//! | [`plans`]   | `DtPlans`: CSV, a daily template, or empty              |
+//! | [`sim`]     | `DtSim`: creation, stepping, stats, and positions       |
+//! | [`error`]   | `DtStatus`, `dt_last_error`, `FfiError`, `FfiResult<T>` |
+//!
+//! # Feature flags
+//!
+//! | Feature | Enables                                      |
+//! |---------|----------------------------------------------|
+//! | `osm`   | `dt_network_load_osm` (null without it)      |
+
+pub mod error;
+pub mod network;
+pub mod plans;
+pub mod sim;
+
+#[cfg(test)]
+mod tests;
+
+pub use error::{DtStatus, FfiError, FfiResult, dt_clear_error, dt_last_error};
+pub use network::{DtNetwork, DtNetworkBuilder};
+pub use plans::DtPlans;
+pub use sim::{DtAgentPosition, DtBehavior, DtMode, DtSim, DtSimConfig, DtTickStats};
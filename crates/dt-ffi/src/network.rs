@@ -0,0 +1,205 @@
+//! Road networks: `DtNetworkBuilder` and `DtNetwork`.
+
+use std::ffi::c_char;
+
+use dt_cli::{GridSpec, NetworkSpec};
+use dt_core::{GeoPoint, NodeId};
+use dt_spatial::{RoadNetwork, RoadNetworkBuilder};
+
+use crate::error::{boxed, c_str, deref, deref_mut, status};
+use crate::{DtStatus, FfiError};
+
+/// A network under construction.  Opaque to C.
+pub struct DtNetworkBuilder(pub(crate) RoadNetworkBuilder);
+
+/// A built road network.  Opaque to C.
+pub struct DtNetwork(pub(crate) RoadNetwork);
+
+// ── Building ──────────────────────────────────────────────────────────────────
+
+/// A new, empty network builder.  Free it with `dt_network_builder_free`
+/// unless it is passed to `dt_network_builder_build`.
+#[unsafe(no_mangle)]
+pub extern "C" fn dt_network_builder_new() -> *mut DtNetworkBuilder {
+    boxed(|| Ok(DtNetworkBuilder(RoadNetworkBuilder::new())))
+}
+
+/// Add a node at `(lat, lon)` and return its id, or `UINT32_MAX` if
+/// `builder` is null.
+///
+/// # Safety
+/// `builder` must be null or a live builder.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_network_builder_add_node(builder: *mut DtNetworkBuilder, lat: f32, lon: f32) -> u32 {
+    let mut id = NodeId::INVALID;
+    status(|| {
+        let b = unsafe { deref_mut(builder, "builder") }?;
+        id = b.0.add_node(GeoPoint::new(lat, lon));
+        Ok(())
+    });
+    id.0
+}
+
+/// Add a road from node `from` to node `to`, in both directions when
+/// `two_way`.
+///
+/// # Safety
+/// `builder` must be null or a live builder.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_network_builder_add_road(
+    builder:   *mut DtNetworkBuilder,
+    from:      u32,
+    to:        u32,
+    length_m:  f32,
+    travel_ms: u32,
+    two_way:   bool,
+) -> DtStatus {
+    status(|| {
+        let b = unsafe { deref_mut(builder, "builder") }?;
+        let nodes = b.0.node_count() as u32;
+        if from >= nodes || to >= nodes {
+            return Err(FfiError::Invalid(format!("road {from} -> {to}: the builder has {nodes} nodes")));
+        }
+        let (from, to) = (NodeId(from), NodeId(to));
+        if two_way {
+            b.0.add_road(from, to, length_m, travel_ms);
+        } else {
+            b.0.add_directed_edge(from, to, length_m, travel_ms);
+        }
+        Ok(())
+    })
+}
+
+/// Build the network, consuming `builder` (which must not be used or
+/// freed afterwards).  Null if `builder` is null.
+///
+/// # Safety
+/// `builder` must be null or a live builder.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_network_builder_build(builder: *mut DtNetworkBuilder) -> *mut DtNetwork {
+    boxed(|| {
+        if builder.is_null() {
+            return Err(FfiError::Null("builder"));
+        }
+        let b = unsafe { Box::from_raw(builder) };
+        Ok(DtNetwork(b.0.build()))
+    })
+}
+
+/// Free a builder that was not built.  Null is ignored.
+///
+/// # Safety
+/// `builder` must be null or a live builder, not used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_network_builder_free(builder: *mut DtNetworkBuilder) {
+    if !builder.is_null() {
+        drop(unsafe { Box::from_raw(builder) });
+    }
+}
+
+// ── Loading ───────────────────────────────────────────────────────────────────
+
+/// A `rows` × `cols` grid with two-way roads between neighbours, as
+/// `[network.grid]` in a dt-cli scenario.  Node `r * cols + c` is row `r`
+/// (from the south) and column `c` (from the west) of a grid whose
+/// south-west node is at `(origin_lat, origin_lon)`.  Null if any size is
+/// not positive.
+#[unsafe(no_mangle)]
+pub extern "C" fn dt_network_grid(
+    rows:       u32,
+    cols:       u32,
+    spacing_m:  f32,
+    speed_kmh:  f32,
+    origin_lat: f32,
+    origin_lon: f32,
+) -> *mut DtNetwork {
+    boxed(|| {
+        if rows == 0 || cols == 0 || spacing_m <= 0.0 || speed_kmh <= 0.0 {
+            return Err(FfiError::Invalid("rows, cols, spacing_m, and speed_kmh must be positive".into()));
+        }
+        let grid = GridSpec { rows, cols, spacing_m, speed_kmh, origin: [origin_lat, origin_lon] };
+        Ok(DtNetwork(dt_cli::network::grid_network(&grid)))
+    })
+}
+
+/// The drivable roads of the OSM PBF file at `path` (UTF-8).  Null on
+/// error, or if the library was built without feature `osm`.
+///
+/// # Safety
+/// `path` must be null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_network_load_osm(path: *const c_char) -> *mut DtNetwork {
+    boxed(|| {
+        let path = unsafe { c_str(path, "path") }?;
+        let spec = NetworkSpec { osm: Some(path.into()), grid: None };
+        Ok(DtNetwork(dt_cli::network::load_network(&spec)?))
+    })
+}
+
+// ── Queries ───────────────────────────────────────────────────────────────────
+
+/// Number of nodes; 0 if `network` is null.
+///
+/// # Safety
+/// `network` must be null or a live network.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_network_node_count(network: *const DtNetwork) -> usize {
+    unsafe { network.as_ref() }.map_or(0, |n| n.0.node_count())
+}
+
+/// Number of directed edges; 0 if `network` is null.
+///
+/// # Safety
+/// `network` must be null or a live network.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_network_edge_count(network: *const DtNetwork) -> usize {
+    unsafe { network.as_ref() }.map_or(0, |n| n.0.edge_count())
+}
+
+/// Write the position of `node` to `*lat` and `*lon`.
+///
+/// # Safety
+/// `network` must be null or a live network; `lat` and `lon` null or
+/// writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_network_node_position(
+    network: *const DtNetwork,
+    node:    u32,
+    lat:     *mut f32,
+    lon:     *mut f32,
+) -> DtStatus {
+    status(|| {
+        let network = unsafe { deref(network, "network") }?;
+        let pos = network.0.node_pos.get(node as usize).ok_or_else(|| {
+            FfiError::Invalid(format!("node {node} is not in the network"))
+        })?;
+        *unsafe { deref_mut(lat, "lat") }? = pos.lat;
+        *unsafe { deref_mut(lon, "lon") }? = pos.lon;
+        Ok(())
+    })
+}
+
+/// The node nearest `(lat, lon)`, or `UINT32_MAX` if `network` is null or
+/// empty.
+///
+/// # Safety
+/// `network` must be null or a live network.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_network_nearest_node(network: *const DtNetwork, lat: f32, lon: f32) -> u32 {
+    unsafe { network.as_ref() }
+        .and_then(|n| n.0.snap_to_node(GeoPoint::new(lat, lon)))
+        .unwrap_or(NodeId::INVALID)
+        .0
+}
+
+/// Free a network.  Null is ignored.  Sims hold their own copy, so a
+/// network may be freed while sims built from it live on.
+///
+/// # Safety
+/// `network` must be null or a live network, not used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_network_free(network: *mut DtNetwork) {
+    if !network.is_null() {
+        drop(unsafe { Box::from_raw(network) });
+    }
+}
@@ -0,0 +1,73 @@
+//! Activity plans: `DtPlans`, one plan per agent.
+
+use std::ffi::c_char;
+
+use dt_cli::{DailyPlan, PlansSpec};
+use dt_schedule::ActivityPlan;
+
+use crate::FfiError;
+use crate::error::{boxed, c_str};
+
+/// One activity plan per agent, indexed by agent id.  Opaque to C.
+pub struct DtPlans(pub(crate) Vec<ActivityPlan>);
+
+/// Plans for `agents` agents from the CSV at `path` (UTF-8), in the
+/// `dt_schedule::load_plans_csv` format.  Agents without rows get an empty
+/// plan.  Null on error.
+///
+/// # Safety
+/// `path` must be null or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_plans_load_csv(path: *const c_char, agents: usize) -> *mut DtPlans {
+    boxed(|| {
+        let path = unsafe { c_str(path, "path") }?;
+        let spec = PlansSpec { csv: Some(path.into()), ..Default::default() };
+        Ok(DtPlans(dt_cli::plans::load_plans(&spec, agents, 0)?))
+    })
+}
+
+/// The same day for each of `agents` agents, as `[plans.daily]` in a
+/// dt-cli scenario: work from `depart_secs` after midnight for `work_secs`,
+/// then home.  Null if the day does not fit in 24 hours.
+#[unsafe(no_mangle)]
+pub extern "C" fn dt_plans_daily(
+    agents:             usize,
+    depart_secs:        u64,
+    work_secs:          u64,
+    tick_duration_secs: u32,
+) -> *mut DtPlans {
+    boxed(|| {
+        if depart_secs.saturating_add(work_secs) > 86_400 {
+            return Err(FfiError::Invalid("depart_secs + work_secs exceeds one day".into()));
+        }
+        let daily = DailyPlan { depart: depart_secs, work: work_secs };
+        let spec = PlansSpec { daily: Some(daily), ..Default::default() };
+        Ok(DtPlans(dt_cli::plans::load_plans(&spec, agents, tick_duration_secs)?))
+    })
+}
+
+/// An empty plan for each of `agents` agents.
+#[unsafe(no_mangle)]
+pub extern "C" fn dt_plans_empty(agents: usize) -> *mut DtPlans {
+    boxed(|| Ok(DtPlans(vec![ActivityPlan::empty(); agents])))
+}
+
+/// Number of plans (agents); 0 if `plans` is null.
+///
+/// # Safety
+/// `plans` must be null or live plans.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_plans_len(plans: *const DtPlans) -> usize {
+    unsafe { plans.as_ref() }.map_or(0, |p| p.0.len())
+}
+
+/// Free plans.  Null is ignored.  Sims hold their own copy.
+///
+/// # Safety
+/// `plans` must be null or live plans, not used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_plans_free(plans: *mut DtPlans) {
+    if !plans.is_null() {
+        drop(unsafe { Box::from_raw(plans) });
+    }
+}
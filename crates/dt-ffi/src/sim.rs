@@ -0,0 +1,326 @@
+//! Simulations: `DtSim`, stepping, and agent positions.
+
+use dt_cli::{BuiltinBehavior, Population, PopulationSpec};
+use dt_core::{NodeId, SimConfig, TransportMode};
+use dt_mobility::MovementState;
+use dt_sim::{FailurePolicy, NoopObserver, Sim, SimBuilder};
+use dt_spatial::DijkstraRouter;
+
+use crate::error::{boxed, deref, deref_mut, status};
+use crate::{DtNetwork, DtPlans, DtStatus, FfiError, FfiResult};
+
+/// A simulation in progress.  Opaque to C.
+pub struct DtSim(pub(crate) Sim<BuiltinBehavior, DijkstraRouter>);
+
+// ── Types ─────────────────────────────────────────────────────────────────────
+
+/// The core fields of `SimConfig`; the rest keep their defaults.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DtSimConfig {
+    pub start_unix_secs:    i64,
+    pub tick_duration_secs: u32,
+    pub total_ticks:        u64,
+    pub seed:               u64,
+}
+
+impl From<DtSimConfig> for SimConfig {
+    fn from(c: DtSimConfig) -> Self {
+        SimConfig {
+            start_unix_secs:    c.start_unix_secs,
+            tick_duration_secs: c.tick_duration_secs,
+            total_ticks:        c.total_ticks,
+            seed:               c.seed,
+            ..SimConfig::default()
+        }
+    }
+}
+
+/// The built-in behavior every agent follows.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DtBehavior {
+    /// Travel to the current activity's destination whenever woken.
+    Commute = 0,
+    /// Never move.
+    Noop    = 1,
+}
+
+/// Transport mode of every agent.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DtMode {
+    Car     = 0,
+    Walk    = 1,
+    Bike    = 2,
+    Transit = 3,
+}
+
+/// Counters of the most recent tick (see `dt_sim::TickStats`).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DtTickStats {
+    /// The tick these counters describe.
+    pub tick:       u64,
+    pub woken:      u64,
+    pub in_transit: u64,
+    pub departures: u64,
+    pub arrivals:   u64,
+}
+
+/// Where an agent is.  `lat`/`lon` are interpolated along the straight line
+/// between the journey's nodes while `in_transit` (the current edge's, with
+/// path-following), and NaN if the agent's node is not in the network.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DtAgentPosition {
+    pub lat:         f32,
+    pub lon:         f32,
+    /// The node the agent is at, or departed from while `in_transit`.
+    pub node:        u32,
+    /// The node the agent is heading to; equals `node` when stationary.
+    pub destination: u32,
+    pub in_transit:  bool,
+}
+
+/// `SimConfig::default()`'s core fields.
+#[unsafe(no_mangle)]
+pub extern "C" fn dt_sim_config_default() -> DtSimConfig {
+    let c = SimConfig::default();
+    DtSimConfig {
+        start_unix_secs:    c.start_unix_secs,
+        tick_duration_secs: c.tick_duration_secs,
+        total_ticks:        c.total_ticks,
+        seed:               c.seed,
+    }
+}
+
+// ── Lifecycle ─────────────────────────────────────────────────────────────────
+
+/// A simulation of one agent per plan on a copy of `network`.
+///
+/// `homes` and `works`, when not null, hold one node id per agent; agents
+/// otherwise get random home and work nodes (seeded by `config->seed`).
+/// Agents start at home.  Routing failures and the like are collected, not
+/// fatal (see `dt_sim_failure_count`).  Null on error.
+///
+/// # Safety
+/// `config`, `network`, and `plans` must be null or live; `homes` and
+/// `works` null or valid for reads of `dt_plans_len(plans)` elements.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_sim_new(
+    config:   *const DtSimConfig,
+    network:  *const DtNetwork,
+    plans:    *const DtPlans,
+    homes:    *const u32,
+    works:    *const u32,
+    behavior: DtBehavior,
+    mode:     DtMode,
+) -> *mut DtSim {
+    boxed(|| {
+        let config = SimConfig::from(*unsafe { deref(config, "config") }?);
+        let network = unsafe { deref(network, "network") }?.0.clone();
+        let plans = unsafe { deref(plans, "plans") }?.0.clone();
+        let agents = plans.len();
+        let nodes = network.node_count();
+
+        let spec = PopulationSpec { agents: Some(agents), ..Default::default() };
+        let mut population = Population::load(&spec, nodes, mode.into(), config.seed)?;
+        if let Some(homes) = unsafe { node_ids(homes, agents, nodes, "homes") }? {
+            population.homes = homes;
+        }
+        if let Some(works) = unsafe { node_ids(works, agents, nodes, "works") }? {
+            population.works = works;
+        }
+
+        let behavior = match behavior {
+            DtBehavior::Commute => BuiltinBehavior::Commute,
+            DtBehavior::Noop    => BuiltinBehavior::Noop,
+        };
+        let (store, rngs) = population.build_store(config.seed);
+        let sim = SimBuilder::new(config, store, rngs, behavior, DijkstraRouter)
+            .plans(plans)
+            .network(network)
+            .initial_positions(population.homes)
+            .failure_policy(FailurePolicy::CollectAndReport)
+            .build()?;
+        Ok(DtSim(sim))
+    })
+}
+
+/// `len` node ids from `ptr`, each checked against `nodes`; `None` if
+/// `ptr` is null.
+unsafe fn node_ids(ptr: *const u32, len: usize, nodes: usize, name: &str) -> FfiResult<Option<Vec<NodeId>>> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    let ids = if len == 0 { &[][..] } else { unsafe { std::slice::from_raw_parts(ptr, len) } };
+    ids.iter()
+        .enumerate()
+        .map(|(agent, &id)| {
+            if (id as usize) < nodes {
+                Ok(NodeId(id))
+            } else {
+                Err(FfiError::Invalid(format!("{name}[{agent}] = {id} is not in the network ({nodes} nodes)")))
+            }
+        })
+        .collect::<FfiResult<_>>()
+        .map(Some)
+}
+
+impl From<DtMode> for TransportMode {
+    fn from(mode: DtMode) -> Self {
+        match mode {
+            DtMode::Car     => TransportMode::Car,
+            DtMode::Walk    => TransportMode::Walk,
+            DtMode::Bike    => TransportMode::Bike,
+            DtMode::Transit => TransportMode::Transit,
+        }
+    }
+}
+
+/// Free a simulation.  Null is ignored.
+///
+/// # Safety
+/// `sim` must be null or a live sim, not used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_sim_free(sim: *mut DtSim) {
+    if !sim.is_null() {
+        drop(unsafe { Box::from_raw(sim) });
+    }
+}
+
+// ── Stepping ──────────────────────────────────────────────────────────────────
+
+/// Advance up to `ticks` ticks, stopping at the configured end.
+///
+/// # Safety
+/// `sim` must be null or a live sim.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_sim_step(sim: *mut DtSim, ticks: u64) -> DtStatus {
+    status(|| {
+        let sim = &mut unsafe { deref_mut(sim, "sim") }?.0;
+        let remaining = sim.config.end_tick().0.saturating_sub(sim.clock.current_tick.0);
+        sim.run_ticks(ticks.min(remaining), &mut NoopObserver)?;
+        Ok(())
+    })
+}
+
+/// The next tick to run; `total_ticks` once finished.  0 if `sim` is null.
+///
+/// # Safety
+/// `sim` must be null or a live sim.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_sim_current_tick(sim: *const DtSim) -> u64 {
+    unsafe { sim.as_ref() }.map_or(0, |s| s.0.clock.current_tick.0)
+}
+
+/// `true` once every configured tick has run, or if `sim` is null.
+///
+/// # Safety
+/// `sim` must be null or a live sim.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_sim_finished(sim: *const DtSim) -> bool {
+    unsafe { sim.as_ref() }.is_none_or(|s| s.0.clock.current_tick >= s.0.config.end_tick())
+}
+
+/// Number of agents; 0 if `sim` is null.
+///
+/// # Safety
+/// `sim` must be null or a live sim.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_sim_agent_count(sim: *const DtSim) -> usize {
+    unsafe { sim.as_ref() }.map_or(0, |s| s.0.mobility.store.states.len())
+}
+
+/// Write the counters of the most recent tick to `*out`.  Before the first
+/// step they are all zero.
+///
+/// # Safety
+/// `sim` must be null or a live sim; `out` null or writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_sim_last_stats(sim: *const DtSim, out: *mut DtTickStats) -> DtStatus {
+    status(|| {
+        let sim = &unsafe { deref(sim, "sim") }?.0;
+        let out = unsafe { deref_mut(out, "out") }?;
+        let s = &sim.stats;
+        *out = DtTickStats {
+            tick:       sim.clock.current_tick.0.saturating_sub(1),
+            woken:      s.woken,
+            in_transit: s.in_transit,
+            departures: s.departures,
+            arrivals:   s.arrivals,
+        };
+        Ok(())
+    })
+}
+
+/// Number of failures collected so far (routing failures, behavior panics,
+/// and the like); 0 if `sim` is null.
+///
+/// # Safety
+/// `sim` must be null or a live sim.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_sim_failure_count(sim: *const DtSim) -> usize {
+    unsafe { sim.as_ref() }.map_or(0, |s| s.0.failures.len())
+}
+
+// ── Positions ─────────────────────────────────────────────────────────────────
+
+/// Write the position of `agent` at the current tick to `*out`.
+///
+/// # Safety
+/// `sim` must be null or a live sim; `out` null or writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_sim_agent_position(sim: *const DtSim, agent: u32, out: *mut DtAgentPosition) -> DtStatus {
+    status(|| {
+        let sim = &unsafe { deref(sim, "sim") }?.0;
+        let out = unsafe { deref_mut(out, "out") }?;
+        let states = &sim.mobility.store.states;
+        let state = states.get(agent as usize).ok_or_else(|| {
+            FfiError::Invalid(format!("agent {agent} is not in the sim ({} agents)", states.len()))
+        })?;
+        *out = position(sim, state);
+        Ok(())
+    })
+}
+
+/// Write the positions of the first `min(capacity, agents)` agents to
+/// `out[0..]` and return the number of agents, so a call with `capacity`
+/// 0 sizes the buffer.  0 if `sim` is null.
+///
+/// # Safety
+/// `sim` must be null or a live sim; `out` valid for writes of `capacity`
+/// elements (or null when `capacity` is 0).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dt_sim_positions(sim: *const DtSim, out: *mut DtAgentPosition, capacity: usize) -> usize {
+    let Some(sim) = (unsafe { sim.as_ref() }) else {
+        return 0;
+    };
+    let states = &sim.0.mobility.store.states;
+    if !out.is_null() && capacity > 0 {
+        let out = unsafe { std::slice::from_raw_parts_mut(out, capacity.min(states.len())) };
+        for (slot, state) in out.iter_mut().zip(states) {
+            *slot = position(&sim.0, state);
+        }
+    }
+    states.len()
+}
+
+/// Position of an agent in `state`, as dt-viz draws it.
+fn position(sim: &Sim<BuiltinBehavior, DijkstraRouter>, state: &MovementState) -> DtAgentPosition {
+    let node_pos = &sim.network.node_pos;
+    let (from, to, t) = state.segment(sim.clock.current_tick, &sim.network);
+    let (lat, lon) = match (node_pos.get(from.index()), node_pos.get(to.index()), state.in_transit) {
+        (Some(from), _, false) => (from.lat, from.lon),
+        (Some(from), Some(to), true) => (from.lat + (to.lat - from.lat) * t, from.lon + (to.lon - from.lon) * t),
+        _ => (f32::NAN, f32::NAN),
+    };
+    DtAgentPosition {
+        lat,
+        lon,
+        node:        state.departure_node.0,
+        destination: state.destination_node.0,
+        in_transit:  state.in_transit,
+    }
+}
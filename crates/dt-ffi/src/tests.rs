@@ -0,0 +1,210 @@
+// ── Networks and plans ────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod input_tests {
+    use std::ffi::{CStr, CString};
+    use std::io::Write;
+    use std::ptr;
+
+    use crate::network::*;
+    use crate::plans::*;
+    use crate::{DtStatus, dt_clear_error, dt_last_error};
+
+    pub(super) fn last_error() -> String {
+        let err = dt_last_error();
+        assert!(!err.is_null());
+        unsafe { CStr::from_ptr(err) }.to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn builder_and_grid_networks() {
+        unsafe {
+            let b = dt_network_builder_new();
+            let a = dt_network_builder_add_node(b, 0.0, 0.0);
+            let c = dt_network_builder_add_node(b, 0.0, 0.01);
+            assert_eq!((a, c), (0, 1));
+            assert_eq!(dt_network_builder_add_road(b, a, c, 1100.0, 80_000, true), DtStatus::Ok);
+            assert_eq!(dt_network_builder_add_road(b, c, a, 1100.0, 80_000, false), DtStatus::Ok);
+            let network = dt_network_builder_build(b);
+            assert_eq!((dt_network_node_count(network), dt_network_edge_count(network)), (2, 3));
+
+            let (mut lat, mut lon) = (f32::NAN, f32::NAN);
+            assert_eq!(dt_network_node_position(network, 1, &mut lat, &mut lon), DtStatus::Ok);
+            assert_eq!((lat, lon), (0.0, 0.01));
+            assert_eq!(dt_network_nearest_node(network, 0.0, 0.009), 1);
+            dt_network_free(network);
+
+            let grid = dt_network_grid(2, 3, 500.0, 50.0, 52.0, 13.0);
+            assert_eq!((dt_network_node_count(grid), dt_network_edge_count(grid)), (6, 14));
+            assert_eq!(dt_network_nearest_node(grid, 52.0, 13.0), 0);
+            dt_network_free(grid);
+        }
+    }
+
+    #[test]
+    fn bad_arguments_set_last_error() {
+        unsafe {
+            dt_clear_error();
+            assert!(dt_last_error().is_null());
+
+            assert_eq!(dt_network_builder_add_node(ptr::null_mut(), 0.0, 0.0), u32::MAX);
+            assert_eq!(last_error(), "`builder` is null");
+
+            let b = dt_network_builder_new();
+            dt_network_builder_add_node(b, 0.0, 0.0);
+            assert_eq!(dt_network_builder_add_road(b, 0, 1, 1.0, 1, true), DtStatus::Invalid);
+            assert!(last_error().contains("the builder has 1 nodes"));
+            dt_network_builder_free(b);
+
+            assert!(dt_network_grid(0, 3, 500.0, 50.0, 0.0, 0.0).is_null());
+            assert!(dt_network_builder_build(ptr::null_mut()).is_null());
+            assert_eq!(dt_network_node_position(ptr::null(), 0, ptr::null_mut(), ptr::null_mut()), DtStatus::Null);
+            assert_eq!(dt_network_node_count(ptr::null()), 0);
+            assert!(dt_plans_daily(3, 20 * 3600, 9 * 3600, 3600).is_null());
+            assert!(last_error().contains("exceeds one day"));
+
+            let missing = CString::new("/nonexistent/plans.csv").unwrap();
+            assert!(dt_plans_load_csv(missing.as_ptr(), 3).is_null());
+            assert!(dt_plans_load_csv(ptr::null(), 3).is_null());
+            assert_eq!(last_error(), "`path` is null");
+        }
+    }
+
+    #[test]
+    fn plans_from_csv_daily_and_empty() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "agent_id,activity_id,start_offset_ticks,duration_ticks,destination,cycle_ticks").unwrap();
+        writeln!(file, "0,1,0,8,5,24").unwrap();
+        file.flush().unwrap();
+        let path = CString::new(file.path().to_str().unwrap()).unwrap();
+
+        unsafe {
+            let csv = dt_plans_load_csv(path.as_ptr(), 3);
+            assert!(!csv.is_null(), "{}", last_error());
+            assert_eq!(dt_plans_len(csv), 3);
+            dt_plans_free(csv);
+
+            let daily = dt_plans_daily(4, 8 * 3600, 9 * 3600, 3600);
+            assert_eq!(dt_plans_len(daily), 4);
+            assert_eq!((&*daily).0[0].cycle_ticks, 24);
+            dt_plans_free(daily);
+
+            let empty = dt_plans_empty(2);
+            assert_eq!(dt_plans_len(empty), 2);
+            dt_plans_free(empty);
+            dt_plans_free(ptr::null_mut());
+        }
+    }
+}
+
+// ── Simulations ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod sim_tests {
+    use std::ptr;
+
+    use crate::network::*;
+    use crate::plans::*;
+    use crate::sim::*;
+    use crate::{DtAgentPosition, DtStatus, DtTickStats};
+
+    unsafe fn commute_sim(agents: usize, homes: *const u32, works: *const u32) -> *mut DtSim {
+        unsafe {
+            let network = dt_network_grid(4, 4, 500.0, 50.0, 0.0, 0.0);
+            let plans = dt_plans_daily(agents, 8 * 3600, 9 * 3600, 3600);
+            let config = DtSimConfig { total_ticks: 48, seed: 42, ..dt_sim_config_default() };
+            let sim = dt_sim_new(&config, network, plans, homes, works, DtBehavior::Commute, DtMode::Car);
+            // The sim holds its own copies.
+            dt_network_free(network);
+            dt_plans_free(plans);
+            sim
+        }
+    }
+
+    #[test]
+    fn stepping_moves_agents_to_work_and_back() {
+        let homes = [0u32, 0, 5];
+        let works = [15u32, 10, 6];
+        unsafe {
+            let sim = commute_sim(3, homes.as_ptr(), works.as_ptr());
+            assert!(!sim.is_null());
+            assert_eq!((dt_sim_agent_count(sim), dt_sim_current_tick(sim)), (3, 0));
+
+            let mut positions = [DtAgentPosition { lat: 0.0, lon: 0.0, node: 0, destination: 0, in_transit: false }; 3];
+            assert_eq!(dt_sim_positions(sim, ptr::null_mut(), 0), 3);
+            assert_eq!(dt_sim_positions(sim, positions.as_mut_ptr(), 3), 3);
+            assert_eq!(positions.map(|p| p.node), homes);
+
+            // 08:00 departs; by noon everyone is at work.
+            assert_eq!(dt_sim_step(sim, 9), DtStatus::Ok);
+            let mut stats = DtTickStats::default();
+            assert_eq!(dt_sim_last_stats(sim, &mut stats), DtStatus::Ok);
+            assert_eq!(stats.tick, 8);
+            assert_eq!(stats.departures, 3);
+
+            assert_eq!(dt_sim_step(sim, 4), DtStatus::Ok);
+            let mut at_work = positions[0];
+            for (agent, &work) in works.iter().enumerate() {
+                assert_eq!(dt_sim_agent_position(sim, agent as u32, &mut at_work), DtStatus::Ok);
+                assert_eq!((at_work.node, at_work.in_transit), (work, false));
+                let (mut lat, mut lon) = (0.0, 0.0);
+                let network = dt_network_grid(4, 4, 500.0, 50.0, 0.0, 0.0);
+                dt_network_node_position(network, work, &mut lat, &mut lon);
+                dt_network_free(network);
+                assert_eq!((at_work.lat, at_work.lon), (lat, lon));
+            }
+
+            // Stepping past the end stops at it.
+            assert_eq!(dt_sim_step(sim, 1000), DtStatus::Ok);
+            assert!(dt_sim_finished(sim));
+            assert_eq!(dt_sim_current_tick(sim), 48);
+            assert_eq!(dt_sim_failure_count(sim), 0);
+            dt_sim_positions(sim, positions.as_mut_ptr(), 3);
+            assert_eq!(positions.map(|p| p.node), homes);
+            dt_sim_free(sim);
+        }
+    }
+
+    #[test]
+    fn invalid_sims_are_rejected() {
+        unsafe {
+            let bad_homes = [0u32, 99];
+            assert!(commute_sim(2, bad_homes.as_ptr(), ptr::null()).is_null());
+            assert!(super::input_tests::last_error().contains("homes[1] = 99"));
+
+            let sim = commute_sim(2, ptr::null(), ptr::null());
+            assert!(!sim.is_null());
+            let mut out = DtAgentPosition { lat: 0.0, lon: 0.0, node: 0, destination: 0, in_transit: false };
+            assert_eq!(dt_sim_agent_position(sim, 2, &mut out), DtStatus::Invalid);
+            assert_eq!(dt_sim_agent_position(sim, 0, ptr::null_mut()), DtStatus::Null);
+            assert!(out.node < 16);
+            dt_sim_free(sim);
+
+            assert_eq!(dt_sim_step(ptr::null_mut(), 1), DtStatus::Null);
+            assert!(dt_sim_finished(ptr::null()));
+            assert!(dt_sim_new(ptr::null(), ptr::null(), ptr::null(), ptr::null(), ptr::null(), DtBehavior::Noop, DtMode::Walk).is_null());
+        }
+    }
+}
+
+// ── Header ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod header_tests {
+    const HEADER: &str = include_str!("../include/dt.h");
+    const SOURCES: [&str; 4] =
+        [include_str!("error.rs"), include_str!("network.rs"), include_str!("plans.rs"), include_str!("sim.rs")];
+
+    #[test]
+    fn header_declares_every_export() {
+        let mut exports = 0;
+        for source in SOURCES {
+            for (i, _) in source.match_indices("extern \"C\" fn ") {
+                let name: String = source[i + 14..].chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+                assert!(HEADER.contains(&format!("{name}(")), "dt.h does not declare {name}");
+                exports += 1;
+            }
+        }
+        assert_eq!(exports, HEADER.matches(");\n").count(), "dt.h declares functions the crate does not export");
+    }
+}
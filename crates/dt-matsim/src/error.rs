@@ -0,0 +1,35 @@
+//! Error types for dt-matsim.
+
+use std::path::PathBuf;
+
+use dt_core::{DtError, ErrorCategory};
+use thiserror::Error;
+
+/// Errors that can occur when reading or writing MATSim files.
+#[derive(Debug, Error)]
+pub enum MatsimError {
+    /// The input is not well-formed XML.
+    #[error("MATSim XML at byte {position}: {message}")]
+    Xml { position: u64, message: String },
+
+    /// Well-formed XML that isn't a usable network or population, or plans
+    /// that can't be expressed in MATSim's format.
+    #[error("MATSim: {0}")]
+    Format(String),
+
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+
+    /// Writing to a caller-supplied writer failed.
+    #[error("writing MATSim XML: {0}")]
+    Write(#[source] std::io::Error),
+}
+
+/// Alias for `Result<T, MatsimError>`.
+pub type MatsimResult<T> = Result<T, MatsimError>;
+
+impl From<MatsimError> for DtError {
+    fn from(err: MatsimError) -> Self {
+        DtError::subsystem(ErrorCategory::Schedule, err)
+    }
+}
@@ -0,0 +1,41 @@
+//! `dt-matsim` — MATSim network and population files.
+//!
+//! Many activity-based model inputs exist only as MATSim scenarios.  This
+//! crate reads a `network.xml` into a [`RoadNetwork`][dt_spatial::RoadNetwork]
+//! and a `plans.xml` into one [`ActivityPlan`][dt_schedule::ActivityPlan]
+//! per person, and writes both back out, so the same scenario can run in
+//! either engine.  Files ending in `.gz` are (de)compressed on the fly.
+//!
+//! ```rust,ignore
+//! use dt_matsim::{Coords, MatsimNetwork, PlanReader};
+//!
+//! let matsim = MatsimNetwork::read(Path::new("network.xml.gz"), Coords::Wgs84)?;
+//! let population = PlanReader::new(&matsim, 900).read(Path::new("plans.xml.gz"))?;
+//! let sim = SimBuilder::new(config, store, rngs, behavior, DijkstraRouter)
+//!     .network(matsim.network.clone())
+//!     .plans(population.plans.clone())
+//!     .build()?;
+//!
+//! // And back, e.g. after changing the plans:
+//! population.write(Path::new("out/plans.xml.gz"), &matsim, 900)?;
+//! ```
+//!
+//! # Crate layout
+//!
+//! | Module      | Contents                                                    |
+//! |-------------|-------------------------------------------------------------|
+//! | [`network`] | `MatsimNetwork` (ids, capacities, lanes), `Coords`          |
+//! | [`plans`]   | `PlanReader`, `MatsimPlans`, `parse_mode`, `mode_name`      |
+//! | [`error`]   | `MatsimError`, `MatsimResult<T>`                            |
+
+pub mod error;
+pub mod network;
+pub mod plans;
+mod xml;
+
+#[cfg(test)]
+mod tests;
+
+pub use error::{MatsimError, MatsimResult};
+pub use network::{Coords, MatsimNetwork};
+pub use plans::{MatsimPlans, PlanReader, mode_name, parse_mode};
@@ -0,0 +1,282 @@
+//! `MatsimNetwork` — a `RoadNetwork` read from or written as a MATSim
+//! `network.xml`.
+//!
+//! MATSim nodes and links carry string ids; the `RoadNetwork` numbers them
+//! from 0, so the ids are kept alongside to resolve the link references in
+//! population files and to write them back out.
+//!
+//! | MATSim link  | `RoadNetwork`                                         |
+//! |--------------|-------------------------------------------------------|
+//! | `length`     | `edge_length_m`                                       |
+//! | `freespeed`  | `edge_travel_ms` = `length / freespeed` in ms         |
+//! | `capacity`   | `edge_capacity_vph` and [`MatsimNetwork::capacities`] |
+//! | `permlanes`  | [`MatsimNetwork::lanes`]                              |
+//! | `modes`      | links without `car` are skipped                       |
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use dt_core::{EdgeId, GeoPoint, LocalProjection, NodeId};
+use dt_spatial::{RoadNetwork, RoadNetworkBuilder};
+
+use crate::xml::{self, Attrs};
+use crate::{MatsimError, MatsimResult};
+
+// ── Coords ────────────────────────────────────────────────────────────────────
+
+/// How a MATSim file's `x`/`y` coordinates map to latitude and longitude.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Coords {
+    /// `x` is longitude and `y` latitude, in degrees (EPSG:4326).
+    Wgs84,
+    /// Metres in a projected system such as UTM, with the file's `(x, y)`
+    /// at `origin`.  Other points are placed by a [`LocalProjection`]
+    /// around it, which is good to a few metres across a city.
+    Metres { x: f64, y: f64, origin: GeoPoint },
+}
+
+impl Coords {
+    pub fn to_geo(&self, x: f64, y: f64) -> GeoPoint {
+        match *self {
+            Coords::Wgs84 => GeoPoint::new(y as f32, x as f32),
+            Coords::Metres { x: x0, y: y0, origin } => LocalProjection::new(origin).unproject(x - x0, y - y0),
+        }
+    }
+
+    pub fn from_geo(&self, p: GeoPoint) -> (f64, f64) {
+        match *self {
+            Coords::Wgs84 => (p.lon as f64, p.lat as f64),
+            Coords::Metres { x: x0, y: y0, origin } => {
+                let (x, y) = LocalProjection::new(origin).project(p);
+                (x0 + x, y0 + y)
+            }
+        }
+    }
+}
+
+// ── MatsimNetwork ─────────────────────────────────────────────────────────────
+
+/// A road network with MATSim's ids and link attributes.
+///
+/// ```rust,ignore
+/// let matsim = MatsimNetwork::read(Path::new("network.xml.gz"), Coords::Metres {
+///     x: 683_000.0, y: 5_333_000.0, origin: GeoPoint::new(48.137, 11.575),
+/// })?;
+/// let capacity = Capacity::PerEdge(matsim.capacities.clone());  // dt-traffic
+/// let network = matsim.network.clone();
+/// ```
+#[derive(Clone)]
+pub struct MatsimNetwork {
+    pub network:    RoadNetwork,
+    pub coords:     Coords,
+    /// Vehicles per hour, by `EdgeId`.
+    pub capacities: Vec<f64>,
+    /// Lanes, by `EdgeId`.
+    pub lanes:      Vec<f64>,
+    node_ids:       Vec<String>,
+    link_ids:       Vec<String>,
+    node_index:     HashMap<String, NodeId>,
+    link_index:     HashMap<String, EdgeId>,
+}
+
+/// A link as read, before the network assigns its `EdgeId`.
+struct Link {
+    id:        String,
+    from:      NodeId,
+    to:        NodeId,
+    length_m:  f32,
+    travel_ms: u32,
+    capacity:  f64,
+    lanes:     f64,
+}
+
+impl MatsimNetwork {
+    /// Wrap a network that didn't come from MATSim: node and link ids are
+    /// the `NodeId`s and `EdgeId`s, with one lane of the network's
+    /// `edge_capacity_vph` per edge.
+    pub fn from_network(network: RoadNetwork, coords: Coords) -> Self {
+        let node_ids = (0..network.node_count()).map(|n| n.to_string()).collect();
+        let link_ids = (0..network.edge_count()).map(|e| e.to_string()).collect();
+        let capacities = network.edge_capacity_vph.iter().map(|&c| c as f64).collect();
+        let edges = network.edge_count();
+        Self::with_ids(network, coords, node_ids, link_ids, capacities, vec![1.0; edges])
+    }
+
+    fn with_ids(
+        network:    RoadNetwork,
+        coords:     Coords,
+        node_ids:   Vec<String>,
+        link_ids:   Vec<String>,
+        capacities: Vec<f64>,
+        lanes:      Vec<f64>,
+    ) -> Self {
+        let node_index = node_ids.iter().enumerate().map(|(n, id)| (id.clone(), NodeId(n as u32))).collect();
+        let link_index = link_ids.iter().enumerate().map(|(e, id)| (id.clone(), EdgeId(e as u32))).collect();
+        Self { network, coords, capacities, lanes, node_ids, link_ids, node_index, link_index }
+    }
+
+    /// Read `path` (gzipped if it ends in `.gz`).
+    pub fn read(path: &Path, coords: Coords) -> MatsimResult<Self> {
+        Self::read_from(xml::open(path)?, coords)
+    }
+
+    /// Read a network file's XML from `input`.
+    pub fn read_from(input: impl BufRead, coords: Coords) -> MatsimResult<Self> {
+        let mut reader = Reader::from_reader(input);
+        let mut buf = Vec::new();
+        let mut b = RoadNetworkBuilder::new();
+        let (mut node_ids, mut node_index) = (Vec::new(), HashMap::new());
+        let mut links = Vec::new();
+        // Capacities are per `capperiod`, an hour unless stated.
+        let mut per_hour = 1.0;
+        loop {
+            let attrs = match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e) | Event::Empty(e)) => Attrs::new(&reader, &e)?,
+                Ok(Event::Eof) => break,
+                Ok(_) => {
+                    buf.clear();
+                    continue;
+                }
+                Err(e) => return Err(xml::xml_error(&reader, e)),
+            };
+            buf.clear();
+            match attrs.element() {
+                "node" => {
+                    let id = attrs.require("id")?.to_owned();
+                    let (x, y) = (attrs.parse("x")?, attrs.parse("y")?);
+                    let (Some(x), Some(y)) = (x, y) else {
+                        return Err(MatsimError::Format(format!("node {id} without coordinates")));
+                    };
+                    let node = b.add_node(coords.to_geo(x, y));
+                    if node_index.insert(id.clone(), node).is_some() {
+                        return Err(MatsimError::Format(format!("duplicate node id {id}")));
+                    }
+                    node_ids.push(id);
+                }
+                "links" => {
+                    if let Some(period) = attrs.time("capperiod")?.filter(|&secs| secs > 0) {
+                        per_hour = 3600.0 / period as f64;
+                    }
+                }
+                "link" => {
+                    let link = read_link(&attrs, &node_index, per_hour)?;
+                    if let Some(link) = link {
+                        b.add_directed_edge_with_capacity(link.from, link.to, link.length_m, link.travel_ms, link.capacity as f32);
+                        links.push(link);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // The builder sorts edges by source node; it tells us where each link went.
+        let (network, edges) = b.build_with_edge_ids();
+        let mut link_of_edge = vec![0; network.edge_count()];
+        for (l, edge) in edges.iter().enumerate() {
+            link_of_edge[edge.index()] = l;
+        }
+        let mut link_ids = Vec::with_capacity(links.len());
+        let (mut capacities, mut lanes) = (Vec::with_capacity(links.len()), Vec::with_capacity(links.len()));
+        for &l in &link_of_edge {
+            link_ids.push(std::mem::take(&mut links[l].id));
+            capacities.push(links[l].capacity);
+            lanes.push(links[l].lanes);
+        }
+        let matsim = Self::with_ids(network, coords, node_ids, link_ids, capacities, lanes);
+        if matsim.link_index.len() < matsim.link_ids.len() {
+            return Err(MatsimError::Format("duplicate link ids".into()));
+        }
+        Ok(matsim)
+    }
+
+    /// The node with MATSim id `id`.
+    pub fn node(&self, id: &str) -> Option<NodeId> {
+        self.node_index.get(id).copied()
+    }
+
+    /// The edge with MATSim link id `id`; `None` for unknown ids and links
+    /// that were skipped.
+    pub fn link(&self, id: &str) -> Option<EdgeId> {
+        self.link_index.get(id).copied()
+    }
+
+    pub fn node_id(&self, node: NodeId) -> &str {
+        &self.node_ids[node.index()]
+    }
+
+    pub fn link_id(&self, edge: EdgeId) -> &str {
+        &self.link_ids[edge.index()]
+    }
+
+    /// Write as a `network_v2` file (gzipped if `path` ends in `.gz`).
+    pub fn write(&self, path: &Path) -> MatsimResult<()> {
+        xml::save(path, |w| self.write_to(w))
+    }
+
+    /// Write the `network_v2` XML to `w`.  Closed edges (`u32::MAX`) are
+    /// left out: MATSim has no closed links.
+    pub fn write_to(&self, w: &mut dyn Write) -> MatsimResult<()> {
+        self.write_xml(w).map_err(MatsimError::Write)
+    }
+
+    fn write_xml(&self, w: &mut dyn Write) -> std::io::Result<()> {
+        let net = &self.network;
+        writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(w, r#"<!DOCTYPE network SYSTEM "http://www.matsim.org/files/dtd/network_v2.dtd">"#)?;
+        writeln!(w, "<network>\n\t<nodes>")?;
+        for (n, &pos) in net.node_pos.iter().enumerate() {
+            let (x, y) = self.coords.from_geo(pos);
+            writeln!(w, r#"		<node id="{}" x="{x}" y="{y}"/>"#, xml::escape(&self.node_ids[n]))?;
+        }
+        writeln!(w, "\t</nodes>\n\t<links capperiod=\"01:00:00\">")?;
+        for e in 0..net.edge_count() {
+            let (length, ms) = (net.edge_length_m[e], net.edge_travel_ms[e]);
+            if ms == u32::MAX {
+                continue;
+            }
+            write!(
+                w,
+                r#"		<link id="{}" from="{}" to="{}" "#,
+                xml::escape(&self.link_ids[e]),
+                xml::escape(&self.node_ids[net.edge_from[e].index()]),
+                xml::escape(&self.node_ids[net.edge_to[e].index()]),
+            )?;
+            writeln!(
+                w,
+                r#"length="{length}" freespeed="{}" capacity="{}" permlanes="{}" modes="car"/>"#,
+                length as f64 / (ms.max(1) as f64 / 1000.0),
+                self.capacities[e],
+                self.lanes[e],
+            )?;
+        }
+        writeln!(w, "\t</links>\n</network>")
+    }
+}
+
+/// One `<link>`, or `None` if cars can't use it.
+fn read_link(attrs: &Attrs, nodes: &HashMap<String, NodeId>, per_hour: f64) -> MatsimResult<Option<Link>> {
+    if attrs.get("modes").is_some_and(|modes| !modes.split(',').any(|m| m.trim() == "car")) {
+        return Ok(None);
+    }
+    let id = attrs.require("id")?.to_owned();
+    let node = |name: &str| -> MatsimResult<NodeId> {
+        let node = attrs.require(name)?;
+        nodes.get(node).copied().ok_or_else(|| MatsimError::Format(format!("link {id}: unknown {name} node {node}")))
+    };
+    let (from, to) = (node("from")?, node("to")?);
+    let invalid = |what: &str| MatsimError::Format(format!("link {id}: invalid or missing {what}"));
+    let length_m: f64 = attrs.parse("length")?.filter(|l: &f64| l.is_finite() && *l >= 0.0).ok_or_else(|| invalid("length"))?;
+    let freespeed: f64 = attrs.parse("freespeed")?.ok_or_else(|| invalid("freespeed"))?;
+    let travel_ms = match length_m {
+        0.0 => 0,
+        _ if freespeed > 0.0 => (length_m / freespeed * 1000.0).round().min(u32::MAX as f64 - 1.0) as u32,
+        _ => return Err(invalid("freespeed")),
+    };
+    let capacity = attrs.parse::<f64>("capacity")?.ok_or_else(|| invalid("capacity"))? * per_hour;
+    let lanes = attrs.parse("permlanes")?.unwrap_or(1.0);
+    Ok(Some(Link { id, from, to, length_m: length_m as f32, travel_ms, capacity, lanes }))
+}
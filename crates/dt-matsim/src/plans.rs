@@ -0,0 +1,398 @@
+//! MATSim populations: `plans.xml` to and from `ActivityPlan`s.
+//!
+//! A MATSim plan is a day of activities joined by legs, each activity
+//! ending at an `end_time` (or after a `max_dur`).  An `ActivityPlan` is a
+//! cycle of activities starting at tick offsets, the agent leaving for each
+//! at its start.  Reading maps every activity after the first to one
+//! starting when the previous one ends; when the day ends where it began
+//! (same type, same place) the first and last activities are one that
+//! spans midnight, as in dt-cli's daily plans.
+//!
+//! Activities are placed at the `to` node of their `link`, or failing
+//! that, at the node nearest their `x`/`y`.
+
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use quick_xml::Reader;
+use quick_xml::events::Event;
+
+use dt_core::{ActivityId, EdgeId, NodeId, TransportMode};
+use dt_schedule::{ActivityPlan, Destination, ScheduledActivity};
+
+use crate::xml::{self, Attrs};
+use crate::{MatsimError, MatsimNetwork, MatsimResult};
+
+const SECS_PER_DAY: u32 = 86_400;
+
+/// `mode` as a `TransportMode`; `None` for modes with no counterpart.
+pub fn parse_mode(mode: &str) -> TransportMode {
+    match mode {
+        "car" | "ride" => TransportMode::Car,
+        "walk" | "transit_walk" | "non_network_walk" => TransportMode::Walk,
+        "bike" | "bicycle" => TransportMode::Bike,
+        "pt" | "bus" | "tram" | "rail" | "train" | "subway" | "ferry" => TransportMode::Transit,
+        _ => TransportMode::None,
+    }
+}
+
+/// The MATSim leg mode for `mode`: `pt` for transit, `car` for `None`.
+pub fn mode_name(mode: TransportMode) -> &'static str {
+    match mode {
+        TransportMode::Walk    => "walk",
+        TransportMode::Bike    => "bike",
+        TransportMode::Transit => "pt",
+        _                      => "car",
+    }
+}
+
+// ── MatsimPlans ───────────────────────────────────────────────────────────────
+
+/// A population: one plan per agent, with MATSim's person ids and names
+/// for the activity types.  Every `Vec` is indexed by `AgentId`, except
+/// `activity_types`, which is indexed by `ActivityId`.
+#[derive(Debug, Clone)]
+pub struct MatsimPlans {
+    pub plans:          Vec<ActivityPlan>,
+    pub person_ids:     Vec<String>,
+    pub activity_types: Vec<String>,
+    /// Mode of the first leg; `None` without legs or for an unknown mode.
+    pub modes:          Vec<TransportMode>,
+    /// Where `Destination::Home` leads; read as the first activity's node.
+    pub homes:          Vec<Option<NodeId>>,
+    /// Where `Destination::Work` leads; never set by reading.
+    pub works:          Vec<Option<NodeId>>,
+}
+
+impl MatsimPlans {
+    /// Plans with person ids `0`, `1`, …, travelling by car, with no home
+    /// or work nodes and activity types named by their ids.
+    pub fn new(plans: Vec<ActivityPlan>) -> Self {
+        let agents = plans.len();
+        Self {
+            plans,
+            person_ids:     (0..agents).map(|a| a.to_string()).collect(),
+            activity_types: Vec::new(),
+            modes:          vec![TransportMode::Car; agents],
+            homes:          vec![None; agents],
+            works:          vec![None; agents],
+        }
+    }
+
+    fn activity_type(&self, id: ActivityId) -> String {
+        self.activity_types.get(id.0 as usize).cloned().unwrap_or_else(|| id.0.to_string())
+    }
+
+    /// Write as a `population_v6` file (gzipped if `path` ends in `.gz`).
+    ///
+    /// See [`write_to`][Self::write_to].
+    pub fn write(&self, path: &Path, network: &MatsimNetwork, tick_duration_secs: u32) -> MatsimResult<()> {
+        xml::save(path, |w| self.write_to(w, network, tick_duration_secs))
+    }
+
+    /// Write the `population_v6` XML to `w`: each plan's cycle as one day,
+    /// starting with the activity in progress at offset 0.
+    ///
+    /// Activities are placed on a link into their node.  Agents with empty
+    /// plans are left out, as MATSim requires an activity; `Home` and
+    /// `Work` destinations must have a node in `homes` or `works`.
+    pub fn write_to(&self, w: &mut dyn Write, network: &MatsimNetwork, tick_duration_secs: u32) -> MatsimResult<()> {
+        let net = &network.network;
+        let mut into = vec![None::<EdgeId>; net.node_count()];
+        for e in (0..net.edge_count()).rev() {
+            if net.edge_travel_ms[e] != u32::MAX {
+                into[net.edge_to[e].index()] = Some(EdgeId(e as u32));
+            }
+        }
+        let io = MatsimError::Write;
+        writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#).map_err(io)?;
+        writeln!(w, r#"<!DOCTYPE population SYSTEM "http://www.matsim.org/files/dtd/population_v6.dtd">"#)
+            .map_err(io)?;
+        writeln!(w, "<population>").map_err(io)?;
+        for (agent, plan) in self.plans.iter().enumerate() {
+            if plan.is_empty() {
+                continue;
+            }
+            let person = &self.person_ids[agent];
+            let node = |destination: &Destination| -> MatsimResult<NodeId> {
+                let resolved = match destination {
+                    Destination::Node(node) => Some(*node),
+                    Destination::Home => self.homes.get(agent).copied().flatten(),
+                    Destination::Work => self.works.get(agent).copied().flatten(),
+                };
+                resolved.filter(|n| n.index() < net.node_count()).ok_or_else(|| {
+                    MatsimError::Format(format!("person {person}: no node for destination {destination:?}"))
+                })
+            };
+            let activities = plan.activities();
+            // The day starts in the activity in progress at offset 0; if that
+            // began the previous day, it is also the day's last.
+            let wraps = activities[0].start_offset_ticks > 0;
+            let mut day: Vec<(&ScheduledActivity, Option<u32>)> = Vec::with_capacity(activities.len() + 1);
+            if wraps {
+                day.push((&activities[activities.len() - 1], Some(activities[0].start_offset_ticks)));
+            }
+            for (i, activity) in activities.iter().enumerate() {
+                let end = activities.get(i + 1).map(|next| next.start_offset_ticks);
+                day.push((activity, end));
+            }
+
+            writeln!(w, "\t<person id=\"{}\">\n\t\t<plan selected=\"yes\">", xml::escape(person)).map_err(io)?;
+            let mode = mode_name(self.modes.get(agent).copied().unwrap_or(TransportMode::Car));
+            for (i, (activity, end)) in day.iter().enumerate() {
+                if i > 0 {
+                    writeln!(w, "\t\t\t<leg mode=\"{mode}\"/>").map_err(io)?;
+                }
+                let node = node(&activity.destination)?;
+                let (x, y) = network.coords.from_geo(net.node_pos[node.index()]);
+                let kind = xml::escape(&self.activity_type(activity.activity_id)).into_owned();
+                write!(w, "\t\t\t<activity type=\"{kind}\"").map_err(io)?;
+                if let Some(link) = into[node.index()] {
+                    write!(w, " link=\"{}\"", xml::escape(network.link_id(link))).map_err(io)?;
+                }
+                write!(w, " x=\"{x}\" y=\"{y}\"").map_err(io)?;
+                if let Some(end) = end {
+                    let secs = *end as u64 * tick_duration_secs as u64;
+                    write!(w, " end_time=\"{}\"", xml::format_time(secs)).map_err(io)?;
+                }
+                writeln!(w, "/>").map_err(io)?;
+            }
+            writeln!(w, "\t\t</plan>\n\t</person>").map_err(io)?;
+        }
+        writeln!(w, "</population>").map_err(io)
+    }
+}
+
+// ── PlanReader ────────────────────────────────────────────────────────────────
+
+/// Reads a MATSim population against a [`MatsimNetwork`].
+///
+/// Each person's selected plan (or first, if none is marked) becomes agent
+/// `i`'s `ActivityPlan`, persons numbered in file order.  Times are rounded
+/// down to whole ticks and wrap into the cycle.
+///
+/// ```rust,ignore
+/// let population = PlanReader::new(&matsim, 900)
+///     .activity_types(["home", "work"])  // ActivityId 0 and 1
+///     .read(Path::new("plans.xml.gz"))?;
+/// ```
+pub struct PlanReader<'n> {
+    network:            &'n MatsimNetwork,
+    tick_duration_secs: u32,
+    cycle_secs:         u32,
+    activity_types:     Vec<String>,
+}
+
+/// One `<activity>` as read.
+struct Act {
+    kind: ActivityId,
+    node: NodeId,
+    end:  Option<u32>,
+    dur:  Option<u32>,
+}
+
+/// The person being read.
+#[derive(Default)]
+struct Person {
+    id:       String,
+    acts:     Vec<Act>,
+    mode:     Option<TransportMode>,
+    plans:    usize,
+    selected: bool,
+    /// Whether activities go into `acts`: inside the selected plan, or
+    /// the first while none is selected.
+    reading:  bool,
+}
+
+impl<'n> PlanReader<'n> {
+    /// Ticks of `tick_duration_secs` seconds and a one-day cycle.
+    pub fn new(network: &'n MatsimNetwork, tick_duration_secs: u32) -> Self {
+        Self { network, tick_duration_secs, cycle_secs: SECS_PER_DAY, activity_types: Vec::new() }
+    }
+
+    /// Length of every plan's cycle; a whole number of ticks.
+    pub fn cycle_secs(mut self, secs: u32) -> Self {
+        self.cycle_secs = secs;
+        self
+    }
+
+    /// Fix the first `ActivityId`s to these types; others are numbered
+    /// after them as they're first seen.
+    pub fn activity_types<S: Into<String>>(mut self, types: impl IntoIterator<Item = S>) -> Self {
+        self.activity_types = types.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Read `path` (gzipped if it ends in `.gz`).
+    pub fn read(&self, path: &Path) -> MatsimResult<MatsimPlans> {
+        self.read_from(xml::open(path)?)
+    }
+
+    /// Read a population file's XML from `input`.
+    pub fn read_from(&self, input: impl BufRead) -> MatsimResult<MatsimPlans> {
+        let tick = self.tick_duration_secs;
+        if tick == 0 || self.cycle_secs == 0 || !self.cycle_secs.is_multiple_of(tick) {
+            return Err(MatsimError::Format(format!(
+                "a cycle of {} s is not a whole number of {tick} s ticks",
+                self.cycle_secs
+            )));
+        }
+        let mut out = MatsimPlans::new(Vec::new());
+        out.activity_types = self.activity_types.clone();
+        let mut types: HashMap<String, ActivityId> = HashMap::new();
+        for (i, kind) in out.activity_types.iter().enumerate() {
+            types.entry(kind.clone()).or_insert(ActivityId(i as u16));
+        }
+
+        let mut reader = Reader::from_reader(input);
+        let mut buf = Vec::new();
+        let mut person: Option<Person> = None;
+        loop {
+            let (attrs, empty) = match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) => (Attrs::new(&reader, &e)?, false),
+                Ok(Event::Empty(e)) => (Attrs::new(&reader, &e)?, true),
+                Ok(Event::End(e)) => {
+                    match e.name().as_ref() {
+                        b"person" => {
+                            if let Some(p) = person.take() {
+                                self.finish(p, &mut out)?;
+                            }
+                        }
+                        b"plan" => {
+                            if let Some(p) = person.as_mut() {
+                                p.reading = false;
+                            }
+                        }
+                        _ => {}
+                    }
+                    buf.clear();
+                    continue;
+                }
+                Ok(Event::Eof) => break,
+                Ok(_) => {
+                    buf.clear();
+                    continue;
+                }
+                Err(e) => return Err(xml::xml_error(&reader, e)),
+            };
+            buf.clear();
+            match (attrs.element(), person.as_mut()) {
+                ("person", _) => {
+                    let p = Person { id: attrs.require("id")?.to_owned(), ..Default::default() };
+                    match empty {
+                        true => self.finish(p, &mut out)?,
+                        false => person = Some(p),
+                    }
+                }
+                ("plan", Some(p)) => {
+                    let selected = attrs.get("selected") == Some("yes");
+                    p.reading = !p.selected && (selected || p.plans == 0);
+                    if p.reading {
+                        p.acts.clear();
+                        p.mode = None;
+                    }
+                    p.selected |= selected;
+                    p.plans += 1;
+                    if empty {
+                        p.reading = false;
+                    }
+                }
+                ("activity" | "act", Some(p)) if p.reading => {
+                    let next = ActivityId(types.len() as u16);
+                    let kind = attrs.require("type")?;
+                    let kind = match types.get(kind) {
+                        Some(&id) => id,
+                        None if types.len() <= u16::MAX as usize => {
+                            types.insert(kind.to_owned(), next);
+                            out.activity_types.push(kind.to_owned());
+                            next
+                        }
+                        None => return Err(MatsimError::Format("more than 65536 activity types".into())),
+                    };
+                    let node = self.locate(&attrs, &p.id)?;
+                    let dur = match attrs.time("max_dur")? {
+                        Some(dur) => Some(dur),
+                        None => attrs.time("dur")?,
+                    };
+                    p.acts.push(Act { kind, node, end: attrs.time("end_time")?, dur });
+                }
+                ("leg", Some(p)) if p.reading && p.mode.is_none() => {
+                    p.mode = Some(parse_mode(attrs.get("mode").unwrap_or("")));
+                }
+                _ => {}
+            }
+        }
+        Ok(out)
+    }
+
+    /// The node an activity takes place at.
+    fn locate(&self, attrs: &Attrs, person: &str) -> MatsimResult<NodeId> {
+        let net = &self.network.network;
+        if let Some(edge) = attrs.get("link").and_then(|link| self.network.link(link)) {
+            return Ok(net.edge_to[edge.index()]);
+        }
+        let (x, y) = (attrs.parse("x")?, attrs.parse("y")?);
+        let (Some(x), Some(y)) = (x, y) else {
+            return Err(MatsimError::Format(format!(
+                "person {person}: activity with neither a known link nor coordinates"
+            )));
+        };
+        net.snap_to_node(self.network.coords.to_geo(x, y))
+            .ok_or_else(|| MatsimError::Format(format!("person {person}: activity but the network has no nodes")))
+    }
+
+    /// Convert `person`'s activities and add them to `out`.
+    fn finish(&self, person: Person, out: &mut MatsimPlans) -> MatsimResult<()> {
+        let plan = self.plan(&person)?;
+        out.homes.push(person.acts.first().map(|a| a.node));
+        out.works.push(None);
+        out.modes.push(person.mode.unwrap_or(TransportMode::None));
+        out.person_ids.push(person.id);
+        out.plans.push(plan);
+        Ok(())
+    }
+
+    fn plan(&self, person: &Person) -> MatsimResult<ActivityPlan> {
+        let acts = &person.acts;
+        let n = acts.len();
+        if n == 0 {
+            return Ok(ActivityPlan::empty());
+        }
+        let tick = self.tick_duration_secs;
+        let cycle = self.cycle_secs / tick;
+        // When each activity starts, in ticks: when the one before ends.
+        let mut starts = vec![0u32; n];
+        for i in 0..n - 1 {
+            let end = match (acts[i].end, acts[i].dur) {
+                (Some(end), _) => end / tick,
+                (None, Some(dur)) => starts[i] + dur / tick,
+                (None, None) => {
+                    return Err(MatsimError::Format(format!(
+                        "person {}: activity {i} has neither end_time nor max_dur",
+                        person.id
+                    )));
+                }
+            };
+            starts[i + 1] = end.max(starts[i]);
+        }
+        let spans_midnight = n > 1 && acts[0].kind == acts[n - 1].kind && acts[0].node == acts[n - 1].node;
+        let first = usize::from(spans_midnight);
+        let activities = (first..n)
+            .map(|i| {
+                let end = match (starts.get(i + 1), spans_midnight) {
+                    (Some(&next), _) => next,
+                    (None, true) => cycle + starts[1],
+                    (None, false) => cycle.max(starts[i]),
+                };
+                ScheduledActivity {
+                    start_offset_ticks: starts[i] % cycle,
+                    duration_ticks:     end.saturating_sub(starts[i]),
+                    activity_id:        acts[i].kind,
+                    destination:        Destination::Node(acts[i].node),
+                }
+            })
+            .collect();
+        Ok(ActivityPlan::new(activities, cycle))
+    }
+}
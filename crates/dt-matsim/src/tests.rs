@@ -0,0 +1,276 @@
+//! Unit tests for dt-matsim.
+
+use dt_core::{ActivityId, GeoPoint, NodeId, TransportMode};
+use dt_schedule::{ActivityPlan, Destination, ScheduledActivity};
+use dt_spatial::RoadNetworkBuilder;
+
+use crate::{Coords, MatsimError, MatsimNetwork, MatsimPlans, PlanReader};
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// Three nodes; two car links between `a` and `b`, a transit-only one
+/// from `b` to `c`.  Capacities are per 12 hours.
+const NETWORK: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE network SYSTEM "http://www.matsim.org/files/dtd/network_v2.dtd">
+<network name="test">
+    <attributes><attribute name="coordinateReferenceSystem" class="java.lang.String">EPSG:4326</attribute></attributes>
+    <nodes>
+        <node id="a" x="10.0" y="50.0"/>
+        <node id="b" x="10.01" y="50.0"/>
+        <node id="c" x="10.02" y="50.0"/>
+    </nodes>
+    <links capperiod="12:00:00">
+        <link id="ab" from="a" to="b" length="700.0" freespeed="14.0" capacity="12000" permlanes="2" modes="car,bike"/>
+        <link id="ba" from="b" to="a" length="700.0" freespeed="10.0" capacity="6000"/>
+        <link id="bc" from="b" to="c" length="700.0" freespeed="20.0" capacity="6000" modes="pt"/>
+    </links>
+</network>
+"#;
+
+fn network() -> MatsimNetwork {
+    MatsimNetwork::read_from(NETWORK.as_bytes(), Coords::Wgs84).unwrap()
+}
+
+fn activity(start: u32, duration: u32, id: u16, destination: Destination) -> ScheduledActivity {
+    ScheduledActivity { start_offset_ticks: start, duration_ticks: duration, activity_id: ActivityId(id), destination }
+}
+
+/// `(start, duration, activity, node)` of each activity.
+fn summary(plan: &ActivityPlan) -> Vec<(u32, u32, u16, Option<u32>)> {
+    plan.activities()
+        .iter()
+        .map(|a| (a.start_offset_ticks, a.duration_ticks, a.activity_id.0, a.destination.node_id().map(|n| n.0)))
+        .collect()
+}
+
+// ── Network ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod network_tests {
+    use super::*;
+
+    #[test]
+    fn reads_car_links() {
+        let matsim = network();
+        let net = &matsim.network;
+        assert_eq!((net.node_count(), net.edge_count()), (3, 2));
+        let (a, b) = (matsim.node("a").unwrap(), matsim.node("b").unwrap());
+        assert_eq!(matsim.node_id(b), "b");
+        assert_eq!(net.node_pos[a.index()], GeoPoint::new(50.0, 10.0));
+        assert!(matsim.link("bc").is_none());
+
+        let ab = matsim.link("ab").unwrap();
+        assert_eq!((net.edge_from[ab.index()], net.edge_to[ab.index()]), (a, b));
+        assert_eq!(matsim.link_id(ab), "ab");
+        assert_eq!(net.edge_travel_ms[ab.index()], 50_000);
+        assert_eq!((matsim.capacities[ab.index()], matsim.lanes[ab.index()]), (1000.0, 2.0));
+        let ba = matsim.link("ba").unwrap();
+        assert_eq!(net.edge_travel_ms[ba.index()], 70_000);
+        assert_eq!((matsim.capacities[ba.index()], matsim.lanes[ba.index()]), (500.0, 1.0));
+    }
+
+    #[test]
+    fn round_trips_through_gzip() {
+        let origin = GeoPoint::new(48.0, 11.0);
+        let coords = Coords::Metres { x: 690_000.0, y: 5_320_000.0, origin };
+        let mut b = RoadNetworkBuilder::new();
+        let nodes: Vec<_> = (0..3).map(|i| b.add_node(GeoPoint::new(48.0 + i as f32 * 0.01, 11.0))).collect();
+        b.add_road(nodes[0], nodes[1], 1112.5, 80_000);
+        b.add_directed_edge(nodes[1], nodes[2], 1112.5, 0);
+        b.add_directed_edge(nodes[2], nodes[0], 2225.0, u32::MAX);
+        let mut original = MatsimNetwork::from_network(b.build(), coords);
+        original.capacities[0] = 900.0;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("network.xml.gz");
+        original.write(&path).unwrap();
+        let read = MatsimNetwork::read(&path, coords).unwrap();
+
+        let (before, after) = (&original.network, &read.network);
+        assert_eq!(after.node_count(), 3);
+        // The closed edge is left out.
+        assert_eq!(after.edge_count(), 3);
+        assert!(read.link("3").is_none());
+        for e in 0..3 {
+            let edge = read.link(&e.to_string()).unwrap().index();
+            assert_eq!(after.edge_length_m[edge], before.edge_length_m[e]);
+            assert_eq!(after.edge_travel_ms[edge], before.edge_travel_ms[e].max(1));
+            assert_eq!(read.capacities[edge], original.capacities[e]);
+            assert_eq!(after.edge_capacity_vph[edge] as f64, read.capacities[edge]);
+        }
+        for (p, q) in before.node_pos.iter().zip(&after.node_pos) {
+            assert!((p.lat - q.lat).abs() < 1e-5 && (p.lon - q.lon).abs() < 1e-5, "{p:?} → {q:?}");
+        }
+        let (x, y) = coords.from_geo(origin);
+        assert_eq!((x, y), (690_000.0, 5_320_000.0));
+    }
+
+    #[test]
+    fn parallel_links_keep_their_ids() {
+        // Identical geometry, told apart only by capacity; links from `b`
+        // are interleaved so the builder has to reorder.
+        let mut xml = String::from(r#"<network><nodes><node id="a" x="0" y="0"/><node id="b" x="1" y="0"/></nodes><links>"#);
+        for i in 0..20 {
+            let (from, to) = if i % 3 == 0 { ("b", "a") } else { ("a", "b") };
+            xml += &format!(r#"<link id="l{i}" from="{from}" to="{to}" length="5" freespeed="1" capacity="{i}"/>"#);
+        }
+        xml += "</links></network>";
+        let matsim = MatsimNetwork::read_from(xml.as_bytes(), Coords::Wgs84).unwrap();
+        assert_eq!(matsim.network.edge_count(), 20);
+        for i in 0..20 {
+            let edge = matsim.link(&format!("l{i}")).unwrap();
+            assert_eq!(matsim.link_id(edge), format!("l{i}"));
+            assert_eq!(matsim.capacities[edge.index()], i as f64);
+            assert_eq!(matsim.network.edge_capacity_vph[edge.index()], i as f32);
+        }
+    }
+
+    #[test]
+    fn invalid_networks() {
+        let format = |xml: &str| {
+            matches!(MatsimNetwork::read_from(xml.as_bytes(), Coords::Wgs84), Err(MatsimError::Format(_)))
+        };
+        let nodes = r#"<network><nodes><node id="a" x="0" y="0"/><node id="b" x="1" y="0"/></nodes><links>"#;
+        assert!(format(&format!(r#"{nodes}<link id="l" from="a" to="z" length="1" freespeed="1" capacity="1"/>"#)));
+        assert!(format(&format!(r#"{nodes}<link id="l" from="a" to="b" length="1" capacity="1"/>"#)));
+        assert!(format(&format!(r#"{nodes}<link id="l" from="a" to="b" length="1" freespeed="0" capacity="1"/>"#)));
+        assert!(format(&format!(r#"{nodes}<link id="l" from="a" to="b" length="NaN" freespeed="1" capacity="1"/>"#)));
+        assert!(format(&format!(r#"{nodes}<link id="l" from="a" to="b" length="inf" freespeed="1" capacity="1"/>"#)));
+        assert!(format(&format!(r#"{nodes}<link id="l" from="a" to="b" length="-1" freespeed="1" capacity="1"/>"#)));
+        assert!(format(r#"<network><nodes><node id="a" x="0" y="0"/><node id="a" x="1" y="0"/></nodes></network>"#));
+        assert!(format(r#"<network><nodes><node id="a" x="east" y="0"/></nodes></network>"#));
+        let malformed = MatsimNetwork::read_from(&b"<network><nodes></links>"[..], Coords::Wgs84);
+        assert!(matches!(malformed, Err(MatsimError::Xml { .. })));
+    }
+}
+
+// ── Plans ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod plans_tests {
+    use super::*;
+    use crate::xml::{format_time, parse_time};
+
+    /// `p1` selects its second plan; `p2` uses v4 `act`/`dur`; `p3` has none.
+    const PLANS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE population SYSTEM "http://www.matsim.org/files/dtd/population_v6.dtd">
+<population>
+    <person id="p1">
+        <attributes><attribute name="age" class="java.lang.Integer">40</attribute></attributes>
+        <plan score="1.0">
+            <activity type="home" link="ab" end_time="06:00:00"/>
+            <leg mode="walk"/>
+            <activity type="home" link="ab"/>
+        </plan>
+        <plan selected="yes">
+            <activity type="home" link="ab" x="10.01" y="50.0" end_time="07:30:00"/>
+            <leg mode="car"><route type="links">ab</route></leg>
+            <activity type="work" x="10.0001" y="50.0" end_time="17:00:00"/>
+            <leg mode="car"/>
+            <activity type="home" link="ab"/>
+        </plan>
+    </person>
+    <person id="p2">
+        <plan>
+            <act type="shop" link="ba" dur="01:00"/>
+            <leg mode="walk"/>
+            <act type="home" link="ab"/>
+        </plan>
+    </person>
+    <person id="p3"/>
+</population>
+"#;
+
+    #[test]
+    fn reads_selected_plans() {
+        let matsim = network();
+        let (a, b) = (matsim.node("a").unwrap().0, matsim.node("b").unwrap().0);
+        let population = PlanReader::new(&matsim, 900).activity_types(["work"]).read_from(PLANS.as_bytes()).unwrap();
+        assert_eq!(population.person_ids, ["p1", "p2", "p3"]);
+        assert_eq!(population.activity_types, ["work", "home", "shop"]);
+        assert_eq!(population.modes, [TransportMode::Car, TransportMode::Walk, TransportMode::None]);
+        assert_eq!(population.homes, [Some(NodeId(b)), Some(NodeId(a)), None]);
+
+        // Home spans midnight: work 07:30–17:00, then home until 07:30.
+        assert_eq!(population.plans[0].cycle_ticks, 96);
+        assert_eq!(summary(&population.plans[0]), [(30, 38, 0, Some(a)), (68, 58, 1, Some(b))]);
+        // Shopping from midnight for an hour, then home for the rest of the day.
+        assert_eq!(summary(&population.plans[1]), [(0, 4, 2, Some(a)), (4, 92, 1, Some(b))]);
+        assert!(population.plans[2].is_empty());
+    }
+
+    #[test]
+    fn plans_round_trip() {
+        let matsim = network();
+        let (a, b) = (matsim.node("a").unwrap(), matsim.node("b").unwrap());
+        let mut population = MatsimPlans::new(vec![
+            ActivityPlan::new(vec![
+                activity(8, 9, 1, Destination::Work),
+                activity(17, 2, 2, Destination::Node(a)),
+                activity(19, 13, 0, Destination::Home),
+            ], 24),
+            ActivityPlan::new(vec![activity(0, 24, 0, Destination::Node(a))], 24),
+            ActivityPlan::empty(),
+            ActivityPlan::new(vec![
+                activity(0, 10, 0, Destination::Node(b)),
+                activity(10, 14, 2, Destination::Node(a)),
+            ], 24),
+        ]);
+        population.activity_types = vec!["home".into(), "work".into()];
+        population.homes[0] = Some(b);
+        population.works[0] = Some(a);
+        population.modes[3] = TransportMode::Bike;
+
+        let mut xml = Vec::new();
+        population.write_to(&mut xml, &matsim, 3600).unwrap();
+        let xml = String::from_utf8(xml).unwrap();
+        assert!(xml.contains(r#"<activity type="2" link="ba" x="10" y="50" end_time="19:00:00"/>"#), "{xml}");
+        assert!(xml.contains(r#"<leg mode="bike"/>"#));
+
+        let read = PlanReader::new(&matsim, 3600).activity_types(["home", "work"]).read_from(xml.as_bytes()).unwrap();
+        // The empty plan is left out.
+        assert_eq!(read.person_ids, ["0", "1", "3"]);
+        assert_eq!(read.activity_types, ["home", "work", "2"]);
+        for (written, read) in [0, 1, 3].iter().zip(&read.plans) {
+            let mut expected = population.plans[*written].clone();
+            if *written == 0 {
+                expected = ActivityPlan::new(vec![
+                    activity(8, 9, 1, Destination::Node(a)),
+                    activity(17, 2, 2, Destination::Node(a)),
+                    activity(19, 13, 0, Destination::Node(b)),
+                ], 24);
+            }
+            assert_eq!(summary(read), summary(&expected));
+        }
+        assert_eq!(read.modes, [TransportMode::Car, TransportMode::None, TransportMode::Bike]);
+    }
+
+    #[test]
+    fn invalid_plans() {
+        let matsim = network();
+        let format = |reader: PlanReader<'_>, xml: &str| {
+            matches!(reader.read_from(xml.as_bytes()), Err(MatsimError::Format(_)))
+        };
+        let open_ended = r#"<population><person id="1"><plan>
+            <activity type="h" link="ab"/><leg mode="car"/><activity type="w" link="ba"/>
+        </plan></person></population>"#;
+        assert!(format(PlanReader::new(&matsim, 900), open_ended));
+        let nowhere = r#"<population><person id="1"><plan><activity type="h" link="bc"/></plan></person></population>"#;
+        assert!(format(PlanReader::new(&matsim, 900), nowhere));
+        assert!(format(PlanReader::new(&matsim, 7), "<population/>"));
+        assert!(format(PlanReader::new(&matsim, 900).cycle_secs(1000), "<population/>"));
+
+        let homeless = MatsimPlans::new(vec![ActivityPlan::new(vec![activity(0, 24, 0, Destination::Home)], 24)]);
+        assert!(matches!(homeless.write_to(&mut Vec::new(), &matsim, 3600), Err(MatsimError::Format(_))));
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(parse_time("07:30:00"), Some(27_000));
+        assert_eq!(parse_time("25:00"), Some(90_000));
+        assert_eq!(parse_time("61.5"), Some(61));
+        assert_eq!(parse_time("7:3x"), None);
+        assert_eq!(parse_time("-1"), None);
+        assert_eq!(format_time(90_061), "25:01:01");
+    }
+}
@@ -0,0 +1,139 @@
+//! Shared plumbing: gzip-aware files, attributes, and MATSim times.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use flate2::Compression;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use quick_xml::Reader;
+use quick_xml::events::BytesStart;
+
+use crate::{MatsimError, MatsimResult};
+
+// ── Files ─────────────────────────────────────────────────────────────────────
+
+fn gzipped(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "gz")
+}
+
+/// `path` for reading, decompressed if it ends in `.gz`.
+pub(crate) fn open(path: &Path) -> MatsimResult<Box<dyn BufRead>> {
+    let file = File::open(path).map_err(|source| MatsimError::Io { path: path.to_path_buf(), source })?;
+    Ok(match gzipped(path) {
+        true => Box::new(BufReader::new(MultiGzDecoder::new(file))),
+        false => Box::new(BufReader::new(file)),
+    })
+}
+
+/// Create `path` and fill it with `write`, gzipped if it ends in `.gz`.
+pub(crate) fn save(path: &Path, write: impl FnOnce(&mut dyn Write) -> MatsimResult<()>) -> MatsimResult<()> {
+    let io = |source| MatsimError::Io { path: path.to_path_buf(), source };
+    let located = |err| match err {
+        MatsimError::Write(source) => io(source),
+        other => other,
+    };
+    let mut file = BufWriter::new(File::create(path).map_err(io)?);
+    if gzipped(path) {
+        let mut gz = GzEncoder::new(file, Compression::default());
+        write(&mut gz).map_err(located)?;
+        gz.finish().map_err(io)?.flush().map_err(io)
+    } else {
+        write(&mut file).map_err(located)?;
+        file.flush().map_err(io)
+    }
+}
+
+/// XML attribute value escaping.
+pub(crate) fn escape(value: &str) -> std::borrow::Cow<'_, str> {
+    quick_xml::escape::escape(value)
+}
+
+// ── Parsing ───────────────────────────────────────────────────────────────────
+
+/// An error from `reader`, at the byte offset it occurred at.
+pub(crate) fn xml_error<R>(reader: &Reader<R>, err: quick_xml::Error) -> MatsimError {
+    MatsimError::Xml { position: reader.error_position(), message: err.to_string() }
+}
+
+/// The attributes of one element, unescaped.
+pub(crate) struct Attrs {
+    element: String,
+    values:  Vec<(String, String)>,
+}
+
+impl Attrs {
+    pub(crate) fn new<R>(reader: &Reader<R>, start: &BytesStart<'_>) -> MatsimResult<Self> {
+        let element = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+        let invalid = |err: &dyn std::fmt::Display| MatsimError::Xml {
+            position: reader.buffer_position(),
+            message:  format!("<{element}>: {err}"),
+        };
+        let values = start
+            .attributes()
+            .map(|attr| {
+                let attr = attr.map_err(|e| invalid(&e))?;
+                let value = attr.unescape_value().map_err(|e| invalid(&e))?;
+                Ok((String::from_utf8_lossy(attr.key.as_ref()).into_owned(), value.into_owned()))
+            })
+            .collect::<MatsimResult<_>>()?;
+        Ok(Self { element, values })
+    }
+
+    pub(crate) fn element(&self) -> &str {
+        &self.element
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&str> {
+        self.values.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    pub(crate) fn require(&self, name: &str) -> MatsimResult<&str> {
+        self.get(name).ok_or_else(|| MatsimError::Format(format!("<{}> without {name}", self.element)))
+    }
+
+    /// Attribute `name` parsed as a `T`; `None` if absent.
+    pub(crate) fn parse<T: FromStr>(&self, name: &str) -> MatsimResult<Option<T>> {
+        self.get(name)
+            .map(|value| {
+                value.trim().parse().map_err(|_| {
+                    MatsimError::Format(format!("<{}> has invalid {name} {value:?}", self.element))
+                })
+            })
+            .transpose()
+    }
+
+    /// Time attribute `name` in seconds; `None` if absent or `undefined`.
+    pub(crate) fn time(&self, name: &str) -> MatsimResult<Option<u32>> {
+        match self.get(name).map(str::trim) {
+            None | Some("undefined") => Ok(None),
+            Some(value) => parse_time(value)
+                .map(Some)
+                .ok_or_else(|| MatsimError::Format(format!("<{}> has invalid {name} {value:?}", self.element))),
+        }
+    }
+}
+
+// ── Times ─────────────────────────────────────────────────────────────────────
+
+/// `HH:MM:SS`, `HH:MM`, or plain seconds; hours may pass 24.  Fractional
+/// seconds are truncated.
+pub(crate) fn parse_time(s: &str) -> Option<u32> {
+    let parts: Vec<&str> = s.split(':').collect();
+    let secs = |p: &str| p.parse::<f64>().ok().filter(|v| *v >= 0.0 && v.is_finite());
+    let whole = |p: &str| p.parse::<u32>().ok();
+    let total = match parts[..] {
+        [s] => secs(s)?,
+        [h, m] => (whole(h)? as f64) * 3600.0 + (whole(m)? as f64) * 60.0,
+        [h, m, s] => (whole(h)? as f64) * 3600.0 + (whole(m)? as f64) * 60.0 + secs(s)?,
+        _ => return None,
+    };
+    (total <= u32::MAX as f64).then_some(total as u32)
+}
+
+/// `HH:MM:SS`, with hours past 24 for times on later days.
+pub(crate) fn format_time(secs: u64) -> String {
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
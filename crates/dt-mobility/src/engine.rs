@@ -3,7 +3,7 @@
 use dt_core::{AgentId, NodeId, Tick, TransportMode};
 use dt_spatial::{RoadNetwork, Router};
 
-use crate::{MobilityError, MobilityStore, MovementState};
+use crate::{MobilityError, MobilityStore, MovementState, Trip};
 
 /// Wraps a [`Router`] and [`MobilityStore`] to provide a simple intent-driven
 /// mobility API used by dt-sim.
@@ -72,15 +72,7 @@ impl<R: Router> MobilityEngine<R> {
     /// the caller can update `AgentStore.node_id` and re-insert them into the
     /// `WakeQueue`.
     pub fn tick_arrivals(&mut self, now: Tick) -> Vec<(AgentId, NodeId)> {
-        // Collect arriving agents first (immutable scan) then mutate.
-        let arriving: Vec<AgentId> = self.store.states
-            .iter()
-            .enumerate()
-            .filter(|(_, s)| s.in_transit && s.arrival_tick <= now)
-            .map(|(i, _)| AgentId(i as u32))
-            .collect();
-
-        arriving
+        self.arriving(now)
             .into_iter()
             .map(|agent| {
                 let dest = self.store.arrive(agent, now);
@@ -89,13 +81,33 @@ impl<R: Router> MobilityEngine<R> {
             .collect()
     }
 
+    /// Like [`tick_arrivals`][Self::tick_arrivals], but return the completed
+    /// [`Trip`] of every arriving agent, in ascending `AgentId` order.
+    pub fn tick_trips(&mut self, now: Tick, network: &RoadNetwork) -> Vec<Trip> {
+        self.arriving(now)
+            .into_iter()
+            .map(|agent| self.store.finish_trip(agent, now, network))
+            .collect()
+    }
+
+    /// Agents whose `arrival_tick <= now`, collected before any are mutated.
+    fn arriving(&self, now: Tick) -> Vec<AgentId> {
+        self.store.states
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.in_transit && s.arrival_tick <= now)
+            .map(|(i, _)| AgentId(i as u32))
+            .collect()
+    }
+
     /// Interpolated visual position for `agent` at `now`.
     ///
-    /// Returns `(departure_node, destination_node, progress)` where `progress`
-    /// is in `[0.0, 1.0]`.  Visualization tools blend between the two nodes'
-    /// `GeoPoint`s using this fraction.
-    pub fn visual_position(&self, agent: AgentId, now: Tick) -> (NodeId, NodeId, f32) {
-        let state = &self.store.states[agent.index()];
-        (state.departure_node, state.destination_node, state.progress(now))
+    /// Returns `(from, to, progress)` where `progress` is in `[0.0, 1.0]`:
+    /// the edge being traversed when path-following, otherwise
+    /// `(departure_node, destination_node, journey progress)` (see
+    /// [`MovementState::segment`]).  Visualization tools blend between the
+    /// two nodes' `GeoPoint`s using this fraction.
+    pub fn visual_position(&self, agent: AgentId, now: Tick, network: &RoadNetwork) -> (NodeId, NodeId, f32) {
+        self.store.states[agent.index()].segment(now, network)
     }
 }
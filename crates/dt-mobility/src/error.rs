@@ -1,4 +1,4 @@
-use dt_core::AgentId;
+use dt_core::{AgentId, DtError, ErrorCategory};
 use dt_spatial::SpatialError;
 use thiserror::Error;
 
@@ -15,3 +15,9 @@ pub enum MobilityError {
 }
 
 pub type MobilityResult<T> = Result<T, MobilityError>;
+
+impl From<MobilityError> for DtError {
+    fn from(err: MobilityError) -> Self {
+        DtError::subsystem(ErrorCategory::Mobility, err)
+    }
+}
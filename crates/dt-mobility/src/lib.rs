@@ -4,9 +4,10 @@
 //!
 //! | Module      | Contents                                                          |
 //! |-------------|-------------------------------------------------------------------|
-//! | [`state`]   | `MovementState` — per-agent travel state                          |
+//! | [`state`]   | `MovementState`, `EdgePosition` — per-agent travel state          |
 //! | [`store`]   | `MobilityStore` — `Vec<MovementState>` + sparse route cache       |
 //! | [`engine`]  | `MobilityEngine<R>` — intent-driven travel + arrival advancement  |
+//! | [`trip`]    | `Trip` — a completed journey, reported on arrival                 |
 //! | [`error`]   | `MobilityError`, `MobilityResult<T>`                              |
 //!
 //! # Movement model (hourly-tick teleport)
@@ -14,26 +15,40 @@
 //! Agents use a **teleport-at-arrival** model:
 //!
 //! 1. `MobilityEngine::begin_travel` computes a route via a pluggable
-//!    [`Router`][dt_spatial::Router] and sets `arrival_tick = now + travel_ticks`.
+//!    [`Router`][dt_spatial::Router] and sets `arrival_tick = now + travel_duration`.
 //! 2. The agent logically stays at `departure_node` until `arrival_tick`.
 //! 3. `MobilityEngine::tick_arrivals(now)` returns all agents whose
 //!    `arrival_tick <= now` and calls `store.arrive()` to mark them stationary
 //!    at `destination_node`.
+//!    `tick_trips(now, network)` does the same but returns a [`Trip`] per
+//!    arrival (mode, ticks, routed time and distance).
 //! 4. dt-sim inserts those agents back into the `WakeQueue` for re-planning.
 //!
 //! For visualization, `MobilityEngine::visual_position` returns
 //! `(departure_node, destination_node, progress ∈ [0,1])` so rendering tools
 //! can interpolate a smooth path along the stored route.
+//!
+//! # Path-following
+//!
+//! With `MobilityStore::path_following` set, `MobilityStore::advance(now)` —
+//! called by dt-sim every tick after arrivals — moves each in-transit agent
+//! along its stored route and records the edge and the travel time covered
+//! of it in `MovementState::on_edge`.  `visual_position`,
+//! `current_edge`, and snapshot positions then report that edge rather than
+//! the straight line from origin to destination.  Arrival ticks, and so the
+//! simulation's outcome, are the same in both models.
 
 pub mod engine;
 pub mod error;
 pub mod state;
 pub mod store;
+pub mod trip;
 
 #[cfg(test)]
 mod tests;
 
 pub use engine::MobilityEngine;
 pub use error::{MobilityError, MobilityResult};
-pub use state::MovementState;
+pub use state::{EdgePosition, MovementState};
 pub use store::MobilityStore;
+pub use trip::Trip;
@@ -1,6 +1,7 @@
 //! Per-agent movement state.
 
-use dt_core::{NodeId, Tick};
+use dt_core::{EdgeId, NodeId, Tick, TransportMode};
+use dt_spatial::RoadNetwork;
 
 /// The movement state for a single agent.
 ///
@@ -10,7 +11,11 @@ use dt_core::{NodeId, Tick};
 /// The simulation uses a **teleport-at-arrival** model: the agent logically
 /// stays at `departure_node` until `arrival_tick`, then instantly appears at
 /// `destination_node`.  The stored route allows visualization tools to
-/// interpolate a smooth position between ticks.
+/// interpolate a smooth position between ticks.  With path-following
+/// enabled (see [`MobilityStore::path_following`]), `on_edge` also tracks
+/// the edge of the route being traversed.
+///
+/// [`MobilityStore::path_following`]: crate::MobilityStore::path_following
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MovementState {
     /// `true` while the agent is travelling to `destination_node`.
@@ -30,6 +35,41 @@ pub struct MovementState {
     /// Tick at which the agent will arrive at `destination_node`.  Equals
     /// `departure_tick` when `!in_transit`.
     pub arrival_tick: Tick,
+
+    /// Mode of the current journey; `TransportMode::None` when `!in_transit`.
+    pub mode: TransportMode,
+
+    /// Position along the stored route when path-following; `None` when
+    /// `!in_transit` or in the teleport model.
+    pub on_edge: Option<EdgePosition>,
+}
+
+/// Where along its route an in-transit agent is.  Distances along the edge
+/// are in milliseconds of the mode's free-flow travel time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgePosition {
+    /// Index of the edge in the route's `edges`.
+    pub index: u32,
+
+    /// The edge being traversed.
+    pub edge: EdgeId,
+
+    /// Travel time already covered on `edge`, at most `edge_ms`.
+    pub offset_ms: u32,
+
+    /// Travel time of the whole of `edge`.
+    pub edge_ms: u32,
+}
+
+impl EdgePosition {
+    /// Fraction of the edge covered, in `[0.0, 1.0]`; `1.0` for an edge
+    /// that takes no time.
+    pub fn progress(&self) -> f32 {
+        if self.edge_ms == 0 {
+            return 1.0;
+        }
+        (self.offset_ms as f64 / self.edge_ms as f64) as f32
+    }
 }
 
 impl MovementState {
@@ -42,6 +82,8 @@ impl MovementState {
             destination_node: node,
             departure_tick:   tick,
             arrival_tick:     tick,
+            mode:             TransportMode::None,
+            on_edge:          None,
         }
     }
 
@@ -56,4 +98,26 @@ impl MovementState {
         let total   = (self.arrival_tick.0 - self.departure_tick.0) as f32;
         (elapsed / total).min(1.0)
     }
+
+    /// The straight segment the agent is on at `now`, as `(from, to,
+    /// fraction)`: the current edge when path-following, otherwise the
+    /// whole journey from `departure_node` to `destination_node`.
+    ///
+    /// `network` must be the one the route was computed on.
+    pub fn segment(&self, now: Tick, network: &RoadNetwork) -> (NodeId, NodeId, f32) {
+        self.segment_by(now, |e| (network.edge_from[e.index()], network.edge_to[e.index()]))
+    }
+
+    /// As [`segment`](Self::segment), with the edge's endpoints from `ends`,
+    /// for callers holding a copy of the network's edge table rather than
+    /// the network.
+    pub fn segment_by(&self, now: Tick, ends: impl FnOnce(EdgeId) -> (NodeId, NodeId)) -> (NodeId, NodeId, f32) {
+        match self.on_edge {
+            Some(pos) if self.in_transit => {
+                let (from, to) = ends(pos.edge);
+                (from, to, pos.progress())
+            }
+            _ => (self.departure_node, self.destination_node, self.progress(now)),
+        }
+    }
 }
@@ -2,10 +2,10 @@
 
 use std::collections::HashMap;
 
-use dt_core::{AgentId, NodeId, Tick, TransportMode};
-use dt_spatial::{Route, Router, SpatialError};
+use dt_core::{AgentId, EdgeId, NodeId, Tick, TickDuration, TransitVehicle, TransportMode};
+use dt_spatial::{EdgeCost, ModeSpeedCost, RoadNetwork, Route, Router, SpatialError};
 
-use crate::MovementState;
+use crate::{EdgePosition, MovementState, Trip};
 
 /// Holds movement state for every agent plus sparse routes for agents in
 /// transit.
@@ -19,6 +19,20 @@ pub struct MobilityStore {
 
     /// Sparse route cache: `AgentId → Route` for agents currently in transit.
     pub routes: HashMap<AgentId, Route>,
+
+    /// Shortest journey, however short the route.  At least one tick, so an
+    /// agent never arrives in the tick it departs.  Default: 1 tick.
+    pub min_travel: TickDuration,
+
+    /// Track each in-transit agent's edge along its route in
+    /// [`MovementState::on_edge`], updated by [`advance`][Self::advance].
+    /// Arrival times are unchanged.  Default: `false` (teleport only).
+    pub path_following: bool,
+
+    /// Cumulative travel time to the end of each route edge, in ms, for
+    /// path-following agents in transit.  Filled by `begin_travel` and
+    /// removed on arrival.
+    route_ends: HashMap<AgentId, Vec<u64>>,
 }
 
 impl MobilityStore {
@@ -28,14 +42,20 @@ impl MobilityStore {
         Self {
             states: vec![invalid_state; agent_count],
             routes: HashMap::new(),
+            min_travel: TickDuration::ONE,
+            path_following: false,
+            route_ends: HashMap::new(),
         }
     }
 
     /// Begin travel for `agent` from `from` to `to` using `router`.
     ///
-    /// Computes the route, sets `in_transit = true`, and stores the route in
-    /// the sparse map.  Returns the `arrival_tick` so the caller can insert it
-    /// into the `WakeQueue`.
+    /// Computes `agent`'s route departing at `now` (via
+    /// [`Router::route_for`]), sets `in_transit = true`, and stores the
+    /// route in the sparse map.  When path-following, the agent starts at
+    /// the beginning of the route's first edge, and the route's cumulative
+    /// edge times are cached for [`advance`][Self::advance].  Returns the `arrival_tick`
+    /// so the caller can insert it into the `WakeQueue`.
     ///
     /// # Errors
     ///
@@ -52,9 +72,9 @@ impl MobilityStore {
         router:             &R,
         network:            &dt_spatial::RoadNetwork,
     ) -> Result<Tick, SpatialError> {
-        let route        = router.route(network, from, to, mode)?;
-        let travel_ticks = route.travel_ticks(tick_duration_secs);
-        let arrival_tick = Tick(now.0 + travel_ticks.max(1)); // arrive at least 1 tick later
+        let route        = router.route_for(network, from, to, mode, now, tick_duration_secs, agent)?;
+        let travel       = route.travel_duration(tick_duration_secs);
+        let arrival_tick = now + travel.max(self.min_travel.max(TickDuration::ONE));
 
         self.states[agent.index()] = MovementState {
             in_transit:       true,
@@ -62,7 +82,15 @@ impl MobilityStore {
             destination_node: to,
             departure_tick:   now,
             arrival_tick,
+            mode,
+            on_edge:          None,
         };
+        self.route_ends.remove(&agent);
+        if self.path_following && !route.edges.is_empty() {
+            let ends = route_ends(&route, mode, network);
+            self.states[agent.index()].on_edge = Some(edge_position(&route, &ends, 0, 0));
+            self.route_ends.insert(agent, ends);
+        }
         self.routes.insert(agent, route);
 
         Ok(arrival_tick)
@@ -76,9 +104,33 @@ impl MobilityStore {
         let dest = self.states[agent.index()].destination_node;
         self.states[agent.index()] = MovementState::stationary(dest, now);
         self.routes.remove(&agent);
+        self.route_ends.remove(&agent);
         dest
     }
 
+    /// Like [`arrive`][Self::arrive], but return the completed journey.
+    ///
+    /// Travel time and distance come from the cached route; an agent put in
+    /// transit without one (e.g. by a custom phase) reports zero for both.
+    pub fn finish_trip(&mut self, agent: AgentId, now: Tick, network: &RoadNetwork) -> Trip {
+        let state = &self.states[agent.index()];
+        let route = self.routes.get(&agent);
+        let trip = Trip {
+            agent,
+            from:        state.departure_node,
+            to:          state.destination_node,
+            mode:        state.mode,
+            depart_tick: state.departure_tick,
+            arrive_tick: now,
+            travel_secs: route.map_or(0.0, |r| r.total_travel_secs()),
+            distance_m:  route.map_or(0.0, |r| {
+                r.edges.iter().map(|e| network.edge_length_m[e.index()]).sum()
+            }),
+        };
+        self.arrive(agent, now);
+        trip
+    }
+
     /// Current progress fraction for `agent` at `now` (see
     /// [`MovementState::progress`]).
     #[inline]
@@ -86,9 +138,116 @@ impl MobilityStore {
         self.states[agent.index()].progress(now)
     }
 
+    /// Move every path-following agent in transit along its route to where
+    /// it is at `now`.  A no-op unless [`path_following`][Self::path_following]
+    /// is set.
+    ///
+    /// The journey's [`progress`][MovementState::progress] is mapped onto
+    /// the route's edges weighted by the mode's free-flow travel time, so an
+    /// agent reaches the end of its last edge at `arrival_tick`.  Edges the
+    /// mode cannot use weigh nothing.  The arithmetic is integer, so
+    /// positions are the same on every platform.  Only agents that began
+    /// travel through [`begin_travel`][Self::begin_travel] are moved; each
+    /// costs a binary search over its route.
+    pub fn advance(&mut self, now: Tick) {
+        if !self.path_following {
+            return;
+        }
+        for (&agent, ends) in &self.route_ends {
+            let (Some(route), state) = (self.routes.get(&agent), &mut self.states[agent.index()]) else {
+                continue;
+            };
+            if !state.in_transit {
+                continue;
+            }
+            let total = *ends.last().unwrap_or(&0);
+            let span = state.arrival_tick.0.saturating_sub(state.departure_tick.0);
+            let elapsed = now.0.saturating_sub(state.departure_tick.0).min(span);
+            let target = if span == 0 { total } else { (total as u128 * elapsed as u128 / span as u128) as u64 };
+            // The first edge not yet finished, or the last once the journey is done.
+            let index = ends.partition_point(|&end| end <= target).min(ends.len() - 1);
+            state.on_edge = Some(edge_position(route, ends, index, target));
+        }
+    }
+
+    /// The edge `agent` is traversing at `now`, or `None` if it is
+    /// stationary or its route has no edges.
+    ///
+    /// When path-following, this is the edge last set by
+    /// [`advance`][Self::advance].  Otherwise movement is
+    /// teleport-at-arrival and the position along the route is an estimate:
+    /// the journey's [`progress`][Self::progress] is mapped onto the route's
+    /// edges weighted by their free-flow travel time.
+    pub fn current_edge(&self, agent: AgentId, now: Tick, network: &RoadNetwork) -> Option<EdgeId> {
+        self.edge_at(agent, now, network).map(|(_, edge)| edge)
+    }
+
+    /// The scheduled transit vehicle `agent` is riding at `now`: the
+    /// [ride](dt_spatial::TransitRide) of its route that covers its
+    /// [`current_edge`][Self::current_edge].  `None` when walking, not on a
+    /// transit route, or stationary.
+    pub fn current_vehicle(&self, agent: AgentId, now: Tick, network: &RoadNetwork) -> Option<TransitVehicle> {
+        let rides = &self.routes.get(&agent)?.rides;
+        if rides.is_empty() {
+            return None;
+        }
+        let (index, _) = self.edge_at(agent, now, network)?;
+        rides.iter().find(|ride| ride.covers(index)).map(|ride| ride.vehicle)
+    }
+
+    /// Index in its route, and id, of the edge `agent` is traversing at
+    /// `now`; see [`current_edge`][Self::current_edge].
+    fn edge_at(&self, agent: AgentId, now: Tick, network: &RoadNetwork) -> Option<(usize, EdgeId)> {
+        let state = &self.states[agent.index()];
+        if !state.in_transit {
+            return None;
+        }
+        if let Some(pos) = state.on_edge {
+            return Some((pos.index as usize, pos.edge));
+        }
+        let edges = &self.routes.get(&agent)?.edges;
+        let total: u64 = edges.iter().map(|e| network.edge_travel_ms[e.index()] as u64).sum();
+        let target = (state.progress(now) as f64 * total as f64) as u64;
+        let mut covered = 0u64;
+        for (index, &edge) in edges.iter().enumerate() {
+            covered += network.edge_travel_ms[edge.index()] as u64;
+            if covered > target {
+                return Some((index, edge));
+            }
+        }
+        edges.last().map(|&edge| (edges.len() - 1, edge))
+    }
+
     /// Returns `true` if `agent` is currently in transit.
     #[inline]
     pub fn in_transit(&self, agent: AgentId) -> bool {
         self.states[agent.index()].in_transit
     }
 }
+
+/// Cumulative travel time to the end of each edge of `route` by `mode`.
+fn route_ends(route: &Route, mode: TransportMode, network: &RoadNetwork) -> Vec<u64> {
+    route
+        .edges
+        .iter()
+        .scan(0u64, |covered, &e| {
+            *covered += match ModeSpeedCost.cost_ms(network, e, mode, None) {
+                u32::MAX => 0,
+                ms => ms as u64,
+            };
+            Some(*covered)
+        })
+        .collect()
+}
+
+/// The position `target` ms into `route` on edge `index`, which must be
+/// the edge `target` falls on.
+fn edge_position(route: &Route, ends: &[u64], index: usize, target: u64) -> EdgePosition {
+    let start = if index == 0 { 0 } else { ends[index - 1] };
+    EdgePosition {
+        index:     index as u32,
+        edge:      route.edges[index],
+        offset_ms: (target.min(ends[index]) - start) as u32,
+        edge_ms:   (ends[index] - start) as u32,
+    }
+}
@@ -1,6 +1,6 @@
 //! Unit tests for dt-mobility.
 
-use dt_core::{AgentId, NodeId, Tick, TransportMode};
+use dt_core::{AgentId, NodeId, Tick, TickDuration, TransportMode};
 use dt_spatial::{DijkstraRouter, RoadNetwork, RoadNetworkBuilder, Router};
 
 use crate::{MobilityEngine, MobilityStore, MovementState};
@@ -53,6 +53,8 @@ mod movement_state {
             destination_node: NodeId(1),
             departure_tick:   Tick(0),
             arrival_tick:     Tick(10),
+            mode:             TransportMode::Car,
+            on_edge:          None,
         };
         assert!((s.progress(Tick(5)) - 0.5).abs() < 1e-6);
         assert_eq!(s.progress(Tick(0)),  0.0);
@@ -68,6 +70,8 @@ mod movement_state {
             destination_node: NodeId(1),
             departure_tick:   Tick(5),
             arrival_tick:     Tick(5),
+            mode:             TransportMode::Car,
+            on_edge:          None,
         };
         assert_eq!(s.progress(Tick(5)), 1.0);
     }
@@ -96,6 +100,7 @@ mod mobility_store {
     }
 
     #[test]
+    #[allow(clippy::unnecessary_get_then_check)]
     fn arrive_removes_route_and_marks_stationary() {
         let net = two_node_network();
         let mut store = MobilityStore::new(2);
@@ -106,6 +111,8 @@ mod mobility_store {
             destination_node: NodeId(1),
             departure_tick:   Tick(0),
             arrival_tick:     Tick(5),
+            mode:             TransportMode::Car,
+            on_edge:          None,
         };
         store.routes.insert(AgentId(0), DijkstraRouter.route(&net, NodeId(0), NodeId(1), TransportMode::Car).unwrap());
 
@@ -114,6 +121,56 @@ mod mobility_store {
         assert!(!store.states[0].in_transit);
         assert!(store.routes.get(&AgentId(0)).is_none());
     }
+
+    #[test]
+    fn current_edge_follows_progress() {
+        let net = three_node_network();
+        let mut store = MobilityStore::new(2);
+        store.states[0] = MovementState {
+            in_transit:       true,
+            departure_node:   NodeId(0),
+            destination_node: NodeId(2),
+            departure_tick:   Tick(0),
+            arrival_tick:     Tick(4),
+            mode:             TransportMode::Car,
+            on_edge:          None,
+        };
+        let route = DijkstraRouter.route(&net, NodeId(0), NodeId(2), TransportMode::Car).unwrap();
+        let (first, second) = (route.edges[0], route.edges[1]);
+        store.routes.insert(AgentId(0), route);
+
+        assert_eq!(store.current_edge(AgentId(0), Tick(0), &net), Some(first));
+        assert_eq!(store.current_edge(AgentId(0), Tick(1), &net), Some(first));
+        assert_eq!(store.current_edge(AgentId(0), Tick(2), &net), Some(second));
+        assert_eq!(store.current_edge(AgentId(0), Tick(4), &net), Some(second));
+        // Stationary agent is on no edge.
+        assert_eq!(store.current_edge(AgentId(1), Tick(1), &net), None);
+    }
+
+    #[test]
+    fn current_vehicle_follows_rides() {
+        let net = three_node_network();
+        let mut store = MobilityStore::new(1);
+        store.states[0] = MovementState {
+            in_transit:       true,
+            departure_node:   NodeId(0),
+            destination_node: NodeId(2),
+            departure_tick:   Tick(0),
+            arrival_tick:     Tick(4),
+            mode:             TransportMode::Transit,
+            on_edge:          None,
+        };
+        // Walk the first edge, ride the second.
+        let bus = dt_core::TransitVehicle { line: 3, departure_ms: 60_000 };
+        let mut route = DijkstraRouter.route(&net, NodeId(0), NodeId(2), TransportMode::Car).unwrap();
+        route.rides.push(dt_spatial::TransitRide { vehicle: bus, first_edge: 1, end_edge: 2 });
+        store.routes.insert(AgentId(0), route);
+
+        assert_eq!(store.current_vehicle(AgentId(0), Tick(1), &net), None);
+        assert_eq!(store.current_vehicle(AgentId(0), Tick(3), &net), Some(bus));
+        store.arrive(AgentId(0), Tick(4));
+        assert_eq!(store.current_vehicle(AgentId(0), Tick(4), &net), None);
+    }
 }
 
 // ── MobilityEngine ────────────────────────────────────────────────────────────
@@ -145,6 +202,19 @@ mod mobility_engine {
         assert_eq!(eng.store.states[0].destination_node, NodeId(1));
     }
 
+    #[test]
+    fn begin_travel_respects_min_travel() {
+        let net = two_node_network();
+        let mut eng = engine(1);
+        eng.store.min_travel = TickDuration(3);
+        eng.place(AgentId(0), NodeId(0), Tick(0));
+
+        let arrival = eng
+            .begin_travel(AgentId(0), NodeId(1), TransportMode::Car, Tick(2), 3600, &net)
+            .unwrap();
+        assert_eq!(arrival, Tick(5));
+    }
+
     #[test]
     fn begin_travel_not_placed_errors() {
         let net = two_node_network();
@@ -190,11 +260,35 @@ mod mobility_engine {
         }
     }
 
+    #[test]
+    fn tick_trips_reports_journey() {
+        let net = three_node_network();
+        let mut eng = engine(2);
+        eng.place(AgentId(0), NodeId(0), Tick(0));
+        eng.place(AgentId(1), NodeId(2), Tick(0));
+        let arrival = eng.begin_travel(AgentId(0), NodeId(2), TransportMode::Walk, Tick(3), 60, &net).unwrap();
+        assert_eq!(eng.store.states[0].mode, TransportMode::Walk);
+
+        assert!(eng.tick_trips(Tick(3), &net).is_empty());
+        let trips = eng.tick_trips(arrival, &net);
+        assert_eq!(trips.len(), 1);
+        let trip = trips[0];
+        assert_eq!((trip.agent, trip.from, trip.to), (AgentId(0), NodeId(0), NodeId(2)));
+        assert_eq!((trip.depart_tick, trip.arrive_tick), (Tick(3), arrival));
+        assert_eq!(trip.mode, TransportMode::Walk);
+        assert_eq!(trip.distance_m, 1000.0);
+        assert!(trip.travel_secs > 0.0);
+        // Arrival resets the journey.
+        assert!(!eng.store.states[0].in_transit);
+        assert_eq!(eng.store.states[0].mode, TransportMode::None);
+    }
+
     #[test]
     fn visual_position_stationary() {
+        let net = two_node_network();
         let mut eng = engine(1);
         eng.place(AgentId(0), NodeId(3), Tick(0));
-        let (dep, dest, progress) = eng.visual_position(AgentId(0), Tick(5));
+        let (dep, dest, progress) = eng.visual_position(AgentId(0), Tick(5), &net);
         assert_eq!(dep, NodeId(3));
         assert_eq!(dest, NodeId(3));
         assert_eq!(progress, 1.0);
@@ -209,12 +303,12 @@ mod mobility_engine {
             .begin_travel(AgentId(0), NodeId(1), TransportMode::Car, Tick(0), 3600, &net)
             .unwrap();
 
-        let (dep, dest, progress) = eng.visual_position(AgentId(0), Tick(0));
+        let (dep, dest, progress) = eng.visual_position(AgentId(0), Tick(0), &net);
         assert_eq!(dep, NodeId(0));
         assert_eq!(dest, NodeId(1));
         assert!((progress - 0.0).abs() < 1e-6);
 
-        let (_, _, progress_end) = eng.visual_position(AgentId(0), arrival);
+        let (_, _, progress_end) = eng.visual_position(AgentId(0), arrival, &net);
         assert!((progress_end - 1.0).abs() < 1e-6);
     }
 
@@ -229,4 +323,103 @@ mod mobility_engine {
         let route = eng.store.routes.get(&AgentId(0)).unwrap();
         assert_eq!(route.edges.len(), 2);
     }
+
+    #[test]
+    fn begin_travel_routes_at_departure_time() {
+        use dt_spatial::{SpeedProfiles, TimeDependentRouter};
+
+        // Every edge takes three times as long between 08:00 and 09:00.
+        let mut net = two_node_network();
+        let mut profiles = SpeedProfiles::new(net.edge_count());
+        let mut factors = [1.0; 24];
+        factors[8] = 3.0;
+        let rush = profiles.add_profile(factors);
+        for e in 0..net.edge_count() as u32 {
+            profiles.assign(dt_core::EdgeId(e), rush);
+        }
+        net.set_speed_profiles(profiles);
+
+        let mut eng = MobilityEngine::new(TimeDependentRouter::new(), 2);
+        eng.place(AgentId(0), NodeId(0), Tick(0));
+        eng.place(AgentId(1), NodeId(0), Tick(0));
+        // 60 s ticks: tick 480 is 08:00, tick 180 is 03:00.
+        let peak     = eng.begin_travel(AgentId(0), NodeId(1), TransportMode::Car, Tick(480), 60, &net).unwrap();
+        let off_peak = eng.begin_travel(AgentId(1), NodeId(1), TransportMode::Car, Tick(180), 60, &net).unwrap();
+        assert_eq!((peak, off_peak), (Tick(486), Tick(182)));
+        assert_eq!(eng.store.routes[&AgentId(0)].total_travel_secs(), 360.0);
+    }
+}
+
+// ── Path-following ────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod path_following {
+    use super::*;
+
+    /// Agent 0 travelling 0 → 2 over four one-minute ticks from tick 0.
+    fn travelling(path_following: bool) -> (RoadNetwork, MobilityEngine<DijkstraRouter>) {
+        let net = three_node_network();
+        let mut eng = engine(2);
+        eng.store.path_following = path_following;
+        eng.store.min_travel = TickDuration(4);
+        eng.place(AgentId(0), NodeId(0), Tick(0));
+        eng.place(AgentId(1), NodeId(1), Tick(0));
+        let arrival = eng.begin_travel(AgentId(0), NodeId(2), TransportMode::Car, Tick(0), 60, &net).unwrap();
+        assert_eq!(arrival, Tick(4));
+        (net, eng)
+    }
+
+    #[test]
+    fn begin_travel_starts_on_first_edge() {
+        let (net, eng) = travelling(true);
+        let pos = eng.store.states[0].on_edge.unwrap();
+        assert_eq!(pos.edge, eng.store.routes[&AgentId(0)].edges[0]);
+        assert_eq!((pos.index, pos.offset_ms, pos.edge_ms), (0, 0, 60_000));
+        assert_eq!(eng.visual_position(AgentId(0), Tick(0), &net), (NodeId(0), NodeId(1), 0.0));
+        assert_eq!(eng.store.current_edge(AgentId(0), Tick(0), &net), Some(pos.edge));
+        assert_eq!(eng.store.states[1].on_edge, None);
+    }
+
+    #[test]
+    fn advance_follows_route_edges() {
+        let (net, mut eng) = travelling(true);
+        let edges = eng.store.routes[&AgentId(0)].edges.clone();
+        let expected = [
+            (Tick(1), 0, 30_000, NodeId(0), NodeId(1), 0.5),
+            (Tick(2), 1, 0, NodeId(1), NodeId(2), 0.0),
+            (Tick(3), 1, 30_000, NodeId(1), NodeId(2), 0.5),
+            (Tick(4), 1, 60_000, NodeId(1), NodeId(2), 1.0),
+        ];
+        for (now, index, offset_ms, from, to, progress) in expected {
+            eng.store.advance(now);
+            let pos = eng.store.states[0].on_edge.unwrap();
+            assert_eq!((pos.index, pos.edge, pos.offset_ms, pos.edge_ms), (index, edges[index as usize], offset_ms, 60_000));
+            assert_eq!(eng.visual_position(AgentId(0), now, &net), (from, to, progress), "{now:?}");
+            assert_eq!(eng.store.current_edge(AgentId(0), now, &net), Some(pos.edge));
+        }
+        // The stationary agent is untouched.
+        assert_eq!(eng.store.states[1], MovementState::stationary(NodeId(1), Tick(0)));
+    }
+
+    #[test]
+    fn arrival_clears_edge_position() {
+        let (net, mut eng) = travelling(true);
+        eng.store.advance(Tick(3));
+        assert_eq!(eng.tick_arrivals(Tick(4)), vec![(AgentId(0), NodeId(2))]);
+        assert_eq!(eng.store.states[0].on_edge, None);
+        assert_eq!(eng.visual_position(AgentId(0), Tick(4), &net), (NodeId(2), NodeId(2), 1.0));
+    }
+
+    #[test]
+    fn teleport_model_reports_journey_line() {
+        let (net, mut eng) = travelling(false);
+        eng.store.advance(Tick(2));
+        assert_eq!(eng.store.states[0].on_edge, None);
+        let (from, to, progress) = eng.visual_position(AgentId(0), Tick(2), &net);
+        assert_eq!((from, to), (NodeId(0), NodeId(2)));
+        assert!((progress - 0.5).abs() < 1e-6);
+        // `current_edge` still estimates the edge from progress.
+        let second = eng.store.routes[&AgentId(0)].edges[1];
+        assert_eq!(eng.store.current_edge(AgentId(0), Tick(2), &net), Some(second));
+    }
 }
@@ -0,0 +1,21 @@
+//! Completed journeys.
+
+use dt_core::{AgentId, NodeId, Tick, TransportMode};
+
+/// One completed journey, produced on arrival by
+/// [`MobilityEngine::tick_trips`][crate::MobilityEngine::tick_trips].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trip {
+    pub agent:       AgentId,
+    pub from:        NodeId,
+    pub to:          NodeId,
+    pub mode:        TransportMode,
+    pub depart_tick: Tick,
+    /// Tick at which the arrival was processed (normally the scheduled
+    /// `arrival_tick`).
+    pub arrive_tick: Tick,
+    /// Routed travel time in seconds, before rounding up to whole ticks.
+    pub travel_secs: f32,
+    /// Total length of the routed edges in metres.
+    pub distance_m:  f32,
+}
@@ -0,0 +1,339 @@
+//! Arrow schemas and record batches shared by the Parquet and Arrow IPC
+//! backends and [`MemoryWriter`][crate::MemoryWriter].
+
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, BooleanBuilder, Float32Builder, Float64Builder, Int64Builder, ListBuilder,
+    StringBuilder, UInt8Builder, UInt32Builder, UInt64Builder,
+};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+
+use crate::columns::cell;
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnType, ColumnValue, ContactRow, LinkVolumeRow, OdRow,
+    OutputResult, RouteRow, TickSummaryRow, TripRow,
+};
+
+// ── Schemas ───────────────────────────────────────────────────────────────────
+
+pub(crate) fn snapshot_schema(extra: &[ColumnSpec]) -> Arc<Schema> {
+    let mut fields = vec![
+        Field::new("agent_id",         DataType::UInt32,  false),
+        Field::new("tick",             DataType::UInt64,  false),
+        Field::new("departure_node",   DataType::UInt32,  false),
+        Field::new("in_transit",       DataType::Boolean, false),
+        Field::new("destination_node", DataType::UInt32,  false),
+        Field::new("lat",              DataType::Float32, true),
+        Field::new("lon",              DataType::Float32, true),
+        Field::new("departure_tick",   DataType::UInt64,  true),
+        Field::new("arrival_tick",     DataType::UInt64,  true),
+    ];
+    fields.extend(extra.iter().map(|col| {
+        let ty = match col.ty {
+            ColumnType::Int   => DataType::Int64,
+            ColumnType::Float => DataType::Float64,
+            ColumnType::Bool  => DataType::Boolean,
+            ColumnType::Text  => DataType::Utf8,
+        };
+        Field::new(col.name.as_str(), ty, true)
+    }));
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn summary_schema() -> Arc<Schema> {
+    let mut fields = vec![
+        Field::new("tick",           DataType::UInt64, false),
+        Field::new("unix_time_secs", DataType::Int64,  false),
+    ];
+    fields.extend(TickSummaryRow::COUNT_COLUMNS.map(|name| Field::new(name, DataType::UInt64, false)));
+    Arc::new(Schema::new(fields))
+}
+
+pub(crate) fn contact_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("tick",    DataType::UInt64, false),
+        Field::new("agent_a", DataType::UInt32, false),
+        Field::new("agent_b", DataType::UInt32, false),
+        Field::new("node",    DataType::UInt32, false),
+    ]))
+}
+
+pub(crate) fn trip_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("agent",       DataType::UInt32,  false),
+        Field::new("depart_tick", DataType::UInt64,  false),
+        Field::new("arrive_tick", DataType::UInt64,  false),
+        Field::new("from",        DataType::UInt32,  false),
+        Field::new("to",          DataType::UInt32,  false),
+        Field::new("mode",        DataType::Utf8,    false),
+        Field::new("travel_secs", DataType::Float32, false),
+        Field::new("distance_m",  DataType::Float32, false),
+    ]))
+}
+
+pub(crate) fn route_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("agent",       DataType::UInt32, false),
+        Field::new("depart_tick", DataType::UInt64, false),
+        Field::new("from",        DataType::UInt32, false),
+        Field::new("to",          DataType::UInt32, false),
+        Field::new("mode",        DataType::Utf8,   false),
+        Field::new_list("edges", Field::new_list_field(DataType::UInt32, true), false),
+    ]))
+}
+
+pub(crate) fn od_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("origin_zone", DataType::UInt32, false),
+        Field::new("dest_zone",   DataType::UInt32, false),
+        Field::new("hour",        DataType::UInt8,  false),
+        Field::new("mode",        DataType::Utf8,   false),
+        Field::new("trips",       DataType::UInt64, false),
+    ]))
+}
+
+pub(crate) fn link_volume_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("tick",     DataType::UInt64, false),
+        Field::new("edge_id",  DataType::UInt32, false),
+        Field::new("vehicles", DataType::UInt64, false),
+    ]))
+}
+
+// ── Batches ───────────────────────────────────────────────────────────────────
+
+/// Arrow array for extra column `c` over `n` rows.  Values of the wrong type
+/// are written as null.
+fn extra_array(ty: ColumnType, columns: &[Vec<ColumnValue>], c: usize, n: usize) -> ArrayRef {
+    let cells = (0..n).map(|i| cell(columns, c, i));
+    match ty {
+        ColumnType::Int => {
+            let mut b = Int64Builder::with_capacity(n);
+            cells.for_each(|v| b.append_option(match v { ColumnValue::Int(x) => Some(*x), _ => None }));
+            Arc::new(b.finish())
+        }
+        ColumnType::Float => {
+            let mut b = Float64Builder::with_capacity(n);
+            cells.for_each(|v| b.append_option(match v { ColumnValue::Float(x) => Some(*x), _ => None }));
+            Arc::new(b.finish())
+        }
+        ColumnType::Bool => {
+            let mut b = BooleanBuilder::with_capacity(n);
+            cells.for_each(|v| b.append_option(match v { ColumnValue::Bool(x) => Some(*x), _ => None }));
+            Arc::new(b.finish())
+        }
+        ColumnType::Text => {
+            let mut b = StringBuilder::new();
+            cells.for_each(|v| b.append_option(match v { ColumnValue::Text(s) => Some(s), _ => None }));
+            Arc::new(b.finish())
+        }
+    }
+}
+
+/// Snapshot rows plus extra columns `extra` as one batch of `schema`.
+pub(crate) fn snapshot_batch(
+    schema:  &Arc<Schema>,
+    extra:   &[ColumnSpec],
+    rows:    &[AgentSnapshotRow],
+    columns: &[Vec<ColumnValue>],
+) -> OutputResult<RecordBatch> {
+    let mut agent_ids         = UInt32Builder::new();
+    let mut ticks             = UInt64Builder::new();
+    let mut departure_nodes   = UInt32Builder::new();
+    let mut in_transits       = BooleanBuilder::new();
+    let mut destination_nodes = UInt32Builder::new();
+    let mut lats              = Float32Builder::new();
+    let mut lons              = Float32Builder::new();
+    let mut departure_ticks   = UInt64Builder::new();
+    let mut arrival_ticks     = UInt64Builder::new();
+
+    for row in rows {
+        agent_ids.append_value(row.agent_id);
+        ticks.append_value(row.tick);
+        departure_nodes.append_value(row.departure_node);
+        in_transits.append_value(row.in_transit);
+        destination_nodes.append_value(row.destination_node);
+        lats.append_option(row.lat);
+        lons.append_option(row.lon);
+        departure_ticks.append_option(row.departure_tick);
+        arrival_ticks.append_option(row.arrival_tick);
+    }
+
+    let mut arrays: Vec<ArrayRef> = vec![
+        Arc::new(agent_ids.finish()),
+        Arc::new(ticks.finish()),
+        Arc::new(departure_nodes.finish()),
+        Arc::new(in_transits.finish()),
+        Arc::new(destination_nodes.finish()),
+        Arc::new(lats.finish()),
+        Arc::new(lons.finish()),
+        Arc::new(departure_ticks.finish()),
+        Arc::new(arrival_ticks.finish()),
+    ];
+    for (c, col) in extra.iter().enumerate() {
+        arrays.push(extra_array(col.ty, columns, c, rows.len()));
+    }
+
+    Ok(RecordBatch::try_new(Arc::clone(schema), arrays)?)
+}
+
+pub(crate) fn summary_batch(schema: &Arc<Schema>, rows: &[TickSummaryRow]) -> OutputResult<RecordBatch> {
+    let mut ticks      = UInt64Builder::new();
+    let mut unix_times = Int64Builder::new();
+    let mut counts     = TickSummaryRow::COUNT_COLUMNS.map(|_| UInt64Builder::with_capacity(rows.len()));
+
+    for row in rows {
+        ticks.append_value(row.tick);
+        unix_times.append_value(row.unix_time_secs);
+        counts.iter_mut().zip(row.counts()).for_each(|(b, count)| b.append_value(count));
+    }
+
+    let mut arrays: Vec<ArrayRef> = vec![
+        Arc::new(ticks.finish()),
+        Arc::new(unix_times.finish()),
+    ];
+    arrays.extend(counts.iter_mut().map(|b| Arc::new(b.finish()) as ArrayRef));
+
+    Ok(RecordBatch::try_new(Arc::clone(schema), arrays)?)
+}
+
+pub(crate) fn contact_batch(schema: &Arc<Schema>, rows: &[ContactRow]) -> OutputResult<RecordBatch> {
+    let mut ticks    = UInt64Builder::new();
+    let mut agents_a = UInt32Builder::new();
+    let mut agents_b = UInt32Builder::new();
+    let mut nodes    = UInt32Builder::new();
+
+    for row in rows {
+        ticks.append_value(row.tick);
+        agents_a.append_value(row.agent_a);
+        agents_b.append_value(row.agent_b);
+        nodes.append_value(row.node);
+    }
+
+    Ok(RecordBatch::try_new(
+        Arc::clone(schema),
+        vec![
+            Arc::new(ticks.finish()),
+            Arc::new(agents_a.finish()),
+            Arc::new(agents_b.finish()),
+            Arc::new(nodes.finish()),
+        ],
+    )?)
+}
+
+pub(crate) fn trip_batch(schema: &Arc<Schema>, rows: &[TripRow]) -> OutputResult<RecordBatch> {
+    let mut agents       = UInt32Builder::new();
+    let mut depart_ticks = UInt64Builder::new();
+    let mut arrive_ticks = UInt64Builder::new();
+    let mut froms        = UInt32Builder::new();
+    let mut tos          = UInt32Builder::new();
+    let mut modes        = StringBuilder::new();
+    let mut travel_secs  = Float32Builder::new();
+    let mut distances    = Float32Builder::new();
+
+    for row in rows {
+        agents.append_value(row.agent);
+        depart_ticks.append_value(row.depart_tick);
+        arrive_ticks.append_value(row.arrive_tick);
+        froms.append_value(row.from);
+        tos.append_value(row.to);
+        modes.append_value(row.mode.as_str());
+        travel_secs.append_value(row.travel_secs);
+        distances.append_value(row.distance_m);
+    }
+
+    Ok(RecordBatch::try_new(
+        Arc::clone(schema),
+        vec![
+            Arc::new(agents.finish()),
+            Arc::new(depart_ticks.finish()),
+            Arc::new(arrive_ticks.finish()),
+            Arc::new(froms.finish()),
+            Arc::new(tos.finish()),
+            Arc::new(modes.finish()),
+            Arc::new(travel_secs.finish()),
+            Arc::new(distances.finish()),
+        ],
+    )?)
+}
+
+pub(crate) fn route_batch(schema: &Arc<Schema>, rows: &[RouteRow]) -> OutputResult<RecordBatch> {
+    let mut agents       = UInt32Builder::new();
+    let mut depart_ticks = UInt64Builder::new();
+    let mut froms        = UInt32Builder::new();
+    let mut tos          = UInt32Builder::new();
+    let mut modes        = StringBuilder::new();
+    let mut edges        = ListBuilder::new(UInt32Builder::new());
+
+    for row in rows {
+        agents.append_value(row.agent);
+        depart_ticks.append_value(row.depart_tick);
+        froms.append_value(row.from);
+        tos.append_value(row.to);
+        modes.append_value(row.mode.as_str());
+        edges.values().append_slice(&row.edges);
+        edges.append(true);
+    }
+
+    Ok(RecordBatch::try_new(
+        Arc::clone(schema),
+        vec![
+            Arc::new(agents.finish()),
+            Arc::new(depart_ticks.finish()),
+            Arc::new(froms.finish()),
+            Arc::new(tos.finish()),
+            Arc::new(modes.finish()),
+            Arc::new(edges.finish()),
+        ],
+    )?)
+}
+
+pub(crate) fn od_batch(schema: &Arc<Schema>, rows: &[OdRow]) -> OutputResult<RecordBatch> {
+    let mut origins = UInt32Builder::new();
+    let mut dests   = UInt32Builder::new();
+    let mut hours   = UInt8Builder::new();
+    let mut modes   = StringBuilder::new();
+    let mut trips   = UInt64Builder::new();
+
+    for row in rows {
+        origins.append_value(row.origin_zone);
+        dests.append_value(row.dest_zone);
+        hours.append_value(row.hour);
+        modes.append_value(row.mode.as_str());
+        trips.append_value(row.trips);
+    }
+
+    Ok(RecordBatch::try_new(
+        Arc::clone(schema),
+        vec![
+            Arc::new(origins.finish()),
+            Arc::new(dests.finish()),
+            Arc::new(hours.finish()),
+            Arc::new(modes.finish()),
+            Arc::new(trips.finish()),
+        ],
+    )?)
+}
+
+pub(crate) fn link_volume_batch(schema: &Arc<Schema>, rows: &[LinkVolumeRow]) -> OutputResult<RecordBatch> {
+    let mut ticks    = UInt64Builder::new();
+    let mut edges    = UInt32Builder::new();
+    let mut vehicles = UInt64Builder::new();
+
+    for row in rows {
+        ticks.append_value(row.tick);
+        edges.append_value(row.edge);
+        vehicles.append_value(row.vehicles);
+    }
+
+    Ok(RecordBatch::try_new(
+        Arc::clone(schema),
+        vec![
+            Arc::new(ticks.finish()),
+            Arc::new(edges.finish()),
+            Arc::new(vehicles.finish()),
+        ],
+    )?)
+}
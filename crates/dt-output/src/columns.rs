@@ -0,0 +1,160 @@
+//! User-defined snapshot columns derived from agent components.
+//!
+//! Register a [`ColumnExtractor`] with
+//! [`SimOutputObserver::with_column`][crate::SimOutputObserver::with_column]
+//! and every backend appends its values to the agent snapshot rows:
+//!
+//! ```rust,ignore
+//! let obs = SimOutputObserver::new(writer, &config)
+//!     .with_column(ComponentColumn::new("infected", ColumnType::Bool, |h: &Health| {
+//!         ColumnValue::Bool(h.infected)
+//!     }))
+//!     .with_column(ComponentColumn::new("income_bracket", ColumnType::Int, |e: &Economy| {
+//!         ColumnValue::Int(e.bracket as i64)
+//!     }));
+//! ```
+//!
+//! Extra columns follow the built-in ones in declaration order and are
+//! nullable in every backend.
+
+use std::marker::PhantomData;
+
+use dt_agent::AgentStore;
+
+use crate::{OutputError, OutputResult};
+
+/// Built-in agent snapshot columns, which extra columns may not shadow.
+pub(crate) const SNAPSHOT_COLUMNS: [&str; 9] = [
+    "agent_id", "tick", "departure_node", "in_transit", "destination_node", "lat", "lon",
+    "departure_tick", "arrival_tick",
+];
+
+// ── Column schema ─────────────────────────────────────────────────────────────
+
+/// Storage type of an extra snapshot column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    /// 64-bit signed integer.
+    Int,
+    /// 64-bit float.
+    Float,
+    /// Boolean; written as `1`/`0` in CSV and SQLite.
+    Bool,
+    /// UTF-8 string.
+    Text,
+}
+
+/// Name and type of an extra snapshot column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSpec {
+    pub name: String,
+    pub ty:   ColumnType,
+}
+
+/// One cell of an extra snapshot column.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValue {
+    /// Missing value; an empty CSV field or SQL/Parquet null.
+    Null,
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Text(String),
+}
+
+impl ColumnValue {
+    /// `true` if the value can be stored in a column of type `ty`.
+    /// [`Null`][Self::Null] fits every type.
+    pub fn fits(&self, ty: ColumnType) -> bool {
+        matches!(
+            (self, ty),
+            (ColumnValue::Null, _)
+                | (ColumnValue::Int(_), ColumnType::Int)
+                | (ColumnValue::Float(_), ColumnType::Float)
+                | (ColumnValue::Bool(_), ColumnType::Bool)
+                | (ColumnValue::Text(_), ColumnType::Text)
+        )
+    }
+
+    /// CSV field text: empty for `Null`, `1`/`0` for booleans.
+    pub(crate) fn to_field(&self) -> String {
+        match self {
+            ColumnValue::Null     => String::new(),
+            ColumnValue::Int(v)   => v.to_string(),
+            ColumnValue::Float(v) => v.to_string(),
+            ColumnValue::Bool(v)  => (*v as u8).to_string(),
+            ColumnValue::Text(s)  => s.clone(),
+        }
+    }
+}
+
+/// Check that extra column names are non-empty, unique, and distinct from
+/// the built-in snapshot columns.
+pub(crate) fn validate(columns: &[ColumnSpec]) -> OutputResult<()> {
+    for (i, col) in columns.iter().enumerate() {
+        if col.name.is_empty() {
+            return Err(OutputError::Column("column name is empty".into()));
+        }
+        if SNAPSHOT_COLUMNS.contains(&col.name.as_str())
+            || columns[..i].iter().any(|c| c.name == col.name)
+        {
+            return Err(OutputError::Column(format!("duplicate column {:?}", col.name)));
+        }
+    }
+    Ok(())
+}
+
+/// The value of column `c` for row `i`, or `Null` if the caller supplied no
+/// values for that column.
+pub(crate) fn cell(columns: &[Vec<ColumnValue>], c: usize, i: usize) -> &ColumnValue {
+    columns.get(c).and_then(|col| col.get(i)).unwrap_or(&ColumnValue::Null)
+}
+
+// ── ColumnExtractor ───────────────────────────────────────────────────────────
+
+/// Produces one extra snapshot column from the agent store.
+pub trait ColumnExtractor {
+    /// Column name and type, fixed for the whole run.
+    fn spec(&self) -> ColumnSpec;
+
+    /// Append one value per agent, in `AgentId` order, to `out`.
+    fn extract(&self, agents: &AgentStore, out: &mut Vec<ColumnValue>);
+}
+
+/// A [`ColumnExtractor`] that maps each agent's component `T` to a value.
+///
+/// If `T` was not registered with the agent store, every value is `Null`.
+pub struct ComponentColumn<T, F> {
+    name: String,
+    ty:   ColumnType,
+    f:    F,
+    _t:   PhantomData<fn(&T)>,
+}
+
+impl<T, F> ComponentColumn<T, F>
+where
+    T: Default + Send + Sync + 'static,
+    F: Fn(&T) -> ColumnValue,
+{
+    /// Column `name` of type `ty`, computed by `f` from component `T`.
+    pub fn new(name: impl Into<String>, ty: ColumnType, f: F) -> Self {
+        Self { name: name.into(), ty, f, _t: PhantomData }
+    }
+}
+
+impl<T, F> ColumnExtractor for ComponentColumn<T, F>
+where
+    T: Default + Send + Sync + 'static,
+    F: Fn(&T) -> ColumnValue,
+{
+    fn spec(&self) -> ColumnSpec {
+        ColumnSpec { name: self.name.clone(), ty: self.ty }
+    }
+
+    fn extract(&self, agents: &AgentStore, out: &mut Vec<ColumnValue>) {
+        match agents.component::<T>() {
+            Some(values) => out.extend(values.iter().map(&self.f)),
+            None => out.resize(out.len() + agents.count, ColumnValue::Null),
+        }
+    }
+}
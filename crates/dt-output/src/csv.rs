@@ -1,71 +1,531 @@
 //! CSV output backend.
 //!
-//! Creates two files in the configured output directory:
+//! Creates four files in the configured output directory:
 //! - `agent_snapshots.csv`
 //! - `tick_summaries.csv`
+//! - `contacts.csv`
+//! - `trips.csv`
+//!
+//! `od_matrix.csv`, `link_volumes.csv`, and `routes.csv` are added if the
+//! origin–destination matrix, link volumes, or routes are written.  A
+//! route's `edges` column holds its edge ids as a JSON array.
+//!
+//! [`CsvWriter::new_compressed`] writes the same files through gzip (feature
+//! `gzip`, `.csv.gz`) or zstd (feature `zstd`, `.csv.zst`) instead.
+//! [`CsvWriter::to_object_store`] (feature `object-store`) uploads them to
+//! an object store rather than a local directory.
+//!
+//! [`CsvSnapshotReader`] reads `agent_snapshots.csv` (or its `.csv.gz` /
+//! `.csv.zst` form, with the matching feature) back for warm starts.
 
 use std::fs::File;
-use std::path::Path;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
 
-use csv::Writer;
+use csv::{Reader, Writer};
+use dt_core::Tick;
+use dt_sim::{SnapshotReader, StateSnapshot};
 
-use crate::{AgentSnapshotRow, OutputResult, TickSummaryRow};
+use crate::columns::{self, cell, SNAPSHOT_COLUMNS};
+use crate::dest::{Dest, DestFile};
+#[cfg(feature = "object-store")]
+use crate::store::ObjectDir;
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnValue, ContactRow, LinkVolumeRow, OdRow, OutputError,
+    OutputResult, RouteRow, TickSummaryRow, TripRow,
+};
 use crate::writer::OutputWriter;
 
-/// Writes simulation output to two CSV files.
+// ── Compression ───────────────────────────────────────────────────────────────
+
+/// Stream compression applied by [`CsvWriter::new_compressed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Plain `.csv` files.
+    #[default]
+    None,
+    /// Gzip at the default level; files end in `.csv.gz`.
+    #[cfg(feature = "gzip")]
+    Gzip,
+    /// Zstandard at the default level; files end in `.csv.zst`.
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// File name extension, including the `csv` part.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Compression::None => "csv",
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => "csv.gz",
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => "csv.zst",
+        }
+    }
+}
+
+/// Open a CSV file written with `compression` for reading.
+pub(crate) fn open_csv(path: &Path, compression: Compression) -> OutputResult<Box<dyn Read>> {
+    let file = File::open(path)?;
+    Ok(match compression {
+        Compression::None => Box::new(file),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(file)),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Box::new(zstd::Decoder::new(file)?),
+    })
+}
+
+/// The byte stream under one CSV file.
+enum Sink {
+    Plain(DestFile),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<DestFile>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::Encoder<'static, DestFile>),
+    /// A compressed stream whose trailer has been written.
+    Finished,
+}
+
+impl Sink {
+    fn create(dest: &Dest, name: &str, compression: Compression) -> io::Result<Self> {
+        let file = dest.create(name)?;
+        Ok(match compression {
+            Compression::None => Sink::Plain(file),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => Sink::Gzip(flate2::write::GzEncoder::new(file, flate2::Compression::default())),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Sink::Zstd(zstd::Encoder::new(file, 0)?),
+        })
+    }
+
+    /// Write the compressed stream's trailer, if any, and finish the file.
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Sink::Plain(file) => file.finish(),
+            #[cfg(feature = "gzip")]
+            Sink::Gzip(encoder) => encoder.finish()?.finish(),
+            #[cfg(feature = "zstd")]
+            Sink::Zstd(encoder) => encoder.finish()?.finish(),
+            Sink::Finished => Ok(()),
+        }
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Sink::Plain(w) => w.write(buf),
+            #[cfg(feature = "gzip")]
+            Sink::Gzip(w) => w.write(buf),
+            #[cfg(feature = "zstd")]
+            Sink::Zstd(w) => w.write(buf),
+            Sink::Finished => Err(io::Error::other("CSV file already finished")),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Sink::Plain(w) => w.flush(),
+            #[cfg(feature = "gzip")]
+            Sink::Gzip(w) => w.flush(),
+            #[cfg(feature = "zstd")]
+            Sink::Zstd(w) => w.flush(),
+            Sink::Finished => Ok(()),
+        }
+    }
+}
+
+/// Create (truncating) CSV file `{table}.{ext}` and write its header.
+fn csv_writer<'a>(
+    dest:        &Dest,
+    table:       &str,
+    compression: Compression,
+    header:      impl IntoIterator<Item = &'a str>,
+) -> OutputResult<Writer<Sink>> {
+    let name = format!("{table}.{}", compression.extension());
+    let mut writer = Writer::from_writer(Sink::create(dest, &name, compression)?);
+    writer.write_record(header)?;
+    Ok(writer)
+}
+
+/// Flush a CSV writer and finish its stream.  Later writes to `writer` fail.
+fn close(writer: &mut Writer<Sink>) -> OutputResult<()> {
+    let writer = std::mem::replace(writer, Writer::from_writer(Sink::Finished));
+    writer.into_inner().map_err(|e| e.into_error())?.finish()?;
+    Ok(())
+}
+
+// ── CsvWriter ─────────────────────────────────────────────────────────────────
+
+/// Writes simulation output to four CSV files, optionally compressed.
 pub struct CsvWriter {
-    snapshots:  Writer<File>,
-    summaries:  Writer<File>,
-    finished:   bool,
+    dest:        Dest,
+    compression: Compression,
+    snapshots:   Writer<Sink>,
+    /// Number of declared extra snapshot columns.
+    extra:       usize,
+    /// Whether any snapshot row has been written (columns are then fixed).
+    snap_rows:   bool,
+    summaries:   Writer<Sink>,
+    contacts:    Writer<Sink>,
+    trips:       Writer<Sink>,
+    /// Created by the first link volume write.
+    volumes:     Option<Writer<Sink>>,
+    /// Created by the first route write.
+    routes:      Option<Writer<Sink>>,
+    finished:    bool,
 }
 
 impl CsvWriter {
-    /// Open (or create) the two CSV files in `dir` and write the header rows.
+    /// Open (or create) the four CSV files in `dir` and write the header rows.
     pub fn new(dir: &Path) -> OutputResult<Self> {
-        let mut snapshots = Writer::from_path(dir.join("agent_snapshots.csv"))?;
-        snapshots.write_record(["agent_id", "tick", "departure_node", "in_transit", "destination_node"])?;
+        Self::new_compressed(dir, Compression::None)
+    }
 
-        let mut summaries = Writer::from_path(dir.join("tick_summaries.csv"))?;
-        summaries.write_record(["tick", "unix_time_secs", "woken_agents"])?;
+    /// Like [`new`][Self::new], but compress every file with `compression`.
+    ///
+    /// Compressed files are only complete once [`finish`][OutputWriter::finish]
+    /// has written the stream trailer.
+    pub fn new_compressed(dir: &Path, compression: Compression) -> OutputResult<Self> {
+        Self::create(Dest::Local(dir.to_path_buf()), compression)
+    }
+
+    /// Like [`new_compressed`][Self::new_compressed], but upload the files
+    /// under `dir` in an object store.
+    ///
+    /// Each object appears once [`finish`][OutputWriter::finish] has
+    /// completed its upload.
+    #[cfg(feature = "object-store")]
+    pub fn to_object_store(dir: ObjectDir, compression: Compression) -> OutputResult<Self> {
+        Self::create(Dest::Store(dir), compression)
+    }
+
+    fn create(dest: Dest, compression: Compression) -> OutputResult<Self> {
+        let snapshots = csv_writer(&dest, "agent_snapshots", compression, SNAPSHOT_COLUMNS)?;
+        let summaries = csv_writer(
+            &dest,
+            "tick_summaries",
+            compression,
+            ["tick", "unix_time_secs"].into_iter().chain(TickSummaryRow::COUNT_COLUMNS),
+        )?;
+        let contacts = csv_writer(&dest, "contacts", compression, ["tick", "agent_a", "agent_b", "node"])?;
+        let trips = csv_writer(
+            &dest,
+            "trips",
+            compression,
+            ["agent", "depart_tick", "arrive_tick", "from", "to", "mode", "travel_secs", "distance_m"],
+        )?;
 
         Ok(Self {
+            dest,
+            compression,
             snapshots,
+            extra:     0,
+            snap_rows: false,
             summaries,
-            finished: false,
+            contacts,
+            trips,
+            volumes:   None,
+            routes:    None,
+            finished:  false,
         })
     }
 }
 
 impl OutputWriter for CsvWriter {
-    fn write_snapshots(&mut self, rows: &[AgentSnapshotRow]) -> OutputResult<()> {
-        for row in rows {
-            self.snapshots.write_record(&[
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()> {
+        columns::validate(columns)?;
+        if self.snap_rows {
+            return Err(OutputError::Column(
+                "cannot change snapshot columns after snapshots were written".into(),
+            ));
+        }
+        // Only the header has been written; close the file and start over.
+        close(&mut self.snapshots)?;
+        let header = SNAPSHOT_COLUMNS.iter().copied().chain(columns.iter().map(|c| c.name.as_str()));
+        self.snapshots = csv_writer(&self.dest, "agent_snapshots", self.compression, header)?;
+        self.extra = columns.len();
+        Ok(())
+    }
+
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:    &[AgentSnapshotRow],
+        columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()> {
+        let mut record = Vec::with_capacity(SNAPSHOT_COLUMNS.len() + self.extra);
+        for (i, row) in rows.iter().enumerate() {
+            record.clear();
+            record.extend([
                 row.agent_id.to_string(),
                 row.tick.to_string(),
                 row.departure_node.to_string(),
                 (row.in_transit as u8).to_string(),
                 row.destination_node.to_string(),
-            ])?;
+                opt_to_string(row.lat),
+                opt_to_string(row.lon),
+                opt_to_string(row.departure_tick),
+                opt_to_string(row.arrival_tick),
+            ]);
+            record.extend((0..self.extra).map(|c| cell(columns, c, i).to_field()));
+            self.snapshots.write_record(&record)?;
+            self.snap_rows = true;
         }
         Ok(())
     }
 
     fn write_tick_summary(&mut self, row: &TickSummaryRow) -> OutputResult<()> {
-        self.summaries.write_record(&[
-            row.tick.to_string(),
-            row.unix_time_secs.to_string(),
-            row.woken_agents.to_string(),
-        ])?;
+        let mut record = vec![row.tick.to_string(), row.unix_time_secs.to_string()];
+        record.extend(row.counts().iter().map(u64::to_string));
+        self.summaries.write_record(&record)?;
+        Ok(())
+    }
+
+    fn write_contacts(&mut self, rows: &[ContactRow]) -> OutputResult<()> {
+        for row in rows {
+            self.contacts.write_record(&[
+                row.tick.to_string(),
+                row.agent_a.to_string(),
+                row.agent_b.to_string(),
+                row.node.to_string(),
+            ])?;
+        }
+        Ok(())
+    }
+
+    fn write_trips(&mut self, rows: &[TripRow]) -> OutputResult<()> {
+        for row in rows {
+            self.trips.write_record(&[
+                row.agent.to_string(),
+                row.depart_tick.to_string(),
+                row.arrive_tick.to_string(),
+                row.from.to_string(),
+                row.to.to_string(),
+                row.mode.as_str().to_owned(),
+                row.travel_secs.to_string(),
+                row.distance_m.to_string(),
+            ])?;
+        }
+        Ok(())
+    }
+
+    fn write_routes(&mut self, rows: &[RouteRow]) -> OutputResult<()> {
+        let routes = match &mut self.routes {
+            Some(routes) => routes,
+            None => self.routes.insert(csv_writer(
+                &self.dest,
+                "routes",
+                self.compression,
+                ["agent", "depart_tick", "from", "to", "mode", "edges"],
+            )?),
+        };
+        for row in rows {
+            routes.write_record(&[
+                row.agent.to_string(),
+                row.depart_tick.to_string(),
+                row.from.to_string(),
+                row.to.to_string(),
+                row.mode.as_str().to_owned(),
+                row.edge_list(),
+            ])?;
+        }
         Ok(())
     }
 
+    fn write_od_matrix(&mut self, rows: &[OdRow]) -> OutputResult<()> {
+        let mut od = csv_writer(
+            &self.dest,
+            "od_matrix",
+            self.compression,
+            ["origin_zone", "dest_zone", "hour", "mode", "trips"],
+        )?;
+        for row in rows {
+            od.write_record(&[
+                row.origin_zone.to_string(),
+                row.dest_zone.to_string(),
+                row.hour.to_string(),
+                row.mode.as_str().to_owned(),
+                row.trips.to_string(),
+            ])?;
+        }
+        close(&mut od)
+    }
+
+    fn write_link_volumes(&mut self, rows: &[LinkVolumeRow]) -> OutputResult<()> {
+        let volumes = match &mut self.volumes {
+            Some(volumes) => volumes,
+            None => self.volumes.insert(csv_writer(
+                &self.dest,
+                "link_volumes",
+                self.compression,
+                ["tick", "edge_id", "vehicles"],
+            )?),
+        };
+        for row in rows {
+            volumes.write_record(&[row.tick.to_string(), row.edge.to_string(), row.vehicles.to_string()])?;
+        }
+        Ok(())
+    }
+
+    fn output_dir(&self) -> Option<&Path> {
+        self.dest.local_dir()
+    }
+
+    fn write_file(&mut self, name: &str, contents: &[u8]) -> OutputResult<()> {
+        Ok(self.dest.write_file(name, contents)?)
+    }
+
     fn finish(&mut self) -> OutputResult<()> {
         if self.finished {
             return Ok(());
         }
         self.finished = true;
-        self.snapshots.flush()?;
-        self.summaries.flush()?;
+        close(&mut self.snapshots)?;
+        close(&mut self.summaries)?;
+        close(&mut self.contacts)?;
+        close(&mut self.trips)?;
+        if let Some(volumes) = &mut self.volumes {
+            close(volumes)?;
+        }
+        if let Some(routes) = &mut self.routes {
+            close(routes)?;
+        }
         Ok(())
     }
 }
+
+// ── CsvSnapshotReader ─────────────────────────────────────────────────────────
+
+/// Reads one tick of `agent_snapshots.csv` (as written by [`CsvWriter`]) for
+/// [`SimBuilder::initial_state_from_snapshot`][dt_sim::SimBuilder::initial_state_from_snapshot].
+///
+/// By default the latest tick in the file is used; select an earlier one
+/// with [`at_tick`][Self::at_tick].  Columns are found by header name, so
+/// extra columns may appear anywhere; `lat`, `lon`, `departure_tick`, and
+/// `arrival_tick` may be missing, the others are required.
+pub struct CsvSnapshotReader {
+    dir:  PathBuf,
+    tick: Option<u64>,
+}
+
+impl CsvSnapshotReader {
+    /// Read `{dir}/agent_snapshots.csv`, or `agent_snapshots.csv.gz` /
+    /// `.csv.zst` if that is what the directory holds.  Compressed files
+    /// need the `gzip` / `zstd` feature; without it reading them fails.
+    pub fn new(dir: &Path) -> Self {
+        Self { dir: dir.to_path_buf(), tick: None }
+    }
+
+    /// Restore the snapshot taken at `tick` instead of the latest one.
+    pub fn at_tick(mut self, tick: Tick) -> Self {
+        self.tick = Some(tick.0);
+        self
+    }
+
+    /// Parse the rows of the selected tick.
+    pub fn read_rows(&self) -> OutputResult<Vec<AgentSnapshotRow>> {
+        let (path, compression) = self.file()?;
+        let mut reader = Reader::from_reader(open_csv(&path, compression)?);
+        let headers = reader.headers()?.clone();
+        let column = |name: &str| headers.iter().position(|h| h == name);
+        let required = |name: &str| {
+            column(name).ok_or_else(|| OutputError::Snapshot(format!("{} has no {name:?} column", path.display())))
+        };
+        let agent_id = required("agent_id")?;
+        let tick = required("tick")?;
+        let departure_node = required("departure_node")?;
+        let in_transit = required("in_transit")?;
+        let destination_node = required("destination_node")?;
+        // Absent in files written before coordinates and journey ticks were added.
+        let (lat, lon) = (column("lat"), column("lon"));
+        let (departure_tick, arrival_tick) = (column("departure_tick"), column("arrival_tick"));
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record?;
+            let field = |i: usize| -> OutputResult<&str> {
+                record.get(i).ok_or_else(|| OutputError::Snapshot(format!("missing field {i} in {record:?}")))
+            };
+            let optional = |i: Option<usize>| i.and_then(|i| record.get(i));
+            rows.push(AgentSnapshotRow {
+                agent_id:         parse(field(agent_id)?)?,
+                tick:             parse(field(tick)?)?,
+                departure_node:   parse(field(departure_node)?)?,
+                in_transit:       field(in_transit)? == "1",
+                destination_node: parse(field(destination_node)?)?,
+                lat:              parse_opt(optional(lat))?,
+                lon:              parse_opt(optional(lon))?,
+                departure_tick:   parse_opt(optional(departure_tick))?,
+                arrival_tick:     parse_opt(optional(arrival_tick))?,
+            });
+        }
+
+        let tick = match self.tick {
+            Some(t) => t,
+            None => rows.iter().map(|r| r.tick).max().ok_or_else(|| {
+                OutputError::Snapshot(format!("{} contains no rows", path.display()))
+            })?,
+        };
+        rows.retain(|r| r.tick == tick);
+        if rows.is_empty() {
+            return Err(OutputError::Snapshot(format!(
+                "{} has no rows for tick {tick}", path.display(),
+            )));
+        }
+        Ok(rows)
+    }
+
+    /// The snapshot file in `dir` and its compression.  A compressed file
+    /// this build cannot decode is an error; with no file at all, the plain
+    /// `.csv` path is returned and opening it fails.
+    fn file(&self) -> OutputResult<(PathBuf, Compression)> {
+        let path = |ext: &str| self.dir.join(format!("agent_snapshots.{ext}"));
+        let readable = [
+            Compression::None,
+            #[cfg(feature = "gzip")]
+            Compression::Gzip,
+            #[cfg(feature = "zstd")]
+            Compression::Zstd,
+        ];
+        if let Some(c) = readable.into_iter().find(|c| path(c.extension()).exists()) {
+            return Ok((path(c.extension()), c));
+        }
+        for (ext, feature) in [("csv.gz", "gzip"), ("csv.zst", "zstd")] {
+            if path(ext).exists() {
+                return Err(OutputError::Snapshot(format!(
+                    "{} is compressed; reading it needs dt-output's `{feature}` feature", path(ext).display(),
+                )));
+            }
+        }
+        Ok((path("csv"), Compression::None))
+    }
+}
+
+impl SnapshotReader for CsvSnapshotReader {
+    fn read_snapshot(&mut self) -> Result<StateSnapshot, String> {
+        let rows = self.read_rows().map_err(|e| e.to_string())?;
+        Ok(StateSnapshot {
+            tick:   Tick(rows[0].tick),
+            agents: rows.into_iter().map(Into::into).collect(),
+        })
+    }
+}
+
+fn parse<T: std::str::FromStr>(s: &str) -> OutputResult<T> {
+    s.parse().map_err(|_| OutputError::Snapshot(format!("invalid number {s:?}")))
+}
+
+/// Parse an optional column; a missing or empty field is `None`.
+fn parse_opt<T: std::str::FromStr>(s: Option<&str>) -> OutputResult<Option<T>> {
+    match s {
+        None | Some("") => Ok(None),
+        Some(s) => parse(s).map(Some),
+    }
+}
+
+/// Format an optional value, writing an empty field for `None`.
+fn opt_to_string<T: ToString>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
@@ -0,0 +1,82 @@
+//! Where the file-based writers put their files: a local directory or, with
+//! feature `object-store`, an [`ObjectDir`][crate::store::ObjectDir].
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "object-store")]
+use crate::store::{ObjectDir, ObjectFile};
+
+/// The destination directory of a writer.
+pub(crate) enum Dest {
+    Local(PathBuf),
+    #[cfg(feature = "object-store")]
+    Store(ObjectDir),
+}
+
+impl Dest {
+    /// Create (truncating) file `name`.
+    pub(crate) fn create(&self, name: &str) -> io::Result<DestFile> {
+        match self {
+            Dest::Local(dir) => Ok(DestFile::Local(File::create(dir.join(name))?)),
+            #[cfg(feature = "object-store")]
+            Dest::Store(dir) => Ok(DestFile::Object(dir.create(name)?)),
+        }
+    }
+
+    /// Write the whole of file `name` at once.
+    pub(crate) fn write_file(&self, name: &str, contents: &[u8]) -> io::Result<()> {
+        match self {
+            Dest::Local(dir) => std::fs::write(dir.join(name), contents),
+            #[cfg(feature = "object-store")]
+            Dest::Store(dir) => dir.put(name, contents),
+        }
+    }
+
+    /// The directory, if local.
+    pub(crate) fn local_dir(&self) -> Option<&Path> {
+        match self {
+            Dest::Local(dir) => Some(dir),
+            #[cfg(feature = "object-store")]
+            Dest::Store(_) => None,
+        }
+    }
+}
+
+/// One file created by [`Dest::create`].
+pub(crate) enum DestFile {
+    Local(File),
+    #[cfg(feature = "object-store")]
+    Object(ObjectFile),
+}
+
+impl DestFile {
+    /// Complete the file.  An object only appears in its store once
+    /// finished; dropping it unfinished discards it.
+    pub(crate) fn finish(self) -> io::Result<()> {
+        match self {
+            DestFile::Local(_) => Ok(()),
+            #[cfg(feature = "object-store")]
+            DestFile::Object(object) => object.finish(),
+        }
+    }
+}
+
+impl Write for DestFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            DestFile::Local(w) => w.write(buf),
+            #[cfg(feature = "object-store")]
+            DestFile::Object(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            DestFile::Local(w) => w.flush(),
+            #[cfg(feature = "object-store")]
+            DestFile::Object(w) => w.flush(),
+        }
+    }
+}
@@ -1,8 +1,9 @@
 //! Error types for dt-output.
 
+use dt_core::{DtError, ErrorCategory};
 use thiserror::Error;
 
-/// Errors that can occur when writing simulation output.
+/// Errors that can occur when writing (or reading back) simulation output.
 #[derive(Debug, Error)]
 pub enum OutputError {
     #[error("I/O error: {0}")]
@@ -11,18 +12,63 @@ pub enum OutputError {
     #[error("CSV write error: {0}")]
     Csv(#[from] csv::Error),
 
+    #[error("snapshot read error: {0}")]
+    Snapshot(String),
+
+    #[error("snapshot column error: {0}")]
+    Column(String),
+
+    /// Output read back by [`ResultsReader`][crate::ResultsReader] is
+    /// missing a column or holds a value of the wrong type.
+    #[error("results read error: {0}")]
+    Read(String),
+
     #[cfg(feature = "sqlite")]
     #[error("SQLite error: {0}")]
     Sqlite(#[from] rusqlite::Error),
 
-    #[cfg(feature = "parquet")]
+    #[cfg(feature = "arrow")]
     #[error("Arrow error: {0}")]
     Arrow(#[from] arrow::error::ArrowError),
 
     #[cfg(feature = "parquet")]
     #[error("Parquet error: {0}")]
     Parquet(#[from] parquet::errors::ParquetError),
+
+    #[cfg(feature = "postgres")]
+    #[error("PostgreSQL error: {0}")]
+    Postgres(String),
+
+    #[cfg(feature = "kafka")]
+    #[error("Kafka error: {0}")]
+    Kafka(#[from] rdkafka::error::KafkaError),
+
+    #[cfg(feature = "mqtt")]
+    #[error("MQTT error: {0}")]
+    Mqtt(String),
+
+    #[cfg(feature = "heatmap")]
+    #[error("heatmap grid error: {0}")]
+    Grid(String),
+
+    #[cfg(feature = "heatmap")]
+    #[error("GeoTIFF error: {0}")]
+    Tiff(#[from] tiff::TiffError),
+
+    #[cfg(feature = "object-store")]
+    #[error("object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+
+    #[cfg(feature = "object-store")]
+    #[error("invalid object store URL: {0}")]
+    Url(#[from] url::ParseError),
 }
 
 /// Alias for `Result<T, OutputError>`.
 pub type OutputResult<T> = Result<T, OutputError>;
+
+impl From<OutputError> for DtError {
+    fn from(err: OutputError) -> Self {
+        DtError::subsystem(ErrorCategory::Output, err)
+    }
+}
@@ -0,0 +1,193 @@
+//! GeoJSON backend for visualization (feature `geojson`).
+//!
+//! Each snapshot becomes one `FeatureCollection` of agent `Point`s,
+//! `snapshot_{tick:08}.geojson` in the configured output directory, so the
+//! files sort in tick order and load directly into deck.gl or kepler.gl.
+//! Feature properties are the snapshot columns (including extra columns),
+//! with `u32::MAX` node sentinels written as `null`:
+//!
+//! ```text
+//! {"type":"Feature","geometry":{"type":"Point","coordinates":[13.405,52.52]},
+//!  "properties":{"agent_id":3,"tick":12,"departure_node":7,"in_transit":false,"destination_node":null}}
+//! ```
+//!
+//! Positions come from the snapshot rows' `lat`/`lon`, so the observer must
+//! be given the network with
+//! [`SimOutputObserver::with_network`][crate::SimOutputObserver::with_network];
+//! agents without a position are left out.  [`GeoJsonWriter::write_network`]
+//! adds the road network as `network.geojson`.
+//!
+//! Tick summaries, contacts, trips, routes, the OD matrix, and link volumes
+//! are not written.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use dt_spatial::RoadNetwork;
+use serde_json::{Map, Value, json};
+
+use crate::columns::{self, cell};
+use crate::json::{json_value, node};
+use crate::writer::OutputWriter;
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnValue, ContactRow, LinkVolumeRow, OdRow, OutputError,
+    OutputResult, RouteRow, TickSummaryRow, TripRow,
+};
+
+/// A coordinate without the noise digits of widening `f32` to `f64`.
+fn coord(v: f32) -> Value {
+    v.to_string().parse::<f64>().map_or(Value::Null, Value::from)
+}
+
+fn write_collection(path: &Path, features: Vec<Value>) -> OutputResult<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let collection = json!({ "type": "FeatureCollection", "features": features });
+    serde_json::to_writer(&mut out, &collection).map_err(std::io::Error::from)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Writes agent snapshots as one GeoJSON `FeatureCollection` per tick.
+///
+/// Rows of one tick may arrive over several calls; a tick's file is written
+/// when a row of another tick arrives or on `finish()`.
+pub struct GeoJsonWriter {
+    dir:       PathBuf,
+    /// Declared extra snapshot columns.
+    extra:     Vec<ColumnSpec>,
+    /// Whether any snapshot row has been written (columns are then fixed).
+    snap_rows: bool,
+    /// Tick and features of the snapshot being collected.
+    pending:   Option<(u64, Vec<Value>)>,
+}
+
+impl GeoJsonWriter {
+    /// Write snapshot files into `dir`, which must exist.
+    pub fn new(dir: &Path) -> OutputResult<Self> {
+        if !dir.is_dir() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("output directory {} does not exist", dir.display()),
+            ).into());
+        }
+        Ok(Self { dir: dir.to_path_buf(), extra: Vec::new(), snap_rows: false, pending: None })
+    }
+
+    /// Write `network` to `network.geojson`: one `LineString` per directed
+    /// edge with properties `edge_id`, `from`, `to`, `length_m`, and
+    /// `travel_secs` (by car).
+    pub fn write_network(&self, network: &RoadNetwork) -> OutputResult<()> {
+        let point = |n: usize| {
+            let p = network.node_pos[n];
+            json!([coord(p.lon), coord(p.lat)])
+        };
+        let features = (0..network.edge_count())
+            .map(|e| {
+                let (from, to) = (network.edge_from[e], network.edge_to[e]);
+                json!({
+                    "type":       "Feature",
+                    "geometry":   { "type": "LineString", "coordinates": [point(from.index()), point(to.index())] },
+                    "properties": {
+                        "edge_id":     e,
+                        "from":        from.0,
+                        "to":          to.0,
+                        "length_m":    coord(network.edge_length_m[e]),
+                        "travel_secs": network.edge_travel_ms[e] as f64 / 1000.0,
+                    },
+                })
+            })
+            .collect();
+        write_collection(&self.dir.join("network.geojson"), features)
+    }
+
+    /// Write the pending snapshot, if any.
+    fn flush_pending(&mut self) -> OutputResult<()> {
+        if let Some((tick, features)) = self.pending.take() {
+            write_collection(&self.dir.join(format!("snapshot_{tick:08}.geojson")), features)?;
+        }
+        Ok(())
+    }
+}
+
+impl OutputWriter for GeoJsonWriter {
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()> {
+        columns::validate(columns)?;
+        if self.snap_rows {
+            return Err(OutputError::Column(
+                "cannot change snapshot columns after snapshots were written".into(),
+            ));
+        }
+        self.extra = columns.to_vec();
+        Ok(())
+    }
+
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:    &[AgentSnapshotRow],
+        columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()> {
+        for (i, row) in rows.iter().enumerate() {
+            if self.pending.as_ref().is_none_or(|(tick, _)| *tick != row.tick) {
+                self.flush_pending()?;
+                self.pending = Some((row.tick, Vec::new()));
+            }
+            let (Some(lat), Some(lon)) = (row.lat, row.lon) else {
+                continue;
+            };
+            let mut props = Map::with_capacity(7 + self.extra.len());
+            props.insert("agent_id".into(),         row.agent_id.into());
+            props.insert("tick".into(),             row.tick.into());
+            props.insert("departure_node".into(),   node(row.departure_node));
+            props.insert("in_transit".into(),       row.in_transit.into());
+            props.insert("destination_node".into(), node(row.destination_node));
+            props.insert("departure_tick".into(),   row.departure_tick.into());
+            props.insert("arrival_tick".into(),     row.arrival_tick.into());
+            for (c, col) in self.extra.iter().enumerate() {
+                props.insert(col.name.clone(), json_value(cell(columns, c, i)));
+            }
+            let feature = json!({
+                "type":       "Feature",
+                "geometry":   { "type": "Point", "coordinates": [coord(lon), coord(lat)] },
+                "properties": props,
+            });
+            if let Some((_, features)) = &mut self.pending {
+                features.push(feature);
+            }
+        }
+        self.snap_rows |= !rows.is_empty();
+        Ok(())
+    }
+
+    fn write_tick_summary(&mut self, _row: &TickSummaryRow) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_contacts(&mut self, _rows: &[ContactRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_trips(&mut self, _rows: &[TripRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_routes(&mut self, _rows: &[RouteRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_od_matrix(&mut self, _rows: &[OdRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_link_volumes(&mut self, _rows: &[LinkVolumeRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn output_dir(&self) -> Option<&Path> {
+        Some(&self.dir)
+    }
+
+    fn finish(&mut self) -> OutputResult<()> {
+        self.flush_pending()
+    }
+}
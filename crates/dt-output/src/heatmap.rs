@@ -0,0 +1,305 @@
+//! Density raster backend (feature `heatmap`).
+//!
+//! [`HeatmapWriter`] counts the agents in each cell of a lat/lon
+//! [`HeatmapGrid`] and writes one raster per snapshot,
+//! `heatmap_{tick:08}.{npy,tif}` in the configured output directory, so
+//! front-ends and notebooks can load density frames without binning the
+//! snapshot rows themselves.  Cells hold `u32` counts, row 0 being the
+//! northernmost row and column 0 the westernmost:
+//! - [`RasterFormat::Npy`] writes a NumPy `.npy` file of shape
+//!   `(rows, cols)` (`numpy.load` reads it directly).
+//! - [`RasterFormat::GeoTiff`] writes a single-band GeoTIFF in WGS 84
+//!   (EPSG:4326), which GIS tools place on a map without further setup.
+//!
+//! Positions come from the snapshot rows' `lat`/`lon`, so the observer must
+//! be given the network with
+//! [`SimOutputObserver::with_network`][crate::SimOutputObserver::with_network];
+//! agents without a position or outside the grid are not counted.  Tick
+//! summaries, contacts, trips, routes, the OD matrix, and link volumes are
+//! not written.
+//!
+//! ```rust,ignore
+//! // ~100 m cells over the network.
+//! let grid = HeatmapGrid::covering(&network, 0.001);
+//! let writer = HeatmapWriter::new(Path::new("./frames"), grid)?.format(RasterFormat::GeoTiff);
+//! let mut obs = SimOutputObserver::new(writer, &config).with_network(&network);
+//! ```
+
+use std::fs::File;
+use std::io::{BufWriter, Seek, Write};
+use std::path::{Path, PathBuf};
+
+use dt_core::BBox;
+use dt_spatial::RoadNetwork;
+use tiff::encoder::{TiffEncoder, colortype};
+use tiff::tags::Tag;
+
+use crate::columns;
+use crate::writer::OutputWriter;
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnValue, ContactRow, LinkVolumeRow, OdRow, OutputError,
+    OutputResult, RouteRow, TickSummaryRow, TripRow,
+};
+
+/// GeoKey directory for a geographic WGS 84 raster with area pixels: a
+/// header (version 1.1.0, 3 keys), then (key, location, count, value) for
+/// `GTModelTypeGeoKey`, `GTRasterTypeGeoKey`, and `GeographicTypeGeoKey`.
+const GEO_KEYS: [u16; 16] = [
+    1,    1, 0, 3,
+    1024, 0, 1, 2,
+    1025, 0, 1, 1,
+    2048, 0, 1, 4326,
+];
+
+/// Encoding of the raster files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RasterFormat {
+    /// NumPy `.npy`, `<u4` little-endian, C order.
+    #[default]
+    Npy,
+    /// GeoTIFF, one uncompressed 32-bit unsigned band.
+    GeoTiff,
+}
+
+impl RasterFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            RasterFormat::Npy     => "npy",
+            RasterFormat::GeoTiff => "tif",
+        }
+    }
+}
+
+/// A lat/lon bounding box divided into `cols` × `rows` equal cells.
+///
+/// Positions on the north or east edge belong to the last row or column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeatmapGrid {
+    pub south: f32,
+    pub west:  f32,
+    pub north: f32,
+    pub east:  f32,
+    pub cols:  u32,
+    pub rows:  u32,
+}
+
+impl HeatmapGrid {
+    /// A grid of square `cell_deg` × `cell_deg` cells whose south-west
+    /// corner is the south-west corner of `network`'s nodes and which
+    /// extends just past its north-east corner.
+    pub fn covering(network: &RoadNetwork, cell_deg: f32) -> Self {
+        let BBox { south, west, north, east } = BBox::from_points(network.node_pos.iter().copied())
+            .unwrap_or(BBox::new(0.0, 0.0, 0.0, 0.0));
+        // One more cell than needed, so the north-east node lies inside.
+        let cells = |span: f32| (span / cell_deg).floor() as u32 + 1;
+        let (cols, rows) = (cells(east - west), cells(north - south));
+        Self {
+            south,
+            west,
+            north: south + rows as f32 * cell_deg,
+            east:  west + cols as f32 * cell_deg,
+            cols,
+            rows,
+        }
+    }
+
+    /// Row-major index of the cell containing (`lat`, `lon`), if inside.
+    pub fn cell(&self, lat: f32, lon: f32) -> Option<usize> {
+        if !(self.south..=self.north).contains(&lat) || !(self.west..=self.east).contains(&lon) {
+            return None;
+        }
+        let fraction = |v: f32, from: f32, to: f32| (v - from) as f64 / (to - from) as f64;
+        let col = ((fraction(lon, self.west, self.east) * self.cols as f64) as u32).min(self.cols - 1);
+        let row = ((fraction(lat, self.north, self.south) * self.rows as f64) as u32).min(self.rows - 1);
+        Some(row as usize * self.cols as usize + col as usize)
+    }
+
+    fn validate(&self) -> OutputResult<()> {
+        if self.cols == 0 || self.rows == 0 {
+            return Err(OutputError::Grid(format!("grid has {} × {} cells", self.cols, self.rows)));
+        }
+        if !(self.south < self.north && self.west < self.east) {
+            return Err(OutputError::Grid(format!(
+                "empty bounds: south {}, west {}, north {}, east {}",
+                self.south, self.west, self.north, self.east,
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Writes agent density rasters, one file per snapshot.
+///
+/// Rows of one tick may arrive over several calls; a tick's file is written
+/// when a row of another tick arrives or on `finish()`.
+pub struct HeatmapWriter {
+    dir:         PathBuf,
+    grid:        HeatmapGrid,
+    format:      RasterFormat,
+    /// Count only agents in transit.
+    moving_only: bool,
+    /// Tick and cell counts of the snapshot being collected.
+    pending:     Option<(u64, Vec<u32>)>,
+}
+
+impl HeatmapWriter {
+    /// Write rasters over `grid` into `dir`, which must exist.
+    pub fn new(dir: &Path, grid: HeatmapGrid) -> OutputResult<Self> {
+        if !dir.is_dir() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("output directory {} does not exist", dir.display()),
+            ).into());
+        }
+        grid.validate()?;
+        Ok(Self {
+            dir:         dir.to_path_buf(),
+            grid,
+            format:      RasterFormat::default(),
+            moving_only: false,
+            pending:     None,
+        })
+    }
+
+    /// File encoding.  Default: [`RasterFormat::Npy`].
+    pub fn format(mut self, format: RasterFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Count only agents in transit, for traffic density.  Default: every
+    /// agent with a position.
+    pub fn in_transit_only(mut self) -> Self {
+        self.moving_only = true;
+        self
+    }
+
+    /// The grid the rasters cover.
+    pub fn grid(&self) -> &HeatmapGrid {
+        &self.grid
+    }
+
+    /// Write the pending raster, if any.
+    fn flush_pending(&mut self) -> OutputResult<()> {
+        let Some((tick, counts)) = self.pending.take() else {
+            return Ok(());
+        };
+        let path = self.dir.join(format!("heatmap_{tick:08}.{}", self.format.extension()));
+        let mut out = BufWriter::new(File::create(path)?);
+        match self.format {
+            RasterFormat::Npy     => write_npy(&mut out, &self.grid, &counts)?,
+            RasterFormat::GeoTiff => write_geotiff(&mut out, &self.grid, &counts)?,
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Write `counts` as a `(rows, cols)` `<u4` array in NumPy format 1.0.
+fn write_npy(out: &mut impl Write, grid: &HeatmapGrid, counts: &[u32]) -> OutputResult<()> {
+    let mut header = format!(
+        "{{'descr': '<u4', 'fortran_order': False, 'shape': ({}, {}), }}",
+        grid.rows, grid.cols,
+    );
+    // Magic, version, and length take 10 bytes; the whole header is padded
+    // with spaces to a multiple of 64 and ends in a newline.
+    let len = (10 + header.len() + 1).next_multiple_of(64) - 10;
+    header.extend(std::iter::repeat_n(' ', len - header.len() - 1));
+    header.push('\n');
+
+    out.write_all(b"\x93NUMPY\x01\x00")?;
+    out.write_all(&(len as u16).to_le_bytes())?;
+    out.write_all(header.as_bytes())?;
+    for count in counts {
+        out.write_all(&count.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Write `counts` as a single-band GeoTIFF georeferenced to `grid`.
+fn write_geotiff(
+    out:    &mut (impl Write + Seek),
+    grid:   &HeatmapGrid,
+    counts: &[u32],
+) -> OutputResult<()> {
+    let scale = [
+        (grid.east - grid.west) as f64 / grid.cols as f64,
+        (grid.north - grid.south) as f64 / grid.rows as f64,
+        0.0,
+    ];
+    // Raster (0, 0) is the north-west corner.
+    let tiepoint = [0.0, 0.0, 0.0, grid.west as f64, grid.north as f64, 0.0];
+
+    let mut tiff = TiffEncoder::new(out)?;
+    let mut image = tiff.new_image::<colortype::Gray32>(grid.cols, grid.rows)?;
+    image.encoder().write_tag(Tag::ModelPixelScaleTag, &scale[..])?;
+    image.encoder().write_tag(Tag::ModelTiepointTag, &tiepoint[..])?;
+    image.encoder().write_tag(Tag::GeoKeyDirectoryTag, &GEO_KEYS[..])?;
+    image.write_data(counts)?;
+    Ok(())
+}
+
+impl OutputWriter for HeatmapWriter {
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()> {
+        // Extra columns are not written, but are still checked.
+        columns::validate(columns)
+    }
+
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:     &[AgentSnapshotRow],
+        _columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()> {
+        let cells = self.grid.cols as usize * self.grid.rows as usize;
+        for row in rows {
+            if self.pending.as_ref().is_none_or(|(tick, _)| *tick != row.tick) {
+                self.flush_pending()?;
+                self.pending = Some((row.tick, vec![0; cells]));
+            }
+            if self.moving_only && !row.in_transit {
+                continue;
+            }
+            let (Some(lat), Some(lon)) = (row.lat, row.lon) else {
+                continue;
+            };
+            if let Some(cell) = self.grid.cell(lat, lon)
+                && let Some((_, counts)) = &mut self.pending
+            {
+                counts[cell] += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_tick_summary(&mut self, _row: &TickSummaryRow) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_contacts(&mut self, _rows: &[ContactRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_trips(&mut self, _rows: &[TripRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_routes(&mut self, _rows: &[RouteRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_od_matrix(&mut self, _rows: &[OdRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_link_volumes(&mut self, _rows: &[LinkVolumeRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn output_dir(&self) -> Option<&Path> {
+        Some(&self.dir)
+    }
+
+    fn finish(&mut self) -> OutputResult<()> {
+        self.flush_pending()
+    }
+}
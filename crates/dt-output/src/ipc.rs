@@ -0,0 +1,256 @@
+//! Arrow IPC streaming backend (feature `arrow-ipc`).
+//!
+//! Each table is written as an Arrow IPC *stream*: a schema message followed
+//! by one record batch per write call, flushed immediately.  Unlike Parquet
+//! there is no footer, so a reader (e.g. `pyarrow.ipc.open_stream` in a
+//! notebook) can consume batches while the run is still in progress.
+//!
+//! [`ArrowIpcWriter::new`] creates seven files in the output directory:
+//! - `agent_snapshots.arrows`
+//! - `tick_summaries.arrows`
+//! - `contacts.arrows`
+//! - `trips.arrows`
+//! - `routes.arrows`
+//! - `od_matrix.arrows`
+//! - `link_volumes.arrows`
+//!
+//! [`ArrowIpcWriter::from_streams`] writes to arbitrary sinks instead, such
+//! as one `TcpStream` per table.
+//!
+//! A stream's schema message is written with its first batch, so snapshot
+//! columns can be declared at any point before the first snapshot.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::datatypes::Schema;
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+
+use crate::batch::{
+    contact_batch, contact_schema, link_volume_batch, link_volume_schema, od_batch, od_schema,
+    route_batch, route_schema, snapshot_batch, snapshot_schema, summary_batch, summary_schema,
+    trip_batch, trip_schema,
+};
+use crate::columns;
+use crate::writer::OutputWriter;
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnValue, ContactRow, LinkVolumeRow, OdRow, OutputError,
+    OutputResult, RouteRow, TickSummaryRow, TripRow,
+};
+
+// ── IpcStreams ────────────────────────────────────────────────────────────────
+
+/// Destination of each table for [`ArrowIpcWriter::from_streams`].  Tables
+/// left as `None` are not written.
+pub struct IpcStreams<W> {
+    pub snapshots:    Option<W>,
+    pub summaries:    Option<W>,
+    pub contacts:     Option<W>,
+    pub trips:        Option<W>,
+    pub routes:       Option<W>,
+    pub od_matrix:    Option<W>,
+    pub link_volumes: Option<W>,
+}
+
+impl<W> Default for IpcStreams<W> {
+    fn default() -> Self {
+        Self {
+            snapshots:    None,
+            summaries:    None,
+            contacts:     None,
+            trips:        None,
+            routes:       None,
+            od_matrix:    None,
+            link_volumes: None,
+        }
+    }
+}
+
+/// One table's IPC stream, opened on the first batch.
+struct Stream<W: Write> {
+    schema: Arc<Schema>,
+    /// The sink, until the schema message has been written to it.
+    sink:   Option<W>,
+    writer: Option<StreamWriter<W>>,
+}
+
+impl<W: Write> Stream<W> {
+    fn new(schema: Arc<Schema>, sink: Option<W>) -> Self {
+        Self { schema, sink, writer: None }
+    }
+
+    /// Whether the schema message has been written.
+    fn started(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    fn open(&mut self) -> OutputResult<Option<&mut StreamWriter<W>>> {
+        if let Some(sink) = self.sink.take() {
+            self.writer = Some(StreamWriter::try_new(sink, &self.schema)?);
+        }
+        Ok(self.writer.as_mut())
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> OutputResult<()> {
+        if let Some(writer) = self.open()? {
+            writer.write(batch)?;
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write the end-of-stream marker (and the schema, if no batch was
+    /// written).  Later writes are ignored.
+    fn finish(&mut self) -> OutputResult<()> {
+        if let Some(writer) = self.open()? {
+            writer.finish()?;
+        }
+        self.writer = None;
+        Ok(())
+    }
+}
+
+// ── ArrowIpcWriter ────────────────────────────────────────────────────────────
+
+/// Streams simulation output as Arrow IPC record batches.
+///
+/// `finish()` writes each stream's end-of-stream marker; streams cut off
+/// without it are still readable up to the last complete batch.
+pub struct ArrowIpcWriter<W: Write = File> {
+    snapshots:    Stream<W>,
+    /// Declared extra snapshot columns.
+    extra:        Vec<ColumnSpec>,
+    summaries:    Stream<W>,
+    contacts:     Stream<W>,
+    trips:        Stream<W>,
+    routes:       Stream<W>,
+    od_matrix:    Stream<W>,
+    link_volumes: Stream<W>,
+    /// Set by [`new`][ArrowIpcWriter::new] only.
+    dir:          Option<PathBuf>,
+}
+
+impl ArrowIpcWriter<File> {
+    /// Create the seven `.arrows` stream files in `dir`.
+    pub fn new(dir: &Path) -> OutputResult<Self> {
+        let writer = Self::from_streams(IpcStreams {
+            snapshots:    Some(File::create(dir.join("agent_snapshots.arrows"))?),
+            summaries:    Some(File::create(dir.join("tick_summaries.arrows"))?),
+            contacts:     Some(File::create(dir.join("contacts.arrows"))?),
+            trips:        Some(File::create(dir.join("trips.arrows"))?),
+            routes:       Some(File::create(dir.join("routes.arrows"))?),
+            od_matrix:    Some(File::create(dir.join("od_matrix.arrows"))?),
+            link_volumes: Some(File::create(dir.join("link_volumes.arrows"))?),
+        });
+        Ok(Self { dir: Some(dir.to_path_buf()), ..writer })
+    }
+}
+
+impl<W: Write> ArrowIpcWriter<W> {
+    /// Write each table to its sink in `streams`.
+    ///
+    /// Batches are flushed after every write; wrap sockets in a
+    /// `BufWriter` only if per-batch latency does not matter.
+    pub fn from_streams(streams: IpcStreams<W>) -> Self {
+        Self {
+            snapshots:    Stream::new(snapshot_schema(&[]), streams.snapshots),
+            extra:        Vec::new(),
+            summaries:    Stream::new(summary_schema(), streams.summaries),
+            contacts:     Stream::new(contact_schema(), streams.contacts),
+            trips:        Stream::new(trip_schema(), streams.trips),
+            routes:       Stream::new(route_schema(), streams.routes),
+            od_matrix:    Stream::new(od_schema(), streams.od_matrix),
+            link_volumes: Stream::new(link_volume_schema(), streams.link_volumes),
+            dir:          None,
+        }
+    }
+}
+
+impl<W: Write> OutputWriter for ArrowIpcWriter<W> {
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()> {
+        columns::validate(columns)?;
+        if self.snapshots.started() {
+            return Err(OutputError::Column(
+                "cannot change snapshot columns after snapshots were written".into(),
+            ));
+        }
+        self.snapshots.schema = snapshot_schema(columns);
+        self.extra = columns.to_vec();
+        Ok(())
+    }
+
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:    &[AgentSnapshotRow],
+        columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let batch = snapshot_batch(&self.snapshots.schema, &self.extra, rows, columns)?;
+        self.snapshots.write(&batch)
+    }
+
+    fn write_tick_summary(&mut self, row: &TickSummaryRow) -> OutputResult<()> {
+        let batch = summary_batch(&self.summaries.schema, std::slice::from_ref(row))?;
+        self.summaries.write(&batch)
+    }
+
+    fn write_contacts(&mut self, rows: &[ContactRow]) -> OutputResult<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let batch = contact_batch(&self.contacts.schema, rows)?;
+        self.contacts.write(&batch)
+    }
+
+    fn write_trips(&mut self, rows: &[TripRow]) -> OutputResult<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let batch = trip_batch(&self.trips.schema, rows)?;
+        self.trips.write(&batch)
+    }
+
+    fn write_routes(&mut self, rows: &[RouteRow]) -> OutputResult<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let batch = route_batch(&self.routes.schema, rows)?;
+        self.routes.write(&batch)
+    }
+
+    fn write_od_matrix(&mut self, rows: &[OdRow]) -> OutputResult<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let batch = od_batch(&self.od_matrix.schema, rows)?;
+        self.od_matrix.write(&batch)
+    }
+
+    fn write_link_volumes(&mut self, rows: &[LinkVolumeRow]) -> OutputResult<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let batch = link_volume_batch(&self.link_volumes.schema, rows)?;
+        self.link_volumes.write(&batch)
+    }
+
+    fn output_dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
+    fn finish(&mut self) -> OutputResult<()> {
+        self.snapshots.finish()?;
+        self.summaries.finish()?;
+        self.contacts.finish()?;
+        self.trips.finish()?;
+        self.routes.finish()?;
+        self.od_matrix.finish()?;
+        self.link_volumes.finish()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+//! JSON helpers shared by the JSON Lines, GeoJSON, and streaming backends.
+//!
+//! Row objects use the CSV header names as keys, with the `u32::MAX` node
+//! sentinels written as `null`, `in_transit` as a boolean, and the trip mode
+//! as a string.
+
+// GeoJSON builds its own features and only needs the value helpers.
+#![cfg_attr(not(any(feature = "jsonl", feature = "kafka", feature = "mqtt")), allow(dead_code))]
+
+use serde_json::{Map, Value, json};
+
+use crate::columns::cell;
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnValue, ContactRow, LinkVolumeRow, OdRow, RouteRow, TickSummaryRow,
+    TripRow,
+};
+
+/// `null` for the `u32::MAX` "no node" sentinel.
+pub(crate) fn node(id: u32) -> Value {
+    if id == u32::MAX { Value::Null } else { id.into() }
+}
+
+pub(crate) fn json_value(value: &ColumnValue) -> Value {
+    match value {
+        ColumnValue::Null     => Value::Null,
+        ColumnValue::Int(v)   => (*v).into(),
+        ColumnValue::Float(v) => (*v).into(),
+        ColumnValue::Bool(v)  => (*v).into(),
+        ColumnValue::Text(s)  => s.as_str().into(),
+    }
+}
+
+/// Snapshot row `i` of a batch, with its values of the `extra` columns.
+pub(crate) fn snapshot(
+    row:     &AgentSnapshotRow,
+    extra:   &[ColumnSpec],
+    columns: &[Vec<ColumnValue>],
+    i:       usize,
+) -> Value {
+    let mut obj = Map::with_capacity(9 + extra.len());
+    obj.insert("agent_id".into(),         row.agent_id.into());
+    obj.insert("tick".into(),             row.tick.into());
+    obj.insert("departure_node".into(),   node(row.departure_node));
+    obj.insert("in_transit".into(),       row.in_transit.into());
+    obj.insert("destination_node".into(), node(row.destination_node));
+    obj.insert("lat".into(),              row.lat.into());
+    obj.insert("lon".into(),              row.lon.into());
+    obj.insert("departure_tick".into(),   row.departure_tick.into());
+    obj.insert("arrival_tick".into(),     row.arrival_tick.into());
+    for (c, col) in extra.iter().enumerate() {
+        obj.insert(col.name.clone(), json_value(cell(columns, c, i)));
+    }
+    Value::Object(obj)
+}
+
+pub(crate) fn summary(row: &TickSummaryRow) -> Value {
+    let mut obj = Map::with_capacity(2 + TickSummaryRow::COUNT_COLUMNS.len());
+    obj.insert("tick".into(),           row.tick.into());
+    obj.insert("unix_time_secs".into(), row.unix_time_secs.into());
+    for (name, count) in TickSummaryRow::COUNT_COLUMNS.into_iter().zip(row.counts()) {
+        obj.insert(name.into(), count.into());
+    }
+    Value::Object(obj)
+}
+
+pub(crate) fn contact(row: &ContactRow) -> Value {
+    json!({
+        "tick":    row.tick,
+        "agent_a": row.agent_a,
+        "agent_b": row.agent_b,
+        "node":    row.node,
+    })
+}
+
+pub(crate) fn trip(row: &TripRow) -> Value {
+    json!({
+        "agent":       row.agent,
+        "depart_tick": row.depart_tick,
+        "arrive_tick": row.arrive_tick,
+        "from":        row.from,
+        "to":          row.to,
+        "mode":        row.mode.as_str(),
+        "travel_secs": row.travel_secs,
+        "distance_m":  row.distance_m,
+    })
+}
+
+pub(crate) fn route(row: &RouteRow) -> Value {
+    json!({
+        "agent":       row.agent,
+        "depart_tick": row.depart_tick,
+        "from":        row.from,
+        "to":          row.to,
+        "mode":        row.mode.as_str(),
+        "edges":       row.edges,
+    })
+}
+
+pub(crate) fn od(row: &OdRow) -> Value {
+    json!({
+        "origin_zone": row.origin_zone,
+        "dest_zone":   row.dest_zone,
+        "hour":        row.hour,
+        "mode":        row.mode.as_str(),
+        "trips":       row.trips,
+    })
+}
+
+pub(crate) fn link_volume(row: &LinkVolumeRow) -> Value {
+    json!({
+        "tick":     row.tick,
+        "edge_id":  row.edge,
+        "vehicles": row.vehicles,
+    })
+}
@@ -0,0 +1,167 @@
+//! JSON Lines output backend (feature `jsonl`).
+//!
+//! Creates four files in the configured output directory, each holding one
+//! JSON object per line:
+//! - `agent_snapshots.jsonl`
+//! - `tick_summaries.jsonl`
+//! - `contacts.jsonl`
+//! - `trips.jsonl`
+//!
+//! `od_matrix.jsonl`, `link_volumes.jsonl`, and `routes.jsonl` are added if
+//! the origin–destination matrix, link volumes, or routes are written.
+//!
+//! Keys match the CSV headers.  Unlike CSV, the `u32::MAX` node sentinels
+//! are written as `null`, `in_transit` is a JSON boolean, and the trip mode
+//! is a string:
+//!
+//! ```text
+//! {"agent_id":3,"tick":12,"departure_node":7,"in_transit":false,"destination_node":null,"lat":null,"lon":null,"departure_tick":null,"arrival_tick":null}
+//! ```
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::columns;
+use crate::json;
+use crate::writer::OutputWriter;
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnValue, ContactRow, LinkVolumeRow, OdRow, OutputError,
+    OutputResult, RouteRow, TickSummaryRow, TripRow,
+};
+
+/// Writes simulation output to four JSON Lines files.
+pub struct JsonlWriter {
+    dir:       PathBuf,
+    snapshots: BufWriter<File>,
+    /// Declared extra snapshot columns.
+    extra:     Vec<ColumnSpec>,
+    /// Whether any snapshot row has been written (columns are then fixed).
+    snap_rows: bool,
+    summaries: BufWriter<File>,
+    contacts:  BufWriter<File>,
+    trips:     BufWriter<File>,
+    /// Created by the first link volume write.
+    volumes:   Option<BufWriter<File>>,
+    /// Created by the first route write.
+    routes:    Option<BufWriter<File>>,
+}
+
+impl JsonlWriter {
+    /// Create (truncating) the four `.jsonl` files in `dir`.
+    pub fn new(dir: &Path) -> OutputResult<Self> {
+        let open = |name: &str| -> OutputResult<_> { Ok(BufWriter::new(File::create(dir.join(name))?)) };
+        Ok(Self {
+            dir:       dir.to_path_buf(),
+            snapshots: open("agent_snapshots.jsonl")?,
+            extra:     Vec::new(),
+            snap_rows: false,
+            summaries: open("tick_summaries.jsonl")?,
+            contacts:  open("contacts.jsonl")?,
+            trips:     open("trips.jsonl")?,
+            volumes:   None,
+            routes:    None,
+        })
+    }
+}
+
+/// Write `value` as one line.
+fn write_line(out: &mut BufWriter<File>, value: &Value) -> OutputResult<()> {
+    serde_json::to_writer(&mut *out, value).map_err(std::io::Error::from)?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+impl OutputWriter for JsonlWriter {
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()> {
+        columns::validate(columns)?;
+        if self.snap_rows {
+            return Err(OutputError::Column(
+                "cannot change snapshot columns after snapshots were written".into(),
+            ));
+        }
+        self.extra = columns.to_vec();
+        Ok(())
+    }
+
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:    &[AgentSnapshotRow],
+        columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()> {
+        for (i, row) in rows.iter().enumerate() {
+            write_line(&mut self.snapshots, &json::snapshot(row, &self.extra, columns, i))?;
+        }
+        self.snap_rows |= !rows.is_empty();
+        Ok(())
+    }
+
+    fn write_tick_summary(&mut self, row: &TickSummaryRow) -> OutputResult<()> {
+        write_line(&mut self.summaries, &json::summary(row))
+    }
+
+    fn write_contacts(&mut self, rows: &[ContactRow]) -> OutputResult<()> {
+        for row in rows {
+            write_line(&mut self.contacts, &json::contact(row))?;
+        }
+        Ok(())
+    }
+
+    fn write_trips(&mut self, rows: &[TripRow]) -> OutputResult<()> {
+        for row in rows {
+            write_line(&mut self.trips, &json::trip(row))?;
+        }
+        Ok(())
+    }
+
+    fn write_routes(&mut self, rows: &[RouteRow]) -> OutputResult<()> {
+        let out = match &mut self.routes {
+            Some(out) => out,
+            None => self.routes.insert(BufWriter::new(File::create(self.dir.join("routes.jsonl"))?)),
+        };
+        for row in rows {
+            write_line(out, &json::route(row))?;
+        }
+        Ok(())
+    }
+
+    fn write_od_matrix(&mut self, rows: &[OdRow]) -> OutputResult<()> {
+        let mut out = BufWriter::new(File::create(self.dir.join("od_matrix.jsonl"))?);
+        for row in rows {
+            write_line(&mut out, &json::od(row))?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    fn write_link_volumes(&mut self, rows: &[LinkVolumeRow]) -> OutputResult<()> {
+        let out = match &mut self.volumes {
+            Some(out) => out,
+            None => self.volumes.insert(BufWriter::new(File::create(self.dir.join("link_volumes.jsonl"))?)),
+        };
+        for row in rows {
+            write_line(out, &json::link_volume(row))?;
+        }
+        Ok(())
+    }
+
+    fn output_dir(&self) -> Option<&Path> {
+        Some(&self.dir)
+    }
+
+    fn finish(&mut self) -> OutputResult<()> {
+        self.snapshots.flush()?;
+        self.summaries.flush()?;
+        self.contacts.flush()?;
+        self.trips.flush()?;
+        if let Some(volumes) = &mut self.volumes {
+            volumes.flush()?;
+        }
+        if let Some(routes) = &mut self.routes {
+            routes.flush()?;
+        }
+        Ok(())
+    }
+}
@@ -1,15 +1,39 @@
 //! `dt-output` — simulation output writers for the rust_dt framework.
 //!
-//! Three backends are provided behind Cargo features:
+//! Twelve backends are provided behind Cargo features:
 //!
-//! | Feature   | Backend     | Files created                                           |
-//! |-----------|-------------|---------------------------------------------------------|
-//! | *(none)*  | CSV         | `agent_snapshots.csv`, `tick_summaries.csv`             |
-//! | `sqlite`  | SQLite      | `output.db`                                             |
-//! | `parquet` | Parquet     | `agent_snapshots.parquet`, `tick_summaries.parquet`     |
+//! | Feature     | Backend     | Files created                                                                            |
+//! |-------------|-------------|------------------------------------------------------------------------------------------|
+//! | *(none)*    | CSV         | `agent_snapshots.csv`, `tick_summaries.csv`, `contacts.csv`, `trips.csv`                 |
+//! | *(none)*    | Memory      | *(none; rows are kept in `Vec`s by `MemoryWriter`)*                                      |
+//! | `sqlite`    | SQLite      | `output.db`                                                                              |
+//! | `parquet`   | Parquet     | `agent_snapshots.parquet`, `tick_summaries.parquet`, `contacts.parquet`, `trips.parquet` |
+//! | `arrow-ipc` | Arrow IPC   | `agent_snapshots.arrows`, `tick_summaries.arrows`, `contacts.arrows`, `trips.arrows`     |
+//! | `jsonl`     | JSON Lines  | `agent_snapshots.jsonl`, `tick_summaries.jsonl`, `contacts.jsonl`, `trips.jsonl`         |
+//! | `postgres`  | PostgreSQL  | *(none; rows are `COPY`ed into a database through `psql`)*                               |
+//! | `geojson`   | GeoJSON     | `snapshot_{tick}.geojson` per snapshot, optionally `network.geojson`                     |
+//! | `kafka`     | Kafka       | *(none; one JSON message per row to a topic per table)*                                  |
+//! | `mqtt`      | MQTT        | *(none; as `kafka`)*                                                                     |
+//! | `websocket` | WebSocket   | *(none; snapshot positions are pushed to connected clients)*                             |
+//! | `heatmap`   | Heatmap     | `heatmap_{tick}.npy` or `heatmap_{tick}.tif` (GeoTIFF) density raster per snapshot       |
+//!
+//! Every backend with an output directory also gets a `run_manifest.json`
+//! recording the config, seed, network fingerprint, and schema versions of
+//! the run (see [`manifest`]), and optionally a `run_summary.json` of
+//! run-level aggregates (see [`summary`]).
+//!
+//! The Arrow IPC backend can also stream to sockets, so results can be read
+//! while the run is in progress.  With the `object-store` feature the CSV
+//! and Parquet backends can upload to S3, GCS, or Azure instead of a local
+//! directory (see [`store`]).
+//!
+//! The `gzip` and `zstd` features add [`Compression`] variants for
+//! [`CsvWriter::new_compressed`], which writes `.csv.gz` / `.csv.zst` files.
 //!
 //! All backends implement [`OutputWriter`] and are driven by
 //! [`SimOutputObserver`], which implements `dt_sim::SimObserver`.
+//! Applications can append component-derived columns to the agent snapshots
+//! with [`ColumnExtractor`]s (see [`columns`]).
 //!
 //! # Usage
 //!
@@ -22,10 +46,18 @@
 //! obs.take_error().map(|e| eprintln!("output error: {e}"));
 //! ```
 
+pub mod columns;
 pub mod csv;
+mod dest;
 pub mod error;
+pub mod manifest;
+pub mod memory;
 pub mod observer;
+pub mod od;
+pub mod reader;
 pub mod row;
+pub mod summary;
+pub mod volumes;
 pub mod writer;
 
 #[cfg(feature = "sqlite")]
@@ -34,17 +66,87 @@ pub mod sqlite;
 #[cfg(feature = "parquet")]
 pub mod parquet;
 
+#[cfg(feature = "arrow-ipc")]
+pub mod ipc;
+
+#[cfg(feature = "jsonl")]
+pub mod jsonl;
+
+#[cfg(feature = "postgres")]
+pub mod postgres;
+
+#[cfg(feature = "geojson")]
+pub mod geojson;
+
+#[cfg(any(feature = "kafka", feature = "mqtt"))]
+pub mod stream;
+
+#[cfg(feature = "websocket")]
+pub mod websocket;
+
+#[cfg(feature = "heatmap")]
+pub mod heatmap;
+
+#[cfg(feature = "object-store")]
+pub mod store;
+
+#[cfg(feature = "arrow")]
+mod batch;
+
+#[cfg(any(feature = "jsonl", feature = "geojson", feature = "kafka", feature = "mqtt"))]
+mod json;
+
 #[cfg(test)]
 mod tests;
 
-pub use csv::CsvWriter;
+pub use columns::{ColumnExtractor, ColumnSpec, ColumnType, ColumnValue, ComponentColumn};
+pub use csv::{Compression, CsvSnapshotReader, CsvWriter};
 pub use error::{OutputError, OutputResult};
-pub use observer::SimOutputObserver;
-pub use row::{AgentSnapshotRow, TickSummaryRow};
+pub use manifest::RunManifest;
+pub use memory::MemoryWriter;
+pub use observer::{OutputCadence, SimOutputObserver};
+pub use od::OdMatrix;
+pub use reader::{Format, ResultsReader};
+pub use row::{AgentSnapshotRow, ContactRow, LinkVolumeRow, OdRow, RouteRow, TickSummaryRow, TripRow};
+pub use summary::{ModeSummary, RunSummary};
+pub use volumes::LinkVolumes;
 pub use writer::OutputWriter;
 
+#[cfg(feature = "arrow")]
+pub use reader::{join_edges, join_nodes};
+
 #[cfg(feature = "sqlite")]
-pub use sqlite::SqliteWriter;
+pub use sqlite::{SqliteOptions, SqliteSynchronous, SqliteWriter};
 
 #[cfg(feature = "parquet")]
 pub use parquet::ParquetWriter;
+
+#[cfg(feature = "arrow-ipc")]
+pub use ipc::{ArrowIpcWriter, IpcStreams};
+
+#[cfg(feature = "jsonl")]
+pub use jsonl::JsonlWriter;
+
+#[cfg(feature = "postgres")]
+pub use postgres::{PostgresWriter, PostgresWriterBuilder};
+
+#[cfg(feature = "geojson")]
+pub use geojson::GeoJsonWriter;
+
+#[cfg(any(feature = "kafka", feature = "mqtt"))]
+pub use stream::{Publisher, StreamWriter};
+
+#[cfg(feature = "kafka")]
+pub use stream::KafkaPublisher;
+
+#[cfg(feature = "mqtt")]
+pub use stream::MqttPublisher;
+
+#[cfg(feature = "websocket")]
+pub use websocket::{FrameFormat, WebSocketWriter};
+
+#[cfg(feature = "heatmap")]
+pub use heatmap::{HeatmapGrid, HeatmapWriter, RasterFormat};
+
+#[cfg(feature = "object-store")]
+pub use store::ObjectDir;
@@ -0,0 +1,205 @@
+//! `run_manifest.json` — the settings that produced an output folder.
+//!
+//! [`SimOutputObserver`][crate::SimOutputObserver] writes a [`RunManifest`]
+//! next to the writer's files (see
+//! [`OutputWriter::write_file`][crate::OutputWriter::write_file]) when the
+//! first tick starts (`"status": "running"`) and rewrites it when the run
+//! ends (`"status": "finished"`), so an interrupted run is recognisable:
+//!
+//! ```text
+//! {
+//!   "manifest_version": 1,
+//!   "status": "finished",
+//!   "crate_versions": { "dt-output": "0.1.0" },
+//!   "config": { "start_unix_secs": 0, "tick_duration_secs": 3600, "total_ticks": 24, "seed": 42, … },
+//!   "seed": 42,
+//!   "network": { "nodes": 2, "edges": 2, "fingerprint": "a8c7f832281a39c5" },
+//!   "started_unix_secs": 1718000000,
+//!   "finished_unix_secs": 1718000012,
+//!   "final_tick": 24,
+//!   "schema_versions": { "agent_snapshots": 1, "tick_summaries": 2, … },
+//!   "snapshot_columns": [ { "name": "infected", "type": "bool" } ]
+//! }
+//! ```
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dt_core::SimConfig;
+use dt_spatial::RoadNetwork;
+
+use crate::{ColumnSpec, ColumnType, OutputResult};
+
+/// Version of the manifest layout itself.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Version of each table's column layout, bumped whenever a column is added,
+/// removed, or changes meaning.
+pub const SCHEMA_VERSIONS: [(&str, u32); 7] = [
+    ("agent_snapshots", 1),
+    ("tick_summaries",  2),
+    ("contacts",        1),
+    ("trips",           1),
+    ("od_matrix",       1),
+    ("link_volumes",    1),
+    ("routes",          1),
+];
+
+/// Size and [`fingerprint`][RoadNetwork::fingerprint] of the road network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkInfo {
+    pub nodes:       usize,
+    pub edges:       usize,
+    pub fingerprint: u64,
+}
+
+impl NetworkInfo {
+    pub fn of(network: &RoadNetwork) -> Self {
+        Self {
+            nodes:       network.node_count(),
+            edges:       network.edge_count(),
+            fingerprint: network.fingerprint(),
+        }
+    }
+}
+
+/// Contents of `run_manifest.json`.
+#[derive(Debug, Clone)]
+pub struct RunManifest {
+    pub config:             SimConfig,
+    /// `None` unless the observer was given the network.
+    pub network:            Option<NetworkInfo>,
+    pub snapshot_columns:   Vec<ColumnSpec>,
+    /// Wall-clock time the first tick started.
+    pub started_unix_secs:  Option<u64>,
+    /// Wall-clock time the run ended; `None` while running.
+    pub finished_unix_secs: Option<u64>,
+    /// Tick at which the run ended.
+    pub final_tick:         Option<u64>,
+}
+
+/// Current wall-clock time in whole seconds since the Unix epoch.
+pub(crate) fn now_unix_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// JSON string literal for `s`.
+fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"'  => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn opt(value: Option<u64>) -> String {
+    value.map_or_else(|| "null".to_owned(), |v| v.to_string())
+}
+
+impl RunManifest {
+    /// A manifest for a run with `config` that has not started yet.
+    pub fn new(config: &SimConfig) -> Self {
+        Self {
+            config:             config.clone(),
+            network:            None,
+            snapshot_columns:   Vec::new(),
+            started_unix_secs:  None,
+            finished_unix_secs: None,
+            final_tick:         None,
+        }
+    }
+
+    /// Pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        let c = &self.config;
+        let status = if self.finished_unix_secs.is_some() { "finished" } else { "running" };
+        let network = self.network.map_or_else(
+            || "null".to_owned(),
+            |n| format!(
+                "{{ \"nodes\": {}, \"edges\": {}, \"fingerprint\": \"{:016x}\" }}",
+                n.nodes, n.edges, n.fingerprint,
+            ),
+        );
+        let extensions: Vec<String> = c
+            .extensions
+            .iter()
+            .map(|(key, value)| format!("{}: {}", string(key), string(value)))
+            .collect();
+        let schemas: Vec<String> = SCHEMA_VERSIONS
+            .iter()
+            .map(|(table, version)| format!("{}: {version}", string(table)))
+            .collect();
+        let columns: Vec<String> = self
+            .snapshot_columns
+            .iter()
+            .map(|col| {
+                let ty = match col.ty {
+                    ColumnType::Int   => "int",
+                    ColumnType::Float => "float",
+                    ColumnType::Bool  => "bool",
+                    ColumnType::Text  => "text",
+                };
+                format!("{{ \"name\": {}, \"type\": \"{ty}\" }}", string(&col.name))
+            })
+            .collect();
+
+        let mut out = String::from("{\n");
+        let _ = writeln!(out, "  \"manifest_version\": {MANIFEST_VERSION},");
+        let _ = writeln!(out, "  \"status\": \"{status}\",");
+        let _ = writeln!(out, "  \"crate_versions\": {{ \"dt-output\": {} }},", string(env!("CARGO_PKG_VERSION")));
+        let _ = writeln!(
+            out,
+            "  \"config\": {{ \"start_unix_secs\": {}, \"tick_duration_secs\": {}, \"total_ticks\": {}, \
+             \"seed\": {}, \"num_threads\": {}, \"output_interval_ticks\": {}, \
+             \"mobility\": {{ \"min_travel_ticks\": {}, \"path_following\": {} }}, \"contacts\": {{ \"edge_contacts\": {} }}, \
+             \"output\": {{ \"snapshots\": {}, \"tick_summaries\": {}, \"contacts\": {}, \"trips\": {} }}, \
+             \"routing\": {{ \"walk_speed_mps\": {}, \"bike_speed_mps\": {}, \"transit_speed_mps\": {} }}, \
+             \"extensions\": {{ {} }} }},",
+            c.start_unix_secs,
+            c.tick_duration_secs,
+            c.total_ticks,
+            c.seed,
+            opt(c.num_threads.map(|n| n as u64)),
+            c.output_interval_ticks,
+            c.mobility.min_travel_ticks,
+            c.mobility.path_following,
+            c.contacts.edge_contacts,
+            c.output.snapshots,
+            c.output.tick_summaries,
+            c.output.contacts,
+            c.output.trips,
+            c.routing.walk_speed_mps,
+            c.routing.bike_speed_mps,
+            c.routing.transit_speed_mps,
+            extensions.join(", "),
+        );
+        let _ = writeln!(out, "  \"seed\": {},", c.seed);
+        let _ = writeln!(out, "  \"network\": {network},");
+        let _ = writeln!(out, "  \"started_unix_secs\": {},", opt(self.started_unix_secs));
+        let _ = writeln!(out, "  \"finished_unix_secs\": {},", opt(self.finished_unix_secs));
+        let _ = writeln!(out, "  \"final_tick\": {},", opt(self.final_tick));
+        let _ = writeln!(out, "  \"schema_versions\": {{ {} }},", schemas.join(", "));
+        let _ = writeln!(out, "  \"snapshot_columns\": [{}]", columns.join(", "));
+        out.push_str("}\n");
+        out
+    }
+
+    /// Write `run_manifest.json` into `dir`, replacing any earlier one.
+    pub fn write(&self, dir: &Path) -> OutputResult<()> {
+        std::fs::write(dir.join("run_manifest.json"), self.to_json())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+//! In-memory output backend.
+//!
+//! [`MemoryWriter`] keeps every row in `Vec`s, for unit tests, notebooks,
+//! and embedding the simulation where nothing needs to reach the disk:
+//!
+//! ```rust,ignore
+//! let mut obs = SimOutputObserver::new(MemoryWriter::new(), &config);
+//! sim.run(&mut obs)?;
+//! let out = obs.into_writer();
+//! assert_eq!(out.tick_summaries.len(), config.total_ticks as usize);
+//! ```
+//!
+//! With the `arrow` feature each table is also available as one Arrow
+//! `RecordBatch` with the schema the Parquet backend writes
+//! ([`MemoryWriter::trip_batch`] and friends).
+
+#[cfg(feature = "arrow")]
+use arrow::record_batch::RecordBatch;
+
+#[cfg(feature = "arrow")]
+use crate::batch;
+use crate::columns::{self, cell};
+use crate::writer::OutputWriter;
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnValue, ContactRow, LinkVolumeRow, OdRow, OutputError,
+    OutputResult, RouteRow, TickSummaryRow, TripRow,
+};
+
+/// Collects simulation output in memory.
+///
+/// Rows are appended in the order they are written.  Extra snapshot column
+/// values are stored per row: `snapshot_values[i][c]` is the value of
+/// `snapshot_columns[c]` for `snapshots[i]`, `Null` where none was given.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryWriter {
+    pub snapshot_columns: Vec<ColumnSpec>,
+    pub snapshots:        Vec<AgentSnapshotRow>,
+    pub snapshot_values:  Vec<Vec<ColumnValue>>,
+    pub tick_summaries:   Vec<TickSummaryRow>,
+    pub contacts:         Vec<ContactRow>,
+    pub trips:            Vec<TripRow>,
+    pub routes:           Vec<RouteRow>,
+    pub od_matrix:        Vec<OdRow>,
+    pub link_volumes:     Vec<LinkVolumeRow>,
+    /// Whether `finish()` has been called.
+    pub finished:         bool,
+}
+
+impl MemoryWriter {
+    /// An empty writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot rows taken at `tick`.
+    pub fn snapshots_at(&self, tick: u64) -> impl Iterator<Item = &AgentSnapshotRow> {
+        self.snapshots.iter().filter(move |row| row.tick == tick)
+    }
+
+    /// Value of extra column `name` for `snapshots[i]`.
+    pub fn snapshot_value(&self, i: usize, name: &str) -> Option<&ColumnValue> {
+        let c = self.snapshot_columns.iter().position(|col| col.name == name)?;
+        self.snapshot_values.get(i)?.get(c)
+    }
+}
+
+// ── Arrow ─────────────────────────────────────────────────────────────────────
+
+#[cfg(feature = "arrow")]
+impl MemoryWriter {
+    /// `snapshots` and their extra columns as one batch.
+    pub fn snapshot_batch(&self) -> OutputResult<RecordBatch> {
+        let schema = batch::snapshot_schema(&self.snapshot_columns);
+        let width = self.snapshot_columns.len();
+        // `snapshot_values` is row-major; batches are built column by column.
+        let columns: Vec<Vec<ColumnValue>> = (0..width)
+            .map(|c| self.snapshot_values.iter().map(|row| row[c].clone()).collect())
+            .collect();
+        batch::snapshot_batch(&schema, &self.snapshot_columns, &self.snapshots, &columns)
+    }
+
+    /// `tick_summaries` as one batch.
+    pub fn tick_summary_batch(&self) -> OutputResult<RecordBatch> {
+        batch::summary_batch(&batch::summary_schema(), &self.tick_summaries)
+    }
+
+    /// `contacts` as one batch.
+    pub fn contact_batch(&self) -> OutputResult<RecordBatch> {
+        batch::contact_batch(&batch::contact_schema(), &self.contacts)
+    }
+
+    /// `trips` as one batch.
+    pub fn trip_batch(&self) -> OutputResult<RecordBatch> {
+        batch::trip_batch(&batch::trip_schema(), &self.trips)
+    }
+
+    /// `routes` as one batch.
+    pub fn route_batch(&self) -> OutputResult<RecordBatch> {
+        batch::route_batch(&batch::route_schema(), &self.routes)
+    }
+
+    /// `od_matrix` as one batch.
+    pub fn od_batch(&self) -> OutputResult<RecordBatch> {
+        batch::od_batch(&batch::od_schema(), &self.od_matrix)
+    }
+
+    /// `link_volumes` as one batch.
+    pub fn link_volume_batch(&self) -> OutputResult<RecordBatch> {
+        batch::link_volume_batch(&batch::link_volume_schema(), &self.link_volumes)
+    }
+}
+
+impl OutputWriter for MemoryWriter {
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()> {
+        columns::validate(columns)?;
+        if !self.snapshots.is_empty() {
+            return Err(OutputError::Column(
+                "cannot change snapshot columns after snapshots were written".into(),
+            ));
+        }
+        self.snapshot_columns = columns.to_vec();
+        Ok(())
+    }
+
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:    &[AgentSnapshotRow],
+        columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()> {
+        self.snapshots.extend_from_slice(rows);
+        let width = self.snapshot_columns.len();
+        self.snapshot_values
+            .extend((0..rows.len()).map(|i| (0..width).map(|c| cell(columns, c, i).clone()).collect()));
+        Ok(())
+    }
+
+    fn write_tick_summary(&mut self, row: &TickSummaryRow) -> OutputResult<()> {
+        self.tick_summaries.push(*row);
+        Ok(())
+    }
+
+    fn write_contacts(&mut self, rows: &[ContactRow]) -> OutputResult<()> {
+        self.contacts.extend_from_slice(rows);
+        Ok(())
+    }
+
+    fn write_trips(&mut self, rows: &[TripRow]) -> OutputResult<()> {
+        self.trips.extend_from_slice(rows);
+        Ok(())
+    }
+
+    fn write_routes(&mut self, rows: &[RouteRow]) -> OutputResult<()> {
+        self.routes.extend_from_slice(rows);
+        Ok(())
+    }
+
+    fn write_od_matrix(&mut self, rows: &[OdRow]) -> OutputResult<()> {
+        self.od_matrix = rows.to_vec();
+        Ok(())
+    }
+
+    fn write_link_volumes(&mut self, rows: &[LinkVolumeRow]) -> OutputResult<()> {
+        self.link_volumes.extend_from_slice(rows);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> OutputResult<()> {
+        self.finished = true;
+        Ok(())
+    }
+}
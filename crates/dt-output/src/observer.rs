@@ -1,39 +1,251 @@
 //! `SimOutputObserver<W>` — bridges `SimObserver` to an `OutputWriter`.
 
 use dt_agent::AgentStore;
-use dt_core::{NodeId, SimConfig, Tick};
-use dt_mobility::MobilityStore;
-use dt_sim::SimObserver;
+use dt_core::{AgentId, EdgeId, GeoPoint, NodeId, OutputConfig, SimConfig, SimRng, Tick, TransportMode};
+use dt_mobility::{MobilityStore, MovementState, Trip};
+use dt_sim::{SimObserver, TickStats};
+use dt_spatial::{RoadNetwork, Route};
 
-use crate::row::{AgentSnapshotRow, TickSummaryRow};
+use crate::columns::{ColumnExtractor, ColumnSpec, ColumnValue};
+use crate::manifest::{self, NetworkInfo, RunManifest};
+use crate::od::OdMatrix;
+use crate::row::{AgentSnapshotRow, ContactRow, LinkVolumeRow, RouteRow, TickSummaryRow, TripRow};
+use crate::summary::RunSummary;
+use crate::volumes::LinkVolumes;
 use crate::writer::OutputWriter;
 use crate::OutputError;
 
-/// A [`SimObserver`] that writes agent snapshots and tick summaries to any
-/// [`OutputWriter`] backend (CSV, SQLite, Parquet, …).
+/// How often [`SimOutputObserver`] writes each table.
+///
+/// Each interval `n` selects the ticks that are multiples of `n`; `0`
+/// disables the table.  Ticks skipped by a cadence are dropped, except for
+/// trips, which are buffered until the next due tick (or the end of the
+/// run).  The observer starts from the config's `output` section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputCadence {
+    /// Agent snapshots.  This thins the snapshots the sim fires (every
+    /// `config.output_interval_ticks` and on triggers); it cannot add any.
+    /// Default: 1.
+    pub snapshots:      u64,
+
+    /// Tick summaries.  Each row counts its own tick only.  Default: 1.
+    pub tick_summaries: u64,
+
+    /// Contact events.  Default: 1.
+    pub contacts:       u64,
+
+    /// Completed trips, flushed every `trips` ticks.  `0` never writes
+    /// trips (they still count towards the OD matrix).  Default: 1.
+    pub trips:          u64,
+}
+
+impl Default for OutputCadence {
+    fn default() -> Self {
+        Self { snapshots: 1, tick_summaries: 1, contacts: 1, trips: 1 }
+    }
+}
+
+impl From<&OutputConfig> for OutputCadence {
+    fn from(config: &OutputConfig) -> Self {
+        Self {
+            snapshots:      config.snapshots,
+            tick_summaries: config.tick_summaries,
+            contacts:       config.contacts,
+            trips:          config.trips,
+        }
+    }
+}
+
+impl OutputCadence {
+    /// Whether a table with `interval` is written at `tick`.
+    fn due(interval: u64, tick: Tick) -> bool {
+        interval > 0 && tick.is_multiple_of(interval)
+    }
+}
+
+/// A [`SimObserver`] that writes agent snapshots, tick summaries, contacts,
+/// and trips to any [`OutputWriter`] backend (CSV, SQLite, Parquet, …).
+///
+/// Contacts and trips are buffered during a tick and written as one batch
+/// each from `on_tick_end`.  Extra snapshot columns are added with
+/// [`with_column`][Self::with_column], an origin–destination matrix
+/// written at the end of the run with [`with_od_matrix`][Self::with_od_matrix],
+/// per-edge vehicle counts with [`with_link_volumes`][Self::with_link_volumes],
+/// a sample of the chosen routes with [`with_routes`][Self::with_routes],
+/// and run-level aggregates with [`with_run_summary`][Self::with_run_summary].
+/// Each table is written every tick it is reported unless thinned with
+/// [`with_cadence`][Self::with_cadence].
+///
+/// Writers that can hold extra files (see [`OutputWriter::write_file`]) also
+/// get a `run_manifest.json` describing the run (see [`manifest`][crate::manifest]),
+/// unless disabled with [`without_manifest`][Self::without_manifest].
 ///
 /// Errors from the writer are stored internally because `SimObserver` methods
-/// have no return value.  After `sim.run()` returns, check for errors with
+/// have no return value.  Each error is also surfaced to the sim through
+/// [`SimObserver::poll_error`] so the run's `FailurePolicy` applies to it.
+/// After `sim.run()` returns, retrieve the first error with
 /// [`take_error`][Self::take_error].
 pub struct SimOutputObserver<W: OutputWriter> {
     writer:             W,
     start_unix_secs:    i64,
     tick_duration_secs: u32,
+    seed:               u64,
     last_error:         Option<OutputError>,
+    unreported:         Option<String>,
+    node_pos:           Option<Vec<GeoPoint>>,
+    /// `(from, to)` of every edge; copied only when path-following.
+    edge_ends:          Vec<(NodeId, NodeId)>,
+    path_following:     bool,
+    contacts:           Vec<ContactRow>,
+    trips:              Vec<TripRow>,
+    routes:             Vec<RouteRow>,
+    /// Sample rate and RNG for route recording; `None` when disabled.
+    route_sample:       Option<(f64, SimRng)>,
+    /// Counts from `on_tick_stats` for the tick in progress.
+    stats:              TickStats,
+    columns:            Vec<Box<dyn ColumnExtractor>>,
+    od:                 Option<OdMatrix>,
+    volumes:            Option<LinkVolumes>,
+    summary:            Option<RunSummary>,
+    cadence:            OutputCadence,
+    manifest:           Option<RunManifest>,
 }
 
 impl<W: OutputWriter> SimOutputObserver<W> {
     /// Create an observer backed by `writer`, using `config` for wall-clock
-    /// conversion.
+    /// conversion and its `output` section for the cadence.
     pub fn new(writer: W, config: &SimConfig) -> Self {
         Self {
             writer,
             start_unix_secs:    config.start_unix_secs,
             tick_duration_secs: config.tick_duration_secs,
+            seed:               config.seed,
             last_error:         None,
+            unreported:         None,
+            node_pos:           None,
+            edge_ends:          Vec::new(),
+            path_following:     config.mobility.path_following,
+            contacts:           Vec::new(),
+            trips:              Vec::new(),
+            routes:             Vec::new(),
+            route_sample:       None,
+            stats:              TickStats::default(),
+            columns:            Vec::new(),
+            od:                 None,
+            volumes:            None,
+            summary:            None,
+            cadence:            OutputCadence::from(&config.output),
+            manifest:           Some(RunManifest::new(config)),
         }
     }
 
+    /// Fill the `lat`/`lon` columns of agent snapshots from `network`'s node
+    /// positions.
+    ///
+    /// The node table is copied, and with `mobility.path_following` the
+    /// edges' endpoints too, so the network may be moved into the sim
+    /// afterwards.
+    pub fn with_network(mut self, network: &RoadNetwork) -> Self {
+        self.node_pos = Some(network.node_pos.clone());
+        if self.path_following {
+            self.edge_ends = network.edge_from.iter().copied().zip(network.edge_to.iter().copied()).collect();
+        }
+        if let Some(manifest) = &mut self.manifest {
+            manifest.network = Some(NetworkInfo::of(network));
+        }
+        self
+    }
+
+    /// Append a column computed by `extractor` to every agent snapshot row.
+    ///
+    /// Columns appear in the order they are added.  Must be called before
+    /// the sim runs; an invalid name is reported through
+    /// [`take_error`][Self::take_error].
+    pub fn with_column(mut self, extractor: impl ColumnExtractor + 'static) -> Self {
+        self.columns.push(Box::new(extractor));
+        let specs: Vec<ColumnSpec> = self.columns.iter().map(|c| c.spec()).collect();
+        let result = self.writer.set_snapshot_columns(&specs);
+        if let Some(manifest) = &mut self.manifest {
+            manifest.snapshot_columns = specs;
+        }
+        self.store_err(result);
+        self
+    }
+
+    /// Count completed trips by origin zone, destination zone, departure
+    /// hour of day, and mode, and write the matrix when the run ends.
+    ///
+    /// `zone_of_node[n]` is the zone of `NodeId(n)`; see [`OdMatrix::new`].
+    pub fn with_od_matrix(mut self, zone_of_node: Vec<u32>) -> Self {
+        self.od = Some(OdMatrix::new(zone_of_node));
+        self
+    }
+
+    /// The origin–destination matrix accumulated so far, if enabled.
+    pub fn od_matrix(&self) -> Option<&OdMatrix> {
+        self.od.as_ref()
+    }
+
+    /// Count the vehicles entering each edge per `interval_ticks` ticks,
+    /// for journeys in `modes` (every mode if empty), and write each
+    /// interval once it is complete.
+    ///
+    /// Routes are expanded when journeys depart; see [`LinkVolumes`].
+    pub fn with_link_volumes(
+        mut self,
+        network:        &RoadNetwork,
+        interval_ticks: u64,
+        modes:          &[TransportMode],
+    ) -> Self {
+        self.volumes = Some(LinkVolumes::new(network, interval_ticks, modes));
+        self
+    }
+
+    /// The link volumes not yet written, if enabled.
+    pub fn link_volumes(&self) -> Option<&LinkVolumes> {
+        self.volumes.as_ref()
+    }
+
+    /// Record the route of each journey with probability `sample_rate`
+    /// (`1.0` keeps every route) and write them as they depart.
+    ///
+    /// Sampling draws from the `"output-routes"` stream of `config.seed`,
+    /// so repeated runs keep the same journeys.
+    pub fn with_routes(mut self, sample_rate: f64) -> Self {
+        self.route_sample = Some((sample_rate, SimRng::new(self.seed).stream("output-routes")));
+        self
+    }
+
+    /// Accumulate run-level aggregates (see [`RunSummary`]) and write them
+    /// to `run_summary.json` next to the writer's files when the run
+    /// ends.  Cadences do not thin the summary.
+    pub fn with_run_summary(mut self) -> Self {
+        self.summary = Some(RunSummary::new());
+        self
+    }
+
+    /// The run summary accumulated so far, if enabled.
+    pub fn run_summary(&self) -> Option<&RunSummary> {
+        self.summary.as_ref()
+    }
+
+    /// Write each table on its own cadence (see [`OutputCadence`]).
+    pub fn with_cadence(mut self, cadence: OutputCadence) -> Self {
+        self.cadence = cadence;
+        self
+    }
+
+    /// Do not write `run_manifest.json`.
+    pub fn without_manifest(mut self) -> Self {
+        self.manifest = None;
+        self
+    }
+
+    /// The run manifest as last written (or to be written), if enabled.
+    pub fn manifest(&self) -> Option<&RunManifest> {
+        self.manifest.as_ref()
+    }
+
     /// Take the stored write error (if any) after `sim.run()` returns.
     ///
     /// Returns `None` if all writes succeeded.
@@ -46,12 +258,93 @@ impl<W: OutputWriter> SimOutputObserver<W> {
         self.writer
     }
 
+    /// Position of an agent in `state` at `tick`, if known.
+    ///
+    /// In-transit agents are placed on the straight line of their
+    /// [`segment`](MovementState::segment): the edge they are on when
+    /// path-following, otherwise departure to destination node.
+    fn position(&self, state: &MovementState, tick: Tick) -> Option<GeoPoint> {
+        let nodes = self.node_pos.as_ref()?;
+        if !state.in_transit {
+            return nodes.get(state.departure_node.index()).copied();
+        }
+        let ends = |e: EdgeId| self.edge_ends.get(e.index()).copied().unwrap_or((NodeId::INVALID, NodeId::INVALID));
+        let (from, to, t) = state.segment_by(tick, ends);
+        let (from, to) = (*nodes.get(from.index())?, *nodes.get(to.index())?);
+        Some(GeoPoint {
+            lat: from.lat + (to.lat - from.lat) * t,
+            lon: from.lon + (to.lon - from.lon) * t,
+        })
+    }
+
+    /// Run every column extractor over `agents`, checking lengths and types.
+    fn extract_columns(&self, agents: &AgentStore) -> crate::OutputResult<Vec<Vec<ColumnValue>>> {
+        self.columns
+            .iter()
+            .map(|extractor| {
+                let spec = extractor.spec();
+                let mut values = Vec::with_capacity(agents.count);
+                extractor.extract(agents, &mut values);
+                if values.len() != agents.count {
+                    return Err(OutputError::Column(format!(
+                        "column {:?} produced {} values for {} agents",
+                        spec.name, values.len(), agents.count,
+                    )));
+                }
+                if let Some(bad) = values.iter().find(|v| !v.fits(spec.ty)) {
+                    return Err(OutputError::Column(format!(
+                        "column {:?} of type {:?} got {bad:?}", spec.name, spec.ty,
+                    )));
+                }
+                Ok(values)
+            })
+            .collect()
+    }
+
     fn unix_time(&self, tick: Tick) -> i64 {
         self.start_unix_secs + tick.0 as i64 * self.tick_duration_secs as i64
     }
 
+    /// Write the buffered trips, if any.
+    fn flush_trips(&mut self) {
+        if !self.trips.is_empty() {
+            let result = self.writer.write_trips(&self.trips);
+            self.trips.clear();
+            self.store_err(result);
+        }
+    }
+
+    /// Write the buffered routes, if any.
+    fn flush_routes(&mut self) {
+        if !self.routes.is_empty() {
+            let result = self.writer.write_routes(&self.routes);
+            self.routes.clear();
+            self.store_err(result);
+        }
+    }
+
+    /// Write `rows` of link volumes, if any.
+    fn write_link_volumes(&mut self, rows: Vec<LinkVolumeRow>) {
+        if !rows.is_empty() {
+            let result = self.writer.write_link_volumes(&rows);
+            self.store_err(result);
+        }
+    }
+
+    /// Write the manifest next to the writer's files, if enabled.
+    fn write_manifest(&mut self) {
+        let Some(manifest) = &self.manifest else {
+            return;
+        };
+        let result = self.writer.write_file("run_manifest.json", manifest.to_json().as_bytes());
+        self.store_err(result);
+    }
+
     fn store_err(&mut self, result: crate::OutputResult<()>) {
         if let Err(e) = result {
+            if self.unreported.is_none() {
+                self.unreported = Some(e.to_string());
+            }
             // Keep only the first error.
             if self.last_error.is_none() {
                 self.last_error = Some(e);
@@ -61,20 +354,99 @@ impl<W: OutputWriter> SimOutputObserver<W> {
 }
 
 impl<W: OutputWriter> SimObserver for SimOutputObserver<W> {
+    fn on_tick_start(&mut self, _tick: Tick) {
+        if let Some(manifest) = &mut self.manifest
+            && manifest.started_unix_secs.is_none()
+        {
+            manifest.started_unix_secs = Some(manifest::now_unix_secs());
+            self.write_manifest();
+        }
+    }
+
     fn on_tick_end(&mut self, tick: Tick, woken: usize) {
+        if !self.contacts.is_empty() {
+            let result = self.writer.write_contacts(&self.contacts);
+            self.contacts.clear();
+            self.store_err(result);
+        }
+        if OutputCadence::due(self.cadence.trips, tick) {
+            self.flush_trips();
+        }
+        self.flush_routes();
+        if let Some(volumes) = &mut self.volumes {
+            let rows = volumes.take_until(tick.0 + 1);
+            self.write_link_volumes(rows);
+        }
+
+        // Without `on_tick_stats` (observer driven by hand) only the wake
+        // count is known.
+        let stats = std::mem::take(&mut self.stats);
+        if !OutputCadence::due(self.cadence.tick_summaries, tick) {
+            return;
+        }
         let row = TickSummaryRow {
-            tick:           tick.0,
-            unix_time_secs: self.unix_time(tick),
-            woken_agents:   woken as u64,
+            woken_agents: woken as u64,
+            ..TickSummaryRow::from_stats(tick.0, self.unix_time(tick), &stats)
         };
         let result = self.writer.write_tick_summary(&row);
         self.store_err(result);
     }
 
+    fn on_tick_stats(&mut self, tick: Tick, stats: &TickStats) {
+        self.stats = *stats;
+        if let Some(summary) = &mut self.summary {
+            summary.record_tick(tick.0, stats);
+        }
+    }
+
+    fn on_trip(&mut self, trip: &Trip) {
+        let row = TripRow::from(trip);
+        if self.od.is_some() {
+            let hour = (self.unix_time(trip.depart_tick).rem_euclid(86_400) / 3600) as u8;
+            if let Some(od) = &mut self.od {
+                od.record(&row, hour);
+            }
+        }
+        if let Some(summary) = &mut self.summary {
+            summary.record_trip(&row);
+        }
+        if self.cadence.trips > 0 {
+            self.trips.push(row);
+        }
+    }
+
+    fn on_departure(&mut self, _tick: Tick, agent: AgentId, state: &MovementState, route: &Route) {
+        if let Some(volumes) = &mut self.volumes {
+            volumes.record(state, route);
+        }
+        if let Some((rate, rng)) = &mut self.route_sample
+            && rng.gen_bool(*rate)
+        {
+            self.routes.push(RouteRow::new(agent, state, route));
+        }
+    }
+
+    fn on_contacts(&mut self, tick: Tick, agent: AgentId, node: NodeId, agents_at_node: &[AgentId]) {
+        if !OutputCadence::due(self.cadence.contacts, tick) {
+            return;
+        }
+        let rows = agents_at_node.iter().filter(|&&other| other != agent).map(|other| ContactRow {
+            tick:    tick.0,
+            agent_a: agent.0,
+            agent_b: other.0,
+            node:    node.0,
+        });
+        self.contacts.extend(rows);
+    }
+
     fn on_snapshot(&mut self, tick: Tick, mobility: &MobilityStore, agents: &AgentStore) {
+        if !OutputCadence::due(self.cadence.snapshots, tick) {
+            return;
+        }
         let rows: Vec<AgentSnapshotRow> = (0..agents.count)
             .map(|i| {
                 let state = &mobility.states[i];
+                let pos = self.position(state, tick);
                 AgentSnapshotRow {
                     agent_id:         i as u32,
                     tick:             tick.0,
@@ -85,18 +457,52 @@ impl<W: OutputWriter> SimObserver for SimOutputObserver<W> {
                     } else {
                         NodeId::INVALID.0
                     },
+                    lat:              pos.map(|p| p.lat),
+                    lon:              pos.map(|p| p.lon),
+                    departure_tick:   state.in_transit.then_some(state.departure_tick.0),
+                    arrival_tick:     state.in_transit.then_some(state.arrival_tick.0),
                 }
             })
             .collect();
 
-        if !rows.is_empty() {
-            let result = self.writer.write_snapshots(&rows);
-            self.store_err(result);
+        if rows.is_empty() {
+            return;
         }
+        let result = self
+            .extract_columns(agents)
+            .and_then(|columns| self.writer.write_snapshots_with_columns(&rows, &columns));
+        self.store_err(result);
     }
 
-    fn on_sim_end(&mut self, _final_tick: Tick) {
+    fn on_sim_end(&mut self, final_tick: Tick) {
+        self.flush_trips();
+        self.flush_routes();
+        if let Some(od) = &self.od {
+            let result = self.writer.write_od_matrix(&od.rows());
+            self.store_err(result);
+        }
+        if let Some(volumes) = &mut self.volumes {
+            let rows = volumes.take_all(final_tick.0);
+            self.write_link_volumes(rows);
+        }
         let result = self.writer.finish();
         self.store_err(result);
+
+        if let Some(summary) = &mut self.summary {
+            summary.finish(final_tick.0);
+            let result = self.writer.write_file("run_summary.json", summary.to_json().as_bytes());
+            self.store_err(result);
+        }
+        if let Some(manifest) = &mut self.manifest {
+            let now = manifest::now_unix_secs();
+            manifest.started_unix_secs.get_or_insert(now);
+            manifest.finished_unix_secs = Some(now);
+            manifest.final_tick = Some(final_tick.0);
+            self.write_manifest();
+        }
+    }
+
+    fn poll_error(&mut self) -> Option<String> {
+        self.unreported.take()
     }
 }
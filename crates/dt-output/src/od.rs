@@ -0,0 +1,71 @@
+//! Origin–destination matrix accumulation.
+//!
+//! [`OdMatrix`] counts completed trips by (origin zone, destination zone,
+//! departure hour, mode).  Attach one to the output observer with
+//! [`SimOutputObserver::with_od_matrix`][crate::SimOutputObserver::with_od_matrix]
+//! and the matrix is written through [`OutputWriter::write_od_matrix`]
+//! when the run ends:
+//!
+//! ```rust,ignore
+//! // zone_of_node[n] is the zone of NodeId(n); u32::MAX for none.
+//! let obs = SimOutputObserver::new(writer, &config).with_od_matrix(zone_of_node);
+//! ```
+//!
+//! [`OutputWriter::write_od_matrix`]: crate::OutputWriter::write_od_matrix
+
+use std::collections::HashMap;
+
+use dt_core::TransportMode;
+
+use crate::row::{OdRow, TripRow};
+
+/// Trip counts keyed by (origin zone, destination zone, hour, mode).
+pub struct OdMatrix {
+    /// Zone of each node, indexed by `NodeId`; `u32::MAX` for none.
+    zone_of: Vec<u32>,
+    counts:  HashMap<(u32, u32, u8, TransportMode), u64>,
+}
+
+impl OdMatrix {
+    /// An empty matrix over the zoning `zone_of_node`, where
+    /// `zone_of_node[n]` is the zone of `NodeId(n)`.
+    ///
+    /// Trips starting or ending at a node without a zone (`u32::MAX`, or
+    /// past the end of the vector) are not counted.
+    pub fn new(zone_of_node: Vec<u32>) -> Self {
+        Self { zone_of: zone_of_node, counts: HashMap::new() }
+    }
+
+    fn zone(&self, node: u32) -> Option<u32> {
+        self.zone_of.get(node as usize).copied().filter(|&z| z != u32::MAX)
+    }
+
+    /// Count `trip`, which departed in hour of day `hour`.
+    pub fn record(&mut self, trip: &TripRow, hour: u8) {
+        if let (Some(origin), Some(dest)) = (self.zone(trip.from), self.zone(trip.to)) {
+            *self.counts.entry((origin, dest, hour, trip.mode)).or_default() += 1;
+        }
+    }
+
+    /// Total trips counted.
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Non-empty cells, sorted by origin, destination, hour, and mode name.
+    pub fn rows(&self) -> Vec<OdRow> {
+        let mut rows: Vec<OdRow> = self
+            .counts
+            .iter()
+            .map(|(&(origin_zone, dest_zone, hour, mode), &trips)| OdRow {
+                origin_zone,
+                dest_zone,
+                hour,
+                mode,
+                trips,
+            })
+            .collect();
+        rows.sort_unstable_by_key(|r| (r.origin_zone, r.dest_zone, r.hour, r.mode.as_str()));
+        rows
+    }
+}
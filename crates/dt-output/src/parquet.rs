@@ -1,41 +1,51 @@
 //! Parquet output backend (feature `parquet`).
 //!
-//! Creates two files in the configured output directory:
+//! Creates four files in the configured output directory:
 //! - `agent_snapshots.parquet`
 //! - `tick_summaries.parquet`
+//! - `contacts.parquet`
+//! - `trips.parquet`
+//!
+//! `od_matrix.parquet`, `link_volumes.parquet`, and `routes.parquet` are
+//! added if the origin–destination matrix, link volumes, or routes are
+//! written.
+//!
+//! [`ParquetWriter::to_object_store`] (feature `object-store`) uploads the
+//! files to an object store rather than a local directory.
 
-use std::fs::File;
 use std::path::Path;
 use std::sync::Arc;
 
-use arrow::array::{
-    BooleanBuilder, Int64Builder, UInt32Builder, UInt64Builder,
-};
-use arrow::datatypes::{DataType, Field, Schema};
-use arrow::record_batch::RecordBatch;
+use arrow::datatypes::Schema;
 use parquet::arrow::ArrowWriter;
 use parquet::basic::Compression;
 use parquet::file::properties::WriterProperties;
 
+use crate::batch::{
+    contact_batch, contact_schema, link_volume_batch, link_volume_schema, od_batch, od_schema,
+    route_batch, route_schema, snapshot_batch, snapshot_schema, summary_batch, summary_schema,
+    trip_batch, trip_schema,
+};
+use crate::columns;
+use crate::dest::{Dest, DestFile};
+#[cfg(feature = "object-store")]
+use crate::store::ObjectDir;
 use crate::writer::OutputWriter;
-use crate::{AgentSnapshotRow, OutputResult, TickSummaryRow};
-
-fn snapshot_schema() -> Arc<Schema> {
-    Arc::new(Schema::new(vec![
-        Field::new("agent_id",         DataType::UInt32,  false),
-        Field::new("tick",             DataType::UInt64,  false),
-        Field::new("departure_node",   DataType::UInt32,  false),
-        Field::new("in_transit",       DataType::Boolean, false),
-        Field::new("destination_node", DataType::UInt32,  false),
-    ]))
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnValue, ContactRow, LinkVolumeRow, OdRow, OutputError,
+    OutputResult, RouteRow, TickSummaryRow, TripRow,
+};
+
+/// Create (truncating) Parquet file `name` with `schema`.
+fn parquet_file(dest: &Dest, name: &str, schema: &Arc<Schema>) -> OutputResult<ArrowWriter<DestFile>> {
+    let file = dest.create(name)?;
+    Ok(ArrowWriter::try_new(file, Arc::clone(schema), Some(snappy_props()))?)
 }
 
-fn summary_schema() -> Arc<Schema> {
-    Arc::new(Schema::new(vec![
-        Field::new("tick",           DataType::UInt64, false),
-        Field::new("unix_time_secs", DataType::Int64,  false),
-        Field::new("woken_agents",   DataType::UInt64, false),
-    ]))
+/// Write the file footer and finish the file.
+fn close(writer: ArrowWriter<DestFile>) -> OutputResult<()> {
+    writer.into_inner()?.finish()?;
+    Ok(())
 }
 
 fn snappy_props() -> WriterProperties {
@@ -44,114 +54,198 @@ fn snappy_props() -> WriterProperties {
         .build()
 }
 
-/// Writes simulation output to two Parquet files.
+/// Writes simulation output to four Parquet files.
 ///
 /// `finish()` **must** be called to write the Parquet file footer; files
 /// written without calling `finish()` cannot be opened by Parquet readers.
 pub struct ParquetWriter {
-    snapshots:   Option<ArrowWriter<File>>,
-    summaries:   Option<ArrowWriter<File>>,
+    dest:        Dest,
+    snapshots:   Option<ArrowWriter<DestFile>>,
+    /// Declared extra snapshot columns.
+    extra:       Vec<ColumnSpec>,
+    /// Whether any snapshot batch has been written (columns are then fixed).
+    snap_rows:   bool,
+    summaries:   Option<ArrowWriter<DestFile>>,
+    contacts:    Option<ArrowWriter<DestFile>>,
+    trips:       Option<ArrowWriter<DestFile>>,
+    /// Created by the first link volume write.
+    volumes:     Option<ArrowWriter<DestFile>>,
+    /// Created by the first route write.
+    routes:      Option<ArrowWriter<DestFile>>,
     snap_schema: Arc<Schema>,
     summ_schema: Arc<Schema>,
+    cont_schema: Arc<Schema>,
+    trip_schema: Arc<Schema>,
 }
 
 impl ParquetWriter {
-    /// Create both Parquet files in `dir`.
+    /// Create the four Parquet files in `dir`.
     pub fn new(dir: &Path) -> OutputResult<Self> {
-        let snap_schema = snapshot_schema();
-        let summ_schema = summary_schema();
+        Self::create(Dest::Local(dir.to_path_buf()))
+    }
 
-        let snap_file = File::create(dir.join("agent_snapshots.parquet"))?;
-        let snapshots = ArrowWriter::try_new(
-            snap_file,
-            Arc::clone(&snap_schema),
-            Some(snappy_props()),
-        )?;
+    /// Like [`new`][Self::new], but upload the files under `dir` in an
+    /// object store.
+    ///
+    /// Each object appears once [`finish`][OutputWriter::finish] has
+    /// written its footer and completed its upload.
+    #[cfg(feature = "object-store")]
+    pub fn to_object_store(dir: ObjectDir) -> OutputResult<Self> {
+        Self::create(Dest::Store(dir))
+    }
+
+    fn create(dest: Dest) -> OutputResult<Self> {
+        let snap_schema = snapshot_schema(&[]);
+        let summ_schema = summary_schema();
+        let cont_schema = contact_schema();
+        let trip_schema = trip_schema();
 
-        let summ_file = File::create(dir.join("tick_summaries.parquet"))?;
-        let summaries = ArrowWriter::try_new(
-            summ_file,
-            Arc::clone(&summ_schema),
-            Some(snappy_props()),
-        )?;
+        let snapshots = parquet_file(&dest, "agent_snapshots.parquet", &snap_schema)?;
+        let summaries = parquet_file(&dest, "tick_summaries.parquet", &summ_schema)?;
+        let contacts = parquet_file(&dest, "contacts.parquet", &cont_schema)?;
+        let trips = parquet_file(&dest, "trips.parquet", &trip_schema)?;
 
         Ok(Self {
+            dest,
             snapshots: Some(snapshots),
+            extra:     Vec::new(),
+            snap_rows: false,
             summaries: Some(summaries),
+            contacts:  Some(contacts),
+            trips:     Some(trips),
+            volumes:   None,
+            routes:    None,
             snap_schema,
             summ_schema,
+            cont_schema,
+            trip_schema,
         })
     }
 }
 
 impl OutputWriter for ParquetWriter {
-    fn write_snapshots(&mut self, rows: &[AgentSnapshotRow]) -> OutputResult<()> {
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()> {
+        columns::validate(columns)?;
+        if self.snap_rows {
+            return Err(OutputError::Column(
+                "cannot change snapshot columns after snapshots were written".into(),
+            ));
+        }
+        // Drop the old writer before truncating the file it writes to.
+        if self.snapshots.take().is_none() {
+            return Ok(());
+        }
+        self.snap_schema = snapshot_schema(columns);
+        self.snapshots = Some(parquet_file(&self.dest, "agent_snapshots.parquet", &self.snap_schema)?);
+        self.extra = columns.to_vec();
+        Ok(())
+    }
+
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:    &[AgentSnapshotRow],
+        columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()> {
         if rows.is_empty() {
             return Ok(());
         }
         let Some(writer) = self.snapshots.as_mut() else {
             return Ok(());
         };
+        writer.write(&snapshot_batch(&self.snap_schema, &self.extra, rows, columns)?)?;
+        self.snap_rows = true;
+        Ok(())
+    }
+
+    fn write_tick_summary(&mut self, row: &TickSummaryRow) -> OutputResult<()> {
+        let Some(writer) = self.summaries.as_mut() else {
+            return Ok(());
+        };
+        writer.write(&summary_batch(&self.summ_schema, std::slice::from_ref(row))?)?;
+        Ok(())
+    }
 
-        let mut agent_ids         = UInt32Builder::new();
-        let mut ticks             = UInt64Builder::new();
-        let mut departure_nodes   = UInt32Builder::new();
-        let mut in_transits       = BooleanBuilder::new();
-        let mut destination_nodes = UInt32Builder::new();
-
-        for row in rows {
-            agent_ids.append_value(row.agent_id);
-            ticks.append_value(row.tick);
-            departure_nodes.append_value(row.departure_node);
-            in_transits.append_value(row.in_transit);
-            destination_nodes.append_value(row.destination_node);
+    fn write_contacts(&mut self, rows: &[ContactRow]) -> OutputResult<()> {
+        if rows.is_empty() {
+            return Ok(());
         }
+        let Some(writer) = self.contacts.as_mut() else {
+            return Ok(());
+        };
+        writer.write(&contact_batch(&self.cont_schema, rows)?)?;
+        Ok(())
+    }
 
-        let batch = RecordBatch::try_new(
-            Arc::clone(&self.snap_schema),
-            vec![
-                Arc::new(agent_ids.finish()),
-                Arc::new(ticks.finish()),
-                Arc::new(departure_nodes.finish()),
-                Arc::new(in_transits.finish()),
-                Arc::new(destination_nodes.finish()),
-            ],
-        )?;
-        writer.write(&batch)?;
+    fn write_trips(&mut self, rows: &[TripRow]) -> OutputResult<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let Some(writer) = self.trips.as_mut() else {
+            return Ok(());
+        };
+        writer.write(&trip_batch(&self.trip_schema, rows)?)?;
         Ok(())
     }
 
-    fn write_tick_summary(&mut self, row: &TickSummaryRow) -> OutputResult<()> {
-        let Some(writer) = self.summaries.as_mut() else {
+    fn write_routes(&mut self, rows: &[RouteRow]) -> OutputResult<()> {
+        if rows.is_empty() {
             return Ok(());
+        }
+        let schema = route_schema();
+        let writer = match &mut self.routes {
+            Some(writer) => writer,
+            None => self.routes.insert(parquet_file(&self.dest, "routes.parquet", &schema)?),
         };
+        writer.write(&route_batch(&schema, rows)?)?;
+        Ok(())
+    }
 
-        let mut ticks      = UInt64Builder::new();
-        let mut unix_times = Int64Builder::new();
-        let mut woken      = UInt64Builder::new();
-
-        ticks.append_value(row.tick);
-        unix_times.append_value(row.unix_time_secs);
-        woken.append_value(row.woken_agents);
-
-        let batch = RecordBatch::try_new(
-            Arc::clone(&self.summ_schema),
-            vec![
-                Arc::new(ticks.finish()),
-                Arc::new(unix_times.finish()),
-                Arc::new(woken.finish()),
-            ],
-        )?;
-        writer.write(&batch)?;
+    fn write_od_matrix(&mut self, rows: &[OdRow]) -> OutputResult<()> {
+        let schema = od_schema();
+        let mut writer = parquet_file(&self.dest, "od_matrix.parquet", &schema)?;
+        writer.write(&od_batch(&schema, rows)?)?;
+        close(writer)
+    }
+
+    fn write_link_volumes(&mut self, rows: &[LinkVolumeRow]) -> OutputResult<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let schema = link_volume_schema();
+        let writer = match &mut self.volumes {
+            Some(writer) => writer,
+            None => self.volumes.insert(parquet_file(&self.dest, "link_volumes.parquet", &schema)?),
+        };
+        writer.write(&link_volume_batch(&schema, rows)?)?;
         Ok(())
     }
 
+    fn output_dir(&self) -> Option<&Path> {
+        self.dest.local_dir()
+    }
+
+    fn write_file(&mut self, name: &str, contents: &[u8]) -> OutputResult<()> {
+        Ok(self.dest.write_file(name, contents)?)
+    }
+
     fn finish(&mut self) -> OutputResult<()> {
         if let Some(w) = self.snapshots.take() {
-            w.close()?;
+            close(w)?;
         }
         if let Some(w) = self.summaries.take() {
-            w.close()?;
+            close(w)?;
+        }
+        if let Some(w) = self.contacts.take() {
+            close(w)?;
+        }
+        if let Some(w) = self.trips.take() {
+            close(w)?;
+        }
+        if let Some(w) = self.volumes.take() {
+            close(w)?;
+        }
+        if let Some(w) = self.routes.take() {
+            close(w)?;
         }
         Ok(())
     }
@@ -0,0 +1,719 @@
+//! PostgreSQL / TimescaleDB output backend (feature `postgres`).
+//!
+//! Rows are bulk-loaded with `COPY … FROM STDIN` through the `psql` client:
+//! each table gets one long-running `psql` process, started at its first
+//! write, whose standard input carries the rows in COPY text format.  No
+//! database driver is linked; `psql` must be installed wherever the
+//! simulation runs.  The connection string is handed to `psql` as `PG*`
+//! environment variables rather than arguments, so a password in it does
+//! not show up in `ps`.
+//!
+//! Seven tables are created if missing: `agent_snapshots`, `tick_summaries`,
+//! `contacts`, `trips`, `routes`, `od_matrix`, and `link_volumes`, each
+//! optionally prefixed so several runs can share one database.  With
+//! [`PostgresWriterBuilder::hypertables`] they are also converted to
+//! TimescaleDB hypertables partitioned on the tick (the small `od_matrix`
+//! table stays a plain table).
+//!
+//! ```rust,ignore
+//! let writer = PostgresWriter::builder("postgresql://dt@db.example/results")
+//!     .table_prefix("run_42_")
+//!     .hypertables(1_000)
+//!     .connect()?;
+//! ```
+
+use std::fmt::Write as _;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+use crate::columns::{self, cell};
+use crate::writer::OutputWriter;
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnType, ColumnValue, ContactRow, LinkVolumeRow, OdRow,
+    OutputError, OutputResult, RouteRow, TickSummaryRow, TripRow,
+};
+
+// ── Builder ───────────────────────────────────────────────────────────────────
+
+/// Fluent builder for [`PostgresWriter`].
+///
+/// | Method              | Default                |
+/// |---------------------|------------------------|
+/// | `.psql(path)`       | `psql` on the `PATH`   |
+/// | `.table_prefix(p)`  | No prefix              |
+/// | `.hypertables(n)`   | Plain tables           |
+pub struct PostgresWriterBuilder {
+    conninfo:    String,
+    psql:        PathBuf,
+    prefix:      String,
+    chunk_ticks: Option<u64>,
+}
+
+impl PostgresWriterBuilder {
+    /// Path of the `psql` executable.
+    pub fn psql(mut self, path: impl Into<PathBuf>) -> Self {
+        self.psql = path.into();
+        self
+    }
+
+    /// Prepend `prefix` to every table name.
+    pub fn table_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Make every per-tick table a TimescaleDB hypertable with chunks spanning
+    /// `chunk_ticks` ticks.  Requires the `timescaledb` extension.
+    pub fn hypertables(mut self, chunk_ticks: u64) -> Self {
+        self.chunk_ticks = Some(chunk_ticks);
+        self
+    }
+
+    /// Create the tables (if missing) and return the writer.
+    pub fn connect(self) -> OutputResult<PostgresWriter> {
+        let table = |name: &str| quote(&format!("{}{name}", self.prefix));
+        let snapshots = table("agent_snapshots");
+        let summaries = table("tick_summaries");
+        let contacts  = table("contacts");
+        let trips     = table("trips");
+        let routes    = table("routes");
+        let od_matrix = table("od_matrix");
+        let volumes   = table("link_volumes");
+
+        let mut ddl = format!(
+            "CREATE TABLE IF NOT EXISTS {snapshots} (
+                 agent_id         BIGINT  NOT NULL,
+                 tick             BIGINT  NOT NULL,
+                 departure_node   BIGINT  NOT NULL,
+                 in_transit       BOOLEAN NOT NULL,
+                 destination_node BIGINT  NOT NULL,
+                 lat              REAL,
+                 lon              REAL,
+                 departure_tick   BIGINT,
+                 arrival_tick     BIGINT
+             );
+             CREATE TABLE IF NOT EXISTS {summaries} (
+                 tick               BIGINT PRIMARY KEY,
+                 unix_time_secs     BIGINT NOT NULL,
+                 woken_agents       BIGINT NOT NULL,
+                 in_transit         BIGINT NOT NULL,
+                 arrivals           BIGINT NOT NULL,
+                 departures         BIGINT NOT NULL,
+                 travel_intents     BIGINT NOT NULL,
+                 wake_intents       BIGINT NOT NULL,
+                 messages_sent      BIGINT NOT NULL,
+                 metric_intents     BIGINT NOT NULL,
+                 messages_delivered BIGINT NOT NULL,
+                 contacts           BIGINT NOT NULL,
+                 routing_failures   BIGINT NOT NULL,
+                 behavior_panics    BIGINT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS {contacts} (
+                 tick    BIGINT NOT NULL,
+                 agent_a BIGINT NOT NULL,
+                 agent_b BIGINT NOT NULL,
+                 node    BIGINT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS {trips} (
+                 agent       BIGINT NOT NULL,
+                 depart_tick BIGINT NOT NULL,
+                 arrive_tick BIGINT NOT NULL,
+                 from_node   BIGINT NOT NULL,
+                 to_node     BIGINT NOT NULL,
+                 mode        TEXT   NOT NULL,
+                 travel_secs REAL   NOT NULL,
+                 distance_m  REAL   NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS {routes} (
+                 agent       BIGINT   NOT NULL,
+                 depart_tick BIGINT   NOT NULL,
+                 from_node   BIGINT   NOT NULL,
+                 to_node     BIGINT   NOT NULL,
+                 mode        TEXT     NOT NULL,
+                 edges       BIGINT[] NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS {od_matrix} (
+                 origin_zone BIGINT   NOT NULL,
+                 dest_zone   BIGINT   NOT NULL,
+                 hour        SMALLINT NOT NULL,
+                 mode        TEXT     NOT NULL,
+                 trips       BIGINT   NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS {volumes} (
+                 tick     BIGINT NOT NULL,
+                 edge_id  BIGINT NOT NULL,
+                 vehicles BIGINT NOT NULL
+             );"
+        );
+        // Tables created before the extended summary get the missing
+        // counters, zero for earlier rows.
+        let added: Vec<String> = TickSummaryRow::COUNT_COLUMNS
+            .iter()
+            .map(|name| format!("ADD COLUMN IF NOT EXISTS {name} BIGINT NOT NULL DEFAULT 0"))
+            .collect();
+        let _ = write!(ddl, "ALTER TABLE {summaries} {};", added.join(", "));
+        // ... and snapshots the journey ticks, null for earlier rows.
+        let _ = write!(
+            ddl,
+            "ALTER TABLE {snapshots} ADD COLUMN IF NOT EXISTS departure_tick BIGINT, \
+             ADD COLUMN IF NOT EXISTS arrival_tick BIGINT;",
+        );
+        if let Some(chunk) = self.chunk_ticks {
+            for (name, column) in [
+                (&snapshots, "tick"),
+                (&summaries, "tick"),
+                (&contacts, "tick"),
+                (&trips, "depart_tick"),
+                (&routes, "depart_tick"),
+                (&volumes, "tick"),
+            ] {
+                let _ = write!(
+                    ddl,
+                    "SELECT create_hypertable({}, '{column}', chunk_time_interval => {chunk}, \
+                     if_not_exists => TRUE);",
+                    literal(name),
+                );
+            }
+        }
+
+        let psql = Psql { env: conninfo_env(&self.conninfo)?, path: self.psql };
+        psql.run(&ddl)?;
+
+        Ok(PostgresWriter {
+            snapshots: CopyStream::new(snapshots, snapshot_columns(&[])),
+            extra:     Vec::new(),
+            summaries: CopyStream::new(
+                summaries,
+                format!("tick, unix_time_secs, {}", TickSummaryRow::COUNT_COLUMNS.join(", ")),
+            ),
+            contacts:  CopyStream::new(contacts, "tick, agent_a, agent_b, node".into()),
+            trips:     CopyStream::new(
+                trips,
+                "agent, depart_tick, arrive_tick, from_node, to_node, mode, travel_secs, distance_m".into(),
+            ),
+            routes:    CopyStream::new(routes, "agent, depart_tick, from_node, to_node, mode, edges".into()),
+            od_matrix: CopyStream::new(od_matrix, "origin_zone, dest_zone, hour, mode, trips".into()),
+            volumes:   CopyStream::new(volumes, "tick, edge_id, vehicles".into()),
+            psql,
+            finished:  false,
+        })
+    }
+}
+
+// ── psql processes ────────────────────────────────────────────────────────────
+
+struct Psql {
+    /// The connection as `PG*` environment variables.
+    env:  Vec<(&'static str, String)>,
+    path: PathBuf,
+}
+
+impl Psql {
+    fn command(&self, sql: &str) -> Command {
+        let mut cmd = Command::new(&self.path);
+        cmd.envs(self.env.iter().map(|(var, value)| (var, value)))
+            .args(["-X", "-q", "-v", "ON_ERROR_STOP=1", "-c", sql])
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        cmd
+    }
+
+    /// Run `sql` to completion.
+    fn run(&self, sql: &str) -> OutputResult<()> {
+        let output = self.command(sql).stdin(Stdio::null()).output()?;
+        if !output.status.success() {
+            return Err(failure(&output.stderr));
+        }
+        Ok(())
+    }
+}
+
+fn failure(stderr: &[u8]) -> OutputError {
+    OutputError::Postgres(String::from_utf8_lossy(stderr).trim().to_string())
+}
+
+/// One table's `COPY … FROM STDIN` stream.
+struct CopyStream {
+    /// Quoted table name.
+    table:   String,
+    /// Comma-separated quoted column list.
+    columns: String,
+    process: Option<(Child, BufWriter<ChildStdin>)>,
+    /// Whether the COPY was ever started (the column list is then fixed).
+    started: bool,
+    /// Row text for the current batch.
+    buf:     String,
+}
+
+impl CopyStream {
+    fn new(table: String, columns: String) -> Self {
+        Self { table, columns, process: None, started: false, buf: String::new() }
+    }
+
+    /// Send the rows in `buf` to the table, starting `psql` if needed.
+    fn send(&mut self, psql: &Psql) -> OutputResult<()> {
+        if self.process.is_none() {
+            let sql = format!("COPY {} ({}) FROM STDIN", self.table, self.columns);
+            let mut child = psql.command(&sql).stdin(Stdio::piped()).spawn()?;
+            let stdin = child.stdin.take().expect("stdin is piped");
+            self.process = Some((child, BufWriter::new(stdin)));
+            self.started = true;
+        }
+        let (_, stdin) = self.process.as_mut().expect("process started above");
+        let result = stdin.write_all(self.buf.as_bytes());
+        self.buf.clear();
+        if let Err(e) = result {
+            // Most likely psql exited; report its error message instead.
+            return Err(self.close().err().unwrap_or(e.into()));
+        }
+        Ok(())
+    }
+
+    /// End the COPY and wait for `psql` to commit it.
+    fn close(&mut self) -> OutputResult<()> {
+        let Some((child, stdin)) = self.process.take() else {
+            return Ok(());
+        };
+        // Dropping stdin ends the COPY data.
+        let flushed = stdin.into_inner().map(drop).map_err(|e| e.into_error());
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(failure(&output.stderr));
+        }
+        flushed?;
+        Ok(())
+    }
+}
+
+// ── Connection parameters ─────────────────────────────────────────────────────
+
+/// libpq connection keywords and the environment variables standing in
+/// for them.
+const KEYWORD_ENV: &[(&str, &str)] = &[
+    ("host",                 "PGHOST"),
+    ("hostaddr",             "PGHOSTADDR"),
+    ("port",                 "PGPORT"),
+    ("dbname",               "PGDATABASE"),
+    ("user",                 "PGUSER"),
+    ("password",             "PGPASSWORD"),
+    ("passfile",             "PGPASSFILE"),
+    ("service",              "PGSERVICE"),
+    ("options",              "PGOPTIONS"),
+    ("application_name",     "PGAPPNAME"),
+    ("connect_timeout",      "PGCONNECT_TIMEOUT"),
+    ("client_encoding",      "PGCLIENTENCODING"),
+    ("sslmode",              "PGSSLMODE"),
+    ("sslcert",              "PGSSLCERT"),
+    ("sslkey",               "PGSSLKEY"),
+    ("sslrootcert",          "PGSSLROOTCERT"),
+    ("sslcrl",               "PGSSLCRL"),
+    ("gssencmode",           "PGGSSENCMODE"),
+    ("channel_binding",      "PGCHANNELBINDING"),
+    ("target_session_attrs", "PGTARGETSESSIONATTRS"),
+];
+
+/// Split `conninfo` — `key=value` pairs, a `postgresql://` URI, or a bare
+/// database name — into the `PG*` variables libpq reads.  Later settings
+/// of the same keyword win, as in libpq.
+pub(crate) fn conninfo_env(conninfo: &str) -> OutputResult<Vec<(&'static str, String)>> {
+    let uri = conninfo.strip_prefix("postgresql://").or_else(|| conninfo.strip_prefix("postgres://"));
+    let pairs = match uri {
+        Some(rest) => uri_params(rest)?,
+        None if conninfo.contains('=') => keyword_params(conninfo)?,
+        None if conninfo.trim().is_empty() => Vec::new(),
+        None => vec![("dbname".to_owned(), conninfo.trim().to_owned())],
+    };
+    pairs
+        .into_iter()
+        .map(|(key, value)| match KEYWORD_ENV.iter().find(|(k, _)| *k == key) {
+            Some(&(_, var)) => Ok((var, value)),
+            None => Err(bad_conninfo(&format!("unsupported parameter `{key}`"))),
+        })
+        .collect()
+}
+
+/// Whitespace-separated `key = value` pairs.  Values may be single-quoted;
+/// `\` escapes the next character.
+fn keyword_params(conninfo: &str) -> OutputResult<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    let mut chars = conninfo.chars().peekable();
+    let skip_space = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    };
+    loop {
+        skip_space(&mut chars);
+        if chars.peek().is_none() {
+            return Ok(pairs);
+        }
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|&c| c != '=' && !c.is_whitespace()) {
+            key.push(c);
+        }
+        skip_space(&mut chars);
+        if chars.next() != Some('=') {
+            return Err(bad_conninfo("a keyword without `=`"));
+        }
+        skip_space(&mut chars);
+        let quoted = chars.next_if_eq(&'\'').is_some();
+        let mut value = String::new();
+        loop {
+            match chars.next() {
+                Some('\\') => value.extend(chars.next()),
+                Some('\'') if quoted => break,
+                Some(c) if quoted || !c.is_whitespace() => value.push(c),
+                None if quoted => return Err(bad_conninfo(&format!("unterminated quote in `{key}`"))),
+                Some(_) | None => break,
+            }
+        }
+        pairs.push((key, value));
+    }
+}
+
+/// A URI after its scheme:
+/// `[user[:password]@][host][:port][,host[:port]…][/dbname][?key=value&…]`.
+fn uri_params(rest: &str) -> OutputResult<Vec<(String, String)>> {
+    let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let (authority, dbname) = rest.split_once('/').unwrap_or((rest, ""));
+    let (userinfo, hosts) = authority.rsplit_once('@').unwrap_or(("", authority));
+    let mut pairs = Vec::new();
+    let (user, password) = userinfo.split_once(':').map_or((userinfo, None), |(u, p)| (u, Some(p)));
+    if !user.is_empty() {
+        pairs.push(("user".to_owned(), percent_decode(user)?));
+    }
+    if let Some(password) = password {
+        pairs.push(("password".to_owned(), percent_decode(password)?));
+    }
+
+    let (mut host_list, mut port_list) = (Vec::new(), Vec::new());
+    for spec in hosts.split(',').filter(|spec| !spec.is_empty()) {
+        // IPv6 addresses are bracketed: `[::1]:5432`.
+        let (host, port) = match spec.strip_prefix('[') {
+            Some(v6) => {
+                let (host, after) = v6.split_once(']').ok_or_else(|| bad_conninfo("unclosed `[` in host"))?;
+                (host, after.strip_prefix(':'))
+            }
+            None => spec.split_once(':').map_or((spec, None), |(h, p)| (h, Some(p))),
+        };
+        host_list.push(percent_decode(host)?);
+        port_list.push(port.unwrap_or_default().to_owned());
+    }
+    if host_list.iter().any(|host| !host.is_empty()) {
+        pairs.push(("host".to_owned(), host_list.join(",")));
+    }
+    if port_list.iter().any(|port| !port.is_empty()) {
+        pairs.push(("port".to_owned(), port_list.join(",")));
+    }
+    if !dbname.is_empty() {
+        pairs.push(("dbname".to_owned(), percent_decode(dbname)?));
+    }
+    for param in query.split('&').filter(|param| !param.is_empty()) {
+        let (key, value) = param.split_once('=').ok_or_else(|| bad_conninfo("query parameter without `=`"))?;
+        pairs.push((percent_decode(key)?, percent_decode(value)?));
+    }
+    Ok(pairs)
+}
+
+/// Undo URI `%XX` escapes.
+fn percent_decode(s: &str) -> OutputResult<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = s
+                .get(i + 1..i + 3)
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| bad_conninfo("bad `%` escape"))?;
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).map_err(|_| bad_conninfo("`%` escapes are not UTF-8"))
+}
+
+/// Errors name the offending keyword at most, never a value, which might
+/// be the password.
+fn bad_conninfo(what: &str) -> OutputError {
+    OutputError::Postgres(format!("invalid connection string: {what}"))
+}
+
+// ── COPY text format ──────────────────────────────────────────────────────────
+
+/// Quote an SQL identifier.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Quote an SQL string literal.
+fn literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// Column list for the snapshot COPY with `extra` columns appended.
+fn snapshot_columns(extra: &[ColumnSpec]) -> String {
+    let mut names = String::from(
+        "agent_id, tick, departure_node, in_transit, destination_node, lat, lon, departure_tick, arrival_tick",
+    );
+    for col in extra {
+        names.push_str(", ");
+        names.push_str(&quote(&col.name));
+    }
+    names
+}
+
+/// Append `s` as a COPY text field.
+fn push_text(buf: &mut String, s: &str) {
+    for ch in s.chars() {
+        match ch {
+            '\\' => buf.push_str("\\\\"),
+            '\t' => buf.push_str("\\t"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            c    => buf.push(c),
+        }
+    }
+}
+
+fn push_value(buf: &mut String, value: &ColumnValue) {
+    let _ = match value {
+        ColumnValue::Null     => write!(buf, "\\N"),
+        ColumnValue::Int(v)   => write!(buf, "{v}"),
+        ColumnValue::Float(v) => write!(buf, "{v}"),
+        ColumnValue::Bool(v)  => write!(buf, "{}", if *v { "t" } else { "f" }),
+        ColumnValue::Text(s)  => {
+            push_text(buf, s);
+            Ok(())
+        }
+    };
+}
+
+fn push_opt(buf: &mut String, value: Option<impl std::fmt::Display>) {
+    let _ = match value {
+        Some(v) => write!(buf, "\t{v}"),
+        None    => write!(buf, "\t\\N"),
+    };
+}
+
+// ── PostgresWriter ────────────────────────────────────────────────────────────
+
+/// Bulk-loads simulation output into PostgreSQL with `COPY`.
+///
+/// Rows become visible to other sessions when `finish()` ends each COPY.
+pub struct PostgresWriter {
+    psql:      Psql,
+    snapshots: CopyStream,
+    /// Declared extra snapshot columns.
+    extra:     Vec<ColumnSpec>,
+    summaries: CopyStream,
+    contacts:  CopyStream,
+    trips:     CopyStream,
+    routes:    CopyStream,
+    od_matrix: CopyStream,
+    volumes:   CopyStream,
+    finished:  bool,
+}
+
+impl PostgresWriter {
+    /// Start building a writer for the database at `conninfo`, a libpq
+    /// connection string or `postgresql://` URI.
+    pub fn builder(conninfo: impl Into<String>) -> PostgresWriterBuilder {
+        PostgresWriterBuilder {
+            conninfo:    conninfo.into(),
+            psql:        PathBuf::from("psql"),
+            prefix:      String::new(),
+            chunk_ticks: None,
+        }
+    }
+
+    /// Connect with default options; see [`builder`][Self::builder].
+    pub fn connect(conninfo: impl Into<String>) -> OutputResult<Self> {
+        Self::builder(conninfo).connect()
+    }
+
+    fn check_open(&self) -> OutputResult<()> {
+        if self.finished {
+            return Err(OutputError::Postgres("writer already finished".into()));
+        }
+        Ok(())
+    }
+}
+
+impl OutputWriter for PostgresWriter {
+    /// Adds any column not yet in the snapshot table; columns are never
+    /// dropped, so an existing table keeps its earlier extras.
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()> {
+        columns::validate(columns)?;
+        if self.snapshots.started {
+            return Err(OutputError::Column(
+                "cannot change snapshot columns after snapshots were written".into(),
+            ));
+        }
+        let mut ddl = String::new();
+        for col in columns {
+            let ty = match col.ty {
+                ColumnType::Int   => "BIGINT",
+                ColumnType::Float => "DOUBLE PRECISION",
+                ColumnType::Bool  => "BOOLEAN",
+                ColumnType::Text  => "TEXT",
+            };
+            let _ = write!(
+                ddl,
+                "ALTER TABLE {} ADD COLUMN IF NOT EXISTS {} {ty};",
+                self.snapshots.table,
+                quote(&col.name),
+            );
+        }
+        if !ddl.is_empty() {
+            self.psql.run(&ddl)?;
+        }
+        self.extra = columns.to_vec();
+        self.snapshots.columns = snapshot_columns(columns);
+        Ok(())
+    }
+
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:    &[AgentSnapshotRow],
+        columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()> {
+        self.check_open()?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let buf = &mut self.snapshots.buf;
+        for (i, row) in rows.iter().enumerate() {
+            let _ = write!(
+                buf,
+                "{}\t{}\t{}\t{}\t{}",
+                row.agent_id,
+                row.tick,
+                row.departure_node,
+                if row.in_transit { "t" } else { "f" },
+                row.destination_node,
+            );
+            push_opt(buf, row.lat);
+            push_opt(buf, row.lon);
+            push_opt(buf, row.departure_tick);
+            push_opt(buf, row.arrival_tick);
+            for c in 0..self.extra.len() {
+                buf.push('\t');
+                push_value(buf, cell(columns, c, i));
+            }
+            buf.push('\n');
+        }
+        self.snapshots.send(&self.psql)
+    }
+
+    fn write_tick_summary(&mut self, row: &TickSummaryRow) -> OutputResult<()> {
+        self.check_open()?;
+        let _ = write!(self.summaries.buf, "{}\t{}", row.tick, row.unix_time_secs);
+        for count in row.counts() {
+            let _ = write!(self.summaries.buf, "\t{count}");
+        }
+        self.summaries.buf.push('\n');
+        self.summaries.send(&self.psql)
+    }
+
+    fn write_contacts(&mut self, rows: &[ContactRow]) -> OutputResult<()> {
+        self.check_open()?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+        for row in rows {
+            let _ = writeln!(
+                self.contacts.buf,
+                "{}\t{}\t{}\t{}",
+                row.tick, row.agent_a, row.agent_b, row.node,
+            );
+        }
+        self.contacts.send(&self.psql)
+    }
+
+    fn write_trips(&mut self, rows: &[TripRow]) -> OutputResult<()> {
+        self.check_open()?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+        for row in rows {
+            let _ = writeln!(
+                self.trips.buf,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                row.agent,
+                row.depart_tick,
+                row.arrive_tick,
+                row.from,
+                row.to,
+                row.mode.as_str(),
+                row.travel_secs,
+                row.distance_m,
+            );
+        }
+        self.trips.send(&self.psql)
+    }
+
+    fn write_routes(&mut self, rows: &[RouteRow]) -> OutputResult<()> {
+        self.check_open()?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+        for row in rows {
+            let edges: Vec<String> = row.edges.iter().map(u32::to_string).collect();
+            let _ = writeln!(
+                self.routes.buf,
+                "{}\t{}\t{}\t{}\t{}\t{{{}}}",
+                row.agent, row.depart_tick, row.from, row.to, row.mode.as_str(), edges.join(","),
+            );
+        }
+        self.routes.send(&self.psql)
+    }
+
+    fn write_od_matrix(&mut self, rows: &[OdRow]) -> OutputResult<()> {
+        self.check_open()?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+        for row in rows {
+            let _ = writeln!(
+                self.od_matrix.buf,
+                "{}\t{}\t{}\t{}\t{}",
+                row.origin_zone, row.dest_zone, row.hour, row.mode.as_str(), row.trips,
+            );
+        }
+        self.od_matrix.send(&self.psql)
+    }
+
+    fn write_link_volumes(&mut self, rows: &[LinkVolumeRow]) -> OutputResult<()> {
+        self.check_open()?;
+        if rows.is_empty() {
+            return Ok(());
+        }
+        for row in rows {
+            let _ = writeln!(self.volumes.buf, "{}\t{}\t{}", row.tick, row.edge, row.vehicles);
+        }
+        self.volumes.send(&self.psql)
+    }
+
+    fn finish(&mut self) -> OutputResult<()> {
+        if self.finished {
+            return Ok(());
+        }
+        self.finished = true;
+        // Close every stream even if one fails; report the first error.
+        [
+            self.snapshots.close(),
+            self.summaries.close(),
+            self.contacts.close(),
+            self.trips.close(),
+            self.routes.close(),
+            self.od_matrix.close(),
+            self.volumes.close(),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
@@ -0,0 +1,751 @@
+//! Reading a run's output back for analysis.
+//!
+//! [`ResultsReader`] loads the tables any file-based backend wrote into the
+//! same row types the backends were given, whatever the format:
+//!
+//! ```rust,ignore
+//! let results = ResultsReader::open(Path::new("./output"))?;  // CSV, Parquet, SQLite, …
+//! let trips: Vec<TripRow> = results.trips()?;
+//! let all: MemoryWriter = results.read_all()?;               // every table, extra columns too
+//! ```
+//!
+//! With the `arrow` feature each table is also one Arrow `RecordBatch` with
+//! the schema the Parquet backend writes, so every format ends up in the
+//! same frame for Arrow-based dataframe libraries.  [`join_nodes`] and
+//! [`join_edges`] add network attributes to a batch by node or edge id:
+//!
+//! ```rust,ignore
+//! let trips = join_nodes(&results.trip_batch()?, "from", &network)?;  // + from_lat, from_lon
+//! let volumes = join_edges(&results.link_volume_batch()?, "edge_id", &network)?;
+//! ```
+//!
+//! The format is detected from the files in the directory (see
+//! [`Format`]); formats whose Cargo feature is off can't be read.  Tables
+//! that were never written read as empty.  CSV and JSON Lines keep no
+//! column types, so their extra snapshot columns get the narrowest type
+//! that holds every value; booleans written as `1`/`0` (CSV, SQLite) come
+//! back as `Int`.  JSON's `null` node sentinels come back as `u32::MAX`.
+
+#[cfg(any(feature = "jsonl", feature = "parquet", feature = "arrow-ipc"))]
+use std::fs::File;
+#[cfg(feature = "jsonl")]
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use dt_core::TransportMode;
+
+use crate::columns::SNAPSHOT_COLUMNS;
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnType, ColumnValue, Compression, ContactRow, LinkVolumeRow,
+    MemoryWriter, OdRow, OutputError, OutputResult, RouteRow, TickSummaryRow, TripRow,
+};
+
+// ── Format ────────────────────────────────────────────────────────────────────
+
+/// On-disk format of a run's output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `{table}.csv`, `.csv.gz`, or `.csv.zst`.
+    Csv(Compression),
+    /// `{table}.jsonl`.
+    #[cfg(feature = "jsonl")]
+    Jsonl,
+    /// `{table}.parquet`.
+    #[cfg(feature = "parquet")]
+    Parquet,
+    /// `{table}.arrows` (IPC streams).
+    #[cfg(feature = "arrow-ipc")]
+    ArrowIpc,
+    /// Tables of `output.db`.
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+}
+
+impl Format {
+    /// Every format this build can read, in detection order.
+    fn readable() -> Vec<Format> {
+        vec![
+            #[cfg(feature = "sqlite")]
+            Format::Sqlite,
+            #[cfg(feature = "parquet")]
+            Format::Parquet,
+            #[cfg(feature = "arrow-ipc")]
+            Format::ArrowIpc,
+            #[cfg(feature = "jsonl")]
+            Format::Jsonl,
+            Format::Csv(Compression::None),
+            #[cfg(feature = "gzip")]
+            Format::Csv(Compression::Gzip),
+            #[cfg(feature = "zstd")]
+            Format::Csv(Compression::Zstd),
+        ]
+    }
+
+    /// The file holding `table` in `dir`.
+    fn path(self, dir: &Path, table: &str) -> PathBuf {
+        match self {
+            Format::Csv(compression) => dir.join(format!("{table}.{}", compression.extension())),
+            #[cfg(feature = "jsonl")]
+            Format::Jsonl => dir.join(format!("{table}.jsonl")),
+            #[cfg(feature = "parquet")]
+            Format::Parquet => dir.join(format!("{table}.parquet")),
+            #[cfg(feature = "arrow-ipc")]
+            Format::ArrowIpc => dir.join(format!("{table}.arrows")),
+            #[cfg(feature = "sqlite")]
+            Format::Sqlite => dir.join("output.db"),
+        }
+    }
+}
+
+// ── ResultsReader ─────────────────────────────────────────────────────────────
+
+/// Reads the tables of one output directory.
+///
+/// Each call reads its table from disk again; use
+/// [`read_all`][Self::read_all] to load everything once.
+#[derive(Debug, Clone)]
+pub struct ResultsReader {
+    dir:    PathBuf,
+    format: Format,
+}
+
+impl ResultsReader {
+    /// Read `dir`, detecting the format from `output.db` or the
+    /// `tick_summaries` file, which every backend creates.
+    pub fn open(dir: &Path) -> OutputResult<Self> {
+        Format::readable()
+            .into_iter()
+            .find(|f| f.path(dir, "tick_summaries").exists())
+            .map(|format| Self::with_format(dir, format))
+            .ok_or_else(|| OutputError::Read(format!("no output this build can read in {}", dir.display())))
+    }
+
+    /// Read `dir` as `format`, without detection.
+    pub fn with_format(dir: &Path, format: Format) -> Self {
+        Self { dir: dir.to_path_buf(), format }
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Every table, in a [`MemoryWriter`] as if the run had written to it;
+    /// extra snapshot columns are in its `snapshot_columns`.
+    pub fn read_all(&self) -> OutputResult<MemoryWriter> {
+        Ok(MemoryWriter {
+            tick_summaries: self.tick_summaries()?,
+            contacts:       self.contacts()?,
+            trips:          self.trips()?,
+            routes:         self.routes()?,
+            od_matrix:      self.od_matrix()?,
+            link_volumes:   self.link_volumes()?,
+            finished:       true,
+            ..self.snapshot_table()?
+        })
+    }
+
+    /// Agent snapshot rows, without their extra columns.
+    pub fn snapshots(&self) -> OutputResult<Vec<AgentSnapshotRow>> {
+        Ok(self.snapshot_table()?.snapshots)
+    }
+
+    /// The snapshot rows and extra columns, in an otherwise empty writer.
+    fn snapshot_table(&self) -> OutputResult<MemoryWriter> {
+        let Some(t) = self.table("agent_snapshots")? else {
+            return Ok(MemoryWriter::new());
+        };
+        let (agent, tick, departure) = (t.col("agent_id")?, t.col("tick")?, t.col("departure_node")?);
+        let (in_transit, destination) = (t.col("in_transit")?, t.col("destination_node")?);
+        // Absent in files written before coordinates were added.
+        let (lat, lon) = (t.get("lat"), t.get("lon"));
+        // ... or journey ticks.
+        let (departure_tick, arrival_tick) = (t.get("departure_tick"), t.get("arrival_tick"));
+        let mut rows = Vec::with_capacity(t.rows);
+        for i in 0..t.rows {
+            rows.push(AgentSnapshotRow {
+                agent_id:         agent.uint(i)?,
+                tick:             tick.uint(i)?,
+                departure_node:   departure.node(i)?,
+                in_transit:       in_transit.bool(i)?,
+                destination_node: destination.node(i)?,
+                lat:              lat.map(|c| c.f32(i)).transpose()?.flatten(),
+                lon:              lon.map(|c| c.f32(i)).transpose()?.flatten(),
+                departure_tick:   departure_tick.map(|c| c.opt_uint(i)).transpose()?.flatten(),
+                arrival_tick:     arrival_tick.map(|c| c.opt_uint(i)).transpose()?.flatten(),
+            });
+        }
+
+        let extra: Vec<&Column> =
+            t.columns.iter().filter(|c| !SNAPSHOT_COLUMNS.contains(&c.name.as_str())).collect();
+        let specs: Vec<ColumnSpec> = extra
+            .iter()
+            .map(|c| ColumnSpec { name: c.name.clone(), ty: c.ty.unwrap_or_else(|| infer(&c.values)) })
+            .collect();
+        let values = (0..t.rows)
+            .map(|i| extra.iter().zip(&specs).map(|(c, spec)| coerce(&c.values[i], spec.ty)).collect())
+            .collect();
+        Ok(MemoryWriter { snapshot_columns: specs, snapshots: rows, snapshot_values: values, ..MemoryWriter::new() })
+    }
+
+    pub fn tick_summaries(&self) -> OutputResult<Vec<TickSummaryRow>> {
+        let Some(t) = self.table("tick_summaries")? else {
+            return Ok(Vec::new());
+        };
+        let (tick, unix) = (t.col("tick")?, t.col("unix_time_secs")?);
+        // Counters added after a file was written read as zero.
+        let counts = TickSummaryRow::COUNT_COLUMNS.map(|name| t.get(name));
+        (0..t.rows)
+            .map(|i| {
+                let mut c = [0u64; 12];
+                for (value, col) in c.iter_mut().zip(&counts) {
+                    if let Some(col) = col {
+                        *value = col.uint(i)?;
+                    }
+                }
+                Ok(TickSummaryRow {
+                    tick:               tick.uint(i)?,
+                    unix_time_secs:     unix.int(i)?,
+                    woken_agents:       c[0],
+                    in_transit:         c[1],
+                    arrivals:           c[2],
+                    departures:         c[3],
+                    travel_intents:     c[4],
+                    wake_intents:       c[5],
+                    messages_sent:      c[6],
+                    metric_intents:     c[7],
+                    messages_delivered: c[8],
+                    contacts:           c[9],
+                    routing_failures:   c[10],
+                    behavior_panics:    c[11],
+                })
+            })
+            .collect()
+    }
+
+    pub fn contacts(&self) -> OutputResult<Vec<ContactRow>> {
+        let Some(t) = self.table("contacts")? else {
+            return Ok(Vec::new());
+        };
+        let (tick, a, b, node) = (t.col("tick")?, t.col("agent_a")?, t.col("agent_b")?, t.col("node")?);
+        (0..t.rows)
+            .map(|i| {
+                Ok(ContactRow { tick: tick.uint(i)?, agent_a: a.uint(i)?, agent_b: b.uint(i)?, node: node.node(i)? })
+            })
+            .collect()
+    }
+
+    pub fn trips(&self) -> OutputResult<Vec<TripRow>> {
+        let Some(t) = self.table("trips")? else {
+            return Ok(Vec::new());
+        };
+        let (agent, depart, arrive) = (t.col("agent")?, t.col("depart_tick")?, t.col("arrive_tick")?);
+        let (from, to, mode) = (t.col("from")?, t.col("to")?, t.col("mode")?);
+        let (secs, dist) = (t.col("travel_secs")?, t.col("distance_m")?);
+        (0..t.rows)
+            .map(|i| {
+                Ok(TripRow {
+                    agent:       agent.uint(i)?,
+                    depart_tick: depart.uint(i)?,
+                    arrive_tick: arrive.uint(i)?,
+                    from:        from.node(i)?,
+                    to:          to.node(i)?,
+                    mode:        mode.mode(i)?,
+                    travel_secs: secs.f32(i)?.ok_or_else(|| secs.bad(i))?,
+                    distance_m:  dist.f32(i)?.ok_or_else(|| dist.bad(i))?,
+                })
+            })
+            .collect()
+    }
+
+    pub fn routes(&self) -> OutputResult<Vec<RouteRow>> {
+        let Some(t) = self.table("routes")? else {
+            return Ok(Vec::new());
+        };
+        let (agent, depart, from, to) = (t.col("agent")?, t.col("depart_tick")?, t.col("from")?, t.col("to")?);
+        let (mode, edges) = (t.col("mode")?, t.col("edges")?);
+        (0..t.rows)
+            .map(|i| {
+                Ok(RouteRow {
+                    agent:       agent.uint(i)?,
+                    depart_tick: depart.uint(i)?,
+                    from:        from.node(i)?,
+                    to:          to.node(i)?,
+                    mode:        mode.mode(i)?,
+                    edges:       edges.edges(i)?,
+                })
+            })
+            .collect()
+    }
+
+    pub fn od_matrix(&self) -> OutputResult<Vec<OdRow>> {
+        let Some(t) = self.table("od_matrix")? else {
+            return Ok(Vec::new());
+        };
+        let (origin, dest, hour) = (t.col("origin_zone")?, t.col("dest_zone")?, t.col("hour")?);
+        let (mode, trips) = (t.col("mode")?, t.col("trips")?);
+        (0..t.rows)
+            .map(|i| {
+                Ok(OdRow {
+                    origin_zone: origin.uint(i)?,
+                    dest_zone:   dest.uint(i)?,
+                    hour:        hour.uint(i)?,
+                    mode:        mode.mode(i)?,
+                    trips:       trips.uint(i)?,
+                })
+            })
+            .collect()
+    }
+
+    pub fn link_volumes(&self) -> OutputResult<Vec<LinkVolumeRow>> {
+        let Some(t) = self.table("link_volumes")? else {
+            return Ok(Vec::new());
+        };
+        let (tick, edge, vehicles) = (t.col("tick")?, t.col("edge_id")?, t.col("vehicles")?);
+        (0..t.rows)
+            .map(|i| Ok(LinkVolumeRow { tick: tick.uint(i)?, edge: edge.uint(i)?, vehicles: vehicles.uint(i)? }))
+            .collect()
+    }
+
+    /// `table` as loosely typed columns; `None` if it wasn't written.
+    fn table(&self, table: &'static str) -> OutputResult<Option<Table>> {
+        let path = self.format.path(&self.dir, table);
+        if !path.exists() {
+            return Ok(None);
+        }
+        match self.format {
+            Format::Csv(compression) => read_csv(&path, compression).map(Some),
+            #[cfg(feature = "jsonl")]
+            Format::Jsonl => read_jsonl(&path).map(Some),
+            #[cfg(feature = "parquet")]
+            Format::Parquet => {
+                let reader = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(File::open(&path)?)?
+                    .build()?;
+                read_batches(reader).map(Some)
+            }
+            #[cfg(feature = "arrow-ipc")]
+            Format::ArrowIpc => {
+                // The schema is written with the first batch; an empty file
+                // is a table that got no rows.
+                if std::fs::metadata(&path)?.len() == 0 {
+                    return Ok(None);
+                }
+                read_batches(arrow::ipc::reader::StreamReader::try_new(File::open(&path)?, None)?).map(Some)
+            }
+            #[cfg(feature = "sqlite")]
+            Format::Sqlite => read_sqlite(&path, table),
+        }
+    }
+}
+
+// ── Arrow ─────────────────────────────────────────────────────────────────────
+
+#[cfg(feature = "arrow")]
+mod arrow_tables {
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, AsArray, Float32Builder, UInt32Builder};
+    use arrow::datatypes::{DataType, Field, Schema, UInt32Type};
+    use arrow::record_batch::RecordBatch;
+    use dt_spatial::RoadNetwork;
+
+    use super::ResultsReader;
+    use crate::{MemoryWriter, OutputError, OutputResult};
+
+    impl ResultsReader {
+        /// Agent snapshots and their extra columns as one batch.
+        pub fn snapshot_batch(&self) -> OutputResult<RecordBatch> {
+            self.snapshot_table()?.snapshot_batch()
+        }
+
+        pub fn tick_summary_batch(&self) -> OutputResult<RecordBatch> {
+            MemoryWriter { tick_summaries: self.tick_summaries()?, ..Default::default() }.tick_summary_batch()
+        }
+
+        pub fn contact_batch(&self) -> OutputResult<RecordBatch> {
+            MemoryWriter { contacts: self.contacts()?, ..Default::default() }.contact_batch()
+        }
+
+        pub fn trip_batch(&self) -> OutputResult<RecordBatch> {
+            MemoryWriter { trips: self.trips()?, ..Default::default() }.trip_batch()
+        }
+
+        pub fn route_batch(&self) -> OutputResult<RecordBatch> {
+            MemoryWriter { routes: self.routes()?, ..Default::default() }.route_batch()
+        }
+
+        pub fn od_batch(&self) -> OutputResult<RecordBatch> {
+            MemoryWriter { od_matrix: self.od_matrix()?, ..Default::default() }.od_batch()
+        }
+
+        pub fn link_volume_batch(&self) -> OutputResult<RecordBatch> {
+            MemoryWriter { link_volumes: self.link_volumes()?, ..Default::default() }.link_volume_batch()
+        }
+    }
+
+    /// The `UInt32` id column `column` of `batch`.
+    fn ids<'b>(batch: &'b RecordBatch, column: &str) -> OutputResult<&'b arrow::array::UInt32Array> {
+        batch
+            .column_by_name(column)
+            .and_then(|a| a.as_primitive_opt::<UInt32Type>())
+            .ok_or_else(|| OutputError::Read(format!("no UInt32 column {column:?} to join on")))
+    }
+
+    /// `batch` with `new` columns appended.
+    fn extend(batch: &RecordBatch, new: Vec<(Field, ArrayRef)>) -> OutputResult<RecordBatch> {
+        let mut fields: Vec<Field> = batch.schema().fields().iter().map(|f| f.as_ref().clone()).collect();
+        let mut columns = batch.columns().to_vec();
+        for (field, array) in new {
+            fields.push(field);
+            columns.push(array);
+        }
+        Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)?)
+    }
+
+    /// `batch` plus `{column}_lat` and `{column}_lon`: the position of the
+    /// node in id column `column`.  Null for `u32::MAX` and unknown ids.
+    pub fn join_nodes(batch: &RecordBatch, column: &str, network: &RoadNetwork) -> OutputResult<RecordBatch> {
+        let ids = ids(batch, column)?;
+        let (mut lats, mut lons) = (Float32Builder::new(), Float32Builder::new());
+        for id in ids.iter() {
+            let pos = id.and_then(|id| network.node_pos.get(id as usize));
+            lats.append_option(pos.map(|p| p.lat));
+            lons.append_option(pos.map(|p| p.lon));
+        }
+        extend(batch, vec![
+            (Field::new(format!("{column}_lat"), DataType::Float32, true), Arc::new(lats.finish())),
+            (Field::new(format!("{column}_lon"), DataType::Float32, true), Arc::new(lons.finish())),
+        ])
+    }
+
+    /// `batch` plus `{column}_from`, `{column}_to`, and `{column}_length_m`
+    /// for the edge in id column `column`.  Null for unknown ids.
+    ///
+    /// Chain with [`join_nodes`] on `{column}_from` for coordinates.
+    pub fn join_edges(batch: &RecordBatch, column: &str, network: &RoadNetwork) -> OutputResult<RecordBatch> {
+        let ids = ids(batch, column)?;
+        let (mut froms, mut tos, mut lengths) = (UInt32Builder::new(), UInt32Builder::new(), Float32Builder::new());
+        for id in ids.iter() {
+            let e = id.map(|id| id as usize).filter(|&e| e < network.edge_count());
+            froms.append_option(e.map(|e| network.edge_from[e].0));
+            tos.append_option(e.map(|e| network.edge_to[e].0));
+            lengths.append_option(e.map(|e| network.edge_length_m[e]));
+        }
+        extend(batch, vec![
+            (Field::new(format!("{column}_from"), DataType::UInt32, true), Arc::new(froms.finish())),
+            (Field::new(format!("{column}_to"), DataType::UInt32, true), Arc::new(tos.finish())),
+            (Field::new(format!("{column}_length_m"), DataType::Float32, true), Arc::new(lengths.finish())),
+        ])
+    }
+
+    /// `array` as cells, with the column type it implies for extra columns.
+    #[cfg(any(feature = "parquet", feature = "arrow-ipc"))]
+    pub(super) fn cells(array: &dyn arrow::array::Array) -> OutputResult<(Option<crate::ColumnType>, Vec<crate::ColumnValue>)> {
+        use arrow::array::Array;
+        use arrow::datatypes::{ArrowPrimitiveType, Float32Type, Float64Type, Int64Type, UInt8Type, UInt64Type};
+
+        use crate::{ColumnType, ColumnValue};
+
+        fn each<T: ArrowPrimitiveType>(array: &dyn Array, f: impl Fn(T::Native) -> ColumnValue) -> Vec<ColumnValue> {
+            array.as_primitive::<T>().iter().map(|v| v.map_or(ColumnValue::Null, &f)).collect()
+        }
+        Ok(match array.data_type() {
+            DataType::UInt8   => (None, each::<UInt8Type>(array, |v| ColumnValue::Int(v.into()))),
+            DataType::UInt32  => (None, each::<UInt32Type>(array, |v| ColumnValue::Int(v.into()))),
+            DataType::UInt64  => (None, each::<UInt64Type>(array, |v| ColumnValue::Int(v as i64))),
+            DataType::Int64   => (Some(ColumnType::Int), each::<Int64Type>(array, ColumnValue::Int)),
+            DataType::Float32 => (None, each::<Float32Type>(array, |v| ColumnValue::Float(v.into()))),
+            DataType::Float64 => (Some(ColumnType::Float), each::<Float64Type>(array, ColumnValue::Float)),
+            DataType::Boolean => {
+                let values = array.as_boolean().iter().map(|v| v.map_or(ColumnValue::Null, ColumnValue::Bool));
+                (Some(ColumnType::Bool), values.collect())
+            }
+            DataType::Utf8 => {
+                let text = |s: &str| ColumnValue::Text(s.to_owned());
+                let values = array.as_string::<i32>().iter().map(|v| v.map_or(ColumnValue::Null, text));
+                (Some(ColumnType::Text), values.collect())
+            }
+            // Route edge lists, as the text backends write them.
+            DataType::List(_) => {
+                let lists = array.as_list::<i32>();
+                let values = (0..lists.len()).map(|i| {
+                    if lists.is_null(i) {
+                        return ColumnValue::Null;
+                    }
+                    let edges = lists.value(i);
+                    let ids: Vec<String> =
+                        edges.as_primitive::<UInt32Type>().iter().flatten().map(|e| e.to_string()).collect();
+                    ColumnValue::Text(format!("[{}]", ids.join(",")))
+                });
+                (None, values.collect())
+            }
+            other => return Err(OutputError::Read(format!("unsupported column type {other}"))),
+        })
+    }
+}
+
+#[cfg(feature = "arrow")]
+pub use arrow_tables::{join_edges, join_nodes};
+
+// ── Tables ────────────────────────────────────────────────────────────────────
+
+/// One column of a table as read, before conversion to row fields.
+struct Column {
+    name:   String,
+    /// The stored type, for formats that keep one.
+    ty:     Option<ColumnType>,
+    values: Vec<ColumnValue>,
+}
+
+/// A table as read: CSV fields are `Text`, other formats' cells are typed.
+#[derive(Default)]
+struct Table {
+    columns: Vec<Column>,
+    rows:    usize,
+}
+
+impl Table {
+    fn get(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    fn col(&self, name: &str) -> OutputResult<&Column> {
+        self.get(name).ok_or_else(|| OutputError::Read(format!("missing column {name:?}")))
+    }
+
+    /// The column called `name`, added (null for earlier rows) if new.
+    fn column_mut(&mut self, name: &str, ty: Option<ColumnType>) -> &mut Column {
+        let i = match self.columns.iter().position(|c| c.name == name) {
+            Some(i) => i,
+            None => {
+                let values = vec![ColumnValue::Null; self.rows];
+                self.columns.push(Column { name: name.to_owned(), ty, values });
+                self.columns.len() - 1
+            }
+        };
+        &mut self.columns[i]
+    }
+
+    /// Pad every column to `rows` with nulls.
+    fn fill(&mut self) {
+        for c in &mut self.columns {
+            c.values.resize(self.rows, ColumnValue::Null);
+        }
+    }
+}
+
+impl Column {
+    fn bad(&self, i: usize) -> OutputError {
+        OutputError::Read(format!("column {:?} row {i}: unexpected value {:?}", self.name, self.values[i]))
+    }
+
+    fn int(&self, i: usize) -> OutputResult<i64> {
+        match &self.values[i] {
+            ColumnValue::Int(v) => Ok(*v),
+            ColumnValue::Bool(b) => Ok(*b as i64),
+            ColumnValue::Text(s) => s.parse().map_err(|_| self.bad(i)),
+            _ => Err(self.bad(i)),
+        }
+    }
+
+    fn uint<T: TryFrom<i64>>(&self, i: usize) -> OutputResult<T> {
+        T::try_from(self.int(i)?).map_err(|_| self.bad(i))
+    }
+
+    /// An unsigned value, `None` if null or empty.
+    fn opt_uint<T: TryFrom<i64>>(&self, i: usize) -> OutputResult<Option<T>> {
+        match &self.values[i] {
+            ColumnValue::Null => Ok(None),
+            ColumnValue::Text(s) if s.is_empty() => Ok(None),
+            _ => self.uint(i).map(Some),
+        }
+    }
+
+    /// A node id; null is the `u32::MAX` sentinel.
+    fn node(&self, i: usize) -> OutputResult<u32> {
+        match self.values[i] {
+            ColumnValue::Null => Ok(u32::MAX),
+            _ => self.uint(i),
+        }
+    }
+
+    fn f32(&self, i: usize) -> OutputResult<Option<f32>> {
+        match &self.values[i] {
+            ColumnValue::Null => Ok(None),
+            ColumnValue::Float(v) => Ok(Some(*v as f32)),
+            ColumnValue::Int(v) => Ok(Some(*v as f32)),
+            ColumnValue::Text(s) if s.is_empty() => Ok(None),
+            ColumnValue::Text(s) => s.parse().map(Some).map_err(|_| self.bad(i)),
+            ColumnValue::Bool(_) => Err(self.bad(i)),
+        }
+    }
+
+    fn bool(&self, i: usize) -> OutputResult<bool> {
+        match &self.values[i] {
+            ColumnValue::Bool(b) => Ok(*b),
+            ColumnValue::Int(v @ (0 | 1)) => Ok(*v == 1),
+            ColumnValue::Text(s) if matches!(s.as_str(), "1" | "true") => Ok(true),
+            ColumnValue::Text(s) if matches!(s.as_str(), "0" | "false") => Ok(false),
+            _ => Err(self.bad(i)),
+        }
+    }
+
+    fn mode(&self, i: usize) -> OutputResult<TransportMode> {
+        let ColumnValue::Text(s) = &self.values[i] else {
+            return Err(self.bad(i));
+        };
+        [TransportMode::None, TransportMode::Car, TransportMode::Walk, TransportMode::Bike, TransportMode::Transit]
+            .into_iter()
+            .find(|m| m.as_str() == s)
+            .ok_or_else(|| self.bad(i))
+    }
+
+    /// An edge list written as a JSON array, e.g. `[3,7,9]`.
+    fn edges(&self, i: usize) -> OutputResult<Vec<u32>> {
+        let ColumnValue::Text(s) = &self.values[i] else {
+            return Err(self.bad(i));
+        };
+        let inner = s.trim().strip_prefix('[').and_then(|s| s.strip_suffix(']')).ok_or_else(|| self.bad(i))?;
+        inner
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|e| e.parse().map_err(|_| self.bad(i)))
+            .collect()
+    }
+}
+
+/// The type of an untyped extra column: the narrowest that holds every value.
+fn infer(values: &[ColumnValue]) -> ColumnType {
+    [ColumnType::Int, ColumnType::Float, ColumnType::Bool]
+        .into_iter()
+        .find(|&ty| values.iter().all(|v| coerce(v, ty).fits(ty)))
+        .unwrap_or(ColumnType::Text)
+}
+
+/// `value` converted to type `ty` where it can be; unchanged otherwise.
+fn coerce(value: &ColumnValue, ty: ColumnType) -> ColumnValue {
+    match (value, ty) {
+        (ColumnValue::Text(s), _) if s.is_empty() => ColumnValue::Null,
+        (ColumnValue::Text(s), ColumnType::Int) => s.parse().map_or_else(|_| value.clone(), ColumnValue::Int),
+        (ColumnValue::Text(s), ColumnType::Float) => s.parse().map_or_else(|_| value.clone(), ColumnValue::Float),
+        (ColumnValue::Int(v), ColumnType::Float) => ColumnValue::Float(*v as f64),
+        (ColumnValue::Int(v), ColumnType::Text) => ColumnValue::Text(v.to_string()),
+        (ColumnValue::Float(v), ColumnType::Text) => ColumnValue::Text(v.to_string()),
+        (ColumnValue::Bool(v), ColumnType::Text) => ColumnValue::Text(v.to_string()),
+        _ => value.clone(),
+    }
+}
+
+// ── Formats ───────────────────────────────────────────────────────────────────
+
+fn read_csv(path: &Path, compression: Compression) -> OutputResult<Table> {
+    let mut reader = csv::Reader::from_reader(crate::csv::open_csv(path, compression)?);
+    let mut table = Table::default();
+    for name in reader.headers()? {
+        table.column_mut(name, None);
+    }
+    for record in reader.records() {
+        for (column, field) in table.columns.iter_mut().zip(record?.iter()) {
+            column.values.push(if field.is_empty() { ColumnValue::Null } else { ColumnValue::Text(field.to_owned()) });
+        }
+        table.rows += 1;
+        table.fill();
+    }
+    Ok(table)
+}
+
+#[cfg(feature = "jsonl")]
+fn read_jsonl(path: &Path) -> OutputResult<Table> {
+    use serde_json::Value;
+
+    let mut table = Table::default();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Value::Object(obj) = serde_json::from_str(&line).map_err(std::io::Error::from)? else {
+            return Err(OutputError::Read(format!("{}: line is not an object", path.display())));
+        };
+        for (key, value) in obj {
+            let cell = match value {
+                Value::Null => ColumnValue::Null,
+                Value::Bool(b) => ColumnValue::Bool(b),
+                Value::Number(n) => match n.as_i64() {
+                    Some(v) => ColumnValue::Int(v),
+                    None => ColumnValue::Float(n.as_f64().unwrap_or(f64::NAN)),
+                },
+                Value::String(s) => ColumnValue::Text(s),
+                // Route edge lists.
+                Value::Array(_) | Value::Object(_) => ColumnValue::Text(value.to_string()),
+            };
+            table.column_mut(&key, None).values.push(cell);
+        }
+        table.rows += 1;
+        table.fill();
+    }
+    Ok(table)
+}
+
+#[cfg(any(feature = "parquet", feature = "arrow-ipc"))]
+fn read_batches(
+    batches: impl Iterator<Item = Result<arrow::record_batch::RecordBatch, arrow::error::ArrowError>>,
+) -> OutputResult<Table> {
+    let mut table = Table::default();
+    for batch in batches {
+        let batch = batch?;
+        for (field, array) in batch.schema().fields().iter().zip(batch.columns()) {
+            let (ty, values) = arrow_tables::cells(array.as_ref())?;
+            table.column_mut(field.name(), ty).values.extend(values);
+        }
+        table.rows += batch.num_rows();
+        table.fill();
+    }
+    Ok(table)
+}
+
+#[cfg(feature = "sqlite")]
+fn read_sqlite(path: &Path, table: &str) -> OutputResult<Option<Table>> {
+    use rusqlite::types::ValueRef;
+    use rusqlite::{Connection, OpenFlags};
+
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let types: Vec<(String, String)> = conn
+        .prepare(&format!("SELECT name, type FROM pragma_table_info('{table}')"))?
+        .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    if types.is_empty() {
+        return Ok(None);
+    }
+
+    let mut out = Table::default();
+    for (name, ty) in &types {
+        // `from` and `to` are SQL keywords.
+        let name = match name.as_str() {
+            "from_node" => "from",
+            "to_node" => "to",
+            name => name,
+        };
+        let ty = match ty.to_ascii_uppercase().as_str() {
+            "INTEGER" => ColumnType::Int,
+            "REAL" => ColumnType::Float,
+            _ => ColumnType::Text,
+        };
+        out.column_mut(name, Some(ty));
+    }
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {table}"))?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        for (c, column) in out.columns.iter_mut().enumerate() {
+            column.values.push(match row.get_ref(c)? {
+                ValueRef::Null => ColumnValue::Null,
+                ValueRef::Integer(v) => ColumnValue::Int(v),
+                ValueRef::Real(v) => ColumnValue::Float(v),
+                ValueRef::Text(s) => ColumnValue::Text(String::from_utf8_lossy(s).into_owned()),
+                ValueRef::Blob(_) => return Err(OutputError::Read(format!("BLOB in {table}.{}", column.name))),
+            });
+        }
+        out.rows += 1;
+    }
+    Ok(Some(out))
+}
@@ -1,7 +1,12 @@
 //! Plain data row types written by output backends.
 
+use dt_core::{AgentId, NodeId, Tick, TransportMode};
+use dt_mobility::{MovementState, Trip};
+use dt_sim::{AgentSnapshot, TickStats};
+use dt_spatial::Route;
+
 /// A snapshot of one agent's mobility state at a given tick.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct AgentSnapshotRow {
     pub agent_id:         u32,
     pub tick:             u64,
@@ -11,12 +16,204 @@ pub struct AgentSnapshotRow {
     pub in_transit:       bool,
     /// Destination node while in transit; `u32::MAX` if stationary.
     pub destination_node: u32,
+    /// Position in degrees, interpolated between the two nodes while in
+    /// transit.  `None` unless the observer was given the network (see
+    /// [`SimOutputObserver::with_network`][crate::SimOutputObserver::with_network])
+    /// and the agent has been placed.
+    pub lat:              Option<f32>,
+    pub lon:              Option<f32>,
+    /// Departure and arrival tick of the journey in progress, so a warm
+    /// start can resume it; `None` if stationary.
+    pub departure_tick:   Option<u64>,
+    pub arrival_tick:     Option<u64>,
+}
+
+/// Rows carry no transport mode, so in-transit agents resume by car.
+impl From<AgentSnapshotRow> for AgentSnapshot {
+    fn from(row: AgentSnapshotRow) -> Self {
+        AgentSnapshot {
+            agent:            AgentId(row.agent_id),
+            departure_node:   NodeId(row.departure_node),
+            in_transit:       row.in_transit,
+            destination_node: NodeId(row.destination_node),
+            mode:             TransportMode::Car,
+            departure_tick:   row.departure_tick.map(Tick),
+            arrival_tick:     row.arrival_tick.map(Tick),
+        }
+    }
 }
 
 /// Summary statistics for one simulation tick.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// The counters after `woken_agents` mirror [`TickStats`]; rows built by
+/// hand may leave them at zero with `..Default::default()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct TickSummaryRow {
-    pub tick:           u64,
-    pub unix_time_secs: i64,
-    pub woken_agents:   u64,
+    pub tick:               u64,
+    pub unix_time_secs:     i64,
+    pub woken_agents:       u64,
+    /// Agents in transit at the end of the tick.
+    pub in_transit:         u64,
+    pub arrivals:           u64,
+    pub departures:         u64,
+    pub travel_intents:     u64,
+    pub wake_intents:       u64,
+    pub messages_sent:      u64,
+    pub metric_intents:     u64,
+    pub messages_delivered: u64,
+    pub contacts:           u64,
+    pub routing_failures:   u64,
+    pub behavior_panics:    u64,
+}
+
+impl TickSummaryRow {
+    /// Names of the counter columns, in the order of [`counts`][Self::counts].
+    /// Every backend writes `tick`, `unix_time_secs`, then these.
+    pub(crate) const COUNT_COLUMNS: [&'static str; 12] = [
+        "woken_agents",
+        "in_transit",
+        "arrivals",
+        "departures",
+        "travel_intents",
+        "wake_intents",
+        "messages_sent",
+        "metric_intents",
+        "messages_delivered",
+        "contacts",
+        "routing_failures",
+        "behavior_panics",
+    ];
+
+    /// The row for `tick` from the sim's built-in counts.
+    pub fn from_stats(tick: u64, unix_time_secs: i64, stats: &TickStats) -> Self {
+        Self {
+            tick,
+            unix_time_secs,
+            woken_agents:       stats.woken,
+            in_transit:         stats.in_transit,
+            arrivals:           stats.arrivals,
+            departures:         stats.departures,
+            travel_intents:     stats.travel_intents,
+            wake_intents:       stats.wake_intents,
+            messages_sent:      stats.messages_sent,
+            metric_intents:     stats.metric_intents,
+            messages_delivered: stats.messages_delivered,
+            contacts:           stats.contacts,
+            routing_failures:   stats.routing_failures,
+            behavior_panics:    stats.behavior_panics,
+        }
+    }
+
+    /// Counter values in [`COUNT_COLUMNS`][Self::COUNT_COLUMNS] order.
+    pub(crate) fn counts(&self) -> [u64; 12] {
+        [
+            self.woken_agents,
+            self.in_transit,
+            self.arrivals,
+            self.departures,
+            self.travel_intents,
+            self.wake_intents,
+            self.messages_sent,
+            self.metric_intents,
+            self.messages_delivered,
+            self.contacts,
+            self.routing_failures,
+            self.behavior_panics,
+        ]
+    }
+}
+
+/// Two stationary agents at the same node in one tick, as seen by the woken
+/// agent `agent_a`.
+///
+/// When both agents woke that tick the contact is recorded once per side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContactRow {
+    pub tick:    u64,
+    pub agent_a: u32,
+    pub agent_b: u32,
+    pub node:    u32,
+}
+
+/// One completed journey, written when the agent arrives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TripRow {
+    pub agent:       u32,
+    pub depart_tick: u64,
+    pub arrive_tick: u64,
+    pub from:        u32,
+    pub to:          u32,
+    pub mode:        TransportMode,
+    /// Routed travel time in seconds, before rounding up to whole ticks.
+    pub travel_secs: f32,
+    pub distance_m:  f32,
+}
+
+/// The route chosen for one journey, recorded when it departs.  Joins to
+/// the trip table on (`agent`, `depart_tick`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteRow {
+    pub agent:       u32,
+    pub depart_tick: u64,
+    pub from:        u32,
+    pub to:          u32,
+    pub mode:        TransportMode,
+    /// Edge ids in travel order.
+    pub edges:       Vec<u32>,
+}
+
+impl RouteRow {
+    /// The row for `agent`, which just departed in `state` along `route`.
+    pub fn new(agent: AgentId, state: &MovementState, route: &Route) -> Self {
+        Self {
+            agent:       agent.0,
+            depart_tick: state.departure_tick.0,
+            from:        state.departure_node.0,
+            to:          state.destination_node.0,
+            mode:        state.mode,
+            edges:       route.edges.iter().map(|e| e.0).collect(),
+        }
+    }
+
+    /// The edge list as a JSON array, e.g. `[3,7,9]`, as written to the
+    /// text-based backends.
+    pub fn edge_list(&self) -> String {
+        let ids: Vec<String> = self.edges.iter().map(u32::to_string).collect();
+        format!("[{}]", ids.join(","))
+    }
+}
+
+/// Completed trips between two zones, by departure hour and mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OdRow {
+    pub origin_zone: u32,
+    pub dest_zone:   u32,
+    /// Hour of day (0–23) of the departure tick's wall-clock time.
+    pub hour:        u8,
+    pub mode:        TransportMode,
+    pub trips:       u64,
+}
+
+/// Vehicles that entered one edge during one aggregation interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkVolumeRow {
+    /// First tick of the interval.
+    pub tick:     u64,
+    pub edge:     u32,
+    pub vehicles: u64,
+}
+
+impl From<&Trip> for TripRow {
+    fn from(trip: &Trip) -> Self {
+        TripRow {
+            agent:       trip.agent.0,
+            depart_tick: trip.depart_tick.0,
+            arrive_tick: trip.arrive_tick.0,
+            from:        trip.from.0,
+            to:          trip.to.0,
+            mode:        trip.mode,
+            travel_secs: trip.travel_secs,
+            distance_m:  trip.distance_m,
+        }
+    }
 }
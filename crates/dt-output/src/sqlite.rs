@@ -1,80 +1,567 @@
 //! SQLite output backend (feature `sqlite`).
 //!
 //! Creates a single `output.db` file in the configured output directory with
-//! two tables: `agent_snapshots` and `tick_summaries`.
+//! eight tables: `agent_snapshots`, `tick_summaries`, `contacts`, `trips`,
+//! `routes`, `od_matrix`, `link_volumes`, and the key/value table `run_info`
+//! (see [`SqliteWriter::set_run_config`]).  A route's `edges` column holds
+//! its edge ids as JSON array text, so `json_each` can search it.
+//!
+//! Rows are inserted in transactions of [`SqliteOptions::batch_size`] rows,
+//! which may span several write calls; [`SqliteWriter::new_with`] also sets
+//! the statement cache, page-level pragmas, and the index and table layout
+//! options.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use dt_core::SimConfig;
 use rusqlite::Connection;
 
-use crate::{AgentSnapshotRow, OutputResult, TickSummaryRow};
+use rusqlite::types::Value;
+
+use crate::columns::{self, cell};
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnType, ColumnValue, ContactRow, LinkVolumeRow, OdRow,
+    OutputError, OutputResult, RouteRow, TickSummaryRow, TripRow,
+};
 use crate::writer::OutputWriter;
 
+// ── Options ───────────────────────────────────────────────────────────────────
+
+/// `PRAGMA synchronous` level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteSynchronous {
+    Off,
+    Normal,
+    Full,
+    Extra,
+}
+
+impl SqliteSynchronous {
+    fn as_str(self) -> &'static str {
+        match self {
+            SqliteSynchronous::Off    => "OFF",
+            SqliteSynchronous::Normal => "NORMAL",
+            SqliteSynchronous::Full   => "FULL",
+            SqliteSynchronous::Extra  => "EXTRA",
+        }
+    }
+}
+
+/// Tuning for [`SqliteWriter::new_with`].
+#[derive(Debug, Clone)]
+pub struct SqliteOptions {
+    /// Rows per transaction.  A transaction stays open across write calls
+    /// until this many rows are pending (or `finish()`); `0` commits at the
+    /// end of every call.  Default: 100 000.
+    pub batch_size: usize,
+
+    /// Prepared statements kept for reuse across calls; `0` re-prepares
+    /// every statement on each call.  Default: 16.
+    pub statement_cache: usize,
+
+    /// `PRAGMA synchronous`.  The database is always in WAL mode.
+    /// Default: `Normal`.
+    pub synchronous: SqliteSynchronous,
+
+    /// `PRAGMA cache_size`: pages if positive, KiB if negative.
+    /// Default: -65 536 (64 MiB).
+    pub cache_size: i64,
+
+    /// `PRAGMA page_size` in bytes, a power of two from 512 to 65 536.
+    /// Only applies when the database file is created.  Default: 4096.
+    pub page_size: u32,
+
+    /// Create query indexes in `finish()`, after all rows are written:
+    /// `agent_snapshots (agent_id, tick)`, `agent_snapshots (tick)`,
+    /// `contacts (tick)`, and `trips (agent, depart_tick)`.  Default: `false`.
+    pub create_indexes: bool,
+
+    /// Create `agent_snapshots` as a `WITHOUT ROWID` table keyed by
+    /// `(agent_id, tick)`, which stores rows in key order and makes
+    /// per-agent lookups cheap.  A second snapshot of the same agent at the
+    /// same tick is then an error.  Only applies when the table is created.
+    /// Default: `false`.
+    pub without_rowid: bool,
+}
+
+impl Default for SqliteOptions {
+    fn default() -> Self {
+        Self {
+            batch_size:      100_000,
+            statement_cache: 16,
+            synchronous:     SqliteSynchronous::Normal,
+            cache_size:      -65_536,
+            page_size:       4096,
+            create_indexes:  false,
+            without_rowid:   false,
+        }
+    }
+}
+
+// ── SqliteWriter ──────────────────────────────────────────────────────────────
+
 /// Writes simulation output to an SQLite database.
+///
+/// Rows still pending in the open transaction are committed by `finish()`
+/// or, failing that, when the writer is dropped.
 pub struct SqliteWriter {
-    conn:     Connection,
-    finished: bool,
+    conn:        Connection,
+    dir:         PathBuf,
+    batch_size:  usize,
+    /// Create indexes in `finish()`.
+    indexes:     bool,
+    /// `agent_snapshots` is keyed by `(agent_id, tick)` already.
+    keyed_snaps: bool,
+    /// Rows inserted in the open transaction.
+    pending:     usize,
+    in_tx:       bool,
+    /// Declared extra snapshot columns.
+    extra:       Vec<ColumnSpec>,
+    /// `INSERT` statement for `agent_snapshots`, including extra columns.
+    snap_insert: String,
+    /// Whether any snapshot row has been written (columns are then fixed).
+    snap_rows:   bool,
+    finished:    bool,
 }
 
 impl SqliteWriter {
-    /// Open (or create) `output.db` in `dir` and initialise the schema.
+    /// Open (or create) `output.db` in `dir` with default
+    /// [`SqliteOptions`] and initialise the schema.
     pub fn new(dir: &Path) -> OutputResult<Self> {
+        Self::new_with(dir, SqliteOptions::default())
+    }
+
+    /// Like [`new`][Self::new], with explicit batching and pragmas.
+    pub fn new_with(dir: &Path, options: SqliteOptions) -> OutputResult<Self> {
         let conn = Connection::open(dir.join("output.db"))?;
+        conn.set_prepared_statement_cache_capacity(options.statement_cache);
 
-        conn.execute_batch(
-            "PRAGMA journal_mode = WAL;
-             PRAGMA synchronous  = NORMAL;
-             CREATE TABLE IF NOT EXISTS agent_snapshots (
+        // page_size must precede WAL mode and the first table.
+        conn.execute_batch(&format!(
+            "PRAGMA page_size    = {};
+             PRAGMA journal_mode = WAL;
+             PRAGMA synchronous  = {};
+             PRAGMA cache_size   = {};",
+            options.page_size,
+            options.synchronous.as_str(),
+            options.cache_size,
+        ))?;
+
+        let (snap_key, snap_layout) = if options.without_rowid {
+            (",\n                 PRIMARY KEY (agent_id, tick)", " WITHOUT ROWID")
+        } else {
+            ("", "")
+        };
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS agent_snapshots (
                  agent_id         INTEGER NOT NULL,
                  tick             INTEGER NOT NULL,
                  departure_node   INTEGER NOT NULL,
                  in_transit       INTEGER NOT NULL,
-                 destination_node INTEGER NOT NULL
+                 destination_node INTEGER NOT NULL,
+                 lat              REAL,
+                 lon              REAL,
+                 departure_tick   INTEGER,
+                 arrival_tick     INTEGER{snap_key}
+             ){snap_layout};"
+        ))?;
+        let keyed_snaps = conn.query_row(
+            "SELECT count(*) FROM pragma_index_list('agent_snapshots') WHERE origin = 'pk'",
+            [],
+            |r| r.get::<_, i64>(0),
+        )? > 0;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS tick_summaries (
+                 tick               INTEGER PRIMARY KEY,
+                 unix_time_secs     INTEGER NOT NULL,
+                 woken_agents       INTEGER NOT NULL,
+                 in_transit         INTEGER NOT NULL,
+                 arrivals           INTEGER NOT NULL,
+                 departures         INTEGER NOT NULL,
+                 travel_intents     INTEGER NOT NULL,
+                 wake_intents       INTEGER NOT NULL,
+                 messages_sent      INTEGER NOT NULL,
+                 metric_intents     INTEGER NOT NULL,
+                 messages_delivered INTEGER NOT NULL,
+                 contacts           INTEGER NOT NULL,
+                 routing_failures   INTEGER NOT NULL,
+                 behavior_panics    INTEGER NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS contacts (
+                 tick    INTEGER NOT NULL,
+                 agent_a INTEGER NOT NULL,
+                 agent_b INTEGER NOT NULL,
+                 node    INTEGER NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS trips (
+                 agent       INTEGER NOT NULL,
+                 depart_tick INTEGER NOT NULL,
+                 arrive_tick INTEGER NOT NULL,
+                 from_node   INTEGER NOT NULL,
+                 to_node     INTEGER NOT NULL,
+                 mode        TEXT    NOT NULL,
+                 travel_secs REAL    NOT NULL,
+                 distance_m  REAL    NOT NULL
              );
-             CREATE TABLE IF NOT EXISTS tick_summaries (
-                 tick           INTEGER PRIMARY KEY,
-                 unix_time_secs INTEGER NOT NULL,
-                 woken_agents   INTEGER NOT NULL
+             CREATE TABLE IF NOT EXISTS routes (
+                 agent       INTEGER NOT NULL,
+                 depart_tick INTEGER NOT NULL,
+                 from_node   INTEGER NOT NULL,
+                 to_node     INTEGER NOT NULL,
+                 mode        TEXT    NOT NULL,
+                 edges       TEXT    NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS od_matrix (
+                 origin_zone INTEGER NOT NULL,
+                 dest_zone   INTEGER NOT NULL,
+                 hour        INTEGER NOT NULL,
+                 mode        TEXT    NOT NULL,
+                 trips       INTEGER NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS link_volumes (
+                 tick     INTEGER NOT NULL,
+                 edge_id  INTEGER NOT NULL,
+                 vehicles INTEGER NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS run_info (
+                 key   TEXT PRIMARY KEY,
+                 value TEXT NOT NULL
              );",
         )?;
 
-        Ok(Self { conn, finished: false })
+        // Databases created before the extended summary get the missing
+        // counters, zero for earlier rows.
+        let existing = table_columns(&conn, "tick_summaries")?;
+        for name in TickSummaryRow::COUNT_COLUMNS.iter().filter(|&&c| !existing.iter().any(|e| e == c)) {
+            conn.execute(
+                &format!("ALTER TABLE tick_summaries ADD COLUMN {name} INTEGER NOT NULL DEFAULT 0"),
+                [],
+            )?;
+        }
+        // ... and snapshots the journey ticks, null for earlier rows.
+        let existing = table_columns(&conn, "agent_snapshots")?;
+        for name in ["departure_tick", "arrival_tick"].iter().filter(|&&c| !existing.iter().any(|e| e == c)) {
+            conn.execute(&format!("ALTER TABLE agent_snapshots ADD COLUMN {name} INTEGER"), [])?;
+        }
+
+        Ok(Self {
+            conn,
+            dir:         dir.to_path_buf(),
+            batch_size:  options.batch_size,
+            indexes:     options.create_indexes,
+            keyed_snaps,
+            pending:     0,
+            in_tx:       false,
+            extra:       Vec::new(),
+            snap_insert: snapshot_insert(&[]),
+            snap_rows:   false,
+            finished:    false,
+        })
+    }
+}
+
+impl SqliteWriter {
+    /// Set `key` in the `run_info` table, replacing any earlier value.
+    pub fn set_run_info(&mut self, key: &str, value: impl ToString) -> OutputResult<()> {
+        self.conn
+            .prepare_cached("INSERT OR REPLACE INTO run_info (key, value) VALUES (?1, ?2)")?
+            .execute(rusqlite::params![key, value.to_string()])?;
+        Ok(())
+    }
+
+    /// Record `config` and the `dt-output` version in `run_info`, one key
+    /// per top-level scalar `SimConfig` field (the sections and extensions
+    /// are in `run_manifest.json`).  `num_threads` is omitted when unset.
+    pub fn set_run_config(&mut self, config: &SimConfig) -> OutputResult<()> {
+        self.set_run_info("dt_output_version",     env!("CARGO_PKG_VERSION"))?;
+        self.set_run_info("start_unix_secs",       config.start_unix_secs)?;
+        self.set_run_info("tick_duration_secs",    config.tick_duration_secs)?;
+        self.set_run_info("total_ticks",           config.total_ticks)?;
+        self.set_run_info("seed",                  config.seed)?;
+        self.set_run_info("output_interval_ticks", config.output_interval_ticks)?;
+        if let Some(threads) = config.num_threads {
+            self.set_run_info("num_threads", threads)?;
+        }
+        Ok(())
+    }
+
+    /// Create the query indexes (see [`SqliteOptions::create_indexes`]).
+    fn create_indexes(&self) -> OutputResult<()> {
+        if !self.keyed_snaps {
+            self.conn.execute_batch(
+                "CREATE INDEX IF NOT EXISTS agent_snapshots_agent_tick ON agent_snapshots (agent_id, tick);",
+            )?;
+        }
+        self.conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS agent_snapshots_tick ON agent_snapshots (tick);
+             CREATE INDEX IF NOT EXISTS contacts_tick ON contacts (tick);
+             CREATE INDEX IF NOT EXISTS trips_agent_depart ON trips (agent, depart_tick);",
+        )?;
+        Ok(())
+    }
+
+    /// Open a transaction unless one is already open.
+    fn begin(&mut self) -> OutputResult<()> {
+        if !self.in_tx {
+            self.conn.execute_batch("BEGIN")?;
+            self.in_tx = true;
+        }
+        Ok(())
+    }
+
+    /// Count `rows` towards the batch and commit if it is full.
+    fn inserted(&mut self, rows: usize) -> OutputResult<()> {
+        self.pending += rows;
+        if self.pending >= self.batch_size {
+            self.commit()?;
+        }
+        Ok(())
+    }
+
+    fn commit(&mut self) -> OutputResult<()> {
+        if self.in_tx {
+            self.conn.execute_batch("COMMIT")?;
+            self.in_tx = false;
+            self.pending = 0;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SqliteWriter {
+    fn drop(&mut self) {
+        let _ = self.commit();
+    }
+}
+
+/// Column names of `table`.
+fn table_columns(conn: &Connection, table: &str) -> OutputResult<Vec<String>> {
+    Ok(conn
+        .prepare(&format!("SELECT name FROM pragma_table_info('{table}')"))?
+        .query_map([], |r| r.get(0))?
+        .collect::<Result<_, _>>()?)
+}
+
+/// `INSERT` statement for `agent_snapshots` with `extra` columns appended.
+fn snapshot_insert(extra: &[ColumnSpec]) -> String {
+    let mut names = String::from(
+        "agent_id, tick, departure_node, in_transit, destination_node, lat, lon, departure_tick, arrival_tick",
+    );
+    for col in extra {
+        names.push_str(", ");
+        names.push_str(&quote(&col.name));
+    }
+    let params: Vec<String> = (1..=9 + extra.len()).map(|i| format!("?{i}")).collect();
+    format!("INSERT INTO agent_snapshots ({names}) VALUES ({})", params.join(", "))
+}
+
+/// Quote an SQL identifier.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn sql_value(value: &ColumnValue) -> Value {
+    match value {
+        ColumnValue::Null     => Value::Null,
+        ColumnValue::Int(v)   => Value::Integer(*v),
+        ColumnValue::Float(v) => Value::Real(*v),
+        ColumnValue::Bool(v)  => Value::Integer(*v as i64),
+        ColumnValue::Text(s)  => Value::Text(s.clone()),
     }
 }
 
 impl OutputWriter for SqliteWriter {
-    fn write_snapshots(&mut self, rows: &[AgentSnapshotRow]) -> OutputResult<()> {
+    /// Adds any column not yet in the `agent_snapshots` table; columns are
+    /// never dropped, so an existing database keeps its earlier extras.
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()> {
+        columns::validate(columns)?;
+        if self.snap_rows {
+            return Err(OutputError::Column(
+                "cannot change snapshot columns after snapshots were written".into(),
+            ));
+        }
+        let existing: Vec<String> = self.conn
+            .prepare("SELECT name FROM pragma_table_info('agent_snapshots')")?
+            .query_map([], |r| r.get(0))?
+            .collect::<Result<_, _>>()?;
+        for col in columns.iter().filter(|c| !existing.contains(&c.name)) {
+            let ty = match col.ty {
+                ColumnType::Int | ColumnType::Bool => "INTEGER",
+                ColumnType::Float                  => "REAL",
+                ColumnType::Text                   => "TEXT",
+            };
+            self.conn.execute(
+                &format!("ALTER TABLE agent_snapshots ADD COLUMN {} {ty}", quote(&col.name)),
+                [],
+            )?;
+        }
+        self.extra = columns.to_vec();
+        self.snap_insert = snapshot_insert(columns);
+        Ok(())
+    }
+
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:    &[AgentSnapshotRow],
+        columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        self.begin()?;
+        {
+            let mut stmt = self.conn.prepare_cached(&self.snap_insert)?;
+            let mut values = Vec::with_capacity(9 + self.extra.len());
+            for (i, row) in rows.iter().enumerate() {
+                values.clear();
+                values.extend([
+                    Value::Integer(row.agent_id.into()),
+                    Value::Integer(row.tick as i64),
+                    Value::Integer(row.departure_node.into()),
+                    Value::Integer(row.in_transit as i64),
+                    Value::Integer(row.destination_node.into()),
+                    row.lat.map_or(Value::Null, |v| Value::Real(v.into())),
+                    row.lon.map_or(Value::Null, |v| Value::Real(v.into())),
+                    row.departure_tick.map_or(Value::Null, |t| Value::Integer(t as i64)),
+                    row.arrival_tick.map_or(Value::Null, |t| Value::Integer(t as i64)),
+                ]);
+                values.extend((0..self.extra.len()).map(|c| sql_value(cell(columns, c, i))));
+                stmt.execute(rusqlite::params_from_iter(&values))?;
+            }
+        }
+        self.snap_rows = true;
+        self.inserted(rows.len())
+    }
+
+    fn write_tick_summary(&mut self, row: &TickSummaryRow) -> OutputResult<()> {
+        self.begin()?;
+        self.conn
+            .prepare_cached(
+                "INSERT INTO tick_summaries (tick, unix_time_secs, woken_agents, in_transit, arrivals, \
+                 departures, travel_intents, wake_intents, messages_sent, metric_intents, \
+                 messages_delivered, contacts, routing_failures, behavior_panics) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            )?
+            .execute(rusqlite::params![
+                row.tick,
+                row.unix_time_secs,
+                row.woken_agents,
+                row.in_transit,
+                row.arrivals,
+                row.departures,
+                row.travel_intents,
+                row.wake_intents,
+                row.messages_sent,
+                row.metric_intents,
+                row.messages_delivered,
+                row.contacts,
+                row.routing_failures,
+                row.behavior_panics,
+            ])?;
+        self.inserted(1)
+    }
+
+    fn write_contacts(&mut self, rows: &[ContactRow]) -> OutputResult<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        self.begin()?;
+        {
+            let mut stmt = self.conn.prepare_cached(
+                "INSERT INTO contacts (tick, agent_a, agent_b, node) \
+                 VALUES (?1, ?2, ?3, ?4)",
+            )?;
+            for row in rows {
+                stmt.execute(rusqlite::params![row.tick, row.agent_a, row.agent_b, row.node])?;
+            }
+        }
+        self.inserted(rows.len())
+    }
+
+    fn write_trips(&mut self, rows: &[TripRow]) -> OutputResult<()> {
         if rows.is_empty() {
             return Ok(());
         }
-        let tx = self.conn.unchecked_transaction()?;
+        self.begin()?;
+        {
+            let mut stmt = self.conn.prepare_cached(
+                "INSERT INTO trips \
+                 (agent, depart_tick, arrive_tick, from_node, to_node, mode, travel_secs, distance_m) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            )?;
+            for row in rows {
+                stmt.execute(rusqlite::params![
+                    row.agent,
+                    row.depart_tick,
+                    row.arrive_tick,
+                    row.from,
+                    row.to,
+                    row.mode.as_str(),
+                    f64::from(row.travel_secs),
+                    f64::from(row.distance_m),
+                ])?;
+            }
+        }
+        self.inserted(rows.len())
+    }
+
+    fn write_routes(&mut self, rows: &[RouteRow]) -> OutputResult<()> {
+        self.begin()?;
+        {
+            let mut stmt = self.conn.prepare_cached(
+                "INSERT INTO routes (agent, depart_tick, from_node, to_node, mode, edges) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for row in rows {
+                stmt.execute(rusqlite::params![
+                    row.agent,
+                    row.depart_tick,
+                    row.from,
+                    row.to,
+                    row.mode.as_str(),
+                    row.edge_list(),
+                ])?;
+            }
+        }
+        self.inserted(rows.len())
+    }
+
+    fn write_od_matrix(&mut self, rows: &[OdRow]) -> OutputResult<()> {
+        self.begin()?;
         {
-            let mut stmt = tx.prepare_cached(
-                "INSERT INTO agent_snapshots \
-                 (agent_id, tick, departure_node, in_transit, destination_node) \
+            let mut stmt = self.conn.prepare_cached(
+                "INSERT INTO od_matrix (origin_zone, dest_zone, hour, mode, trips) \
                  VALUES (?1, ?2, ?3, ?4, ?5)",
             )?;
             for row in rows {
                 stmt.execute(rusqlite::params![
-                    row.agent_id,
-                    row.tick,
-                    row.departure_node,
-                    row.in_transit as i64,
-                    row.destination_node,
+                    row.origin_zone,
+                    row.dest_zone,
+                    row.hour,
+                    row.mode.as_str(),
+                    row.trips,
                 ])?;
             }
         }
-        tx.commit()?;
-        Ok(())
+        self.inserted(rows.len())
     }
 
-    fn write_tick_summary(&mut self, row: &TickSummaryRow) -> OutputResult<()> {
-        self.conn.execute(
-            "INSERT INTO tick_summaries (tick, unix_time_secs, woken_agents) \
-             VALUES (?1, ?2, ?3)",
-            rusqlite::params![row.tick, row.unix_time_secs, row.woken_agents],
-        )?;
-        Ok(())
+    fn write_link_volumes(&mut self, rows: &[LinkVolumeRow]) -> OutputResult<()> {
+        self.begin()?;
+        {
+            let mut stmt = self.conn.prepare_cached(
+                "INSERT INTO link_volumes (tick, edge_id, vehicles) VALUES (?1, ?2, ?3)",
+            )?;
+            for row in rows {
+                stmt.execute(rusqlite::params![row.tick, row.edge, row.vehicles])?;
+            }
+        }
+        self.inserted(rows.len())
+    }
+
+    fn output_dir(&self) -> Option<&Path> {
+        Some(&self.dir)
     }
 
     fn finish(&mut self) -> OutputResult<()> {
@@ -82,6 +569,10 @@ impl OutputWriter for SqliteWriter {
             return Ok(());
         }
         self.finished = true;
+        self.commit()?;
+        if self.indexes {
+            self.create_indexes()?;
+        }
         self.conn
             .execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")?;
         Ok(())
@@ -0,0 +1,196 @@
+//! Object-store destinations (feature `object-store`).
+//!
+//! [`ObjectDir`] names a prefix in S3, Google Cloud Storage, Azure Blob
+//! Storage, or any other [`ObjectStore`], and stands in for the output
+//! directory of the CSV and Parquet writers:
+//!
+//! ```rust,ignore
+//! let dir = ObjectDir::parse("s3://sim-results/run42")?;
+//! let writer = ParquetWriter::to_object_store(dir)?;
+//! let mut obs = SimOutputObserver::new(writer, &config);
+//! ```
+//!
+//! Each file is streamed as a multipart upload while the run writes it, so
+//! no local disk is needed and memory use stays bounded however large the
+//! file grows.  A file's object appears once the writer's `finish()`
+//! completes its upload; an upload left unfinished is aborted.  The run
+//! manifest and summary are uploaded next to the tables.
+//!
+//! Uploads run on a small runtime owned by the destination, so the writers
+//! can be used from synchronous code and from `Sim::run_async` alike.
+
+use std::future::Future;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutPayload, WriteMultipart};
+use tokio::runtime::Runtime;
+
+use crate::OutputResult;
+
+/// Parts buffered or uploading per file before `write` waits.  Parts are
+/// 10 MiB, as [`WriteMultipart`] chooses.
+const PARTS_IN_FLIGHT: usize = 4;
+
+/// Environment variable prefixes read for store credentials and settings.
+const ENV_PREFIXES: [&str; 3] = ["AWS_", "GOOGLE_", "AZURE_"];
+
+/// A runtime that shuts down without blocking, so it may be dropped inside
+/// another runtime.
+struct UploadRuntime(Option<Runtime>);
+
+impl Drop for UploadRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// A prefix in an object store that writers create their files under.
+#[derive(Clone)]
+pub struct ObjectDir {
+    store:   Arc<dyn ObjectStore>,
+    prefix:  ObjectPath,
+    runtime: Arc<UploadRuntime>,
+}
+
+impl ObjectDir {
+    /// The store and prefix named by `url`: `s3://bucket/prefix`,
+    /// `gs://bucket/prefix`, `az://container/prefix` (and the other Azure
+    /// URL forms), `file:///path`, or `memory:///`.
+    ///
+    /// Credentials, region, and endpoint come from the `AWS_*`, `GOOGLE_*`,
+    /// and `AZURE_*` environment variables, as for the stores' own
+    /// `from_env` builders.
+    pub fn parse(url: &str) -> OutputResult<Self> {
+        Self::parse_with_options(url, std::iter::empty::<(String, String)>())
+    }
+
+    /// Like [`parse`][Self::parse], with store settings such as
+    /// `("aws_region", "eu-central-1")` overriding the environment.  Keys
+    /// the store does not know are ignored.
+    pub fn parse_with_options<K, V>(
+        url:     &str,
+        options: impl IntoIterator<Item = (K, V)>,
+    ) -> OutputResult<Self>
+    where
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let env = std::env::vars()
+            .filter(|(key, _)| ENV_PREFIXES.iter().any(|prefix| key.starts_with(prefix)))
+            .map(|(key, value)| (key.to_ascii_lowercase(), value));
+        let options = options.into_iter().map(|(key, value)| (key.as_ref().to_owned(), value.into()));
+        let (store, prefix) = object_store::parse_url_opts(&url::Url::parse(url)?, env.chain(options))?;
+        Self::with_runtime(Arc::from(store), prefix)
+    }
+
+    /// Files under `prefix` in an already configured `store`.
+    pub fn new(store: Arc<dyn ObjectStore>, prefix: &str) -> OutputResult<Self> {
+        Self::with_runtime(store, ObjectPath::parse(prefix).map_err(object_store::Error::from)?)
+    }
+
+    fn with_runtime(store: Arc<dyn ObjectStore>, prefix: ObjectPath) -> OutputResult<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("dt-output-upload")
+            .enable_all()
+            .build()?;
+        Ok(Self { store, prefix, runtime: Arc::new(UploadRuntime(Some(runtime))) })
+    }
+
+    /// The underlying store.
+    pub fn store(&self) -> &Arc<dyn ObjectStore> {
+        &self.store
+    }
+
+    /// Location of file `name` in the store.
+    pub fn path(&self, name: &str) -> ObjectPath {
+        self.prefix.child(name)
+    }
+
+    /// Download file `name`.
+    pub fn read(&self, name: &str) -> OutputResult<Vec<u8>> {
+        let (store, path) = (Arc::clone(&self.store), self.path(name));
+        let bytes = self.block_on(async move { store.get(&path).await?.bytes().await })??;
+        Ok(bytes.to_vec())
+    }
+
+    /// Upload `contents` as file `name` in one request, replacing any
+    /// earlier object.
+    pub(crate) fn put(&self, name: &str, contents: &[u8]) -> io::Result<()> {
+        let (store, path) = (Arc::clone(&self.store), self.path(name));
+        let payload = PutPayload::from(contents.to_vec());
+        self.block_on(async move { store.put(&path, payload).await })?.map_err(io::Error::other)?;
+        Ok(())
+    }
+
+    /// Start a streaming upload of file `name`.
+    pub(crate) fn create(&self, name: &str) -> io::Result<ObjectFile> {
+        let (store, path) = (Arc::clone(&self.store), self.path(name));
+        let upload = self
+            .block_on(async move { store.put_multipart(&path).await })?
+            .map_err(io::Error::other)?;
+        Ok(ObjectFile { upload: Some(WriteMultipart::new(upload)), dir: self.clone() })
+    }
+
+    fn runtime(&self) -> &Runtime {
+        self.runtime.0.as_ref().expect("runtime is only taken on drop")
+    }
+
+    /// Run `future` on the upload runtime and wait for it.  Works from any
+    /// thread, including one driven by another runtime.
+    fn block_on<T>(&self, future: impl Future<Output = T> + Send + 'static) -> io::Result<T>
+    where
+        T: Send + 'static,
+    {
+        futures::executor::block_on(self.runtime().spawn(future)).map_err(io::Error::other)
+    }
+}
+
+/// One file being uploaded by [`ObjectDir::create`].
+pub(crate) struct ObjectFile {
+    /// `None` once finished.
+    upload: Option<WriteMultipart>,
+    dir:    ObjectDir,
+}
+
+impl ObjectFile {
+    /// Upload the remaining data and complete the object.
+    pub(crate) fn finish(mut self) -> io::Result<()> {
+        let Some(upload) = self.upload.take() else {
+            return Ok(());
+        };
+        self.dir.block_on(upload.finish())?.map_err(io::Error::other)?;
+        Ok(())
+    }
+}
+
+impl Write for ObjectFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let Some(upload) = &mut self.upload else {
+            return Err(io::Error::other("object already finished"));
+        };
+        // Full parts are uploaded by tasks spawned on the upload runtime.
+        let _runtime = self.dir.runtime().enter();
+        upload.write(buf);
+        futures::executor::block_on(upload.wait_for_capacity(PARTS_IN_FLIGHT)).map_err(io::Error::other)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        // Data is uploaded in whole parts; the rest goes up on `finish`.
+        Ok(())
+    }
+}
+
+impl Drop for ObjectFile {
+    fn drop(&mut self) {
+        if let Some(upload) = self.upload.take() {
+            // Discard the uploaded parts rather than leave them billed.
+            let _ = self.dir.block_on(upload.abort());
+        }
+    }
+}
@@ -0,0 +1,343 @@
+//! Message broker backend (features `kafka` and `mqtt`).
+//!
+//! [`StreamWriter`] publishes every row as one JSON message — the same
+//! objects the JSON Lines backend writes — to one topic per table:
+//! - `{prefix}agent_snapshots` (keyed by `agent_id`)
+//! - `{prefix}tick_summaries` (keyed by `tick`)
+//! - `{prefix}contacts` (keyed by `agent_a`)
+//! - `{prefix}trips` (keyed by `agent`)
+//! - `{prefix}routes` (keyed by `agent`)
+//! - `{prefix}od_matrix` (keyed by `origin_zone`)
+//! - `{prefix}link_volumes` (keyed by `edge_id`)
+//!
+//! Messages go out through a [`Publisher`]:
+//! - [`KafkaPublisher`] (feature `kafka`) produces through librdkafka, using
+//!   the key to pick the partition, so each agent's rows stay in order.
+//! - [`MqttPublisher`] (feature `mqtt`) publishes to an MQTT broker; keys are
+//!   not used.  MQTT topics are hierarchical, so a prefix such as `"dt/run42/"`
+//!   gives `dt/run42/agent_snapshots`.
+//!
+//! ```rust,ignore
+//! let publisher = KafkaPublisher::new("kafka-1:9092,kafka-2:9092")?;
+//! let writer = StreamWriter::new(publisher).topic_prefix("twin.");
+//! let mut obs = SimOutputObserver::new(writer, &config);
+//! ```
+
+use serde_json::Value;
+
+use crate::columns;
+use crate::json;
+use crate::writer::OutputWriter;
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnValue, ContactRow, LinkVolumeRow, OdRow, OutputError,
+    OutputResult, RouteRow, TickSummaryRow, TripRow,
+};
+
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaPublisher;
+
+#[cfg(feature = "mqtt")]
+pub use mqtt::MqttPublisher;
+
+// ── Publisher ─────────────────────────────────────────────────────────────────
+
+/// A message broker connection used by [`StreamWriter`].
+pub trait Publisher {
+    /// Queue `payload` for delivery to `topic`.  `key` groups related
+    /// messages; brokers without keys ignore it.
+    fn publish(&mut self, topic: &str, key: &str, payload: Vec<u8>) -> OutputResult<()>;
+
+    /// Deliver every queued message and close the connection.
+    fn close(&mut self) -> OutputResult<()>;
+}
+
+// ── StreamWriter ──────────────────────────────────────────────────────────────
+
+/// Publishes simulation output to a message broker, one message per row.
+pub struct StreamWriter<P: Publisher> {
+    publisher: P,
+    prefix:    String,
+    /// Declared extra snapshot columns.
+    extra:     Vec<ColumnSpec>,
+    /// Whether any snapshot row has been published (columns are then fixed).
+    snap_rows: bool,
+    closed:    bool,
+}
+
+impl<P: Publisher> StreamWriter<P> {
+    /// Publish through `publisher` to topics named after the tables.
+    pub fn new(publisher: P) -> Self {
+        Self { publisher, prefix: String::new(), extra: Vec::new(), snap_rows: false, closed: false }
+    }
+
+    /// Prepend `prefix` to every topic name.
+    pub fn topic_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Unwrap the publisher.
+    pub fn into_publisher(self) -> P {
+        self.publisher
+    }
+
+    fn send(&mut self, table: &str, key: impl ToString, value: &Value) -> OutputResult<()> {
+        let topic = format!("{}{table}", self.prefix);
+        self.publisher.publish(&topic, &key.to_string(), value.to_string().into_bytes())
+    }
+}
+
+impl<P: Publisher> OutputWriter for StreamWriter<P> {
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()> {
+        columns::validate(columns)?;
+        if self.snap_rows {
+            return Err(OutputError::Column(
+                "cannot change snapshot columns after snapshots were written".into(),
+            ));
+        }
+        self.extra = columns.to_vec();
+        Ok(())
+    }
+
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:    &[AgentSnapshotRow],
+        columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()> {
+        for (i, row) in rows.iter().enumerate() {
+            let value = json::snapshot(row, &self.extra, columns, i);
+            self.send("agent_snapshots", row.agent_id, &value)?;
+        }
+        self.snap_rows |= !rows.is_empty();
+        Ok(())
+    }
+
+    fn write_tick_summary(&mut self, row: &TickSummaryRow) -> OutputResult<()> {
+        self.send("tick_summaries", row.tick, &json::summary(row))
+    }
+
+    fn write_contacts(&mut self, rows: &[ContactRow]) -> OutputResult<()> {
+        for row in rows {
+            self.send("contacts", row.agent_a, &json::contact(row))?;
+        }
+        Ok(())
+    }
+
+    fn write_trips(&mut self, rows: &[TripRow]) -> OutputResult<()> {
+        for row in rows {
+            self.send("trips", row.agent, &json::trip(row))?;
+        }
+        Ok(())
+    }
+
+    fn write_routes(&mut self, rows: &[RouteRow]) -> OutputResult<()> {
+        for row in rows {
+            self.send("routes", row.agent, &json::route(row))?;
+        }
+        Ok(())
+    }
+
+    fn write_od_matrix(&mut self, rows: &[OdRow]) -> OutputResult<()> {
+        for row in rows {
+            self.send("od_matrix", row.origin_zone, &json::od(row))?;
+        }
+        Ok(())
+    }
+
+    fn write_link_volumes(&mut self, rows: &[LinkVolumeRow]) -> OutputResult<()> {
+        for row in rows {
+            self.send("link_volumes", row.edge, &json::link_volume(row))?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> OutputResult<()> {
+        if !self.closed {
+            self.closed = true;
+            self.publisher.close()?;
+        }
+        Ok(())
+    }
+}
+
+// ── KafkaPublisher ────────────────────────────────────────────────────────────
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use rdkafka::ClientContext;
+    use rdkafka::config::ClientConfig;
+    use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+    use rdkafka::producer::{BaseProducer, BaseRecord, DeliveryResult, Producer, ProducerContext};
+
+    use super::Publisher;
+    use crate::OutputResult;
+
+    /// Keeps the first failed delivery.
+    #[derive(Default)]
+    struct Deliveries {
+        failed: Mutex<Option<KafkaError>>,
+    }
+
+    impl ClientContext for Deliveries {}
+
+    impl ProducerContext for Deliveries {
+        type DeliveryOpaque = ();
+
+        fn delivery(&self, result: &DeliveryResult<'_>, _: ()) {
+            if let Err((e, _)) = result
+                && let Ok(mut failed) = self.failed.lock()
+            {
+                failed.get_or_insert_with(|| e.clone());
+            }
+        }
+    }
+
+    /// Produces to Kafka through librdkafka.
+    ///
+    /// Messages are batched by librdkafka in the background; a failed
+    /// delivery is reported by the next `publish` or by `close`, which waits
+    /// up to the close timeout for outstanding messages.
+    pub struct KafkaPublisher {
+        producer:      BaseProducer<Deliveries>,
+        close_timeout: Duration,
+    }
+
+    impl KafkaPublisher {
+        /// Connect to the brokers in `bootstrap_servers` (`host:port,…`).
+        pub fn new(bootstrap_servers: &str) -> OutputResult<Self> {
+            Self::from_config(ClientConfig::new().set("bootstrap.servers", bootstrap_servers))
+        }
+
+        /// Connect with a full librdkafka configuration (security,
+        /// compression, `linger.ms`, …).
+        pub fn from_config(config: &ClientConfig) -> OutputResult<Self> {
+            Ok(Self {
+                producer:      config.create_with_context(Deliveries::default())?,
+                close_timeout: Duration::from_secs(30),
+            })
+        }
+
+        /// How long `close` waits for outstanding messages.  Default: 30 s.
+        pub fn close_timeout(mut self, timeout: Duration) -> Self {
+            self.close_timeout = timeout;
+            self
+        }
+
+        fn take_failure(&self) -> OutputResult<()> {
+            match self.producer.context().failed.lock().ok().and_then(|mut f| f.take()) {
+                Some(e) => Err(e.into()),
+                None => Ok(()),
+            }
+        }
+    }
+
+    impl Publisher for KafkaPublisher {
+        fn publish(&mut self, topic: &str, key: &str, payload: Vec<u8>) -> OutputResult<()> {
+            let mut record = BaseRecord::to(topic).key(key).payload(&payload);
+            loop {
+                match self.producer.send(record) {
+                    Ok(()) => break,
+                    // The local queue is full: serve delivery reports, retry.
+                    Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), r)) => {
+                        record = r;
+                        self.producer.poll(Duration::from_millis(100));
+                    }
+                    Err((e, _)) => return Err(e.into()),
+                }
+            }
+            self.producer.poll(Duration::ZERO);
+            self.take_failure()
+        }
+
+        fn close(&mut self) -> OutputResult<()> {
+            let flushed = self.producer.flush(self.close_timeout);
+            self.take_failure()?;
+            Ok(flushed?)
+        }
+    }
+}
+
+// ── MqttPublisher ─────────────────────────────────────────────────────────────
+
+#[cfg(feature = "mqtt")]
+mod mqtt {
+    use std::thread::JoinHandle;
+
+    use rumqttc::{Client, Event, MqttOptions, Outgoing, QoS};
+
+    use super::Publisher;
+    use crate::{OutputError, OutputResult};
+
+    /// Capacity of the queue between `publish` and the network thread.
+    const QUEUE_CAP: usize = 1024;
+
+    /// Publishes to an MQTT broker.
+    ///
+    /// A background thread drives the connection; `publish` blocks only
+    /// while its queue is full.  `close` sends every queued message, then
+    /// disconnects.
+    pub struct MqttPublisher {
+        client:     Client,
+        qos:        QoS,
+        /// The connection thread, returning the error that ended it.
+        connection: Option<JoinHandle<Option<String>>>,
+    }
+
+    impl MqttPublisher {
+        /// Connect to the broker at `host:port` as `client_id`.
+        pub fn new(client_id: &str, host: &str, port: u16) -> Self {
+            Self::from_options(MqttOptions::new(client_id, host, port))
+        }
+
+        /// Connect with full client options (keep-alive, credentials, …).
+        pub fn from_options(options: MqttOptions) -> Self {
+            let (client, mut connection) = Client::new(options, QUEUE_CAP);
+            let connection = std::thread::spawn(move || {
+                for event in connection.iter() {
+                    match event {
+                        Ok(Event::Outgoing(Outgoing::Disconnect)) => return None,
+                        Ok(_) => {}
+                        Err(e) => return Some(e.to_string()),
+                    }
+                }
+                None
+            });
+            Self { client, qos: QoS::AtLeastOnce, connection: Some(connection) }
+        }
+
+        /// Quality of service for every message.  Default: at least once.
+        pub fn qos(mut self, qos: QoS) -> Self {
+            self.qos = qos;
+            self
+        }
+
+        /// Wait for the connection thread and return its error, if any.
+        fn join(&mut self) -> Option<String> {
+            let handle = self.connection.take()?;
+            handle.join().unwrap_or_else(|_| Some("MQTT connection thread panicked".into()))
+        }
+    }
+
+    impl Publisher for MqttPublisher {
+        fn publish(&mut self, topic: &str, _key: &str, payload: Vec<u8>) -> OutputResult<()> {
+            self.client.publish(topic, self.qos, false, payload).map_err(|e| {
+                // The queue closes when the connection thread exits.
+                OutputError::Mqtt(self.join().unwrap_or_else(|| e.to_string()))
+            })
+        }
+
+        fn close(&mut self) -> OutputResult<()> {
+            if self.connection.is_none() {
+                return Ok(());
+            }
+            let sent = self.client.disconnect();
+            match self.join() {
+                Some(e) => Err(OutputError::Mqtt(e)),
+                None => sent.map_err(|e| OutputError::Mqtt(e.to_string())),
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! `run_summary.json` — run-level aggregates.
+//!
+//! [`RunSummary`] accumulates the figures most analyses start from — trip
+//! counts, travel-time statistics per mode, distance travelled, the peak
+//! number of agents in transit, and contact totals — so they need not be
+//! re-derived from the raw tables.  Attach one to the output observer with
+//! [`SimOutputObserver::with_run_summary`][crate::SimOutputObserver::with_run_summary]
+//! and it is written next to the writer's files when the run ends.
+//! It is also a [`SimObserver`] in its own right, for runs that write no
+//! other output:
+//!
+//! ```text
+//! {
+//!   "final_tick": 24,
+//!   "ticks": 24,
+//!   "trips": 3,
+//!   "distance_m": 3300,
+//!   "departures": 3,
+//!   "routing_failures": 0,
+//!   "contacts": 14,
+//!   "peak_in_transit": { "tick": 8, "agents": 2 },
+//!   "modes": [
+//!     { "mode": "walk", "trips": 3, "distance_m": 3300,
+//!       "travel_secs": { "mean": 80, "p50": 80, "p90": 80, "p95": 80, "max": 80 } }
+//!   ]
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use dt_core::{Tick, TransportMode};
+use dt_mobility::Trip;
+use dt_sim::{SimObserver, TickStats};
+
+use crate::row::TripRow;
+use crate::OutputResult;
+
+/// Travel-time statistics of the trips made in one mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModeSummary {
+    pub mode:             TransportMode,
+    pub trips:            u64,
+    pub distance_m:       f64,
+    pub mean_travel_secs: f64,
+    /// Nearest-rank percentiles of the routed travel time.
+    pub p50_travel_secs:  f32,
+    pub p90_travel_secs:  f32,
+    pub p95_travel_secs:  f32,
+    pub max_travel_secs:  f32,
+}
+
+/// Travel times and distance of the trips made in one mode.
+#[derive(Default)]
+struct ModeTrips {
+    travel_secs: Vec<f32>,
+    distance_m:  f64,
+}
+
+/// Run-level aggregates over completed trips and tick statistics.
+#[derive(Default)]
+pub struct RunSummary {
+    modes:            HashMap<TransportMode, ModeTrips>,
+    ticks:            u64,
+    departures:       u64,
+    routing_failures: u64,
+    contacts:         u64,
+    /// (tick, agents) of the first tick with the most agents in transit.
+    peak_in_transit:  Option<(u64, u64)>,
+    final_tick:       Option<u64>,
+}
+
+/// Nearest-rank `p`-quantile of the non-empty ascending slice `sorted`.
+fn percentile(sorted: &[f32], p: f64) -> f32 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// JSON number for `value`; `null` if not finite.
+fn num(value: f64) -> String {
+    if value.is_finite() { value.to_string() } else { "null".to_owned() }
+}
+
+impl RunSummary {
+    /// An empty summary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count the completed trip `trip`.
+    pub fn record_trip(&mut self, trip: &TripRow) {
+        let mode = self.modes.entry(trip.mode).or_default();
+        mode.travel_secs.push(trip.travel_secs);
+        mode.distance_m += trip.distance_m as f64;
+    }
+
+    /// Add the statistics of `tick`.
+    pub fn record_tick(&mut self, tick: u64, stats: &TickStats) {
+        self.ticks += 1;
+        self.departures += stats.departures;
+        self.routing_failures += stats.routing_failures;
+        self.contacts += stats.contacts;
+        if self.peak_in_transit.is_none_or(|(_, peak)| stats.in_transit > peak) {
+            self.peak_in_transit = Some((tick, stats.in_transit));
+        }
+    }
+
+    /// Mark the run as ended at `final_tick`.
+    pub fn finish(&mut self, final_tick: u64) {
+        self.final_tick = Some(final_tick);
+    }
+
+    /// Total completed trips.
+    pub fn trips(&self) -> u64 {
+        self.modes.values().map(|m| m.travel_secs.len() as u64).sum()
+    }
+
+    /// Total routed distance of the completed trips in metres.
+    pub fn distance_m(&self) -> f64 {
+        self.modes.values().map(|m| m.distance_m).sum()
+    }
+
+    /// Journeys started.
+    pub fn departures(&self) -> u64 {
+        self.departures
+    }
+
+    /// Co-located pairs reported, one per side (as in the contacts table).
+    pub fn contacts(&self) -> u64 {
+        self.contacts
+    }
+
+    /// The first tick with the most agents in transit, and their number.
+    pub fn peak_in_transit(&self) -> Option<(u64, u64)> {
+        self.peak_in_transit
+    }
+
+    /// Per-mode statistics, sorted by mode name.
+    pub fn modes(&self) -> Vec<ModeSummary> {
+        let mut modes: Vec<ModeSummary> = self
+            .modes
+            .iter()
+            .filter(|(_, m)| !m.travel_secs.is_empty())
+            .map(|(&mode, m)| {
+                let mut sorted = m.travel_secs.clone();
+                sorted.sort_by(f32::total_cmp);
+                let total: f64 = sorted.iter().map(|&s| s as f64).sum();
+                ModeSummary {
+                    mode,
+                    trips:            sorted.len() as u64,
+                    distance_m:       m.distance_m,
+                    mean_travel_secs: total / sorted.len() as f64,
+                    p50_travel_secs:  percentile(&sorted, 0.50),
+                    p90_travel_secs:  percentile(&sorted, 0.90),
+                    p95_travel_secs:  percentile(&sorted, 0.95),
+                    max_travel_secs:  sorted[sorted.len() - 1],
+                }
+            })
+            .collect();
+        modes.sort_by_key(|m| m.mode.as_str());
+        modes
+    }
+
+    /// Pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        let peak = self.peak_in_transit.map_or_else(
+            || "null".to_owned(),
+            |(tick, agents)| format!("{{ \"tick\": {tick}, \"agents\": {agents} }}"),
+        );
+        let modes: Vec<String> = self
+            .modes()
+            .iter()
+            .map(|m| {
+                format!(
+                    "    {{ \"mode\": \"{}\", \"trips\": {}, \"distance_m\": {}, \"travel_secs\": \
+                     {{ \"mean\": {}, \"p50\": {}, \"p90\": {}, \"p95\": {}, \"max\": {} }} }}",
+                    m.mode.as_str(),
+                    m.trips,
+                    num(m.distance_m),
+                    num(m.mean_travel_secs),
+                    num(m.p50_travel_secs as f64),
+                    num(m.p90_travel_secs as f64),
+                    num(m.p95_travel_secs as f64),
+                    num(m.max_travel_secs as f64),
+                )
+            })
+            .collect();
+
+        let mut out = String::from("{\n");
+        let final_tick = self.final_tick.map_or_else(|| "null".to_owned(), |t| t.to_string());
+        let _ = writeln!(out, "  \"final_tick\": {final_tick},");
+        let _ = writeln!(out, "  \"ticks\": {},", self.ticks);
+        let _ = writeln!(out, "  \"trips\": {},", self.trips());
+        let _ = writeln!(out, "  \"distance_m\": {},", num(self.distance_m()));
+        let _ = writeln!(out, "  \"departures\": {},", self.departures);
+        let _ = writeln!(out, "  \"routing_failures\": {},", self.routing_failures);
+        let _ = writeln!(out, "  \"contacts\": {},", self.contacts);
+        let _ = writeln!(out, "  \"peak_in_transit\": {peak},");
+        if modes.is_empty() {
+            out.push_str("  \"modes\": []\n");
+        } else {
+            let _ = writeln!(out, "  \"modes\": [\n{}\n  ]", modes.join(",\n"));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Write `run_summary.json` into `dir`, replacing any earlier one.
+    pub fn write(&self, dir: &Path) -> OutputResult<()> {
+        std::fs::write(dir.join("run_summary.json"), self.to_json())?;
+        Ok(())
+    }
+}
+
+impl SimObserver for RunSummary {
+    fn on_trip(&mut self, trip: &Trip) {
+        self.record_trip(&TripRow::from(trip));
+    }
+
+    fn on_tick_stats(&mut self, tick: Tick, stats: &TickStats) {
+        self.record_tick(tick.0, stats);
+    }
+
+    fn on_sim_end(&mut self, final_tick: Tick) {
+        self.finish(final_tick.0);
+    }
+}
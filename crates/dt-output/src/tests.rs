@@ -5,7 +5,7 @@ mod csv_tests {
     use tempfile::TempDir;
 
     use crate::csv::CsvWriter;
-    use crate::row::{AgentSnapshotRow, TickSummaryRow};
+    use crate::row::{AgentSnapshotRow, ContactRow, TickSummaryRow, TripRow};
     use crate::writer::OutputWriter;
 
     fn tmp() -> TempDir {
@@ -19,11 +19,15 @@ mod csv_tests {
             departure_node:   agent_id * 10,
             in_transit:       false,
             destination_node: u32::MAX,
+            lat:              None,
+            lon:              None,
+            departure_tick:   None,
+            arrival_tick:     None,
         }
     }
 
     fn summary_row(tick: u64) -> TickSummaryRow {
-        TickSummaryRow { tick, unix_time_secs: tick as i64 * 3600, woken_agents: tick }
+        TickSummaryRow { tick, unix_time_secs: tick as i64 * 3600, woken_agents: tick, ..Default::default() }
     }
 
     #[test]
@@ -32,6 +36,8 @@ mod csv_tests {
         let _w = CsvWriter::new(dir.path()).unwrap();
         assert!(dir.path().join("agent_snapshots.csv").exists());
         assert!(dir.path().join("tick_summaries.csv").exists());
+        assert!(dir.path().join("contacts.csv").exists());
+        assert!(dir.path().join("trips.csv").exists());
     }
 
     #[test]
@@ -42,11 +48,26 @@ mod csv_tests {
 
         let mut rdr = csv::Reader::from_path(dir.path().join("agent_snapshots.csv")).unwrap();
         let headers: Vec<_> = rdr.headers().unwrap().iter().map(str::to_owned).collect();
-        assert_eq!(headers, ["agent_id", "tick", "departure_node", "in_transit", "destination_node"]);
+        assert_eq!(headers, ["agent_id", "tick", "departure_node", "in_transit", "destination_node", "lat", "lon",
+                             "departure_tick", "arrival_tick"]);
 
         let mut rdr2 = csv::Reader::from_path(dir.path().join("tick_summaries.csv")).unwrap();
         let headers2: Vec<_> = rdr2.headers().unwrap().iter().map(str::to_owned).collect();
-        assert_eq!(headers2, ["tick", "unix_time_secs", "woken_agents"]);
+        assert_eq!(headers2, [
+            "tick", "unix_time_secs", "woken_agents", "in_transit", "arrivals", "departures",
+            "travel_intents", "wake_intents", "messages_sent", "metric_intents", "messages_delivered",
+            "contacts", "routing_failures", "behavior_panics",
+        ]);
+
+        let mut rdr3 = csv::Reader::from_path(dir.path().join("contacts.csv")).unwrap();
+        let headers3: Vec<_> = rdr3.headers().unwrap().iter().map(str::to_owned).collect();
+        assert_eq!(headers3, ["tick", "agent_a", "agent_b", "node"]);
+
+        let mut rdr4 = csv::Reader::from_path(dir.path().join("trips.csv")).unwrap();
+        let headers4: Vec<_> = rdr4.headers().unwrap().iter().map(str::to_owned).collect();
+        assert_eq!(headers4, [
+            "agent", "depart_tick", "arrive_tick", "from", "to", "mode", "travel_secs", "distance_m",
+        ]);
     }
 
     #[test]
@@ -82,25 +103,28 @@ mod csv_tests {
     }
 
     #[test]
-    fn csv_finish_idempotent() {
+    fn csv_contacts_round_trip() {
         let dir = tmp();
         let mut w = CsvWriter::new(dir.path()).unwrap();
+        w.write_contacts(&[
+            ContactRow { tick: 4, agent_a: 1, agent_b: 7, node: 3 },
+            ContactRow { tick: 4, agent_a: 7, agent_b: 1, node: 3 },
+        ]).unwrap();
         w.finish().unwrap();
-        w.finish().unwrap(); // second call should not panic
-    }
 
-    #[test]
-    fn csv_empty_snapshot_ok() {
-        let dir = tmp();
-        let mut w = CsvWriter::new(dir.path()).unwrap();
-        w.write_snapshots(&[]).unwrap(); // should return Ok(())
+        let mut rdr = csv::Reader::from_path(dir.path().join("contacts.csv")).unwrap();
+        let read_rows: Vec<_> = rdr.records().map(|r| r.unwrap()).collect();
+        assert_eq!(read_rows.len(), 2);
+        assert_eq!(read_rows[0].iter().collect::<Vec<_>>(), ["4", "1", "7", "3"]);
+        assert_eq!(read_rows[1].iter().collect::<Vec<_>>(), ["4", "7", "1", "3"]);
     }
 
     #[test]
-    fn integration_csv() {
+    fn integration_csv_contacts() {
         use dt_agent::AgentStoreBuilder;
         use dt_behavior::NoopBehavior;
-        use dt_core::{NodeId, SimConfig};
+        use dt_core::{ActivityId, NodeId, SimConfig};
+        use dt_schedule::{ActivityPlan, Destination, ScheduledActivity};
         use dt_sim::SimBuilder;
         use dt_spatial::DijkstraRouter;
 
@@ -109,208 +133,3027 @@ mod csv_tests {
         let config = SimConfig {
             start_unix_secs:       0,
             tick_duration_secs:    3600,
-            total_ticks:           6,
+            total_ticks:           2,
             seed:                  1,
             num_threads:           Some(1),
             output_interval_ticks: 2,
+            ..Default::default()
         };
 
-        let (store, rngs) = AgentStoreBuilder::new(3, 1).build();
+        // Every agent wakes once, at tick 1.  Agents 0, 1, 2 share node 5.
+        let plan = ActivityPlan::new(vec![ScheduledActivity {
+            start_offset_ticks: 0,
+            duration_ticks:     1,
+            activity_id:        ActivityId(0),
+            destination:        Destination::Home,
+        }], 1);
+        let (store, rngs) = AgentStoreBuilder::new(4, 1).build();
         let mut sim = SimBuilder::new(config.clone(), store, rngs, NoopBehavior, DijkstraRouter)
-            .initial_positions(vec![NodeId(0), NodeId(1), NodeId(2)])
+            .plans(vec![plan; 4])
+            .initial_positions(vec![NodeId(5), NodeId(5), NodeId(5), NodeId(6)])
             .build()
             .unwrap();
 
         let dir = tmp();
-        let writer = CsvWriter::new(dir.path()).unwrap();
-        let mut obs = SimOutputObserver::new(writer, &config);
+        let mut obs = SimOutputObserver::new(CsvWriter::new(dir.path()).unwrap(), &config);
         sim.run(&mut obs).unwrap();
-        assert!(obs.take_error().is_none(), "no write errors expected");
+        assert!(obs.take_error().is_none());
 
-        // output_interval = 2 → snapshots fired at ticks 0, 2, 4 (3 ticks × 3 agents = 9 rows)
-        let mut rdr = csv::Reader::from_path(dir.path().join("agent_snapshots.csv")).unwrap();
-        let rows: Vec<_> = rdr.records().map(|r| r.unwrap()).collect();
-        assert_eq!(rows.len(), 9, "expected 3 ticks × 3 agents = 9 snapshot rows, got {}", rows.len());
+        let mut rdr = csv::Reader::from_path(dir.path().join("contacts.csv")).unwrap();
+        let pairs: Vec<(u32, u32)> = rdr
+            .records()
+            .map(|r| {
+                let r = r.unwrap();
+                assert_eq!((&r[0], &r[3]), ("1", "5"));
+                (r[1].parse().unwrap(), r[2].parse().unwrap())
+            })
+            .collect();
+        assert_eq!(pairs, [(0, 1), (0, 2), (1, 0), (1, 2), (2, 0), (2, 1)]);
     }
-}
-
-// ── SQLite tests ──────────────────────────────────────────────────────────────
 
-#[cfg(all(test, feature = "sqlite"))]
-mod sqlite_tests {
-    use tempfile::TempDir;
+    #[test]
+    fn csv_trips_round_trip() {
+        use dt_core::TransportMode;
 
-    use crate::row::{AgentSnapshotRow, TickSummaryRow};
-    use crate::sqlite::SqliteWriter;
-    use crate::writer::OutputWriter;
+        let dir = tmp();
+        let mut w = CsvWriter::new(dir.path()).unwrap();
+        w.write_trips(&[TripRow {
+            agent:       3,
+            depart_tick: 10,
+            arrive_tick: 12,
+            from:        1,
+            to:          4,
+            mode:        TransportMode::Bike,
+            travel_secs: 5400.5,
+            distance_m:  18250.0,
+        }]).unwrap();
+        w.finish().unwrap();
 
-    fn tmp() -> TempDir {
-        tempfile::tempdir().expect("create temp dir")
+        let mut rdr = csv::Reader::from_path(dir.path().join("trips.csv")).unwrap();
+        let read_rows: Vec<_> = rdr.records().map(|r| r.unwrap()).collect();
+        assert_eq!(read_rows.len(), 1);
+        assert_eq!(
+            read_rows[0].iter().collect::<Vec<_>>(),
+            ["3", "10", "12", "1", "4", "bike", "5400.5", "18250"],
+        );
     }
 
     #[test]
-    fn sqlite_db_created() {
+    fn integration_csv_trips() {
+        use dt_agent::AgentStoreBuilder;
+        use dt_behavior::{BehaviorModel, Intent, SimContext};
+        use dt_core::{ActivityId, AgentId, AgentRng, GeoPoint, NodeId, SimConfig, TransportMode};
+        use dt_schedule::{ActivityPlan, Destination, ScheduledActivity};
+        use dt_sim::SimBuilder;
+        use dt_spatial::{DijkstraRouter, RoadNetworkBuilder};
+
+        use crate::observer::SimOutputObserver;
+
+        /// Walks to node 1 at its first wake and back home at the next.
+        struct Shuttle;
+        impl BehaviorModel for Shuttle {
+            fn replan(&self, _a: AgentId, ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+                let destination = if ctx.tick.0 % 4 == 1 { NodeId(1) } else { NodeId(0) };
+                vec![Intent::TravelTo { destination, mode: TransportMode::Walk }]
+            }
+        }
+
+        let mut b = RoadNetworkBuilder::new();
+        let n0 = b.add_node(GeoPoint { lat: 0.0, lon: 0.0 });
+        let n1 = b.add_node(GeoPoint { lat: 0.0, lon: 0.01 });
+        b.add_road(n0, n1, 1100.0, 80_000);
+
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    3600,
+            total_ticks:           5,
+            seed:                  1,
+            num_threads:           Some(1),
+            output_interval_ticks: 5,
+            ..Default::default()
+        };
+        let plan = ActivityPlan::new(vec![ScheduledActivity {
+            start_offset_ticks: 0,
+            duration_ticks:     1,
+            activity_id:        ActivityId(0),
+            destination:        Destination::Home,
+        }], 1);
+        let (store, rngs) = AgentStoreBuilder::new(1, 1).build();
+        let mut sim = SimBuilder::new(config.clone(), store, rngs, Shuttle, DijkstraRouter)
+            .plans(vec![plan])
+            .network(b.build())
+            .initial_positions(vec![NodeId(0)])
+            .build()
+            .unwrap();
+
         let dir = tmp();
-        let _w = SqliteWriter::new(dir.path()).unwrap();
-        assert!(dir.path().join("output.db").exists());
+        let mut obs = SimOutputObserver::new(CsvWriter::new(dir.path()).unwrap(), &config);
+        sim.run(&mut obs).unwrap();
+        assert!(obs.take_error().is_none());
+
+        // Out at tick 1 → 2; the plan wakes it again at tick 3, home at tick 4.
+        let mut rdr = csv::Reader::from_path(dir.path().join("trips.csv")).unwrap();
+        let trips: Vec<Vec<String>> = rdr
+            .records()
+            .map(|r| r.unwrap().iter().take(6).map(str::to_owned).collect())
+            .collect();
+        assert_eq!(trips, [
+            ["0", "1", "2", "0", "1", "walk"],
+            ["0", "3", "4", "1", "0", "walk"],
+        ]);
+
+        // The tick summaries carry the sim's built-in counts.
+        let mut rdr = csv::Reader::from_path(dir.path().join("tick_summaries.csv")).unwrap();
+        let headers = rdr.headers().unwrap().clone();
+        assert_eq!(headers.len(), 14);
+        let col = |name: &str| headers.iter().position(|h| h == name).unwrap();
+        let (departures, arrivals, in_transit) = (col("departures"), col("arrivals"), col("in_transit"));
+        let counts: Vec<[String; 3]> = rdr
+            .records()
+            .map(|r| {
+                let r = r.unwrap();
+                [&r[departures], &r[arrivals], &r[in_transit]].map(str::to_owned)
+            })
+            .collect();
+        assert_eq!(counts, [
+            ["0", "0", "0"],
+            ["1", "0", "1"],
+            ["0", "1", "0"],
+            ["1", "0", "1"],
+            ["0", "1", "0"],
+        ]);
     }
 
     #[test]
-    fn sqlite_snapshot_count() {
-        let dir = tmp();
-        let mut w = SqliteWriter::new(dir.path()).unwrap();
-        let rows = vec![
-            AgentSnapshotRow { agent_id: 0, tick: 1, departure_node: 10, in_transit: false, destination_node: u32::MAX },
-            AgentSnapshotRow { agent_id: 1, tick: 1, departure_node: 11, in_transit: true,  destination_node: 20 },
-            AgentSnapshotRow { agent_id: 2, tick: 1, departure_node: 12, in_transit: false, destination_node: u32::MAX },
-        ];
-        w.write_snapshots(&rows).unwrap();
-        w.finish().unwrap();
+    fn od_matrix_buckets_trips() {
+        use dt_core::TransportMode;
 
-        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
-        let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM agent_snapshots", [], |r| r.get(0)
-        ).unwrap();
-        assert_eq!(count, 3);
+        use crate::od::OdMatrix;
+        use crate::row::OdRow;
+
+        let trip = |from, to, mode| TripRow {
+            agent: 0, depart_tick: 0, arrive_tick: 1, from, to, mode, travel_secs: 1.0, distance_m: 1.0,
+        };
+        // Nodes 0,1 → zone 10; node 2 → zone 20; node 3 unzoned; node 4 out of range.
+        let mut od = OdMatrix::new(vec![10, 10, 20, u32::MAX]);
+        od.record(&trip(0, 2, TransportMode::Walk), 8);
+        od.record(&trip(1, 2, TransportMode::Walk), 8);
+        od.record(&trip(1, 2, TransportMode::Car),  8);
+        od.record(&trip(2, 0, TransportMode::Walk), 17);
+        od.record(&trip(0, 3, TransportMode::Walk), 8);
+        od.record(&trip(4, 0, TransportMode::Walk), 8);
+
+        assert_eq!(od.total(), 4);
+        let row = |origin_zone, dest_zone, hour, mode, trips| OdRow { origin_zone, dest_zone, hour, mode, trips };
+        assert_eq!(od.rows(), [
+            row(10, 20, 8,  TransportMode::Car,  1),
+            row(10, 20, 8,  TransportMode::Walk, 2),
+            row(20, 10, 17, TransportMode::Walk, 1),
+        ]);
     }
 
     #[test]
-    fn sqlite_in_transit_as_integer() {
+    fn link_volumes_expand_routes() {
+        use dt_core::{GeoPoint, NodeId, Tick, TransportMode};
+        use dt_mobility::MovementState;
+        use dt_spatial::{DijkstraRouter, RoadNetworkBuilder, Router};
+
+        use crate::row::LinkVolumeRow;
+        use crate::volumes::LinkVolumes;
+
+        let mut b = RoadNetworkBuilder::new();
+        let n0 = b.add_node(GeoPoint { lat: 0.0, lon: 0.0 });
+        let n1 = b.add_node(GeoPoint { lat: 0.0, lon: 0.005 });
+        let n2 = b.add_node(GeoPoint { lat: 0.0, lon: 0.02 });
+        b.add_road(n0, n1, 500.0, 60_000);
+        b.add_road(n1, n2, 1500.0, 180_000);
+        let network = b.build();
+        let route = DijkstraRouter.route(&network, n0, n2, TransportMode::Car).unwrap();
+        let (first, second) = (route.edges[0].0, route.edges[1].0);
+
+        let state = |mode| MovementState {
+            in_transit:       true,
+            departure_node:   NodeId(0),
+            destination_node: NodeId(2),
+            departure_tick:   Tick(10),
+            arrival_tick:     Tick(18),
+            mode,
+            on_edge:          None,
+        };
+        let mut volumes = LinkVolumes::new(&network, 4, &[TransportMode::Car]);
+        // The first edge takes a quarter of the journey: entered at 10 and 12.
+        volumes.record(&state(TransportMode::Car), &route);
+        volumes.record(&state(TransportMode::Car), &route);
+        volumes.record(&state(TransportMode::Walk), &route);
+        assert_eq!(volumes.total(), 4);
+
+        let row = |tick, edge, vehicles| LinkVolumeRow { tick, edge, vehicles };
+        assert_eq!(volumes.take_until(11), []);
+        assert_eq!(volumes.take_until(12), [row(8, first, 2)]);
+        assert_eq!(volumes.total(), 2);
+        assert_eq!(volumes.take_all(13), [row(12, second, 2)]);
+        assert_eq!(volumes.total(), 0);
+
+        // Intervals starting at or after the end of the run are dropped.
+        volumes.record(&state(TransportMode::Car), &route);
+        assert_eq!(volumes.take_all(12), [row(8, first, 1)]);
+
         let dir = tmp();
-        let mut w = SqliteWriter::new(dir.path()).unwrap();
-        w.write_snapshots(&[AgentSnapshotRow {
-            agent_id: 0, tick: 0, departure_node: 5, in_transit: true, destination_node: 9,
-        }]).unwrap();
+        let mut w = CsvWriter::new(dir.path()).unwrap();
+        w.write_link_volumes(&[row(8, first, 2)]).unwrap();
+        w.write_link_volumes(&[row(12, second, 2)]).unwrap();
         w.finish().unwrap();
-
-        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
-        let val: i64 = conn.query_row(
-            "SELECT in_transit FROM agent_snapshots WHERE agent_id = 0", [], |r| r.get(0)
-        ).unwrap();
-        assert_eq!(val, 1, "in_transit=true should be stored as 1");
+        let mut rdr = csv::Reader::from_path(dir.path().join("link_volumes.csv")).unwrap();
+        assert_eq!(rdr.headers().unwrap().iter().collect::<Vec<_>>(), ["tick", "edge_id", "vehicles"]);
+        assert_eq!(rdr.records().count(), 2);
     }
 
     #[test]
-    fn sqlite_invalid_node_stored() {
+    fn route_sampling_is_seeded() {
+        use dt_core::{AgentId, EdgeId, NodeId, SimConfig, Tick, TransportMode};
+        use dt_mobility::MovementState;
+        use dt_sim::SimObserver;
+        use dt_spatial::Route;
+
+        use crate::memory::MemoryWriter;
+        use crate::observer::SimOutputObserver;
+
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    60,
+            total_ticks:           10,
+            seed:                  7,
+            num_threads:           Some(1),
+            output_interval_ticks: 0,
+            ..Default::default()
+        };
+        let state = MovementState {
+            in_transit:       true,
+            departure_node:   NodeId(0),
+            destination_node: NodeId(1),
+            departure_tick:   Tick(0),
+            arrival_tick:     Tick(2),
+            mode:             TransportMode::Car,
+            on_edge:          None,
+        };
+        let route = Route { edges: vec![EdgeId(3), EdgeId(8)], total_travel_ms: 90_000, rides: Vec::new() };
+        let sampled = |rate| {
+            let mut obs = SimOutputObserver::new(MemoryWriter::new(), &config).with_routes(rate);
+            for agent in 0..200 {
+                obs.on_departure(Tick(0), AgentId(agent), &state, &route);
+            }
+            obs.on_tick_end(Tick(0), 0);
+            obs.into_writer().routes
+        };
+
+        assert!(sampled(0.0).is_empty());
+        assert_eq!(sampled(1.0).len(), 200);
+        let half = sampled(0.5);
+        assert!((60..140).contains(&half.len()), "{} of 200 sampled", half.len());
+        assert_eq!(half, sampled(0.5), "same seed, same sample");
+        assert_eq!(half[0].edges, [3, 8]);
+        assert_eq!(half[0].edge_list(), "[3,8]");
+
         let dir = tmp();
-        let mut w = SqliteWriter::new(dir.path()).unwrap();
-        w.write_snapshots(&[AgentSnapshotRow {
-            agent_id: 0, tick: 0, departure_node: u32::MAX, in_transit: false, destination_node: u32::MAX,
-        }]).unwrap();
+        let mut w = CsvWriter::new(dir.path()).unwrap();
+        w.write_routes(&half[..1]).unwrap();
         w.finish().unwrap();
-
-        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
-        // SQLite INTEGER is signed 64-bit; u32::MAX fits without loss.
-        let val: i64 = conn.query_row(
-            "SELECT departure_node FROM agent_snapshots WHERE agent_id = 0", [], |r| r.get(0)
-        ).unwrap();
-        assert_eq!(val, u32::MAX as i64);
+        let mut rdr = csv::Reader::from_path(dir.path().join("routes.csv")).unwrap();
+        assert_eq!(rdr.headers().unwrap().iter().collect::<Vec<_>>(), ["agent", "depart_tick", "from", "to", "mode", "edges"]);
+        let record = rdr.records().next().unwrap().unwrap();
+        assert_eq!(&record[4], "car");
+        assert_eq!(&record[5], "[3,8]");
     }
 
     #[test]
-    fn sqlite_tick_summary() {
+    fn integration_csv_od_matrix() {
+        use dt_core::{AgentId, NodeId, SimConfig, Tick, TransportMode};
+        use dt_mobility::Trip;
+        use dt_sim::SimObserver;
+
+        use crate::observer::SimOutputObserver;
+
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    3600,
+            total_ticks:           48,
+            seed:                  1,
+            num_threads:           Some(1),
+            output_interval_ticks: 48,
+            ..Default::default()
+        };
         let dir = tmp();
-        let mut w = SqliteWriter::new(dir.path()).unwrap();
-        w.write_tick_summary(&TickSummaryRow {
-            tick: 7, unix_time_secs: 25_200, woken_agents: 42,
-        }).unwrap();
-        w.finish().unwrap();
+        let mut obs = SimOutputObserver::new(CsvWriter::new(dir.path()).unwrap(), &config)
+            .with_od_matrix(vec![0, 1]);
+        // Tick 9 and tick 33 are both 09:00.
+        for depart in [9, 33, 10] {
+            obs.on_trip(&Trip {
+                agent:       AgentId(0),
+                from:        NodeId(0),
+                to:          NodeId(1),
+                mode:        TransportMode::Bike,
+                depart_tick: Tick(depart),
+                arrive_tick: Tick(depart + 1),
+                travel_secs: 600.0,
+                distance_m:  2000.0,
+            });
+        }
+        assert_eq!(obs.od_matrix().unwrap().total(), 3);
+        obs.on_sim_end(Tick(48));
+        assert!(obs.take_error().is_none());
 
-        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
-        let (tick, unix_time, woken): (i64, i64, i64) = conn.query_row(
-            "SELECT tick, unix_time_secs, woken_agents FROM tick_summaries WHERE tick = 7",
-            [],
-            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
-        ).unwrap();
-        assert_eq!(tick, 7);
-        assert_eq!(unix_time, 25_200);
-        assert_eq!(woken, 42);
+        let mut rdr = csv::Reader::from_path(dir.path().join("od_matrix.csv")).unwrap();
+        assert_eq!(rdr.headers().unwrap().iter().collect::<Vec<_>>(), ["origin_zone", "dest_zone", "hour", "mode", "trips"]);
+        let rows: Vec<Vec<String>> = rdr
+            .records()
+            .map(|r| r.unwrap().iter().map(str::to_owned).collect())
+            .collect();
+        assert_eq!(rows, [["0", "1", "9", "bike", "2"], ["0", "1", "10", "bike", "1"]]);
     }
-}
 
-// ── Parquet tests ─────────────────────────────────────────────────────────────
+    #[test]
+    fn integration_csv_run_manifest() {
+        use dt_core::{GeoPoint, SimConfig, Tick};
+        use dt_sim::SimObserver;
+        use dt_spatial::RoadNetworkBuilder;
 
-#[cfg(all(test, feature = "parquet"))]
-mod parquet_tests {
-    use tempfile::TempDir;
+        use crate::columns::{ColumnType, ColumnValue, ComponentColumn};
+        use crate::observer::SimOutputObserver;
 
-    use arrow::datatypes::DataType;
-    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+        #[derive(Default)]
+        struct Health { infected: bool }
 
-    use crate::parquet::ParquetWriter;
-    use crate::row::AgentSnapshotRow;
-    use crate::writer::OutputWriter;
+        let mut b = RoadNetworkBuilder::new();
+        let n0 = b.add_node(GeoPoint { lat: 0.0, lon: 0.0 });
+        let n1 = b.add_node(GeoPoint { lat: 0.0, lon: 0.01 });
+        b.add_road(n0, n1, 1100.0, 80_000);
+        let network = b.build();
 
-    fn tmp() -> TempDir {
-        tempfile::tempdir().expect("create temp dir")
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    3600,
+            total_ticks:           24,
+            seed:                  42,
+            num_threads:           None,
+            output_interval_ticks: 6,
+            ..Default::default()
+        };
+        let dir = tmp();
+        let path = dir.path().join("run_manifest.json");
+        let mut obs = SimOutputObserver::new(CsvWriter::new(dir.path()).unwrap(), &config)
+            .with_network(&network)
+            .with_column(ComponentColumn::new("infected", ColumnType::Bool, |h: &Health| {
+                ColumnValue::Bool(h.infected)
+            }));
+        assert!(!path.exists());
+
+        obs.on_tick_start(Tick(0));
+        let running = std::fs::read_to_string(&path).unwrap();
+        assert!(running.contains("\"status\": \"running\""));
+        assert!(running.contains("\"finished_unix_secs\": null"));
+
+        obs.on_sim_end(Tick(24));
+        assert!(obs.take_error().is_none());
+        let finished = std::fs::read_to_string(&path).unwrap();
+        assert!(finished.contains("\"status\": \"finished\""));
+        assert!(finished.contains("\"seed\": 42"));
+        assert!(finished.contains("\"num_threads\": null"));
+        assert!(finished.contains("\"final_tick\": 24"));
+        assert!(finished.contains(&format!("\"fingerprint\": \"{:016x}\"", network.fingerprint())));
+        assert!(finished.contains("\"tick_summaries\": 2"));
+        assert!(finished.contains("{ \"name\": \"infected\", \"type\": \"bool\" }"));
+        let manifest = obs.manifest().unwrap();
+        assert!(manifest.started_unix_secs <= manifest.finished_unix_secs);
     }
 
     #[test]
-    fn parquet_files_created() {
-        let dir = tmp();
-        let mut w = ParquetWriter::new(dir.path()).unwrap();
-        w.finish().unwrap();
-        assert!(dir.path().join("agent_snapshots.parquet").exists());
-        assert!(dir.path().join("tick_summaries.parquet").exists());
+    fn run_summary_aggregates() {
+        use dt_core::TransportMode;
+        use dt_sim::TickStats;
+
+        use crate::summary::RunSummary;
+
+        let trip = |mode, travel_secs| TripRow {
+            agent: 0, depart_tick: 0, arrive_tick: 1, from: 0, to: 1, mode, travel_secs, distance_m: 500.0,
+        };
+        let mut summary = RunSummary::new();
+        for secs in 1..=20 {
+            summary.record_trip(&trip(TransportMode::Walk, secs as f32 * 10.0));
+        }
+        summary.record_trip(&trip(TransportMode::Car, 90.0));
+        for (tick, in_transit) in [(0, 1), (1, 4), (2, 4), (3, 2)] {
+            summary.record_tick(tick, &TickStats { in_transit, departures: 1, contacts: 2, ..Default::default() });
+        }
+        summary.finish(4);
+
+        assert_eq!(summary.trips(), 21);
+        assert_eq!(summary.distance_m(), 10_500.0);
+        assert_eq!(summary.departures(), 4);
+        assert_eq!(summary.contacts(), 8);
+        assert_eq!(summary.peak_in_transit(), Some((1, 4)));
+
+        let modes = summary.modes();
+        assert_eq!(modes.iter().map(|m| m.mode).collect::<Vec<_>>(), [TransportMode::Car, TransportMode::Walk]);
+        assert_eq!((modes[0].trips, modes[0].p50_travel_secs, modes[0].max_travel_secs), (1, 90.0, 90.0));
+        let walk = &modes[1];
+        assert_eq!(walk.mean_travel_secs, 105.0);
+        assert_eq!((walk.p50_travel_secs, walk.p90_travel_secs, walk.p95_travel_secs), (100.0, 180.0, 190.0));
+        assert_eq!(walk.max_travel_secs, 200.0);
+
+        let json = summary.to_json();
+        assert!(json.contains("\"final_tick\": 4,"));
+        assert!(json.contains("\"peak_in_transit\": { \"tick\": 1, \"agents\": 4 },"));
+        assert!(json.contains("\"mode\": \"walk\", \"trips\": 20, \"distance_m\": 10000,"));
+        assert!(RunSummary::new().to_json().contains("\"modes\": []"));
     }
 
     #[test]
-    fn parquet_snapshot_round_trip() {
+    fn integration_csv_run_summary() {
+        use dt_core::{AgentId, NodeId, SimConfig, Tick, TransportMode};
+        use dt_mobility::Trip;
+        use dt_sim::{SimObserver, TickStats};
+
+        use crate::observer::{OutputCadence, SimOutputObserver};
+
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    60,
+            total_ticks:           2,
+            seed:                  1,
+            num_threads:           Some(1),
+            output_interval_ticks: 2,
+            ..Default::default()
+        };
         let dir = tmp();
-        let mut w = ParquetWriter::new(dir.path()).unwrap();
-        let rows = vec![
-            AgentSnapshotRow { agent_id: 0, tick: 2, departure_node: 10, in_transit: false, destination_node: u32::MAX },
-            AgentSnapshotRow { agent_id: 1, tick: 2, departure_node: 11, in_transit: true,  destination_node: 20 },
-        ];
-        w.write_snapshots(&rows).unwrap();
-        w.finish().unwrap();
+        // Trips are not written, but still summarised.
+        let cadence = OutputCadence { trips: 0, ..OutputCadence::default() };
+        let mut obs = SimOutputObserver::new(CsvWriter::new(dir.path()).unwrap(), &config)
+            .with_cadence(cadence)
+            .with_run_summary();
+        obs.on_tick_stats(Tick(0), &TickStats { in_transit: 3, departures: 3, ..Default::default() });
+        obs.on_trip(&Trip {
+            agent:       AgentId(0),
+            from:        NodeId(0),
+            to:          NodeId(1),
+            mode:        TransportMode::Bike,
+            depart_tick: Tick(0),
+            arrive_tick: Tick(1),
+            travel_secs: 45.0,
+            distance_m:  200.0,
+        });
+        obs.on_sim_end(Tick(2));
+        assert!(obs.take_error().is_none());
+        assert_eq!(obs.run_summary().unwrap().trips(), 1);
 
-        let file = std::fs::File::open(dir.path().join("agent_snapshots.parquet")).unwrap();
-        let builder = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
-        let schema = builder.schema().clone();
-        let reader = builder.build().unwrap();
+        let json = std::fs::read_to_string(dir.path().join("run_summary.json")).unwrap();
+        assert!(json.contains("\"final_tick\": 2,"));
+        assert!(json.contains("\"departures\": 3,"));
+        assert!(json.contains("\"peak_in_transit\": { \"tick\": 0, \"agents\": 3 },"));
+        assert!(json.contains("\"mode\": \"bike\", \"trips\": 1, \"distance_m\": 200,"));
+    }
 
-        let batches: Vec<_> = reader.map(|b| b.unwrap()).collect();
-        let total_rows: usize = batches.iter().map(|b| b.num_rows()).sum();
-        assert_eq!(total_rows, 2, "expected 2 rows");
+    #[test]
+    fn run_manifest_disabled() {
+        use dt_core::{SimConfig, Tick};
+        use dt_sim::SimObserver;
 
-        // Check schema field names
-        let field_names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
-        assert_eq!(field_names, ["agent_id", "tick", "departure_node", "in_transit", "destination_node"]);
+        use crate::observer::SimOutputObserver;
+
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    3600,
+            total_ticks:           1,
+            seed:                  1,
+            num_threads:           Some(1),
+            output_interval_ticks: 1,
+            ..Default::default()
+        };
+        let dir = tmp();
+        let mut obs = SimOutputObserver::new(CsvWriter::new(dir.path()).unwrap(), &config)
+            .without_manifest();
+        obs.on_tick_start(Tick(0));
+        obs.on_sim_end(Tick(1));
+        assert!(obs.manifest().is_none());
+        assert!(!dir.path().join("run_manifest.json").exists());
     }
 
     #[test]
-    fn parquet_boolean_column_type() {
+    fn csv_extra_columns_from_components() {
+        use dt_agent::AgentStoreBuilder;
+        use dt_core::{AgentId, NodeId, SimConfig, Tick};
+        use dt_mobility::MobilityEngine;
+        use dt_sim::SimObserver;
+        use dt_spatial::DijkstraRouter;
+
+        use crate::columns::{ColumnType, ColumnValue, ComponentColumn};
+        use crate::observer::SimOutputObserver;
+
+        #[derive(Default)]
+        struct Health { infected: bool }
+        #[derive(Default)]
+        struct Income(Option<i64>);
+
+        let (mut store, _) = AgentStoreBuilder::new(2, 1)
+            .register_component::<Health>()
+            .register_component::<Income>()
+            .build();
+        store.component_mut::<Health>().unwrap()[1].infected = true;
+        store.component_mut::<Income>().unwrap()[0].0 = Some(3);
+        let mut engine = MobilityEngine::new(DijkstraRouter, 2);
+        engine.place(AgentId(0), NodeId(0), Tick(0));
+        engine.place(AgentId(1), NodeId(0), Tick(0));
+
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    3600,
+            total_ticks:           1,
+            seed:                  1,
+            num_threads:           Some(1),
+            output_interval_ticks: 1,
+            ..Default::default()
+        };
         let dir = tmp();
-        let mut w = ParquetWriter::new(dir.path()).unwrap();
-        w.write_snapshots(&[AgentSnapshotRow {
-            agent_id: 0, tick: 0, departure_node: 1, in_transit: true, destination_node: 2,
-        }]).unwrap();
-        w.finish().unwrap();
+        let mut obs = SimOutputObserver::new(CsvWriter::new(dir.path()).unwrap(), &config)
+            .with_column(ComponentColumn::new("infected", ColumnType::Bool, |h: &Health| {
+                ColumnValue::Bool(h.infected)
+            }))
+            .with_column(ComponentColumn::new("income_bracket", ColumnType::Int, |i: &Income| {
+                i.0.map_or(ColumnValue::Null, ColumnValue::Int)
+            }));
+        obs.on_snapshot(Tick(0), &engine.store, &store);
+        obs.on_sim_end(Tick(0));
+        assert!(obs.take_error().is_none());
 
-        let file = std::fs::File::open(dir.path().join("agent_snapshots.parquet")).unwrap();
-        let builder = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
-        let schema = builder.schema().clone();
+        let mut rdr = csv::Reader::from_path(dir.path().join("agent_snapshots.csv")).unwrap();
+        let headers: Vec<_> = rdr.headers().unwrap().iter().map(str::to_owned).collect();
+        assert_eq!(&headers[9..], ["infected", "income_bracket"]);
+        let rows: Vec<Vec<String>> = rdr
+            .records()
+            .map(|r| r.unwrap().iter().skip(9).map(str::to_owned).collect())
+            .collect();
+        assert_eq!(rows, [["0", "3"], ["1", ""]]);
 
-        let in_transit_field = schema.field_with_name("in_transit").unwrap();
-        assert_eq!(*in_transit_field.data_type(), DataType::Boolean);
+        // Built-in columns still read back.
+        let read = crate::csv::CsvSnapshotReader::new(dir.path()).read_rows().unwrap();
+        assert_eq!(read.len(), 2);
     }
 
     #[test]
-    fn parquet_finish_required() {
-        // A Parquet file whose writer was NOT closed is invalid (missing footer).
-        // We verify that a dropped-without-finish writer produces an unreadable file.
-        let dir = tmp();
-        {
-            let mut w = ParquetWriter::new(dir.path()).unwrap();
-            w.write_snapshots(&[AgentSnapshotRow {
-                agent_id: 0, tick: 0, departure_node: 1, in_transit: false, destination_node: u32::MAX,
-            }]).unwrap();
-            // Drop without calling finish() — ArrowWriter's Drop will NOT write the footer.
-        }
+    fn integration_csv_output_cadence() {
+        use dt_agent::AgentStoreBuilder;
+        use dt_core::{AgentId, NodeId, SimConfig, Tick, TransportMode};
+        use dt_mobility::{MobilityEngine, Trip};
+        use dt_sim::SimObserver;
+        use dt_spatial::DijkstraRouter;
 
-        let file = std::fs::File::open(dir.path().join("agent_snapshots.parquet")).unwrap();
-        let result = ParquetRecordBatchReaderBuilder::try_new(file);
-        assert!(result.is_err(), "file without Parquet footer should fail to open");
+        use crate::observer::{OutputCadence, SimOutputObserver};
+
+        let (store, _) = AgentStoreBuilder::new(2, 1).build();
+        let mut engine = MobilityEngine::new(DijkstraRouter, 2);
+        engine.place(AgentId(0), NodeId(0), Tick(0));
+        engine.place(AgentId(1), NodeId(0), Tick(0));
+
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    3600,
+            total_ticks:           6,
+            seed:                  1,
+            num_threads:           Some(1),
+            output_interval_ticks: 1,
+            ..Default::default()
+        };
+        let dir = tmp();
+        let mut obs = SimOutputObserver::new(CsvWriter::new(dir.path()).unwrap(), &config)
+            .with_cadence(OutputCadence { snapshots: 3, tick_summaries: 2, contacts: 2, trips: 4 });
+        for t in 0..6 {
+            let tick = Tick(t);
+            obs.on_tick_start(tick);
+            obs.on_contacts(tick, AgentId(0), NodeId(0), &[AgentId(0), AgentId(1)]);
+            obs.on_trip(&Trip {
+                agent:       AgentId(1),
+                from:        NodeId(0),
+                to:          NodeId(1),
+                mode:        TransportMode::Walk,
+                depart_tick: tick,
+                arrive_tick: tick,
+                travel_secs: 60.0,
+                distance_m:  80.0,
+            });
+            obs.on_snapshot(tick, &engine.store, &store);
+            obs.on_tick_end(tick, 2);
+        }
+        obs.on_sim_end(Tick(6));
+        assert!(obs.take_error().is_none());
+
+        let ticks = |file: &str, column: &str| -> Vec<u64> {
+            let mut rdr = csv::Reader::from_path(dir.path().join(file)).unwrap();
+            let i = rdr.headers().unwrap().iter().position(|h| h == column).unwrap();
+            rdr.records().map(|r| r.unwrap()[i].parse().unwrap()).collect()
+        };
+        assert_eq!(ticks("agent_snapshots.csv", "tick"), [0, 0, 3, 3]);
+        assert_eq!(ticks("tick_summaries.csv", "tick"), [0, 2, 4]);
+        assert_eq!(ticks("contacts.csv", "tick"), [0, 2, 4]);
+        // Trips are buffered, never dropped.
+        assert_eq!(ticks("trips.csv", "depart_tick"), [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn cadence_from_config_section() {
+        use dt_core::OutputConfig;
+
+        use crate::observer::OutputCadence;
+
+        let section = OutputConfig { snapshots: 3, tick_summaries: 2, contacts: 0, trips: 24 };
+        assert_eq!(
+            OutputCadence::from(&section),
+            OutputCadence { snapshots: 3, tick_summaries: 2, contacts: 0, trips: 24 },
+        );
+        assert_eq!(OutputCadence::from(&OutputConfig::default()), OutputCadence::default());
+    }
+
+    #[test]
+    fn csv_extra_columns_validated() {
+        use crate::columns::{ColumnSpec, ColumnType};
+
+        let spec = |name: &str| ColumnSpec { name: name.into(), ty: ColumnType::Int };
+        let dir = tmp();
+        let mut w = CsvWriter::new(dir.path()).unwrap();
+        assert!(w.set_snapshot_columns(&[spec("tick")]).is_err());
+        assert!(w.set_snapshot_columns(&[spec("a"), spec("a")]).is_err());
+        assert!(w.set_snapshot_columns(&[spec("")]).is_err());
+
+        w.set_snapshot_columns(&[spec("a")]).unwrap();
+        w.write_snapshots(&[snap_row(0, 0)]).unwrap();
+        assert!(w.set_snapshot_columns(&[spec("b")]).is_err(), "columns are fixed once written");
+        w.finish().unwrap();
+
+        // Rows written without values get an empty field.
+        let mut rdr = csv::Reader::from_path(dir.path().join("agent_snapshots.csv")).unwrap();
+        let row = rdr.records().next().unwrap().unwrap();
+        assert_eq!(row.len(), 10);
+        assert_eq!(&row[9], "");
+    }
+
+    #[test]
+    fn csv_finish_idempotent() {
+        let dir = tmp();
+        let mut w = CsvWriter::new(dir.path()).unwrap();
+        w.finish().unwrap();
+        w.finish().unwrap(); // second call should not panic
+    }
+
+    #[test]
+    fn csv_empty_snapshot_ok() {
+        let dir = tmp();
+        let mut w = CsvWriter::new(dir.path()).unwrap();
+        w.write_snapshots(&[]).unwrap(); // should return Ok(())
+    }
+
+    #[test]
+    fn integration_csv() {
+        use dt_agent::AgentStoreBuilder;
+        use dt_behavior::NoopBehavior;
+        use dt_core::{NodeId, SimConfig};
+        use dt_sim::SimBuilder;
+        use dt_spatial::DijkstraRouter;
+
+        use crate::observer::SimOutputObserver;
+
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    3600,
+            total_ticks:           6,
+            seed:                  1,
+            num_threads:           Some(1),
+            output_interval_ticks: 2,
+            ..Default::default()
+        };
+
+        let (store, rngs) = AgentStoreBuilder::new(3, 1).build();
+        let mut sim = SimBuilder::new(config.clone(), store, rngs, NoopBehavior, DijkstraRouter)
+            .initial_positions(vec![NodeId(0), NodeId(1), NodeId(2)])
+            .build()
+            .unwrap();
+
+        let dir = tmp();
+        let writer = CsvWriter::new(dir.path()).unwrap();
+        let mut obs = SimOutputObserver::new(writer, &config);
+        sim.run(&mut obs).unwrap();
+        assert!(obs.take_error().is_none(), "no write errors expected");
+
+        // output_interval = 2 → snapshots fired at ticks 0, 2, 4 (3 ticks × 3 agents = 9 rows)
+        let mut rdr = csv::Reader::from_path(dir.path().join("agent_snapshots.csv")).unwrap();
+        let rows: Vec<_> = rdr.records().map(|r| r.unwrap()).collect();
+        assert_eq!(rows.len(), 9, "expected 3 ticks × 3 agents = 9 snapshot rows, got {}", rows.len());
+    }
+
+    #[test]
+    fn csv_snapshot_reader_picks_latest_tick() {
+        use dt_core::{AgentId, NodeId, Tick};
+        use dt_sim::SnapshotReader;
+
+        use crate::csv::CsvSnapshotReader;
+
+        let dir = tmp();
+        let mut w = CsvWriter::new(dir.path()).unwrap();
+        w.write_snapshots(&[snap_row(0, 2), snap_row(1, 2)]).unwrap();
+        let mut moving = snap_row(1, 4);
+        moving.in_transit       = true;
+        moving.destination_node = 7;
+        moving.departure_tick   = Some(3);
+        moving.arrival_tick     = Some(6);
+        w.write_snapshots(&[snap_row(0, 4), moving]).unwrap();
+        w.finish().unwrap();
+
+        let snap = CsvSnapshotReader::new(dir.path()).read_snapshot().unwrap();
+        assert_eq!(snap.tick, Tick(4));
+        assert_eq!(snap.agents.len(), 2);
+        assert_eq!(snap.agents[1].agent, AgentId(1));
+        assert!(snap.agents[1].in_transit);
+        assert_eq!(snap.agents[1].destination_node, NodeId(7));
+        assert_eq!((snap.agents[1].departure_tick, snap.agents[1].arrival_tick), (Some(Tick(3)), Some(Tick(6))));
+        assert_eq!(snap.agents[0].departure_tick, None);
+
+        let earlier = CsvSnapshotReader::new(dir.path()).at_tick(Tick(2)).read_snapshot().unwrap();
+        assert_eq!(earlier.tick, Tick(2));
+        assert!(earlier.agents.iter().all(|a| !a.in_transit));
+
+        assert!(CsvSnapshotReader::new(dir.path()).at_tick(Tick(3)).read_snapshot().is_err());
+    }
+
+    #[test]
+    fn csv_snapshot_reader_finds_columns_by_name() {
+        use crate::csv::CsvSnapshotReader;
+
+        let dir = tmp();
+        std::fs::write(
+            dir.path().join("agent_snapshots.csv"),
+            "score,lon,tick,agent_id,destination_node,in_transit,departure_node\n5,20.5,3,7,9,1,2\n",
+        ).unwrap();
+        let rows = CsvSnapshotReader::new(dir.path()).read_rows().unwrap();
+        assert_eq!((rows[0].agent_id, rows[0].tick, rows[0].departure_node), (7, 3, 2));
+        assert!(rows[0].in_transit);
+        assert_eq!(rows[0].destination_node, 9);
+        assert_eq!((rows[0].lat, rows[0].lon), (None, Some(20.5)));
+        assert_eq!(rows[0].departure_tick, None);
+
+        std::fs::write(dir.path().join("agent_snapshots.csv"), "agent_id,tick,in_transit,destination_node\n7,3,0,9\n")
+            .unwrap();
+        let err = CsvSnapshotReader::new(dir.path()).read_rows().unwrap_err().to_string();
+        assert!(err.contains("\"departure_node\""), "{err}");
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn csv_snapshot_reader_rejects_undecodable_compression() {
+        use crate::csv::CsvSnapshotReader;
+
+        let dir = tmp();
+        std::fs::write(dir.path().join("agent_snapshots.csv.gz"), b"\x1f\x8b").unwrap();
+        let err = CsvSnapshotReader::new(dir.path()).read_rows().unwrap_err().to_string();
+        assert!(err.contains("agent_snapshots.csv.gz") && err.contains("`gzip` feature"), "{err}");
+    }
+
+    #[test]
+    fn warm_start_from_csv_snapshot() {
+        use dt_agent::AgentStoreBuilder;
+        use dt_behavior::NoopBehavior;
+        use dt_core::{NodeId, SimConfig, Tick};
+        use dt_sim::SimBuilder;
+        use dt_spatial::DijkstraRouter;
+
+        use crate::csv::CsvSnapshotReader;
+        use crate::observer::SimOutputObserver;
+
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    3600,
+            total_ticks:           6,
+            seed:                  1,
+            num_threads:           Some(1),
+            output_interval_ticks: 2,
+            ..Default::default()
+        };
+
+        let (store, rngs) = AgentStoreBuilder::new(3, 1).build();
+        let mut sim = SimBuilder::new(config.clone(), store, rngs, NoopBehavior, DijkstraRouter)
+            .initial_positions(vec![NodeId(0), NodeId(1), NodeId(2)])
+            .build()
+            .unwrap();
+        let dir = tmp();
+        let mut obs = SimOutputObserver::new(CsvWriter::new(dir.path()).unwrap(), &config);
+        sim.run(&mut obs).unwrap();
+
+        let (store, rngs) = AgentStoreBuilder::new(3, 1).build();
+        let resumed = SimBuilder::new(config, store, rngs, NoopBehavior, DijkstraRouter)
+            .initial_state_from_snapshot(CsvSnapshotReader::new(dir.path()))
+            .build()
+            .unwrap();
+        assert_eq!(resumed.clock.current_tick, Tick(5));
+        assert_eq!(resumed.mobility.store.states[2].departure_node, NodeId(2));
+    }
+
+    #[test]
+    fn snapshot_coordinates_from_network() {
+        use dt_agent::AgentStoreBuilder;
+        use dt_core::{AgentId, GeoPoint, NodeId, SimConfig, Tick, TransportMode};
+        use dt_mobility::MobilityEngine;
+        use dt_sim::SimObserver;
+        use dt_spatial::{DijkstraRouter, RoadNetworkBuilder};
+
+        use crate::csv::CsvSnapshotReader;
+        use crate::observer::SimOutputObserver;
+
+        // Two nodes 0.01° apart; the road takes two one-hour ticks.
+        let mut b = RoadNetworkBuilder::new();
+        let n0 = b.add_node(GeoPoint { lat: 10.0, lon: 20.0 });
+        let n1 = b.add_node(GeoPoint { lat: 10.0, lon: 20.01 });
+        b.add_road(n0, n1, 1000.0, 7_200_000);
+        let net = b.build();
+
+        let mut engine = MobilityEngine::new(DijkstraRouter, 3);
+        engine.place(AgentId(0), NodeId(1), Tick(0));
+        engine.place(AgentId(1), NodeId(0), Tick(0));
+        engine.begin_travel(AgentId(1), NodeId(1), TransportMode::Car, Tick(0), 3600, &net).unwrap();
+        let (store, _) = AgentStoreBuilder::new(3, 1).build();
+
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    3600,
+            total_ticks:           4,
+            seed:                  1,
+            num_threads:           Some(1),
+            output_interval_ticks: 1,
+            ..Default::default()
+        };
+        let dir = tmp();
+        let mut obs = SimOutputObserver::new(CsvWriter::new(dir.path()).unwrap(), &config).with_network(&net);
+        obs.on_snapshot(Tick(1), &engine.store, &store);
+        obs.on_sim_end(Tick(1));
+        assert!(obs.take_error().is_none());
+
+        let rows = CsvSnapshotReader::new(dir.path()).read_rows().unwrap();
+        assert_eq!((rows[0].lat, rows[0].lon), (Some(10.0), Some(20.01)));
+        // Halfway along the road at tick 1.
+        assert!((rows[1].lon.unwrap() - 20.005).abs() < 1e-4);
+        // Journey ticks are recorded for the in-transit agent only.
+        assert_eq!((rows[1].departure_tick, rows[1].arrival_tick), (Some(0), Some(2)));
+        assert_eq!((rows[0].departure_tick, rows[0].arrival_tick), (None, None));
+        // Never placed: no position.
+        assert_eq!((rows[2].lat, rows[2].lon), (None, None));
+    }
+}
+
+// ── SQLite tests ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod memory_tests {
+    use dt_core::TransportMode;
+
+    use crate::columns::{ColumnSpec, ColumnType, ColumnValue};
+    use crate::memory::MemoryWriter;
+    use crate::row::{AgentSnapshotRow, OdRow, TickSummaryRow};
+    use crate::writer::OutputWriter;
+
+    fn snap(agent_id: u32, tick: u64) -> AgentSnapshotRow {
+        AgentSnapshotRow {
+            agent_id, tick, departure_node: 0, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+            departure_tick: None, arrival_tick: None,
+        }
+    }
+
+    #[test]
+    fn memory_collects_rows() {
+        let mut w = MemoryWriter::new();
+        w.set_snapshot_columns(&[
+            ColumnSpec { name: "infected".into(), ty: ColumnType::Bool },
+            ColumnSpec { name: "age".into(),      ty: ColumnType::Int },
+        ]).unwrap();
+        w.write_snapshots_with_columns(&[snap(0, 0), snap(1, 0)], &[vec![ColumnValue::Bool(true)]]).unwrap();
+        w.write_snapshots(&[snap(0, 24)]).unwrap();
+        assert!(w.set_snapshot_columns(&[]).is_err());
+        w.write_tick_summary(&TickSummaryRow { tick: 0, woken_agents: 2, ..Default::default() }).unwrap();
+        let od = OdRow { origin_zone: 0, dest_zone: 1, hour: 8, mode: TransportMode::Car, trips: 3 };
+        w.write_od_matrix(&[od]).unwrap();
+        w.finish().unwrap();
+
+        assert!(w.finished);
+        assert_eq!(w.snapshots_at(0).count(), 2);
+        assert_eq!(w.snapshots_at(24).map(|r| r.agent_id).collect::<Vec<_>>(), [0]);
+        assert_eq!(w.snapshot_value(0, "infected"), Some(&ColumnValue::Bool(true)));
+        assert_eq!(w.snapshot_value(1, "infected"), Some(&ColumnValue::Null));
+        assert_eq!(w.snapshot_value(2, "age"), Some(&ColumnValue::Null));
+        assert_eq!(w.snapshot_value(0, "missing"), None);
+        assert_eq!(w.tick_summaries[0].woken_agents, 2);
+        assert_eq!(w.od_matrix, [od]);
+    }
+
+    #[test]
+    fn boxed_writer_forwards() {
+        let mut w: Box<dyn OutputWriter> = Box::new(MemoryWriter::new());
+        w.write_snapshots(&[snap(0, 0)]).unwrap();
+        w.write_tick_summary(&TickSummaryRow { tick: 0, ..Default::default() }).unwrap();
+        assert!(w.output_dir().is_none());
+        w.finish().unwrap();
+        // `Box<dyn OutputWriter>` is itself a writer, so it fits the observer.
+        let obs = crate::observer::SimOutputObserver::new(w, &dt_core::SimConfig::default());
+        assert!(obs.into_writer().output_dir().is_none());
+    }
+
+    #[test]
+    fn memory_integration_run() {
+        use dt_agent::AgentStoreBuilder;
+        use dt_behavior::{BehaviorModel, Intent, SimContext};
+        use dt_core::{ActivityId, AgentId, AgentRng, GeoPoint, NodeId, SimConfig};
+        use dt_schedule::{ActivityPlan, Destination, ScheduledActivity};
+        use dt_sim::SimBuilder;
+        use dt_spatial::{DijkstraRouter, RoadNetworkBuilder};
+
+        use crate::observer::SimOutputObserver;
+
+        /// Walks to node 1 at its first wake and back home at the next.
+        struct Shuttle;
+        impl BehaviorModel for Shuttle {
+            fn replan(&self, _a: AgentId, ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+                let destination = if ctx.tick.0 % 4 == 1 { NodeId(1) } else { NodeId(0) };
+                vec![Intent::TravelTo { destination, mode: TransportMode::Walk }]
+            }
+        }
+
+        let mut b = RoadNetworkBuilder::new();
+        let n0 = b.add_node(GeoPoint { lat: 0.0, lon: 0.0 });
+        let n1 = b.add_node(GeoPoint { lat: 0.0, lon: 0.01 });
+        b.add_road(n0, n1, 1100.0, 80_000);
+        let network = b.build();
+
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    3600,
+            total_ticks:           5,
+            seed:                  1,
+            num_threads:           Some(1),
+            output_interval_ticks: 5,
+            ..Default::default()
+        };
+        let plan = ActivityPlan::new(vec![ScheduledActivity {
+            start_offset_ticks: 0,
+            duration_ticks:     1,
+            activity_id:        ActivityId(0),
+            destination:        Destination::Home,
+        }], 1);
+        let mut obs = SimOutputObserver::new(MemoryWriter::new(), &config)
+            .with_network(&network)
+            .with_od_matrix(vec![0, 1])
+            .with_link_volumes(&network, 2, &[])
+            .with_routes(1.0)
+            .with_run_summary();
+        let (store, rngs) = AgentStoreBuilder::new(1, 1).build();
+        let mut sim = SimBuilder::new(config, store, rngs, Shuttle, DijkstraRouter)
+            .plans(vec![plan])
+            .network(network)
+            .initial_positions(vec![NodeId(0)])
+            .build()
+            .unwrap();
+        sim.run(&mut obs).unwrap();
+        assert!(obs.take_error().is_none());
+        let summary = obs.run_summary().unwrap();
+        assert_eq!((summary.trips(), summary.departures()), (2, 2));
+        assert_eq!(summary.distance_m(), 2200.0);
+        assert_eq!(summary.peak_in_transit(), Some((1, 1)));
+        let walk = summary.modes()[0];
+        assert_eq!((walk.mode, walk.trips), (TransportMode::Walk, 2));
+        // Both legs take the same road.
+        assert_eq!(walk.mean_travel_secs, walk.max_travel_secs as f64);
+
+        let out = obs.into_writer();
+        assert!(out.finished);
+        assert_eq!(out.tick_summaries.iter().map(|r| r.tick).collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+        assert_eq!(out.tick_summaries[1].departures, 1);
+        let legs: Vec<(u64, u32, u32)> = out.trips.iter().map(|t| (t.depart_tick, t.from, t.to)).collect();
+        assert_eq!(legs, [(1, 0, 1), (3, 1, 0)]);
+        assert_eq!(out.snapshots_at(0).next().unwrap().lat, Some(0.0));
+        assert_eq!(out.od_matrix.iter().map(|r| r.trips).sum::<u64>(), 2);
+        // One vehicle on each direction of the road, in intervals [0, 2) and [2, 4).
+        let volumes: Vec<(u64, u64)> = out.link_volumes.iter().map(|r| (r.tick, r.vehicles)).collect();
+        assert_eq!(volumes, [(0, 1), (2, 1)]);
+        assert_ne!(out.link_volumes[0].edge, out.link_volumes[1].edge);
+        let routes: Vec<(u64, u32, u32, usize)> =
+            out.routes.iter().map(|r| (r.depart_tick, r.from, r.to, r.edges.len())).collect();
+        assert_eq!(routes, [(1, 0, 1, 1), (3, 1, 0, 1)]);
+    }
+}
+
+#[cfg(all(test, feature = "arrow"))]
+mod memory_arrow_tests {
+    use arrow::array::{AsArray, RecordBatch};
+    use arrow::datatypes::{Int64Type, UInt64Type};
+
+    use crate::columns::{ColumnSpec, ColumnType, ColumnValue};
+    use crate::memory::MemoryWriter;
+    use crate::row::{AgentSnapshotRow, TickSummaryRow};
+    use crate::writer::OutputWriter;
+
+    fn names(batch: &RecordBatch) -> Vec<String> {
+        batch.schema().fields().iter().map(|f| f.name().clone()).collect()
+    }
+
+    #[test]
+    fn tables_as_record_batches() {
+        let mut w = MemoryWriter::new();
+        w.set_snapshot_columns(&[ColumnSpec { name: "age".into(), ty: ColumnType::Int }]).unwrap();
+        let snap = |agent_id| AgentSnapshotRow {
+            agent_id, tick: 0, departure_node: 0, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+            departure_tick: None, arrival_tick: None,
+        };
+        w.write_snapshots_with_columns(&[snap(0), snap(1)], &[vec![ColumnValue::Int(30)]]).unwrap();
+        for tick in 0..3 {
+            w.write_tick_summary(&TickSummaryRow { tick, woken_agents: tick * 2, ..Default::default() }).unwrap();
+        }
+
+        let snapshots = w.snapshot_batch().unwrap();
+        assert_eq!(snapshots.num_rows(), 2);
+        assert_eq!(names(&snapshots).last().map(String::as_str), Some("age"));
+        let ages = snapshots.column_by_name("age").unwrap().as_primitive::<Int64Type>();
+        assert_eq!(ages.iter().collect::<Vec<_>>(), [Some(30), None]);
+
+        let summaries = w.tick_summary_batch().unwrap();
+        assert_eq!(summaries.num_rows(), 3);
+        let woken = summaries.column_by_name("woken_agents").unwrap().as_primitive::<UInt64Type>();
+        assert_eq!(woken.values().to_vec(), [0, 2, 4]);
+
+        assert_eq!(names(&w.trip_batch().unwrap())[0], "agent");
+        for empty in [w.contact_batch(), w.trip_batch(), w.route_batch(), w.od_batch(), w.link_volume_batch()] {
+            assert_eq!(empty.unwrap().num_rows(), 0);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod sqlite_tests {
+    use tempfile::TempDir;
+
+    use crate::row::{AgentSnapshotRow, ContactRow, TickSummaryRow, TripRow};
+    use crate::sqlite::{SqliteOptions, SqliteSynchronous, SqliteWriter};
+    use crate::writer::OutputWriter;
+
+    fn tmp() -> TempDir {
+        tempfile::tempdir().expect("create temp dir")
+    }
+
+    #[test]
+    fn sqlite_db_created() {
+        let dir = tmp();
+        let _w = SqliteWriter::new(dir.path()).unwrap();
+        assert!(dir.path().join("output.db").exists());
+    }
+
+    #[test]
+    fn sqlite_snapshot_count() {
+        let dir = tmp();
+        let mut w = SqliteWriter::new(dir.path()).unwrap();
+        let rows = vec![
+            AgentSnapshotRow {
+                agent_id: 0, tick: 1, departure_node: 10, in_transit: false, destination_node: u32::MAX,
+                lat: None, lon: None,
+                departure_tick: None, arrival_tick: None,
+            },
+            AgentSnapshotRow {
+                agent_id: 1, tick: 1, departure_node: 11, in_transit: true,  destination_node: 20,
+                lat: None, lon: None,
+                departure_tick: None, arrival_tick: None,
+            },
+            AgentSnapshotRow {
+                agent_id: 2, tick: 1, departure_node: 12, in_transit: false, destination_node: u32::MAX,
+                lat: None, lon: None,
+                departure_tick: None, arrival_tick: None,
+            },
+        ];
+        w.write_snapshots(&rows).unwrap();
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM agent_snapshots", [], |r| r.get(0)
+        ).unwrap();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn sqlite_in_transit_as_integer() {
+        let dir = tmp();
+        let mut w = SqliteWriter::new(dir.path()).unwrap();
+        w.write_snapshots(&[AgentSnapshotRow {
+            agent_id: 0, tick: 0, departure_node: 5, in_transit: true, destination_node: 9, lat: None, lon: None,
+            departure_tick: None, arrival_tick: None,
+        }]).unwrap();
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let val: i64 = conn.query_row(
+            "SELECT in_transit FROM agent_snapshots WHERE agent_id = 0", [], |r| r.get(0)
+        ).unwrap();
+        assert_eq!(val, 1, "in_transit=true should be stored as 1");
+    }
+
+    #[test]
+    fn sqlite_invalid_node_stored() {
+        let dir = tmp();
+        let mut w = SqliteWriter::new(dir.path()).unwrap();
+        w.write_snapshots(&[AgentSnapshotRow {
+            agent_id: 0, tick: 0, departure_node: u32::MAX, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+            departure_tick: None, arrival_tick: None,
+        }]).unwrap();
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        // SQLite INTEGER is signed 64-bit; u32::MAX fits without loss.
+        let val: i64 = conn.query_row(
+            "SELECT departure_node FROM agent_snapshots WHERE agent_id = 0", [], |r| r.get(0)
+        ).unwrap();
+        assert_eq!(val, u32::MAX as i64);
+    }
+
+    #[test]
+    fn sqlite_tick_summary() {
+        let dir = tmp();
+        let mut w = SqliteWriter::new(dir.path()).unwrap();
+        w.write_tick_summary(&TickSummaryRow {
+            tick: 7, unix_time_secs: 25_200, woken_agents: 42,
+            ..Default::default()
+        }).unwrap();
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let (tick, unix_time, woken): (i64, i64, i64) = conn.query_row(
+            "SELECT tick, unix_time_secs, woken_agents FROM tick_summaries WHERE tick = 7",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        ).unwrap();
+        assert_eq!(tick, 7);
+        assert_eq!(unix_time, 25_200);
+        assert_eq!(woken, 42);
+    }
+
+    #[test]
+    fn sqlite_contacts() {
+        let dir = tmp();
+        let mut w = SqliteWriter::new(dir.path()).unwrap();
+        w.write_contacts(&[
+            ContactRow { tick: 2, agent_a: 0, agent_b: 1, node: 9 },
+            ContactRow { tick: 2, agent_a: 1, agent_b: 0, node: 9 },
+        ]).unwrap();
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let (count, node): (i64, i64) = conn.query_row(
+            "SELECT COUNT(*), MAX(node) FROM contacts WHERE tick = 2",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        ).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(node, 9);
+    }
+
+    #[test]
+    fn sqlite_extra_columns() {
+        use crate::columns::{ColumnSpec, ColumnType, ColumnValue};
+
+        let dir = tmp();
+        let mut w = SqliteWriter::new(dir.path()).unwrap();
+        w.set_snapshot_columns(&[
+            ColumnSpec { name: "infected".into(), ty: ColumnType::Bool },
+            ColumnSpec { name: "zone".into(),     ty: ColumnType::Text },
+        ]).unwrap();
+        let row = AgentSnapshotRow {
+            agent_id: 4, tick: 1, departure_node: 0, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+            departure_tick: None, arrival_tick: None,
+        };
+        w.write_snapshots_with_columns(&[row], &[
+            vec![ColumnValue::Bool(true)],
+            vec![ColumnValue::Text("north".into())],
+        ]).unwrap();
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let (infected, zone): (i64, String) = conn.query_row(
+            "SELECT infected, zone FROM agent_snapshots WHERE agent_id = 4",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        ).unwrap();
+        assert_eq!(infected, 1);
+        assert_eq!(zone, "north");
+    }
+
+    #[test]
+    fn sqlite_trips() {
+        let dir = tmp();
+        let mut w = SqliteWriter::new(dir.path()).unwrap();
+        w.write_trips(&[TripRow {
+            agent:       5,
+            depart_tick: 1,
+            arrive_tick: 3,
+            from:        0,
+            to:          7,
+            mode:        dt_core::TransportMode::Transit,
+            travel_secs: 6000.0,
+            distance_m:  21_000.0,
+        }]).unwrap();
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let (to, mode, distance): (i64, String, f64) = conn.query_row(
+            "SELECT to_node, mode, distance_m FROM trips WHERE agent = 5",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        ).unwrap();
+        assert_eq!(to, 7);
+        assert_eq!(mode, "transit");
+        assert_eq!(distance, 21_000.0);
+    }
+
+    fn count(dir: &TempDir, table: &str) -> i64 {
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |r| r.get(0)).unwrap()
+    }
+
+    fn snap(agent_id: u32) -> AgentSnapshotRow {
+        AgentSnapshotRow {
+            agent_id, tick: 0, departure_node: 1, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+            departure_tick: None, arrival_tick: None,
+        }
+    }
+
+    #[test]
+    fn sqlite_tick_summary_counts() {
+        let dir = tmp();
+        let mut w = SqliteWriter::new(dir.path()).unwrap();
+        w.write_tick_summary(&TickSummaryRow {
+            tick: 2, unix_time_secs: 7200, woken_agents: 5, arrivals: 3, messages_sent: 4, behavior_panics: 1,
+            ..Default::default()
+        }).unwrap();
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let row: (i64, i64, i64, i64) = conn.query_row(
+            "SELECT arrivals, messages_sent, behavior_panics, contacts FROM tick_summaries WHERE tick = 2",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+        ).unwrap();
+        assert_eq!(row, (3, 4, 1, 0));
+    }
+
+    #[test]
+    fn sqlite_old_tick_summaries_gain_counts() {
+        let dir = tmp();
+        {
+            let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE tick_summaries (
+                     tick           INTEGER PRIMARY KEY,
+                     unix_time_secs INTEGER NOT NULL,
+                     woken_agents   INTEGER NOT NULL
+                 );
+                 INSERT INTO tick_summaries VALUES (0, 0, 9);",
+            ).unwrap();
+        }
+        let mut w = SqliteWriter::new(dir.path()).unwrap();
+        w.write_tick_summary(&TickSummaryRow { tick: 1, unix_time_secs: 3600, departures: 2, ..Default::default() })
+            .unwrap();
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let departures: Vec<i64> = conn
+            .prepare("SELECT departures FROM tick_summaries ORDER BY tick").unwrap()
+            .query_map([], |r| r.get(0)).unwrap()
+            .collect::<Result<_, _>>().unwrap();
+        assert_eq!(departures, [0, 2]);
+    }
+
+    #[test]
+    fn sqlite_od_matrix() {
+        use dt_core::TransportMode;
+
+        use crate::row::OdRow;
+
+        let dir = tmp();
+        let mut w = SqliteWriter::new(dir.path()).unwrap();
+        w.write_od_matrix(&[OdRow { origin_zone: 3, dest_zone: 4, hour: 7, mode: TransportMode::Transit, trips: 12 }])
+            .unwrap();
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let (hour, mode, trips): (i64, String, i64) = conn.query_row(
+            "SELECT hour, mode, trips FROM od_matrix WHERE origin_zone = 3 AND dest_zone = 4",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        ).unwrap();
+        assert_eq!((hour, mode.as_str(), trips), (7, "transit", 12));
+    }
+
+    #[test]
+    fn sqlite_link_volumes() {
+        use crate::row::LinkVolumeRow;
+
+        let dir = tmp();
+        let mut w = SqliteWriter::new(dir.path()).unwrap();
+        w.write_link_volumes(&[LinkVolumeRow { tick: 0, edge: 5, vehicles: 3 }]).unwrap();
+        w.write_link_volumes(&[LinkVolumeRow { tick: 60, edge: 5, vehicles: 1 }]).unwrap();
+        w.finish().unwrap();
+
+        assert_eq!(count(&dir, "link_volumes"), 2);
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let total: i64 = conn
+            .query_row("SELECT SUM(vehicles) FROM link_volumes WHERE edge_id = 5", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(total, 4);
+    }
+
+    #[test]
+    fn sqlite_batch_spans_calls() {
+        let dir = tmp();
+        let options = SqliteOptions { batch_size: 3, ..SqliteOptions::default() };
+        let mut w = SqliteWriter::new_with(dir.path(), options).unwrap();
+        w.write_snapshots(&[snap(0), snap(1)]).unwrap();
+        assert_eq!(count(&dir, "agent_snapshots"), 0, "batch not yet full");
+
+        w.write_tick_summary(&TickSummaryRow { tick: 0, unix_time_secs: 0, woken_agents: 2, ..Default::default() }).unwrap();
+        assert_eq!(count(&dir, "agent_snapshots"), 2);
+        assert_eq!(count(&dir, "tick_summaries"), 1);
+
+        w.write_snapshots(&[snap(2)]).unwrap();
+        w.finish().unwrap();
+        assert_eq!(count(&dir, "agent_snapshots"), 3);
+    }
+
+    #[test]
+    fn sqlite_batch_size_zero_commits_each_call() {
+        let dir = tmp();
+        let options = SqliteOptions { batch_size: 0, statement_cache: 0, ..SqliteOptions::default() };
+        let mut w = SqliteWriter::new_with(dir.path(), options).unwrap();
+        w.write_snapshots(&[snap(0)]).unwrap();
+        assert_eq!(count(&dir, "agent_snapshots"), 1);
+        w.write_snapshots(&[snap(1)]).unwrap();
+        assert_eq!(count(&dir, "agent_snapshots"), 2);
+    }
+
+    #[test]
+    fn sqlite_drop_commits_pending_rows() {
+        let dir = tmp();
+        {
+            let mut w = SqliteWriter::new(dir.path()).unwrap();
+            w.write_snapshots(&[snap(0), snap(1)]).unwrap();
+        }
+        assert_eq!(count(&dir, "agent_snapshots"), 2);
+    }
+
+    #[test]
+    fn sqlite_page_size_applied() {
+        let dir = tmp();
+        let options = SqliteOptions {
+            page_size:   8192,
+            synchronous: SqliteSynchronous::Off,
+            cache_size:  500,
+            ..SqliteOptions::default()
+        };
+        let mut w = SqliteWriter::new_with(dir.path(), options).unwrap();
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let page_size: i64 = conn.query_row("PRAGMA page_size", [], |r| r.get(0)).unwrap();
+        assert_eq!(page_size, 8192);
+    }
+
+    fn index_names(dir: &TempDir) -> Vec<String> {
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        conn.prepare("SELECT name FROM sqlite_master WHERE type = 'index' AND sql IS NOT NULL ORDER BY name")
+            .unwrap()
+            .query_map([], |r| r.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn sqlite_indexes_created_on_finish() {
+        let dir = tmp();
+        let options = SqliteOptions { create_indexes: true, ..SqliteOptions::default() };
+        let mut w = SqliteWriter::new_with(dir.path(), options).unwrap();
+        w.write_snapshots(&[snap(0), snap(1)]).unwrap();
+        assert!(index_names(&dir).is_empty(), "no indexes while writing");
+        w.finish().unwrap();
+        assert_eq!(index_names(&dir), [
+            "agent_snapshots_agent_tick", "agent_snapshots_tick", "contacts_tick", "trips_agent_depart",
+        ]);
+
+        let dir = tmp();
+        SqliteWriter::new(dir.path()).unwrap().finish().unwrap();
+        assert!(index_names(&dir).is_empty(), "off by default");
+    }
+
+    #[test]
+    fn sqlite_without_rowid_snapshots() {
+        let dir = tmp();
+        let options = SqliteOptions { without_rowid: true, create_indexes: true, ..SqliteOptions::default() };
+        let mut w = SqliteWriter::new_with(dir.path(), options).unwrap();
+        w.write_snapshots(&[snap(1), snap(0)]).unwrap();
+        assert!(w.write_snapshots(&[snap(0)]).is_err(), "duplicate (agent_id, tick)");
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let sql: String = conn
+            .query_row("SELECT sql FROM sqlite_master WHERE name = 'agent_snapshots'", [], |r| r.get(0))
+            .unwrap();
+        assert!(sql.ends_with("WITHOUT ROWID"), "{sql}");
+        // The primary key already covers (agent_id, tick).
+        assert!(!index_names(&dir).contains(&"agent_snapshots_agent_tick".to_owned()));
+
+        // Reopening with default options keeps the existing layout.
+        let mut w = SqliteWriter::new_with(dir.path(), SqliteOptions { create_indexes: true, ..SqliteOptions::default() })
+            .unwrap();
+        w.finish().unwrap();
+        assert!(!index_names(&dir).contains(&"agent_snapshots_agent_tick".to_owned()));
+    }
+
+    #[test]
+    fn sqlite_run_info() {
+        use dt_core::SimConfig;
+
+        let dir = tmp();
+        let mut w = SqliteWriter::new(dir.path()).unwrap();
+        let config = SimConfig {
+            start_unix_secs:       1_700_000_000,
+            tick_duration_secs:    60,
+            total_ticks:           1440,
+            seed:                  7,
+            num_threads:           None,
+            output_interval_ticks: 60,
+            ..Default::default()
+        };
+        w.set_run_config(&config).unwrap();
+        w.set_run_info("scenario", "baseline").unwrap();
+        w.set_run_info("seed", 8).unwrap();
+        w.finish().unwrap();
+
+        let conn = rusqlite::Connection::open(dir.path().join("output.db")).unwrap();
+        let info: std::collections::BTreeMap<String, String> = conn
+            .prepare("SELECT key, value FROM run_info")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(info["start_unix_secs"], "1700000000");
+        assert_eq!(info["tick_duration_secs"], "60");
+        assert_eq!(info["total_ticks"], "1440");
+        assert_eq!(info["seed"], "8", "later value replaces earlier");
+        assert_eq!(info["scenario"], "baseline");
+        assert_eq!(info["dt_output_version"], env!("CARGO_PKG_VERSION"));
+        assert!(!info.contains_key("num_threads"));
+    }
+}
+
+// ── Parquet tests ─────────────────────────────────────────────────────────────
+
+#[cfg(all(test, feature = "parquet"))]
+mod parquet_tests {
+    use tempfile::TempDir;
+
+    use arrow::datatypes::DataType;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use crate::parquet::ParquetWriter;
+    use crate::row::{AgentSnapshotRow, ContactRow, TripRow};
+    use crate::writer::OutputWriter;
+
+    fn tmp() -> TempDir {
+        tempfile::tempdir().expect("create temp dir")
+    }
+
+    #[test]
+    fn parquet_files_created() {
+        let dir = tmp();
+        let mut w = ParquetWriter::new(dir.path()).unwrap();
+        w.finish().unwrap();
+        assert!(dir.path().join("agent_snapshots.parquet").exists());
+        assert!(dir.path().join("tick_summaries.parquet").exists());
+        assert!(dir.path().join("contacts.parquet").exists());
+        assert!(dir.path().join("trips.parquet").exists());
+    }
+
+    #[test]
+    fn parquet_contacts_round_trip() {
+        let dir = tmp();
+        let mut w = ParquetWriter::new(dir.path()).unwrap();
+        w.write_contacts(&[
+            ContactRow { tick: 3, agent_a: 0, agent_b: 1, node: 4 },
+            ContactRow { tick: 3, agent_a: 1, agent_b: 0, node: 4 },
+        ]).unwrap();
+        w.finish().unwrap();
+
+        let file = std::fs::File::open(dir.path().join("contacts.parquet")).unwrap();
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+        let field_names: Vec<String> = builder.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(field_names, ["tick", "agent_a", "agent_b", "node"]);
+        let total_rows: usize = builder.build().unwrap().map(|b| b.unwrap().num_rows()).sum();
+        assert_eq!(total_rows, 2);
+    }
+
+    #[test]
+    fn parquet_snapshot_round_trip() {
+        let dir = tmp();
+        let mut w = ParquetWriter::new(dir.path()).unwrap();
+        let rows = vec![
+            AgentSnapshotRow {
+                agent_id: 0, tick: 2, departure_node: 10, in_transit: false, destination_node: u32::MAX,
+                lat: None, lon: None,
+                departure_tick: None, arrival_tick: None,
+            },
+            AgentSnapshotRow {
+                agent_id: 1, tick: 2, departure_node: 11, in_transit: true,  destination_node: 20,
+                lat: None, lon: None,
+                departure_tick: None, arrival_tick: None,
+            },
+        ];
+        w.write_snapshots(&rows).unwrap();
+        w.finish().unwrap();
+
+        let file = std::fs::File::open(dir.path().join("agent_snapshots.parquet")).unwrap();
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+        let schema = builder.schema().clone();
+        let reader = builder.build().unwrap();
+
+        let batches: Vec<_> = reader.map(|b| b.unwrap()).collect();
+        let total_rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(total_rows, 2, "expected 2 rows");
+
+        // Check schema field names
+        let field_names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(field_names, ["agent_id", "tick", "departure_node", "in_transit", "destination_node", "lat", "lon",
+                             "departure_tick", "arrival_tick"]);
+    }
+
+    #[test]
+    fn parquet_routes_are_edge_lists() {
+        use arrow::array::{Array, ListArray, UInt32Array};
+
+        use crate::row::RouteRow;
+
+        let dir = tmp();
+        let mut w = ParquetWriter::new(dir.path()).unwrap();
+        let route = |agent, edges: &[u32]| RouteRow {
+            agent, depart_tick: 5, from: 0, to: 2, mode: dt_core::TransportMode::Bike, edges: edges.to_vec(),
+        };
+        w.write_routes(&[route(0, &[4, 9]), route(1, &[])]).unwrap();
+        w.finish().unwrap();
+
+        let file = std::fs::File::open(dir.path().join("routes.parquet")).unwrap();
+        let batch = ParquetRecordBatchReaderBuilder::try_new(file).unwrap().build().unwrap().next().unwrap().unwrap();
+        let edges = batch.column(5).as_any().downcast_ref::<ListArray>().unwrap();
+        let first = edges.value(0);
+        assert_eq!(first.as_any().downcast_ref::<UInt32Array>().unwrap().values(), &[4, 9]);
+        assert_eq!(edges.value(1).len(), 0);
+    }
+
+    #[test]
+    fn parquet_trip_mode_is_string() {
+        let dir = tmp();
+        let mut w = ParquetWriter::new(dir.path()).unwrap();
+        w.write_trips(&[TripRow {
+            agent:       0,
+            depart_tick: 0,
+            arrive_tick: 1,
+            from:        0,
+            to:          1,
+            mode:        dt_core::TransportMode::Car,
+            travel_secs: 60.0,
+            distance_m:  500.0,
+        }]).unwrap();
+        w.finish().unwrap();
+
+        let file = std::fs::File::open(dir.path().join("trips.parquet")).unwrap();
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+        let mode_field = builder.schema().field_with_name("mode").unwrap().clone();
+        assert_eq!(*mode_field.data_type(), DataType::Utf8);
+        let total_rows: usize = builder.build().unwrap().map(|b| b.unwrap().num_rows()).sum();
+        assert_eq!(total_rows, 1);
+    }
+
+    #[test]
+    fn parquet_extra_columns() {
+        use arrow::array::{Array, Float64Array};
+
+        use crate::columns::{ColumnSpec, ColumnType, ColumnValue};
+
+        let dir = tmp();
+        let mut w = ParquetWriter::new(dir.path()).unwrap();
+        w.set_snapshot_columns(&[ColumnSpec { name: "wealth".into(), ty: ColumnType::Float }]).unwrap();
+        let row = |agent_id| AgentSnapshotRow {
+            agent_id, tick: 0, departure_node: 1, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+            departure_tick: None, arrival_tick: None,
+        };
+        w.write_snapshots_with_columns(&[row(0), row(1)], &[
+            vec![ColumnValue::Float(2.5), ColumnValue::Null],
+        ]).unwrap();
+        w.finish().unwrap();
+
+        let file = std::fs::File::open(dir.path().join("agent_snapshots.parquet")).unwrap();
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+        let field = builder.schema().field_with_name("wealth").unwrap().clone();
+        assert_eq!(*field.data_type(), DataType::Float64);
+        assert!(field.is_nullable());
+
+        let batch = builder.build().unwrap().next().unwrap().unwrap();
+        let wealth = batch.column_by_name("wealth").unwrap();
+        let wealth = wealth.as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(wealth.value(0), 2.5);
+        assert!(wealth.is_null(1));
+    }
+
+    #[test]
+    fn parquet_boolean_column_type() {
+        let dir = tmp();
+        let mut w = ParquetWriter::new(dir.path()).unwrap();
+        w.write_snapshots(&[AgentSnapshotRow {
+            agent_id: 0, tick: 0, departure_node: 1, in_transit: true, destination_node: 2, lat: None, lon: None,
+            departure_tick: None, arrival_tick: None,
+        }]).unwrap();
+        w.finish().unwrap();
+
+        let file = std::fs::File::open(dir.path().join("agent_snapshots.parquet")).unwrap();
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+        let schema = builder.schema().clone();
+
+        let in_transit_field = schema.field_with_name("in_transit").unwrap();
+        assert_eq!(*in_transit_field.data_type(), DataType::Boolean);
+    }
+
+    #[test]
+    fn parquet_od_matrix() {
+        use arrow::array::{StringArray, UInt8Array};
+        use dt_core::TransportMode;
+
+        use crate::row::OdRow;
+
+        let dir = tmp();
+        let mut w = ParquetWriter::new(dir.path()).unwrap();
+        w.write_od_matrix(&[
+            OdRow { origin_zone: 0, dest_zone: 1, hour: 6,  mode: TransportMode::Car,  trips: 4 },
+            OdRow { origin_zone: 1, dest_zone: 0, hour: 18, mode: TransportMode::Walk, trips: 2 },
+        ]).unwrap();
+        w.finish().unwrap();
+
+        let file = std::fs::File::open(dir.path().join("od_matrix.parquet")).unwrap();
+        let batch = ParquetRecordBatchReaderBuilder::try_new(file).unwrap().build().unwrap().next().unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        let hours = batch.column_by_name("hour").unwrap().as_any().downcast_ref::<UInt8Array>().unwrap();
+        assert_eq!(hours.value(1), 18);
+        let modes = batch.column_by_name("mode").unwrap().as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(modes.value(0), "car");
+    }
+
+    #[test]
+    fn parquet_finish_required() {
+        // A Parquet file whose writer was NOT closed is invalid (missing footer).
+        // We verify that a dropped-without-finish writer produces an unreadable file.
+        let dir = tmp();
+        {
+            let mut w = ParquetWriter::new(dir.path()).unwrap();
+            w.write_snapshots(&[AgentSnapshotRow {
+                agent_id: 0, tick: 0, departure_node: 1, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+                departure_tick: None, arrival_tick: None,
+            }]).unwrap();
+            // Drop without calling finish() — ArrowWriter's Drop will NOT write the footer.
+        }
+
+        let file = std::fs::File::open(dir.path().join("agent_snapshots.parquet")).unwrap();
+        let result = ParquetRecordBatchReaderBuilder::try_new(file);
+        assert!(result.is_err(), "file without Parquet footer should fail to open");
+    }
+}
+
+#[cfg(all(test, feature = "gzip"))]
+mod gzip_tests {
+    use std::io::Read;
+
+    use crate::columns::{ColumnSpec, ColumnType, ColumnValue};
+    use crate::csv::{Compression, CsvWriter};
+    use crate::row::{AgentSnapshotRow, TickSummaryRow};
+    use crate::writer::OutputWriter;
+
+    fn gunzip(path: &std::path::Path) -> String {
+        let mut text = String::new();
+        flate2::read::GzDecoder::new(std::fs::File::open(path).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    }
+
+    #[test]
+    fn gzip_files_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = CsvWriter::new_compressed(dir.path(), Compression::Gzip).unwrap();
+        w.write_snapshots(&[AgentSnapshotRow {
+            agent_id: 7, tick: 3, departure_node: 2, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+            departure_tick: None, arrival_tick: None,
+        }]).unwrap();
+        w.write_tick_summary(&TickSummaryRow { tick: 3, unix_time_secs: 10800, woken_agents: 1, ..Default::default() }).unwrap();
+        w.finish().unwrap();
+
+        assert!(!dir.path().join("agent_snapshots.csv").exists());
+        assert_eq!(
+            gunzip(&dir.path().join("agent_snapshots.csv.gz")),
+            "agent_id,tick,departure_node,in_transit,destination_node,lat,lon,departure_tick,arrival_tick\n7,3,2,0,4294967295,,,,\n",
+        );
+        assert_eq!(
+            gunzip(&dir.path().join("tick_summaries.csv.gz")),
+            format!("tick,unix_time_secs,{}\n3,10800,1{}\n", TickSummaryRow::COUNT_COLUMNS.join(","), ",0".repeat(11)),
+        );
+        assert_eq!(gunzip(&dir.path().join("contacts.csv.gz")), "tick,agent_a,agent_b,node\n");
+    }
+
+    #[test]
+    fn gzip_extra_columns_restart_stream() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = CsvWriter::new_compressed(dir.path(), Compression::Gzip).unwrap();
+        w.set_snapshot_columns(&[ColumnSpec { name: "score".into(), ty: ColumnType::Int }]).unwrap();
+        w.write_snapshots_with_columns(
+            &[AgentSnapshotRow {
+                agent_id: 0, tick: 0, departure_node: 1, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+                departure_tick: None, arrival_tick: None,
+            }],
+            &[vec![ColumnValue::Int(5)]],
+        ).unwrap();
+        w.finish().unwrap();
+
+        let text = gunzip(&dir.path().join("agent_snapshots.csv.gz"));
+        assert_eq!(text.lines().collect::<Vec<_>>(), [
+            "agent_id,tick,departure_node,in_transit,destination_node,lat,lon,departure_tick,arrival_tick,score",
+            "0,0,1,0,4294967295,,,,,5",
+        ]);
+    }
+    #[test]
+    fn gzip_snapshots_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = CsvWriter::new_compressed(dir.path(), Compression::Gzip).unwrap();
+        w.write_snapshots(&[AgentSnapshotRow {
+            agent_id: 7, tick: 3, departure_node: 2, in_transit: true, destination_node: 5, lat: None, lon: None,
+            departure_tick: Some(1), arrival_tick: Some(4),
+        }]).unwrap();
+        w.finish().unwrap();
+
+        let rows = crate::csv::CsvSnapshotReader::new(dir.path()).read_rows().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!((rows[0].agent_id, rows[0].destination_node, rows[0].arrival_tick), (7, 5, Some(4)));
+    }
+}
+
+#[cfg(all(test, feature = "zstd"))]
+mod zstd_tests {
+    use crate::csv::{Compression, CsvWriter};
+    use crate::row::{AgentSnapshotRow, ContactRow};
+    use crate::writer::OutputWriter;
+
+    #[test]
+    fn zstd_files_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = CsvWriter::new_compressed(dir.path(), Compression::Zstd).unwrap();
+        w.write_contacts(&[ContactRow { tick: 4, agent_a: 1, agent_b: 2, node: 9 }]).unwrap();
+        w.finish().unwrap();
+
+        let bytes = zstd::decode_all(std::fs::File::open(dir.path().join("contacts.csv.zst")).unwrap()).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), "tick,agent_a,agent_b,node\n4,1,2,9\n");
+        assert!(dir.path().join("trips.csv.zst").exists());
+    }
+
+    #[test]
+    fn zstd_snapshots_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = CsvWriter::new_compressed(dir.path(), Compression::Zstd).unwrap();
+        w.write_snapshots(&[AgentSnapshotRow {
+            agent_id: 4, tick: 2, departure_node: 1, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+            departure_tick: None, arrival_tick: None,
+        }]).unwrap();
+        w.finish().unwrap();
+
+        let rows = crate::csv::CsvSnapshotReader::new(dir.path()).read_rows().unwrap();
+        assert_eq!((rows[0].agent_id, rows[0].tick, rows[0].departure_node), (4, 2, 1));
+    }
+}
+
+#[cfg(all(test, feature = "arrow-ipc"))]
+mod ipc_tests {
+    use arrow::array::{Array, Int64Array, UInt64Array};
+    use arrow::ipc::reader::StreamReader;
+
+    use crate::columns::{ColumnSpec, ColumnType, ColumnValue};
+    use crate::ipc::{ArrowIpcWriter, IpcStreams};
+    use crate::row::{AgentSnapshotRow, TickSummaryRow};
+    use crate::writer::OutputWriter;
+
+    fn snap_row(agent_id: u32) -> AgentSnapshotRow {
+        AgentSnapshotRow {
+            agent_id, tick: 0, departure_node: 1, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+            departure_tick: None, arrival_tick: None,
+        }
+    }
+
+    fn summary(tick: u64) -> TickSummaryRow {
+        TickSummaryRow { tick, unix_time_secs: tick as i64 * 3600, woken_agents: tick, ..Default::default() }
+    }
+
+    fn ticks(path: &std::path::Path) -> Vec<u64> {
+        let reader = StreamReader::try_new(std::fs::File::open(path).unwrap(), None).unwrap();
+        reader
+            .flat_map(|batch| {
+                let batch = batch.unwrap();
+                let col = batch.column(0).as_any().downcast_ref::<UInt64Array>().unwrap();
+                col.values().to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ipc_files_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = ArrowIpcWriter::new(dir.path()).unwrap();
+        w.write_snapshots(&[snap_row(0), snap_row(1)]).unwrap();
+        w.write_tick_summary(&summary(0)).unwrap();
+        w.write_tick_summary(&summary(1)).unwrap();
+        w.finish().unwrap();
+
+        assert_eq!(ticks(&dir.path().join("tick_summaries.arrows")), [0, 1]);
+        let reader = StreamReader::try_new(
+            std::fs::File::open(dir.path().join("agent_snapshots.arrows")).unwrap(),
+            None,
+        ).unwrap();
+        let rows: usize = reader.map(|b| b.unwrap().num_rows()).sum();
+        assert_eq!(rows, 2);
+
+        // Tables that received no rows are still valid, empty streams.
+        let trips = StreamReader::try_new(
+            std::fs::File::open(dir.path().join("trips.arrows")).unwrap(),
+            None,
+        ).unwrap();
+        assert_eq!(trips.schema().field(5).name(), "mode");
+        assert_eq!(trips.count(), 0);
+    }
+
+    #[test]
+    fn ipc_batches_readable_before_finish() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = ArrowIpcWriter::new(dir.path()).unwrap();
+        w.write_tick_summary(&summary(0)).unwrap();
+        w.write_tick_summary(&summary(1)).unwrap();
+
+        assert_eq!(ticks(&dir.path().join("tick_summaries.arrows")), [0, 1]);
+
+        w.write_tick_summary(&summary(2)).unwrap();
+        assert_eq!(ticks(&dir.path().join("tick_summaries.arrows")), [0, 1, 2]);
+        w.finish().unwrap();
+    }
+
+    #[test]
+    fn ipc_from_streams_skips_missing_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("live.arrows");
+        let mut w = ArrowIpcWriter::from_streams(IpcStreams {
+            summaries: Some(std::fs::File::create(&path).unwrap()),
+            ..Default::default()
+        });
+        w.write_snapshots(&[snap_row(0)]).unwrap();
+        w.write_tick_summary(&summary(5)).unwrap();
+        w.finish().unwrap();
+
+        assert_eq!(ticks(&path), [5]);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn ipc_extra_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = ArrowIpcWriter::new(dir.path()).unwrap();
+        w.set_snapshot_columns(&[ColumnSpec { name: "score".into(), ty: ColumnType::Int }]).unwrap();
+        w.write_snapshots_with_columns(&[snap_row(0), snap_row(1)], &[vec![ColumnValue::Int(3), ColumnValue::Null]])
+            .unwrap();
+        assert!(w.set_snapshot_columns(&[]).is_err());
+        w.finish().unwrap();
+
+        let mut reader = StreamReader::try_new(
+            std::fs::File::open(dir.path().join("agent_snapshots.arrows")).unwrap(),
+            None,
+        ).unwrap();
+        let batch = reader.next().unwrap().unwrap();
+        let score = batch.column_by_name("score").unwrap().as_any().downcast_ref::<Int64Array>().unwrap();
+        assert_eq!(score.value(0), 3);
+        assert!(score.is_null(1));
+    }
+}
+
+#[cfg(all(test, unix, feature = "postgres"))]
+mod postgres_tests {
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+
+    use dt_core::TransportMode;
+
+    use crate::columns::{ColumnSpec, ColumnType, ColumnValue};
+    use crate::error::OutputError;
+    use crate::postgres::PostgresWriter;
+    use crate::row::{AgentSnapshotRow, RouteRow, TickSummaryRow, TripRow};
+    use crate::writer::OutputWriter;
+
+    /// A stand-in `psql` that records its SQL argument and stdin to
+    /// `{dir}/calls/<pid>`.
+    fn fake_psql(dir: &Path) -> PathBuf {
+        std::fs::create_dir(dir.join("calls")).unwrap();
+        let path = dir.join("psql");
+        std::fs::write(
+            &path,
+            format!(
+                "#!/bin/sh\nout={}/calls/$$\nfor a; do sql=\"$a\"; done\nprintf '%s\\n--\\n' \"$sql\" > \"$out\"\ncat >> \"$out\"\n",
+                dir.display(),
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    /// Recorded calls as `(sql, stdin)`, in no particular order.
+    fn calls(dir: &Path) -> Vec<(String, String)> {
+        std::fs::read_dir(dir.join("calls"))
+            .unwrap()
+            .map(|e| {
+                let text = std::fs::read_to_string(e.unwrap().path()).unwrap();
+                let (sql, data) = text.split_once("\n--\n").unwrap();
+                (sql.to_string(), data.to_string())
+            })
+            .collect()
+    }
+
+    fn copy_data(dir: &Path, table: &str) -> String {
+        let prefix = format!("COPY \"{table}\" ");
+        calls(dir)
+            .into_iter()
+            .find(|(sql, _)| sql.starts_with(&prefix))
+            .map(|(_, data)| data)
+            .unwrap_or_else(|| panic!("no COPY into {table}"))
+    }
+
+    #[test]
+    fn postgres_copies_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = PostgresWriter::builder("dbname=dt")
+            .psql(fake_psql(dir.path()))
+            .table_prefix("run1_")
+            .connect()
+            .unwrap();
+        w.write_snapshots(&[AgentSnapshotRow {
+            agent_id: 2, tick: 5, departure_node: 7, in_transit: true, destination_node: u32::MAX, lat: Some(1.5), lon: None,
+            departure_tick: None, arrival_tick: None,
+        }]).unwrap();
+        w.write_tick_summary(&TickSummaryRow { tick: 5, unix_time_secs: 18000, woken_agents: 1, ..Default::default() }).unwrap();
+        w.write_trips(&[TripRow {
+            agent: 2, depart_tick: 5, arrive_tick: 6, from: 7, to: 8,
+            mode: TransportMode::Walk, travel_secs: 90.5, distance_m: 120.0,
+        }]).unwrap();
+        w.write_routes(&[RouteRow { agent: 2, depart_tick: 5, from: 7, to: 8, mode: TransportMode::Walk, edges: vec![11, 12] }])
+            .unwrap();
+        w.finish().unwrap();
+
+        assert!(calls(dir.path()).iter().any(|(sql, _)| sql.contains("CREATE TABLE IF NOT EXISTS \"run1_trips\"")));
+        assert_eq!(copy_data(dir.path(), "run1_agent_snapshots"), "2\t5\t7\tt\t4294967295\t1.5\t\\N\t\\N\t\\N\n");
+        assert_eq!(copy_data(dir.path(), "run1_tick_summaries"), format!("5\t18000\t1{}\n", "\t0".repeat(11)));
+        assert_eq!(copy_data(dir.path(), "run1_trips"), "2\t5\t6\t7\t8\twalk\t90.5\t120\n");
+        assert_eq!(copy_data(dir.path(), "run1_routes"), "2\t5\t7\t8\twalk\t{11,12}\n");
+        // No contacts were written, so no COPY was started.
+        assert!(!calls(dir.path()).iter().any(|(sql, _)| sql.starts_with("COPY \"run1_contacts\"")));
+    }
+
+    #[test]
+    fn postgres_extra_columns_escaped() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = PostgresWriter::builder("dbname=dt").psql(fake_psql(dir.path())).connect().unwrap();
+        w.set_snapshot_columns(&[
+            ColumnSpec { name: "note".into(), ty: ColumnType::Text },
+            ColumnSpec { name: "sick".into(), ty: ColumnType::Bool },
+        ]).unwrap();
+        w.write_snapshots_with_columns(
+            &[AgentSnapshotRow {
+                agent_id: 0, tick: 0, departure_node: 1, in_transit: false, destination_node: 2, lat: None, lon: None,
+                departure_tick: None, arrival_tick: None,
+            }],
+            &[vec![ColumnValue::Text("a\tb\\c\nd".into())], vec![ColumnValue::Null]],
+        ).unwrap();
+        assert!(w.set_snapshot_columns(&[]).is_err());
+        w.finish().unwrap();
+
+        assert!(calls(dir.path()).iter().any(|(sql, _)| {
+            sql == "ALTER TABLE \"agent_snapshots\" ADD COLUMN IF NOT EXISTS \"note\" TEXT;\
+                    ALTER TABLE \"agent_snapshots\" ADD COLUMN IF NOT EXISTS \"sick\" BOOLEAN;"
+        }));
+        let copy = calls(dir.path())
+            .into_iter()
+            .find(|(sql, _)| sql.starts_with("COPY"))
+            .unwrap();
+        assert!(copy.0.ends_with("lat, lon, departure_tick, arrival_tick, \"note\", \"sick\") FROM STDIN"));
+        assert_eq!(copy.1, "0\t0\t1\tf\t2\t\\N\t\\N\t\\N\t\\N\ta\\tb\\\\c\\nd\t\\N\n");
+    }
+
+    #[test]
+    fn postgres_reports_psql_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let psql = dir.path().join("psql");
+        std::fs::write(&psql, "#!/bin/sh\necho 'FATAL:  database \"dt\" does not exist' >&2\nexit 2\n").unwrap();
+        std::fs::set_permissions(&psql, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let err = PostgresWriter::builder("dbname=dt").psql(&psql).connect().err().unwrap();
+        assert!(matches!(&err, OutputError::Postgres(msg) if msg.contains("does not exist")), "{err}");
+    }
+
+    #[test]
+    fn postgres_conninfo_passed_in_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let psql = dir.path().join("psql");
+        let log = dir.path().join("log");
+        std::fs::write(
+            &psql,
+            format!("#!/bin/sh\nprintf '%s|%s|%s|%s\\n' \"$*\" \"$PGHOST\" \"$PGUSER\" \"$PGPASSWORD\" > {}\n", log.display()),
+        )
+        .unwrap();
+        std::fs::set_permissions(&psql, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        PostgresWriter::builder("host=db.example user=dt password='s3cr3t pw'").psql(&psql).connect().unwrap();
+        let seen = std::fs::read_to_string(&log).unwrap();
+        let (args, env) = seen.trim_end().split_once('|').unwrap();
+        assert!(!args.contains("s3cr3t"), "password on the command line: {args}");
+        assert_eq!(env, "db.example|dt|s3cr3t pw");
+    }
+
+    #[test]
+    fn postgres_conninfo_parsing() {
+        use crate::postgres::conninfo_env;
+
+        let env = conninfo_env("host = db port=5433 dbname='my db' password='it\\'s'").unwrap();
+        assert_eq!(env, [
+            ("PGHOST", "db".to_owned()),
+            ("PGPORT", "5433".to_owned()),
+            ("PGDATABASE", "my db".to_owned()),
+            ("PGPASSWORD", "it's".to_owned()),
+        ]);
+        let env = conninfo_env("postgresql://dt:p%40ss@[::1]:5432,replica/results?sslmode=require").unwrap();
+        assert_eq!(env, [
+            ("PGUSER", "dt".to_owned()),
+            ("PGPASSWORD", "p@ss".to_owned()),
+            ("PGHOST", "::1,replica".to_owned()),
+            ("PGPORT", "5432,".to_owned()),
+            ("PGDATABASE", "results".to_owned()),
+            ("PGSSLMODE", "require".to_owned()),
+        ]);
+        assert_eq!(conninfo_env("results").unwrap(), [("PGDATABASE", "results".to_owned())]);
+        assert!(conninfo_env("").unwrap().is_empty());
+
+        for bad in ["host=h dbname", "password='open", "bogus=1", "postgres://h/db?x", "postgres://u:%zz@h"] {
+            let err = conninfo_env(bad).unwrap_err();
+            assert!(!err.to_string().contains("zz") && !err.to_string().contains("open"), "{err}");
+        }
+    }
+}
+
+#[cfg(all(test, feature = "jsonl"))]
+mod jsonl_tests {
+    use dt_core::TransportMode;
+    use serde_json::{Value, json};
+
+    use crate::columns::{ColumnSpec, ColumnType, ColumnValue};
+    use crate::jsonl::JsonlWriter;
+    use crate::row::{AgentSnapshotRow, ContactRow, TickSummaryRow, TripRow};
+    use crate::writer::OutputWriter;
+
+    fn lines(path: &std::path::Path) -> Vec<Value> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn jsonl_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = JsonlWriter::new(dir.path()).unwrap();
+        w.write_snapshots(&[
+            AgentSnapshotRow {
+                agent_id: 0, tick: 1, departure_node: 4, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+                departure_tick: None, arrival_tick: None,
+            },
+            AgentSnapshotRow {
+                agent_id: 1, tick: 1, departure_node: 4, in_transit: true, destination_node: 5, lat: Some(0.5), lon: Some(-1.0),
+                departure_tick: None, arrival_tick: None,
+            },
+        ]).unwrap();
+        w.write_tick_summary(&TickSummaryRow { tick: 1, unix_time_secs: 3600, woken_agents: 2, ..Default::default() }).unwrap();
+        w.write_contacts(&[ContactRow { tick: 1, agent_a: 0, agent_b: 2, node: 4 }]).unwrap();
+        w.write_trips(&[TripRow {
+            agent: 1, depart_tick: 0, arrive_tick: 1, from: 3, to: 4,
+            mode: TransportMode::Transit, travel_secs: 30.5, distance_m: 400.0,
+        }]).unwrap();
+        w.finish().unwrap();
+
+        assert_eq!(lines(&dir.path().join("agent_snapshots.jsonl")), [
+            json!({"agent_id": 0, "tick": 1, "departure_node": 4, "in_transit": false,
+                   "destination_node": null, "lat": null, "lon": null, "departure_tick": null, "arrival_tick": null}),
+            json!({"agent_id": 1, "tick": 1, "departure_node": 4, "in_transit": true,
+                   "destination_node": 5, "lat": 0.5, "lon": -1.0, "departure_tick": null, "arrival_tick": null}),
+        ]);
+        assert_eq!(
+            lines(&dir.path().join("tick_summaries.jsonl")),
+            [json!({
+                "tick": 1, "unix_time_secs": 3600, "woken_agents": 2, "in_transit": 0, "arrivals": 0,
+                "departures": 0, "travel_intents": 0, "wake_intents": 0, "messages_sent": 0,
+                "metric_intents": 0, "messages_delivered": 0, "contacts": 0, "routing_failures": 0,
+                "behavior_panics": 0,
+            })],
+        );
+        assert_eq!(
+            lines(&dir.path().join("contacts.jsonl")),
+            [json!({"tick": 1, "agent_a": 0, "agent_b": 2, "node": 4})],
+        );
+        assert_eq!(lines(&dir.path().join("trips.jsonl")), [json!({
+            "agent": 1, "depart_tick": 0, "arrive_tick": 1, "from": 3, "to": 4,
+            "mode": "transit", "travel_secs": 30.5, "distance_m": 400.0,
+        })]);
+    }
+
+    #[test]
+    fn jsonl_extra_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = JsonlWriter::new(dir.path()).unwrap();
+        w.set_snapshot_columns(&[
+            ColumnSpec { name: "infected".into(), ty: ColumnType::Bool },
+            ColumnSpec { name: "label".into(),    ty: ColumnType::Text },
+        ]).unwrap();
+        w.write_snapshots_with_columns(
+            &[AgentSnapshotRow {
+                agent_id: 0, tick: 0, departure_node: u32::MAX, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+                departure_tick: None, arrival_tick: None,
+            }],
+            &[vec![ColumnValue::Bool(true)], vec![ColumnValue::Text("say \"hi\"".into())]],
+        ).unwrap();
+        assert!(w.set_snapshot_columns(&[]).is_err());
+        w.finish().unwrap();
+
+        let rows = lines(&dir.path().join("agent_snapshots.jsonl"));
+        assert_eq!(rows[0]["departure_node"], Value::Null);
+        assert_eq!(rows[0]["infected"], json!(true));
+        assert_eq!(rows[0]["label"], json!("say \"hi\""));
+    }
+
+    #[test]
+    fn run_manifest_is_valid_json() {
+        use dt_core::SimConfig;
+
+        use crate::manifest::{NetworkInfo, RunManifest};
+
+        let mut manifest = RunManifest::new(&SimConfig {
+            start_unix_secs:       -5,
+            tick_duration_secs:    900,
+            total_ticks:           96,
+            seed:                  7,
+            num_threads:           Some(4),
+            output_interval_ticks: 4,
+            ..Default::default()
+        }.with_extension("scenario", "base\"line"));
+        manifest.network = Some(NetworkInfo { nodes: 3, edges: 4, fingerprint: 0xab });
+        manifest.snapshot_columns = vec![ColumnSpec { name: "a \"b\"\n".into(), ty: ColumnType::Float }];
+        manifest.started_unix_secs = Some(100);
+        manifest.finished_unix_secs = Some(160);
+        manifest.final_tick = Some(96);
+
+        let v: Value = serde_json::from_str(&manifest.to_json()).unwrap();
+        assert_eq!(v["manifest_version"], json!(1));
+        assert_eq!(v["status"], json!("finished"));
+        assert_eq!(v["crate_versions"]["dt-output"], json!(env!("CARGO_PKG_VERSION")));
+        assert_eq!(v["config"], json!({
+            "start_unix_secs": -5, "tick_duration_secs": 900, "total_ticks": 96, "seed": 7,
+            "num_threads": 4, "output_interval_ticks": 4,
+            "mobility": {"min_travel_ticks": 1, "path_following": false}, "contacts": {"edge_contacts": false},
+            "output": {"snapshots": 1, "tick_summaries": 1, "contacts": 1, "trips": 1},
+            "routing": {"walk_speed_mps": 1.4, "bike_speed_mps": 4.2, "transit_speed_mps": 8.3},
+            "extensions": {"scenario": "base\"line"},
+        }));
+        assert_eq!(v["network"], json!({"nodes": 3, "edges": 4, "fingerprint": "00000000000000ab"}));
+        assert_eq!(v["finished_unix_secs"], json!(160));
+        assert_eq!(v["schema_versions"]["agent_snapshots"], json!(1));
+        assert_eq!(v["snapshot_columns"], json!([{"name": "a \"b\"\n", "type": "float"}]));
+    }
+}
+
+#[cfg(all(test, feature = "geojson"))]
+mod geojson_tests {
+    use dt_core::GeoPoint;
+    use dt_spatial::RoadNetworkBuilder;
+    use serde_json::{Value, json};
+
+    use crate::columns::{ColumnSpec, ColumnType, ColumnValue};
+    use crate::geojson::GeoJsonWriter;
+    use crate::row::AgentSnapshotRow;
+    use crate::writer::OutputWriter;
+
+    fn read(path: &std::path::Path) -> Value {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    fn row(agent_id: u32, tick: u64, pos: Option<(f32, f32)>) -> AgentSnapshotRow {
+        AgentSnapshotRow {
+            agent_id, tick, departure_node: 1, in_transit: false, destination_node: u32::MAX,
+            lat: pos.map(|p| p.0), lon: pos.map(|p| p.1),
+            departure_tick: None, arrival_tick: None,
+        }
+    }
+
+    #[test]
+    fn geojson_one_collection_per_tick() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = GeoJsonWriter::new(dir.path()).unwrap();
+        w.set_snapshot_columns(&[ColumnSpec { name: "infected".into(), ty: ColumnType::Bool }]).unwrap();
+        // Tick 0 arrives over two calls; agent 2 has no position.
+        w.write_snapshots_with_columns(
+            &[row(0, 0, Some((52.52, 13.405))), row(1, 0, Some((52.5, 13.4)))],
+            &[vec![ColumnValue::Bool(true), ColumnValue::Bool(false)]],
+        ).unwrap();
+        w.write_snapshots(&[row(2, 0, None)]).unwrap();
+        w.write_snapshots(&[row(0, 24, Some((52.0, 13.0)))]).unwrap();
+        assert!(dir.path().join("snapshot_00000000.geojson").exists());
+        assert!(!dir.path().join("snapshot_00000024.geojson").exists(), "written on finish");
+        w.finish().unwrap();
+
+        let tick0 = read(&dir.path().join("snapshot_00000000.geojson"));
+        assert_eq!(tick0["type"], "FeatureCollection");
+        let features = tick0["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[0], json!({
+            "type":       "Feature",
+            "geometry":   { "type": "Point", "coordinates": [13.405, 52.52] },
+            "properties": {
+                "agent_id": 0, "tick": 0, "departure_node": 1, "in_transit": false,
+                "destination_node": null, "departure_tick": null, "arrival_tick": null, "infected": true,
+            },
+        }));
+        assert_eq!(features[1]["properties"]["infected"], false);
+
+        let tick24 = read(&dir.path().join("snapshot_00000024.geojson"));
+        assert_eq!(tick24["features"][0]["properties"]["infected"], Value::Null);
+    }
+
+    #[test]
+    fn geojson_network_edges() {
+        let mut b = RoadNetworkBuilder::new();
+        let n0 = b.add_node(GeoPoint { lat: 0.0, lon: 0.0 });
+        let n1 = b.add_node(GeoPoint { lat: 0.0, lon: 0.01 });
+        b.add_road(n0, n1, 1100.0, 80_000);
+
+        let dir = tempfile::tempdir().unwrap();
+        let w = GeoJsonWriter::new(dir.path()).unwrap();
+        w.write_network(&b.build()).unwrap();
+
+        let network = read(&dir.path().join("network.geojson"));
+        let features = network["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2, "one feature per direction");
+        let forward = features.iter().find(|f| f["properties"]["from"] == 0).unwrap();
+        assert_eq!(forward["geometry"], json!({ "type": "LineString", "coordinates": [[0.0, 0.0], [0.01, 0.0]] }));
+        assert_eq!(forward["properties"]["length_m"], 1100.0);
+        assert_eq!(forward["properties"]["travel_secs"], 80.0);
+    }
+
+    #[test]
+    fn geojson_missing_dir_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(GeoJsonWriter::new(&dir.path().join("missing")).is_err());
+    }
+}
+
+#[cfg(all(test, any(feature = "kafka", feature = "mqtt")))]
+mod stream_tests {
+    use serde_json::{Value, json};
+
+    use crate::columns::{ColumnSpec, ColumnType, ColumnValue};
+    use crate::row::{AgentSnapshotRow, ContactRow, TickSummaryRow};
+    use crate::stream::{Publisher, StreamWriter};
+    use crate::writer::OutputWriter;
+
+    /// Keeps every message in memory.
+    #[derive(Default)]
+    struct Recorder {
+        messages: Vec<(String, String, Value)>,
+        closes:   usize,
+    }
+
+    impl Publisher for Recorder {
+        fn publish(&mut self, topic: &str, key: &str, payload: Vec<u8>) -> crate::OutputResult<()> {
+            let value = serde_json::from_slice(&payload).unwrap();
+            self.messages.push((topic.to_owned(), key.to_owned(), value));
+            Ok(())
+        }
+
+        fn close(&mut self) -> crate::OutputResult<()> {
+            self.closes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn stream_publishes_one_message_per_row() {
+        let mut w = StreamWriter::new(Recorder::default()).topic_prefix("run1.");
+        w.set_snapshot_columns(&[ColumnSpec { name: "infected".into(), ty: ColumnType::Bool }]).unwrap();
+        w.write_snapshots_with_columns(
+            &[
+                AgentSnapshotRow {
+                    agent_id: 4, tick: 2, departure_node: 1, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+                    departure_tick: None, arrival_tick: None,
+                },
+                AgentSnapshotRow {
+                    agent_id: 5, tick: 2, departure_node: 1, in_transit: true, destination_node: 3, lat: None, lon: None,
+                    departure_tick: None, arrival_tick: None,
+                },
+            ],
+            &[vec![ColumnValue::Bool(true), ColumnValue::Bool(false)]],
+        ).unwrap();
+        assert!(w.set_snapshot_columns(&[]).is_err());
+        w.write_tick_summary(&TickSummaryRow { tick: 2, unix_time_secs: 7200, woken_agents: 2, ..Default::default() }).unwrap();
+        w.write_contacts(&[ContactRow { tick: 2, agent_a: 4, agent_b: 5, node: 1 }]).unwrap();
+        w.finish().unwrap();
+        w.finish().unwrap();
+
+        let rec = w.into_publisher();
+        assert_eq!(rec.closes, 1);
+        let routes: Vec<(&str, &str)> =
+            rec.messages.iter().map(|(t, k, _)| (t.as_str(), k.as_str())).collect();
+        assert_eq!(routes, [
+            ("run1.agent_snapshots", "4"),
+            ("run1.agent_snapshots", "5"),
+            ("run1.tick_summaries", "2"),
+            ("run1.contacts", "4"),
+        ]);
+        assert_eq!(rec.messages[0].2, json!({
+            "agent_id": 4, "tick": 2, "departure_node": 1, "in_transit": false,
+            "destination_node": null, "lat": null, "lon": null, "departure_tick": null, "arrival_tick": null,
+            "infected": true,
+        }));
+        assert_eq!(rec.messages[2].2["woken_agents"], json!(2));
+        assert_eq!(rec.messages[3].2, json!({"tick": 2, "agent_a": 4, "agent_b": 5, "node": 1}));
+    }
+
+    #[cfg(feature = "mqtt")]
+    #[test]
+    fn mqtt_publishes_to_broker() {
+        use std::io::{Read, Write};
+        use std::net::{TcpListener, TcpStream};
+
+        use crate::stream::MqttPublisher;
+
+        /// Read one packet: the fixed-header byte and the body.
+        fn packet(s: &mut TcpStream) -> (u8, Vec<u8>) {
+            let mut byte = [0u8; 1];
+            s.read_exact(&mut byte).unwrap();
+            let header = byte[0];
+            let (mut len, mut shift) = (0usize, 0);
+            loop {
+                s.read_exact(&mut byte).unwrap();
+                len |= ((byte[0] & 0x7f) as usize) << shift;
+                shift += 7;
+                if byte[0] & 0x80 == 0 {
+                    break;
+                }
+            }
+            let mut body = vec![0u8; len];
+            s.read_exact(&mut body).unwrap();
+            (header, body)
+        }
+
+        // A broker that acknowledges everything and records publishes.
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let broker = std::thread::spawn(move || {
+            let (mut s, _) = listener.accept().unwrap();
+            let mut published = Vec::new();
+            loop {
+                let (header, body) = packet(&mut s);
+                match header >> 4 {
+                    1 => s.write_all(&[0x20, 2, 0, 0]).unwrap(),
+                    3 => {
+                        let topic_len = u16::from_be_bytes([body[0], body[1]]) as usize;
+                        let topic = String::from_utf8(body[2..2 + topic_len].to_vec()).unwrap();
+                        let mut at = 2 + topic_len;
+                        if header & 0x06 != 0 {
+                            s.write_all(&[0x40, 2, body[at], body[at + 1]]).unwrap();
+                            at += 2;
+                        }
+                        published.push((topic, String::from_utf8(body[at..].to_vec()).unwrap()));
+                    }
+                    12 => s.write_all(&[0xd0, 0]).unwrap(),
+                    14 => return published,
+                    _ => {}
+                }
+            }
+        });
+
+        let mut w = StreamWriter::new(MqttPublisher::new("dt-test", "127.0.0.1", port)).topic_prefix("dt/");
+        w.write_contacts(&[
+            ContactRow { tick: 1, agent_a: 0, agent_b: 1, node: 9 },
+            ContactRow { tick: 1, agent_a: 1, agent_b: 0, node: 9 },
+        ]).unwrap();
+        w.finish().unwrap();
+
+        let published = broker.join().unwrap();
+        assert_eq!(published.len(), 2);
+        assert_eq!(published[0].0, "dt/contacts");
+        let second: Value = serde_json::from_str(&published[1].1).unwrap();
+        assert_eq!(second, json!({"tick": 1, "agent_a": 1, "agent_b": 0, "node": 9}));
+    }
+
+    #[cfg(feature = "mqtt")]
+    #[test]
+    fn mqtt_unreachable_broker_reported() {
+        use crate::stream::MqttPublisher;
+
+        // Nothing listens on a port just released by the OS.
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let mut publisher = MqttPublisher::new("dt-test", "127.0.0.1", port);
+        assert!(publisher.close().is_err());
+    }
+
+    #[cfg(feature = "kafka")]
+    #[test]
+    fn kafka_undelivered_messages_reported() {
+        use std::time::Duration;
+
+        use rdkafka::config::ClientConfig;
+
+        use crate::stream::KafkaPublisher;
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let publisher = KafkaPublisher::from_config(
+            ClientConfig::new()
+                .set("bootstrap.servers", format!("127.0.0.1:{port}"))
+                .set("message.timeout.ms", "200"),
+        )
+        .unwrap()
+        .close_timeout(Duration::from_secs(10));
+        let mut w = StreamWriter::new(publisher);
+        w.write_tick_summary(&TickSummaryRow::default()).unwrap();
+        assert!(w.finish().is_err());
+    }
+}
+
+#[cfg(all(test, feature = "websocket"))]
+mod websocket_tests {
+    use std::time::{Duration, Instant};
+
+    use tungstenite::Message;
+
+    use crate::row::AgentSnapshotRow;
+    use crate::websocket::{FrameFormat, WebSocketWriter};
+    use crate::writer::OutputWriter;
+
+    fn row(agent_id: u32, pos: Option<(f32, f32)>) -> AgentSnapshotRow {
+        AgentSnapshotRow {
+            agent_id,
+            tick:             12,
+            departure_node:   0,
+            in_transit:       agent_id == 2,
+            destination_node: u32::MAX,
+            lat:              pos.map(|p| p.0),
+            lon:              pos.map(|p| p.1),
+            departure_tick:   None,
+            arrival_tick:     None,
+        }
+    }
+
+    fn rows() -> Vec<AgentSnapshotRow> {
+        vec![
+            row(0, Some((52.5, 13.25))),
+            row(1, Some((52.0, 13.0))),
+            row(2, Some((51.5, 12.75))),
+            row(4, None),
+        ]
+    }
+
+    /// Connect a client and wait until the writer has accepted it.
+    fn connect(w: &WebSocketWriter) -> tungstenite::WebSocket<tungstenite::stream::MaybeTlsStream<std::net::TcpStream>> {
+        let (client, _) = tungstenite::connect(format!("ws://{}", w.local_addr())).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while w.clients() == 0 {
+            assert!(Instant::now() < deadline, "client not accepted");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        client
+    }
+
+    #[test]
+    fn websocket_json_frames() {
+        let mut w = WebSocketWriter::bind("127.0.0.1:0").unwrap().sample_every(2);
+        // Without clients, snapshots are dropped.
+        w.write_snapshots(&rows()).unwrap();
+        let mut client = connect(&w);
+
+        w.write_snapshots(&rows()).unwrap();
+        assert_eq!(
+            client.read().unwrap(),
+            Message::Text(r#"{"tick":12,"agents":[[0,52.5,13.25,0],[2,51.5,12.75,1]]}"#.into()),
+        );
+        w.finish().unwrap();
+        assert!(matches!(client.read().unwrap(), Message::Close(_)));
+        assert_eq!(w.clients(), 0);
+    }
+
+    #[test]
+    fn websocket_binary_frames() {
+        let mut w = WebSocketWriter::bind("127.0.0.1:0").unwrap().format(FrameFormat::Binary);
+        let mut client = connect(&w);
+        w.write_snapshots(&rows()).unwrap();
+
+        let Message::Binary(bytes) = client.read().unwrap() else { panic!("expected a binary frame") };
+        let u32_at = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+        let f32_at = |i: usize| f32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+        assert_eq!(u64::from_le_bytes(bytes[0..8].try_into().unwrap()), 12);
+        assert_eq!(u32_at(8), 3, "agent 4 has no position");
+        assert_eq!(bytes.len(), 12 + 3 * 16);
+        // Third agent: id 2, in transit.
+        assert_eq!((u32_at(44), f32_at(48), f32_at(52), u32_at(56)), (2, 51.5, 12.75, 1));
+    }
+
+    #[test]
+    fn websocket_drops_closed_clients() {
+        let mut w = WebSocketWriter::bind("127.0.0.1:0").unwrap();
+        drop(connect(&w));
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while w.clients() > 0 {
+            assert!(Instant::now() < deadline, "closed client kept");
+            w.write_snapshots(&rows()).unwrap();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "heatmap"))]
+mod heatmap_tests {
+    use dt_core::GeoPoint;
+    use dt_spatial::RoadNetworkBuilder;
+    use tiff::decoder::{Decoder, DecodingResult};
+    use tiff::tags::Tag;
+
+    use crate::heatmap::{HeatmapGrid, HeatmapWriter, RasterFormat};
+    use crate::row::AgentSnapshotRow;
+    use crate::writer::OutputWriter;
+    use crate::OutputError;
+
+    /// 3 × 2 one-degree cells over [0, 2] × [0, 3].
+    const GRID: HeatmapGrid = HeatmapGrid { south: 0.0, west: 0.0, north: 2.0, east: 3.0, cols: 3, rows: 2 };
+
+    fn row(agent_id: u32, tick: u64, pos: Option<(f32, f32)>, in_transit: bool) -> AgentSnapshotRow {
+        AgentSnapshotRow {
+            agent_id, tick, departure_node: 0, in_transit, destination_node: u32::MAX,
+            lat: pos.map(|p| p.0), lon: pos.map(|p| p.1),
+            departure_tick: None, arrival_tick: None,
+        }
+    }
+
+    fn rows(tick: u64) -> Vec<AgentSnapshotRow> {
+        vec![
+            row(0, tick, Some((0.5, 0.5)), false),  // south-west cell
+            row(1, tick, Some((1.5, 2.5)), true),   // north-east cell
+            row(2, tick, Some((2.0, 3.0)), false),  // north-east corner
+            row(3, tick, Some((5.0, 5.0)), true),   // outside
+            row(4, tick, None, true),
+        ]
+    }
+
+    #[test]
+    fn heatmap_grid_cells() {
+        assert_eq!(GRID.cell(0.0, 0.0), Some(3));
+        assert_eq!(GRID.cell(1.99, 0.5), Some(0));
+        assert_eq!(GRID.cell(0.5, 1.5), Some(4));
+        assert_eq!(GRID.cell(-0.1, 0.5), None);
+
+        let mut b = RoadNetworkBuilder::new();
+        let n0 = b.add_node(GeoPoint { lat: 10.0, lon: 20.0 });
+        let n1 = b.add_node(GeoPoint { lat: 10.25, lon: 20.5 });
+        b.add_road(n0, n1, 1000.0, 60_000);
+        let grid = HeatmapGrid::covering(&b.build(), 0.1);
+        assert_eq!((grid.cols, grid.rows), (6, 3));
+        assert_eq!((grid.south, grid.west), (10.0, 20.0));
+        assert!(grid.cell(10.25, 20.5).is_some());
+
+        let dir = tempfile::tempdir().unwrap();
+        let empty = HeatmapGrid { cols: 0, ..GRID };
+        assert!(matches!(HeatmapWriter::new(dir.path(), empty), Err(OutputError::Grid(_))));
+        let inverted = HeatmapGrid { north: -1.0, ..GRID };
+        assert!(matches!(HeatmapWriter::new(dir.path(), inverted), Err(OutputError::Grid(_))));
+    }
+
+    #[test]
+    fn heatmap_npy_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = HeatmapWriter::new(dir.path(), GRID).unwrap();
+        // Tick 0 arrives over two calls.
+        let tick0 = rows(0);
+        w.write_snapshots(&tick0[..2]).unwrap();
+        w.write_snapshots(&tick0[2..]).unwrap();
+        w.write_snapshots(&rows(24)).unwrap();
+        assert!(!dir.path().join("heatmap_00000024.npy").exists(), "written on finish");
+        w.finish().unwrap();
+
+        let bytes = std::fs::read(dir.path().join("heatmap_00000000.npy")).unwrap();
+        assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+        assert!(header.starts_with("{'descr': '<u4', 'fortran_order': False, 'shape': (2, 3), }"));
+        assert!(header.ends_with('\n'));
+        let counts: Vec<u32> = bytes[10 + header_len..]
+            .chunks(4)
+            .map(|c| u32::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+        assert_eq!(counts, [0, 0, 2, 1, 0, 0]);
+        assert!(dir.path().join("heatmap_00000024.npy").exists());
+    }
+
+    #[test]
+    fn heatmap_geotiff_georeferenced() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = HeatmapWriter::new(dir.path(), GRID).unwrap().format(RasterFormat::GeoTiff).in_transit_only();
+        w.write_snapshots(&rows(7)).unwrap();
+        w.finish().unwrap();
+
+        let file = std::fs::File::open(dir.path().join("heatmap_00000007.tif")).unwrap();
+        let mut tiff = Decoder::new(file).unwrap();
+        assert_eq!(tiff.dimensions().unwrap(), (3, 2));
+        assert_eq!(tiff.get_tag_f64_vec(Tag::ModelPixelScaleTag).unwrap(), [1.0, 1.0, 0.0]);
+        assert_eq!(tiff.get_tag_f64_vec(Tag::ModelTiepointTag).unwrap(), [0.0, 0.0, 0.0, 0.0, 2.0, 0.0]);
+        let keys = tiff.get_tag_u16_vec(Tag::GeoKeyDirectoryTag).unwrap();
+        assert_eq!(&keys[12..], [2048, 0, 1, 4326]);
+        // Only agent 1 is in transit inside the grid.
+        let DecodingResult::U32(counts) = tiff.read_image().unwrap() else { panic!("not u32") };
+        assert_eq!(counts, [0, 0, 1, 0, 0, 0]);
+    }
+}
+
+#[cfg(all(test, feature = "object-store"))]
+mod store_tests {
+    use std::io::Write;
+    use std::sync::Arc;
+
+    use dt_core::{SimConfig, Tick};
+    use dt_sim::SimObserver;
+    use object_store::memory::InMemory;
+
+    use crate::csv::{Compression, CsvWriter};
+    use crate::dest::Dest;
+    use crate::observer::SimOutputObserver;
+    use crate::row::{AgentSnapshotRow, TripRow};
+    use crate::store::ObjectDir;
+    use crate::writer::OutputWriter;
+
+    fn memory_dir() -> ObjectDir {
+        ObjectDir::new(Arc::new(InMemory::new()), "runs/42").unwrap()
+    }
+
+    fn config() -> SimConfig {
+        SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    3600,
+            total_ticks:           2,
+            seed:                  7,
+            num_threads:           Some(1),
+            output_interval_ticks: 1,
+            ..Default::default()
+        }
+    }
+
+    fn snap(agent_id: u32, tick: u64) -> AgentSnapshotRow {
+        AgentSnapshotRow {
+            agent_id, tick, departure_node: 0, in_transit: false, destination_node: u32::MAX, lat: None, lon: None,
+            departure_tick: None, arrival_tick: None,
+        }
+    }
+
+    #[test]
+    fn store_csv_upload() {
+        let dir = memory_dir();
+        let mut obs = SimOutputObserver::new(CsvWriter::to_object_store(dir.clone(), Compression::None).unwrap(), &config())
+            .with_run_summary();
+        obs.on_tick_start(Tick(0));
+        let manifest = String::from_utf8(dir.read("run_manifest.json").unwrap()).unwrap();
+        assert!(manifest.contains("\"status\": \"running\""));
+        assert!(dir.read("trips.csv").is_err(), "objects appear on finish");
+        obs.on_sim_end(Tick(2));
+        assert!(obs.take_error().is_none());
+        assert!(obs.into_writer().output_dir().is_none());
+
+        let trips = String::from_utf8(dir.read("trips.csv").unwrap()).unwrap();
+        assert!(trips.starts_with("agent,depart_tick,arrive_tick,"));
+        let manifest = String::from_utf8(dir.read("run_manifest.json").unwrap()).unwrap();
+        assert!(manifest.contains("\"status\": \"finished\""));
+        assert!(dir.read("run_summary.json").is_ok());
+        assert_eq!(dir.path("trips.csv").as_ref(), "runs/42/trips.csv");
+    }
+
+    #[test]
+    fn store_writer_inside_async_runtime() {
+        let dir = memory_dir();
+        // As under `Sim::run_async`: the writer is driven from a task.
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(async {
+            let mut w = CsvWriter::to_object_store(dir.clone(), Compression::None).unwrap();
+            w.write_snapshots(&[snap(0, 0), snap(1, 0)]).unwrap();
+            w.write_trips(&[TripRow {
+                agent: 3, depart_tick: 0, arrive_tick: 1, from: 0, to: 1,
+                mode: dt_core::TransportMode::Car, travel_secs: 60.0, distance_m: 900.0,
+            }]).unwrap();
+            w.finish().unwrap();
+        });
+        drop(runtime);
+
+        let snaps = String::from_utf8(dir.read("agent_snapshots.csv").unwrap()).unwrap();
+        assert_eq!(snaps.lines().count(), 3);
+        let trips = String::from_utf8(dir.read("trips.csv").unwrap()).unwrap();
+        assert!(trips.lines().nth(1).unwrap().starts_with("3,0,1,0,1,car,"));
+    }
+
+    #[test]
+    fn store_multipart_and_abort() {
+        let dir = memory_dir();
+        let dest = Dest::Store(dir.clone());
+
+        // Larger than two 10 MiB parts.
+        let chunk = vec![7u8; 1 << 20];
+        let mut file = dest.create("big.bin").unwrap();
+        for _ in 0..25 {
+            file.write_all(&chunk).unwrap();
+        }
+        file.finish().unwrap();
+        let big = dir.read("big.bin").unwrap();
+        assert_eq!(big.len(), 25 << 20);
+        assert!(big.iter().all(|&b| b == 7));
+
+        let mut unfinished = dest.create("partial.bin").unwrap();
+        unfinished.write_all(b"lost").unwrap();
+        drop(unfinished);
+        assert!(dir.read("partial.bin").is_err());
+    }
+
+    #[test]
+    fn store_parse_file_url() {
+        let tmp = tempfile::tempdir().unwrap();
+        let url = format!("file://{}/out", tmp.path().display());
+        let mut w = CsvWriter::to_object_store(ObjectDir::parse(&url).unwrap(), Compression::None).unwrap();
+        w.write_snapshots(&[snap(0, 0)]).unwrap();
+        w.finish().unwrap();
+        let text = std::fs::read_to_string(tmp.path().join("out/agent_snapshots.csv")).unwrap();
+        assert_eq!(text.lines().count(), 2);
+
+        assert!(ObjectDir::parse("not a url").is_err());
+        assert!(ObjectDir::parse("ftp://host/path").is_err());
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn store_parquet_upload() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        use crate::parquet::ParquetWriter;
+
+        let dir = memory_dir();
+        let mut w = ParquetWriter::to_object_store(dir.clone()).unwrap();
+        w.write_snapshots(&[snap(0, 0), snap(1, 0), snap(2, 0)]).unwrap();
+        w.finish().unwrap();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("agent_snapshots.parquet");
+        std::fs::write(&path, dir.read("agent_snapshots.parquet").unwrap()).unwrap();
+        let reader = SerializedFileReader::new(std::fs::File::open(path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 3);
+    }
+}
+
+// ── Results reader tests ──────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod reader_tests {
+    use std::path::Path;
+
+    use dt_core::TransportMode;
+
+    use crate::columns::{ColumnSpec, ColumnType, ColumnValue};
+    use crate::csv::CsvWriter;
+    use crate::reader::{Format, ResultsReader};
+    use crate::row::{AgentSnapshotRow, ContactRow, LinkVolumeRow, OdRow, RouteRow, TickSummaryRow, TripRow};
+    use crate::writer::OutputWriter;
+    use crate::{Compression, MemoryWriter};
+
+    pub(super) fn snapshots() -> Vec<AgentSnapshotRow> {
+        vec![
+            AgentSnapshotRow {
+                agent_id: 0, tick: 2, departure_node: 4, in_transit: false, destination_node: u32::MAX,
+                lat: Some(51.5), lon: Some(-0.25),
+                departure_tick: None, arrival_tick: None,
+            },
+            AgentSnapshotRow {
+                agent_id: 1, tick: 2, departure_node: 4, in_transit: true, destination_node: 7, lat: None, lon: None,
+                departure_tick: None, arrival_tick: None,
+            },
+        ]
+    }
+
+    pub(super) fn columns() -> Vec<ColumnSpec> {
+        vec![
+            ColumnSpec { name: "age".into(), ty: ColumnType::Int },
+            ColumnSpec { name: "score".into(), ty: ColumnType::Float },
+            ColumnSpec { name: "status".into(), ty: ColumnType::Text },
+        ]
+    }
+
+    /// Per row, as read back.
+    pub(super) fn values() -> Vec<Vec<ColumnValue>> {
+        vec![
+            vec![ColumnValue::Int(30), ColumnValue::Float(0.25), ColumnValue::Text("home".into())],
+            vec![ColumnValue::Null, ColumnValue::Float(1.5), ColumnValue::Text("work".into())],
+        ]
+    }
+
+    pub(super) fn trips() -> Vec<TripRow> {
+        vec![TripRow {
+            agent:       1,
+            depart_tick: 1,
+            arrive_tick: 3,
+            from:        4,
+            to:          7,
+            mode:        TransportMode::Car,
+            travel_secs: 90.5,
+            distance_m:  1200.0,
+        }]
+    }
+
+    pub(super) fn routes() -> Vec<RouteRow> {
+        vec![RouteRow { agent: 1, depart_tick: 1, from: 4, to: 7, mode: TransportMode::Walk, edges: vec![3, 0, 9] }]
+    }
+
+    /// A run with a row in every table.
+    pub(super) fn write_run(w: &mut impl OutputWriter) {
+        w.set_snapshot_columns(&columns()).unwrap();
+        let by_column: Vec<Vec<ColumnValue>> =
+            (0..columns().len()).map(|c| values().iter().map(|row| row[c].clone()).collect()).collect();
+        w.write_snapshots_with_columns(&snapshots(), &by_column).unwrap();
+        for tick in 0..3 {
+            w.write_tick_summary(&TickSummaryRow { tick, unix_time_secs: -60, arrivals: tick, ..Default::default() })
+                .unwrap();
+        }
+        w.write_contacts(&[ContactRow { tick: 2, agent_a: 0, agent_b: 1, node: 4 }]).unwrap();
+        w.write_trips(&trips()).unwrap();
+        w.write_routes(&routes()).unwrap();
+        w.write_od_matrix(&[OdRow { origin_zone: 1, dest_zone: 2, hour: 8, mode: TransportMode::Car, trips: 5 }])
+            .unwrap();
+        w.write_link_volumes(&[LinkVolumeRow { tick: 2, edge: 3, vehicles: 11 }]).unwrap();
+        w.finish().unwrap();
+    }
+
+    /// `dir` reads back as [`write_run`] wrote it.
+    pub(super) fn check_run(dir: &Path, format: Format) {
+        let reader = ResultsReader::open(dir).unwrap();
+        assert_eq!(reader.format(), format);
+        let run: MemoryWriter = reader.read_all().unwrap();
+        assert_eq!(run.snapshots, snapshots());
+        assert_eq!(run.snapshot_columns, columns());
+        assert_eq!(run.snapshot_values, values());
+        assert_eq!(run.tick_summaries.len(), 3);
+        assert_eq!((run.tick_summaries[2].unix_time_secs, run.tick_summaries[2].arrivals), (-60, 2));
+        assert_eq!(run.contacts, [ContactRow { tick: 2, agent_a: 0, agent_b: 1, node: 4 }]);
+        assert_eq!(run.trips, trips());
+        assert_eq!(run.routes, routes());
+        assert_eq!(run.od_matrix[0].hour, 8);
+        assert_eq!(run.link_volumes, [LinkVolumeRow { tick: 2, edge: 3, vehicles: 11 }]);
+    }
+
+    #[test]
+    fn csv_run_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        write_run(&mut CsvWriter::new(dir.path()).unwrap());
+        check_run(dir.path(), Format::Csv(Compression::None));
+    }
+
+    #[test]
+    fn missing_tables_and_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        // An older run: no routes file, and fewer tick summary counters.
+        let old = "tick,unix_time_secs,woken_agents\n4,14400,9\n";
+        std::fs::write(dir.path().join("tick_summaries.csv"), old).unwrap();
+        let reader = ResultsReader::open(dir.path()).unwrap();
+        let summaries = reader.tick_summaries().unwrap();
+        let expected = TickSummaryRow { tick: 4, unix_time_secs: 14400, woken_agents: 9, ..Default::default() };
+        assert_eq!(summaries, [expected]);
+        assert!(reader.routes().unwrap().is_empty());
+        assert!(reader.snapshots().unwrap().is_empty());
+
+        std::fs::write(dir.path().join("contacts.csv"), "tick,agent_a,agent_b,node\n1,x,2,3\n").unwrap();
+        assert!(reader.contacts().is_err());
+        std::fs::write(dir.path().join("trips.csv"), "agent,depart_tick\n1,2\n").unwrap();
+        assert!(reader.trips().is_err());
+
+        assert!(ResultsReader::open(&dir.path().join("nowhere")).is_err());
+    }
+
+    #[cfg(feature = "jsonl")]
+    #[test]
+    fn jsonl_run_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        write_run(&mut crate::jsonl::JsonlWriter::new(dir.path()).unwrap());
+        check_run(dir.path(), Format::Jsonl);
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn sqlite_run_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        write_run(&mut crate::sqlite::SqliteWriter::new(dir.path()).unwrap());
+        check_run(dir.path(), Format::Sqlite);
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet_run_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        write_run(&mut crate::parquet::ParquetWriter::new(dir.path()).unwrap());
+        check_run(dir.path(), Format::Parquet);
+    }
+
+    #[cfg(feature = "arrow-ipc")]
+    #[test]
+    fn ipc_run_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        write_run(&mut crate::ipc::ArrowIpcWriter::new(dir.path()).unwrap());
+        check_run(dir.path(), Format::ArrowIpc);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_run_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        write_run(&mut CsvWriter::new_compressed(dir.path(), Compression::Gzip).unwrap());
+        check_run(dir.path(), Format::Csv(Compression::Gzip));
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zstd_run_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        write_run(&mut CsvWriter::new_compressed(dir.path(), Compression::Zstd).unwrap());
+        check_run(dir.path(), Format::Csv(Compression::Zstd));
+    }
+}
+
+#[cfg(all(test, feature = "arrow"))]
+mod reader_arrow_tests {
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::{Float32Type, UInt32Type};
+    use dt_core::GeoPoint;
+    use dt_spatial::RoadNetworkBuilder;
+
+    use super::reader_tests::write_run;
+    use crate::csv::CsvWriter;
+    use crate::reader::{ResultsReader, join_edges, join_nodes};
+
+    #[test]
+    fn batches_join_the_network() {
+        let dir = tempfile::tempdir().unwrap();
+        write_run(&mut CsvWriter::new(dir.path()).unwrap());
+        let reader = ResultsReader::open(dir.path()).unwrap();
+        let snapshots = reader.snapshot_batch().unwrap();
+        assert_eq!(snapshots.num_rows(), 2);
+        assert!(snapshots.column_by_name("status").is_some());
+
+        let mut b = RoadNetworkBuilder::new();
+        let nodes: Vec<_> = (0..8).map(|i| b.add_node(GeoPoint::new(50.0 + i as f32, 1.0))).collect();
+        for pair in nodes.windows(2) {
+            b.add_road(pair[0], pair[1], 250.0, 20_000);
+        }
+        let network = b.build();
+
+        let trips = join_nodes(&reader.trip_batch().unwrap(), "to", &network).unwrap();
+        let lat = trips.column_by_name("to_lat").unwrap().as_primitive::<Float32Type>();
+        assert_eq!(lat.value(0), 57.0);
+        let contacts = join_nodes(&reader.contact_batch().unwrap(), "node", &network).unwrap();
+        assert_eq!(contacts.num_columns(), reader.contact_batch().unwrap().num_columns() + 2);
+
+        let volumes = join_edges(&reader.link_volume_batch().unwrap(), "edge_id", &network).unwrap();
+        let from = volumes.column_by_name("edge_id_from").unwrap().as_primitive::<UInt32Type>();
+        assert_eq!(from.value(0), network.edge_from[3].0);
+        let length = volumes.column_by_name("edge_id_length_m").unwrap().as_primitive::<Float32Type>();
+        assert_eq!(length.value(0), 250.0);
+
+        // Out-of-network ids join to nulls; a missing column is an error.
+        let far = join_edges(&reader.link_volume_batch().unwrap(), "edge_id", &RoadNetworkBuilder::new().build());
+        assert!(far.unwrap().column_by_name("edge_id_to").unwrap().is_null(0));
+        assert!(join_nodes(&trips, "nowhere", &network).is_err());
     }
 }
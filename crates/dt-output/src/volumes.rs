@@ -0,0 +1,112 @@
+//! Link volume accumulation.
+//!
+//! [`LinkVolumes`] counts the vehicles entering each road edge per
+//! aggregation interval.  Movement is teleport-at-arrival, so each journey's
+//! route is expanded when it departs: the journey's ticks are spread over its
+//! edges in proportion to their free-flow travel time (as in
+//! `MobilityStore::current_edge`), giving the tick each edge is entered.
+//!
+//! Attach one to the output observer with
+//! [`SimOutputObserver::with_link_volumes`][crate::SimOutputObserver::with_link_volumes];
+//! each interval is written through [`OutputWriter::write_link_volumes`]
+//! once no later departure can add to it:
+//!
+//! ```rust,ignore
+//! // Hourly car volumes with one-minute ticks.
+//! let obs = SimOutputObserver::new(writer, &config)
+//!     .with_link_volumes(&network, 60, &[TransportMode::Car]);
+//! ```
+//!
+//! [`OutputWriter::write_link_volumes`]: crate::OutputWriter::write_link_volumes
+
+use std::collections::BTreeMap;
+
+use dt_core::{EdgeId, TransportMode};
+use dt_mobility::MovementState;
+use dt_spatial::{RoadNetwork, Route};
+
+use crate::row::LinkVolumeRow;
+
+/// Vehicle counts keyed by (interval start tick, edge).
+pub struct LinkVolumes {
+    /// Free-flow travel time of each edge, indexed by `EdgeId`.
+    edge_ms:  Vec<u32>,
+    interval: u64,
+    /// Modes counted; empty counts every mode.
+    modes:    Vec<TransportMode>,
+    counts:   BTreeMap<(u64, u32), u64>,
+}
+
+impl LinkVolumes {
+    /// An empty accumulator over `network`'s edges, counting journeys in
+    /// `modes` (every mode if empty) per `interval_ticks` ticks.
+    ///
+    /// The edge table is copied, so the network may be moved into the sim
+    /// afterwards.  An interval of `0` is treated as `1`.
+    pub fn new(network: &RoadNetwork, interval_ticks: u64, modes: &[TransportMode]) -> Self {
+        Self {
+            edge_ms:  network.edge_travel_ms.clone(),
+            interval: interval_ticks.max(1),
+            modes:    modes.to_vec(),
+            counts:   BTreeMap::new(),
+        }
+    }
+
+    /// Ticks per aggregation interval.
+    pub fn interval_ticks(&self) -> u64 {
+        self.interval
+    }
+
+    /// Count a journey in `state` that just departed along `route`.
+    ///
+    /// Edges unknown to the network are skipped.
+    pub fn record(&mut self, state: &MovementState, route: &Route) {
+        if !self.modes.is_empty() && !self.modes.contains(&state.mode) {
+            return;
+        }
+        let weight = |e: &EdgeId| self.edge_ms.get(e.index()).map(|&ms| ms as u64);
+        let total: u64 = route.edges.iter().filter_map(weight).sum();
+        let depart = state.departure_tick.0;
+        let ticks = state.arrival_tick.0.saturating_sub(depart);
+
+        let mut covered = 0u64;
+        for edge in &route.edges {
+            let Some(ms) = weight(edge) else { continue };
+            let offset = (covered * ticks).checked_div(total).unwrap_or(0);
+            let entered = depart + offset;
+            *self.counts.entry((entered - entered % self.interval, edge.0)).or_default() += 1;
+            covered += ms;
+        }
+    }
+
+    /// Total edge entries counted and not yet taken.
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Remove and return the intervals lying wholly before `end_tick`,
+    /// sorted by interval and edge.
+    ///
+    /// Journeys depart at the current tick or later, so once tick `t` has
+    /// ended the intervals ending by `t + 1` are complete.
+    pub fn take_until(&mut self, end_tick: u64) -> Vec<LinkVolumeRow> {
+        // The interval containing `end_tick` is the first still open.
+        let first_open = end_tick - end_tick % self.interval;
+        let open = self.counts.split_off(&(first_open, 0));
+        let done = std::mem::replace(&mut self.counts, open);
+        done.into_iter()
+            .map(|((tick, edge), vehicles)| LinkVolumeRow { tick, edge, vehicles })
+            .collect()
+    }
+
+    /// Remove and return every interval starting before `end_tick`; later
+    /// ones (edges entered after the run ends) are dropped.
+    pub fn take_all(&mut self, end_tick: u64) -> Vec<LinkVolumeRow> {
+        let counts = std::mem::take(&mut self.counts);
+        counts
+            .into_iter()
+            .filter(|&((tick, _), _)| tick < end_tick)
+            .map(|((tick, edge), vehicles)| LinkVolumeRow { tick, edge, vehicles })
+            .collect()
+    }
+}
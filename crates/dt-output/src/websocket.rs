@@ -0,0 +1,252 @@
+//! WebSocket live-streaming backend (feature `websocket`).
+//!
+//! [`WebSocketWriter`] listens on a TCP address and pushes every snapshot
+//! to the connected clients as one frame, so a map front-end can follow the
+//! run live without writing or polling files.  Clients may connect and
+//! disconnect at any time; each receives the snapshots taken after it
+//! connected.
+//!
+//! Only agents with a position are sent, so the observer must be given the
+//! network with
+//! [`SimOutputObserver::with_network`][crate::SimOutputObserver::with_network].
+//! Large populations can be thinned with
+//! [`sample_every`][WebSocketWriter::sample_every].  Tick summaries, contacts,
+//! trips, and the other tables are not sent.
+//!
+//! [`FrameFormat::Json`] sends a text frame per snapshot, one
+//! `[agent_id, lat, lon, in_transit]` array per agent:
+//!
+//! ```text
+//! {"tick":12,"agents":[[3,52.52,13.405,0],[7,52.51,13.39,1]]}
+//! ```
+//!
+//! [`FrameFormat::Binary`] sends the same data little-endian: a `u64` tick
+//! and a `u32` agent count, then per agent a `u32` agent id, `f32` lat,
+//! `f32` lon, and `u32` flags (bit 0: in transit).  Every field is 4-byte
+//! aligned, so the body can be viewed as a `Float32Array`/`Uint32Array`.
+//!
+//! ```rust,ignore
+//! let writer = WebSocketWriter::bind("0.0.0.0:9001")?.sample_every(10);
+//! let mut obs = SimOutputObserver::new(writer, &config).with_network(&network);
+//! ```
+
+use std::fmt::Write as _;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use tungstenite::{Message, WebSocket};
+
+use crate::columns;
+use crate::writer::OutputWriter;
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnValue, ContactRow, LinkVolumeRow, OdRow, OutputResult,
+    RouteRow, TickSummaryRow, TripRow,
+};
+
+/// How long a client may take to complete the handshake or accept a frame
+/// before it is dropped.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often the accept thread checks whether the writer has finished.
+const ACCEPT_POLL: Duration = Duration::from_millis(20);
+
+/// Encoding of snapshot frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameFormat {
+    /// One JSON text frame per snapshot.
+    #[default]
+    Json,
+    /// One packed little-endian binary frame per snapshot.
+    Binary,
+}
+
+type Clients = Arc<Mutex<Vec<WebSocket<TcpStream>>>>;
+
+/// Streams agent positions to WebSocket clients, one frame per snapshot.
+///
+/// A background thread accepts connections.  A client that cannot take a
+/// frame within one second is dropped, so a stalled browser tab does not
+/// hold up the run.  `finish()` closes every connection and stops listening.
+pub struct WebSocketWriter {
+    addr:     SocketAddr,
+    clients:  Clients,
+    stop:     Arc<AtomicBool>,
+    acceptor: Option<JoinHandle<()>>,
+    format:   FrameFormat,
+    every:    u32,
+}
+
+impl WebSocketWriter {
+    /// Listen on `addr` (e.g. `"0.0.0.0:9001"`; port `0` picks a free port,
+    /// see [`local_addr`][Self::local_addr]).
+    pub fn bind(addr: impl ToSocketAddrs) -> OutputResult<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let clients = Clients::default();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let acceptor = {
+            let (clients, stop) = (Arc::clone(&clients), Arc::clone(&stop));
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            // A failed handshake only loses that client.
+                            if let Some(ws) = handshake(stream)
+                                && let Ok(mut clients) = clients.lock()
+                            {
+                                clients.push(ws);
+                            }
+                        }
+                        Err(_) => std::thread::sleep(ACCEPT_POLL),
+                    }
+                }
+            })
+        };
+
+        Ok(Self {
+            addr,
+            clients,
+            stop,
+            acceptor: Some(acceptor),
+            format:   FrameFormat::default(),
+            every:    1,
+        })
+    }
+
+    /// Frame encoding.  Default: [`FrameFormat::Json`].
+    pub fn format(mut self, format: FrameFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Send only agents whose id is a multiple of `n`, so the same agents
+    /// are shown every frame.  Default: 1 (every agent).
+    pub fn sample_every(mut self, n: u32) -> Self {
+        self.every = n.max(1);
+        self
+    }
+
+    /// The address the writer listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Number of connected clients.
+    pub fn clients(&self) -> usize {
+        self.clients.lock().map_or(0, |c| c.len())
+    }
+
+    fn encode(&self, tick: u64, rows: &[&AgentSnapshotRow]) -> Message {
+        let positions = rows.iter().filter_map(|r| Some((r.agent_id, r.lat?, r.lon?, r.in_transit)));
+        match self.format {
+            FrameFormat::Json => {
+                let mut text = format!("{{\"tick\":{tick},\"agents\":[");
+                for (i, (id, lat, lon, moving)) in positions.enumerate() {
+                    let sep = if i == 0 { "" } else { "," };
+                    let _ = write!(text, "{sep}[{id},{lat},{lon},{}]", moving as u8);
+                }
+                text.push_str("]}");
+                Message::Text(text)
+            }
+            FrameFormat::Binary => {
+                let positions: Vec<_> = positions.collect();
+                let mut bytes = Vec::with_capacity(12 + 16 * positions.len());
+                bytes.extend_from_slice(&tick.to_le_bytes());
+                bytes.extend_from_slice(&(positions.len() as u32).to_le_bytes());
+                for (id, lat, lon, moving) in positions {
+                    bytes.extend_from_slice(&id.to_le_bytes());
+                    bytes.extend_from_slice(&lat.to_le_bytes());
+                    bytes.extend_from_slice(&lon.to_le_bytes());
+                    bytes.extend_from_slice(&(moving as u32).to_le_bytes());
+                }
+                Message::Binary(bytes)
+            }
+        }
+    }
+}
+
+/// Complete the server handshake on a newly accepted connection.
+fn handshake(stream: TcpStream) -> Option<WebSocket<TcpStream>> {
+    stream.set_nonblocking(false).ok()?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT)).ok()?;
+    tungstenite::accept(stream).ok()
+}
+
+impl OutputWriter for WebSocketWriter {
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()> {
+        // Extra columns are not sent, but are still checked.
+        columns::validate(columns)
+    }
+
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:     &[AgentSnapshotRow],
+        _columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()> {
+        let Some(first) = rows.first() else {
+            return Ok(());
+        };
+        let Ok(mut clients) = self.clients.lock() else {
+            return Ok(());
+        };
+        if clients.is_empty() {
+            return Ok(());
+        }
+        let sampled: Vec<&AgentSnapshotRow> =
+            rows.iter().filter(|r| r.agent_id.is_multiple_of(self.every)).collect();
+        let frame = self.encode(first.tick, &sampled);
+        // Drop every client the frame could not be delivered to.
+        clients.retain_mut(|ws| ws.send(frame.clone()).is_ok());
+        Ok(())
+    }
+
+    fn write_tick_summary(&mut self, _row: &TickSummaryRow) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_contacts(&mut self, _rows: &[ContactRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_trips(&mut self, _rows: &[TripRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_routes(&mut self, _rows: &[RouteRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_od_matrix(&mut self, _rows: &[OdRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn write_link_volumes(&mut self, _rows: &[LinkVolumeRow]) -> OutputResult<()> {
+        Ok(())
+    }
+
+    fn finish(&mut self) -> OutputResult<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(acceptor) = self.acceptor.take() {
+            let _ = acceptor.join();
+        }
+        if let Ok(mut clients) = self.clients.lock() {
+            for mut ws in clients.drain(..) {
+                let _ = ws.close(None);
+                let _ = ws.flush();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for WebSocketWriter {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
@@ -1,20 +1,133 @@
 //! The `OutputWriter` trait implemented by all backend writers.
 
-use crate::{AgentSnapshotRow, OutputResult, TickSummaryRow};
+use std::path::Path;
+
+use crate::{
+    AgentSnapshotRow, ColumnSpec, ColumnValue, ContactRow, LinkVolumeRow, OdRow, OutputResult,
+    RouteRow, TickSummaryRow, TripRow,
+};
 
 /// Trait implemented by CSV, SQLite, and Parquet writers.
 ///
 /// All methods are infallible from the observer's perspective — errors are
 /// stored internally and retrieved with [`SimOutputObserver::take_error`].
 pub trait OutputWriter {
-    /// Write a batch of agent snapshots.
-    fn write_snapshots(&mut self, rows: &[AgentSnapshotRow]) -> OutputResult<()>;
+    /// Declare extra snapshot columns, appended after the built-in ones.
+    ///
+    /// Replaces any earlier declaration.  Fails once snapshots have been
+    /// written, or if a name is empty, repeated, or a built-in column.
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()>;
+
+    /// Write a batch of agent snapshots.  Declared extra columns are null.
+    fn write_snapshots(&mut self, rows: &[AgentSnapshotRow]) -> OutputResult<()> {
+        self.write_snapshots_with_columns(rows, &[])
+    }
+
+    /// Write a batch of agent snapshots with extra column values.
+    ///
+    /// `columns[c][i]` is the value of declared column `c` for `rows[i]`;
+    /// missing columns or cells are written as null.
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:    &[AgentSnapshotRow],
+        columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()>;
 
     /// Write one tick summary row.
     fn write_tick_summary(&mut self, row: &TickSummaryRow) -> OutputResult<()>;
 
+    /// Write a batch of contact events.
+    fn write_contacts(&mut self, rows: &[ContactRow]) -> OutputResult<()>;
+
+    /// Write a batch of completed trips.
+    fn write_trips(&mut self, rows: &[TripRow]) -> OutputResult<()>;
+
+    /// Write a batch of chosen routes, one per sampled departure.
+    fn write_routes(&mut self, rows: &[RouteRow]) -> OutputResult<()>;
+
+    /// Write the origin–destination matrix.  Called at most once, at the
+    /// end of the run.
+    fn write_od_matrix(&mut self, rows: &[OdRow]) -> OutputResult<()>;
+
+    /// Write link volumes for intervals that have closed.  Called as each
+    /// interval completes and once more at the end of the run.
+    fn write_link_volumes(&mut self, rows: &[LinkVolumeRow]) -> OutputResult<()>;
+
+    /// Local directory the writer's files are created in.  `None` for
+    /// writers without one.
+    fn output_dir(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Write `contents` as file `name` alongside the writer's own files;
+    /// the observer writes `run_manifest.json` and `run_summary.json` this
+    /// way.  Default: into [`output_dir`][Self::output_dir], or nowhere if
+    /// there is none.
+    fn write_file(&mut self, name: &str, contents: &[u8]) -> OutputResult<()> {
+        if let Some(dir) = self.output_dir() {
+            std::fs::write(dir.join(name), contents)?;
+        }
+        Ok(())
+    }
+
     /// Flush and close all underlying file handles.
     ///
     /// Idempotent — safe to call more than once.
     fn finish(&mut self) -> OutputResult<()>;
 }
+
+/// Boxed writers, so the backend can be chosen at run time
+/// (`SimOutputObserver<Box<dyn OutputWriter>>`).
+impl<W: OutputWriter + ?Sized> OutputWriter for Box<W> {
+    fn set_snapshot_columns(&mut self, columns: &[ColumnSpec]) -> OutputResult<()> {
+        (**self).set_snapshot_columns(columns)
+    }
+
+    fn write_snapshots(&mut self, rows: &[AgentSnapshotRow]) -> OutputResult<()> {
+        (**self).write_snapshots(rows)
+    }
+
+    fn write_snapshots_with_columns(
+        &mut self,
+        rows:    &[AgentSnapshotRow],
+        columns: &[Vec<ColumnValue>],
+    ) -> OutputResult<()> {
+        (**self).write_snapshots_with_columns(rows, columns)
+    }
+
+    fn write_tick_summary(&mut self, row: &TickSummaryRow) -> OutputResult<()> {
+        (**self).write_tick_summary(row)
+    }
+
+    fn write_contacts(&mut self, rows: &[ContactRow]) -> OutputResult<()> {
+        (**self).write_contacts(rows)
+    }
+
+    fn write_trips(&mut self, rows: &[TripRow]) -> OutputResult<()> {
+        (**self).write_trips(rows)
+    }
+
+    fn write_routes(&mut self, rows: &[RouteRow]) -> OutputResult<()> {
+        (**self).write_routes(rows)
+    }
+
+    fn write_od_matrix(&mut self, rows: &[OdRow]) -> OutputResult<()> {
+        (**self).write_od_matrix(rows)
+    }
+
+    fn write_link_volumes(&mut self, rows: &[LinkVolumeRow]) -> OutputResult<()> {
+        (**self).write_link_volumes(rows)
+    }
+
+    fn output_dir(&self) -> Option<&Path> {
+        (**self).output_dir()
+    }
+
+    fn write_file(&mut self, name: &str, contents: &[u8]) -> OutputResult<()> {
+        (**self).write_file(name, contents)
+    }
+
+    fn finish(&mut self) -> OutputResult<()> {
+        (**self).finish()
+    }
+}
@@ -0,0 +1,184 @@
+//! `SimConfig` — the run configuration.
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
+use serde_json::{Map, Number, Value};
+
+use dt_core::SimConfig;
+
+use crate::{PyDtError, PyDtResult};
+
+/// Run configuration, as the `[sim]` table of a scenario file.
+///
+/// Keyword arguments are the table's keys, so times and durations may be
+/// strings and subsystem sections are dicts.  Keys that are not given keep
+/// their `SimConfig::default()` values:
+///
+/// ```python
+/// config = SimConfig(
+///     start_unix_secs="2024-03-04T00:00:00Z",
+///     total_ticks="7d",
+///     seed=42,
+///     contacts={"edge_contacts": True},
+/// )
+/// ```
+#[pyclass(name = "SimConfig", module = "rust_dt")]
+#[derive(Clone)]
+pub struct PySimConfig {
+    pub(crate) inner: SimConfig,
+}
+
+#[pymethods]
+impl PySimConfig {
+    #[new]
+    #[pyo3(signature = (**kwargs))]
+    pub fn new(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let value = match kwargs {
+            Some(kwargs) => to_json(kwargs.as_any())?,
+            None => Value::Object(Map::new()),
+        };
+        Ok(Self::from_value(value)?)
+    }
+
+    /// Parse a `[sim]` table written in TOML.
+    #[staticmethod]
+    pub fn from_toml(text: &str) -> PyResult<Self> {
+        let value = toml::from_str(text).map_err(|e| invalid(e.message()))?;
+        Ok(Self::from_value(value)?)
+    }
+
+    /// Parse a `sim` object written in JSON.
+    #[staticmethod]
+    pub fn from_json(text: &str) -> PyResult<Self> {
+        let value = serde_json::from_str(text).map_err(|e| invalid(&e.to_string()))?;
+        Ok(Self::from_value(value)?)
+    }
+
+    #[getter]
+    pub fn start_unix_secs(&self) -> i64 {
+        self.inner.start_unix_secs
+    }
+
+    #[setter]
+    pub fn set_start_unix_secs(&mut self, value: i64) {
+        self.inner.start_unix_secs = value;
+    }
+
+    #[getter]
+    pub fn tick_duration_secs(&self) -> u32 {
+        self.inner.tick_duration_secs
+    }
+
+    #[setter]
+    pub fn set_tick_duration_secs(&mut self, value: u32) {
+        self.inner.tick_duration_secs = value;
+    }
+
+    #[getter]
+    pub fn total_ticks(&self) -> u64 {
+        self.inner.total_ticks
+    }
+
+    #[setter]
+    pub fn set_total_ticks(&mut self, value: u64) {
+        self.inner.total_ticks = value;
+    }
+
+    #[getter]
+    pub fn seed(&self) -> u64 {
+        self.inner.seed
+    }
+
+    #[setter]
+    pub fn set_seed(&mut self, value: u64) {
+        self.inner.seed = value;
+    }
+
+    #[getter]
+    pub fn output_interval_ticks(&self) -> u64 {
+        self.inner.output_interval_ticks
+    }
+
+    #[setter]
+    pub fn set_output_interval_ticks(&mut self, value: u64) {
+        self.inner.output_interval_ticks = value;
+    }
+
+    #[getter]
+    pub fn num_threads(&self) -> Option<usize> {
+        self.inner.num_threads
+    }
+
+    #[setter]
+    pub fn set_num_threads(&mut self, value: Option<usize>) {
+        self.inner.num_threads = value;
+    }
+
+    fn __repr__(&self) -> String {
+        let c = &self.inner;
+        format!(
+            "SimConfig(start_unix_secs={}, tick_duration_secs={}, total_ticks={}, seed={})",
+            c.start_unix_secs, c.tick_duration_secs, c.total_ticks, c.seed,
+        )
+    }
+}
+
+impl PySimConfig {
+    /// The config `value` describes, on top of the default config.
+    fn from_value(value: Value) -> PyDtResult<Self> {
+        let mut merged = serde_json::to_value(SimConfig::default()).map_err(|e| invalid(&e.to_string()))?;
+        merge(&mut merged, value);
+        let inner = serde_json::from_value(merged).map_err(|e| invalid(&e.to_string()))?;
+        Ok(Self { inner })
+    }
+}
+
+/// Overwrite `base` with `over`, key by key where both are objects.
+fn merge(base: &mut Value, over: Value) {
+    match (base, over) {
+        (Value::Object(base), Value::Object(over)) => {
+            for (key, value) in over {
+                match base.get_mut(&key) {
+                    Some(slot) => merge(slot, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, over) => *base = over,
+    }
+}
+
+fn invalid(message: &str) -> PyDtError {
+    PyDtError::Invalid(format!("invalid SimConfig: {message}"))
+}
+
+/// `obj` as JSON: dicts with string keys, lists and tuples, strings,
+/// numbers, booleans, and `None`.
+pub(crate) fn to_json(obj: &Bound<'_, PyAny>) -> PyResult<Value> {
+    // `bool` is a subclass of `int`, so it is checked first.
+    if obj.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(b) = obj.downcast::<PyBool>() {
+        Ok(Value::Bool(b.is_true()))
+    } else if obj.is_instance_of::<PyInt>() {
+        match obj.extract::<i64>() {
+            Ok(n) => Ok(Value::from(n)),
+            Err(_) => Ok(Value::from(obj.extract::<u64>()?)),
+        }
+    } else if let Ok(f) = obj.downcast::<PyFloat>() {
+        Ok(Number::from_f64(f.value()).map_or(Value::Null, Value::Number))
+    } else if let Ok(s) = obj.downcast::<PyString>() {
+        Ok(Value::String(s.to_str()?.to_owned()))
+    } else if let Ok(dict) = obj.downcast::<PyDict>() {
+        dict.iter()
+            .map(|(k, v)| Ok((k.extract::<String>()?, to_json(&v)?)))
+            .collect::<PyResult<Map<_, _>>>()
+            .map(Value::Object)
+    } else if obj.is_instance_of::<PyList>() || obj.is_instance_of::<PyTuple>() {
+        obj.iter()?.map(|item| to_json(&item?)).collect::<PyResult<_>>().map(Value::Array)
+    } else {
+        Err(PyDtError::Invalid(format!("cannot convert {} to a config value", obj.get_type().name()?)).into())
+    }
+}
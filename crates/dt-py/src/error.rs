@@ -0,0 +1,40 @@
+//! Error types for dt-py and their Python exceptions.
+
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::{PyErr, create_exception};
+use thiserror::Error;
+
+create_exception!(rust_dt, DtError, PyException, "Raised when loading inputs or running a simulation fails.");
+
+/// Errors raised to Python by the bindings.
+///
+/// `Invalid` becomes a `ValueError`; everything else a `rust_dt.DtError`.
+#[derive(Debug, Error)]
+pub enum PyDtError {
+    #[error("{0}")]
+    Invalid(String),
+
+    #[error(transparent)]
+    Cli(#[from] dt_cli::CliError),
+
+    #[error(transparent)]
+    Core(#[from] dt_core::DtError),
+
+    #[error(transparent)]
+    Sim(#[from] dt_sim::SimError),
+
+    #[error(transparent)]
+    Output(#[from] dt_output::OutputError),
+}
+
+/// Alias for `Result<T, PyDtError>`.
+pub type PyDtResult<T> = Result<T, PyDtError>;
+
+impl From<PyDtError> for PyErr {
+    fn from(err: PyDtError) -> Self {
+        match err {
+            PyDtError::Invalid(message) => PyValueError::new_err(message),
+            other => DtError::new_err(other.to_string()),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! `dt-py` — Python bindings for rust_dt.
+//!
+//! The `rust_dt` Python module builds or loads a road network, loads
+//! activity plans, configures a run, runs it with one of dt-cli's built-in
+//! behaviors, and returns the output as `pyarrow` tables — no files
+//! needed:
+//!
+//! ```python
+//! import rust_dt as dt
+//!
+//! network = dt.Network.grid(rows=10, cols=10, spacing_m=500)
+//! config = dt.SimConfig(start_unix_secs="2024-03-04T00:00:00Z", total_ticks="7d", seed=42)
+//! plans = dt.Plans.daily(agents=1000, depart="8h", work="9h")
+//!
+//! results = dt.run(config, network, plans, behavior="commute", mode="car")
+//! trips = results.trips().to_pandas()
+//! ```
+//!
+//! Build and install the module into the active environment with
+//! [maturin](https://www.maturin.rs) from this crate's directory:
+//!
+//! ```text
+//! maturin develop --release
+//! ```
+//!
+//! # Crate layout
+//!
+//! | Module      | Contents                                              |
+//! |-------------|-------------------------------------------------------|
+//! | [`config`]  | `SimConfig` (keyword arguments, TOML, or JSON)        |
+//! | [`network`] | `Network`: grid, explicit edges, or OSM PBF           |
+//! | [`plans`]   | `Plans`: CSV, Parquet, or a daily template            |
+//! | [`run`]     | `run` and `Results` (Arrow tables)                    |
+//! | [`error`]   | `PyDtError`, `PyDtResult<T>`, the `DtError` exception |
+//!
+//! # Feature flags
+//!
+//! | Feature            | Enables                                        |
+//! |--------------------|------------------------------------------------|
+//! | `extension-module` | building for import from Python (maturin sets) |
+//! | `osm`              | `Network.from_osm`                             |
+//! | `parquet`          | `Plans.from_parquet`                           |
+
+// pyo3 0.22's macros expand to unsafe calls outside `unsafe` blocks, which
+// edition 2024 warns about, and to `PyErr::from(PyErr)` conversions.
+#![allow(unsafe_op_in_unsafe_fn, clippy::useless_conversion)]
+
+use pyo3::prelude::*;
+
+pub mod config;
+pub mod error;
+pub mod network;
+pub mod plans;
+pub mod run;
+
+#[cfg(test)]
+mod tests;
+
+pub use config::PySimConfig;
+pub use error::{DtError, PyDtError, PyDtResult};
+pub use network::PyNetwork;
+pub use plans::PyPlans;
+pub use run::PyResults;
+
+/// The `rust_dt` Python module.
+#[pymodule]
+fn rust_dt(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PySimConfig>()?;
+    m.add_class::<PyNetwork>()?;
+    m.add_class::<PyPlans>()?;
+    m.add_class::<PyResults>()?;
+    m.add_function(wrap_pyfunction!(run::run, m)?)?;
+    m.add("DtError", m.py().get_type_bound::<DtError>())?;
+    Ok(())
+}
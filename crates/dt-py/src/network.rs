@@ -0,0 +1,97 @@
+//! `Network` — building and loading road networks.
+
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+
+use dt_cli::GridSpec;
+use dt_core::{GeoPoint, NodeId};
+use dt_spatial::{RoadNetwork, RoadNetworkBuilder};
+
+use crate::PyDtError;
+
+/// A road network.  Runs use a copy, so one network can be run many
+/// times.
+#[pyclass(name = "Network", module = "rust_dt", frozen)]
+pub struct PyNetwork {
+    pub(crate) inner: RoadNetwork,
+}
+
+#[pymethods]
+impl PyNetwork {
+    /// A `rows` × `cols` grid with two-way roads between neighbours, as
+    /// `[network.grid]` in a scenario.  Node `r * cols + c` is row `r`
+    /// (from the south) and column `c` (from the west).
+    #[staticmethod]
+    #[pyo3(signature = (rows, cols, spacing_m = 500.0, speed_kmh = 50.0, origin = (0.0, 0.0)))]
+    pub fn grid(rows: u32, cols: u32, spacing_m: f32, speed_kmh: f32, origin: (f32, f32)) -> PyResult<Self> {
+        if rows == 0 || cols == 0 || spacing_m <= 0.0 || speed_kmh <= 0.0 {
+            return Err(PyDtError::Invalid("rows, cols, spacing_m, and speed_kmh must be positive".into()).into());
+        }
+        let spec = GridSpec { rows, cols, spacing_m, speed_kmh, origin: [origin.0, origin.1] };
+        Ok(Self { inner: dt_cli::network::grid_network(&spec) })
+    }
+
+    /// A network from `(lat, lon)` nodes and `(from, to, length_m,
+    /// travel_ms)` roads between node indices.  Roads are two-way unless
+    /// `directed`.
+    #[staticmethod]
+    #[pyo3(signature = (nodes, roads, directed = false))]
+    pub fn from_edges(nodes: Vec<(f32, f32)>, roads: Vec<(u32, u32, f32, u32)>, directed: bool) -> PyResult<Self> {
+        let mut b = RoadNetworkBuilder::with_capacity(nodes.len(), roads.len() * 2);
+        for (lat, lon) in nodes {
+            b.add_node(GeoPoint::new(lat, lon));
+        }
+        for (from, to, length_m, travel_ms) in roads {
+            if from as usize >= b.node_count() || to as usize >= b.node_count() {
+                return Err(PyDtError::Invalid(format!("road ({from}, {to}) names a node that does not exist")).into());
+            }
+            let (from, to) = (NodeId(from), NodeId(to));
+            if directed {
+                b.add_directed_edge(from, to, length_m, travel_ms);
+            } else {
+                b.add_road(from, to, length_m, travel_ms);
+            }
+        }
+        Ok(Self { inner: b.build() })
+    }
+
+    /// The drivable roads of an OSM PBF file (requires feature `osm`).
+    #[staticmethod]
+    pub fn from_osm(path: PathBuf) -> PyResult<Self> {
+        let spec = dt_cli::NetworkSpec { osm: Some(path), grid: None };
+        let inner = dt_cli::network::load_network(&spec).map_err(PyDtError::from)?;
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    pub fn node_count(&self) -> usize {
+        self.inner.node_count()
+    }
+
+    #[getter]
+    pub fn edge_count(&self) -> usize {
+        self.inner.edge_count()
+    }
+
+    /// `(lat, lon)` of node `node`.
+    pub fn node_position(&self, node: usize) -> PyResult<(f32, f32)> {
+        let pos = self.inner.node_pos.get(node).ok_or_else(|| {
+            PyDtError::Invalid(format!("node {node} is not in the network"))
+        })?;
+        Ok((pos.lat, pos.lon))
+    }
+
+    /// The node nearest `(lat, lon)`, or `None` for an empty network.
+    pub fn nearest_node(&self, lat: f32, lon: f32) -> Option<u32> {
+        self.inner.snap_to_node(GeoPoint::new(lat, lon)).map(|n| n.0)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.node_count()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Network(nodes={}, edges={})", self.inner.node_count(), self.inner.edge_count())
+    }
+}
@@ -0,0 +1,81 @@
+//! `Plans` — one activity plan per agent.
+
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+
+use dt_cli::{DailyPlan, PlansSpec};
+use dt_core::timefmt;
+use dt_schedule::ActivityPlan;
+
+use crate::{PyDtError, PyDtResult};
+
+/// One activity plan per agent, indexed by agent id.
+#[pyclass(name = "Plans", module = "rust_dt", frozen)]
+pub struct PyPlans {
+    pub(crate) inner: Vec<ActivityPlan>,
+}
+
+#[pymethods]
+impl PyPlans {
+    /// Plans from a CSV in the `dt_schedule::load_plans_csv` format.
+    /// Agents without rows get an empty plan.
+    #[staticmethod]
+    pub fn from_csv(path: PathBuf, agents: usize) -> PyResult<Self> {
+        Ok(Self::load(&PlansSpec { csv: Some(path), ..Default::default() }, agents, 0)?)
+    }
+
+    /// Plans from a Parquet file with the CSV's columns (requires feature
+    /// `parquet`).
+    #[staticmethod]
+    pub fn from_parquet(path: PathBuf, agents: usize) -> PyResult<Self> {
+        Ok(Self::load(&PlansSpec { parquet: Some(path), ..Default::default() }, agents, 0)?)
+    }
+
+    /// The same day for every agent, as `[plans.daily]` in a scenario:
+    /// work from `depart`, then home.  `depart` and `work` are seconds or
+    /// durations such as `"8h30m"`.
+    #[staticmethod]
+    #[pyo3(signature = (agents, depart, work, tick_duration_secs = 3600))]
+    pub fn daily(
+        agents:             usize,
+        depart:             &Bound<'_, PyAny>,
+        work:               &Bound<'_, PyAny>,
+        tick_duration_secs: u32,
+    ) -> PyResult<Self> {
+        let daily = DailyPlan { depart: secs(depart)?, work: secs(work)? };
+        if daily.depart.saturating_add(daily.work) > 86_400 {
+            return Err(PyDtError::Invalid("`depart` + `work` exceeds one day".into()).into());
+        }
+        Ok(Self::load(&PlansSpec { daily: Some(daily), ..Default::default() }, agents, tick_duration_secs)?)
+    }
+
+    /// An empty plan for each of `agents` agents.
+    #[staticmethod]
+    pub fn empty(agents: usize) -> Self {
+        Self { inner: vec![ActivityPlan::empty(); agents] }
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Plans(agents={})", self.inner.len())
+    }
+}
+
+impl PyPlans {
+    fn load(spec: &PlansSpec, agents: usize, tick_duration_secs: u32) -> PyDtResult<Self> {
+        Ok(Self { inner: dt_cli::plans::load_plans(spec, agents, tick_duration_secs)? })
+    }
+}
+
+/// Seconds from an `int`, or from a duration string such as `"1h30m"`.
+fn secs(obj: &Bound<'_, PyAny>) -> PyResult<u64> {
+    if let Ok(secs) = obj.extract::<u64>() {
+        return Ok(secs);
+    }
+    let text: String = obj.extract()?;
+    Ok(timefmt::parse_duration(&text).map_err(|e| PyDtError::Invalid(e.to_string()))?)
+}
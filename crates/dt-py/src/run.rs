@@ -0,0 +1,153 @@
+//! `run` and its `Results`.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use arrow::pyarrow::ToPyArrow;
+use arrow::record_batch::RecordBatch;
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use dt_cli::scenario::parse_mode;
+use dt_cli::{BuiltinBehavior, Population, PopulationSpec};
+use dt_core::SimConfig;
+use dt_output::{MemoryWriter, OutputResult, SimOutputObserver};
+use dt_schedule::ActivityPlan;
+use dt_sim::{FailurePolicy, SimBuilder};
+use dt_spatial::{DijkstraRouter, RoadNetwork};
+
+use crate::{PyDtError, PyDtResult, PyNetwork, PyPlans, PySimConfig};
+
+/// Run a simulation with a built-in behavior and keep its output in
+/// memory.
+///
+/// `agents` defaults to the number of plans.  `attributes` is a CSV of
+/// `agent_id,home,work[,mode]` rows; agents without one get a random home
+/// and work node.  `behavior` is `"commute"` or `"noop"`, and `mode` the
+/// mode of agents whose attributes name none.  Without `plans` every plan
+/// is empty.  The GIL is released while the simulation runs.
+#[pyfunction]
+#[pyo3(signature = (config, network, plans = None, *, agents = None, attributes = None, behavior = "commute", mode = "car"))]
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    py:         Python<'_>,
+    config:     &PySimConfig,
+    network:    &PyNetwork,
+    plans:      Option<&PyPlans>,
+    agents:     Option<usize>,
+    attributes: Option<PathBuf>,
+    behavior:   &str,
+    mode:       &str,
+) -> PyResult<PyResults> {
+    let behavior: BuiltinBehavior = serde_json::from_value(behavior.into())
+        .map_err(|_| PyDtError::Invalid(format!("unknown behavior {behavior:?}: expected commute or noop")))?;
+    let mode = parse_mode(mode)
+        .ok_or_else(|| PyDtError::Invalid(format!("unknown mode {mode:?}: expected car, walk, bike, or transit")))?;
+    let agents = agents.or(plans.map(|p| p.inner.len()));
+    if agents.is_none() && attributes.is_none() {
+        return Err(PyDtError::Invalid("give `plans`, `agents`, or `attributes`".into()).into());
+    }
+
+    let config = config.inner.clone();
+    let network = network.inner.clone();
+    let plans = plans.map(|p| p.inner.clone());
+    let population = Population::load(&PopulationSpec { agents, attributes, positions: None }, network.node_count(), mode, config.seed)
+        .map_err(PyDtError::from)?;
+    Ok(py.allow_threads(|| simulate(config, network, plans, population, behavior))?)
+}
+
+/// Build and run the sim, collecting output in a [`MemoryWriter`].
+fn simulate(
+    config:     SimConfig,
+    network:    RoadNetwork,
+    plans:      Option<Vec<ActivityPlan>>,
+    population: Population,
+    behavior:   BuiltinBehavior,
+) -> PyDtResult<PyResults> {
+    let started = Instant::now();
+    let plans = plans.unwrap_or_else(|| vec![ActivityPlan::empty(); population.len()]);
+    if plans.len() != population.len() {
+        return Err(PyDtError::Invalid(format!("{} plans for {} agents", plans.len(), population.len())));
+    }
+    let (store, rngs) = population.build_store(config.seed);
+    let mut observer = SimOutputObserver::new(MemoryWriter::new(), &config).with_network(&network);
+    let mut sim = SimBuilder::new(config.clone(), store, rngs, behavior, DijkstraRouter)
+        .plans(plans)
+        .network(network)
+        .initial_positions(population.homes)
+        .failure_policy(FailurePolicy::CollectAndReport)
+        .build()?;
+    sim.run(&mut observer)?;
+    if let Some(e) = observer.take_error() {
+        return Err(e.into());
+    }
+    Ok(PyResults {
+        output:    observer.into_writer(),
+        failures:  sim.failures.iter().map(ToString::to_string).collect(),
+        wall_time: started.elapsed(),
+    })
+}
+
+// ── Results ───────────────────────────────────────────────────────────────────
+
+/// The output of a [`run`]: each table is a `pyarrow.Table` with the
+/// columns the Parquet backend writes.  Reading a table needs `pyarrow`.
+#[pyclass(name = "Results", module = "rust_dt", frozen)]
+pub struct PyResults {
+    pub(crate) output:    MemoryWriter,
+    /// Failures collected during the run, as messages.
+    pub(crate) failures:  Vec<String>,
+    pub(crate) wall_time: Duration,
+}
+
+#[pymethods]
+impl PyResults {
+    /// Agent snapshots, one row per agent per snapshot tick.
+    pub fn snapshots(&self, py: Python<'_>) -> PyResult<PyObject> {
+        table(py, self.output.snapshot_batch())
+    }
+
+    /// One row per tick.
+    pub fn tick_summaries(&self, py: Python<'_>) -> PyResult<PyObject> {
+        table(py, self.output.tick_summary_batch())
+    }
+
+    /// Co-location contacts (when `contacts` are enabled in the config).
+    pub fn contacts(&self, py: Python<'_>) -> PyResult<PyObject> {
+        table(py, self.output.contact_batch())
+    }
+
+    /// Completed trips.
+    pub fn trips(&self, py: Python<'_>) -> PyResult<PyObject> {
+        table(py, self.output.trip_batch())
+    }
+
+    /// Failures collected during the run (routing failures and the like).
+    #[getter]
+    pub fn failures(&self) -> Vec<String> {
+        self.failures.clone()
+    }
+
+    /// Wall time of the run in seconds.
+    #[getter]
+    pub fn wall_time(&self) -> f64 {
+        self.wall_time.as_secs_f64()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Results(ticks={}, trips={}, failures={})",
+            self.output.tick_summaries.len(),
+            self.output.trips.len(),
+            self.failures.len(),
+        )
+    }
+}
+
+/// `batch` as a one-batch `pyarrow.Table`.
+fn table(py: Python<'_>, batch: OutputResult<RecordBatch>) -> PyResult<PyObject> {
+    let batch = batch.map_err(PyDtError::from)?.to_pyarrow(py)?;
+    let pyarrow = py.import_bound("pyarrow")?;
+    let table = pyarrow.getattr("Table")?.call_method1("from_batches", (PyList::new_bound(py, [batch]),))?;
+    Ok(table.unbind())
+}
@@ -0,0 +1,169 @@
+// ── Config ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod config_tests {
+    use pyo3::exceptions::PyValueError;
+    use pyo3::prelude::*;
+    use pyo3::types::PyDict;
+
+    use crate::PySimConfig;
+
+    fn config(py: Python<'_>, kwargs: &str) -> PyResult<PySimConfig> {
+        let kwargs = py.eval_bound(&format!("dict({kwargs})"), None, None)?;
+        PySimConfig::new(Some(kwargs.downcast::<PyDict>()?))
+    }
+
+    #[test]
+    fn kwargs_take_scenario_syntax() {
+        Python::with_gil(|py| {
+            let c = config(
+                py,
+                "start_unix_secs='1970-01-02T00:00:00Z', tick_duration_secs='30m', total_ticks='1d', \
+                 seed=7, contacts={'edge_contacts': True}",
+            )
+            .unwrap()
+            .inner;
+            assert_eq!((c.start_unix_secs, c.tick_duration_secs, c.total_ticks, c.seed), (86_400, 1800, 48, 7));
+            assert!(c.contacts.edge_contacts);
+
+            let defaults = PySimConfig::new(None).unwrap().inner;
+            assert_eq!(defaults.tick_duration_secs, 3600);
+        });
+    }
+
+    #[test]
+    fn toml_and_json() {
+        let toml = PySimConfig::from_toml("total_ticks = \"2d\"\nseed = 3").unwrap().inner;
+        assert_eq!((toml.total_ticks, toml.seed), (48, 3));
+        let json = PySimConfig::from_json(r#"{"total_ticks": 5}"#).unwrap().inner;
+        assert_eq!(json.total_ticks, 5);
+    }
+
+    #[test]
+    fn bad_values_raise_value_error() {
+        Python::with_gil(|py| {
+            let err = config(py, "total_ticks='soon'").err().unwrap();
+            assert!(err.is_instance_of::<PyValueError>(py));
+            assert!(err.to_string().contains("invalid SimConfig"));
+            assert!(config(py, "seed=object()").err().unwrap().is_instance_of::<PyValueError>(py));
+        });
+    }
+}
+
+// ── Network and plans ─────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod input_tests {
+    use pyo3::prelude::*;
+
+    use crate::{PyNetwork, PyPlans};
+
+    #[test]
+    fn grid_and_explicit_networks() {
+        let grid = PyNetwork::grid(2, 3, 500.0, 50.0, (52.0, 13.0)).unwrap();
+        assert_eq!((grid.node_count(), grid.edge_count()), (6, 14));
+        assert_eq!(grid.nearest_node(52.0, 13.0), Some(0));
+        assert!(PyNetwork::grid(0, 3, 500.0, 50.0, (0.0, 0.0)).is_err());
+
+        let nodes = vec![(0.0, 0.0), (0.0, 0.01), (0.01, 0.01)];
+        let two_way = PyNetwork::from_edges(nodes.clone(), vec![(0, 1, 1100.0, 80_000)], false).unwrap();
+        let directed = PyNetwork::from_edges(nodes.clone(), vec![(0, 1, 1100.0, 80_000)], true).unwrap();
+        assert_eq!((two_way.edge_count(), directed.edge_count()), (2, 1));
+        assert_eq!(two_way.node_position(2).unwrap(), (0.01, 0.01));
+        assert!(two_way.node_position(3).is_err());
+        assert!(PyNetwork::from_edges(nodes, vec![(0, 3, 1.0, 1)], false).is_err());
+    }
+
+    #[test]
+    fn daily_plans_take_durations() {
+        Python::with_gil(|py| {
+            let (depart, work): (PyObject, PyObject) = ("8h".into_py(py), (9 * 3600).into_py(py));
+            let plans = PyPlans::daily(4, depart.bind(py), work.bind(py), 3600).unwrap();
+            assert_eq!(plans.inner.len(), 4);
+            assert_eq!(plans.inner[0].cycle_ticks, 24);
+
+            let late: PyObject = "20h".into_py(py);
+            assert!(PyPlans::daily(4, late.bind(py), work.bind(py), 3600).is_err());
+        });
+        assert_eq!(PyPlans::empty(3).inner.len(), 3);
+    }
+}
+
+// ── Run ───────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod run_tests {
+    use pyo3::exceptions::PyValueError;
+    use pyo3::prelude::*;
+
+    use crate::run::run;
+    use crate::{PyNetwork, PyPlans, PySimConfig};
+
+    fn config() -> PySimConfig {
+        PySimConfig::from_toml("total_ticks = \"2d\"\nseed = 42").unwrap()
+    }
+
+    fn daily(py: Python<'_>, agents: usize) -> PyPlans {
+        let (depart, work): (PyObject, PyObject) = ("8h".into_py(py), "9h".into_py(py));
+        PyPlans::daily(agents, depart.bind(py), work.bind(py), 3600).unwrap()
+    }
+
+    #[test]
+    fn commute_run_keeps_output_in_memory() {
+        Python::with_gil(|py| {
+            let network = PyNetwork::grid(4, 4, 500.0, 50.0, (0.0, 0.0)).unwrap();
+            let plans = daily(py, 20);
+            let results = run(py, &config(), &network, Some(&plans), None, None, "commute", "car").unwrap();
+
+            assert_eq!(results.output.tick_summaries.len(), 48);
+            assert!(results.output.trips.len() >= 20);
+            assert_eq!(results.output.trip_batch().unwrap().num_rows(), results.output.trips.len());
+            assert!(results.failures.is_empty());
+
+            // Same seed, same output; the network is reused.
+            let again = run(py, &config(), &network, Some(&plans), None, None, "commute", "car").unwrap();
+            assert_eq!(again.output.trips, results.output.trips);
+        });
+    }
+
+    #[test]
+    fn bad_arguments_raise_value_error() {
+        Python::with_gil(|py| {
+            let network = PyNetwork::grid(2, 2, 500.0, 50.0, (0.0, 0.0)).unwrap();
+            let plans = daily(py, 3);
+            let cases = [
+                run(py, &config(), &network, Some(&plans), Some(4), None, "commute", "car"),
+                run(py, &config(), &network, Some(&plans), None, None, "teleport", "car"),
+                run(py, &config(), &network, Some(&plans), None, None, "commute", "hovercraft"),
+                run(py, &config(), &network, None, None, None, "commute", "car"),
+            ];
+            for case in cases {
+                assert!(case.err().unwrap().is_instance_of::<PyValueError>(py));
+            }
+        });
+    }
+
+    #[test]
+    fn module_runs_from_python() {
+        Python::with_gil(|py| {
+            let module = pyo3::wrap_pymodule!(crate::rust_dt)(py);
+            py.import_bound("sys").unwrap().getattr("modules").unwrap().set_item("rust_dt", module).unwrap();
+            py.run_bound(
+                r#"
+import rust_dt as dt
+
+network = dt.Network.grid(rows=3, cols=3)
+config = dt.SimConfig(total_ticks="1d", seed=1)
+config.seed = 2
+results = dt.run(config, network, dt.Plans.daily(5, depart="8h", work="8h"))
+assert results.failures == [], results.failures
+assert repr(results).startswith("Results(ticks=24, trips="), repr(results)
+assert issubclass(dt.DtError, Exception)
+"#,
+                None,
+                None,
+            )
+            .unwrap();
+        });
+    }
+}
@@ -0,0 +1,24 @@
+//! Error types for dt-query.
+
+use dt_core::{DtError, ErrorCategory};
+use dt_sim::SimError;
+use thiserror::Error;
+
+/// Errors that can occur when serving a simulation.
+#[derive(Debug, Error)]
+pub enum QueryError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error(transparent)]
+    Sim(#[from] SimError),
+}
+
+/// Alias for `Result<T, QueryError>`.
+pub type QueryResult<T> = Result<T, QueryError>;
+
+impl From<QueryError> for DtError {
+    fn from(err: QueryError) -> Self {
+        DtError::subsystem(ErrorCategory::Output, err)
+    }
+}
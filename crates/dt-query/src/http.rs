@@ -0,0 +1,147 @@
+//! Minimal HTTP/1.1 requests and JSON responses.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+/// Longest request head read before a connection is dropped.
+const MAX_HEAD: usize = 8192;
+
+/// Largest request body accepted.
+const MAX_BODY: usize = 1 << 20;
+
+// ── Request ───────────────────────────────────────────────────────────────────
+
+/// A request to the query server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// `GET`, `POST`, … as sent.
+    pub method: String,
+    /// The path without the query string, e.g. `/agents/3`.
+    pub path:   String,
+    /// Query-string pairs in order.  Values are taken as sent: no
+    /// percent-decoding.
+    pub query:  Vec<(String, String)>,
+    pub body:   String,
+}
+
+impl Request {
+    /// Parse a request line such as `GET /agents/3?x=1 HTTP/1.1` (headers
+    /// after it are ignored).
+    pub fn parse(head: &str, body: String) -> Option<Self> {
+        let mut parts = head.lines().next()?.split_whitespace();
+        let (method, target) = (parts.next()?, parts.next()?);
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let query = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (key.to_string(), value.to_string())
+            })
+            .collect();
+        Some(Self { method: method.to_string(), path: path.to_string(), query, body })
+    }
+
+    /// The first value of query parameter `key`.
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.query.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// The non-empty path segments, e.g. `["agents", "3"]`.
+    pub fn segments(&self) -> Vec<&str> {
+        self.path.split('/').filter(|s| !s.is_empty()).collect()
+    }
+}
+
+/// Read one request from `stream`, or `None` if it is malformed, too large,
+/// or not complete within `timeout`.
+pub(crate) fn read_request(stream: &mut TcpStream, timeout: Duration) -> Option<Request> {
+    let deadline = Instant::now() + timeout;
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+        if buf.len() > MAX_HEAD || Instant::now() >= deadline {
+            return None;
+        }
+        let n = stream.read(&mut chunk).ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    let head = String::from_utf8(buf[..head_end].to_vec()).ok()?;
+    let length = content_length(&head)?;
+    if length > MAX_BODY {
+        return None;
+    }
+    let mut body = buf.split_off(head_end + 4);
+    if body.len() < length {
+        let start = body.len();
+        body.resize(length, 0);
+        stream.read_exact(&mut body[start..]).ok()?;
+    }
+    body.truncate(length);
+    Request::parse(&head, String::from_utf8(body).ok()?)
+}
+
+/// The `Content-Length` header's value; `0` if absent, `None` if invalid.
+fn content_length(head: &str) -> Option<usize> {
+    for line in head.lines().skip(1) {
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            return value.trim().parse().ok();
+        }
+    }
+    Some(0)
+}
+
+// ── Response ──────────────────────────────────────────────────────────────────
+
+/// A JSON response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body:   String,
+}
+
+impl Response {
+    /// `200 OK` with a JSON body.
+    pub fn ok(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    /// An error status with `{"error":"<message>"}`.
+    pub fn error(status: u16, message: &str) -> Self {
+        Self { status, body: format!(r#"{{"error":{}}}"#, crate::json::string(message)) }
+    }
+
+    pub(crate) fn write(&self, stream: &mut TcpStream) {
+        let _ = write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason(self.status),
+            self.body.len(),
+            self.body,
+        );
+        let _ = stream.flush();
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _   => "",
+    }
+}
@@ -0,0 +1,157 @@
+//! JSON response bodies.
+//!
+//! Every response is one JSON object:
+//!
+//! ```text
+//! GET  /status        {"tick":480,"time":"2024-03-04T08:00:00Z","end_tick":1440,"agents":5000,"in_transit":812,"paused":true,"finished":false}
+//! GET  /agents/3      {"agent":3,"in_transit":true,"node":17,"destination":40,"mode":"car","departure_tick":470,"arrival_tick":492,"edge":63,"lat":52.52,"lon":13.405}
+//! GET  /nodes/17      {"node":17,"lat":52.51,"lon":13.39,"present":[1,8],"inbound":[5]}
+//! GET  /occupancy     {"tick":480,"nodes":[{"node":40,"agents":211},{"node":17,"agents":96}]}
+//! GET  /ticks         {"ticks":[{"tick":479,"woken":40,"in_transit":812,"departures":9,"arrivals":4,"contacts":0,"routing_failures":0,"messages_sent":0}]}
+//! any  (on error)     {"error":"no agent 9000"}
+//! ```
+//!
+//! `tick` in `status` is the next tick to run; the state described is the
+//! state after the tick before it.  An agent's `node` is where it is, or
+//! where its journey started if `in_transit`; its `lat`/`lon` are then
+//! interpolated along the journey, and `edge` is the road it is estimated
+//! to be on (`null` when parked).  `inbound` lists agents travelling to the
+//! node.
+
+use std::fmt::Write as _;
+
+use dt_core::{AgentId, EdgeId, GeoPoint, NodeId, Tick};
+use dt_mobility::MovementState;
+use dt_sim::TickStats;
+
+/// The fields of a `status` response.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Status {
+    pub tick:       u64,
+    pub time:       String,
+    pub end_tick:   u64,
+    pub agents:     usize,
+    pub in_transit: usize,
+    pub paused:     bool,
+    pub finished:   bool,
+}
+
+/// `s` as a quoted JSON string.
+pub fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"'  => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub fn status(s: &Status) -> String {
+    format!(
+        "{{\"tick\":{},\"time\":{},\"end_tick\":{},\"agents\":{},\"in_transit\":{},\"paused\":{},\"finished\":{}}}",
+        s.tick,
+        string(&s.time),
+        s.end_tick,
+        s.agents,
+        s.in_transit,
+        s.paused,
+        s.finished,
+    )
+}
+
+/// One agent's movement state, with its estimated edge and position.
+pub fn agent(agent: AgentId, state: &MovementState, edge: Option<EdgeId>, pos: Option<GeoPoint>) -> String {
+    let mut text = format!(
+        "{{\"agent\":{},\"in_transit\":{},\"node\":{},\"destination\":{},\"mode\":{},\
+         \"departure_tick\":{},\"arrival_tick\":{},\"edge\":{}",
+        agent.0,
+        state.in_transit,
+        node_or_null(state.departure_node),
+        node_or_null(state.destination_node),
+        string(state.mode.as_str()),
+        state.departure_tick.0,
+        state.arrival_tick.0,
+        edge.map_or("null".into(), |e| e.0.to_string()),
+    );
+    push_position(&mut text, pos);
+    text.push('}');
+    text
+}
+
+/// A node, the agents parked at it, and the agents travelling to it.
+pub fn node(node: NodeId, pos: GeoPoint, present: &[AgentId], inbound: &[AgentId]) -> String {
+    let mut text = format!("{{\"node\":{}", node.0);
+    push_position(&mut text, Some(pos));
+    text.push_str(",\"present\":");
+    push_ids(&mut text, present);
+    text.push_str(",\"inbound\":");
+    push_ids(&mut text, inbound);
+    text.push('}');
+    text
+}
+
+/// The answer to `POST /snapshot`.
+pub fn snapshot(tick: u64) -> String {
+    format!("{{\"snapshot\":{tick}}}")
+}
+
+/// The busiest nodes after `tick`, as `(node, agents)` pairs.
+pub fn occupancy(tick: u64, nodes: &[(NodeId, usize)]) -> String {
+    let mut text = format!("{{\"tick\":{tick},\"nodes\":[");
+    for (i, (node, count)) in nodes.iter().enumerate() {
+        let sep = if i == 0 { "" } else { "," };
+        let _ = write!(text, "{sep}{{\"node\":{},\"agents\":{count}}}", node.0);
+    }
+    text.push_str("]}");
+    text
+}
+
+/// Per-tick summaries, oldest first.
+pub fn ticks<'a>(ticks: impl IntoIterator<Item = &'a (Tick, TickStats)>) -> String {
+    let mut text = String::from("{\"ticks\":[");
+    for (i, (tick, s)) in ticks.into_iter().enumerate() {
+        let sep = if i == 0 { "" } else { "," };
+        let _ = write!(
+            text,
+            "{sep}{{\"tick\":{},\"woken\":{},\"in_transit\":{},\"departures\":{},\"arrivals\":{},\
+             \"contacts\":{},\"routing_failures\":{},\"messages_sent\":{}}}",
+            tick.0, s.woken, s.in_transit, s.departures, s.arrivals, s.contacts, s.routing_failures,
+            s.messages_sent,
+        );
+    }
+    text.push_str("]}");
+    text
+}
+
+fn node_or_null(node: NodeId) -> String {
+    if node == NodeId::INVALID { "null".into() } else { node.0.to_string() }
+}
+
+fn push_position(text: &mut String, pos: Option<GeoPoint>) {
+    match pos {
+        Some(p) => {
+            let _ = write!(text, ",\"lat\":{},\"lon\":{}", p.lat, p.lon);
+        }
+        None => text.push_str(",\"lat\":null,\"lon\":null"),
+    }
+}
+
+fn push_ids(text: &mut String, ids: &[AgentId]) {
+    text.push('[');
+    for (i, id) in ids.iter().enumerate() {
+        let sep = if i == 0 { "" } else { "," };
+        let _ = write!(text, "{sep}{}", id.0);
+    }
+    text.push(']');
+}
@@ -0,0 +1,58 @@
+//! `dt-query` — query and steer a running simulation over HTTP.
+//!
+//! [`QueryServer`] listens for JSON requests; a [`Session`] runs the sim on
+//! the current thread in place of [`Sim::run`][dt_sim::Sim::run] and
+//! answers them between ticks: agent state, node occupancy, recent tick
+//! summaries, snapshots on demand, and commands that pause, step, wake or
+//! move agents, or run handlers the caller registers.
+//!
+//! ```rust,ignore
+//! let server = QueryServer::bind("127.0.0.1:8090")?;
+//! println!("serving {}", server.url());
+//! server
+//!     .session()
+//!     .start_paused(true)
+//!     .command("close", |sim, request| {
+//!         let node = request.param("node").ok_or("missing node")?;
+//!         // … mutate the sim …
+//!         Ok(format!(r#"{{"closed":{node}}}"#))
+//!     })
+//!     .run(&mut sim, &mut writer)?;
+//! ```
+//!
+//! ```text
+//! curl localhost:8090/status
+//! curl -X POST 'localhost:8090/step?ticks=60'
+//! curl localhost:8090/agents/42
+//! curl 'localhost:8090/occupancy?top=5'
+//! curl -X POST 'localhost:8090/agents/42/travel?to=17&mode=bike'
+//! curl -X POST localhost:8090/resume
+//! ```
+//!
+//! Requests are answered one at a time on the sim's thread, so a response
+//! always sees a consistent state between two ticks.  Endpoints are listed
+//! on [`Session`] and responses documented in [`json`].
+//!
+//! # Crate layout
+//!
+//! | Module      | Contents                                       |
+//! |-------------|------------------------------------------------|
+//! | [`server`]  | `QueryServer`: HTTP listener                   |
+//! | [`session`] | `Session`: the run loop and request handlers   |
+//! | [`http`]    | `Request`, `Response`                          |
+//! | [`json`]    | JSON response encoding                         |
+//! | [`error`]   | `QueryError`, `QueryResult<T>`                 |
+
+pub mod error;
+pub mod http;
+pub mod json;
+pub mod server;
+pub mod session;
+
+#[cfg(test)]
+mod tests;
+
+pub use error::{QueryError, QueryResult};
+pub use http::{Request, Response};
+pub use server::QueryServer;
+pub use session::{Command, DEFAULT_HISTORY, Session};
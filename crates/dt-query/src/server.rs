@@ -0,0 +1,146 @@
+//! `QueryServer` — accepts HTTP requests and hands them to the sim thread.
+
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use dt_behavior::BehaviorModel;
+use dt_spatial::Router;
+
+use crate::http::{Request, Response, read_request};
+use crate::{QueryResult, Session};
+
+/// How long a client may take to send its request or accept the response.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often the accept thread checks whether the server has stopped.
+const ACCEPT_POLL: Duration = Duration::from_millis(20);
+
+/// How long a request waits for the sim thread before `503`.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A request waiting for the sim thread, with the channel to answer on.
+pub(crate) struct Pending {
+    pub request: Request,
+    pub reply:   Sender<Response>,
+}
+
+/// State shared with the accept thread.
+#[derive(Default)]
+struct Shared {
+    /// Whether a [`Session`] is answering requests.
+    serving: AtomicBool,
+    stop:    AtomicBool,
+}
+
+/// An HTTP server answering JSON queries about a simulation.
+///
+/// A background thread accepts connections and forwards each request to
+/// the thread running the sim, which answers between ticks (see
+/// [`Session`]).  While no session is running every request gets `503`.
+/// Connections are handled one at a time, so a slow tick delays every
+/// client behind it.
+///
+/// Dropping the server stops listening.
+pub struct QueryServer {
+    addr:     SocketAddr,
+    shared:   Arc<Shared>,
+    requests: Receiver<Pending>,
+    acceptor: Option<JoinHandle<()>>,
+}
+
+impl QueryServer {
+    /// Listen on `addr` (e.g. `"127.0.0.1:8090"`; port `0` picks a free
+    /// port, see [`local_addr`][Self::local_addr]).
+    pub fn bind(addr: impl ToSocketAddrs) -> QueryResult<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared::default());
+        let (tx, requests) = mpsc::channel();
+
+        let acceptor = {
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || {
+                while !shared.stop.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        // A failed request only loses that connection.
+                        Ok((stream, _)) => serve(stream, &shared, &tx),
+                        Err(_) => std::thread::sleep(ACCEPT_POLL),
+                    }
+                }
+            })
+        };
+
+        Ok(Self { addr, shared, requests, acceptor: Some(acceptor) })
+    }
+
+    /// The address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The base URL of the API, e.g. `http://127.0.0.1:8090`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Start configuring a session that serves a sim with this server.
+    pub fn session<'s, B: BehaviorModel, R: Router>(&'s self) -> Session<'s, B, R> {
+        Session::new(self)
+    }
+
+    /// Stop listening.
+    pub fn shutdown(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        if let Some(acceptor) = self.acceptor.take() {
+            let _ = acceptor.join();
+        }
+    }
+
+    pub(crate) fn set_serving(&self, serving: bool) {
+        self.shared.serving.store(serving, Ordering::Relaxed);
+    }
+
+    /// The next request, waiting up to `timeout`.
+    pub(crate) fn next_request(&self, timeout: Duration) -> Option<Pending> {
+        if timeout.is_zero() {
+            self.requests.try_recv().ok()
+        } else {
+            self.requests.recv_timeout(timeout).ok()
+        }
+    }
+}
+
+impl Drop for QueryServer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Read one request, pass it to the session, and write its answer.
+fn serve(mut stream: TcpStream, shared: &Shared, tx: &Sender<Pending>) {
+    if stream.set_nonblocking(false).is_err()
+        || stream.set_read_timeout(Some(CLIENT_TIMEOUT)).is_err()
+        || stream.set_write_timeout(Some(CLIENT_TIMEOUT)).is_err()
+    {
+        return;
+    }
+    let response = match read_request(&mut stream, CLIENT_TIMEOUT) {
+        None => Response::error(400, "malformed request"),
+        Some(_) if !shared.serving.load(Ordering::Relaxed) => Response::error(503, "no simulation attached"),
+        Some(request) => {
+            let (reply, answer) = mpsc::channel();
+            match tx.send(Pending { request, reply }) {
+                Ok(()) => answer
+                    .recv_timeout(REPLY_TIMEOUT)
+                    .unwrap_or_else(|_| Response::error(503, "simulation did not answer")),
+                Err(_) => Response::error(503, "no simulation attached"),
+            }
+        }
+    };
+    response.write(&mut stream);
+}
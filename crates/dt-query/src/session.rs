@@ -0,0 +1,437 @@
+//! `Session` — runs a sim and answers the server's requests between ticks.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use dt_agent::AgentStore;
+use dt_behavior::BehaviorModel;
+use dt_core::{AgentId, GeoPoint, NodeId, Tick, TransportMode};
+use dt_mobility::{MobilityStore, MovementState, Trip};
+use dt_sim::{PhaseTimings, Sim, SimObserver, TickMetrics, TickStats, TraceEvent};
+use dt_spatial::{Route, Router};
+
+use crate::http::{Request, Response};
+use crate::json::{self, Status};
+use crate::{QueryResult, QueryServer};
+
+/// How long a paused or finished session waits for a request before
+/// checking again.
+const IDLE_POLL: Duration = Duration::from_millis(100);
+
+/// Ticks of summaries kept for `GET /ticks` unless set with
+/// [`Session::history`].
+pub const DEFAULT_HISTORY: usize = 1440;
+
+/// Rows returned by `GET /occupancy` without `top`.
+const DEFAULT_TOP: usize = 10;
+
+/// Modes `POST /agents/{id}/travel` accepts, by [`TransportMode::as_str`].
+const MODES: [TransportMode; 4] =
+    [TransportMode::Car, TransportMode::Walk, TransportMode::Bike, TransportMode::Transit];
+
+/// A custom command: gets the sim and the request, returns a JSON body or
+/// an error message (sent as `400`).
+pub type Command<'s, B, R> = Box<dyn FnMut(&mut Sim<B, R>, &Request) -> Result<String, String> + 's>;
+
+// ── Session ───────────────────────────────────────────────────────────────────
+
+/// Runs a sim on the current thread, answering a [`QueryServer`]'s
+/// requests between ticks.
+///
+/// Created by [`QueryServer::session`]; [`run`][Self::run] takes the place
+/// of [`Sim::run`].  Requests are answered before each tick, so every
+/// response describes the state between two ticks and every change a
+/// request makes applies from the next tick.
+///
+/// | Request                          | Effect                                                   |
+/// |----------------------------------|----------------------------------------------------------|
+/// | `GET /status`                    | Clock, agent counts, paused/finished                     |
+/// | `GET /agents/{id}`               | Movement state and estimated position                    |
+/// | `GET /nodes/{id}`                | Agents parked at and travelling to a node                |
+/// | `GET /occupancy?top=N`           | The `N` (10) nodes with most agents parked               |
+/// | `GET /ticks?last=N`              | Summaries of the last `N` (all kept) ticks               |
+/// | `POST /pause`, `POST /resume`    | Stop or restart ticking                                  |
+/// | `POST /step?ticks=N`             | Run `N` (1) ticks, then answer                           |
+/// | `POST /snapshot`                 | Call the observer's `on_snapshot` with the current state |
+/// | `POST /agents/{id}/wake?at=T`    | Wake the agent at tick `T`                               |
+/// | `POST /agents/{id}/travel?to=N`  | Start a journey to node `N` (`&mode=car`)                |
+/// | `POST /commands/{name}`          | Run a [`command`][Self::command] with the request        |
+/// | `POST /stop`                     | End the run at the current tick                          |
+///
+/// Responses are documented in [`json`][crate::json].  Bad parameters get
+/// `400`, unknown agents, nodes, and commands `404`, and a journey for an
+/// agent already travelling `409`.
+pub struct Session<'s, B: BehaviorModel, R: Router> {
+    server:   &'s QueryServer,
+    commands: HashMap<String, Command<'s, B, R>>,
+    paused:   bool,
+    linger:   bool,
+    history:  usize,
+}
+
+impl<'s, B: BehaviorModel, R: Router> Session<'s, B, R> {
+    pub(crate) fn new(server: &'s QueryServer) -> Self {
+        Self { server, commands: HashMap::new(), paused: false, linger: false, history: DEFAULT_HISTORY }
+    }
+
+    /// Answer `POST /commands/{name}` with `handler`.
+    pub fn command(
+        mut self,
+        name:    impl Into<String>,
+        handler: impl FnMut(&mut Sim<B, R>, &Request) -> Result<String, String> + 's,
+    ) -> Self {
+        self.commands.insert(name.into(), Box::new(handler));
+        self
+    }
+
+    /// Start paused, waiting for `POST /resume` or `POST /step`.
+    /// Default: `false`.
+    pub fn start_paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self
+    }
+
+    /// Keep answering after the last tick until `POST /stop`.
+    /// Default: `false`, return as soon as the run ends.
+    pub fn linger(mut self, linger: bool) -> Self {
+        self.linger = linger;
+        self
+    }
+
+    /// Keep the summaries of the last `ticks` ticks for `GET /ticks`.
+    /// Default: [`DEFAULT_HISTORY`].
+    pub fn history(mut self, ticks: usize) -> Self {
+        self.history = ticks;
+        self
+    }
+
+    /// Run `sim` to `config.end_tick()` — or until `POST /stop` — calling
+    /// `observer` as [`Sim::run`] does.
+    ///
+    /// `on_sim_end` fires once, when the run ends or is stopped.  A sim
+    /// error ends the session; the request that caused it gets `500`.
+    pub fn run<O: SimObserver>(mut self, sim: &mut Sim<B, R>, observer: &mut O) -> QueryResult<()> {
+        self.server.set_serving(true);
+        let result = self.serve(sim, observer);
+        self.server.set_serving(false);
+        while let Some(pending) = self.server.next_request(Duration::ZERO) {
+            let _ = pending.reply.send(Response::error(503, "no simulation attached"));
+        }
+        result
+    }
+
+    fn serve<O: SimObserver>(&mut self, sim: &mut Sim<B, R>, observer: &mut O) -> QueryResult<()> {
+        let mut state = RunState {
+            paused:   self.paused,
+            finished: false,
+            stopped:  false,
+            history:  VecDeque::new(),
+            capacity: self.history,
+        };
+        while !state.stopped && (self.linger || !state.finished) {
+            let idle = state.paused || state.finished;
+            let wait = if idle { IDLE_POLL } else { Duration::ZERO };
+            if let Some(pending) = self.server.next_request(wait) {
+                match self.handle(sim, observer, &mut state, &pending.request) {
+                    Ok(response) => {
+                        let _ = pending.reply.send(response);
+                    }
+                    Err(err) => {
+                        let _ = pending.reply.send(Response::error(500, &err.to_string()));
+                        return Err(err);
+                    }
+                }
+            } else if !idle {
+                state.advance(sim, observer)?;
+            }
+        }
+        if !state.finished {
+            sim.finish(observer)?;
+        }
+        Ok(())
+    }
+
+    fn handle<O: SimObserver>(
+        &mut self,
+        sim:      &mut Sim<B, R>,
+        observer: &mut O,
+        state:    &mut RunState,
+        request:  &Request,
+    ) -> QueryResult<Response> {
+        let segments = request.segments();
+        let response = match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["status"]) => Ok(state.status(sim)),
+            ("GET", ["agents", id]) => agent(sim, id),
+            ("GET", ["nodes", id]) => node(sim, id),
+            ("GET", ["occupancy"]) => occupancy(sim, request),
+            ("GET", ["ticks"]) => state.ticks(request),
+            ("POST", ["pause" | "resume"]) => {
+                state.paused = segments[0] == "pause";
+                Ok(state.status(sim))
+            }
+            ("POST", ["step"]) => match param(request, "ticks", 1u64) {
+                Ok(ticks) => {
+                    for _ in 0..ticks {
+                        if !state.advance(sim, observer)? {
+                            break;
+                        }
+                    }
+                    Ok(state.status(sim))
+                }
+                Err(response) => Err(response),
+            },
+            ("POST", ["snapshot"]) => {
+                let tick = last_tick(sim);
+                observer.on_snapshot(tick, &sim.mobility.store, &sim.agents);
+                Ok(Response::ok(json::snapshot(tick.0)))
+            }
+            ("POST", ["agents", id, "wake"]) => wake(sim, id, request),
+            ("POST", ["agents", id, "travel"]) => travel(sim, id, request),
+            ("POST", ["commands", name]) => match self.commands.get_mut(*name) {
+                Some(handler) => Ok(handler(sim, request).map_or_else(|e| Response::error(400, &e), Response::ok)),
+                None => Err(Response::error(404, &format!("no command {name}"))),
+            },
+            ("POST", ["stop"]) => {
+                state.stopped = true;
+                Ok(state.status(sim))
+            }
+            (_, ["status" | "occupancy" | "ticks" | "pause" | "resume" | "step" | "snapshot" | "stop"])
+            | (_, ["agents" | "nodes", _])
+            | (_, ["agents", _, "wake" | "travel"])
+            | (_, ["commands", _]) => Err(Response::error(405, "method not allowed")),
+            _ => Err(Response::error(404, "not found")),
+        };
+        Ok(response.unwrap_or_else(|e| e))
+    }
+}
+
+// ── Run state ─────────────────────────────────────────────────────────────────
+
+struct RunState {
+    paused:   bool,
+    finished: bool,
+    stopped:  bool,
+    /// Summaries of the latest ticks, oldest first.
+    history:  VecDeque<(Tick, TickStats)>,
+    capacity: usize,
+}
+
+impl RunState {
+    /// Process the next tick; at the end of the run, finish it instead and
+    /// return `false`.
+    fn advance<B: BehaviorModel, R: Router, O: SimObserver>(
+        &mut self,
+        sim:      &mut Sim<B, R>,
+        observer: &mut O,
+    ) -> QueryResult<bool> {
+        if self.finished {
+            return Ok(false);
+        }
+        let mut recorder = Recorder { inner: observer, history: &mut self.history, capacity: self.capacity };
+        if sim.advance(&mut recorder)? {
+            return Ok(true);
+        }
+        sim.finish(observer)?;
+        self.finished = true;
+        Ok(false)
+    }
+
+    fn status<B: BehaviorModel, R: Router>(&self, sim: &Sim<B, R>) -> Response {
+        let tick = sim.clock.current_tick;
+        Response::ok(json::status(&Status {
+            tick:       tick.0,
+            time:       sim.clock.format_tick(tick),
+            end_tick:   sim.config.end_tick().0,
+            agents:     sim.agents.count,
+            in_transit: sim.mobility.store.states.iter().filter(|s| s.in_transit).count(),
+            paused:     self.paused,
+            finished:   self.finished,
+        }))
+    }
+
+    fn ticks(&self, request: &Request) -> Result<Response, Response> {
+        let last = param(request, "last", self.history.len())?;
+        Ok(Response::ok(json::ticks(self.history.iter().skip(self.history.len().saturating_sub(last)))))
+    }
+}
+
+// ── Requests ──────────────────────────────────────────────────────────────────
+
+fn agent<B: BehaviorModel, R: Router>(sim: &Sim<B, R>, id: &str) -> Result<Response, Response> {
+    let agent = agent_id(sim, id)?;
+    let (store, net) = (&sim.mobility.store, &sim.network);
+    let now = sim.clock.current_tick;
+    let state = &store.states[agent.index()];
+    let edge = store.current_edge(agent, now, net);
+    Ok(Response::ok(json::agent(agent, state, edge, position(sim, state, now))))
+}
+
+fn node<B: BehaviorModel, R: Router>(sim: &Sim<B, R>, id: &str) -> Result<Response, Response> {
+    let node = node_id(sim, id)?;
+    let (mut present, mut inbound) = (Vec::new(), Vec::new());
+    for (i, s) in sim.mobility.store.states.iter().enumerate() {
+        if s.in_transit && s.destination_node == node {
+            inbound.push(AgentId(i as u32));
+        } else if !s.in_transit && s.departure_node == node {
+            present.push(AgentId(i as u32));
+        }
+    }
+    Ok(Response::ok(json::node(node, sim.network.node_pos[node.index()], &present, &inbound)))
+}
+
+fn occupancy<B: BehaviorModel, R: Router>(sim: &Sim<B, R>, request: &Request) -> Result<Response, Response> {
+    let top = param(request, "top", DEFAULT_TOP)?;
+    let mut counts: HashMap<NodeId, usize> = HashMap::new();
+    for s in sim.mobility.store.states.iter().filter(|s| !s.in_transit && s.departure_node != NodeId::INVALID) {
+        *counts.entry(s.departure_node).or_default() += 1;
+    }
+    let mut nodes: Vec<(NodeId, usize)> = counts.into_iter().collect();
+    nodes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    nodes.truncate(top);
+    Ok(Response::ok(json::occupancy(last_tick(sim).0, &nodes)))
+}
+
+fn wake<B: BehaviorModel, R: Router>(sim: &mut Sim<B, R>, id: &str, request: &Request) -> Result<Response, Response> {
+    let agent = agent_id(sim, id)?;
+    let now = sim.clock.current_tick;
+    let at = Tick(param(request, "at", now.0)?);
+    if at < now {
+        return Err(Response::error(400, &format!("tick {} has already run", at.0)));
+    }
+    sim.wake_queue.push(at, agent);
+    Ok(Response::ok(format!("{{\"agent\":{},\"wake_tick\":{}}}", agent.0, at.0)))
+}
+
+fn travel<B: BehaviorModel, R: Router>(sim: &mut Sim<B, R>, id: &str, request: &Request) -> Result<Response, Response> {
+    let agent = agent_id(sim, id)?;
+    let destination = node_id(sim, request.param("to").ok_or_else(|| Response::error(400, "missing to"))?)?;
+    let mode = match request.param("mode") {
+        None => TransportMode::Car,
+        Some(name) => MODES
+            .into_iter()
+            .find(|m| m.as_str() == name)
+            .ok_or_else(|| Response::error(400, &format!("unknown mode {name}")))?,
+    };
+    let now = sim.clock.current_tick;
+    let arrival = sim
+        .mobility
+        .begin_travel(agent, destination, mode, now, sim.config.tick_duration_secs, &sim.network)
+        .map_err(|e| match e {
+            dt_mobility::MobilityError::AlreadyInTransit(_) => Response::error(409, &e.to_string()),
+            e => Response::error(400, &e.to_string()),
+        })?;
+    // Reported through `on_departure` with the next tick.
+    sim.departures.push(agent);
+    Ok(Response::ok(format!("{{\"agent\":{},\"arrival_tick\":{}}}", agent.0, arrival.0)))
+}
+
+/// The tick whose end state the sim holds: the one before the clock, or
+/// tick 0 before the first.
+fn last_tick<B: BehaviorModel, R: Router>(sim: &Sim<B, R>) -> Tick {
+    Tick(sim.clock.current_tick.0.saturating_sub(1))
+}
+
+/// Where an agent in `state` is at `now`, interpolated along its journey.
+fn position<B: BehaviorModel, R: Router>(sim: &Sim<B, R>, state: &MovementState, now: Tick) -> Option<GeoPoint> {
+    let pos = &sim.network.node_pos;
+    if !state.in_transit {
+        return pos.get(state.departure_node.index()).copied();
+    }
+    let (from, to, t) = state.segment(now, &sim.network);
+    let (from, to) = (*pos.get(from.index())?, *pos.get(to.index())?);
+    Some(GeoPoint { lat: from.lat + (to.lat - from.lat) * t, lon: from.lon + (to.lon - from.lon) * t })
+}
+
+fn agent_id<B: BehaviorModel, R: Router>(sim: &Sim<B, R>, id: &str) -> Result<AgentId, Response> {
+    match id.parse::<u32>() {
+        Ok(i) if (i as usize) < sim.agents.count => Ok(AgentId(i)),
+        Ok(_) => Err(Response::error(404, &format!("no agent {id}"))),
+        Err(_) => Err(Response::error(400, &format!("bad agent id {id}"))),
+    }
+}
+
+fn node_id<B: BehaviorModel, R: Router>(sim: &Sim<B, R>, id: &str) -> Result<NodeId, Response> {
+    match id.parse::<u32>() {
+        Ok(i) if (i as usize) < sim.network.node_count() => Ok(NodeId(i)),
+        Ok(_) => Err(Response::error(404, &format!("no node {id}"))),
+        Err(_) => Err(Response::error(400, &format!("bad node id {id}"))),
+    }
+}
+
+/// Query parameter `key` parsed as a `T`, or `default` if absent.
+fn param<T: std::str::FromStr>(request: &Request, key: &str, default: T) -> Result<T, Response> {
+    match request.param(key) {
+        None => Ok(default),
+        Some(value) => value.parse().map_err(|_| Response::error(400, &format!("bad {key}: {value}"))),
+    }
+}
+
+// ── Recorder ──────────────────────────────────────────────────────────────────
+
+/// Forwards every callback to the caller's observer, keeping tick
+/// summaries for `GET /ticks`.
+struct Recorder<'a, O> {
+    inner:    &'a mut O,
+    history:  &'a mut VecDeque<(Tick, TickStats)>,
+    capacity: usize,
+}
+
+impl<O: SimObserver> SimObserver for Recorder<'_, O> {
+    fn on_tick_start(&mut self, tick: Tick) {
+        self.inner.on_tick_start(tick);
+    }
+
+    fn on_tick_end(&mut self, tick: Tick, woken: usize) {
+        self.inner.on_tick_end(tick, woken);
+    }
+
+    fn on_snapshot(&mut self, tick: Tick, mobility: &MobilityStore, agents: &AgentStore) {
+        self.inner.on_snapshot(tick, mobility, agents);
+    }
+
+    fn on_trip(&mut self, trip: &Trip) {
+        self.inner.on_trip(trip);
+    }
+
+    fn on_departure(&mut self, tick: Tick, agent: AgentId, state: &MovementState, route: &Route) {
+        self.inner.on_departure(tick, agent, state, route);
+    }
+
+    fn on_contacts(&mut self, tick: Tick, agent: AgentId, node: NodeId, agents_at_node: &[AgentId]) {
+        self.inner.on_contacts(tick, agent, node, agents_at_node);
+    }
+
+    fn on_tick_stats(&mut self, tick: Tick, stats: &TickStats) {
+        if self.capacity > 0 {
+            if self.history.len() == self.capacity {
+                self.history.pop_front();
+            }
+            self.history.push_back((tick, *stats));
+        }
+        self.inner.on_tick_stats(tick, stats);
+    }
+
+    fn on_phase_timings(&mut self, tick: Tick, timings: &PhaseTimings) {
+        self.inner.on_phase_timings(tick, timings);
+    }
+
+    fn on_metrics(&mut self, tick: Tick, metrics: &TickMetrics) {
+        self.inner.on_metrics(tick, metrics);
+    }
+
+    fn on_trace(&mut self, event: &TraceEvent) {
+        self.inner.on_trace(event);
+    }
+
+    fn on_ticks_skipped(&mut self, from: Tick, to: Tick) {
+        self.inner.on_ticks_skipped(from, to);
+    }
+
+    fn on_sim_end(&mut self, final_tick: Tick) {
+        self.inner.on_sim_end(final_tick);
+    }
+
+    fn poll_error(&mut self) -> Option<String> {
+        self.inner.poll_error()
+    }
+}
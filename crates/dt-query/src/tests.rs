@@ -0,0 +1,312 @@
+// ── HTTP ──────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod http_tests {
+    use crate::{Request, Response};
+
+    #[test]
+    fn parses_path_and_query() {
+        let head = "POST /agents/3/travel?to=17&mode=bike&flag HTTP/1.1\r\nHost: localhost";
+        let r = Request::parse(head, "{}".into()).unwrap();
+        assert_eq!(r.method, "POST");
+        assert_eq!(r.path, "/agents/3/travel");
+        assert_eq!(r.segments(), ["agents", "3", "travel"]);
+        assert_eq!(r.param("to"), Some("17"));
+        assert_eq!(r.param("mode"), Some("bike"));
+        assert_eq!(r.param("flag"), Some(""));
+        assert_eq!(r.param("missing"), None);
+        assert_eq!(r.body, "{}");
+
+        assert_eq!(Request::parse("GET /status HTTP/1.1", String::new()).unwrap().query, []);
+        assert!(Request::parse("", String::new()).is_none());
+    }
+
+    #[test]
+    fn error_bodies_are_escaped() {
+        let r = Response::error(400, "bad \"id\"\n");
+        assert_eq!(r.status, 400);
+        assert_eq!(r.body, r#"{"error":"bad \"id\"\n"}"#);
+    }
+}
+
+// ── JSON ──────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod json_tests {
+    use dt_core::{AgentId, EdgeId, GeoPoint, NodeId, Tick, TransportMode};
+    use dt_mobility::MovementState;
+    use dt_sim::TickStats;
+
+    use crate::json::{self, Status};
+
+    #[test]
+    fn status_and_agent() {
+        let status = Status {
+            tick:       4,
+            time:       "1970-01-01T00:04:00Z".into(),
+            end_tick:   10,
+            agents:     3,
+            in_transit: 1,
+            paused:     true,
+            finished:   false,
+        };
+        assert_eq!(
+            json::status(&status),
+            r#"{"tick":4,"time":"1970-01-01T00:04:00Z","end_tick":10,"agents":3,"in_transit":1,"paused":true,"finished":false}"#,
+        );
+
+        let state = MovementState {
+            in_transit:       true,
+            departure_node:   NodeId(0),
+            destination_node: NodeId(2),
+            departure_tick:   Tick(1),
+            arrival_tick:     Tick(5),
+            mode:             TransportMode::Bike,
+            on_edge:          None,
+        };
+        assert_eq!(
+            json::agent(AgentId(7), &state, Some(EdgeId(1)), Some(GeoPoint { lat: 1.5, lon: 2.0 })),
+            r#"{"agent":7,"in_transit":true,"node":0,"destination":2,"mode":"bike","departure_tick":1,"arrival_tick":5,"edge":1,"lat":1.5,"lon":2}"#,
+        );
+        let unplaced = MovementState::stationary(NodeId::INVALID, Tick(0));
+        assert!(json::agent(AgentId(0), &unplaced, None, None).ends_with(r#""edge":null,"lat":null,"lon":null}"#));
+    }
+
+    #[test]
+    fn node_occupancy_and_ticks() {
+        assert_eq!(
+            json::node(NodeId(2), GeoPoint { lat: 1.0, lon: 2.5 }, &[AgentId(1), AgentId(4)], &[]),
+            r#"{"node":2,"lat":1,"lon":2.5,"present":[1,4],"inbound":[]}"#,
+        );
+        assert_eq!(
+            json::occupancy(9, &[(NodeId(3), 5), (NodeId(0), 2)]),
+            r#"{"tick":9,"nodes":[{"node":3,"agents":5},{"node":0,"agents":2}]}"#,
+        );
+        let stats = TickStats { woken: 4, in_transit: 2, departures: 1, contacts: 6, ..Default::default() };
+        assert_eq!(
+            json::ticks(&[(Tick(8), stats)]),
+            r#"{"ticks":[{"tick":8,"woken":4,"in_transit":2,"departures":1,"arrivals":0,"contacts":6,"routing_failures":0,"messages_sent":0}]}"#,
+        );
+        assert_eq!(json::snapshot(3), r#"{"snapshot":3}"#);
+    }
+}
+
+// ── Session ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod session_tests {
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+    use std::panic::{self, AssertUnwindSafe};
+    use std::thread::JoinHandle;
+
+    use dt_agent::{AgentStore, AgentStoreBuilder};
+    use dt_behavior::{BehaviorModel, Intent, SimContext};
+    use dt_core::{AgentId, AgentRng, GeoPoint, NodeId, SimConfig, Tick, TransportMode};
+    use dt_mobility::{MobilityStore, MovementState};
+    use dt_sim::{Sim, SimBuilder, SimObserver};
+    use dt_spatial::{DijkstraRouter, Route, RoadNetworkBuilder};
+
+    use crate::QueryServer;
+
+    /// Woken agents drive to node 2 and wake again 10 ticks later.
+    struct Shuttle;
+
+    impl BehaviorModel for Shuttle {
+        fn replan(&self, _agent: AgentId, ctx: &SimContext<'_>, _rng: &mut AgentRng) -> Vec<Intent> {
+            vec![Intent::TravelTo { destination: NodeId(2), mode: TransportMode::Car }, Intent::WakeAt(ctx.tick + 10)]
+        }
+    }
+
+    #[derive(Default)]
+    struct Log {
+        snapshots:  Vec<Tick>,
+        departures: Vec<(Tick, AgentId)>,
+        ends:       Vec<Tick>,
+    }
+
+    impl SimObserver for Log {
+        fn on_snapshot(&mut self, tick: Tick, _mobility: &MobilityStore, _agents: &AgentStore) {
+            self.snapshots.push(tick);
+        }
+
+        fn on_departure(&mut self, tick: Tick, agent: AgentId, _state: &MovementState, _route: &Route) {
+            self.departures.push((tick, agent));
+        }
+
+        fn on_sim_end(&mut self, final_tick: Tick) {
+            self.ends.push(final_tick);
+        }
+    }
+
+    /// Three agents at node 0 of a 0–1–2 line, two ticks per road; only
+    /// agent 0 is woken, at tick 0.
+    fn sim() -> Sim<Shuttle, DijkstraRouter> {
+        let mut b = RoadNetworkBuilder::new();
+        let nodes: Vec<NodeId> = (0..3).map(|i| b.add_node(GeoPoint { lat: i as f32, lon: 0.0 })).collect();
+        b.add_road(nodes[0], nodes[1], 100.0, 120_000);
+        b.add_road(nodes[1], nodes[2], 100.0, 120_000);
+        let config = SimConfig {
+            start_unix_secs:       0,
+            tick_duration_secs:    60,
+            total_ticks:           10,
+            output_interval_ticks: 0,
+            ..Default::default()
+        };
+        let (store, rngs) = AgentStoreBuilder::new(3, 1).build();
+        let mut sim = SimBuilder::new(config, store, rngs, Shuttle, DijkstraRouter)
+            .network(b.build())
+            .initial_positions(vec![NodeId(0); 3])
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(0), AgentId(0));
+        sim
+    }
+
+    /// Send a request and return the status code and body.
+    fn call(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}", body.len())
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        (head[9..12].parse().unwrap(), body.to_string())
+    }
+
+    fn get(addr: SocketAddr, path: &str) -> (u16, String) {
+        call(addr, "GET", path, "")
+    }
+
+    fn post(addr: SocketAddr, path: &str) -> (u16, String) {
+        call(addr, "POST", path, "")
+    }
+
+    /// Run `requests` against `addr` on another thread.  If they panic, the
+    /// session is stopped so the test fails rather than hangs.
+    fn client(addr: SocketAddr, requests: impl FnOnce() + Send + 'static) -> JoinHandle<()> {
+        std::thread::spawn(move || {
+            if let Err(panic) = panic::catch_unwind(AssertUnwindSafe(requests)) {
+                post(addr, "/stop");
+                panic::resume_unwind(panic);
+            }
+        })
+    }
+
+    #[test]
+    fn answers_queries_while_paused() {
+        let server = QueryServer::bind("127.0.0.1:0").unwrap();
+        assert_eq!(server.url(), format!("http://{}", server.local_addr()));
+        let addr = server.local_addr();
+        let (mut sim, mut log) = (sim(), Log::default());
+
+        let client = client(addr, move || {
+            let (status, body) = get(addr, "/status");
+            assert_eq!(status, 200);
+            assert!(body.starts_with(r#"{"tick":0,"time":"1970-01-01T00:00:00Z","end_tick":10,"agents":3,"#), "{body}");
+            assert!(body.contains(r#""paused":true"#));
+
+            let (_, body) = post(addr, "/step?ticks=2");
+            assert!(body.starts_with(r#"{"tick":2,"#), "{body}");
+            assert!(body.contains(r#""in_transit":1,"#));
+
+            let (_, body) = get(addr, "/agents/0");
+            let moving = r#"{"agent":0,"in_transit":true,"node":0,"destination":2,"mode":"car","#;
+            assert!(body.starts_with(moving), "{body}");
+            assert!(body.contains(r#""arrival_tick":4,"edge":2,"lat":1,"lon":0}"#), "{body}");
+            assert_eq!(get(addr, "/nodes/0").1, r#"{"node":0,"lat":0,"lon":0,"present":[1,2],"inbound":[]}"#);
+            assert_eq!(get(addr, "/nodes/2").1, r#"{"node":2,"lat":2,"lon":0,"present":[],"inbound":[0]}"#);
+            assert_eq!(get(addr, "/occupancy?top=5").1, r#"{"tick":1,"nodes":[{"node":0,"agents":2}]}"#);
+
+            let (_, body) = get(addr, "/ticks");
+            assert!(body.starts_with(r#"{"ticks":[{"tick":0,"woken":1,"in_transit":1,"departures":1,"#), "{body}");
+            let (_, body) = get(addr, "/ticks?last=1");
+            assert!(body.starts_with(r#"{"ticks":[{"tick":1,"woken":0,"#), "{body}");
+            assert_eq!(post(addr, "/snapshot"), (200, r#"{"snapshot":1}"#.into()));
+            assert_eq!(call(addr, "POST", "/commands/echo", "hi"), (200, r#"{"echo":"hi","tick":2}"#.into()));
+
+            assert_eq!(get(addr, "/agents/3").0, 404);
+            assert_eq!(get(addr, "/agents/x").0, 400);
+            assert_eq!(get(addr, "/nodes/3").0, 404);
+            assert_eq!(get(addr, "/occupancy?top=-1").0, 400);
+            assert_eq!(get(addr, "/step").0, 405);
+            assert_eq!(post(addr, "/commands/missing").0, 404);
+            assert_eq!(get(addr, "/missing").0, 404);
+            assert_eq!(call(addr, "POST", "/commands/fail", "").1, r#"{"error":"nope"}"#);
+
+            let (status, body) = post(addr, "/stop");
+            assert_eq!(status, 200);
+            assert!(body.starts_with(r#"{"tick":2,"#));
+        });
+
+        server
+            .session()
+            .start_paused(true)
+            .command("echo", |sim, request| {
+                Ok(format!(r#"{{"echo":"{}","tick":{}}}"#, request.body, sim.clock.current_tick.0))
+            })
+            .command("fail", |_, _| Err("nope".into()))
+            .run(&mut sim, &mut log)
+            .unwrap();
+        client.join().unwrap();
+
+        assert_eq!(sim.clock.current_tick, Tick(2));
+        assert_eq!(log.snapshots, [Tick(1)]);
+        assert_eq!(log.departures, [(Tick(0), AgentId(0))]);
+        assert_eq!(log.ends, [Tick(2)]);
+    }
+
+    #[test]
+    fn injected_journeys_and_wakes() {
+        let server = QueryServer::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr();
+        let (mut sim, mut log) = (sim(), Log::default());
+
+        let client = client(addr, move || {
+            assert_eq!(post(addr, "/agents/1/travel?to=1&mode=car"), (200, r#"{"agent":1,"arrival_tick":2}"#.into()));
+            assert_eq!(post(addr, "/agents/1/travel?to=2").0, 409);
+            assert_eq!(post(addr, "/agents/2/travel?to=2&mode=jet").0, 400);
+            assert_eq!(post(addr, "/agents/2/travel").0, 400);
+            assert_eq!(post(addr, "/agents/2/wake?at=3"), (200, r#"{"agent":2,"wake_tick":3}"#.into()));
+            post(addr, "/step?ticks=4");
+            assert_eq!(post(addr, "/agents/2/wake?at=1").0, 400);
+            let (_, body) = post(addr, "/resume");
+            assert!(body.contains(r#""paused":false"#));
+        });
+
+        server.session().start_paused(true).run(&mut sim, &mut log).unwrap();
+        client.join().unwrap();
+
+        // The resumed run ends by itself at tick 10.
+        assert_eq!(sim.clock.current_tick, Tick(10));
+        assert_eq!(log.departures, [(Tick(0), AgentId(1)), (Tick(0), AgentId(0)), (Tick(3), AgentId(2))]);
+        assert_eq!(sim.mobility.store.states[1].departure_node, NodeId(1));
+        assert_eq!(log.ends, [Tick(10)]);
+    }
+
+    #[test]
+    fn lingers_after_the_run_and_refuses_without_a_session() {
+        let server = QueryServer::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr();
+        assert_eq!(get(addr, "/status"), (503, r#"{"error":"no simulation attached"}"#.into()));
+
+        let (mut sim, mut log) = (sim(), Log::default());
+        let client = client(addr, move || {
+            loop {
+                let (_, body) = get(addr, "/status");
+                if body.contains(r#""finished":true"#) {
+                    assert!(body.starts_with(r#"{"tick":10,"#));
+                    break;
+                }
+            }
+            // Stepping a finished run changes nothing.
+            assert!(post(addr, "/step").1.starts_with(r#"{"tick":10,"#));
+            post(addr, "/stop");
+        });
+        server.session().linger(true).history(3).run(&mut sim, &mut log).unwrap();
+        client.join().unwrap();
+
+        assert_eq!(log.ends, [Tick(10)]);
+        assert_eq!(get(addr, "/status").0, 503);
+    }
+}
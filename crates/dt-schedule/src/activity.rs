@@ -25,7 +25,7 @@
 
 use std::sync::Arc;
 
-use dt_core::{ActivityId, NodeId, Tick};
+use dt_core::{ActivityId, NodeId, Tick, TickDuration};
 
 // ── Destination ───────────────────────────────────────────────────────────────
 
@@ -81,6 +81,20 @@ pub struct ScheduledActivity {
     pub destination: Destination,
 }
 
+impl ScheduledActivity {
+    /// `start_offset_ticks` as a duration from the start of the cycle.
+    #[inline]
+    pub fn start_offset(&self) -> TickDuration {
+        TickDuration(self.start_offset_ticks as u64)
+    }
+
+    /// `duration_ticks` as a duration.
+    #[inline]
+    pub fn duration(&self) -> TickDuration {
+        TickDuration(self.duration_ticks as u64)
+    }
+}
+
 // ── ActivityPlan ──────────────────────────────────────────────────────────────
 
 /// A cyclic activity schedule for one agent.
@@ -141,6 +155,12 @@ impl ActivityPlan {
         &self.activities
     }
 
+    /// Length of one schedule cycle.
+    #[inline]
+    pub fn cycle(&self) -> TickDuration {
+        TickDuration(self.cycle_ticks as u64)
+    }
+
     // ── Cycle position ────────────────────────────────────────────────────
 
     /// Tick offset within the current cycle for absolute tick `t`.
@@ -175,28 +195,35 @@ impl ActivityPlan {
     /// (start of the next cycle).  For multi-activity plans the agent wakes at
     /// the start of the next sequential activity.
     pub fn next_wake_tick(&self, tick: Tick) -> Option<Tick> {
+        Some(tick + self.time_until_next(tick)?)
+    }
+
+    /// Time from `tick` until the next activity starts, or `None` if the
+    /// plan is empty.  Always at least one tick.
+    pub fn time_until_next(&self, tick: Tick) -> Option<TickDuration> {
         if self.activities.is_empty() {
             return None;
         }
         let pos = self.cycle_pos(tick);
         let cur_idx = self.activity_idx_at(pos);
         let next_idx = (cur_idx + 1) % self.activities.len();
+        let next_offset = self.activities[next_idx].start_offset();
+        let pos = TickDuration(pos as u64);
 
-        let ticks_until: u64 = if next_idx > cur_idx {
+        // Compare offsets rather than indices: before the first activity of
+        // a cycle the current one is the previous cycle's last, yet the
+        // next one still starts in this cycle.
+        let until = if next_offset > pos {
             // Next activity is later in the same cycle.
-            let next_offset = self.activities[next_idx].start_offset_ticks as u64;
-            next_offset - pos as u64
+            next_offset - pos
         } else {
             // Next activity wraps to the next cycle.
-            let next_offset = self.activities[next_idx].start_offset_ticks as u64;
-            self.cycle_ticks as u64 - pos as u64 + next_offset
+            self.cycle() - pos + next_offset
         };
 
-        // Guard against a degenerate plan where ticks_until would be 0
+        // Guard against a degenerate plan where `until` would be 0
         // (e.g. duplicate start offsets).  Advance by one full cycle.
-        let ticks_until = ticks_until.max(1);
-
-        Some(tick + ticks_until)
+        Some(until.max(TickDuration::ONE))
     }
 
     // ── Private helpers ───────────────────────────────────────────────────
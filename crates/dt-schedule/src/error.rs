@@ -1,3 +1,4 @@
+use dt_core::{DtError, ErrorCategory};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -10,3 +11,9 @@ pub enum ScheduleError {
 }
 
 pub type ScheduleResult<T> = Result<T, ScheduleError>;
+
+impl From<ScheduleError> for DtError {
+    fn from(err: ScheduleError) -> Self {
+        DtError::subsystem(ErrorCategory::Schedule, err)
+    }
+}
@@ -34,6 +34,8 @@ mod tests;
 
 pub use activity::{ActivityPlan, Destination, ScheduledActivity};
 pub use error::{ScheduleError, ScheduleResult};
-pub use loader::{load_plans_csv, load_plans_reader};
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub use loader::load_plans_csv;
+pub use loader::load_plans_reader;
 pub use modifier::{ChainedModifier, NoModification, ScheduleModifier, ScheduleModifierExt};
 pub use wake_queue::WakeQueue;
@@ -33,6 +33,7 @@
 
 use std::collections::HashMap;
 use std::io::Read;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
 use std::path::Path;
 
 use serde::Deserialize;
@@ -60,6 +61,10 @@ struct ScheduleRecord {
 ///
 /// Returns a `Vec` of length `agent_count`, indexed by `AgentId`.  Agents
 /// with no rows in the file receive [`ActivityPlan::empty`].
+///
+/// Not available on `wasm32-unknown-unknown`, which has no filesystem; use
+/// [`load_plans_reader`] on fetched bytes instead.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
 pub fn load_plans_csv(path: &Path, agent_count: usize) -> Result<Vec<ActivityPlan>, ScheduleError> {
     let file = std::fs::File::open(path)
         .map_err(ScheduleError::Io)?;
@@ -1,6 +1,6 @@
 //! Unit tests for dt-schedule.
 
-use dt_core::{ActivityId, NodeId, Tick};
+use dt_core::{ActivityId, NodeId, Tick, TickDuration};
 
 use crate::{
     ActivityPlan, Destination, NoModification, ScheduleModifier, ScheduledActivity, WakeQueue,
@@ -131,6 +131,30 @@ mod activity_plan {
         assert_eq!(plan.next_wake_tick(Tick(24)),  Some(Tick(48)));
     }
 
+    #[test]
+    fn next_wake_tick_before_first_activity_stays_in_cycle() {
+        // Work 8–17, home 17–32 (across midnight).  Before 8 the agent is
+        // still home from the previous cycle, and work starts this cycle.
+        let plan = ActivityPlan::new(vec![act(8, 9, 1), act(17, 15, 0)], 24);
+        assert_eq!(plan.next_wake_tick(Tick(0)),  Some(Tick(8)));
+        assert_eq!(plan.next_wake_tick(Tick(27)), Some(Tick(32)));
+        assert_eq!(plan.next_wake_tick(Tick(17)), Some(Tick(32)));
+        // One activity not at offset 0.
+        let plan = ActivityPlan::new(vec![act(5, 24, 0)], 24);
+        assert_eq!(plan.next_wake_tick(Tick(2)),  Some(Tick(5)));
+        assert_eq!(plan.next_wake_tick(Tick(5)),  Some(Tick(29)));
+    }
+
+    #[test]
+    fn time_until_next_as_duration() {
+        let plan = daily_plan();
+        assert_eq!(plan.cycle(), TickDuration(24));
+        assert_eq!(plan.activities()[1].start_offset(), TickDuration(8));
+        assert_eq!(plan.time_until_next(Tick(4)),  Some(TickDuration(4)));
+        assert_eq!(plan.time_until_next(Tick(20)), Some(TickDuration(4)));
+        assert!(ActivityPlan::empty().time_until_next(Tick(0)).is_none());
+    }
+
     #[test]
     fn cycle_pos_correct() {
         let plan = daily_plan(); // cycle_ticks = 24
@@ -201,6 +225,19 @@ mod wake_queue {
         assert_eq!(q.len(), 3);        // 3 total agents
     }
 
+    #[test]
+    fn iter_in_tick_then_push_order() {
+        let mut q = WakeQueue::new();
+        q.push(Tick(3), AgentId(2));
+        q.push(Tick(1), AgentId(1));
+        q.push(Tick(1), AgentId(0));
+        let entries: Vec<(Tick, Vec<AgentId>)> = q.iter().map(|(t, a)| (t, a.to_vec())).collect();
+        assert_eq!(entries, vec![
+            (Tick(1), vec![AgentId(1), AgentId(0)]),
+            (Tick(3), vec![AgentId(2)]),
+        ]);
+    }
+
     #[test]
     fn build_from_plans_skips_empty() {
         let plans = vec![
@@ -340,6 +377,10 @@ agent_id,activity_id,start_offset_ticks,duration_ticks,destination,cycle_ticks\n
 ";
         let result = load_plans_reader(Cursor::new(bad.as_slice()), 1);
         assert!(result.is_err());
+
+        let err = dt_core::DtError::from(result.unwrap_err());
+        assert_eq!(err.category(), dt_core::ErrorCategory::Schedule);
+        assert!(err.to_string().starts_with("schedule error: "), "{err}");
     }
 
     #[test]
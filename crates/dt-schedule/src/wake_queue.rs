@@ -112,4 +112,10 @@ impl WakeQueue {
     pub fn tick_count(&self) -> usize {
         self.inner.len()
     }
+
+    /// Iterate over queued ticks in ascending order, each with its agents in
+    /// the order they were pushed.
+    pub fn iter(&self) -> impl Iterator<Item = (Tick, &[AgentId])> {
+        self.inner.iter().map(|(&tick, agents)| (tick, agents.as_slice()))
+    }
 }
@@ -1,15 +1,18 @@
 //! Fluent builder for constructing a [`Sim`].
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use dt_agent::{AgentRngs, AgentStore};
 use dt_behavior::BehaviorModel;
-use dt_core::{AgentId, NodeId, Tick, SimConfig};
+use dt_core::{AgentId, NodeId, SimConfig, Tick, TickDuration};
 use dt_mobility::MobilityEngine;
 use dt_schedule::{ActivityPlan, WakeQueue};
 use dt_spatial::{RoadNetwork, Router};
 
-use crate::{Sim, SimError, SimResult};
+use crate::{
+    AgentSnapshot, FailurePolicy, IdlePolicy, PhasePoint, PhaseTimings, Sim, SimError, SimResult, SnapshotReader,
+    SnapshotTrigger, StateSnapshot, TickMetrics, TickPhase, TickStats, TriggerContext,
+};
 
 /// Fluent builder for [`Sim<B, R>`].
 ///
@@ -22,11 +25,20 @@ use crate::{Sim, SimError, SimResult};
 ///
 /// # Optional inputs (have defaults)
 ///
-/// | Method                   | Default                     |
-/// |--------------------------|-----------------------------|
-/// | `.plans(v)`              | All-empty `ActivityPlan`s   |
-/// | `.network(n)`            | `RoadNetwork::empty()`      |
-/// | `.initial_positions(v)`  | All `NodeId::INVALID`       |
+/// | Method                             | Default                          |
+/// |------------------------------------|----------------------------------|
+/// | `.plans(v)`                        | All-empty `ActivityPlan`s        |
+/// | `.network(n)`                      | `RoadNetwork::empty()`           |
+/// | `.initial_positions(v)`            | All `NodeId::INVALID`            |
+/// | `.failure_policy(p)`               | `FailurePolicy::Continue`        |
+/// | `.initial_state_from_snapshot(r)`  | Cold start at tick 0             |
+/// | `.phase(point, p)`                 | No custom phases                 |
+/// | `.edge_contacts(b)`                | `config.contacts.edge_contacts`  |
+/// | `.snapshot_when(f)`                | Interval snapshots only          |
+/// | `.idle_policy(p)`                  | `IdlePolicy::RunAll`             |
+/// | `.trace_agents(ids)`               | No agents traced                 |
+/// | `.max_woken_per_tick(n)`           | Unbounded                        |
+/// | `.intra_tick_rounds(n)`            | 1                                |
 ///
 /// # Example
 ///
@@ -40,14 +52,23 @@ use crate::{Sim, SimError, SimResult};
 /// sim.run(&mut NoopObserver)?;
 /// ```
 pub struct SimBuilder<B: BehaviorModel, R: Router> {
-    config:    SimConfig,
-    agents:    AgentStore,
-    rngs:      AgentRngs,
-    plans:     Option<Vec<ActivityPlan>>,
-    network:   Option<RoadNetwork>,
-    positions: Option<Vec<NodeId>>,
-    behavior:  B,
-    router:    R,
+    config:        SimConfig,
+    agents:        AgentStore,
+    rngs:          AgentRngs,
+    plans:         Option<Vec<ActivityPlan>>,
+    network:       Option<RoadNetwork>,
+    positions:     Option<Vec<NodeId>>,
+    policy:        FailurePolicy,
+    snapshot:      Option<Result<StateSnapshot, String>>,
+    phases:        Vec<(PhasePoint, Box<dyn TickPhase>)>,
+    edge_contacts: bool,
+    triggers:      Vec<SnapshotTrigger>,
+    idle:          IdlePolicy,
+    traced:        BTreeSet<AgentId>,
+    woken_cap:     Option<usize>,
+    rounds:        u32,
+    behavior:      B,
+    router:        R,
 }
 
 impl<B: BehaviorModel, R: Router> SimBuilder<B, R> {
@@ -59,13 +80,23 @@ impl<B: BehaviorModel, R: Router> SimBuilder<B, R> {
         behavior: B,
         router:   R,
     ) -> Self {
+        let edge_contacts = config.contacts.edge_contacts;
         Self {
             config,
             agents,
             rngs,
-            plans:     None,
-            network:   None,
-            positions: None,
+            plans:         None,
+            network:       None,
+            positions:     None,
+            policy:        FailurePolicy::default(),
+            snapshot:      None,
+            phases:        Vec::new(),
+            edge_contacts,
+            triggers:      Vec::new(),
+            idle:          IdlePolicy::default(),
+            traced:        BTreeSet::new(),
+            woken_cap:     None,
+            rounds:        1,
             behavior,
             router,
         }
@@ -99,6 +130,108 @@ impl<B: BehaviorModel, R: Router> SimBuilder<B, R> {
         self
     }
 
+    /// Choose how routing errors, behavior panics, and observer errors are
+    /// handled during the run (see [`FailurePolicy`]).
+    pub fn failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Warm-start from a snapshot written by an earlier run.
+    ///
+    /// The snapshot replaces [`initial_positions`][Self::initial_positions]:
+    /// stationary agents are placed at their recorded node, and in-transit
+    /// agents are re-routed from their departure node to their destination,
+    /// keeping their recorded departure and arrival ticks (see
+    /// [`AgentSnapshot`]).  The clock starts at
+    /// [`StateSnapshot::resume_tick`] and the wake queue is seeded from each
+    /// agent's plan at that tick; in-transit agents are instead re-woken on
+    /// arrival as usual.  A journey that can no longer be routed goes
+    /// through the [`FailurePolicy`] like any routing failure: the agent
+    /// stays at its departure node and is re-woken by its plan.
+    ///
+    /// `reader` is consumed immediately; any read error is reported by
+    /// [`build`][Self::build].
+    pub fn initial_state_from_snapshot<S: SnapshotReader>(mut self, mut reader: S) -> Self {
+        self.snapshot = Some(reader.read_snapshot());
+        self
+    }
+
+    /// Register a custom [`TickPhase`] to run at `point` every tick.
+    ///
+    /// Phases registered at the same point run in registration order.
+    pub fn phase<P: TickPhase + 'static>(mut self, point: PhasePoint, phase: P) -> Self {
+        self.phases.push((point, Box::new(phase)));
+        self
+    }
+
+    /// Report in-transit agents sharing an edge to
+    /// [`BehaviorModel::on_edge_contacts`], and transit riders sharing a
+    /// vehicle to [`BehaviorModel::on_vehicle_contacts`], every tick.
+    ///
+    /// Off unless enabled in `config.contacts`: the co-traveler index costs
+    /// an extra O(N) scan plus a route walk per traveler each tick.
+    pub fn edge_contacts(mut self, enabled: bool) -> Self {
+        self.edge_contacts = enabled;
+        self
+    }
+
+    /// Also snapshot any tick for which `trigger` returns `true`.
+    ///
+    /// Triggers are evaluated at the end of every tick, after
+    /// `on_tick_end`, and combine with `config.output_interval_ticks`
+    /// (see [`trigger`][crate::trigger]).
+    pub fn snapshot_when<F>(mut self, trigger: F) -> Self
+    where
+        F: FnMut(&TriggerContext<'_>) -> bool + Send + 'static,
+    {
+        self.triggers.push(Box::new(trigger));
+        self
+    }
+
+    /// Choose whether `run` may skip idle ticks (see [`IdlePolicy`]).
+    pub fn idle_policy(mut self, policy: IdlePolicy) -> Self {
+        self.idle = policy;
+        self
+    }
+
+    /// Report every wake, intent, applied effect, message, and arrival of
+    /// `agents` through [`SimObserver::on_trace`][crate::SimObserver::on_trace].
+    ///
+    /// May be called repeatedly; the sets are merged.
+    pub fn trace_agents(mut self, agents: impl IntoIterator<Item = AgentId>) -> Self {
+        self.traced.extend(agents);
+        self
+    }
+
+    /// Process at most `cap` woken agents per tick, deferring the rest to the
+    /// next tick.
+    ///
+    /// Bounds per-tick latency in interactive runs when many agents wake at
+    /// once (e.g. a morning commute surge).  Deferred agents are processed
+    /// first on the following tick, so nobody waits indefinitely; within a
+    /// tick's batch, agents are taken in ascending `AgentId` order.  A
+    /// deferred agent's callbacks see the tick at which it is actually
+    /// processed.
+    pub fn max_woken_per_tick(mut self, cap: usize) -> Self {
+        self.woken_cap = Some(cap);
+        self
+    }
+
+    /// Allow up to `rounds` message exchanges per tick.
+    ///
+    /// With the default of 1, a message is delivered at the recipient's next
+    /// natural wake.  With more, each extra round immediately delivers the
+    /// messages sent in the previous round (via `on_message` only) to their
+    /// stationary recipients, whether or not they woke this tick, and
+    /// applies their replies — enough for a few steps of negotiation or
+    /// matching inside one long tick.  Rounds stop early once a round sends no
+    /// messages; messages sent in the last round wait for the next wake.
+    pub fn intra_tick_rounds(mut self, rounds: u32) -> Self {
+        self.rounds = rounds;
+        self
+    }
+
     /// Validate inputs, build the wake queue and mobility engine, and return
     /// a ready-to-run [`Sim`].
     pub fn build(self) -> SimResult<Sim<B, R>> {
@@ -133,31 +266,131 @@ impl<B: BehaviorModel, R: Router> SimBuilder<B, R> {
             None => vec![NodeId::INVALID; agent_count],
         };
 
-        let network = self.network.unwrap_or_else(RoadNetwork::empty);
+        if self.rounds == 0 {
+            return Err(SimError::Config("intra_tick_rounds must be at least 1".into()));
+        }
+        if self.woken_cap == Some(0) {
+            return Err(SimError::Config("max_woken_per_tick must be at least 1".into()));
+        }
+        if let Some(agent) = self.traced.iter().find(|a| a.index() >= agent_count) {
+            return Err(SimError::Config(format!(
+                "traced agent {agent} out of range ({agent_count} agents)"
+            )));
+        }
+
+        let routing = &self.config.routing;
+        for (name, speed) in [
+            ("walk_speed_mps", routing.walk_speed_mps),
+            ("bike_speed_mps", routing.bike_speed_mps),
+            ("transit_speed_mps", routing.transit_speed_mps),
+        ] {
+            if !(speed.is_finite() && speed > 0.0) {
+                return Err(SimError::Config(format!("routing.{name} must be positive, got {speed}")));
+            }
+        }
+
+        let mut network = self.network.unwrap_or_else(RoadNetwork::empty);
+        network.mode_speeds = self.config.routing.clone();
 
-        // ── Build initial wake queue from plans ───────────────────────────
-        let wake_queue = WakeQueue::build_from_plans(&plans, Tick(0));
+        let snapshot = match self.snapshot {
+            Some(Ok(snap)) => Some(validate_snapshot(snap, agent_count)?),
+            Some(Err(e))   => return Err(SimError::Snapshot(e)),
+            None           => None,
+        };
 
-        // ── Build mobility engine and place agents ────────────────────────
+        let mut clock = self.config.make_clock();
         let mut mobility = MobilityEngine::new(self.router, agent_count);
-        for (i, &node) in positions.iter().enumerate() {
-            if node != NodeId::INVALID {
-                mobility.place(AgentId(i as u32), node, Tick(0));
+        mobility.store.min_travel = TickDuration(self.config.mobility.min_travel_ticks);
+        mobility.store.path_following = self.config.mobility.path_following;
+
+        let snapshot_tick = snapshot.as_ref().map(|snap| snap.tick);
+        let mut journeys: Vec<AgentSnapshot> = Vec::new();
+        let wake_queue = match snapshot {
+            // ── Cold start: place agents, seed wake queue from plans ──────
+            None => {
+                for (i, &node) in positions.iter().enumerate() {
+                    if node != NodeId::INVALID {
+                        mobility.place(AgentId(i as u32), node, Tick(0));
+                    }
+                }
+                WakeQueue::build_from_plans(&plans, Tick(0))
             }
-        }
 
-        let sim = Sim {
-            clock:         self.config.make_clock(),
-            config:        self.config,
-            agents:        self.agents,
-            rngs:          self.rngs,
+            // ── Warm start: restore snapshot state at the resume tick ─────
+            //
+            // Journeys are resumed once the sim exists, so that routing
+            // failures go through its failure policy.
+            Some(snap) => {
+                let start = snap.resume_tick();
+                clock.current_tick = start;
+                let mut wake_queue = WakeQueue::new();
+                for a in &snap.agents {
+                    if a.departure_node != NodeId::INVALID {
+                        mobility.place(a.agent, a.departure_node, start);
+                    }
+                    if a.in_transit {
+                        journeys.push(*a);
+                    } else if let Some(wake) = plans[a.agent.index()].next_wake_tick(snap.tick) {
+                        wake_queue.push(wake, a.agent);
+                    }
+                }
+                wake_queue
+            }
+        };
+
+        let mut sim = Sim {
+            clock,
+            config:             self.config,
+            agents:             self.agents,
+            rngs:               self.rngs,
             plans,
             wake_queue,
             mobility,
-            behavior:      self.behavior,
+            behavior:           self.behavior,
             network,
-            message_queue: HashMap::new(),
+            message_queue:      HashMap::new(),
+            failure_policy:     self.policy,
+            failures:           Vec::new(),
+            phases:             self.phases,
+            edge_contacts:      self.edge_contacts,
+            metrics:            TickMetrics::default(),
+            metric_totals:      TickMetrics::default(),
+            stats:              TickStats::default(),
+            timings:            PhaseTimings::default(),
+            snapshot_triggers:  self.triggers,
+            idle_policy:        self.idle,
+            skipped_ticks:      0,
+            traced:             self.traced,
+            trace_buffer:       Vec::new(),
+            departures:         Vec::new(),
+            max_woken_per_tick: self.woken_cap,
+            deferred:           Vec::new(),
+            intra_tick_rounds:  self.rounds,
+            round_recipients:   Vec::new(),
         };
+        if let Some(tick) = snapshot_tick {
+            sim.resume_journeys(tick, &journeys)?;
+        }
         Ok(sim)
     }
 }
+
+/// Check that `snap` holds exactly one entry per agent.
+fn validate_snapshot(snap: StateSnapshot, agent_count: usize) -> SimResult<StateSnapshot> {
+    if snap.agents.len() != agent_count {
+        return Err(SimError::AgentCountMismatch {
+            expected: agent_count,
+            got:      snap.agents.len(),
+            what:     "snapshot agents",
+        });
+    }
+    let mut seen = vec![false; agent_count];
+    for a in &snap.agents {
+        match seen.get_mut(a.agent.index()) {
+            Some(s) if !*s => *s = true,
+            Some(_) => return Err(SimError::Snapshot(format!("{} appears twice", a.agent))),
+            None    => return Err(SimError::Snapshot(format!("{} out of range", a.agent))),
+        }
+    }
+    Ok(snap)
+}
@@ -0,0 +1,187 @@
+//! Checking that parallel runs reproduce serial ones.
+//!
+//! The apply phase is sequential and the wake order is fixed, so a run is
+//! meant to be bit-identical regardless of how the intent phase is scheduled.
+//! A behavior model that breaks this (shared interior mutability, a global
+//! RNG, iteration over a `HashMap`) usually does so silently.  This module
+//! makes the guarantee checkable:
+//!
+//! - [`Sim::state_hash`] digests the mutable simulation state.
+//! - [`tick_hashes`] runs a sim to completion, hashing after every tick.
+//! - [`first_divergence`] compares two hash sequences.
+//! - With the `parallel` feature, `check_thread_equivalence` runs the same
+//!   scenario on Rayon pools of different sizes and reports the first tick at
+//!   which any of them disagrees with the first.
+//!
+//! ```rust,ignore
+//! dt_sim::check_thread_equivalence(|| build_scenario(), &[1, 2, 8])?;
+//! ```
+//!
+//! A one-thread pool visits agents in the same order as the serial build, so
+//! `&[1, n]` compares the parallel path against serial behavior.  Hashes from
+//! separate builds (with and without `parallel`) can also be compared with
+//! [`first_divergence`], as long as both use the same compiler version.
+
+use std::hash::{DefaultHasher, Hasher};
+
+use dt_behavior::BehaviorModel;
+use dt_core::Tick;
+use dt_spatial::Router;
+
+use crate::{Sim, SimResult};
+
+// ── State hashing ─────────────────────────────────────────────────────────────
+
+impl<B: BehaviorModel, R: Router> Sim<B, R> {
+    /// Digest of the state a tick can change: the clock, every agent's
+    /// movement state, the wake queue (including per-tick order), deferred
+    /// wakes, pending messages, this tick's metrics, and the failure count.
+    ///
+    /// Agent components and RNG states are not included; divergence there
+    /// shows up in the hashed state on a later tick.  The hash is stable
+    /// within one build but not across compiler versions; see
+    /// [`stable_state_hash`][Self::stable_state_hash] for one that is.
+    pub fn state_hash(&self) -> u64 {
+        let mut h = DefaultHasher::new();
+        self.hash_state(&mut h);
+        h.finish()
+    }
+
+    /// Feed the state [`state_hash`][Self::state_hash] digests to `h`.
+    ///
+    /// Every field is written through a fixed-width `write_u*` call, and
+    /// strings and byte payloads with a `u64` length first, so the bytes fed
+    /// to `h` depend on neither the platform nor std's `Hash` impls.
+    pub(crate) fn hash_state<H: Hasher>(&self, h: &mut H) {
+        h.write_u64(self.clock.current_tick.0);
+
+        for s in &self.mobility.store.states {
+            h.write_u8(u8::from(s.in_transit));
+            h.write_u32(s.departure_node.0);
+            h.write_u32(s.destination_node.0);
+            write_bytes(h, s.mode.as_str().as_bytes());
+            h.write_u64(s.departure_tick.0);
+            h.write_u64(s.arrival_tick.0);
+        }
+
+        for (tick, agents) in self.wake_queue.iter() {
+            h.write_u64(tick.0);
+            h.write_u64(agents.len() as u64);
+            for agent in agents {
+                h.write_u32(agent.0);
+            }
+        }
+        h.write_u64(self.deferred.len() as u64);
+        for agent in &self.deferred {
+            h.write_u32(agent.0);
+        }
+
+        // `message_queue` iteration order is random; hash by recipient.
+        let mut recipients: Vec<_> = self.message_queue.keys().copied().collect();
+        recipients.sort_unstable();
+        for to in recipients {
+            h.write_u32(to.0);
+            let messages = &self.message_queue[&to];
+            h.write_u64(messages.len() as u64);
+            for (from, payload) in messages {
+                h.write_u32(from.0);
+                write_bytes(h, payload);
+            }
+        }
+
+        for (name, &count) in &self.metrics.counters {
+            write_bytes(h, name.as_bytes());
+            h.write_u64(count);
+        }
+        for (name, s) in &self.metrics.samples {
+            write_bytes(h, name.as_bytes());
+            h.write_u64(s.count);
+            for v in [s.sum, s.min, s.max] {
+                h.write_u64(v.to_bits());
+            }
+        }
+
+        h.write_u64(self.failures.len() as u64);
+    }
+}
+
+/// Write `bytes` to `h` after their length as a `u64`.
+fn write_bytes<H: Hasher>(h: &mut H, bytes: &[u8]) {
+    h.write_u64(bytes.len() as u64);
+    h.write(bytes);
+}
+
+// ── Hash sequences ────────────────────────────────────────────────────────────
+
+/// Run `sim` from its current tick to `config.end_tick()` and return
+/// `(tick, state_hash)` after every tick.
+///
+/// Every tick is processed (the idle policy is not applied) and no observer
+/// is attached.
+pub fn tick_hashes<B: BehaviorModel, R: Router>(sim: &mut Sim<B, R>) -> SimResult<Vec<(Tick, u64)>> {
+    let end = sim.config.end_tick();
+    let mut hashes = Vec::with_capacity(end.0.saturating_sub(sim.clock.current_tick.0) as usize);
+    while sim.clock.current_tick < end {
+        let tick = sim.clock.current_tick;
+        sim.run_ticks(1, &mut crate::NoopObserver)?;
+        hashes.push((tick, sim.state_hash()));
+    }
+    Ok(hashes)
+}
+
+/// The first tick at which `a` and `b` disagree, or `None` if they match.
+///
+/// If one sequence is a prefix of the other, the first tick missing from the
+/// shorter one is reported.
+pub fn first_divergence(a: &[(Tick, u64)], b: &[(Tick, u64)]) -> Option<Tick> {
+    a.iter()
+        .zip(b)
+        .find(|(x, y)| x != y)
+        .map(|(x, _)| x.0)
+        .or_else(|| match a.len().cmp(&b.len()) {
+            std::cmp::Ordering::Less    => Some(b[a.len()].0),
+            std::cmp::Ordering::Greater => Some(a[b.len()].0),
+            std::cmp::Ordering::Equal   => None,
+        })
+}
+
+// ── Thread-count equivalence ──────────────────────────────────────────────────
+
+/// Build one sim per entry of `thread_counts` with `make`, run each on a
+/// Rayon pool of that size, and check that all per-tick hashes match the
+/// first run's.
+///
+/// Returns [`SimError::Diverged`][crate::SimError::Diverged] naming the first
+/// divergent tick and the thread counts involved.
+#[cfg(feature = "parallel")]
+pub fn check_thread_equivalence<B, R, F>(mut make: F, thread_counts: &[usize]) -> SimResult<()>
+where
+    B: BehaviorModel,
+    R: Router,
+    F: FnMut() -> SimResult<Sim<B, R>>,
+{
+    use crate::SimError;
+
+    let mut baseline: Option<(usize, Vec<(Tick, u64)>)> = None;
+    for &threads in thread_counts {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map_err(|e| SimError::Config(format!("cannot build {threads}-thread pool: {e}")))?;
+        let mut sim = make()?;
+        let hashes = pool.install(|| tick_hashes(&mut sim))?;
+
+        match &baseline {
+            None => baseline = Some((threads, hashes)),
+            Some((base_threads, base)) => {
+                if let Some(tick) = first_divergence(base, &hashes) {
+                    return Err(SimError::Diverged {
+                        tick,
+                        detail: format!("{base_threads} thread(s) vs {threads} thread(s)"),
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
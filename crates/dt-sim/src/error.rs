@@ -1,3 +1,6 @@
+use std::path::PathBuf;
+
+use dt_core::{AgentId, DtError, ErrorCategory, Tick};
 use dt_mobility::MobilityError;
 use thiserror::Error;
 
@@ -15,6 +18,58 @@ pub enum SimError {
 
     #[error("mobility error for agent: {0}")]
     Mobility(#[from] MobilityError),
+
+    /// Only with `panic = "unwind"`; see [`failure`](crate::failure).
+    #[cfg(panic = "unwind")]
+    #[error("behavior panicked for {agent} at {tick}: {message}")]
+    BehaviorPanic {
+        agent:   AgentId,
+        tick:    Tick,
+        message: String,
+    },
+
+    #[error("routing failed for {agent} at {tick}: {message}")]
+    Routing {
+        agent:   AgentId,
+        tick:    Tick,
+        message: String,
+    },
+
+    #[error("warm-start snapshot error: {0}")]
+    Snapshot(String),
+
+    #[error("observer error at {tick}: {message}")]
+    Observer {
+        tick:    Tick,
+        message: String,
+    },
+
+    #[error("run cancelled before {tick}")]
+    Cancelled { tick: Tick },
+
+    #[error("tick phase error at {tick}: {message}")]
+    Phase {
+        tick:    Tick,
+        message: String,
+    },
+
+    #[error("runs diverged at {tick}: {detail}")]
+    Diverged {
+        tick:   Tick,
+        detail: String,
+    },
+
+    #[error("golden trace {}: {message}", path.display())]
+    Golden {
+        path:    PathBuf,
+        message: String,
+    },
 }
 
 pub type SimResult<T> = Result<T, SimError>;
+
+impl From<SimError> for DtError {
+    fn from(err: SimError) -> Self {
+        DtError::subsystem(ErrorCategory::Sim, err)
+    }
+}
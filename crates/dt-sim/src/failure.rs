@@ -0,0 +1,145 @@
+//! Failure handling policy for the tick loop.
+//!
+//! Four kinds of per-agent or per-tick failure can occur while a simulation
+//! is running:
+//!
+//! | Kind            | Source                                                  |
+//! |-----------------|---------------------------------------------------------|
+//! | `Routing`       | `TravelTo` intent whose route could not be computed     |
+//! | `BehaviorPanic` | `replan` / `on_message` / `on_contacts` panicked (unwind builds only) |
+//! | `Observer`      | `SimObserver::poll_error` reported a write error        |
+//! | `Phase`         | a registered `TickPhase` returned an error              |
+//!
+//! [`FailurePolicy`] decides what happens next: abort the run, carry on
+//! (quietly by default, or logging each failure to stderr), or collect every
+//! failure into [`Sim::failures`][crate::Sim::failures] for inspection after
+//! the run.  Routing failures and behavior panics are counted in
+//! [`TickStats`][crate::TickStats] under every policy.
+//!
+//! # Behavior panics require `panic = "unwind"`
+//!
+//! Behavior panics can only be caught when the binary is compiled with
+//! `panic = "unwind"` (the Cargo default, and always the case for tests).
+//! The workspace `release` and `fast` profiles use `panic = "abort"`; there
+//! a panicking behavior terminates the process whatever the policy, so
+//! `FailureKind::BehaviorPanic` and `SimError::BehaviorPanic` are not
+//! compiled in and `TickStats::behavior_panics` stays zero.  Build with a
+//! profile that sets `panic = "unwind"` to have the policy cover panics.
+
+#[cfg(panic = "unwind")]
+use std::any::Any;
+use std::fmt;
+
+use dt_core::{AgentId, Tick};
+
+// ── FailurePolicy ─────────────────────────────────────────────────────────────
+
+/// What the tick loop does when an agent or observer fails.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum FailurePolicy {
+    /// Abort the run and return the failure as a [`SimError`][crate::SimError].
+    FailFast,
+
+    /// Continue without printing anything.  The failing agent is
+    /// re-scheduled via its activity plan, exactly as if it had emitted no
+    /// intents this tick; the failure only shows in the tick's
+    /// [`TickStats`][crate::TickStats] and in agent traces.
+    #[default]
+    Continue,
+
+    /// As `Continue`, also printing each failure to stderr.  A run with
+    /// many unroutable agents prints a line for every one.
+    LogAndContinue,
+
+    /// Continue silently, appending every failure to
+    /// [`Sim::failures`][crate::Sim::failures] for reporting after the run.
+    CollectAndReport,
+}
+
+// ── SimFailure ────────────────────────────────────────────────────────────────
+
+/// Category of a recorded [`SimFailure`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum FailureKind {
+    /// The router could not produce a route for a `TravelTo` intent.
+    Routing,
+    /// A behavior callback panicked; the agent's intents for the tick were
+    /// discarded.  Only with `panic = "unwind"`.
+    #[cfg(panic = "unwind")]
+    BehaviorPanic,
+    /// An observer (usually an output writer) reported an error.
+    Observer,
+    /// A registered [`TickPhase`][crate::TickPhase] returned an error.
+    Phase,
+}
+
+impl FailureKind {
+    /// Short lowercase label used in log lines.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FailureKind::Routing       => "routing",
+            #[cfg(panic = "unwind")]
+            FailureKind::BehaviorPanic => "behavior panic",
+            FailureKind::Observer      => "observer",
+            FailureKind::Phase         => "tick phase",
+        }
+    }
+}
+
+/// One non-fatal failure recorded under [`FailurePolicy::CollectAndReport`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimFailure {
+    /// Tick during which the failure occurred.
+    pub tick: Tick,
+    /// The agent concerned, or `None` for tick-level (observer, phase)
+    /// failures.
+    pub agent: Option<AgentId>,
+    /// What went wrong.
+    pub kind: FailureKind,
+    /// Human-readable detail (error `Display` or panic payload).
+    pub message: String,
+}
+
+impl fmt::Display for SimFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.agent {
+            Some(agent) => write!(
+                f, "{} {agent}: {} failure: {}", self.tick, self.kind.as_str(), self.message,
+            ),
+            None => write!(f, "{}: {} failure: {}", self.tick, self.kind.as_str(), self.message),
+        }
+    }
+}
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// What a behavior callback's panic leaves behind: its message where panics
+/// unwind, and nothing at all (no panic is ever caught) where they abort.
+#[cfg(panic = "unwind")]
+pub(crate) type Panic = String;
+#[cfg(not(panic = "unwind"))]
+pub(crate) type Panic = std::convert::Infallible;
+
+/// Run a behavior callback, catching a panic where panics unwind.
+pub(crate) fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, Panic> {
+    #[cfg(panic = "unwind")]
+    {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).map_err(|payload| panic_message(payload.as_ref()))
+    }
+    #[cfg(not(panic = "unwind"))]
+    {
+        Ok(f())
+    }
+}
+
+/// Extract a readable message from a `catch_unwind` payload.
+#[cfg(panic = "unwind")]
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_owned()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "non-string panic payload".to_owned()
+    }
+}
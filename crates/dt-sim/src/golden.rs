@@ -0,0 +1,355 @@
+//! Golden runs: reference traces that catch changes in simulation results.
+//!
+//! A [`GoldenTrace`] is a compact record of one run — after every tick, a
+//! [stable state hash][Sim::stable_state_hash] and a few [`TickStats`]
+//! aggregates — saved as a small text file next to the tests.  Re-running
+//! the same scenario and comparing with [`GoldenTrace::first_divergence`]
+//! reports the first tick at which the results changed, and which of the
+//! recorded quantities differ there:
+//!
+//! ```rust,ignore
+//! // Records the trace on first use; afterwards, fails on the first divergent tick.
+//! dt_sim::check_golden("golden/commute.golden".as_ref(), &mut build_commute())?;
+//! ```
+//!
+//! A refactor that is meant to change results is accepted by re-recording:
+//! run the tests with `DT_BLESS_GOLDEN=1` and commit the updated files.
+//! Traces recorded on a 64-bit target do not carry over to 32-bit ones
+//! (`wasm32` included), whose agent RNGs draw different numbers.
+//!
+//! The text format is one header line naming the trace, one naming the
+//! columns, and one line per tick, so a re-recorded file diffs cleanly:
+//!
+//! ```text
+//! # dt-sim golden trace v1: commute
+//! # tick hash woken departures arrivals in_transit routing_failures contacts
+//! 0 9f1c2b7a0e43d615 40 38 0 38 2 0
+//! ```
+
+use std::fmt;
+use std::hash::Hasher;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::path::Path;
+
+use dt_behavior::BehaviorModel;
+use dt_core::Tick;
+use dt_spatial::Router;
+
+use crate::{Sim, SimError, SimResult, TickStats};
+
+/// Environment variable that makes [`check_golden`] re-record instead of
+/// compare.
+pub const BLESS_ENV: &str = "DT_BLESS_GOLDEN";
+
+const HEADER: &str = "# dt-sim golden trace v1: ";
+const COLUMNS: &str = "# tick hash woken departures arrivals in_transit routing_failures contacts";
+
+// ── Stable hashing ────────────────────────────────────────────────────────────
+
+/// 64-bit FNV-1a, with integers written as fixed-width little-endian bytes so
+/// the digest does not depend on the platform or the std hasher.
+#[derive(Debug, Clone, Copy)]
+pub struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, n: u16) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, n: u128) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.write_u64(n as u64);
+    }
+
+    fn write_isize(&mut self, n: isize) {
+        self.write_u64(n as i64 as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<B: BehaviorModel, R: Router> Sim<B, R> {
+    /// [`state_hash`][Self::state_hash] computed with [`StableHasher`]:
+    /// the state is written field by field as fixed-width little-endian
+    /// integers and length-prefixed bytes, never through std `Hash` impls,
+    /// so the digest is the same on every platform and compiler version and
+    /// can be saved and compared with later builds.
+    pub fn stable_state_hash(&self) -> u64 {
+        let mut h = StableHasher::default();
+        self.hash_state(&mut h);
+        h.finish()
+    }
+}
+
+// ── GoldenTick ────────────────────────────────────────────────────────────────
+
+/// One tick of a golden trace: the state hash after the tick and the tick's
+/// key aggregates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GoldenTick {
+    pub tick:             Tick,
+    pub hash:             u64,
+    pub woken:            u64,
+    pub departures:       u64,
+    pub arrivals:         u64,
+    pub in_transit:       u64,
+    pub routing_failures: u64,
+    pub contacts:         u64,
+}
+
+impl GoldenTick {
+    fn new(tick: Tick, hash: u64, stats: &TickStats) -> Self {
+        Self {
+            tick,
+            hash,
+            woken:            stats.woken,
+            departures:       stats.departures,
+            arrivals:         stats.arrivals,
+            in_transit:       stats.in_transit,
+            routing_failures: stats.routing_failures,
+            contacts:         stats.contacts,
+        }
+    }
+
+    /// `(name, expected, actual)` for every aggregate that differs from
+    /// `other`, in column order (the hash is formatted in hex).
+    fn differences(&self, other: &Self) -> Vec<(&'static str, String, String)> {
+        let mut out = Vec::new();
+        if self.hash != other.hash {
+            out.push(("hash", format!("{:016x}", self.hash), format!("{:016x}", other.hash)));
+        }
+        let fields = [
+            ("woken",            self.woken,            other.woken),
+            ("departures",       self.departures,       other.departures),
+            ("arrivals",         self.arrivals,         other.arrivals),
+            ("in_transit",       self.in_transit,       other.in_transit),
+            ("routing_failures", self.routing_failures, other.routing_failures),
+            ("contacts",         self.contacts,         other.contacts),
+        ];
+        for (name, a, b) in fields {
+            if a != b {
+                out.push((name, a.to_string(), b.to_string()));
+            }
+        }
+        out
+    }
+}
+
+impl fmt::Display for GoldenTick {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:016x} {} {} {} {} {} {}",
+            self.tick.0,
+            self.hash,
+            self.woken,
+            self.departures,
+            self.arrivals,
+            self.in_transit,
+            self.routing_failures,
+            self.contacts
+        )
+    }
+}
+
+// ── GoldenTrace ───────────────────────────────────────────────────────────────
+
+/// A named reference trace: one [`GoldenTick`] per tick of a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenTrace {
+    pub name:  String,
+    pub ticks: Vec<GoldenTick>,
+}
+
+impl GoldenTrace {
+    /// Run `sim` from its current tick to `config.end_tick()`, recording
+    /// every tick.
+    ///
+    /// Like [`tick_hashes`][crate::tick_hashes], every tick is processed and
+    /// no observer is attached.
+    pub fn record<B: BehaviorModel, R: Router>(name: impl Into<String>, sim: &mut Sim<B, R>) -> SimResult<Self> {
+        let end = sim.config.end_tick();
+        let mut ticks = Vec::with_capacity(end.0.saturating_sub(sim.clock.current_tick.0) as usize);
+        while sim.clock.current_tick < end {
+            let tick = sim.clock.current_tick;
+            sim.run_ticks(1, &mut crate::NoopObserver)?;
+            ticks.push(GoldenTick::new(tick, sim.stable_state_hash(), &sim.stats));
+        }
+        Ok(Self { name: name.into(), ticks })
+    }
+
+    /// The first tick at which `actual` differs from this (expected) trace,
+    /// or `None` if they match.  Names are not compared.
+    ///
+    /// If one trace is a prefix of the other, the first tick missing from
+    /// the shorter one is reported.
+    pub fn first_divergence(&self, actual: &GoldenTrace) -> Option<Divergence> {
+        let (e, a) = (&self.ticks, &actual.ticks);
+        if let Some((x, y)) = e.iter().zip(a).find(|(x, y)| x != y) {
+            return Some(Divergence { tick: x.tick, expected: Some(*x), actual: Some(*y) });
+        }
+        let (expected, actual) = match e.len().cmp(&a.len()) {
+            std::cmp::Ordering::Less    => (None, Some(a[e.len()])),
+            std::cmp::Ordering::Greater => (Some(e[a.len()]), None),
+            std::cmp::Ordering::Equal   => return None,
+        };
+        let tick = expected.or(actual).expect("one side has the tick").tick;
+        Some(Divergence { tick, expected, actual })
+    }
+
+    /// Parse the text format written by `Display`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate();
+        let name = match lines.next() {
+            Some((_, line)) if line.starts_with(HEADER) => line[HEADER.len()..].to_owned(),
+            _ => return Err(format!("line 1: expected `{}<name>`", HEADER.trim_end())),
+        };
+
+        let mut ticks = Vec::new();
+        for (i, line) in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != 8 {
+                return Err(format!("line {}: expected 8 fields, found {}", i + 1, fields.len()));
+            }
+            let num = |j: usize| fields[j].parse::<u64>().map_err(|e| format!("line {}: {e}", i + 1));
+            ticks.push(GoldenTick {
+                tick:             Tick(num(0)?),
+                hash:             u64::from_str_radix(fields[1], 16).map_err(|e| format!("line {}: {e}", i + 1))?,
+                woken:            num(2)?,
+                departures:       num(3)?,
+                arrivals:         num(4)?,
+                in_transit:       num(5)?,
+                routing_failures: num(6)?,
+                contacts:         num(7)?,
+            });
+        }
+        Ok(Self { name, ticks })
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    pub fn read(path: &Path) -> SimResult<Self> {
+        let text = std::fs::read_to_string(path).map_err(|e| golden_error(path, e))?;
+        Self::parse(&text).map_err(|message| golden_error(path, message))
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    pub fn write(&self, path: &Path) -> SimResult<()> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| golden_error(path, e))?;
+        }
+        std::fs::write(path, self.to_string()).map_err(|e| golden_error(path, e))
+    }
+}
+
+impl fmt::Display for GoldenTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{HEADER}{}", self.name)?;
+        writeln!(f, "{COLUMNS}")?;
+        for t in &self.ticks {
+            writeln!(f, "{t}")?;
+        }
+        Ok(())
+    }
+}
+
+// ── Divergence ────────────────────────────────────────────────────────────────
+
+/// Where a run left its golden trace.  `expected` or `actual` is `None` when
+/// that trace ended first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Divergence {
+    pub tick:     Tick,
+    pub expected: Option<GoldenTick>,
+    pub actual:   Option<GoldenTick>,
+}
+
+/// Lists the differing quantities, e.g. `departures 12 -> 11, hash … -> …`.
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.expected, &self.actual) {
+            (Some(e), Some(a)) => {
+                let diffs: Vec<String> =
+                    e.differences(a).into_iter().map(|(name, e, a)| format!("{name} {e} -> {a}")).collect();
+                write!(f, "{}", diffs.join(", "))
+            }
+            (Some(_), None) => write!(f, "run ended before the golden trace"),
+            (None, Some(_)) => write!(f, "run continued past the end of the golden trace"),
+            (None, None) => write!(f, "no difference"),
+        }
+    }
+}
+
+// ── check_golden ──────────────────────────────────────────────────────────────
+
+/// What [`check_golden`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoldenOutcome {
+    /// The run matched the trace at `path`.
+    Matched,
+    /// The trace was missing or [`BLESS_ENV`] was set, and the run was
+    /// recorded to `path`.
+    Recorded,
+}
+
+/// Run `sim` to the end and compare it with the golden trace at `path`
+/// (named after the file stem).
+///
+/// If the file does not exist, or `DT_BLESS_GOLDEN` is set to anything but
+/// `0`, the run is recorded there instead.  A mismatch is returned as
+/// [`SimError::Diverged`] at the first divergent tick.
+///
+/// Not available on `wasm32-unknown-unknown`; compare traces with
+/// [`GoldenTrace::parse`] and [`GoldenTrace::first_divergence`] there.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub fn check_golden<B: BehaviorModel, R: Router>(path: &Path, sim: &mut Sim<B, R>) -> SimResult<GoldenOutcome> {
+    let name = path.file_stem().map_or_else(String::new, |s| s.to_string_lossy().into_owned());
+    let actual = GoldenTrace::record(name, sim)?;
+
+    let bless = std::env::var_os(BLESS_ENV).is_some_and(|v| v != "0");
+    if bless || !path.exists() {
+        actual.write(path)?;
+        return Ok(GoldenOutcome::Recorded);
+    }
+
+    match GoldenTrace::read(path)?.first_divergence(&actual) {
+        None => Ok(GoldenOutcome::Matched),
+        Some(d) => Err(SimError::Diverged {
+            tick:   d.tick,
+            detail: format!("{d} (golden trace {}; set {BLESS_ENV}=1 to re-record)", path.display()),
+        }),
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn golden_error(path: &Path, message: impl ToString) -> SimError {
+    SimError::Golden { path: path.to_path_buf(), message: message.to_string() }
+}
@@ -0,0 +1,33 @@
+//! Skipping idle ticks.
+//!
+//! A sim is **quiescent** when no agent is in transit and nobody is due to
+//! wake before some future tick: nothing can change until then.  By default
+//! the loop still walks through every idle tick; [`IdlePolicy`] lets
+//! [`Sim::run`][crate::Sim::run] jump over them instead.
+//!
+//! Skipped ticks are not processed at all: no observer tick hooks, no
+//! interval or trigger snapshots (the state would be identical anyway).
+//! Observers are told about each jump through
+//! [`SimObserver::on_ticks_skipped`][crate::SimObserver::on_ticks_skipped],
+//! and the running total is kept in
+//! [`Sim::skipped_ticks`][crate::Sim::skipped_ticks].
+//!
+//! Sims with registered [`TickPhase`][crate::TickPhase]s never skip, since a
+//! phase may change state on any tick.  [`Sim::run_ticks`][crate::Sim::run_ticks]
+//! always processes exactly the requested ticks.
+
+/// What [`Sim::run`][crate::Sim::run] does with idle ticks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum IdlePolicy {
+    /// Process every tick, idle or not.
+    #[default]
+    RunAll,
+
+    /// Once the wake queue is empty and nobody is in transit, jump straight
+    /// to `end_tick`.
+    EndWhenQuiescent,
+
+    /// Additionally jump over idle gaps: whenever nobody is in transit, move
+    /// the clock directly to the next tick with a scheduled wake.
+    FastForward,
+}
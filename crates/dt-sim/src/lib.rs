@@ -17,9 +17,67 @@
 //!
 //! # Cargo features
 //!
-//! | Feature    | Effect                                                 |
-//! |------------|--------------------------------------------------------|
-//! | `parallel` | Runs the intent phase on Rayon's thread pool.          |
+//! | Feature    | Effect                                                  |
+//! |------------|---------------------------------------------------------|
+//! | `parallel` | Runs the intent phase on Rayon's thread pool.           |
+//! | `fx-hash`  | FxHashMap for the per-tick contact indexes.             |
+//! | `tokio`    | Adds `Sim::run_async` with `CancellationToken` support. |
+//!
+//! # WebAssembly
+//!
+//! Without `parallel`, the crate builds for `wasm32-unknown-unknown`, so a
+//! sim can run in the browser and be stepped from JavaScript with
+//! [`Sim::run_ticks`].  Phase timings use `dt_core::wallclock::Instant`, and
+//! path-based file I/O ([`check_golden`], `GoldenTrace::read`/`write`) is
+//! left out of browser builds.  Behavior panics are not caught there: wasm
+//! aborts on panic.
+//!
+//! # Custom phases
+//!
+//! Whole-population steps (traffic assignment, disease progression, …) plug
+//! into the loop as [`TickPhase`]s registered at a [`PhasePoint`] with
+//! [`SimBuilder::phase`].
+//!
+//! # Idle ticks
+//!
+//! With [`SimBuilder::idle_policy`], `run` can stop early or fast-forward
+//! when nothing is scheduled to happen (see [`idle`]).
+//!
+//! # Metrics
+//!
+//! Behaviors report named counters and samples with `Intent::Count` /
+//! `Intent::Sample`; the sim reduces them deterministically per tick into
+//! [`TickMetrics`] (see [`metrics`]).  Alongside, the loop counts wakes,
+//! departures, arrivals, intents by type, messages, contacts, and failures
+//! into [`TickStats`] (see [`stats`]), and times each phase of the tick
+//! into [`PhaseTimings`].
+//!
+//! # Failure handling
+//!
+//! Routing errors, behavior panics, and observer errors are handled according
+//! to the [`FailurePolicy`] set with [`SimBuilder::failure_policy`]
+//! (default: count in [`TickStats`] and continue, printing nothing).
+//!
+//! # Tracing
+//!
+//! [`SimBuilder::trace_agents`] follows a handful of agents through the run;
+//! every event involving them is reported via [`SimObserver::on_trace`] (see
+//! [`trace`]).
+//!
+//! # Determinism checks
+//!
+//! [`Sim::state_hash`] and [`tick_hashes`] digest the state after every tick;
+//! with `parallel`, `check_thread_equivalence` runs a scenario at several
+//! thread counts and reports the first divergent tick (see [`equivalence`]).
+//! [`check_golden`] compares a run with a recorded reference trace of stable
+//! per-tick hashes and aggregates, so changed results show up as the first
+//! tick that differs (see [`golden`]).
+//!
+//! # Warm start
+//!
+//! [`SimBuilder::initial_state_from_snapshot`] restores agent positions and
+//! in-progress journeys from a snapshot written by an earlier run and starts
+//! the clock just after the snapshot tick (see [`snapshot`]).
 //!
 //! # Quick-start
 //!
@@ -37,14 +95,43 @@
 //! ```
 
 pub mod builder;
+pub mod equivalence;
 pub mod error;
+pub mod failure;
+pub mod golden;
+pub mod idle;
+pub mod metrics;
 pub mod observer;
+pub mod phase;
 pub mod sim;
+pub mod snapshot;
+pub mod stats;
+pub mod trace;
+pub mod trigger;
 
 #[cfg(test)]
 mod tests;
 
+#[cfg(all(feature = "parallel", target_arch = "wasm32", target_os = "unknown"))]
+compile_error!("the `parallel` feature needs OS threads, which wasm32-unknown-unknown does not have");
+
 pub use builder::SimBuilder;
+pub use equivalence::{first_divergence, tick_hashes};
+#[cfg(feature = "parallel")]
+pub use equivalence::check_thread_equivalence;
 pub use error::{SimError, SimResult};
+pub use failure::{FailureKind, FailurePolicy, SimFailure};
+pub use golden::{Divergence, GoldenOutcome, GoldenTick, GoldenTrace, StableHasher};
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub use golden::check_golden;
+pub use idle::IdlePolicy;
+pub use metrics::{SampleStats, TickMetrics};
 pub use observer::{NoopObserver, SimObserver};
+pub use phase::{PhaseContext, PhasePoint, TickPhase};
 pub use sim::Sim;
+#[cfg(feature = "tokio")]
+pub use tokio_util::sync::CancellationToken;
+pub use snapshot::{AgentSnapshot, SnapshotReader, StateSnapshot};
+pub use stats::{PhaseTimings, TickStats};
+pub use trace::TraceEvent;
+pub use trigger::{SnapshotTrigger, TriggerContext};
@@ -0,0 +1,112 @@
+//! Deterministic reduction of behavior-emitted metrics.
+//!
+//! Behaviors emit [`Intent::Count`][dt_behavior::Intent::Count] and
+//! [`Intent::Sample`][dt_behavior::Intent::Sample] alongside their other
+//! intents.  The apply phase folds them into [`TickMetrics`] in the same
+//! fixed agent order it applies everything else, so totals — including
+//! floating-point sums — are identical between serial and parallel runs.
+//!
+//! | Where                                                       | Holds                              |
+//! |-------------------------------------------------------------|------------------------------------|
+//! | [`Sim::metrics`][crate::Sim::metrics]                       | the most recent tick only          |
+//! | [`Sim::metric_totals`][crate::Sim::metric_totals]           | everything since the sim was built |
+//! | [`SimObserver::on_metrics`][crate::SimObserver::on_metrics] | each tick, after `on_tick_end`     |
+
+use std::collections::BTreeMap;
+
+// ── SampleStats ───────────────────────────────────────────────────────────────
+
+/// Running summary of a named sample.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleStats {
+    pub count: u64,
+    pub sum:   f64,
+    pub min:   f64,
+    pub max:   f64,
+}
+
+impl SampleStats {
+    fn new(value: f64) -> Self {
+        Self { count: 1, sum: value, min: value, max: value }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum   += value;
+        self.min    = self.min.min(value);
+        self.max    = self.max.max(value);
+    }
+
+    fn merge(&mut self, other: &SampleStats) {
+        self.count += other.count;
+        self.sum   += other.sum;
+        self.min    = self.min.min(other.min);
+        self.max    = self.max.max(other.max);
+    }
+
+    /// Arithmetic mean of the recorded values.
+    #[inline]
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+}
+
+// ── TickMetrics ───────────────────────────────────────────────────────────────
+
+/// Named counters and samples, keyed by the `&'static str` the behavior used.
+///
+/// `BTreeMap` keeps iteration order stable for output writers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TickMetrics {
+    pub counters: BTreeMap<&'static str, u64>,
+    pub samples:  BTreeMap<&'static str, SampleStats>,
+}
+
+impl TickMetrics {
+    /// Value of counter `name`, or 0 if it was never incremented.
+    pub fn count(&self, name: &str) -> u64 {
+        self.counters.get(name).copied().unwrap_or(0)
+    }
+
+    /// Summary of sample `name`, if any value was recorded.
+    pub fn sample(&self, name: &str) -> Option<&SampleStats> {
+        self.samples.get(name)
+    }
+
+    /// `true` if nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty() && self.samples.is_empty()
+    }
+
+    /// Add `delta` to counter `name`.
+    pub fn add_count(&mut self, name: &'static str, delta: u64) {
+        *self.counters.entry(name).or_insert(0) += delta;
+    }
+
+    /// Record one value of sample `name`.
+    pub fn add_sample(&mut self, name: &'static str, value: f64) {
+        self.samples
+            .entry(name)
+            .and_modify(|s| s.add(value))
+            .or_insert_with(|| SampleStats::new(value));
+    }
+
+    /// Fold `other` into `self` (counters add, samples combine).
+    pub fn merge(&mut self, other: &TickMetrics) {
+        for (&name, &delta) in &other.counters {
+            self.add_count(name, delta);
+        }
+        for (&name, stats) in &other.samples {
+            self.samples
+                .entry(name)
+                .and_modify(|s| s.merge(stats))
+                .or_insert(*stats);
+        }
+    }
+
+    /// Remove all counters and samples.
+    pub fn clear(&mut self) {
+        self.counters.clear();
+        self.samples.clear();
+    }
+}
@@ -1,8 +1,11 @@
 //! Simulation observer trait for progress reporting and data collection.
 
 use dt_agent::AgentStore;
-use dt_core::Tick;
-use dt_mobility::MobilityStore;
+use dt_core::{AgentId, NodeId, Tick};
+use dt_mobility::{MobilityStore, MovementState, Trip};
+use dt_spatial::Route;
+
+use crate::{PhaseTimings, TickMetrics, TickStats, TraceEvent};
 
 /// Callbacks invoked by [`Sim::run`][crate::Sim::run] at key points in the
 /// tick loop.
@@ -17,7 +20,7 @@ use dt_mobility::MobilityStore;
 ///
 /// impl SimObserver for ProgressPrinter {
 ///     fn on_tick_end(&mut self, tick: Tick, woken: usize) {
-///         if tick.0 % self.interval == 0 {
+///         if tick.is_multiple_of(self.interval) {
 ///             println!("tick {tick}: woke {woken} agents");
 ///         }
 ///     }
@@ -45,8 +48,74 @@ pub trait SimObserver {
         _agents:   &AgentStore,
     ) {}
 
+    /// Called for each journey completed this tick, in ascending `AgentId`
+    /// order, before any agent is woken.
+    fn on_trip(&mut self, _trip: &Trip) {}
+
+    /// Called for each journey started this tick, in the order the
+    /// `TravelTo` intents were applied, just before `on_tick_stats`.
+    ///
+    /// `state` is the agent's movement state (departure and arrival tick,
+    /// mode) and `route` the path it follows; the route is dropped on
+    /// arrival, so this is the only place it can be recorded.
+    fn on_departure(
+        &mut self,
+        _tick:  Tick,
+        _agent: AgentId,
+        _state: &MovementState,
+        _route: &Route,
+    ) {}
+
+    /// Called for each woken agent that shares its node with at least one
+    /// other stationary agent, after the intent phase.
+    ///
+    /// `agents_at_node` is the slice passed to `BehaviorModel::on_contacts`
+    /// (ascending `AgentId`, including `agent` itself).  A pair of agents
+    /// that both woke this tick is reported once from each side.
+    fn on_contacts(
+        &mut self,
+        _tick:           Tick,
+        _agent:          AgentId,
+        _node:           NodeId,
+        _agents_at_node: &[AgentId],
+    ) {}
+
+    /// Called every tick, before `on_phase_timings` and `on_tick_end`, with
+    /// the tick loop's built-in counts (see [`TickStats`]).
+    fn on_tick_stats(&mut self, _tick: Tick, _stats: &TickStats) {}
+
+    /// Called every tick, right after `on_tick_stats`, with the wall time
+    /// spent in each phase of the tick (see [`PhaseTimings`]).
+    fn on_phase_timings(&mut self, _tick: Tick, _timings: &PhaseTimings) {}
+
+    /// Called every tick, right after `on_tick_end`, with the metrics that
+    /// behaviors emitted this tick (often empty).
+    fn on_metrics(&mut self, _tick: Tick, _metrics: &TickMetrics) {}
+
+    /// Called for each event involving a traced agent (see
+    /// [`SimBuilder::trace_agents`][crate::SimBuilder::trace_agents]), in
+    /// order, just before `on_tick_end`.
+    fn on_trace(&mut self, _event: &TraceEvent) {}
+
+    /// Called when the sim jumps over idle ticks `from..to` without
+    /// processing them (see [`IdlePolicy`][crate::IdlePolicy]).  The next
+    /// tick processed (if any) is `to`.
+    fn on_ticks_skipped(&mut self, _from: Tick, _to: Tick) {}
+
     /// Called once after the final tick completes.
     fn on_sim_end(&mut self, _final_tick: Tick) {}
+
+    /// Report an error raised since the last poll, if any.
+    ///
+    /// Observer callbacks cannot return errors, so observers that can fail
+    /// (e.g. output writers) buffer the error and hand it over here.  The sim
+    /// polls after each tick's callbacks and after `on_sim_end`, then applies
+    /// its [`FailurePolicy`][crate::FailurePolicy].
+    ///
+    /// Default: never reports an error.
+    fn poll_error(&mut self) -> Option<String> {
+        None
+    }
 }
 
 /// A [`SimObserver`] that does nothing.  Use when you need to call `run` but
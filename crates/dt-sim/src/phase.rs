@@ -0,0 +1,95 @@
+//! Pluggable tick phases.
+//!
+//! A [`TickPhase`] is user code that runs inside the tick loop at one of the
+//! fixed [`PhasePoint`]s, with mutable access to simulation state through a
+//! [`PhaseContext`].  This is the extension point for models that need a
+//! whole-population step alongside the per-agent behavior callbacks:
+//!
+//! | Point           | Runs                                         | Typical use                    |
+//! |-----------------|----------------------------------------------|--------------------------------|
+//! | `BeforeIntents` | after arrivals and the wake-queue drain      | environment / exogenous events |
+//! | `AfterIntents`  | between the intent and apply phases          | traffic assignment, intent filtering |
+//! | `AfterApply`    | after all intents are applied                | disease-state update, counters |
+//!
+//! Phases run every tick — including ticks on which no agent wakes — in
+//! registration order, on the calling thread.
+//!
+//! ```rust,ignore
+//! struct Recovery;
+//! impl TickPhase for Recovery {
+//!     fn name(&self) -> &str { "recovery" }
+//!     fn run(&mut self, ctx: &mut PhaseContext<'_>) -> Result<(), String> {
+//!         let health = ctx.agents.component_mut::<Health>().ok_or("no Health")?;
+//!         for h in health.iter_mut() { h.tick(); }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let sim = SimBuilder::new(config, store, rngs, behavior, router)
+//!     .phase(PhasePoint::AfterApply, Recovery)
+//!     .build()?;
+//! ```
+
+use dt_agent::AgentStore;
+use dt_behavior::Intent;
+use dt_core::{AgentId, SimConfig, Tick};
+use dt_mobility::MobilityStore;
+use dt_schedule::{ActivityPlan, WakeQueue};
+use dt_spatial::RoadNetwork;
+
+// ── PhasePoint ────────────────────────────────────────────────────────────────
+
+/// Where in the tick loop a [`TickPhase`] runs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PhasePoint {
+    /// After arrivals are processed and the wake queue is drained, before any
+    /// behavior callback runs.
+    BeforeIntents,
+    /// After all woken agents have produced intents, before any is applied.
+    /// [`PhaseContext::intents`] holds the pending intents.
+    AfterIntents,
+    /// After every intent has been applied, before `on_tick_end`.
+    AfterApply,
+}
+
+// ── PhaseContext ──────────────────────────────────────────────────────────────
+
+/// Mutable view of the simulation state handed to a [`TickPhase`].
+pub struct PhaseContext<'a> {
+    /// The tick being processed.
+    pub tick:       Tick,
+    /// Which point of the tick loop is running.
+    pub point:      PhasePoint,
+    pub config:     &'a SimConfig,
+    pub agents:     &'a mut AgentStore,
+    pub plans:      &'a [ActivityPlan],
+    pub wake_queue: &'a mut WakeQueue,
+    pub mobility:   &'a mut MobilityStore,
+    pub network:    &'a mut RoadNetwork,
+    /// Agents woken this tick.
+    pub woken:      &'a [AgentId],
+    /// Intents about to be applied, one entry per woken agent whose
+    /// callbacks succeeded (in wake order), then one per co-traveler given
+    /// edge contacts (by `AgentId`).  Phases may edit, drop, or add entries.
+    ///
+    /// Only populated at [`PhasePoint::AfterIntents`]; empty elsewhere.
+    pub intents:    &'a mut Vec<(AgentId, Vec<Intent>)>,
+}
+
+// ── TickPhase ─────────────────────────────────────────────────────────────────
+
+/// A custom step in the tick loop.  Register with
+/// [`SimBuilder::phase`][crate::SimBuilder::phase].
+pub trait TickPhase: Send {
+    /// Label used in failure reports.
+    fn name(&self) -> &str {
+        "tick phase"
+    }
+
+    /// Run the phase for `ctx.tick`.
+    ///
+    /// An `Err` is handled by the sim's
+    /// [`FailurePolicy`][crate::FailurePolicy] as a
+    /// [`FailureKind::Phase`][crate::FailureKind::Phase] failure.
+    fn run(&mut self, ctx: &mut PhaseContext<'_>) -> Result<(), String>;
+}
@@ -1,6 +1,7 @@
 //! The `Sim` struct and its tick loop.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::hash::{BuildHasher, Hash};
 
 #[cfg(feature = "fx-hash")]
 use rustc_hash::FxHashMap;
@@ -15,14 +16,32 @@ type ContactIndex = FxHashMap<NodeId, Vec<AgentId>>;
 #[cfg(not(feature = "fx-hash"))]
 type ContactIndex = HashMap<NodeId, Vec<AgentId>>;
 
+/// HashMap type used for the per-tick co-traveler index (`EdgeId` keys).
+#[cfg(feature = "fx-hash")]
+type EdgeContactIndex = FxHashMap<EdgeId, Vec<AgentId>>;
+#[cfg(not(feature = "fx-hash"))]
+type EdgeContactIndex = HashMap<EdgeId, Vec<AgentId>>;
+
+/// HashMap type used for the per-tick fellow-rider index.
+#[cfg(feature = "fx-hash")]
+type VehicleContactIndex = FxHashMap<TransitVehicle, Vec<AgentId>>;
+#[cfg(not(feature = "fx-hash"))]
+type VehicleContactIndex = HashMap<TransitVehicle, Vec<AgentId>>;
+
 use dt_agent::{AgentRngs, AgentStore};
 use dt_behavior::{BehaviorModel, Intent, SimContext};
-use dt_core::{AgentId, NodeId, SimClock, SimConfig, Tick};
+use dt_core::wallclock::Instant;
+use dt_core::{AgentId, AgentRng, EdgeId, NodeId, SimClock, SimConfig, Tick, TransitVehicle};
 use dt_mobility::{MobilityEngine, MobilityStore};
 use dt_schedule::{ActivityPlan, WakeQueue};
 use dt_spatial::{RoadNetwork, Router};
 
-use crate::{SimObserver, SimResult};
+use crate::failure::{Panic, catch_panic};
+use crate::{
+    AgentSnapshot, FailureKind, FailurePolicy, IdlePolicy, PhaseContext, PhasePoint, PhaseTimings, SimError,
+    SimFailure, SimObserver, SimResult, SnapshotTrigger, TickMetrics, TickPhase, TickStats, TraceEvent,
+    TriggerContext,
+};
 
 // ── Per-agent inputs assembled before the intent phase ────────────────────────
 
@@ -34,6 +53,14 @@ struct AgentInputs {
     messages: Vec<(AgentId, Vec<u8>)>,
 }
 
+/// Outcome of one agent's intent computation: its intents, or the panic
+/// message if a behavior callback panicked.
+type AgentOutcome = (AgentId, Result<Vec<Intent>, Panic>);
+
+/// A co-traveler hook of [`BehaviorModel`]: `on_edge_contacts` or
+/// `on_vehicle_contacts`, with the edge or vehicle shared.
+type CoTravelerHook<B, K> = fn(&B, AgentId, K, &[AgentId], &SimContext<'_>, &mut AgentRng) -> Vec<Intent>;
+
 // ── Sim ───────────────────────────────────────────────────────────────────────
 
 /// The main simulation runner.
@@ -41,16 +68,33 @@ struct AgentInputs {
 /// `Sim<B, R>` holds all simulation state and drives the four-phase tick loop:
 ///
 /// 1. **Arrivals**: agents reaching their destination are marked stationary
-///    and re-inserted into the wake queue via their activity plan.
+///    and re-inserted into the wake queue via their activity plan.  With
+///    `config.mobility.path_following`, agents still in transit are then
+///    moved along their routes.
 /// 2. **Wake**: drain agents scheduled for this tick.
 /// 3. **Intent phase** (optionally parallel with the `parallel` feature):
 ///    - Call [`BehaviorModel::replan`] for each woken agent.
 ///    - Deliver any pending messages via [`BehaviorModel::on_message`].
 ///    - Report co-located agents via [`BehaviorModel::on_contacts`].
+///    - If `edge_contacts` is enabled, report co-travelers on the same edge
+///      via [`BehaviorModel::on_edge_contacts`] and on the same transit
+///      vehicle via [`BehaviorModel::on_vehicle_contacts`] (woken or not).
 /// 4. **Apply phase** (sequential, ascending `AgentId` for determinism):
 ///    - `WakeAt(t)`         → insert into wake queue.
 ///    - `TravelTo{..}`      → start journey; push arrival tick.
 ///    - `SendMessage{..}`   → store in message queue for recipient's next wake.
+///    - `Count` / `Sample`  → fold into [`Sim::metrics`].
+///
+/// With [`intra_tick_rounds`][Self::intra_tick_rounds] above 1, step 4 is
+/// followed by up to that many minus one reaction rounds: messages sent in
+/// the previous round are delivered immediately via `on_message` and the
+/// replies applied.
+///
+/// With [`max_woken_per_tick`][Self::max_woken_per_tick] set, step 2 takes at
+/// most that many agents and defers the rest to the next tick.
+///
+/// Custom [`TickPhase`]s run after step 2, between steps 3 and 4, and after
+/// step 4, according to their [`PhasePoint`].
 ///
 /// Create via [`SimBuilder`][crate::SimBuilder].
 pub struct Sim<B: BehaviorModel, R: Router> {
@@ -89,6 +133,71 @@ pub struct Sim<B: BehaviorModel, R: Router> {
     /// apply phase.  They are drained (and `on_message` called) the next
     /// time the recipient wakes.
     pub message_queue: HashMap<AgentId, Vec<(AgentId, Vec<u8>)>>,
+
+    /// How routing errors, behavior panics, and observer errors are handled.
+    pub failure_policy: FailurePolicy,
+
+    /// Failures recorded under [`FailurePolicy::CollectAndReport`], in the
+    /// order they occurred.  Always empty under the other policies.
+    pub failures: Vec<SimFailure>,
+
+    /// Custom phases and the point in the tick loop at which each runs.
+    pub phases: Vec<(PhasePoint, Box<dyn TickPhase>)>,
+
+    /// When `true`, in-transit agents sharing an edge are reported to
+    /// [`BehaviorModel::on_edge_contacts`] each tick.
+    pub edge_contacts: bool,
+
+    /// Metrics emitted via `Intent::Count` / `Intent::Sample` during the most
+    /// recently processed tick.
+    pub metrics: TickMetrics,
+
+    /// Metrics accumulated over every tick processed so far.
+    pub metric_totals: TickMetrics,
+
+    /// Built-in counts for the most recently processed tick.
+    pub stats: TickStats,
+
+    /// Wall time spent in each phase of the most recently processed tick.
+    pub timings: PhaseTimings,
+
+    /// Extra snapshot conditions, evaluated at the end of every tick in
+    /// addition to `config.output_interval_ticks`.
+    pub snapshot_triggers: Vec<SnapshotTrigger>,
+
+    /// Whether `run` may skip idle ticks.
+    pub idle_policy: IdlePolicy,
+
+    /// Total number of ticks skipped under `idle_policy` so far.
+    pub skipped_ticks: u64,
+
+    /// Agents whose activity is reported through
+    /// [`SimObserver::on_trace`].  Empty disables tracing.
+    pub traced: BTreeSet<AgentId>,
+
+    /// Trace events of the tick in progress, drained to the observer before
+    /// `on_tick_end`.
+    pub trace_buffer: Vec<TraceEvent>,
+
+    /// Agents that started a journey in the tick in progress, reported
+    /// through [`SimObserver::on_departure`] before `on_tick_end`.
+    pub departures: Vec<AgentId>,
+
+    /// Upper bound on the number of agents processed per tick.  `None`
+    /// processes every woken agent.
+    pub max_woken_per_tick: Option<usize>,
+
+    /// Agents woken but not yet processed because of `max_woken_per_tick`.
+    /// They are processed first on the next tick, in this order.
+    pub deferred: Vec<AgentId>,
+
+    /// Message-exchange rounds per tick (at least 1).  Each round after the
+    /// first delivers the messages sent in the previous round.
+    pub intra_tick_rounds: u32,
+
+    /// Recipients of messages sent during the current round.  Only tracked
+    /// when `intra_tick_rounds > 1`.
+    pub(crate) round_recipients: Vec<AgentId>,
 }
 
 impl<B: BehaviorModel, R: Router> Sim<B, R> {
@@ -99,25 +208,63 @@ impl<B: BehaviorModel, R: Router> Sim<B, R> {
     /// Calls observer hooks at every tick boundary.  Use
     /// [`NoopObserver`][crate::NoopObserver] if you don't need callbacks.
     pub fn run<O: SimObserver>(&mut self, observer: &mut O) -> SimResult<()> {
-        loop {
-            let now = self.clock.current_tick;
-            if now >= self.config.end_tick() {
-                break;
-            }
+        while self.advance(observer)? {}
+        self.finish(observer)
+    }
 
-            observer.on_tick_start(now);
-            let woken = self.process_tick(now)?;
-            observer.on_tick_end(now, woken);
-            if self.config.output_interval_ticks > 0
-                && now.0.is_multiple_of(self.config.output_interval_ticks)
-            {
-                observer.on_snapshot(now, &self.mobility.store, &self.agents);
-            }
+    /// Process the next tick as [`run`][Self::run] does — or skip the idle
+    /// ticks ahead under `idle_policy` — unless the clock has reached
+    /// `config.end_tick()`.  Returns `false` once it has.
+    ///
+    /// For drivers that interleave their own work with the run; call
+    /// [`finish`][Self::finish] when done.
+    pub fn advance<O: SimObserver>(&mut self, observer: &mut O) -> SimResult<bool> {
+        if self.clock.current_tick >= self.config.end_tick() {
+            return Ok(false);
+        }
+        if !self.skip_idle(observer) {
+            self.step(observer)?;
+        }
+        Ok(true)
+    }
 
-            self.clock.advance();
+    /// End the run at the current tick: fire `on_sim_end` and route any
+    /// error the observer buffered through the failure policy.
+    pub fn finish<O: SimObserver>(&mut self, observer: &mut O) -> SimResult<()> {
+        let final_tick = self.clock.current_tick;
+        observer.on_sim_end(final_tick);
+        self.poll_observer(observer, final_tick)
+    }
+
+    /// Async variant of [`run`][Self::run] for embedding in a Tokio service.
+    ///
+    /// Yields to the runtime after every tick so a long run never monopolises
+    /// a worker thread, and checks `cancel` before each tick.  On
+    /// cancellation `on_sim_end` still fires (so output writers flush) and
+    /// `SimError::Cancelled` is returned; the clock is left at the first
+    /// unprocessed tick, so calling `run`/`run_async` again resumes the run.
+    ///
+    /// Each tick itself runs synchronously.  If single ticks are long enough
+    /// to matter, drive the sim from `spawn_blocking` instead.
+    #[cfg(feature = "tokio")]
+    pub async fn run_async<O: SimObserver>(
+        &mut self,
+        observer: &mut O,
+        cancel:   &tokio_util::sync::CancellationToken,
+    ) -> SimResult<()> {
+        while self.clock.current_tick < self.config.end_tick() {
+            if cancel.is_cancelled() {
+                let tick = self.clock.current_tick;
+                self.finish(observer)?;
+                return Err(SimError::Cancelled { tick });
+            }
+            if self.skip_idle(observer) {
+                continue;
+            }
+            self.step(observer)?;
+            tokio::task::yield_now().await;
         }
-        observer.on_sim_end(self.clock.current_tick);
-        Ok(())
+        self.finish(observer)
     }
 
     /// Run exactly `n` ticks from the current position (ignores `end_tick`).
@@ -125,95 +272,505 @@ impl<B: BehaviorModel, R: Router> Sim<B, R> {
     /// Useful for tests and incremental stepping.
     pub fn run_ticks<O: SimObserver>(&mut self, n: u64, observer: &mut O) -> SimResult<()> {
         for _ in 0..n {
-            let now = self.clock.current_tick;
-            observer.on_tick_start(now);
-            let woken = self.process_tick(now)?;
-            observer.on_tick_end(now, woken);
-            if self.config.output_interval_ticks > 0
-                && now.0.is_multiple_of(self.config.output_interval_ticks)
-            {
-                observer.on_snapshot(now, &self.mobility.store, &self.agents);
+            self.step(observer)?;
+        }
+        Ok(())
+    }
+
+    // ── Core tick processing ──────────────────────────────────────────────
+
+    /// Process the current tick, fire observer hooks, and advance the clock.
+    fn step<O: SimObserver>(&mut self, observer: &mut O) -> SimResult<()> {
+        let now = self.clock.current_tick;
+        observer.on_tick_start(now);
+        self.metrics.clear();
+        self.stats = TickStats::default();
+        self.timings = PhaseTimings::default();
+        let woken = self.process_tick(now, observer)?;
+        self.metric_totals.merge(&self.metrics);
+        self.stats.woken = woken as u64;
+        // Only routed journeys put agents in transit (see `skip_idle`).
+        self.stats.in_transit = self.mobility.store.routes.len() as u64;
+        for event in self.trace_buffer.drain(..) {
+            observer.on_trace(&event);
+        }
+        let store = &self.mobility.store;
+        for agent in self.departures.drain(..) {
+            if let Some(route) = store.routes.get(&agent) {
+                observer.on_departure(now, agent, &store.states[agent.index()], route);
+            }
+        }
+        observer.on_tick_stats(now, &self.stats);
+        observer.on_phase_timings(now, &self.timings);
+        observer.on_tick_end(now, woken);
+        observer.on_metrics(now, &self.metrics);
+        if self.snapshot_due(now, woken) {
+            observer.on_snapshot(now, &self.mobility.store, &self.agents);
+        }
+        self.poll_observer(observer, now)?;
+        self.clock.advance();
+        Ok(())
+    }
+
+    /// Jump the clock over idle ticks if `idle_policy` allows it.
+    ///
+    /// Returns `true` if ticks were skipped.
+    fn skip_idle<O: SimObserver>(&mut self, observer: &mut O) -> bool {
+        // Only routed journeys put agents in transit, so an empty route
+        // cache means nobody is travelling.
+        if self.idle_policy == IdlePolicy::RunAll
+            || !self.phases.is_empty()
+            || !self.deferred.is_empty()
+            || !self.mobility.store.routes.is_empty()
+        {
+            return false;
+        }
+        let now = self.clock.current_tick;
+        let end = self.config.end_tick();
+        let target = match (self.idle_policy, self.wake_queue.next_tick()) {
+            (_, None)                           => end,
+            (IdlePolicy::FastForward, Some(t))  => t.min(end),
+            (_, Some(_))                        => return false,
+        };
+        if target <= now {
+            return false;
+        }
+        observer.on_ticks_skipped(now, target);
+        self.skipped_ticks += target - now;
+        self.clock.current_tick = target;
+        true
+    }
+
+    /// `true` if tick `now` is on the output interval or any trigger fires.
+    ///
+    /// All triggers are evaluated, even after one has fired.
+    fn snapshot_due(&mut self, now: Tick, woken: usize) -> bool {
+        let mut due = self.config.output_interval_ticks > 0
+            && now.is_multiple_of(self.config.output_interval_ticks);
+        if !self.snapshot_triggers.is_empty() {
+            let ctx = TriggerContext {
+                tick:     now,
+                woken,
+                agents:   &self.agents,
+                mobility: &self.mobility.store,
+                metrics:  &self.metrics,
+            };
+            for trigger in self.snapshot_triggers.iter_mut() {
+                due |= trigger(&ctx);
+            }
+        }
+        due
+    }
+
+    /// Route any error buffered by `observer` through the failure policy.
+    fn poll_observer<O: SimObserver>(&mut self, observer: &mut O, now: Tick) -> SimResult<()> {
+        match observer.poll_error() {
+            Some(message) => self.handle_failure(SimFailure {
+                tick:  now,
+                agent: None,
+                kind:  FailureKind::Observer,
+                message,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Apply `failure_policy` to a single failure.
+    ///
+    /// Under `FailFast` the failure is converted into a `SimError`; otherwise
+    /// it is dropped, logged, or collected and `Ok(())` is returned.
+    fn handle_failure(&mut self, failure: SimFailure) -> SimResult<()> {
+        match failure.kind {
+            FailureKind::Routing       => self.stats.routing_failures += 1,
+            #[cfg(panic = "unwind")]
+            FailureKind::BehaviorPanic => self.stats.behavior_panics += 1,
+            FailureKind::Observer | FailureKind::Phase => {}
+        }
+        if let Some(agent) = failure.agent
+            && self.is_traced(agent)
+        {
+            self.trace_buffer.push(TraceEvent::Failed {
+                tick:    failure.tick,
+                agent,
+                message: failure.message.clone(),
+            });
+        }
+        match self.failure_policy {
+            FailurePolicy::FailFast => Err(match failure.kind {
+                FailureKind::Observer => SimError::Observer {
+                    tick:    failure.tick,
+                    message: failure.message,
+                },
+                FailureKind::Phase => SimError::Phase {
+                    tick:    failure.tick,
+                    message: failure.message,
+                },
+                FailureKind::Routing => SimError::Routing {
+                    agent:   failure.agent.unwrap_or(AgentId::INVALID),
+                    tick:    failure.tick,
+                    message: failure.message,
+                },
+                #[cfg(panic = "unwind")]
+                FailureKind::BehaviorPanic => SimError::BehaviorPanic {
+                    agent:   failure.agent.unwrap_or(AgentId::INVALID),
+                    tick:    failure.tick,
+                    message: failure.message,
+                },
+            }),
+            FailurePolicy::Continue => Ok(()),
+            FailurePolicy::LogAndContinue => {
+                eprintln!("[dt-sim] {failure}");
+                Ok(())
+            }
+            FailurePolicy::CollectAndReport => {
+                self.failures.push(failure);
+                Ok(())
+            }
+        }
+    }
+
+    /// Apply `failure_policy` to a behavior panic caught for `agent`.
+    #[cfg(panic = "unwind")]
+    fn handle_panic(&mut self, tick: Tick, agent: AgentId, message: Panic) -> SimResult<()> {
+        self.handle_failure(SimFailure { tick, agent: Some(agent), kind: FailureKind::BehaviorPanic, message })
+    }
+
+    /// Without unwinding no panic is ever caught.
+    #[cfg(not(panic = "unwind"))]
+    fn handle_panic(&mut self, _tick: Tick, _agent: AgentId, panic: Panic) -> SimResult<()> {
+        match panic {}
+    }
+
+    /// Re-schedule `agent` at its plan's next wake tick.
+    ///
+    /// Used whenever an agent's tick ends without a journey being started
+    /// (routing failure, discarded intents after a panic) so the agent wakes
+    /// at its next activity rather than silently vanishing.
+    fn reschedule_from_plan(&mut self, agent: AgentId, now: Tick) {
+        if let Some(next_wake) = self.plans[agent.index()].next_wake_tick(now) {
+            self.wake_queue.push(next_wake, agent);
+        }
+    }
+
+    /// Restart the journeys of agents in transit in a warm-start snapshot
+    /// taken at `snapshot_tick`, keeping their recorded departure and
+    /// arrival ticks.
+    ///
+    /// A journey that can't be routed is a routing failure at the resume
+    /// tick: the agent stays at its departure node and is re-scheduled from
+    /// its plan, unless the policy is `FailFast`.
+    pub(crate) fn resume_journeys(&mut self, snapshot_tick: Tick, journeys: &[AgentSnapshot]) -> SimResult<()> {
+        let now = self.clock.current_tick;
+        for a in journeys {
+            let departure = a.departure_tick.unwrap_or(now);
+            let tick_secs = self.config.tick_duration_secs;
+            match self.mobility.begin_travel(a.agent, a.destination_node, a.mode, departure, tick_secs, &self.network) {
+                Ok(_) => {
+                    // A re-computed route may take a different time; the
+                    // journey ends when the snapshot says it does.
+                    if let Some(arrival) = a.arrival_tick {
+                        self.mobility.store.states[a.agent.index()].arrival_tick = arrival.max(now);
+                    }
+                }
+                Err(e) => {
+                    self.handle_failure(SimFailure {
+                        tick:    now,
+                        agent:   Some(a.agent),
+                        kind:    FailureKind::Routing,
+                        message: format!("cannot resume journey: {e}"),
+                    })?;
+                    self.reschedule_from_plan(a.agent, snapshot_tick);
+                }
             }
-            self.clock.advance();
         }
         Ok(())
     }
 
+    /// `true` if `agent` is in the traced set.
+    #[inline]
+    fn is_traced(&self, agent: AgentId) -> bool {
+        !self.traced.is_empty() && self.traced.contains(&agent)
+    }
+
     // ── Core tick processing ──────────────────────────────────────────────
 
-    fn process_tick(&mut self, now: Tick) -> SimResult<usize> {
+    fn process_tick<O: SimObserver>(&mut self, now: Tick, observer: &mut O) -> SimResult<usize> {
         // ── Phase 0: process mobility arrivals ────────────────────────────
         //
         // Agents that arrive this tick are marked stationary and re-inserted
         // into the wake queue so they can re-plan from their new position.
-        let arrived: Vec<(AgentId, _)> = self.mobility.tick_arrivals(now);
-        for (agent, _dest) in arrived {
+        let started = Instant::now();
+        let trips = self.mobility.tick_trips(now, &self.network);
+        self.stats.arrivals = trips.len() as u64;
+        for trip in trips {
+            let agent = trip.agent;
+            if self.is_traced(agent) {
+                self.trace_buffer.push(TraceEvent::Arrived { tick: now, agent, node: trip.to });
+            }
+            observer.on_trip(&trip);
             if let Some(wake) = self.plans[agent.index()].next_wake_tick(now) {
                 self.wake_queue.push(wake, agent);
             }
         }
+        self.mobility.store.advance(now);
+
+        self.timings.arrivals = started.elapsed();
 
         // ── Phase 1: drain the wake queue ─────────────────────────────────
-        let woken = match self.wake_queue.drain_tick(now) {
-            None    => return Ok(0),
-            Some(w) => w,
-        };
+        let lap = Instant::now();
+        let mut woken = self.wake_queue.drain_tick(now).unwrap_or_default();
+        if let Some(cap) = self.max_woken_per_tick {
+            woken = self.limit_woken(woken, cap);
+        }
         let woken_count = woken.len();
+        if !self.traced.is_empty() {
+            for &agent in woken.iter().filter(|a| self.traced.contains(a)) {
+                self.trace_buffer.push(TraceEvent::Woke { tick: now, agent });
+            }
+        }
+        self.timings.wake = lap.elapsed();
 
-        // ── Phase 2: build spatial contact index ──────────────────────────
-        //
-        // O(N) scan of all agent positions → NodeId → Vec<AgentId>.
-        // Only stationary, placed agents are included.  Built once per tick
-        // and reused for all woken agents' contact lookups.
-        let contact_index = build_contact_index(&self.mobility.store);
+        self.run_phases(PhasePoint::BeforeIntents, now, &woken, &mut Vec::new())?;
 
-        // ── Phase 3: pre-collect per-agent inputs (sequential) ────────────
-        //
-        // Drain each woken agent's pending messages BEFORE the intent phase
-        // so the intent phase (which may run in parallel) only reads
-        // immutable data.
-        //
-        // Contact lists are NOT collected here.  They are looked up lazily
-        // inside `compute_intents` — the contact index is a shared read-only
-        // `HashMap` and each agent's slice is borrowed directly from it with
-        // zero allocation.
+        let mut intents: Vec<(AgentId, Vec<Intent>)> = Vec::with_capacity(woken_count);
+        if !woken.is_empty() {
+            // ── Phase 2: build spatial contact index ──────────────────────
+            //
+            // O(N) scan of all agent positions → NodeId → Vec<AgentId>.
+            // Only stationary, placed agents are included.  Built once per
+            // tick and reused for all woken agents' contact lookups.
+            let lap = Instant::now();
+            let contact_index = build_contact_index(&self.mobility.store);
+            self.timings.contact_index = lap.elapsed();
+
+            // ── Phase 3: pre-collect per-agent inputs (sequential) ────────
+            //
+            // Drain each woken agent's pending messages BEFORE the intent
+            // phase so the intent phase (which may run in parallel) only
+            // reads immutable data.
+            //
+            // Contact lists are NOT collected here.  They are looked up
+            // lazily inside `compute_intents` — the contact index is a shared
+            // read-only `HashMap` and each agent's slice is borrowed directly
+            // from it with zero allocation.
+            //
+            // Messages sent *this tick* (during the apply phase below) will
+            // be delivered at the recipient's *next* wake — not this one.
+            let lap = Instant::now();
+            let inputs: Vec<AgentInputs> = woken.iter().map(|&agent| self.take_inputs(agent, now)).collect();
+
+            // ── Phase 4: intent phase (produce) ───────────────────────────
+            //
+            // Agents whose callbacks panicked are handled here and dropped;
+            // their intents (if any) are never applied.
+            for (agent, outcome) in self.compute_intents(&woken, inputs, &contact_index) {
+                match outcome {
+                    Ok(agent_intents) => intents.push((agent, agent_intents)),
+                    Err(panic) => {
+                        self.handle_panic(now, agent, panic)?;
+                        self.reschedule_from_plan(agent, now);
+                    }
+                }
+            }
+
+            // Hand the same contact lists to the observer before the index
+            // is dropped.
+            for &agent in &woken {
+                let state = &self.mobility.store.states[agent.index()];
+                if state.in_transit {
+                    continue;
+                }
+                if let Some(agents_at_node) = contact_index.get(&state.departure_node)
+                    && agents_at_node.len() > 1
+                {
+                    self.stats.contacts += agents_at_node.len() as u64 - 1;
+                    observer.on_contacts(now, agent, state.departure_node, agents_at_node);
+                }
+            }
+            self.timings.intents = lap.elapsed();
+        }
+
+        // ── Phase 4b: co-traveler contacts ────────────────────────────────
         //
-        // Messages sent *this tick* (during the apply phase below) will be
-        // delivered at the recipient's *next* wake — not this one.
-        let inputs: Vec<AgentInputs> = woken
-            .iter()
-            .map(|&agent| {
-                let messages = self.message_queue.remove(&agent).unwrap_or_default();
-                AgentInputs { messages }
-            })
-            .collect();
+        // In-transit agents are normally asleep until arrival, so they are
+        // visited here independently of the wake queue.  A panic is handled
+        // like any other, but the agent is not re-scheduled: it still wakes
+        // on arrival.  Their intents follow the woken agents': edge contacts
+        // then vehicle contacts, each in ascending `AgentId` order.
+        if self.edge_contacts {
+            let lap = Instant::now();
+            for (agent, outcome) in self.compute_edge_contacts(now) {
+                match outcome {
+                    Ok(agent_intents) => intents.push((agent, agent_intents)),
+                    Err(panic) => self.handle_panic(now, agent, panic)?,
+                }
+            }
+            self.timings.intents += lap.elapsed();
+        }
 
-        // ── Phase 4: intent phase (produce) ───────────────────────────────
-        let intents = self.compute_intents(&woken, inputs, contact_index);
+        self.run_phases(PhasePoint::AfterIntents, now, &woken, &mut intents)?;
 
         // ── Phase 5: apply phase (consume) ────────────────────────────────
         //
-        // Intents arrive in ascending AgentId order (BTreeMap drain).
-        // Sequential application in this order makes results deterministic
+        // Intents are applied sequentially — woken agents' in wake order,
+        // then co-travelers' by `AgentId` — which makes results deterministic
         // even when the intent phase ran in parallel.
+        let lap = Instant::now();
         for (agent, agent_intents) in intents {
             self.apply_intents(agent, agent_intents, now)?;
         }
+        self.timings.apply = lap.elapsed();
 
+        // ── Phase 6: reaction rounds ──────────────────────────────────────
+        //
+        // Each extra round hands the messages sent in the previous round to
+        // their recipients straight away, so negotiations can go back and
+        // forth within one tick.  Stops early once a round sends nothing.
+        let lap = Instant::now();
+        for _ in 1..self.intra_tick_rounds {
+            if !self.run_reaction_round(now)? {
+                break;
+            }
+        }
+        self.round_recipients.clear();
+        self.timings.reactions = lap.elapsed();
+
+        self.run_phases(PhasePoint::AfterApply, now, &woken, &mut Vec::new())?;
+
+        self.timings.total = started.elapsed();
         Ok(woken_count)
     }
 
+    /// Drain `agent`'s pending messages for delivery this tick.
+    fn take_inputs(&mut self, agent: AgentId, now: Tick) -> AgentInputs {
+        let messages = self.message_queue.remove(&agent).unwrap_or_default();
+        self.stats.messages_delivered += messages.len() as u64;
+        if self.is_traced(agent) {
+            for (from, payload) in &messages {
+                self.trace_buffer.push(TraceEvent::MessageDelivered {
+                    tick:  now,
+                    agent,
+                    from:  *from,
+                    bytes: payload.len(),
+                });
+            }
+        }
+        AgentInputs { messages }
+    }
+
+    /// Deliver the messages sent during the previous round and apply the
+    /// recipients' replies.
+    ///
+    /// Only `on_message` is called.  In-transit recipients are skipped; their
+    /// messages stay queued until they next wake.  Returns `false` if there
+    /// was nothing to deliver.
+    fn run_reaction_round(&mut self, now: Tick) -> SimResult<bool> {
+        let mut recipients = std::mem::take(&mut self.round_recipients);
+        recipients.sort_unstable();
+        recipients.dedup();
+        recipients.retain(|&agent| !self.mobility.store.in_transit(agent));
+        if recipients.is_empty() {
+            return Ok(false);
+        }
+
+        let inputs: Vec<AgentInputs> = recipients.iter().map(|&agent| self.take_inputs(agent, now)).collect();
+        let mut intents = Vec::with_capacity(recipients.len());
+        for (agent, outcome) in self.compute_reactions(&recipients, inputs) {
+            match outcome {
+                Ok(agent_intents) => intents.push((agent, agent_intents)),
+                Err(panic) => self.handle_panic(now, agent, panic)?,
+            }
+        }
+        for (agent, agent_intents) in intents {
+            self.apply_intents(agent, agent_intents, now)?;
+        }
+        Ok(true)
+    }
+
+    /// Apply `max_woken_per_tick` to this tick's wakes.
+    ///
+    /// Agents deferred from earlier ticks go first, followed by this tick's
+    /// wakes in ascending `AgentId` order with duplicates removed.  Everything
+    /// past `cap` is deferred to the next tick.
+    fn limit_woken(&mut self, mut drained: Vec<AgentId>, cap: usize) -> Vec<AgentId> {
+        drained.sort_unstable();
+        drained.dedup();
+        if self.deferred.is_empty() && drained.len() <= cap {
+            return drained;
+        }
+
+        let mut woken = std::mem::take(&mut self.deferred);
+        if !woken.is_empty() {
+            // An agent already waiting must not be processed twice.
+            let mut waiting = woken.clone();
+            waiting.sort_unstable();
+            drained.retain(|agent| waiting.binary_search(agent).is_err());
+        }
+        woken.extend(drained);
+        if woken.len() > cap {
+            self.deferred = woken.split_off(cap);
+        }
+        woken
+    }
+
+    /// Run every phase registered at `point`, in registration order.
+    ///
+    /// Errors are collected first and passed to the failure policy once all
+    /// phases at this point have run.
+    fn run_phases(
+        &mut self,
+        point:   PhasePoint,
+        now:     Tick,
+        woken:   &[AgentId],
+        intents: &mut Vec<(AgentId, Vec<Intent>)>,
+    ) -> SimResult<()> {
+        if self.phases.is_empty() {
+            return Ok(());
+        }
+        let started = Instant::now();
+        let mut errors = Vec::new();
+        for (at, phase) in self.phases.iter_mut() {
+            if *at != point {
+                continue;
+            }
+            let mut ctx = PhaseContext {
+                tick:       now,
+                point,
+                config:     &self.config,
+                agents:     &mut self.agents,
+                plans:      &self.plans,
+                wake_queue: &mut self.wake_queue,
+                mobility:   &mut self.mobility.store,
+                network:    &mut self.network,
+                woken,
+                intents:    &mut *intents,
+            };
+            if let Err(e) = phase.run(&mut ctx) {
+                errors.push(format!("{}: {e}", phase.name()));
+            }
+        }
+        self.timings.custom_phases += started.elapsed();
+        for message in errors {
+            self.handle_failure(SimFailure { tick: now, agent: None, kind: FailureKind::Phase, message })?;
+        }
+        Ok(())
+    }
+
     /// Compute intents for all woken agents.
     ///
     /// Calls `replan`, `on_message`, and `on_contacts` for each agent.
     /// With the `parallel` Cargo feature, all three calls run on Rayon's
     /// thread pool.
+    ///
+    /// Where panics unwind, each agent's callbacks run under `catch_unwind`;
+    /// a panic yields `Err(message)` for that agent only and leaves the
+    /// others untouched.
     fn compute_intents(
         &mut self,
         woken:         &[AgentId],
         inputs:        Vec<AgentInputs>,
-        contact_index: ContactIndex,
-    ) -> Vec<(AgentId, Vec<Intent>)> {
+        contact_index: &ContactIndex,
+    ) -> Vec<AgentOutcome> {
         // Explicit field borrows so the borrow checker sees disjoint access.
         let agents   = &self.agents;
         let plans    = self.plans.as_slice();
@@ -231,27 +788,10 @@ impl<B: BehaviorModel, R: Router> Sim<B, R> {
                 .zip(inputs)
                 .map(|(&agent, input)| {
                     let rng = rngs.get_mut(agent);
-                    let mut intents = behavior.replan(agent, &ctx, rng);
-
-                    for (from, payload) in input.messages {
-                        intents.extend(behavior.on_message(agent, from, &payload, &ctx, rng));
-                    }
-
-                    // Pass the raw agents-at-node slice directly — zero allocation.
-                    // The slice includes `agent` itself; behavior filters self if needed.
-                    let state = &mobility.states[agent.index()];
-                    if !state.in_transit && state.departure_node != NodeId::INVALID {
-                        let node = state.departure_node;
-                        if let Some(agents_at_node) = contact_index.get(&node) {
-                            if agents_at_node.len() > 1 {
-                                intents.extend(behavior.on_contacts(
-                                    agent, node, agents_at_node, &ctx, rng,
-                                ));
-                            }
-                        }
-                    }
-
-                    (agent, intents)
+                    let outcome = agent_intents(
+                        behavior, agent, input, &ctx, rng, mobility, contact_index,
+                    );
+                    (agent, outcome)
                 })
                 .collect()
         }
@@ -269,27 +809,118 @@ impl<B: BehaviorModel, R: Router> Sim<B, R> {
                 .zip(rng_refs.into_par_iter())
                 .zip(inputs.into_par_iter())
                 .map(|((&agent, rng), input)| {
-                    let mut intents = behavior.replan(agent, &ctx, rng);
+                    let outcome = agent_intents(
+                        behavior, agent, input, &ctx, rng, mobility, contact_index,
+                    );
+                    (agent, outcome)
+                })
+                .collect()
+        }
+    }
 
-                    for (from, payload) in input.messages {
-                        intents.extend(behavior.on_message(agent, from, &payload, &ctx, rng));
-                    }
+    /// Call `on_message` for each recipient of a reaction round.
+    ///
+    /// `recipients` must be unique; with the `parallel` feature the callbacks
+    /// run on Rayon's thread pool.
+    fn compute_reactions(&mut self, recipients: &[AgentId], inputs: Vec<AgentInputs>) -> Vec<AgentOutcome> {
+        let agents   = &self.agents;
+        let plans    = self.plans.as_slice();
+        let behavior = &self.behavior;
+        let rngs     = &mut self.rngs;
+        let ctx = SimContext::new(self.clock.current_tick, self.config.tick_duration_secs, agents, plans);
 
-                    // Pass the raw agents-at-node slice directly — zero allocation.
-                    // The slice includes `agent` itself; behavior filters self if needed.
-                    let state = &mobility.states[agent.index()];
-                    if !state.in_transit && state.departure_node != NodeId::INVALID {
-                        let node = state.departure_node;
-                        if let Some(agents_at_node) = contact_index.get(&node) {
-                            if agents_at_node.len() > 1 {
-                                intents.extend(behavior.on_contacts(
-                                    agent, node, agents_at_node, &ctx, rng,
-                                ));
-                            }
-                        }
-                    }
+        #[cfg(not(feature = "parallel"))]
+        {
+            recipients
+                .iter()
+                .zip(inputs)
+                .map(|(&agent, input)| {
+                    let rng = rngs.get_mut(agent);
+                    (agent, reaction_intents(behavior, agent, input, &ctx, rng))
+                })
+                .collect()
+        }
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            let rng_refs = rngs.get_many_mut(recipients);
 
-                    (agent, intents)
+            recipients
+                .par_iter()
+                .zip(rng_refs.into_par_iter())
+                .zip(inputs.into_par_iter())
+                .map(|((&agent, rng), input)| (agent, reaction_intents(behavior, agent, input, &ctx, rng)))
+                .collect()
+        }
+    }
+
+    /// Call `on_edge_contacts` for every in-transit agent that shares its
+    /// current edge with at least one other traveler, then
+    /// `on_vehicle_contacts` for every transit rider that shares its vehicle.
+    ///
+    /// Each hook visits agents in ascending `AgentId` order; with the
+    /// `parallel` feature the callbacks run on Rayon's thread pool.
+    fn compute_edge_contacts(&mut self, now: Tick) -> Vec<AgentOutcome> {
+        let (on_edges, on_vehicles) = build_edge_contact_index(&self.mobility.store, &self.network, now);
+        let mut outcomes = self.co_traveler_intents(now, &on_edges, B::on_edge_contacts);
+        outcomes.extend(self.co_traveler_intents(now, &on_vehicles, B::on_vehicle_contacts));
+        outcomes
+    }
+
+    /// Call `hook` for every agent in an entry of `index` shared with at
+    /// least one other agent, in ascending `AgentId` order.
+    fn co_traveler_intents<K, S>(
+        &mut self,
+        now:   Tick,
+        index: &HashMap<K, Vec<AgentId>, S>,
+        hook:  CoTravelerHook<B, K>,
+    ) -> Vec<AgentOutcome>
+    where
+        K: Copy + Eq + Hash + Send + Sync,
+        S: BuildHasher + Sync,
+    {
+        let mut travelers: Vec<(AgentId, K)> = index
+            .iter()
+            .filter(|(_, sharing)| sharing.len() > 1)
+            .flat_map(|(&key, sharing)| sharing.iter().map(move |&agent| (agent, key)))
+            .collect();
+        if travelers.is_empty() {
+            return Vec::new();
+        }
+        travelers.sort_unstable_by_key(|&(agent, _)| agent);
+
+        let agents   = &self.agents;
+        let plans    = self.plans.as_slice();
+        let behavior = &self.behavior;
+        let rngs     = &mut self.rngs;
+        let ctx = SimContext::new(now, self.config.tick_duration_secs, agents, plans);
+
+        #[cfg(not(feature = "parallel"))]
+        {
+            travelers
+                .iter()
+                .map(|&(agent, key)| {
+                    let rng = rngs.get_mut(agent);
+                    (agent, catch_panic(|| hook(behavior, agent, key, &index[&key], &ctx, rng)))
+                })
+                .collect()
+        }
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            // Each agent is in one entry of the index, so `travelers` has unique IDs.
+            let ids: Vec<AgentId> = travelers.iter().map(|&(agent, _)| agent).collect();
+            let rng_refs = rngs.get_many_mut(&ids);
+
+            travelers
+                .par_iter()
+                .zip(rng_refs.into_par_iter())
+                .map(|(&(agent, key), rng)| {
+                    (agent, catch_panic(|| hook(behavior, agent, key, &index[&key], &ctx, rng)))
                 })
                 .collect()
         }
@@ -302,12 +933,20 @@ impl<B: BehaviorModel, R: Router> Sim<B, R> {
         intents: Vec<Intent>,
         now:     Tick,
     ) -> SimResult<()> {
+        let traced = self.is_traced(agent);
+        if traced {
+            self.trace_buffer.push(TraceEvent::Intents { tick: now, agent, intents: intents.clone() });
+        }
         for intent in intents {
             match intent {
                 // ── WakeAt: re-insert agent into wake queue ────────────────
                 Intent::WakeAt(tick) => {
+                    self.stats.wake_intents += 1;
                     if tick > now {
                         self.wake_queue.push(tick, agent);
+                        if traced {
+                            self.trace_buffer.push(TraceEvent::WakeScheduled { tick: now, agent, at: tick });
+                        }
                     }
                     // Silently ignore WakeAt(tick <= now) to prevent infinite
                     // loops from badly-written behavior models.
@@ -315,6 +954,7 @@ impl<B: BehaviorModel, R: Router> Sim<B, R> {
 
                 // ── TravelTo: start a journey via the mobility engine ──────
                 Intent::TravelTo { destination, mode } => {
+                    self.stats.travel_intents += 1;
                     match self.mobility.begin_travel(
                         agent,
                         destination,
@@ -323,7 +963,9 @@ impl<B: BehaviorModel, R: Router> Sim<B, R> {
                         self.config.tick_duration_secs,
                         &self.network,
                     ) {
-                        Ok(_arrival_tick) => {
+                        Ok(arrival) => {
+                            self.stats.departures += 1;
+                            self.departures.push(agent);
                             // Do NOT push arrival_tick to the wake queue.
                             //
                             // `tick_arrivals()` runs at the start of every
@@ -333,20 +975,28 @@ impl<B: BehaviorModel, R: Router> Sim<B, R> {
                             // causing a spurious re-plan that emits another
                             // TravelTo(same_node), which cascades: each cycle
                             // doubles the duplicate queue entries.
+                            if traced {
+                                self.trace_buffer.push(TraceEvent::Departed {
+                                    tick: now,
+                                    agent,
+                                    destination,
+                                    mode,
+                                    arrival,
+                                });
+                            }
                         }
-                        Err(_e) => {
+                        Err(e) => {
                             // Routing failure: agent stays put (never enters
                             // transit), so `tick_arrivals` will never fire.
                             // Re-schedule via the plan so the agent wakes at
                             // its next activity rather than silently vanishing.
-                            // This handles the common TravelTo(current_node)
-                            // at a cycle boundary (e.g. "go home" when the
-                            // router has no same-node route cached).
-                            if let Some(next_wake) =
-                                self.plans[agent.index()].next_wake_tick(now)
-                            {
-                                self.wake_queue.push(next_wake, agent);
-                            }
+                            self.handle_failure(SimFailure {
+                                tick:    now,
+                                agent:   Some(agent),
+                                kind:    FailureKind::Routing,
+                                message: e.to_string(),
+                            })?;
+                            self.reschedule_from_plan(agent, now);
                         }
                     }
                 }
@@ -358,17 +1008,94 @@ impl<B: BehaviorModel, R: Router> Sim<B, R> {
                 // auto-woken; they receive the message at their natural next
                 // wake tick (from their plan or a prior WakeAt intent).
                 Intent::SendMessage { to, payload } => {
+                    self.stats.messages_sent += 1;
+                    if self.intra_tick_rounds > 1 {
+                        self.round_recipients.push(to);
+                    }
+                    if traced || self.is_traced(to) {
+                        self.trace_buffer.push(TraceEvent::MessageSent {
+                            tick:  now,
+                            from:  agent,
+                            to,
+                            bytes: payload.len(),
+                        });
+                    }
                     self.message_queue
                         .entry(to)
                         .or_default()
                         .push((agent, payload));
                 }
+
+                // ── Count / Sample: fold into this tick's metrics ──────────
+                Intent::Count { name, delta } => {
+                    self.stats.metric_intents += 1;
+                    self.metrics.add_count(name, delta);
+                }
+                Intent::Sample { name, value } => {
+                    self.stats.metric_intents += 1;
+                    self.metrics.add_sample(name, value);
+                }
             }
         }
         Ok(())
     }
 }
 
+// ── Per-agent intent computation ──────────────────────────────────────────────
+
+/// Run every behavior callback for one woken agent, catching panics.
+///
+/// Shared by the sequential and parallel intent phases.
+fn agent_intents<B: BehaviorModel>(
+    behavior:      &B,
+    agent:         AgentId,
+    input:         AgentInputs,
+    ctx:           &SimContext<'_>,
+    rng:           &mut dt_core::AgentRng,
+    mobility:      &MobilityStore,
+    contact_index: &ContactIndex,
+) -> Result<Vec<Intent>, Panic> {
+    catch_panic(|| {
+        let mut intents = behavior.replan(agent, ctx, rng);
+
+        for (from, payload) in input.messages {
+            intents.extend(behavior.on_message(agent, from, &payload, ctx, rng));
+        }
+
+        // Pass the raw agents-at-node slice directly — zero allocation.
+        // The slice includes `agent` itself; behavior filters self if needed.
+        let state = &mobility.states[agent.index()];
+        if !state.in_transit && state.departure_node != NodeId::INVALID {
+            let node = state.departure_node;
+            if let Some(agents_at_node) = contact_index.get(&node)
+                && agents_at_node.len() > 1
+            {
+                intents.extend(behavior.on_contacts(agent, node, agents_at_node, ctx, rng));
+            }
+        }
+
+        intents
+    })
+}
+
+/// Run `on_message` for each message delivered in a reaction round,
+/// catching panics.
+fn reaction_intents<B: BehaviorModel>(
+    behavior: &B,
+    agent:    AgentId,
+    input:    AgentInputs,
+    ctx:      &SimContext<'_>,
+    rng:      &mut dt_core::AgentRng,
+) -> Result<Vec<Intent>, Panic> {
+    catch_panic(|| {
+        let mut intents = Vec::new();
+        for (from, payload) in input.messages {
+            intents.extend(behavior.on_message(agent, from, &payload, ctx, rng));
+        }
+        intents
+    })
+}
+
 // ── Contact index helpers ─────────────────────────────────────────────────────
 
 /// Build a `NodeId → Vec<AgentId>` index of all stationary, placed agents.
@@ -400,3 +1127,32 @@ fn build_contact_index(store: &MobilityStore) -> ContactIndex {
     index
 }
 
+
+/// Build an `EdgeId → Vec<AgentId>` index of all routed in-transit agents
+/// by the edge they are traversing (or, without path-following, estimated
+/// to be traversing) at `now`, and a `TransitVehicle → Vec<AgentId>` index
+/// of the agents among them riding a scheduled vehicle.
+///
+/// Agents within each entry are in ascending `AgentId` order.
+/// Time complexity: O(T log T) for T travelers.
+fn build_edge_contact_index(
+    store:   &MobilityStore,
+    network: &RoadNetwork,
+    now:     Tick,
+) -> (EdgeContactIndex, VehicleContactIndex) {
+    let mut on_edges = EdgeContactIndex::default();
+    let mut on_vehicles = VehicleContactIndex::default();
+    for &agent in store.routes.keys() {
+        if let Some(edge) = store.current_edge(agent, now, network) {
+            on_edges.entry(edge).or_default().push(agent);
+        }
+        if let Some(vehicle) = store.current_vehicle(agent, now, network) {
+            on_vehicles.entry(vehicle).or_default().push(agent);
+        }
+    }
+    // `routes` is a hash map, so sort for a deterministic order.
+    for sharing in on_edges.values_mut().chain(on_vehicles.values_mut()) {
+        sharing.sort_unstable();
+    }
+    (on_edges, on_vehicles)
+}
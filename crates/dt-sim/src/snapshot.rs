@@ -0,0 +1,82 @@
+//! Warm-start input: restoring mobility state from a previously written
+//! agent snapshot.
+//!
+//! Burning a population in from tick 0 to reach a realistic mid-week state
+//! can take hours.  Instead, a run can resume from any snapshot an earlier run
+//! wrote via [`SimObserver::on_snapshot`][crate::SimObserver::on_snapshot]:
+//!
+//! ```rust,ignore
+//! let reader = dt_output::CsvSnapshotReader::new(Path::new("./burn_in"));
+//! let mut sim = SimBuilder::new(config, store, rngs, behavior, router)
+//!     .plans(plans)
+//!     .network(network)
+//!     .initial_state_from_snapshot(reader)
+//!     .build()?;
+//! ```
+//!
+//! dt-sim does not know about any output format; backends implement
+//! [`SnapshotReader`] to hand over a [`StateSnapshot`].
+
+use dt_core::{AgentId, NodeId, Tick, TransportMode};
+
+// ── Snapshot data ─────────────────────────────────────────────────────────────
+
+/// One agent's recorded mobility state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgentSnapshot {
+    pub agent:            AgentId,
+    /// The node the agent is at (or departed from if in transit).
+    /// `NodeId::INVALID` means the agent was never placed on the network.
+    pub departure_node:   NodeId,
+    pub in_transit:       bool,
+    /// Destination while in transit; ignored when stationary.
+    pub destination_node: NodeId,
+    /// Mode used to re-route an in-transit agent.  Snapshots that do not
+    /// record the mode should use `TransportMode::Car`.
+    pub mode:             TransportMode,
+    /// Tick the journey in progress began; ignored when stationary.  `None`
+    /// if not recorded, in which case it restarts at the resume tick.
+    pub departure_tick:   Option<Tick>,
+    /// Tick the journey in progress ends; ignored when stationary.  `None`
+    /// if not recorded, in which case the re-computed route decides.
+    pub arrival_tick:     Option<Tick>,
+}
+
+/// The full population state recorded at the end of `tick`.
+///
+/// Snapshots are taken after a tick has been processed, so a sim restored
+/// from a snapshot at tick `t` resumes at tick `t + 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateSnapshot {
+    /// Tick at which the snapshot was taken.
+    pub tick:   Tick,
+    /// Exactly one entry per agent, in any order.
+    pub agents: Vec<AgentSnapshot>,
+}
+
+impl StateSnapshot {
+    /// First tick processed by a sim restored from this snapshot.
+    #[inline]
+    pub fn resume_tick(&self) -> Tick {
+        self.tick + 1
+    }
+}
+
+// ── SnapshotReader ────────────────────────────────────────────────────────────
+
+/// A source of a [`StateSnapshot`] for
+/// [`SimBuilder::initial_state_from_snapshot`][crate::SimBuilder::initial_state_from_snapshot].
+///
+/// Errors are returned as plain strings so that dt-sim stays independent of
+/// any output backend's error type.
+pub trait SnapshotReader {
+    /// Read the snapshot to restore.
+    fn read_snapshot(&mut self) -> Result<StateSnapshot, String>;
+}
+
+/// An in-memory snapshot can be used directly.
+impl SnapshotReader for StateSnapshot {
+    fn read_snapshot(&mut self) -> Result<StateSnapshot, String> {
+        Ok(self.clone())
+    }
+}
@@ -0,0 +1,97 @@
+//! Built-in per-tick run statistics.
+//!
+//! Unlike [`TickMetrics`][crate::TickMetrics], which only holds what
+//! behaviors choose to emit, [`TickStats`] is filled in by the tick loop
+//! itself: how many agents woke, departed, and arrived, what they asked
+//! for, and what went wrong.  The sim keeps the most recent tick's stats in
+//! [`Sim::stats`][crate::Sim::stats] and hands them to
+//! [`SimObserver::on_tick_stats`][crate::SimObserver::on_tick_stats] before
+//! `on_tick_end`.  [`PhaseTimings`] records where the tick's wall
+//! time went.
+
+use std::time::Duration;
+
+/// Counts gathered by the tick loop over one tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TickStats {
+    /// Agents woken (processed) this tick.
+    pub woken:              u64,
+    /// Agents in transit at the end of the tick (those with a stored route).
+    pub in_transit:         u64,
+    /// Journeys completed at the start of the tick.
+    pub arrivals:           u64,
+    /// Journeys started this tick (successful `TravelTo`).
+    pub departures:         u64,
+    /// `TravelTo` intents applied.
+    pub travel_intents:     u64,
+    /// `WakeAt` intents applied, including ignored ones in the past.
+    pub wake_intents:       u64,
+    /// `SendMessage` intents applied.
+    pub messages_sent:      u64,
+    /// `Count` and `Sample` intents applied.
+    pub metric_intents:     u64,
+    /// Messages handed to `on_message`.
+    pub messages_delivered: u64,
+    /// Co-located pairs reported through `SimObserver::on_contacts`, one per
+    /// side.
+    pub contacts:           u64,
+    /// `TravelTo` intents that failed to route.
+    pub routing_failures:   u64,
+    /// Behavior callbacks that panicked.
+    pub behavior_panics:    u64,
+}
+
+impl TickStats {
+    /// Total intents applied this tick.
+    pub fn intents(&self) -> u64 {
+        self.travel_intents + self.wake_intents + self.messages_sent + self.metric_intents
+    }
+}
+
+// ── PhaseTimings ──────────────────────────────────────────────────────────────
+
+/// Wall time spent in each part of one tick.
+///
+/// Unlike [`TickStats`], timings differ from run to run, so they are kept
+/// out of every determinism check.  The sim keeps the most recent tick's
+/// timings in [`Sim::timings`][crate::Sim::timings] and hands them to
+/// [`SimObserver::on_phase_timings`][crate::SimObserver::on_phase_timings]
+/// right after `on_tick_stats`.  Observer callbacks made during a phase
+/// (`on_trip`, `on_contacts`) count towards it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PhaseTimings {
+    /// Processing arrivals and reporting trips.
+    pub arrivals:      Duration,
+    /// Draining the wake queue.
+    pub wake:          Duration,
+    /// Building the per-node contact index.
+    pub contact_index: Duration,
+    /// Collecting inputs and running the behavior callbacks, including
+    /// co-traveler contacts.
+    pub intents:       Duration,
+    /// Applying intents.
+    pub apply:         Duration,
+    /// Message-exchange rounds after the first.
+    pub reactions:     Duration,
+    /// Custom [`TickPhase`][crate::TickPhase]s, at every point.
+    pub custom_phases: Duration,
+    /// The whole tick, excluding the `on_tick_*`, `on_snapshot`, and
+    /// `on_metrics` callbacks.
+    pub total:         Duration,
+}
+
+impl PhaseTimings {
+    /// Each phase with its name (`"arrivals"`, `"wake"`, …), excluding
+    /// `total`.
+    pub fn phases(&self) -> [(&'static str, Duration); 7] {
+        [
+            ("arrivals",      self.arrivals),
+            ("wake",          self.wake),
+            ("contact_index", self.contact_index),
+            ("intents",       self.intents),
+            ("apply",         self.apply),
+            ("reactions",     self.reactions),
+            ("custom_phases", self.custom_phases),
+        ]
+    }
+}
@@ -21,6 +21,7 @@ fn test_config(total_ticks: u64) -> SimConfig {
         seed:                  42,
         num_threads:           Some(1),
         output_interval_ticks: total_ticks,
+        ..Default::default()
     }
 }
 
@@ -55,6 +56,31 @@ mod builder_tests {
         assert_eq!(sim.plans.len(), 3);
     }
 
+    #[test]
+    fn routing_config_sets_mode_speeds() {
+        use dt_spatial::Router;
+
+        let walk_ms = |walk_speed_mps: f32| {
+            let (store, rngs) = small_store(1);
+            let mut config = test_config(10);
+            config.routing.walk_speed_mps = walk_speed_mps;
+            let sim = SimBuilder::new(config, store, rngs, NoopBehavior, DijkstraRouter)
+                .network(line_network())
+                .build()
+                .unwrap();
+            sim.mobility.router.route(&sim.network, NodeId(0), NodeId(2), TransportMode::Walk).unwrap().total_travel_ms
+        };
+        // 2 × 500 m.
+        assert_eq!(walk_ms(2.0), 500_000);
+        assert_eq!(walk_ms(1.0), 1_000_000);
+
+        let (store, rngs) = small_store(1);
+        let mut config = test_config(10);
+        config.routing.bike_speed_mps = 0.0;
+        let result = SimBuilder::new(config, store, rngs, NoopBehavior, DijkstraRouter).build();
+        assert!(matches!(result, Err(crate::SimError::Config(msg)) if msg.contains("bike_speed_mps")));
+    }
+
     #[test]
     fn plan_count_mismatch_errors() {
         let (store, rngs) = small_store(3);
@@ -288,6 +314,25 @@ mod intent_tests {
         assert_eq!(sim.clock.current_tick, Tick(5));
     }
 
+    #[test]
+    fn wake_at_before_start_does_not_underflow() {
+        // `ctx.tick - 2 days` at tick 1 clamps to tick 0, which is ignored.
+        struct WakeLongAgo;
+        impl BehaviorModel for WakeLongAgo {
+            fn replan(&self, _a: AgentId, ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+                vec![Intent::WakeAt(ctx.tick - dt_core::TickDuration(48))]
+            }
+        }
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, WakeLongAgo, DijkstraRouter)
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(1), AgentId(0));
+        sim.run(&mut NoopObserver).unwrap();
+        assert_eq!(sim.clock.current_tick, Tick(5));
+        assert!(sim.wake_queue.is_empty());
+    }
+
     #[test]
     fn travel_to_initiates_transit() {
         // Agent at node 0 requests travel to node 2 on its first wake.
@@ -343,7 +388,7 @@ mod intent_tests {
     #[test]
     fn agent_arrives_after_travel_ticks() {
         // Agent travels from 0 to 2; each leg is 60 s = 1 tick at 3600 s/tick?
-        // travel_ticks = ceil(total_travel_secs / tick_duration_secs)
+        // travel_ticks = ceil(total_travel_ms / (tick_duration_secs * 1000))
         // For node 0→1→2 via Dijkstra: 60s + 60s = 120s → ceil(120/3600) = 1 tick.
         struct TravelToNode2(Mutex<bool>);
         impl BehaviorModel for TravelToNode2 {
@@ -394,6 +439,55 @@ mod intent_tests {
             "agent should be at destination node"
         );
     }
+
+    #[test]
+    fn observer_receives_trip() {
+        struct WalkOnce(Mutex<bool>);
+        impl BehaviorModel for WalkOnce {
+            fn replan(&self, _a: AgentId, _ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+                let mut done = self.0.lock().unwrap();
+                if std::mem::replace(&mut *done, true) {
+                    vec![]
+                } else {
+                    vec![Intent::TravelTo { destination: NodeId(2), mode: TransportMode::Walk }]
+                }
+            }
+        }
+
+        #[derive(Default)]
+        struct RecordTrips(Vec<dt_mobility::Trip>);
+        impl SimObserver for RecordTrips {
+            fn on_trip(&mut self, trip: &dt_mobility::Trip) {
+                self.0.push(*trip);
+            }
+        }
+
+        let act = ScheduledActivity {
+            start_offset_ticks: 0,
+            duration_ticks:     1,
+            activity_id:        dt_core::ActivityId(0),
+            destination:        Destination::Home,
+        };
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(10), store, rngs, WalkOnce(Mutex::new(false)), DijkstraRouter)
+            .plans(vec![ActivityPlan::new(vec![act], 1)])
+            .network(line_network())
+            .initial_positions(vec![NodeId(0)])
+            .build()
+            .unwrap();
+
+        let mut obs = RecordTrips::default();
+        sim.run(&mut obs).unwrap();
+
+        // Departs at its first wake (tick 1); the walk rounds up to one tick.
+        assert_eq!(obs.0.len(), 1);
+        let trip = obs.0[0];
+        assert_eq!((trip.agent, trip.from, trip.to), (AgentId(0), NodeId(0), NodeId(2)));
+        assert_eq!((trip.depart_tick, trip.arrive_tick), (Tick(1), Tick(2)));
+        assert_eq!(trip.mode, TransportMode::Walk);
+        assert!(trip.travel_secs > 120.0, "walking is slower than the 120 s car time");
+        assert_eq!(trip.distance_m, 1000.0);
+    }
 }
 
 // ── Message queue ─────────────────────────────────────────────────────────────
@@ -664,6 +758,37 @@ mod contact_tests {
         );
     }
 
+    #[test]
+    fn observer_receives_contacts() {
+        type ContactLog = Vec<(Tick, AgentId, NodeId, Vec<AgentId>)>;
+
+        #[derive(Default)]
+        struct RecordContacts(ContactLog);
+        impl SimObserver for RecordContacts {
+            fn on_contacts(&mut self, tick: Tick, agent: AgentId, node: NodeId, agents_at_node: &[AgentId]) {
+                self.0.push((tick, agent, node, agents_at_node.to_vec()));
+            }
+        }
+
+        // Agents 0 and 2 share node 0; agent 1 is alone at node 1.
+        let plan = tick1_plan();
+        let (store, rngs) = small_store(3);
+        let mut sim = SimBuilder::new(test_config(2), store, rngs, NoopBehavior, DijkstraRouter)
+            .plans(vec![plan.clone(), plan.clone(), plan])
+            .initial_positions(vec![NodeId(0), NodeId(1), NodeId(0)])
+            .build()
+            .unwrap();
+
+        let mut obs = RecordContacts::default();
+        sim.run(&mut obs).unwrap();
+
+        let pair = vec![AgentId(0), AgentId(2)];
+        assert_eq!(obs.0, vec![
+            (Tick(1), AgentId(0), NodeId(0), pair.clone()),
+            (Tick(1), AgentId(2), NodeId(0), pair),
+        ]);
+    }
+
     #[test]
     fn separated_agents_see_no_contacts() {
         let contact_count = Arc::new(AtomicUsize::new(0));
@@ -770,6 +895,8 @@ mod contact_tests {
             destination_node: NodeId(2),
             departure_tick:   Tick(0),
             arrival_tick:     Tick(100), // won't arrive during this run
+            mode:             TransportMode::Car,
+            on_edge:          None,
         };
 
         sim.run(&mut NoopObserver).unwrap();
@@ -777,3 +904,1905 @@ mod contact_tests {
             "in-transit agent should not appear in contact index");
     }
 }
+
+// ── Failure policy ────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod failure_tests {
+    use super::*;
+    use crate::{FailureKind, FailurePolicy, SimError};
+
+    fn tick1_plan() -> ActivityPlan {
+        let act = ScheduledActivity {
+            start_offset_ticks: 0,
+            duration_ticks:     1,
+            activity_id:        dt_core::ActivityId(0),
+            destination:        Destination::Home,
+        };
+        ActivityPlan::new(vec![act], 1)
+    }
+
+    /// Requests travel to a node that does not exist in the network.
+    struct TravelNowhere;
+    impl BehaviorModel for TravelNowhere {
+        fn replan(&self, _a: AgentId, _ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+            vec![Intent::TravelTo { destination: NodeId(2), mode: TransportMode::Car }]
+        }
+    }
+
+    /// Panics for agent 0; agent 1 keeps waking every tick.
+    struct PanicForAgentZero;
+    impl BehaviorModel for PanicForAgentZero {
+        fn replan(&self, agent: AgentId, ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+            if agent == AgentId(0) {
+                panic!("bad agent");
+            }
+            vec![Intent::WakeAt(ctx.tick + 1)]
+        }
+    }
+
+    /// Two disconnected nodes: any route between them fails.
+    fn disconnected_network() -> dt_spatial::RoadNetwork {
+        let mut b = RoadNetworkBuilder::new();
+        b.add_node(GeoPoint::new(0.0, 0.0));
+        b.add_node(GeoPoint::new(0.0, 1.0));
+        b.add_node(GeoPoint::new(0.0, 2.0));
+        b.build()
+    }
+
+    #[test]
+    fn default_policy_continues_quietly() {
+        let (store, rngs) = small_store(1);
+        let sim = SimBuilder::new(test_config(1), store, rngs, NoopBehavior, DijkstraRouter)
+            .build()
+            .unwrap();
+        assert_eq!(sim.failure_policy, FailurePolicy::Continue);
+
+        // Failures are only counted: nothing is collected.
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, TravelNowhere, DijkstraRouter)
+            .plans(vec![tick1_plan()])
+            .network(disconnected_network())
+            .initial_positions(vec![NodeId(0)])
+            .build()
+            .unwrap();
+        struct CountFailures(u64);
+        impl SimObserver for CountFailures {
+            fn on_tick_stats(&mut self, _tick: Tick, stats: &crate::TickStats) { self.0 += stats.routing_failures; }
+        }
+        let mut counted = CountFailures(0);
+        sim.run(&mut counted).unwrap();
+        assert_eq!(counted.0, 4);
+        assert!(sim.failures.is_empty());
+    }
+
+    #[test]
+    fn routing_failure_fail_fast_aborts() {
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, TravelNowhere, DijkstraRouter)
+            .plans(vec![tick1_plan()])
+            .network(disconnected_network())
+            .initial_positions(vec![NodeId(0)])
+            .failure_policy(FailurePolicy::FailFast)
+            .build()
+            .unwrap();
+        let result = sim.run(&mut NoopObserver);
+        match &result {
+            Err(SimError::Routing { agent, tick, message }) => {
+                assert_eq!((*agent, *tick), (AgentId(0), Tick(1)));
+                assert!(message.contains("routing failed"), "{message}");
+            }
+            other => panic!("expected a routing error, got {other:?}"),
+        }
+        assert_eq!(sim.clock.current_tick, Tick(1), "should stop at the failing tick");
+        assert_eq!(sim.stats.routing_failures, 1);
+
+        let err = dt_core::DtError::from(result.unwrap_err());
+        assert_eq!(err.category(), dt_core::ErrorCategory::Sim);
+        assert!(matches!(err.downcast_ref(), Some(SimError::Routing { .. })));
+    }
+
+    #[test]
+    fn routing_failure_collected_and_agent_rescheduled() {
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, TravelNowhere, DijkstraRouter)
+            .plans(vec![tick1_plan()])
+            .network(disconnected_network())
+            .initial_positions(vec![NodeId(0)])
+            .failure_policy(FailurePolicy::CollectAndReport)
+            .build()
+            .unwrap();
+        sim.run(&mut NoopObserver).unwrap();
+
+        // Agent wakes at ticks 1..=4 and fails each time.
+        assert_eq!(sim.failures.len(), 4);
+        assert!(sim.failures.iter().all(|f| f.kind == FailureKind::Routing));
+        assert_eq!(sim.failures[0].agent, Some(AgentId(0)));
+        assert_eq!(sim.failures[0].tick, Tick(1));
+    }
+
+    #[test]
+    #[cfg(panic = "unwind")]
+    fn behavior_panic_fail_fast_aborts() {
+        let plan = tick1_plan();
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, PanicForAgentZero, DijkstraRouter)
+            .plans(vec![plan.clone(), plan])
+            .failure_policy(FailurePolicy::FailFast)
+            .build()
+            .unwrap();
+        match sim.run(&mut NoopObserver) {
+            Err(SimError::BehaviorPanic { agent, tick, message }) => {
+                assert_eq!(agent, AgentId(0));
+                assert_eq!(tick, Tick(1));
+                assert_eq!(message, "bad agent");
+            }
+            other => panic!("expected BehaviorPanic, got {other:?}"),
+        }
+    }
+
+    #[test]
+    #[cfg(panic = "unwind")]
+    fn behavior_panic_isolated_to_one_agent() {
+        let plan = tick1_plan();
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, PanicForAgentZero, DijkstraRouter)
+            .plans(vec![plan.clone(), plan])
+            .failure_policy(FailurePolicy::CollectAndReport)
+            .build()
+            .unwrap();
+
+        let woken = Arc::new(Mutex::new(Vec::new()));
+        struct RecordWoken(Arc<Mutex<Vec<usize>>>);
+        impl SimObserver for RecordWoken {
+            fn on_tick_end(&mut self, _t: Tick, w: usize) {
+                self.0.lock().unwrap().push(w);
+            }
+        }
+        sim.run(&mut RecordWoken(Arc::clone(&woken))).unwrap();
+
+        // Agent 0 panics every tick but is re-scheduled from its plan, so
+        // both agents keep waking.
+        assert_eq!(*woken.lock().unwrap(), vec![0, 2, 2, 2, 2]);
+        assert_eq!(sim.failures.len(), 4);
+        assert!(sim.failures.iter().all(|f| {
+            f.kind == FailureKind::BehaviorPanic && f.agent == Some(AgentId(0))
+        }));
+    }
+
+    #[test]
+    fn failures_counted_in_tick_stats() {
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, TravelNowhere, DijkstraRouter)
+            .plans(vec![tick1_plan()])
+            .network(disconnected_network())
+            .initial_positions(vec![NodeId(0)])
+            .failure_policy(FailurePolicy::CollectAndReport)
+            .build()
+            .unwrap();
+        sim.run_ticks(2, &mut NoopObserver).unwrap();
+        assert_eq!((sim.stats.travel_intents, sim.stats.departures), (1, 0));
+        assert_eq!(sim.stats.routing_failures, 1);
+
+        let plan = tick1_plan();
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, PanicForAgentZero, DijkstraRouter)
+            .plans(vec![plan.clone(), plan])
+            .failure_policy(FailurePolicy::CollectAndReport)
+            .build()
+            .unwrap();
+        sim.run_ticks(2, &mut NoopObserver).unwrap();
+        assert_eq!(sim.stats.behavior_panics, 1);
+        assert_eq!(sim.stats.wake_intents, 1);
+    }
+
+    /// Reports one error after the first tick.
+    struct FailingObserver {
+        reported: bool,
+    }
+    impl SimObserver for FailingObserver {
+        fn poll_error(&mut self) -> Option<String> {
+            if self.reported {
+                None
+            } else {
+                self.reported = true;
+                Some("disk full".to_owned())
+            }
+        }
+    }
+
+    #[test]
+    fn observer_error_fail_fast_aborts() {
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, NoopBehavior, DijkstraRouter)
+            .failure_policy(FailurePolicy::FailFast)
+            .build()
+            .unwrap();
+        let result = sim.run(&mut FailingObserver { reported: false });
+        assert!(matches!(result, Err(SimError::Observer { tick: Tick(0), .. })), "got {result:?}");
+    }
+
+    #[test]
+    fn observer_error_collected() {
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, NoopBehavior, DijkstraRouter)
+            .failure_policy(FailurePolicy::CollectAndReport)
+            .build()
+            .unwrap();
+        sim.run(&mut FailingObserver { reported: false }).unwrap();
+        assert_eq!(sim.clock.current_tick, Tick(5));
+        assert_eq!(sim.failures.len(), 1);
+        assert_eq!(sim.failures[0].kind, FailureKind::Observer);
+        assert_eq!(sim.failures[0].agent, None);
+        assert_eq!(sim.failures[0].message, "disk full");
+    }
+}
+
+// ── Warm start ────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use crate::{AgentSnapshot, FailureKind, FailurePolicy, SimError, StateSnapshot};
+
+    fn stationary(agent: u32, node: u32) -> AgentSnapshot {
+        AgentSnapshot {
+            agent:            AgentId(agent),
+            departure_node:   NodeId(node),
+            in_transit:       false,
+            destination_node: NodeId::INVALID,
+            mode:             TransportMode::Car,
+            departure_tick:   None,
+            arrival_tick:     None,
+        }
+    }
+
+    /// Two nodes and no roads: no journey can be routed.
+    fn roadless_network() -> dt_spatial::RoadNetwork {
+        let mut b = RoadNetworkBuilder::new();
+        b.add_node(GeoPoint::new(0.0, 0.0));
+        b.add_node(GeoPoint::new(0.0, 1.0));
+        b.build()
+    }
+
+    fn hourly_plan() -> ActivityPlan {
+        let act = ScheduledActivity {
+            start_offset_ticks: 0,
+            duration_ticks:     1,
+            activity_id:        dt_core::ActivityId(0),
+            destination:        Destination::Home,
+        };
+        ActivityPlan::new(vec![act], 1)
+    }
+
+    #[test]
+    fn warm_start_sets_clock_and_positions() {
+        let snap = StateSnapshot {
+            tick:   Tick(10),
+            agents: vec![stationary(1, 2), stationary(0, 1)],
+        };
+        let (store, rngs) = small_store(2);
+        let sim = SimBuilder::new(test_config(20), store, rngs, NoopBehavior, DijkstraRouter)
+            .plans(vec![hourly_plan(), hourly_plan()])
+            .network(line_network())
+            .initial_state_from_snapshot(snap)
+            .build()
+            .unwrap();
+        assert_eq!(sim.clock.current_tick, Tick(11));
+        assert_eq!(sim.mobility.store.states[0].departure_node, NodeId(1));
+        assert_eq!(sim.mobility.store.states[1].departure_node, NodeId(2));
+        assert_eq!(sim.wake_queue.next_tick(), Some(Tick(11)));
+        assert_eq!(sim.wake_queue.len(), 2);
+    }
+
+    #[test]
+    fn warm_start_resumes_journey() {
+        let mut moving = stationary(0, 0);
+        moving.in_transit       = true;
+        moving.destination_node = NodeId(2);
+        let snap = StateSnapshot { tick: Tick(3), agents: vec![moving] };
+
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(10), store, rngs, NoopBehavior, DijkstraRouter)
+            .plans(vec![hourly_plan()])
+            .network(line_network())
+            .initial_state_from_snapshot(snap)
+            .build()
+            .unwrap();
+
+        let state = &sim.mobility.store.states[0];
+        assert!(state.in_transit);
+        assert_eq!(state.departure_tick, Tick(4));
+        assert!(sim.mobility.store.routes.contains_key(&AgentId(0)));
+        assert!(sim.wake_queue.is_empty(), "in-transit agents wake on arrival");
+
+        sim.run(&mut NoopObserver).unwrap();
+        assert!(!sim.mobility.store.states[0].in_transit);
+        assert_eq!(sim.mobility.store.states[0].departure_node, NodeId(2));
+    }
+
+    #[test]
+    fn warm_start_keeps_recorded_journey_ticks() {
+        let mut moving = stationary(0, 0);
+        moving.in_transit       = true;
+        moving.destination_node = NodeId(2);
+        moving.departure_tick   = Some(Tick(2));
+        moving.arrival_tick     = Some(Tick(6));
+        let snap = StateSnapshot { tick: Tick(3), agents: vec![moving] };
+
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(10), store, rngs, NoopBehavior, DijkstraRouter)
+            .plans(vec![hourly_plan()])
+            .network(line_network())
+            .initial_state_from_snapshot(snap)
+            .build()
+            .unwrap();
+        let state = &sim.mobility.store.states[0];
+        assert!(state.in_transit);
+        assert_eq!((state.departure_tick, state.arrival_tick), (Tick(2), Tick(6)));
+
+        #[derive(Default)]
+        struct Trips(Vec<(Tick, Tick)>);
+        impl SimObserver for Trips {
+            fn on_trip(&mut self, trip: &dt_mobility::Trip) { self.0.push((trip.depart_tick, trip.arrive_tick)); }
+        }
+        let mut trips = Trips::default();
+        sim.run(&mut trips).unwrap();
+        assert_eq!(trips.0.first(), Some(&(Tick(2), Tick(6))), "the resumed journey ends when recorded");
+    }
+
+    #[test]
+    fn warm_start_routing_failure_collected() {
+        let mut moving = stationary(0, 0);
+        moving.in_transit       = true;
+        moving.destination_node = NodeId(1);
+        let snap = StateSnapshot { tick: Tick(3), agents: vec![moving] };
+
+        let (store, rngs) = small_store(1);
+        let sim = SimBuilder::new(test_config(10), store, rngs, NoopBehavior, DijkstraRouter)
+            .plans(vec![hourly_plan()])
+            .network(roadless_network())
+            .initial_state_from_snapshot(snap)
+            .failure_policy(FailurePolicy::CollectAndReport)
+            .build()
+            .unwrap();
+        assert_eq!(sim.failures.len(), 1);
+        assert_eq!(sim.failures[0].kind, FailureKind::Routing);
+        assert_eq!((sim.failures[0].agent, sim.failures[0].tick), (Some(AgentId(0)), Tick(4)));
+        assert_eq!(sim.stats.routing_failures, 1);
+        assert!(!sim.mobility.store.states[0].in_transit);
+        assert_eq!(sim.wake_queue.next_tick(), Some(Tick(4)), "the agent is re-queued from its plan");
+    }
+
+    #[test]
+    fn warm_start_routing_failure_fail_fast() {
+        let mut moving = stationary(0, 0);
+        moving.in_transit       = true;
+        moving.destination_node = NodeId(1);
+        let snap = StateSnapshot { tick: Tick(3), agents: vec![moving] };
+
+        let (store, rngs) = small_store(1);
+        let result = SimBuilder::new(test_config(10), store, rngs, NoopBehavior, DijkstraRouter)
+            .network(roadless_network())
+            .initial_state_from_snapshot(snap)
+            .failure_policy(FailurePolicy::FailFast)
+            .build();
+        match result {
+            Err(SimError::Routing { agent, tick, .. }) => assert_eq!((agent, tick), (AgentId(0), Tick(4))),
+            Err(e) => panic!("expected a routing error, got {e}"),
+            Ok(_)  => panic!("expected a routing error, got Ok"),
+        }
+    }
+
+    #[test]
+    fn warm_start_run_reaches_end_tick() {
+        let snap = StateSnapshot { tick: Tick(6), agents: vec![stationary(0, 0)] };
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(10), store, rngs, NoopBehavior, DijkstraRouter)
+            .initial_state_from_snapshot(snap)
+            .build()
+            .unwrap();
+
+        struct CountTicks(usize);
+        impl SimObserver for CountTicks {
+            fn on_tick_start(&mut self, _t: Tick) { self.0 += 1; }
+        }
+        let mut obs = CountTicks(0);
+        sim.run(&mut obs).unwrap();
+        assert_eq!(obs.0, 3, "ticks 7, 8, 9");
+        assert_eq!(sim.clock.current_tick, Tick(10));
+    }
+
+    #[test]
+    fn snapshot_agent_count_mismatch_errors() {
+        let snap = StateSnapshot { tick: Tick(0), agents: vec![stationary(0, 0)] };
+        let (store, rngs) = small_store(2);
+        let result = SimBuilder::new(test_config(10), store, rngs, NoopBehavior, DijkstraRouter)
+            .initial_state_from_snapshot(snap)
+            .build();
+        assert!(matches!(result, Err(SimError::AgentCountMismatch { .. })));
+    }
+
+    #[test]
+    fn snapshot_duplicate_agent_errors() {
+        let snap = StateSnapshot {
+            tick:   Tick(0),
+            agents: vec![stationary(0, 0), stationary(0, 1)],
+        };
+        let (store, rngs) = small_store(2);
+        let result = SimBuilder::new(test_config(10), store, rngs, NoopBehavior, DijkstraRouter)
+            .initial_state_from_snapshot(snap)
+            .build();
+        assert!(matches!(result, Err(SimError::Snapshot(_))));
+    }
+
+    #[test]
+    fn snapshot_read_error_reported_at_build() {
+        struct Broken;
+        impl crate::SnapshotReader for Broken {
+            fn read_snapshot(&mut self) -> Result<StateSnapshot, String> {
+                Err("file not found".to_owned())
+            }
+        }
+        let (store, rngs) = small_store(1);
+        let result = SimBuilder::new(test_config(10), store, rngs, NoopBehavior, DijkstraRouter)
+            .initial_state_from_snapshot(Broken)
+            .build();
+        match result {
+            Err(SimError::Snapshot(msg)) => assert_eq!(msg, "file not found"),
+            Err(e) => panic!("expected Snapshot error, got {e}"),
+            Ok(_)  => panic!("expected Snapshot error, got Ok"),
+        }
+    }
+}
+
+// ── Custom tick phases ────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod phase_tests {
+    use super::*;
+    use crate::{FailureKind, FailurePolicy, PhaseContext, PhasePoint, SimError, TickPhase};
+
+    /// `(tick, point, woken, pending intents)` for every phase call.
+    type PhaseLog = Arc<Mutex<Vec<(Tick, PhasePoint, usize, usize)>>>;
+
+    struct Recorder(PhaseLog);
+    impl TickPhase for Recorder {
+        fn run(&mut self, ctx: &mut PhaseContext<'_>) -> Result<(), String> {
+            self.0.lock().unwrap().push((ctx.tick, ctx.point, ctx.woken.len(), ctx.intents.len()));
+            Ok(())
+        }
+    }
+
+    struct WakeEveryTick;
+    impl BehaviorModel for WakeEveryTick {
+        fn replan(&self, _a: AgentId, ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+            vec![Intent::WakeAt(ctx.tick + 1)]
+        }
+    }
+
+    fn hourly_plan() -> ActivityPlan {
+        let act = ScheduledActivity {
+            start_offset_ticks: 0,
+            duration_ticks:     1,
+            activity_id:        dt_core::ActivityId(0),
+            destination:        Destination::Home,
+        };
+        ActivityPlan::new(vec![act], 1)
+    }
+
+    #[test]
+    fn phases_run_every_tick_at_each_point() {
+        let log: PhaseLog = Arc::new(Mutex::new(Vec::new()));
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(2), store, rngs, WakeEveryTick, DijkstraRouter)
+            .plans(vec![hourly_plan()])
+            .phase(PhasePoint::AfterApply, Recorder(Arc::clone(&log)))
+            .phase(PhasePoint::BeforeIntents, Recorder(Arc::clone(&log)))
+            .phase(PhasePoint::AfterIntents, Recorder(Arc::clone(&log)))
+            .build()
+            .unwrap();
+        sim.run(&mut NoopObserver).unwrap();
+
+        // Tick 0: nobody wakes, phases still run.  Tick 1: agent 0 wakes.
+        assert_eq!(*log.lock().unwrap(), vec![
+            (Tick(0), PhasePoint::BeforeIntents, 0, 0),
+            (Tick(0), PhasePoint::AfterIntents,  0, 0),
+            (Tick(0), PhasePoint::AfterApply,    0, 0),
+            (Tick(1), PhasePoint::BeforeIntents, 1, 0),
+            (Tick(1), PhasePoint::AfterIntents,  1, 1),
+            (Tick(1), PhasePoint::AfterApply,    1, 0),
+        ]);
+    }
+
+    #[test]
+    fn after_intents_phase_can_drop_intents() {
+        struct DropAll;
+        impl TickPhase for DropAll {
+            fn run(&mut self, ctx: &mut PhaseContext<'_>) -> Result<(), String> {
+                ctx.intents.clear();
+                Ok(())
+            }
+        }
+
+        // Without a plan, the agent is kept alive only by its WakeAt intent.
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, WakeEveryTick, DijkstraRouter)
+            .phase(PhasePoint::AfterIntents, DropAll)
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(1), AgentId(0));
+        sim.run(&mut NoopObserver).unwrap();
+        assert!(sim.wake_queue.is_empty(), "dropped WakeAt must not be applied");
+    }
+
+    #[test]
+    fn after_apply_phase_mutates_state() {
+        /// Teleports every agent to node 2 at the end of each tick.
+        struct Teleport;
+        impl TickPhase for Teleport {
+            fn run(&mut self, ctx: &mut PhaseContext<'_>) -> Result<(), String> {
+                for state in ctx.mobility.states.iter_mut() {
+                    *state = dt_mobility::MovementState::stationary(NodeId(2), ctx.tick);
+                }
+                Ok(())
+            }
+        }
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(1), store, rngs, NoopBehavior, DijkstraRouter)
+            .network(line_network())
+            .phase(PhasePoint::AfterApply, Teleport)
+            .build()
+            .unwrap();
+        sim.run(&mut NoopObserver).unwrap();
+        assert!(sim.mobility.store.states.iter().all(|s| s.departure_node == NodeId(2)));
+    }
+
+    struct Failing;
+    impl TickPhase for Failing {
+        fn name(&self) -> &str { "failing" }
+        fn run(&mut self, _ctx: &mut PhaseContext<'_>) -> Result<(), String> {
+            Err("boom".to_owned())
+        }
+    }
+
+    #[test]
+    fn phase_error_fail_fast_aborts() {
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, NoopBehavior, DijkstraRouter)
+            .phase(PhasePoint::AfterApply, Failing)
+            .failure_policy(FailurePolicy::FailFast)
+            .build()
+            .unwrap();
+        match sim.run(&mut NoopObserver) {
+            Err(SimError::Phase { tick, message }) => {
+                assert_eq!(tick, Tick(0));
+                assert_eq!(message, "failing: boom");
+            }
+            other => panic!("expected Phase error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn phase_error_collected() {
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(3), store, rngs, NoopBehavior, DijkstraRouter)
+            .phase(PhasePoint::BeforeIntents, Failing)
+            .failure_policy(FailurePolicy::CollectAndReport)
+            .build()
+            .unwrap();
+        sim.run(&mut NoopObserver).unwrap();
+        assert_eq!(sim.failures.len(), 3);
+        assert!(sim.failures.iter().all(|f| f.kind == FailureKind::Phase && f.agent.is_none()));
+    }
+}
+
+// ── Co-traveler (edge) contacts ───────────────────────────────────────────────
+
+#[cfg(test)]
+mod edge_contact_tests {
+    use super::*;
+    use dt_core::{EdgeId, TransitVehicle};
+    use dt_spatial::{TransitLine, TransitRouter};
+    use crate::{PhaseContext, PhasePoint, TickPhase};
+
+    /// One-minute ticks so a 2-edge trip on `line_network` spans 2 ticks.
+    fn minute_config(total_ticks: u64) -> SimConfig {
+        SimConfig { tick_duration_secs: 60, ..test_config(total_ticks) }
+    }
+
+    /// `(tick, agent, edge, travelers on edge)` for every edge contact.
+    type EdgeLog = Vec<(Tick, AgentId, EdgeId, usize)>;
+
+    /// Travels 0 → 2 when woken at tick 1; records edge contacts.
+    struct Commuter(Arc<Mutex<EdgeLog>>);
+    impl BehaviorModel for Commuter {
+        fn replan(&self, _a: AgentId, ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+            if ctx.tick == Tick(1) {
+                vec![Intent::TravelTo { destination: NodeId(2), mode: TransportMode::Car }]
+            } else {
+                vec![]
+            }
+        }
+        fn on_edge_contacts(
+            &self,
+            agent:          AgentId,
+            edge:           EdgeId,
+            agents_on_edge: &[AgentId],
+            ctx:            &SimContext<'_>,
+            _rng:           &mut AgentRng,
+        ) -> Vec<Intent> {
+            self.0.lock().unwrap().push((ctx.tick, agent, edge, agents_on_edge.len()));
+            vec![]
+        }
+    }
+
+    fn run(edge_contacts: bool, n: usize) -> EdgeLog {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (store, rngs) = small_store(n);
+        let mut sim = SimBuilder::new(minute_config(5), store, rngs, Commuter(Arc::clone(&log)), DijkstraRouter)
+            .network(line_network())
+            .initial_positions(vec![NodeId(0); n])
+            .edge_contacts(edge_contacts)
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(1), AgentId(0));
+        sim.wake_queue.push(Tick(1), AgentId(1));
+        sim.run(&mut NoopObserver).unwrap();
+        log.lock().unwrap().clone()
+    }
+
+    #[test]
+    fn co_travelers_see_each_other() {
+        let log = run(true, 3);
+        // Depart at tick 1, arrive at tick 3: both in transit during tick 2.
+        // Agent 2 never travels and is never reported.
+        assert_eq!(log.len(), 2, "{log:?}");
+        assert_eq!((log[0].0, log[0].1, log[0].3), (Tick(2), AgentId(0), 2));
+        assert_eq!((log[1].0, log[1].1, log[1].3), (Tick(2), AgentId(1), 2));
+        assert_eq!(log[0].2, log[1].2, "same edge");
+    }
+
+    #[test]
+    fn co_traveler_intents_follow_woken_agents() {
+        /// The agents with pending intents at tick 2, in apply order.
+        struct Order(Arc<Mutex<Vec<AgentId>>>);
+        impl TickPhase for Order {
+            fn run(&mut self, ctx: &mut PhaseContext<'_>) -> Result<(), String> {
+                if ctx.tick == Tick(2) {
+                    self.0.lock().unwrap().extend(ctx.intents.iter().map(|&(agent, _)| agent));
+                }
+                Ok(())
+            }
+        }
+
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let (store, rngs) = small_store(4);
+        let mut sim = SimBuilder::new(minute_config(5), store, rngs, Commuter(Arc::default()), DijkstraRouter)
+            .network(line_network())
+            .initial_positions(vec![NodeId(0); 4])
+            .edge_contacts(true)
+            .phase(PhasePoint::AfterIntents, Order(Arc::clone(&order)))
+            .build()
+            .unwrap();
+        // Agents 0 and 1 are on the road at tick 2, when 3 and then 2 wake.
+        sim.wake_queue.push(Tick(1), AgentId(1));
+        sim.wake_queue.push(Tick(1), AgentId(0));
+        sim.wake_queue.push(Tick(2), AgentId(3));
+        sim.wake_queue.push(Tick(2), AgentId(2));
+        sim.run(&mut NoopObserver).unwrap();
+        assert_eq!(*order.lock().unwrap(), vec![AgentId(3), AgentId(2), AgentId(0), AgentId(1)]);
+    }
+
+    #[test]
+    fn edge_contacts_off_by_default() {
+        assert!(run(false, 2).is_empty());
+    }
+
+    #[test]
+    fn edge_contacts_enabled_from_config() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (store, rngs) = small_store(2);
+        let mut config = minute_config(5);
+        config.contacts.edge_contacts = true;
+        let mut sim = SimBuilder::new(config, store, rngs, Commuter(Arc::clone(&log)), DijkstraRouter)
+            .network(line_network())
+            .initial_positions(vec![NodeId(0); 2])
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(1), AgentId(0));
+        sim.wake_queue.push(Tick(1), AgentId(1));
+        sim.run(&mut NoopObserver).unwrap();
+        assert_eq!(log.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn path_following_tracks_edge_from_config() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (store, rngs) = small_store(1);
+        let mut config = minute_config(5);
+        config.mobility.path_following = true;
+        let mut sim = SimBuilder::new(config, store, rngs, Commuter(Arc::clone(&log)), DijkstraRouter)
+            .network(line_network())
+            .initial_positions(vec![NodeId(0)])
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(1), AgentId(0));
+
+        // Departs at tick 1 onto the first edge; halfway, at tick 2, starts the second.
+        sim.run_ticks(2, &mut NoopObserver).unwrap();
+        let pos = sim.mobility.store.states[0].on_edge.unwrap();
+        assert_eq!((pos.index, pos.offset_ms), (0, 0));
+        assert_eq!(sim.mobility.store.states[0].segment(Tick(1), &sim.network), (NodeId(0), NodeId(1), 0.0));
+        sim.run_ticks(1, &mut NoopObserver).unwrap();
+        let pos = sim.mobility.store.states[0].on_edge.unwrap();
+        assert_eq!((pos.index, pos.offset_ms), (1, 0));
+        assert_eq!(sim.mobility.store.states[0].segment(Tick(2), &sim.network), (NodeId(1), NodeId(2), 0.0));
+
+        // Arrival at tick 3 is unchanged.
+        sim.run_ticks(1, &mut NoopObserver).unwrap();
+        assert!(!sim.mobility.store.in_transit(AgentId(0)));
+        assert_eq!(sim.mobility.store.states[0].on_edge, None);
+    }
+
+    /// `(tick, agent, vehicle, riders)` for every vehicle contact.
+    type VehicleLog = Vec<(Tick, AgentId, TransitVehicle, usize)>;
+
+    /// Rides transit to the far end of `line_network` when woken (agent 4
+    /// to node 0, the rest to node 2), except agent 3, who walks.  Records
+    /// vehicle contacts.
+    struct Rider(Arc<Mutex<VehicleLog>>);
+    impl BehaviorModel for Rider {
+        fn replan(&self, agent: AgentId, _ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+            let destination = if agent == AgentId(4) { NodeId(0) } else { NodeId(2) };
+            let mode = if agent == AgentId(3) { TransportMode::Walk } else { TransportMode::Transit };
+            vec![Intent::TravelTo { destination, mode }]
+        }
+        fn on_vehicle_contacts(
+            &self,
+            agent:   AgentId,
+            vehicle: TransitVehicle,
+            riders:  &[AgentId],
+            ctx:     &SimContext<'_>,
+            _rng:    &mut AgentRng,
+        ) -> Vec<Intent> {
+            self.0.lock().unwrap().push((ctx.tick, agent, vehicle, riders.len()));
+            vec![]
+        }
+    }
+
+    #[test]
+    fn fellow_riders_share_a_vehicle() {
+        // Buses along 0 → 1 → 2 (line 0) and back (line 1) every five
+        // minutes from midnight, a minute a hop.
+        let net = line_network();
+        let line = TransitLine::new("A", 300).stop(NodeId(0), 0).stop(NodeId(1), 60).stop(NodeId(2), 60);
+        let router = TransitRouter::new(&net, vec![line.clone(), line.reversed()]).unwrap();
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (store, rngs) = small_store(5);
+        let mut sim = SimBuilder::new(minute_config(10), store, rngs, Rider(Arc::clone(&log)), router)
+            .network(net)
+            .initial_positions(vec![NodeId(0), NodeId(0), NodeId(1), NodeId(0), NodeId(2)])
+            .edge_contacts(true)
+            .build()
+            .unwrap();
+        // 0 and 1 catch the 00:05 bus at stop 0 and 2 the same bus at stop 1
+        // (00:06), while 3 walks alongside and 4 rides the 00:05 back.
+        for (tick, agent) in [(1, 0), (1, 1), (1, 3), (1, 4), (3, 2)] {
+            sim.wake_queue.push(Tick(tick), AgentId(agent));
+        }
+        sim.run(&mut NoopObserver).unwrap();
+
+        let log = log.lock().unwrap();
+        let bus = TransitVehicle { line: 0, departure_ms: 300_000 };
+        assert!(log.iter().all(|&(_, agent, vehicle, _)| agent.0 < 3 && vehicle == bus), "{log:?}");
+        // 0 and 1 ride from tick 2; all three are aboard at tick 5, whichever
+        // edge each is estimated on.
+        assert_eq!((log[0].0, log[0].1, log[0].3), (Tick(2), AgentId(0), 2));
+        let at_5: Vec<_> = log.iter().filter(|e| e.0 == Tick(5)).map(|e| (e.1, e.3)).collect();
+        assert_eq!(at_5, vec![(AgentId(0), 3), (AgentId(1), 3), (AgentId(2), 3)]);
+    }
+}
+
+// ── Behavior metrics ──────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod metrics_tests {
+    use super::*;
+    use crate::TickMetrics;
+
+    /// Every woken agent counts itself and samples its own id, then wakes
+    /// again next tick.
+    struct Emit;
+    impl BehaviorModel for Emit {
+        fn replan(&self, agent: AgentId, ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+            vec![
+                Intent::Count { name: "woken", delta: 1 },
+                Intent::Sample { name: "id", value: agent.0 as f64 },
+                Intent::WakeAt(ctx.tick + 1),
+            ]
+        }
+    }
+
+    fn emitting_sim(n: usize, ticks: u64) -> crate::Sim<Emit, DijkstraRouter> {
+        let (store, rngs) = small_store(n);
+        let mut sim = SimBuilder::new(test_config(ticks), store, rngs, Emit, DijkstraRouter)
+            .build()
+            .unwrap();
+        for i in 0..n {
+            sim.wake_queue.push(Tick(0), AgentId(i as u32));
+        }
+        sim
+    }
+
+    #[test]
+    fn counts_and_samples_reduced_per_tick() {
+        let mut sim = emitting_sim(4, 3);
+        sim.run_ticks(1, &mut NoopObserver).unwrap();
+
+        assert_eq!(sim.metrics.count("woken"), 4);
+        assert_eq!(sim.metrics.count("missing"), 0);
+        let id = sim.metrics.sample("id").unwrap();
+        assert_eq!(id.count, 4);
+        assert_eq!(id.sum, 6.0);
+        assert_eq!((id.min, id.max), (0.0, 3.0));
+        assert_eq!(id.mean(), 1.5);
+    }
+
+    #[test]
+    fn totals_accumulate_and_tick_metrics_reset() {
+        let mut sim = emitting_sim(2, 5);
+        sim.run(&mut NoopObserver).unwrap();
+        assert_eq!(sim.metrics.count("woken"), 2, "last tick only");
+        assert_eq!(sim.metric_totals.count("woken"), 10);
+        assert_eq!(sim.metric_totals.sample("id").unwrap().count, 10);
+    }
+
+    #[test]
+    fn observer_receives_metrics_every_tick() {
+        struct Collect(Vec<u64>);
+        impl SimObserver for Collect {
+            fn on_metrics(&mut self, _t: Tick, m: &TickMetrics) {
+                self.0.push(m.count("woken"));
+            }
+        }
+        let mut sim = emitting_sim(3, 3);
+        let mut obs = Collect(Vec::new());
+        sim.run(&mut obs).unwrap();
+        assert_eq!(obs.0, vec![3, 3, 3]);
+    }
+
+    #[test]
+    fn merge_combines_counters_and_samples() {
+        let mut a = TickMetrics::default();
+        a.add_count("x", 2);
+        a.add_sample("s", 1.0);
+        let mut b = TickMetrics::default();
+        b.add_count("x", 3);
+        b.add_count("y", 1);
+        b.add_sample("s", -4.0);
+        a.merge(&b);
+        assert_eq!(a.count("x"), 5);
+        assert_eq!(a.count("y"), 1);
+        let s = a.sample("s").unwrap();
+        assert_eq!((s.count, s.sum, s.min, s.max), (2, -3.0, -4.0, 1.0));
+        a.clear();
+        assert!(a.is_empty());
+    }
+}
+
+// ── Tick stats ────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+    use crate::TickStats;
+
+    /// At tick 0 both agents message each other, count once, and wake at
+    /// tick 2; agent 0 also travels to node 2.
+    struct TravelAndChat;
+    impl BehaviorModel for TravelAndChat {
+        fn replan(&self, agent: AgentId, ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+            if ctx.tick > Tick(0) {
+                return vec![];
+            }
+            let mut intents = vec![
+                Intent::SendMessage { to: AgentId(1 - agent.0), payload: vec![1] },
+                Intent::Count { name: "chats", delta: 1 },
+                Intent::WakeAt(Tick(2)),
+            ];
+            if agent == AgentId(0) {
+                intents.push(Intent::TravelTo { destination: NodeId(2), mode: TransportMode::Car });
+            }
+            intents
+        }
+    }
+
+    struct Collect(Vec<(Tick, TickStats)>);
+    impl SimObserver for Collect {
+        fn on_tick_stats(&mut self, tick: Tick, stats: &TickStats) {
+            self.0.push((tick, *stats));
+        }
+    }
+
+    #[test]
+    fn tick_stats_count_loop_events() {
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(3), store, rngs, TravelAndChat, DijkstraRouter)
+            .network(line_network())
+            .initial_positions(vec![NodeId(0), NodeId(0)])
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(0), AgentId(0));
+        sim.wake_queue.push(Tick(0), AgentId(1));
+        let mut obs = Collect(Vec::new());
+        sim.run(&mut obs).unwrap();
+
+        let ticks: Vec<Tick> = obs.0.iter().map(|&(t, _)| t).collect();
+        assert_eq!(ticks, [Tick(0), Tick(1), Tick(2)]);
+        assert_eq!(obs.0[0].1, TickStats {
+            woken:              2,
+            in_transit:         1,
+            arrivals:           0,
+            departures:         1,
+            travel_intents:     1,
+            wake_intents:       2,
+            messages_sent:      2,
+            metric_intents:     2,
+            messages_delivered: 0,
+            contacts:           2,
+            routing_failures:   0,
+            behavior_panics:    0,
+        });
+        assert_eq!(obs.0[0].1.intents(), 7);
+        assert_eq!(obs.0[1].1, TickStats { arrivals: 1, ..TickStats::default() });
+        assert_eq!(obs.0[2].1, TickStats { woken: 2, messages_delivered: 2, ..TickStats::default() });
+        assert_eq!(sim.stats, obs.0[2].1);
+    }
+
+    #[test]
+    fn departures_reported_with_route() {
+        use dt_core::EdgeId;
+        use dt_mobility::MovementState;
+        use dt_spatial::Route;
+
+        #[derive(Default)]
+        struct Departures(Vec<(Tick, AgentId, Tick, Vec<EdgeId>)>);
+        impl SimObserver for Departures {
+            fn on_departure(&mut self, tick: Tick, agent: AgentId, state: &MovementState, route: &Route) {
+                self.0.push((tick, agent, state.arrival_tick, route.edges.clone()));
+            }
+        }
+
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(3), store, rngs, TravelAndChat, DijkstraRouter)
+            .network(line_network())
+            .initial_positions(vec![NodeId(0), NodeId(0)])
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(0), AgentId(0));
+        sim.wake_queue.push(Tick(0), AgentId(1));
+        let mut obs = Departures::default();
+        sim.run(&mut obs).unwrap();
+
+        let route: Vec<EdgeId> = sim.network.out_edges(NodeId(0))
+            .chain(sim.network.out_edges(NodeId(1)).filter(|&e| sim.network.edge_to[e.index()] == NodeId(2)))
+            .collect();
+        assert_eq!(obs.0, [(Tick(0), AgentId(0), Tick(1), route)]);
+        assert!(sim.departures.is_empty());
+    }
+
+    #[test]
+    fn phase_timings_reported_between_stats_and_tick_end() {
+        use crate::PhaseTimings;
+
+        #[derive(Default)]
+        struct Order(Vec<(&'static str, Tick)>, Vec<PhaseTimings>);
+        impl SimObserver for Order {
+            fn on_tick_stats(&mut self, tick: Tick, _stats: &TickStats) {
+                self.0.push(("stats", tick));
+            }
+            fn on_phase_timings(&mut self, tick: Tick, timings: &PhaseTimings) {
+                self.0.push(("timings", tick));
+                self.1.push(*timings);
+            }
+            fn on_tick_end(&mut self, tick: Tick, _woken: usize) {
+                self.0.push(("end", tick));
+            }
+        }
+
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(2), store, rngs, TravelAndChat, DijkstraRouter)
+            .network(line_network())
+            .initial_positions(vec![NodeId(0), NodeId(0)])
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(0), AgentId(0));
+        sim.wake_queue.push(Tick(0), AgentId(1));
+        let mut obs = Order::default();
+        sim.run(&mut obs).unwrap();
+
+        assert_eq!(obs.0, [
+            ("stats", Tick(0)), ("timings", Tick(0)), ("end", Tick(0)),
+            ("stats", Tick(1)), ("timings", Tick(1)), ("end", Tick(1)),
+        ]);
+        for timings in &obs.1 {
+            let phases: std::time::Duration = timings.phases().iter().map(|&(_, d)| d).sum();
+            assert!(phases <= timings.total);
+        }
+        assert!(obs.1[0].total > std::time::Duration::ZERO);
+        assert_eq!(sim.timings, obs.1[1]);
+    }
+}
+
+// ── Async run (feature: tokio) ────────────────────────────────────────────────
+
+#[cfg(all(test, feature = "tokio"))]
+mod async_tests {
+    use super::*;
+    use crate::{CancellationToken, SimError};
+
+    fn block_on<F: std::future::Future>(f: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(f)
+    }
+
+    #[test]
+    fn run_async_reaches_end_tick() {
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(10), store, rngs, NoopBehavior, DijkstraRouter)
+            .build()
+            .unwrap();
+        block_on(sim.run_async(&mut NoopObserver, &CancellationToken::new())).unwrap();
+        assert_eq!(sim.clock.current_tick, Tick(10));
+    }
+
+    #[test]
+    fn run_async_stops_on_cancel_and_resumes() {
+        /// Cancels the token at the end of tick 3.
+        struct CancelAt3 {
+            token: CancellationToken,
+            ended: Option<Tick>,
+        }
+        impl SimObserver for CancelAt3 {
+            fn on_tick_end(&mut self, t: Tick, _w: usize) {
+                if t == Tick(3) {
+                    self.token.cancel();
+                }
+            }
+            fn on_sim_end(&mut self, t: Tick) { self.ended = Some(t); }
+        }
+
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(10), store, rngs, NoopBehavior, DijkstraRouter)
+            .build()
+            .unwrap();
+        let token = CancellationToken::new();
+        let mut obs = CancelAt3 { token: token.clone(), ended: None };
+
+        let result = block_on(sim.run_async(&mut obs, &token));
+        assert!(matches!(result, Err(SimError::Cancelled { tick: Tick(4) })), "got {result:?}");
+        assert_eq!(obs.ended, Some(Tick(4)), "on_sim_end fires on cancel");
+
+        sim.run(&mut NoopObserver).unwrap();
+        assert_eq!(sim.clock.current_tick, Tick(10));
+    }
+
+    #[test]
+    fn run_async_yields_between_ticks() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let seen = Arc::new(AtomicBool::new(false));
+
+        struct CheckAt2(Arc<AtomicBool>, Arc<AtomicBool>);
+        impl SimObserver for CheckAt2 {
+            fn on_tick_start(&mut self, t: Tick) {
+                if t == Tick(2) {
+                    self.1.store(self.0.load(Ordering::SeqCst), Ordering::SeqCst);
+                }
+            }
+        }
+
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, NoopBehavior, DijkstraRouter)
+            .build()
+            .unwrap();
+        let mut obs = CheckAt2(Arc::clone(&flag), Arc::clone(&seen));
+        block_on(async {
+            // Single-threaded runtime: this task only runs if run_async yields.
+            let f = Arc::clone(&flag);
+            tokio::spawn(async move { f.store(true, Ordering::SeqCst) });
+            sim.run_async(&mut obs, &CancellationToken::new()).await.unwrap();
+        });
+        assert!(seen.load(Ordering::SeqCst), "spawned task should run before tick 2");
+    }
+}
+
+// ── Snapshot triggers ─────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod trigger_tests {
+    use super::*;
+    use dt_mobility::MobilityStore;
+
+    struct SnapshotTicks(Vec<Tick>);
+    impl SimObserver for SnapshotTicks {
+        fn on_snapshot(&mut self, t: Tick, _m: &MobilityStore, _a: &dt_agent::AgentStore) {
+            self.0.push(t);
+        }
+    }
+
+    fn config_no_interval(total_ticks: u64) -> SimConfig {
+        SimConfig { output_interval_ticks: 0, ..test_config(total_ticks) }
+    }
+
+    #[test]
+    fn trigger_fires_on_matching_ticks() {
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(config_no_interval(6), store, rngs, NoopBehavior, DijkstraRouter)
+            .snapshot_when(|ctx| ctx.woken > 0)
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(2), AgentId(0));
+        sim.wake_queue.push(Tick(4), AgentId(1));
+
+        let mut obs = SnapshotTicks(Vec::new());
+        sim.run(&mut obs).unwrap();
+        assert_eq!(obs.0, vec![Tick(2), Tick(4)]);
+    }
+
+    #[test]
+    fn triggers_combine_with_interval_without_duplicates() {
+        let (store, rngs) = small_store(1);
+        let config = SimConfig { output_interval_ticks: 3, ..test_config(6) };
+        let mut sim = SimBuilder::new(config, store, rngs, NoopBehavior, DijkstraRouter)
+            .snapshot_when(|ctx| ctx.tick == Tick(3) || ctx.tick == Tick(4))
+            .build()
+            .unwrap();
+        let mut obs = SnapshotTicks(Vec::new());
+        sim.run(&mut obs).unwrap();
+        assert_eq!(obs.0, vec![Tick(0), Tick(3), Tick(4)]);
+    }
+
+    #[test]
+    fn every_trigger_sees_every_tick() {
+        let seen = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&seen);
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(config_no_interval(5), store, rngs, NoopBehavior, DijkstraRouter)
+            .snapshot_when(|_| true)
+            .snapshot_when(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                false
+            })
+            .build()
+            .unwrap();
+        let mut obs = SnapshotTicks(Vec::new());
+        sim.run(&mut obs).unwrap();
+        assert_eq!(obs.0.len(), 5);
+        assert_eq!(seen.load(Ordering::SeqCst), 5, "second trigger not short-circuited");
+    }
+
+    #[test]
+    fn stateful_threshold_crossing() {
+        struct CountAgents;
+        impl BehaviorModel for CountAgents {
+            fn replan(&self, _a: AgentId, ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+                vec![Intent::Count { name: "infected", delta: 1 }, Intent::WakeAt(ctx.tick + 1)]
+            }
+        }
+        let (store, rngs) = small_store(3);
+        let mut was_high = false;
+        let mut sim = SimBuilder::new(config_no_interval(6), store, rngs, CountAgents, DijkstraRouter)
+            .snapshot_when(move |ctx| {
+                let high = ctx.metrics.count("infected") >= 2;
+                let crossed = high && !was_high;
+                was_high = high;
+                crossed
+            })
+            .build()
+            .unwrap();
+        // One agent from tick 0, a second from tick 2, a third from tick 3.
+        sim.wake_queue.push(Tick(0), AgentId(0));
+        sim.wake_queue.push(Tick(2), AgentId(1));
+        sim.wake_queue.push(Tick(3), AgentId(2));
+
+        let mut obs = SnapshotTicks(Vec::new());
+        sim.run(&mut obs).unwrap();
+        assert_eq!(obs.0, vec![Tick(2)], "fires only on the crossing tick");
+    }
+}
+
+// ── Idle ticks ────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod quiescence_tests {
+    use super::*;
+    use crate::{IdlePolicy, PhaseContext, PhasePoint, TickPhase};
+
+    #[derive(Default)]
+    struct TickLog {
+        processed: Vec<u64>,
+        skipped:   Vec<(u64, u64)>,
+    }
+    impl SimObserver for TickLog {
+        fn on_tick_start(&mut self, t: Tick) {
+            self.processed.push(t.0);
+        }
+        fn on_ticks_skipped(&mut self, from: Tick, to: Tick) {
+            self.skipped.push((from.0, to.0));
+        }
+    }
+
+    fn sim_with(policy: IdlePolicy, wakes: &[u64]) -> crate::Sim<NoopBehavior, DijkstraRouter> {
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(100), store, rngs, NoopBehavior, DijkstraRouter)
+            .idle_policy(policy)
+            .build()
+            .unwrap();
+        for &t in wakes {
+            sim.wake_queue.push(Tick(t), AgentId(0));
+        }
+        sim
+    }
+
+    #[test]
+    fn run_all_processes_every_tick() {
+        let mut sim = sim_with(IdlePolicy::RunAll, &[2]);
+        let mut log = TickLog::default();
+        sim.run(&mut log).unwrap();
+        assert_eq!(log.processed.len(), 100);
+        assert!(log.skipped.is_empty());
+        assert_eq!(sim.skipped_ticks, 0);
+    }
+
+    #[test]
+    fn end_when_quiescent_stops_after_last_wake() {
+        let mut sim = sim_with(IdlePolicy::EndWhenQuiescent, &[2, 10]);
+        let mut log = TickLog::default();
+        sim.run(&mut log).unwrap();
+        // Gaps between wakes are still walked; only the idle tail is skipped.
+        assert_eq!(log.processed, (0..=10).collect::<Vec<_>>());
+        assert_eq!(log.skipped, vec![(11, 100)]);
+        assert_eq!(sim.skipped_ticks, 89);
+        assert_eq!(sim.clock.current_tick, Tick(100));
+    }
+
+    #[test]
+    fn fast_forward_jumps_between_wakes() {
+        let mut sim = sim_with(IdlePolicy::FastForward, &[5, 50]);
+        let mut log = TickLog::default();
+        sim.run(&mut log).unwrap();
+        assert_eq!(log.processed, vec![5, 50]);
+        assert_eq!(log.skipped, vec![(0, 5), (6, 50), (51, 100)]);
+        assert_eq!(sim.skipped_ticks, 98);
+        assert_eq!(sim.clock.current_tick, Tick(100));
+    }
+
+    #[test]
+    fn agents_in_transit_block_skipping() {
+        let (store, rngs) = small_store(1);
+        let config = test_config(100);
+        let mut sim = SimBuilder::new(config.clone(), store, rngs, NoopBehavior, DijkstraRouter)
+            .network(line_network())
+            .idle_policy(IdlePolicy::FastForward)
+            .build()
+            .unwrap();
+        sim.mobility.place(AgentId(0), NodeId(0), Tick(0));
+        let arrival = sim
+            .mobility
+            .begin_travel(AgentId(0), NodeId(2), TransportMode::Car, Tick(0), config.tick_duration_secs, &sim.network)
+            .unwrap();
+
+        let mut log = TickLog::default();
+        sim.run(&mut log).unwrap();
+        let walked: Vec<u64> = (0..=arrival.0).collect();
+        assert_eq!(&log.processed[..walked.len()], &walked[..]);
+        assert_eq!(sim.clock.current_tick, Tick(100));
+        assert!(sim.skipped_ticks > 0);
+    }
+
+    #[test]
+    fn registered_phases_disable_skipping() {
+        struct Nothing;
+        impl TickPhase for Nothing {
+            fn run(&mut self, _ctx: &mut PhaseContext<'_>) -> Result<(), String> {
+                Ok(())
+            }
+        }
+        let (store, rngs) = small_store(1);
+        let mut sim = SimBuilder::new(test_config(20), store, rngs, NoopBehavior, DijkstraRouter)
+            .phase(PhasePoint::AfterApply, Nothing)
+            .idle_policy(IdlePolicy::FastForward)
+            .build()
+            .unwrap();
+        let mut log = TickLog::default();
+        sim.run(&mut log).unwrap();
+        assert_eq!(log.processed.len(), 20);
+        assert_eq!(sim.skipped_ticks, 0);
+    }
+
+    #[test]
+    fn run_ticks_never_skips() {
+        let mut sim = sim_with(IdlePolicy::FastForward, &[]);
+        let mut log = TickLog::default();
+        sim.run_ticks(5, &mut log).unwrap();
+        assert_eq!(log.processed, vec![0, 1, 2, 3, 4]);
+        assert_eq!(sim.skipped_ticks, 0);
+    }
+}
+
+// ── Tracing ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod trace_tests {
+    use super::*;
+    use crate::{SimError, TraceEvent};
+
+    #[derive(Default)]
+    struct TraceLog(Vec<TraceEvent>);
+    impl SimObserver for TraceLog {
+        fn on_trace(&mut self, event: &TraceEvent) {
+            self.0.push(event.clone());
+        }
+    }
+
+    /// At tick 0 every agent travels to node 2 and messages agent 1.
+    struct TravelAndTell;
+    impl BehaviorModel for TravelAndTell {
+        fn replan(&self, _a: AgentId, ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+            if ctx.tick > Tick(0) {
+                return vec![];
+            }
+            vec![
+                Intent::TravelTo { destination: NodeId(2), mode: TransportMode::Car },
+                Intent::SendMessage { to: AgentId(1), payload: vec![7, 7, 7] },
+                Intent::WakeAt(Tick(3)),
+            ]
+        }
+    }
+
+    fn traced_sim(traced: &[AgentId]) -> crate::Sim<TravelAndTell, DijkstraRouter> {
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, TravelAndTell, DijkstraRouter)
+            .network(line_network())
+            .initial_positions(vec![NodeId(0), NodeId(0)])
+            .trace_agents(traced.iter().copied())
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(0), AgentId(0));
+        sim.wake_queue.push(Tick(0), AgentId(1));
+        sim
+    }
+
+    #[test]
+    fn follows_only_the_traced_agent() {
+        let mut sim = traced_sim(&[AgentId(0)]);
+        let mut log = TraceLog::default();
+        sim.run(&mut log).unwrap();
+
+        let a = AgentId(0);
+        assert_eq!(log.0[0], TraceEvent::Woke { tick: Tick(0), agent: a });
+        assert!(matches!(log.0[1], TraceEvent::Intents { agent, ref intents, .. } if agent == a && intents.len() == 3));
+        assert!(matches!(
+            log.0[2],
+            TraceEvent::Departed { agent, destination: NodeId(2), arrival: Tick(1), .. } if agent == a
+        ));
+        assert_eq!(log.0[3], TraceEvent::MessageSent { tick: Tick(0), from: a, to: AgentId(1), bytes: 3 });
+        assert_eq!(log.0[4], TraceEvent::WakeScheduled { tick: Tick(0), agent: a, at: Tick(3) });
+        assert_eq!(log.0[5], TraceEvent::Arrived { tick: Tick(1), agent: a, node: NodeId(2) });
+        assert_eq!(log.0[6], TraceEvent::Woke { tick: Tick(3), agent: a });
+        // Agent 1's own events never appear.
+        assert!(log.0.iter().all(|e| !matches!(e, TraceEvent::Woke { agent, .. } if *agent == AgentId(1))));
+    }
+
+    #[test]
+    fn traced_recipient_sees_messages_both_ways() {
+        let mut sim = traced_sim(&[AgentId(1)]);
+        let mut log = TraceLog::default();
+        sim.run(&mut log).unwrap();
+
+        // Agent 0's message to agent 1 is recorded when sent …
+        assert!(log.0.contains(&TraceEvent::MessageSent {
+            tick: Tick(0), from: AgentId(0), to: AgentId(1), bytes: 3,
+        }));
+        // … and both messages are delivered at agent 1's next wake.
+        let delivered: Vec<AgentId> = log
+            .0
+            .iter()
+            .filter_map(|e| match e {
+                TraceEvent::MessageDelivered { tick: Tick(3), from, .. } => Some(*from),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(delivered, vec![AgentId(0), AgentId(1)]);
+    }
+
+    #[test]
+    fn events_are_in_tick_order() {
+        let mut sim = traced_sim(&[AgentId(0), AgentId(1)]);
+        let mut log = TraceLog::default();
+        sim.run(&mut log).unwrap();
+        assert!(log.0.windows(2).all(|w| w[0].tick() <= w[1].tick()));
+        assert!(sim.trace_buffer.is_empty());
+    }
+
+    #[test]
+    fn routing_failure_is_traced() {
+        // Three nodes, no roads: node 2 is unreachable.
+        let mut net = RoadNetworkBuilder::new();
+        for i in 0..3 {
+            net.add_node(GeoPoint { lat: 0.0, lon: i as f32 * 0.01 });
+        }
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(2), store, rngs, TravelAndTell, DijkstraRouter)
+            .network(net.build())
+            .initial_positions(vec![NodeId(0), NodeId(0)])
+            .failure_policy(crate::FailurePolicy::CollectAndReport)
+            .trace_agents([AgentId(0)])
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(0), AgentId(0));
+        let mut log = TraceLog::default();
+        sim.run(&mut log).unwrap();
+        assert!(log.0.iter().any(|e| matches!(e, TraceEvent::Failed { agent: AgentId(0), .. })));
+    }
+
+    #[test]
+    fn no_tracing_by_default() {
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(5), store, rngs, TravelAndTell, DijkstraRouter)
+            .network(line_network())
+            .initial_positions(vec![NodeId(0), NodeId(0)])
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(0), AgentId(0));
+        let mut log = TraceLog::default();
+        sim.run(&mut log).unwrap();
+        assert!(log.0.is_empty());
+    }
+
+    #[test]
+    fn out_of_range_agent_rejected() {
+        let (store, rngs) = small_store(2);
+        let result = SimBuilder::new(test_config(5), store, rngs, NoopBehavior, DijkstraRouter)
+            .trace_agents([AgentId(5)])
+            .build();
+        assert!(matches!(result, Err(SimError::Config(_))));
+    }
+}
+
+// ── Per-tick wake cap ─────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod woken_cap_tests {
+    use super::*;
+    use crate::{IdlePolicy, SimError};
+
+    type Visits = Arc<Mutex<Vec<(u64, u32)>>>;
+
+    /// Records `(tick, agent)` for every replan.
+    struct Recorder(Visits);
+    impl BehaviorModel for Recorder {
+        fn replan(&self, agent: AgentId, ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+            self.0.lock().unwrap().push((ctx.tick.0, agent.0));
+            vec![]
+        }
+    }
+
+    fn capped_sim(n: usize, cap: usize, wakes: &[(u64, u32)]) -> (crate::Sim<Recorder, DijkstraRouter>, Visits) {
+        let visits = Visits::default();
+        let (store, rngs) = small_store(n);
+        let mut sim = SimBuilder::new(test_config(10), store, rngs, Recorder(visits.clone()), DijkstraRouter)
+            .max_woken_per_tick(cap)
+            .build()
+            .unwrap();
+        for &(t, a) in wakes {
+            sim.wake_queue.push(Tick(t), AgentId(a));
+        }
+        (sim, visits)
+    }
+
+    #[test]
+    fn burst_is_spread_in_agent_id_order() {
+        let (mut sim, visits) = capped_sim(5, 2, &[(0, 4), (0, 3), (0, 2), (0, 1), (0, 0)]);
+        sim.run(&mut NoopObserver).unwrap();
+        assert_eq!(
+            *visits.lock().unwrap(),
+            vec![(0, 0), (0, 1), (1, 2), (1, 3), (2, 4)]
+        );
+    }
+
+    #[test]
+    fn deferred_agents_go_before_new_wakes() {
+        let (mut sim, visits) = capped_sim(5, 2, &[(0, 2), (0, 3), (0, 4), (1, 0), (1, 1)]);
+        sim.run(&mut NoopObserver).unwrap();
+        assert_eq!(
+            *visits.lock().unwrap(),
+            vec![(0, 2), (0, 3), (1, 4), (1, 0), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn deferred_agent_woken_again_runs_once() {
+        let (mut sim, visits) = capped_sim(2, 1, &[(0, 0), (0, 1), (1, 1)]);
+        sim.run(&mut NoopObserver).unwrap();
+        assert_eq!(*visits.lock().unwrap(), vec![(0, 0), (1, 1)]);
+        assert!(sim.deferred.is_empty());
+    }
+
+    #[test]
+    fn uncapped_processes_everyone_at_once() {
+        let visits = Visits::default();
+        let (store, rngs) = small_store(3);
+        let mut sim = SimBuilder::new(test_config(3), store, rngs, Recorder(visits.clone()), DijkstraRouter)
+            .build()
+            .unwrap();
+        for a in 0..3 {
+            sim.wake_queue.push(Tick(0), AgentId(a));
+        }
+        sim.run(&mut NoopObserver).unwrap();
+        assert!(visits.lock().unwrap().iter().all(|&(t, _)| t == 0));
+    }
+
+    #[test]
+    fn deferred_agents_block_idle_skipping() {
+        let visits = Visits::default();
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(10), store, rngs, Recorder(visits.clone()), DijkstraRouter)
+            .max_woken_per_tick(1)
+            .idle_policy(IdlePolicy::FastForward)
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(0), AgentId(0));
+        sim.wake_queue.push(Tick(0), AgentId(1));
+        sim.run(&mut NoopObserver).unwrap();
+        assert_eq!(*visits.lock().unwrap(), vec![(0, 0), (1, 1)]);
+        assert_eq!(sim.skipped_ticks, 8);
+    }
+
+    #[test]
+    fn zero_cap_rejected() {
+        let (store, rngs) = small_store(1);
+        let result = SimBuilder::new(test_config(1), store, rngs, NoopBehavior, DijkstraRouter)
+            .max_woken_per_tick(0)
+            .build();
+        assert!(matches!(result, Err(SimError::Config(_))));
+    }
+}
+
+// ── Determinism checks ────────────────────────────────────────────────────────
+
+/// Wanders between the three `line_network` nodes at random intervals.
+#[cfg(test)]
+struct RandomWalk;
+
+#[cfg(test)]
+impl BehaviorModel for RandomWalk {
+    fn replan(&self, _a: AgentId, ctx: &SimContext<'_>, rng: &mut AgentRng) -> Vec<Intent> {
+        let node = NodeId(rng.gen_range(0..3u32));
+        vec![
+            Intent::TravelTo { destination: node, mode: TransportMode::Car },
+            Intent::WakeAt(ctx.tick + rng.gen_range(2..5u64)),
+            Intent::Sample { name: "draw", value: rng.random::<f64>() },
+        ]
+    }
+}
+
+#[cfg(test)]
+fn random_walk_sim(n: usize) -> crate::SimResult<crate::Sim<RandomWalk, DijkstraRouter>> {
+    let (store, rngs) = small_store(n);
+    let mut sim = SimBuilder::new(test_config(48), store, rngs, RandomWalk, DijkstraRouter)
+        .network(line_network())
+        .initial_positions(vec![NodeId(1); n])
+        .build()?;
+    for a in 0..n as u32 {
+        sim.wake_queue.push(Tick(u64::from(a) % 3), AgentId(a));
+    }
+    Ok(sim)
+}
+
+#[cfg(test)]
+mod equivalence_tests {
+    use super::*;
+    use crate::{first_divergence, tick_hashes};
+
+    #[test]
+    fn identical_runs_hash_identically() {
+        let a = tick_hashes(&mut random_walk_sim(40).unwrap()).unwrap();
+        let b = tick_hashes(&mut random_walk_sim(40).unwrap()).unwrap();
+        assert_eq!(a.len(), 48);
+        assert_eq!(first_divergence(&a, &b), None);
+    }
+
+    #[test]
+    fn perturbation_found_at_first_divergent_tick() {
+        let baseline = tick_hashes(&mut random_walk_sim(40).unwrap()).unwrap();
+
+        let mut sim = random_walk_sim(40).unwrap();
+        sim.run_ticks(10, &mut NoopObserver).unwrap();
+        sim.wake_queue.push(Tick(20), AgentId(7));
+        let mut perturbed: Vec<(Tick, u64)> = baseline[..10].to_vec();
+        perturbed.extend(tick_hashes(&mut sim).unwrap());
+
+        assert_eq!(first_divergence(&baseline, &perturbed), Some(Tick(10)));
+    }
+
+    #[test]
+    fn shorter_sequence_diverges_where_it_ends() {
+        let a = [(Tick(0), 1), (Tick(1), 2)];
+        assert_eq!(first_divergence(&a, &a[..1]), Some(Tick(1)));
+        assert_eq!(first_divergence(&a[..1], &a), Some(Tick(1)));
+    }
+
+    #[test]
+    fn hash_ignores_message_queue_insertion_order() {
+        let mut a = random_walk_sim(3).unwrap();
+        let mut b = random_walk_sim(3).unwrap();
+        a.message_queue.insert(AgentId(0), vec![(AgentId(1), vec![1])]);
+        a.message_queue.insert(AgentId(2), vec![(AgentId(1), vec![2])]);
+        b.message_queue.insert(AgentId(2), vec![(AgentId(1), vec![2])]);
+        b.message_queue.insert(AgentId(0), vec![(AgentId(1), vec![1])]);
+        assert_eq!(a.state_hash(), b.state_hash());
+    }
+}
+
+#[cfg(test)]
+mod golden_tests {
+    use super::*;
+    use crate::golden::BLESS_ENV;
+    use crate::{GoldenOutcome, GoldenTrace, SimError, check_golden};
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("dt-sim-golden-{}-{name}.golden", std::process::id()))
+    }
+
+    #[test]
+    fn random_walk_matches_committed_trace() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("golden/random_walk.golden");
+        let outcome = check_golden(&path, &mut random_walk_sim(40).unwrap()).unwrap();
+        if std::env::var_os(BLESS_ENV).is_none() {
+            assert_eq!(outcome, GoldenOutcome::Matched);
+        }
+    }
+
+    #[test]
+    fn stable_hash_tracks_state_hash() {
+        let mut a = random_walk_sim(5).unwrap();
+        let b = random_walk_sim(5).unwrap();
+        assert_eq!(a.stable_state_hash(), b.stable_state_hash());
+        a.wake_queue.push(Tick(30), AgentId(1));
+        assert_ne!(a.stable_state_hash(), b.stable_state_hash());
+    }
+
+    #[test]
+    fn stable_hasher_digests_fixed_width_le_bytes() {
+        use std::hash::Hasher;
+        use crate::StableHasher;
+
+        // Published FNV-1a test vectors.
+        assert_eq!(StableHasher::default().finish(), 0xcbf2_9ce4_8422_2325);
+        let mut h = StableHasher::default();
+        h.write(b"a");
+        assert_eq!(h.finish(), 0xaf63_dc4c_8601_ec8c);
+
+        // Integers are their little-endian bytes, `usize` always 8 of them.
+        let mut bytes = StableHasher::default();
+        bytes.write(&[0x78, 0x56, 0x34, 0x12, 7, 0, 0, 0, 0, 0, 0, 0]);
+        let mut ints = StableHasher::default();
+        ints.write_u32(0x1234_5678);
+        ints.write_usize(7);
+        assert_eq!(ints.finish(), bytes.finish());
+    }
+
+    #[test]
+    fn trace_round_trips_through_text() {
+        let trace = GoldenTrace::record("walk", &mut random_walk_sim(10).unwrap()).unwrap();
+        assert_eq!(trace.ticks.len(), 48);
+        assert!(trace.ticks.iter().any(|t| t.departures > 0));
+
+        let text = trace.to_string();
+        assert!(text.starts_with("# dt-sim golden trace v1: walk\n"));
+        assert_eq!(GoldenTrace::parse(&text).unwrap(), trace);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_lines() {
+        assert!(GoldenTrace::parse("0 00 1 1 0 1 0 0\n").is_err());
+        let err = GoldenTrace::parse("# dt-sim golden trace v1: x\n0 00 1 1\n").unwrap_err();
+        assert!(err.starts_with("line 2"), "{err}");
+        assert!(GoldenTrace::parse("# dt-sim golden trace v1: x\n0 zz 1 1 0 1 0 0\n").is_err());
+    }
+
+    #[test]
+    fn perturbation_reported_at_first_divergent_tick() {
+        let expected = GoldenTrace::record("walk", &mut random_walk_sim(40).unwrap()).unwrap();
+
+        let mut sim = random_walk_sim(40).unwrap();
+        sim.run_ticks(10, &mut NoopObserver).unwrap();
+        sim.wake_queue.push(Tick(20), AgentId(7));
+        let mut actual = GoldenTrace::record("walk", &mut sim).unwrap();
+        actual.ticks.splice(0..0, expected.ticks[..10].iter().copied());
+
+        let d = expected.first_divergence(&actual).unwrap();
+        assert_eq!(d.tick, Tick(10));
+        assert!(d.to_string().starts_with("hash "), "{d}");
+        assert_eq!(expected.first_divergence(&expected), None);
+    }
+
+    #[test]
+    fn truncated_trace_diverges_where_it_ends() {
+        let expected = GoldenTrace::record("walk", &mut random_walk_sim(5).unwrap()).unwrap();
+        let mut actual = expected.clone();
+        actual.ticks.truncate(30);
+
+        let d = expected.first_divergence(&actual).unwrap();
+        assert_eq!((d.tick, d.actual), (Tick(30), None));
+        assert_eq!(d.to_string(), "run ended before the golden trace");
+        assert_eq!(actual.first_divergence(&expected).unwrap().expected, None);
+    }
+
+    #[test]
+    fn check_golden_records_then_compares() {
+        let path = temp_path("check");
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(check_golden(&path, &mut random_walk_sim(20).unwrap()).unwrap(), GoldenOutcome::Recorded);
+        assert_eq!(GoldenTrace::read(&path).unwrap().name, format!("dt-sim-golden-{}-check", std::process::id()));
+        assert_eq!(check_golden(&path, &mut random_walk_sim(20).unwrap()).unwrap(), GoldenOutcome::Matched);
+
+        let err = check_golden(&path, &mut random_walk_sim(21).unwrap()).unwrap_err();
+        let _ = std::fs::remove_file(&path);
+        match err {
+            SimError::Diverged { tick, detail } => {
+                assert_eq!(tick, Tick(0));
+                assert!(detail.contains(BLESS_ENV), "{detail}");
+            }
+            other => panic!("expected Diverged, got {other}"),
+        }
+    }
+
+    #[test]
+    fn unreadable_trace_is_a_golden_error() {
+        let path = temp_path("bad");
+        std::fs::write(&path, "not a trace\n").unwrap();
+        let err = GoldenTrace::read(&path).unwrap_err();
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(err, SimError::Golden { .. }), "{err}");
+    }
+}
+
+#[cfg(all(test, feature = "parallel"))]
+mod thread_equivalence_tests {
+    use super::*;
+    use crate::{check_thread_equivalence, SimError};
+
+    #[test]
+    fn thread_counts_agree() {
+        check_thread_equivalence(|| random_walk_sim(200), &[1, 2, 4]).unwrap();
+    }
+
+    #[test]
+    fn shared_state_behavior_is_caught() {
+        use std::sync::atomic::AtomicU64;
+
+        /// Destination depends on global call order — not thread-safe.
+        struct Racy(Arc<AtomicU64>);
+        impl BehaviorModel for Racy {
+            fn replan(&self, _a: AgentId, ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+                std::thread::yield_now();
+                let n = self.0.fetch_add(1, Ordering::SeqCst);
+                vec![
+                    Intent::TravelTo { destination: NodeId((n % 3) as u32), mode: TransportMode::Car },
+                    Intent::WakeAt(ctx.tick + 2),
+                ]
+            }
+        }
+        let make = || {
+            let (store, rngs) = small_store(400);
+            let mut sim = SimBuilder::new(test_config(24), store, rngs, Racy(Arc::default()), DijkstraRouter)
+                .network(line_network())
+                .initial_positions(vec![NodeId(1); 400])
+                .build()?;
+            for a in 0..400 {
+                sim.wake_queue.push(Tick(0), AgentId(a));
+            }
+            Ok(sim)
+        };
+        // Races are not guaranteed on every run, so retry a few times.
+        let caught = (0..20).any(|_| {
+            matches!(check_thread_equivalence(make, &[1, 8]), Err(SimError::Diverged { .. }))
+        });
+        assert!(caught);
+    }
+}
+
+// ── Intra-tick rounds ─────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod round_tests {
+    use super::*;
+    use crate::SimError;
+
+    type Received = Arc<Mutex<Vec<(u64, u32, u8)>>>;
+
+    /// Agent 0 opens with `0` to agent 1; every recipient replies with the
+    /// received value plus one.
+    struct PingPong(Received);
+    impl BehaviorModel for PingPong {
+        fn replan(&self, agent: AgentId, _ctx: &SimContext<'_>, _r: &mut AgentRng) -> Vec<Intent> {
+            if agent == AgentId(0) {
+                vec![Intent::SendMessage { to: AgentId(1), payload: vec![0] }]
+            } else {
+                vec![]
+            }
+        }
+        fn on_message(
+            &self,
+            agent:   AgentId,
+            from:    AgentId,
+            payload: &[u8],
+            ctx:     &SimContext<'_>,
+            _rng:    &mut AgentRng,
+        ) -> Vec<Intent> {
+            self.0.lock().unwrap().push((ctx.tick.0, agent.0, payload[0]));
+            vec![Intent::SendMessage { to: from, payload: vec![payload[0] + 1] }]
+        }
+    }
+
+    fn ping_pong(rounds: u32) -> (crate::Sim<PingPong, DijkstraRouter>, Received) {
+        let received = Received::default();
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(2), store, rngs, PingPong(received.clone()), DijkstraRouter)
+            .intra_tick_rounds(rounds)
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(0), AgentId(0));
+        (sim, received)
+    }
+
+    #[test]
+    fn single_round_defers_delivery_to_next_wake() {
+        let (mut sim, received) = ping_pong(1);
+        sim.run(&mut NoopObserver).unwrap();
+        assert!(received.lock().unwrap().is_empty());
+        assert_eq!(sim.message_queue[&AgentId(1)].len(), 1);
+    }
+
+    #[test]
+    fn extra_rounds_exchange_within_the_tick() {
+        let (mut sim, received) = ping_pong(4);
+        sim.run(&mut NoopObserver).unwrap();
+        assert_eq!(*received.lock().unwrap(), vec![(0, 1, 0), (0, 0, 1), (0, 1, 2)]);
+        // The reply sent in the last round waits for agent 0's next wake.
+        assert_eq!(sim.message_queue[&AgentId(0)], vec![(AgentId(1), vec![3])]);
+    }
+
+    #[test]
+    fn in_transit_recipient_keeps_message_queued() {
+        let received = Received::default();
+        let (store, rngs) = small_store(2);
+        let mut sim = SimBuilder::new(test_config(1), store, rngs, PingPong(received.clone()), DijkstraRouter)
+            .network(line_network())
+            .initial_positions(vec![NodeId(0), NodeId(0)])
+            .intra_tick_rounds(3)
+            .build()
+            .unwrap();
+        sim.wake_queue.push(Tick(0), AgentId(0));
+        let config = sim.config.clone();
+        sim.mobility
+            .begin_travel(AgentId(1), NodeId(2), TransportMode::Car, Tick(0), config.tick_duration_secs, &sim.network)
+            .unwrap();
+        sim.run(&mut NoopObserver).unwrap();
+        assert!(received.lock().unwrap().is_empty());
+        assert_eq!(sim.message_queue[&AgentId(1)].len(), 1);
+    }
+
+    #[test]
+    fn zero_rounds_rejected() {
+        let (store, rngs) = small_store(1);
+        let result = SimBuilder::new(test_config(1), store, rngs, NoopBehavior, DijkstraRouter)
+            .intra_tick_rounds(0)
+            .build();
+        assert!(matches!(result, Err(SimError::Config(_))));
+    }
+}
@@ -0,0 +1,108 @@
+//! Per-agent tracing.
+//!
+//! Following one misbehaving agent through a million-agent run via position
+//! snapshots is slow going.  Instead, name the agents to follow and the sim
+//! reports everything that happens to them:
+//!
+//! ```rust,ignore
+//! struct PrintTrace;
+//! impl SimObserver for PrintTrace {
+//!     fn on_trace(&mut self, event: &TraceEvent) {
+//!         eprintln!("{event}");
+//!     }
+//! }
+//!
+//! let mut sim = SimBuilder::new(config, store, rngs, behavior, router)
+//!     .trace_agents([AgentId(4_182), AgentId(90_017)])
+//!     .build()?;
+//! sim.run(&mut PrintTrace)?;
+//! ```
+//!
+//! Events are buffered during the tick and handed to
+//! [`SimObserver::on_trace`][crate::SimObserver::on_trace] in the order they
+//! happened, just before `on_tick_end`.  Untraced agents cost one empty-set
+//! check per event site.
+
+use std::fmt;
+
+use dt_behavior::Intent;
+use dt_core::{AgentId, NodeId, Tick, TransportMode};
+
+// ── TraceEvent ────────────────────────────────────────────────────────────────
+
+/// One thing that happened to a traced agent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceEvent {
+    /// The agent was drained from the wake queue.
+    Woke { tick: Tick, agent: AgentId },
+
+    /// A pending message was handed to the agent's `on_message`.
+    MessageDelivered { tick: Tick, agent: AgentId, from: AgentId, bytes: usize },
+
+    /// Intents about to be applied for the agent, after any
+    /// [`PhasePoint::AfterIntents`][crate::PhasePoint::AfterIntents] phases.
+    Intents { tick: Tick, agent: AgentId, intents: Vec<Intent> },
+
+    /// A `WakeAt` intent put the agent back in the wake queue.
+    WakeScheduled { tick: Tick, agent: AgentId, at: Tick },
+
+    /// A `TravelTo` intent started a journey.
+    Departed {
+        tick:        Tick,
+        agent:       AgentId,
+        destination: NodeId,
+        mode:        TransportMode,
+        arrival:     Tick,
+    },
+
+    /// A message was queued; recorded if either sender or recipient is traced.
+    MessageSent { tick: Tick, from: AgentId, to: AgentId, bytes: usize },
+
+    /// The agent reached the end of its journey.
+    Arrived { tick: Tick, agent: AgentId, node: NodeId },
+
+    /// A routing error or behavior panic for the agent, as passed to the
+    /// [`FailurePolicy`][crate::FailurePolicy].
+    Failed { tick: Tick, agent: AgentId, message: String },
+}
+
+impl TraceEvent {
+    /// Tick at which the event happened.
+    pub fn tick(&self) -> Tick {
+        match *self {
+            TraceEvent::Woke { tick, .. }
+            | TraceEvent::MessageDelivered { tick, .. }
+            | TraceEvent::Intents { tick, .. }
+            | TraceEvent::WakeScheduled { tick, .. }
+            | TraceEvent::Departed { tick, .. }
+            | TraceEvent::MessageSent { tick, .. }
+            | TraceEvent::Arrived { tick, .. }
+            | TraceEvent::Failed { tick, .. } => tick,
+        }
+    }
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceEvent::Woke { tick, agent } => write!(f, "{tick} {agent}: woke"),
+            TraceEvent::MessageDelivered { tick, agent, from, bytes } => {
+                write!(f, "{tick} {agent}: received {bytes} B from {from}")
+            }
+            TraceEvent::Intents { tick, agent, intents } => {
+                write!(f, "{tick} {agent}: intents {intents:?}")
+            }
+            TraceEvent::WakeScheduled { tick, agent, at } => {
+                write!(f, "{tick} {agent}: wake scheduled at {at}")
+            }
+            TraceEvent::Departed { tick, agent, destination, mode, arrival } => {
+                write!(f, "{tick} {agent}: departed for {destination} by {mode}, arriving {arrival}")
+            }
+            TraceEvent::MessageSent { tick, from, to, bytes } => {
+                write!(f, "{tick} {from}: sent {bytes} B to {to}")
+            }
+            TraceEvent::Arrived { tick, agent, node } => write!(f, "{tick} {agent}: arrived at {node}"),
+            TraceEvent::Failed { tick, agent, message } => write!(f, "{tick} {agent}: failed: {message}"),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Predicate-based snapshot triggers.
+//!
+//! `config.output_interval_ticks` fires [`SimObserver::on_snapshot`] on a
+//! fixed cadence.  Triggers add snapshots on ticks chosen by a closure over
+//! the end-of-tick state:
+//!
+//! ```rust,ignore
+//! let mut was_high = false;
+//! let sim = SimBuilder::new(config, store, rngs, behavior, router)
+//!     // Every busy tick.
+//!     .snapshot_when(|ctx| ctx.woken > 100_000)
+//!     // The tick on which infections first exceed 1 000.
+//!     .snapshot_when(move |ctx| {
+//!         let high = ctx.metrics.count("infected") > 1_000;
+//!         let crossed = high && !was_high;
+//!         was_high = high;
+//!         crossed
+//!     })
+//!     .build()?;
+//! ```
+//!
+//! Every trigger is evaluated on every tick (so stateful triggers see each
+//! tick exactly once); a snapshot fires if the interval or any trigger says
+//! so, and never more than once per tick.
+//!
+//! [`SimObserver::on_snapshot`]: crate::SimObserver::on_snapshot
+
+use dt_agent::AgentStore;
+use dt_core::Tick;
+use dt_mobility::MobilityStore;
+
+use crate::TickMetrics;
+
+/// End-of-tick state passed to a [`SnapshotTrigger`].
+pub struct TriggerContext<'a> {
+    /// The tick that just finished processing.
+    pub tick:     Tick,
+    /// Number of agents woken this tick.
+    pub woken:    usize,
+    pub agents:   &'a AgentStore,
+    pub mobility: &'a MobilityStore,
+    /// Metrics emitted by behaviors this tick.
+    pub metrics:  &'a TickMetrics,
+}
+
+/// A closure deciding whether to snapshot the current tick.
+pub type SnapshotTrigger = Box<dyn FnMut(&TriggerContext<'_>) -> bool + Send>;
@@ -0,0 +1,391 @@
+//! ALT: A* search with landmarks and the triangle inequality.
+//!
+//! # Preprocessing
+//!
+//! [`Landmarks::build`] picks a handful of landmark nodes, each as far as
+//! possible (by car travel time) from those already picked, so they end up
+//! spread around the edge of the network.  It then stores the travel time
+//! from every landmark to every node and from every node to every landmark:
+//! `2 × landmarks × nodes` `u32`s, one Dijkstra per landmark and direction.
+//!
+//! # Queries
+//!
+//! For a landmark `L`, the triangle inequality bounds the remaining time
+//! from `v` to the target `t` from below by both `d(L, t) − d(L, v)` and
+//! `d(v, L) − d(t, L)`.  [`AltRouter`] runs A* with the largest such bound
+//! as its heuristic, which steers the search towards the target: on a
+//! sprawling metro graph it settles a small fraction of Dijkstra's nodes,
+//! without the preprocessing time and complexity of a
+//! [`ContractionHierarchy`](crate::ContractionHierarchy).  Routes cost the
+//! same as [`DijkstraRouter`]'s.
+//!
+//! # Persistence
+//!
+//! Like a hierarchy, landmarks can be [`save`](Landmarks::save)d next to
+//! their network and [`load`](Landmarks::load)ed later; the file records the
+//! network's [`fingerprint`](RoadNetwork::fingerprint) and is rejected once
+//! the network changes.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let landmarks = Landmarks::load_or_build(Path::new("city.alt"), &network, 16)?;
+//! let router = AltRouter::new(landmarks);
+//! let route = router.route(&network, home, work, TransportMode::Car)?;
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use dt_core::{EdgeId, NodeId, TransportMode};
+
+use crate::network::RoadNetwork;
+use crate::persist::{read_words, write_words};
+use crate::router::{reconstruct, DijkstraRouter, Route, Router};
+use crate::{SpatialError, SpatialResult};
+
+/// Leading bytes of a saved landmark table.
+const MAGIC: &[u8; 4] = b"DTLM";
+
+/// File format version, bumped on any layout change.
+const VERSION: u32 = 1;
+
+/// Unreachable marker in the distance tables.
+const UNREACHABLE: u32 = u32::MAX;
+
+// ── Landmarks ─────────────────────────────────────────────────────────────────
+
+/// Landmark nodes and their car travel-time tables.  See the module docs.
+#[derive(Clone)]
+pub struct Landmarks {
+    /// [`RoadNetwork::fingerprint`] of the network this was built from.
+    fingerprint: u64,
+    node_count:  usize,
+    edge_count:  usize,
+    landmarks:   Vec<u32>,
+    /// `from[v * k + i]`: ms from landmark `i` to node `v`.
+    from:        Vec<u32>,
+    /// `to[v * k + i]`: ms from node `v` to landmark `i`.
+    to:          Vec<u32>,
+}
+
+impl Landmarks {
+    /// Pick up to `count` landmarks on `network` and tabulate car travel
+    /// times to and from them.
+    ///
+    /// Fewer come back if the network has fewer nodes.  Closed edges
+    /// (`u32::MAX` travel time) are left out, so re-run this after reopening
+    /// roads; closing roads only makes the tables less tight.
+    pub fn build(network: &RoadNetwork, count: usize) -> Self {
+        let n = network.node_count();
+        let k = count.min(n);
+        let mut landmarks: Vec<u32> = Vec::with_capacity(k);
+        let mut forward: Vec<Vec<u32>> = Vec::with_capacity(k);
+        let mut backward: Vec<Vec<u32>> = Vec::with_capacity(k);
+        // min_dist[v]: travel time from the nearest landmark so far.
+        let mut min_dist = vec![UNREACHABLE; n];
+
+        if k > 0 {
+            let (in_start, in_ids) = network.reverse_csr();
+            let forward_rows = (&network.node_out_start[..], None);
+            let backward_rows = (&in_start[..], Some(&in_ids[..]));
+            // Start from whatever lies farthest from node 0.
+            let seed = one_to_all(network, NodeId(0), forward_rows);
+            let mut next = farthest(&seed, |_| true);
+            while landmarks.len() < k {
+                let fwd = one_to_all(network, NodeId(next), forward_rows);
+                let bwd = one_to_all(network, NodeId(next), backward_rows);
+                for (m, &d) in min_dist.iter_mut().zip(&fwd) {
+                    *m = (*m).min(d);
+                }
+                landmarks.push(next);
+                forward.push(fwd);
+                backward.push(bwd);
+                if landmarks.len() == k {
+                    break;
+                }
+                // Farthest from every landmark; nodes none can reach come
+                // first, since no landmark helps with them yet.
+                next = farthest(&min_dist, |v| !landmarks.contains(&v));
+            }
+        }
+
+        let mut from = vec![0u32; n * k];
+        let mut to = vec![0u32; n * k];
+        for v in 0..n {
+            for i in 0..k {
+                from[v * k + i] = forward[i][v];
+                to[v * k + i] = backward[i][v];
+            }
+        }
+        Self {
+            fingerprint: network.fingerprint(),
+            node_count:  n,
+            edge_count:  network.edge_count(),
+            landmarks,
+            from,
+            to,
+        }
+    }
+
+    /// [`RoadNetwork::fingerprint`] of the network this was built from.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// `true` if these tables were built from `network` as it is now.
+    ///
+    /// Computes the network's fingerprint, so check once rather than per query.
+    pub fn matches(&self, network: &RoadNetwork) -> bool {
+        self.fingerprint == network.fingerprint()
+    }
+
+    /// The landmark nodes, in the order they were picked.
+    pub fn nodes(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.landmarks.iter().map(|&v| NodeId(v))
+    }
+
+    /// Number of landmarks.
+    pub fn len(&self) -> usize {
+        self.landmarks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.landmarks.is_empty()
+    }
+
+    /// Lower bound in ms on the car travel time from `v` to `target`.
+    #[inline]
+    fn lower_bound(&self, v: usize, target: usize) -> u32 {
+        let k = self.landmarks.len();
+        let (from_v, from_t) = (&self.from[v * k..v * k + k], &self.from[target * k..target * k + k]);
+        let (to_v, to_t) = (&self.to[v * k..v * k + k], &self.to[target * k..target * k + k]);
+        let mut best = 0;
+        for i in 0..k {
+            if from_v[i] != UNREACHABLE && from_t[i] != UNREACHABLE {
+                best = best.max(from_t[i].saturating_sub(from_v[i]));
+            }
+            if to_v[i] != UNREACHABLE && to_t[i] != UNREACHABLE {
+                best = best.max(to_v[i].saturating_sub(to_t[i]));
+            }
+        }
+        best
+    }
+
+    // ── Query ─────────────────────────────────────────────────────────────
+
+    /// Fastest car route by A*; `None` if unreachable.
+    fn query(&self, network: &RoadNetwork, from: NodeId, to: NodeId) -> Option<Route> {
+        let n = network.node_count();
+        let mut dist = vec![u32::MAX; n];
+        let mut prev_edge = vec![EdgeId::INVALID; n];
+        // Min-heap on (cost + bound, node); NodeId breaks ties.
+        let mut heap: BinaryHeap<Reverse<(u32, NodeId)>> = BinaryHeap::new();
+        dist[from.index()] = 0;
+        heap.push(Reverse((self.lower_bound(from.index(), to.index()), from)));
+
+        while let Some(Reverse((key, node))) = heap.pop() {
+            let cost = dist[node.index()];
+            if node == to {
+                return Some(reconstruct(network, prev_edge, to, cost));
+            }
+            // Skip stale heap entries.
+            if key > cost.saturating_add(self.lower_bound(node.index(), to.index())) {
+                continue;
+            }
+            for edge in network.out_edges(node) {
+                let ms = network.edge_travel_ms[edge.index()];
+                if ms == u32::MAX {
+                    continue;
+                }
+                let neighbor = network.edge_to[edge.index()];
+                let new_cost = cost.saturating_add(ms);
+                if new_cost < dist[neighbor.index()] {
+                    dist[neighbor.index()] = new_cost;
+                    prev_edge[neighbor.index()] = edge;
+                    let key = new_cost.saturating_add(self.lower_bound(neighbor.index(), to.index()));
+                    heap.push(Reverse((key, neighbor)));
+                }
+            }
+        }
+        None
+    }
+
+    // ── Persistence ───────────────────────────────────────────────────────
+
+    /// Write the tables to `path` (see [`write_to`](Self::write_to)).
+    pub fn save(&self, path: &Path) -> SpatialResult<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write_to(&mut w)?;
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Serialise as `DTLM`, a version, the network fingerprint, then the
+    /// landmarks and both tables as length-prefixed little-endian `u32`s.
+    pub fn write_to(&self, w: &mut impl Write) -> SpatialResult<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&VERSION.to_le_bytes())?;
+        w.write_all(&self.fingerprint.to_le_bytes())?;
+        for words in [&self.landmarks, &self.from, &self.to] {
+            write_words(w, words.iter().copied(), words.len())?;
+        }
+        Ok(())
+    }
+
+    /// Read landmarks saved for `network` from `path`.
+    ///
+    /// Fails with [`SpatialError::Landmarks`] if the file is not a landmark
+    /// table, is corrupt, or was built from a different network.
+    pub fn load(path: &Path, network: &RoadNetwork) -> SpatialResult<Self> {
+        Self::read_from(&mut BufReader::new(File::open(path)?), network)
+    }
+
+    /// Read landmarks written by [`write_to`](Self::write_to) for `network`.
+    pub fn read_from(r: &mut impl Read, network: &RoadNetwork) -> SpatialResult<Self> {
+        let invalid = |msg: &str| SpatialError::Landmarks(msg.to_string());
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a landmark file"));
+        }
+        let mut word = [0u8; 4];
+        r.read_exact(&mut word)?;
+        if u32::from_le_bytes(word) != VERSION {
+            return Err(SpatialError::Landmarks(format!("unsupported version {}", u32::from_le_bytes(word))));
+        }
+        let mut fingerprint = [0u8; 8];
+        r.read_exact(&mut fingerprint)?;
+        let fingerprint = u64::from_le_bytes(fingerprint);
+        if fingerprint != network.fingerprint() {
+            return Err(invalid("built from a different network"));
+        }
+
+        let landmarks = read_words(r, SpatialError::Landmarks)?;
+        let from = read_words(r, SpatialError::Landmarks)?;
+        let to = read_words(r, SpatialError::Landmarks)?;
+        let n = network.node_count();
+        if landmarks.iter().any(|&v| v as usize >= n) {
+            return Err(invalid("landmark refers to a missing node"));
+        }
+        if from.len() != n * landmarks.len() || to.len() != from.len() {
+            return Err(invalid("distance tables don't match the landmark count"));
+        }
+        Ok(Self { fingerprint, node_count: n, edge_count: network.edge_count(), landmarks, from, to })
+    }
+
+    /// [`load`](Self::load) `path` if it holds `count` landmarks of
+    /// `network` (or all its nodes, if fewer); otherwise build them and save
+    /// them there for the next run.
+    pub fn load_or_build(path: &Path, network: &RoadNetwork, count: usize) -> SpatialResult<Self> {
+        if let Ok(landmarks) = Self::load(path, network)
+            && landmarks.len() == count.min(network.node_count())
+        {
+            return Ok(landmarks);
+        }
+        let landmarks = Self::build(network, count);
+        landmarks.save(path)?;
+        Ok(landmarks)
+    }
+}
+
+/// Car travel time from `source` to every node, `UNREACHABLE` where there
+/// is none.  `rows` is the forward CSR row pointer, or the reverse one with
+/// its `EdgeId`s for the time from every node to `source`.
+fn one_to_all(network: &RoadNetwork, source: NodeId, rows: (&[u32], Option<&[EdgeId]>)) -> Vec<u32> {
+    let mut dist = vec![UNREACHABLE; network.node_count()];
+    let mut heap: BinaryHeap<Reverse<(u32, NodeId)>> = BinaryHeap::new();
+    dist[source.index()] = 0;
+    heap.push(Reverse((0, source)));
+    while let Some(Reverse((cost, node))) = heap.pop() {
+        if cost > dist[node.index()] {
+            continue;
+        }
+        // Outgoing edges forward, incoming edges in reverse.
+        let (row, in_edge_ids) = rows;
+        for i in row[node.index()]..row[node.index() + 1] {
+            let (edge, neighbor) = match in_edge_ids {
+                Some(ids) => {
+                    let edge = ids[i as usize];
+                    (edge, network.edge_from[edge.index()])
+                }
+                None => (EdgeId(i), network.edge_to[i as usize]),
+            };
+            let ms = network.edge_travel_ms[edge.index()];
+            if ms == u32::MAX {
+                continue;
+            }
+            let new_cost = cost.saturating_add(ms);
+            if new_cost < dist[neighbor.index()] {
+                dist[neighbor.index()] = new_cost;
+                heap.push(Reverse((new_cost, neighbor)));
+            }
+        }
+    }
+    dist
+}
+
+/// The node with the largest `dist` among those `eligible`, lowest id first
+/// on ties.  `UNREACHABLE` counts as farthest.
+fn farthest(dist: &[u32], eligible: impl Fn(u32) -> bool) -> u32 {
+    let mut best = (0, 0u32);
+    for (v, &d) in dist.iter().enumerate() {
+        let v = v as u32;
+        if eligible(v) && (d > best.0 || !eligible(best.1)) {
+            best = (d, v);
+        }
+    }
+    best.1
+}
+
+// ── AltRouter ─────────────────────────────────────────────────────────────────
+
+/// [`Router`] answering car queries by A* over [`Landmarks`].
+///
+/// Other modes go to [`DijkstraRouter`], as do queries against a network
+/// whose node or edge count differs from the tables' or whose
+/// [overlay](crate::NetworkOverlay) closes or scales edges.  Changing travel
+/// times in place is not detected per query; rebuild, or check
+/// [`Landmarks::matches`], after editing the network.
+pub struct AltRouter {
+    landmarks: Landmarks,
+}
+
+impl AltRouter {
+    pub fn new(landmarks: Landmarks) -> Self {
+        Self { landmarks }
+    }
+
+    /// Pick `count` landmarks on `network` and route over them.
+    pub fn build(network: &RoadNetwork, count: usize) -> Self {
+        Self::new(Landmarks::build(network, count))
+    }
+
+    pub fn landmarks(&self) -> &Landmarks {
+        &self.landmarks
+    }
+}
+
+impl Router for AltRouter {
+    fn route(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+    ) -> Result<Route, SpatialError> {
+        let car = matches!(mode, TransportMode::Car | TransportMode::None);
+        let fits = network.node_count() == self.landmarks.node_count
+            && network.edge_count() == self.landmarks.edge_count;
+        if !car || !fits || network.has_overlay_changes() {
+            return DijkstraRouter.route(network, from, to, mode);
+        }
+        if from == to {
+            return Ok(Route { edges: vec![], total_travel_ms: 0, rides: Vec::new() });
+        }
+        self.landmarks.query(network, from, to).ok_or(SpatialError::NoRoute { from, to })
+    }
+}
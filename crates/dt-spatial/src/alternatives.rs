@@ -0,0 +1,87 @@
+//! Alternative routes between one origin and destination.
+//!
+//! [`DijkstraRouter::route_k`] uses the penalty method: after each search the
+//! edges of the route found get more expensive, so the next search drifts
+//! onto a parallel corridor once the detour costs less than the penalised
+//! original.  Candidates sharing too much of their length with a route
+//! already kept are dropped, so the alternatives differ noticeably rather
+//! than by one block.
+//!
+//! Behaviour models can then spread agents over the alternatives instead of
+//! sending everyone down the single shortest path.
+
+use std::collections::HashSet;
+
+use dt_core::{EdgeId, NodeId, TransportMode};
+
+use crate::network::RoadNetwork;
+use crate::router::{dijkstra, edge_cost_ms, DijkstraRouter, Route};
+use crate::SpatialResult;
+
+/// Cost factor applied to an edge each time a search uses it.
+const PENALTY: f32 = 1.5;
+
+/// Largest share of a candidate's length that may overlap a kept route.
+const MAX_SHARED: f32 = 0.8;
+
+/// Searches run per requested alternative before giving up.
+const ATTEMPTS_PER_ROUTE: usize = 4;
+
+impl DijkstraRouter {
+    /// Up to `k` diverse routes from `from` to `to`, fastest first.
+    ///
+    /// The first is the route [`route`](crate::Router::route) returns; each
+    /// later one shares at most 80% of its length with every route before it.
+    /// Totals are true (unpenalised) travel times.  Fewer than `k` come back
+    /// when the network has no more distinct corridors, and `from == to`
+    /// gives the single empty route.
+    pub fn route_k(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+        k: usize,
+    ) -> SpatialResult<Vec<Route>> {
+        let mut routes: Vec<Route> = Vec::with_capacity(k);
+        let mut factor = vec![1.0f32; network.edge_count()];
+
+        for _ in 0..k * ATTEMPTS_PER_ROUTE {
+            if routes.len() == k {
+                break;
+            }
+            let found = dijkstra(network, from, to, |edge, _| {
+                let ms = edge_cost_ms(network, edge, mode) as f32 * factor[edge.index()];
+                ms.min((u32::MAX - 1) as f32) as u32
+            })?;
+            if found.edges.is_empty() {
+                return Ok(vec![found]);
+            }
+            for edge in &found.edges {
+                factor[edge.index()] *= PENALTY;
+            }
+
+            let total_ms: u64 = found.edges.iter().map(|&e| edge_cost_ms(network, e, mode) as u64).sum();
+            let route = Route { total_travel_ms: total_ms, edges: found.edges, rides: Vec::new() };
+            if routes.iter().all(|kept| shared_fraction(network, &route.edges, &kept.edges) <= MAX_SHARED) {
+                routes.push(route);
+            }
+        }
+
+        routes.sort_by_key(|r| r.total_travel_ms);
+        Ok(routes)
+    }
+}
+
+/// Share of the length of `edges` that also lies on `other`.
+fn shared_fraction(network: &RoadNetwork, edges: &[EdgeId], other: &[EdgeId]) -> f32 {
+    let other: HashSet<EdgeId> = other.iter().copied().collect();
+    let length = |e: &EdgeId| network.edge_length_m[e.index()];
+    let total: f32 = edges.iter().map(length).sum();
+    let shared = edges.iter().filter(|e| other.contains(e));
+    if total <= 0.0 {
+        // Zero-length roads: fall back to counting edges.
+        return shared.count() as f32 / edges.len() as f32;
+    }
+    shared.map(length).sum::<f32>() / total
+}
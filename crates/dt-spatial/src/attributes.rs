@@ -0,0 +1,219 @@
+//! Typed per-edge attributes.
+//!
+//! The counterpart of `dt_agent::ComponentMap` for the road graph: each
+//! attribute type `T` is one `Vec<T>` indexed by `EdgeId`, so custom
+//! [`Router`](crate::Router)s and behaviors can read capacities, tolls, zone
+//! ids, and the like without forking [`RoadNetwork`](crate::RoadNetwork).
+//! Wrap plain values in a newtype per attribute; the type is the key.
+//!
+//! # Example
+//!
+//! ```
+//! use dt_core::GeoPoint;
+//! use dt_spatial::RoadNetworkBuilder;
+//!
+//! #[derive(Clone, Default)]
+//! struct Toll(f32);
+//!
+//! let mut b = RoadNetworkBuilder::new();
+//! let a = b.add_node(GeoPoint::new(30.69, -88.04));
+//! let c = b.add_node(GeoPoint::new(30.70, -88.03));
+//! b.add_road(a, c, 1_200.0, 90_000);
+//! let mut net = b.build();
+//!
+//! net.edge_attrs.register::<Toll>();
+//! net.edge_attrs.get_mut::<Toll>().unwrap()[0] = Toll(2.5);
+//! assert_eq!(net.edge_attrs.get::<Toll>().unwrap()[0].0, 2.5);
+//! ```
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// ── Trait object ──────────────────────────────────────────────────────────────
+
+/// Type-erased per-edge `Vec<T>`, cloneable so [`RoadNetwork`] stays `Clone`.
+///
+/// [`RoadNetwork`]: crate::RoadNetwork
+trait AttributeVec: Send + Sync + 'static {
+    fn clone_box(&self) -> Box<dyn AttributeVec>;
+
+    /// The values of `edges` (old indices, in their new order).
+    fn select_edges(&self, edges: &[usize]) -> Box<dyn AttributeVec>;
+
+    /// Add `count` default values.
+    fn append_defaults(&mut self, count: usize);
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: Clone + Default + Send + Sync + 'static> AttributeVec for Vec<T> {
+    fn clone_box(&self) -> Box<dyn AttributeVec> {
+        Box::new(self.clone())
+    }
+
+    fn select_edges(&self, edges: &[usize]) -> Box<dyn AttributeVec> {
+        Box::new(edges.iter().map(|&e| self[e].clone()).collect::<Vec<T>>())
+    }
+
+    fn append_defaults(&mut self, count: usize) {
+        self.resize(self.len() + count, T::default());
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+// ── EdgeAttributes ────────────────────────────────────────────────────────────
+
+/// Registry of application-defined edge attributes, one `Vec<T>` per type,
+/// each `edge_count` long.
+///
+/// Every [`RoadNetwork`](crate::RoadNetwork) carries one in
+/// [`edge_attrs`](crate::RoadNetwork::edge_attrs), empty when built.
+/// [`retain_largest_scc`](crate::RoadNetwork::retain_largest_scc) keeps the
+/// values of the edges it keeps; [`save`](crate::RoadNetwork::save) does not
+/// write them.
+pub struct EdgeAttributes {
+    edge_count: usize,
+    map:        HashMap<TypeId, Box<dyn AttributeVec>>,
+}
+
+impl EdgeAttributes {
+    /// An empty registry for `edge_count` edges.
+    pub fn new(edge_count: usize) -> Self {
+        Self { edge_count, map: HashMap::new() }
+    }
+
+    /// Register attribute `T`, filling every edge with `T::default()`.
+    ///
+    /// Calling this twice for the same `T` is a no-op — existing values are
+    /// not disturbed.
+    pub fn register<T: Clone + Default + Send + Sync + 'static>(&mut self) {
+        let edge_count = self.edge_count;
+        self.map
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(vec![T::default(); edge_count]));
+    }
+
+    /// Set attribute `T` to `values`, one per edge in `EdgeId` order,
+    /// replacing any earlier values.
+    ///
+    /// # Panics
+    ///
+    /// If `values.len()` is not the edge count.
+    pub fn insert<T: Clone + Default + Send + Sync + 'static>(&mut self, values: Vec<T>) {
+        assert_eq!(
+            values.len(),
+            self.edge_count,
+            "edge attribute has {} values, network has {} edges",
+            values.len(),
+            self.edge_count
+        );
+        self.map.insert(TypeId::of::<T>(), Box::new(values));
+    }
+
+    /// Remove attribute `T`, returning its values.
+    pub fn remove<T: Clone + Default + Send + Sync + 'static>(&mut self) -> Option<Vec<T>> {
+        self.map
+            .remove(&TypeId::of::<T>())
+            .and_then(|v| v.into_any().downcast::<Vec<T>>().ok())
+            .map(|v| *v)
+    }
+
+    // ── Access ────────────────────────────────────────────────────────────
+
+    /// Values of attribute `T` for all edges (indexed by `EdgeId`).
+    ///
+    /// Returns `None` if `T` was never registered.  In a router's inner loop,
+    /// look the slice up once per query rather than once per edge.
+    pub fn get<T: Clone + Default + Send + Sync + 'static>(&self) -> Option<&[T]> {
+        self.map
+            .get(&TypeId::of::<T>())
+            .and_then(|v| v.as_any().downcast_ref::<Vec<T>>())
+            .map(Vec::as_slice)
+    }
+
+    /// Mutable values of attribute `T`.  The length is fixed at the edge
+    /// count.
+    ///
+    /// Returns `None` if `T` was never registered.
+    pub fn get_mut<T: Clone + Default + Send + Sync + 'static>(&mut self) -> Option<&mut [T]> {
+        self.map
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|v| v.as_any_mut().downcast_mut::<Vec<T>>())
+            .map(Vec::as_mut_slice)
+    }
+
+    // ── Metadata ──────────────────────────────────────────────────────────
+
+    /// `true` if attribute `T` has been registered.
+    pub fn contains<T: Clone + Default + Send + Sync + 'static>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Number of distinct attribute types registered.
+    pub fn type_count(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Number of edges every attribute covers.
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Cover `count` more edges, with every attribute at its default.
+    pub(crate) fn append_edges(&mut self, count: usize) {
+        self.edge_count += count;
+        for values in self.map.values_mut() {
+            values.append_defaults(count);
+        }
+    }
+
+    /// The registry restricted to `edges` (old indices, in their new order).
+    pub(crate) fn select_edges(&self, edges: &[usize]) -> Self {
+        let map = self.map.iter().map(|(&k, v)| (k, v.select_edges(edges))).collect();
+        Self { edge_count: edges.len(), map }
+    }
+}
+
+impl Clone for EdgeAttributes {
+    fn clone(&self) -> Self {
+        let map = self.map.iter().map(|(&k, v)| (k, v.clone_box())).collect();
+        Self { edge_count: self.edge_count, map }
+    }
+}
+
+impl std::fmt::Debug for EdgeAttributes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EdgeAttributes")
+            .field("edge_count", &self.edge_count)
+            .field("type_count", &self.map.len())
+            .finish()
+    }
+}
+
+// ── Built-in attributes ───────────────────────────────────────────────────────
+
+/// The OpenStreetMap way an edge came from.  Registered by the OSM loader
+/// when asked to keep ids; `0` for edges not from OSM.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct OsmWayId(pub i64);
+
+/// The street name of an edge — the way's `name` tag when loaded from OSM.
+/// Empty if the road is unnamed.  Edges of one way share the string.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct StreetName(pub Arc<str>);
@@ -0,0 +1,202 @@
+//! Memoising wrapper for any [`Router`].
+//!
+//! Commuter populations ask for the same few thousand origin–destination
+//! pairs over and over.  [`CachedRouter`] keeps the most recently used
+//! `(from, to, mode)` → [`Route`] results, evicting the least recently used
+//! once `capacity` is reached, so repeated trips cost a hash lookup and a
+//! clone instead of a search.
+//!
+//! Cached routes are not tied to a network: call
+//! [`clear`](CachedRouter::clear) after editing travel times or the
+//! [overlay](crate::NetworkOverlay).
+//!
+//! [`route_at`](Router::route_at) and [`route_for`](Router::route_for) reach
+//! the wrapped router on a miss, departure time and agent included.  If it
+//! [depends on the agent](Router::depends_on_agent) — a
+//! [`CostRouter`](crate::CostRouter) over a per-agent
+//! [`EdgeCost`](crate::EdgeCost) — `route_for` keys routes by agent too, so
+//! one agent's route is never handed to another.  Departure times are never
+//! part of the key, so don't wrap a router whose answers depend on them,
+//! such as [`TimeDependentRouter`](crate::TimeDependentRouter).
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let router = CachedRouter::new(ChRouter::build(&network), 100_000);
+//! let sim = SimBuilder::new(config, store, behavior, network, router).build()?;
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use dt_core::{AgentId, NodeId, Tick, TransportMode};
+
+use crate::network::RoadNetwork;
+use crate::router::{Route, Router};
+use crate::SpatialError;
+
+/// `(from, to, mode, agent)`, the agent only for agent-dependent routers.
+type Key = (NodeId, NodeId, TransportMode, Option<AgentId>);
+
+/// A [`Router`] that remembers the last `capacity` routes of another.  See
+/// the module docs.
+pub struct CachedRouter<R: Router> {
+    inner:    R,
+    capacity: usize,
+    state:    Mutex<Lru>,
+}
+
+/// LRU bookkeeping: each entry carries the tick of its last use, and
+/// `order` maps ticks back to keys so the oldest is found in O(log n).
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<Key, (Route, u64)>,
+    order:   BTreeMap<u64, Key>,
+    clock:   u64,
+    hits:    u64,
+    misses:  u64,
+}
+
+impl<R: Router> CachedRouter<R> {
+    /// Wrap `inner`, keeping at most `capacity` routes.  A capacity of 0
+    /// caches nothing.
+    pub fn new(inner: R, capacity: usize) -> Self {
+        Self { inner, capacity, state: Mutex::new(Lru::default()) }
+    }
+
+    /// The wrapped router.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of routes cached.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Queries answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.lock().hits
+    }
+
+    /// Queries passed to the wrapped router, failed ones included.
+    pub fn misses(&self) -> u64 {
+        self.lock().misses
+    }
+
+    /// Forget every cached route; the hit and miss counts are kept.
+    pub fn clear(&self) {
+        let mut lru = self.lock();
+        lru.entries.clear();
+        lru.order.clear();
+    }
+
+    /// The cached route for `key`, or `search`'s answer, cached if it
+    /// found a route.
+    fn cached(&self, key: Key, search: impl FnOnce() -> Result<Route, SpatialError>) -> Result<Route, SpatialError> {
+        {
+            let mut lru = self.lock();
+            if let Some(route) = lru.get(&key) {
+                lru.hits += 1;
+                return Ok(route);
+            }
+            lru.misses += 1;
+        }
+        // Route without the lock so parallel callers don't queue behind
+        // each other's searches.
+        let route = search()?;
+        self.lock().insert(key, route.clone(), self.capacity);
+        Ok(route)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lru> {
+        // A panic while holding the lock leaves the maps consistent.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Lru {
+    /// The cached route for `key`, marked as just used.
+    fn get(&mut self, key: &Key) -> Option<Route> {
+        self.clock += 1;
+        let clock = self.clock;
+        let (route, used) = self.entries.get_mut(key)?;
+        self.order.remove(used);
+        *used = clock;
+        self.order.insert(clock, *key);
+        Some(route.clone())
+    }
+
+    fn insert(&mut self, key: Key, route: Route, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+        while self.entries.len() >= capacity {
+            let Some((_, oldest)) = self.order.pop_first() else { break };
+            self.entries.remove(&oldest);
+        }
+        self.clock += 1;
+        self.order.insert(self.clock, key);
+        if let Some((_, used)) = self.entries.insert(key, (route, self.clock)) {
+            self.order.remove(&used);
+        }
+    }
+}
+
+impl<R: Router> Router for CachedRouter<R> {
+    /// The cached route for `(from, to, mode)` if there is one; otherwise
+    /// the wrapped router's answer, cached if it found a route.
+    fn route(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+    ) -> Result<Route, SpatialError> {
+        self.cached((from, to, mode, None), || self.inner.route(network, from, to, mode))
+    }
+
+    /// As [`route`](Self::route), asking the wrapped router's `route_at` on
+    /// a miss.
+    fn route_at(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+        departure: Tick,
+        tick_duration_secs: u32,
+    ) -> Result<Route, SpatialError> {
+        self.cached((from, to, mode, None), || {
+            self.inner.route_at(network, from, to, mode, departure, tick_duration_secs)
+        })
+    }
+
+    /// As [`route`](Self::route), asking the wrapped router's `route_for`
+    /// on a miss and keying by `agent` if the wrapped router depends on it.
+    fn route_for(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+        departure: Tick,
+        tick_duration_secs: u32,
+        agent: AgentId,
+    ) -> Result<Route, SpatialError> {
+        let key = (from, to, mode, self.inner.depends_on_agent().then_some(agent));
+        self.cached(key, || self.inner.route_for(network, from, to, mode, departure, tick_duration_secs, agent))
+    }
+
+    fn depends_on_agent(&self) -> bool {
+        self.inner.depends_on_agent()
+    }
+}
@@ -0,0 +1,611 @@
+//! Contraction hierarchies: preprocessed car routing for large networks.
+//!
+//! # Preprocessing
+//!
+//! [`ContractionHierarchy::build`] contracts nodes one at a time, least
+//! important first (edge difference plus already-contracted neighbours).
+//! Removing a node adds a *shortcut* arc for every shortest path through it
+//! that a bounded witness search can't route around.  Each arc — original
+//! edge or shortcut — ends up in one of two CSR arrays, indexed by its
+//! lower-ranked (earlier contracted) endpoint:
+//!
+//! - `up`: arcs `u → v` stored at `u`, walked by the forward search;
+//! - `down`: arcs `u → v` stored at `v`, walked backwards by the reverse search.
+//!
+//! Shortcuts record the two arcs they replace, so routes unpack to the
+//! network's own `EdgeId`s.
+//!
+//! # Queries
+//!
+//! [`ChRouter`] runs a bidirectional Dijkstra in which both searches only
+//! climb in rank.  They meet at the highest node of the shortest path after
+//! settling a few hundred nodes instead of most of the city.  Costs are car
+//! travel times (`edge_travel_ms`); other modes go to [`DijkstraRouter`].
+//!
+//! # Persistence
+//!
+//! Preprocessing a city takes seconds to minutes, so a hierarchy can be
+//! [`save`](ContractionHierarchy::save)d next to its network and
+//! [`load`](ContractionHierarchy::load)ed on later runs.  The file records
+//! the network's [`fingerprint`](RoadNetwork::fingerprint) and is rejected
+//! once the network changes.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let hierarchy = ContractionHierarchy::load_or_build(Path::new("city.ch"), &network)?;
+//! let router = ChRouter::new(hierarchy);
+//! let route = router.route(&network, home, work, TransportMode::Car)?;
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use dt_core::{EdgeId, NodeId, TransportMode};
+
+use crate::network::RoadNetwork;
+use crate::persist::{read_words, write_words};
+use crate::router::{DijkstraRouter, Route, Router};
+use crate::{SpatialError, SpatialResult};
+
+/// Settled-node budget of one witness search.  A search that runs out adds
+/// the shortcut anyway: redundant shortcuts cost memory, never correctness.
+const WITNESS_SETTLE_LIMIT: usize = 500;
+
+/// Leading bytes of a saved hierarchy.
+const MAGIC: &[u8; 4] = b"DTCH";
+
+/// File format version, bumped on any layout change.
+const VERSION: u32 = 1;
+
+/// Absent arc / node marker.
+const NONE: u32 = u32::MAX;
+
+// ── ContractionHierarchy ──────────────────────────────────────────────────────
+
+/// A preprocessed [`RoadNetwork`]: upward and downward arcs including
+/// shortcuts.  See the module docs.
+#[derive(Clone)]
+pub struct ContractionHierarchy {
+    /// [`RoadNetwork::fingerprint`] of the network this was built from.
+    fingerprint: u64,
+    node_count:  usize,
+    edge_count:  usize,
+    up:          ArcSet,
+    down:        ArcSet,
+    /// Original `EdgeId` of each arc, or for shortcuts the first arc replaced.
+    arc_first:   Vec<u32>,
+    /// Second arc replaced by a shortcut; `NONE` for original edges.
+    arc_second:  Vec<u32>,
+}
+
+impl ContractionHierarchy {
+    /// Preprocess `network` by car travel time.
+    ///
+    /// Closed edges (`u32::MAX` travel time) and self-loops are left out, so
+    /// re-run this after closing or reopening roads.
+    pub fn build(network: &RoadNetwork) -> Self {
+        let n = network.node_count();
+        let mut graph = Contractor::new(network);
+        let mut contracted_neighbours = vec![0u32; n];
+        let mut queue: BinaryHeap<Reverse<(i64, u32)>> = (0..n as u32)
+            .map(|v| {
+                let shortcuts = graph.shortcuts(v);
+                Reverse((graph.priority(v, shortcuts.len(), 0), v))
+            })
+            .collect();
+        let mut up   = vec![Vec::new(); n];
+        let mut down = vec![Vec::new(); n];
+
+        while let Some(Reverse((_, v))) = queue.pop() {
+            // Lazy update: priorities go stale as neighbours are contracted,
+            // so re-evaluate and requeue if `v` is no longer the minimum.
+            let shortcuts = graph.shortcuts(v);
+            let priority = graph.priority(v, shortcuts.len(), contracted_neighbours[v as usize]);
+            if queue.peek().is_some_and(|Reverse((next, _))| priority > *next) {
+                queue.push(Reverse((priority, v)));
+                continue;
+            }
+            let (out, inc) = graph.contract(v, shortcuts);
+            for link in out.iter().chain(&inc) {
+                contracted_neighbours[link.node as usize] += 1;
+            }
+            up[v as usize]   = out;
+            down[v as usize] = inc;
+        }
+
+        Self {
+            fingerprint: network.fingerprint(),
+            node_count:  n,
+            edge_count:  network.edge_count(),
+            up:          ArcSet::from_lists(up),
+            down:        ArcSet::from_lists(down),
+            arc_first:   graph.arc_first,
+            arc_second:  graph.arc_second,
+        }
+    }
+
+    /// [`RoadNetwork::fingerprint`] of the network this was built from.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// `true` if this hierarchy was built from `network` as it is now.
+    ///
+    /// Computes the network's fingerprint, so check once rather than per query.
+    pub fn matches(&self, network: &RoadNetwork) -> bool {
+        self.fingerprint == network.fingerprint()
+    }
+
+    /// Number of shortcut arcs added by preprocessing.
+    pub fn shortcut_count(&self) -> usize {
+        self.arc_second.iter().filter(|&&second| second != NONE).count()
+    }
+
+    // ── Query ─────────────────────────────────────────────────────────────
+
+    /// Fastest car path as `(total ms, edges)`; `None` if unreachable.
+    fn query(&self, from: NodeId, to: NodeId) -> Option<(u32, Vec<EdgeId>)> {
+        let n = self.node_count;
+        let sets = [&self.up, &self.down];
+        // Index 0 is the forward search from `from`, 1 the reverse one from `to`.
+        let mut dist = [vec![u32::MAX; n], vec![u32::MAX; n]];
+        // (arc, previous node) that reached each node.
+        let mut prev = [vec![(NONE, NONE); n], vec![(NONE, NONE); n]];
+        let mut heaps: [BinaryHeap<Reverse<(u32, u32)>>; 2] = [BinaryHeap::new(), BinaryHeap::new()];
+        for (side, node) in [from, to].into_iter().enumerate() {
+            dist[side][node.index()] = 0;
+            heaps[side].push(Reverse((0, node.0)));
+        }
+
+        // (total cost, meeting node) of the best path found so far.
+        let mut best = (u32::MAX, NONE);
+        loop {
+            let tops = [0, 1].map(|side| heaps[side].peek().map_or(u32::MAX, |Reverse((cost, _))| *cost));
+            let side = if tops[0] <= tops[1] { 0 } else { 1 };
+            // Neither search can improve on `best` any more.
+            if tops[side] >= best.0 {
+                break;
+            }
+            let Some(Reverse((cost, node))) = heaps[side].pop() else { break };
+            if cost > dist[side][node as usize] {
+                continue;
+            }
+            let other = dist[1 - side][node as usize];
+            if other != u32::MAX && cost.saturating_add(other) < best.0 {
+                best = (cost.saturating_add(other), node);
+            }
+            for (head, arc_cost, arc) in sets[side].links(node) {
+                let next = cost.saturating_add(arc_cost);
+                if next < dist[side][head as usize] {
+                    dist[side][head as usize] = next;
+                    prev[side][head as usize] = (arc, node);
+                    heaps[side].push(Reverse((next, head)));
+                }
+            }
+        }
+
+        let (total, meet) = best;
+        if meet == NONE {
+            return None;
+        }
+        let mut arcs = Vec::new();
+        let mut node = meet;
+        while node != from.0 {
+            let (arc, previous) = prev[0][node as usize];
+            arcs.push(arc);
+            node = previous;
+        }
+        arcs.reverse();
+        node = meet;
+        while node != to.0 {
+            let (arc, previous) = prev[1][node as usize];
+            arcs.push(arc);
+            node = previous;
+        }
+
+        let mut edges = Vec::new();
+        for arc in arcs {
+            self.unpack(arc, &mut edges);
+        }
+        Some((total, edges))
+    }
+
+    /// Append the original edges behind `arc` to `edges`, in travel order.
+    fn unpack(&self, arc: u32, edges: &mut Vec<EdgeId>) {
+        let mut stack = vec![arc];
+        while let Some(arc) = stack.pop() {
+            let (first, second) = (self.arc_first[arc as usize], self.arc_second[arc as usize]);
+            if second == NONE {
+                edges.push(EdgeId(first));
+            } else {
+                stack.push(second);
+                stack.push(first);
+            }
+        }
+    }
+
+    // ── Persistence ───────────────────────────────────────────────────────
+
+    /// Write the hierarchy to `path` (see [`write_to`](Self::write_to)).
+    pub fn save(&self, path: &Path) -> SpatialResult<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write_to(&mut w)?;
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Serialise as `DTCH`, a version, the network fingerprint, then the
+    /// arc arrays as length-prefixed little-endian `u32`s.
+    pub fn write_to(&self, w: &mut impl Write) -> SpatialResult<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&VERSION.to_le_bytes())?;
+        w.write_all(&self.fingerprint.to_le_bytes())?;
+        for words in self.arrays() {
+            write_words(w, words.iter().copied(), words.len())?;
+        }
+        Ok(())
+    }
+
+    /// Read a hierarchy saved for `network` from `path`.
+    ///
+    /// Fails with [`SpatialError::Hierarchy`] if the file is not a hierarchy,
+    /// is corrupt, or was built from a different network.
+    pub fn load(path: &Path, network: &RoadNetwork) -> SpatialResult<Self> {
+        Self::read_from(&mut BufReader::new(File::open(path)?), network)
+    }
+
+    /// Read a hierarchy written by [`write_to`](Self::write_to) for `network`.
+    pub fn read_from(r: &mut impl Read, network: &RoadNetwork) -> SpatialResult<Self> {
+        let invalid = |msg: &str| SpatialError::Hierarchy(msg.to_string());
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a contraction hierarchy file"));
+        }
+        let mut word = [0u8; 4];
+        r.read_exact(&mut word)?;
+        if u32::from_le_bytes(word) != VERSION {
+            return Err(SpatialError::Hierarchy(format!("unsupported version {}", u32::from_le_bytes(word))));
+        }
+        let mut fingerprint = [0u8; 8];
+        r.read_exact(&mut fingerprint)?;
+        let fingerprint = u64::from_le_bytes(fingerprint);
+        if fingerprint != network.fingerprint() {
+            return Err(invalid("built from a different network"));
+        }
+
+        let mut ch = Self {
+            fingerprint,
+            node_count:  network.node_count(),
+            edge_count:  network.edge_count(),
+            up:          ArcSet::default(),
+            down:        ArcSet::default(),
+            arc_first:   Vec::new(),
+            arc_second:  Vec::new(),
+        };
+        for words in ch.arrays_mut() {
+            *words = read_words(r, SpatialError::Hierarchy)?;
+        }
+        ch.validate().map_err(invalid)?;
+        Ok(ch)
+    }
+
+    /// [`load`](Self::load) `path` if it holds a hierarchy of `network`;
+    /// otherwise build one and save it there for the next run.
+    pub fn load_or_build(path: &Path, network: &RoadNetwork) -> SpatialResult<Self> {
+        if let Ok(ch) = Self::load(path, network) {
+            return Ok(ch);
+        }
+        let ch = Self::build(network);
+        ch.save(path)?;
+        Ok(ch)
+    }
+
+    /// The serialised arrays, in file order.
+    fn arrays(&self) -> [&Vec<u32>; 10] {
+        [
+            &self.arc_first, &self.arc_second,
+            &self.up.start, &self.up.node, &self.up.cost, &self.up.arc,
+            &self.down.start, &self.down.node, &self.down.cost, &self.down.arc,
+        ]
+    }
+
+    fn arrays_mut(&mut self) -> [&mut Vec<u32>; 10] {
+        [
+            &mut self.arc_first, &mut self.arc_second,
+            &mut self.up.start, &mut self.up.node, &mut self.up.cost, &mut self.up.arc,
+            &mut self.down.start, &mut self.down.node, &mut self.down.cost, &mut self.down.arc,
+        ]
+    }
+
+    /// Check that a loaded hierarchy can't index out of bounds or loop while
+    /// unpacking.
+    fn validate(&self) -> Result<(), &'static str> {
+        let arcs = self.arc_first.len();
+        if self.arc_second.len() != arcs {
+            return Err("arc arrays differ in length");
+        }
+        for (i, (&first, &second)) in self.arc_first.iter().zip(&self.arc_second).enumerate() {
+            let valid = match second {
+                NONE => (first as usize) < self.edge_count,
+                _ => (first as usize) < i && (second as usize) < i,
+            };
+            if !valid {
+                return Err("arc refers to a missing edge or a later arc");
+            }
+        }
+        for set in [&self.up, &self.down] {
+            let len = set.node.len();
+            let rows_ok = set.start.len() == self.node_count + 1
+                && set.start.first() == Some(&0)
+                && set.start.windows(2).all(|w| w[0] <= w[1])
+                && set.start.last() == Some(&(len as u32));
+            if !rows_ok || set.cost.len() != len || set.arc.len() != len {
+                return Err("malformed arc index");
+            }
+            if set.node.iter().any(|&v| v as usize >= self.node_count) || set.arc.iter().any(|&a| a as usize >= arcs) {
+                return Err("arc index refers to a missing node or arc");
+            }
+        }
+        Ok(())
+    }
+}
+
+// ── ArcSet ────────────────────────────────────────────────────────────────────
+
+/// One search direction of the hierarchy in CSR form.
+#[derive(Clone, Default)]
+struct ArcSet {
+    /// Row pointer; arcs of node `v` are at `start[v] .. start[v+1]`.
+    start: Vec<u32>,
+    /// The other, higher-ranked endpoint of each arc.
+    node:  Vec<u32>,
+    /// Travel time in milliseconds.
+    cost:  Vec<u32>,
+    /// Index into the hierarchy's arc arrays, for unpacking.
+    arc:   Vec<u32>,
+}
+
+impl ArcSet {
+    fn from_lists(lists: Vec<Vec<Link>>) -> Self {
+        let mut set = Self { start: Vec::with_capacity(lists.len() + 1), ..Self::default() };
+        set.start.push(0);
+        for links in lists {
+            for link in links {
+                set.node.push(link.node);
+                set.cost.push(link.cost);
+                set.arc.push(link.arc);
+            }
+            set.start.push(set.node.len() as u32);
+        }
+        set
+    }
+
+    /// `(other endpoint, cost, arc)` of each arc at `v`.
+    #[inline]
+    fn links(&self, v: u32) -> impl Iterator<Item = (u32, u32, u32)> + '_ {
+        let start = self.start[v as usize] as usize;
+        let end   = self.start[v as usize + 1] as usize;
+        (start..end).map(|i| (self.node[i], self.cost[i], self.arc[i]))
+    }
+}
+
+// ── ChRouter ──────────────────────────────────────────────────────────────────
+
+/// [`Router`] answering car queries from a [`ContractionHierarchy`].
+///
+/// Walk, bike, and transit costs aren't the ones the hierarchy was built
+/// with, so those queries go to [`DijkstraRouter`], as do queries against a
+/// network whose node or edge count differs from the hierarchy's or whose
+/// [overlay](crate::NetworkOverlay) closes or scales edges.  Changing
+/// travel times in place is not detected per query; rebuild, or check
+/// [`ContractionHierarchy::matches`], after editing the network.
+pub struct ChRouter {
+    hierarchy: ContractionHierarchy,
+}
+
+impl ChRouter {
+    pub fn new(hierarchy: ContractionHierarchy) -> Self {
+        Self { hierarchy }
+    }
+
+    /// Preprocess `network` and route over the result.
+    pub fn build(network: &RoadNetwork) -> Self {
+        Self::new(ContractionHierarchy::build(network))
+    }
+
+    pub fn hierarchy(&self) -> &ContractionHierarchy {
+        &self.hierarchy
+    }
+}
+
+impl Router for ChRouter {
+    fn route(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+    ) -> Result<Route, SpatialError> {
+        let car = matches!(mode, TransportMode::Car | TransportMode::None);
+        let fits = network.node_count() == self.hierarchy.node_count
+            && network.edge_count() == self.hierarchy.edge_count;
+        if !car || !fits || network.has_overlay_changes() {
+            return DijkstraRouter.route(network, from, to, mode);
+        }
+        if from == to {
+            return Ok(Route { edges: vec![], total_travel_ms: 0, rides: Vec::new() });
+        }
+        let (total_ms, edges) = self.hierarchy.query(from, to).ok_or(SpatialError::NoRoute { from, to })?;
+        Ok(Route { edges, total_travel_ms: total_ms as u64, rides: Vec::new() })
+    }
+}
+
+// ── Contraction internals ─────────────────────────────────────────────────────
+
+/// An arc between two uncontracted nodes, stored at one end.
+#[derive(Clone, Copy)]
+struct Link {
+    /// The other endpoint.
+    node: u32,
+    cost: u32,
+    arc:  u32,
+}
+
+/// A shortcut `from → to` replacing arcs `first` then `second`.
+struct Shortcut {
+    from:   u32,
+    to:     u32,
+    cost:   u32,
+    first:  u32,
+    second: u32,
+}
+
+/// The remaining graph during preprocessing, plus witness-search scratch.
+struct Contractor {
+    out:        Vec<Vec<Link>>,
+    inc:        Vec<Vec<Link>>,
+    arc_first:  Vec<u32>,
+    arc_second: Vec<u32>,
+    dist:       Vec<u32>,
+    touched:    Vec<u32>,
+    heap:       BinaryHeap<Reverse<(u32, u32)>>,
+}
+
+impl Contractor {
+    fn new(network: &RoadNetwork) -> Self {
+        let n = network.node_count();
+        let mut graph = Self {
+            out:        vec![Vec::new(); n],
+            inc:        vec![Vec::new(); n],
+            arc_first:  Vec::new(),
+            arc_second: Vec::new(),
+            dist:       vec![u32::MAX; n],
+            touched:    Vec::new(),
+            heap:       BinaryHeap::new(),
+        };
+        for e in 0..network.edge_count() {
+            let (from, to, cost) = (network.edge_from[e].0, network.edge_to[e].0, network.edge_travel_ms[e]);
+            if from != to && cost != u32::MAX {
+                graph.insert(Shortcut { from, to, cost, first: e as u32, second: NONE });
+            }
+        }
+        graph
+    }
+
+    /// Add arc `s.from → s.to` unless an existing one is at least as fast.
+    fn insert(&mut self, s: Shortcut) {
+        let existing = self.out[s.from as usize].iter().position(|l| l.node == s.to);
+        if existing.is_some_and(|i| self.out[s.from as usize][i].cost <= s.cost) {
+            return;
+        }
+        let arc = self.arc_first.len() as u32;
+        self.arc_first.push(s.first);
+        self.arc_second.push(s.second);
+        let forward  = Link { node: s.to, cost: s.cost, arc };
+        let backward = Link { node: s.from, cost: s.cost, arc };
+        match existing {
+            Some(i) => {
+                self.out[s.from as usize][i] = forward;
+                let inc = &mut self.inc[s.to as usize];
+                if let Some(j) = inc.iter().position(|l| l.node == s.from) {
+                    inc[j] = backward;
+                }
+            }
+            None => {
+                self.out[s.from as usize].push(forward);
+                self.inc[s.to as usize].push(backward);
+            }
+        }
+    }
+
+    /// Shortcuts that contracting `v` would need.
+    fn shortcuts(&mut self, v: u32) -> Vec<Shortcut> {
+        let mut needed = Vec::new();
+        let outs = self.out[v as usize].clone();
+        for i in 0..self.inc[v as usize].len() {
+            let into = self.inc[v as usize][i];
+            let Some(limit) = outs
+                .iter()
+                .filter(|o| o.node != into.node)
+                .map(|o| into.cost.saturating_add(o.cost))
+                .max()
+            else {
+                continue;
+            };
+            self.witness_search(into.node, v, limit);
+            for o in outs.iter().filter(|o| o.node != into.node) {
+                let cost = into.cost.saturating_add(o.cost);
+                if self.dist[o.node as usize] > cost {
+                    needed.push(Shortcut { from: into.node, to: o.node, cost, first: into.arc, second: o.arc });
+                }
+            }
+        }
+        needed
+    }
+
+    /// Edge difference plus contracted neighbours: lower is contracted first.
+    fn priority(&self, v: u32, shortcuts: usize, contracted_neighbours: u32) -> i64 {
+        let removed = self.out[v as usize].len() + self.inc[v as usize].len();
+        shortcuts as i64 - removed as i64 + contracted_neighbours as i64
+    }
+
+    /// Add `shortcuts` and remove `v`, returning its outgoing and incoming arcs.
+    fn contract(&mut self, v: u32, shortcuts: Vec<Shortcut>) -> (Vec<Link>, Vec<Link>) {
+        for s in shortcuts {
+            self.insert(s);
+        }
+        let out = std::mem::take(&mut self.out[v as usize]);
+        let inc = std::mem::take(&mut self.inc[v as usize]);
+        for link in &out {
+            self.inc[link.node as usize].retain(|l| l.node != v);
+        }
+        for link in &inc {
+            self.out[link.node as usize].retain(|l| l.node != v);
+        }
+        (out, inc)
+    }
+
+    /// Bounded Dijkstra from `source` avoiding `skip`, leaving distances in
+    /// `dist`.  Every finite distance is the length of a real path, settled
+    /// or not, so any of them is a valid witness.
+    fn witness_search(&mut self, source: u32, skip: u32, limit: u32) {
+        for &node in &self.touched {
+            self.dist[node as usize] = u32::MAX;
+        }
+        self.touched.clear();
+        self.heap.clear();
+        self.dist[source as usize] = 0;
+        self.touched.push(source);
+        self.heap.push(Reverse((0, source)));
+
+        let mut settled = 0;
+        while let Some(Reverse((cost, node))) = self.heap.pop() {
+            if cost > self.dist[node as usize] {
+                continue;
+            }
+            if cost > limit || settled == WITNESS_SETTLE_LIMIT {
+                break;
+            }
+            settled += 1;
+            for link in &self.out[node as usize] {
+                if link.node == skip {
+                    continue;
+                }
+                let next = cost.saturating_add(link.cost);
+                let dist = &mut self.dist[link.node as usize];
+                if next < *dist {
+                    if *dist == u32::MAX {
+                        self.touched.push(link.node);
+                    }
+                    *dist = next;
+                    self.heap.push(Reverse((next, link.node)));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Pluggable edge costs.
+//!
+//! [`DijkstraRouter`] weighs edges by travel time at fixed mode speeds.
+//! An [`EdgeCost`] replaces that weight — to add tolls, keep some agents
+//! off motorways, or prefer quiet streets for cyclists — and
+//! [`DijkstraRouter::with_cost`] routes by it.  Costs only choose the path:
+//! the route's `total_travel_ms` is still the mode's travel time along
+//! it, so arrival ticks stay physical.
+//!
+//! Two objectives are built in: [`ModeSpeedCost`], the default
+//! fastest-path weight, and [`DistanceCost`] for shortest-distance routing —
+//! what logistics and mileage-billed fleets usually want.  Anything else is
+//! a closure or an `EdgeCost` impl of your own.
+//!
+//! Costs see the travelling agent when the router is called through
+//! [`Router::route_for`], as `dt-mobility` does for every trip; plain
+//! [`route`](Router::route) calls pass `None`.
+//!
+//! # Example
+//!
+//! ```
+//! use dt_core::{AgentId, EdgeId, GeoPoint, TransportMode};
+//! use dt_spatial::{DijkstraRouter, EdgeCost, ModeSpeedCost, RoadNetwork, RoadNetworkBuilder, Router};
+//!
+//! let mut b = RoadNetworkBuilder::new();
+//! let a = b.add_node(GeoPoint::new(30.69, -88.04));
+//! let c = b.add_node(GeoPoint::new(30.70, -88.03));
+//! b.add_directed_edge(a, c, 1_200.0, 60_000);    // fast road
+//! b.add_directed_edge(a, c, 1_200.0, 90_000);    // slower road
+//! let net = b.build();
+//!
+//! // Avoid anything faster than 15 m/s, as if it were a motorway.
+//! let avoid_fast = DijkstraRouter::with_cost(|net: &RoadNetwork, edge: EdgeId, mode, agent: Option<AgentId>| {
+//!     let ms = ModeSpeedCost.cost_ms(net, edge, mode, agent);
+//!     if net.edge_freeflow_mps[edge.index()] > 15.0 { ms.saturating_mul(10) } else { ms }
+//! });
+//! let route = avoid_fast.route(&net, a, c, TransportMode::Car).unwrap();
+//! assert_eq!(route.total_travel_secs(), 90.0);
+//! ```
+
+use dt_core::{AgentId, EdgeId, NodeId, Tick, TransportMode};
+
+use crate::network::RoadNetwork;
+use crate::router::{dijkstra, edge_cost_ms, DijkstraRouter, Route, Router};
+use crate::SpatialError;
+
+// ── EdgeCost ──────────────────────────────────────────────────────────────────
+
+/// The weight of an edge for routing, in milliseconds-equivalent.
+///
+/// `u32::MAX` makes an edge impassable.  Implemented for closures with the
+/// same signature as [`cost_ms`](Self::cost_ms).
+pub trait EdgeCost: Send + Sync {
+    /// Cost of traversing `edge` by `mode`, for `agent_hint` if the caller
+    /// knows who is travelling.
+    fn cost_ms(&self, network: &RoadNetwork, edge: EdgeId, mode: TransportMode, agent_hint: Option<AgentId>) -> u32;
+
+    /// `true` if the cost may differ between agents (the default, as for
+    /// closures); [`CostRouter`] reports it through
+    /// [`Router::depends_on_agent`].
+    fn depends_on_agent(&self) -> bool {
+        true
+    }
+}
+
+impl<F> EdgeCost for F
+where
+    F: Fn(&RoadNetwork, EdgeId, TransportMode, Option<AgentId>) -> u32 + Send + Sync,
+{
+    #[inline]
+    fn cost_ms(&self, network: &RoadNetwork, edge: EdgeId, mode: TransportMode, agent_hint: Option<AgentId>) -> u32 {
+        self(network, edge, mode, agent_hint)
+    }
+}
+
+/// The built-in cost: travel time at the speeds in [`DijkstraRouter`]'s
+/// docs, with the network's overlay applied.  A starting point for custom
+/// costs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ModeSpeedCost;
+
+impl EdgeCost for ModeSpeedCost {
+    #[inline]
+    fn cost_ms(&self, network: &RoadNetwork, edge: EdgeId, mode: TransportMode, _agent_hint: Option<AgentId>) -> u32 {
+        edge_cost_ms(network, edge, mode)
+    }
+
+    fn depends_on_agent(&self) -> bool {
+        false
+    }
+}
+
+/// Edge length in centimetres: [`DijkstraRouter::with_cost(DistanceCost)`]
+/// finds the shortest path by distance rather than by time.  Edges the
+/// mode cannot use — closed, or closed for it by the overlay — stay
+/// impassable.
+///
+/// [`DijkstraRouter::with_cost(DistanceCost)`]: DijkstraRouter::with_cost
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DistanceCost;
+
+impl EdgeCost for DistanceCost {
+    #[inline]
+    fn cost_ms(&self, network: &RoadNetwork, edge: EdgeId, mode: TransportMode, _agent_hint: Option<AgentId>) -> u32 {
+        match edge_cost_ms(network, edge, mode) {
+            u32::MAX => u32::MAX,
+            _ => (network.edge_length_m[edge.index()] * 100.0).round() as u32,
+        }
+    }
+
+    fn depends_on_agent(&self) -> bool {
+        false
+    }
+}
+
+// ── CostRouter ────────────────────────────────────────────────────────────────
+
+/// Dijkstra weighted by an [`EdgeCost`].  Build with
+/// [`DijkstraRouter::with_cost`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CostRouter<C> {
+    cost: C,
+}
+
+impl DijkstraRouter {
+    /// A Dijkstra router that weighs edges by `cost` instead of travel time.
+    pub fn with_cost<C: EdgeCost>(cost: C) -> CostRouter<C> {
+        CostRouter { cost }
+    }
+}
+
+impl<C: EdgeCost> CostRouter<C> {
+    /// The cost function.
+    pub fn cost(&self) -> &C {
+        &self.cost
+    }
+
+    fn search(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+        agent: Option<AgentId>,
+    ) -> Result<Route, SpatialError> {
+        let mut route = dijkstra(network, from, to, |edge, _| self.cost.cost_ms(network, edge, mode, agent))?;
+        let ms: u64 = route.edges.iter().map(|&e| edge_cost_ms(network, e, mode) as u64).sum();
+        route.total_travel_ms = ms;
+        Ok(route)
+    }
+}
+
+impl<C: EdgeCost> Router for CostRouter<C> {
+    fn route(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+    ) -> Result<Route, SpatialError> {
+        self.search(network, from, to, mode, None)
+    }
+
+    fn route_for(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+        _departure: Tick,
+        _tick_duration_secs: u32,
+        agent: AgentId,
+    ) -> Result<Route, SpatialError> {
+        self.search(network, from, to, mode, Some(agent))
+    }
+
+    fn depends_on_agent(&self) -> bool {
+        self.cost.depends_on_agent()
+    }
+}
@@ -2,7 +2,7 @@
 
 use thiserror::Error;
 
-use dt_core::NodeId;
+use dt_core::{DtError, ErrorCategory, NodeId};
 
 /// Errors produced by `dt-spatial`.
 #[derive(Debug, Error)]
@@ -13,12 +13,41 @@ pub enum SpatialError {
     #[error("node {0} not found in network")]
     NodeNotFound(NodeId),
 
+    #[error("invalid contraction hierarchy: {0}")]
+    Hierarchy(String),
+
+    #[error("invalid landmark table: {0}")]
+    Landmarks(String),
+
+    #[error("invalid network file: {0}")]
+    NetworkFile(String),
+
+    #[error("invalid CSV network: {0}")]
+    Csv(String),
+
+    #[error("invalid transit line: {0}")]
+    Transit(String),
+
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
     #[cfg(feature = "osm")]
     #[error("OSM parse error: {0}")]
     Osm(String),
+
+    #[cfg(feature = "gis")]
+    #[error("invalid shapefile: {0}")]
+    Shapefile(String),
+
+    #[cfg(feature = "geojson")]
+    #[error("invalid GeoJSON: {0}")]
+    GeoJson(String),
 }
 
 pub type SpatialResult<T> = Result<T, SpatialError>;
+
+impl From<SpatialError> for DtError {
+    fn from(err: SpatialError) -> Self {
+        DtError::subsystem(ErrorCategory::Spatial, err)
+    }
+}
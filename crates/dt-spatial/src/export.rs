@@ -0,0 +1,109 @@
+//! Graph export for analysis in other tools.
+//!
+//! [`RoadNetwork::write_graphml`] writes the network as a directed GraphML
+//! graph that `networkx.read_graphml` and igraph's `Graph.Read_GraphML`
+//! load as is, with positions and edge weights as attributes.
+//! [`RoadNetwork::write_dot`] writes Graphviz DOT, which is only practical
+//! for a few thousand nodes but is handy for looking at test networks.
+//!
+//! Both write the network as built: the overlay, speed profiles, and edge
+//! geometry are not applied.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! network.save_graphml(Path::new("mobile.graphml"))?;
+//! ```
+//!
+//! ```python
+//! g = networkx.read_graphml("mobile.graphml")
+//! networkx.shortest_path(g, "n0", "n42", weight="travel_secs")
+//! ```
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::network::RoadNetwork;
+use crate::SpatialResult;
+
+/// GraphML attribute keys: `(id, for, name, type)`.
+const GRAPHML_KEYS: [(&str, &str, &str, &str); 7] = [
+    ("d0", "node", "lat", "double"),
+    ("d1", "node", "lon", "double"),
+    ("d2", "edge", "edge_id", "long"),
+    ("d3", "edge", "length_m", "double"),
+    ("d4", "edge", "travel_secs", "double"),
+    ("d5", "edge", "capacity_vph", "double"),
+    ("d6", "edge", "freeflow_mps", "double"),
+];
+
+impl RoadNetwork {
+    /// Write the network to `path` as GraphML (see
+    /// [`write_graphml`](Self::write_graphml)).
+    pub fn save_graphml(&self, path: &Path) -> SpatialResult<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write_graphml(&mut w)?;
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Write a directed GraphML graph.  Node `i` has id `n{i}` and `lat` /
+    /// `lon` attributes; edges, in `EdgeId` order, carry `edge_id`,
+    /// `length_m`, `travel_secs` (`INF` when closed), `capacity_vph`, and
+    /// `freeflow_mps`.
+    pub fn write_graphml(&self, w: &mut impl Write) -> SpatialResult<()> {
+        writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(w, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#)?;
+        for (id, target, name, ty) in GRAPHML_KEYS {
+            writeln!(w, r#"  <key id="{id}" for="{target}" attr.name="{name}" attr.type="{ty}"/>"#)?;
+        }
+        writeln!(w, r#"  <graph id="road_network" edgedefault="directed">"#)?;
+        for (i, pos) in self.node_pos.iter().enumerate() {
+            writeln!(
+                w,
+                r#"    <node id="n{i}"><data key="d0">{}</data><data key="d1">{}</data></node>"#,
+                pos.lat, pos.lon
+            )?;
+        }
+        for e in 0..self.edge_count() {
+            write!(w, r#"    <edge id="e{e}" source="n{}" target="n{}">"#, self.edge_from[e].0, self.edge_to[e].0)?;
+            write!(w, r#"<data key="d2">{e}</data><data key="d3">{}</data>"#, self.edge_length_m[e])?;
+            match self.edge_travel_ms[e] {
+                u32::MAX => write!(w, r#"<data key="d4">INF</data>"#)?,
+                ms => write!(w, r#"<data key="d4">{}</data>"#, ms as f64 / 1000.0)?,
+            }
+            writeln!(
+                w,
+                r#"<data key="d5">{}</data><data key="d6">{}</data></edge>"#,
+                self.edge_capacity_vph[e], self.edge_freeflow_mps[e]
+            )?;
+        }
+        writeln!(w, "  </graph>")?;
+        writeln!(w, "</graphml>")?;
+        Ok(())
+    }
+
+    /// Write a Graphviz `digraph`.  Nodes are pinned at `pos="lon,lat!"` for
+    /// `neato -n`; edges are labelled with their travel time in seconds and
+    /// weighted by it in whole seconds.  Closed edges are drawn dashed.
+    pub fn write_dot(&self, w: &mut impl Write) -> SpatialResult<()> {
+        writeln!(w, "digraph road_network {{")?;
+        writeln!(w, "  node [shape=point];")?;
+        for (i, pos) in self.node_pos.iter().enumerate() {
+            writeln!(w, r#"  n{i} [pos="{},{}!"];"#, pos.lon, pos.lat)?;
+        }
+        for e in 0..self.edge_count() {
+            let (from, to) = (self.edge_from[e].0, self.edge_to[e].0);
+            match self.edge_travel_ms[e] {
+                u32::MAX => writeln!(w, r#"  n{from} -> n{to} [label="closed", style=dashed];"#)?,
+                ms => {
+                    let secs = ms as f64 / 1000.0;
+                    writeln!(w, r#"  n{from} -> n{to} [label="{secs:.1} s", weight={}];"#, (secs as u64).max(1))?
+                }
+            }
+        }
+        writeln!(w, "}}")?;
+        Ok(())
+    }
+}
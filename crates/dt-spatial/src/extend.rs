@@ -0,0 +1,204 @@
+//! Incremental additions to a built network.
+//!
+//! [`RoadNetworkBuilder::build`](crate::RoadNetworkBuilder::build) is
+//! one-shot.  To add a new development area to a network that is already
+//! loaded — and may already carry an overlay, speed profiles, and edge
+//! attributes — collect the new nodes and edges in a [`NetworkExtension`]
+//! and apply it with [`RoadNetwork::extend`].
+//!
+//! Existing `NodeId`s are kept and new nodes are numbered after them.
+//! Edges stay sorted by source node, so existing `EdgeId`s shift to make
+//! room for new edges out of lower-numbered nodes; `extend` returns the
+//! old → new mapping.  The merge is O(N + E) with no re-sort, and only the
+//! new nodes are inserted into the node R-tree.
+//!
+//! # Example
+//!
+//! ```
+//! use dt_core::{GeoPoint, NodeId, TransportMode};
+//! use dt_spatial::{DijkstraRouter, RoadNetworkBuilder, Router};
+//!
+//! let mut b = RoadNetworkBuilder::new();
+//! let a = b.add_node(GeoPoint::new(30.690, -88.040));
+//! let c = b.add_node(GeoPoint::new(30.700, -88.040));
+//! b.add_road(a, c, 1_100.0, 90_000);
+//! let mut net = b.build();
+//!
+//! let mut ext = net.extension();
+//! let estate = ext.add_node(GeoPoint::new(30.700, -88.030));
+//! ext.add_road(c, estate, 950.0, 80_000);
+//! let remap = net.extend(ext);
+//!
+//! assert_eq!(estate, NodeId(2));
+//! assert_eq!(remap.len(), 2);
+//! let route = DijkstraRouter.route(&net, a, estate, TransportMode::Car).unwrap();
+//! assert_eq!(route.total_travel_secs(), 170.0);
+//! ```
+
+use dt_core::{EdgeId, GeoPoint, NodeId};
+
+use crate::network::{capacity_for_speed, freeflow_mps, RawEdge, RoadNetwork};
+
+// ── NetworkExtension ──────────────────────────────────────────────────────────
+
+/// Nodes and edges to add to a [`RoadNetwork`].  Made by
+/// [`RoadNetwork::extension`]; edges may join new nodes to existing ones.
+pub struct NetworkExtension {
+    /// Node count of the network this extends.
+    base_nodes: usize,
+    nodes:      Vec<GeoPoint>,
+    edges:      Vec<RawEdge>,
+}
+
+impl NetworkExtension {
+    /// Add a node and return its `NodeId`, numbered after the network's.
+    pub fn add_node(&mut self, pos: GeoPoint) -> NodeId {
+        self.nodes.push(pos);
+        NodeId((self.base_nodes + self.nodes.len() - 1) as u32)
+    }
+
+    /// Add a directed edge, as
+    /// [`RoadNetworkBuilder::add_directed_edge`](crate::RoadNetworkBuilder::add_directed_edge).
+    pub fn add_directed_edge(&mut self, from: NodeId, to: NodeId, length_m: f32, travel_ms: u32) {
+        let capacity_vph = capacity_for_speed(freeflow_mps(length_m, travel_ms));
+        self.add_directed_edge_with_capacity(from, to, length_m, travel_ms, capacity_vph);
+    }
+
+    /// Add a directed edge with a known capacity in vehicles per hour.
+    pub fn add_directed_edge_with_capacity(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        length_m: f32,
+        travel_ms: u32,
+        capacity_vph: f32,
+    ) {
+        self.edges.push(RawEdge { from, to, length_m, travel_ms, capacity_vph });
+    }
+
+    /// Add edges in both directions.
+    pub fn add_road(&mut self, a: NodeId, b: NodeId, length_m: f32, travel_ms: u32) {
+        self.add_directed_edge(a, b, length_m, travel_ms);
+        self.add_directed_edge(b, a, length_m, travel_ms);
+    }
+
+    /// As [`add_road`](Self::add_road), with a known capacity per direction.
+    pub fn add_road_with_capacity(&mut self, a: NodeId, b: NodeId, length_m: f32, travel_ms: u32, capacity_vph: f32) {
+        self.add_directed_edge_with_capacity(a, b, length_m, travel_ms, capacity_vph);
+        self.add_directed_edge_with_capacity(b, a, length_m, travel_ms, capacity_vph);
+    }
+
+    /// Number of new nodes.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Number of new edges.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+}
+
+// ── Applying an extension ─────────────────────────────────────────────────────
+
+impl RoadNetwork {
+    /// An empty extension of this network.
+    pub fn extension(&self) -> NetworkExtension {
+        NetworkExtension { base_nodes: self.node_count(), nodes: Vec::new(), edges: Vec::new() }
+    }
+
+    /// Add the nodes and edges of `extension`.
+    ///
+    /// Returns the edge remapping: entry `i` is the new id of old edge `i`;
+    /// ids not in it are the new edges.  New edges of a node follow its
+    /// existing ones, in the order they were added.  The overlay, speed
+    /// profiles, edge attributes, and edge geometry carry over, with new
+    /// edges unchanged, free-flow, defaulted, and straight; new nodes have
+    /// no OSM id.  The fingerprint changes.
+    ///
+    /// # Panics
+    ///
+    /// If `extension` was made for a network with a different node count,
+    /// or one of its edges refers to a node that does not exist.
+    pub fn extend(&mut self, extension: NetworkExtension) -> Vec<EdgeId> {
+        let NetworkExtension { base_nodes, nodes, mut edges } = extension;
+        assert_eq!(
+            base_nodes,
+            self.node_count(),
+            "extension was made for a network with {base_nodes} nodes, this one has {}",
+            self.node_count()
+        );
+        let node_count = base_nodes + nodes.len();
+        if let Some(e) = edges.iter().find(|e| e.from.index() >= node_count || e.to.index() >= node_count) {
+            panic!("extension edge {} → {} refers to a missing node ({node_count} nodes)", e.from, e.to);
+        }
+        // Stable, so each node's new edges keep the order they were added.
+        edges.sort_by_key(|e| e.from);
+
+        // `order[i]` is the source of merged edge `i`: old edge `k < old`,
+        // or new edge `k - old`.
+        let old = self.edge_count();
+        let mut order = Vec::with_capacity(old + edges.len());
+        let mut next = 0;
+        for v in 0..node_count {
+            if v < base_nodes {
+                order.extend(self.node_out_start[v] as usize..self.node_out_start[v + 1] as usize);
+            }
+            while next < edges.len() && edges[next].from.index() == v {
+                order.push(old + next);
+                next += 1;
+            }
+        }
+
+        let mut remap = vec![EdgeId::INVALID; old];
+        for (new, &k) in order.iter().enumerate() {
+            if k < old {
+                remap[k] = EdgeId(new as u32);
+            }
+        }
+
+        let edge_from = merged(&order, &self.edge_from, edges.iter().map(|e| e.from));
+        let edge_to = merged(&order, &self.edge_to, edges.iter().map(|e| e.to));
+        let edge_length_m = merged(&order, &self.edge_length_m, edges.iter().map(|e| e.length_m));
+        let edge_travel_ms = merged(&order, &self.edge_travel_ms, edges.iter().map(|e| e.travel_ms));
+        let edge_capacity_vph = merged(&order, &self.edge_capacity_vph, edges.iter().map(|e| e.capacity_vph));
+        let edge_freeflow_mps =
+            merged(&order, &self.edge_freeflow_mps, edges.iter().map(|e| freeflow_mps(e.length_m, e.travel_ms)));
+
+        let added = edges.len();
+        if let Some(profiles) = self.speed_profiles.as_mut() {
+            profiles.append_edges(added);
+            *profiles = profiles.select_edges(&order);
+        }
+        if let Some(overlay) = self.overlay.as_mut() {
+            overlay.append_edges(added);
+            *overlay = overlay.select_edges(&order);
+        }
+        self.edge_attrs.append_edges(added);
+        self.edge_attrs = self.edge_attrs.select_edges(&order);
+        if let Some(geometry) = self.edge_geometry.as_mut() {
+            geometry.append_edges(added);
+            *geometry = geometry.select_edges(&order);
+        }
+
+        if let Some(ids) = self.node_osm_ids.as_mut() {
+            ids.resize(node_count, 0);
+        }
+        self.splice(
+            &nodes,
+            edge_from,
+            edge_to,
+            edge_length_m,
+            edge_travel_ms,
+            edge_capacity_vph,
+            edge_freeflow_mps,
+        );
+        remap
+    }
+}
+
+/// `old` followed by `new`, arranged in `order`.
+fn merged<T: Copy>(order: &[usize], old: &[T], new: impl Iterator<Item = T>) -> Vec<T> {
+    let new: Vec<T> = new.collect();
+    order.iter().map(|&k| if k < old.len() { old[k] } else { new[k - old.len()] }).collect()
+}
@@ -0,0 +1,327 @@
+//! Shapefile loader — enabled with the `gis` Cargo feature.
+//!
+//! Agencies often publish road centerlines as ESRI shapefiles rather than
+//! OSM.  [`load_from_shapefile`] reads the polyline geometry from the `.shp`
+//! file and speed and one-way columns from the `.dbf` file beside it, as
+//! named by a [`FieldMapping`].
+//!
+//! # Usage
+//!
+//! ```ignore
+//! use std::path::Path;
+//! use dt_spatial::gis::{load_from_shapefile, FieldMapping};
+//!
+//! let fields = FieldMapping {
+//!     speed:        Some("SPEED_MPH".into()),
+//!     speed_to_mps: 0.447_04,
+//!     oneway:       Some("ONEWAY".into()),
+//!     ..FieldMapping::default()
+//! };
+//! let network = load_from_shapefile(Path::new("centerlines.shp"), &fields)?;
+//! ```
+//!
+//! # What is loaded
+//!
+//! Every vertex becomes a node, and vertices with identical coordinates —
+//! where lines meet — become the same node.  Consecutive vertices of each
+//! part are joined by an edge in both directions unless the one-way column
+//! says otherwise.  Null shapes and deleted records are skipped.
+//!
+//! Coordinates must be WGS 84 longitude/latitude; the `.prj` file is not
+//! read, so reproject projected data first (for example with
+//! `ogr2ogr -t_srs EPSG:4326`).  Other formats, GeoPackage included, can be
+//! converted to shapefiles the same way.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use dt_core::{GeoPoint, NodeId};
+
+use crate::network::{RoadNetwork, RoadNetworkBuilder};
+use crate::SpatialError;
+
+// ── Field mapping ─────────────────────────────────────────────────────────────
+
+/// Which `.dbf` columns hold each line's speed and direction.  Column names
+/// match case-insensitively.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldMapping {
+    /// Numeric speed column, or `None` to give every line
+    /// `default_speed_mps`.
+    pub speed: Option<String>,
+    /// Multiplier from the speed column's unit to m/s: `1.0 / 3.6` (the
+    /// default) for km/h, `0.447_04` for mph.
+    pub speed_to_mps: f32,
+    /// Speed for lines whose speed value is missing, unparseable, or not
+    /// positive.
+    pub default_speed_mps: f32,
+    /// One-way column, or `None` for all lines two-way.  `yes`, `y`,
+    /// `true`, `1`, and `FT` mean one-way in digitised direction; `TF`,
+    /// `-1`, and `reverse` mean one-way against it; anything else is
+    /// two-way.
+    pub oneway: Option<String>,
+}
+
+impl Default for FieldMapping {
+    fn default() -> Self {
+        Self {
+            speed:             None,
+            speed_to_mps:      1.0 / 3.6,
+            default_speed_mps: 8.9, // ~20 mph, the OSM loader's residential speed
+            oneway:            None,
+        }
+    }
+}
+
+// ── Public entry point ────────────────────────────────────────────────────────
+
+/// Load a road network from the polylines in the shapefile at `path` (the
+/// `.shp` file; the `.dbf` is found beside it).  See the module docs.
+///
+/// The `.dbf` file is only read when `fields` names a column.
+///
+/// # Errors
+///
+/// Returns [`SpatialError::Shapefile`] if either file is malformed, the
+/// shapes are not polylines, or a mapped column is missing, and
+/// [`SpatialError::Io`] on file errors.
+pub fn load_from_shapefile(path: &Path, fields: &FieldMapping) -> Result<RoadNetwork, SpatialError> {
+    let lines = read_shp(&std::fs::read(path)?)?;
+
+    let attrs = if fields.speed.is_some() || fields.oneway.is_some() {
+        let table = read_dbf(&std::fs::read(path.with_extension("dbf"))?)?;
+        if table.rows.len() != lines.len() {
+            return Err(malformed(format!(
+                ".dbf has {} records, .shp has {}",
+                table.rows.len(),
+                lines.len()
+            )));
+        }
+        Some(table)
+    } else {
+        None
+    };
+    let speed_col = column(attrs.as_ref(), fields.speed.as_deref())?;
+    let oneway_col = column(attrs.as_ref(), fields.oneway.as_deref())?;
+
+    let vertex_count: usize = lines.iter().flatten().flat_map(|l| &l.parts).map(Vec::len).sum();
+    let mut builder = RoadNetworkBuilder::with_capacity(vertex_count, vertex_count * 2);
+    let mut nodes: HashMap<(u64, u64), NodeId> = HashMap::with_capacity(vertex_count);
+
+    for (record, line) in lines.iter().enumerate() {
+        let Some(line) = line else { continue };
+        let row = attrs.as_ref().map(|t| &t.rows[record]);
+        if row.is_some_and(|r| r.deleted) {
+            continue;
+        }
+        let value = |col: Option<usize>| col.and_then(|c| row.map(|r| r.values[c].as_str()));
+
+        let speed_mps = value(speed_col)
+            .and_then(|v| v.parse::<f32>().ok())
+            .map(|v| v * fields.speed_to_mps)
+            .filter(|&v| v > 0.0)
+            .unwrap_or(fields.default_speed_mps);
+        let direction = value(oneway_col).map_or(Direction::Both, Direction::parse);
+
+        for part in &line.parts {
+            let ids: Vec<NodeId> = part
+                .iter()
+                .map(|&(x, y)| {
+                    *nodes
+                        .entry((x.to_bits(), y.to_bits()))
+                        .or_insert_with(|| builder.add_node(GeoPoint::new(y as f32, x as f32)))
+                })
+                .collect();
+            for pair in ids.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                if a == b {
+                    continue;
+                }
+                let len_m = builder.node_pos(a).approx_distance_m(builder.node_pos(b));
+                let travel_ms = (len_m / speed_mps * 1_000.0) as u32;
+                if direction != Direction::Backward {
+                    builder.add_directed_edge(a, b, len_m, travel_ms);
+                }
+                if direction != Direction::Forward {
+                    builder.add_directed_edge(b, a, len_m, travel_ms);
+                }
+            }
+        }
+    }
+
+    Ok(builder.build())
+}
+
+// ── Internals ─────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Forward,
+    Backward,
+    Both,
+}
+
+impl Direction {
+    fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "yes" | "y" | "true" | "1" | "ft" => Direction::Forward,
+            "tf" | "-1" | "reverse" => Direction::Backward,
+            _ => Direction::Both,
+        }
+    }
+}
+
+fn malformed(msg: impl Into<String>) -> SpatialError {
+    SpatialError::Shapefile(msg.into())
+}
+
+/// Index of column `name` in `table`, or `None` if no column is mapped.
+fn column(table: Option<&DbfTable>, name: Option<&str>) -> Result<Option<usize>, SpatialError> {
+    let (Some(table), Some(name)) = (table, name) else { return Ok(None) };
+    table
+        .columns
+        .iter()
+        .position(|c| c.eq_ignore_ascii_case(name))
+        .map(Some)
+        .ok_or_else(|| malformed(format!("no column named {name:?} in .dbf")))
+}
+
+/// One shape record: `(x, y)` = (lon, lat) vertices of each part.
+struct Polyline {
+    parts: Vec<Vec<(f64, f64)>>,
+}
+
+struct DbfRow {
+    deleted: bool,
+    values:  Vec<String>,
+}
+
+struct DbfTable {
+    columns: Vec<String>,
+    rows:    Vec<DbfRow>,
+}
+
+/// Bounds-checked little- and big-endian reads.
+struct Bytes<'a>(&'a [u8]);
+
+impl Bytes<'_> {
+    fn slice(&self, at: usize, len: usize) -> Result<&[u8], SpatialError> {
+        at.checked_add(len)
+            .and_then(|end| self.0.get(at..end))
+            .ok_or_else(|| malformed("file is truncated"))
+    }
+
+    fn i32_be(&self, at: usize) -> Result<i32, SpatialError> {
+        Ok(i32::from_be_bytes(self.slice(at, 4)?.try_into().unwrap()))
+    }
+
+    fn i32_le(&self, at: usize) -> Result<i32, SpatialError> {
+        Ok(i32::from_le_bytes(self.slice(at, 4)?.try_into().unwrap()))
+    }
+
+    fn u32_le(&self, at: usize) -> Result<u32, SpatialError> {
+        Ok(u32::from_le_bytes(self.slice(at, 4)?.try_into().unwrap()))
+    }
+
+    fn u16_le(&self, at: usize) -> Result<u16, SpatialError> {
+        Ok(u16::from_le_bytes(self.slice(at, 2)?.try_into().unwrap()))
+    }
+
+    fn f64_le(&self, at: usize) -> Result<f64, SpatialError> {
+        Ok(f64::from_le_bytes(self.slice(at, 8)?.try_into().unwrap()))
+    }
+}
+
+const SHP_FILE_CODE: i32 = 9994;
+const SHP_HEADER_LEN: usize = 100;
+const SHAPE_NULL: i32 = 0;
+/// PolyLine, PolyLineZ, PolyLineM.  The Z and M values are ignored.
+const SHAPE_POLYLINES: [i32; 3] = [3, 13, 23];
+
+/// The records of a `.shp` file, `None` for null shapes.
+fn read_shp(data: &[u8]) -> Result<Vec<Option<Polyline>>, SpatialError> {
+    let bytes = Bytes(data);
+    if bytes.i32_be(0)? != SHP_FILE_CODE {
+        return Err(malformed("not a .shp file"));
+    }
+    let shape_type = bytes.i32_le(32)?;
+    if shape_type != SHAPE_NULL && !SHAPE_POLYLINES.contains(&shape_type) {
+        return Err(malformed(format!("shape type {shape_type} is not a polyline")));
+    }
+
+    let mut records = Vec::new();
+    let mut at = SHP_HEADER_LEN;
+    while at < data.len() {
+        // Record header: number and content length in 16-bit words.
+        let content_len = bytes.i32_be(at + 4)?.max(0) as usize * 2;
+        let content = Bytes(bytes.slice(at + 8, content_len)?);
+        at += 8 + content_len;
+
+        let record_type = content.i32_le(0)?;
+        if record_type == SHAPE_NULL {
+            records.push(None);
+            continue;
+        }
+        if !SHAPE_POLYLINES.contains(&record_type) {
+            return Err(malformed(format!("shape type {record_type} is not a polyline")));
+        }
+        // Skip the 32-byte bounding box.
+        let num_parts = content.i32_le(36)?.max(0) as usize;
+        let num_points = content.i32_le(40)?.max(0) as usize;
+        let points_at = 44 + 4 * num_parts;
+        let mut starts = Vec::with_capacity(num_parts + 1);
+        for p in 0..num_parts {
+            starts.push((content.i32_le(44 + 4 * p)?.max(0) as usize).min(num_points));
+        }
+        starts.push(num_points);
+
+        let mut parts = Vec::with_capacity(num_parts);
+        for w in starts.windows(2) {
+            let part = (w[0]..w[1].max(w[0]))
+                .map(|i| Ok((content.f64_le(points_at + 16 * i)?, content.f64_le(points_at + 16 * i + 8)?)))
+                .collect::<Result<Vec<_>, SpatialError>>()?;
+            parts.push(part);
+        }
+        records.push(Some(Polyline { parts }));
+    }
+    Ok(records)
+}
+
+const DBF_FIELD_LEN: usize = 32;
+const DBF_HEADER_END: u8 = 0x0D;
+
+/// The columns and rows of a `.dbf` file, every value as trimmed text.
+fn read_dbf(data: &[u8]) -> Result<DbfTable, SpatialError> {
+    let bytes = Bytes(data);
+    let row_count = bytes.u32_le(4)? as usize;
+    let header_len = bytes.u16_le(8)? as usize;
+    let row_len = bytes.u16_le(10)? as usize;
+
+    let mut columns = Vec::new();
+    let mut widths = Vec::new();
+    let mut at = 32;
+    while *bytes.slice(at, 1)?.first().unwrap() != DBF_HEADER_END {
+        let field = bytes.slice(at, DBF_FIELD_LEN)?;
+        let name = &field[..11];
+        let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+        columns.push(String::from_utf8_lossy(name).trim().to_string());
+        widths.push(field[16] as usize);
+        at += DBF_FIELD_LEN;
+    }
+    if 1 + widths.iter().sum::<usize>() > row_len {
+        return Err(malformed(".dbf fields are wider than its records"));
+    }
+
+    let mut rows = Vec::with_capacity(row_count);
+    for r in 0..row_count {
+        let row = bytes.slice(header_len + r * row_len, row_len)?;
+        let mut values = Vec::with_capacity(widths.len());
+        let mut col_at = 1;
+        for &width in &widths {
+            values.push(String::from_utf8_lossy(&row[col_at..col_at + width]).trim().to_string());
+            col_at += width;
+        }
+        rows.push(DbfRow { deleted: row[0] == b'*', values });
+    }
+    Ok(DbfTable { columns, rows })
+}
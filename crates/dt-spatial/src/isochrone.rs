@@ -0,0 +1,51 @@
+//! Reachability within a travel-time budget.
+//!
+//! [`RoadNetwork::reachable_nodes`] runs one Dijkstra from an origin and
+//! stops once the budget is spent, returning every node it settled with its
+//! travel time.  Costs per mode are those of [`DijkstraRouter`], so a node is
+//! listed exactly when `DijkstraRouter` would route to it within the budget.
+//!
+//! Typical uses are accessibility measures ("jobs within 30 minutes by
+//! bike") and behaviour models choosing among destinations "within 20
+//! minutes".
+//!
+//! [`DijkstraRouter`]: crate::DijkstraRouter
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use dt_core::{NodeId, TransportMode};
+
+use crate::network::RoadNetwork;
+use crate::router::edge_cost_ms;
+
+impl RoadNetwork {
+    /// Every node reachable from `from` within `max_secs` by `mode`, with its
+    /// travel time in seconds, nearest first (ties by `NodeId`).
+    ///
+    /// `from` itself is always first, at `0.0`.
+    pub fn reachable_nodes(&self, from: NodeId, max_secs: f32, mode: TransportMode) -> Vec<(NodeId, f32)> {
+        let budget_ms = (max_secs.max(0.0) as f64 * 1000.0).min(u32::MAX as f64 - 1.0) as u32;
+        let mut dist = vec![u32::MAX; self.node_count()];
+        let mut heap: BinaryHeap<Reverse<(u32, NodeId)>> = BinaryHeap::new();
+        let mut reached = Vec::new();
+        dist[from.index()] = 0;
+        heap.push(Reverse((0, from)));
+
+        while let Some(Reverse((cost, node))) = heap.pop() {
+            if cost > dist[node.index()] {
+                continue;
+            }
+            reached.push((node, cost as f32 / 1000.0));
+            for edge in self.out_edges(node) {
+                let neighbor = self.edge_to[edge.index()];
+                let new_cost = cost.saturating_add(edge_cost_ms(self, edge, mode));
+                if new_cost <= budget_ms && new_cost < dist[neighbor.index()] {
+                    dist[neighbor.index()] = new_cost;
+                    heap.push(Reverse((new_cost, neighbor)));
+                }
+            }
+        }
+        reached
+    }
+}
@@ -4,9 +4,29 @@
 //!
 //! | Module      | Contents                                                    |
 //! |-------------|-------------------------------------------------------------|
-//! | [`network`] | `RoadNetwork` (CSR + R-tree), `RoadNetworkBuilder`          |
-//! | [`router`]  | `Router` trait, `Route`, `DijkstraRouter`                  |
-//! | [`osm`]     | `load_from_pbf` (feature = `"osm"` only)                   |
+//! | [`network`] | `RoadNetwork` (CSR + R-tree), `RoadNetworkBuilder`, `diagnostics` |
+//! | [`attributes`] | `EdgeAttributes`: typed per-edge application data        |
+//! | [`overlay`] | `NetworkOverlay`: runtime closures and travel-time factors    |
+//! | [`extend`]  | `RoadNetwork::extend`: add nodes and edges to a built network |
+//! | [`simplify`] | `RoadNetwork::simplify`: degree-2 contraction, `EdgeGeometry` |
+//! | [`persist`] | `RoadNetwork::save` / `load` in a compact binary format      |
+//! | [`export`]  | `RoadNetwork::write_graphml` / `write_dot` for other graph tools |
+//! | [`router`]  | `Router` trait, `Route`, Dijkstra, bidirectional, and time-dependent routers |
+//! | [`cost`]    | `EdgeCost` trait, `ModeSpeedCost`, `DistanceCost`, `DijkstraRouter::with_cost` |
+//! | [`cache`]   | `CachedRouter`: LRU memoisation around any `Router`         |
+//! | [`alternatives`] | `DijkstraRouter::route_k`: diverse alternative routes      |
+//! | [`pareto`]  | `ParetoRouter`: Pareto frontier over two `EdgeCost`s          |
+//! | [`matrix`]  | `TravelTimeMatrix` from `DijkstraRouter::travel_time_matrix`  |
+//! | [`mapmatch`] | `map_match`: HMM matching of GPS traces onto edges         |
+//! | [`isochrone`] | `RoadNetwork::reachable_nodes` within a travel-time budget  |
+//! | [`profile`] | `SpeedProfiles`: hourly travel-time factors per edge          |
+//! | [`zones`]   | `ZoneSet`: TAZ polygons, `zone_of`, node → zone; GeoJSON (feature `"geojson"`) |
+//! | [`transit`] | `TransitRouter` over timetabled `TransitLine`s plus walking   |
+//! | [`ch`]      | `ContractionHierarchy` preprocessing, `ChRouter`            |
+//! | [`alt`]     | `Landmarks` preprocessing, `AltRouter` (A* with landmarks)  |
+//! | [`osm`]     | `load_from_pbf`, `PbfLoadOptions` filters (feature `"osm"`)  |
+//! | [`gis`]     | `load_from_shapefile` (feature `"gis"`)                     |
+//! | [`tabular`] | `load_from_csv`: node and edge CSV tables                   |
 //! | [`error`]   | `SpatialError`, `SpatialResult<T>`                         |
 //!
 //! # Feature flags
@@ -14,18 +34,58 @@
 //! | Flag    | Effect                                                       |
 //! |---------|--------------------------------------------------------------|
 //! | `osm`   | Enables OSM PBF loading via the `osmpbf` crate.             |
+//! | `gis`   | Enables ESRI shapefile loading.                              |
+//! | `geojson` | Enables `ZoneSet::load_geojson` via `serde_json`.          |
 //! | `serde` | Derives `Serialize`/`Deserialize` on public types.           |
+//! | `parallel` | Runs travel-time matrix searches on Rayon's thread pool.  |
 
+pub mod alt;
+pub mod alternatives;
+pub mod attributes;
+pub mod cache;
+pub mod ch;
+pub mod cost;
 pub mod error;
+pub mod export;
+pub mod extend;
+pub mod isochrone;
+pub mod mapmatch;
+pub mod matrix;
 pub mod network;
+pub mod overlay;
+pub mod pareto;
+pub mod persist;
+pub mod profile;
 pub mod router;
+pub mod simplify;
+pub mod tabular;
+pub mod transit;
+pub mod zones;
 
 #[cfg(feature = "osm")]
 pub mod osm;
 
+#[cfg(feature = "gis")]
+pub mod gis;
+
 #[cfg(test)]
 mod tests;
 
+pub use alt::{AltRouter, Landmarks};
+pub use attributes::{EdgeAttributes, OsmWayId, StreetName};
+pub use cache::CachedRouter;
+pub use ch::{ChRouter, ContractionHierarchy};
+pub use cost::{CostRouter, DistanceCost, EdgeCost, ModeSpeedCost};
 pub use error::{SpatialError, SpatialResult};
-pub use network::{RoadNetwork, RoadNetworkBuilder};
-pub use router::{DijkstraRouter, Route, Router};
+pub use extend::NetworkExtension;
+pub use mapmatch::{map_match, map_match_with, MapMatchConfig};
+pub use matrix::TravelTimeMatrix;
+pub use network::{NetworkDiagnostics, RoadNetwork, RoadNetworkBuilder};
+pub use overlay::NetworkOverlay;
+pub use pareto::{ParetoRoute, ParetoRouter};
+pub use profile::SpeedProfiles;
+pub use router::{BidirectionalRouter, DijkstraRouter, Route, RouteLeg, Router, TimeDependentRouter};
+pub use simplify::EdgeGeometry;
+pub use tabular::load_from_csv;
+pub use transit::{TransitLine, TransitRide, TransitRouter};
+pub use zones::{Zone, ZoneSet};
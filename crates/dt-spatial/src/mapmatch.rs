@@ -0,0 +1,275 @@
+//! Matching GPS traces onto network edges.
+//!
+//! [`map_match`] follows Newson & Krumm's hidden Markov model: each fix's
+//! hidden state is a position on one of the edges near it, fixes are more
+//! likely near their true edge (Gaussian in the GPS error), and consecutive
+//! states are more likely when the network distance between them is close
+//! to the straight-line distance between the fixes (exponential in the
+//! difference).  Viterbi picks the most likely sequence of positions, and
+//! the shortest paths between them fill in the edges the vehicle must have
+//! driven.
+//!
+//! Typical use is turning probe trajectories into edge sequences for
+//! comparison with simulated link flows during calibration.
+//!
+//! Fixes with no edge within [`MapMatchConfig::search_radius_m`] are
+//! skipped.  Where no candidate of a fix can be reached from any candidate
+//! of the previous one, matching restarts at that fix and the edge
+//! sequences of the pieces are concatenated.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let edges = map_match(&network, &probe.fixes);
+//! for edge in edges {
+//!     observed[edge.index()] += 1;
+//! }
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap};
+
+use dt_core::{EdgeId, GeoPoint, NodeId};
+
+use crate::network::RoadNetwork;
+
+/// Tuning for [`map_match_with`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapMatchConfig {
+    /// Standard deviation of the GPS error in metres.
+    pub gps_sigma_m: f32,
+    /// Scale of the exponential transition model in metres: how much longer
+    /// than the straight line a route between fixes may typically be.
+    pub beta_m: f32,
+    /// Only edges within this distance of a fix are candidates.
+    pub search_radius_m: f32,
+    /// At most this many candidate edges per fix, nearest first.
+    pub max_candidates: usize,
+    /// Routes between consecutive fixes longer than the straight line plus
+    /// this many metres are not considered.
+    pub max_detour_m: f32,
+}
+
+impl Default for MapMatchConfig {
+    fn default() -> Self {
+        Self {
+            gps_sigma_m:     10.0,
+            beta_m:          50.0,
+            search_radius_m: 50.0,
+            max_candidates:  8,
+            max_detour_m:    2_000.0,
+        }
+    }
+}
+
+/// Match `trace` onto `network` with the default [`MapMatchConfig`].
+///
+/// Returns the edges driven in order, consecutive duplicates removed; empty
+/// if no fix lies near an edge.
+pub fn map_match(network: &RoadNetwork, trace: &[GeoPoint]) -> Vec<EdgeId> {
+    map_match_with(network, trace, &MapMatchConfig::default())
+}
+
+/// Match `trace` onto `network`.  See the module docs.
+pub fn map_match_with(network: &RoadNetwork, trace: &[GeoPoint], config: &MapMatchConfig) -> Vec<EdgeId> {
+    let mut edges = Vec::new();
+    let mut layers: Vec<Layer> = Vec::new();
+
+    for &fix in trace {
+        let candidates = candidates(network, fix, config);
+        if candidates.is_empty() {
+            continue;
+        }
+        let emission: Vec<f64> = candidates
+            .iter()
+            .map(|c| {
+                let z = c.dist_m as f64 / config.gps_sigma_m as f64;
+                -0.5 * z * z
+            })
+            .collect();
+
+        let Some(last) = layers.last() else {
+            layers.push(Layer::first(fix, candidates, emission));
+            continue;
+        };
+        let straight_m = last.fix.distance_m(fix) as f64;
+        let bound_mm = to_mm(straight_m + config.max_detour_m as f64);
+        let mut layer = Layer {
+            fix,
+            score: vec![f64::NEG_INFINITY; candidates.len()],
+            back:  vec![usize::MAX; candidates.len()],
+            path:  vec![Vec::new(); candidates.len()],
+            candidates,
+        };
+        for (p, from) in last.candidates.iter().enumerate() {
+            if last.score[p] == f64::NEG_INFINITY {
+                continue;
+            }
+            let tree = SearchTree::grow(network, network.edge_to[from.edge.index()], bound_mm);
+            for (c, to) in layer.candidates.iter().enumerate() {
+                let Some((route_m, via)) = connect(network, &tree, from, to) else { continue };
+                let transition = -(route_m - straight_m).abs() / config.beta_m as f64;
+                let score = last.score[p] + transition + emission[c];
+                if score > layer.score[c] {
+                    layer.score[c] = score;
+                    layer.back[c] = p;
+                    layer.path[c] = via.map_or_else(Vec::new, |node| tree.path_to(network, node));
+                }
+            }
+        }
+        if layer.score.iter().all(|&s| s == f64::NEG_INFINITY) {
+            // Unreachable from everything before: close this piece.
+            flush(&mut layers, &mut edges);
+            layers.push(Layer::first(fix, layer.candidates, emission));
+        } else {
+            layers.push(layer);
+        }
+    }
+    flush(&mut layers, &mut edges);
+    edges
+}
+
+// ── Internals ─────────────────────────────────────────────────────────────────
+
+/// A position on an edge near a fix.
+#[derive(Clone)]
+struct Candidate {
+    edge:   EdgeId,
+    /// Fraction of the way from `edge_from` to `edge_to`.
+    t:      f32,
+    /// Distance from the fix in metres.
+    dist_m: f32,
+}
+
+/// Viterbi state for one fix.
+struct Layer {
+    fix:        GeoPoint,
+    candidates: Vec<Candidate>,
+    /// Log-likelihood of the best sequence ending at each candidate.
+    score:      Vec<f64>,
+    /// Best predecessor of each candidate in the previous layer.
+    back:       Vec<usize>,
+    /// Edges strictly between the predecessor's edge and the candidate's.
+    path:       Vec<Vec<EdgeId>>,
+}
+
+impl Layer {
+    fn first(fix: GeoPoint, candidates: Vec<Candidate>, emission: Vec<f64>) -> Self {
+        let n = candidates.len();
+        Self { fix, candidates, score: emission, back: vec![usize::MAX; n], path: vec![Vec::new(); n] }
+    }
+}
+
+/// Candidates for `fix`, nearest first (ties by `EdgeId`).
+fn candidates(network: &RoadNetwork, fix: GeoPoint, config: &MapMatchConfig) -> Vec<Candidate> {
+    let mut found: Vec<Candidate> = network
+        .edges_near(fix, config.search_radius_m)
+        .into_iter()
+        .map(|(edge, t, snapped)| Candidate { edge, t, dist_m: fix.distance_m(snapped) })
+        .filter(|c| c.dist_m <= config.search_radius_m)
+        .collect();
+    found.sort_by(|a, b| a.dist_m.total_cmp(&b.dist_m).then(a.edge.0.cmp(&b.edge.0)));
+    found.truncate(config.max_candidates);
+    found
+}
+
+/// Backtrack the best sequence through `layers`, append its edges to `out`,
+/// and clear `layers`.
+fn flush(layers: &mut Vec<Layer>, out: &mut Vec<EdgeId>) {
+    let Some(last) = layers.last() else { return };
+    // Highest score; ties go to the nearer candidate.
+    let mut c = (0..last.score.len())
+        .rev()
+        .max_by(|&a, &b| last.score[a].total_cmp(&last.score[b]))
+        .unwrap_or(0);
+    let mut reversed = Vec::new();
+    for layer in layers.iter().rev() {
+        reversed.push(layer.candidates[c].edge);
+        reversed.extend(layer.path[c].iter().rev());
+        c = layer.back[c];
+    }
+    for edge in reversed.into_iter().rev() {
+        if out.last() != Some(&edge) {
+            out.push(edge);
+        }
+    }
+    layers.clear();
+}
+
+/// Network distance in metres from `from` to `to` along the direction of
+/// travel, or `None` if `to` is out of reach.  The node is where the path
+/// through `tree` ends, or `None` if `to` is further along the same edge.
+fn connect(network: &RoadNetwork, tree: &SearchTree, from: &Candidate, to: &Candidate) -> Option<(f64, Option<NodeId>)> {
+    let len = |c: &Candidate| network.edge_length_m[c.edge.index()] as f64;
+    if from.edge == to.edge && to.t >= from.t {
+        return Some(((to.t - from.t) as f64 * len(from), None));
+    }
+    let via = network.edge_from[to.edge.index()];
+    let mid_mm = tree.dist_mm(via)?;
+    let route_m = (1.0 - from.t as f64) * len(from) + mid_mm as f64 / 1000.0 + to.t as f64 * len(to);
+    Some((route_m, Some(via)))
+}
+
+fn to_mm(m: f64) -> u32 {
+    (m * 1000.0).clamp(0.0, (u32::MAX - 1) as f64) as u32
+}
+
+/// Shortest distances by length from one node, up to a bound.  Sparse, so
+/// each search costs only what it visits.
+struct SearchTree {
+    /// Distance in millimetres and the edge that reached each settled node.
+    reached: HashMap<NodeId, (u32, EdgeId)>,
+}
+
+impl SearchTree {
+    fn grow(network: &RoadNetwork, source: NodeId, bound_mm: u32) -> Self {
+        let mut reached: HashMap<NodeId, (u32, EdgeId)> = HashMap::new();
+        reached.insert(source, (0, EdgeId::INVALID));
+        let mut heap: BinaryHeap<Reverse<(u32, NodeId)>> = BinaryHeap::new();
+        heap.push(Reverse((0, source)));
+
+        while let Some(Reverse((cost, node))) = heap.pop() {
+            if cost > reached[&node].0 {
+                continue;
+            }
+            for edge in network.out_edges(node) {
+                let neighbor = network.edge_to[edge.index()];
+                let new_cost = cost.saturating_add(to_mm(network.edge_length_m[edge.index()] as f64));
+                if new_cost > bound_mm {
+                    continue;
+                }
+                match reached.entry(neighbor) {
+                    Entry::Occupied(mut e) if new_cost < e.get().0 => {
+                        e.insert((new_cost, edge));
+                    }
+                    Entry::Occupied(_) => continue,
+                    Entry::Vacant(e) => {
+                        e.insert((new_cost, edge));
+                    }
+                }
+                heap.push(Reverse((new_cost, neighbor)));
+            }
+        }
+        Self { reached }
+    }
+
+    fn dist_mm(&self, node: NodeId) -> Option<u32> {
+        self.reached.get(&node).map(|&(d, _)| d)
+    }
+
+    /// Edges from the source to `node`, in order.
+    fn path_to(&self, network: &RoadNetwork, node: NodeId) -> Vec<EdgeId> {
+        let mut edges = Vec::new();
+        let mut cur = node;
+        while let Some(&(_, e)) = self.reached.get(&cur) {
+            if e == EdgeId::INVALID {
+                break;
+            }
+            edges.push(e);
+            cur = network.edge_from[e.index()];
+        }
+        edges.reverse();
+        edges
+    }
+}
@@ -0,0 +1,224 @@
+//! Many-to-many travel times.
+//!
+//! [`DijkstraRouter::travel_time_matrix`] runs one Dijkstra per origin,
+//! stopping as soon as every destination is settled, and returns a dense
+//! [`TravelTimeMatrix`].  [`DijkstraRouter::route_matrix`] also keeps each
+//! cell's [`Route`], which is what a precomputed home × work route table
+//! needs.
+//!
+//! With the `parallel` feature the per-origin searches run on Rayon's thread
+//! pool; results are identical either way.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use dt_core::{EdgeId, NodeId, TransportMode};
+
+use crate::network::RoadNetwork;
+use crate::router::{edge_cost_ms, DijkstraRouter, Route};
+
+// ── TravelTimeMatrix ──────────────────────────────────────────────────────────
+
+/// Travel times from each origin (row) to each destination (column).
+#[derive(Debug, Clone)]
+pub struct TravelTimeMatrix {
+    origins:      Vec<NodeId>,
+    destinations: Vec<NodeId>,
+    /// Row-major seconds; `f32::INFINITY` where there is no route.
+    secs:         Vec<f32>,
+    /// Row-major routes, present for [`DijkstraRouter::route_matrix`].
+    routes:       Option<Vec<Option<Route>>>,
+}
+
+impl TravelTimeMatrix {
+    pub fn origins(&self) -> &[NodeId] {
+        &self.origins
+    }
+
+    pub fn destinations(&self) -> &[NodeId] {
+        &self.destinations
+    }
+
+    /// Seconds from origin `row` to destination `col`, or `None` if
+    /// unreachable.
+    ///
+    /// # Panics
+    ///
+    /// If `row` or `col` is out of range.
+    pub fn secs(&self, row: usize, col: usize) -> Option<f32> {
+        Some(self.secs[self.cell(row, col)]).filter(|s| s.is_finite())
+    }
+
+    /// The times of origin `row`, one per destination, `f32::INFINITY` where
+    /// unreachable.
+    pub fn row(&self, row: usize) -> &[f32] {
+        let cols = self.destinations.len();
+        &self.secs[row * cols..(row + 1) * cols]
+    }
+
+    /// All times, row-major, `f32::INFINITY` where unreachable.
+    pub fn as_slice(&self) -> &[f32] {
+        &self.secs
+    }
+
+    /// Route from origin `row` to destination `col`; `None` if unreachable
+    /// or the matrix was built without routes.
+    pub fn route(&self, row: usize, col: usize) -> Option<&Route> {
+        self.routes.as_ref()?[self.cell(row, col)].as_ref()
+    }
+
+    /// `true` if built by [`DijkstraRouter::route_matrix`].
+    pub fn has_routes(&self) -> bool {
+        self.routes.is_some()
+    }
+
+    /// Move the routes out, row-major; `None` if built without them.
+    pub fn into_routes(self) -> Option<Vec<Option<Route>>> {
+        self.routes
+    }
+
+    fn cell(&self, row: usize, col: usize) -> usize {
+        assert!(row < self.origins.len() && col < self.destinations.len(), "no cell ({row}, {col})");
+        row * self.destinations.len() + col
+    }
+}
+
+// ── DijkstraRouter ────────────────────────────────────────────────────────────
+
+impl DijkstraRouter {
+    /// Travel times from every origin to every destination by `mode`.
+    ///
+    /// Costs are those of [`route`](crate::Router::route), so each finite
+    /// cell equals the matching route's `total_travel_secs()`.
+    pub fn travel_time_matrix(
+        &self,
+        network: &RoadNetwork,
+        origins: &[NodeId],
+        destinations: &[NodeId],
+        mode: TransportMode,
+    ) -> TravelTimeMatrix {
+        matrix(network, origins, destinations, mode, false)
+    }
+
+    /// Like [`travel_time_matrix`](Self::travel_time_matrix), also keeping
+    /// every route.
+    pub fn route_matrix(
+        &self,
+        network: &RoadNetwork,
+        origins: &[NodeId],
+        destinations: &[NodeId],
+        mode: TransportMode,
+    ) -> TravelTimeMatrix {
+        matrix(network, origins, destinations, mode, true)
+    }
+}
+
+// ── Internals ─────────────────────────────────────────────────────────────────
+
+type Row = (Vec<f32>, Option<Vec<Option<Route>>>);
+
+fn matrix(
+    network: &RoadNetwork,
+    origins: &[NodeId],
+    destinations: &[NodeId],
+    mode: TransportMode,
+    keep_routes: bool,
+) -> TravelTimeMatrix {
+    #[cfg(not(feature = "parallel"))]
+    let rows: Vec<Row> = origins
+        .iter()
+        .map(|&from| one_to_many(network, from, destinations, mode, keep_routes))
+        .collect();
+
+    #[cfg(feature = "parallel")]
+    let rows: Vec<Row> = {
+        use rayon::prelude::*;
+
+        origins
+            .par_iter()
+            .map(|&from| one_to_many(network, from, destinations, mode, keep_routes))
+            .collect()
+    };
+
+    let cells = origins.len() * destinations.len();
+    let mut secs = Vec::with_capacity(cells);
+    let mut routes = keep_routes.then(|| Vec::with_capacity(cells));
+    for (row_secs, row_routes) in rows {
+        secs.extend(row_secs);
+        if let (Some(all), Some(row)) = (&mut routes, row_routes) {
+            all.extend(row);
+        }
+    }
+    TravelTimeMatrix { origins: origins.to_vec(), destinations: destinations.to_vec(), secs, routes }
+}
+
+/// One Dijkstra from `from`, stopped once every target is settled.
+fn one_to_many(
+    network: &RoadNetwork,
+    from: NodeId,
+    targets: &[NodeId],
+    mode: TransportMode,
+    keep_routes: bool,
+) -> Row {
+    let n = network.node_count();
+    let mut dist      = vec![u32::MAX; n];
+    let mut prev_edge = vec![EdgeId::INVALID; n];
+    let mut is_target = vec![false; n];
+    let mut remaining = 0usize;
+    for t in targets {
+        if !std::mem::replace(&mut is_target[t.index()], true) {
+            remaining += 1;
+        }
+    }
+
+    let mut heap: BinaryHeap<Reverse<(u32, NodeId)>> = BinaryHeap::new();
+    dist[from.index()] = 0;
+    heap.push(Reverse((0, from)));
+    while let Some(Reverse((cost, node))) = heap.pop() {
+        if cost > dist[node.index()] {
+            continue;
+        }
+        if is_target[node.index()] {
+            remaining -= 1;
+            if remaining == 0 {
+                break;
+            }
+        }
+        for edge in network.out_edges(node) {
+            let neighbor = network.edge_to[edge.index()];
+            let new_cost = cost.saturating_add(edge_cost_ms(network, edge, mode));
+            if new_cost < dist[neighbor.index()] {
+                dist[neighbor.index()] = new_cost;
+                prev_edge[neighbor.index()] = edge;
+                heap.push(Reverse((new_cost, neighbor)));
+            }
+        }
+    }
+
+    let secs = targets
+        .iter()
+        .map(|t| match dist[t.index()] {
+            u32::MAX => f32::INFINITY,
+            ms => ms as f32 / 1000.0,
+        })
+        .collect();
+    let routes = keep_routes.then(|| {
+        targets
+            .iter()
+            .map(|&t| (dist[t.index()] != u32::MAX).then(|| path_to(network, &prev_edge, from, t, dist[t.index()])))
+            .collect()
+    });
+    (secs, routes)
+}
+
+fn path_to(network: &RoadNetwork, prev_edge: &[EdgeId], from: NodeId, to: NodeId, total_ms: u32) -> Route {
+    let mut edges = Vec::new();
+    let mut cur = to;
+    while cur != from {
+        let e = prev_edge[cur.index()];
+        edges.push(e);
+        cur = network.edge_from[e.index()];
+    }
+    edges.reverse();
+    Route { edges, total_travel_ms: total_ms as u64, rides: Vec::new() }
+}
@@ -9,27 +9,60 @@
 //! edge_from[ node_out_start[n] .. node_out_start[n+1] ]
 //! ```
 //!
-//! All edge arrays (`edge_from`, `edge_to`, `edge_length_m`, `edge_travel_ms`)
-//! are sorted by source node and indexed by `EdgeId`.  Iteration over a
+//! All edge arrays (`edge_from`, `edge_to`, `edge_length_m`, `edge_travel_ms`,
+//! `edge_capacity_vph`, `edge_freeflow_mps`) are sorted by source node and indexed by `EdgeId`.  Iteration over a
 //! node's outgoing edges is therefore a contiguous memory scan — ideal for
 //! Dijkstra's inner loop.
 //!
+//! A second, transposed CSR (`node_in_start` / `in_edge_ids`) lists each
+//! node's incoming `EdgeId`s for backward searches.  It costs one `u32` per
+//! node and per edge; [`RoadNetworkBuilder::reverse_adjacency`] skips it,
+//! leaving [`RoadNetwork::in_edges`] empty.  Without it
+//! [`BidirectionalRouter`](crate::BidirectionalRouter) falls back to plain
+//! Dijkstra, while landmark preprocessing and
+//! [`simplify`](RoadNetwork::simplify) build a temporary copy for their own
+//! backward passes.
+//!
 //! # Spatial index
 //!
 //! An R-tree (via `rstar`) maps `(lat, lon)` to the nearest `NodeId`.  Used
 //! at load time to snap agent home/work lat/lon pairs to road nodes.
+//!
+//! Points are stored as `[lat, lon × cos(ref_lat)]`, `ref_lat` being the
+//! middle latitude of the network, so Euclidean distance in the index is
+//! the equirectangular approximation of ground distance (up to a constant
+//! factor).  Without the scaling, a degree of longitude would count as
+//! much as a degree of latitude, and snapping would favour nodes to the
+//! east or west away from the equator.
+//!
+//! A second R-tree over edge segments, in the same coordinates, backs
+//! [`RoadNetwork::snap_to_edge`] and [`RoadNetwork::edges_in_bbox`].  It is
+//! built on the first edge query.
+
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::fmt;
+use std::sync::OnceLock;
 
 use rstar::{PointDistance, RTree, RTreeObject, AABB};
 
-use dt_core::{EdgeId, GeoPoint, NodeId};
+use dt_core::{BBox, EdgeId, GeoPoint, NodeId, RoutingConfig};
+
+use crate::attributes::{EdgeAttributes, StreetName};
+use crate::overlay::NetworkOverlay;
+use crate::profile::SpeedProfiles;
+use crate::simplify::EdgeGeometry;
+
+/// Metres per degree of latitude, matching `GeoPoint::distance_m`.
+const M_PER_DEG_LAT: f32 = 111_194.93;
 
 // ── R-tree node entry ─────────────────────────────────────────────────────────
 
-/// Entry stored in the R-tree spatial index: a 2-D `[lat, lon]` point with
-/// the associated `NodeId`.
+/// Entry stored in the R-tree spatial index: a 2-D `[lat, scaled lon]`
+/// point (see the module docs) with the associated `NodeId`.
 #[derive(Clone)]
 struct NodeEntry {
-    point: [f32; 2], // [lat, lon]
+    point: [f32; 2], // [lat, lon × lon_scale]
     id: NodeId,
 }
 
@@ -41,8 +74,8 @@ impl RTreeObject for NodeEntry {
 }
 
 impl PointDistance for NodeEntry {
-    /// Squared Euclidean distance in lat/lon space.  Sufficient for
-    /// nearest-node queries within a city (error < 0.1 % at ≤ 60° lat).
+    /// Squared equirectangular distance in degrees of latitude.  Sufficient
+    /// for nearest-node queries within a city.
     fn distance_2(&self, point: &[f32; 2]) -> f32 {
         let dlat = self.point[0] - point[0];
         let dlon = self.point[1] - point[1];
@@ -50,12 +83,63 @@ impl PointDistance for NodeEntry {
     }
 }
 
+// ── R-tree edge entry ─────────────────────────────────────────────────────────
+
+/// Entry in the edge R-tree: the straight segment between an edge's end
+/// nodes in index coordinates, boxed with any intermediate geometry.
+#[derive(Clone)]
+struct EdgeEntry {
+    from:     [f32; 2],
+    to:       [f32; 2],
+    id:       EdgeId,
+    envelope: AABB<[f32; 2]>,
+}
+
+impl EdgeEntry {
+    /// Position of the point of the segment nearest `point`, as a fraction
+    /// of the way from `from` to `to`.
+    fn fraction(&self, point: &[f32; 2]) -> f32 {
+        let d = [self.to[0] - self.from[0], self.to[1] - self.from[1]];
+        let len_2 = d[0] * d[0] + d[1] * d[1];
+        if len_2 == 0.0 {
+            return 0.0;
+        }
+        let t = ((point[0] - self.from[0]) * d[0] + (point[1] - self.from[1]) * d[1]) / len_2;
+        t.clamp(0.0, 1.0)
+    }
+
+    fn at(&self, t: f32) -> [f32; 2] {
+        [
+            self.from[0] + (self.to[0] - self.from[0]) * t,
+            self.from[1] + (self.to[1] - self.from[1]) * t,
+        ]
+    }
+}
+
+impl RTreeObject for EdgeEntry {
+    type Envelope = AABB<[f32; 2]>;
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+impl PointDistance for EdgeEntry {
+    /// Squared equirectangular distance from `point` to the segment.
+    fn distance_2(&self, point: &[f32; 2]) -> f32 {
+        let p = self.at(self.fraction(point));
+        let dlat = p[0] - point[0];
+        let dlon = p[1] - point[1];
+        dlat * dlat + dlon * dlon
+    }
+}
+
 // ── RoadNetwork ───────────────────────────────────────────────────────────────
 
 /// Directed road graph in CSR format plus a spatial index for node snapping.
 ///
 /// All fields are `pub` for direct indexed access on hot paths.  Do not
 /// construct directly; use [`RoadNetworkBuilder`].
+#[derive(Clone)]
 pub struct RoadNetwork {
     // ── Node data ─────────────────────────────────────────────────────────
     /// Geographic position of each node.  Indexed by `NodeId`.
@@ -82,8 +166,60 @@ pub struct RoadNetwork {
     /// Other modes compute their own costs from `edge_length_m` at query time.
     pub edge_travel_ms: Vec<u32>,
 
+    /// Capacity of each edge in vehicles per hour, for volume-delay
+    /// functions.
+    pub edge_capacity_vph: Vec<f32>,
+
+    /// Free-flow car speed of each edge in m/s, as built.  Unlike
+    /// `edge_travel_ms`, which congestion feedback may overwrite, this
+    /// keeps the uncongested speed.
+    pub edge_freeflow_mps: Vec<f32>,
+
+    // ── Reverse CSR adjacency ─────────────────────────────────────────────
+    /// Reverse row pointer.  Incoming edges of node `n` are
+    /// `in_edge_ids[node_in_start[n] .. node_in_start[n+1]]`.
+    /// Length = `node_count + 1`, or empty if the network was built
+    /// without reverse adjacency.
+    pub node_in_start: Vec<u32>,
+
+    /// `EdgeId`s grouped by destination node, ascending within each group.
+    pub in_edge_ids: Vec<EdgeId>,
+
+    // ── Time dependence ───────────────────────────────────────────────────
+    /// Optional hourly travel-time factors per edge, read by
+    /// [`travel_ms_at`](Self::travel_ms_at).  Set with
+    /// [`set_speed_profiles`](Self::set_speed_profiles).
+    pub speed_profiles: Option<SpeedProfiles>,
+
+    /// Optional runtime closures and travel-time factors, consulted by the
+    /// routers.  Edit with [`overlay_mut`](Self::overlay_mut).
+    pub overlay: Option<NetworkOverlay>,
+
+    /// Assumed speeds of walking, cycling, and transit, from which every
+    /// router and [`EdgeCost`](crate::EdgeCost) derives those modes' edge
+    /// times.  `SimBuilder` sets it from `SimConfig::routing`.  Default:
+    /// `RoutingConfig::default()`.
+    pub mode_speeds: RoutingConfig,
+
+    // ── Application attributes ────────────────────────────────────────────
+    /// Typed per-edge attributes (capacity, toll, zone id, …) for custom
+    /// routers and behaviors.  Empty when built.
+    pub edge_attrs: EdgeAttributes,
+
+    /// Intermediate points of edges merged by
+    /// [`simplify`](Self::simplify); `None` when every edge is straight.
+    pub edge_geometry: Option<EdgeGeometry>,
+
+    /// OpenStreetMap id of each node, indexed by `NodeId`; `0` for nodes
+    /// not from OSM.  Set by the OSM loader when asked to keep ids.
+    pub node_osm_ids: Option<Vec<i64>>,
+
     // ── Spatial index ─────────────────────────────────────────────────────
     spatial_idx: RTree<NodeEntry>,
+    /// Edge segments, bulk-loaded on the first edge query.
+    edge_idx: OnceLock<RTree<EdgeEntry>>,
+    /// `cos(ref_lat)`: longitudes are multiplied by this in the index.
+    lon_scale: f32,
 }
 
 impl RoadNetwork {
@@ -110,6 +246,35 @@ impl RoadNetwork {
         self.node_pos.is_empty()
     }
 
+    /// 64-bit FNV-1a digest of the node positions and every edge's
+    /// endpoints, length, and travel time.
+    ///
+    /// Identifies a network in run metadata: unlike `std::hash`, the value is
+    /// stable across builds, compiler versions, and platforms.
+    pub fn fingerprint(&self) -> u64 {
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+        let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut feed = |word: u32| {
+            for byte in word.to_le_bytes() {
+                h ^= byte as u64;
+                h = h.wrapping_mul(PRIME);
+            }
+        };
+        feed(self.node_pos.len() as u32);
+        for p in &self.node_pos {
+            feed(p.lat.to_bits());
+            feed(p.lon.to_bits());
+        }
+        feed(self.edge_to.len() as u32);
+        for e in 0..self.edge_to.len() {
+            feed(self.edge_from[e].0);
+            feed(self.edge_to[e].0);
+            feed(self.edge_length_m[e].to_bits());
+            feed(self.edge_travel_ms[e]);
+        }
+        h
+    }
+
     // ── Graph traversal ───────────────────────────────────────────────────
 
     /// Iterator over the `EdgeId`s of all outgoing edges from `node`.
@@ -130,6 +295,70 @@ impl RoadNetwork {
         end - start
     }
 
+    /// Iterator over the `EdgeId`s of all edges arriving at `node`.
+    ///
+    /// Empty if the network was built without reverse adjacency (see
+    /// [`has_reverse_adjacency`](Self::has_reverse_adjacency)).
+    #[inline]
+    pub fn in_edges(&self, node: NodeId) -> impl Iterator<Item = EdgeId> + '_ {
+        let (start, end) = self.in_range(node);
+        self.in_edge_ids[start..end].iter().copied()
+    }
+
+    /// In-degree of `node` (number of incoming edges), or `0` without
+    /// reverse adjacency.
+    #[inline]
+    pub fn in_degree(&self, node: NodeId) -> usize {
+        let (start, end) = self.in_range(node);
+        end - start
+    }
+
+    /// `true` if the network keeps the reverse CSR behind
+    /// [`in_edges`](Self::in_edges).
+    pub fn has_reverse_adjacency(&self) -> bool {
+        !self.node_in_start.is_empty()
+    }
+
+    /// Build or drop the reverse CSR after the fact.
+    pub fn set_reverse_adjacency(&mut self, keep: bool) {
+        (self.node_in_start, self.in_edge_ids) = match keep {
+            true => in_adjacency(self.node_count(), &self.edge_to),
+            false => (Vec::new(), Vec::new()),
+        };
+    }
+
+    /// The reverse CSR, borrowed if the network keeps it and rebuilt
+    /// otherwise, for searches that walk edges backwards.
+    pub(crate) fn reverse_csr(&self) -> (Cow<'_, [u32]>, Cow<'_, [EdgeId]>) {
+        if self.has_reverse_adjacency() {
+            return (Cow::Borrowed(&self.node_in_start), Cow::Borrowed(&self.in_edge_ids));
+        }
+        let (start, ids) = in_adjacency(self.node_count(), &self.edge_to);
+        (Cow::Owned(start), Cow::Owned(ids))
+    }
+
+    #[inline]
+    fn in_range(&self, node: NodeId) -> (usize, usize) {
+        match self.node_in_start.get(node.index()..node.index() + 2) {
+            Some(&[start, end]) => (start as usize, end as usize),
+            _ => (0, 0),
+        }
+    }
+
+    // ── Source identifiers ────────────────────────────────────────────────
+
+    /// The OpenStreetMap id of `node`, if the network keeps them.
+    pub fn osm_node_id(&self, node: NodeId) -> Option<i64> {
+        self.node_osm_ids.as_ref().map(|ids| ids[node.index()]).filter(|&id| id != 0)
+    }
+
+    /// The [`StreetName`] of `edge`, or `None` if it is unnamed or names
+    /// are not kept.
+    pub fn street_name(&self, edge: EdgeId) -> Option<&str> {
+        let names = self.edge_attrs.get::<StreetName>()?;
+        Some(&*names[edge.index()].0).filter(|name| !name.is_empty())
+    }
+
     // ── Spatial queries ───────────────────────────────────────────────────
 
     /// Return the `NodeId` of the nearest road node to `pos`.
@@ -137,18 +366,157 @@ impl RoadNetwork {
     /// Returns `None` only if the network has no nodes.
     pub fn snap_to_node(&self, pos: GeoPoint) -> Option<NodeId> {
         self.spatial_idx
-            .nearest_neighbor(&[pos.lat, pos.lon])
+            .nearest_neighbor(&self.index_point(pos))
             .map(|e| e.id)
     }
 
     /// Return up to `k` nearest nodes to `pos`, sorted by ascending distance.
     pub fn k_nearest_nodes(&self, pos: GeoPoint, k: usize) -> Vec<NodeId> {
         self.spatial_idx
-            .nearest_neighbor_iter(&[pos.lat, pos.lon])
+            .nearest_neighbor_iter(&self.index_point(pos))
             .take(k)
             .map(|e| e.id)
             .collect()
     }
+
+    /// The nearest node to `pos` and its distance in metres, or `None` if
+    /// no node is within `max_m`.  Use instead of
+    /// [`snap_to_node`](Self::snap_to_node) to reject points far off the
+    /// network.
+    pub fn snap_to_node_within(&self, pos: GeoPoint, max_m: f32) -> Option<(NodeId, f32)> {
+        self.nearest_nodes(pos, 1, max_m).into_iter().next()
+    }
+
+    /// Up to `k` nearest nodes to `pos` within `max_m` metres, with their
+    /// distances, nearest first.  Distances are the equirectangular ground
+    /// distance the index ranks by; pass `f32::INFINITY` for no limit.
+    pub fn nearest_nodes(&self, pos: GeoPoint, k: usize, max_m: f32) -> Vec<(NodeId, f32)> {
+        self.spatial_idx
+            .nearest_neighbor_iter_with_distance_2(&self.index_point(pos))
+            .map(|(e, d2)| (e.id, d2.sqrt() * M_PER_DEG_LAT))
+            .take_while(|&(_, d)| d <= max_m)
+            .take(k)
+            .collect()
+    }
+
+    /// Every node within `meters` of `pos` by great-circle distance,
+    /// nearest first (ties by `NodeId`).
+    pub fn nodes_within_radius(&self, pos: GeoPoint, meters: f32) -> Vec<NodeId> {
+        let bbox = BBox::around(pos, meters.max(0.0));
+        let mut found: Vec<(f32, NodeId)> = self
+            .nodes_in_bbox(GeoPoint::new(bbox.south, bbox.west), GeoPoint::new(bbox.north, bbox.east))
+            .into_iter()
+            .map(|n| (pos.distance_m(self.node_pos[n.index()]), n))
+            .filter(|&(d, _)| d <= meters)
+            .collect();
+        found.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        found.into_iter().map(|(_, n)| n).collect()
+    }
+
+    /// Every node with `min.lat <= lat <= max.lat` and
+    /// `min.lon <= lon <= max.lon`, in `NodeId` order.
+    pub fn nodes_in_bbox(&self, min: GeoPoint, max: GeoPoint) -> Vec<NodeId> {
+        let envelope = AABB::from_corners(self.index_point(min), self.index_point(max));
+        let mut found: Vec<NodeId> = self
+            .spatial_idx
+            .locate_in_envelope(&envelope)
+            .map(|e| e.id)
+            .collect();
+        found.sort_unstable();
+        found
+    }
+
+    /// Every edge whose bounding box — end nodes and any intermediate
+    /// geometry — meets the box from `min` to `max`, in `EdgeId` order.
+    /// For fetching what a map viewport shows: an edge passing near a
+    /// corner of the box may be included without crossing it.
+    ///
+    /// The edge index is built on the first call, in O(E log E); later
+    /// calls cost O(log E + k).
+    pub fn edges_in_bbox(&self, min: GeoPoint, max: GeoPoint) -> Vec<EdgeId> {
+        let envelope = AABB::from_corners(self.index_point(min), self.index_point(max));
+        let mut found: Vec<EdgeId> = self
+            .edge_index()
+            .locate_in_envelope_intersecting(&envelope)
+            .map(|e| e.id)
+            .collect();
+        found.sort_unstable();
+        found
+    }
+
+    /// Project `pos` onto the nearest edge, treating each edge as the
+    /// straight segment between its end nodes.
+    ///
+    /// Returns the edge, how far along it the projection lies (`0.0` at
+    /// `edge_from`, `1.0` at `edge_to`), and the projected point, or `None`
+    /// if the network has no edges.  The two directions of a road share a
+    /// segment; ties go to the lower `EdgeId`.  Closed edges are candidates
+    /// too.
+    ///
+    /// The edge index is built on the first call, in O(E log E).
+    pub fn snap_to_edge(&self, pos: GeoPoint) -> Option<(EdgeId, f32, GeoPoint)> {
+        let point = self.index_point(pos);
+        let mut candidates = self.edge_index().nearest_neighbor_iter_with_distance_2(&point);
+        let (first, best) = candidates.next()?;
+        let entry = candidates
+            .take_while(|&(_, d)| d <= best)
+            .map(|(e, _)| e)
+            .fold(first, |a, b| if b.id.0 < a.id.0 { b } else { a });
+        let t = entry.fraction(&point);
+        Some((entry.id, t, self.point_along(entry.id, t)))
+    }
+
+    /// Edges whose segment passes within roughly `radius_m` of `pos`
+    /// (equirectangular), with the fraction along each and the projected
+    /// point, nearest first.
+    pub(crate) fn edges_near(&self, pos: GeoPoint, radius_m: f32) -> Vec<(EdgeId, f32, GeoPoint)> {
+        let point = self.index_point(pos);
+        let radius = radius_m / M_PER_DEG_LAT;
+        self.edge_index()
+            .nearest_neighbor_iter_with_distance_2(&point)
+            .take_while(|&(_, d)| d <= radius * radius)
+            .map(|(entry, _)| {
+                let t = entry.fraction(&point);
+                (entry.id, t, self.point_along(entry.id, t))
+            })
+            .collect()
+    }
+
+    /// The point `t` of the way along `edge`'s straight segment.
+    fn point_along(&self, edge: EdgeId, t: f32) -> GeoPoint {
+        let from = self.node_pos[self.edge_from[edge.index()].index()];
+        let to = self.node_pos[self.edge_to[edge.index()].index()];
+        GeoPoint::new(from.lat + (to.lat - from.lat) * t, from.lon + (to.lon - from.lon) * t)
+    }
+
+    /// The edge R-tree, built on first use.
+    fn edge_index(&self) -> &RTree<EdgeEntry> {
+        self.edge_idx.get_or_init(|| {
+            let entries = (0..self.edge_count())
+                .map(|e| {
+                    let from = self.index_point(self.node_pos[self.edge_from[e].index()]);
+                    let to = self.index_point(self.node_pos[self.edge_to[e].index()]);
+                    let id = EdgeId(e as u32);
+                    let bends = self.edge_geometry.as_ref().map_or(&[][..], |g| g.points(id));
+                    let envelope = bends.iter().fold(AABB::from_corners(from, to), |env, &p| {
+                        let p = self.index_point(p);
+                        AABB::from_corners(
+                            [env.lower()[0].min(p[0]), env.lower()[1].min(p[1])],
+                            [env.upper()[0].max(p[0]), env.upper()[1].max(p[1])],
+                        )
+                    });
+                    EdgeEntry { from, to, id, envelope }
+                })
+                .collect();
+            RTree::bulk_load(entries)
+        })
+    }
+
+    /// `pos` in the coordinates of the spatial index.
+    #[inline]
+    fn index_point(&self, pos: GeoPoint) -> [f32; 2] {
+        [pos.lat, pos.lon * self.lon_scale]
+    }
 }
 
 // ── RoadNetworkBuilder ────────────────────────────────────────────────────────
@@ -156,8 +524,9 @@ impl RoadNetwork {
 /// Construct a [`RoadNetwork`] incrementally, then call [`build`](Self::build).
 ///
 /// The builder accepts nodes and directed edges in any order.  `build()`
-/// sorts edges by source node, constructs the CSR arrays, and bulk-loads the
-/// R-tree.
+/// sorts edges by source node, constructs the forward and (unless
+/// [`reverse_adjacency`](Self::reverse_adjacency) turned it off) reverse CSR
+/// arrays, and bulk-loads the R-tree.
 ///
 /// # Example
 ///
@@ -176,18 +545,20 @@ impl RoadNetwork {
 pub struct RoadNetworkBuilder {
     nodes:     Vec<GeoPoint>,
     raw_edges: Vec<RawEdge>,
+    reverse:   bool,
 }
 
-struct RawEdge {
-    from:       NodeId,
-    to:         NodeId,
-    length_m:   f32,
-    travel_ms:  u32,
+pub(crate) struct RawEdge {
+    pub(crate) from:         NodeId,
+    pub(crate) to:           NodeId,
+    pub(crate) length_m:     f32,
+    pub(crate) travel_ms:    u32,
+    pub(crate) capacity_vph: f32,
 }
 
 impl RoadNetworkBuilder {
     pub fn new() -> Self {
-        Self { nodes: Vec::new(), raw_edges: Vec::new() }
+        Self { nodes: Vec::new(), raw_edges: Vec::new(), reverse: true }
     }
 
     /// Pre-allocate for the expected number of nodes and edges to reduce
@@ -196,9 +567,21 @@ impl RoadNetworkBuilder {
         Self {
             nodes:     Vec::with_capacity(nodes),
             raw_edges: Vec::with_capacity(edges),
+            reverse:   true,
         }
     }
 
+    /// Whether [`build`](Self::build) makes the reverse CSR behind
+    /// [`RoadNetwork::in_edges`] (default `true`).
+    ///
+    /// Without it `in_edges` is empty; the bidirectional router falls back
+    /// to plain Dijkstra, and landmark tables and
+    /// [`simplify`](RoadNetwork::simplify) rebuild it for their own use.
+    pub fn reverse_adjacency(&mut self, keep: bool) -> &mut Self {
+        self.reverse = keep;
+        self
+    }
+
     /// Add a road node and return its `NodeId` (sequential from 0).
     pub fn add_node(&mut self, pos: GeoPoint) -> NodeId {
         let id = NodeId(self.nodes.len() as u32);
@@ -210,8 +593,25 @@ impl RoadNetworkBuilder {
     ///
     /// - `length_m`: physical length in metres.
     /// - `travel_ms`: car travel time in milliseconds (used as Dijkstra cost).
+    ///
+    /// The free-flow speed is `length_m / travel_ms`; the capacity is
+    /// guessed from it with [`capacity_for_speed`].
     pub fn add_directed_edge(&mut self, from: NodeId, to: NodeId, length_m: f32, travel_ms: u32) {
-        self.raw_edges.push(RawEdge { from, to, length_m, travel_ms });
+        let capacity_vph = capacity_for_speed(freeflow_mps(length_m, travel_ms));
+        self.add_directed_edge_with_capacity(from, to, length_m, travel_ms, capacity_vph);
+    }
+
+    /// As [`add_directed_edge`](Self::add_directed_edge), with a known
+    /// capacity in vehicles per hour.
+    pub fn add_directed_edge_with_capacity(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        length_m: f32,
+        travel_ms: u32,
+        capacity_vph: f32,
+    ) {
+        self.raw_edges.push(RawEdge { from, to, length_m, travel_ms, capacity_vph });
     }
 
     /// Convenience: add edges in **both directions** for an undirected road
@@ -221,6 +621,13 @@ impl RoadNetworkBuilder {
         self.add_directed_edge(b, a, length_m, travel_ms);
     }
 
+    /// As [`add_road`](Self::add_road), with a known capacity per direction
+    /// in vehicles per hour.
+    pub fn add_road_with_capacity(&mut self, a: NodeId, b: NodeId, length_m: f32, travel_ms: u32, capacity_vph: f32) {
+        self.add_directed_edge_with_capacity(a, b, length_m, travel_ms, capacity_vph);
+        self.add_directed_edge_with_capacity(b, a, length_m, travel_ms, capacity_vph);
+    }
+
     /// Look up the position of a node added earlier (used by the OSM loader
     /// to compute edge lengths between adjacent way nodes).
     pub fn node_pos(&self, id: NodeId) -> GeoPoint {
@@ -235,51 +642,200 @@ impl RoadNetworkBuilder {
     /// Time complexity: O(E log E) for edge sort + O(N log N) for R-tree bulk
     /// load, where N = nodes, E = edges.
     pub fn build(self) -> RoadNetwork {
-        let node_count = self.nodes.len();
-        let edge_count = self.raw_edges.len();
-
         // Sort edges by source node for CSR construction.
         let mut raw = self.raw_edges;
         raw.sort_unstable_by_key(|e| e.from.0);
+        Self::build_sorted(self.nodes, raw, self.reverse)
+    }
+
+    /// As [`build`](Self::build), also returning the `EdgeId` each edge was
+    /// given, in the order the edges were added.
+    ///
+    /// For loaders that keep per-edge data of their own (ids, attributes)
+    /// and need to line it up with the reordered edges.
+    pub fn build_with_edge_ids(self) -> (RoadNetwork, Vec<EdgeId>) {
+        let mut raw: Vec<(u32, RawEdge)> = (0..).zip(self.raw_edges).collect();
+        raw.sort_unstable_by_key(|(_, e)| e.from.0);
+        let mut edge_ids = vec![EdgeId(0); raw.len()];
+        for (e, (added, _)) in raw.iter().enumerate() {
+            edge_ids[*added as usize] = EdgeId(e as u32);
+        }
+        let raw = raw.into_iter().map(|(_, e)| e).collect();
+        (Self::build_sorted(self.nodes, raw, self.reverse), edge_ids)
+    }
 
-        // Build edge arrays from sorted raw edges.
+    /// Build edge arrays from raw edges already sorted by source node.
+    fn build_sorted(nodes: Vec<GeoPoint>, raw: Vec<RawEdge>, reverse: bool) -> RoadNetwork {
         let edge_from:      Vec<NodeId> = raw.iter().map(|e| e.from).collect();
         let edge_to:        Vec<NodeId> = raw.iter().map(|e| e.to).collect();
         let edge_length_m:  Vec<f32>    = raw.iter().map(|e| e.length_m).collect();
         let edge_travel_ms: Vec<u32>    = raw.iter().map(|e| e.travel_ms).collect();
+        let edge_capacity:  Vec<f32>    = raw.iter().map(|e| e.capacity_vph).collect();
+        let edge_freeflow:  Vec<f32>    = raw.iter().map(|e| freeflow_mps(e.length_m, e.travel_ms)).collect();
 
-        // Build CSR row pointer (node_out_start).
-        let mut node_out_start = vec![0u32; node_count + 1];
-        for e in &raw {
-            node_out_start[e.from.index() + 1] += 1;
-        }
-        for i in 1..=node_count {
-            node_out_start[i] += node_out_start[i - 1];
-        }
-        debug_assert_eq!(node_out_start[node_count] as usize, edge_count);
+        RoadNetwork::from_sorted_edges(
+            nodes,
+            edge_from,
+            edge_to,
+            edge_length_m,
+            edge_travel_ms,
+            edge_capacity,
+            edge_freeflow,
+            reverse,
+        )
+    }
+}
+
+/// Free-flow speed in m/s of an edge `length_m` long taking `travel_ms`;
+/// `0.0` if the time is zero or the edge is closed.
+pub(crate) fn freeflow_mps(length_m: f32, travel_ms: u32) -> f32 {
+    match travel_ms {
+        0 | u32::MAX => 0.0,
+        ms => length_m / ms as f32 * 1_000.0,
+    }
+}
+
+/// A rough per-direction capacity (vehicles per hour) for a road with
+/// free-flow speed `mps`: 2000 for motorways (≥ 90 km/h), 1500 for
+/// arterials (≥ 60), 900 for collectors (≥ 40), 600 otherwise.
+///
+/// The default for edges added without a capacity.
+pub fn capacity_for_speed(mps: f32) -> f32 {
+    match mps * 3.6 {
+        kmh if kmh >= 90.0 => 2000.0,
+        kmh if kmh >= 60.0 => 1500.0,
+        kmh if kmh >= 40.0 => 900.0,
+        _ => 600.0,
+    }
+}
+
+impl RoadNetwork {
+    /// Build the CSR arrays (the reverse one only if `reverse`) and spatial
+    /// index over edges already sorted by source node, keeping their order
+    /// as `EdgeId`s.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_sorted_edges(
+        nodes: Vec<GeoPoint>,
+        edge_from: Vec<NodeId>,
+        edge_to: Vec<NodeId>,
+        edge_length_m: Vec<f32>,
+        edge_travel_ms: Vec<u32>,
+        edge_capacity_vph: Vec<f32>,
+        edge_freeflow_mps: Vec<f32>,
+        reverse: bool,
+    ) -> RoadNetwork {
+        let node_count = nodes.len();
+        let edge_count = edge_from.len();
+        let node_out_start = out_adjacency(node_count, &edge_from);
+        let (node_in_start, in_edge_ids) = match reverse {
+            true => in_adjacency(node_count, &edge_to),
+            false => (Vec::new(), Vec::new()),
+        };
 
         // Bulk-load R-tree for O(N log N) construction (faster than N inserts).
-        let entries: Vec<NodeEntry> = self
-            .nodes
+        let lon_scale = BBox::from_points(nodes.iter().copied())
+            .map_or(1.0, |bbox| bbox.center().lat.to_radians().cos());
+        let entries: Vec<NodeEntry> = nodes
             .iter()
             .enumerate()
             .map(|(i, &pos)| NodeEntry {
-                point: [pos.lat, pos.lon],
+                point: [pos.lat, pos.lon * lon_scale],
                 id: NodeId(i as u32),
             })
             .collect();
         let spatial_idx = RTree::bulk_load(entries);
 
         RoadNetwork {
-            node_pos: self.nodes,
+            node_pos: nodes,
             node_out_start,
             edge_from,
             edge_to,
             edge_length_m,
             edge_travel_ms,
+            edge_capacity_vph,
+            edge_freeflow_mps,
+            node_in_start,
+            in_edge_ids,
+            speed_profiles: None,
+            overlay: None,
+            mode_speeds: RoutingConfig::default(),
+            edge_attrs: EdgeAttributes::new(edge_count),
+            edge_geometry: None,
+            node_osm_ids: None,
             spatial_idx,
+            edge_idx: OnceLock::new(),
+            lon_scale,
+        }
+    }
+
+    /// Append `nodes`, inserting them into the existing node R-tree, and
+    /// replace the edge arrays (sorted by source node), rebuilding both
+    /// CSRs.  The edge R-tree is dropped; side tables are left to the
+    /// caller.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn splice(
+        &mut self,
+        nodes: &[GeoPoint],
+        edge_from: Vec<NodeId>,
+        edge_to: Vec<NodeId>,
+        edge_length_m: Vec<f32>,
+        edge_travel_ms: Vec<u32>,
+        edge_capacity_vph: Vec<f32>,
+        edge_freeflow_mps: Vec<f32>,
+    ) {
+        for &pos in nodes {
+            let id = NodeId(self.node_pos.len() as u32);
+            self.spatial_idx.insert(NodeEntry { point: self.index_point(pos), id });
+            self.node_pos.push(pos);
+        }
+        self.node_out_start = out_adjacency(self.node_count(), &edge_from);
+        if self.has_reverse_adjacency() {
+            (self.node_in_start, self.in_edge_ids) = in_adjacency(self.node_count(), &edge_to);
         }
+        self.edge_from = edge_from;
+        self.edge_to = edge_to;
+        self.edge_length_m = edge_length_m;
+        self.edge_travel_ms = edge_travel_ms;
+        self.edge_capacity_vph = edge_capacity_vph;
+        self.edge_freeflow_mps = edge_freeflow_mps;
+        self.edge_idx = OnceLock::new();
+    }
+}
+
+/// Forward row pointers for edges sorted by source node.
+fn out_adjacency(node_count: usize, edge_from: &[NodeId]) -> Vec<u32> {
+    debug_assert!(edge_from.windows(2).all(|w| w[0] <= w[1]));
+
+    // Build CSR row pointer (node_out_start).
+    let mut node_out_start = vec![0u32; node_count + 1];
+    for from in edge_from {
+        node_out_start[from.index() + 1] += 1;
+    }
+    for i in 1..=node_count {
+        node_out_start[i] += node_out_start[i - 1];
+    }
+    debug_assert_eq!(node_out_start[node_count] as usize, edge_from.len());
+    node_out_start
+}
+
+/// Reverse row pointers and reverse `EdgeId`s.
+fn in_adjacency(node_count: usize, edge_to: &[NodeId]) -> (Vec<u32>, Vec<EdgeId>) {
+    // Reverse CSR: counting sort of EdgeIds by destination node.  Edges
+    // are visited in id order, so each group stays ascending.
+    let mut node_in_start = vec![0u32; node_count + 1];
+    for to in edge_to {
+        node_in_start[to.index() + 1] += 1;
+    }
+    for i in 1..=node_count {
+        node_in_start[i] += node_in_start[i - 1];
     }
+    let mut fill = node_in_start.clone();
+    let mut in_edge_ids = vec![EdgeId::INVALID; edge_to.len()];
+    for (e, to) in edge_to.iter().enumerate() {
+        in_edge_ids[fill[to.index()] as usize] = EdgeId(e as u32);
+        fill[to.index()] += 1;
+    }
+    (node_in_start, in_edge_ids)
 }
 
 impl Default for RoadNetworkBuilder {
@@ -287,3 +843,247 @@ impl Default for RoadNetworkBuilder {
         Self::new()
     }
 }
+
+// ── Diagnostics ───────────────────────────────────────────────────────────────
+
+/// Structural problems found by [`diagnostics`].
+///
+/// Bad OSM extracts otherwise only show up as `NoRoute` errors once agents
+/// start travelling; print the report (it implements `Display`) after
+/// loading a network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkDiagnostics {
+    pub node_count:        usize,
+    pub edge_count:        usize,
+    /// Nodes with no outgoing edges: trips ending there can't leave.
+    pub dead_end_nodes:    Vec<NodeId>,
+    /// Edges of length 0 m.
+    pub zero_length_edges: Vec<EdgeId>,
+    /// Edges with the same endpoints as a lower-numbered edge.
+    pub duplicate_edges:   Vec<EdgeId>,
+    /// Number of strongly connected components, counting only open edges
+    /// (`edge_travel_ms != u32::MAX`).
+    pub component_count:   usize,
+    /// Nodes in the largest strongly connected component.
+    pub largest_component: usize,
+    /// `degree_histogram[d]` is the number of nodes with out-degree `d`.
+    pub degree_histogram:  Vec<usize>,
+}
+
+impl NetworkDiagnostics {
+    /// Nodes outside the largest strongly connected component: each one
+    /// either can't be reached from most of the network or can't reach it.
+    pub fn unreachable_nodes(&self) -> usize {
+        self.node_count - self.largest_component
+    }
+
+    /// `true` if nothing was found: no dead ends, zero-length or duplicate
+    /// edges, and every node in one strongly connected component.
+    pub fn is_clean(&self) -> bool {
+        self.dead_end_nodes.is_empty()
+            && self.zero_length_edges.is_empty()
+            && self.duplicate_edges.is_empty()
+            && self.unreachable_nodes() == 0
+    }
+}
+
+/// A plain-text summary, one finding per line.
+impl fmt::Display for NetworkDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} nodes, {} edges", self.node_count, self.edge_count)?;
+        writeln!(
+            f,
+            "{} strongly connected components; largest has {} nodes, {} nodes outside it",
+            self.component_count,
+            self.largest_component,
+            self.unreachable_nodes(),
+        )?;
+        writeln!(f, "{} dead-end nodes", self.dead_end_nodes.len())?;
+        writeln!(f, "{} zero-length edges", self.zero_length_edges.len())?;
+        writeln!(f, "{} duplicate edges", self.duplicate_edges.len())?;
+        write!(f, "out-degree histogram:")?;
+        for (degree, nodes) in self.degree_histogram.iter().enumerate().filter(|(_, n)| **n > 0) {
+            write!(f, " {degree}:{nodes}")?;
+        }
+        writeln!(f)
+    }
+}
+
+/// Check `network` for dead ends, zero-length and duplicate edges, and
+/// disconnected parts.  Runs in O(N + E).
+pub fn diagnostics(network: &RoadNetwork) -> NetworkDiagnostics {
+    let n = network.node_count();
+    let mut dead_end_nodes   = Vec::new();
+    let mut duplicate_edges  = Vec::new();
+    let mut degree_histogram = Vec::new();
+    // seen_from[v] = last node with an edge to v, to spot parallel edges.
+    let mut seen_from = vec![NodeId::INVALID; n];
+    for node in (0..n as u32).map(NodeId) {
+        let degree = network.out_degree(node);
+        if degree == 0 {
+            dead_end_nodes.push(node);
+        }
+        if degree_histogram.len() <= degree {
+            degree_histogram.resize(degree + 1, 0);
+        }
+        degree_histogram[degree] += 1;
+        for edge in network.out_edges(node) {
+            let to = network.edge_to[edge.index()];
+            if std::mem::replace(&mut seen_from[to.index()], node) == node {
+                duplicate_edges.push(edge);
+            }
+        }
+    }
+    let zero_length_edges = (0..network.edge_count() as u32)
+        .map(EdgeId)
+        .filter(|e| network.edge_length_m[e.index()] == 0.0)
+        .collect();
+
+    let (component, component_count) = strongly_connected_components(network);
+    let mut sizes = vec![0usize; component_count];
+    for c in component {
+        sizes[c as usize] += 1;
+    }
+
+    NetworkDiagnostics {
+        node_count:        n,
+        edge_count:        network.edge_count(),
+        dead_end_nodes,
+        zero_length_edges,
+        duplicate_edges,
+        component_count,
+        largest_component: sizes.into_iter().max().unwrap_or(0),
+        degree_histogram,
+    }
+}
+
+/// Strongly connected component of every node over open edges (iterative
+/// Tarjan), and the number of components.
+pub(crate) fn strongly_connected_components(network: &RoadNetwork) -> (Vec<u32>, usize) {
+    const UNVISITED: u32 = u32::MAX;
+    let n = network.node_count();
+    let mut index     = vec![UNVISITED; n];
+    let mut low       = vec![0u32; n];
+    let mut on_stack  = vec![false; n];
+    let mut component = vec![0u32; n];
+    let mut stack: Vec<usize> = Vec::new();
+    // DFS frames: (node, next out-edge to explore).
+    let mut frames: Vec<(usize, u32)> = Vec::new();
+    let (mut next_index, mut count) = (0u32, 0usize);
+
+    for root in 0..n {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        let mut enter = Some(root);
+        loop {
+            if let Some(v) = enter.take() {
+                index[v] = next_index;
+                low[v] = next_index;
+                next_index += 1;
+                stack.push(v);
+                on_stack[v] = true;
+                frames.push((v, network.node_out_start[v]));
+            }
+            let Some(&(v, edge)) = frames.last() else { break };
+            if edge < network.node_out_start[v + 1] {
+                frames.last_mut().unwrap().1 += 1;
+                let w = network.edge_to[edge as usize].index();
+                if network.edge_travel_ms[edge as usize] == u32::MAX {
+                    continue;
+                }
+                if index[w] == UNVISITED {
+                    enter = Some(w);
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if low[v] == index[v] {
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component[w] = count as u32;
+                    if w == v {
+                        break;
+                    }
+                }
+                count += 1;
+            }
+        }
+    }
+    (component, count)
+}
+
+// ── Largest component ─────────────────────────────────────────────────────────
+
+impl RoadNetwork {
+    /// Drop every node outside the largest strongly connected component (by
+    /// car: closed edges don't connect), with the edges touching them, so
+    /// every remaining node can reach every other.
+    ///
+    /// Returns the node remapping: entry `i` is the new id of old node `i`,
+    /// or `NodeId::INVALID` if it was dropped.  Kept nodes and edges keep
+    /// their relative order; speed profiles, the overlay, edge attributes,
+    /// and edge geometry follow their edges, and `mode_speeds` is kept.
+    /// Run this before snapping agents or building a contraction hierarchy,
+    /// since `NodeId`s, `EdgeId`s, and the fingerprint all change.
+    pub fn retain_largest_scc(&mut self) -> Vec<NodeId> {
+        let (component, count) = strongly_connected_components(self);
+        let mut sizes = vec![0usize; count];
+        for &c in &component {
+            sizes[c as usize] += 1;
+        }
+        // Ties go to the lowest-numbered component.
+        let Some(largest) = (0..count).max_by_key(|&c| (sizes[c], Reverse(c))) else {
+            return Vec::new();
+        };
+
+        let mut remap = vec![NodeId::INVALID; self.node_count()];
+        let mut nodes = Vec::with_capacity(sizes[largest]);
+        for (old, &c) in component.iter().enumerate() {
+            if c as usize == largest {
+                remap[old] = NodeId(nodes.len() as u32);
+                nodes.push(self.node_pos[old]);
+            }
+        }
+        if nodes.len() == self.node_count() {
+            return remap;
+        }
+
+        let kept: Vec<usize> = (0..self.edge_count())
+            .filter(|&e| [self.edge_from[e], self.edge_to[e]].iter().all(|n| remap[n.index()] != NodeId::INVALID))
+            .collect();
+        let profiles = self.speed_profiles.take().map(|p| p.select_edges(&kept));
+        let overlay = self.overlay.take().map(|o| o.select_edges(&kept));
+        let edge_attrs = self.edge_attrs.select_edges(&kept);
+        let geometry = self.edge_geometry.take().map(|g| g.select_edges(&kept));
+        let osm_ids = self.node_osm_ids.take().map(|ids| select_nodes(&ids, &remap));
+        let mode_speeds = std::mem::take(&mut self.mode_speeds);
+        *self = RoadNetwork::from_sorted_edges(
+            nodes,
+            kept.iter().map(|&e| remap[self.edge_from[e].index()]).collect(),
+            kept.iter().map(|&e| remap[self.edge_to[e].index()]).collect(),
+            kept.iter().map(|&e| self.edge_length_m[e]).collect(),
+            kept.iter().map(|&e| self.edge_travel_ms[e]).collect(),
+            kept.iter().map(|&e| self.edge_capacity_vph[e]).collect(),
+            kept.iter().map(|&e| self.edge_freeflow_mps[e]).collect(),
+            self.has_reverse_adjacency(),
+        );
+        self.speed_profiles = profiles;
+        self.overlay = overlay;
+        self.edge_attrs = edge_attrs;
+        self.edge_geometry = geometry;
+        self.node_osm_ids = osm_ids;
+        self.mode_speeds = mode_speeds;
+        remap
+    }
+}
+
+/// The values of the nodes `remap` keeps, in their new order.
+pub(crate) fn select_nodes<T: Copy>(values: &[T], remap: &[NodeId]) -> Vec<T> {
+    values.iter().zip(remap).filter(|(_, n)| **n != NodeId::INVALID).map(|(&v, _)| v).collect()
+}
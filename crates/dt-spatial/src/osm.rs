@@ -14,129 +14,334 @@
 //! Only drivable `highway=*` ways are included (see [`car_speed_mps`]).
 //! All other features (footways, buildings, POIs, relations) are ignored.
 //! One-way roads add a single directed edge; two-way roads add both directions.
+//! Each edge's capacity is a per-lane capacity for its road class (see
+//! [`lane_capacity_vph`]) times its lanes in that direction: the `lanes`
+//! tag, halved on two-way roads, or two on motorways and trunks and one
+//! elsewhere when untagged.
+//! [`PbfLoadOptions`] swaps in other roads and speeds (a walking network,
+//! say), clips to a bounding box, and can keep the OSM node and way ids and
+//! street names for mapping results back to the map.
 //!
 //! # Memory note
 //!
-//! The loader buffers all OSM nodes in a `HashMap<i64, GeoPoint>` for the
-//! first pass (needed because ways reference node IDs by OSM integer ID).
-//! For Mobile, AL this is roughly 3–8 million entries (≈ 100–200 MB).
-//! The map is freed before the R-tree is built.
+//! [`load_from_pbf`] reads the file once, buffering every OSM node in a
+//! `HashMap<i64, GeoPoint>` because ways reference nodes by OSM id.  For
+//! Mobile, AL this is roughly 3–8 million entries (≈ 100–200 MB); a state
+//! extract runs to hundreds of MB more.
+//!
+//! [`load_from_pbf_streaming`] reads the file twice instead: first the ways,
+//! collecting the ids of nodes on roads, then the nodes, keeping only those.
+//! Roads reference a small fraction of an extract's nodes, so peak memory
+//! drops to the road nodes and ways at the cost of decoding the file again.
+//! Both produce the same network.
 
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::Arc;
 
 use osmpbf::{Element, ElementReader};
 
-use dt_core::{GeoPoint, NodeId};
+use dt_core::{BBox, GeoPoint, NodeId};
 
+use crate::attributes::{OsmWayId, StreetName};
 use crate::network::{RoadNetwork, RoadNetworkBuilder};
 use crate::SpatialError;
 
-// ── Public entry point ────────────────────────────────────────────────────────
+// ── Options ───────────────────────────────────────────────────────────────────
+
+/// Decides whether a way is loaded and at what speed: given the `highway`
+/// value and all of the way's tags, returns the speed in m/s, or `None` to
+/// skip the way.
+pub type HighwayFilter = Box<dyn Fn(&str, &[(&str, &str)]) -> Option<f32> + Send + Sync>;
+
+/// What [`load_from_pbf_with`] loads.
+///
+/// The default matches [`load_from_pbf`]: car-drivable roads at the speeds
+/// in the module docs, one-way tags respected, no clipping, one pass.
+///
+/// ```ignore
+/// // Walking network of the city centre at 1.4 m/s.
+/// let options = PbfLoadOptions::new()
+///     .bbox(BBox::new(30.68, -88.06, 30.70, -88.03))
+///     .highways(|highway, _tags| match highway {
+///         "motorway" | "motorway_link" | "trunk" | "trunk_link" => None,
+///         _ => Some(1.4),
+///     })
+///     .oneway(false);
+/// let network = load_from_pbf_with(path, &options)?;
+/// ```
+pub struct PbfLoadOptions {
+    bbox:         Option<BBox>,
+    highways:     HighwayFilter,
+    oneway:       bool,
+    streaming:    bool,
+    keep_osm_ids: bool,
+}
+
+impl PbfLoadOptions {
+    pub fn new() -> Self {
+        Self {
+            bbox:         None,
+            highways:     Box::new(|highway, _| car_speed_mps(highway)),
+            oneway:       true,
+            streaming:    false,
+            keep_osm_ids: false,
+        }
+    }
+
+    /// Keep only nodes inside `bbox`.  Ways leaving the box are cut at its
+    /// edge; call [`RoadNetwork::retain_largest_scc`] afterwards to drop any
+    /// fragments left unconnected.
+    pub fn bbox(mut self, bbox: BBox) -> Self {
+        self.bbox = Some(bbox);
+        self
+    }
+
+    /// Replace the car-speed table with `filter` (see [`HighwayFilter`]).
+    pub fn highways(
+        mut self,
+        filter: impl Fn(&str, &[(&str, &str)]) -> Option<f32> + Send + Sync + 'static,
+    ) -> Self {
+        self.highways = Box::new(filter);
+        self
+    }
+
+    /// Whether one-way ways get a single directed edge (default `true`).
+    /// Turn off for walking networks.
+    pub fn oneway(mut self, oneway: bool) -> Self {
+        self.oneway = oneway;
+        self
+    }
+
+    /// Read the file in two passes, keeping only road nodes in memory (see
+    /// the module docs).  Default `false`.
+    pub fn streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    /// Keep each node's OSM id in [`RoadNetwork::node_osm_ids`] and register
+    /// the [`OsmWayId`] and [`StreetName`] (the `name` tag) edge attributes.
+    /// Default `false`.
+    pub fn keep_osm_ids(mut self, keep: bool) -> Self {
+        self.keep_osm_ids = keep;
+        self
+    }
+
+    fn keeps(&self, pos: GeoPoint) -> bool {
+        self.bbox.is_none_or(|b| b.contains(pos))
+    }
+
+    /// The way as a road, or `None` if the filter skips it.
+    fn road_way(&self, w: &osmpbf::Way<'_>) -> Option<OsmWay> {
+        // Collect tags eagerly so &str lifetimes don't escape the closure.
+        let tags: Vec<(&str, &str)> = w.tags().collect();
+        let highway = tags.iter().find(|(k, _)| *k == "highway").map(|(_, v)| *v)?;
+        let speed_mps = (self.highways)(highway, &tags)?;
+        let oneway = self.oneway && is_oneway(highway, &tags);
+        let capacity_vph = lane_capacity_vph(highway) * lanes_per_direction(highway, &tags, oneway);
+        let name = if self.keep_osm_ids {
+            tags.iter().find(|(k, _)| *k == "name").map(|(_, v)| Arc::from(*v))
+        } else {
+            None
+        };
+        Some(OsmWay { id: w.id(), refs: w.refs().collect(), speed_mps, oneway, capacity_vph, name })
+    }
+}
+
+impl Default for PbfLoadOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-/// Load a road network from an OSM PBF file.
+impl std::fmt::Debug for PbfLoadOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PbfLoadOptions")
+            .field("bbox", &self.bbox)
+            .field("oneway", &self.oneway)
+            .field("streaming", &self.streaming)
+            .field("keep_osm_ids", &self.keep_osm_ids)
+            .finish_non_exhaustive()
+    }
+}
+
+// ── Public entry points ───────────────────────────────────────────────────────
+
+/// Load a road network from an OSM PBF file in one pass.
 ///
-/// Only car-drivable roads are included.  Use
-/// [`RoadNetworkBuilder`] directly for non-OSM sources.
+/// Only car-drivable roads are included.  Use [`load_from_pbf_with`] to
+/// choose roads or clip to a box, and [`RoadNetworkBuilder`] directly for
+/// non-OSM sources.
 ///
 /// # Errors
 ///
 /// Returns [`SpatialError::Osm`] on parse errors,
 /// [`SpatialError::Io`] on file errors.
 pub fn load_from_pbf(path: &Path) -> Result<RoadNetwork, SpatialError> {
-    // ── Phase 1: collect all OSM nodes + road ways in one sequential pass ──
-    let reader = ElementReader::from_path(path)?;
+    load_from_pbf_with(path, &PbfLoadOptions::new())
+}
 
+/// Load a road network from an OSM PBF file in two passes, keeping only
+/// road nodes in memory (see the module docs).
+///
+/// # Errors
+///
+/// As [`load_from_pbf`].
+pub fn load_from_pbf_streaming(path: &Path) -> Result<RoadNetwork, SpatialError> {
+    load_from_pbf_with(path, &PbfLoadOptions::new().streaming(true))
+}
+
+/// Load the roads `options` selects from an OSM PBF file.
+///
+/// # Errors
+///
+/// As [`load_from_pbf`].
+pub fn load_from_pbf_with(path: &Path, options: &PbfLoadOptions) -> Result<RoadNetwork, SpatialError> {
+    if options.streaming {
+        return load_two_pass(path, options);
+    }
     let mut all_nodes: HashMap<i64, GeoPoint> = HashMap::new();
     let mut road_ways: Vec<OsmWay> = Vec::new();
+    let mut keep = |id: i64, lat: f64, lon: f64| {
+        let pos = GeoPoint::new(lat as f32, lon as f32);
+        if options.keeps(pos) {
+            all_nodes.insert(id, pos);
+        }
+    };
 
-    reader
+    reader(path)?
         .for_each(|elem| match elem {
-            Element::Node(n) => {
-                all_nodes.insert(
-                    n.id(),
-                    GeoPoint::new(n.lat() as f32, n.lon() as f32),
-                );
-            }
-            Element::DenseNode(n) => {
-                all_nodes.insert(
-                    n.id(),
-                    GeoPoint::new(n.lat() as f32, n.lon() as f32),
-                );
-            }
-            Element::Way(w) => {
-                // Collect tags eagerly so &str lifetimes don't escape the closure.
-                let tags: Vec<(&str, &str)> = w.tags().collect();
-                let highway = tags
-                    .iter()
-                    .find(|(k, _)| *k == "highway")
-                    .map(|(_, v)| *v);
-
-                if let Some(speed_mps) = highway.and_then(car_speed_mps) {
-                    let oneway = is_oneway(highway.unwrap_or(""), &tags);
-                    let refs: Vec<i64> = w.refs().collect();
-                    road_ways.push(OsmWay { refs, speed_mps, oneway });
-                }
+            Element::Node(n) => keep(n.id(), n.lat(), n.lon()),
+            Element::DenseNode(n) => keep(n.id(), n.lat(), n.lon()),
+            Element::Way(w) => road_ways.extend(options.road_way(&w)),
+            _ => {}
+        })
+        .map_err(osm_error)?;
+
+    Ok(build_network(&road_ways, all_nodes, options.keep_osm_ids))
+}
+
+fn load_two_pass(path: &Path, options: &PbfLoadOptions) -> Result<RoadNetwork, SpatialError> {
+    // ── Pass 1: road ways and the node ids they reference ─────────────────
+    let mut road_ways: Vec<OsmWay> = Vec::new();
+    reader(path)?
+        .for_each(|elem| {
+            if let Element::Way(w) = elem {
+                road_ways.extend(options.road_way(&w));
             }
+        })
+        .map_err(osm_error)?;
+    let road_node_ids: HashSet<i64> = road_ways.iter().flat_map(|w| w.refs.iter().copied()).collect();
+
+    // ── Pass 2: positions of those nodes only ─────────────────────────────
+    let mut road_nodes: HashMap<i64, GeoPoint> = HashMap::with_capacity(road_node_ids.len());
+    let mut keep = |id: i64, lat: f64, lon: f64| {
+        let pos = GeoPoint::new(lat as f32, lon as f32);
+        if road_node_ids.contains(&id) && options.keeps(pos) {
+            road_nodes.insert(id, pos);
+        }
+    };
+    reader(path)?
+        .for_each(|elem| match elem {
+            Element::Node(n) => keep(n.id(), n.lat(), n.lon()),
+            Element::DenseNode(n) => keep(n.id(), n.lat(), n.lon()),
             _ => {}
         })
-        .map_err(|e| SpatialError::Osm(e.to_string()))?;
+        .map_err(osm_error)?;
+    drop(road_node_ids);
 
-    // ── Phase 2: identify road-referenced node IDs ────────────────────────
-    let road_node_ids: HashSet<i64> = road_ways
-        .iter()
-        .flat_map(|w| w.refs.iter().copied())
-        .collect();
+    Ok(build_network(&road_ways, road_nodes, options.keep_osm_ids))
+}
+
+// ── Shared steps ──────────────────────────────────────────────────────────────
+
+fn reader(path: &Path) -> Result<ElementReader<std::io::BufReader<std::fs::File>>, SpatialError> {
+    ElementReader::from_path(path).map_err(osm_error)
+}
+
+fn osm_error(e: osmpbf::Error) -> SpatialError {
+    SpatialError::Osm(e.to_string())
+}
 
-    // ── Phase 3: build network ────────────────────────────────────────────
+/// Build the network from road ways and the positions of (at least) their
+/// nodes.  `NodeId`s follow each node's first appearance in `road_ways`, so
+/// the same file always gives the same ids.  With `keep_ids`, also record
+/// the OSM ids and street names (see [`PbfLoadOptions::keep_osm_ids`]).
+pub(crate) fn build_network(road_ways: &[OsmWay], positions: HashMap<i64, GeoPoint>, keep_ids: bool) -> RoadNetwork {
+    let ref_count: usize = road_ways.iter().map(|w| w.refs.len()).sum();
     // Pre-allocate: ~2× road nodes for edges (rough estimate).
-    let mut builder = RoadNetworkBuilder::with_capacity(
-        road_node_ids.len(),
-        road_node_ids.len() * 2,
-    );
+    let mut builder = RoadNetworkBuilder::with_capacity(ref_count, ref_count * 2);
 
     // Map OSM node IDs → our NodeIds, adding only road-relevant nodes.
-    let mut osm_to_dt: HashMap<i64, NodeId> =
-        HashMap::with_capacity(road_node_ids.len());
-
-    for osm_id in &road_node_ids {
-        if let Some(&pos) = all_nodes.get(osm_id) {
-            let dt_id = builder.add_node(pos);
-            osm_to_dt.insert(*osm_id, dt_id);
+    let mut osm_to_dt: HashMap<i64, NodeId> = HashMap::new();
+    let mut node_osm_ids = Vec::new();
+    for osm_id in road_ways.iter().flat_map(|w| w.refs.iter()) {
+        if !osm_to_dt.contains_key(osm_id)
+            && let Some(&pos) = positions.get(osm_id)
+        {
+            osm_to_dt.insert(*osm_id, builder.add_node(pos));
+            if keep_ids {
+                node_osm_ids.push(*osm_id);
+            }
         }
     }
 
-    // Free the full node map — no longer needed.
-    drop(all_nodes);
-    drop(road_node_ids);
+    // Free the node positions before the R-tree is built.
+    drop(positions);
 
-    // Add directed edges from way node sequences.
-    for way in &road_ways {
+    // Add directed edges from way node sequences.  The builder reorders
+    // edges, so remember which way each (from, to) pair came from; the
+    // first way wins where two share a segment.
+    let mut segment_way: HashMap<(NodeId, NodeId), usize> = HashMap::new();
+    for (w, way) in road_ways.iter().enumerate() {
         for window in way.refs.windows(2) {
             let (osm_a, osm_b) = (window[0], window[1]);
             if let (Some(&from), Some(&to)) =
                 (osm_to_dt.get(&osm_a), osm_to_dt.get(&osm_b))
             {
-                let len_m = builder.node_pos(from).distance_m(builder.node_pos(to));
+                // Consecutive way nodes are metres apart, where the
+                // equirectangular approximation matches haversine.
+                let len_m = builder.node_pos(from).approx_distance_m(builder.node_pos(to));
                 let travel_ms = (len_m / way.speed_mps * 1_000.0) as u32;
 
-                builder.add_directed_edge(from, to, len_m, travel_ms);
+                builder.add_directed_edge_with_capacity(from, to, len_m, travel_ms, way.capacity_vph);
                 if !way.oneway {
-                    builder.add_directed_edge(to, from, len_m, travel_ms);
+                    builder.add_directed_edge_with_capacity(to, from, len_m, travel_ms, way.capacity_vph);
+                }
+                if keep_ids {
+                    segment_way.entry((from, to)).or_insert(w);
+                    if !way.oneway {
+                        segment_way.entry((to, from)).or_insert(w);
+                    }
                 }
             }
         }
     }
 
-    Ok(builder.build())
+    let mut network = builder.build();
+    if keep_ids {
+        let ways: Vec<&OsmWay> = (0..network.edge_count())
+            .map(|e| &road_ways[segment_way[&(network.edge_from[e], network.edge_to[e])]])
+            .collect();
+        network.edge_attrs.insert(ways.iter().map(|w| OsmWayId(w.id)).collect());
+        network.edge_attrs.insert(ways.iter().map(|w| StreetName(w.name.clone().unwrap_or_default())).collect());
+        network.node_osm_ids = Some(node_osm_ids);
+    }
+    network
 }
 
 // ── Internal types ────────────────────────────────────────────────────────────
 
-struct OsmWay {
-    refs:      Vec<i64>,
-    speed_mps: f32,
-    oneway:    bool,
+pub(crate) struct OsmWay {
+    pub(crate) id:           i64,
+    pub(crate) refs:         Vec<i64>,
+    pub(crate) speed_mps:    f32,
+    pub(crate) oneway:       bool,
+    /// Capacity of each direction, vehicles per hour.
+    pub(crate) capacity_vph: f32,
+    /// The `name` tag, kept only with [`PbfLoadOptions::keep_osm_ids`].
+    pub(crate) name:         Option<Arc<str>>,
 }
 
 // ── Tag helpers ───────────────────────────────────────────────────────────────
@@ -163,6 +368,35 @@ fn car_speed_mps(highway: &str) -> Option<f32> {
     }
 }
 
+/// Return the assumed capacity of one lane (vehicles per hour) for a road
+/// class.  Unknown classes get the residential figure.
+fn lane_capacity_vph(highway: &str) -> f32 {
+    match highway {
+        "motorway" | "motorway_link"         => 2000.0,
+        "trunk"    | "trunk_link"            => 1800.0,
+        "primary"  | "primary_link"          => 1500.0,
+        "secondary"| "secondary_link"        => 1200.0,
+        "tertiary" | "tertiary_link"         => 900.0,
+        _                                    => 600.0,
+    }
+}
+
+/// Lanes in each direction of travel: the `lanes` tag (total across both
+/// directions, so halved unless `oneway`), else a default by road class.
+fn lanes_per_direction(highway: &str, tags: &[(&str, &str)], oneway: bool) -> f32 {
+    let tagged = tags
+        .iter()
+        .find(|(k, _)| *k == "lanes")
+        .and_then(|(_, v)| v.trim().parse::<f32>().ok())
+        .filter(|&lanes| lanes >= 1.0);
+    match tagged {
+        Some(lanes) if oneway => lanes,
+        Some(lanes) => (lanes / 2.0).max(1.0),
+        None if matches!(highway, "motorway" | "trunk") => 2.0,
+        None => 1.0,
+    }
+}
+
 /// Determine whether a way should be treated as one-way for car traffic.
 ///
 /// Motorways and motorway links are implicitly one-way in OSM convention.
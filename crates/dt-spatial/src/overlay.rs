@@ -0,0 +1,175 @@
+//! Runtime edge closures and travel-time changes.
+//!
+//! A [`NetworkOverlay`] marks edges closed or scales their car travel time
+//! without touching `edge_travel_ms` or rebuilding the CSR, so scenario
+//! events — a bridge closing, a flooded district, an evacuation contraflow —
+//! can be switched on and off mid-run and undone exactly.
+//!
+//! Attach one with [`RoadNetwork::set_overlay`], or edit it in place through
+//! [`RoadNetwork::overlay_mut`].  Every router built on the crate's edge
+//! costs consults it: [`DijkstraRouter`](crate::DijkstraRouter),
+//! [`BidirectionalRouter`](crate::BidirectionalRouter),
+//! [`TimeDependentRouter`](crate::TimeDependentRouter), travel-time
+//! matrices, alternatives, and [`RoadNetwork::reachable_nodes`].
+//! [`ChRouter`](crate::ChRouter) falls back to Dijkstra while the overlay
+//! changes any edge.
+//!
+//! Closed edges are closed to every mode; factors scale car travel times
+//! only, like speed profiles.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! for edge in network.out_edges(bridge_end) {
+//!     network.overlay_mut().close(edge);
+//! }
+//! // … later
+//! network.clear_overlay();
+//! ```
+
+use dt_core::EdgeId;
+
+use crate::network::RoadNetwork;
+
+/// Factor of an edge the overlay leaves alone.
+const UNCHANGED: f32 = 1.0;
+
+/// Factor of a closed edge.
+const CLOSED: f32 = f32::INFINITY;
+
+/// Per-edge closures and travel-time factors.  See the module docs.
+#[derive(Debug, Clone, Default)]
+pub struct NetworkOverlay {
+    /// Car travel-time factor of each edge; `CLOSED` for closed edges.
+    factor:  Vec<f32>,
+    /// Number of edges whose factor isn't `UNCHANGED`.
+    changed: usize,
+}
+
+impl NetworkOverlay {
+    /// An overlay for `edge_count` edges that changes nothing.
+    pub fn new(edge_count: usize) -> Self {
+        Self { factor: vec![UNCHANGED; edge_count], changed: 0 }
+    }
+
+    /// Close `edge` to every mode.
+    pub fn close(&mut self, edge: EdgeId) {
+        self.set(edge, CLOSED);
+    }
+
+    /// Multiply the car travel time of `edge` by `factor`, replacing any
+    /// earlier factor or closure.
+    ///
+    /// # Panics
+    ///
+    /// If `factor` is not finite and positive.
+    pub fn scale(&mut self, edge: EdgeId, factor: f32) {
+        assert!(factor.is_finite() && factor > 0.0, "overlay factor must be finite and positive: {factor}");
+        self.set(edge, factor);
+    }
+
+    /// Undo any closure or factor on `edge`.
+    pub fn reset(&mut self, edge: EdgeId) {
+        self.set(edge, UNCHANGED);
+    }
+
+    /// Undo every change.
+    pub fn clear(&mut self) {
+        self.factor.fill(UNCHANGED);
+        self.changed = 0;
+    }
+
+    /// `true` if `edge` is closed.
+    pub fn is_closed(&self, edge: EdgeId) -> bool {
+        self.factor[edge.index()] == CLOSED
+    }
+
+    /// Car travel-time factor of `edge`: `1.0` if unchanged, infinite if
+    /// closed.
+    pub fn factor(&self, edge: EdgeId) -> f32 {
+        self.factor[edge.index()]
+    }
+
+    /// Number of edges closed or scaled.
+    pub fn changed_count(&self) -> usize {
+        self.changed
+    }
+
+    /// `true` if no edge is closed or scaled.
+    pub fn is_empty(&self) -> bool {
+        self.changed == 0
+    }
+
+    /// Number of edges the overlay covers.
+    pub fn edge_count(&self) -> usize {
+        self.factor.len()
+    }
+
+    /// `ms` to traverse `edge` with the overlay applied: `u32::MAX` if
+    /// closed, scaled if `car`.
+    #[inline]
+    pub(crate) fn apply(&self, edge: EdgeId, ms: u32, car: bool) -> u32 {
+        match self.factor[edge.index()] {
+            UNCHANGED => ms,
+            CLOSED => u32::MAX,
+            _ if !car || ms == u32::MAX => ms,
+            f => (ms as f64 * f as f64).round().min((u32::MAX - 1) as f64) as u32,
+        }
+    }
+
+    /// Cover `count` more edges, left unchanged.
+    pub(crate) fn append_edges(&mut self, count: usize) {
+        self.factor.resize(self.factor.len() + count, UNCHANGED);
+    }
+
+    /// The overlay restricted to `edges` (old indices, in their new order).
+    pub(crate) fn select_edges(&self, edges: &[usize]) -> Self {
+        let factor: Vec<f32> = edges.iter().map(|&e| self.factor[e]).collect();
+        let changed = factor.iter().filter(|&&f| f != UNCHANGED).count();
+        Self { factor, changed }
+    }
+
+    fn set(&mut self, edge: EdgeId, factor: f32) {
+        let old = std::mem::replace(&mut self.factor[edge.index()], factor);
+        match (old == UNCHANGED, factor == UNCHANGED) {
+            (true, false) => self.changed += 1,
+            (false, true) => self.changed -= 1,
+            _ => {}
+        }
+    }
+}
+
+impl RoadNetwork {
+    /// Attach `overlay`, replacing any already set.
+    ///
+    /// # Panics
+    ///
+    /// If `overlay` was not sized for this network's edges.
+    pub fn set_overlay(&mut self, overlay: NetworkOverlay) {
+        assert_eq!(
+            overlay.edge_count(),
+            self.edge_count(),
+            "overlay covers {} edges, network has {}",
+            overlay.edge_count(),
+            self.edge_count()
+        );
+        self.overlay = Some(overlay);
+    }
+
+    /// The attached overlay, attaching an empty one first if there is none.
+    pub fn overlay_mut(&mut self) -> &mut NetworkOverlay {
+        let edges = self.edge_count();
+        self.overlay.get_or_insert_with(|| NetworkOverlay::new(edges))
+    }
+
+    /// Detach and return the overlay, restoring the network's own costs.
+    pub fn clear_overlay(&mut self) -> Option<NetworkOverlay> {
+        self.overlay.take()
+    }
+
+    /// `true` if an attached overlay closes or scales any edge.
+    #[inline]
+    pub fn has_overlay_changes(&self) -> bool {
+        self.overlay.as_ref().is_some_and(|o| !o.is_empty())
+    }
+}
@@ -0,0 +1,175 @@
+//! Bi-criteria (Pareto) routing.
+//!
+//! A [`CostRouter`](crate::CostRouter) folds everything into one weight, so
+//! a study of how agents trade time against distance or tolls has to guess
+//! the exchange rate up front.  [`ParetoRouter`] keeps two [`EdgeCost`]s
+//! apart and returns every route that no other route beats on both: the
+//! Pareto frontier, from fastest-by-the-first-cost to cheapest-by-the-second.
+//! Behaviour models then choose along it with whatever value of time they
+//! assign each agent.
+//!
+//! The search is Martins' label-setting algorithm: labels leave the queue in
+//! lexicographic `(first, second)` order, so a node's new label is kept only
+//! if its second cost beats every label the node already has.  The frontier
+//! between two correlated costs such as time and distance is usually a
+//! handful of routes; uncorrelated costs make it, and the search, larger.
+//!
+//! # Example
+//!
+//! ```
+//! use dt_core::{GeoPoint, TransportMode};
+//! use dt_spatial::{ParetoRouter, RoadNetworkBuilder};
+//!
+//! let mut b = RoadNetworkBuilder::new();
+//! let a = b.add_node(GeoPoint::new(30.69, -88.04));
+//! let c = b.add_node(GeoPoint::new(30.70, -88.03));
+//! b.add_directed_edge(a, c, 2_000.0, 80_000);    // bypass: longer, faster
+//! b.add_directed_edge(a, c, 1_200.0, 120_000);   // through town
+//! b.add_directed_edge(a, c, 2_500.0, 150_000);   // worse on both
+//! let net = b.build();
+//!
+//! // Travel time against distance.
+//! let frontier = ParetoRouter::time_distance().frontier(&net, a, c, TransportMode::Car).unwrap();
+//! assert_eq!(frontier.len(), 2);
+//! assert_eq!(frontier[0].route.total_travel_secs(), 80.0);
+//! assert_eq!(frontier[1].route.length_m(&net), 1_200.0);
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use dt_core::{EdgeId, NodeId, TransportMode};
+
+use crate::cost::{DistanceCost, EdgeCost, ModeSpeedCost};
+use crate::network::RoadNetwork;
+use crate::router::{edge_cost_ms, Route};
+use crate::{SpatialError, SpatialResult};
+
+// ── ParetoRouter ──────────────────────────────────────────────────────────────
+
+/// Routes on two costs at once.  The default pairs travel time
+/// ([`ModeSpeedCost`]) with distance ([`DistanceCost`]); for time against
+/// tolls, pass a closure reading a toll from the network's
+/// [`EdgeAttributes`](crate::EdgeAttributes) as `second`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ParetoRouter<A = ModeSpeedCost, B = DistanceCost> {
+    first:  A,
+    second: B,
+}
+
+/// One route of a Pareto frontier and its two costs.
+#[derive(Debug, Clone)]
+pub struct ParetoRoute {
+    /// The route; `total_travel_ms` is the mode's travel time along it,
+    /// whatever the costs are.
+    pub route: Route,
+    /// Summed `[first, second]` edge costs.
+    pub costs: [u64; 2],
+}
+
+impl ParetoRouter {
+    /// Travel time against distance.
+    pub fn time_distance() -> Self {
+        Self::default()
+    }
+}
+
+impl<A: EdgeCost, B: EdgeCost> ParetoRouter<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// The cost functions, `(first, second)`.
+    pub fn costs(&self) -> (&A, &B) {
+        (&self.first, &self.second)
+    }
+
+    /// Every Pareto-optimal route from `from` to `to`, by ascending first
+    /// cost (and so descending second cost).  Of routes with equal costs
+    /// one is kept.  An edge either cost makes impassable (`u32::MAX`) is
+    /// not used.  `from == to` gives the single empty route.
+    ///
+    /// # Errors
+    ///
+    /// [`SpatialError::NoRoute`] if `to` cannot be reached.
+    pub fn frontier(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+    ) -> SpatialResult<Vec<ParetoRoute>> {
+        if from == to {
+            let route = Route { edges: vec![], total_travel_ms: 0, rides: Vec::new() };
+            return Ok(vec![ParetoRoute { route, costs: [0, 0] }]);
+        }
+
+        // Permanent labels; `best_second[v]` is the lowest second cost of
+        // any permanent label at `v`, which dominates every later label
+        // there with a second cost at least as high.
+        let mut labels: Vec<Label> = Vec::new();
+        let mut best_second = vec![u64::MAX; network.node_count()];
+        let mut targets: Vec<usize> = Vec::new();
+
+        // Ties go to the lower node, then edge, for a deterministic order.
+        let mut heap: BinaryHeap<Reverse<Entry>> = BinaryHeap::new();
+        heap.push(Reverse((0, 0, from, EdgeId::INVALID, u32::MAX)));
+
+        while let Some(Reverse((first, second, node, edge, pred))) = heap.pop() {
+            if second >= best_second[node.index()] {
+                continue;
+            }
+            best_second[node.index()] = second;
+            let label = labels.len() as u32;
+            labels.push(Label { edge, pred, costs: [first, second] });
+            if node == to {
+                targets.push(label as usize);
+                continue;
+            }
+
+            for e in network.out_edges(node) {
+                let (a, b) = (self.first.cost_ms(network, e, mode, None), self.second.cost_ms(network, e, mode, None));
+                if a == u32::MAX || b == u32::MAX {
+                    continue;
+                }
+                let next = network.edge_to[e.index()];
+                let (first, second) = (first + a as u64, second + b as u64);
+                // Dominated by a label already at `next` or at the target.
+                if second >= best_second[next.index()] || second >= best_second[to.index()] {
+                    continue;
+                }
+                heap.push(Reverse((first, second, next, e, label)));
+            }
+        }
+
+        if targets.is_empty() {
+            return Err(SpatialError::NoRoute { from, to });
+        }
+        Ok(targets
+            .into_iter()
+            .map(|target| {
+                let mut edges = Vec::new();
+                let costs = labels[target].costs;
+                let mut label = &labels[target];
+                while label.pred != u32::MAX {
+                    edges.push(label.edge);
+                    label = &labels[label.pred as usize];
+                }
+                edges.reverse();
+                let ms: u64 = edges.iter().map(|&e| edge_cost_ms(network, e, mode) as u64).sum();
+                ParetoRoute { route: Route { edges, total_travel_ms: ms, rides: Vec::new() }, costs }
+            })
+            .collect())
+    }
+}
+
+/// Queue entry: `(first, second, node, edge, predecessor label)`.
+type Entry = (u64, u64, NodeId, EdgeId, u32);
+
+/// A permanent label: the edge it arrived by, the label it extended, and
+/// its costs.
+struct Label {
+    edge:  EdgeId,
+    pred:  u32,
+    costs: [u64; 2],
+}
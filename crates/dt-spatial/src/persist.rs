@@ -0,0 +1,148 @@
+//! Binary save and load of a [`RoadNetwork`].
+//!
+//! Loading a metro-area PBF takes minutes; reading a saved network takes
+//! about as long as the file takes to read.  The file holds the node
+//! positions and the edge arrays in `EdgeId` order, so `EdgeId`s — and the
+//! network [`fingerprint`](RoadNetwork::fingerprint), which the file also
+//! records — survive the round trip.  The CSR row pointers, the reverse
+//! adjacency, and the R-tree are rebuilt on load.
+//!
+//! Speed profiles, overlays, edge attributes, node OSM ids, and the edge
+//! geometry of a simplified network are not saved; attach them again after
+//! loading.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let network = if path.exists() {
+//!     RoadNetwork::load(path)?
+//! } else {
+//!     let network = dt_spatial::osm::load_from_pbf(pbf)?;
+//!     network.save(path)?;
+//!     network
+//! };
+//! ```
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use dt_core::{GeoPoint, NodeId};
+
+use crate::network::RoadNetwork;
+use crate::{SpatialError, SpatialResult};
+
+/// Leading bytes of a saved network.
+const MAGIC: &[u8; 4] = b"DTRN";
+
+/// File format version, bumped on any layout change.
+const VERSION: u32 = 2;
+
+impl RoadNetwork {
+    /// Write the network to `path` (see [`write_to`](Self::write_to)).
+    pub fn save(&self, path: &Path) -> SpatialResult<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write_to(&mut w)?;
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Serialise as `DTRN`, a version, the fingerprint, then node latitudes,
+    /// node longitudes, and the six edge arrays as length-prefixed
+    /// little-endian `u32`s (floats by their bits).
+    pub fn write_to(&self, w: &mut impl Write) -> SpatialResult<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&VERSION.to_le_bytes())?;
+        w.write_all(&self.fingerprint().to_le_bytes())?;
+        write_words(w, self.node_pos.iter().map(|p| p.lat.to_bits()), self.node_count())?;
+        write_words(w, self.node_pos.iter().map(|p| p.lon.to_bits()), self.node_count())?;
+        write_words(w, self.edge_from.iter().map(|n| n.0), self.edge_count())?;
+        write_words(w, self.edge_to.iter().map(|n| n.0), self.edge_count())?;
+        write_words(w, self.edge_length_m.iter().map(|m| m.to_bits()), self.edge_count())?;
+        write_words(w, self.edge_travel_ms.iter().copied(), self.edge_count())?;
+        write_words(w, self.edge_capacity_vph.iter().map(|c| c.to_bits()), self.edge_count())?;
+        write_words(w, self.edge_freeflow_mps.iter().map(|v| v.to_bits()), self.edge_count())?;
+        Ok(())
+    }
+
+    /// Read a network saved with [`save`](Self::save).
+    ///
+    /// Fails with [`SpatialError::NetworkFile`] if the file is not a saved
+    /// network or is corrupt.
+    pub fn load(path: &Path) -> SpatialResult<Self> {
+        Self::read_from(&mut BufReader::new(File::open(path)?))
+    }
+
+    /// Read a network written by [`write_to`](Self::write_to).
+    pub fn read_from(r: &mut impl Read) -> SpatialResult<Self> {
+        let invalid = |msg: &str| SpatialError::NetworkFile(msg.to_string());
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a road network file"));
+        }
+        let mut word = [0u8; 4];
+        r.read_exact(&mut word)?;
+        if u32::from_le_bytes(word) != VERSION {
+            return Err(SpatialError::NetworkFile(format!("unsupported version {}", u32::from_le_bytes(word))));
+        }
+        let mut fingerprint = [0u8; 8];
+        r.read_exact(&mut fingerprint)?;
+        let fingerprint = u64::from_le_bytes(fingerprint);
+
+        let mut arrays: [Vec<u32>; 8] = Default::default();
+        for words in &mut arrays {
+            *words = read_words(r, SpatialError::NetworkFile)?;
+        }
+        let [lat, lon, from, to, length_m, travel_ms, capacity, freeflow] = arrays;
+        let (nodes, edges) = (lat.len(), from.len());
+        if lon.len() != nodes || [&to, &length_m, &travel_ms, &capacity, &freeflow].iter().any(|a| a.len() != edges) {
+            return Err(invalid("arrays differ in length"));
+        }
+        if from.iter().chain(&to).any(|&n| n as usize >= nodes) {
+            return Err(invalid("edge refers to a missing node"));
+        }
+        if !from.windows(2).all(|w| w[0] <= w[1]) {
+            return Err(invalid("edges not sorted by source node"));
+        }
+
+        let network = Self::from_sorted_edges(
+            lat.iter().zip(&lon).map(|(&la, &lo)| GeoPoint::new(f32::from_bits(la), f32::from_bits(lo))).collect(),
+            from.into_iter().map(NodeId).collect(),
+            to.into_iter().map(NodeId).collect(),
+            length_m.into_iter().map(f32::from_bits).collect(),
+            travel_ms,
+            capacity.into_iter().map(f32::from_bits).collect(),
+            freeflow.into_iter().map(f32::from_bits).collect(),
+            true,
+        );
+        if network.fingerprint() != fingerprint {
+            return Err(invalid("fingerprint mismatch"));
+        }
+        Ok(network)
+    }
+}
+
+/// Write `len` words as one length-prefixed little-endian `u32` array.
+pub(crate) fn write_words(w: &mut impl Write, words: impl IntoIterator<Item = u32>, len: usize) -> SpatialResult<()> {
+    w.write_all(&(len as u32).to_le_bytes())?;
+    for word in words {
+        w.write_all(&word.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Read one length-prefixed little-endian `u32` array; a short read is
+/// reported through `invalid`.
+pub(crate) fn read_words(r: &mut impl Read, invalid: fn(String) -> SpatialError) -> SpatialResult<Vec<u32>> {
+    let mut len = [0u8; 4];
+    r.read_exact(&mut len)?;
+    let bytes = u32::from_le_bytes(len) as u64 * 4;
+    // Read through `take` so a corrupt length can't trigger a huge allocation.
+    let mut buf = Vec::new();
+    r.take(bytes).read_to_end(&mut buf)?;
+    if buf.len() as u64 != bytes {
+        return Err(invalid("truncated file".to_string()));
+    }
+    Ok(buf.chunks_exact(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect())
+}
@@ -0,0 +1,167 @@
+//! Hourly speed profiles for time-dependent car travel times.
+//!
+//! A profile is 24 travel-time factors, one per hour of the day, applied to
+//! an edge's free-flow `edge_travel_ms`: `2.0` at 08:00 means the edge takes
+//! twice as long to enter between 08:00 and 09:00.  Edges share profiles
+//! (typically one per road class or corridor), so the table costs one `u32`
+//! per edge plus 96 bytes per profile.
+//!
+//! Attach a table with [`RoadNetwork::set_speed_profiles`] and route with
+//! [`TimeDependentRouter`](crate::TimeDependentRouter); other routers keep
+//! using the free-flow times.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut profiles = SpeedProfiles::new(network.edge_count());
+//! let mut peak = [1.0; 24];
+//! peak[7..10].fill(2.0);
+//! let rush = profiles.add_profile(peak);
+//! for edge in network.in_edges(downtown) {
+//!     profiles.assign(edge, rush);
+//! }
+//! network.set_speed_profiles(profiles);
+//! ```
+
+use dt_core::{EdgeId, Tick};
+
+use crate::network::RoadNetwork;
+
+/// Profile index of edges without one.
+const FREE_FLOW: u32 = u32::MAX;
+
+/// Seconds in a day; profiles repeat with this period.
+pub(crate) const SECS_PER_DAY: u32 = 86_400;
+
+/// Seconds after midnight at the start of `tick`, tick 0 being
+/// `start_secs_of_day` after midnight.
+pub(crate) fn time_of_day(start_secs_of_day: u32, tick: Tick, tick_duration_secs: u32) -> u32 {
+    let day = SECS_PER_DAY as u64;
+    let elapsed = tick.0 % day * tick_duration_secs as u64 % day;
+    (start_secs_of_day + elapsed as u32) % SECS_PER_DAY
+}
+
+/// Per-edge hourly travel-time factors.  See the module docs.
+#[derive(Debug, Clone, Default)]
+pub struct SpeedProfiles {
+    /// 24 hourly factors per profile.
+    factors:      Vec<[f32; 24]>,
+    /// Profile of each edge, or `FREE_FLOW`.
+    edge_profile: Vec<u32>,
+}
+
+impl SpeedProfiles {
+    /// A table for `edge_count` edges, all free-flow.
+    pub fn new(edge_count: usize) -> Self {
+        Self { factors: Vec::new(), edge_profile: vec![FREE_FLOW; edge_count] }
+    }
+
+    /// Add a profile and return its index for [`assign`](Self::assign).
+    ///
+    /// # Panics
+    ///
+    /// If any factor is not finite and positive.
+    pub fn add_profile(&mut self, factors: [f32; 24]) -> u32 {
+        assert!(
+            factors.iter().all(|f| f.is_finite() && *f > 0.0),
+            "speed profile factors must be finite and positive: {factors:?}"
+        );
+        self.factors.push(factors);
+        (self.factors.len() - 1) as u32
+    }
+
+    /// Give `edge` profile `profile`.
+    ///
+    /// # Panics
+    ///
+    /// If `edge` or `profile` is out of range.
+    pub fn assign(&mut self, edge: EdgeId, profile: u32) {
+        assert!((profile as usize) < self.factors.len(), "no speed profile {profile}");
+        self.edge_profile[edge.index()] = profile;
+    }
+
+    /// Return `edge` to free flow.
+    pub fn clear(&mut self, edge: EdgeId) {
+        self.edge_profile[edge.index()] = FREE_FLOW;
+    }
+
+    /// The profile of `edge`, or `None` if it is free-flow.
+    pub fn profile_of(&self, edge: EdgeId) -> Option<u32> {
+        Some(self.edge_profile[edge.index()]).filter(|&p| p != FREE_FLOW)
+    }
+
+    /// The 24 factors of `profile`.
+    pub fn factors(&self, profile: u32) -> &[f32; 24] {
+        &self.factors[profile as usize]
+    }
+
+    pub fn profile_count(&self) -> usize {
+        self.factors.len()
+    }
+
+    /// Number of edges the table covers.
+    pub fn edge_count(&self) -> usize {
+        self.edge_profile.len()
+    }
+
+    /// Cover `count` more edges, at free flow.
+    pub(crate) fn append_edges(&mut self, count: usize) {
+        self.edge_profile.resize(self.edge_profile.len() + count, FREE_FLOW);
+    }
+
+    /// The table restricted to `edges` (old indices, in their new order).
+    pub(crate) fn select_edges(&self, edges: &[usize]) -> Self {
+        Self {
+            factors:      self.factors.clone(),
+            edge_profile: edges.iter().map(|&e| self.edge_profile[e]).collect(),
+        }
+    }
+
+    /// Travel-time factor of `edge` entered `secs_of_day` after midnight
+    /// (taken modulo a day); `1.0` for free-flow edges.
+    #[inline]
+    pub fn factor(&self, edge: EdgeId, secs_of_day: u32) -> f32 {
+        match self.edge_profile[edge.index()] {
+            FREE_FLOW => 1.0,
+            p => self.factors[p as usize][(secs_of_day % SECS_PER_DAY / 3_600) as usize],
+        }
+    }
+}
+
+impl RoadNetwork {
+    /// Attach hourly speed profiles, replacing any already set.
+    ///
+    /// # Panics
+    ///
+    /// If `profiles` was not sized for this network's edges.
+    pub fn set_speed_profiles(&mut self, profiles: SpeedProfiles) {
+        assert_eq!(
+            profiles.edge_count(),
+            self.edge_count(),
+            "speed profiles cover {} edges, network has {}",
+            profiles.edge_count(),
+            self.edge_count()
+        );
+        self.speed_profiles = Some(profiles);
+    }
+
+    /// Car travel time of `edge` in milliseconds when entered `secs_of_day`
+    /// after midnight: `edge_travel_ms` scaled by the edge's speed profile,
+    /// if any, and by the [overlay](crate::NetworkOverlay).  Closed edges
+    /// (`u32::MAX`) stay closed.
+    #[inline]
+    pub fn travel_ms_at(&self, edge: EdgeId, secs_of_day: u32) -> u32 {
+        let base = self.edge_travel_ms[edge.index()];
+        let ms = match &self.speed_profiles {
+            Some(profiles) if base != u32::MAX => {
+                let ms = (base as f64 * profiles.factor(edge, secs_of_day) as f64).round();
+                ms.min((u32::MAX - 1) as f64) as u32
+            }
+            _ => base,
+        };
+        match &self.overlay {
+            Some(overlay) => overlay.apply(edge, ms, true),
+            None => ms,
+        }
+    }
+}
@@ -9,16 +9,43 @@
 //!
 //! # Cost units
 //!
-//! All costs and totals are in **milliseconds** (u32) internally.  `Route`
-//! exposes `total_travel_secs: f32` and a `travel_ticks()` helper for
-//! integration with the sim clock.
+//! All costs and totals are integer **milliseconds**, from edge weights
+//! through the search to [`Route::total_travel_ms`]; `travel_ticks()` and
+//! `travel_duration()` convert to the sim clock by integer division.
+//! [`Route::total_travel_secs`] is for display only.
+//!
+//! # Determinism
+//!
+//! Every router in this crate returns the same route for the same network
+//! and query, on every platform and run:
+//!
+//! - Costs are integers, so path comparisons are exact.  Mode speeds,
+//!   overlay factors, and speed profiles turn into integer edge costs
+//!   through single IEEE-754 operations, which round identically
+//!   everywhere.
+//! - Queues order entries by `(cost, NodeId)`, so equal-cost nodes settle
+//!   lowest id first, and edges are relaxed in `EdgeId` order.
+//! - A node's predecessor only changes on a strictly cheaper path, so of
+//!   equal-cost paths the first found is kept.
+//! - Hash maps are only used for lookups, never iterated to make a choice.
+//!
+//! # Time dependence
+//!
+//! [`Router::route_at`] also receives the departure tick.  Routers that
+//! ignore the time of day need not implement it; [`TimeDependentRouter`]
+//! uses it to read the network's [`SpeedProfiles`](crate::SpeedProfiles).
+//! [`Router::route_for`] adds the travelling agent, for per-agent
+//! [`EdgeCost`](crate::EdgeCost)s.
 
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
+use std::fmt;
 
-use dt_core::{EdgeId, NodeId, TransportMode};
+use dt_core::{AgentId, EdgeId, GeoPoint, NodeId, Tick, TickDuration, TransportMode};
 
 use crate::network::RoadNetwork;
+use crate::profile::{time_of_day, SECS_PER_DAY};
+use crate::transit::TransitRide;
 use crate::SpatialError;
 
 // ── Route ─────────────────────────────────────────────────────────────────────
@@ -29,21 +56,126 @@ use crate::SpatialError;
 pub struct Route {
     /// Edges to traverse in order, from source to destination.
     pub edges: Vec<EdgeId>,
-    /// Cumulative car travel time in seconds.
-    pub total_travel_secs: f32,
+    /// Cumulative car travel time in milliseconds.
+    pub total_travel_ms: u64,
+    /// Scheduled vehicles ridden, in travel order.  Empty except for
+    /// [`TransitRouter`](crate::TransitRouter) routes.
+    pub rides: Vec<TransitRide>,
 }
 
 impl Route {
+    /// Total travel time in seconds, for display; compute with
+    /// [`total_travel_ms`](Self::total_travel_ms).
+    pub fn total_travel_secs(&self) -> f32 {
+        self.total_travel_ms as f32 / 1000.0
+    }
+
     /// Convert travel time to simulation ticks (ceiling division so agents
     /// never arrive before the correct tick).
     pub fn travel_ticks(&self, tick_duration_secs: u32) -> u64 {
-        (self.total_travel_secs / tick_duration_secs as f32).ceil() as u64
+        self.total_travel_ms.div_ceil(tick_duration_secs as u64 * 1000)
+    }
+
+    /// Travel time as a [`TickDuration`], rounded up like
+    /// [`travel_ticks`][Self::travel_ticks].
+    pub fn travel_duration(&self, tick_duration_secs: u32) -> TickDuration {
+        TickDuration(self.travel_ticks(tick_duration_secs))
+    }
+
+    /// Total length of the route's edges in metres.
+    pub fn length_m(&self, network: &RoadNetwork) -> f32 {
+        self.edges.iter().map(|e| network.edge_length_m[e.index()]).sum()
     }
 
     /// `true` if the source and destination are the same node.
     pub fn is_trivial(&self) -> bool {
         self.edges.is_empty()
     }
+
+    /// Nodes visited in travel order, from the source to the destination.
+    /// Empty for a trivial route.
+    ///
+    /// `network` must be the one the route was computed on.
+    pub fn nodes(&self, network: &RoadNetwork) -> Vec<NodeId> {
+        let Some(first) = self.edges.first() else {
+            return Vec::new();
+        };
+        let mut nodes = Vec::with_capacity(self.edges.len() + 1);
+        nodes.push(network.edge_from[first.index()]);
+        nodes.extend(self.edges.iter().map(|e| network.edge_to[e.index()]));
+        nodes
+    }
+
+    /// Node positions along the route in travel order: the source, then
+    /// each edge's intermediate points (on a
+    /// [simplified](RoadNetwork::simplify) network) and end.  Empty for a
+    /// trivial route.
+    ///
+    /// `network` must be the one the route was computed on.
+    pub fn polyline(&self, network: &RoadNetwork) -> Vec<GeoPoint> {
+        let Some(first) = self.edges.first() else {
+            return Vec::new();
+        };
+        let start = network.node_pos[network.edge_from[first.index()].index()];
+        let mut points = vec![start];
+        for e in &self.edges {
+            if let Some(geometry) = &network.edge_geometry {
+                points.extend_from_slice(geometry.points(*e));
+            }
+            points.push(network.node_pos[network.edge_to[e.index()].index()]);
+        }
+        points
+    }
+
+    /// One [`RouteLeg`] per edge, in travel order, for inspecting why a
+    /// route goes the way it does.  Print the legs (they implement
+    /// `Display`) to read it turn by turn.
+    ///
+    /// Leg times are car times as the routers see them: `edge_travel_ms`
+    /// with the network's overlay applied.  `network` must be the one the
+    /// route was computed on.
+    pub fn describe(&self, network: &RoadNetwork) -> Vec<RouteLeg> {
+        let mut cumulative_ms = 0u64;
+        self.edges
+            .iter()
+            .map(|&edge| {
+                let ms = edge_cost_ms(network, edge, TransportMode::Car);
+                cumulative_ms += ms as u64;
+                RouteLeg {
+                    edge,
+                    from:            network.edge_from[edge.index()],
+                    to:              network.edge_to[edge.index()],
+                    length_m:        network.edge_length_m[edge.index()],
+                    travel_secs:     ms as f32 / 1000.0,
+                    cumulative_secs: cumulative_ms as f32 / 1000.0,
+                }
+            })
+            .collect()
+    }
+}
+
+/// One edge of a [`Route`], from [`Route::describe`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RouteLeg {
+    pub edge:            EdgeId,
+    pub from:            NodeId,
+    pub to:              NodeId,
+    pub length_m:        f32,
+    /// Car travel time of this edge.
+    pub travel_secs:     f32,
+    /// Car travel time from the start of the route to the end of this edge.
+    pub cumulative_secs: f32,
+}
+
+/// `edge 12: node 3 → node 7, 120 m, 9.0 s (45.0 s total)`.
+impl fmt::Display for RouteLeg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "edge {}: node {} → node {}, {:.0} m, {:.1} s ({:.1} s total)",
+            self.edge.0, self.from.0, self.to.0, self.length_m, self.travel_secs, self.cumulative_secs,
+        )
+    }
 }
 
 // ── Router trait ──────────────────────────────────────────────────────────────
@@ -69,6 +201,70 @@ pub trait Router: Send + Sync {
         to: NodeId,
         mode: TransportMode,
     ) -> Result<Route, SpatialError>;
+
+    /// Like [`route`](Self::route), for a trip leaving at `departure`.
+    ///
+    /// The default ignores the departure time.
+    fn route_at(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+        _departure: Tick,
+        _tick_duration_secs: u32,
+    ) -> Result<Route, SpatialError> {
+        self.route(network, from, to, mode)
+    }
+
+    /// Like [`route_at`](Self::route_at), for a trip made by `agent`.
+    ///
+    /// Called by `dt-mobility` for every trip, so routers with per-agent
+    /// costs (see [`EdgeCost`](crate::EdgeCost)) know who is travelling.
+    /// The default ignores the agent.
+    #[allow(clippy::too_many_arguments)]
+    fn route_for(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+        departure: Tick,
+        tick_duration_secs: u32,
+        _agent: AgentId,
+    ) -> Result<Route, SpatialError> {
+        self.route_at(network, from, to, mode, departure, tick_duration_secs)
+    }
+
+    /// `true` if [`route_for`](Self::route_for) may answer differently for
+    /// different agents, so [`CachedRouter`](crate::CachedRouter) keeps a
+    /// separate route per agent.  The default is `false`.
+    fn depends_on_agent(&self) -> bool {
+        false
+    }
+
+    /// A route from `from` to `to` that passes through each of `vias` in
+    /// order, made by joining one [`route`](Self::route) per leg.  The
+    /// total travel time is the sum of the legs'.
+    ///
+    /// Fails with the first leg's error if any leg has no route.
+    fn route_via(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        vias: &[NodeId],
+        to: NodeId,
+        mode: TransportMode,
+    ) -> Result<Route, SpatialError> {
+        let stops: Vec<NodeId> = std::iter::once(from).chain(vias.iter().copied()).chain([to]).collect();
+        let mut route = Route { edges: Vec::new(), total_travel_ms: 0, rides: Vec::new() };
+        for leg in stops.windows(2) {
+            let part = self.route(network, leg[0], leg[1], mode)?;
+            route.edges.extend(part.edges);
+            route.total_travel_ms += part.total_travel_ms;
+        }
+        Ok(route)
+    }
 }
 
 // ── DijkstraRouter ────────────────────────────────────────────────────────────
@@ -76,17 +272,22 @@ pub trait Router: Send + Sync {
 /// Standard Dijkstra's algorithm over the CSR road graph.
 ///
 /// Uses `edge_travel_ms` as cost for `Car` mode.  For other modes the cost is
-/// derived from `edge_length_m` divided by the mode's assumed speed:
+/// derived from `edge_length_m` divided by the mode's assumed speed in
+/// [`RoadNetwork::mode_speeds`]:
 ///
-/// | Mode    | Speed     |
-/// |---------|-----------|
-/// | Car     | from OSM  |
-/// | Walk    | 1.4 m/s   |
-/// | Bike    | 4.2 m/s   |
-/// | Transit | 8.3 m/s   |
+/// | Mode    | Default speed |
+/// |---------|---------------|
+/// | Car     | from OSM      |
+/// | Walk    | 1.4 m/s       |
+/// | Bike    | 4.2 m/s       |
+/// | Transit | 8.3 m/s       |
 ///
-/// Applications that need mode-specific road graphs (e.g. cycling paths,
-/// GTFS transit) should implement their own [`Router`].
+/// [`with_cost`](Self::with_cost) swaps in another [`EdgeCost`](crate::EdgeCost).
+/// Applications that need mode-specific road graphs (e.g. cycling paths)
+/// should implement their own [`Router`]; for timetabled transit see
+/// [`TransitRouter`](crate::TransitRouter).
+///
+/// [`route_k`](Self::route_k) returns alternatives to the shortest route.
 pub struct DijkstraRouter;
 
 impl Router for DijkstraRouter {
@@ -97,40 +298,151 @@ impl Router for DijkstraRouter {
         to: NodeId,
         mode: TransportMode,
     ) -> Result<Route, SpatialError> {
-        dijkstra(network, from, to, mode)
+        dijkstra(network, from, to, |edge, _| edge_cost_ms(network, edge, mode))
+    }
+}
+
+// ── BidirectionalRouter ───────────────────────────────────────────────────────
+
+/// Dijkstra run from both ends at once: forward over outgoing edges from the
+/// source, backward over [`in_edges`][RoadNetwork::in_edges] from the
+/// target, always advancing the side with the cheaper frontier.
+///
+/// Each search covers roughly a disc of half the route's cost, so it settles
+/// about half as many nodes as [`DijkstraRouter`] with no preprocessing.
+/// Costs per mode are the same, and so are route totals; for repeated car
+/// queries on one network [`ChRouter`](crate::ChRouter) is faster still.
+/// On a network built without reverse adjacency it runs plain Dijkstra.
+pub struct BidirectionalRouter;
+
+impl Router for BidirectionalRouter {
+    fn route(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+    ) -> Result<Route, SpatialError> {
+        if !network.has_reverse_adjacency() {
+            return DijkstraRouter.route(network, from, to, mode);
+        }
+        bidirectional(network, from, to, mode)
+    }
+}
+
+// ── TimeDependentRouter ───────────────────────────────────────────────────────
+
+/// Dijkstra over time-dependent car travel times.
+///
+/// Each edge costs [`RoadNetwork::travel_ms_at`] at the moment the search
+/// reaches its source, so a route planned for 08:00 avoids roads that the
+/// network's speed profiles slow down at 08:00 — and takes longer than the
+/// same trip at 03:00.  Walk, bike, and transit costs don't vary by time.
+///
+/// Tick 0 is taken to be midnight, as in the 24-hour schedule cycles;
+/// [`with_start_unix_secs`](Self::with_start_unix_secs) aligns it to the
+/// UTC time of day instead.  Plain [`route`](Router::route) calls depart at
+/// tick 0.
+///
+/// Factors change in hourly steps and the search never waits at a node, so
+/// just before an hour where a factor drops, the route found can be slower
+/// than waiting for the drop would have been.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimeDependentRouter {
+    /// Seconds after midnight at tick 0.
+    start_secs_of_day: u32,
+}
+
+impl TimeDependentRouter {
+    /// A router whose tick 0 is midnight.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take the time of day from the sim's wall clock: tick 0 is at
+    /// `start_unix_secs` (`SimConfig::start_unix_secs`), read as UTC.
+    pub fn with_start_unix_secs(mut self, start_unix_secs: i64) -> Self {
+        self.start_secs_of_day = start_unix_secs.rem_euclid(SECS_PER_DAY as i64) as u32;
+        self
+    }
+
+    /// Seconds after midnight at the start of `tick`.
+    pub fn secs_of_day(&self, tick: Tick, tick_duration_secs: u32) -> u32 {
+        time_of_day(self.start_secs_of_day, tick, tick_duration_secs)
+    }
+}
+
+impl Router for TimeDependentRouter {
+    fn route(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+    ) -> Result<Route, SpatialError> {
+        self.route_at(network, from, to, mode, Tick::ZERO, 1)
+    }
+
+    fn route_at(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+        departure: Tick,
+        tick_duration_secs: u32,
+    ) -> Result<Route, SpatialError> {
+        let depart = self.secs_of_day(departure, tick_duration_secs);
+        dijkstra(network, from, to, |edge, elapsed_ms| match mode {
+            TransportMode::Car | TransportMode::None => {
+                network.travel_ms_at(edge, (depart + elapsed_ms / 1000) % SECS_PER_DAY)
+            }
+            _ => edge_cost_ms(network, edge, mode),
+        })
     }
 }
 
 // ── Dijkstra internals ────────────────────────────────────────────────────────
 
-/// Edge cost in milliseconds for the given mode.
+/// Edge cost in milliseconds for the given mode, with the network's overlay
+/// applied.
 #[inline]
-fn edge_cost_ms(network: &RoadNetwork, edge: EdgeId, mode: TransportMode) -> u32 {
-    match mode {
+pub(crate) fn edge_cost_ms(network: &RoadNetwork, edge: EdgeId, mode: TransportMode) -> u32 {
+    let speeds = &network.mode_speeds;
+    let ms = match mode {
         TransportMode::Car | TransportMode::None => network.edge_travel_ms[edge.index()],
         TransportMode::Walk => {
-            (network.edge_length_m[edge.index()] / 1.4 * 1000.0) as u32
+            (network.edge_length_m[edge.index()] / speeds.walk_speed_mps * 1000.0) as u32
         }
         TransportMode::Bike => {
-            (network.edge_length_m[edge.index()] / 4.2 * 1000.0) as u32
+            (network.edge_length_m[edge.index()] / speeds.bike_speed_mps * 1000.0) as u32
         }
         TransportMode::Transit => {
-            // Approximation; real transit uses GTFS schedules in dt-mobility.
-            (network.edge_length_m[edge.index()] / 8.3 * 1000.0) as u32
+            // Approximation; `TransitRouter` routes over real timetables.
+            (network.edge_length_m[edge.index()] / speeds.transit_speed_mps * 1000.0) as u32
         }
         // Future modes added to TransportMode fall back to car cost.
         _ => network.edge_travel_ms[edge.index()],
+    };
+    match &network.overlay {
+        Some(overlay) => {
+            let car = !matches!(mode, TransportMode::Walk | TransportMode::Bike | TransportMode::Transit);
+            overlay.apply(edge, ms, car)
+        }
+        None => ms,
     }
 }
 
-fn dijkstra(
+/// Dijkstra with `cost(edge, elapsed_ms)` giving the cost of `edge` once
+/// `elapsed_ms` have passed since departure.
+pub(crate) fn dijkstra(
     network: &RoadNetwork,
     from: NodeId,
     to: NodeId,
-    mode: TransportMode,
+    cost_ms: impl Fn(EdgeId, u32) -> u32,
 ) -> Result<Route, SpatialError> {
     if from == to {
-        return Ok(Route { edges: vec![], total_travel_secs: 0.0 });
+        return Ok(Route { edges: vec![], total_travel_ms: 0, rides: Vec::new() });
     }
 
     let n = network.node_count();
@@ -158,7 +470,7 @@ fn dijkstra(
 
         for edge in network.out_edges(node) {
             let neighbor = network.edge_to[edge.index()];
-            let new_cost = cost.saturating_add(edge_cost_ms(network, edge, mode));
+            let new_cost = cost.saturating_add(cost_ms(edge, cost));
 
             if new_cost < dist[neighbor.index()] {
                 dist[neighbor.index()] = new_cost;
@@ -171,7 +483,82 @@ fn dijkstra(
     Err(SpatialError::NoRoute { from, to })
 }
 
-fn reconstruct(
+fn bidirectional(
+    network: &RoadNetwork,
+    from: NodeId,
+    to: NodeId,
+    mode: TransportMode,
+) -> Result<Route, SpatialError> {
+    if from == to {
+        return Ok(Route { edges: vec![], total_travel_ms: 0, rides: Vec::new() });
+    }
+
+    // Index 0 is the forward search from `from`, 1 the backward one from `to`.
+    let n = network.node_count();
+    let mut dist      = [vec![u32::MAX; n], vec![u32::MAX; n]];
+    let mut prev_edge = [vec![EdgeId::INVALID; n], vec![EdgeId::INVALID; n]];
+    let mut heaps: [BinaryHeap<Reverse<(u32, NodeId)>>; 2] = [BinaryHeap::new(), BinaryHeap::new()];
+    for (side, node) in [from, to].into_iter().enumerate() {
+        dist[side][node.index()] = 0;
+        heaps[side].push(Reverse((0, node)));
+    }
+
+    // Cheapest complete path seen so far and the node where its halves meet.
+    let mut best: Option<(u32, NodeId)> = None;
+    loop {
+        let tops = [0, 1].map(|side| heaps[side].peek().map(|Reverse((cost, _))| *cost));
+        let [Some(top_f), Some(top_b)] = tops else { break };
+        // Any path not yet seen costs at least the two frontiers combined.
+        if best.is_some_and(|(cost, _)| top_f.saturating_add(top_b) >= cost) {
+            break;
+        }
+        let side = if top_f <= top_b { 0 } else { 1 };
+        let Some(Reverse((cost, node))) = heaps[side].pop() else { break };
+        if cost > dist[side][node.index()] {
+            continue;
+        }
+
+        // Outgoing edges forward, incoming edges backward.
+        let row = [&network.node_out_start, &network.node_in_start][side];
+        for i in row[node.index()]..row[node.index() + 1] {
+            let (edge, neighbor) = match side {
+                0 => (EdgeId(i), network.edge_to[i as usize]),
+                _ => {
+                    let edge = network.in_edge_ids[i as usize];
+                    (edge, network.edge_from[edge.index()])
+                }
+            };
+            let new_cost = cost.saturating_add(edge_cost_ms(network, edge, mode));
+            if new_cost < dist[side][neighbor.index()] {
+                dist[side][neighbor.index()] = new_cost;
+                prev_edge[side][neighbor.index()] = edge;
+                heaps[side].push(Reverse((new_cost, neighbor)));
+
+                let other = dist[1 - side][neighbor.index()];
+                let total = new_cost.saturating_add(other);
+                if other != u32::MAX && best.is_none_or(|(cost, _)| total < cost) {
+                    best = Some((total, neighbor));
+                }
+            }
+        }
+    }
+
+    let (total_ms, meet) = best.ok_or(SpatialError::NoRoute { from, to })?;
+    let [forward, backward] = prev_edge;
+    let mut route = reconstruct(network, forward, meet, total_ms);
+    let mut cur = meet;
+    loop {
+        let e = backward[cur.index()];
+        if e == EdgeId::INVALID {
+            break;
+        }
+        route.edges.push(e);
+        cur = network.edge_to[e.index()];
+    }
+    Ok(route)
+}
+
+pub(crate) fn reconstruct(
     network: &RoadNetwork,
     prev_edge: Vec<EdgeId>,
     to: NodeId,
@@ -190,6 +577,7 @@ fn reconstruct(
     edges.reverse();
     Route {
         edges,
-        total_travel_secs: total_ms as f32 / 1000.0,
+        total_travel_ms: total_ms as u64,
+        rides:           Vec::new(),
     }
 }
@@ -0,0 +1,245 @@
+//! Degree-2 node contraction.
+//!
+//! OSM ways carry a node at every bend, so a loaded network has 5–10× more
+//! nodes than junctions.  [`RoadNetwork::simplify`] merges each chain of
+//! pass-through nodes into one edge per direction, summing lengths and
+//! travel times, which shrinks every search by the same factor.  The
+//! positions of the removed nodes are kept in an [`EdgeGeometry`] so
+//! [`Route::polyline`](crate::Route::polyline) still follows the road.
+//!
+//! A node is pass-through if it joins exactly two other nodes, either as a
+//! one-way link (one edge in, one edge out) or as a two-way road (an edge
+//! in from and out to each neighbour).  Junctions, dead ends, and nodes
+//! where a two-way road turns one-way are kept.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let mut network = dt_spatial::osm::load_from_pbf(pbf)?;
+//! network.retain_largest_scc();
+//! network.simplify();
+//! ```
+
+use dt_core::{EdgeId, GeoPoint, NodeId};
+
+use crate::attributes::EdgeAttributes;
+use crate::network::{select_nodes, RoadNetwork};
+
+// ── EdgeGeometry ──────────────────────────────────────────────────────────────
+
+/// Intermediate points of each edge, between its end nodes, in CSR form.
+///
+/// Set by [`RoadNetwork::simplify`]; a network without one has straight
+/// edges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeGeometry {
+    /// Points of edge `e` are `points[start[e] .. start[e+1]]`.
+    /// Length = `edge_count + 1`.
+    start:  Vec<u32>,
+    points: Vec<GeoPoint>,
+}
+
+impl EdgeGeometry {
+    /// Geometry for `edge_count` straight edges.
+    pub fn new(edge_count: usize) -> Self {
+        Self { start: vec![0; edge_count + 1], points: Vec::new() }
+    }
+
+    /// The points between `edge`'s end nodes, in travel order.
+    #[inline]
+    pub fn points(&self, edge: EdgeId) -> &[GeoPoint] {
+        &self.points[self.start[edge.index()] as usize..self.start[edge.index() + 1] as usize]
+    }
+
+    /// Number of edges the geometry covers.
+    pub fn edge_count(&self) -> usize {
+        self.start.len() - 1
+    }
+
+    /// Total number of intermediate points.
+    pub fn point_count(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Cover `count` more edges, all straight.
+    pub(crate) fn append_edges(&mut self, count: usize) {
+        let end = self.points.len() as u32;
+        self.start.resize(self.start.len() + count, end);
+    }
+
+    /// The geometry restricted to `edges` (old indices, in their new order).
+    pub(crate) fn select_edges(&self, edges: &[usize]) -> Self {
+        let mut out = Self { start: Vec::with_capacity(edges.len() + 1), points: Vec::new() };
+        out.start.push(0);
+        for &e in edges {
+            out.points.extend_from_slice(self.points(EdgeId(e as u32)));
+            out.start.push(out.points.len() as u32);
+        }
+        out
+    }
+}
+
+// ── Simplification ────────────────────────────────────────────────────────────
+
+/// A merged edge, before renumbering.
+struct Chain {
+    from:          NodeId,
+    to:            NodeId,
+    length_m:      f32,
+    travel_ms:     u32,
+    capacity_vph:  f32,
+    /// Seconds to traverse the chain at each part's free-flow speed.
+    freeflow_secs: f32,
+    points:        Vec<GeoPoint>,
+}
+
+impl Chain {
+    /// Free-flow speed over the whole chain.
+    fn freeflow_mps(&self) -> f32 {
+        if self.freeflow_secs > 0.0 { self.length_m / self.freeflow_secs } else { 0.0 }
+    }
+}
+
+impl RoadNetwork {
+    /// Contract every pass-through node (see the module docs), replacing
+    /// each chain of them with one edge per direction.
+    ///
+    /// Returns the node remapping: entry `i` is the new id of old node `i`,
+    /// or `NodeId::INVALID` if it was contracted.  A merged edge's length
+    /// and travel time are the sums over the chain (closed if any part is
+    /// closed), its capacity the chain's smallest, and its free-flow speed
+    /// the chain's average; the contracted nodes' positions become its
+    /// geometry.  A ring made only of pass-through nodes keeps its
+    /// lowest-numbered node.
+    ///
+    /// Speed profiles, the overlay, and edge attributes (OSM way ids and
+    /// street names included) are dropped, since a merged edge has no
+    /// single value for them: attach them afterwards.  Kept nodes keep
+    /// their OSM ids, and `mode_speeds` is kept.
+    /// `NodeId`s, `EdgeId`s, and the fingerprint all change.
+    pub fn simplify(&mut self) -> Vec<NodeId> {
+        let n = self.node_count();
+        let mut keep: Vec<bool> = {
+            let (in_start, in_ids) = self.reverse_csr();
+            let ins = |v: usize| &in_ids[in_start[v] as usize..in_start[v + 1] as usize];
+            (0..n).map(|v| !self.is_pass_through(NodeId(v as u32), ins(v))).collect()
+        };
+        let mut visited = vec![false; n];
+        let mut chains = Vec::new();
+        let mut walk_from = |net: &RoadNetwork, keep: &[bool], visited: &mut [bool], v: usize| {
+            for edge in net.out_edges(NodeId(v as u32)) {
+                chains.push(net.follow_chain(edge, keep, visited));
+            }
+        };
+        for v in 0..n {
+            if keep[v] {
+                walk_from(self, &keep, &mut visited, v);
+            }
+        }
+        for v in 0..n {
+            if !keep[v] && !visited[v] {
+                keep[v] = true;
+                walk_from(self, &keep, &mut visited, v);
+            }
+        }
+        if keep.iter().all(|&k| k) {
+            return (0..n as u32).map(NodeId).collect();
+        }
+
+        let mut remap = vec![NodeId::INVALID; n];
+        let mut nodes = Vec::new();
+        for v in (0..n).filter(|&v| keep[v]) {
+            remap[v] = NodeId(nodes.len() as u32);
+            nodes.push(self.node_pos[v]);
+        }
+        // Chains were gathered by source node, ring starts last.
+        chains.sort_by_key(|c| remap[c.from.index()]);
+
+        let mut geometry = EdgeGeometry { start: vec![0], points: Vec::new() };
+        for chain in &chains {
+            geometry.points.extend_from_slice(&chain.points);
+            geometry.start.push(geometry.points.len() as u32);
+        }
+        let edge_count = chains.len();
+        let osm_ids = self.node_osm_ids.take().map(|ids| select_nodes(&ids, &remap));
+        let mode_speeds = std::mem::take(&mut self.mode_speeds);
+        *self = RoadNetwork::from_sorted_edges(
+            nodes,
+            chains.iter().map(|c| remap[c.from.index()]).collect(),
+            chains.iter().map(|c| remap[c.to.index()]).collect(),
+            chains.iter().map(|c| c.length_m).collect(),
+            chains.iter().map(|c| c.travel_ms).collect(),
+            chains.iter().map(|c| c.capacity_vph).collect(),
+            chains.iter().map(Chain::freeflow_mps).collect(),
+            self.has_reverse_adjacency(),
+        );
+        self.edge_geometry = Some(geometry);
+        self.edge_attrs = EdgeAttributes::new(edge_count);
+        self.node_osm_ids = osm_ids;
+        self.mode_speeds = mode_speeds;
+        remap
+    }
+
+    /// The full shape of `edge`: its end nodes and any intermediate points.
+    pub fn edge_polyline(&self, edge: EdgeId) -> Vec<GeoPoint> {
+        let from = self.node_pos[self.edge_from[edge.index()].index()];
+        let to = self.node_pos[self.edge_to[edge.index()].index()];
+        let mid = self.edge_geometry.as_ref().map_or(&[][..], |g| g.points(edge));
+        std::iter::once(from).chain(mid.iter().copied()).chain(std::iter::once(to)).collect()
+    }
+
+    /// `true` if `v`, arrived at by `in_edges`, joins exactly two other
+    /// nodes as a one-way link or a two-way road.
+    fn is_pass_through(&self, v: NodeId, in_edges: &[EdgeId]) -> bool {
+        let outs: Vec<NodeId> = self.out_edges(v).map(|e| self.edge_to[e.index()]).collect();
+        let ins: Vec<NodeId> = in_edges.iter().map(|e| self.edge_from[e.index()]).collect();
+        match (ins.as_slice(), outs.as_slice()) {
+            ([a], [b]) => a != b && *a != v && *b != v,
+            ([a, b], [c, d]) => {
+                a != b && *a != v && *b != v && ((a, b) == (c, d) || (a, b) == (d, c))
+            }
+            _ => false,
+        }
+    }
+
+    /// Follow `edge` through pass-through nodes to the next kept node.
+    fn follow_chain(&self, edge: EdgeId, keep: &[bool], visited: &mut [bool]) -> Chain {
+        let from = self.edge_from[edge.index()];
+        let mut chain = Chain {
+            from,
+            to: from,
+            length_m: 0.0,
+            travel_ms: 0,
+            capacity_vph: f32::INFINITY,
+            freeflow_secs: 0.0,
+            points: Vec::new(),
+        };
+        let (mut prev, mut edge) = (from, edge);
+        loop {
+            let (to, length_m) = (self.edge_to[edge.index()], self.edge_length_m[edge.index()]);
+            chain.length_m += length_m;
+            chain.capacity_vph = chain.capacity_vph.min(self.edge_capacity_vph[edge.index()]);
+            if length_m > 0.0 {
+                // A zero free-flow speed makes the whole chain's zero.
+                chain.freeflow_secs += length_m / self.edge_freeflow_mps[edge.index()];
+            }
+            chain.travel_ms = match (chain.travel_ms, self.edge_travel_ms[edge.index()]) {
+                (u32::MAX, _) | (_, u32::MAX) => u32::MAX,
+                (a, b) => a.saturating_add(b).min(u32::MAX - 1),
+            };
+            if keep[to.index()] {
+                chain.to = to;
+                return chain;
+            }
+            visited[to.index()] = true;
+            chain.points.push(self.node_pos[to.index()]);
+            // One-way links have a single way out; two-way roads carry on
+            // away from where we came from.
+            edge = self
+                .out_edges(to)
+                .find(|e| self.out_degree(to) == 1 || self.edge_to[e.index()] != prev)
+                .expect("pass-through node has a way on");
+            prev = to;
+        }
+    }
+}
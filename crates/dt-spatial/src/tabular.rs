@@ -0,0 +1,200 @@
+//! CSV network loader.
+//!
+//! Many networks arrive already preprocessed into node and edge tables —
+//! exported from a GIS, a travel-demand model, or a notebook.
+//! [`load_from_csv`] reads two such files without needing the `osm` or
+//! `gis` features.
+//!
+//! # Usage
+//!
+//! ```ignore
+//! use std::path::Path;
+//! use dt_spatial::load_from_csv;
+//!
+//! let network = load_from_csv(Path::new("nodes.csv"), Path::new("edges.csv"))?;
+//! ```
+//!
+//! # File format
+//!
+//! Both files need a header row; columns are found by name
+//! (case-insensitively), in any order, and extra columns are ignored.
+//!
+//! ```text
+//! node_id,lat,lon                  from,to,length_m,speed_mps,oneway
+//! 101,30.6900,-88.0400             101,102,120.5,13.4,false
+//! 102,30.6910,-88.0400             102,103,,8.9,yes
+//! ```
+//!
+//! - `node_id` is any string, unique within the file.  Nodes get `NodeId`s
+//!   in file order, so row `i` (after the header) is `NodeId(i)`.
+//! - `from` and `to` refer to `node_id`s.  A blank `length_m` is the
+//!   straight-line distance between the nodes.
+//! - `speed_mps` must be positive.  Travel time is `length_m / speed_mps`.
+//! - `oneway` is optional: `true`, `yes`, `y`, or `1` adds only the
+//!   `from → to` edge; anything else, or no column, adds both directions.
+//! - An optional `capacity_vph` column sets each direction's capacity;
+//!   without it capacity follows from the speed as for
+//!   [`RoadNetworkBuilder::add_directed_edge`].
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use dt_core::{GeoPoint, NodeId};
+
+use crate::network::{RoadNetwork, RoadNetworkBuilder};
+use crate::SpatialError;
+
+// ── Public entry point ────────────────────────────────────────────────────────
+
+/// Load a road network from a node table and an edge table.  See the module
+/// docs for the columns.
+///
+/// # Errors
+///
+/// Returns [`SpatialError::Csv`] — naming the file and line — for a missing
+/// column, an unparseable or out-of-range value, a duplicate `node_id`, or
+/// an edge referring to an unknown node, and [`SpatialError::Io`] if a file
+/// cannot be opened.
+pub fn load_from_csv(nodes_path: &Path, edges_path: &Path) -> Result<RoadNetwork, SpatialError> {
+    let mut nodes = Table::open(nodes_path)?;
+    let [id_col, lat_col, lon_col] = [nodes.column("node_id")?, nodes.column("lat")?, nodes.column("lon")?];
+
+    let mut builder = RoadNetworkBuilder::new();
+    let mut ids: HashMap<String, NodeId> = HashMap::new();
+    while let Some(row) = nodes.next_row()? {
+        let id = row.get(id_col).trim();
+        let pos = GeoPoint::new(row.number(lat_col, "lat")? as f32, row.number(lon_col, "lon")? as f32);
+        if !(-90.0..=90.0).contains(&pos.lat) || !(-180.0..=180.0).contains(&pos.lon) {
+            return Err(row.error(format!("coordinates ({}, {}) out of range", pos.lat, pos.lon)));
+        }
+        if ids.insert(id.to_owned(), builder.add_node(pos)).is_some() {
+            return Err(row.error(format!("duplicate node_id {id:?}")));
+        }
+    }
+
+    let mut edges = Table::open(edges_path)?;
+    let [from_col, to_col, length_col, speed_col] = [
+        edges.column("from")?,
+        edges.column("to")?,
+        edges.column("length_m")?,
+        edges.column("speed_mps")?,
+    ];
+    let oneway_col = edges.optional_column("oneway");
+    let capacity_col = edges.optional_column("capacity_vph");
+
+    while let Some(row) = edges.next_row()? {
+        let node = |col: usize| {
+            let id = row.get(col).trim();
+            ids.get(id).copied().ok_or_else(|| row.error(format!("unknown node_id {id:?}")))
+        };
+        let (from, to) = (node(from_col)?, node(to_col)?);
+        let length_m = if row.get(length_col).trim().is_empty() {
+            builder.node_pos(from).approx_distance_m(builder.node_pos(to))
+        } else {
+            row.number(length_col, "length_m")? as f32
+        };
+        if length_m < 0.0 {
+            return Err(row.error(format!("negative length_m {length_m}")));
+        }
+        let speed_mps = row.number(speed_col, "speed_mps")? as f32;
+        if speed_mps <= 0.0 {
+            return Err(row.error(format!("speed_mps must be positive, got {speed_mps}")));
+        }
+        let oneway = oneway_col.is_some_and(|c| is_yes(row.get(c)));
+        let capacity_vph = match capacity_col {
+            Some(c) if !row.get(c).trim().is_empty() => Some(row.number(c, "capacity_vph")? as f32),
+            _ => None,
+        };
+
+        let travel_ms = (length_m / speed_mps * 1_000.0) as u32;
+        let mut add = |a: NodeId, b: NodeId| match capacity_vph {
+            Some(cap) => builder.add_directed_edge_with_capacity(a, b, length_m, travel_ms, cap),
+            None => builder.add_directed_edge(a, b, length_m, travel_ms),
+        };
+        add(from, to);
+        if !oneway {
+            add(to, from);
+        }
+    }
+
+    Ok(builder.build())
+}
+
+// ── Internals ─────────────────────────────────────────────────────────────────
+
+fn is_yes(value: &str) -> bool {
+    matches!(value.trim().to_ascii_lowercase().as_str(), "true" | "yes" | "y" | "1")
+}
+
+/// A CSV file being read row by row, with its header for column lookup.
+struct Table {
+    name:   String,
+    reader: csv::Reader<std::fs::File>,
+    header: csv::StringRecord,
+    record: csv::StringRecord,
+}
+
+impl Table {
+    fn open(path: &Path) -> Result<Self, SpatialError> {
+        let file = std::fs::File::open(path)?;
+        let name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+        let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(file);
+        let header = reader.headers().map_err(|e| csv_error(&name, e))?.clone();
+        Ok(Self { name, reader, header, record: csv::StringRecord::new() })
+    }
+
+    fn optional_column(&self, name: &str) -> Option<usize> {
+        self.header.iter().position(|h| h.trim().eq_ignore_ascii_case(name))
+    }
+
+    fn column(&self, name: &str) -> Result<usize, SpatialError> {
+        self.optional_column(name)
+            .ok_or_else(|| SpatialError::Csv(format!("{}: no column named {name:?}", self.name)))
+    }
+
+    fn next_row(&mut self) -> Result<Option<Row<'_>>, SpatialError> {
+        match self.reader.read_record(&mut self.record) {
+            Ok(false) => Ok(None),
+            Ok(true) => {
+                let line = self.record.position().map_or(0, |p| p.line());
+                Ok(Some(Row { name: &self.name, line, record: &self.record }))
+            }
+            Err(e) => Err(csv_error(&self.name, e)),
+        }
+    }
+}
+
+/// One data row, for reading fields and reporting errors against its line.
+struct Row<'a> {
+    name:   &'a str,
+    line:   u64,
+    record: &'a csv::StringRecord,
+}
+
+impl Row<'_> {
+    /// Field `col`, or `""` if the row is short.
+    fn get(&self, col: usize) -> &str {
+        self.record.get(col).unwrap_or("")
+    }
+
+    fn number(&self, col: usize, column: &str) -> Result<f64, SpatialError> {
+        let value = self.get(col).trim();
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| self.error(format!("{column}: expected a number, got {value:?}")))
+    }
+
+    fn error(&self, msg: String) -> SpatialError {
+        SpatialError::Csv(format!("{} line {}: {msg}", self.name, self.line))
+    }
+}
+
+fn csv_error(name: &str, err: csv::Error) -> SpatialError {
+    if matches!(err.kind(), csv::ErrorKind::Io(_)) {
+        let csv::ErrorKind::Io(e) = err.into_kind() else { unreachable!() };
+        return SpatialError::Io(e);
+    }
+    SpatialError::Csv(format!("{name}: {err}"))
+}
@@ -4,7 +4,7 @@
 
 #[cfg(test)]
 mod helpers {
-    use dt_core::GeoPoint;
+    use dt_core::{EdgeId, GeoPoint, NodeId};
     use crate::{RoadNetwork, RoadNetworkBuilder};
 
     /// Build a small grid network for testing.
@@ -41,6 +41,46 @@ mod helpers {
 
         (b.build(), [n0, n1, n2, n3, n4])
     }
+
+    /// 8×8 grid with pseudo-random travel times, some one-way streets, and
+    /// a parallel slow edge.
+    pub fn city() -> RoadNetwork {
+        let mut b = RoadNetworkBuilder::new();
+        let nodes: Vec<NodeId> = (0..64).map(|i| b.add_node(GeoPoint::new((i / 8) as f32, (i % 8) as f32))).collect();
+        let mut seed = 12_345u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            seed >> 16
+        };
+        for i in 0..64 {
+            for j in [i + 1, i + 8] {
+                if (j == i + 1 && i % 8 == 7) || j >= 64 {
+                    continue;
+                }
+                let ms = 5_000 + next() % 20_000;
+                if next() % 5 == 0 {
+                    b.add_directed_edge(nodes[i], nodes[j], 100.0, ms);
+                } else {
+                    b.add_road(nodes[i], nodes[j], 100.0, ms);
+                }
+            }
+        }
+        b.add_directed_edge(nodes[0], nodes[1], 100.0, 90_000);
+        b.build()
+    }
+
+    /// Assert that `edges` lead from `from` to `to` in `total_secs` of car time.
+    pub fn assert_path(net: &RoadNetwork, from: NodeId, to: NodeId, edges: &[EdgeId], total_secs: f32) {
+        let mut at = from;
+        let mut ms = 0u32;
+        for e in edges {
+            assert_eq!(net.edge_from[e.index()], at);
+            at = net.edge_to[e.index()];
+            ms += net.edge_travel_ms[e.index()];
+        }
+        assert_eq!(at, to);
+        assert_eq!(ms as f32 / 1000.0, total_secs);
+    }
 }
 
 // ── Builder & network structure ────────────────────────────────────────────────
@@ -111,6 +151,107 @@ mod builder {
         assert_eq!(net.out_degree(a), 1);
         assert_eq!(net.out_degree(c), 0); // no return edge
     }
+
+    #[test]
+    fn in_edges_mirror_out_edges() {
+        let net = super::helpers::city();
+        let mut incoming = 0;
+        for node in (0..net.node_count() as u32).map(dt_core::NodeId) {
+            let ins: Vec<_> = net.in_edges(node).collect();
+            assert_eq!(ins.len(), net.in_degree(node));
+            assert!(ins.windows(2).all(|w| w[0] < w[1]));
+            assert!(ins.iter().all(|e| net.edge_to[e.index()] == node));
+            incoming += ins.len();
+        }
+        assert_eq!(incoming, net.edge_count());
+
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint::new(0.0, 0.0));
+        let c = b.add_node(GeoPoint::new(0.0, 1.0));
+        b.add_directed_edge(a, c, 100.0, 10_000);
+        let net = b.build();
+        assert_eq!((net.in_degree(a), net.in_degree(c)), (0, 1));
+    }
+
+    #[test]
+    fn build_with_edge_ids_follows_reordering() {
+        let mut b = RoadNetworkBuilder::new();
+        let nodes: Vec<_> = (0..4).map(|i| b.add_node(GeoPoint::new(0.0, i as f32))).collect();
+        // Added out of source order, with parallel edges told apart by length.
+        let added = [(3, 0, 1.0), (0, 1, 2.0), (3, 0, 3.0), (1, 2, 4.0), (0, 1, 5.0), (2, 3, 6.0)];
+        for &(from, to, len) in &added {
+            b.add_directed_edge(nodes[from], nodes[to], len, 1_000);
+        }
+        let (net, edges) = b.build_with_edge_ids();
+        assert_eq!(edges.len(), added.len());
+        for (&(from, to, len), edge) in added.iter().zip(&edges) {
+            let e = edge.index();
+            assert_eq!((net.edge_from[e], net.edge_to[e], net.edge_length_m[e]), (nodes[from], nodes[to], len));
+        }
+    }
+
+    #[test]
+    fn reverse_adjacency_can_be_skipped() {
+        use dt_core::TransportMode;
+        use crate::{AltRouter, BidirectionalRouter, DijkstraRouter, Router};
+
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint::new(0.0, 0.0));
+        let c = b.add_node(GeoPoint::new(0.0, 1.0));
+        b.add_directed_edge(a, c, 100.0, 10_000);
+        b.reverse_adjacency(false);
+        let mut net = b.build();
+        assert!(!net.has_reverse_adjacency());
+        assert!(net.node_in_start.is_empty() && net.in_edge_ids.is_empty());
+        assert_eq!(net.in_edges(c).count(), 0);
+        assert_eq!(net.in_degree(c), 0);
+        net.set_reverse_adjacency(true);
+        assert_eq!(net.in_edges(c).collect::<Vec<_>>(), vec![dt_core::EdgeId(0)]);
+
+        // Backward searches still agree with plain Dijkstra without it.
+        let mut net = super::helpers::city();
+        net.set_reverse_adjacency(false);
+        let alt = AltRouter::build(&net, 4);
+        for (from, to) in [(0, 63), (63, 0), (7, 56), (20, 41)] {
+            let (from, to) = (dt_core::NodeId(from), dt_core::NodeId(to));
+            let expected = DijkstraRouter.route(&net, from, to, TransportMode::Car).unwrap().total_travel_ms;
+            let bi = BidirectionalRouter.route(&net, from, to, TransportMode::Car).unwrap();
+            assert_eq!(bi.total_travel_ms, expected);
+            assert_eq!(alt.route(&net, from, to, TransportMode::Car).unwrap().total_travel_ms, expected);
+        }
+        assert!(!net.has_reverse_adjacency());
+    }
+
+    #[test]
+    fn capacity_and_freeflow_speed() {
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint::new(0.0, 0.0));
+        let c = b.add_node(GeoPoint::new(0.0, 1.0));
+        b.add_directed_edge(a, c, 1_000.0, 40_000);               // 25 m/s = 90 km/h
+        b.add_road_with_capacity(c, a, 1_000.0, 100_000, 750.0);
+        b.add_directed_edge(a, a, 0.0, 0);
+        let net = b.build();
+        let edge = |from, to| (0..net.edge_count()).find(|&e| net.edge_from[e] == from && net.edge_to[e] == to).unwrap();
+        let (ac, ca, aa) = (edge(a, c), edge(c, a), edge(a, a));
+        assert_eq!([net.edge_freeflow_mps[ac], net.edge_freeflow_mps[ca], net.edge_freeflow_mps[aa]], [25.0, 10.0, 0.0]);
+        assert_eq!([net.edge_capacity_vph[ac], net.edge_capacity_vph[ca], net.edge_capacity_vph[aa]], [2000.0, 750.0, 600.0]);
+        assert_eq!(crate::network::capacity_for_speed(16.0), 900.0);
+    }
+
+    #[test]
+    fn fingerprint_tracks_content() {
+        let build = |travel_ms| {
+            let mut b = RoadNetworkBuilder::new();
+            let a = b.add_node(GeoPoint::new(0.0, 0.0));
+            let c = b.add_node(GeoPoint::new(0.0, 1.0));
+            b.add_road(a, c, 100.0, travel_ms);
+            b.build()
+        };
+        assert_eq!(build(10_000).fingerprint(), build(10_000).fingerprint());
+        assert_ne!(build(10_000).fingerprint(), build(10_001).fingerprint());
+        // Pinned so an accidental change to the digest is noticed.
+        assert_eq!(RoadNetworkBuilder::new().build().fingerprint(), 0xa8c7_f832_281a_39c5);
+    }
 }
 
 // ── Spatial snap ──────────────────────────────────────────────────────────────
@@ -153,6 +294,124 @@ mod snap {
         // n1 (dist=1) and n3 (dist=1) are equidistant in lat/lon — either is valid.
         assert!(nearest[1] == nodes[1] || nearest[1] == nodes[3]);
     }
+
+    #[test]
+    fn nearest_nodes_report_distance_and_respect_the_radius() {
+        let (net, [n0, n1, _, n3, _]) = super::helpers::grid_network();
+        let from = GeoPoint::new(0.0, 0.1);
+
+        let nearest = net.nearest_nodes(from, 3, f32::INFINITY);
+        assert_eq!(nearest.len(), 3);
+        assert_eq!(nearest[0].0, n0);
+        assert!((nearest[0].1 - from.distance_m(net.node_pos[n0.index()])).abs() < 1.0);
+        assert!(nearest.windows(2).all(|w| w[0].1 <= w[1].1));
+
+        // n1 is 0.9° (≈ 100 km) away, n3 ≈ 1°; a 20 km radius keeps only n0.
+        assert_eq!(net.nearest_nodes(from, 3, 20_000.0).iter().map(|p| p.0).collect::<Vec<_>>(), vec![n0]);
+        assert_eq!(net.nearest_nodes(from, 3, 1_000.0), vec![]);
+        assert!(!net.nearest_nodes(from, 5, 105_000.0).iter().any(|p| p.0 == n3));
+        assert_eq!(net.snap_to_node_within(GeoPoint::new(0.0, 0.95), 20_000.0).map(|p| p.0), Some(n1));
+        assert_eq!(net.snap_to_node_within(GeoPoint::new(0.5, 1.5), 20_000.0), None);
+        assert_eq!(RoadNetworkBuilder::new().build().snap_to_node_within(from, f32::INFINITY), None);
+    }
+
+    #[test]
+    fn snap_uses_ground_distance_at_high_latitude() {
+        // At 60° N a degree of longitude is half a degree of latitude, so
+        // `east` (0.009° lon ≈ 500 m) is nearer than `north` (0.006° lat ≈ 667 m).
+        let mut b = RoadNetworkBuilder::new();
+        let east = b.add_node(GeoPoint::new(60.0, 10.009));
+        let _north = b.add_node(GeoPoint::new(60.006, 10.0));
+        let net = b.build();
+        assert_eq!(net.snap_to_node(GeoPoint::new(60.0, 10.0)), Some(east));
+    }
+
+    #[test]
+    fn range_queries() {
+        let mut b = RoadNetworkBuilder::new();
+        // 5×5 lattice 0.001° apart at 60° N: ≈ 111 m north–south, ≈ 56 m east–west.
+        let nodes: Vec<_> = (0..25)
+            .map(|i| b.add_node(GeoPoint::new(60.0 + (i / 5) as f32 * 0.001, 10.0 + (i % 5) as f32 * 0.001)))
+            .collect();
+        let net = b.build();
+        let center = net.node_pos[nodes[12].index()];
+
+        // East–west neighbours (≈ 56 m) are within 100 m, north–south ones (≈ 111 m) aren't.
+        assert_eq!(net.nodes_within_radius(center, 100.0)[0], nodes[12]);
+        let mut near = net.nodes_within_radius(center, 100.0);
+        near.sort();
+        assert_eq!(near, vec![nodes[11], nodes[12], nodes[13]]);
+        assert_eq!(net.nodes_within_radius(center, 0.0), vec![nodes[12]]);
+        assert_eq!(net.nodes_within_radius(center, 10_000.0).len(), 25);
+
+        // Bounds are inclusive.
+        let lo = net.node_pos[nodes[6].index()];
+        let hi = net.node_pos[nodes[13].index()];
+        assert_eq!(net.nodes_in_bbox(lo, hi), vec![nodes[6], nodes[7], nodes[8], nodes[11], nodes[12], nodes[13]]);
+        assert!(net.nodes_in_bbox(GeoPoint::new(0.0, 0.0), GeoPoint::new(1.0, 1.0)).is_empty());
+    }
+
+    #[test]
+    fn snap_to_edge_projects_onto_segment() {
+        let (net, [n0, n1, ..]) = super::helpers::grid_network();
+        // Just north of the 0–1 road, 30 % of the way from n0: nearer that
+        // road than any node.
+        let (edge, t, snapped) = net.snap_to_edge(GeoPoint::new(0.1, 0.3)).unwrap();
+        assert_eq!((net.edge_from[edge.index()], net.edge_to[edge.index()]), (n0, n1));
+        assert!((t - 0.3).abs() < 1e-5);
+        assert!((snapped.lat).abs() < 1e-6 && (snapped.lon - 0.3).abs() < 1e-5);
+
+        // Past the end of every segment clamps to the nearest end node.
+        let (edge, t, snapped) = net.snap_to_edge(GeoPoint::new(-1.0, -1.0)).unwrap();
+        let end = if t == 0.0 { net.edge_from[edge.index()] } else { net.edge_to[edge.index()] };
+        assert_eq!(end, n0);
+        assert_eq!(snapped, GeoPoint::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn edges_in_bbox_returns_the_viewport() {
+        let (net, [n0, n1, _, n3, _]) = super::helpers::grid_network();
+        let ends = |edges: Vec<dt_core::EdgeId>| -> Vec<_> {
+            edges.iter().map(|e| (net.edge_from[e.index()], net.edge_to[e.index()])).collect()
+        };
+        // Across the middle of the 0–1 road only.
+        let view = net.edges_in_bbox(GeoPoint::new(-0.1, 0.4), GeoPoint::new(0.1, 0.6));
+        assert!(view.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(ends(view), [(n0, n1), (n1, n0)]);
+        // Along the 0–3 road, touching no node.
+        assert_eq!(ends(net.edges_in_bbox(GeoPoint::new(0.4, -0.1), GeoPoint::new(0.6, 0.1))), [(n0, n3), (n3, n0)]);
+        assert!(net.edges_in_bbox(GeoPoint::new(5.0, 5.0), GeoPoint::new(6.0, 6.0)).is_empty());
+        assert_eq!(net.edges_in_bbox(GeoPoint::new(-1.0, -1.0), GeoPoint::new(2.0, 3.0)).len(), net.edge_count());
+
+        // A simplified road bending north is found by its bend.
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint::new(0.0, 0.0));
+        let bend = b.add_node(GeoPoint::new(0.5, 1.0));
+        let c = b.add_node(GeoPoint::new(0.0, 2.0));
+        b.add_road(a, bend, 100.0, 10_000);
+        b.add_road(bend, c, 100.0, 10_000);
+        let mut net = b.build();
+        net.simplify();
+        assert_eq!(net.edges_in_bbox(GeoPoint::new(0.4, 0.9), GeoPoint::new(0.6, 1.1)).len(), 2);
+    }
+
+    #[test]
+    fn snap_to_edge_prefers_lower_edge_id_and_needs_edges() {
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint::new(0.0, 0.0));
+        let c = b.add_node(GeoPoint::new(0.0, 0.01));
+        assert!(b.build().snap_to_edge(GeoPoint::new(0.0, 0.0)).is_none());
+
+        let mut b = RoadNetworkBuilder::new();
+        b.add_node(GeoPoint::new(0.0, 0.0));
+        b.add_node(GeoPoint::new(0.0, 0.01));
+        b.add_road(c, a, 1_000.0, 60_000);
+        let net = b.build();
+        let (edge, t, _) = net.snap_to_edge(GeoPoint::new(0.001, 0.0025)).unwrap();
+        assert_eq!(edge.0, 0);
+        assert!((t - 0.25).abs() < 1e-4);
+        assert!(net.clone().snap_to_edge(GeoPoint::new(0.0, 0.0)).is_some());
+    }
 }
 
 // ── Dijkstra routing ──────────────────────────────────────────────────────────
@@ -167,7 +426,23 @@ mod routing {
         let (net, [n0, ..]) = super::helpers::grid_network();
         let r = DijkstraRouter.route(&net, n0, n0, TransportMode::Car).unwrap();
         assert!(r.is_trivial());
-        assert_eq!(r.total_travel_secs, 0.0);
+        assert_eq!(r.total_travel_secs(), 0.0);
+    }
+
+    #[test]
+    fn describe_lists_legs_with_running_time() {
+        let (mut net, [n0, n1, n2, _, n4]) = super::helpers::grid_network();
+        let e12 = net.out_edges(n1).find(|e| net.edge_to[e.index()] == n2).unwrap();
+        net.overlay_mut().scale(e12, 2.0);
+        let route = DijkstraRouter.route(&net, n0, n4, TransportMode::Car).unwrap();
+        let legs = route.describe(&net);
+
+        assert_eq!(legs.len(), 3);
+        assert_eq!((legs[0].from, legs[0].to, legs[2].to), (n0, n1, n4));
+        assert_eq!(legs.iter().map(|l| l.travel_secs).collect::<Vec<_>>(), [10.0, 20.0, 10.0]);
+        assert_eq!(legs[2].cumulative_secs, route.total_travel_secs());
+        assert_eq!(legs[1].to_string(), format!("edge {}: node 1 → node 2, 100 m, 20.0 s (30.0 s total)", e12.0));
+        assert!(DijkstraRouter.route(&net, n0, n0, TransportMode::Car).unwrap().describe(&net).is_empty());
     }
 
     #[test]
@@ -178,7 +453,7 @@ mod routing {
             .unwrap();
 
         // Shortest: n0→n1→n2→n4 = 30 s
-        assert_eq!(route.total_travel_secs, 30.0);
+        assert_eq!(route.total_travel_secs(), 30.0);
         assert_eq!(route.edges.len(), 3);
 
         // Verify edge sequence connectivity
@@ -188,6 +463,58 @@ mod routing {
         assert_eq!(net.edge_to[route.edges[2].index()], n4);
     }
 
+    #[test]
+    fn polyline_follows_nodes() {
+        let (net, [n0, n1, n2, _, n4]) = super::helpers::grid_network();
+        let route = DijkstraRouter.route(&net, n0, n4, TransportMode::Car).unwrap();
+        let expected: Vec<_> = [n0, n1, n2, n4].iter().map(|n| net.node_pos[n.index()]).collect();
+        assert_eq!(route.polyline(&net), expected);
+
+        let trivial = DijkstraRouter.route(&net, n2, n2, TransportMode::Car).unwrap();
+        assert!(trivial.polyline(&net).is_empty());
+    }
+
+    #[test]
+    fn nodes_follow_the_route() {
+        let (net, [n0, n1, n2, _, n4]) = super::helpers::grid_network();
+        let route = DijkstraRouter.route(&net, n0, n4, TransportMode::Car).unwrap();
+        assert_eq!(route.nodes(&net), vec![n0, n1, n2, n4]);
+        assert!(DijkstraRouter.route(&net, n2, n2, TransportMode::Car).unwrap().nodes(&net).is_empty());
+    }
+
+    #[test]
+    fn route_via_stitches_legs_through_each_stop() {
+        let (net, [n0, n1, n2, n3, n4]) = super::helpers::grid_network();
+        // n3 is quickest reached past n4 (40 s), then back to n4 (10 s).
+        let route = DijkstraRouter.route_via(&net, n0, &[n3], n4, TransportMode::Car).unwrap();
+        assert_eq!(route.nodes(&net), vec![n0, n1, n2, n4, n3, n4]);
+        assert_eq!(route.total_travel_secs(), 50.0);
+
+        // Out to n4 and back to n1; a repeated stop adds nothing.
+        let errands = DijkstraRouter.route_via(&net, n0, &[n4, n4], n1, TransportMode::Car).unwrap();
+        assert_eq!(errands.nodes(&net), vec![n0, n1, n2, n4, n2, n1]);
+        assert_eq!(errands.total_travel_secs(), 50.0);
+
+        let direct = DijkstraRouter.route_via(&net, n0, &[], n4, TransportMode::Car).unwrap();
+        assert_eq!(direct.edges, DijkstraRouter.route(&net, n0, n4, TransportMode::Car).unwrap().edges);
+    }
+
+    #[test]
+    fn route_via_fails_on_an_unreachable_stop() {
+        use dt_core::GeoPoint;
+        use crate::RoadNetworkBuilder;
+
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint::new(0.0, 0.0));
+        let c = b.add_node(GeoPoint::new(0.0, 1.0));
+        let island = b.add_node(GeoPoint::new(1.0, 0.0));
+        b.add_road(a, c, 100.0, 10_000);
+        let net = b.build();
+
+        let result = DijkstraRouter.route_via(&net, a, &[island], c, TransportMode::Car);
+        assert!(matches!(result, Err(SpatialError::NoRoute { from, to }) if from == a && to == island));
+    }
+
     #[test]
     fn no_route_disconnected() {
         use dt_core::GeoPoint;
@@ -200,6 +527,11 @@ mod routing {
         let net = b.build();
         let result = DijkstraRouter.route(&net, a, c, TransportMode::Car);
         assert!(matches!(result, Err(SpatialError::NoRoute { .. })));
+
+        // Applications bubble it up as a categorised `DtError`.
+        let err = dt_core::DtError::from(result.unwrap_err());
+        assert_eq!(err.category(), dt_core::ErrorCategory::Spatial);
+        assert!(matches!(err.downcast_ref(), Some(SpatialError::NoRoute { .. })));
     }
 
     #[test]
@@ -240,6 +572,1913 @@ mod routing {
         let walk = DijkstraRouter.route(&net, n0, n4, TransportMode::Walk).unwrap();
         // Walk uses length/speed, car uses pre-computed OSM times.
         // Both should find a valid route; walk should take longer.
-        assert!(walk.total_travel_secs > car.total_travel_secs);
+        assert!(walk.total_travel_secs() > car.total_travel_secs());
+    }
+}
+
+// ── Deterministic routing ─────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod determinism {
+    use dt_core::{GeoPoint, NodeId, TransportMode};
+    use crate::{
+        AltRouter, BidirectionalRouter, ChRouter, DijkstraRouter, Landmarks, RoadNetwork, RoadNetworkBuilder, Router,
+    };
+
+    /// 10×10 lattice of identical 1 km, 60 s roads: nearly every query has
+    /// many equal-cost paths, so only the tie-breaking rules pick one.
+    fn lattice() -> RoadNetwork {
+        let mut b = RoadNetworkBuilder::new();
+        let nodes: Vec<NodeId> = (0..100).map(|i| b.add_node(GeoPoint::new((i / 10) as f32, (i % 10) as f32))).collect();
+        for i in 0..100 {
+            if i % 10 != 9 {
+                b.add_road(nodes[i], nodes[i + 1], 1_000.0, 60_000);
+            }
+            if i < 90 {
+                b.add_road(nodes[i], nodes[i + 10], 1_000.0, 60_000);
+            }
+        }
+        b.build()
+    }
+
+    /// FNV-1a over every route's edges and integer travel time, all pairs.
+    fn digest(router: &dyn Router, net: &RoadNetwork, mode: TransportMode) -> u64 {
+        let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut feed = |word: u64| {
+            for byte in word.to_le_bytes() {
+                h ^= byte as u64;
+                h = h.wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        };
+        for from in 0..net.node_count() as u32 {
+            for to in 0..net.node_count() as u32 {
+                match router.route(net, NodeId(from), NodeId(to), mode) {
+                    Ok(route) => {
+                        feed(route.total_travel_ms);
+                        route.edges.iter().for_each(|e| feed(e.0 as u64));
+                    }
+                    Err(_) => feed(u64::MAX),
+                }
+            }
+        }
+        h
+    }
+
+    #[test]
+    fn equal_cost_ties_go_to_the_lower_node() {
+        // Two equal 20 s paths a → d, via b or via c.
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint::new(0.0, 0.0));
+        let via_b = b.add_node(GeoPoint::new(0.0, 1.0));
+        let via_c = b.add_node(GeoPoint::new(1.0, 0.0));
+        let d = b.add_node(GeoPoint::new(1.0, 1.0));
+        b.add_directed_edge(a, via_c, 100.0, 10_000);
+        b.add_directed_edge(a, via_b, 100.0, 10_000);
+        b.add_directed_edge(via_c, d, 100.0, 10_000);
+        b.add_directed_edge(via_b, d, 100.0, 10_000);
+        let net = b.build();
+
+        let route = DijkstraRouter.route(&net, a, d, TransportMode::Car).unwrap();
+        assert_eq!(route.nodes(&net), [a, via_b, d]);
+        assert_eq!(route.total_travel_ms, 20_000);
+    }
+
+    #[test]
+    fn travel_ticks_are_exact_for_long_trips() {
+        // 20 000.001 s rounds to 20 000.0 in f32; the integer total does not.
+        let route = crate::Route { edges: vec![], total_travel_ms: 20_000_001, rides: Vec::new() };
+        assert_eq!(route.travel_ticks(10), 2_001);
+        assert_eq!(route.travel_ticks(1), 20_001);
+    }
+
+    /// Route digests pinned across runs, builds, and platforms.  A change
+    /// here means route choice changed: update the constants only for an
+    /// intended change to costs or tie-breaking.
+    #[test]
+    fn route_digests_are_stable() {
+        let lattice = lattice();
+        let city = super::helpers::city();
+        let digests = [
+            digest(&DijkstraRouter, &lattice, TransportMode::Car),
+            digest(&DijkstraRouter, &city, TransportMode::Car),
+            digest(&DijkstraRouter, &city, TransportMode::Walk),
+            digest(&BidirectionalRouter, &lattice, TransportMode::Car),
+            digest(&ChRouter::build(&lattice), &lattice, TransportMode::Car),
+            digest(&AltRouter::new(Landmarks::build(&city, 4)), &city, TransportMode::Car),
+        ];
+        // Each router is repeatable within a run, too.
+        assert_eq!(digests[0], digest(&DijkstraRouter, &lattice, TransportMode::Car));
+        assert_eq!(
+            digests,
+            [
+                0xd7ac_2537_d3cf_1ae6,
+                0x5952_a1b0_54fa_eef6,
+                0xa29f_ea4d_7cb8_4fa3,
+                0x9842_aa60_a466_7841,
+                0x48f0_291e_11c0_1d7b,
+                // ALT finds the same routes as Dijkstra.
+                0x5952_a1b0_54fa_eef6,
+            ]
+        );
+    }
+}
+
+// ── Custom edge costs ─────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod edge_cost {
+    use dt_core::{AgentId, EdgeId, GeoPoint, Tick, TransportMode};
+    use crate::{DijkstraRouter, DistanceCost, EdgeCost, ModeSpeedCost, RoadNetwork, RoadNetworkBuilder, Router, SpatialError};
+
+    #[test]
+    fn tolls_change_the_path_not_the_time() {
+        let (net, [n0, n1, n2, n3, n4]) = super::helpers::grid_network();
+        let e12 = net.out_edges(n1).find(|e| net.edge_to[e.index()] == n2).unwrap();
+        // A 60 s toll on 1→2 makes the 60 s road through n3 cheaper.
+        let toll = move |net: &RoadNetwork, edge: EdgeId, mode, agent: Option<AgentId>| {
+            let ms = ModeSpeedCost.cost_ms(net, edge, mode, agent);
+            if edge == e12 { ms + 60_000 } else { ms }
+        };
+        let router = DijkstraRouter::with_cost(toll);
+        let route = router.route(&net, n0, n4, TransportMode::Car).unwrap();
+        super::helpers::assert_path(&net, n0, n4, &route.edges, 60.0);
+        assert!(net.in_edges(n3).any(|e| route.edges.contains(&e)));
+        assert_eq!(route.total_travel_secs(), 60.0);
+
+        // The built-in cost reproduces DijkstraRouter.
+        let plain = DijkstraRouter::with_cost(ModeSpeedCost).route(&net, n0, n4, TransportMode::Walk).unwrap();
+        assert_eq!(plain.edges, DijkstraRouter.route(&net, n0, n4, TransportMode::Walk).unwrap().edges);
+    }
+
+    #[test]
+    fn agent_hints_and_impassable_edges() {
+        let (net, [n0, n1, n2, _, n4]) = super::helpers::grid_network();
+        let e12 = net.out_edges(n1).find(|e| net.edge_to[e.index()] == n2).unwrap();
+        // Agent 7 won't use 1→2 at all.
+        let router = DijkstraRouter::with_cost(move |net: &RoadNetwork, edge: EdgeId, mode, agent: Option<AgentId>| {
+            if edge == e12 && agent == Some(AgentId(7)) { u32::MAX } else { ModeSpeedCost.cost_ms(net, edge, mode, agent) }
+        });
+        let secs = |agent| router.route_for(&net, n0, n4, TransportMode::Car, Tick(0), 3600, AgentId(agent)).unwrap().total_travel_secs();
+        assert_eq!(secs(7), 60.0);
+        assert_eq!(secs(8), 30.0);
+        assert_eq!(router.route(&net, n0, n4, TransportMode::Car).unwrap().total_travel_secs(), 30.0);
+
+        let closed = DijkstraRouter::with_cost(|_: &RoadNetwork, _: EdgeId, _, _: Option<AgentId>| u32::MAX);
+        assert!(matches!(closed.route(&net, n0, n4, TransportMode::Car), Err(SpatialError::NoRoute { .. })));
+    }
+
+    #[test]
+    fn distance_objective_takes_the_shorter_slower_road() {
+        let mut b = RoadNetworkBuilder::new();
+        let [a, m, c] = [0.0, 0.01, 0.02].map(|lon| b.add_node(GeoPoint::new(0.0, lon)));
+        b.add_directed_edge(a, c, 1_000.0, 100_000); // short, slow
+        b.add_directed_edge(a, m, 600.0, 20_000); // long, fast
+        b.add_directed_edge(m, c, 600.0, 20_000);
+        let mut net = b.build();
+
+        let fastest = DijkstraRouter.route(&net, a, c, TransportMode::Car).unwrap();
+        assert_eq!((fastest.edges.len(), fastest.length_m(&net)), (2, 1_200.0));
+        let shortest = DijkstraRouter::with_cost(DistanceCost).route(&net, a, c, TransportMode::Car).unwrap();
+        assert_eq!((shortest.edges.len(), shortest.length_m(&net)), (1, 1_000.0));
+        // The route still reports its travel time, not its cost.
+        assert_eq!(shortest.total_travel_secs(), 100.0);
+
+        // Closed edges are not shortcuts.
+        net.overlay_mut().close(shortest.edges[0]);
+        let detour = DijkstraRouter::with_cost(DistanceCost).route(&net, a, c, TransportMode::Car).unwrap();
+        assert_eq!(detour.length_m(&net), 1_200.0);
+    }
+}
+
+// ── Pareto routing ────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod pareto {
+    use dt_core::{AgentId, EdgeId, GeoPoint, NodeId, TransportMode};
+    use crate::{ParetoRouter, RoadNetwork, RoadNetworkBuilder, SpatialError};
+
+    #[derive(Clone, Copy, Default)]
+    struct TollCents(u32);
+
+    /// Three roads a → c: a tolled bypass (2 km, 80 s, $2), through town
+    /// (1.2 km, 120 s), and a detour via b that is worse on time and
+    /// distance than the bypass (1.5 + 1.5 km, 60 + 60 s).
+    fn corridors() -> (RoadNetwork, NodeId, NodeId) {
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint::new(30.69, -88.04));
+        let c = b.add_node(GeoPoint::new(30.70, -88.03));
+        let via = b.add_node(GeoPoint::new(30.70, -88.05));
+        b.add_directed_edge(a, c, 2_000.0, 80_000);
+        b.add_directed_edge(a, c, 1_200.0, 120_000);
+        b.add_directed_edge(a, via, 1_500.0, 60_000);
+        b.add_directed_edge(via, c, 1_500.0, 60_000);
+        let mut net = b.build();
+        let tolls = (0..net.edge_count()).map(|e| TollCents(if e == 0 { 200 } else { 0 })).collect();
+        net.edge_attrs.insert(tolls);
+        (net, a, c)
+    }
+
+    #[test]
+    fn time_and_distance_frontier_drops_dominated_routes() {
+        let (net, a, c) = corridors();
+        let frontier = ParetoRouter::time_distance().frontier(&net, a, c, TransportMode::Car).unwrap();
+
+        assert_eq!(frontier.iter().map(|p| p.costs).collect::<Vec<_>>(), [[80_000, 200_000], [120_000, 120_000]]);
+        assert_eq!(frontier[0].route.edges, [EdgeId(0)]);
+        assert_eq!(frontier[1].route.total_travel_secs(), 120.0);
+        assert_eq!(frontier[1].route.length_m(&net), 1_200.0);
+    }
+
+    #[test]
+    fn time_and_toll_frontier() {
+        let (net, a, c) = corridors();
+        let toll = |net: &RoadNetwork, e: EdgeId, _: TransportMode, _: Option<AgentId>| {
+            net.edge_attrs.get::<TollCents>().unwrap()[e.index()].0
+        };
+        let router = ParetoRouter::new(crate::ModeSpeedCost, toll);
+        let frontier = router.frontier(&net, a, c, TransportMode::Car).unwrap();
+
+        // Untolled, the 120 s roads tie; one is kept.
+        assert_eq!(frontier.iter().map(|p| p.costs).collect::<Vec<_>>(), [[80_000, 200], [120_000, 0]]);
+
+        // Close the bypass: only the free routes are left.
+        let mut net = net;
+        net.overlay_mut().close(EdgeId(0));
+        let frontier = router.frontier(&net, a, c, TransportMode::Car).unwrap();
+        assert_eq!(frontier.len(), 1);
+        assert_eq!(frontier[0].costs, [120_000, 0]);
+    }
+
+    #[test]
+    fn trivial_and_unreachable() {
+        let (net, a, c) = corridors();
+        let router = ParetoRouter::time_distance();
+        let same = router.frontier(&net, c, c, TransportMode::Car).unwrap();
+        assert!(same.len() == 1 && same[0].route.is_trivial());
+        assert!(matches!(router.frontier(&net, c, a, TransportMode::Car), Err(SpatialError::NoRoute { .. })));
+    }
+}
+
+// ── Bidirectional routing ─────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod bidirectional {
+    use dt_core::{GeoPoint, NodeId, TransportMode};
+    use crate::{BidirectionalRouter, DijkstraRouter, RoadNetworkBuilder, Router, SpatialError};
+
+    #[test]
+    fn matches_dijkstra_on_every_pair() {
+        let net = super::helpers::city();
+        for mode in [TransportMode::Car, TransportMode::Walk] {
+            for (from, to) in (0..64).flat_map(|a| (0..64).map(move |b| (NodeId(a), NodeId(b)))) {
+                let expected = DijkstraRouter.route(&net, from, to, mode);
+                match (expected, BidirectionalRouter.route(&net, from, to, mode)) {
+                    (Ok(expected), Ok(route)) => {
+                        assert_eq!(route.total_travel_secs(), expected.total_travel_secs(), "{from} → {to}");
+                        if mode == TransportMode::Car {
+                            super::helpers::assert_path(&net, from, to, &route.edges, route.total_travel_secs());
+                        }
+                    }
+                    (Err(_), Err(SpatialError::NoRoute { .. })) => {}
+                    (expected, route) => panic!("{from} → {to}: {:?} vs {:?}", expected.is_ok(), route.is_ok()),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn trivial_one_way_and_disconnected() {
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint::new(0.0, 0.0));
+        let c = b.add_node(GeoPoint::new(0.0, 1.0));
+        let d = b.add_node(GeoPoint::new(0.0, 2.0));
+        b.add_directed_edge(a, c, 100.0, 10_000);
+        let net = b.build();
+
+        assert!(BidirectionalRouter.route(&net, a, a, TransportMode::Car).unwrap().is_trivial());
+        let route = BidirectionalRouter.route(&net, a, c, TransportMode::Car).unwrap();
+        assert_eq!((route.edges.len(), route.total_travel_secs()), (1, 10.0));
+        assert!(matches!(BidirectionalRouter.route(&net, c, a, TransportMode::Car), Err(SpatialError::NoRoute { .. })));
+        assert!(BidirectionalRouter.route(&net, a, d, TransportMode::Car).is_err());
+    }
+}
+
+// ── Contraction hierarchies ───────────────────────────────────────────────────
+
+#[cfg(test)]
+mod ch {
+    use dt_core::{GeoPoint, NodeId, TransportMode};
+    use crate::{ChRouter, ContractionHierarchy, DijkstraRouter, RoadNetwork, RoadNetworkBuilder, Router, SpatialError};
+
+    #[test]
+    fn matches_dijkstra_on_every_pair() {
+        let net = super::helpers::city();
+        let router = ChRouter::build(&net);
+        assert!(router.hierarchy().matches(&net));
+        for from in 0..64 {
+            for to in 0..64 {
+                let (from, to) = (NodeId(from), NodeId(to));
+                let expected = DijkstraRouter.route(&net, from, to, TransportMode::Car);
+                let route = router.route(&net, from, to, TransportMode::Car);
+                match (expected, route) {
+                    (Ok(expected), Ok(route)) => {
+                        assert_eq!(route.total_travel_secs(), expected.total_travel_secs(), "{from} → {to}");
+                        super::helpers::assert_path(&net, from, to, &route.edges, route.total_travel_secs());
+                    }
+                    (Err(_), Err(SpatialError::NoRoute { .. })) => {}
+                    (expected, route) => panic!("{from} → {to}: {:?} vs {:?}", expected.is_ok(), route.is_ok()),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn one_way_and_closed_edges() {
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint::new(0.0, 0.0));
+        let c = b.add_node(GeoPoint::new(0.0, 1.0));
+        let d = b.add_node(GeoPoint::new(0.0, 2.0));
+        b.add_directed_edge(a, c, 100.0, 10_000);
+        b.add_road(c, d, 100.0, u32::MAX); // closed
+        let net = b.build();
+        let router = ChRouter::build(&net);
+
+        assert_eq!(router.route(&net, a, c, TransportMode::Car).unwrap().edges.len(), 1);
+        assert!(router.route(&net, a, a, TransportMode::Car).unwrap().is_trivial());
+        assert!(matches!(router.route(&net, c, a, TransportMode::Car), Err(SpatialError::NoRoute { .. })));
+        assert!(router.route(&net, a, d, TransportMode::Car).is_err());
+    }
+
+    #[test]
+    fn other_modes_and_networks_fall_back_to_dijkstra() {
+        let net = super::helpers::city();
+        let router = ChRouter::build(&RoadNetwork::empty());
+        let (from, to) = (NodeId(0), NodeId(63));
+        let secs = |router: &dyn Router, mode| router.route(&net, from, to, mode).unwrap().total_travel_secs();
+        assert_eq!(secs(&router, TransportMode::Car), secs(&DijkstraRouter, TransportMode::Car));
+
+        let router = ChRouter::build(&net);
+        assert_eq!(secs(&router, TransportMode::Walk), secs(&DijkstraRouter, TransportMode::Walk));
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let net = super::helpers::city();
+        let built = ContractionHierarchy::build(&net);
+        assert!(built.shortcut_count() > 0);
+        let mut bytes = Vec::new();
+        built.write_to(&mut bytes).unwrap();
+
+        let loaded = ContractionHierarchy::read_from(&mut bytes.as_slice(), &net).unwrap();
+        assert_eq!(loaded.fingerprint(), net.fingerprint());
+        let (a, b) = (ChRouter::new(built), ChRouter::new(loaded));
+        for to in 0..64 {
+            let ra = a.route(&net, NodeId(5), NodeId(to), TransportMode::Car).unwrap();
+            let rb = b.route(&net, NodeId(5), NodeId(to), TransportMode::Car).unwrap();
+            assert_eq!(ra.edges, rb.edges);
+        }
+
+        // A different network, a corrupt body, and a truncated file are rejected.
+        let other = RoadNetwork::empty();
+        let rejected = |bytes: &[u8], net| {
+            matches!(ContractionHierarchy::read_from(&mut &bytes[..], net), Err(SpatialError::Hierarchy(_)))
+        };
+        assert!(rejected(&bytes, &other));
+        let mut corrupt = bytes.clone();
+        let last = corrupt.len() - 4;
+        corrupt[last..].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(rejected(&corrupt, &net));
+        assert!(ContractionHierarchy::read_from(&mut &bytes[..bytes.len() - 1], &net).is_err());
+        assert!(rejected(b"nope", &net));
+    }
+}
+
+// ── ALT (landmarks) ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod alt {
+    use dt_core::{NodeId, TransportMode};
+    use crate::{AltRouter, DijkstraRouter, Landmarks, RoadNetwork, Router, SpatialError};
+
+    #[test]
+    fn matches_dijkstra_on_every_pair() {
+        let net = super::helpers::city();
+        let router = AltRouter::build(&net, 4);
+        assert_eq!(router.landmarks().len(), 4);
+        assert!(router.landmarks().matches(&net));
+        let mut picked: Vec<_> = router.landmarks().nodes().collect();
+        picked.sort();
+        picked.dedup();
+        assert_eq!(picked.len(), 4);
+        for from in 0..64 {
+            for to in 0..64 {
+                let (from, to) = (NodeId(from), NodeId(to));
+                let expected = DijkstraRouter.route(&net, from, to, TransportMode::Car);
+                let route = router.route(&net, from, to, TransportMode::Car);
+                match (expected, route) {
+                    (Ok(expected), Ok(route)) => {
+                        assert_eq!(route.total_travel_secs(), expected.total_travel_secs(), "{from} → {to}");
+                        super::helpers::assert_path(&net, from, to, &route.edges, route.total_travel_secs());
+                    }
+                    (Err(_), Err(SpatialError::NoRoute { .. })) => {}
+                    (expected, route) => panic!("{from} → {to}: {:?} vs {:?}", expected.is_ok(), route.is_ok()),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn falls_back_to_dijkstra_and_handles_tiny_networks() {
+        let net = super::helpers::city();
+        let (from, to) = (NodeId(0), NodeId(63));
+        let secs = |router: &dyn Router, mode| router.route(&net, from, to, mode).unwrap().total_travel_secs();
+        let stale = AltRouter::build(&RoadNetwork::empty(), 8);
+        assert!(stale.landmarks().is_empty());
+        assert_eq!(secs(&stale, TransportMode::Car), secs(&DijkstraRouter, TransportMode::Car));
+        let router = AltRouter::build(&net, 2);
+        assert_eq!(secs(&router, TransportMode::Walk), secs(&DijkstraRouter, TransportMode::Walk));
+
+        let (small, [n0, .., n4]) = super::helpers::grid_network();
+        let router = AltRouter::build(&small, 16);
+        assert_eq!(router.landmarks().len(), 5);
+        assert_eq!(router.route(&small, n0, n4, TransportMode::Car).unwrap().total_travel_secs(), 30.0);
+    }
+
+    #[test]
+    fn round_trips_through_bytes() {
+        let net = super::helpers::city();
+        let built = Landmarks::build(&net, 3);
+        let mut bytes = Vec::new();
+        built.write_to(&mut bytes).unwrap();
+
+        let loaded = Landmarks::read_from(&mut bytes.as_slice(), &net).unwrap();
+        assert_eq!(loaded.nodes().collect::<Vec<_>>(), built.nodes().collect::<Vec<_>>());
+        let (a, b) = (AltRouter::new(built), AltRouter::new(loaded));
+        for to in 0..64 {
+            let ra = a.route(&net, NodeId(9), NodeId(to), TransportMode::Car).unwrap();
+            let rb = b.route(&net, NodeId(9), NodeId(to), TransportMode::Car).unwrap();
+            assert_eq!(ra.edges, rb.edges);
+        }
+
+        let other = RoadNetwork::empty();
+        let rejected = |bytes: &[u8], net| matches!(Landmarks::read_from(&mut &bytes[..], net), Err(SpatialError::Landmarks(_)));
+        assert!(rejected(&bytes, &other));
+        assert!(rejected(&bytes[..bytes.len() - 1], &net));
+        assert!(rejected(b"nope", &net));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("city.alt");
+        assert_eq!(Landmarks::load_or_build(&path, &net, 3).unwrap().len(), 3);
+        assert_eq!(Landmarks::load(&path, &net).unwrap().len(), 3);
+        assert_eq!(Landmarks::load_or_build(&path, &net, 5).unwrap().len(), 5);
+    }
+}
+
+// ── Cached router ─────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod cache {
+    use dt_core::{GeoPoint, NodeId, TransportMode};
+    use crate::{CachedRouter, DijkstraRouter, RoadNetworkBuilder, Router, SpatialError};
+
+    #[test]
+    fn hits_evict_least_recently_used() {
+        let net = super::helpers::city();
+        let router = CachedRouter::new(DijkstraRouter, 2);
+        let route = |from, to| router.route(&net, NodeId(from), NodeId(to), TransportMode::Car).unwrap();
+
+        let first = route(0, 63);
+        assert_eq!(route(0, 63).edges, first.edges);
+        assert_eq!((router.hits(), router.misses()), (1, 1));
+
+        route(5, 9);
+        route(0, 63); // (0, 63) is now the most recent
+        route(7, 56); // evicts (5, 9)
+        assert_eq!(router.len(), 2);
+        let misses = router.misses();
+        route(0, 63);
+        assert_eq!(router.misses(), misses);
+        route(5, 9);
+        assert_eq!(router.misses(), misses + 1);
+
+        // Modes are cached separately.
+        let walk = |router: &dyn Router| router.route(&net, NodeId(0), NodeId(63), TransportMode::Walk).unwrap();
+        assert_eq!(walk(&router).total_travel_secs(), walk(&DijkstraRouter).total_travel_secs());
+
+        router.clear();
+        assert!(router.is_empty());
+    }
+
+    #[test]
+    fn errors_and_zero_capacity_are_not_cached() {
+        let (net, [n0, .., n4]) = super::helpers::grid_network();
+        let router = CachedRouter::new(DijkstraRouter, 0);
+        router.route(&net, n0, n4, TransportMode::Car).unwrap();
+        router.route(&net, n0, n4, TransportMode::Car).unwrap();
+        assert_eq!((router.hits(), router.misses(), router.len()), (0, 2, 0));
+
+        // Two nodes, no edges.
+        let mut b = RoadNetworkBuilder::new();
+        let [a, c] = [0.0, 1.0].map(|lon| b.add_node(GeoPoint::new(0.0, lon)));
+        let islands = b.build();
+        let router = CachedRouter::new(DijkstraRouter, 8);
+        assert!(matches!(router.route(&islands, a, c, TransportMode::Car), Err(SpatialError::NoRoute { .. })));
+        assert!(router.is_empty());
+    }
+
+    #[test]
+    fn agent_sensitive_costs_are_cached_per_agent() {
+        use dt_core::{AgentId, EdgeId, Tick};
+        use crate::{EdgeCost, ModeSpeedCost, RoadNetwork};
+
+        let mut b = RoadNetworkBuilder::new();
+        let [a, c] = [0.0, 1.0].map(|lon| b.add_node(GeoPoint::new(0.0, lon)));
+        b.add_directed_edge(a, c, 1_000.0, 60_000); // fast
+        b.add_directed_edge(a, c, 1_000.0, 90_000); // slow
+        let net = b.build();
+        let fast = net.out_edges(a).find(|e| net.edge_travel_ms[e.index()] == 60_000).unwrap();
+
+        // Agent 1 stays off the fast edge.
+        let avoid = |net: &RoadNetwork, edge: EdgeId, mode, agent: Option<AgentId>| match agent {
+            Some(AgentId(1)) if edge == fast => u32::MAX,
+            _ => ModeSpeedCost.cost_ms(net, edge, mode, agent),
+        };
+        let router = CachedRouter::new(DijkstraRouter::with_cost(avoid), 8);
+        assert!(router.depends_on_agent());
+        let route = |agent| router.route_for(&net, a, c, TransportMode::Car, Tick(0), 1, AgentId(agent)).unwrap();
+        assert_eq!(route(0).total_travel_ms, 60_000);
+        assert_eq!(route(1).total_travel_ms, 90_000);
+        assert_eq!(route(1).total_travel_ms, 90_000);
+        assert_eq!(route(0).total_travel_ms, 60_000);
+        assert_eq!((router.hits(), router.misses(), router.len()), (2, 2, 2));
+
+        // Agent-blind costs share one entry.
+        let router = CachedRouter::new(DijkstraRouter::with_cost(ModeSpeedCost), 8);
+        assert!(!router.depends_on_agent());
+        for agent in 0..3 {
+            router.route_for(&net, a, c, TransportMode::Car, Tick(0), 1, AgentId(agent)).unwrap();
+        }
+        assert_eq!((router.hits(), router.misses(), router.len()), (2, 1, 1));
+    }
+}
+
+// ── Time-dependent routing ────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod time_dependent {
+    use dt_core::{GeoPoint, Tick, TransportMode};
+    use crate::{DijkstraRouter, RoadNetwork, RoadNetworkBuilder, Router, SpeedProfiles, TimeDependentRouter};
+
+    /// `home → town` directly (200 s) or via `ring` (120 s + 120 s).  The
+    /// direct road is three times slower from 07:00 to 09:00.
+    fn commute() -> (RoadNetwork, [dt_core::NodeId; 3]) {
+        let mut b = RoadNetworkBuilder::new();
+        let home = b.add_node(GeoPoint::new(0.0, 0.0));
+        let town = b.add_node(GeoPoint::new(0.0, 2.0));
+        let ring = b.add_node(GeoPoint::new(1.0, 1.0));
+        b.add_road(home, town, 2_000.0, 200_000);
+        b.add_road(home, ring, 1_500.0, 120_000);
+        b.add_road(ring, town, 1_500.0, 120_000);
+        let mut net = b.build();
+
+        let mut profiles = SpeedProfiles::new(net.edge_count());
+        let mut factors = [1.0; 24];
+        factors[7..9].fill(3.0);
+        let rush = profiles.add_profile(factors);
+        let direct: Vec<_> = net.out_edges(home).filter(|e| net.edge_to[e.index()] == town).collect();
+        for edge in direct {
+            profiles.assign(edge, rush);
+        }
+        net.set_speed_profiles(profiles);
+        (net, [home, town, ring])
+    }
+
+    #[test]
+    fn profiles_scale_travel_times() {
+        let (net, [home, town, _]) = commute();
+        let direct = net.out_edges(home).find(|e| net.edge_to[e.index()] == town).unwrap();
+        let profiles = net.speed_profiles.as_ref().unwrap();
+        assert_eq!((profiles.profile_count(), profiles.profile_of(direct)), (1, Some(0)));
+        assert_eq!(net.travel_ms_at(direct, 7 * 3600), 600_000);
+        assert_eq!(net.travel_ms_at(direct, 9 * 3600), 200_000);
+        // Times past midnight wrap around.
+        assert_eq!(net.travel_ms_at(direct, 31 * 3600), 600_000);
+        let back = net.out_edges(town).find(|e| net.edge_to[e.index()] == home).unwrap();
+        assert_eq!(profiles.profile_of(back), None);
+        assert_eq!(net.travel_ms_at(back, 8 * 3600), 200_000);
+    }
+
+    #[test]
+    fn peak_departures_take_the_bypass() {
+        let (net, [home, town, ring]) = commute();
+        let router = TimeDependentRouter::new();
+        let at = |hour: u64| router.route_at(&net, home, town, TransportMode::Car, Tick(hour), 3600).unwrap();
+
+        let off_peak = at(3);
+        assert_eq!((off_peak.edges.len(), off_peak.total_travel_secs()), (1, 200.0));
+        let peak = at(8);
+        assert_eq!((peak.edges.len(), peak.total_travel_secs()), (2, 240.0));
+        assert_eq!(net.edge_to[peak.edges[0].index()], ring);
+        // The next day's peak too; static routers and other modes ignore the time.
+        assert_eq!(at(32).total_travel_secs(), 240.0);
+        assert_eq!(router.route(&net, home, town, TransportMode::Car).unwrap().total_travel_secs(), 200.0);
+        let fixed = DijkstraRouter.route_at(&net, home, town, TransportMode::Car, Tick(8), 3600).unwrap();
+        assert_eq!(fixed.edges.len(), 1);
+        let walk = router.route_at(&net, home, town, TransportMode::Walk, Tick(8), 3600).unwrap();
+        assert_eq!(walk.edges.len(), 1);
+    }
+
+    #[test]
+    fn costs_follow_the_clock_along_the_route() {
+        // a → b → c, with b → c three times slower from 07:00.  Leaving a at
+        // 06:59, b is reached at 07:01, after the slowdown.
+        let mut b = RoadNetworkBuilder::new();
+        let nodes: Vec<_> = (0..3).map(|i| b.add_node(GeoPoint::new(0.0, i as f32))).collect();
+        b.add_directed_edge(nodes[0], nodes[1], 1_000.0, 120_000);
+        b.add_directed_edge(nodes[1], nodes[2], 1_000.0, 100_000);
+        let mut chain = b.build();
+        let mut profiles = SpeedProfiles::new(2);
+        let mut factors = [1.0; 24];
+        factors[7] = 3.0;
+        let rush = profiles.add_profile(factors);
+        profiles.assign(chain.out_edges(nodes[1]).next().unwrap(), rush);
+        chain.set_speed_profiles(profiles);
+
+        let router = TimeDependentRouter::new();
+        let secs = |minute: u64| {
+            router.route_at(&chain, nodes[0], nodes[2], TransportMode::Car, Tick(minute), 60).unwrap().total_travel_secs()
+        };
+        assert_eq!(secs(6 * 60 + 57), 220.0);
+        assert_eq!(secs(6 * 60 + 59), 420.0);
+
+        // A clock starting at 07:00 UTC makes tick 0 peak time.
+        let router = TimeDependentRouter::new().with_start_unix_secs(7 * 3600 + 86_400 * 10);
+        assert_eq!(router.secs_of_day(Tick(1), 3600), 8 * 3600);
+        let (net, [home, town, _]) = commute();
+        let route = router.route_at(&net, home, town, TransportMode::Car, Tick(0), 3600).unwrap();
+        assert_eq!(route.total_travel_secs(), 240.0);
+    }
+}
+
+// ── Transit routing ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod transit {
+    use dt_core::{EdgeId, GeoPoint, NodeId, Tick, TransitVehicle, TransportMode};
+    use crate::{
+        DijkstraRouter, RoadNetwork, RoadNetworkBuilder, Router, SpatialError, TransitLine, TransitRide, TransitRouter,
+    };
+
+    /// Five nodes on a straight road, 1 km (≈ 714 s on foot) apart.
+    fn street() -> (RoadNetwork, Vec<NodeId>) {
+        let mut b = RoadNetworkBuilder::new();
+        let nodes: Vec<NodeId> = (0..5).map(|i| b.add_node(GeoPoint::new(0.0, i as f32 * 0.01))).collect();
+        for pair in nodes.windows(2) {
+            b.add_road(pair[0], pair[1], 1_000.0, 72_000);
+        }
+        (b.build(), nodes)
+    }
+
+    /// Line `A` rides 0 → 1 → 2 and line `B` 2 → 3 → 4, a minute per hop,
+    /// every 10 minutes from 06:00 to 22:00.
+    fn lines(nodes: &[NodeId]) -> Vec<TransitLine> {
+        let line = |name, stops: &[NodeId]| {
+            stops.iter().fold(TransitLine::new(name, 600).service(6 * 3600, 22 * 3600), |l, &s| l.stop(s, 60))
+        };
+        vec![line("A", &nodes[0..3]), line("B", &nodes[2..5])]
+    }
+
+    fn assert_connected(net: &RoadNetwork, from: NodeId, to: NodeId, edges: &[EdgeId]) {
+        let end = edges.iter().fold(from, |at, e| {
+            assert_eq!(net.edge_from[e.index()], at);
+            net.edge_to[e.index()]
+        });
+        assert_eq!(end, to);
+    }
+
+    #[test]
+    fn rides_waits_and_transfers() {
+        let (net, nodes) = street();
+        let router = TransitRouter::new(&net, lines(&nodes)).unwrap();
+        // 60 s ticks: tick 360 is 06:00.
+        let at = |minute: u64, to: usize| {
+            router.route_at(&net, nodes[0], nodes[to], TransportMode::Transit, Tick(minute), 60).unwrap()
+        };
+
+        // Board at once, two hops.
+        assert_eq!(at(360, 2).total_travel_secs(), 120.0);
+        // Just missed it: wait nine minutes.
+        assert_eq!(at(361, 2).total_travel_secs(), 660.0);
+        // A reaches 2 at 06:02; B leaves 2 at 06:10 and reaches 4 at 06:12.
+        let transfer = at(360, 4);
+        assert_eq!(transfer.total_travel_secs(), 720.0);
+        assert_connected(&net, nodes[0], nodes[4], &transfer.edges);
+        assert_eq!(transfer.edges.len(), 4);
+
+        // The 23:50 night bus leaves stop 1 at 00:05 the next day.
+        let night = TransitLine::new("N", 600).service(23 * 3600 + 50 * 60, 23 * 3600 + 50 * 60);
+        let night = night.stop(nodes[0], 0).stop(nodes[1], 900).stop(nodes[2], 60);
+        let router = TransitRouter::new(&net, vec![night]).unwrap();
+        let route = router.route_at(&net, nodes[1], nodes[2], TransportMode::Transit, Tick(24 * 60 + 1), 60).unwrap();
+        assert_eq!(route.total_travel_secs(), 300.0);
+    }
+
+    #[test]
+    fn rides_name_their_vehicles() {
+        let (net, nodes) = street();
+        let router = TransitRouter::new(&net, lines(&nodes)).unwrap();
+        let at = |from: usize, to: usize, minute: u64| {
+            router.route_at(&net, nodes[from], nodes[to], TransportMode::Transit, Tick(minute), 60).unwrap()
+        };
+        let vehicle = |line, minute: i64| TransitVehicle { line, departure_ms: minute * 60_000 };
+
+        // The 06:00 A, then the 06:10 B from stop 2.
+        let transfer = at(0, 4, 360);
+        assert_eq!(transfer.rides, vec![
+            TransitRide { vehicle: vehicle(0, 360), first_edge: 0, end_edge: 2 },
+            TransitRide { vehicle: vehicle(1, 370), first_edge: 2, end_edge: 4 },
+        ]);
+        assert!(transfer.rides[1].covers(3) && !transfer.rides[1].covers(4));
+        // Boarding the same run a stop later, and the next day's run.
+        assert_eq!(at(1, 2, 361).rides[0].vehicle, vehicle(0, 360));
+        assert_eq!(at(0, 2, 360 + 24 * 60).rides[0].vehicle, vehicle(0, 360 + 24 * 60));
+        // Walking the whole way rides nothing.
+        assert!(at(0, 1, 3 * 60).rides.is_empty());
+    }
+
+    #[test]
+    fn walks_when_nothing_runs_sooner() {
+        let (net, nodes) = street();
+        let router = TransitRouter::new(&net, lines(&nodes)).unwrap();
+        let walk = DijkstraRouter.route(&net, nodes[0], nodes[1], TransportMode::Walk).unwrap();
+        // 03:00 and 22:05 are outside service; one stop is quicker on foot anyway.
+        for hour in [3, 22] {
+            let route = router.route_at(&net, nodes[0], nodes[1], TransportMode::Transit, Tick(hour * 60 + 5), 60);
+            assert_eq!(route.unwrap().total_travel_secs(), walk.total_travel_secs());
+        }
+        // Against the direction of travel only walking works.
+        let back = router.route_at(&net, nodes[2], nodes[0], TransportMode::Transit, Tick(400), 60).unwrap();
+        assert_eq!(back.total_travel_secs(), 2.0 * walk.total_travel_secs());
+
+        let reversed: Vec<_> = lines(&nodes).iter().map(TransitLine::reversed).collect();
+        let router = TransitRouter::new(&net, reversed).unwrap();
+        let back = router.route_at(&net, nodes[2], nodes[0], TransportMode::Transit, Tick(360), 60).unwrap();
+        assert_eq!(back.total_travel_secs(), 120.0);
+        assert_connected(&net, nodes[2], nodes[0], &back.edges);
+    }
+
+    #[test]
+    fn other_modes_and_bad_lines() {
+        let (net, nodes) = street();
+        let router = TransitRouter::new(&net, lines(&nodes)).unwrap();
+        let car = router.route_at(&net, nodes[0], nodes[4], TransportMode::Car, Tick(360), 60).unwrap();
+        assert_eq!(car.total_travel_secs(), 288.0);
+        assert!(router.route(&net, nodes[3], nodes[3], TransportMode::Transit).unwrap().is_trivial());
+
+        let short = TransitLine::new("short", 600).stop(nodes[0], 0);
+        assert!(matches!(TransitRouter::new(&net, vec![short]), Err(SpatialError::Transit(_))));
+        let stray = TransitLine::new("stray", 600).stop(nodes[0], 0).stop(NodeId(99), 60);
+        assert!(matches!(TransitRouter::new(&net, vec![stray]), Err(SpatialError::NodeNotFound(NodeId(99)))));
+        let never = TransitLine::new("never", 0).stop(nodes[0], 0).stop(nodes[1], 60);
+        assert!(matches!(TransitRouter::new(&net, vec![never]), Err(SpatialError::Transit(_))));
+    }
+}
+
+// ── Isochrones ────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod isochrone {
+    use dt_core::{NodeId, TransportMode};
+    use crate::{DijkstraRouter, Router};
+
+    #[test]
+    fn budget_bounds_the_reachable_set() {
+        let (net, [n0, n1, n2, n3, n4]) = super::helpers::grid_network();
+        // Car times from n0: n1 10 s, n2 20 s, n4 30 s, n3 40 s (via n4).
+        let within = |secs| net.reachable_nodes(n0, secs, TransportMode::Car);
+        assert_eq!(within(0.0), vec![(n0, 0.0)]);
+        assert_eq!(within(25.0), vec![(n0, 0.0), (n1, 10.0), (n2, 20.0)]);
+        assert_eq!(within(40.0), vec![(n0, 0.0), (n1, 10.0), (n2, 20.0), (n4, 30.0), (n3, 40.0)]);
+        // Walking covers less ground in the same time.
+        assert!(net.reachable_nodes(n0, 40.0, TransportMode::Walk).len() < 5);
+    }
+
+    #[test]
+    fn agrees_with_dijkstra() {
+        let net = super::helpers::city();
+        for mode in [TransportMode::Car, TransportMode::Bike] {
+            let reached = net.reachable_nodes(NodeId(0), 60.0, mode);
+            assert!(reached.windows(2).all(|w| w[0].1 <= w[1].1));
+            for to in (0..64).map(NodeId) {
+                let secs = DijkstraRouter.route(&net, NodeId(0), to, mode).map(|r| r.total_travel_secs());
+                let found = reached.iter().find(|(n, _)| *n == to).map(|(_, s)| *s);
+                match secs {
+                    Ok(secs) if secs <= 60.0 => assert_eq!(found, Some(secs), "{to}"),
+                    _ => assert_eq!(found, None, "{to}"),
+                }
+            }
+        }
+    }
+}
+
+// ── Alternative routes ────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod alternatives {
+    use std::collections::HashSet;
+
+    use dt_core::{NodeId, TransportMode};
+    use crate::{DijkstraRouter, Router};
+
+    #[test]
+    fn finds_both_corridors() {
+        let (net, [n0, _, _, _, n4]) = super::helpers::grid_network();
+        let routes = DijkstraRouter.route_k(&net, n0, n4, TransportMode::Car, 3).unwrap();
+        // Only 0→1→2→4 (30 s) and 0→3→4 (60 s) exist without loops.
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].total_travel_secs(), 30.0);
+        assert_eq!(routes[1].total_travel_secs(), 60.0);
+        super::helpers::assert_path(&net, n0, n4, &routes[1].edges, 60.0);
+    }
+
+    #[test]
+    fn alternatives_are_valid_and_diverse() {
+        let net = super::helpers::city();
+        let (from, to) = (NodeId(0), NodeId(63));
+        let routes = DijkstraRouter.route_k(&net, from, to, TransportMode::Car, 4).unwrap();
+        assert!(routes.len() > 1);
+        let best = DijkstraRouter.route(&net, from, to, TransportMode::Car).unwrap();
+        assert_eq!(routes[0].edges, best.edges);
+        assert!(routes.windows(2).all(|w| w[0].total_travel_secs() <= w[1].total_travel_secs()));
+        for (i, route) in routes.iter().enumerate() {
+            super::helpers::assert_path(&net, from, to, &route.edges, route.total_travel_secs());
+            for other in &routes[..i] {
+                let other: HashSet<_> = other.edges.iter().collect();
+                let shared = route.edges.iter().filter(|e| other.contains(e)).count();
+                assert!(shared < route.edges.len(), "duplicate alternative");
+            }
+        }
+    }
+
+    #[test]
+    fn trivial_and_empty_requests() {
+        let (net, [n0, _, n2, _, _]) = super::helpers::grid_network();
+        let same = DijkstraRouter.route_k(&net, n2, n2, TransportMode::Car, 3).unwrap();
+        assert_eq!(same.len(), 1);
+        assert!(same[0].is_trivial());
+        assert!(DijkstraRouter.route_k(&net, n0, n2, TransportMode::Car, 0).unwrap().is_empty());
+    }
+}
+
+// ── Travel-time matrix ────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod matrix {
+    use dt_core::{GeoPoint, NodeId, TransportMode};
+    use crate::{DijkstraRouter, RoadNetworkBuilder, Router};
+
+    #[test]
+    fn cells_match_single_queries() {
+        let net = super::helpers::city();
+        let origins: Vec<NodeId> = [0, 9, 27, 63].map(NodeId).to_vec();
+        let destinations: Vec<NodeId> = [63, 0, 36, 9, 9].map(NodeId).to_vec();
+        for mode in [TransportMode::Car, TransportMode::Walk] {
+            let m = DijkstraRouter.route_matrix(&net, &origins, &destinations, mode);
+            assert_eq!(m.as_slice().len(), 20);
+            for (i, &from) in origins.iter().enumerate() {
+                for (j, &to) in destinations.iter().enumerate() {
+                    let single = DijkstraRouter.route(&net, from, to, mode).ok();
+                    assert_eq!(m.secs(i, j), single.as_ref().map(|r| r.total_travel_secs()), "{from}→{to}");
+                    assert_eq!(m.route(i, j).map(|r| &r.edges), single.as_ref().map(|r| &r.edges));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unreachable_cells_are_infinite() {
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint::new(0.0, 0.0));
+        let c = b.add_node(GeoPoint::new(0.0, 0.01));
+        b.add_directed_edge(a, c, 100.0, 7_000);
+        let net = b.build();
+
+        let m = DijkstraRouter.travel_time_matrix(&net, &[a, c], &[a, c], TransportMode::Car);
+        assert!(!m.has_routes());
+        assert_eq!(m.row(0), &[0.0, 7.0]);
+        assert_eq!(m.row(1), &[f32::INFINITY, 0.0]);
+        assert_eq!(m.secs(1, 0), None);
+        assert!(m.route(0, 1).is_none());
+    }
+}
+
+// ── Persistence ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod persist {
+    use dt_core::{GeoPoint, NodeId, TransportMode};
+    use crate::{DijkstraRouter, RoadNetwork, Router, SpatialError};
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let net = super::helpers::city();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("city.dtrn");
+        net.save(&path).unwrap();
+        let loaded = RoadNetwork::load(&path).unwrap();
+
+        assert_eq!(loaded.fingerprint(), net.fingerprint());
+        assert_eq!(loaded.node_out_start, net.node_out_start);
+        assert_eq!(loaded.node_in_start, net.node_in_start);
+        assert_eq!(loaded.in_edge_ids, net.in_edge_ids);
+        assert_eq!(loaded.edge_capacity_vph, net.edge_capacity_vph);
+        assert_eq!(loaded.edge_freeflow_mps, net.edge_freeflow_mps);
+        let probe = GeoPoint::new(3.4, 5.6);
+        assert_eq!(loaded.snap_to_node(probe), net.snap_to_node(probe));
+        let route = |n: &RoadNetwork| DijkstraRouter.route(n, NodeId(0), NodeId(63), TransportMode::Car).unwrap();
+        assert_eq!(route(&loaded).edges, route(&net).edges);
+
+        let empty = RoadNetwork::empty();
+        let mut bytes = Vec::new();
+        empty.write_to(&mut bytes).unwrap();
+        assert!(RoadNetwork::read_from(&mut bytes.as_slice()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn corrupt_files_are_rejected() {
+        let net = super::helpers::city();
+        let mut bytes = Vec::new();
+        net.write_to(&mut bytes).unwrap();
+        let rejected = |bytes: &[u8]| {
+            matches!(RoadNetwork::read_from(&mut &bytes[..]), Err(SpatialError::NetworkFile(_)))
+        };
+
+        // A changed travel time fails the fingerprint check.  It is the
+        // last word before the capacity and free-flow arrays.
+        let mut corrupt = bytes.clone();
+        let last = corrupt.len() - 2 * (4 + 4 * net.edge_count()) - 4;
+        corrupt[last..last + 4].copy_from_slice(&1u32.to_le_bytes());
+        assert!(rejected(&corrupt));
+        // A missing trailing word, and a file that is not a network.
+        assert!(rejected(&bytes[..bytes.len() - 4]));
+        assert!(rejected(b"DTCH\x01\x00\x00\x00"));
+        assert!(RoadNetwork::read_from(&mut &bytes[..6]).is_err());
+    }
+}
+
+// ── Graph export ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod export {
+    use dt_core::EdgeId;
+
+    fn text(write: impl FnOnce(&mut Vec<u8>)) -> String {
+        let mut bytes = Vec::new();
+        write(&mut bytes);
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn graphml_has_every_node_and_edge_with_weights() {
+        let (mut net, _) = super::helpers::grid_network();
+        net.edge_travel_ms[0] = u32::MAX;
+        let xml = text(|w| net.write_graphml(w).unwrap());
+
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains(r#"edgedefault="directed""#));
+        assert!(xml.contains(r#"attr.name="travel_secs" attr.type="double""#));
+        assert_eq!(xml.matches("<node ").count(), net.node_count());
+        assert_eq!(xml.matches("<edge ").count(), net.edge_count());
+        assert!(xml.contains(r#"<node id="n2"><data key="d0">0</data><data key="d1">2</data></node>"#));
+
+        let e = EdgeId(1).index();
+        let edge = format!(
+            r#"<edge id="e1" source="n{}" target="n{}"><data key="d2">1</data><data key="d3">{}</data><data key="d4">{}</data>"#,
+            net.edge_from[e].0,
+            net.edge_to[e].0,
+            net.edge_length_m[e],
+            net.edge_travel_ms[e] as f64 / 1000.0,
+        );
+        assert!(xml.contains(&edge), "{xml}");
+        assert!(xml.contains(r#"<edge id="e0" source="n0""#));
+        assert!(xml.contains(r#"<data key="d4">INF</data>"#));
+        assert!(xml.trim_end().ends_with("</graphml>"));
+    }
+
+    #[test]
+    fn dot_lists_edges_with_travel_time_labels() {
+        let (net, _) = super::helpers::grid_network();
+        let dot = text(|w| net.write_dot(w).unwrap());
+
+        assert!(dot.starts_with("digraph road_network {"));
+        assert_eq!(dot.matches(" -> ").count(), net.edge_count());
+        assert!(dot.contains(r#"n3 [pos="0,1!"];"#));
+        assert!(dot.contains(r#"n0 -> n1 [label="10.0 s", weight=10];"#));
+        assert!(dot.contains(r#"n0 -> n3 [label="50.0 s", weight=50];"#));
+    }
+
+    #[test]
+    fn save_graphml_writes_a_file() {
+        let (net, _) = super::helpers::grid_network();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("grid.graphml");
+        net.save_graphml(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), text(|w| net.write_graphml(w).unwrap()));
+    }
+}
+
+// ── Diagnostics ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod diagnostics {
+    use dt_core::{EdgeId, GeoPoint, NodeId};
+    use crate::network::{diagnostics, strongly_connected_components};
+    use crate::RoadNetworkBuilder;
+
+    #[test]
+    fn clean_grid() {
+        let (net, _) = super::helpers::grid_network();
+        let report = diagnostics(&net);
+        assert!(report.is_clean(), "{report}");
+        assert_eq!(report.component_count, 1);
+        assert_eq!(report.largest_component, 5);
+        // Every node of the ring has two neighbours.
+        assert_eq!(report.degree_histogram, vec![0, 0, 5]);
+    }
+
+    #[test]
+    fn finds_each_problem() {
+        let mut b = RoadNetworkBuilder::new();
+        let n: Vec<NodeId> = (0..5).map(|i| b.add_node(GeoPoint::new(0.0, i as f32 * 0.01))).collect();
+        b.add_road(n[0], n[1], 100.0, 10_000);
+        b.add_directed_edge(n[0], n[1], 120.0, 12_000); // duplicate of 0→1
+        b.add_road(n[1], n[2], 0.0, 0);                  // zero length
+        b.add_directed_edge(n[2], n[3], 100.0, 10_000); // 3 is a dead end
+        let net = b.build();
+
+        let report = diagnostics(&net);
+        assert!(!report.is_clean());
+        assert_eq!(report.dead_end_nodes, vec![n[3], n[4]]);
+        assert_eq!(report.duplicate_edges.len(), 1);
+        let dup = report.duplicate_edges[0];
+        assert_eq!((net.edge_from[dup.index()], net.edge_to[dup.index()]), (n[0], n[1]));
+        assert_eq!(report.zero_length_edges.len(), 2);
+        assert!(report.zero_length_edges.iter().all(|e: &EdgeId| net.edge_length_m[e.index()] == 0.0));
+        // {0, 1, 2}, {3}, {4}.
+        assert_eq!(report.component_count, 3);
+        assert_eq!(report.largest_component, 3);
+        assert_eq!(report.unreachable_nodes(), 2);
+        assert!(report.to_string().contains("1 duplicate edges"));
+    }
+
+    #[test]
+    fn components_follow_one_way_streets() {
+        let net = super::helpers::city();
+        let (component, count) = strongly_connected_components(&net);
+        // Nodes share a component exactly when each reaches the other.
+        let reach: Vec<Vec<bool>> = (0..64)
+            .map(|i| {
+                let mut seen = vec![false; 64];
+                for (node, _) in net.reachable_nodes(NodeId(i), f32::MAX, dt_core::TransportMode::Car) {
+                    seen[node.index()] = true;
+                }
+                seen
+            })
+            .collect();
+        for a in 0..64 {
+            for b in 0..64 {
+                assert_eq!(component[a] == component[b], reach[a][b] && reach[b][a], "{a} {b}");
+            }
+        }
+        assert!(count >= 1);
+    }
+}
+
+// ── Largest strongly connected component ──────────────────────────────────────
+
+#[cfg(test)]
+mod largest_scc {
+    use dt_core::{GeoPoint, NodeId, TransportMode};
+    use crate::network::diagnostics;
+    use crate::{DijkstraRouter, RoadNetworkBuilder, Router, SpeedProfiles};
+
+    #[test]
+    fn drops_fragments_and_remaps() {
+        let mut b = RoadNetworkBuilder::new();
+        let n: Vec<NodeId> = (0..7).map(|i| b.add_node(GeoPoint::new(0.0, i as f32 * 0.01))).collect();
+        b.add_directed_edge(n[5], n[6], 50.0, 5_000);   // separate fragment, one-way
+        b.add_road(n[1], n[2], 100.0, 10_000);
+        b.add_road(n[2], n[4], 100.0, 10_000);
+        b.add_directed_edge(n[4], n[1], 100.0, 10_000);
+        b.add_directed_edge(n[1], n[0], 100.0, 10_000); // 0 can't get back
+        b.add_directed_edge(n[3], n[2], 100.0, 10_000); // 3 can't be reached
+        let mut net = b.build();
+        let mut profiles = SpeedProfiles::new(net.edge_count());
+        let slow = profiles.add_profile([2.0; 24]);
+        let e24 = net.out_edges(n[2]).find(|e| net.edge_to[e.index()] == n[4]).unwrap();
+        profiles.assign(e24, slow);
+        net.set_speed_profiles(profiles);
+
+        let remap = net.retain_largest_scc();
+        let i = NodeId::INVALID;
+        assert_eq!(remap, vec![i, NodeId(0), NodeId(1), i, NodeId(2), i, i]);
+        assert_eq!(net.node_count(), 3);
+        assert_eq!(net.edge_count(), 5);
+        assert_eq!(net.node_pos[1], GeoPoint::new(0.0, 0.02));
+        assert!(diagnostics(&net).is_clean());
+
+        let e = net.out_edges(NodeId(1)).find(|e| net.edge_to[e.index()] == NodeId(2)).unwrap();
+        assert_eq!(net.travel_ms_at(e, 0), 20_000);
+        for from in 0..3 {
+            for to in 0..3 {
+                assert!(DijkstraRouter.route(&net, NodeId(from), NodeId(to), TransportMode::Car).is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn closed_edges_do_not_connect() {
+        let mut b = RoadNetworkBuilder::new();
+        let [a, c, d] = [0.0, 0.01, 0.02].map(|lon| b.add_node(GeoPoint::new(0.0, lon)));
+        b.add_road(a, c, 100.0, 10_000);
+        b.add_directed_edge(c, d, 100.0, 10_000);
+        b.add_directed_edge(d, c, 100.0, u32::MAX);
+        let mut net = b.build();
+        assert_eq!(net.retain_largest_scc(), vec![a, c, NodeId::INVALID]);
+        assert_eq!(net.edge_count(), 2);
+    }
+
+    #[test]
+    fn connected_network_is_unchanged() {
+        let (mut net, _) = super::helpers::grid_network();
+        let before = net.fingerprint();
+        assert_eq!(net.retain_largest_scc(), (0..5).map(NodeId).collect::<Vec<_>>());
+        assert_eq!(net.fingerprint(), before);
+    }
+
+    #[test]
+    fn mode_speeds_survive() {
+        let mut b = RoadNetworkBuilder::new();
+        let [a, c, d] = [0.0, 0.01, 0.02].map(|lon| b.add_node(GeoPoint::new(0.0, lon)));
+        b.add_road(a, c, 100.0, 10_000);
+        b.add_directed_edge(c, d, 100.0, 10_000);
+        let mut net = b.build();
+        let speeds = dt_core::RoutingConfig { walk_speed_mps: 2.0, bike_speed_mps: 6.0, transit_speed_mps: 12.0 };
+        net.mode_speeds = speeds.clone();
+        assert_eq!(net.retain_largest_scc(), vec![a, c, NodeId::INVALID]);
+        assert_eq!(net.mode_speeds, speeds);
+    }
+}
+
+// ── Degree-2 simplification ───────────────────────────────────────────────────
+
+#[cfg(test)]
+mod simplify {
+    use dt_core::{GeoPoint, NodeId, TransportMode};
+    use crate::{DijkstraRouter, RoadNetworkBuilder, Router};
+
+    #[test]
+    fn chains_merge_into_single_edges() {
+        // 0 ─ 1 ─ 2 ─ 3 junction, with a spur 3 ─ 4 and a one-way 3 → 5 → 6.
+        let mut b = RoadNetworkBuilder::new();
+        let n: Vec<NodeId> = (0..7).map(|i| b.add_node(GeoPoint::new(0.0, i as f32 * 0.01))).collect();
+        b.add_road(n[0], n[1], 100.0, 10_000);
+        b.add_road(n[1], n[2], 200.0, 20_000);
+        b.add_road(n[2], n[3], 300.0, 30_000);
+        b.add_road(n[3], n[4], 50.0, 5_000);
+        b.add_directed_edge(n[3], n[5], 10.0, 1_000);
+        b.add_directed_edge(n[5], n[6], 10.0, 1_000);
+        let mut net = b.build();
+        let original = net.clone();
+        let before = DijkstraRouter.route(&net, n[0], n[4], TransportMode::Car).unwrap();
+
+        let remap = net.simplify();
+        let i = NodeId::INVALID;
+        assert_eq!(remap, vec![NodeId(0), i, i, NodeId(1), NodeId(2), i, NodeId(3)]);
+        assert_eq!(net.node_count(), 4);
+        assert_eq!(net.edge_count(), 5);
+
+        let e03 = net.out_edges(NodeId(0)).next().unwrap();
+        assert_eq!(net.edge_to[e03.index()], NodeId(1));
+        assert_eq!(net.edge_length_m[e03.index()], 600.0);
+        assert_eq!(net.edge_travel_ms[e03.index()], 60_000);
+        assert_eq!(net.edge_capacity_vph[e03.index()], 600.0);
+        assert!((net.edge_freeflow_mps[e03.index()] - 10.0).abs() < 1e-4);
+        let geometry = net.edge_geometry.as_ref().unwrap();
+        assert_eq!(geometry.points(e03), &[GeoPoint::new(0.0, 0.01), GeoPoint::new(0.0, 0.02)]);
+        let e30 = net.out_edges(NodeId(1)).find(|e| net.edge_to[e.index()] == NodeId(0)).unwrap();
+        assert_eq!(geometry.points(e30), &[GeoPoint::new(0.0, 0.02), GeoPoint::new(0.0, 0.01)]);
+
+        let after = DijkstraRouter.route(&net, NodeId(0), NodeId(2), TransportMode::Car).unwrap();
+        assert_eq!(after.total_travel_secs(), before.total_travel_secs());
+        assert_eq!(after.polyline(&net), before.polyline(&original));
+        assert!(DijkstraRouter.route(&net, NodeId(1), NodeId(3), TransportMode::Car).is_ok());
+
+        // A simplified network simplifies to itself.
+        let fingerprint = net.fingerprint();
+        assert_eq!(net.simplify(), (0..4).map(NodeId).collect::<Vec<_>>());
+        assert_eq!(net.fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn rings_keep_their_first_node() {
+        // Every node of the grid network is pass-through: it is one ring.
+        let (mut net, _) = super::helpers::grid_network();
+        let i = NodeId::INVALID;
+        assert_eq!(net.simplify(), vec![NodeId(0), i, i, i, i]);
+        assert_eq!(net.node_count(), 1);
+        assert_eq!(net.edge_count(), 2);
+        assert!(net.edge_length_m.iter().all(|&m| m == 900.0));
+        assert!(net.edge_travel_ms.iter().all(|&ms| ms == 90_000));
+        assert_eq!(net.edge_polyline(dt_core::EdgeId(0)).len(), 6);
+    }
+
+    #[test]
+    fn mode_speeds_survive() {
+        let (mut net, _) = super::helpers::grid_network();
+        let speeds = dt_core::RoutingConfig { walk_speed_mps: 2.0, bike_speed_mps: 6.0, transit_speed_mps: 12.0 };
+        net.mode_speeds = speeds.clone();
+        net.simplify();
+        assert_eq!(net.node_count(), 1);
+        assert_eq!(net.mode_speeds, speeds);
+    }
+}
+
+// ── Incremental extension ─────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod extend {
+    use dt_core::{EdgeId, GeoPoint, NodeId, TransportMode};
+    use crate::{DijkstraRouter, RoadNetwork, Router};
+
+    #[derive(Clone, Default, PartialEq, Debug)]
+    struct Toll(u32);
+
+    fn edge(net: &RoadNetwork, from: NodeId, to: NodeId) -> EdgeId {
+        net.out_edges(from).find(|e| net.edge_to[e.index()] == to).unwrap()
+    }
+
+    #[test]
+    fn new_nodes_and_edges_join_the_graph() {
+        let (mut net, [n0, n1, _, n3, n4]) = super::helpers::grid_network();
+        let before = net.clone();
+        let mut ext = net.extension();
+        let n5 = ext.add_node(GeoPoint::new(2.0, 1.0));
+        ext.add_road(n3, n5, 100.0, 5_000);
+        ext.add_directed_edge_with_capacity(n5, n4, 100.0, 5_000, 1_234.0);
+        ext.add_directed_edge(n0, n3, 50.0, 1_000); // a second, faster 0→3
+        assert_eq!((ext.node_count(), ext.edge_count()), (1, 4));
+        let remap = net.extend(ext);
+
+        assert_eq!(n5, NodeId(5));
+        assert_eq!((net.node_count(), net.edge_count()), (6, before.edge_count() + 4));
+        assert_eq!(net.node_pos[..5], before.node_pos[..]);
+        // Old edges keep their data under their new ids.
+        for (old, &new) in remap.iter().enumerate() {
+            assert_eq!(net.edge_from[new.index()], before.edge_from[old]);
+            assert_eq!(net.edge_to[new.index()], before.edge_to[old]);
+            assert_eq!(net.edge_travel_ms[new.index()], before.edge_travel_ms[old]);
+        }
+        // The 0→3 edge pushes every edge out of nodes 1.. up by one.
+        assert_eq!(remap[before.out_edges(n1).next().unwrap().index()].0, net.out_edges(n1).next().unwrap().0);
+        assert_eq!(net.out_degree(n0), 3);
+        assert_eq!(net.in_degree(n5), 1);
+        assert_eq!(net.edge_capacity_vph[edge(&net, n5, n4).index()], 1_234.0);
+
+        // 0 → 3 → 5 → 4 = 1 + 5 + 5 s.
+        let route = DijkstraRouter.route(&net, n0, n4, TransportMode::Car).unwrap();
+        assert_eq!(route.total_travel_secs(), 11.0);
+        assert_eq!(net.snap_to_node(GeoPoint::new(2.1, 1.0)), Some(n5));
+        assert_eq!(net.snap_to_edge(GeoPoint::new(1.5, 0.6)).map(|(e, ..)| net.edge_to[e.index()]), Some(n5));
+    }
+
+    #[test]
+    fn side_tables_follow_their_edges() {
+        let (mut net, [n0, n1, ..]) = super::helpers::grid_network();
+        let e10 = edge(&net, n1, n0);
+        net.overlay_mut().close(e10);
+        net.edge_attrs.register::<Toll>();
+        net.edge_attrs.get_mut::<Toll>().unwrap()[e10.index()] = Toll(7);
+
+        let mut ext = net.extension();
+        ext.add_directed_edge(n0, n1, 10.0, 1_000);
+        let remap = net.extend(ext);
+
+        let moved = remap[e10.index()];
+        assert_eq!(moved.0, e10.0 + 1);
+        assert!(net.overlay.as_ref().unwrap().is_closed(moved));
+        assert_eq!(net.edge_attrs.get::<Toll>().unwrap()[moved.index()], Toll(7));
+        let new = EdgeId(net.out_edges(n0).last().unwrap().0);
+        assert!(!net.overlay.as_ref().unwrap().is_closed(new));
+        assert_eq!(net.edge_attrs.get::<Toll>().unwrap()[new.index()], Toll(0));
+        assert_eq!(net.edge_attrs.get::<Toll>().unwrap().len(), net.edge_count());
+    }
+
+    #[test]
+    #[should_panic(expected = "extension was made for a network with 5 nodes")]
+    fn stale_extensions_are_rejected() {
+        let (mut net, _) = super::helpers::grid_network();
+        let mut first = net.extension();
+        let second = net.extension();
+        first.add_node(GeoPoint::new(3.0, 3.0));
+        net.extend(first);
+        net.extend(second);
+    }
+}
+
+// ── Network overlay ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod overlay {
+    use dt_core::{NodeId, TransportMode};
+    use crate::{BidirectionalRouter, ChRouter, DijkstraRouter, Router, SpatialError, TimeDependentRouter};
+
+    #[test]
+    fn closures_and_factors_reroute() {
+        let (mut net, [n0, n1, n2, _, n4]) = super::helpers::grid_network();
+        let secs = |net: &_, router: &dyn Router, mode| router.route(net, n0, n4, mode).unwrap().total_travel_secs();
+        let e12 = net.out_edges(n1).find(|e| net.edge_to[e.index()] == n2).unwrap();
+
+        // Closing 1→2 forces the 60 s road through n3, for cars and walkers.
+        net.overlay_mut().close(e12);
+        assert!(net.overlay.as_ref().unwrap().is_closed(e12));
+        let ch = ChRouter::build(&net);
+        for router in [&DijkstraRouter as &dyn Router, &BidirectionalRouter, &TimeDependentRouter::new(), &ch] {
+            assert_eq!(secs(&net, router, TransportMode::Car), 60.0);
+        }
+        let walk = DijkstraRouter.route(&net, n0, n4, TransportMode::Walk).unwrap();
+        assert!(!walk.edges.contains(&e12));
+
+        // Tripling it instead: 10 + 30 + 10 = 50 s still beats 60 s; walking is unaffected.
+        net.overlay_mut().scale(e12, 3.0);
+        assert_eq!(net.overlay.as_ref().unwrap().changed_count(), 1);
+        assert_eq!(secs(&net, &DijkstraRouter, TransportMode::Car), 50.0);
+        assert_eq!(net.travel_ms_at(e12, 0), 30_000);
+        assert!(DijkstraRouter.route(&net, n0, n4, TransportMode::Walk).unwrap().edges.contains(&e12));
+
+        net.overlay_mut().reset(e12);
+        assert!(net.overlay.as_ref().unwrap().is_empty());
+        assert_eq!(secs(&net, &ch, TransportMode::Car), 30.0);
+        assert_eq!(net.reachable_nodes(n0, 20.0, TransportMode::Car).len(), 3);
+    }
+
+    #[test]
+    fn closing_every_way_in_disconnects() {
+        let (mut net, [n0, _, _, _, n4]) = super::helpers::grid_network();
+        let ins: Vec<_> = net.in_edges(n4).collect();
+        for e in ins {
+            net.overlay_mut().close(e);
+        }
+        assert!(matches!(DijkstraRouter.route(&net, n0, n4, TransportMode::Car), Err(SpatialError::NoRoute { .. })));
+        assert!(net.reachable_nodes(n0, 1e6, TransportMode::Walk).iter().all(|(n, _)| *n != n4));
+
+        net.clear_overlay();
+        assert!(DijkstraRouter.route(&net, n0, NodeId(4), TransportMode::Car).is_ok());
+    }
+}
+
+// ── Edge attributes ───────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod edge_attributes {
+    use dt_core::{GeoPoint, NodeId};
+    use crate::RoadNetworkBuilder;
+
+    #[derive(Clone, Default, Debug, PartialEq)]
+    struct Capacity(u32);
+
+    #[derive(Clone, Default, Debug, PartialEq)]
+    struct ZoneId(u16);
+
+    #[test]
+    fn register_insert_get_remove() {
+        let (mut net, _) = super::helpers::grid_network();
+        assert_eq!(net.edge_attrs.edge_count(), net.edge_count());
+        assert!(net.edge_attrs.get::<Capacity>().is_none());
+
+        net.edge_attrs.register::<Capacity>();
+        net.edge_attrs.get_mut::<Capacity>().unwrap()[3] = Capacity(1_800);
+        net.edge_attrs.register::<Capacity>(); // no-op
+        assert_eq!(net.edge_attrs.get::<Capacity>().unwrap()[3], Capacity(1_800));
+        assert_eq!(net.edge_attrs.get::<Capacity>().unwrap()[0], Capacity(0));
+
+        net.edge_attrs.insert((0..net.edge_count() as u16).map(ZoneId).collect());
+        assert_eq!(net.edge_attrs.type_count(), 2);
+        let copy = net.clone();
+        assert_eq!(copy.edge_attrs.get::<ZoneId>().unwrap()[7], ZoneId(7));
+
+        let zones = net.edge_attrs.remove::<ZoneId>().unwrap();
+        assert_eq!(zones.len(), net.edge_count());
+        assert!(!net.edge_attrs.contains::<ZoneId>());
+        assert!(copy.edge_attrs.contains::<ZoneId>());
+    }
+
+    #[test]
+    #[should_panic(expected = "edge attribute has 2 values")]
+    fn insert_rejects_wrong_length() {
+        let (mut net, _) = super::helpers::grid_network();
+        net.edge_attrs.insert(vec![Capacity(1), Capacity(2)]);
+    }
+
+    #[test]
+    fn follow_edges_through_retain_largest_scc() {
+        let mut b = RoadNetworkBuilder::new();
+        let [a, c, d] = [0.0, 0.01, 0.02].map(|lon| b.add_node(GeoPoint::new(0.0, lon)));
+        b.add_directed_edge(d, a, 100.0, 10_000); // d can't be reached
+        b.add_road(a, c, 100.0, 10_000);
+        let mut net = b.build();
+        let ids: Vec<_> = (0..net.edge_count() as u32).map(|e| Capacity(e * 100)).collect();
+        net.edge_attrs.insert(ids);
+
+        net.retain_largest_scc();
+        assert_eq!(net.edge_attrs.edge_count(), 2);
+        let caps = net.edge_attrs.get::<Capacity>().unwrap();
+        for e in net.out_edges(NodeId(0)).chain(net.out_edges(NodeId(1))) {
+            let (from, to) = (net.edge_from[e.index()], net.edge_to[e.index()]);
+            // Old ids: 0 = a→c, 1 = c→a, 2 = d→a.
+            let old = if (from, to) == (NodeId(0), NodeId(1)) { 0 } else { 1 };
+            assert_eq!(caps[e.index()], Capacity(old * 100));
+        }
+    }
+}
+
+// ── Map matching ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod map_matching {
+    use dt_core::{EdgeId, GeoPoint, NodeId};
+    use crate::{map_match, RoadNetwork, RoadNetworkBuilder};
+
+    const STEP: f32 = 0.001; // ≈ 111 m
+
+    /// 3×3 grid of two-way streets, node `r * 3 + c` at (r, c) × `STEP`.
+    fn grid() -> RoadNetwork {
+        let mut b = RoadNetworkBuilder::new();
+        let nodes: Vec<NodeId> =
+            (0..9).map(|i| b.add_node(GeoPoint::new((i / 3) as f32 * STEP, (i % 3) as f32 * STEP))).collect();
+        for r in 0..3 {
+            for c in 0..3 {
+                let n = nodes[r * 3 + c];
+                let east = (c < 2).then(|| nodes[r * 3 + c + 1]);
+                let north = (r < 2).then(|| nodes[r * 3 + c + 3]);
+                for m in [east, north].into_iter().flatten() {
+                    let len = b.node_pos(n).distance_m(b.node_pos(m));
+                    b.add_road(n, m, len, (len * 100.0) as u32);
+                }
+            }
+        }
+        b.build()
+    }
+
+    fn edge(net: &RoadNetwork, from: u32, to: u32) -> EdgeId {
+        net.out_edges(NodeId(from)).find(|e| net.edge_to[e.index()] == NodeId(to)).unwrap()
+    }
+
+    #[test]
+    fn noisy_trace_follows_the_driven_streets() {
+        let net = grid();
+        // East along row 0, then north up column 2, ~5 m off the centreline
+        // and one fix per ~55 m, so some fixes sit nearer the corner node
+        // than the street they were recorded on.
+        let trace = [
+            (0.00005, 0.0002),
+            (-0.00004, 0.0007),
+            (0.00005, 0.0012),
+            (-0.00004, 0.0017),
+            (0.0004, 0.00204),
+            (0.0009, 0.00196),
+            (0.0014, 0.00205),
+            (0.0019, 0.00195),
+        ]
+        .map(|(lat, lon)| GeoPoint::new(lat, lon));
+        let matched = map_match(&net, &trace);
+        assert_eq!(matched, vec![edge(&net, 0, 1), edge(&net, 1, 2), edge(&net, 2, 5), edge(&net, 5, 8)]);
+    }
+
+    #[test]
+    fn sparse_fixes_are_joined_by_shortest_paths() {
+        let net = grid();
+        // Only the ends of the same L: the edges in between are inferred.
+        let trace = [GeoPoint::new(0.0, 0.0003), GeoPoint::new(0.0017, 0.002)];
+        let matched = map_match(&net, &trace);
+        assert_eq!(matched.first(), Some(&edge(&net, 0, 1)));
+        assert_eq!(matched.last(), Some(&edge(&net, 5, 8)));
+        assert_eq!(matched.len(), 4);
+        for pair in matched.windows(2) {
+            assert_eq!(net.edge_to[pair[0].index()], net.edge_from[pair[1].index()]);
+        }
+    }
+
+    #[test]
+    fn fixes_far_from_any_edge_are_skipped() {
+        let net = grid();
+        assert!(map_match(&net, &[]).is_empty());
+        assert!(map_match(&net, &[GeoPoint::new(1.0, 1.0)]).is_empty());
+        let trace = [GeoPoint::new(0.0, 0.0002), GeoPoint::new(1.0, 1.0), GeoPoint::new(0.0, 0.0008)];
+        assert_eq!(map_match(&net, &trace), vec![edge(&net, 0, 1)]);
+    }
+}
+
+// ── OSM ids and street names ──────────────────────────────────────────────────
+
+#[cfg(all(test, feature = "osm"))]
+mod osm_ids {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use dt_core::{EdgeId, GeoPoint, NodeId};
+    use crate::osm::{build_network, OsmWay};
+    use crate::{OsmWayId, RoadNetwork, StreetName};
+
+    /// A two-way named street 10 → 11 → 12 and an unnamed one-way spur
+    /// 12 → 13, plus a node 99 that no road uses.
+    fn load(keep_ids: bool) -> RoadNetwork {
+        let way = |id, refs: &[i64], oneway, name: Option<&str>| OsmWay {
+            id,
+            refs: refs.to_vec(),
+            speed_mps: 10.0,
+            oneway,
+            capacity_vph: 600.0,
+            name: name.map(Arc::from),
+        };
+        let ways = [way(500, &[10, 11, 12], false, Some("Dauphin Street")), way(501, &[12, 13], true, None)];
+        let positions: HashMap<i64, GeoPoint> = [10, 11, 12, 13, 99]
+            .into_iter()
+            .map(|id| (id, GeoPoint::new(30.69, -88.04 + id as f32 * 0.001)))
+            .collect();
+        build_network(&ways, positions, keep_ids)
+    }
+
+    fn edge(net: &RoadNetwork, from: u32, to: u32) -> EdgeId {
+        net.out_edges(NodeId(from)).find(|e| net.edge_to[e.index()] == NodeId(to)).unwrap()
+    }
+
+    #[test]
+    fn ids_and_names_map_back_to_osm() {
+        let net = load(true);
+        assert_eq!(net.node_osm_ids.as_deref(), Some(&[10, 11, 12, 13][..]));
+        assert_eq!(net.osm_node_id(NodeId(2)), Some(12));
+
+        let ways = net.edge_attrs.get::<OsmWayId>().unwrap();
+        assert_eq!(ways[edge(&net, 1, 0).index()], OsmWayId(500));
+        assert_eq!(ways[edge(&net, 2, 3).index()], OsmWayId(501));
+        assert_eq!(net.street_name(edge(&net, 1, 2)), Some("Dauphin Street"));
+        assert_eq!(net.street_name(edge(&net, 2, 3)), None);
+        assert_eq!(net.edge_attrs.get::<StreetName>().unwrap().len(), net.edge_count());
+    }
+
+    #[test]
+    fn ids_are_opt_in_and_follow_node_renumbering() {
+        let plain = load(false);
+        assert!(plain.node_osm_ids.is_none() && plain.osm_node_id(NodeId(0)).is_none());
+        assert!(!plain.edge_attrs.contains::<OsmWayId>());
+        assert_eq!(plain.street_name(EdgeId(0)), None);
+
+        // The spur's end is a dead end, outside the largest SCC.
+        let mut net = load(true);
+        net.retain_largest_scc();
+        assert_eq!(net.node_osm_ids.as_deref(), Some(&[10, 11, 12][..]));
+        assert_eq!(net.street_name(edge(&net, 0, 1)), Some("Dauphin Street"));
+        // Node 11 is a pass-through node.
+        net.simplify();
+        assert_eq!(net.node_osm_ids.as_deref(), Some(&[10, 12][..]));
+
+        let mut ext = net.extension();
+        ext.add_node(GeoPoint::new(0.0, 0.0));
+        net.extend(ext);
+        assert_eq!(net.osm_node_id(NodeId(2)), None);
+    }
+}
+
+// ── CSV loading ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod csv_loading {
+    use std::path::{Path, PathBuf};
+
+    use dt_core::NodeId;
+    use crate::{load_from_csv, RoadNetwork, SpatialError};
+
+    fn write(dir: &Path, nodes: &str, edges: &str) -> (PathBuf, PathBuf) {
+        let (n, e) = (dir.join("nodes.csv"), dir.join("edges.csv"));
+        std::fs::write(&n, nodes).unwrap();
+        std::fs::write(&e, edges).unwrap();
+        (n, e)
+    }
+
+    fn edge(net: &RoadNetwork, from: u32, to: u32) -> Option<usize> {
+        net.out_edges(NodeId(from)).find(|e| net.edge_to[e.index()] == NodeId(to)).map(|e| e.index())
+    }
+
+    const NODES: &str = "node_id,lat,lon\nA,30.690,-88.040\nB,30.691,-88.040\nC,30.691,-88.039\n";
+
+    #[test]
+    fn loads_nodes_in_file_order_and_edges_by_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let edges = "Speed_MPS,to,from,length_m,oneway,name\n10,B,A,200,no,Main\n5,C,B,,yes,Side\n";
+        let (n, e) = write(dir.path(), NODES, edges);
+        let net = load_from_csv(&n, &e).unwrap();
+
+        assert_eq!(net.node_count(), 3);
+        assert_eq!(net.node_pos[1].lat, 30.691);
+        assert_eq!(net.edge_count(), 3);
+        let ab = edge(&net, 0, 1).unwrap();
+        assert_eq!((net.edge_length_m[ab], net.edge_travel_ms[ab]), (200.0, 20_000));
+        assert!(edge(&net, 1, 0).is_some());
+        // One-way, with the length measured from the coordinates.
+        let bc = edge(&net, 1, 2).unwrap();
+        assert!(edge(&net, 2, 1).is_none());
+        assert!((net.edge_length_m[bc] - 96.0).abs() < 2.0);
+        assert!((net.edge_freeflow_mps[bc] - 5.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn capacity_column_is_optional_per_row() {
+        let dir = tempfile::tempdir().unwrap();
+        let edges = "from,to,length_m,speed_mps,capacity_vph\nA,B,100,25,1800\nB,C,100,25,\n";
+        let (n, e) = write(dir.path(), NODES, edges);
+        let net = load_from_csv(&n, &e).unwrap();
+
+        assert_eq!(net.edge_capacity_vph[edge(&net, 1, 0).unwrap()], 1800.0);
+        assert_eq!(net.edge_capacity_vph[edge(&net, 1, 2).unwrap()], crate::network::capacity_for_speed(25.0));
+    }
+
+    #[test]
+    fn bad_rows_are_errors_naming_the_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let check = |nodes: &str, edges: &str, expect: &str| {
+            let (n, e) = write(dir.path(), nodes, edges);
+            match load_from_csv(&n, &e) {
+                Err(SpatialError::Csv(msg)) => assert!(msg.contains(expect), "{msg}"),
+                Err(other) => panic!("expected a CSV error, got {other}"),
+                Ok(_) => panic!("expected a CSV error for {edges:?}"),
+            }
+        };
+        let header = "from,to,length_m,speed_mps\n";
+        check(NODES, &format!("{header}A,B,100,10\nA,Z,100,10\n"), "edges.csv line 3: unknown node_id \"Z\"");
+        check(NODES, &format!("{header}A,B,100,0\n"), "speed_mps must be positive");
+        check(NODES, &format!("{header}A,B,far,10\n"), "length_m: expected a number");
+        check(&format!("{NODES}A,0,0\n"), header, "nodes.csv line 5: duplicate node_id");
+        check("node_id,lat\nA,30\n", header, "no column named \"lon\"");
+        check("node_id,lat,lon\nA,95,0\n", header, "out of range");
+
+        let (n, _) = write(dir.path(), NODES, header);
+        assert!(matches!(load_from_csv(&n, &dir.path().join("missing.csv")), Err(SpatialError::Io(_))));
+    }
+}
+
+// ── Zones ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod zones {
+    use dt_core::{GeoPoint, ZoneId};
+    use crate::zones::{Polygon, Zone, ZoneSet};
+
+    fn ring(corners: &[(f32, f32)]) -> Vec<GeoPoint> {
+        corners.iter().map(|&(lat, lon)| GeoPoint::new(lat, lon)).collect()
+    }
+
+    /// West: a unit square with a hole in the middle.  East: an L-shape
+    /// beside it, plus an island farther east.
+    fn zones() -> ZoneSet {
+        let west = Polygon {
+            exterior: ring(&[(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0), (0.0, 0.0)]),
+            holes:    vec![ring(&[(0.4, 0.4), (0.4, 0.6), (0.6, 0.6), (0.6, 0.4)])],
+        };
+        let l_shape = Polygon::new(ring(&[(0.0, 1.0), (0.0, 3.0), (0.5, 3.0), (0.5, 2.0), (1.0, 2.0), (1.0, 1.0)]));
+        let island = Polygon::new(ring(&[(0.0, 5.0), (0.0, 6.0), (1.0, 6.0)]));
+        ZoneSet::new(vec![Zone::new("west", vec![west]), Zone::new("east", vec![l_shape, island])])
+    }
+
+    #[test]
+    fn points_fall_in_the_polygon_containing_them() {
+        let zones = zones();
+        let at = |lat, lon| zones.zone_of(GeoPoint::new(lat, lon));
+
+        assert_eq!(at(0.2, 0.2), Some(ZoneId(0)));
+        assert_eq!(at(0.5, 0.5), None, "inside the hole");
+        assert_eq!(at(0.2, 2.5), Some(ZoneId(1)));
+        assert_eq!(at(0.8, 2.5), None, "in the L's bounding box but not the L");
+        assert_eq!(at(0.2, 5.8), Some(ZoneId(1)));
+        assert_eq!(at(0.8, 5.2), None, "outside the triangle");
+        assert_eq!(at(-1.0, 0.5), None);
+
+        assert_eq!(zones.len(), 2);
+        assert_eq!(zones.find("east"), Some(ZoneId(1)));
+        assert_eq!(zones.zone(ZoneId(0)).name, "west");
+        assert!(ZoneSet::new(Vec::new()).zone_of(GeoPoint::new(0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn overlapping_zones_resolve_to_the_lowest_id() {
+        let square = Polygon::new(ring(&[(0.0, 0.0), (0.0, 2.0), (2.0, 2.0), (2.0, 0.0)]));
+        let zones = ZoneSet::new(vec![
+            Zone::new("small", vec![Polygon::new(ring(&[(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)]))]),
+            Zone::new("big", vec![square]),
+        ]);
+        assert_eq!(zones.zone_of(GeoPoint::new(0.5, 0.5)), Some(ZoneId(0)));
+        assert_eq!(zones.zone_of(GeoPoint::new(1.5, 1.5)), Some(ZoneId(1)));
+    }
+
+    #[test]
+    fn nodes_are_assigned_by_position() {
+        // grid_network nodes sit at (0,0), (0,1), (0,2), (1,0), (1,2).
+        let (net, _) = super::helpers::grid_network();
+        let zones = ZoneSet::new(vec![
+            Zone::new("a", vec![Polygon::new(ring(&[(-0.5, -0.5), (-0.5, 1.5), (0.5, 1.5), (0.5, -0.5)]))]),
+            Zone::new("b", vec![Polygon::new(ring(&[(0.5, 1.5), (0.5, 2.5), (1.5, 2.5), (1.5, 1.5)]))]),
+        ]);
+        let (a, b) = (Some(ZoneId(0)), Some(ZoneId(1)));
+        assert_eq!(zones.assign_nodes(&net), vec![a, a, None, None, b]);
+    }
+}
+
+#[cfg(all(test, feature = "geojson"))]
+mod zones_geojson {
+    use dt_core::{GeoPoint, ZoneId};
+    use crate::zones::ZoneSet;
+    use crate::SpatialError;
+
+    const TAZ: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {"type": "Feature", "properties": {"TAZ": 101},
+             "geometry": {"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 1], [0, 0]]]}},
+            {"type": "Feature", "properties": {"TAZ": "102-B"},
+             "geometry": {"type": "MultiPolygon", "coordinates": [
+                [[[2, 0], [3, 0], [3, 1], [2, 1], [2, 0]]],
+                [[[5, 0], [9, 0], [9, 4], [5, 4], [5, 0]], [[6, 1], [8, 1], [8, 3], [6, 3], [6, 1]]]
+             ]}}
+        ]
+    }"#;
+
+    #[test]
+    fn polygons_and_multipolygons_load_with_names() {
+        let zones = ZoneSet::from_geojson_str(TAZ, "TAZ").unwrap();
+        assert_eq!(zones.len(), 2);
+        assert_eq!(zones.zone(ZoneId(0)).name, "101");
+        assert_eq!(zones.zone(ZoneId(1)).name, "102-B");
+        // GeoJSON positions are [lon, lat].
+        assert_eq!(zones.zone_of(GeoPoint::new(0.5, 2.5)), Some(ZoneId(1)));
+        assert_eq!(zones.zone_of(GeoPoint::new(0.5, 5.5)), Some(ZoneId(1)));
+        assert_eq!(zones.zone_of(GeoPoint::new(2.0, 7.0)), None, "inside the hole");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("taz.geojson");
+        std::fs::write(&path, TAZ).unwrap();
+        assert_eq!(ZoneSet::load_geojson(&path, "TAZ").unwrap().zones(), zones.zones());
+    }
+
+    #[test]
+    fn malformed_collections_are_errors() {
+        let err = |text: &str, property: &str| match ZoneSet::from_geojson_str(text, property) {
+            Err(SpatialError::GeoJson(msg)) => msg,
+            other => panic!("expected a GeoJSON error, got {other:?}"),
+        };
+        assert!(err(TAZ, "ZONE").contains("feature 0: no \"ZONE\" property"));
+        assert!(err(r#"{"type": "Feature"}"#, "TAZ").contains("FeatureCollection"));
+        assert!(!err("{", "TAZ").is_empty());
+        let line = r#"{"features": [{"properties": {"TAZ": 1},
+            "geometry": {"type": "LineString", "coordinates": [[0, 0], [1, 1]]}}]}"#;
+        assert!(err(line, "TAZ").contains("LineString is not a polygon"));
+    }
+}
+
+// ── Shapefile loading ─────────────────────────────────────────────────────────
+
+#[cfg(all(test, feature = "gis"))]
+mod shapefile {
+    use std::path::{Path, PathBuf};
+
+    use dt_core::{GeoPoint, NodeId};
+    use crate::gis::{load_from_shapefile, FieldMapping};
+    use crate::{RoadNetwork, SpatialError};
+
+    /// Write `lines` (each a list of `(lon, lat)` vertices) as a PolyLine
+    /// shapefile with one character column per entry of `columns`.
+    fn write(dir: &Path, lines: &[&[(f64, f64)]], columns: &[(&str, &[&str])]) -> PathBuf {
+        let mut records = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let mut content = Vec::new();
+            content.extend(3i32.to_le_bytes());
+            content.extend([0u8; 32]);
+            content.extend(1i32.to_le_bytes());
+            content.extend((line.len() as i32).to_le_bytes());
+            content.extend(0i32.to_le_bytes());
+            for &(x, y) in *line {
+                content.extend(x.to_le_bytes());
+                content.extend(y.to_le_bytes());
+            }
+            records.extend((i as i32 + 1).to_be_bytes());
+            records.extend((content.len() as i32 / 2).to_be_bytes());
+            records.extend(content);
+        }
+        let mut shp = vec![0u8; 100];
+        shp[..4].copy_from_slice(&9994i32.to_be_bytes());
+        shp[24..28].copy_from_slice((((100 + records.len()) / 2) as i32).to_be_bytes().as_slice());
+        shp[28..32].copy_from_slice(&1000i32.to_le_bytes());
+        shp[32..36].copy_from_slice(&3i32.to_le_bytes());
+        shp.extend(records);
+
+        const WIDTH: usize = 8;
+        let mut dbf = vec![0u8; 32];
+        dbf[0] = 3;
+        dbf[4..8].copy_from_slice(&(lines.len() as u32).to_le_bytes());
+        dbf[8..10].copy_from_slice(&((33 + 32 * columns.len()) as u16).to_le_bytes());
+        dbf[10..12].copy_from_slice(&((1 + WIDTH * columns.len()) as u16).to_le_bytes());
+        for (name, _) in columns {
+            let mut field = [0u8; 32];
+            field[..name.len()].copy_from_slice(name.as_bytes());
+            field[11] = b'C';
+            field[16] = WIDTH as u8;
+            dbf.extend(field);
+        }
+        dbf.push(0x0D);
+        for row in 0..lines.len() {
+            dbf.push(b' ');
+            for (_, values) in columns {
+                dbf.extend(format!("{:<WIDTH$}", values[row]).bytes());
+            }
+        }
+
+        let path = dir.join("roads.shp");
+        std::fs::write(&path, shp).unwrap();
+        std::fs::write(path.with_extension("dbf"), dbf).unwrap();
+        path
+    }
+
+    fn has_edge(net: &RoadNetwork, from: NodeId, to: NodeId) -> bool {
+        net.out_edges(from).any(|e| net.edge_to[e.index()] == to)
+    }
+
+    #[test]
+    fn lines_meeting_at_a_vertex_share_a_node() {
+        let dir = tempfile::tempdir().unwrap();
+        let a: &[(f64, f64)] = &[(-88.040, 30.690), (-88.039, 30.690), (-88.038, 30.690)];
+        let b: &[(f64, f64)] = &[(-88.038, 30.690), (-88.038, 30.691)];
+        let path = write(dir.path(), &[a, b], &[]);
+
+        let net = load_from_shapefile(&path, &FieldMapping::default()).unwrap();
+        assert_eq!(net.node_count(), 4);
+        assert_eq!(net.edge_count(), 6);
+        assert_eq!(net.node_pos[0], GeoPoint::new(30.690, -88.040));
+        // Default speed: 8.9 m/s.
+        let e = net.out_edges(NodeId(0)).next().unwrap().index();
+        let expected = net.edge_length_m[e] / 8.9 * 1_000.0;
+        assert!((net.edge_travel_ms[e] as f32 - expected).abs() <= 1.0);
+    }
+
+    #[test]
+    fn speed_and_oneway_columns_are_applied() {
+        let dir = tempfile::tempdir().unwrap();
+        let a: &[(f64, f64)] = &[(-88.040, 30.690), (-88.039, 30.690)];
+        let b: &[(f64, f64)] = &[(-88.039, 30.690), (-88.038, 30.690)];
+        let c: &[(f64, f64)] = &[(-88.038, 30.690), (-88.037, 30.690)];
+        let path = write(
+            dir.path(),
+            &[a, b, c],
+            &[("SPEED_MPH", &["30", "", "45"]), ("ONEWAY", &["FT", "TF", "B"])],
+        );
+        let fields = FieldMapping {
+            speed: Some("speed_mph".into()),
+            speed_to_mps: 0.447_04,
+            oneway: Some("OneWay".into()),
+            ..FieldMapping::default()
+        };
+        let net = load_from_shapefile(&path, &fields).unwrap();
+        let [n0, n1, n2, n3] = [0, 1, 2, 3].map(NodeId);
+
+        assert!(has_edge(&net, n0, n1) && !has_edge(&net, n1, n0));
+        assert!(has_edge(&net, n2, n1) && !has_edge(&net, n1, n2));
+        assert!(has_edge(&net, n2, n3) && has_edge(&net, n3, n2));
+
+        let speed = |from: NodeId, to: NodeId| {
+            let e = net.out_edges(from).find(|e| net.edge_to[e.index()] == to).unwrap().index();
+            net.edge_length_m[e] / net.edge_travel_ms[e] as f32 * 1_000.0
+        };
+        assert!((speed(n0, n1) - 30.0 * 0.447_04).abs() < 0.05);
+        // Blank speed falls back to the default.
+        assert!((speed(n2, n1) - 8.9).abs() < 0.05);
+        assert!((speed(n3, n2) - 45.0 * 0.447_04).abs() < 0.05);
+    }
+
+    #[test]
+    fn bad_files_and_missing_columns_are_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let a: &[(f64, f64)] = &[(-88.040, 30.690), (-88.039, 30.690)];
+        let path = write(dir.path(), &[a], &[("SPEED", &["50"])]);
+
+        let fields = FieldMapping { oneway: Some("DIR".into()), ..FieldMapping::default() };
+        assert!(matches!(load_from_shapefile(&path, &fields), Err(SpatialError::Shapefile(_))));
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 8]).unwrap();
+        assert!(matches!(load_from_shapefile(&path, &FieldMapping::default()), Err(SpatialError::Shapefile(_))));
+        std::fs::write(&path, b"not a shapefile at all").unwrap();
+        assert!(matches!(load_from_shapefile(&path, &FieldMapping::default()), Err(SpatialError::Shapefile(_))));
+        assert!(matches!(
+            load_from_shapefile(&dir.path().join("missing.shp"), &FieldMapping::default()),
+            Err(SpatialError::Io(_))
+        ));
     }
 }
@@ -0,0 +1,382 @@
+//! Timetabled public transport routing.
+//!
+//! [`TransitRouter`] answers `TransportMode::Transit` queries over a set of
+//! [`TransitLine`]s: fixed sequences of stops at road nodes, run at a
+//! constant headway within a daily service window.  A trip walks to a stop
+//! over the road graph, waits for the next departure, rides — changing lines
+//! at shared stops as needed — and walks on from the last stop, or walks the
+//! whole way if that is faster.
+//!
+//! The search is a time-dependent Dijkstra over the road nodes plus one
+//! "on board" state per (line, stop).  Waiting for a scheduled departure
+//! never lets a later start arrive sooner, so the result is exact.
+//!
+//! Routes list the walked edges and, for each ride, the car shortest path
+//! between consecutive stops (computed once, when the router is built), so
+//! progress interpolation and trip distances work as for other modes.
+//! `total_travel_ms` includes waiting.  [`Route::rides`] names the vehicle
+//! of each ride as a [`TransitVehicle`] and the edges it covers, so agents
+//! on the same bus can be found.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let line = TransitLine::new("1", 600)           // every 10 minutes
+//!     .service(6 * 3600, 22 * 3600)
+//!     .stop(depot, 0)
+//!     .stop(market, 180)
+//!     .stop(station, 240);
+//! let router = TransitRouter::new(&network, vec![line.reversed(), line])?;
+//! let route = router.route_at(&network, home, work, TransportMode::Transit, now, tick_secs)?;
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use dt_core::{EdgeId, NodeId, Tick, TransitVehicle, TransportMode};
+
+use crate::network::RoadNetwork;
+use crate::profile::{time_of_day, SECS_PER_DAY};
+use crate::router::{edge_cost_ms, DijkstraRouter, Route, Router};
+use crate::{SpatialError, SpatialResult};
+
+/// Absent state / edge marker.
+const NONE: u32 = u32::MAX;
+
+const MS_PER_DAY: u64 = SECS_PER_DAY as u64 * 1000;
+
+// ── TransitLine ───────────────────────────────────────────────────────────────
+
+/// One direction of a scheduled line.
+///
+/// Vehicles leave the first stop every `headway_secs` from
+/// `first_departure_secs` to `last_departure_secs` (seconds after midnight,
+/// every day) and reach each later stop `hop_secs` after the one before.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitLine {
+    pub name:                 String,
+    /// Road nodes served, in order.
+    pub stops:                Vec<NodeId>,
+    /// In-vehicle seconds from each stop to the next; one fewer than `stops`.
+    pub hop_secs:             Vec<u32>,
+    pub headway_secs:         u32,
+    pub first_departure_secs: u32,
+    pub last_departure_secs:  u32,
+}
+
+impl TransitLine {
+    /// A line with no stops yet, leaving every `headway_secs` around the clock.
+    pub fn new(name: impl Into<String>, headway_secs: u32) -> Self {
+        Self {
+            name:                 name.into(),
+            stops:                Vec::new(),
+            hop_secs:             Vec::new(),
+            headway_secs,
+            first_departure_secs: 0,
+            last_departure_secs:  SECS_PER_DAY - 1,
+        }
+    }
+
+    /// Append a stop reached `secs` after the previous one (ignored for the
+    /// first stop).
+    pub fn stop(mut self, node: NodeId, secs: u32) -> Self {
+        if !self.stops.is_empty() {
+            self.hop_secs.push(secs);
+        }
+        self.stops.push(node);
+        self
+    }
+
+    /// Run from the first stop only between `first_secs` and `last_secs`
+    /// after midnight.
+    pub fn service(mut self, first_secs: u32, last_secs: u32) -> Self {
+        self.first_departure_secs = first_secs;
+        self.last_departure_secs  = last_secs;
+        self
+    }
+
+    /// The same line run in the opposite direction on the same timetable.
+    pub fn reversed(&self) -> Self {
+        Self {
+            stops:    self.stops.iter().rev().copied().collect(),
+            hop_secs: self.hop_secs.iter().rev().copied().collect(),
+            ..self.clone()
+        }
+    }
+
+    fn validate(&self, network: &RoadNetwork) -> SpatialResult<()> {
+        let invalid = |msg: &str| Err(SpatialError::Transit(format!("line {:?} {msg}", self.name)));
+        if self.stops.len() < 2 {
+            return invalid("needs at least two stops");
+        }
+        if self.hop_secs.len() != self.stops.len() - 1 {
+            return invalid("needs one hop time per pair of consecutive stops");
+        }
+        if self.headway_secs == 0 {
+            return invalid("has a zero headway");
+        }
+        if self.first_departure_secs > self.last_departure_secs || self.last_departure_secs >= SECS_PER_DAY {
+            return invalid("has a service window outside one day");
+        }
+        match self.stops.iter().find(|s| s.index() >= network.node_count()) {
+            Some(&stop) => Err(SpatialError::NodeNotFound(stop)),
+            None => Ok(()),
+        }
+    }
+}
+
+// ── TransitRide ───────────────────────────────────────────────────────────────
+
+/// One ride on a scheduled vehicle within a transit [`Route`]: the vehicle
+/// covers `route.edges[first_edge..end_edge]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransitRide {
+    pub vehicle:    TransitVehicle,
+    /// Index in [`Route::edges`] of the ride's first edge.
+    pub first_edge: u32,
+    /// One past the index of the ride's last edge.
+    pub end_edge:   u32,
+}
+
+impl TransitRide {
+    /// `true` if the edge at `index` in the route is covered by this ride.
+    pub fn covers(&self, index: usize) -> bool {
+        (self.first_edge as usize..self.end_edge as usize).contains(&index)
+    }
+}
+
+// ── TransitRouter ─────────────────────────────────────────────────────────────
+
+/// [`Router`] for `TransportMode::Transit` over timetabled lines plus
+/// walking.  See the module docs.
+///
+/// Other modes are routed by [`DijkstraRouter`], as are queries against a
+/// network with a different node count from the one the router was built
+/// for.  Tick 0 is midnight unless set with
+/// [`with_start_unix_secs`](Self::with_start_unix_secs); plain
+/// [`route`](Router::route) calls depart at tick 0.
+pub struct TransitRouter {
+    lines:             Vec<TransitLine>,
+    node_count:        usize,
+    start_secs_of_day: u32,
+    /// Line and stop index of each on-board state.  On-board states are
+    /// numbered after the road nodes, each line's stops consecutively.
+    state_stop:        Vec<(u32, u32)>,
+    /// Seconds from the first stop's departure to each state's departure.
+    state_offset_secs: Vec<u32>,
+    /// Car path from each state's stop to the next stop of its line.
+    hop_edges:         Vec<Vec<EdgeId>>,
+    /// On-board states that can be boarded at each road node.
+    boardings:         HashMap<NodeId, Vec<u32>>,
+}
+
+impl TransitRouter {
+    /// Index `lines` for routing over `network`.
+    ///
+    /// Fails with [`SpatialError::NodeNotFound`] for a stop outside the
+    /// network and [`SpatialError::Transit`] for a malformed line.
+    pub fn new(network: &RoadNetwork, lines: Vec<TransitLine>) -> SpatialResult<Self> {
+        let mut router = Self {
+            lines:             Vec::new(),
+            node_count:        network.node_count(),
+            start_secs_of_day: 0,
+            state_stop:        Vec::new(),
+            state_offset_secs: Vec::new(),
+            hop_edges:         Vec::new(),
+            boardings:         HashMap::new(),
+        };
+        for (l, line) in lines.iter().enumerate() {
+            line.validate(network)?;
+            let mut offset = 0u32;
+            for (i, &stop) in line.stops.iter().enumerate() {
+                let state = (network.node_count() + router.state_stop.len()) as u32;
+                router.state_stop.push((l as u32, i as u32));
+                router.state_offset_secs.push(offset);
+                let Some(&hop) = line.hop_secs.get(i) else {
+                    router.hop_edges.push(Vec::new());
+                    break;
+                };
+                offset = offset.saturating_add(hop);
+                router.boardings.entry(stop).or_default().push(state);
+                let path = DijkstraRouter.route(network, stop, line.stops[i + 1], TransportMode::Car);
+                router.hop_edges.push(path.map(|r| r.edges).unwrap_or_default());
+            }
+        }
+        router.lines = lines;
+        Ok(router)
+    }
+
+    /// Take the time of day from the sim's wall clock: tick 0 is at
+    /// `start_unix_secs`, read as UTC.
+    pub fn with_start_unix_secs(mut self, start_unix_secs: i64) -> Self {
+        self.start_secs_of_day = start_unix_secs.rem_euclid(SECS_PER_DAY as i64) as u32;
+        self
+    }
+
+    pub fn lines(&self) -> &[TransitLine] {
+        &self.lines
+    }
+
+    /// Earliest departure of on-board state `state` (index past the road
+    /// nodes) at or after `at_ms`, in ms after midnight of the trip's day.
+    fn next_departure_ms(&self, state: usize, at_ms: u64) -> u64 {
+        let line = &self.lines[self.state_stop[state].0 as usize];
+        let offset = self.state_offset_secs[state] as u64 * 1000;
+        let first = line.first_departure_secs as u64 * 1000 + offset;
+        let last = line.last_departure_secs as u64 * 1000 + offset;
+        let headway = line.headway_secs as u64 * 1000;
+        // Late services from the day before can still be running, so shift
+        // by a day to give the trip's first day a predecessor.
+        let at = at_ms + MS_PER_DAY;
+        let day = at / MS_PER_DAY;
+        (day - 1..=day + 1)
+            .filter_map(|d| {
+                let start = d * MS_PER_DAY;
+                let dep = match at.checked_sub(start + first) {
+                    None => start + first,
+                    Some(after) => start + first + after.div_ceil(headway) * headway,
+                };
+                (dep <= start + last).then_some(dep - MS_PER_DAY)
+            })
+            .min()
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Route from `from` to `to` leaving `depart_ms` after midnight of the
+    /// trip's day, which starts `day_start_ms` after midnight of tick 0's.
+    fn search(
+        &self,
+        network:      &RoadNetwork,
+        from:         NodeId,
+        to:           NodeId,
+        depart_ms:    u64,
+        day_start_ms: u64,
+    ) -> SpatialResult<Route> {
+        let n = self.node_count;
+        let states = n + self.state_stop.len();
+        let mut search = Search {
+            dist: vec![u32::MAX; states],
+            prev: vec![(NONE, NONE); states],
+            heap: BinaryHeap::new(),
+        };
+        relax(&mut search, from.index(), 0, (NONE, NONE));
+
+        while let Some(Reverse((cost, s))) = search.heap.pop() {
+            if s == to.0 {
+                return Ok(self.reconstruct(&search, to, cost, depart_ms, day_start_ms));
+            }
+            if cost > search.dist[s as usize] {
+                continue;
+            }
+            let s_idx = s as usize;
+            if s_idx < n {
+                let node = NodeId(s);
+                for edge in network.out_edges(node) {
+                    let walk = edge_cost_ms(network, edge, TransportMode::Walk);
+                    relax(&mut search, network.edge_to[edge.index()].index(), cost.saturating_add(walk), (s, edge.0));
+                }
+                for &state in self.boardings.get(&node).into_iter().flatten() {
+                    let now = depart_ms + cost as u64;
+                    let wait = self.next_departure_ms(state as usize - n, now).saturating_sub(now);
+                    let boarded = cost.saturating_add(wait.min(u32::MAX as u64) as u32);
+                    relax(&mut search, state as usize, boarded, (s, NONE));
+                }
+            } else {
+                let (l, i) = self.state_stop[s_idx - n];
+                let line = &self.lines[l as usize];
+                if i > 0 {
+                    relax(&mut search, line.stops[i as usize].index(), cost, (s, NONE));
+                }
+                if let Some(&hop) = line.hop_secs.get(i as usize) {
+                    relax(&mut search, s_idx + 1, cost.saturating_add(hop.saturating_mul(1000)), (s, NONE));
+                }
+            }
+        }
+        Err(SpatialError::NoRoute { from, to })
+    }
+
+    fn reconstruct(&self, search: &Search, to: NodeId, total_ms: u32, depart_ms: u64, day_start_ms: u64) -> Route {
+        let n = self.node_count as u32;
+        let mut steps = Vec::new();
+        let mut state = to.0;
+        while search.prev[state as usize].0 != NONE {
+            let (before, edge) = search.prev[state as usize];
+            steps.push((before, state, edge));
+            state = before;
+        }
+        let mut edges = Vec::new();
+        let mut rides = Vec::new();
+        let mut boarded = None;
+        for (before, after, edge) in steps.into_iter().rev() {
+            if edge != NONE {
+                edges.push(EdgeId(edge));
+            } else if before < n {
+                // Boarding: the on-board state's cost is the departure from this stop.
+                let on_board = (after - n) as usize;
+                let leaves_ms = day_start_ms + depart_ms + search.dist[after as usize] as u64;
+                let vehicle = TransitVehicle {
+                    line:         self.state_stop[on_board].0,
+                    departure_ms: leaves_ms as i64 - self.state_offset_secs[on_board] as i64 * 1000,
+                };
+                boarded = Some((vehicle, edges.len() as u32));
+            } else if after >= n {
+                edges.extend_from_slice(&self.hop_edges[(before - n) as usize]);
+            } else if let Some((vehicle, first_edge)) = boarded.take() {
+                let end_edge = edges.len() as u32;
+                if end_edge > first_edge {
+                    rides.push(TransitRide { vehicle, first_edge, end_edge });
+                }
+            }
+        }
+        Route { edges, total_travel_ms: total_ms as u64, rides }
+    }
+}
+
+impl Router for TransitRouter {
+    fn route(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+    ) -> Result<Route, SpatialError> {
+        self.route_at(network, from, to, mode, Tick::ZERO, 1)
+    }
+
+    fn route_at(
+        &self,
+        network: &RoadNetwork,
+        from: NodeId,
+        to: NodeId,
+        mode: TransportMode,
+        departure: Tick,
+        tick_duration_secs: u32,
+    ) -> Result<Route, SpatialError> {
+        if mode != TransportMode::Transit || network.node_count() != self.node_count {
+            return DijkstraRouter.route(network, from, to, mode);
+        }
+        if from == to {
+            return Ok(Route { edges: vec![], total_travel_ms: 0, rides: Vec::new() });
+        }
+        let depart_secs = time_of_day(self.start_secs_of_day, departure, tick_duration_secs);
+        let days = (self.start_secs_of_day as u64 + departure.0 * tick_duration_secs as u64) / SECS_PER_DAY as u64;
+        self.search(network, from, to, depart_secs as u64 * 1000, days * MS_PER_DAY)
+    }
+}
+
+// ── Search internals ──────────────────────────────────────────────────────────
+
+struct Search {
+    dist: Vec<u32>,
+    /// (previous state, walked edge or `NONE`) that reached each state.
+    prev: Vec<(u32, u32)>,
+    heap: BinaryHeap<Reverse<(u32, u32)>>,
+}
+
+fn relax(search: &mut Search, state: usize, cost: u32, step: (u32, u32)) {
+    if cost < search.dist[state] {
+        search.dist[state] = cost;
+        search.prev[state] = step;
+        search.heap.push(Reverse((cost, state as u32)));
+    }
+}
@@ -0,0 +1,281 @@
+//! Traffic-analysis zones.
+//!
+//! Output aggregation and destination-choice models work in zones (TAZs)
+//! rather than nodes.  A [`ZoneSet`] holds each zone's polygons in an
+//! R-tree of their bounding boxes, answers [`zone_of`](ZoneSet::zone_of)
+//! for any point, and assigns every node of a network to its zone in one
+//! pass.
+//!
+//! Zones are usually published as GeoJSON; with the `geojson` feature,
+//! `ZoneSet::load_geojson` reads `Polygon` and `MultiPolygon` features
+//! and names each zone by one of its properties.
+//!
+//! # Example
+//!
+//! ```
+//! use dt_core::{GeoPoint, ZoneId};
+//! use dt_spatial::zones::{Polygon, Zone, ZoneSet};
+//!
+//! let square = |lat: f32, lon: f32| Polygon::new(vec![
+//!     GeoPoint::new(lat, lon),
+//!     GeoPoint::new(lat, lon + 1.0),
+//!     GeoPoint::new(lat + 1.0, lon + 1.0),
+//!     GeoPoint::new(lat + 1.0, lon),
+//! ]);
+//! let zones = ZoneSet::new(vec![
+//!     Zone::new("downtown", vec![square(0.0, 0.0)]),
+//!     Zone::new("midtown", vec![square(0.0, 1.0)]),
+//! ]);
+//! assert_eq!(zones.zone_of(GeoPoint::new(0.5, 1.5)), Some(ZoneId(1)));
+//! assert_eq!(zones.zone_of(GeoPoint::new(5.0, 5.0)), None);
+//! ```
+
+use std::fmt;
+
+use rstar::{RTree, RTreeObject, AABB};
+
+use dt_core::{BBox, GeoPoint, ZoneId};
+
+use crate::network::RoadNetwork;
+
+// ── Polygon & Zone ────────────────────────────────────────────────────────────
+
+/// A polygon: an exterior ring and any holes.  Rings may be open or closed
+/// (first point repeated) and wound either way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
+    pub exterior: Vec<GeoPoint>,
+    pub holes:    Vec<Vec<GeoPoint>>,
+}
+
+impl Polygon {
+    /// A polygon without holes.
+    pub fn new(exterior: Vec<GeoPoint>) -> Self {
+        Self { exterior, holes: Vec::new() }
+    }
+
+    /// `true` if `pos` is inside the exterior ring and outside every hole.
+    /// Points exactly on an edge may fall either side.
+    pub fn contains(&self, pos: GeoPoint) -> bool {
+        ring_contains(&self.exterior, pos) && !self.holes.iter().any(|h| ring_contains(h, pos))
+    }
+
+    /// Bounding box of the exterior ring, or `None` if it has no points.
+    pub fn bbox(&self) -> Option<BBox> {
+        BBox::from_points(self.exterior.iter().copied())
+    }
+}
+
+/// One zone: a name and the polygons that make it up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zone {
+    pub name:     String,
+    pub polygons: Vec<Polygon>,
+}
+
+impl Zone {
+    pub fn new(name: impl Into<String>, polygons: Vec<Polygon>) -> Self {
+        Self { name: name.into(), polygons }
+    }
+
+    /// `true` if any of the zone's polygons contains `pos`.
+    pub fn contains(&self, pos: GeoPoint) -> bool {
+        self.polygons.iter().any(|p| p.contains(pos))
+    }
+}
+
+/// Even-odd ray casting along increasing longitude.
+fn ring_contains(ring: &[GeoPoint], pos: GeoPoint) -> bool {
+    let mut inside = false;
+    let mut prev = match ring.last() {
+        Some(&p) => p,
+        None => return false,
+    };
+    for &p in ring {
+        if (p.lat > pos.lat) != (prev.lat > pos.lat) {
+            let lon = p.lon + (pos.lat - p.lat) / (prev.lat - p.lat) * (prev.lon - p.lon);
+            if pos.lon < lon {
+                inside = !inside;
+            }
+        }
+        prev = p;
+    }
+    inside
+}
+
+// ── ZoneSet ───────────────────────────────────────────────────────────────────
+
+/// R-tree entry: the bounding box of one polygon of a zone.
+struct PolygonEntry {
+    envelope: AABB<[f32; 2]>, // [lat, lon]
+    zone:     ZoneId,
+    polygon:  u32,
+}
+
+impl RTreeObject for PolygonEntry {
+    type Envelope = AABB<[f32; 2]>;
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+/// A set of zones with a spatial index over their polygons.  Zone `i` is
+/// `ZoneId(i)`.
+pub struct ZoneSet {
+    zones: Vec<Zone>,
+    index: RTree<PolygonEntry>,
+}
+
+impl ZoneSet {
+    /// Index `zones`; zone `i` gets `ZoneId(i)`.
+    pub fn new(zones: Vec<Zone>) -> Self {
+        let entries = zones
+            .iter()
+            .enumerate()
+            .flat_map(|(z, zone)| {
+                zone.polygons.iter().enumerate().filter_map(move |(p, polygon)| {
+                    let bbox = polygon.bbox()?;
+                    Some(PolygonEntry {
+                        envelope: AABB::from_corners([bbox.south, bbox.west], [bbox.north, bbox.east]),
+                        zone:     ZoneId(z as u32),
+                        polygon:  p as u32,
+                    })
+                })
+            })
+            .collect();
+        Self { zones, index: RTree::bulk_load(entries) }
+    }
+
+    pub fn len(&self) -> usize {
+        self.zones.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.zones.is_empty()
+    }
+
+    /// The zone with id `id`.  Panics if it is out of range.
+    pub fn zone(&self, id: ZoneId) -> &Zone {
+        &self.zones[id.index()]
+    }
+
+    /// All zones in `ZoneId` order.
+    pub fn zones(&self) -> &[Zone] {
+        &self.zones
+    }
+
+    /// The first zone named `name`.
+    pub fn find(&self, name: &str) -> Option<ZoneId> {
+        self.zones.iter().position(|z| z.name == name).map(|i| ZoneId(i as u32))
+    }
+
+    /// The zone containing `pos`, or `None` if it is in none.  Where zones
+    /// overlap the lowest `ZoneId` wins.
+    pub fn zone_of(&self, pos: GeoPoint) -> Option<ZoneId> {
+        self.index
+            .locate_in_envelope_intersecting(&AABB::from_point([pos.lat, pos.lon]))
+            .filter(|e| self.zones[e.zone.index()].polygons[e.polygon as usize].contains(pos))
+            .map(|e| e.zone)
+            .min()
+    }
+
+    /// The zone of every node of `network`, indexed by `NodeId`.
+    pub fn assign_nodes(&self, network: &RoadNetwork) -> Vec<Option<ZoneId>> {
+        network.node_pos.iter().map(|&pos| self.zone_of(pos)).collect()
+    }
+}
+
+impl fmt::Debug for ZoneSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZoneSet").field("zones", &self.zones.len()).finish()
+    }
+}
+
+// ── GeoJSON ───────────────────────────────────────────────────────────────────
+
+#[cfg(feature = "geojson")]
+mod geojson {
+    use std::path::Path;
+
+    use serde_json::Value;
+
+    use dt_core::GeoPoint;
+
+    use super::{Polygon, Zone, ZoneSet};
+    use crate::SpatialError;
+
+    fn invalid(msg: impl Into<String>) -> SpatialError {
+        SpatialError::GeoJson(msg.into())
+    }
+
+    impl ZoneSet {
+        /// Load the features of the GeoJSON `FeatureCollection` at `path`,
+        /// naming each zone by its `name_property` property (strings as is,
+        /// numbers formatted).  See [`from_geojson_str`](Self::from_geojson_str).
+        pub fn load_geojson(path: &Path, name_property: &str) -> Result<Self, SpatialError> {
+            Self::from_geojson_str(&std::fs::read_to_string(path)?, name_property)
+        }
+
+        /// Parse a GeoJSON `FeatureCollection` of `Polygon` and
+        /// `MultiPolygon` features into zones, in feature order.
+        /// Coordinates must be WGS 84 `[lon, lat]`, as GeoJSON requires.
+        ///
+        /// # Errors
+        ///
+        /// [`SpatialError::GeoJson`] if the text is not a feature
+        /// collection, a feature is not a polygon, or a feature lacks
+        /// `name_property`.
+        pub fn from_geojson_str(text: &str, name_property: &str) -> Result<Self, SpatialError> {
+            let root: Value = serde_json::from_str(text).map_err(|e| invalid(e.to_string()))?;
+            let features = root
+                .get("features")
+                .and_then(Value::as_array)
+                .ok_or_else(|| invalid("expected a FeatureCollection"))?;
+            let zones = features
+                .iter()
+                .enumerate()
+                .map(|(i, f)| zone(f, name_property).map_err(|msg| invalid(format!("feature {i}: {msg}"))))
+                .collect::<Result<_, _>>()?;
+            Ok(ZoneSet::new(zones))
+        }
+    }
+
+    fn zone(feature: &Value, name_property: &str) -> Result<Zone, String> {
+        let name = match feature.get("properties").and_then(|p| p.get(name_property)) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Number(n)) => n.to_string(),
+            _ => return Err(format!("no {name_property:?} property")),
+        };
+        let geometry = feature.get("geometry").ok_or("no geometry")?;
+        let coords = geometry.get("coordinates").ok_or("geometry has no coordinates")?;
+        let polygons = match geometry.get("type").and_then(Value::as_str) {
+            Some("Polygon") => vec![polygon(coords)?],
+            Some("MultiPolygon") => {
+                coords.as_array().ok_or("bad coordinates")?.iter().map(polygon).collect::<Result<_, _>>()?
+            }
+            other => return Err(format!("geometry type {} is not a polygon", other.unwrap_or("null"))),
+        };
+        Ok(Zone::new(name, polygons))
+    }
+
+    fn polygon(rings: &Value) -> Result<Polygon, String> {
+        let mut rings = rings.as_array().ok_or("bad coordinates")?.iter().map(ring);
+        let exterior = rings.next().ok_or("polygon has no rings")??;
+        Ok(Polygon { exterior, holes: rings.collect::<Result<_, _>>()? })
+    }
+
+    fn ring(points: &Value) -> Result<Vec<GeoPoint>, String> {
+        points
+            .as_array()
+            .ok_or("bad coordinates")?
+            .iter()
+            .map(|p| match p.as_array().map(Vec::as_slice) {
+                Some([lon, lat, ..]) => match (lon.as_f64(), lat.as_f64()) {
+                    (Some(lon), Some(lat)) => Ok(GeoPoint::new(lat as f32, lon as f32)),
+                    _ => Err("bad position".to_string()),
+                },
+                _ => Err("bad position".to_string()),
+            })
+            .collect()
+    }
+}
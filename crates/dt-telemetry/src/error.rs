@@ -0,0 +1,23 @@
+//! Error types for dt-telemetry.
+
+use dt_core::{DtError, ErrorCategory};
+use thiserror::Error;
+
+/// Errors that can occur when creating or serving metrics.
+#[derive(Debug, Error)]
+pub enum TelemetryError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Prometheus error: {0}")]
+    Prometheus(#[from] prometheus::Error),
+}
+
+/// Alias for `Result<T, TelemetryError>`.
+pub type TelemetryResult<T> = Result<T, TelemetryError>;
+
+impl From<TelemetryError> for DtError {
+    fn from(err: TelemetryError) -> Self {
+        DtError::subsystem(ErrorCategory::Output, err)
+    }
+}
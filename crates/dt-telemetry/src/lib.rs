@@ -0,0 +1,50 @@
+//! `dt-telemetry` — standard observability for long-running simulations.
+//!
+//! [`MetricsObserver`] wraps the run's observer and records Prometheus
+//! metrics ([`SimMetrics`]): tick rate, tick and per-phase wall time, woken
+//! and travelling agents, departures, arrivals, failures, time spent
+//! writing output, and resident memory.  [`MetricsServer`] serves them for
+//! Prometheus to scrape:
+//!
+//! ```rust,ignore
+//! let output = SimOutputObserver::new(writer, &config);
+//! let mut obs = MetricsObserver::new(output, &config)?.serve("0.0.0.0:9898")?;
+//! sim.run(&mut obs)?;
+//! let output = obs.into_inner();
+//! ```
+//!
+//! Phase timings come from the tick loop itself (see
+//! [`PhaseTimings`][dt_sim::PhaseTimings]).  With the `otel` feature,
+//! [`MetricsObserver::with_tracer`] also records one OpenTelemetry span per
+//! tick (see [`otel`]).
+//!
+//! # Crate layout
+//!
+//! | Module       | Contents                                         |
+//! |--------------|--------------------------------------------------|
+//! | [`metrics`]  | `SimMetrics`: the metrics and their registry     |
+//! | [`observer`] | `MetricsObserver`: sim callbacks to metrics      |
+//! | [`server`]   | `MetricsServer`: `GET /metrics` over HTTP        |
+//! | `otel`       | per-tick spans (feature `otel`)                  |
+//! | [`error`]    | `TelemetryError`, `TelemetryResult<T>`           |
+//!
+//! # Feature flags
+//!
+//! | Feature | Enables                                                    |
+//! |---------|------------------------------------------------------------|
+//! | `otel`  | `MetricsObserver::with_tracer` and the [`otel`] module     |
+
+pub mod error;
+pub mod metrics;
+pub mod observer;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod server;
+
+#[cfg(test)]
+mod tests;
+
+pub use error::{TelemetryError, TelemetryResult};
+pub use metrics::SimMetrics;
+pub use observer::MetricsObserver;
+pub use server::MetricsServer;
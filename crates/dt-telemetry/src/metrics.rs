@@ -0,0 +1,196 @@
+//! `SimMetrics` — the Prometheus metrics a run exports.
+
+use std::time::Duration;
+
+use dt_core::Tick;
+use dt_sim::{PhaseTimings, TickStats};
+use prometheus::{
+    Counter, CounterVec, Encoder, Gauge, Histogram, HistogramOpts, IntCounter, IntGauge, Opts, Registry, TextEncoder,
+};
+
+use crate::TelemetryResult;
+
+/// Metric name prefix.
+const NAMESPACE: &str = "dt";
+
+/// The metrics a [`MetricsObserver`][crate::MetricsObserver] updates,
+/// registered in one [`Registry`].
+///
+/// | Metric                          | Type      | Meaning                                                 |
+/// |---------------------------------|-----------|---------------------------------------------------------|
+/// | `dt_ticks_total`                | counter   | ticks processed                                         |
+/// | `dt_skipped_ticks_total`        | counter   | idle ticks jumped over                                  |
+/// | `dt_tick`                       | gauge     | last tick processed                                     |
+/// | `dt_run_ticks`                  | gauge     | ticks in the run (`config.total_ticks`)                 |
+/// | `dt_tick_rate`                  | gauge     | ticks per wall-clock second, over the last second or so |
+/// | `dt_tick_duration_seconds`      | histogram | wall time per tick, excluding observers                 |
+/// | `dt_phase_seconds_total{phase}` | counter   | wall time per phase (see [`PhaseTimings::phases`])      |
+/// | `dt_woken_agents`               | gauge     | agents woken in the last tick                           |
+/// | `dt_woken_agents_total`         | counter   | agents woken                                            |
+/// | `dt_agents_in_transit`          | gauge     | agents travelling at the end of the last tick           |
+/// | `dt_departures_total`           | counter   | journeys started                                        |
+/// | `dt_arrivals_total`             | counter   | journeys completed                                      |
+/// | `dt_routing_failures_total`     | counter   | `TravelTo` intents that failed to route                 |
+/// | `dt_behavior_panics_total`      | counter   | behavior callbacks that panicked                        |
+/// | `dt_output_seconds_total`       | counter   | wall time spent in the wrapped observer                 |
+/// | `dt_output_lag_seconds`         | gauge     | wall time spent in the wrapped observer last tick       |
+/// | `dt_resident_memory_bytes`      | gauge     | resident memory of the process                          |
+///
+/// Handles are cheap to clone and share their values, so the same metrics
+/// can be served by a [`MetricsServer`][crate::MetricsServer] while the run
+/// updates them.
+#[derive(Clone)]
+pub struct SimMetrics {
+    registry:         Registry,
+    ticks:            IntCounter,
+    skipped_ticks:    IntCounter,
+    tick:             IntGauge,
+    run_ticks:        IntGauge,
+    tick_rate:        Gauge,
+    tick_duration:    Histogram,
+    phase_seconds:    CounterVec,
+    woken:            IntGauge,
+    woken_total:      IntCounter,
+    in_transit:       IntGauge,
+    departures:       IntCounter,
+    arrivals:         IntCounter,
+    routing_failures: IntCounter,
+    behavior_panics:  IntCounter,
+    output_seconds:   Counter,
+    output_lag:       Gauge,
+    resident_memory:  IntGauge,
+}
+
+impl SimMetrics {
+    /// Create the metrics in a new registry.
+    pub fn new() -> TelemetryResult<Self> {
+        Self::in_registry(Registry::new())
+    }
+
+    /// Create the metrics and register them in `registry`, e.g. one that
+    /// already holds an application's own metrics.  Fails if `registry`
+    /// already has metrics of the same names.
+    pub fn in_registry(registry: Registry) -> TelemetryResult<Self> {
+        let opts = |name: &str, help: &str| Opts::new(name, help).namespace(NAMESPACE);
+        let tick_buckets = prometheus::exponential_buckets(1e-5, 4.0, 12)?;
+        let metrics = Self {
+            ticks:            IntCounter::with_opts(opts("ticks_total", "Ticks processed."))?,
+            skipped_ticks:    IntCounter::with_opts(opts("skipped_ticks_total", "Idle ticks jumped over."))?,
+            tick:             IntGauge::with_opts(opts("tick", "Last tick processed."))?,
+            run_ticks:        IntGauge::with_opts(opts("run_ticks", "Ticks in the run."))?,
+            tick_rate:        Gauge::with_opts(opts("tick_rate", "Ticks processed per wall-clock second."))?,
+            tick_duration:    Histogram::with_opts(
+                HistogramOpts::new("tick_duration_seconds", "Wall time per tick, excluding observers.")
+                    .namespace(NAMESPACE)
+                    .buckets(tick_buckets),
+            )?,
+            phase_seconds:    CounterVec::new(
+                opts("phase_seconds_total", "Wall time per phase of the tick loop."),
+                &["phase"],
+            )?,
+            woken:            IntGauge::with_opts(opts("woken_agents", "Agents woken in the last tick."))?,
+            woken_total:      IntCounter::with_opts(opts("woken_agents_total", "Agents woken."))?,
+            in_transit:       IntGauge::with_opts(opts("agents_in_transit", "Agents travelling."))?,
+            departures:       IntCounter::with_opts(opts("departures_total", "Journeys started."))?,
+            arrivals:         IntCounter::with_opts(opts("arrivals_total", "Journeys completed."))?,
+            routing_failures: IntCounter::with_opts(opts("routing_failures_total", "Failed routing requests."))?,
+            behavior_panics:  IntCounter::with_opts(opts("behavior_panics_total", "Behavior callbacks that panicked."))?,
+            output_seconds:   Counter::with_opts(opts("output_seconds_total", "Wall time spent writing output."))?,
+            output_lag:       Gauge::with_opts(opts("output_lag_seconds", "Wall time spent writing output last tick."))?,
+            resident_memory:  IntGauge::with_opts(opts("resident_memory_bytes", "Resident memory of the process."))?,
+            registry,
+        };
+        metrics.register()?;
+        // Show every phase from the first scrape, even those that never run.
+        for (phase, _) in PhaseTimings::default().phases() {
+            metrics.phase_seconds.with_label_values(&[phase]);
+        }
+        Ok(metrics)
+    }
+
+    fn register(&self) -> TelemetryResult<()> {
+        let r = &self.registry;
+        r.register(Box::new(self.ticks.clone()))?;
+        r.register(Box::new(self.skipped_ticks.clone()))?;
+        r.register(Box::new(self.tick.clone()))?;
+        r.register(Box::new(self.run_ticks.clone()))?;
+        r.register(Box::new(self.tick_rate.clone()))?;
+        r.register(Box::new(self.tick_duration.clone()))?;
+        r.register(Box::new(self.phase_seconds.clone()))?;
+        r.register(Box::new(self.woken.clone()))?;
+        r.register(Box::new(self.woken_total.clone()))?;
+        r.register(Box::new(self.in_transit.clone()))?;
+        r.register(Box::new(self.departures.clone()))?;
+        r.register(Box::new(self.arrivals.clone()))?;
+        r.register(Box::new(self.routing_failures.clone()))?;
+        r.register(Box::new(self.behavior_panics.clone()))?;
+        r.register(Box::new(self.output_seconds.clone()))?;
+        r.register(Box::new(self.output_lag.clone()))?;
+        r.register(Box::new(self.resident_memory.clone()))?;
+        Ok(())
+    }
+
+    /// The registry holding the metrics.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// The registry's metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        render(&self.registry)
+    }
+
+    // ── Updates ───────────────────────────────────────────────────────────
+
+    pub(crate) fn record_stats(&self, tick: Tick, stats: &TickStats) {
+        self.ticks.inc();
+        self.tick.set(tick.0 as i64);
+        self.woken.set(stats.woken as i64);
+        self.woken_total.inc_by(stats.woken);
+        self.in_transit.set(stats.in_transit as i64);
+        self.departures.inc_by(stats.departures);
+        self.arrivals.inc_by(stats.arrivals);
+        self.routing_failures.inc_by(stats.routing_failures);
+        self.behavior_panics.inc_by(stats.behavior_panics);
+    }
+
+    pub(crate) fn record_timings(&self, timings: &PhaseTimings) {
+        self.tick_duration.observe(timings.total.as_secs_f64());
+        for (phase, duration) in timings.phases() {
+            self.phase_seconds.with_label_values(&[phase]).inc_by(duration.as_secs_f64());
+        }
+    }
+
+    pub(crate) fn record_skipped(&self, ticks: u64) {
+        self.skipped_ticks.inc_by(ticks);
+    }
+
+    pub(crate) fn record_output(&self, time: Duration) {
+        self.output_seconds.inc_by(time.as_secs_f64());
+    }
+
+    pub(crate) fn set_output_lag(&self, time: Duration) {
+        self.output_lag.set(time.as_secs_f64());
+    }
+
+    pub(crate) fn set_run_ticks(&self, ticks: u64) {
+        self.run_ticks.set(ticks as i64);
+    }
+
+    pub(crate) fn set_tick_rate(&self, rate: f64) {
+        self.tick_rate.set(rate);
+    }
+
+    pub(crate) fn set_resident_memory(&self, bytes: usize) {
+        self.resident_memory.set(bytes as i64);
+    }
+}
+
+/// `registry`'s metrics in the Prometheus text exposition format.
+pub(crate) fn render(registry: &Registry) -> String {
+    let mut buf = Vec::new();
+    // Encoding into a `Vec` only fails on malformed metric families, which
+    // the registry never produces.
+    let _ = TextEncoder::new().encode(&registry.gather(), &mut buf);
+    String::from_utf8(buf).unwrap_or_default()
+}
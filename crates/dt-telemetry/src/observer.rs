@@ -0,0 +1,213 @@
+//! `MetricsObserver` — updates [`SimMetrics`] as a run proceeds.
+
+use std::net::ToSocketAddrs;
+use std::time::{Duration, Instant};
+
+use dt_agent::AgentStore;
+use dt_core::{AgentId, NodeId, SimConfig, Tick};
+use dt_mobility::{MobilityStore, MovementState, Trip};
+use dt_sim::{PhaseTimings, SimObserver, TickMetrics, TickStats, TraceEvent};
+use dt_spatial::Route;
+
+use crate::{MetricsServer, SimMetrics, TelemetryResult};
+
+/// Shortest window the tick rate is averaged over.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// How often resident memory is read.
+const MEMORY_POLL: Duration = Duration::from_secs(1);
+
+/// A [`SimObserver`] that records [`SimMetrics`] and forwards every
+/// callback to `inner` (typically the output observer).
+///
+/// Time spent in `inner` is the run's output lag: the sim is blocked while
+/// output is written, so it is reported as `dt_output_seconds_total` and,
+/// for the last tick, `dt_output_lag_seconds`.  Use
+/// [`NoopObserver`][dt_sim::NoopObserver] as `inner` when there is no
+/// other observer.
+pub struct MetricsObserver<O> {
+    inner:       O,
+    metrics:     SimMetrics,
+    server:      Option<MetricsServer>,
+    /// Time spent in `inner` since the tick in progress started.
+    output:      Duration,
+    rate_since:  Instant,
+    rate_ticks:  u64,
+    memory_read: Option<Instant>,
+    #[cfg(feature = "otel")]
+    spans:       Option<crate::otel::TickSpans>,
+}
+
+impl<O: SimObserver> MetricsObserver<O> {
+    /// Record metrics for a run of `config`, forwarding to `inner`.
+    pub fn new(inner: O, config: &SimConfig) -> TelemetryResult<Self> {
+        Ok(Self::with_metrics(inner, config, SimMetrics::new()?))
+    }
+
+    /// Record into `metrics`, e.g. ones created in an application's own
+    /// registry with [`SimMetrics::in_registry`].
+    pub fn with_metrics(inner: O, config: &SimConfig, metrics: SimMetrics) -> Self {
+        metrics.set_run_ticks(config.total_ticks);
+        Self {
+            inner,
+            metrics,
+            server:      None,
+            output:      Duration::ZERO,
+            rate_since:  Instant::now(),
+            rate_ticks:  0,
+            memory_read: None,
+            #[cfg(feature = "otel")]
+            spans:       None,
+        }
+    }
+
+    /// Serve the metrics on `addr` (see [`MetricsServer`]) for as long as
+    /// the observer lives.
+    pub fn serve(mut self, addr: impl ToSocketAddrs) -> TelemetryResult<Self> {
+        self.server = Some(MetricsServer::bind(addr, self.metrics.registry().clone())?);
+        Ok(self)
+    }
+
+    /// Also record one span per tick through `tracer` (see [`otel`][crate::otel]).
+    #[cfg(feature = "otel")]
+    pub fn with_tracer(mut self, tracer: opentelemetry::global::BoxedTracer) -> Self {
+        self.spans = Some(crate::otel::TickSpans::new(tracer));
+        self
+    }
+
+    /// The metrics being recorded.
+    pub fn metrics(&self) -> &SimMetrics {
+        &self.metrics
+    }
+
+    /// The server started by [`serve`][Self::serve], if any.
+    pub fn server(&self) -> Option<&MetricsServer> {
+        self.server.as_ref()
+    }
+
+    /// The wrapped observer.
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+
+    /// The wrapped observer, mutably.
+    pub fn inner_mut(&mut self) -> &mut O {
+        &mut self.inner
+    }
+
+    /// Unwrap the wrapped observer.  Stops the server, if any.
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+
+    /// Run `f` on `inner`, adding its wall time to the output time.
+    fn timed<R>(&mut self, f: impl FnOnce(&mut O) -> R) -> R {
+        let started = Instant::now();
+        let result = f(&mut self.inner);
+        self.output += started.elapsed();
+        result
+    }
+
+    /// Publish the output time of the tick that just ended and close its
+    /// span.
+    fn finish_tick(&mut self) {
+        let output = std::mem::take(&mut self.output);
+        self.metrics.record_output(output);
+        self.metrics.set_output_lag(output);
+        #[cfg(feature = "otel")]
+        if let Some(spans) = &mut self.spans {
+            spans.end(output);
+        }
+    }
+
+    fn read_memory(&mut self, force: bool) {
+        let now = Instant::now();
+        if !force && self.memory_read.is_some_and(|last| now - last < MEMORY_POLL) {
+            return;
+        }
+        self.memory_read = Some(now);
+        if let Some(usage) = memory_stats::memory_stats() {
+            self.metrics.set_resident_memory(usage.physical_mem);
+        }
+    }
+}
+
+impl<O: SimObserver> SimObserver for MetricsObserver<O> {
+    fn on_tick_start(&mut self, tick: Tick) {
+        self.finish_tick();
+        #[cfg(feature = "otel")]
+        if let Some(spans) = &mut self.spans {
+            spans.start(tick);
+        }
+        self.timed(|inner| inner.on_tick_start(tick));
+    }
+
+    fn on_tick_end(&mut self, tick: Tick, woken: usize) {
+        self.timed(|inner| inner.on_tick_end(tick, woken));
+        self.rate_ticks += 1;
+        let elapsed = self.rate_since.elapsed();
+        if elapsed >= RATE_WINDOW {
+            self.metrics.set_tick_rate(self.rate_ticks as f64 / elapsed.as_secs_f64());
+            self.rate_since = Instant::now();
+            self.rate_ticks = 0;
+        }
+        self.read_memory(false);
+    }
+
+    fn on_snapshot(&mut self, tick: Tick, mobility: &MobilityStore, agents: &AgentStore) {
+        self.timed(|inner| inner.on_snapshot(tick, mobility, agents));
+    }
+
+    fn on_trip(&mut self, trip: &Trip) {
+        self.timed(|inner| inner.on_trip(trip));
+    }
+
+    fn on_departure(&mut self, tick: Tick, agent: AgentId, state: &MovementState, route: &Route) {
+        self.timed(|inner| inner.on_departure(tick, agent, state, route));
+    }
+
+    fn on_contacts(&mut self, tick: Tick, agent: AgentId, node: NodeId, agents_at_node: &[AgentId]) {
+        self.timed(|inner| inner.on_contacts(tick, agent, node, agents_at_node));
+    }
+
+    fn on_tick_stats(&mut self, tick: Tick, stats: &TickStats) {
+        self.metrics.record_stats(tick, stats);
+        #[cfg(feature = "otel")]
+        if let Some(spans) = &mut self.spans {
+            spans.record_stats(stats);
+        }
+        self.timed(|inner| inner.on_tick_stats(tick, stats));
+    }
+
+    fn on_phase_timings(&mut self, tick: Tick, timings: &PhaseTimings) {
+        self.metrics.record_timings(timings);
+        #[cfg(feature = "otel")]
+        if let Some(spans) = &mut self.spans {
+            spans.record_timings(timings);
+        }
+        self.timed(|inner| inner.on_phase_timings(tick, timings));
+    }
+
+    fn on_metrics(&mut self, tick: Tick, metrics: &TickMetrics) {
+        self.timed(|inner| inner.on_metrics(tick, metrics));
+    }
+
+    fn on_trace(&mut self, event: &TraceEvent) {
+        self.timed(|inner| inner.on_trace(event));
+    }
+
+    fn on_ticks_skipped(&mut self, from: Tick, to: Tick) {
+        self.metrics.record_skipped(to.0.saturating_sub(from.0));
+        self.timed(|inner| inner.on_ticks_skipped(from, to));
+    }
+
+    fn on_sim_end(&mut self, final_tick: Tick) {
+        self.timed(|inner| inner.on_sim_end(final_tick));
+        self.finish_tick();
+        self.read_memory(true);
+    }
+
+    fn poll_error(&mut self) -> Option<String> {
+        self.inner.poll_error()
+    }
+}
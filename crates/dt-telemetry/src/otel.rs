@@ -0,0 +1,65 @@
+//! One OpenTelemetry span per tick (feature `otel`).
+//!
+//! Each span is named `tick`, opens at `on_tick_start`, and closes when the
+//! next tick starts or the run ends, so it includes the output written for
+//! the tick.  Attributes:
+//!
+//! | Attribute                                                   | Value                                   |
+//! |-------------------------------------------------------------|-----------------------------------------|
+//! | `dt.tick`                                                   | tick number                             |
+//! | `dt.woken`, `dt.in_transit`, `dt.departures`, `dt.arrivals` | as in [`TickStats`]                     |
+//! | `dt.phase.<name>.seconds`                                   | each of [`PhaseTimings::phases`]        |
+//! | `dt.output.seconds`                                         | wall time spent in the wrapped observer |
+
+use std::time::Duration;
+
+use dt_core::Tick;
+use dt_sim::{PhaseTimings, TickStats};
+use opentelemetry::KeyValue;
+use opentelemetry::global::{BoxedSpan, BoxedTracer};
+use opentelemetry::trace::{Span, Tracer};
+
+/// The tracer and the span of the tick in progress.
+pub(crate) struct TickSpans {
+    tracer: BoxedTracer,
+    span:   Option<BoxedSpan>,
+}
+
+impl TickSpans {
+    pub(crate) fn new(tracer: BoxedTracer) -> Self {
+        Self { tracer, span: None }
+    }
+
+    pub(crate) fn start(&mut self, tick: Tick) {
+        let mut span = self.tracer.start("tick");
+        span.set_attribute(KeyValue::new("dt.tick", tick.0 as i64));
+        self.span = Some(span);
+    }
+
+    pub(crate) fn record_stats(&mut self, stats: &TickStats) {
+        if let Some(span) = &mut self.span {
+            span.set_attributes([
+                KeyValue::new("dt.woken", stats.woken as i64),
+                KeyValue::new("dt.in_transit", stats.in_transit as i64),
+                KeyValue::new("dt.departures", stats.departures as i64),
+                KeyValue::new("dt.arrivals", stats.arrivals as i64),
+            ]);
+        }
+    }
+
+    pub(crate) fn record_timings(&mut self, timings: &PhaseTimings) {
+        if let Some(span) = &mut self.span {
+            for (phase, duration) in timings.phases() {
+                span.set_attribute(KeyValue::new(format!("dt.phase.{phase}.seconds"), duration.as_secs_f64()));
+            }
+        }
+    }
+
+    /// Close the open span, if any, after `output` in the wrapped observer.
+    pub(crate) fn end(&mut self, output: Duration) {
+        if let Some(mut span) = self.span.take() {
+            span.set_attribute(KeyValue::new("dt.output.seconds", output.as_secs_f64()));
+            span.end();
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! `MetricsServer` — serves a registry for Prometheus to scrape.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use prometheus::Registry;
+
+use crate::TelemetryResult;
+use crate::metrics::render;
+
+/// How long a scraper may take to send its request or read the response.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often the accept thread checks whether the server has stopped.
+const ACCEPT_POLL: Duration = Duration::from_millis(20);
+
+/// Longest request head read before a connection is dropped.
+const MAX_REQUEST: usize = 8192;
+
+/// Answers `GET /metrics` with a registry in the Prometheus text format.
+///
+/// A background thread handles one request at a time; every other path
+/// gets a 404.  Dropping the server stops listening.
+pub struct MetricsServer {
+    addr:     SocketAddr,
+    stop:     Arc<AtomicBool>,
+    acceptor: Option<JoinHandle<()>>,
+}
+
+impl MetricsServer {
+    /// Serve `registry` on `addr` (e.g. `"0.0.0.0:9898"`; port `0` picks a
+    /// free port, see [`local_addr`][Self::local_addr]).
+    pub fn bind(addr: impl ToSocketAddrs, registry: Registry) -> TelemetryResult<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let acceptor = {
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        // A failed request only loses that connection.
+                        Ok((stream, _)) => respond(stream, &registry),
+                        Err(_) => std::thread::sleep(ACCEPT_POLL),
+                    }
+                }
+            })
+        };
+
+        Ok(Self { addr, stop, acceptor: Some(acceptor) })
+    }
+
+    /// The address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The URL to scrape.
+    pub fn url(&self) -> String {
+        format!("http://{}/metrics", self.addr)
+    }
+}
+
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(acceptor) = self.acceptor.take() {
+            let _ = acceptor.join();
+        }
+    }
+}
+
+/// Read one request from `stream` and answer it.
+fn respond(mut stream: TcpStream, registry: &Registry) {
+    if stream.set_nonblocking(false).is_err()
+        || stream.set_read_timeout(Some(CLIENT_TIMEOUT)).is_err()
+        || stream.set_write_timeout(Some(CLIENT_TIMEOUT)).is_err()
+    {
+        return;
+    }
+    let Some(head) = read_head(&mut stream) else {
+        return;
+    };
+    let mut parts = head.split_whitespace();
+    let (status, content_type, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", "text/plain; version=0.0.4", render(registry)),
+        _ => ("404 Not Found", "text/plain", "not found\n".to_owned()),
+    };
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len(),
+    );
+    let _ = stream.flush();
+}
+
+/// The request line and headers.
+fn read_head(stream: &mut TcpStream) -> Option<String> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.ends_with(b"\r\n\r\n") {
+        let n = stream.read(&mut buf).ok()?;
+        if n == 0 || head.len() + n > MAX_REQUEST {
+            return None;
+        }
+        head.extend_from_slice(&buf[..n]);
+        if let Some(end) = head.windows(4).position(|w| w == b"\r\n\r\n") {
+            head.truncate(end + 4);
+        }
+    }
+    String::from_utf8(head).ok()
+}
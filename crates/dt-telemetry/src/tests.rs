@@ -0,0 +1,238 @@
+use dt_agent::AgentStoreBuilder;
+use dt_behavior::NoopBehavior;
+use dt_core::{SimConfig, Tick};
+use dt_sim::{Sim, SimBuilder};
+use dt_spatial::DijkstraRouter;
+
+fn test_config(total_ticks: u64) -> SimConfig {
+    SimConfig {
+        start_unix_secs:       0,
+        tick_duration_secs:    3600,
+        total_ticks,
+        seed:                  1,
+        num_threads:           Some(1),
+        output_interval_ticks: 1,
+        ..Default::default()
+    }
+}
+
+/// Two agents woken every tick.
+fn test_sim(config: &SimConfig) -> Sim<NoopBehavior, DijkstraRouter> {
+    let (store, rngs) = AgentStoreBuilder::new(2, 1).build();
+    let mut sim = SimBuilder::new(config.clone(), store, rngs, NoopBehavior, DijkstraRouter).build().unwrap();
+    for tick in 0..config.total_ticks {
+        sim.wake_queue.push(Tick(tick), dt_core::AgentId(0));
+        sim.wake_queue.push(Tick(tick), dt_core::AgentId(1));
+    }
+    sim
+}
+
+/// The value of the sample line `name` (with labels, if any) in `text`.
+fn sample(text: &str, name: &str) -> f64 {
+    text.lines()
+        .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+        .unwrap_or_else(|| panic!("{name} missing from\n{text}"))
+        .parse()
+        .unwrap()
+}
+
+// ── Metrics ───────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod metrics_tests {
+    use prometheus::Registry;
+
+    use super::sample;
+    use crate::{SimMetrics, TelemetryError};
+
+    #[test]
+    fn every_metric_is_exported() {
+        let text = SimMetrics::new().unwrap().render();
+        for name in [
+            "dt_ticks_total", "dt_skipped_ticks_total", "dt_tick", "dt_run_ticks", "dt_tick_rate",
+            "dt_tick_duration_seconds_count", "dt_woken_agents", "dt_woken_agents_total",
+            "dt_agents_in_transit", "dt_departures_total", "dt_arrivals_total",
+            "dt_routing_failures_total", "dt_behavior_panics_total", "dt_output_seconds_total",
+            "dt_output_lag_seconds", "dt_resident_memory_bytes",
+            "dt_phase_seconds_total{phase=\"arrivals\"}", "dt_phase_seconds_total{phase=\"custom_phases\"}",
+        ] {
+            assert_eq!(sample(&text, name), 0.0, "{name}");
+        }
+    }
+
+    #[test]
+    fn shared_registry_rejects_duplicates() {
+        let registry = Registry::new();
+        let metrics = SimMetrics::in_registry(registry.clone()).unwrap();
+        assert_eq!(metrics.registry().gather().len(), registry.gather().len());
+        assert!(matches!(SimMetrics::in_registry(registry), Err(TelemetryError::Prometheus(_))));
+    }
+}
+
+// ── Observer ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod observer_tests {
+    use std::time::Duration;
+
+    use dt_agent::AgentStore;
+    use dt_core::Tick;
+    use dt_mobility::MobilityStore;
+    use dt_sim::{NoopObserver, PhaseTimings, SimObserver};
+
+    use super::{sample, test_config, test_sim};
+    use crate::MetricsObserver;
+
+    /// Counts forwarded callbacks and takes a while over each snapshot.
+    #[derive(Default)]
+    struct SlowOutput {
+        ticks:     u64,
+        snapshots: u64,
+        timings:   u64,
+        ended:     bool,
+    }
+    impl SimObserver for SlowOutput {
+        fn on_tick_end(&mut self, _tick: Tick, _woken: usize) {
+            self.ticks += 1;
+        }
+        fn on_snapshot(&mut self, _tick: Tick, _mobility: &MobilityStore, _agents: &AgentStore) {
+            self.snapshots += 1;
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        fn on_phase_timings(&mut self, _tick: Tick, _timings: &PhaseTimings) {
+            self.timings += 1;
+        }
+        fn on_sim_end(&mut self, _final_tick: Tick) {
+            self.ended = true;
+        }
+    }
+
+    #[test]
+    fn run_updates_metrics_and_forwards() {
+        let config = test_config(4);
+        let mut sim = test_sim(&config);
+        let mut obs = MetricsObserver::new(SlowOutput::default(), &config).unwrap();
+        sim.run(&mut obs).unwrap();
+
+        let text = obs.metrics().render();
+        assert_eq!(sample(&text, "dt_ticks_total"), 4.0);
+        assert_eq!(sample(&text, "dt_tick"), 3.0);
+        assert_eq!(sample(&text, "dt_run_ticks"), 4.0);
+        assert_eq!(sample(&text, "dt_woken_agents"), 2.0);
+        assert_eq!(sample(&text, "dt_woken_agents_total"), 8.0);
+        assert_eq!(sample(&text, "dt_tick_duration_seconds_count"), 4.0);
+        assert!(sample(&text, "dt_phase_seconds_total{phase=\"intents\"}") > 0.0);
+        // Every snapshot's output time is counted, the last one as the lag.
+        assert!(sample(&text, "dt_output_seconds_total") >= 0.02);
+        assert!(sample(&text, "dt_output_lag_seconds") >= 0.005);
+        assert!(sample(&text, "dt_resident_memory_bytes") > 0.0);
+
+        let inner = obs.into_inner();
+        assert_eq!((inner.ticks, inner.snapshots, inner.timings, inner.ended), (4, 4, 4, true));
+    }
+
+    #[test]
+    fn skipped_ticks_are_counted() {
+        let mut obs = MetricsObserver::new(NoopObserver, &test_config(10)).unwrap();
+        obs.on_ticks_skipped(Tick(2), Tick(9));
+        assert_eq!(sample(&obs.metrics().render(), "dt_skipped_ticks_total"), 7.0);
+    }
+}
+
+// ── Server ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod server_tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    use dt_sim::NoopObserver;
+
+    use super::{sample, test_config, test_sim};
+    use crate::MetricsObserver;
+
+    fn get(addr: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_metrics_while_observer_lives() {
+        let config = test_config(3);
+        let mut sim = test_sim(&config);
+        let mut obs = MetricsObserver::new(NoopObserver, &config).unwrap().serve("127.0.0.1:0").unwrap();
+        sim.run(&mut obs).unwrap();
+
+        let server = obs.server().unwrap();
+        let addr = server.local_addr();
+        assert_eq!(server.url(), format!("http://{addr}/metrics"));
+        let response = get(addr, "/metrics");
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(head.contains("Content-Type: text/plain; version=0.0.4"));
+        assert_eq!(sample(body, "dt_ticks_total"), 3.0);
+        assert!(get(addr, "/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        drop(obs);
+        assert!(TcpStream::connect(addr).is_err());
+    }
+}
+
+// ── OpenTelemetry (feature: otel) ─────────────────────────────────────────────
+
+#[cfg(all(test, feature = "otel"))]
+mod otel_tests {
+    use std::sync::{Arc, Mutex};
+
+    use dt_sim::NoopObserver;
+    use opentelemetry::Value;
+    use opentelemetry::global::BoxedTracer;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry::trace::TraceResult;
+    use opentelemetry_sdk::export::trace::SpanData;
+    use opentelemetry_sdk::trace::{Span, SpanProcessor, TracerProvider};
+
+    use super::{test_config, test_sim};
+    use crate::MetricsObserver;
+
+    #[derive(Debug, Clone, Default)]
+    struct Collect(Arc<Mutex<Vec<SpanData>>>);
+    impl SpanProcessor for Collect {
+        fn on_start(&self, _span: &mut Span, _cx: &opentelemetry::Context) {}
+        fn on_end(&self, span: SpanData) {
+            self.0.lock().unwrap().push(span);
+        }
+        fn force_flush(&self) -> TraceResult<()> {
+            Ok(())
+        }
+        fn shutdown(&self) -> TraceResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn one_span_per_tick() {
+        let spans = Collect::default();
+        let provider = TracerProvider::builder().with_span_processor(spans.clone()).build();
+        let tracer = BoxedTracer::new(Box::new(provider.tracer("dt-telemetry-test")));
+
+        let config = test_config(3);
+        let mut sim = test_sim(&config);
+        let mut obs = MetricsObserver::new(NoopObserver, &config).unwrap().with_tracer(tracer);
+        sim.run(&mut obs).unwrap();
+
+        let spans = spans.0.lock().unwrap();
+        assert_eq!(spans.len(), 3);
+        for (tick, span) in spans.iter().enumerate() {
+            assert_eq!(span.name, "tick");
+            let attr = |key: &str| span.attributes.iter().find(|kv| kv.key.as_str() == key).map(|kv| kv.value.clone());
+            assert_eq!(attr("dt.tick"), Some(Value::I64(tick as i64)));
+            assert_eq!(attr("dt.woken"), Some(Value::I64(2)));
+            assert!(matches!(attr("dt.phase.intents.seconds"), Some(Value::F64(_))));
+            assert!(matches!(attr("dt.output.seconds"), Some(Value::F64(_))));
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! `Assignment` — iterative user-equilibrium traffic assignment.
+//!
+//! Each iteration loads the whole demand all-or-nothing onto the shortest
+//! paths under the current congested travel times, then moves the link
+//! volumes part of the way towards that loading:
+//!
+//! | Method                 | Step                                                   |
+//! |------------------------|--------------------------------------------------------|
+//! | [`Method::FrankWolfe`] | the one minimizing the Beckmann objective (bisection)  |
+//! | [`Method::Msa`]        | `1 / (k + 1)`, the method of successive averages       |
+//!
+//! Convergence is measured by the relative gap between the total travel
+//! time on the current volumes and what it would be if every trip took a
+//! current shortest path; it is `0` at equilibrium.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use dt_core::{EdgeId, NodeId};
+use dt_spatial::RoadNetwork;
+
+use crate::{Capacity, Equilibrium, Iteration, OdMatrix, TrafficError, TrafficResult, VolumeDelay};
+
+/// How far each iteration moves towards the all-or-nothing loading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Method {
+    /// Method of successive averages: fixed steps, no line search.
+    Msa,
+    /// Frank-Wolfe: the optimal step along the search direction.
+    #[default]
+    FrankWolfe,
+}
+
+// ── Assignment ────────────────────────────────────────────────────────────────
+
+/// A static assignment of `demand` to the car network.
+///
+/// Free-flow times are the network's `edge_travel_ms`; edges at `u32::MAX`
+/// (closed, as dt-cli's road interventions leave them) carry no traffic.
+///
+/// ```rust,ignore
+/// let eq = Assignment::new(&network, demand)
+///     .vdf(VolumeDelay::Bpr { alpha: 0.15, beta: 4.0 })
+///     .capacity(Capacity::Uniform(1800.0))
+///     .period_hours(2.0)
+///     .gap(1e-4)
+///     .run()?;
+/// eq.travel_times().apply(&mut network);
+/// ```
+pub struct Assignment<'n> {
+    network:        &'n RoadNetwork,
+    demand:         OdMatrix,
+    vdf:            VolumeDelay,
+    capacity:       Capacity,
+    method:         Method,
+    period_hours:   f64,
+    max_iterations: usize,
+    target_gap:     f64,
+}
+
+impl<'n> Assignment<'n> {
+    /// Default settings: BPR, capacities by speed, Frank-Wolfe, a one-hour
+    /// period, at most 50 iterations or a relative gap of `1e-4`.
+    pub fn new(network: &'n RoadNetwork, demand: OdMatrix) -> Self {
+        Self {
+            network,
+            demand,
+            vdf:            VolumeDelay::default(),
+            capacity:       Capacity::default(),
+            method:         Method::default(),
+            period_hours:   1.0,
+            max_iterations: 50,
+            target_gap:     1e-4,
+        }
+    }
+
+    pub fn vdf(mut self, vdf: VolumeDelay) -> Self {
+        self.vdf = vdf;
+        self
+    }
+
+    pub fn capacity(mut self, capacity: Capacity) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    pub fn method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Hours the demand is spread over; capacities are per hour.
+    pub fn period_hours(mut self, hours: f64) -> Self {
+        self.period_hours = hours;
+        self
+    }
+
+    pub fn max_iterations(mut self, n: usize) -> Self {
+        self.max_iterations = n;
+        self
+    }
+
+    /// Stop once the relative gap is at most `gap`.
+    pub fn gap(mut self, gap: f64) -> Self {
+        self.target_gap = gap;
+        self
+    }
+
+    /// Iterate to equilibrium, or until `max_iterations`.
+    pub fn run(&self) -> TrafficResult<Equilibrium> {
+        self.validate()?;
+        let net = self.network;
+        let capacities = self.capacity.resolve(net)?;
+        let free_flow: Vec<f64> = net.edge_travel_ms.iter().map(|&ms| ms as f64).collect();
+        let closed: Vec<bool> = net.edge_travel_ms.iter().map(|&ms| ms == u32::MAX).collect();
+        // Vehicles per period each edge can carry at x = 1.
+        let period_caps: Vec<f64> = capacities.iter().map(|c| c * self.period_hours).collect();
+        let costs = |volumes: &[f64]| -> Vec<f64> {
+            (0..volumes.len())
+                .map(|e| match closed[e] {
+                    true => f64::INFINITY,
+                    false => self.vdf.travel_time(free_flow[e], volumes[e] / period_caps[e]),
+                })
+                .collect()
+        };
+
+        let (mut volumes, _, unassigned) = all_or_nothing(net, &self.demand, &costs(&vec![0.0; free_flow.len()]));
+        let mut iterations = Vec::new();
+        let mut converged = false;
+        for k in 1..=self.max_iterations {
+            let times = costs(&volumes);
+            let (target, shortest, _) = all_or_nothing(net, &self.demand, &times);
+            let total: f64 = volumes.iter().zip(&times).filter(|(v, _)| **v > 0.0).map(|(v, t)| v * t).sum();
+            let relative_gap = if total > 0.0 { ((total - shortest) / total).max(0.0) } else { 0.0 };
+            if relative_gap <= self.target_gap {
+                iterations.push(Iteration { iteration: k, relative_gap, step: 0.0, vehicle_hours: total / 3.6e6 });
+                converged = true;
+                break;
+            }
+            let step = match self.method {
+                Method::Msa => 1.0 / (k as f64 + 1.0),
+                Method::FrankWolfe => self.line_search(&volumes, &target, &free_flow, &period_caps, &closed),
+            };
+            iterations.push(Iteration { iteration: k, relative_gap, step, vehicle_hours: total / 3.6e6 });
+            for (v, y) in volumes.iter_mut().zip(&target) {
+                *v += step * (y - *v);
+            }
+        }
+
+        let travel_ms = costs(&volumes)
+            .into_iter()
+            .zip(&free_flow)
+            .map(|(t, &t0)| if t.is_finite() { t } else { t0 })
+            .collect();
+        Ok(Equilibrium {
+            volumes,
+            capacities,
+            free_flow_ms: free_flow,
+            travel_ms,
+            period_hours: self.period_hours,
+            iterations,
+            unassigned,
+            converged,
+        })
+    }
+
+    /// The step in `[0, 1]` from `volumes` towards `target` minimizing the
+    /// Beckmann objective: where `Σ t_a(x + λd) · d_a` crosses zero.
+    fn line_search(&self, volumes: &[f64], target: &[f64], t0: &[f64], caps: &[f64], closed: &[bool]) -> f64 {
+        let slope = |lambda: f64| -> f64 {
+            (0..volumes.len())
+                .filter(|&e| !closed[e])
+                .map(|e| {
+                    let d = target[e] - volumes[e];
+                    self.vdf.travel_time(t0[e], (volumes[e] + lambda * d) / caps[e]) * d
+                })
+                .sum()
+        };
+        if slope(1.0) <= 0.0 {
+            return 1.0;
+        }
+        let (mut lo, mut hi) = (0.0, 1.0);
+        for _ in 0..40 {
+            let mid = (lo + hi) / 2.0;
+            if slope(mid) > 0.0 { hi = mid } else { lo = mid }
+        }
+        (lo + hi) / 2.0
+    }
+
+    fn validate(&self) -> TrafficResult<()> {
+        let config = |msg: String| Err(TrafficError::Config(msg));
+        if !(self.period_hours > 0.0 && self.period_hours.is_finite()) {
+            return config(format!("period of {} hours", self.period_hours));
+        }
+        if self.max_iterations == 0 {
+            return config("max_iterations must be at least 1".into());
+        }
+        if self.target_gap.is_nan() || self.target_gap < 0.0 {
+            return config(format!("relative gap target {}", self.target_gap));
+        }
+        self.vdf.validate()?;
+        let nodes = self.network.node_count();
+        for pair in self.demand.pairs() {
+            if pair.from.index() >= nodes || pair.to.index() >= nodes {
+                return config(format!("OD pair {} → {} outside the network's {nodes} nodes", pair.from.0, pair.to.0));
+            }
+            if !(pair.trips >= 0.0 && pair.trips.is_finite()) {
+                return config(format!("{} trips from {} to {}", pair.trips, pair.from.0, pair.to.0));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ── All-or-nothing loading ────────────────────────────────────────────────────
+
+/// Every OD pair's trips on its shortest path under `costs` (milliseconds per
+/// edge): `(volumes, Σ trips · shortest time, trips with no path)`.
+fn all_or_nothing(network: &RoadNetwork, demand: &OdMatrix, costs: &[f64]) -> (Vec<f64>, f64, f64) {
+    let mut volumes = vec![0.0; network.edge_count()];
+    let (mut shortest, mut unassigned) = (0.0, 0.0);
+    let mut tree = Tree::new(network.node_count());
+    let mut origin = None;
+    for pair in demand.pairs() {
+        if pair.from == pair.to || pair.trips == 0.0 {
+            continue;
+        }
+        if origin != Some(pair.from) {
+            tree.grow(network, pair.from, costs);
+            origin = Some(pair.from);
+        }
+        let dist = tree.dist[pair.to.index()];
+        if !dist.is_finite() {
+            unassigned += pair.trips;
+            continue;
+        }
+        shortest += pair.trips * dist;
+        let mut node = pair.to;
+        while let Some(edge) = tree.prev[node.index()] {
+            volumes[edge.index()] += pair.trips;
+            node = network.edge_from[edge.index()];
+        }
+    }
+    (volumes, shortest, unassigned)
+}
+
+/// One-to-all shortest-path tree, reused across origins.
+struct Tree {
+    dist: Vec<f64>,
+    prev: Vec<Option<EdgeId>>,
+}
+
+impl Tree {
+    fn new(nodes: usize) -> Self {
+        Self { dist: vec![f64::INFINITY; nodes], prev: vec![None; nodes] }
+    }
+
+    fn grow(&mut self, network: &RoadNetwork, origin: NodeId, costs: &[f64]) {
+        self.dist.fill(f64::INFINITY);
+        self.prev.fill(None);
+        self.dist[origin.index()] = 0.0;
+        // Non-negative floats order like their bit patterns, which gives the
+        // heap a total order; the node breaks ties deterministically.
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((0f64.to_bits(), origin)));
+        while let Some(Reverse((bits, node))) = heap.pop() {
+            let cost = f64::from_bits(bits);
+            if cost > self.dist[node.index()] {
+                continue;
+            }
+            for edge in network.out_edges(node) {
+                let next = network.edge_to[edge.index()];
+                let c = cost + costs[edge.index()];
+                if c < self.dist[next.index()] {
+                    self.dist[next.index()] = c;
+                    self.prev[next.index()] = Some(edge);
+                    heap.push(Reverse((c.to_bits(), next)));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! `OdMatrix` — trips between pairs of nodes over the assignment period.
+
+use std::collections::BTreeMap;
+
+use dt_core::{NodeId, TransportMode};
+use dt_mobility::Trip;
+
+/// Trips from one node to another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OdPair {
+    pub from:  NodeId,
+    pub to:    NodeId,
+    /// Vehicles over the whole period; fractional demand is allowed.
+    pub trips: f64,
+}
+
+/// Origin–destination demand, one entry per `(from, to)` pair.
+///
+/// Pairs are kept sorted by origin, so the assignment runs one shortest-path
+/// tree per origin in a fixed order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OdMatrix {
+    pairs: BTreeMap<(NodeId, NodeId), f64>,
+}
+
+impl OdMatrix {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `trips` from `from` to `to`, on top of any already there.
+    pub fn add(&mut self, from: NodeId, to: NodeId, trips: f64) -> &mut Self {
+        *self.pairs.entry((from, to)).or_default() += trips;
+        self
+    }
+
+    /// One trip per completed journey in `modes` (every mode if empty), e.g.
+    /// the trips a sim reported through `SimObserver::on_trip` in a period.
+    pub fn from_trips<'a>(trips: impl IntoIterator<Item = &'a Trip>, modes: &[TransportMode]) -> Self {
+        let mut od = Self::new();
+        for trip in trips {
+            if modes.is_empty() || modes.contains(&trip.mode) {
+                od.add(trip.from, trip.to, 1.0);
+            }
+        }
+        od
+    }
+
+    /// Every multiple of `factor` (expansion of a sample, growth scenarios).
+    pub fn scaled(&self, factor: f64) -> Self {
+        Self { pairs: self.pairs.iter().map(|(&k, &v)| (k, v * factor)).collect() }
+    }
+
+    /// The pairs in `(from, to)` order.
+    pub fn pairs(&self) -> impl Iterator<Item = OdPair> + '_ {
+        self.pairs.iter().map(|(&(from, to), &trips)| OdPair { from, to, trips })
+    }
+
+    /// Number of `(from, to)` pairs.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Sum of all trips.
+    pub fn total(&self) -> f64 {
+        self.pairs.values().sum()
+    }
+}
+
+impl FromIterator<OdPair> for OdMatrix {
+    fn from_iter<I: IntoIterator<Item = OdPair>>(iter: I) -> Self {
+        let mut od = Self::new();
+        for pair in iter {
+            od.add(pair.from, pair.to, pair.trips);
+        }
+        od
+    }
+}
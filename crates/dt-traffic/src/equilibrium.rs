@@ -0,0 +1,131 @@
+//! `Equilibrium` — the outcome of an assignment, and the congested travel
+//! times it implies.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use dt_core::EdgeId;
+use dt_spatial::RoadNetwork;
+
+use crate::{TrafficError, TrafficResult};
+
+/// Progress of one iteration.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Iteration {
+    /// From 1.
+    pub iteration:     usize,
+    /// Relative gap of the volumes this iteration started from.
+    pub relative_gap:  f64,
+    /// Step taken towards the all-or-nothing loading; `0` once converged.
+    pub step:          f64,
+    /// Total travel time on those volumes, in vehicle-hours.
+    pub vehicle_hours: f64,
+}
+
+// ── Equilibrium ───────────────────────────────────────────────────────────────
+
+/// Link volumes and travel times after an [`Assignment`][crate::Assignment].
+///
+/// Edge vectors are indexed by `EdgeId`; times are in milliseconds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equilibrium {
+    /// Vehicles over the period.
+    pub volumes:      Vec<f64>,
+    /// Vehicles per hour.
+    pub capacities:   Vec<f64>,
+    pub free_flow_ms: Vec<f64>,
+    /// Congested travel time at `volumes`; closed edges keep `u32::MAX`.
+    pub travel_ms:    Vec<f64>,
+    pub period_hours: f64,
+    pub iterations:   Vec<Iteration>,
+    /// Trips whose destination can't be reached from their origin.
+    pub unassigned:   f64,
+    /// Whether the target gap was reached within the iteration limit.
+    pub converged:    bool,
+}
+
+impl Equilibrium {
+    /// Relative gap of the last iteration.
+    pub fn relative_gap(&self) -> f64 {
+        self.iterations.last().map_or(0.0, |i| i.relative_gap)
+    }
+
+    /// Volume over the capacity for the period.
+    pub fn volume_capacity(&self, edge: EdgeId) -> f64 {
+        let e = edge.index();
+        self.volumes[e] / (self.capacities[e] * self.period_hours)
+    }
+
+    /// Total travel time at the final volumes, in vehicle-hours.
+    pub fn vehicle_hours(&self) -> f64 {
+        self.volumes.iter().zip(&self.travel_ms).filter(|(v, _)| **v > 0.0).map(|(v, t)| v * t).sum::<f64>() / 3.6e6
+    }
+
+    /// The congested travel times, rounded to whole milliseconds.
+    pub fn travel_times(&self) -> TravelTimes {
+        let edge_ms = self.travel_ms.iter().map(|&t| t.round().clamp(0.0, u32::MAX as f64) as u32).collect();
+        TravelTimes { edge_ms }
+    }
+
+    /// One row per edge: `edge,volume,capacity,volume_capacity,free_flow_secs,travel_secs`.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("edge,volume,capacity,volume_capacity,free_flow_secs,travel_secs\n");
+        for e in 0..self.volumes.len() {
+            let _ = writeln!(
+                out,
+                "{e},{},{},{},{},{}",
+                self.volumes[e],
+                self.capacities[e],
+                self.volume_capacity(EdgeId(e as u32)),
+                self.free_flow_ms[e] / 1000.0,
+                self.travel_ms[e] / 1000.0,
+            );
+        }
+        out
+    }
+
+    /// One row per iteration: `iteration,relative_gap,step,vehicle_hours`.
+    pub fn iterations_csv(&self) -> String {
+        let mut out = String::from("iteration,relative_gap,step,vehicle_hours\n");
+        for i in &self.iterations {
+            let _ = writeln!(out, "{},{},{},{}", i.iteration, i.relative_gap, i.step, i.vehicle_hours);
+        }
+        out
+    }
+
+    pub fn write_csv(&self, path: &Path) -> TrafficResult<()> {
+        std::fs::write(path, self.to_csv()).map_err(|source| TrafficError::Io { path: path.to_path_buf(), source })
+    }
+}
+
+// ── TravelTimes ───────────────────────────────────────────────────────────────
+
+/// A car travel time for every edge, in milliseconds, to route against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TravelTimes {
+    pub edge_ms: Vec<u32>,
+}
+
+impl TravelTimes {
+    /// Write the times into `network.edge_travel_ms`, so the sim's router
+    /// (and trip durations) use them.  Closed edges (`u32::MAX`) stay
+    /// closed; the network must be the one that was assigned.
+    ///
+    /// ```rust,ignore
+    /// equilibrium.travel_times().apply(&mut sim.network);
+    /// ```
+    pub fn apply(&self, network: &mut RoadNetwork) {
+        for (ms, &congested) in network.edge_travel_ms.iter_mut().zip(&self.edge_ms) {
+            if *ms != u32::MAX {
+                *ms = congested;
+            }
+        }
+    }
+
+    /// A copy of `network` with these times.
+    pub fn congested(&self, network: &RoadNetwork) -> RoadNetwork {
+        let mut network = network.clone();
+        self.apply(&mut network);
+        network
+    }
+}
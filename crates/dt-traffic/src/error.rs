@@ -0,0 +1,26 @@
+//! Error types for dt-traffic.
+
+use std::path::PathBuf;
+
+use dt_core::{DtError, ErrorCategory};
+use thiserror::Error;
+
+/// Errors that can occur when setting up an assignment or writing its output.
+#[derive(Debug, Error)]
+pub enum TrafficError {
+    /// A demand, capacity, or convergence parameter is out of range.
+    #[error("traffic assignment: {0}")]
+    Config(String),
+
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+}
+
+/// Alias for `Result<T, TrafficError>`.
+pub type TrafficResult<T> = Result<T, TrafficError>;
+
+impl From<TrafficError> for DtError {
+    fn from(err: TrafficError) -> Self {
+        DtError::subsystem(ErrorCategory::Mobility, err)
+    }
+}
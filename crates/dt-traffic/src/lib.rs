@@ -0,0 +1,47 @@
+//! `dt-traffic` — static traffic assignment and congested travel times.
+//!
+//! Movement in the sim is teleport-at-arrival over free-flow times, so
+//! agents never slow each other down.  This crate supplies the congestion
+//! separately: an [`Assignment`] loads origin–destination demand
+//! ([`OdMatrix`]) onto the road network until no trip could be faster on
+//! another route (Wardrop's user equilibrium), with each link slowed by a
+//! [`VolumeDelay`] function of its volume and [`Capacity`].  The resulting
+//! [`TravelTimes`] are written into the network's `edge_travel_ms`, which
+//! the sim's router and trip durations already use.
+//!
+//! ```rust,ignore
+//! use dt_traffic::{Assignment, OdMatrix};
+//!
+//! // Demand from a first run: the morning's car trips.
+//! let demand = OdMatrix::from_trips(&morning_trips, &[TransportMode::Car]);
+//! let eq = Assignment::new(&sim.network, demand).period_hours(3.0).run()?;
+//! println!("gap {:.1e} after {} iterations", eq.relative_gap(), eq.iterations.len());
+//!
+//! // Route the next run against the congested times.
+//! eq.travel_times().apply(&mut next.network);
+//! ```
+//!
+//! # Crate layout
+//!
+//! | Module          | Contents                                          |
+//! |-----------------|---------------------------------------------------|
+//! | [`assignment`]  | `Assignment` (builder and iteration), `Method`    |
+//! | [`demand`]      | `OdMatrix`, `OdPair`                              |
+//! | [`vdf`]         | `VolumeDelay`, `Capacity`, `capacity_for_speed`   |
+//! | [`equilibrium`] | `Equilibrium`, `Iteration`, `TravelTimes`         |
+//! | [`error`]       | `TrafficError`, `TrafficResult<T>`                |
+
+pub mod assignment;
+pub mod demand;
+pub mod equilibrium;
+pub mod error;
+pub mod vdf;
+
+#[cfg(test)]
+mod tests;
+
+pub use assignment::{Assignment, Method};
+pub use demand::{OdMatrix, OdPair};
+pub use equilibrium::{Equilibrium, Iteration, TravelTimes};
+pub use error::{TrafficError, TrafficResult};
+pub use vdf::{Capacity, VolumeDelay, capacity_for_speed};
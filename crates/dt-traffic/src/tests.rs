@@ -0,0 +1,215 @@
+//! Unit tests for dt-traffic.
+
+use dt_core::{AgentId, EdgeId, GeoPoint, NodeId, Tick, TransportMode};
+use dt_mobility::Trip;
+use dt_spatial::{DijkstraRouter, RoadNetwork, RoadNetworkBuilder, Router};
+
+use crate::{Assignment, Capacity, Method, OdMatrix, TrafficError, TravelTimes, VolumeDelay, capacity_for_speed};
+
+// ── Helpers ───────────────────────────────────────────────────────────────────
+
+/// Two one-way roads from 0 to 1: edge 0 takes 600 s, edge 1 900 s.
+fn parallel() -> RoadNetwork {
+    let mut b = RoadNetworkBuilder::new();
+    let a = b.add_node(GeoPoint::new(0.0, 0.0));
+    let z = b.add_node(GeoPoint::new(0.0, 0.1));
+    b.add_directed_edge(a, z, 10_000.0, 600_000);
+    b.add_directed_edge(a, z, 10_000.0, 900_000);
+    b.build()
+}
+
+fn demand(trips: f64) -> OdMatrix {
+    let mut od = OdMatrix::new();
+    od.add(NodeId(0), NodeId(1), trips);
+    od
+}
+
+/// Linear delay on 1000 veh/h roads: with 2000 trips, equal times at
+/// 1400 / 600 (both 1440 s).
+fn linear(network: &RoadNetwork, trips: f64) -> Assignment<'_> {
+    Assignment::new(network, demand(trips))
+        .vdf(VolumeDelay::Bpr { alpha: 1.0, beta: 1.0 })
+        .capacity(Capacity::Uniform(1000.0))
+}
+
+// ── Model ─────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod model_tests {
+    use super::*;
+
+    #[test]
+    fn volume_delay_functions() {
+        let bpr = VolumeDelay::default();
+        assert_eq!(bpr.travel_time(100.0, 0.0), 100.0);
+        assert!((bpr.travel_time(100.0, 1.0) - 115.0).abs() < 1e-9);
+
+        let conical = VolumeDelay::Conical { alpha: 4.0 };
+        assert!((conical.travel_time(100.0, 0.0) - 100.0).abs() < 1e-9);
+        assert!((conical.travel_time(100.0, 1.0) - 200.0).abs() < 1e-9);
+        assert!(conical.travel_time(100.0, 2.0) > conical.travel_time(100.0, 1.5));
+    }
+
+    #[test]
+    fn demand_matrix() {
+        let trip = |from: u32, to: u32, mode| Trip {
+            agent:       AgentId(0),
+            from:        NodeId(from),
+            to:          NodeId(to),
+            mode,
+            depart_tick: Tick(0),
+            arrive_tick: Tick(1),
+            travel_secs: 60.0,
+            distance_m:  1000.0,
+        };
+        let trips =
+            [trip(0, 1, TransportMode::Car), trip(0, 1, TransportMode::Car), trip(1, 0, TransportMode::Walk)];
+        let cars = OdMatrix::from_trips(&trips, &[TransportMode::Car]);
+        assert_eq!(cars.len(), 1);
+        assert_eq!(cars.total(), 2.0);
+        assert_eq!(OdMatrix::from_trips(&trips, &[]).len(), 2);
+
+        let mut od = cars.scaled(1.5);
+        od.add(NodeId(0), NodeId(1), 1.0).add(NodeId(2), NodeId(0), 4.0);
+        let pairs: Vec<_> = od.pairs().map(|p| (p.from.0, p.to.0, p.trips)).collect();
+        assert_eq!(pairs, [(0, 1, 4.0), (2, 0, 4.0)]);
+    }
+
+    #[test]
+    fn capacities() {
+        assert_eq!(capacity_for_speed(100.0), 2000.0);
+        assert_eq!(capacity_for_speed(50.0), 900.0);
+        assert_eq!(capacity_for_speed(10.0), 600.0);
+
+        // 10 km in 600 s is 60 km/h; in 900 s, 40 km/h.
+        let eq = Assignment::new(&parallel(), OdMatrix::new()).run().unwrap();
+        assert_eq!(eq.capacities, [1500.0, 900.0]);
+        assert_eq!(eq.volumes, [0.0, 0.0]);
+        assert!(eq.converged);
+
+        let mut network = parallel();
+        network.edge_capacity_vph = vec![3000.0, 1200.0];
+        let eq = Assignment::new(&network, OdMatrix::new()).capacity(Capacity::Network).run().unwrap();
+        assert_eq!(eq.capacities, [3000.0, 1200.0]);
+    }
+}
+
+// ── Assignment ────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod assignment_tests {
+    use super::*;
+
+    #[test]
+    fn frank_wolfe_reaches_equilibrium() {
+        let network = parallel();
+        let eq = linear(&network, 2000.0).gap(1e-6).run().unwrap();
+        assert!(eq.converged, "{:?}", eq.iterations);
+        assert!((eq.volumes[0] - 1400.0).abs() < 1.0, "{:?}", eq.volumes);
+        assert!((eq.volumes[1] - 600.0).abs() < 1.0, "{:?}", eq.volumes);
+        assert!((eq.travel_ms[0] - 1_440_000.0).abs() < 1000.0);
+        assert!((eq.travel_ms[1] - 1_440_000.0).abs() < 1000.0);
+        assert!((eq.volume_capacity(EdgeId(0)) - 1.4).abs() < 1e-3);
+        assert!((eq.vehicle_hours() - 800.0).abs() < 1.0);
+        // Everything starts on the faster road.
+        assert_eq!(eq.iterations[0].relative_gap, 1.0 - 2000.0 * 900.0 / (2000.0 * 1800.0));
+    }
+
+    #[test]
+    fn msa_approaches_equilibrium() {
+        let network = parallel();
+        let eq = linear(&network, 2000.0).method(Method::Msa).gap(1e-6).run().unwrap();
+        assert!(eq.converged, "{:?}", eq.iterations);
+        assert_eq!(eq.iterations[1].step, 1.0 / 3.0);
+        assert!((eq.volumes[0] - 1400.0).abs() < 1.0, "{:?}", eq.volumes);
+
+        let capped = linear(&network, 2000.0).method(Method::Msa).max_iterations(3).gap(0.0).run().unwrap();
+        assert!(!capped.converged);
+        assert_eq!(capped.iterations.len(), 3);
+        assert!(capped.relative_gap() > 0.0);
+    }
+
+    #[test]
+    fn period_spreads_demand() {
+        let network = parallel();
+        let hour = linear(&network, 1000.0).run().unwrap();
+        let two_hours = linear(&network, 2000.0).period_hours(2.0).run().unwrap();
+        assert!((two_hours.volumes[0] - 2.0 * hour.volumes[0]).abs() < 1.0);
+        assert!((two_hours.travel_ms[0] - hour.travel_ms[0]).abs() < 1000.0);
+        // Below 300 trips the slower road is never worth it.
+        let light = linear(&network, 250.0).run().unwrap();
+        assert_eq!(light.volumes, [250.0, 0.0]);
+    }
+
+    #[test]
+    fn closed_and_unreachable() {
+        let mut network = parallel();
+        network.edge_travel_ms[0] = u32::MAX;
+        let mut od = demand(100.0);
+        od.add(NodeId(1), NodeId(0), 30.0);
+        let eq = Assignment::new(&network, od).run().unwrap();
+        assert_eq!(eq.volumes, [0.0, 100.0]);
+        assert_eq!(eq.unassigned, 30.0);
+        assert_eq!(eq.travel_times().edge_ms[0], u32::MAX);
+    }
+
+    #[test]
+    fn invalid_settings() {
+        let network = parallel();
+        let config = |a: Assignment<'_>| matches!(a.run(), Err(TrafficError::Config(_)));
+        assert!(config(linear(&network, 10.0).period_hours(0.0)));
+        assert!(config(linear(&network, 10.0).max_iterations(0)));
+        assert!(config(linear(&network, 10.0).vdf(VolumeDelay::Conical { alpha: 1.0 })));
+        assert!(config(linear(&network, 10.0).capacity(Capacity::PerEdge(vec![1000.0]))));
+        assert!(config(linear(&network, 10.0).capacity(Capacity::Uniform(0.0))));
+        assert!(config(Assignment::new(&network, demand(-1.0))));
+        let mut od = OdMatrix::new();
+        od.add(NodeId(0), NodeId(7), 1.0);
+        assert!(config(Assignment::new(&network, od)));
+    }
+}
+
+// ── Travel times ──────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod travel_time_tests {
+    use super::*;
+
+    #[test]
+    fn congested_times_change_routes() {
+        let mut network = parallel();
+        let route = |net: &RoadNetwork| DijkstraRouter.route(net, NodeId(0), NodeId(1), TransportMode::Car).unwrap();
+        assert_eq!(route(&network).edges, [EdgeId(0)]);
+
+        // At equilibrium both roads take 1440 s.
+        let eq = linear(&network, 2000.0).gap(1e-6).run().unwrap();
+        let times = eq.travel_times();
+        assert!(times.edge_ms.iter().all(|&ms| ms.abs_diff(1_440_000) < 1000), "{:?}", times.edge_ms);
+
+        let times = TravelTimes { edge_ms: vec![1_800_000, 900_000] };
+        let congested = times.congested(&network);
+        assert_eq!(route(&congested).edges, [EdgeId(1)]);
+        assert_eq!(network.edge_travel_ms, [600_000, 900_000]);
+
+        network.edge_travel_ms[1] = u32::MAX;
+        times.apply(&mut network);
+        assert_eq!(network.edge_travel_ms, [1_800_000, u32::MAX]);
+    }
+
+    #[test]
+    fn csv() {
+        let network = parallel();
+        let eq = linear(&network, 250.0).run().unwrap();
+        let csv = eq.to_csv();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], "edge,volume,capacity,volume_capacity,free_flow_secs,travel_secs");
+        assert_eq!(lines[1], "0,250,1000,0.25,600,750");
+        assert_eq!(lines[2], "1,0,1000,0,900,900");
+        assert!(eq.iterations_csv().starts_with("iteration,relative_gap,step,vehicle_hours\n1,0,0,"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("links.csv");
+        eq.write_csv(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), csv);
+    }
+}
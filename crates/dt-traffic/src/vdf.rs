@@ -0,0 +1,112 @@
+//! Volume-delay functions and link capacities.
+
+use dt_spatial::RoadNetwork;
+
+use crate::{TrafficError, TrafficResult};
+
+// ── VolumeDelay ───────────────────────────────────────────────────────────────
+
+/// Congested travel time of a link as a function of its volume/capacity
+/// ratio `x`, relative to the free-flow time `t0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VolumeDelay {
+    /// Bureau of Public Roads: `t0 · (1 + alpha · x^beta)`.
+    Bpr { alpha: f64, beta: f64 },
+    /// Spiess (1990) conical: `t0 · (2 + √(α²(1−x)² + β²) − α(1−x) − β)`
+    /// with `β = (2α − 1) / (2α − 2)`.  Unlike BPR it stays finite in
+    /// slope past capacity; `alpha` must be above 1.
+    Conical { alpha: f64 },
+}
+
+impl Default for VolumeDelay {
+    /// BPR with the classic `alpha = 0.15`, `beta = 4`.
+    fn default() -> Self {
+        VolumeDelay::Bpr { alpha: 0.15, beta: 4.0 }
+    }
+}
+
+impl VolumeDelay {
+    /// Travel time at volume/capacity ratio `x` for free-flow time `t0`.
+    pub fn travel_time(&self, t0: f64, x: f64) -> f64 {
+        let x = x.max(0.0);
+        match *self {
+            VolumeDelay::Bpr { alpha, beta } => t0 * (1.0 + alpha * x.powf(beta)),
+            VolumeDelay::Conical { alpha } => {
+                let b = (2.0 * alpha - 1.0) / (2.0 * alpha - 2.0);
+                let r = 1.0 - x;
+                t0 * (2.0 + (alpha * alpha * r * r + b * b).sqrt() - alpha * r - b)
+            }
+        }
+    }
+
+    pub(crate) fn validate(&self) -> TrafficResult<()> {
+        let ok = match *self {
+            VolumeDelay::Bpr { alpha, beta } => alpha >= 0.0 && beta >= 0.0 && (alpha + beta).is_finite(),
+            VolumeDelay::Conical { alpha } => alpha > 1.0 && alpha.is_finite(),
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(TrafficError::Config(format!("invalid volume-delay function {self:?}")))
+        }
+    }
+}
+
+// ── Capacity ──────────────────────────────────────────────────────────────────
+
+/// Capacity of every edge, in vehicles per hour.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Capacity {
+    /// The same capacity on every edge.
+    Uniform(f64),
+    /// One value per edge, indexed by `EdgeId`.
+    PerEdge(Vec<f64>),
+    /// The network's own `edge_capacity_vph`, from the OSM or MATSim
+    /// loader or the builder.
+    Network,
+    /// Guessed from each edge's free-flow speed (see
+    /// [`capacity_for_speed`]).
+    #[default]
+    BySpeed,
+}
+
+impl Capacity {
+    /// Vehicles per hour of each edge of `network`.
+    pub(crate) fn resolve(&self, network: &RoadNetwork) -> TrafficResult<Vec<f64>> {
+        let caps = match self {
+            Capacity::Uniform(vph) => vec![*vph; network.edge_count()],
+            Capacity::PerEdge(caps) if caps.len() == network.edge_count() => caps.clone(),
+            Capacity::PerEdge(caps) => {
+                return Err(TrafficError::Config(format!(
+                    "{} capacities for {} edges",
+                    caps.len(),
+                    network.edge_count()
+                )));
+            }
+            Capacity::Network => network.edge_capacity_vph.iter().map(|&c| c as f64).collect(),
+            Capacity::BySpeed => (0..network.edge_count())
+                .map(|e| {
+                    let secs = network.edge_travel_ms[e] as f64 / 1000.0;
+                    let kmh = if secs > 0.0 { network.edge_length_m[e] as f64 / secs * 3.6 } else { 0.0 };
+                    capacity_for_speed(kmh)
+                })
+                .collect(),
+        };
+        if let Some(e) = caps.iter().position(|&c| !(c > 0.0 && c.is_finite())) {
+            return Err(TrafficError::Config(format!("edge {e} has capacity {}", caps[e])));
+        }
+        Ok(caps)
+    }
+}
+
+/// A rough per-direction capacity (vehicles per hour) for a road with
+/// free-flow speed `kmh`: 2000 for motorways (≥ 90 km/h), 1500 for
+/// arterials (≥ 60), 900 for collectors (≥ 40), 600 otherwise.
+pub fn capacity_for_speed(kmh: f64) -> f64 {
+    match kmh {
+        s if s >= 90.0 => 2000.0,
+        s if s >= 60.0 => 1500.0,
+        s if s >= 40.0 => 900.0,
+        _ => 600.0,
+    }
+}
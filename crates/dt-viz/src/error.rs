@@ -0,0 +1,20 @@
+//! Error types for dt-viz.
+
+use dt_core::{DtError, ErrorCategory};
+use thiserror::Error;
+
+/// Errors that can occur when starting the visualization server.
+#[derive(Debug, Error)]
+pub enum VizError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Alias for `Result<T, VizError>`.
+pub type VizResult<T> = Result<T, VizError>;
+
+impl From<VizError> for DtError {
+    fn from(err: VizError) -> Self {
+        DtError::subsystem(ErrorCategory::Output, err)
+    }
+}
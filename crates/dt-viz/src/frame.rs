@@ -0,0 +1,76 @@
+//! JSON frames sent to the browser.
+//!
+//! Every frame is one WebSocket text message holding a JSON object whose
+//! `type` field names its kind:
+//!
+//! ```text
+//! {"type":"network","nodes":[[52.52,13.405],[52.51,13.39]],"edges":[[0,1]]}
+//! {"type":"agents","tick":12,"time":"2024-03-04T12:00:00Z","agents":[[3,52.52,13.405,0],[7,52.51,13.39,1]]}
+//! {"type":"tick","tick":12,"time":"2024-03-04T12:00:00Z","woken":40,"in_transit":12,"departures":9,"arrivals":4,"trips":131}
+//! {"type":"end","tick":168}
+//! ```
+//!
+//! `network` lists node `[lat, lon]` pairs and each road once as a pair of
+//! node indices.  `agents` holds one `[agent_id, lat, lon, in_transit]`
+//! array per sampled agent.  `tick` summarises one tick; `trips` counts the
+//! trips completed so far in the run.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use dt_sim::TickStats;
+use dt_spatial::RoadNetwork;
+
+/// One agent in an `agents` frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AgentPoint {
+    pub agent_id:   u32,
+    pub lat:        f32,
+    pub lon:        f32,
+    pub in_transit: bool,
+}
+
+/// The `network` frame for `network`.
+///
+/// Two-way roads are stored as two directed edges; each is sent once.
+pub fn network(network: &RoadNetwork) -> String {
+    let mut text = String::from("{\"type\":\"network\",\"nodes\":[");
+    for (i, pos) in network.node_pos.iter().enumerate() {
+        let sep = if i == 0 { "" } else { "," };
+        let _ = write!(text, "{sep}[{},{}]", pos.lat, pos.lon);
+    }
+    text.push_str("],\"edges\":[");
+    let mut seen = HashSet::with_capacity(network.edge_count());
+    let roads = network.edge_from.iter().zip(&network.edge_to).map(|(a, b)| (a.0.min(b.0), a.0.max(b.0)));
+    for (i, (a, b)) in roads.filter(|road| seen.insert(*road)).enumerate() {
+        let sep = if i == 0 { "" } else { "," };
+        let _ = write!(text, "{sep}[{a},{b}]");
+    }
+    text.push_str("]}");
+    text
+}
+
+/// An `agents` frame for `tick`, shown as `time`.
+pub fn agents(tick: u64, time: &str, agents: &[AgentPoint]) -> String {
+    let mut text = format!("{{\"type\":\"agents\",\"tick\":{tick},\"time\":\"{time}\",\"agents\":[");
+    for (i, a) in agents.iter().enumerate() {
+        let sep = if i == 0 { "" } else { "," };
+        let _ = write!(text, "{sep}[{},{},{},{}]", a.agent_id, a.lat, a.lon, a.in_transit as u8);
+    }
+    text.push_str("]}");
+    text
+}
+
+/// A `tick` frame summarising `stats`, with `trips` completed so far.
+pub fn tick(tick: u64, time: &str, stats: &TickStats, trips: u64) -> String {
+    format!(
+        "{{\"type\":\"tick\",\"tick\":{tick},\"time\":\"{time}\",\"woken\":{},\"in_transit\":{},\
+         \"departures\":{},\"arrivals\":{},\"trips\":{trips}}}",
+        stats.woken, stats.in_transit, stats.departures, stats.arrivals,
+    )
+}
+
+/// The `end` frame, sent once the run has finished at `final_tick`.
+pub fn end(final_tick: u64) -> String {
+    format!("{{\"type\":\"end\",\"tick\":{final_tick}}}")
+}
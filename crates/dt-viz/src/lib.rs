@@ -0,0 +1,43 @@
+//! `dt-viz` — watch a simulation live in the browser.
+//!
+//! [`VizServer`] serves an embedded map page (deck.gl on a MapLibre base
+//! map, loaded from a CDN) and streams frames to it over WebSocket on the
+//! same port.  [`VizObserver`] is the [`SimObserver`][dt_sim::SimObserver]
+//! that feeds it: the road network once, sampled agent positions at every
+//! snapshot, and a summary of every tick.
+//!
+//! ```rust,ignore
+//! let server = VizServer::bind("127.0.0.1:8080")?;
+//! println!("open {}", server.url());
+//! server.wait_for_client(Duration::from_secs(30));
+//!
+//! let mut viz = VizObserver::new(server, &config, &network)
+//!     .sample_every(10)
+//!     .pace(Duration::from_millis(100));
+//! sim.run(&mut viz)?;
+//! ```
+//!
+//! The page reconnects by itself, so it may be opened or reloaded at any
+//! time during the run.  Other programs can subscribe too; the frames are
+//! documented in [`frame`].
+//!
+//! # Crate layout
+//!
+//! | Module       | Contents                                          |
+//! |--------------|---------------------------------------------------|
+//! | [`server`]   | `VizServer`: HTTP page and WebSocket broadcasting |
+//! | [`observer`] | `VizObserver`: sim callbacks to frames            |
+//! | [`frame`]    | JSON frame encoding                               |
+//! | [`error`]    | `VizError`, `VizResult<T>`                        |
+
+pub mod error;
+pub mod frame;
+pub mod observer;
+pub mod server;
+
+#[cfg(test)]
+mod tests;
+
+pub use error::{VizError, VizResult};
+pub use observer::VizObserver;
+pub use server::{INDEX_HTML, VizServer};
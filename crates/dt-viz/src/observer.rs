@@ -0,0 +1,143 @@
+//! `VizObserver` — turns sim callbacks into frames for a [`VizServer`].
+
+use std::time::{Duration, Instant};
+
+use dt_agent::AgentStore;
+use dt_core::{EdgeId, GeoPoint, NodeId, SimClock, SimConfig, Tick};
+use dt_mobility::{MobilityStore, MovementState, Trip};
+use dt_sim::{SimObserver, TickStats};
+use dt_spatial::RoadNetwork;
+
+use crate::frame::{self, AgentPoint};
+use crate::VizServer;
+
+/// A [`SimObserver`] that streams a run to the browsers connected to a
+/// [`VizServer`].
+///
+/// Every snapshot the sim fires becomes an `agents` frame of sampled agent
+/// positions, and every tick a `tick` frame of its counts (see
+/// [`frame`][crate::frame]).  In-transit agents are placed on the straight
+/// line between departure and destination node according to their journey
+/// progress.  Nothing is encoded while no browser is connected.
+///
+/// A fast run finishes before anyone can watch it; [`pace`][Self::pace]
+/// slows the sim down to a watchable rate.
+pub struct VizObserver {
+    server:     VizServer,
+    clock:      SimClock,
+    node_pos:   Vec<GeoPoint>,
+    /// `(from, to)` of every edge; copied only when path-following.
+    edge_ends:  Vec<(NodeId, NodeId)>,
+    every:      u32,
+    pace:       Duration,
+    last_frame: Option<Instant>,
+    trips:      u64,
+}
+
+impl VizObserver {
+    /// Stream a run of `config` over `network` to `server`'s clients.
+    pub fn new(server: VizServer, config: &SimConfig, network: &RoadNetwork) -> Self {
+        server.set_network(frame::network(network));
+        let edge_ends = if config.mobility.path_following {
+            network.edge_from.iter().copied().zip(network.edge_to.iter().copied()).collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            server,
+            clock:      SimClock::new(config.start_unix_secs, config.tick_duration_secs),
+            node_pos:   network.node_pos.clone(),
+            edge_ends,
+            every:      1,
+            pace:       Duration::ZERO,
+            last_frame: None,
+            trips:      0,
+        }
+    }
+
+    /// Send only agents whose id is a multiple of `n`, so the same agents
+    /// are shown every frame.  Default: 1 (every agent).
+    pub fn sample_every(mut self, n: u32) -> Self {
+        self.every = n.max(1);
+        self
+    }
+
+    /// Hold each `agents` frame for at least `interval` of wall time,
+    /// blocking the sim while a browser is connected.  Default: zero (run
+    /// at full speed).
+    pub fn pace(mut self, interval: Duration) -> Self {
+        self.pace = interval;
+        self
+    }
+
+    /// The server frames are sent through.
+    pub fn server(&self) -> &VizServer {
+        &self.server
+    }
+
+    /// Unwrap the server, e.g. to keep serving the final state after the
+    /// run.
+    pub fn into_server(self) -> VizServer {
+        self.server
+    }
+
+    /// Position of an agent in `state` at `tick`, if its nodes are known.
+    fn position(&self, state: &MovementState, tick: Tick) -> Option<GeoPoint> {
+        if !state.in_transit {
+            return self.node_pos.get(state.departure_node.index()).copied();
+        }
+        let ends = |e: EdgeId| self.edge_ends.get(e.index()).copied().unwrap_or((NodeId::INVALID, NodeId::INVALID));
+        let (from, to, t) = state.segment_by(tick, ends);
+        let (from, to) = (*self.node_pos.get(from.index())?, *self.node_pos.get(to.index())?);
+        Some(GeoPoint {
+            lat: from.lat + (to.lat - from.lat) * t,
+            lon: from.lon + (to.lon - from.lon) * t,
+        })
+    }
+
+    /// Sleep until `pace` has passed since the previous `agents` frame.
+    fn wait_for_pace(&mut self) {
+        if let Some(last) = self.last_frame {
+            let elapsed = last.elapsed();
+            if elapsed < self.pace {
+                std::thread::sleep(self.pace - elapsed);
+            }
+        }
+        self.last_frame = Some(Instant::now());
+    }
+}
+
+impl SimObserver for VizObserver {
+    fn on_trip(&mut self, _trip: &Trip) {
+        self.trips += 1;
+    }
+
+    fn on_tick_stats(&mut self, tick: Tick, stats: &TickStats) {
+        if self.server.clients() == 0 {
+            return;
+        }
+        let time = self.clock.format_tick(tick);
+        self.server.broadcast(&frame::tick(tick.0, &time, stats, self.trips));
+    }
+
+    fn on_snapshot(&mut self, tick: Tick, mobility: &MobilityStore, agents: &AgentStore) {
+        if self.server.clients() == 0 {
+            return;
+        }
+        let points: Vec<AgentPoint> = (0..agents.count)
+            .step_by(self.every as usize)
+            .filter_map(|i| {
+                let state = &mobility.states[i];
+                let pos = self.position(state, tick)?;
+                Some(AgentPoint { agent_id: i as u32, lat: pos.lat, lon: pos.lon, in_transit: state.in_transit })
+            })
+            .collect();
+        let time = self.clock.format_tick(tick);
+        self.wait_for_pace();
+        self.server.send_agents(frame::agents(tick.0, &time, &points));
+    }
+
+    fn on_sim_end(&mut self, final_tick: Tick) {
+        self.server.broadcast(&frame::end(final_tick.0));
+    }
+}
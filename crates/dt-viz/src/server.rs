@@ -0,0 +1,242 @@
+//! `VizServer` — serves the map page and streams frames to it.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use tungstenite::{Message, WebSocket};
+
+use crate::VizResult;
+
+/// The map page, served at `/`.
+pub const INDEX_HTML: &str = include_str!("index.html");
+
+/// How long a client may take to send its request, complete the handshake,
+/// or accept a frame before it is dropped.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How often the accept thread checks whether the server has stopped.
+const ACCEPT_POLL: Duration = Duration::from_millis(20);
+
+/// Longest request head read before a connection is dropped.
+const MAX_REQUEST: usize = 8192;
+
+/// State shared with the accept thread.
+#[derive(Default)]
+struct Shared {
+    clients: Mutex<Vec<WebSocket<TcpStream>>>,
+    /// Frames every client gets on connecting: the network, then the
+    /// latest positions.
+    network: Mutex<Option<String>>,
+    latest:  Mutex<Option<String>>,
+    stop:    AtomicBool,
+}
+
+/// An HTTP and WebSocket server on one port.
+///
+/// `GET /` returns the map page ([`INDEX_HTML`]); a WebSocket upgrade on any
+/// path subscribes to frames.  A background thread accepts connections, so
+/// browsers may connect and reconnect at any time: each new client first
+/// receives the network and the latest agent positions, then every frame
+/// broadcast after it connected.  A client that cannot take a frame within
+/// one second is dropped, so a stalled browser tab does not hold up the run.
+///
+/// Dropping the server closes every connection and stops listening.
+pub struct VizServer {
+    addr:     SocketAddr,
+    shared:   Arc<Shared>,
+    acceptor: Option<JoinHandle<()>>,
+}
+
+impl VizServer {
+    /// Listen on `addr` (e.g. `"127.0.0.1:8080"`; port `0` picks a free
+    /// port, see [`local_addr`][Self::local_addr]).
+    pub fn bind(addr: impl ToSocketAddrs) -> VizResult<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared::default());
+
+        let acceptor = {
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || {
+                while !shared.stop.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        // A failed request only loses that connection.
+                        Ok((stream, _)) => serve(stream, &shared),
+                        Err(_) => std::thread::sleep(ACCEPT_POLL),
+                    }
+                }
+            })
+        };
+
+        Ok(Self { addr, shared, acceptor: Some(acceptor) })
+    }
+
+    /// The address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The URL of the map page.
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    /// Number of connected WebSocket clients.
+    pub fn clients(&self) -> usize {
+        self.shared.clients.lock().map_or(0, |c| c.len())
+    }
+
+    /// Block until at least one client is connected or `timeout` passes.
+    /// Returns whether a client is connected.
+    ///
+    /// Call before starting the run so its first ticks are not missed.
+    pub fn wait_for_client(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        while self.clients() == 0 {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(ACCEPT_POLL);
+        }
+        true
+    }
+
+    /// Set the `network` frame sent to every client on connecting, and send
+    /// it to the clients already connected.
+    pub fn set_network(&self, frame: String) {
+        self.broadcast(&frame);
+        if let Ok(mut network) = self.shared.network.lock() {
+            *network = Some(frame);
+        }
+    }
+
+    /// Send an `agents` frame to every client, and keep it for clients that
+    /// connect later.
+    pub fn send_agents(&self, frame: String) {
+        self.broadcast(&frame);
+        if let Ok(mut latest) = self.shared.latest.lock() {
+            *latest = Some(frame);
+        }
+    }
+
+    /// Send `frame` to every connected client.
+    pub fn broadcast(&self, frame: &str) {
+        let Ok(mut clients) = self.shared.clients.lock() else {
+            return;
+        };
+        if clients.is_empty() {
+            return;
+        }
+        let message = Message::text(frame);
+        // Drop every client the frame could not be delivered to.
+        clients.retain_mut(|ws| ws.send(message.clone()).is_ok());
+    }
+
+    /// Close every connection and stop listening.
+    pub fn shutdown(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        if let Some(acceptor) = self.acceptor.take() {
+            let _ = acceptor.join();
+        }
+        if let Ok(mut clients) = self.shared.clients.lock() {
+            for mut ws in clients.drain(..) {
+                let _ = ws.close(None);
+                let _ = ws.flush();
+            }
+        }
+    }
+}
+
+impl Drop for VizServer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Answer one newly accepted connection: upgrade it to a WebSocket client
+/// or serve the page.
+fn serve(stream: TcpStream, shared: &Shared) {
+    if stream.set_nonblocking(false).is_err()
+        || stream.set_read_timeout(Some(CLIENT_TIMEOUT)).is_err()
+        || stream.set_write_timeout(Some(CLIENT_TIMEOUT)).is_err()
+    {
+        return;
+    }
+    let Some(head) = peek_head(&stream) else {
+        return;
+    };
+    if is_upgrade(&head) {
+        subscribe(stream, shared);
+    } else {
+        respond(stream, &head);
+    }
+}
+
+/// The request line and headers, left unread in the stream for the
+/// WebSocket handshake.
+fn peek_head(stream: &TcpStream) -> Option<String> {
+    let mut buf = [0u8; MAX_REQUEST];
+    let deadline = Instant::now() + CLIENT_TIMEOUT;
+    loop {
+        let n = stream.peek(&mut buf).ok()?;
+        if let Some(end) = buf[..n].windows(4).position(|w| w == b"\r\n\r\n") {
+            return String::from_utf8(buf[..end].to_vec()).ok();
+        }
+        if n == 0 || n == MAX_REQUEST || Instant::now() >= deadline {
+            return None;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn is_upgrade(head: &str) -> bool {
+    head.lines().skip(1).any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("upgrade") && value.trim().eq_ignore_ascii_case("websocket")
+        })
+    })
+}
+
+/// Complete the handshake and send the network and latest positions.
+fn subscribe(stream: TcpStream, shared: &Shared) {
+    let Ok(mut ws) = tungstenite::accept(stream) else {
+        return;
+    };
+    for frame in [&shared.network, &shared.latest] {
+        let frame = frame.lock().ok().and_then(|f| f.clone());
+        if let Some(frame) = frame
+            && ws.send(Message::text(frame)).is_err()
+        {
+            return;
+        }
+    }
+    if let Ok(mut clients) = shared.clients.lock() {
+        clients.push(ws);
+    }
+}
+
+/// Serve the page for `GET /`, and 404 for anything else.
+fn respond(mut stream: TcpStream, head: &str) {
+    // Consume the request so closing the socket does not reset it.
+    let mut request = vec![0u8; head.len() + 4];
+    if stream.read_exact(&mut request).is_err() {
+        return;
+    }
+    let mut parts = head.split_whitespace();
+    let (status, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/" | "/index.html")) => ("200 OK", INDEX_HTML),
+        _ => ("404 Not Found", "not found\n"),
+    };
+    let content_type = if status == "200 OK" { "text/html; charset=utf-8" } else { "text/plain" };
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len(),
+    );
+    let _ = stream.flush();
+}
@@ -0,0 +1,225 @@
+// ── Frames ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod frame_tests {
+    use dt_core::GeoPoint;
+    use dt_sim::TickStats;
+    use dt_spatial::RoadNetworkBuilder;
+
+    use crate::frame::{self, AgentPoint};
+
+    #[test]
+    fn network_sends_each_road_once() {
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint { lat: 52.5, lon: 13.25 });
+        let c = b.add_node(GeoPoint { lat: 52.0, lon: 13.0 });
+        let d = b.add_node(GeoPoint { lat: 51.5, lon: 12.75 });
+        b.add_road(a, c, 100.0, 1000);
+        b.add_directed_edge(c, d, 100.0, 1000);
+        assert_eq!(
+            frame::network(&b.build()),
+            r#"{"type":"network","nodes":[[52.5,13.25],[52,13],[51.5,12.75]],"edges":[[0,1],[1,2]]}"#,
+        );
+    }
+
+    #[test]
+    fn agents_and_tick_frames() {
+        let points = [
+            AgentPoint { agent_id: 0, lat: 52.5, lon: 13.25, in_transit: false },
+            AgentPoint { agent_id: 7, lat: 51.5, lon: 12.75, in_transit: true },
+        ];
+        assert_eq!(
+            frame::agents(12, "1970-01-01T12:00:00Z", &points),
+            r#"{"type":"agents","tick":12,"time":"1970-01-01T12:00:00Z","agents":[[0,52.5,13.25,0],[7,51.5,12.75,1]]}"#,
+        );
+        assert_eq!(frame::agents(0, "t", &[]), r#"{"type":"agents","tick":0,"time":"t","agents":[]}"#);
+
+        let stats = TickStats { woken: 40, in_transit: 12, departures: 9, arrivals: 4, ..Default::default() };
+        assert_eq!(
+            frame::tick(12, "t", &stats, 131),
+            r#"{"type":"tick","tick":12,"time":"t","woken":40,"in_transit":12,"departures":9,"arrivals":4,"trips":131}"#,
+        );
+        assert_eq!(frame::end(168), r#"{"type":"end","tick":168}"#);
+    }
+}
+
+// ── Server ────────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod server_tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::time::Duration;
+
+    use tungstenite::Message;
+
+    use crate::{INDEX_HTML, VizServer};
+
+    fn get(server: &VizServer, path: &str) -> String {
+        let mut stream = TcpStream::connect(server.local_addr()).unwrap();
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    pub(super) fn connect(
+        server: &VizServer,
+    ) -> tungstenite::WebSocket<tungstenite::stream::MaybeTlsStream<TcpStream>> {
+        let before = server.clients();
+        let (client, _) = tungstenite::connect(format!("ws://{}/ws", server.local_addr())).unwrap();
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while server.clients() == before {
+            assert!(std::time::Instant::now() < deadline, "client not accepted");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        client
+    }
+
+    #[test]
+    fn serves_page_and_404() {
+        let server = VizServer::bind("127.0.0.1:0").unwrap();
+        assert_eq!(server.url(), format!("http://{}/", server.local_addr()));
+
+        let page = get(&server, "/");
+        assert!(page.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(page.contains("text/html"));
+        assert!(page.ends_with(INDEX_HTML));
+        assert!(get(&server, "/missing").starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert_eq!(server.clients(), 0);
+    }
+
+    #[test]
+    fn new_clients_get_network_and_latest_positions() {
+        let mut server = VizServer::bind("127.0.0.1:0").unwrap();
+        assert!(!server.wait_for_client(Duration::from_millis(10)));
+        server.set_network("net".into());
+        server.send_agents("a1".into());
+        server.send_agents("a2".into());
+
+        let mut client = connect(&server);
+        assert!(server.wait_for_client(Duration::ZERO));
+        assert_eq!(client.read().unwrap(), Message::text("net"));
+        assert_eq!(client.read().unwrap(), Message::text("a2"));
+
+        server.broadcast("t");
+        assert_eq!(client.read().unwrap(), Message::text("t"));
+
+        server.shutdown();
+        assert!(matches!(client.read().unwrap(), Message::Close(_)));
+        assert_eq!(server.clients(), 0);
+    }
+
+    #[test]
+    fn closed_clients_are_dropped() {
+        let server = VizServer::bind("127.0.0.1:0").unwrap();
+        let client = connect(&server);
+        drop(client);
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while server.clients() > 0 {
+            assert!(std::time::Instant::now() < deadline, "client not dropped");
+            server.broadcast("t");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+// ── Observer ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod observer_tests {
+    use std::time::{Duration, Instant};
+
+    use dt_agent::AgentStoreBuilder;
+    use dt_core::{GeoPoint, NodeId, SimConfig, Tick, TransportMode};
+    use dt_mobility::{MobilityStore, MovementState};
+    use dt_sim::{SimObserver, TickStats};
+    use dt_spatial::{RoadNetwork, RoadNetworkBuilder};
+    use tungstenite::Message;
+
+    use super::server_tests::connect;
+    use crate::{VizObserver, VizServer};
+
+    fn network() -> RoadNetwork {
+        let mut b = RoadNetworkBuilder::new();
+        let a = b.add_node(GeoPoint { lat: 10.0, lon: 20.0 });
+        let c = b.add_node(GeoPoint { lat: 12.0, lon: 24.0 });
+        b.add_road(a, c, 100.0, 1000);
+        b.build()
+    }
+
+    fn config() -> SimConfig {
+        SimConfig { start_unix_secs: 0, tick_duration_secs: 3600, total_ticks: 4, ..Default::default() }
+    }
+
+    fn text(message: Message) -> String {
+        message.into_text().unwrap().to_string()
+    }
+
+    #[test]
+    fn streams_positions_ticks_and_end() {
+        let network = network();
+        let server = VizServer::bind("127.0.0.1:0").unwrap();
+        let mut viz = VizObserver::new(server, &config(), &network).sample_every(2);
+
+        let (agents, _) = AgentStoreBuilder::new(3, 1).build();
+        let mut mobility = MobilityStore::new(3);
+        mobility.states[0] = MovementState {
+            in_transit:       true,
+            departure_node:   NodeId(0),
+            destination_node: NodeId(1),
+            departure_tick:   Tick(0),
+            arrival_tick:     Tick(4),
+            mode:             TransportMode::Car,
+            on_edge:          None,
+        };
+        mobility.states[1] = MovementState::stationary(NodeId(1), Tick(0));
+        // Agent 2 is sampled but has no position.
+
+        // Nothing is encoded without clients.
+        viz.on_snapshot(Tick(0), &mobility, &agents);
+        let mut client = connect(viz.server());
+        assert!(text(client.read().unwrap()).starts_with(r#"{"type":"network","#));
+
+        viz.on_trip(&dt_mobility::Trip {
+            agent:       dt_core::AgentId(1),
+            depart_tick: Tick(0),
+            arrive_tick: Tick(1),
+            from:        NodeId(0),
+            to:          NodeId(1),
+            mode:        TransportMode::Car,
+            travel_secs: 10.0,
+            distance_m:  100.0,
+        });
+        viz.on_tick_stats(Tick(1), &TickStats { woken: 2, in_transit: 1, ..Default::default() });
+        viz.on_snapshot(Tick(1), &mobility, &agents);
+        viz.on_sim_end(Tick(4));
+
+        assert_eq!(
+            text(client.read().unwrap()),
+            r#"{"type":"tick","tick":1,"time":"1970-01-01T01:00:00Z","woken":2,"in_transit":1,"departures":0,"arrivals":0,"trips":1}"#,
+        );
+        assert_eq!(
+            text(client.read().unwrap()),
+            r#"{"type":"agents","tick":1,"time":"1970-01-01T01:00:00Z","agents":[[0,10.5,21,1]]}"#,
+        );
+        assert_eq!(text(client.read().unwrap()), r#"{"type":"end","tick":4}"#);
+    }
+
+    #[test]
+    fn pace_spaces_out_frames() {
+        let network = network();
+        let server = VizServer::bind("127.0.0.1:0").unwrap();
+        let mut viz = VizObserver::new(server, &config(), &network).pace(Duration::from_millis(50));
+        let _client = connect(viz.server());
+
+        let (agents, _) = AgentStoreBuilder::new(1, 1).build();
+        let mobility = MobilityStore::new(1);
+        let started = Instant::now();
+        for tick in 0..3 {
+            viz.on_snapshot(Tick(tick), &mobility, &agents);
+        }
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert_eq!(viz.into_server().clients(), 1);
+    }
+}
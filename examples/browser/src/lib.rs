@@ -0,0 +1,206 @@
+//! browser — a rust_dt simulation running in a web page.
+//!
+//! Agents wander a small grid of streets: each time one wakes it either
+//! drives to a random intersection or waits a few minutes.  The crate
+//! compiles to `wasm32-unknown-unknown`; [`Demo`] is the JavaScript-facing
+//! handle, stepped from `requestAnimationFrame` and drawn on a canvas by
+//! `www/index.html`.
+//!
+//! ```text
+//! rustup target add wasm32-unknown-unknown
+//! cargo install wasm-pack
+//! wasm-pack build examples/browser --target web --out-dir www/pkg
+//! python3 -m http.server -d examples/browser/www 8000   # then open localhost:8000
+//! ```
+//!
+//! Everything the page needs comes back as flat typed arrays (`Float32Array`,
+//! `Uint32Array`), so drawing a frame copies one buffer across the boundary.
+
+use dt_agent::AgentStoreBuilder;
+use dt_behavior::{BehaviorModel, Intent, SimContext};
+use dt_core::wallclock::Instant;
+use dt_core::{AgentId, AgentRng, GeoPoint, NodeId, SimConfig, TransportMode};
+use dt_sim::{NoopObserver, Sim, SimBuilder};
+use dt_spatial::{DijkstraRouter, RoadNetwork, RoadNetworkBuilder};
+use wasm_bindgen::prelude::*;
+
+// ── Constants ─────────────────────────────────────────────────────────────────
+
+const TICK_DURATION_SECS: u32 = 60;    // 1 tick = 1 minute
+const BLOCK_M:            f32 = 400.0; // street spacing
+const SPEED_KMH:          f32 = 30.0;
+/// Degrees of latitude per metre, near enough for a few kilometres.
+const DEG_PER_M:          f32 = 1.0 / 111_320.0;
+
+// ── Behavior ──────────────────────────────────────────────────────────────────
+
+/// On each wake: drive somewhere random (30 %), or wait 1–15 minutes.
+///
+/// Agents without plans are not woken on arrival, so a driver also
+/// schedules its next wake, after the longest trip the grid allows.
+struct Wander {
+    nodes:    u32,
+    /// Ticks for the longest possible trip.
+    max_trip: u64,
+}
+
+impl BehaviorModel for Wander {
+    fn replan(&self, _agent: AgentId, ctx: &SimContext<'_>, rng: &mut AgentRng) -> Vec<Intent> {
+        let wait = rng.gen_range(1..16u64);
+        if rng.gen_bool(0.3) {
+            let destination = NodeId(rng.gen_range(0..self.nodes));
+            vec![
+                Intent::TravelTo { destination, mode: TransportMode::Car },
+                Intent::WakeAt(ctx.tick + self.max_trip + wait),
+            ]
+        } else {
+            vec![Intent::WakeAt(ctx.tick + wait)]
+        }
+    }
+}
+
+/// Milliseconds to drive one block.
+fn block_ms() -> u32 {
+    (BLOCK_M / (SPEED_KMH / 3.6) * 1000.0).round() as u32
+}
+
+/// A `size` × `size` grid of two-way streets.
+fn grid(size: u32) -> RoadNetwork {
+    let travel_ms = block_ms();
+    let step = BLOCK_M * DEG_PER_M;
+    let mut b = RoadNetworkBuilder::new();
+    for r in 0..size {
+        for c in 0..size {
+            b.add_node(GeoPoint::new(r as f32 * step, c as f32 * step));
+        }
+    }
+    for r in 0..size {
+        for c in 0..size {
+            let here = NodeId(r * size + c);
+            if c + 1 < size {
+                b.add_road(here, NodeId(here.0 + 1), BLOCK_M, travel_ms);
+            }
+            if r + 1 < size {
+                b.add_road(here, NodeId(here.0 + size), BLOCK_M, travel_ms);
+            }
+        }
+    }
+    b.build()
+}
+
+// ── Demo ──────────────────────────────────────────────────────────────────────
+
+/// A running simulation, as seen from JavaScript.
+///
+/// Coordinates are scaled to the unit square, `y` pointing down, ready to
+/// multiply by the canvas size.
+#[wasm_bindgen]
+pub struct Demo {
+    sim:         Sim<Wander, DijkstraRouter>,
+    /// `(min_lat, min_lon, span)` for scaling to the unit square.
+    frame:       (f32, f32, f32),
+    step_millis: f64,
+}
+
+#[wasm_bindgen]
+impl Demo {
+    /// `agents` agents on a `size` × `size` grid (at least 2 × 2), spread
+    /// over the intersections.
+    #[wasm_bindgen(constructor)]
+    pub fn new(agents: u32, size: u32, seed: u32) -> Result<Demo, JsError> {
+        if size < 2 {
+            return Err(JsError::new("size must be at least 2"));
+        }
+        let network = grid(size);
+        let nodes = network.node_count() as u32;
+        let n = agents as usize;
+        // Corner to corner, rounded up to whole ticks.
+        let tick_ms = u64::from(TICK_DURATION_SECS) * 1_000;
+        let max_trip = (u64::from(2 * (size - 1)) * u64::from(block_ms())).div_ceil(tick_ms);
+
+        let config = SimConfig {
+            start_unix_secs:    1_700_000_000,
+            tick_duration_secs: TICK_DURATION_SECS,
+            total_ticks:        525_600, // a year; `step` runs past it regardless
+            seed:               u64::from(seed),
+            ..Default::default()
+        };
+        let starts = (0..agents).map(|a| NodeId(a.wrapping_mul(2_654_435_761) % nodes)).collect();
+        let (store, rngs) = AgentStoreBuilder::new(n, config.seed).build();
+        let mut sim = SimBuilder::new(config, store, rngs, Wander { nodes, max_trip }, DijkstraRouter)
+            .network(network)
+            .initial_positions(starts)
+            .build()
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        for a in 0..agents {
+            sim.wake_queue.push(dt_core::Tick(u64::from(a % 15)), AgentId(a));
+        }
+
+        let span = (size - 1) as f32 * BLOCK_M * DEG_PER_M;
+        Ok(Demo { sim, frame: (0.0, 0.0, span), step_millis: 0.0 })
+    }
+
+    /// Advance `ticks` minutes.
+    pub fn step(&mut self, ticks: u32) -> Result<(), JsError> {
+        let started = Instant::now();
+        self.sim.run_ticks(u64::from(ticks), &mut NoopObserver).map_err(|e| JsError::new(&e.to_string()))?;
+        self.step_millis = started.elapsed().as_secs_f64() * 1000.0;
+        Ok(())
+    }
+
+    /// Simulated minutes since the start.
+    pub fn tick(&self) -> f64 {
+        self.sim.clock.current_tick.0 as f64
+    }
+
+    /// Wall-clock time the last [`step`][Self::step] took, in milliseconds.
+    #[wasm_bindgen(js_name = stepMillis)]
+    pub fn step_millis(&self) -> f64 {
+        self.step_millis
+    }
+
+    /// Agents on the road at the end of the last tick.
+    #[wasm_bindgen(js_name = inTransit)]
+    pub fn in_transit(&self) -> u32 {
+        self.sim.stats.in_transit as u32
+    }
+
+    /// `[x0, y0, x1, y1, …]` for every intersection.
+    pub fn nodes(&self) -> Vec<f32> {
+        self.sim.network.node_pos.iter().flat_map(|&p| self.scale(p)).collect()
+    }
+
+    /// `[from0, to0, from1, to1, …]` node indexes for every directed road.
+    pub fn edges(&self) -> Vec<u32> {
+        let net = &self.sim.network;
+        net.edge_from.iter().zip(&net.edge_to).flat_map(|(a, b)| [a.0, b.0]).collect()
+    }
+
+    /// `[x0, y0, x1, y1, …]` for every agent: its intersection if parked,
+    /// otherwise the middle of the street it is estimated to be on.
+    pub fn positions(&self) -> Vec<f32> {
+        let (net, store) = (&self.sim.network, &self.sim.mobility.store);
+        let now = self.sim.clock.current_tick;
+        (0..self.sim.agents.count)
+            .flat_map(|i| {
+                let agent = AgentId(i as u32);
+                let p = match store.current_edge(agent, now, net) {
+                    Some(e) => {
+                        let a = net.node_pos[net.edge_from[e.index()].index()];
+                        let b = net.node_pos[net.edge_to[e.index()].index()];
+                        GeoPoint::new((a.lat + b.lat) / 2.0, (a.lon + b.lon) / 2.0)
+                    }
+                    None => net.node_pos[store.states[i].departure_node.index()],
+                };
+                self.scale(p)
+            })
+            .collect()
+    }
+}
+
+impl Demo {
+    fn scale(&self, p: GeoPoint) -> [f32; 2] {
+        let (lat0, lon0, span) = self.frame;
+        [(p.lon - lon0) / span, 1.0 - (p.lat - lat0) / span]
+    }
+}
@@ -24,8 +24,6 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 mod network;
 
 use std::path::Path;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
 use anyhow::Result;
@@ -77,9 +75,7 @@ struct WorkNode(NodeId);
 
 // ── Behavior model ────────────────────────────────────────────────────────────
 
-struct DailyCommuteBehavior {
-    contacts_observed: Arc<AtomicU64>,
-}
+struct DailyCommuteBehavior;
 
 impl BehaviorModel for DailyCommuteBehavior {
     fn replan(&self, agent: AgentId, ctx: &SimContext<'_>, _rng: &mut AgentRng) -> Vec<Intent> {
@@ -135,8 +131,10 @@ impl BehaviorModel for DailyCommuteBehavior {
             seen += 1;
         }
         let _ = &sample[..k];
-        self.contacts_observed.fetch_add(k as u64, Ordering::Relaxed);
-        vec![]
+        if k == 0 {
+            return vec![];
+        }
+        vec![Intent::Count { name: "contacts", delta: k as u64 }]
     }
 }
 
@@ -152,18 +150,16 @@ struct PrecomputedRouter {
 
 impl PrecomputedRouter {
     fn build(network: &RoadNetwork, homes: &[NodeId], works: &[NodeId]) -> Self {
-        let router = DijkstraRouter;
         let mut routes = FxHashMap::with_capacity_and_hasher(
             homes.len() * works.len() * 2,
             Default::default(),
         );
-        for &h in homes {
-            for &w in works {
-                if let Ok(r) = router.route(network, h, w, TransportMode::Car) {
-                    routes.insert((h.0, w.0), r);
-                }
-                if let Ok(r) = router.route(network, w, h, TransportMode::Car) {
-                    routes.insert((w.0, h.0), r);
+        for (origins, destinations) in [(homes, works), (works, homes)] {
+            let matrix = DijkstraRouter.route_matrix(network, origins, destinations, TransportMode::Car);
+            let pairs = origins.iter().flat_map(|o| destinations.iter().map(move |d| (o.0, d.0)));
+            for (pair, route) in pairs.zip(matrix.into_routes().unwrap_or_default()) {
+                if let Some(route) = route {
+                    routes.insert(pair, route);
                 }
             }
         }
@@ -179,6 +175,10 @@ impl Router for PrecomputedRouter {
         to:       NodeId,
         _mode:    TransportMode,
     ) -> Result<Route, SpatialError> {
+        // Same-node requests get an empty route, per the `Router` contract.
+        if from == to {
+            return Ok(Route { edges: vec![], total_travel_ms: 0, rides: Vec::new() });
+        }
         self.routes
             .get(&(from.0, to.0))
             .cloned()
@@ -233,6 +233,7 @@ impl SimObserver for SampledObserver {
             unix_time_secs: self.start_unix_secs
                 + tick.0 as i64 * self.tick_duration_secs as i64,
             woken_agents:   woken as u64,
+            ..Default::default()
         };
         self.writer.write_tick_summary(&row).ok();
     }
@@ -254,6 +255,10 @@ impl SimObserver for SampledObserver {
                 } else {
                     NodeId::INVALID.0
                 },
+                lat:              None,
+                lon:              None,
+                departure_tick:   state.in_transit.then_some(state.departure_tick.0),
+                arrival_tick:     state.in_transit.then_some(state.arrival_tick.0),
             });
         }
         self.writer.write_snapshots(&rows).ok();
@@ -375,6 +380,7 @@ fn main() -> Result<()> {
         seed:                  SEED,
         num_threads:           Some(num_threads),
         output_interval_ticks: OUTPUT_INTERVAL_TICKS,
+        ..Default::default()
     };
     println!(
         "Sim: {} ticks ({} days), snapshots every {} ticks, 1-in-{} agents sampled",
@@ -383,10 +389,9 @@ fn main() -> Result<()> {
     println!();
 
     // 7. Build sim.
-    let contacts_observed = Arc::new(AtomicU64::new(0));
     let mut sim = SimBuilder::new(
             config.clone(), store, rngs,
-            DailyCommuteBehavior { contacts_observed: Arc::clone(&contacts_observed) },
+            DailyCommuteBehavior,
             router,
         )
         .plans(plans)
@@ -423,8 +428,8 @@ fn main() -> Result<()> {
     );
     println!(
         "Contacts sampled:   {} total  ({:.1} M/s)",
-        contacts_observed.load(Ordering::Relaxed),
-        contacts_observed.load(Ordering::Relaxed) as f64 / elapsed / 1_000_000.0,
+        sim.metric_totals.count("contacts"),
+        sim.metric_totals.count("contacts") as f64 / elapsed / 1_000_000.0,
     );
 
     Ok(())
@@ -13,10 +13,7 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
 mod network;
 
-use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
 use anyhow::Result;
@@ -29,7 +26,7 @@ use dt_mobility::MobilityStore;
 use dt_output::{AgentSnapshotRow, CsvWriter, OutputWriter, TickSummaryRow};
 use dt_schedule::{ActivityPlan, Destination, ScheduledActivity};
 use dt_sim::{SimBuilder, SimObserver};
-use dt_spatial::{DijkstraRouter, RoadNetwork, Route, Router, SpatialError};
+use dt_spatial::{CachedRouter, DijkstraRouter};
 
 use network::{build_network, home_nodes, work_nodes};
 
@@ -67,9 +64,7 @@ struct WorkNode(NodeId);
 
 // ── Behavior model ────────────────────────────────────────────────────────────
 
-struct DailyCommuteBehavior {
-    contacts_observed: Arc<AtomicU64>,
-}
+struct DailyCommuteBehavior;
 
 impl BehaviorModel for DailyCommuteBehavior {
     fn replan(&self, agent: AgentId, ctx: &SimContext<'_>, _rng: &mut AgentRng) -> Vec<Intent> {
@@ -126,51 +121,10 @@ impl BehaviorModel for DailyCommuteBehavior {
         }
         // `sample[..k]` holds the chosen contacts — available for downstream use.
         let _ = &sample[..k];
-        self.contacts_observed.fetch_add(k as u64, Ordering::Relaxed);
-        vec![]
-    }
-}
-
-// ── Pre-computed router ───────────────────────────────────────────────────────
-
-/// Wraps a pre-computed route table for O(1) lookups per (from, to) pair.
-///
-/// Pre-computing all 21×21 home↔work pairs takes ~1 ms; look-ups during
-/// the sim's apply phase have zero Dijkstra overhead.
-struct PrecomputedRouter {
-    routes: HashMap<(u32, u32), Route>,
-}
-
-impl PrecomputedRouter {
-    fn build(network: &RoadNetwork, homes: &[NodeId], works: &[NodeId]) -> Self {
-        let router = DijkstraRouter;
-        let mut routes = HashMap::new();
-        for &h in homes {
-            for &w in works {
-                if let Ok(r) = router.route(network, h, w, TransportMode::Car) {
-                    routes.insert((h.0, w.0), r);
-                }
-                if let Ok(r) = router.route(network, w, h, TransportMode::Car) {
-                    routes.insert((w.0, h.0), r);
-                }
-            }
+        if k == 0 {
+            return vec![];
         }
-        Self { routes }
-    }
-}
-
-impl Router for PrecomputedRouter {
-    fn route(
-        &self,
-        _network: &RoadNetwork,
-        from:     NodeId,
-        to:       NodeId,
-        _mode:    TransportMode,
-    ) -> Result<Route, SpatialError> {
-        self.routes
-            .get(&(from.0, to.0))
-            .cloned()
-            .ok_or(SpatialError::NoRoute { from, to })
+        vec![Intent::Count { name: "contacts", delta: k as u64 }]
     }
 }
 
@@ -220,6 +174,7 @@ impl SimObserver for SampledObserver {
             unix_time_secs: self.start_unix_secs
                 + tick.0 as i64 * self.tick_duration_secs as i64,
             woken_agents:   woken as u64,
+            ..Default::default()
         };
         self.writer.write_tick_summary(&row).ok();
     }
@@ -237,6 +192,10 @@ impl SimObserver for SampledObserver {
                 } else {
                     NodeId::INVALID.0
                 },
+                lat:              None,
+                lon:              None,
+                departure_tick:   state.in_transit.then_some(state.departure_tick.0),
+                arrival_tick:     state.in_transit.then_some(state.arrival_tick.0),
             };
             self.writer.write_snapshots(std::slice::from_ref(&row)).ok();
         }
@@ -298,9 +257,9 @@ fn main() -> Result<()> {
         work_list.len(),
     );
 
-    // 2. Pre-compute all home↔work routes.
-    let router = PrecomputedRouter::build(&network, &home_list, &work_list);
-    println!("Pre-computed {} routes", router.routes.len());
+    // 2. Cache every home↔work route after its first search.
+    let router = CachedRouter::new(DijkstraRouter, 2 * home_list.len() * work_list.len());
+    println!("Caching up to {} routes", router.capacity());
 
     // 3. Agent store with HomeNode / WorkNode components.
     let (mut store, rngs) = AgentStoreBuilder::new(AGENT_COUNT, SEED)
@@ -347,6 +306,7 @@ fn main() -> Result<()> {
         seed:                  SEED,
         num_threads:           None,
         output_interval_ticks: OUTPUT_INTERVAL_TICKS,
+        ..Default::default()
     };
     println!(
         "Sim: {} ticks ({} days), snapshots every {} ticks, 1-in-{} agents sampled",
@@ -355,10 +315,9 @@ fn main() -> Result<()> {
     println!();
 
     // 7. Build sim.
-    let contacts_observed = Arc::new(AtomicU64::new(0));
     let mut sim = SimBuilder::new(
             config.clone(), store, rngs,
-            DailyCommuteBehavior { contacts_observed: Arc::clone(&contacts_observed) },
+            DailyCommuteBehavior,
             router,
         )
         .plans(plans)
@@ -395,8 +354,8 @@ fn main() -> Result<()> {
     );
     println!(
         "Contacts sampled:   {} total  ({:.1} M/s)",
-        contacts_observed.load(Ordering::Relaxed),
-        contacts_observed.load(Ordering::Relaxed) as f64 / elapsed / 1_000_000.0,
+        sim.metric_totals.count("contacts"),
+        sim.metric_totals.count("contacts") as f64 / elapsed / 1_000_000.0,
     );
 
     Ok(())
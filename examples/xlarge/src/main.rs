@@ -98,15 +98,13 @@ struct PrecomputedRouter {
 
 impl PrecomputedRouter {
     fn build(network: &RoadNetwork, homes: &[NodeId], works: &[NodeId]) -> Self {
-        let router = DijkstraRouter;
         let mut routes = HashMap::new();
-        for &h in homes {
-            for &w in works {
-                if let Ok(r) = router.route(network, h, w, TransportMode::Car) {
-                    routes.insert((h.0, w.0), r);
-                }
-                if let Ok(r) = router.route(network, w, h, TransportMode::Car) {
-                    routes.insert((w.0, h.0), r);
+        for (origins, destinations) in [(homes, works), (works, homes)] {
+            let matrix = DijkstraRouter.route_matrix(network, origins, destinations, TransportMode::Car);
+            let pairs = origins.iter().flat_map(|o| destinations.iter().map(move |d| (o.0, d.0)));
+            for (pair, route) in pairs.zip(matrix.into_routes().unwrap_or_default()) {
+                if let Some(route) = route {
+                    routes.insert(pair, route);
                 }
             }
         }
@@ -122,6 +120,10 @@ impl Router for PrecomputedRouter {
         to:       NodeId,
         _mode:    TransportMode,
     ) -> Result<Route, SpatialError> {
+        // Same-node requests get an empty route, per the `Router` contract.
+        if from == to {
+            return Ok(Route { edges: vec![], total_travel_ms: 0, rides: Vec::new() });
+        }
         self.routes
             .get(&(from.0, to.0))
             .cloned()
@@ -163,6 +165,7 @@ impl SimObserver for SampledObserver {
             unix_time_secs: self.start_unix_secs
                 + tick.0 as i64 * self.tick_duration_secs as i64,
             woken_agents:   woken as u64,
+            ..Default::default()
         };
         self.writer.write_tick_summary(&row).ok();
     }
@@ -180,6 +183,10 @@ impl SimObserver for SampledObserver {
                 } else {
                     NodeId::INVALID.0
                 },
+                lat:              None,
+                lon:              None,
+                departure_tick:   state.in_transit.then_some(state.departure_tick.0),
+                arrival_tick:     state.in_transit.then_some(state.arrival_tick.0),
             };
             self.writer.write_snapshots(std::slice::from_ref(&row)).ok();
         }
@@ -288,6 +295,7 @@ fn main() -> Result<()> {
         seed:                  SEED,
         num_threads:           None,
         output_interval_ticks: OUTPUT_INTERVAL_TICKS,
+        ..Default::default()
     };
     println!(
         "Sim: {} ticks ({} days), snapshots every {} ticks, 1-in-{} agents sampled",
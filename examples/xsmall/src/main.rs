@@ -17,11 +17,11 @@ use anyhow::Result;
 
 use dt_agent::AgentStoreBuilder;
 use dt_behavior::{BehaviorModel, Intent, SimContext};
-use dt_core::{AgentId, AgentRng, NodeId, SimConfig, TransportMode};
+use dt_core::{AgentId, AgentRng, NodeId, SimConfig, Tick, TransportMode};
 use dt_output::{CsvWriter, SimOutputObserver};
 use dt_schedule::{Destination, load_plans_reader};
 use dt_sim::{SimBuilder, SimObserver};
-use dt_spatial::DijkstraRouter;
+use dt_spatial::{RoadNetwork, Router, SpeedProfiles, TimeDependentRouter};
 
 use network::build_network;
 
@@ -33,6 +33,23 @@ const TICK_DURATION_SECS:    u32   = 3_600; // 1 tick = 1 hour
 const SIM_DAYS:              u64   = 7;
 const OUTPUT_INTERVAL_TICKS: u64   = 1;     // snapshot every tick (captures commute movement)
 
+// ── Rush hour ─────────────────────────────────────────────────────────────────
+
+/// Slow every road into `downtown` during the 07:00–09:00 and 16:00–18:00
+/// peaks (tick 0 is midnight), so `TimeDependentRouter` sends peak commuters
+/// the long way round.
+fn add_rush_hour(network: &mut RoadNetwork, downtown: NodeId) {
+    let mut factors = [1.0; 24];
+    factors[7..9].fill(2.5);
+    factors[16..18].fill(2.5);
+    let mut profiles = SpeedProfiles::new(network.edge_count());
+    let rush = profiles.add_profile(factors);
+    for edge in network.in_edges(downtown) {
+        profiles.assign(edge, rush);
+    }
+    network.set_speed_profiles(profiles);
+}
+
 // ── Memory helper ─────────────────────────────────────────────────────────────
 
 fn mem_mb() -> f64 {
@@ -164,14 +181,26 @@ fn main() -> Result<()> {
     println!("mem[startup]       {:.1} MB", mem_mb());
     println!();
 
-    // 1. Build road network.
-    let (network, nodes) = build_network();
+    // 1. Build road network, with rush-hour slowdowns into downtown.
+    let (mut network, nodes) = build_network();
     let [north_residential, south_residential, downtown, commerce_park, _connector] = nodes;
+    add_rush_hour(&mut network, downtown);
     println!(
         "Road network: {} nodes, {} edges",
         network.node_count(),
         network.edge_count()
     );
+    let router = TimeDependentRouter::new();
+    for (label, hour) in [("03:00", 3), ("08:00", 8)] {
+        let route = router.route_at(
+            &network, north_residential, downtown, TransportMode::Car, Tick(hour), TICK_DURATION_SECS,
+        )?;
+        println!(
+            "North residential → downtown at {label}: {:.0} s over {} roads",
+            route.total_travel_secs(),
+            route.edges.len()
+        );
+    }
 
     // 2. Build agent store with custom components.
     let (mut store, rngs) = AgentStoreBuilder::new(AGENT_COUNT, SEED)
@@ -214,6 +243,7 @@ fn main() -> Result<()> {
         seed:                  SEED,
         num_threads:           None, // all logical cores
         output_interval_ticks: OUTPUT_INTERVAL_TICKS,
+        ..Default::default()
     };
     println!(
         "Sim: {} ticks ({} days × 24 h), output every {} ticks",
@@ -224,7 +254,7 @@ fn main() -> Result<()> {
     println!("mem[before sim build] {:.1} MB", mem_mb());
 
     // 6. Build sim.
-    let mut sim = SimBuilder::new(config.clone(), store, rngs, DailyCommuteBehavior, DijkstraRouter)
+    let mut sim = SimBuilder::new(config.clone(), store, rngs, DailyCommuteBehavior, router)
         .plans(plans)
         .network(network)
         .initial_positions(initial_positions)